@@ -0,0 +1,120 @@
+//! The block info of the blocks the node proposes.
+//!
+//! A proposed block is priced by the L1 gas and data gas prices that the base layer sampler last
+//! published through its [`GasPriceProvider`]. Its L2 gas prices are set by the [`FeeMarket`], by
+//! the L2 gas prices of its parent block and how full the parent block was. Without a known parent
+//! block, e.g. when the parent block was proposed by another node, they're converted from the L1
+//! gas prices.
+
+use std::num::NonZeroU128;
+
+use blockifier::blockifier::block::{BlockInfo, GasPrices};
+use blockifier::bouncer::{BouncerConfig, BouncerWeights};
+use blockifier::fee::fee_market::{FeeMarket, FeeMarketConfig};
+use blockifier::transaction::objects::FeeType;
+use blockifier::versioned_constants::VersionedConstants;
+use papyrus_base_layer::l1_gas_price::{GasPriceProvider, GasPriceProviderError, L1GasPrices};
+use starknet_api::block::{BlockNumber, BlockTimestamp, GasPrice};
+use starknet_api::core::ContractAddress;
+
+/// Builds the block info of the proposed blocks.
+pub struct BlockInfoBuilder {
+    sequencer_address: ContractAddress,
+    use_kzg_da: bool,
+    gas_price_provider: GasPriceProvider,
+    fee_market: FeeMarketConfig,
+    bouncer_config: BouncerConfig,
+    /// The last decided block, if the node knows its gas prices and the weights it used.
+    parent_block: Option<ParentBlockUsage>,
+}
+
+/// The gas prices of a decided block and the weights it used out of the block capacity, which the
+/// L2 gas prices of its child block are set by.
+#[derive(Clone, Debug)]
+pub struct ParentBlockUsage {
+    pub gas_prices: GasPrices,
+    pub weights: BouncerWeights,
+}
+
+impl BlockInfoBuilder {
+    pub fn new(
+        sequencer_address: ContractAddress,
+        use_kzg_da: bool,
+        gas_price_provider: GasPriceProvider,
+        fee_market: FeeMarketConfig,
+        block_max_capacity: BouncerWeights,
+    ) -> Self {
+        Self {
+            sequencer_address,
+            use_kzg_da,
+            gas_price_provider,
+            fee_market,
+            bouncer_config: BouncerConfig { block_max_capacity, ..BouncerConfig::empty() },
+            parent_block: None,
+        }
+    }
+
+    /// Sets the block that the next blocks build on, or forgets the previous one if the usage of
+    /// the decided block isn't known.
+    pub fn set_parent_block(&mut self, parent_block: Option<ParentBlockUsage>) {
+        self.parent_block = parent_block;
+    }
+
+    /// Returns the block info of a block proposed for `height` at `timestamp`. Fails if the base
+    /// layer wasn't sampled yet.
+    pub fn build(
+        &self,
+        height: BlockNumber,
+        timestamp: BlockTimestamp,
+    ) -> Result<BlockInfo, GasPriceProviderError> {
+        Ok(BlockInfo {
+            block_number: height,
+            block_timestamp: timestamp,
+            sequencer_address: self.sequencer_address,
+            gas_prices: self.gas_prices()?,
+            use_kzg_da: self.use_kzg_da,
+        })
+    }
+
+    /// The gas prices the node prices its next block by, which the prices of received proposals
+    /// are validated against.
+    pub fn gas_prices(&self) -> Result<GasPrices, GasPriceProviderError> {
+        let gas_prices = gas_prices(self.gas_price_provider.get_gas_prices()?);
+        let Some(ParentBlockUsage { gas_prices: parent_gas_prices, weights }) = &self.parent_block
+        else {
+            return Ok(gas_prices);
+        };
+        let next_gas_prices = FeeMarket::new(&self.fee_market).next_gas_prices(
+            parent_gas_prices,
+            weights,
+            &self.bouncer_config,
+        );
+        Ok(gas_prices.with_l2_gas_prices(
+            next_gas_prices.get_l2_gas_price_by_fee_type(&FeeType::Eth),
+            next_gas_prices.get_l2_gas_price_by_fee_type(&FeeType::Strk),
+        ))
+    }
+}
+
+/// The gas prices of a block by the L1 gas prices, with the L2 gas prices they convert to.
+pub fn gas_prices(l1_gas_prices: L1GasPrices) -> GasPrices {
+    let versioned_constants = VersionedConstants::latest_constants();
+    let eth_l1_gas_price = non_zero(l1_gas_prices.l1_gas_price.price_in_wei);
+    let strk_l1_gas_price = non_zero(l1_gas_prices.l1_gas_price.price_in_fri);
+    let l2_gas_price = |l1_gas_price: NonZeroU128| {
+        non_zero(GasPrice(versioned_constants.l1_to_l2_gas_price_conversion(l1_gas_price.get())))
+    };
+    GasPrices::new(
+        eth_l1_gas_price,
+        strk_l1_gas_price,
+        non_zero(l1_gas_prices.l1_data_gas_price.price_in_wei),
+        non_zero(l1_gas_prices.l1_data_gas_price.price_in_fri),
+        l2_gas_price(eth_l1_gas_price),
+        l2_gas_price(strk_l1_gas_price),
+    )
+}
+
+// Block gas prices must be non-zero.
+fn non_zero(price: GasPrice) -> NonZeroU128 {
+    NonZeroU128::new(price.0).unwrap_or(NonZeroU128::MIN)
+}
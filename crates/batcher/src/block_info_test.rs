@@ -0,0 +1,127 @@
+use std::num::NonZeroU128;
+
+use blockifier::bouncer::BouncerWeights;
+use blockifier::fee::fee_market::{FeeMarket, FeeMarketConfig};
+use blockifier::transaction::objects::FeeType;
+use blockifier::versioned_constants::VersionedConstants;
+use papyrus_base_layer::l1_gas_price::{GasPriceProvider, L1GasPrices};
+use starknet_api::block::{BlockNumber, BlockTimestamp, GasPrice, GasPricePerToken};
+use starknet_api::core::ContractAddress;
+
+use crate::block_info::{gas_prices, BlockInfoBuilder, ParentBlockUsage};
+
+fn l1_gas_prices(l1_gas_price: u128, l1_data_gas_price: u128) -> L1GasPrices {
+    L1GasPrices {
+        l1_gas_price: GasPricePerToken {
+            price_in_wei: GasPrice(l1_gas_price),
+            price_in_fri: GasPrice(l1_gas_price * 10),
+        },
+        l1_data_gas_price: GasPricePerToken {
+            price_in_wei: GasPrice(l1_data_gas_price),
+            price_in_fri: GasPrice(l1_data_gas_price * 10),
+        },
+    }
+}
+
+#[test]
+fn block_info_is_priced_by_the_sampled_gas_prices() {
+    let sequencer_address = ContractAddress::from(1_u128);
+    let builder = BlockInfoBuilder::new(
+        sequencer_address,
+        true,
+        GasPriceProvider::fixed(l1_gas_prices(100_000_000_000, 7)),
+        FeeMarketConfig::default(),
+        BouncerWeights::max(),
+    );
+
+    let block_info = builder.build(BlockNumber(3), BlockTimestamp(1000)).unwrap();
+    assert_eq!(block_info.block_number, BlockNumber(3));
+    assert_eq!(block_info.block_timestamp, BlockTimestamp(1000));
+    assert_eq!(block_info.sequencer_address, sequencer_address);
+    assert!(block_info.use_kzg_da);
+
+    let gas_prices = &block_info.gas_prices;
+    let price = |price: u128| NonZeroU128::new(price).unwrap();
+    assert_eq!(gas_prices.get_l1_gas_price_by_fee_type(&FeeType::Eth), price(100_000_000_000));
+    assert_eq!(gas_prices.get_l1_gas_price_by_fee_type(&FeeType::Strk), price(1_000_000_000_000));
+    assert_eq!(gas_prices.get_l1_data_gas_price_by_fee_type(&FeeType::Eth), price(7));
+    assert_eq!(gas_prices.get_l1_data_gas_price_by_fee_type(&FeeType::Strk), price(70));
+    let versioned_constants = VersionedConstants::latest_constants();
+    assert_eq!(
+        gas_prices.get_l2_gas_price_by_fee_type(&FeeType::Eth).get(),
+        versioned_constants.l1_to_l2_gas_price_conversion(100_000_000_000)
+    );
+}
+
+#[test]
+fn block_gas_prices_are_non_zero() {
+    let builder = BlockInfoBuilder::new(
+        ContractAddress::from(1_u128),
+        false,
+        GasPriceProvider::fixed(l1_gas_prices(0, 0)),
+        FeeMarketConfig::default(),
+        BouncerWeights::max(),
+    );
+
+    let block_info = builder.build(BlockNumber(0), BlockTimestamp(0)).unwrap();
+    assert_eq!(block_info.gas_prices.get_l1_gas_price_by_fee_type(&FeeType::Eth), NonZeroU128::MIN);
+    assert_eq!(
+        block_info.gas_prices.get_l2_gas_price_by_fee_type(&FeeType::Strk),
+        NonZeroU128::MIN
+    );
+}
+
+#[test]
+fn l2_gas_prices_follow_the_usage_of_the_parent_block() {
+    let fee_market = FeeMarketConfig::default();
+    let block_max_capacity = BouncerWeights { n_steps: 100, ..BouncerWeights::max() };
+    let mut builder = BlockInfoBuilder::new(
+        ContractAddress::from(1_u128),
+        true,
+        GasPriceProvider::fixed(l1_gas_prices(100_000_000_000, 7)),
+        fee_market.clone(),
+        block_max_capacity,
+    );
+    let parent_gas_prices = builder.gas_prices().unwrap();
+    let parent_l2_gas_price = parent_gas_prices.get_l2_gas_price_by_fee_type(&FeeType::Strk);
+
+    // A full parent block raises the L2 gas prices; the L1 gas prices are still sampled.
+    builder.set_parent_block(Some(ParentBlockUsage {
+        gas_prices: parent_gas_prices.clone(),
+        weights: BouncerWeights { n_steps: 100, ..BouncerWeights::default() },
+    }));
+    let gas_prices_after_full_block = builder.gas_prices().unwrap();
+    assert_eq!(
+        gas_prices_after_full_block.get_l2_gas_price_by_fee_type(&FeeType::Strk),
+        FeeMarket::new(&fee_market).next_l2_gas_price(
+            parent_l2_gas_price,
+            1_000_000,
+            fee_market.min_strk_l2_gas_price
+        )
+    );
+    assert!(
+        gas_prices_after_full_block.get_l2_gas_price_by_fee_type(&FeeType::Strk)
+            > parent_l2_gas_price
+    );
+    assert_eq!(
+        gas_prices_after_full_block.get_l1_gas_price_by_fee_type(&FeeType::Eth),
+        parent_gas_prices.get_l1_gas_price_by_fee_type(&FeeType::Eth)
+    );
+
+    // An empty parent block lowers them.
+    builder.set_parent_block(Some(ParentBlockUsage {
+        gas_prices: parent_gas_prices.clone(),
+        weights: BouncerWeights::default(),
+    }));
+    assert!(
+        builder.gas_prices().unwrap().get_l2_gas_price_by_fee_type(&FeeType::Strk)
+            < parent_l2_gas_price
+    );
+
+    // Without a known parent block, they're converted from the L1 gas prices.
+    builder.set_parent_block(None);
+    assert_eq!(
+        builder.gas_prices().unwrap().get_l2_gas_price_by_fee_type(&FeeType::Eth),
+        gas_prices(l1_gas_prices(100_000_000_000, 7)).get_l2_gas_price_by_fee_type(&FeeType::Eth)
+    );
+}
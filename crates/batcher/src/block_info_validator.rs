@@ -0,0 +1,154 @@
+//! Validation of the block info of a received proposal, before its transactions are executed.
+//!
+//! A proposal is rejected if its block info doesn't follow its parent block, as checked by
+//! [`blockifier::blockifier::block::validate_block_info`], if its timestamp is too far ahead of the
+//! local clock, or if its gas prices deviate too much from the prices the node computes by itself
+//! for the block.
+//!
+//! The deviations of the gas prices are recorded in the [`BATCHER_GAS_PRICE_DEVIATION_PERCENT`]
+//! metric, also for proposals rejected for their gas prices, for tuning the tolerance.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use blockifier::blockifier::block::{self, BlockInfo, GasPrices, ParentBlockInfo};
+use blockifier::state::errors::StateError;
+use blockifier::transaction::objects::FeeType;
+use papyrus_config::converters::deserialize_seconds_to_duration;
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+use starknet_mempool_infra::metrics::{
+    BATCHER_GAS_PRICE_DEVIATION_PERCENT,
+    COMPONENT_LABEL,
+    GAS_LABEL,
+};
+use thiserror::Error;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct BlockInfoValidationConfig {
+    /// How far ahead of the local clock the timestamp of a proposal may be.
+    #[serde(deserialize_with = "deserialize_seconds_to_duration")]
+    pub max_timestamp_drift: Duration,
+    /// How much the gas prices of a proposal may deviate from the local ones, in percents.
+    pub max_gas_price_deviation_percent: u8,
+}
+
+impl Default for BlockInfoValidationConfig {
+    fn default() -> Self {
+        Self { max_timestamp_drift: Duration::from_secs(15), max_gas_price_deviation_percent: 10 }
+    }
+}
+
+impl SerializeConfig for BlockInfoValidationConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "max_timestamp_drift",
+                &self.max_timestamp_drift.as_secs(),
+                "How far (seconds) ahead of the local clock the timestamp of a proposal may be",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_gas_price_deviation_percent",
+                &self.max_gas_price_deviation_percent,
+                "How much the gas prices of a proposal may deviate from the local ones, in \
+                 percents",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum BlockInfoValidationError {
+    #[error(transparent)]
+    InvalidParent(#[from] StateError),
+    #[error("Timestamp {timestamp} is more than {max_drift:?} ahead of the local clock ({now}).")]
+    TimestampTooFarAhead { timestamp: u64, now: u64, max_drift: Duration },
+    #[error(
+        "{gas} price {gas_price} deviates by more than {max_deviation_percent}% from the local \
+         price {local_gas_price}."
+    )]
+    GasPriceOutOfBounds {
+        gas: &'static str,
+        gas_price: u128,
+        local_gas_price: u128,
+        max_deviation_percent: u8,
+    },
+}
+
+// The gas prices of a block, by name.
+type GasPriceGetter = fn(&GasPrices) -> u128;
+const GAS_PRICES: [(&str, GasPriceGetter); 6] = [
+    ("ETH L1 gas", |gas_prices| gas_prices.get_l1_gas_price_by_fee_type(&FeeType::Eth).get()),
+    ("STRK L1 gas", |gas_prices| gas_prices.get_l1_gas_price_by_fee_type(&FeeType::Strk).get()),
+    ("ETH L1 data gas", |gas_prices| {
+        gas_prices.get_l1_data_gas_price_by_fee_type(&FeeType::Eth).get()
+    }),
+    ("STRK L1 data gas", |gas_prices| {
+        gas_prices.get_l1_data_gas_price_by_fee_type(&FeeType::Strk).get()
+    }),
+    ("ETH L2 gas", |gas_prices| gas_prices.get_l2_gas_price_by_fee_type(&FeeType::Eth).get()),
+    ("STRK L2 gas", |gas_prices| gas_prices.get_l2_gas_price_by_fee_type(&FeeType::Strk).get()),
+];
+
+pub type BlockInfoValidationResult = Result<(), BlockInfoValidationError>;
+
+/// Validates the block info of a proposal that builds on `parent`, given the local clock (`now`,
+/// in seconds since the epoch) and the gas prices the node computes for the block.
+pub fn validate_block_info(
+    config: &BlockInfoValidationConfig,
+    block_info: &BlockInfo,
+    parent: &ParentBlockInfo,
+    now: u64,
+    local_gas_prices: &GasPrices,
+) -> BlockInfoValidationResult {
+    block::validate_block_info(block_info, parent)?;
+
+    // The deviations of all the gas prices are recorded before the first one out of bounds fails
+    // the validation.
+    let mut result: BlockInfoValidationResult = Ok(());
+    for (gas, get_gas_price) in GAS_PRICES {
+        let gas_price = get_gas_price(&block_info.gas_prices);
+        let local_gas_price = get_gas_price(local_gas_prices);
+        let deviation_percent = deviation_percent(gas_price, local_gas_price);
+        metrics::histogram!(
+            BATCHER_GAS_PRICE_DEVIATION_PERCENT,
+            f64::from(deviation_percent),
+            COMPONENT_LABEL => "batcher",
+            GAS_LABEL => gas
+        );
+        if result.is_ok() && deviation_percent > u32::from(config.max_gas_price_deviation_percent) {
+            result = Err(BlockInfoValidationError::GasPriceOutOfBounds {
+                gas,
+                gas_price,
+                local_gas_price,
+                max_deviation_percent: config.max_gas_price_deviation_percent,
+            });
+        }
+    }
+
+    result?;
+
+    // Checked last, so a proposal rejected for its timestamp is otherwise valid, see
+    // [`crate::clock_skew`].
+    let timestamp = block_info.block_timestamp.0;
+    if timestamp > now.saturating_add(config.max_timestamp_drift.as_secs()) {
+        return Err(BlockInfoValidationError::TimestampTooFarAhead {
+            timestamp,
+            now,
+            max_drift: config.max_timestamp_drift,
+        });
+    }
+
+    Ok(())
+}
+
+// The deviation of `gas_price` from `local_gas_price`, in percents rounded up, so it exceeds a
+// whole percentage exactly when the precise deviation does. Capped at `u32::MAX`.
+pub(crate) fn deviation_percent(gas_price: u128, local_gas_price: u128) -> u32 {
+    let deviation =
+        gas_price.abs_diff(local_gas_price).saturating_mul(100).div_ceil(local_gas_price.max(1));
+    u32::try_from(deviation).unwrap_or(u32::MAX)
+}
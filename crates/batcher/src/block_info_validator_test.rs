@@ -0,0 +1,137 @@
+use std::num::NonZeroU128;
+use std::time::Duration;
+
+use assert_matches::assert_matches;
+use blockifier::blockifier::block::{BlockInfo, GasPrices, ParentBlockInfo};
+use blockifier::state::errors::StateError;
+use starknet_api::block::{BlockNumber, BlockTimestamp};
+use starknet_api::core::ContractAddress;
+
+use crate::block_info_validator::{
+    deviation_percent,
+    validate_block_info,
+    BlockInfoValidationConfig,
+    BlockInfoValidationError,
+};
+
+const NOW: u64 = 1_000_000;
+const PARENT: ParentBlockInfo =
+    ParentBlockInfo { block_number: BlockNumber(10), block_timestamp: BlockTimestamp(NOW - 10) };
+const LOCAL_GAS_PRICE: u128 = 1000;
+
+fn gas_prices(l1_gas_price: u128, l2_gas_price: u128) -> GasPrices {
+    let l1_gas_price = NonZeroU128::new(l1_gas_price).unwrap();
+    let l2_gas_price = NonZeroU128::new(l2_gas_price).unwrap();
+    GasPrices::new(
+        l1_gas_price,
+        l1_gas_price,
+        l1_gas_price,
+        l1_gas_price,
+        l2_gas_price,
+        l2_gas_price,
+    )
+}
+
+fn block_info(block_number: u64, timestamp: u64, gas_prices: GasPrices) -> BlockInfo {
+    BlockInfo {
+        block_number: BlockNumber(block_number),
+        block_timestamp: BlockTimestamp(timestamp),
+        sequencer_address: ContractAddress::default(),
+        gas_prices,
+        use_kzg_da: false,
+    }
+}
+
+fn validate(block_info: &BlockInfo) -> Result<(), BlockInfoValidationError> {
+    let config = BlockInfoValidationConfig {
+        max_timestamp_drift: Duration::from_secs(15),
+        max_gas_price_deviation_percent: 10,
+    };
+    validate_block_info(
+        &config,
+        block_info,
+        &PARENT,
+        NOW,
+        &gas_prices(LOCAL_GAS_PRICE, LOCAL_GAS_PRICE),
+    )
+}
+
+#[test]
+fn valid_block_info() {
+    let local_gas_prices = gas_prices(LOCAL_GAS_PRICE, LOCAL_GAS_PRICE);
+    // The timestamp may equal the parent's, or be slightly ahead of the local clock.
+    for timestamp in [PARENT.block_timestamp.0, NOW, NOW + 15] {
+        assert_matches!(validate(&block_info(11, timestamp, local_gas_prices.clone())), Ok(()));
+    }
+    // The gas prices may deviate from the local ones within the bounds.
+    assert_matches!(validate(&block_info(11, NOW, gas_prices(900, 1100))), Ok(()));
+}
+
+#[test]
+fn block_number_must_follow_parent() {
+    for block_number in [10, 12] {
+        assert_matches!(
+            validate(&block_info(block_number, NOW, gas_prices(LOCAL_GAS_PRICE, LOCAL_GAS_PRICE))),
+            Err(BlockInfoValidationError::InvalidParent(StateError::NonConsecutiveBlockNumber {
+                block_number: actual_block_number,
+                parent_block_number,
+            })) if actual_block_number == BlockNumber(block_number)
+                && parent_block_number == PARENT.block_number
+        );
+    }
+}
+
+#[test]
+fn timestamp_must_be_monotonic() {
+    let timestamp = PARENT.block_timestamp.0 - 1;
+    assert_matches!(
+        validate(&block_info(11, timestamp, gas_prices(LOCAL_GAS_PRICE, LOCAL_GAS_PRICE))),
+        Err(BlockInfoValidationError::InvalidParent(StateError::DecreasingBlockTimestamp {
+            timestamp: actual_timestamp,
+            parent_timestamp,
+            ..
+        })) if actual_timestamp == timestamp && parent_timestamp == PARENT.block_timestamp.0
+    );
+}
+
+#[test]
+fn timestamp_must_not_drift_ahead() {
+    assert_matches!(
+        validate(&block_info(11, NOW + 16, gas_prices(LOCAL_GAS_PRICE, LOCAL_GAS_PRICE))),
+        Err(BlockInfoValidationError::TimestampTooFarAhead { timestamp, now: NOW, .. })
+            if timestamp == NOW + 16
+    );
+}
+
+#[test]
+fn gas_prices_must_be_within_bounds() {
+    assert_matches!(
+        validate(&block_info(11, NOW, gas_prices(899, LOCAL_GAS_PRICE))),
+        Err(BlockInfoValidationError::GasPriceOutOfBounds {
+            gas: "ETH L1 gas",
+            gas_price: 899,
+            local_gas_price: LOCAL_GAS_PRICE,
+            ..
+        })
+    );
+    assert_matches!(
+        validate(&block_info(11, NOW, gas_prices(LOCAL_GAS_PRICE, 1101))),
+        Err(BlockInfoValidationError::GasPriceOutOfBounds {
+            gas: "ETH L2 gas",
+            gas_price: 1101,
+            local_gas_price: LOCAL_GAS_PRICE,
+            ..
+        })
+    );
+}
+
+#[test]
+fn gas_price_deviation_percent() {
+    assert_eq!(deviation_percent(LOCAL_GAS_PRICE, LOCAL_GAS_PRICE), 0);
+    assert_eq!(deviation_percent(1100, LOCAL_GAS_PRICE), 10);
+    assert_eq!(deviation_percent(900, LOCAL_GAS_PRICE), 10);
+    // Rounded up, so a deviation just above the bound exceeds it.
+    assert_eq!(deviation_percent(899, LOCAL_GAS_PRICE), 11);
+    assert_eq!(deviation_percent(3000, LOCAL_GAS_PRICE), 200);
+    assert_eq!(deviation_percent(u128::MAX, 1), u32::MAX);
+}
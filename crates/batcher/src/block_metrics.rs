@@ -0,0 +1,131 @@
+//! Metrics of block building, for tuning the proposal deadlines and the policy of the node.
+//!
+//! When a block is closed, the batcher records how long before the deadline it was closed, and the
+//! percentage of the capacity of each bouncer resource that it used. Transactions that were taken
+//! from the mempool but not added to the block are counted by the [`RejectionReason`].
+// TODO: Count the transactions that didn't fit in a block or its deadline, or failed their
+// validation, once the block builder executes the transactions.
+
+use std::time::Duration;
+
+use blockifier::bouncer::{BouncerWeights, BuiltinCount};
+use starknet_api::block::BlockNumber;
+use starknet_mempool_infra::metrics::{
+    BATCHER_CAPACITY_USED_PERCENT,
+    BATCHER_DEADLINE_SLACK_SECS,
+    BATCHER_REJECTED_TRANSACTIONS,
+    COMPONENT_LABEL,
+    HEIGHT_LABEL,
+    REASON_LABEL,
+    RESOURCE_LABEL,
+};
+
+/// Why a transaction that was taken from the mempool wasn't added to a block.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RejectionReason {
+    /// The contract policy of the node doesn't allow the transaction.
+    ContractPolicy,
+    /// The transaction doesn't fit in a chunk of the streamed proposal.
+    Oversized,
+}
+
+impl RejectionReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ContractPolicy => "contract_policy",
+            Self::Oversized => "oversized",
+        }
+    }
+}
+
+/// Records the transactions that weren't added to the block of the given height for the given
+/// reason.
+pub fn record_rejected_txs(height: BlockNumber, reason: RejectionReason, n_txs: usize) {
+    if n_txs == 0 {
+        return;
+    }
+    metrics::counter!(
+        BATCHER_REJECTED_TRANSACTIONS,
+        u64::try_from(n_txs).expect("usize should fit in u64."),
+        COMPONENT_LABEL => "batcher",
+        REASON_LABEL => reason.as_str(),
+        HEIGHT_LABEL => height.to_string()
+    );
+}
+
+/// Records the time that was left until the deadline of a proposal when its block was closed.
+pub fn record_deadline_slack(deadline_slack: Duration) {
+    metrics::histogram!(
+        BATCHER_DEADLINE_SLACK_SECS,
+        deadline_slack.as_secs_f64(),
+        COMPONENT_LABEL => "batcher"
+    );
+}
+
+/// Records the percentage of the capacity of each resource that a closed block used.
+pub fn record_capacity_used(weights: &BouncerWeights, block_max_capacity: &BouncerWeights) {
+    for (resource, used_percent) in capacity_used_percents(weights, block_max_capacity) {
+        metrics::histogram!(
+            BATCHER_CAPACITY_USED_PERCENT,
+            f64::from(used_percent),
+            COMPONENT_LABEL => "batcher",
+            RESOURCE_LABEL => resource
+        );
+    }
+}
+
+/// Returns the percentage of the capacity of each resource that the weights use, rounded down and
+/// capped at `u32::MAX`.
+pub fn capacity_used_percents(
+    weights: &BouncerWeights,
+    block_max_capacity: &BouncerWeights,
+) -> Vec<(&'static str, u32)> {
+    named_resources(weights)
+        .into_iter()
+        .zip(named_resources(block_max_capacity))
+        .map(|((resource, weight), (_resource, capacity))| {
+            let used_percent = weight.saturating_mul(100) / capacity.max(1);
+            (resource, u32::try_from(used_percent).unwrap_or(u32::MAX))
+        })
+        .collect()
+}
+
+fn named_resources(weights: &BouncerWeights) -> [(&'static str, usize); 15] {
+    let BouncerWeights {
+        builtin_count,
+        gas,
+        message_segment_length,
+        n_events,
+        n_steps,
+        state_diff_size,
+    } = *weights;
+    let BuiltinCount {
+        add_mod,
+        bitwise,
+        ecdsa,
+        ec_op,
+        keccak,
+        mul_mod,
+        pedersen,
+        poseidon,
+        range_check,
+        range_check96,
+    } = builtin_count;
+    [
+        ("gas", gas),
+        ("message_segment_length", message_segment_length),
+        ("n_events", n_events),
+        ("n_steps", n_steps),
+        ("state_diff_size", state_diff_size),
+        ("add_mod", add_mod),
+        ("bitwise", bitwise),
+        ("ecdsa", ecdsa),
+        ("ec_op", ec_op),
+        ("keccak", keccak),
+        ("mul_mod", mul_mod),
+        ("pedersen", pedersen),
+        ("poseidon", poseidon),
+        ("range_check", range_check),
+        ("range_check96", range_check96),
+    ]
+}
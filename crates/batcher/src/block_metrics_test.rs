@@ -0,0 +1,35 @@
+use blockifier::bouncer::{BouncerWeights, BuiltinCount};
+
+use crate::block_metrics::{capacity_used_percents, RejectionReason};
+
+#[test]
+fn rejection_reason_labels() {
+    assert_eq!(RejectionReason::ContractPolicy.as_str(), "contract_policy");
+    assert_eq!(RejectionReason::Oversized.as_str(), "oversized");
+}
+
+#[test]
+fn capacity_used_by_resource() {
+    let block_max_capacity = BouncerWeights {
+        n_steps: 1000,
+        builtin_count: BuiltinCount { pedersen: 10, ..BuiltinCount::max() },
+        ..BouncerWeights::max()
+    };
+    let weights = BouncerWeights {
+        n_steps: 255,
+        n_events: 1,
+        builtin_count: BuiltinCount { pedersen: 10, ..Default::default() },
+        ..Default::default()
+    };
+
+    let used_percents = capacity_used_percents(&weights, &block_max_capacity);
+    assert_eq!(used_percents.len(), 15);
+    let used_percent = |resource: &str| {
+        used_percents.iter().find(|(name, _)| *name == resource).map(|(_, percent)| *percent)
+    };
+    assert_eq!(used_percent("n_steps"), Some(25));
+    assert_eq!(used_percent("pedersen"), Some(100));
+    // A resource that is barely used by a huge capacity rounds down.
+    assert_eq!(used_percent("n_events"), Some(0));
+    assert_eq!(used_percent("gas"), Some(0));
+}
@@ -0,0 +1,149 @@
+//! Detection of skew between the local clock and the clocks of the other validators.
+//!
+//! A proposer sets the timestamp of its proposal from its own clock, so the offset of the
+//! timestamp of a received proposal from the local clock samples how far the local clock is behind
+//! the proposer's. [`ClockSkewDetector`] keeps the offsets of the last received proposals, and
+//! estimates the skew of the local clock as their median, which a minority of skewed proposers
+//! doesn't move. The estimate is recorded in the [`BATCHER_CLOCK_SKEW_SECS`] metric, and a warning
+//! is logged once it exceeds the threshold: a lagging local clock makes the node reject valid
+//! proposals as too far ahead of it.
+//!
+//! If enabled, the tolerance for proposals ahead of the local clock is widened by the estimated lag
+//! of the local clock, up to a bound.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Duration;
+
+use papyrus_config::converters::deserialize_seconds_to_duration;
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+use starknet_mempool_infra::metrics::{BATCHER_CLOCK_SKEW_SECS, COMPONENT_LABEL};
+use tracing::{info, warn};
+
+/// The number of proposals to receive before estimating the skew.
+const MIN_SAMPLES: usize = 3;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ClockSkewConfig {
+    /// The number of last received proposals whose timestamps the skew is estimated from.
+    pub n_samples: usize,
+    #[serde(deserialize_with = "deserialize_seconds_to_duration")]
+    pub warning_threshold: Duration,
+    /// Whether to widen the tolerance for proposals ahead of the local clock by its estimated lag.
+    pub widen_timestamp_tolerance: bool,
+    #[serde(deserialize_with = "deserialize_seconds_to_duration")]
+    pub max_tolerance_widening: Duration,
+}
+
+impl Default for ClockSkewConfig {
+    fn default() -> Self {
+        Self {
+            n_samples: 20,
+            warning_threshold: Duration::from_secs(5),
+            widen_timestamp_tolerance: false,
+            max_tolerance_widening: Duration::from_secs(30),
+        }
+    }
+}
+
+impl SerializeConfig for ClockSkewConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "n_samples",
+                &self.n_samples,
+                "The number of last received proposals whose timestamps the skew of the local \
+                 clock is estimated from",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "warning_threshold",
+                &self.warning_threshold.as_secs(),
+                "The estimated skew (seconds) of the local clock from which a warning is logged",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "widen_timestamp_tolerance",
+                &self.widen_timestamp_tolerance,
+                "If true, the tolerance for proposals ahead of the local clock is widened by the \
+                 estimated lag of the local clock",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_tolerance_widening",
+                &self.max_tolerance_widening.as_secs(),
+                "The maximal widening (seconds) of the tolerance for proposals ahead of the local \
+                 clock",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+#[derive(Debug)]
+pub struct ClockSkewDetector {
+    config: ClockSkewConfig,
+    /// The offsets of the timestamps of the last received proposals from the local clock, in
+    /// seconds, oldest first.
+    offsets: VecDeque<i64>,
+    skew_detected: bool,
+}
+
+impl ClockSkewDetector {
+    pub fn new(config: ClockSkewConfig) -> Self {
+        Self { config, offsets: VecDeque::new(), skew_detected: false }
+    }
+
+    /// Records the timestamp of a received proposal, given the local clock (`now`), both in
+    /// seconds since the epoch.
+    pub fn record_proposal_timestamp(&mut self, timestamp: u64, now: u64) {
+        let offset = i128::from(timestamp) - i128::from(now);
+        let offset = i64::try_from(offset).unwrap_or(if offset > 0 { i64::MAX } else { i64::MIN });
+        self.offsets.push_back(offset);
+        while self.offsets.len() > self.config.n_samples.max(1) {
+            self.offsets.pop_front();
+        }
+
+        let Some(skew) = self.estimated_skew() else {
+            return;
+        };
+        let skew_secs = i32::try_from(skew).unwrap_or(if skew > 0 { i32::MAX } else { i32::MIN });
+        metrics::gauge!(
+            BATCHER_CLOCK_SKEW_SECS,
+            f64::from(skew_secs),
+            COMPONENT_LABEL => "batcher"
+        );
+        let skew_detected = skew.unsigned_abs() > self.config.warning_threshold.as_secs();
+        if skew_detected && !self.skew_detected {
+            warn!(
+                "The local clock is estimated to be {skew} seconds behind the clocks of the \
+                 proposers (negative if ahead), which may cause valid proposals to be rejected."
+            );
+        } else if !skew_detected && self.skew_detected {
+            info!("The local clock is back in sync with the clocks of the proposers.");
+        }
+        self.skew_detected = skew_detected;
+    }
+
+    /// The estimated lag of the local clock behind the clocks of the proposers, in seconds; a
+    /// negative skew means the local clock is ahead. None until enough proposals were received.
+    pub fn estimated_skew(&self) -> Option<i64> {
+        if self.offsets.len() < MIN_SAMPLES.min(self.config.n_samples.max(1)) {
+            return None;
+        }
+        let mut offsets: Vec<i64> = self.offsets.iter().copied().collect();
+        offsets.sort_unstable();
+        Some(offsets[offsets.len() / 2])
+    }
+
+    /// Returns how far ahead of the local clock the timestamp of a proposal may be, given the
+    /// configured tolerance.
+    pub fn timestamp_tolerance(&self, max_timestamp_drift: Duration) -> Duration {
+        if !self.config.widen_timestamp_tolerance {
+            return max_timestamp_drift;
+        }
+        let lag = self.estimated_skew().unwrap_or_default().max(0).unsigned_abs();
+        max_timestamp_drift + Duration::from_secs(lag).min(self.config.max_tolerance_widening)
+    }
+}
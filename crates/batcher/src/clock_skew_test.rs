@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use crate::clock_skew::{ClockSkewConfig, ClockSkewDetector};
+
+const NOW: u64 = 1_000_000;
+const MAX_TIMESTAMP_DRIFT: Duration = Duration::from_secs(15);
+
+fn detector(widen_timestamp_tolerance: bool) -> ClockSkewDetector {
+    ClockSkewDetector::new(ClockSkewConfig {
+        n_samples: 5,
+        widen_timestamp_tolerance,
+        max_tolerance_widening: Duration::from_secs(30),
+        ..Default::default()
+    })
+}
+
+fn record_offsets(detector: &mut ClockSkewDetector, offsets: &[i64]) {
+    for offset in offsets {
+        detector.record_proposal_timestamp(NOW.checked_add_signed(*offset).unwrap(), NOW);
+    }
+}
+
+#[test]
+fn skew_is_estimated_once_enough_proposals_are_received() {
+    let mut detector = detector(false);
+    record_offsets(&mut detector, &[10, 12]);
+    assert_eq!(detector.estimated_skew(), None);
+
+    record_offsets(&mut detector, &[11]);
+    assert_eq!(detector.estimated_skew(), Some(11));
+}
+
+#[test]
+fn skew_is_the_median_of_the_last_proposals() {
+    let mut detector = detector(false);
+    // A single skewed proposer doesn't move the estimate.
+    record_offsets(&mut detector, &[-1, 0, 1000, 0, -2]);
+    assert_eq!(detector.estimated_skew(), Some(0));
+
+    // The oldest offsets are dropped.
+    record_offsets(&mut detector, &[-20, -21, -22]);
+    assert_eq!(detector.estimated_skew(), Some(-20));
+}
+
+#[test]
+fn timestamp_tolerance_is_widened_by_the_lag() {
+    let cases: [(bool, &[i64], Duration); 5] = [
+        // Disabled.
+        (false, &[20, 20, 20], MAX_TIMESTAMP_DRIFT),
+        // A lagging local clock.
+        (true, &[20, 20, 20], Duration::from_secs(35)),
+        // The widening is capped.
+        (true, &[100, 100, 100], Duration::from_secs(45)),
+        // A local clock that's ahead.
+        (true, &[-20, -20, -20], MAX_TIMESTAMP_DRIFT),
+        // Not enough proposals were received.
+        (true, &[20], MAX_TIMESTAMP_DRIFT),
+    ];
+    for (widen_timestamp_tolerance, offsets, expected_tolerance) in cases {
+        let mut detector = detector(widen_timestamp_tolerance);
+        record_offsets(&mut detector, offsets);
+        assert_eq!(detector.timestamp_tolerance(MAX_TIMESTAMP_DRIFT), expected_tolerance);
+    }
+}
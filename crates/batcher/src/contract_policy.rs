@@ -0,0 +1,201 @@
+//! A local policy of the contracts whose transactions the node proposes.
+//!
+//! Operators may exclude contracts, by address or by class hash, e.g. for compliance or in an
+//! emergency, or restrict the blocks to an allowlist of contract addresses. The batcher doesn't
+//! select transactions that violate the policy into its proposals; they're left in the mempool for
+//! other nodes to propose.
+//!
+//! The policy covers only the contracts a transaction names: its sender (or the deployed account)
+//! and the class it declares or deploys. The contracts that a transaction calls during its
+//! validation or execution aren't covered, since the batcher selects transactions without executing
+//! them. The proposals of other nodes aren't checked either: the policy is local, so it doesn't
+//! affect their validity.
+
+use std::collections::{BTreeMap, HashSet};
+
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use starknet_api::core::{ClassHash, ContractAddress};
+use starknet_api::executable_transaction::Transaction;
+use starknet_api::transaction::TransactionHash;
+use starknet_types_core::felt::Felt;
+use thiserror::Error;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ContractPolicyConfig {
+    /// Contracts whose transactions, including their deployment, aren't proposed.
+    #[serde(serialize_with = "serialize_addresses", deserialize_with = "deserialize_addresses")]
+    pub excluded_contract_addresses: Vec<ContractAddress>,
+    /// Classes that aren't declared, and whose accounts aren't deployed, in the proposed blocks.
+    #[serde(
+        serialize_with = "serialize_class_hashes",
+        deserialize_with = "deserialize_class_hashes"
+    )]
+    pub excluded_class_hashes: Vec<ClassHash>,
+    /// If not empty, only the transactions of these contracts are proposed.
+    #[serde(serialize_with = "serialize_addresses", deserialize_with = "deserialize_addresses")]
+    pub allowed_contract_addresses: Vec<ContractAddress>,
+}
+
+impl SerializeConfig for ContractPolicyConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "excluded_contract_addresses",
+                &addresses_to_string(&self.excluded_contract_addresses),
+                "A comma separated list of contract addresses whose transactions, including their \
+                 deployment, aren't in the blocks the node proposes",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "excluded_class_hashes",
+                &class_hashes_to_string(&self.excluded_class_hashes),
+                "A comma separated list of class hashes that aren't declared, and whose accounts \
+                 aren't deployed, in the blocks the node proposes",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "allowed_contract_addresses",
+                &addresses_to_string(&self.allowed_contract_addresses),
+                "A comma separated list of the only contract addresses whose transactions are in \
+                 the blocks the node proposes. If empty, the transactions of any contract that \
+                 isn't excluded are",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum ContractPolicyViolation {
+    #[error("Transaction {tx_hash} is of the excluded contract {contract_address}.")]
+    ExcludedContract { tx_hash: TransactionHash, contract_address: ContractAddress },
+    #[error("Transaction {tx_hash} declares or deploys the excluded class {class_hash}.")]
+    ExcludedClass { tx_hash: TransactionHash, class_hash: ClassHash },
+    #[error("Transaction {tx_hash} is of the contract {contract_address}, which isn't allowed.")]
+    ContractNotAllowed { tx_hash: TransactionHash, contract_address: ContractAddress },
+}
+
+pub type ContractPolicyResult = Result<(), ContractPolicyViolation>;
+
+/// Checks transactions against a [`ContractPolicyConfig`].
+#[derive(Clone, Debug, Default)]
+pub struct ContractPolicy {
+    excluded_contract_addresses: HashSet<ContractAddress>,
+    excluded_class_hashes: HashSet<ClassHash>,
+    allowed_contract_addresses: HashSet<ContractAddress>,
+}
+
+impl ContractPolicy {
+    pub fn new(config: &ContractPolicyConfig) -> Self {
+        Self {
+            excluded_contract_addresses: config
+                .excluded_contract_addresses
+                .iter()
+                .copied()
+                .collect(),
+            excluded_class_hashes: config.excluded_class_hashes.iter().copied().collect(),
+            allowed_contract_addresses: config.allowed_contract_addresses.iter().copied().collect(),
+        }
+    }
+
+    /// Checks the contracts a transaction names. The contracts it calls aren't checked.
+    pub fn check_transaction(&self, tx: &Transaction) -> ContractPolicyResult {
+        let tx_hash = tx.tx_hash();
+        self.check_contract(tx_hash, tx.contract_address())?;
+        let class_hash = match tx {
+            Transaction::Declare(declare_tx) => Some(declare_tx.tx.class_hash()),
+            Transaction::DeployAccount(deploy_account_tx) => {
+                Some(deploy_account_tx.tx.class_hash())
+            }
+            Transaction::Invoke(_) => None,
+        };
+        match class_hash {
+            Some(class_hash) => self.check_class(tx_hash, class_hash),
+            None => Ok(()),
+        }
+    }
+
+    fn check_contract(
+        &self,
+        tx_hash: TransactionHash,
+        contract_address: ContractAddress,
+    ) -> ContractPolicyResult {
+        if self.excluded_contract_addresses.contains(&contract_address) {
+            return Err(ContractPolicyViolation::ExcludedContract { tx_hash, contract_address });
+        }
+        if !self.allowed_contract_addresses.is_empty()
+            && !self.allowed_contract_addresses.contains(&contract_address)
+        {
+            return Err(ContractPolicyViolation::ContractNotAllowed { tx_hash, contract_address });
+        }
+        Ok(())
+    }
+
+    fn check_class(&self, tx_hash: TransactionHash, class_hash: ClassHash) -> ContractPolicyResult {
+        if self.excluded_class_hashes.contains(&class_hash) {
+            return Err(ContractPolicyViolation::ExcludedClass { tx_hash, class_hash });
+        }
+        Ok(())
+    }
+}
+
+fn felts_to_string(felts: impl Iterator<Item = Felt>) -> String {
+    felts.map(|felt| format!("{felt:#x}")).collect::<Vec<_>>().join(",")
+}
+
+fn addresses_to_string(addresses: &[ContractAddress]) -> String {
+    felts_to_string(addresses.iter().map(|address| Felt::from(*address)))
+}
+
+fn class_hashes_to_string(class_hashes: &[ClassHash]) -> String {
+    felts_to_string(class_hashes.iter().map(|class_hash| class_hash.0))
+}
+
+fn serialize_addresses<S: Serializer>(
+    addresses: &[ContractAddress],
+    ser: S,
+) -> Result<S::Ok, S::Error> {
+    ser.serialize_str(&addresses_to_string(addresses))
+}
+
+fn serialize_class_hashes<S: Serializer>(
+    class_hashes: &[ClassHash],
+    ser: S,
+) -> Result<S::Ok, S::Error> {
+    ser.serialize_str(&class_hashes_to_string(class_hashes))
+}
+
+fn deserialize_addresses<'de, D: Deserializer<'de>>(
+    de: D,
+) -> Result<Vec<ContractAddress>, D::Error> {
+    deserialize_felts(de)?
+        .into_iter()
+        .map(|felt| {
+            ContractAddress::try_from(felt)
+                .map_err(|_| D::Error::custom(format!("Invalid contract address '{felt:#x}'.")))
+        })
+        .collect()
+}
+
+fn deserialize_class_hashes<'de, D: Deserializer<'de>>(de: D) -> Result<Vec<ClassHash>, D::Error> {
+    Ok(deserialize_felts(de)?.into_iter().map(ClassHash).collect())
+}
+
+// Deserializes a comma separated list of hex values. An empty string is deserialized as an empty
+// list.
+fn deserialize_felts<'de, D: Deserializer<'de>>(de: D) -> Result<Vec<Felt>, D::Error> {
+    let raw_str = String::deserialize(de)?;
+    if raw_str.is_empty() {
+        return Ok(Vec::new());
+    }
+    raw_str
+        .split(',')
+        .map(|value| {
+            let value = value.trim();
+            Felt::from_hex(value).map_err(|_| D::Error::custom(format!("Invalid value '{value}'.")))
+        })
+        .collect()
+}
@@ -0,0 +1,104 @@
+use starknet_api::core::{ClassHash, ContractAddress, PatriciaKey};
+use starknet_api::executable_transaction::{
+    DeployAccountTransaction,
+    InvokeTransaction,
+    Transaction,
+};
+use starknet_api::transaction::{DeployAccountTransactionV1, InvokeTransactionV1, TransactionHash};
+use starknet_api::{class_hash, contract_address, felt, patricia_key};
+
+use crate::contract_policy::{ContractPolicy, ContractPolicyConfig, ContractPolicyViolation};
+
+const TX_HASH: TransactionHash = TransactionHash(starknet_types_core::felt::Felt::ONE);
+
+fn invoke_tx(sender_address: ContractAddress) -> Transaction {
+    Transaction::Invoke(InvokeTransaction {
+        tx: starknet_api::transaction::InvokeTransaction::V1(InvokeTransactionV1 {
+            sender_address,
+            ..Default::default()
+        }),
+        tx_hash: TX_HASH,
+    })
+}
+
+fn deploy_account_tx(contract_address: ContractAddress, class_hash: ClassHash) -> Transaction {
+    Transaction::DeployAccount(DeployAccountTransaction {
+        tx: starknet_api::transaction::DeployAccountTransaction::V1(DeployAccountTransactionV1 {
+            class_hash,
+            ..Default::default()
+        }),
+        tx_hash: TX_HASH,
+        contract_address,
+    })
+}
+
+#[test]
+fn excluded_contracts_and_classes() {
+    let policy = ContractPolicy::new(&ContractPolicyConfig {
+        excluded_contract_addresses: vec![contract_address!("0x1")],
+        excluded_class_hashes: vec![class_hash!("0x2")],
+        ..Default::default()
+    });
+
+    assert_eq!(policy.check_transaction(&invoke_tx(contract_address!("0x3"))), Ok(()));
+    assert_eq!(
+        policy.check_transaction(&invoke_tx(contract_address!("0x1"))),
+        Err(ContractPolicyViolation::ExcludedContract {
+            tx_hash: TX_HASH,
+            contract_address: contract_address!("0x1")
+        })
+    );
+    assert_eq!(
+        policy.check_transaction(&deploy_account_tx(contract_address!("0x3"), class_hash!("0x2"))),
+        Err(ContractPolicyViolation::ExcludedClass {
+            tx_hash: TX_HASH,
+            class_hash: class_hash!("0x2")
+        })
+    );
+}
+
+#[test]
+fn allowed_contracts() {
+    let policy = ContractPolicy::new(&ContractPolicyConfig {
+        allowed_contract_addresses: vec![contract_address!("0x1"), contract_address!("0x2")],
+        ..Default::default()
+    });
+
+    assert_eq!(policy.check_transaction(&invoke_tx(contract_address!("0x1"))), Ok(()));
+    assert_eq!(
+        policy.check_transaction(&deploy_account_tx(contract_address!("0x2"), class_hash!("0x4"))),
+        Ok(())
+    );
+    assert_eq!(
+        policy.check_transaction(&invoke_tx(contract_address!("0x3"))),
+        Err(ContractPolicyViolation::ContractNotAllowed {
+            tx_hash: TX_HASH,
+            contract_address: contract_address!("0x3")
+        })
+    );
+}
+
+#[test]
+fn deserialize_config() {
+    let config: ContractPolicyConfig = serde_json::from_str(
+        r#"{
+            "excluded_contract_addresses": "0x1, 0x2",
+            "excluded_class_hashes": "0x3",
+            "allowed_contract_addresses": ""
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        config,
+        ContractPolicyConfig {
+            excluded_contract_addresses: vec![contract_address!("0x1"), contract_address!("0x2")],
+            excluded_class_hashes: vec![class_hash!("0x3")],
+            allowed_contract_addresses: vec![],
+        }
+    );
+    assert_eq!(
+        serde_json::from_str::<ContractPolicyConfig>(&serde_json::to_string(&config).unwrap())
+            .unwrap(),
+        config
+    );
+}
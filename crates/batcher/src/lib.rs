@@ -1,6 +1,34 @@
 pub mod batcher;
+pub mod block_info;
+#[cfg(test)]
+mod block_info_test;
+pub mod block_info_validator;
+#[cfg(test)]
+mod block_info_validator_test;
+pub mod block_metrics;
+#[cfg(test)]
+mod block_metrics_test;
+pub mod clock_skew;
+#[cfg(test)]
+mod clock_skew_test;
 pub mod communication;
 pub mod config;
+pub mod contract_policy;
+#[cfg(test)]
+mod contract_policy_test;
+pub mod pending_block;
+pub mod proposal_artifacts;
+#[cfg(test)]
+mod proposal_artifacts_test;
+pub mod proposal_chunker;
+#[cfg(test)]
+mod proposal_chunker_test;
 pub mod proposals_manager;
 #[cfg(test)]
 mod proposals_manager_test;
+pub mod recent_transactions;
+#[cfg(test)]
+mod recent_transactions_test;
+pub mod transaction_prioritizer;
+#[cfg(test)]
+mod transaction_prioritizer_test;
@@ -0,0 +1,35 @@
+//! The view of the block that is currently being built by the batcher.
+//!
+//! While a proposal is generated, the batcher publishes the transactions it executed so far and
+//! the state diff they produced to a [`SharedPendingBlock`]. Readers (e.g. the JSON-RPC server
+//! answering queries on the pending block) layer it on top of the latest committed state.
+
+use std::sync::Arc;
+
+use starknet_api::block::BlockNumber;
+use starknet_api::executable_transaction::Transaction;
+use starknet_api::state::StateDiff;
+use starknet_api::transaction::TransactionHash;
+use tokio::sync::RwLock;
+
+/// A handle to the block in generation. None when no block is being generated.
+pub type SharedPendingBlock = Arc<RwLock<Option<PendingBlock>>>;
+
+/// The executed transactions and the intermediate state of the block in generation.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PendingBlock {
+    pub height: BlockNumber,
+    pub transactions: Vec<Transaction>,
+    /// The state diff of all the transactions in the block so far.
+    pub state_diff: StateDiff,
+}
+
+impl PendingBlock {
+    pub fn new(height: BlockNumber) -> Self {
+        Self { height, ..Default::default() }
+    }
+
+    pub fn get_transaction(&self, tx_hash: &TransactionHash) -> Option<&Transaction> {
+        self.transactions.iter().find(|tx| tx.tx_hash() == *tx_hash)
+    }
+}
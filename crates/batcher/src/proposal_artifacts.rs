@@ -0,0 +1,127 @@
+//! Persistence of the proposals in generation, for recovering from a crash mid-height.
+//!
+//! While a proposal is generated, the batcher persists the hashes of the transactions it selected
+//! so far, keyed by the height and round of the proposal, before streaming them. A node that
+//! restarts after streaming part of a proposal finds them, and abandons the proposal instead of
+//! proposing a conflicting block in the same round.
+//! The artifacts of a height are removed once a block is decided for it.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockNumber;
+use starknet_api::transaction::TransactionHash;
+use thiserror::Error;
+
+use crate::proposals_manager::Round;
+
+const ARTIFACTS_EXTENSION: &str = "json";
+const TEMPORARY_EXTENSION: &str = "tmp";
+
+/// The content of a proposal in generation, as much as was generated before it was persisted.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ProposalArtifacts {
+    pub height: BlockNumber,
+    pub round: Round,
+    /// The hashes of the transactions selected for the proposal, in their order in the block.
+    /// Includes the transactions that were selected but not added to the block yet.
+    pub tx_hashes: Vec<TransactionHash>,
+}
+
+#[derive(Debug, Error)]
+pub enum ProposalArtifactsError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Failed to (de)serialize the artifacts in {path:?}: {source}")]
+    Serialization { path: PathBuf, source: serde_json::Error },
+}
+
+pub type ProposalArtifactsResult<T> = Result<T, ProposalArtifactsError>;
+
+/// Stores the artifacts of each proposal in a file of its own in a directory.
+#[derive(Debug)]
+pub struct ProposalArtifactsStore {
+    dir: PathBuf,
+}
+
+impl ProposalArtifactsStore {
+    /// Opens the store in the given directory, creating the directory if it doesn't exist.
+    pub fn open(dir: PathBuf) -> ProposalArtifactsResult<Self> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    /// Replaces the persisted artifacts of the proposal. The artifacts are written to a temporary
+    /// file first, so a crash while writing leaves the previous artifacts intact.
+    pub async fn write(&self, artifacts: &ProposalArtifacts) -> ProposalArtifactsResult<()> {
+        let path = self.path(artifacts.height, artifacts.round);
+        let temporary_path = path.with_extension(TEMPORARY_EXTENSION);
+        let bytes = serde_json::to_vec(artifacts).map_err(|source| {
+            ProposalArtifactsError::Serialization { path: path.clone(), source }
+        })?;
+        tokio::fs::write(&temporary_path, bytes).await?;
+        tokio::fs::rename(temporary_path, path).await?;
+        Ok(())
+    }
+
+    /// Returns the artifacts of all the persisted proposals. Blocks, so it's meant for when the
+    /// node starts.
+    pub fn read_all(&self) -> ProposalArtifactsResult<Vec<ProposalArtifacts>> {
+        let mut all_artifacts = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|extension| extension.to_str())
+                != Some(ARTIFACTS_EXTENSION)
+            {
+                continue;
+            }
+            let bytes = fs::read(&path)?;
+            let artifacts = serde_json::from_slice(&bytes)
+                .map_err(|source| ProposalArtifactsError::Serialization { path, source })?;
+            all_artifacts.push(artifacts);
+        }
+        Ok(all_artifacts)
+    }
+
+    /// Removes the artifacts of the proposal, if they were persisted.
+    pub async fn remove(&self, height: BlockNumber, round: Round) -> ProposalArtifactsResult<()> {
+        remove_if_exists(&self.path(height, round)).await
+    }
+
+    /// Removes the artifacts of all the proposals up to the given height, inclusive.
+    pub async fn remove_up_to(&self, height: BlockNumber) -> ProposalArtifactsResult<()> {
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            // The height of the proposal is taken from the name of its file, so the file isn't
+            // read.
+            if parse_height(&path).is_some_and(|artifacts_height| artifacts_height <= height) {
+                remove_if_exists(&path).await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn path(&self, height: BlockNumber, round: Round) -> PathBuf {
+        self.dir.join(format!("{}_{round}.{ARTIFACTS_EXTENSION}", height.0))
+    }
+}
+
+// Returns the height of the proposal whose artifacts are in the given file, or None if the file
+// doesn't hold artifacts.
+fn parse_height(path: &Path) -> Option<BlockNumber> {
+    if path.extension().and_then(|extension| extension.to_str()) != Some(ARTIFACTS_EXTENSION) {
+        return None;
+    }
+    let (height, _round) = path.file_stem()?.to_str()?.split_once('_')?;
+    height.parse().ok().map(BlockNumber)
+}
+
+async fn remove_if_exists(path: &Path) -> ProposalArtifactsResult<()> {
+    match tokio::fs::remove_file(path).await {
+        Err(error) if error.kind() != ErrorKind::NotFound => Err(error.into()),
+        _ => Ok(()),
+    }
+}
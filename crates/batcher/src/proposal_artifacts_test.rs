@@ -0,0 +1,52 @@
+use starknet_api::block::BlockNumber;
+use starknet_api::felt;
+use starknet_api::transaction::TransactionHash;
+
+use crate::proposal_artifacts::{ProposalArtifacts, ProposalArtifactsStore};
+
+fn artifacts(height: u64, round: u32) -> ProposalArtifacts {
+    ProposalArtifacts {
+        height: BlockNumber(height),
+        round,
+        tx_hashes: vec![TransactionHash(felt!(height)), TransactionHash(felt!(u64::from(round)))],
+    }
+}
+
+fn sorted_artifacts(store: &ProposalArtifactsStore) -> Vec<ProposalArtifacts> {
+    let mut all_artifacts = store.read_all().unwrap();
+    all_artifacts.sort_by_key(|artifacts| (artifacts.height, artifacts.round));
+    all_artifacts
+}
+
+#[tokio::test]
+async fn write_replaces_artifacts_of_same_proposal() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = ProposalArtifactsStore::open(dir.path().to_path_buf()).unwrap();
+
+    store.write(&artifacts(1, 0)).await.unwrap();
+    let mut updated_artifacts = artifacts(1, 0);
+    updated_artifacts.tx_hashes.push(TransactionHash(felt!("0x2")));
+    store.write(&updated_artifacts).await.unwrap();
+    store.write(&artifacts(1, 1)).await.unwrap();
+
+    // The artifacts are read back after reopening the store, e.g. by a restarted node.
+    let store = ProposalArtifactsStore::open(dir.path().to_path_buf()).unwrap();
+    assert_eq!(sorted_artifacts(&store), vec![updated_artifacts, artifacts(1, 1)]);
+}
+
+#[tokio::test]
+async fn remove_artifacts() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = ProposalArtifactsStore::open(dir.path().to_path_buf()).unwrap();
+    for (height, round) in [(1, 0), (1, 1), (2, 0), (3, 0)] {
+        store.write(&artifacts(height, round)).await.unwrap();
+    }
+
+    store.remove(BlockNumber(3), 0).await.unwrap();
+    // Removing artifacts that weren't persisted is a no-op.
+    store.remove(BlockNumber(3), 1).await.unwrap();
+    assert_eq!(sorted_artifacts(&store), vec![artifacts(1, 0), artifacts(1, 1), artifacts(2, 0)]);
+
+    store.remove_up_to(BlockNumber(1)).await.unwrap();
+    assert_eq!(sorted_artifacts(&store), vec![artifacts(2, 0)]);
+}
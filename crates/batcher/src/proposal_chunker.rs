@@ -0,0 +1,240 @@
+//! Splits the transactions of a proposal in generation into the chunks the proposal is streamed in.
+//!
+//! A chunk is closed once it has `max_txs_per_chunk` transactions, once adding the next
+//! transaction would exceed `max_bytes_per_chunk`, or `max_chunk_delay` after its first
+//! transaction was added, so a slow proposal is still streamed as it's built. Hence, chunks of
+//! large transactions, e.g. declares, hold fewer transactions, and every chunk fits in a single
+//! network message. The proposal generation skips the transactions that are larger than
+//! `max_bytes_per_chunk` on their own; the chunker drops them. The size of a transaction is
+//! estimated by the felts it holds.
+//!
+//! The latency of each chunk, from adding its first transaction until it's streamed, is recorded.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use papyrus_config::converters::deserialize_milliseconds_to_duration;
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use papyrus_network::MAX_MESSAGE_SIZE;
+use serde::{Deserialize, Serialize};
+use starknet_api::executable_transaction::Transaction;
+use starknet_mempool_infra::metrics::{
+    BATCHER_PROPOSAL_CHUNK_BYTES,
+    BATCHER_PROPOSAL_CHUNK_LATENCY_SECS,
+    COMPONENT_LABEL,
+};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+// The size of a felt on the network.
+const FELT_BYTES: usize = 32;
+// A bound on the size of the fields of a transaction that aren't counted one by one: its hash,
+// sender, nonce, resource bounds, etc.
+const TX_FIXED_FIELDS_BYTES: usize = 32 * FELT_BYTES;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ProposalChunkingConfig {
+    pub max_txs_per_chunk: usize,
+    /// Capped by the maximal size of a network message.
+    pub max_bytes_per_chunk: usize,
+    #[serde(deserialize_with = "deserialize_milliseconds_to_duration")]
+    pub max_chunk_delay: Duration,
+}
+
+impl ProposalChunkingConfig {
+    /// The maximal size of a chunk, and hence of a transaction of a proposal, in bytes.
+    pub(crate) fn effective_max_bytes_per_chunk(&self) -> usize {
+        self.max_bytes_per_chunk.min(MAX_MESSAGE_SIZE)
+    }
+}
+
+impl Default for ProposalChunkingConfig {
+    fn default() -> Self {
+        Self {
+            max_txs_per_chunk: 100,
+            max_bytes_per_chunk: 512 * 1024,
+            max_chunk_delay: Duration::from_millis(100),
+        }
+    }
+}
+
+impl SerializeConfig for ProposalChunkingConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "max_txs_per_chunk",
+                &self.max_txs_per_chunk,
+                "Maximum transactions in a chunk of a streamed proposal",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_bytes_per_chunk",
+                &self.max_bytes_per_chunk,
+                "Maximum estimated size of a chunk of a streamed proposal, in bytes. Capped by \
+                 the maximal size of a network message",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_chunk_delay",
+                &self.max_chunk_delay.as_millis(),
+                "The time (milliseconds) after its first transaction is added, after which a \
+                 chunk of a streamed proposal is streamed even if it isn't full",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+/// A chunk of the transactions of a proposal.
+#[derive(Debug, PartialEq)]
+pub(crate) struct ProposalChunk {
+    pub txs: Vec<Transaction>,
+    /// When the first transaction of the chunk was added.
+    pub start: Instant,
+}
+
+pub(crate) struct ProposalChunker {
+    max_txs_per_chunk: usize,
+    max_bytes_per_chunk: usize,
+    max_chunk_delay: Duration,
+    chunk: Vec<Transaction>,
+    chunk_bytes: usize,
+    chunk_start: Instant,
+}
+
+impl ProposalChunker {
+    pub(crate) fn new(config: &ProposalChunkingConfig) -> Self {
+        if config.max_bytes_per_chunk > MAX_MESSAGE_SIZE {
+            warn!(
+                "The maximal chunk size {} exceeds the maximal network message size; using {} \
+                 instead.",
+                config.max_bytes_per_chunk, MAX_MESSAGE_SIZE
+            );
+        }
+        Self {
+            max_txs_per_chunk: config.max_txs_per_chunk.max(1),
+            max_bytes_per_chunk: config.effective_max_bytes_per_chunk(),
+            max_chunk_delay: config.max_chunk_delay,
+            chunk: Vec::new(),
+            chunk_bytes: 0,
+            chunk_start: Instant::now(),
+        }
+    }
+
+    /// Adds a transaction to the current chunk. Returns the chunk that was closed to make room for
+    /// the transaction, or the current chunk if the transaction filled it. A transaction that
+    /// doesn't fit in a chunk on its own is dropped.
+    pub(crate) fn push(&mut self, tx: Transaction) -> Option<ProposalChunk> {
+        let tx_bytes = estimated_size(&tx);
+        if tx_bytes > self.max_bytes_per_chunk {
+            warn!(
+                "Dropping transaction {} of {tx_bytes} bytes, which doesn't fit in a proposal \
+                 chunk.",
+                tx.tx_hash()
+            );
+            return None;
+        }
+        let mut closed_chunk = None;
+        let chunk_is_full = self.chunk.len() >= self.max_txs_per_chunk
+            || self.chunk_bytes + tx_bytes > self.max_bytes_per_chunk;
+        if !self.chunk.is_empty() && chunk_is_full {
+            closed_chunk = self.flush();
+        }
+        if self.chunk.is_empty() {
+            self.chunk_start = Instant::now();
+        }
+        self.chunk.push(tx);
+        self.chunk_bytes += tx_bytes;
+        if closed_chunk.is_none() && self.chunk.len() >= self.max_txs_per_chunk {
+            closed_chunk = self.flush();
+        }
+        closed_chunk
+    }
+
+    /// When the current chunk is due to be streamed even if it isn't full, if it isn't empty.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        if self.chunk.is_empty() {
+            return None;
+        }
+        Some(self.chunk_start + self.max_chunk_delay)
+    }
+
+    /// Closes the current chunk, if it isn't empty.
+    pub(crate) fn flush(&mut self) -> Option<ProposalChunk> {
+        if self.chunk.is_empty() {
+            return None;
+        }
+        metrics::histogram!(
+            BATCHER_PROPOSAL_CHUNK_BYTES,
+            f64::from(u32::try_from(self.chunk_bytes).unwrap_or(u32::MAX)),
+            COMPONENT_LABEL => "batcher"
+        );
+        self.chunk_bytes = 0;
+        Some(ProposalChunk { txs: std::mem::take(&mut self.chunk), start: self.chunk_start })
+    }
+}
+
+/// Streams the transactions received from `tx_receiver` in chunks, until the receiver is closed.
+pub(crate) async fn stream_chunks(
+    mut chunker: ProposalChunker,
+    mut tx_receiver: mpsc::Receiver<Transaction>,
+    chunk_sender: mpsc::Sender<Vec<Transaction>>,
+) {
+    loop {
+        let deadline = chunker.deadline();
+        let chunk = tokio::select! {
+            tx = tx_receiver.recv() => match tx {
+                Some(tx) => chunker.push(tx),
+                None => break,
+            },
+            _ = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now)),
+                if deadline.is_some() => chunker.flush(),
+        };
+        if let Some(chunk) = chunk {
+            if !send_chunk(&chunk_sender, chunk).await {
+                return;
+            }
+        }
+    }
+    if let Some(chunk) = chunker.flush() {
+        send_chunk(&chunk_sender, chunk).await;
+    }
+}
+
+// Returns false if the receiver of the chunks is closed.
+async fn send_chunk(chunk_sender: &mpsc::Sender<Vec<Transaction>>, chunk: ProposalChunk) -> bool {
+    let n_txs = chunk.txs.len();
+    if chunk_sender.send(chunk.txs).await.is_err() {
+        debug!("The receiver of the proposal chunks is closed.");
+        return false;
+    }
+    metrics::histogram!(
+        BATCHER_PROPOSAL_CHUNK_LATENCY_SECS,
+        chunk.start.elapsed().as_secs_f64(),
+        COMPONENT_LABEL => "batcher"
+    );
+    debug!("Streamed a proposal chunk of {n_txs} transactions.");
+    true
+}
+
+/// Estimates the size of a transaction on the network by the felts it holds, without encoding it.
+pub(crate) fn estimated_size(tx: &Transaction) -> usize {
+    let n_felts = match tx {
+        Transaction::Declare(tx) => {
+            tx.tx.signature().0.len()
+                + tx.class_info.sierra_program_length
+                + tx.class_info.casm_contract_class.bytecode.len()
+        }
+        Transaction::DeployAccount(tx) => {
+            tx.signature().0.len() + tx.constructor_calldata().0.len()
+        }
+        Transaction::Invoke(tx) => tx.signature().0.len() + tx.calldata().0.len(),
+    };
+    let abi_bytes = match tx {
+        Transaction::Declare(tx) => tx.class_info.abi_length,
+        Transaction::DeployAccount(_) | Transaction::Invoke(_) => 0,
+    };
+    TX_FIXED_FIELDS_BYTES + n_felts * FELT_BYTES + abi_bytes
+}
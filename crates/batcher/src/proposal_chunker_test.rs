@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use papyrus_network::MAX_MESSAGE_SIZE;
+use starknet_api::calldata;
+use starknet_api::executable_transaction::{InvokeTransaction, Transaction};
+use starknet_api::transaction::{Calldata, InvokeTransactionV1, TransactionHash};
+use starknet_types_core::felt::Felt;
+use tokio::sync::mpsc;
+
+use crate::proposal_chunker::{
+    estimated_size,
+    stream_chunks,
+    ProposalChunk,
+    ProposalChunker,
+    ProposalChunkingConfig,
+};
+
+const MAX_CHUNK_DELAY: Duration = Duration::from_secs(60);
+
+fn tx(hash: u64) -> Transaction {
+    Transaction::Invoke(InvokeTransaction {
+        tx: starknet_api::transaction::InvokeTransaction::V1(InvokeTransactionV1::default()),
+        tx_hash: TransactionHash(Felt::from(hash)),
+    })
+}
+
+fn chunk_txs(chunk: Option<ProposalChunk>) -> Option<Vec<Transaction>> {
+    chunk.map(|chunk| chunk.txs)
+}
+
+#[test]
+fn chunk_is_closed_by_number_of_transactions() {
+    let mut chunker = ProposalChunker::new(&ProposalChunkingConfig {
+        max_txs_per_chunk: 2,
+        max_bytes_per_chunk: MAX_MESSAGE_SIZE,
+        max_chunk_delay: MAX_CHUNK_DELAY,
+    });
+
+    assert_eq!(chunk_txs(chunker.push(tx(1))), None);
+    assert_eq!(chunk_txs(chunker.push(tx(2))), Some(vec![tx(1), tx(2)]));
+    assert_eq!(chunk_txs(chunker.push(tx(3))), None);
+    assert_eq!(chunk_txs(chunker.flush()), Some(vec![tx(3)]));
+    assert_eq!(chunk_txs(chunker.flush()), None);
+}
+
+#[test]
+fn chunk_is_closed_by_size() {
+    let tx_bytes = estimated_size(&tx(1));
+    // Room for 2 transactions and a half.
+    let mut chunker = ProposalChunker::new(&ProposalChunkingConfig {
+        max_txs_per_chunk: 100,
+        max_bytes_per_chunk: tx_bytes * 5 / 2,
+        max_chunk_delay: MAX_CHUNK_DELAY,
+    });
+
+    assert_eq!(chunk_txs(chunker.push(tx(1))), None);
+    assert_eq!(chunk_txs(chunker.push(tx(2))), None);
+    assert_eq!(chunk_txs(chunker.push(tx(3))), Some(vec![tx(1), tx(2)]));
+    assert_eq!(chunk_txs(chunker.flush()), Some(vec![tx(3)]));
+}
+
+#[test]
+fn oversized_transaction_is_dropped() {
+    let tx_bytes = estimated_size(&tx(1));
+    let mut chunker = ProposalChunker::new(&ProposalChunkingConfig {
+        max_txs_per_chunk: 100,
+        max_bytes_per_chunk: tx_bytes / 2,
+        max_chunk_delay: MAX_CHUNK_DELAY,
+    });
+
+    assert_eq!(chunk_txs(chunker.push(tx(1))), None);
+    assert_eq!(chunk_txs(chunker.flush()), None);
+}
+
+#[test]
+fn estimated_size_counts_the_felts() {
+    let tx_with_calldata = Transaction::Invoke(InvokeTransaction {
+        tx: starknet_api::transaction::InvokeTransaction::V1(InvokeTransactionV1 {
+            calldata: calldata![Felt::ONE, Felt::TWO],
+            ..Default::default()
+        }),
+        tx_hash: TransactionHash(Felt::ONE),
+    });
+
+    assert_eq!(estimated_size(&tx_with_calldata), estimated_size(&tx(1)) + 2 * 32);
+}
+
+#[tokio::test]
+async fn chunk_is_streamed_after_the_max_delay() {
+    let chunker = ProposalChunker::new(&ProposalChunkingConfig {
+        max_txs_per_chunk: 100,
+        max_bytes_per_chunk: MAX_MESSAGE_SIZE,
+        max_chunk_delay: Duration::ZERO,
+    });
+    let (tx_sender, tx_receiver) = mpsc::channel(10);
+    let (chunk_sender, mut chunk_receiver) = mpsc::channel(10);
+    tokio::spawn(stream_chunks(chunker, tx_receiver, chunk_sender));
+
+    // The chunk isn't full, and the transactions are still being received.
+    tx_sender.send(tx(1)).await.unwrap();
+    assert_eq!(chunk_receiver.recv().await, Some(vec![tx(1)]));
+}
+
+#[tokio::test]
+async fn stream_transactions_in_chunks() {
+    let chunker = ProposalChunker::new(&ProposalChunkingConfig {
+        max_txs_per_chunk: 2,
+        max_bytes_per_chunk: MAX_MESSAGE_SIZE,
+        max_chunk_delay: MAX_CHUNK_DELAY,
+    });
+    let (tx_sender, tx_receiver) = mpsc::channel(10);
+    let (chunk_sender, mut chunk_receiver) = mpsc::channel(10);
+    for hash in 1..=3 {
+        tx_sender.send(tx(hash)).await.unwrap();
+    }
+    // The last chunk is streamed once all the transactions were received.
+    drop(tx_sender);
+
+    stream_chunks(chunker, tx_receiver, chunk_sender).await;
+
+    assert_eq!(chunk_receiver.recv().await, Some(vec![tx(1), tx(2)]));
+    assert_eq!(chunk_receiver.recv().await, Some(vec![tx(3)]));
+    assert_eq!(chunk_receiver.recv().await, None);
+}
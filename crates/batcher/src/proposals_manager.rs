@@ -1,49 +1,233 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use papyrus_config::dumping::{ser_param, SerializeConfig};
+use blockifier::blockifier::block::{BlockInfo, ParentBlockInfo};
+use blockifier::bouncer::BouncerWeights;
+use blockifier::fee::fee_market::FeeMarketConfig;
+use blockifier::transaction::objects::FeeType;
+use blockifier::versioned_constants::VersionedConstants;
+use papyrus_base_layer::l1_gas_price::{GasPriceProvider, GasPriceProviderError};
+use papyrus_base_layer::l1_messages::{L1HandlerMessage, SharedL1HandlerQueue};
+use papyrus_config::dumping::{
+    append_sub_config_name,
+    ser_optional_param,
+    ser_param,
+    SerializeConfig,
+};
+use papyrus_config::validators::cross_param_validation_error;
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use serde::{Deserialize, Serialize};
-use starknet_api::block::BlockNumber;
+use starknet_api::block::{BlockNumber, BlockTimestamp};
+use starknet_api::core::ContractAddress;
 use starknet_api::executable_transaction::Transaction;
+use starknet_api::transaction::{L1HandlerTransaction, TransactionHash};
+use starknet_mempool_infra::metrics::{
+    BATCHER_PROPOSAL_TRANSACTIONS,
+    COMPONENT_LABEL,
+    HEIGHT_LABEL,
+};
 use starknet_mempool_types::communication::{MempoolClientError, SharedMempoolClient};
+use starknet_mempool_types::mempool_types::{AccountState, LeaseId};
 use thiserror::Error;
 use tokio::sync::Mutex;
+use tokio::task::AbortHandle;
 use tokio_stream::wrappers::ReceiverStream;
-use tracing::{debug, error, info, instrument};
+use tracing::{debug, debug_span, error, info, info_span, instrument, warn, Instrument};
+use validator::{Validate, ValidationError};
+
+use crate::block_info::{BlockInfoBuilder, ParentBlockUsage};
+use crate::block_info_validator::{
+    validate_block_info,
+    BlockInfoValidationConfig,
+    BlockInfoValidationError,
+};
+use crate::block_metrics::{
+    record_capacity_used,
+    record_deadline_slack,
+    record_rejected_txs,
+    RejectionReason,
+};
+use crate::clock_skew::{ClockSkewConfig, ClockSkewDetector};
+use crate::contract_policy::{ContractPolicy, ContractPolicyConfig};
+use crate::pending_block::{PendingBlock, SharedPendingBlock};
+use crate::proposal_artifacts::{
+    ProposalArtifacts,
+    ProposalArtifactsError,
+    ProposalArtifactsStore,
+};
+use crate::proposal_chunker::{
+    estimated_size,
+    stream_chunks,
+    ProposalChunker,
+    ProposalChunkingConfig,
+};
+use crate::recent_transactions::RecentTransactions;
+use crate::transaction_prioritizer::ResourceProfiles;
 
 // TODO: Should be defined in SN_API probably (shared with the consensus).
 pub type ProposalId = u64;
+pub type Round = u32;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_block_capacity_fits_a_tx", skip_on_field_errors = false))]
 pub struct ProposalsManagerConfig {
     pub max_txs_per_mempool_request: usize,
+    /// The maximal number of messages from L1 that a proposed block handles.
+    pub max_l1_handler_txs_per_proposal: usize,
     pub outstream_content_buffer_size: usize,
+    pub n_recent_blocks_to_deduplicate: usize,
+    pub prioritize_by_resources: bool,
+    #[validate(range(min = 1))]
+    pub n_resource_profiles: usize,
+    pub block_max_capacity: BouncerWeights,
+    /// Sets the L2 gas prices of the proposed blocks by how full their parent blocks were.
+    pub fee_market: FeeMarketConfig,
+    /// The address that collects the fees of the proposed blocks.
+    pub sequencer_address: ContractAddress,
+    /// Whether the proposed blocks publish their state diffs as blobs.
+    pub use_kzg_da: bool,
+    pub block_info_validation: BlockInfoValidationConfig,
+    pub clock_skew: ClockSkewConfig,
+    pub contract_policy: ContractPolicyConfig,
+    pub proposal_chunking: ProposalChunkingConfig,
+    /// The directory in which the proposals in generation are persisted. If None, they aren't.
+    pub proposal_artifacts_dir: Option<PathBuf>,
 }
 
 impl Default for ProposalsManagerConfig {
     fn default() -> Self {
         // TODO: Get correct value for default max_txs_per_mempool_request.
-        Self { max_txs_per_mempool_request: 10, outstream_content_buffer_size: 100 }
+        Self {
+            max_txs_per_mempool_request: 10,
+            max_l1_handler_txs_per_proposal: 10,
+            outstream_content_buffer_size: 100,
+            n_recent_blocks_to_deduplicate: 10,
+            prioritize_by_resources: false,
+            n_resource_profiles: 1000,
+            block_max_capacity: BouncerWeights::max(),
+            fee_market: FeeMarketConfig::default(),
+            sequencer_address: ContractAddress::default(),
+            use_kzg_da: true,
+            block_info_validation: BlockInfoValidationConfig::default(),
+            clock_skew: ClockSkewConfig::default(),
+            contract_policy: ContractPolicyConfig::default(),
+            proposal_chunking: ProposalChunkingConfig::default(),
+            proposal_artifacts_dir: None,
+        }
+    }
+}
+
+/// Validates that the block capacity fits the largest transaction the versioned constants allow;
+/// otherwise such a transaction can't be included in any block.
+fn validate_block_capacity_fits_a_tx(
+    config: &ProposalsManagerConfig,
+) -> Result<(), ValidationError> {
+    let versioned_constants = VersionedConstants::latest_constants();
+    let max_tx_n_steps = usize::try_from(
+        versioned_constants.invoke_tx_max_n_steps + versioned_constants.validate_max_n_steps,
+    )
+    .expect("The max number of steps of a transaction should fit in usize.");
+    let too_small_capacities: Vec<&str> = [
+        ("block_max_capacity.n_steps", config.block_max_capacity.n_steps, max_tx_n_steps),
+        (
+            "block_max_capacity.n_events",
+            config.block_max_capacity.n_events,
+            versioned_constants.tx_event_limits.max_n_emitted_events,
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, capacity, max_tx_weight)| capacity < max_tx_weight)
+    .map(|(param_path, ..)| param_path)
+    .collect();
+    if !too_small_capacities.is_empty() {
+        return Err(cross_param_validation_error(
+            "block capacity below a single transaction",
+            &too_small_capacities,
+            "The block capacity must fit the largest transaction allowed by the versioned \
+             constants."
+                .to_owned(),
+        ));
     }
+    Ok(())
 }
 
 impl SerializeConfig for ProposalsManagerConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
-        BTreeMap::from_iter([
+        let members = BTreeMap::from_iter([
             ser_param(
                 "max_txs_per_mempool_request",
                 &self.max_txs_per_mempool_request,
                 "Maximum transactions to get from the mempool per iteration of proposal generation",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "max_l1_handler_txs_per_proposal",
+                &self.max_l1_handler_txs_per_proposal,
+                "The maximal number of messages from L1 that a proposed block handles",
+                ParamPrivacyInput::Public,
+            ),
             ser_param(
                 "outstream_content_buffer_size",
                 &self.outstream_content_buffer_size,
                 "Maximum items to add to the outstream buffer before blocking",
                 ParamPrivacyInput::Public,
             ),
-        ])
+            ser_param(
+                "n_recent_blocks_to_deduplicate",
+                &self.n_recent_blocks_to_deduplicate,
+                "The number of last decided blocks whose transactions are never proposed again",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "prioritize_by_resources",
+                &self.prioritize_by_resources,
+                "If true, transactions from the mempool are ordered by their fee per the \
+                 estimated fraction of the block capacity they use",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "n_resource_profiles",
+                &self.n_resource_profiles,
+                "Maximum entry points whose resource usage is kept for prioritizing transactions",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "sequencer_address",
+                &self.sequencer_address,
+                "The address that collects the fees of the proposed blocks",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "use_kzg_da",
+                &self.use_kzg_da,
+                "Whether the proposed blocks publish their state diffs as blobs",
+                ParamPrivacyInput::Public,
+            ),
+        ]);
+
+        vec![
+            members,
+            ser_optional_param(
+                &self.proposal_artifacts_dir,
+                PathBuf::from("./data/proposal_artifacts"),
+                "proposal_artifacts_dir",
+                "The directory in which the proposals in generation are persisted, for recovering \
+                 from a crash mid-height",
+                ParamPrivacyInput::Public,
+            ),
+            append_sub_config_name(self.block_max_capacity.dump(), "block_max_capacity"),
+            append_sub_config_name(self.fee_market.dump(), "fee_market"),
+            append_sub_config_name(self.block_info_validation.dump(), "block_info_validation"),
+            append_sub_config_name(self.clock_skew.dump(), "clock_skew"),
+            append_sub_config_name(self.contract_policy.dump(), "contract_policy"),
+            append_sub_config_name(self.proposal_chunking.dump(), "proposal_chunking"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
     }
 }
 
@@ -57,10 +241,21 @@ pub enum ProposalsManagerError {
         current_generating_proposal_id: ProposalId,
         new_proposal_id: ProposalId,
     },
+    #[error(transparent)]
+    GasPriceProviderError(#[from] GasPriceProviderError),
     #[error("Internal error.")]
     InternalError,
+    #[error("Invalid block info: {0}")]
+    InvalidBlockInfo(#[from] BlockInfoValidationError),
     #[error(transparent)]
     MempoolError(#[from] MempoolClientError),
+    #[error(transparent)]
+    ProposalArtifactsError(#[from] ProposalArtifactsError),
+    #[error(
+        "The generation of the proposal for height {height} round {round} was interrupted by a \
+         restart; a conflicting proposal isn't generated for the same round."
+    )]
+    ProposalInterrupted { height: BlockNumber, round: Round },
 }
 
 pub type ProposalsManagerResult<T> = Result<T, ProposalsManagerError>;
@@ -74,51 +269,348 @@ pub type ProposalsManagerResult<T> = Result<T, ProposalsManagerError>;
 /// Triggered by the consensus.
 // TODO: Remove dead_code attribute.
 #[allow(dead_code)]
-pub(crate) struct ProposalsManager {
+pub struct ProposalsManager {
     config: ProposalsManagerConfig,
     mempool_client: SharedMempoolClient,
     /// The block proposal that is currently being proposed, if any.
     /// At any given time, there can be only one proposal being actively executed (either proposed
     /// or validated).
     proposal_in_generation: Arc<Mutex<Option<ProposalId>>>,
+    /// The block that is currently being generated, as published to readers of the pending state.
+    pending_block: SharedPendingBlock,
+    /// The transactions of the recently decided blocks and of the proposals in flight, which are
+    /// skipped when pulling transactions from the mempool.
+    recent_transactions: Arc<Mutex<RecentTransactions>>,
+    /// The resource usage of recently executed entry points, for prioritizing transactions.
+    resource_profiles: Arc<Mutex<ResourceProfiles>>,
+    /// The contracts whose transactions are proposed.
+    contract_policy: Arc<ContractPolicy>,
+    block_info_builder: BlockInfoBuilder,
+    /// The skew of the local clock, as estimated from the timestamps of the received proposals.
+    clock_skew: ClockSkewDetector,
+    /// The messages from L1 that weren't handled by a decided block yet.
+    l1_handler_queue: SharedL1HandlerQueue,
+    /// Where the proposals in generation are persisted, if they are.
+    artifacts_store: Option<Arc<ProposalArtifactsStore>>,
+    /// The proposals whose generation was interrupted by a restart of the node.
+    interrupted_proposals: BTreeSet<(BlockNumber, Round)>,
+    /// The proposals generated since the node started, until their height is decided or they're
+    /// aborted.
+    proposals: HashMap<ProposalId, ProposalHandle>,
+}
+
+/// A proposal generated by the node.
+struct ProposalHandle {
+    height: BlockNumber,
+    round: Round,
+    block_info: BlockInfo,
+    /// The messages from L1 that the block handles, before the transactions from the mempool.
+    l1_handler_messages: Vec<L1HandlerMessage>,
+    /// The mempool lease under which the transactions of the proposal are taken.
+    lease_id: LeaseId,
+    /// The weights of the transactions added to the block so far.
+    weights: Arc<Mutex<BouncerWeights>>,
+    /// Aborts the task that generates the proposal.
+    abort_handle: AbortHandle,
 }
 
 impl ProposalsManager {
     // TODO: Remove dead_code attribute.
     #[allow(dead_code)]
-    pub fn new(config: ProposalsManagerConfig, mempool_client: SharedMempoolClient) -> Self {
-        Self { config, mempool_client, proposal_in_generation: Arc::new(Mutex::new(None)) }
+    pub fn new(
+        config: ProposalsManagerConfig,
+        mempool_client: SharedMempoolClient,
+        gas_price_provider: GasPriceProvider,
+        l1_handler_queue: SharedL1HandlerQueue,
+    ) -> Self {
+        let recent_transactions =
+            Arc::new(Mutex::new(RecentTransactions::new(config.n_recent_blocks_to_deduplicate)));
+        let resource_profiles = Arc::new(Mutex::new(ResourceProfiles::new(
+            config.block_max_capacity,
+            NonZeroUsize::new(config.n_resource_profiles)
+                .expect("n_resource_profiles is validated to be positive."),
+        )));
+        let artifacts_store = config.proposal_artifacts_dir.clone().map(|dir| {
+            Arc::new(
+                ProposalArtifactsStore::open(dir)
+                    .expect("Failed to open the proposal artifacts directory."),
+            )
+        });
+        let interrupted_artifacts = match &artifacts_store {
+            Some(artifacts_store) => artifacts_store
+                .read_all()
+                .expect("Failed to read the persisted proposal artifacts."),
+            None => Vec::new(),
+        };
+        for ProposalArtifacts { height, round, tx_hashes } in &interrupted_artifacts {
+            info!(
+                "Found the artifacts of an interrupted proposal for height {height} round {round} \
+                 with {} transactions.",
+                tx_hashes.len()
+            );
+        }
+        let interrupted_proposals = interrupted_artifacts
+            .into_iter()
+            .map(|artifacts| (artifacts.height, artifacts.round))
+            .collect();
+        let contract_policy = Arc::new(ContractPolicy::new(&config.contract_policy));
+        let block_info_builder = BlockInfoBuilder::new(
+            config.sequencer_address,
+            config.use_kzg_da,
+            gas_price_provider,
+            config.fee_market.clone(),
+            config.block_max_capacity,
+        );
+        let clock_skew = ClockSkewDetector::new(config.clock_skew.clone());
+        Self {
+            config,
+            mempool_client,
+            proposal_in_generation: Arc::new(Mutex::new(None)),
+            pending_block: SharedPendingBlock::default(),
+            recent_transactions,
+            resource_profiles,
+            contract_policy,
+            block_info_builder,
+            clock_skew,
+            l1_handler_queue,
+            artifacts_store,
+            interrupted_proposals,
+            proposals: HashMap::new(),
+        }
+    }
+
+    /// Returns a handle to the block that is currently being generated.
+    pub fn pending_block(&self) -> SharedPendingBlock {
+        self.pending_block.clone()
     }
 
-    /// Starts a new block proposal generation task for the given proposal_id and height with
-    /// transactions from the mempool.
+    /// Starts a new block proposal generation task for the given proposal_id, height and round with
+    /// transactions from the mempool. Fails if the generation of a proposal for the same height and
+    /// round was interrupted by a restart, since the new proposal may conflict with the part of the
+    /// interrupted one that was already streamed. The transactions of the proposal are streamed in
+    /// chunks, see [`crate::proposal_chunker`].
+    ///
+    /// The transactions are taken from the mempool under a lease of the height, which ends once the
+    /// height is decided or the proposal is aborted. The block is priced by the latest gas prices
+    /// sampled from the base layer and by the fee market, see [`crate::block_info`], and starts by
+    /// handling the pending messages from L1, lowest nonces first.
     #[instrument(skip(self))]
     pub async fn generate_block_proposal(
         &mut self,
         proposal_id: ProposalId,
         timeout: tokio::time::Instant,
-        _height: BlockNumber,
-    ) -> ProposalsManagerResult<ReceiverStream<Transaction>> {
+        height: BlockNumber,
+        round: Round,
+    ) -> ProposalsManagerResult<ReceiverStream<Vec<Transaction>>> {
         info!("Starting generation of new proposal.");
+        if self.interrupted_proposals.contains(&(height, round)) {
+            return Err(ProposalsManagerError::ProposalInterrupted { height, round });
+        }
+        let block_info = self.block_info_builder.build(height, now())?;
+        let l1_handler_messages =
+            self.l1_handler_queue.lock().await.get_txs(self.config.max_l1_handler_txs_per_proposal);
         self.set_proposal_in_generation(proposal_id).await?;
+        let lease_id = match self.open_lease(height, &block_info).await {
+            Ok(lease_id) => lease_id,
+            Err(error) => {
+                *self.proposal_in_generation.lock().await = None;
+                return Err(error.into());
+            }
+        };
+        *self.pending_block.write().await = Some(PendingBlock::new(height));
 
-        let (sender, receiver) =
+        let (sender, tx_receiver) =
             tokio::sync::mpsc::channel::<Transaction>(self.config.outstream_content_buffer_size);
+        let (chunk_sender, receiver) = tokio::sync::mpsc::channel::<Vec<Transaction>>(
+            self.config.outstream_content_buffer_size,
+        );
+        tokio::spawn(
+            stream_chunks(
+                ProposalChunker::new(&self.config.proposal_chunking),
+                tx_receiver,
+                chunk_sender,
+            )
+            .instrument(debug_span!("batcher_stream_proposal_chunks", %proposal_id)),
+        );
+        let weights = Arc::new(Mutex::new(BouncerWeights::default()));
         // TODO: Find where to join the task - needed to make sure it starts immediatly.
-        let _handle = tokio::spawn(
+        let handle = tokio::spawn(
             ProposalGenerationTask {
+                proposal_id,
+                height,
+                round,
                 timeout,
+                block_info: block_info.clone(),
+                l1_handler_messages: l1_handler_messages.clone(),
+                lease_id,
+                weights: weights.clone(),
+                block_max_capacity: self.config.block_max_capacity,
                 mempool_client: self.mempool_client.clone(),
                 max_txs_per_mempool_request: self.config.max_txs_per_mempool_request,
+                max_tx_bytes: self.config.proposal_chunking.effective_max_bytes_per_chunk(),
                 sender,
                 proposal_in_generation: self.proposal_in_generation.clone(),
+                pending_block: self.pending_block.clone(),
+                recent_transactions: self.recent_transactions.clone(),
+                resource_profiles: self
+                    .config
+                    .prioritize_by_resources
+                    .then(|| self.resource_profiles.clone()),
+                contract_policy: self.contract_policy.clone(),
+                artifacts_store: self.artifacts_store.clone(),
             }
-            .run(),
+            .run()
+            .instrument(info_span!("batcher_generate_proposal", %proposal_id, %height, round)),
+        );
+        self.proposals.insert(
+            proposal_id,
+            ProposalHandle {
+                height,
+                round,
+                block_info,
+                l1_handler_messages,
+                lease_id,
+                weights,
+                abort_handle: handle.abort_handle(),
+            },
         );
 
         Ok(ReceiverStream::new(receiver))
     }
 
+    /// Validates the block info of a received proposal that builds on `parent`, against the local
+    /// clock and the gas prices the node would price the block by. Called before the transactions
+    /// of the proposal are executed, so proposals with manipulated timestamps or prices are
+    /// rejected early.
+    ///
+    /// The timestamps of the proposals that are valid, or invalid only for being too far ahead of
+    /// the local clock, are sampled for estimating the skew of the local clock.
+    #[instrument(skip(self, block_info), fields(height = %block_info.block_number))]
+    pub fn validate_block_proposal(
+        &mut self,
+        block_info: &BlockInfo,
+        parent: &ParentBlockInfo,
+    ) -> ProposalsManagerResult<()> {
+        let local_gas_prices = self.block_info_builder.gas_prices()?;
+        let validation_config = BlockInfoValidationConfig {
+            max_timestamp_drift: self
+                .clock_skew
+                .timestamp_tolerance(self.config.block_info_validation.max_timestamp_drift),
+            ..self.config.block_info_validation.clone()
+        };
+        let now = now().0;
+        let result =
+            validate_block_info(&validation_config, block_info, parent, now, &local_gas_prices);
+        if matches!(result, Ok(()) | Err(BlockInfoValidationError::TimestampTooFarAhead { .. })) {
+            self.clock_skew.record_proposal_timestamp(block_info.block_timestamp.0, now);
+        }
+        Ok(result?)
+    }
+
+    /// Records the transactions of a decided block, so they aren't proposed again even if the
+    /// mempool still returns them, and commits the block to the mempool, which ends the leases of
+    /// the proposals for its height. The messages from L1 that the block handled are removed from
+    /// the L1 handler queue. Proposals for the same height that weren't decided are dropped.
+    ///
+    /// The nonce of each account in `state_changes` is the nonce of its last transaction in the
+    /// block.
+    ///
+    /// If the decided block is a proposal of the node, `decided_proposal_id` is its id, and the L2
+    /// gas prices of the next proposed blocks are set by its gas prices and how full it was.
+    #[instrument(
+        skip(self, tx_hashes, l1_handler_txs, state_changes),
+        fields(num_txs = tx_hashes.len(), num_accounts = state_changes.len())
+    )]
+    pub async fn add_decided_block(
+        &mut self,
+        height: BlockNumber,
+        decided_proposal_id: Option<ProposalId>,
+        tx_hashes: Vec<TransactionHash>,
+        l1_handler_txs: Vec<L1HandlerTransaction>,
+        state_changes: HashMap<ContractAddress, AccountState>,
+    ) -> ProposalsManagerResult<()> {
+        self.mempool_client.commit_block(height, state_changes).await?;
+        self.l1_handler_queue.lock().await.commit_block(&l1_handler_txs);
+        self.recent_transactions.lock().await.add_decided_block(height, tx_hashes);
+        let parent_block = match decided_proposal_id.and_then(|id| self.proposals.get(&id)) {
+            Some(proposal) => Some(ParentBlockUsage {
+                gas_prices: proposal.block_info.gas_prices.clone(),
+                weights: *proposal.weights.lock().await,
+            }),
+            None => None,
+        };
+        self.block_info_builder.set_parent_block(parent_block);
+        self.proposals.retain(|_, proposal| proposal.height > height);
+        self.interrupted_proposals.retain(|(proposal_height, _)| *proposal_height > height);
+        if let Some(artifacts_store) = &self.artifacts_store {
+            if let Err(error) = artifacts_store.remove_up_to(height).await {
+                error!(
+                    "Failed to remove the artifacts of the proposals up to height {height}: \
+                     {error}"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Drops a proposal that won't be decided, so its transactions can be proposed again: its
+    /// generation is stopped and its mempool lease is released.
+    #[instrument(skip(self))]
+    pub async fn abort_proposal(&mut self, proposal_id: ProposalId) {
+        self.recent_transactions.lock().await.remove_proposal(proposal_id);
+        let Some(ProposalHandle { height, round, lease_id, abort_handle, .. }) =
+            self.proposals.remove(&proposal_id)
+        else {
+            return;
+        };
+        // The aborted task neither releases the lease nor ends the generation by itself.
+        abort_handle.abort();
+        {
+            let mut proposal_in_generation = self.proposal_in_generation.lock().await;
+            if *proposal_in_generation == Some(proposal_id) {
+                *proposal_in_generation = None;
+                *self.pending_block.write().await = None;
+            }
+        }
+        if let Err(error) = self.mempool_client.release_lease(lease_id).await {
+            warn!("Failed to release the mempool lease of aborted proposal {proposal_id}: {error}");
+        }
+        let Some(artifacts_store) = &self.artifacts_store else {
+            return;
+        };
+        if let Err(error) = artifacts_store.remove(height, round).await {
+            error!("Failed to remove the artifacts of proposal {proposal_id}: {error}");
+        }
+    }
+
+    /// The block info of a proposal generated by the node, until its height is decided or it's
+    /// aborted.
+    pub fn proposal_block_info(&self, proposal_id: ProposalId) -> Option<&BlockInfo> {
+        self.proposals.get(&proposal_id).map(|proposal| &proposal.block_info)
+    }
+
+    /// The messages from L1 that a proposal generated by the node handles, until its height is
+    /// decided or it's aborted.
+    pub fn proposal_l1_handler_messages(
+        &self,
+        proposal_id: ProposalId,
+    ) -> Option<&[L1HandlerMessage]> {
+        self.proposals.get(&proposal_id).map(|proposal| proposal.l1_handler_messages.as_slice())
+    }
+
+    // Opens the mempool lease of a proposal, after raising or lowering the gas price threshold of
+    // the mempool to the L2 gas price of the proposed block: the transactions that can't pay it are
+    // parked, and the parked transactions that can are promoted.
+    async fn open_lease(
+        &self,
+        height: BlockNumber,
+        block_info: &BlockInfo,
+    ) -> Result<LeaseId, MempoolClientError> {
+        let l2_gas_price = block_info.gas_prices.get_l2_gas_price_by_fee_type(&FeeType::Strk);
+        self.mempool_client.update_gas_price_threshold(l2_gas_price.get()).await?;
+        self.mempool_client.open_lease(height).await
+    }
+
     // Checks if there is already a proposal being generated, and if not, sets the given proposal_id
     // as the one being generated.
     async fn set_proposal_in_generation(
@@ -142,7 +634,10 @@ impl ProposalsManager {
 
 // TODO: Should be defined elsewhere.
 #[allow(dead_code)]
-mod block_builder {
+pub(crate) mod block_builder {
+    use blockifier::blockifier::block::BlockInfo;
+    use blockifier::bouncer::BouncerWeights;
+    use papyrus_base_layer::l1_messages::L1HandlerMessage;
     use starknet_api::executable_transaction::Transaction;
     use starknet_api::state::StateDiff;
 
@@ -153,69 +648,275 @@ mod block_builder {
         Timeout,
     }
 
-    pub struct BlockBuilder {}
+    pub struct BlockBuilder {
+        /// The block info that the transactions are executed under.
+        pub block_info: BlockInfo,
+        /// The messages from L1 that the block handles before any other transaction.
+        pub l1_handler_messages: Vec<L1HandlerMessage>,
+    }
 
     impl BlockBuilder {
         pub fn status(&self) -> Status {
             Status::Building
         }
 
-        /// Returning true if the block is ready to be proposed.
+        /// Adds the transactions in order until the block is full or its deadline passes, as
+        /// reflected by the status, and streams them. Returns the bouncer weights of the
+        /// transactions that were added, in order.
+        ///
+        /// A deploy-account transaction and the next transaction of its account that follows it
+        /// are added together or not at all.
         pub fn add_txs_and_stream(
             &self,
-            _txs: &[Transaction],
-            _sender: &tokio::sync::mpsc::Sender<Transaction>,
-        ) -> bool {
-            false
+            txs: &[Transaction],
+            sender: &tokio::sync::mpsc::Sender<Transaction>,
+        ) -> Vec<BouncerWeights> {
+            // TODO: Execute the transactions. Until then, they're added as long as they can be
+            // streamed, and weigh nothing.
+            let mut added_tx_weights = Vec::new();
+            for bundle in bundles(txs) {
+                if sender.is_closed() || sender.capacity() < bundle.len() {
+                    break;
+                }
+                for tx in bundle {
+                    sender.try_send(tx.clone()).expect("The channel has capacity for the bundle.");
+                }
+                added_tx_weights.extend(bundle.iter().map(|_| BouncerWeights::default()));
+            }
+            added_tx_weights
+        }
+
+        /// The state diff of the transactions that were added so far.
+        pub fn state_diff(&self) -> StateDiff {
+            StateDiff::default()
         }
 
         pub fn close_block(&self) -> StateDiff {
             StateDiff::default()
         }
     }
+
+    // Splits the transactions into the units they're added in: a deploy-account transaction
+    // followed by a transaction of the same account, or a single transaction.
+    fn bundles(txs: &[Transaction]) -> Vec<&[Transaction]> {
+        let mut bundles = Vec::new();
+        let mut start = 0;
+        while start < txs.len() {
+            let is_bundle = matches!(txs[start], Transaction::DeployAccount(_))
+                && txs
+                    .get(start + 1)
+                    .is_some_and(|tx| tx.contract_address() == txs[start].contract_address());
+            let end = start + 1 + usize::from(is_bundle);
+            bundles.push(&txs[start..end]);
+            start = end;
+        }
+        bundles
+    }
 }
 
 #[allow(dead_code)]
 struct ProposalGenerationTask {
+    pub proposal_id: ProposalId,
+    pub height: BlockNumber,
+    pub round: Round,
     pub timeout: tokio::time::Instant,
+    pub block_info: BlockInfo,
+    pub l1_handler_messages: Vec<L1HandlerMessage>,
+    /// The mempool lease of the proposal, opened for its height.
+    pub lease_id: LeaseId,
+    /// The weights of the transactions added to the block so far.
+    pub weights: Arc<Mutex<BouncerWeights>>,
+    pub block_max_capacity: BouncerWeights,
     pub mempool_client: SharedMempoolClient,
     pub max_txs_per_mempool_request: usize,
+    /// Transactions larger than this don't fit in a chunk of the streamed proposal.
+    pub max_tx_bytes: usize,
     pub sender: tokio::sync::mpsc::Sender<Transaction>,
     pub proposal_in_generation: Arc<Mutex<Option<ProposalId>>>,
+    pub pending_block: SharedPendingBlock,
+    pub recent_transactions: Arc<Mutex<RecentTransactions>>,
+    /// Set when prioritizing transactions by resources.
+    pub resource_profiles: Option<Arc<Mutex<ResourceProfiles>>>,
+    pub contract_policy: Arc<ContractPolicy>,
+    /// Set when persisting the proposals in generation.
+    pub artifacts_store: Option<Arc<ProposalArtifactsStore>>,
 }
 
 impl ProposalGenerationTask {
+    // Takes the transactions of the proposal under a lease of its own, so that other batchers can
+    // take transactions from the mempool concurrently. If the generation fails, the transactions
+    // are returned to the mempool; otherwise, they're returned once the height is decided, unless
+    // they were included in the decided block, or once the proposal is aborted.
     #[allow(dead_code)]
     async fn run(self) -> ProposalsManagerResult<()> {
-        let block_builder = block_builder::BlockBuilder {};
+        let result = self.build_proposal(self.lease_id).await;
+        if result.is_err() {
+            if let Err(error) = self.mempool_client.release_lease(self.lease_id).await {
+                warn!("Failed to release the mempool lease of a failed proposal: {error}");
+            }
+        }
+        result
+    }
+
+    async fn build_proposal(&self, lease_id: LeaseId) -> ProposalsManagerResult<()> {
+        let block_builder = block_builder::BlockBuilder {
+            block_info: self.block_info.clone(),
+            l1_handler_messages: self.l1_handler_messages.clone(),
+        };
+        let mut artifacts =
+            ProposalArtifacts { height: self.height, round: self.round, ..Default::default() };
+        let mut skipped_accounts = HashSet::new();
         loop {
             if tokio::time::Instant::now() > self.timeout {
                 info!("Proposal reached timeout.");
                 break;
             }
-            let mempool_txs = self.mempool_client.get_txs(self.max_txs_per_mempool_request).await?;
+            let mempool_txs =
+                self.mempool_client.lease_txs(lease_id, self.max_txs_per_mempool_request).await?;
+            let mempool_txs = self.skip_policy_violations(mempool_txs, &mut skipped_accounts);
+            let mut mempool_txs = self.skip_recent_transactions(mempool_txs).await;
             if mempool_txs.is_empty() {
                 // TODO: check if sleep is needed here.
                 tokio::task::yield_now().await;
                 continue;
             }
+            if let Some(resource_profiles) = &self.resource_profiles {
+                mempool_txs = resource_profiles.lock().await.prioritize(mempool_txs);
+            }
 
-            // TODO: Get L1 transactions.
+            // The transactions are persisted before they're streamed, so a node that restarts
+            // knows of every transaction it may have proposed.
+            if let Some(artifacts_store) = &self.artifacts_store {
+                artifacts.tx_hashes.extend(mempool_txs.iter().map(Transaction::tx_hash));
+                artifacts_store
+                    .write(&artifacts)
+                    .instrument(debug_span!("batcher_persist_artifacts"))
+                    .await?;
+            }
             debug!("Adding {} mempool transactions to proposal in generation.", mempool_txs.len());
+            metrics::counter!(
+                BATCHER_PROPOSAL_TRANSACTIONS,
+                mempool_txs.len() as u64,
+                COMPONENT_LABEL => "batcher",
+                HEIGHT_LABEL => self.height.to_string()
+            );
             // TODO: This is cpu bound operation, should use spawn_blocking / Rayon / std::thread
             // here or from inside the function.
-            let is_block_ready =
-                block_builder.add_txs_and_stream(mempool_txs.as_slice(), &self.sender);
-            if is_block_ready {
+            let added_tx_weights = debug_span!("batcher_execute_txs", num_txs = mempool_txs.len())
+                .in_scope(|| {
+                    block_builder.add_txs_and_stream(mempool_txs.as_slice(), &self.sender)
+                });
+            let added_txs = &mempool_txs[..added_tx_weights.len()];
+            // The transactions that weren't added are returned to the mempool with the lease.
+            debug!("Added {} of the mempool transactions to the proposal.", added_txs.len());
+            {
+                let mut weights = self.weights.lock().await;
+                for added_tx_weights in &added_tx_weights {
+                    *weights += *added_tx_weights;
+                }
+            }
+            if let Some(resource_profiles) = &self.resource_profiles {
+                let mut resource_profiles = resource_profiles.lock().await;
+                for (tx, weights) in added_txs.iter().zip(&added_tx_weights) {
+                    resource_profiles.record(tx, weights);
+                }
+            }
+            self.publish_pending_txs(added_txs, &block_builder).await;
+            if block_builder.status() != block_builder::Status::Building {
                 break;
             }
         }
 
         info!("Closing block.");
+        record_deadline_slack(self.timeout.saturating_duration_since(tokio::time::Instant::now()));
+        record_capacity_used(&self.weights.lock().await, &self.block_max_capacity);
         // TODO: Get state diff.
         let mut proposal_id = self.proposal_in_generation.lock().await;
         *proposal_id = None;
+        // The closed block is no longer pending; once committed, it's read from the storage.
+        *self.pending_block.write().await = None;
 
         Ok(())
     }
+
+    // Publishes the transactions that were added to the block, with the state diff of the block so
+    // far, to the readers of the pending block.
+    async fn publish_pending_txs(
+        &self,
+        txs: &[Transaction],
+        block_builder: &block_builder::BlockBuilder,
+    ) {
+        if let Some(pending_block) = self.pending_block.write().await.as_mut() {
+            pending_block.transactions.extend_from_slice(txs);
+            pending_block.state_diff = block_builder.state_diff();
+        }
+    }
+
+    // Drops the transactions that violate the contract policy of the node or are too large to be
+    // streamed, and the later transactions of their accounts, which can't be included without
+    // them. The dropped transactions stay in the lease, so they're returned to the mempool once it
+    // ends.
+    fn skip_policy_violations(
+        &self,
+        txs: Vec<Transaction>,
+        skipped_accounts: &mut HashSet<ContractAddress>,
+    ) -> Vec<Transaction> {
+        let mut n_oversized_txs = 0;
+        let mut n_policy_violations = 0;
+        let allowed_txs: Vec<_> = txs
+            .into_iter()
+            .filter(|tx| {
+                if skipped_accounts.contains(&tx.contract_address()) {
+                    return false;
+                }
+                let tx_bytes = estimated_size(tx);
+                if tx_bytes > self.max_tx_bytes {
+                    info!(
+                        "Skipping mempool transaction {} of {tx_bytes} bytes, which doesn't fit \
+                         in a proposal chunk.",
+                        tx.tx_hash()
+                    );
+                    skipped_accounts.insert(tx.contract_address());
+                    n_oversized_txs += 1;
+                    return false;
+                }
+                match self.contract_policy.check_transaction(tx) {
+                    Ok(()) => true,
+                    Err(violation) => {
+                        info!("Skipping a mempool transaction: {violation}");
+                        skipped_accounts.insert(tx.contract_address());
+                        n_policy_violations += 1;
+                        false
+                    }
+                }
+            })
+            .collect();
+        record_rejected_txs(self.height, RejectionReason::Oversized, n_oversized_txs);
+        record_rejected_txs(self.height, RejectionReason::ContractPolicy, n_policy_violations);
+        allowed_txs
+    }
+
+    // Drops the transactions that were already included in a recent block or proposal, and records
+    // the rest as included in this proposal.
+    async fn skip_recent_transactions(&self, txs: Vec<Transaction>) -> Vec<Transaction> {
+        let mut recent_transactions = self.recent_transactions.lock().await;
+        let (recent_txs, new_txs): (Vec<_>, Vec<_>) =
+            txs.into_iter().partition(|tx| recent_transactions.contains(&tx.tx_hash()));
+        if !recent_txs.is_empty() {
+            debug!("Skipping {} recently included mempool transactions.", recent_txs.len());
+        }
+        recent_transactions.add_proposal_transactions(
+            self.proposal_id,
+            self.height,
+            new_txs.iter().map(Transaction::tx_hash),
+        );
+        new_txs
+    }
+}
+
+// The current time, as the timestamp of a proposed block.
+fn now() -> BlockTimestamp {
+    let since_epoch =
+        SystemTime::now().duration_since(UNIX_EPOCH).expect("The clock is set before 1970.");
+    BlockTimestamp(since_epoch.as_secs())
 }
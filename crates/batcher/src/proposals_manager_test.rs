@@ -1,24 +1,101 @@
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use assert_matches::assert_matches;
-use starknet_api::block::BlockNumber;
+use blockifier::blockifier::block::{BlockInfo, GasPrices, ParentBlockInfo};
+use blockifier::bouncer::BouncerWeights;
+use blockifier::fee::fee_market::{FeeMarket, FeeMarketConfig};
+use blockifier::transaction::objects::FeeType;
+use blockifier::versioned_constants::VersionedConstants;
+use papyrus_base_layer::l1_gas_price::{GasPriceProvider, L1GasPrices};
+use papyrus_base_layer::l1_messages::{L1HandlerMessage, SharedL1HandlerQueue};
+use papyrus_config::validators::config_validate;
+use papyrus_config::ConfigError;
+use starknet_api::block::{BlockNumber, BlockTimestamp, GasPrice, GasPricePerToken};
+use starknet_api::core::{ClassHash, ContractAddress, Nonce, PatriciaKey};
+use starknet_api::data_availability::DataAvailabilityMode;
+use starknet_api::executable_transaction::{
+    DeployAccountTransaction,
+    InvokeTransaction,
+    Transaction,
+};
+use starknet_api::transaction::{
+    Calldata,
+    DeployAccountTransactionV1,
+    DeprecatedResourceBoundsMapping,
+    Fee,
+    InvokeTransactionV1,
+    InvokeTransactionV3,
+    L1HandlerTransaction,
+    Resource,
+    ResourceBounds,
+    Tip,
+    TransactionHash,
+};
+use starknet_api::{class_hash, contract_address, felt, patricia_key};
 use starknet_mempool_types::communication::MockMempoolClient;
+use starknet_mempool_types::mempool_types::AccountState;
+use starknet_types_core::felt::Felt;
+use tokio::sync::Notify;
+use tokio_stream::StreamExt;
 
-use crate::proposals_manager::{ProposalsManager, ProposalsManagerConfig, ProposalsManagerError};
+use crate::block_info::{gas_prices, BlockInfoBuilder};
+use crate::block_info_validator::BlockInfoValidationError;
+use crate::clock_skew::ClockSkewConfig;
+use crate::contract_policy::ContractPolicyConfig;
+use crate::pending_block::PendingBlock;
+use crate::proposal_artifacts::{ProposalArtifacts, ProposalArtifactsStore};
+use crate::proposal_chunker::ProposalChunkingConfig;
+use crate::proposals_manager::block_builder::BlockBuilder;
+use crate::proposals_manager::{
+    ProposalsManager,
+    ProposalsManagerConfig,
+    ProposalsManagerError,
+    Round,
+};
 
 const GENERATION_TIMEOUT: tokio::time::Duration = tokio::time::Duration::from_secs(1);
+const ROUND: Round = 0;
+
+fn gas_price_provider() -> GasPriceProvider {
+    GasPriceProvider::fixed(L1GasPrices::default())
+}
+
+fn block_info_builder(use_kzg_da: bool, gas_price_provider: GasPriceProvider) -> BlockInfoBuilder {
+    BlockInfoBuilder::new(
+        ContractAddress::default(),
+        use_kzg_da,
+        gas_price_provider,
+        FeeMarketConfig::default(),
+        BouncerWeights::max(),
+    )
+}
+
+// A mempool client that accepts any gas price threshold.
+fn mock_mempool_client() -> MockMempoolClient {
+    let mut mempool_client = MockMempoolClient::new();
+    mempool_client.expect_update_gas_price_threshold().returning(|_| Ok(()));
+    mempool_client
+}
 
 #[tokio::test]
 async fn multiple_proposals_generation_fails() {
-    let mut mempool_client = MockMempoolClient::new();
-    mempool_client.expect_get_txs().returning(|_| Ok(vec![]));
-    let mut proposals_manager =
-        ProposalsManager::new(ProposalsManagerConfig::default(), Arc::new(mempool_client));
+    let mut mempool_client = mock_mempool_client();
+    mempool_client.expect_open_lease().returning(|_| Ok(0));
+    mempool_client.expect_lease_txs().returning(|_, _| Ok(vec![]));
+    let mut proposals_manager = ProposalsManager::new(
+        ProposalsManagerConfig::default(),
+        Arc::new(mempool_client),
+        gas_price_provider(),
+        SharedL1HandlerQueue::default(),
+    );
     let _ = proposals_manager
         .generate_block_proposal(
             0,
             tokio::time::Instant::now() + GENERATION_TIMEOUT,
             BlockNumber::default(),
+            ROUND,
         )
         .await
         .unwrap();
@@ -28,6 +105,7 @@ async fn multiple_proposals_generation_fails() {
             1,
             tokio::time::Instant::now() + GENERATION_TIMEOUT,
             BlockNumber::default(),
+            ROUND,
         )
         .await;
 
@@ -39,3 +117,690 @@ async fn multiple_proposals_generation_fails() {
         }) if current_generating_proposal_id == 0 && new_proposal_id == 1
     );
 }
+
+#[tokio::test]
+async fn proposal_generation_skips_recently_decided_transactions() {
+    let tx_hash = TransactionHash(starknet_api::felt!("0x1"));
+    let tx = Transaction::Invoke(InvokeTransaction {
+        tx: starknet_api::transaction::InvokeTransaction::V1(InvokeTransactionV1::default()),
+        tx_hash,
+    });
+    let mut mempool_client = mock_mempool_client();
+    mempool_client.expect_open_lease().returning(|_| Ok(0));
+    mempool_client.expect_lease_txs().returning(move |_, _| Ok(vec![tx.clone()]));
+    let decided_state_changes =
+        HashMap::from([(ContractAddress::default(), AccountState { nonce: Nonce::default() })]);
+    mempool_client
+        .expect_commit_block()
+        .withf({
+            let decided_state_changes = decided_state_changes.clone();
+            move |height, state_changes| {
+                *height == BlockNumber(0) && *state_changes == decided_state_changes
+            }
+        })
+        .times(1)
+        .returning(|_, _| Ok(()));
+    let mut proposals_manager = ProposalsManager::new(
+        ProposalsManagerConfig::default(),
+        Arc::new(mempool_client),
+        gas_price_provider(),
+        SharedL1HandlerQueue::default(),
+    );
+    let pending_block = proposals_manager.pending_block();
+    proposals_manager
+        .add_decided_block(BlockNumber(0), None, vec![tx_hash], vec![], decided_state_changes)
+        .await
+        .unwrap();
+
+    let height = BlockNumber(1);
+    let _streamed_txs = proposals_manager
+        .generate_block_proposal(0, tokio::time::Instant::now() + GENERATION_TIMEOUT, height, ROUND)
+        .await
+        .unwrap();
+
+    tokio::time::sleep(GENERATION_TIMEOUT / 2).await;
+    assert_eq!(*pending_block.read().await, Some(PendingBlock::new(height)));
+}
+
+#[tokio::test]
+async fn proposal_generation_publishes_pending_block() {
+    let tx = Transaction::Invoke(InvokeTransaction {
+        tx: starknet_api::transaction::InvokeTransaction::V1(InvokeTransactionV1::default()),
+        tx_hash: TransactionHash::default(),
+    });
+    let mut mempool_client = mock_mempool_client();
+    mempool_client.expect_open_lease().returning(|_| Ok(0));
+    let mempool_tx = tx.clone();
+    mempool_client.expect_lease_txs().times(1).returning(move |_, _| Ok(vec![mempool_tx.clone()]));
+    mempool_client.expect_lease_txs().returning(|_, _| Ok(vec![]));
+    let mut proposals_manager = ProposalsManager::new(
+        ProposalsManagerConfig::default(),
+        Arc::new(mempool_client),
+        gas_price_provider(),
+        SharedL1HandlerQueue::default(),
+    );
+    let pending_block = proposals_manager.pending_block();
+    let height = BlockNumber(1);
+
+    let _streamed_txs = proposals_manager
+        .generate_block_proposal(0, tokio::time::Instant::now() + GENERATION_TIMEOUT, height, ROUND)
+        .await
+        .unwrap();
+    assert_eq!(pending_block.read().await.as_ref().unwrap().height, height);
+
+    tokio::time::sleep(GENERATION_TIMEOUT / 2).await;
+    assert_eq!(
+        *pending_block.read().await,
+        Some(PendingBlock { height, transactions: vec![tx], ..Default::default() })
+    );
+
+    tokio::time::sleep(GENERATION_TIMEOUT).await;
+    assert_eq!(*pending_block.read().await, None);
+}
+
+#[tokio::test]
+async fn proposal_generation_skips_policy_violations_and_their_accounts() {
+    let deployed_account = contract_address!("0x1");
+    let excluded_class_hash = class_hash!("0x2");
+    let deploy_account_tx = Transaction::DeployAccount(DeployAccountTransaction {
+        tx: starknet_api::transaction::DeployAccountTransaction::V1(DeployAccountTransactionV1 {
+            class_hash: excluded_class_hash,
+            ..Default::default()
+        }),
+        tx_hash: TransactionHash(felt!("0x1")),
+        contract_address: deployed_account,
+    });
+    let invoke_tx = |sender_address, tx_hash| {
+        Transaction::Invoke(InvokeTransaction {
+            tx: starknet_api::transaction::InvokeTransaction::V1(InvokeTransactionV1 {
+                sender_address,
+                ..Default::default()
+            }),
+            tx_hash,
+        })
+    };
+    let allowed_tx_hash = TransactionHash(felt!("0x3"));
+    let mempool_txs = vec![
+        deploy_account_tx,
+        invoke_tx(deployed_account, TransactionHash(felt!("0x2"))),
+        invoke_tx(contract_address!("0x3"), allowed_tx_hash),
+    ];
+    let mut mempool_client = mock_mempool_client();
+    mempool_client.expect_open_lease().returning(|_| Ok(0));
+    mempool_client.expect_lease_txs().times(1).returning(move |_, _| Ok(mempool_txs.clone()));
+    mempool_client.expect_lease_txs().returning(|_, _| Ok(vec![]));
+    // The skipped transactions stay in the lease, which isn't released.
+    mempool_client.expect_release_lease().never();
+    let artifacts_dir = tempfile::tempdir().unwrap();
+    let config = ProposalsManagerConfig {
+        contract_policy: ContractPolicyConfig {
+            excluded_class_hashes: vec![excluded_class_hash],
+            ..Default::default()
+        },
+        proposal_artifacts_dir: Some(artifacts_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let mut proposals_manager = ProposalsManager::new(
+        config,
+        Arc::new(mempool_client),
+        gas_price_provider(),
+        SharedL1HandlerQueue::default(),
+    );
+
+    let streamed_txs = proposals_manager
+        .generate_block_proposal(
+            0,
+            tokio::time::Instant::now() + GENERATION_TIMEOUT,
+            BlockNumber(0),
+            ROUND,
+        )
+        .await
+        .unwrap();
+    streamed_txs.collect::<Vec<_>>().await;
+
+    let artifacts_store = ProposalArtifactsStore::open(artifacts_dir.path().to_path_buf()).unwrap();
+    let artifacts = artifacts_store.read_all().unwrap();
+    assert_eq!(artifacts.len(), 1);
+    assert_eq!(artifacts[0].tx_hashes, vec![allowed_tx_hash]);
+}
+
+#[tokio::test]
+async fn proposal_generation_skips_transactions_too_large_to_stream() {
+    let invoke_tx = |sender_address, tx_hash, calldata_length| {
+        Transaction::Invoke(InvokeTransaction {
+            tx: starknet_api::transaction::InvokeTransaction::V1(InvokeTransactionV1 {
+                sender_address,
+                calldata: Calldata(vec![Felt::ONE; calldata_length].into()),
+                ..Default::default()
+            }),
+            tx_hash,
+        })
+    };
+    let small_tx = invoke_tx(contract_address!("0x2"), TransactionHash(felt!("0x2")), 0);
+    let mempool_txs = vec![
+        invoke_tx(contract_address!("0x1"), TransactionHash(felt!("0x1")), 1000),
+        small_tx.clone(),
+    ];
+    let mut mempool_client = mock_mempool_client();
+    mempool_client.expect_open_lease().returning(|_| Ok(0));
+    mempool_client.expect_lease_txs().times(1).returning(move |_, _| Ok(mempool_txs.clone()));
+    mempool_client.expect_lease_txs().returning(|_, _| Ok(vec![]));
+    mempool_client.expect_release_lease().never();
+    let config = ProposalsManagerConfig {
+        proposal_chunking: ProposalChunkingConfig {
+            max_bytes_per_chunk: 4096,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut proposals_manager = ProposalsManager::new(
+        config,
+        Arc::new(mempool_client),
+        gas_price_provider(),
+        SharedL1HandlerQueue::default(),
+    );
+
+    let streamed_txs = proposals_manager
+        .generate_block_proposal(
+            0,
+            tokio::time::Instant::now() + GENERATION_TIMEOUT,
+            BlockNumber(0),
+            ROUND,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(streamed_txs.collect::<Vec<_>>().await.concat(), vec![small_tx]);
+}
+
+#[tokio::test]
+async fn proposal_generation_prioritizes_by_resources() {
+    let invoke_tx = |sender_address, max_amount| {
+        let resource_bounds = DeprecatedResourceBoundsMapping::try_from(vec![(
+            Resource::L1Gas,
+            ResourceBounds { max_amount, max_price_per_unit: 1 },
+        )])
+        .unwrap();
+        Transaction::Invoke(InvokeTransaction {
+            tx: starknet_api::transaction::InvokeTransaction::V3(InvokeTransactionV3 {
+                sender_address,
+                resource_bounds,
+                tip: Tip::default(),
+                signature: Default::default(),
+                nonce: Nonce::default(),
+                calldata: Calldata::default(),
+                nonce_data_availability_mode: DataAvailabilityMode::L1,
+                fee_data_availability_mode: DataAvailabilityMode::L1,
+                paymaster_data: Default::default(),
+                account_deployment_data: Default::default(),
+            }),
+            tx_hash: TransactionHash(felt!(max_amount)),
+        })
+    };
+    let cheap_tx = invoke_tx(contract_address!("0x1"), 10);
+    let expensive_tx = invoke_tx(contract_address!("0x2"), 100);
+    let mempool_txs = vec![cheap_tx.clone(), expensive_tx.clone()];
+    let mut mempool_client = mock_mempool_client();
+    mempool_client.expect_open_lease().returning(|_| Ok(0));
+    mempool_client.expect_lease_txs().times(1).returning(move |_, _| Ok(mempool_txs.clone()));
+    mempool_client.expect_lease_txs().returning(|_, _| Ok(vec![]));
+    let config = ProposalsManagerConfig { prioritize_by_resources: true, ..Default::default() };
+    let mut proposals_manager = ProposalsManager::new(
+        config,
+        Arc::new(mempool_client),
+        gas_price_provider(),
+        SharedL1HandlerQueue::default(),
+    );
+
+    let streamed_txs = proposals_manager
+        .generate_block_proposal(
+            0,
+            tokio::time::Instant::now() + GENERATION_TIMEOUT,
+            BlockNumber(0),
+            ROUND,
+        )
+        .await
+        .unwrap();
+
+    // Neither transaction has a profile, so they're ordered by their maximal fee.
+    assert_eq!(streamed_txs.collect::<Vec<_>>().await.concat(), vec![expensive_tx, cheap_tx]);
+}
+
+#[tokio::test]
+async fn proposal_generation_persists_artifacts() {
+    let tx_hash = TransactionHash(starknet_api::felt!("0x1"));
+    let tx = Transaction::Invoke(InvokeTransaction {
+        tx: starknet_api::transaction::InvokeTransaction::V1(InvokeTransactionV1::default()),
+        tx_hash,
+    });
+    // The transactions are persisted before the next ones are leased.
+    let persisted = Arc::new(Notify::new());
+    let mut mempool_client = mock_mempool_client();
+    mempool_client.expect_open_lease().returning(|_| Ok(0));
+    mempool_client.expect_lease_txs().times(1).returning(move |_, _| Ok(vec![tx.clone()]));
+    mempool_client.expect_lease_txs().returning({
+        let persisted = persisted.clone();
+        move |_, _| {
+            persisted.notify_one();
+            Ok(vec![])
+        }
+    });
+    let artifacts_dir = tempfile::tempdir().unwrap();
+    let config = ProposalsManagerConfig {
+        proposal_artifacts_dir: Some(artifacts_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let mut proposals_manager = ProposalsManager::new(
+        config.clone(),
+        Arc::new(mempool_client),
+        gas_price_provider(),
+        SharedL1HandlerQueue::default(),
+    );
+
+    let height = BlockNumber(1);
+    // The proposal is streamed as long as its content is consumed.
+    let _streamed_txs = proposals_manager
+        .generate_block_proposal(0, tokio::time::Instant::now() + GENERATION_TIMEOUT, height, ROUND)
+        .await
+        .unwrap();
+    persisted.notified().await;
+    let artifacts_store = ProposalArtifactsStore::open(artifacts_dir.path().to_path_buf()).unwrap();
+    assert_eq!(
+        artifacts_store.read_all().unwrap(),
+        vec![ProposalArtifacts { height, round: ROUND, tx_hashes: vec![tx_hash] }]
+    );
+
+    // The node restarts mid-generation, and doesn't generate a conflicting proposal.
+    let mut restarted_mempool_client = MockMempoolClient::new();
+    restarted_mempool_client
+        .expect_commit_block()
+        .withf(move |decided_height, state_changes| {
+            *decided_height == height && state_changes.is_empty()
+        })
+        .times(1)
+        .returning(|_, _| Ok(()));
+    let mut restarted_proposals_manager = ProposalsManager::new(
+        config,
+        Arc::new(restarted_mempool_client),
+        gas_price_provider(),
+        SharedL1HandlerQueue::default(),
+    );
+    assert_matches!(
+        restarted_proposals_manager
+            .generate_block_proposal(
+                1,
+                tokio::time::Instant::now() + GENERATION_TIMEOUT,
+                height,
+                ROUND
+            )
+            .await,
+        Err(ProposalsManagerError::ProposalInterrupted { height: h, round: ROUND }) if h == height
+    );
+
+    // Once the height is decided, the artifacts are removed. The block is committed to the mempool
+    // even though it changed no account, to end the leases of its height.
+    restarted_proposals_manager
+        .add_decided_block(height, None, vec![tx_hash], vec![], HashMap::new())
+        .await
+        .unwrap();
+    assert!(artifacts_store.read_all().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn aborted_proposal_releases_its_lease() {
+    let lease_id = 7;
+    let height = BlockNumber(1);
+    let mut mempool_client = mock_mempool_client();
+    mempool_client
+        .expect_open_lease()
+        .withf(move |lease_height| *lease_height == height)
+        .times(2)
+        .returning(move |_| Ok(lease_id));
+    mempool_client.expect_lease_txs().returning(|_, _| Ok(vec![]));
+    mempool_client
+        .expect_release_lease()
+        .withf(move |released_lease_id| *released_lease_id == lease_id)
+        .times(1)
+        .returning(|_| Ok(()));
+    let mut proposals_manager = ProposalsManager::new(
+        ProposalsManagerConfig::default(),
+        Arc::new(mempool_client),
+        gas_price_provider(),
+        SharedL1HandlerQueue::default(),
+    );
+    let streamed_txs = proposals_manager
+        .generate_block_proposal(0, tokio::time::Instant::now() + GENERATION_TIMEOUT, height, ROUND)
+        .await
+        .unwrap();
+
+    proposals_manager.abort_proposal(0).await;
+
+    // The generation stops before its timeout, and another proposal can be generated.
+    tokio::time::timeout(GENERATION_TIMEOUT / 2, streamed_txs.collect::<Vec<_>>()).await.unwrap();
+    proposals_manager
+        .generate_block_proposal(1, tokio::time::Instant::now() + GENERATION_TIMEOUT, height, ROUND)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn proposal_is_priced_by_the_sampled_gas_prices() {
+    let l1_gas_price = GasPricePerToken { price_in_wei: GasPrice(3), price_in_fri: GasPrice(30) };
+    let l1_gas_prices = L1GasPrices { l1_gas_price, l1_data_gas_price: l1_gas_price };
+    let l2_gas_price = gas_prices(l1_gas_prices).get_l2_gas_price_by_fee_type(&FeeType::Strk).get();
+    let mut mempool_client = MockMempoolClient::new();
+    // The transactions that can't pay the L2 gas price of the block are parked in the mempool.
+    mempool_client
+        .expect_update_gas_price_threshold()
+        .withf(move |threshold| *threshold == l2_gas_price)
+        .times(1)
+        .returning(|_| Ok(()));
+    mempool_client.expect_open_lease().returning(|_| Ok(0));
+    mempool_client.expect_lease_txs().returning(|_, _| Ok(vec![]));
+    let gas_price_provider = GasPriceProvider::fixed(l1_gas_prices);
+    let config = ProposalsManagerConfig {
+        sequencer_address: contract_address!("0x1"),
+        ..Default::default()
+    };
+    let mut proposals_manager = ProposalsManager::new(
+        config,
+        Arc::new(mempool_client),
+        gas_price_provider,
+        SharedL1HandlerQueue::default(),
+    );
+    let height = BlockNumber(1);
+    proposals_manager
+        .generate_block_proposal(0, tokio::time::Instant::now() + GENERATION_TIMEOUT, height, ROUND)
+        .await
+        .unwrap();
+
+    let block_info = proposals_manager.proposal_block_info(0).unwrap();
+    assert_eq!(block_info.block_number, height);
+    assert_eq!(block_info.sequencer_address, contract_address!("0x1"));
+    assert_eq!(block_info.gas_prices.get_l1_gas_price_by_fee_type(&FeeType::Eth).get(), 3);
+    assert_eq!(block_info.gas_prices.get_l1_data_gas_price_by_fee_type(&FeeType::Strk).get(), 30);
+}
+
+#[tokio::test]
+async fn l2_gas_price_is_set_by_the_usage_of_the_decided_proposal() {
+    let l1_gas_price = GasPricePerToken {
+        price_in_wei: GasPrice(10_u128.pow(12)),
+        price_in_fri: GasPrice(10_u128.pow(13)),
+    };
+    let l1_gas_prices = L1GasPrices { l1_gas_price, l1_data_gas_price: l1_gas_price };
+    let mut mempool_client = mock_mempool_client();
+    mempool_client.expect_open_lease().returning(|_| Ok(0));
+    mempool_client.expect_lease_txs().returning(|_, _| Ok(vec![]));
+    mempool_client.expect_commit_block().returning(|_, _| Ok(()));
+    let config = ProposalsManagerConfig::default();
+    let mut proposals_manager = ProposalsManager::new(
+        config.clone(),
+        Arc::new(mempool_client),
+        GasPriceProvider::fixed(l1_gas_prices),
+        SharedL1HandlerQueue::default(),
+    );
+    // The proposal times out before taking any transaction, so the block is left empty.
+    let streamed_txs = proposals_manager
+        .generate_block_proposal(0, tokio::time::Instant::now(), BlockNumber(1), ROUND)
+        .await
+        .unwrap();
+    streamed_txs.collect::<Vec<_>>().await;
+    let parent_l2_gas_price = proposals_manager
+        .proposal_block_info(0)
+        .unwrap()
+        .gas_prices
+        .get_l2_gas_price_by_fee_type(&FeeType::Strk);
+    proposals_manager
+        .add_decided_block(BlockNumber(1), Some(0), vec![], vec![], HashMap::new())
+        .await
+        .unwrap();
+
+    // The parent block was empty, so the L2 gas price falls.
+    let streamed_txs = proposals_manager
+        .generate_block_proposal(1, tokio::time::Instant::now(), BlockNumber(2), ROUND)
+        .await
+        .unwrap();
+    streamed_txs.collect::<Vec<_>>().await;
+    let l2_gas_price = proposals_manager
+        .proposal_block_info(1)
+        .unwrap()
+        .gas_prices
+        .get_l2_gas_price_by_fee_type(&FeeType::Strk);
+    assert!(l2_gas_price < parent_l2_gas_price);
+    assert_eq!(
+        l2_gas_price,
+        FeeMarket::new(&config.fee_market).next_l2_gas_price(
+            parent_l2_gas_price,
+            0,
+            config.fee_market.min_strk_l2_gas_price
+        )
+    );
+
+    // The usage of a block proposed by another node isn't known, so the L2 gas price of the next
+    // block is converted from the L1 gas price.
+    proposals_manager
+        .add_decided_block(BlockNumber(2), None, vec![], vec![], HashMap::new())
+        .await
+        .unwrap();
+    proposals_manager
+        .generate_block_proposal(2, tokio::time::Instant::now(), BlockNumber(3), ROUND)
+        .await
+        .unwrap();
+    assert_eq!(
+        proposals_manager
+            .proposal_block_info(2)
+            .unwrap()
+            .gas_prices
+            .get_l2_gas_price_by_fee_type(&FeeType::Strk),
+        gas_prices(l1_gas_prices).get_l2_gas_price_by_fee_type(&FeeType::Strk)
+    );
+}
+
+#[tokio::test]
+async fn proposal_handles_the_pending_l1_messages() {
+    let l1_handler_message = |nonce: u64| L1HandlerMessage {
+        tx: L1HandlerTransaction { nonce: Nonce(Felt::from(nonce)), ..Default::default() },
+        paid_fee_on_l1: Fee(1),
+    };
+    let mut mempool_client = mock_mempool_client();
+    mempool_client.expect_open_lease().returning(|_| Ok(0));
+    mempool_client.expect_lease_txs().returning(|_, _| Ok(vec![]));
+    mempool_client.expect_release_lease().returning(|_| Ok(()));
+    mempool_client.expect_commit_block().returning(|_, _| Ok(()));
+    let l1_handler_queue = SharedL1HandlerQueue::default();
+    for nonce in [2, 0, 1] {
+        l1_handler_queue.lock().await.add(l1_handler_message(nonce));
+    }
+    let config =
+        ProposalsManagerConfig { max_l1_handler_txs_per_proposal: 2, ..Default::default() };
+    let mut proposals_manager = ProposalsManager::new(
+        config,
+        Arc::new(mempool_client),
+        gas_price_provider(),
+        l1_handler_queue.clone(),
+    );
+
+    proposals_manager
+        .generate_block_proposal(
+            0,
+            tokio::time::Instant::now() + GENERATION_TIMEOUT,
+            BlockNumber(0),
+            ROUND,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        proposals_manager.proposal_l1_handler_messages(0).unwrap(),
+        [l1_handler_message(0), l1_handler_message(1)]
+    );
+
+    // The messages that the decided block handled aren't proposed again.
+    proposals_manager.abort_proposal(0).await;
+    proposals_manager
+        .add_decided_block(
+            BlockNumber(0),
+            None,
+            vec![],
+            vec![l1_handler_message(0).tx],
+            HashMap::new(),
+        )
+        .await
+        .unwrap();
+    proposals_manager
+        .generate_block_proposal(
+            1,
+            tokio::time::Instant::now() + GENERATION_TIMEOUT,
+            BlockNumber(1),
+            ROUND,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        proposals_manager.proposal_l1_handler_messages(1).unwrap(),
+        [l1_handler_message(1), l1_handler_message(2)]
+    );
+}
+
+#[test]
+fn received_proposal_is_validated_against_the_local_clock_and_gas_prices() {
+    let l1_gas_price =
+        GasPricePerToken { price_in_wei: GasPrice(1000), price_in_fri: GasPrice(1000) };
+    let gas_price_provider =
+        || GasPriceProvider::fixed(L1GasPrices { l1_gas_price, l1_data_gas_price: l1_gas_price });
+    let mut proposals_manager = ProposalsManager::new(
+        ProposalsManagerConfig::default(),
+        Arc::new(MockMempoolClient::new()),
+        gas_price_provider(),
+        SharedL1HandlerQueue::default(),
+    );
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let parent =
+        ParentBlockInfo { block_number: BlockNumber(0), block_timestamp: BlockTimestamp(now) };
+    // A proposal priced like the node would price it.
+    let block_info = block_info_builder(true, gas_price_provider())
+        .build(BlockNumber(1), BlockTimestamp(now))
+        .unwrap();
+    proposals_manager.validate_block_proposal(&block_info, &parent).unwrap();
+
+    let far_ahead_block_info =
+        BlockInfo { block_timestamp: BlockTimestamp(now + 3600), ..block_info.clone() };
+    assert_matches!(
+        proposals_manager.validate_block_proposal(&far_ahead_block_info, &parent),
+        Err(ProposalsManagerError::InvalidBlockInfo(
+            BlockInfoValidationError::TimestampTooFarAhead { .. }
+        ))
+    );
+
+    let doubled_gas_price = l1_gas_price.price_in_wei.0 * 2;
+    let overpriced_block_info = BlockInfo {
+        gas_prices: GasPrices::new(
+            doubled_gas_price.try_into().unwrap(),
+            block_info.gas_prices.get_l1_gas_price_by_fee_type(&FeeType::Strk),
+            block_info.gas_prices.get_l1_data_gas_price_by_fee_type(&FeeType::Eth),
+            block_info.gas_prices.get_l1_data_gas_price_by_fee_type(&FeeType::Strk),
+            block_info.gas_prices.get_l2_gas_price_by_fee_type(&FeeType::Eth),
+            block_info.gas_prices.get_l2_gas_price_by_fee_type(&FeeType::Strk),
+        ),
+        ..block_info
+    };
+    assert_matches!(
+        proposals_manager.validate_block_proposal(&overpriced_block_info, &parent),
+        Err(ProposalsManagerError::InvalidBlockInfo(
+            BlockInfoValidationError::GasPriceOutOfBounds { gas: "ETH L1 gas", .. }
+        ))
+    );
+}
+
+#[test]
+fn timestamp_tolerance_is_widened_by_the_lag_of_the_local_clock() {
+    let config = ProposalsManagerConfig {
+        clock_skew: ClockSkewConfig { widen_timestamp_tolerance: true, ..Default::default() },
+        ..Default::default()
+    };
+    let mut proposals_manager = ProposalsManager::new(
+        config,
+        Arc::new(MockMempoolClient::new()),
+        gas_price_provider(),
+        SharedL1HandlerQueue::default(),
+    );
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let parent =
+        ParentBlockInfo { block_number: BlockNumber(0), block_timestamp: BlockTimestamp(now) };
+    // The proposers' clocks are 20 seconds ahead of the local one, beyond the default tolerance.
+    let block_info = block_info_builder(true, gas_price_provider())
+        .build(BlockNumber(1), BlockTimestamp(now + 20))
+        .unwrap();
+
+    // The rejected proposals are sampled until the skew is estimated.
+    for _ in 0..3 {
+        assert_matches!(
+            proposals_manager.validate_block_proposal(&block_info, &parent),
+            Err(ProposalsManagerError::InvalidBlockInfo(
+                BlockInfoValidationError::TimestampTooFarAhead { .. }
+            ))
+        );
+    }
+    proposals_manager.validate_block_proposal(&block_info, &parent).unwrap();
+}
+
+#[test]
+fn deploy_account_bundle_is_added_atomically() {
+    let deployed_account = contract_address!("0x1");
+    let deploy_account_tx = Transaction::DeployAccount(DeployAccountTransaction {
+        tx: starknet_api::transaction::DeployAccountTransaction::V1(
+            DeployAccountTransactionV1::default(),
+        ),
+        tx_hash: TransactionHash(felt!("0x1")),
+        contract_address: deployed_account,
+    });
+    let invoke_tx = Transaction::Invoke(InvokeTransaction {
+        tx: starknet_api::transaction::InvokeTransaction::V1(InvokeTransactionV1 {
+            sender_address: deployed_account,
+            ..Default::default()
+        }),
+        tx_hash: TransactionHash(felt!("0x2")),
+    });
+    let txs = [deploy_account_tx, invoke_tx];
+    let block_info = block_info_builder(false, gas_price_provider())
+        .build(BlockNumber(0), BlockTimestamp(0))
+        .unwrap();
+    let block_builder = BlockBuilder { block_info, l1_handler_messages: vec![] };
+
+    // The channel has room for one transaction only, so the bundle isn't added.
+    let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+    assert_eq!(block_builder.add_txs_and_stream(&txs, &sender).len(), 0);
+    assert!(receiver.try_recv().is_err());
+
+    let (sender, mut receiver) = tokio::sync::mpsc::channel(2);
+    assert_eq!(block_builder.add_txs_and_stream(&txs, &sender).len(), 2);
+    assert_eq!(receiver.try_recv().unwrap(), txs[0]);
+    assert_eq!(receiver.try_recv().unwrap(), txs[1]);
+}
+
+#[test]
+fn block_capacity_must_fit_a_transaction() {
+    let mut config = ProposalsManagerConfig::default();
+    assert_matches!(config_validate(&config), Ok(()));
+
+    let versioned_constants = VersionedConstants::latest_constants();
+    config.block_max_capacity.n_events =
+        versioned_constants.tx_event_limits.max_n_emitted_events - 1;
+    assert_matches!(
+        config_validate(&config),
+        Err(ConfigError::ConfigValidationError(errors))
+        if errors.0.len() == 1 && errors.0[0].param_path == "block_max_capacity.n_events"
+    );
+
+    config.block_max_capacity.n_events = versioned_constants.tx_event_limits.max_n_emitted_events;
+    assert_matches!(config_validate(&config), Ok(()));
+}
+
+#[test]
+fn resource_profiles_must_not_be_empty() {
+    let config = ProposalsManagerConfig { n_resource_profiles: 0, ..Default::default() };
+    assert_matches!(
+        config_validate(&config),
+        Err(ConfigError::ConfigValidationError(errors))
+        if errors.0.len() == 1 && errors.0[0].param_path == "n_resource_profiles"
+    );
+}
@@ -0,0 +1,80 @@
+//! The transactions that were recently included in blocks, to avoid proposing them twice.
+//!
+//! The mempool removes the transactions of a decided block only once it's notified of the commit,
+//! so for a short while it may still return them. The batcher consults [`RecentTransactions`]
+//! before executing the transactions it gets from the mempool, and skips those that are already
+//! included in one of the last decided blocks or in a proposal that is still in flight.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use starknet_api::block::BlockNumber;
+use starknet_api::transaction::TransactionHash;
+
+use crate::proposals_manager::ProposalId;
+
+#[derive(Debug, Default)]
+pub struct RecentTransactions {
+    /// The number of decided blocks whose transactions are kept.
+    n_blocks: usize,
+    /// The transactions of the last decided blocks, oldest first.
+    decided_blocks: VecDeque<(BlockNumber, Vec<TransactionHash>)>,
+    /// The block each of the decided transactions was included in.
+    decided_transactions: HashMap<TransactionHash, BlockNumber>,
+    /// The transactions of the proposals that weren't decided yet, with the height they're for.
+    proposals: HashMap<ProposalId, (BlockNumber, HashSet<TransactionHash>)>,
+}
+
+impl RecentTransactions {
+    pub fn new(n_blocks: usize) -> Self {
+        Self { n_blocks, ..Default::default() }
+    }
+
+    /// Returns true if the transaction is included in a recently decided block or in a proposal in
+    /// flight.
+    pub fn contains(&self, tx_hash: &TransactionHash) -> bool {
+        self.decided_transactions.contains_key(tx_hash)
+            || self.proposals.values().any(|(_, tx_hashes)| tx_hashes.contains(tx_hash))
+    }
+
+    /// Records transactions that were added to a proposal for the given height.
+    pub fn add_proposal_transactions(
+        &mut self,
+        proposal_id: ProposalId,
+        height: BlockNumber,
+        tx_hashes: impl IntoIterator<Item = TransactionHash>,
+    ) {
+        self.proposals
+            .entry(proposal_id)
+            .or_insert_with(|| (height, HashSet::new()))
+            .1
+            .extend(tx_hashes);
+    }
+
+    /// Forgets the transactions of a proposal that won't be decided, so they can be proposed again.
+    pub fn remove_proposal(&mut self, proposal_id: ProposalId) {
+        self.proposals.remove(&proposal_id);
+    }
+
+    /// Records the transactions of a decided block. The proposals for its height or below are
+    /// dropped, as the decided block replaces them, and the transactions of blocks older than the
+    /// last `n_blocks` are forgotten.
+    pub fn add_decided_block(&mut self, height: BlockNumber, tx_hashes: Vec<TransactionHash>) {
+        self.proposals.retain(|_, (proposal_height, _)| *proposal_height > height);
+        for tx_hash in &tx_hashes {
+            self.decided_transactions.insert(*tx_hash, height);
+        }
+        self.decided_blocks.push_back((height, tx_hashes));
+
+        while self.decided_blocks.len() > self.n_blocks {
+            let Some((old_height, old_tx_hashes)) = self.decided_blocks.pop_front() else {
+                break;
+            };
+            for tx_hash in old_tx_hashes {
+                // A transaction that was included again in a later block stays.
+                if self.decided_transactions.get(&tx_hash) == Some(&old_height) {
+                    self.decided_transactions.remove(&tx_hash);
+                }
+            }
+        }
+    }
+}
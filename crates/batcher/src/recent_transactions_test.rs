@@ -0,0 +1,51 @@
+use starknet_api::block::BlockNumber;
+use starknet_api::felt;
+use starknet_api::transaction::TransactionHash;
+
+use crate::recent_transactions::RecentTransactions;
+
+fn tx_hash(hash: u8) -> TransactionHash {
+    TransactionHash(felt!(hash))
+}
+
+#[test]
+fn proposal_transactions() {
+    let mut recent_transactions = RecentTransactions::new(1);
+    recent_transactions.add_proposal_transactions(0, BlockNumber(0), vec![tx_hash(1)]);
+    recent_transactions.add_proposal_transactions(1, BlockNumber(0), vec![tx_hash(2)]);
+    assert!(recent_transactions.contains(&tx_hash(1)));
+    assert!(recent_transactions.contains(&tx_hash(2)));
+
+    // The transactions of an aborted proposal can be proposed again.
+    recent_transactions.remove_proposal(0);
+    assert!(!recent_transactions.contains(&tx_hash(1)));
+    assert!(recent_transactions.contains(&tx_hash(2)));
+}
+
+#[test]
+fn decided_block_replaces_proposals_of_its_height() {
+    let mut recent_transactions = RecentTransactions::new(1);
+    recent_transactions.add_proposal_transactions(0, BlockNumber(0), vec![tx_hash(1)]);
+    recent_transactions.add_proposal_transactions(1, BlockNumber(1), vec![tx_hash(2)]);
+
+    recent_transactions.add_decided_block(BlockNumber(0), vec![tx_hash(3)]);
+    assert!(!recent_transactions.contains(&tx_hash(1)));
+    assert!(recent_transactions.contains(&tx_hash(2)));
+    assert!(recent_transactions.contains(&tx_hash(3)));
+}
+
+#[test]
+fn keep_last_decided_blocks() {
+    let mut recent_transactions = RecentTransactions::new(2);
+    recent_transactions.add_decided_block(BlockNumber(0), vec![tx_hash(1), tx_hash(2)]);
+    recent_transactions.add_decided_block(BlockNumber(1), vec![tx_hash(3)]);
+    recent_transactions.add_decided_block(BlockNumber(2), vec![tx_hash(2)]);
+
+    assert!(!recent_transactions.contains(&tx_hash(1)));
+    // Included again in a block that is kept.
+    assert!(recent_transactions.contains(&tx_hash(2)));
+    assert!(recent_transactions.contains(&tx_hash(3)));
+
+    recent_transactions.add_decided_block(BlockNumber(3), vec![]);
+    assert!(!recent_transactions.contains(&tx_hash(3)));
+}
@@ -0,0 +1,142 @@
+//! Prioritizing transactions by the fee they pay per the block capacity they use.
+//!
+//! The block is closed once one of its bouncer weights (steps, gas, builtins, ...) reaches its
+//! capacity, so a transaction that pays a high fee but uses a large part of the block may be worth
+//! less than a few cheaper ones. When prioritizing by resources, the batcher orders the
+//! transactions it gets from the mempool by their maximal fee per the estimated fraction of the
+//! block capacity they use, which packs the block greedily as a fractional knapsack. The fraction
+//! is estimated from the bouncer weights of previous executions of the same entry point (see
+//! [`ResourceProfileKey`]).
+//!
+//! The transactions of each account keep their nonce order.
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+use std::num::NonZeroUsize;
+
+use blockifier::bouncer::{BouncerWeights, FULL_BLOCK_FRACTION};
+use lru::LruCache;
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector};
+use starknet_api::executable_transaction::Transaction;
+
+/// Identifies the transactions that are expected to use similar resources.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ResourceProfileKey {
+    Declare,
+    DeployAccount(ClassHash),
+    /// The first call of an invoke transaction, assuming the calldata of the account is an array
+    /// of calls that starts with the called contract and entry point of each call.
+    Invoke {
+        contract_address: ContractAddress,
+        selector: EntryPointSelector,
+    },
+    /// An invoke transaction whose calldata doesn't start with a call.
+    Account(ContractAddress),
+}
+
+impl ResourceProfileKey {
+    pub fn new(tx: &Transaction) -> Self {
+        match tx {
+            Transaction::Declare(_) => Self::Declare,
+            Transaction::DeployAccount(tx) => Self::DeployAccount(tx.class_hash()),
+            Transaction::Invoke(tx) => {
+                let calldata = tx.calldata();
+                let Some([contract_address, selector]) = calldata.0.get(1..3) else {
+                    return Self::Account(tx.sender_address());
+                };
+                match ContractAddress::try_from(*contract_address) {
+                    Ok(contract_address) => {
+                        Self::Invoke { contract_address, selector: EntryPointSelector(*selector) }
+                    }
+                    Err(_) => Self::Account(tx.sender_address()),
+                }
+            }
+        }
+    }
+}
+
+/// The fractions of the block capacity that recently executed transactions used, by entry point.
+pub struct ResourceProfiles {
+    block_max_capacity: BouncerWeights,
+    block_fractions: LruCache<ResourceProfileKey, u128>,
+}
+
+impl ResourceProfiles {
+    pub fn new(block_max_capacity: BouncerWeights, n_profiles: NonZeroUsize) -> Self {
+        Self { block_max_capacity, block_fractions: LruCache::new(n_profiles) }
+    }
+
+    /// Records the bouncer weights of an executed transaction. The profile of its entry point is a
+    /// moving average that gives the latest execution a quarter of the weight.
+    pub fn record(&mut self, tx: &Transaction, weights: &BouncerWeights) {
+        let block_fraction = weights.block_fraction(&self.block_max_capacity);
+        let key = ResourceProfileKey::new(tx);
+        let profile = match self.block_fractions.get(&key) {
+            Some(profile) => (profile * 3 + block_fraction) / 4,
+            None => block_fraction,
+        };
+        self.block_fractions.put(key, profile);
+    }
+
+    /// Returns the estimated fraction of the block capacity the transaction uses, in parts per
+    /// million, or None if no transaction of its entry point was executed recently.
+    pub fn estimated_block_fraction(&self, tx: &Transaction) -> Option<u128> {
+        self.block_fractions.peek(&ResourceProfileKey::new(tx)).copied()
+    }
+
+    /// Orders the transactions by their maximal fee per the estimated fraction of the block
+    /// capacity they use, highest first. Transactions without a profile are estimated by the
+    /// average of the known profiles. The transactions of each account keep their relative order.
+    pub fn prioritize(&self, txs: Vec<Transaction>) -> Vec<Transaction> {
+        let default_block_fraction = self.average_block_fraction();
+        let mut slots: Vec<(ContractAddress, u128)> = txs
+            .iter()
+            .map(|tx| {
+                let block_fraction =
+                    self.estimated_block_fraction(tx).unwrap_or(default_block_fraction).max(1);
+                let priority = max_fee(tx).saturating_mul(FULL_BLOCK_FRACTION) / block_fraction;
+                (tx.contract_address(), priority)
+            })
+            .collect();
+        // A stable sort, so transactions of the same priority keep their order.
+        slots.sort_by_key(|(_, priority)| Reverse(*priority));
+
+        // Each account fills the slots of its transactions with them in their original order, so
+        // a transaction never comes before one with a lower nonce of the same account.
+        let mut account_txs: HashMap<ContractAddress, VecDeque<Transaction>> = HashMap::new();
+        for tx in txs {
+            account_txs.entry(tx.contract_address()).or_default().push_back(tx);
+        }
+        slots
+            .into_iter()
+            .map(|(address, _)| {
+                account_txs
+                    .get_mut(&address)
+                    .and_then(VecDeque::pop_front)
+                    .expect("Each slot should have a transaction of its account.")
+            })
+            .collect()
+    }
+
+    fn average_block_fraction(&self) -> u128 {
+        let n_profiles =
+            u128::try_from(self.block_fractions.len()).expect("usize should fit in u128.");
+        match n_profiles {
+            0 => FULL_BLOCK_FRACTION,
+            _ => self.block_fractions.iter().map(|(_, profile)| profile).sum::<u128>() / n_profiles,
+        }
+    }
+}
+
+// The maximal fee the transaction may pay, by its resource bounds.
+fn max_fee(tx: &Transaction) -> u128 {
+    tx.resource_bounds()
+        .map(|resource_bounds| {
+            resource_bounds.0.values().fold(0, |max_fee: u128, bounds| {
+                max_fee.saturating_add(
+                    u128::from(bounds.max_amount).saturating_mul(bounds.max_price_per_unit),
+                )
+            })
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,110 @@
+use std::num::NonZeroUsize;
+
+use blockifier::bouncer::BouncerWeights;
+use starknet_api::core::{ContractAddress, EntryPointSelector, Nonce, PatriciaKey};
+use starknet_api::data_availability::DataAvailabilityMode;
+use starknet_api::executable_transaction::{InvokeTransaction, Transaction};
+use starknet_api::transaction::{
+    Calldata,
+    DeprecatedResourceBoundsMapping,
+    InvokeTransactionV3,
+    Resource,
+    ResourceBounds,
+    Tip,
+    TransactionHash,
+};
+use starknet_api::{calldata, contract_address, felt, patricia_key};
+
+use crate::transaction_prioritizer::{ResourceProfileKey, ResourceProfiles};
+
+// An invoke transaction of the account, whose first call is to the given contract, and that pays
+// at most `max_fee`.
+fn invoke_tx(sender: &str, nonce: u8, called_contract: &str, max_fee: u64) -> Transaction {
+    let resource_bounds = DeprecatedResourceBoundsMapping::try_from(vec![(
+        Resource::L1Gas,
+        ResourceBounds { max_amount: max_fee, max_price_per_unit: 1 },
+    )])
+    .unwrap();
+    Transaction::Invoke(InvokeTransaction {
+        tx: starknet_api::transaction::InvokeTransaction::V3(InvokeTransactionV3 {
+            sender_address: contract_address!(sender),
+            tip: Tip(0),
+            nonce: Nonce(felt!(nonce)),
+            resource_bounds,
+            signature: Default::default(),
+            calldata: calldata![felt!(1_u8), felt!(called_contract), felt!("0x99")],
+            nonce_data_availability_mode: DataAvailabilityMode::L1,
+            fee_data_availability_mode: DataAvailabilityMode::L1,
+            paymaster_data: Default::default(),
+            account_deployment_data: Default::default(),
+        }),
+        tx_hash: TransactionHash(felt!(nonce)),
+    })
+}
+
+fn profiles() -> ResourceProfiles {
+    let block_max_capacity = BouncerWeights { n_steps: 1000, ..BouncerWeights::max() };
+    ResourceProfiles::new(block_max_capacity, NonZeroUsize::new(10).unwrap())
+}
+
+fn steps(n_steps: usize) -> BouncerWeights {
+    BouncerWeights { n_steps, ..Default::default() }
+}
+
+#[test]
+fn profile_key_of_first_call() {
+    assert_eq!(
+        ResourceProfileKey::new(&invoke_tx("0x1", 0, "0x2", 0)),
+        ResourceProfileKey::Invoke {
+            contract_address: contract_address!("0x2"),
+            selector: EntryPointSelector(felt!("0x99")),
+        }
+    );
+}
+
+#[test]
+fn record_moving_average() {
+    let mut profiles = profiles();
+    let tx = invoke_tx("0x1", 0, "0x2", 0);
+    assert_eq!(profiles.estimated_block_fraction(&tx), None);
+
+    profiles.record(&tx, &steps(100));
+    assert_eq!(profiles.estimated_block_fraction(&tx), Some(100_000));
+    profiles.record(&tx, &steps(500));
+    assert_eq!(profiles.estimated_block_fraction(&tx), Some(200_000));
+}
+
+#[test]
+fn prioritize_by_fee_per_block_fraction() {
+    let mut profiles = profiles();
+    let heavy_tx = invoke_tx("0x1", 0, "0x10", 100);
+    let light_tx = invoke_tx("0x2", 0, "0x20", 50);
+    let unknown_tx = invoke_tx("0x3", 0, "0x30", 30);
+    profiles.record(&heavy_tx, &steps(500));
+    profiles.record(&light_tx, &steps(100));
+
+    // Fee per block fraction: 50 / 0.1 for the light transaction, 100 / 0.5 for the heavy one,
+    // and 30 / 0.3 for the unknown one, estimated by the average of the profiles.
+    assert_eq!(
+        profiles.prioritize(vec![heavy_tx.clone(), light_tx.clone(), unknown_tx.clone()]),
+        vec![light_tx, heavy_tx, unknown_tx]
+    );
+}
+
+#[test]
+fn prioritize_keeps_nonce_order_of_account() {
+    let mut profiles = profiles();
+    let first_tx = invoke_tx("0x1", 0, "0x10", 10);
+    let second_tx = invoke_tx("0x1", 1, "0x20", 100);
+    let other_tx = invoke_tx("0x2", 0, "0x30", 50);
+    for tx in [&first_tx, &second_tx, &other_tx] {
+        profiles.record(tx, &steps(100));
+    }
+
+    // The second transaction of the account has the highest priority, but it can't come before
+    // the first one, which takes its place.
+    assert_eq!(
+        profiles.prioritize(vec![first_tx.clone(), second_tx.clone(), other_tx.clone()]),
+        vec![first_tx, other_tx, second_tx]
+    );
+}
@@ -5,14 +5,36 @@
 //! The main benchmark function is `transfers_benchmark`, which measures the performance
 //! of transfers between randomly created accounts, which are iterated over round-robin.
 //!
-//! Run the benchmarks using `cargo bench --bench blockifier_bench`.
+//! `workloads_benchmark` measures the throughput of the executor on representative workloads (see
+//! [`Workload`]), each executed sequentially and, with the `concurrency` feature, concurrently.
+//!
+//! Run the benchmarks using `cargo bench --bench blockifier_bench`. To gate a change on performance
+//! regressions, save a baseline of the base code with `-- --save-baseline base` and then compare
+//! the new code with `-- --baseline base`. Similarly, the `memory_pools` feature (see
+//! [`blockifier::execution::memory_pool`]) is evaluated by comparing a run with `--features
+//! memory_pools` to a baseline without it; `memory_pool_benchmark` measures the pooled buffers
+//! alone, and `workloads_benchmark` the executor as a whole.
 
+use blockifier::execution::memory_pool;
 use blockifier::test_utils::transfers_generator::{
     RecipientGeneratorType,
     TransfersGenerator,
     TransfersGeneratorConfig,
 };
-use criterion::{criterion_group, criterion_main, Criterion};
+use blockifier::test_utils::workloads::{Workload, WorkloadGenerator, WorkloadGeneratorConfig};
+use cairo_vm::types::relocatable::MaybeRelocatable;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use starknet_types_core::felt::Felt;
+
+// The length of the calldata loaded through the pooled buffers in `memory_pool_benchmark`.
+const CALLDATA_LENGTH: u64 = 2000;
+
+const WORKLOADS: [Workload; 4] = [
+    Workload::Erc20Transfers,
+    Workload::KeccakHashing,
+    Workload::DeepCallTree { depth: 30 },
+    Workload::LargeCalldata { calldata_length: 2000 },
+];
 
 pub fn transfers_benchmark(c: &mut Criterion) {
     let transfers_generator_config = TransfersGeneratorConfig {
@@ -29,5 +51,47 @@ pub fn transfers_benchmark(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, transfers_benchmark);
+pub fn workloads_benchmark(c: &mut Criterion) {
+    for workload in WORKLOADS {
+        let mut group = c.benchmark_group(workload.name());
+        let sequential_config = WorkloadGeneratorConfig::new(workload);
+        // The throughput is reported in transactions per second.
+        group.throughput(Throughput::Elements(u64::try_from(sequential_config.n_txs).unwrap()));
+
+        let mut workload_generator = WorkloadGenerator::new(sequential_config);
+        group.bench_function("sequential", |benchmark| {
+            benchmark.iter(|| workload_generator.execute_batch())
+        });
+
+        #[cfg(feature = "concurrency")]
+        {
+            let mut workload_generator =
+                WorkloadGenerator::new(WorkloadGeneratorConfig::new(workload).concurrent());
+            group.bench_function("concurrent", |benchmark| {
+                benchmark.iter(|| workload_generator.execute_batch())
+            });
+        }
+
+        group.finish();
+    }
+}
+
+pub fn memory_pool_benchmark(c: &mut Criterion) {
+    let calldata: Vec<MaybeRelocatable> =
+        (0..CALLDATA_LENGTH).map(|i| MaybeRelocatable::from(Felt::from(i))).collect();
+    // Loads the calldata of a call into the buffer its arguments are loaded through, as on every
+    // entry point call, and resets the pool as between transactions.
+    c.bench_function("memory_pool", |benchmark| {
+        benchmark.iter(|| {
+            let n_args = memory_pool::with_args_buffer(|args| {
+                args.extend(calldata.iter().cloned());
+                black_box(args.len())
+            });
+            memory_pool::reset();
+            n_args
+        })
+    });
+}
+
+criterion_group!(benches, transfers_benchmark, workloads_benchmark, memory_pool_benchmark);
 criterion_main!(benches);
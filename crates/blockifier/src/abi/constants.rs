@@ -36,6 +36,7 @@ pub const N_STEPS_RESOURCE: &str = "n_steps";
 pub const N_EVENTS: &str = "n_events";
 pub const MESSAGE_SEGMENT_LENGTH: &str = "message_segment_length";
 pub const STATE_DIFF_SIZE: &str = "state_diff_size";
+pub const BUILTIN_GAS: &str = "builtin_gas";
 pub const N_MEMORY_HOLES: &str = "n_memory_holes";
 
 // Casm hash calculation-related constants.
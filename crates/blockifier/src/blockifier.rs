@@ -4,3 +4,5 @@ pub mod stateful_validator;
 pub mod transaction_executor;
 #[cfg(test)]
 pub mod transfers_flow_test;
+#[cfg(test)]
+pub mod workloads_flow_test;
@@ -7,6 +7,8 @@ use starknet_api::state::StorageKey;
 use starknet_types_core::felt::Felt;
 
 use crate::abi::constants;
+use crate::bouncer::BouncerConfig;
+use crate::context::{BlockContext, ChainInfo};
 use crate::state::errors::StateError;
 use crate::state::state_api::{State, StateResult};
 use crate::transaction::objects::FeeType;
@@ -74,6 +76,15 @@ impl GasPrices {
         }
     }
 
+    /// Returns the gas prices with the given L2 gas prices, e.g. as set by the fee market.
+    pub fn with_l2_gas_prices(
+        &self,
+        eth_l2_gas_price: NonZeroU128,
+        strk_l2_gas_price: NonZeroU128,
+    ) -> Self {
+        Self { eth_l2_gas_price, strk_l2_gas_price, ..self.clone() }
+    }
+
     pub fn get_l1_gas_price_by_fee_type(&self, fee_type: &FeeType) -> NonZeroU128 {
         match fee_type {
             FeeType::Strk => self.strk_l1_gas_price,
@@ -111,6 +122,17 @@ pub fn pre_process_block(
     if let Some(BlockNumberHashPair { number: block_number, hash: block_hash }) =
         old_block_number_and_hash
     {
+        // Writing the hash of any other block would silently corrupt the block hash table.
+        let expected_block_number =
+            next_block_number.0.checked_sub(constants::STORED_BLOCK_HASH_BUFFER).map(BlockNumber);
+        if expected_block_number != Some(block_number) {
+            return Err(StateError::InvalidOldBlockNumber {
+                next_block_number,
+                expected: expected_block_number.unwrap_or_default(),
+                actual: block_number,
+            });
+        }
+
         let block_hash_contract_address =
             ContractAddress::from(constants::BLOCK_HASH_CONTRACT_ADDRESS);
         let block_number_as_storage_key = StorageKey::from(block_number.0);
@@ -126,6 +148,59 @@ pub fn pre_process_block(
     Ok(())
 }
 
+/// The parts of a block that the info of its child block is validated against.
+#[derive(Clone, Copy, Debug)]
+pub struct ParentBlockInfo {
+    pub block_number: BlockNumber,
+    pub block_timestamp: BlockTimestamp,
+}
+
+impl From<&BlockInfo> for ParentBlockInfo {
+    fn from(block_info: &BlockInfo) -> Self {
+        Self { block_number: block_info.block_number, block_timestamp: block_info.block_timestamp }
+    }
+}
+
+/// Checks that a block follows its parent block: its number is the next one, and its timestamp
+/// isn't lower.
+pub fn validate_block_info(
+    block_info: &BlockInfo,
+    parent_block_info: &ParentBlockInfo,
+) -> StateResult<()> {
+    let block_number = block_info.block_number;
+    let parent_block_number = parent_block_info.block_number;
+    if parent_block_number.next() != Some(block_number) {
+        return Err(StateError::NonConsecutiveBlockNumber { block_number, parent_block_number });
+    }
+    if block_info.block_timestamp < parent_block_info.block_timestamp {
+        return Err(StateError::DecreasingBlockTimestamp {
+            block_number,
+            timestamp: block_info.block_timestamp.0,
+            parent_timestamp: parent_block_info.block_timestamp.0,
+        });
+    }
+    Ok(())
+}
+
+/// Prepares the state for the execution of a block and returns the context to execute its
+/// transactions in. The block info is validated against the parent block, if it's given, and the
+/// hash of the block `STORED_BLOCK_HASH_BUFFER` blocks back is written by [`pre_process_block`].
+pub fn pre_process_block_and_create_context(
+    state: &mut dyn State,
+    old_block_number_and_hash: Option<BlockNumberHashPair>,
+    parent_block_info: Option<ParentBlockInfo>,
+    block_info: BlockInfo,
+    chain_info: ChainInfo,
+    versioned_constants: VersionedConstants,
+    bouncer_config: BouncerConfig,
+) -> StateResult<BlockContext> {
+    if let Some(parent_block_info) = parent_block_info {
+        validate_block_info(&block_info, &parent_block_info)?;
+    }
+    pre_process_block(state, old_block_number_and_hash, block_info.block_number)?;
+    Ok(BlockContext::new(block_info, chain_info, versioned_constants, bouncer_config))
+}
+
 pub struct BlockNumberHashPair {
     pub number: BlockNumber,
     pub hash: BlockHash,
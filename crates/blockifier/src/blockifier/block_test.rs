@@ -1,15 +1,25 @@
-use starknet_api::block::BlockNumber;
+use assert_matches::assert_matches;
+use starknet_api::block::{BlockNumber, BlockTimestamp};
 use starknet_api::core::ContractAddress;
 use starknet_api::felt;
 use starknet_api::state::StorageKey;
 
 use crate::abi::constants;
-use crate::blockifier::block::{pre_process_block, BlockNumberHashPair};
+use crate::blockifier::block::{
+    pre_process_block,
+    pre_process_block_and_create_context,
+    BlockInfo,
+    BlockNumberHashPair,
+    ParentBlockInfo,
+};
+use crate::bouncer::BouncerConfig;
 use crate::context::ChainInfo;
-use crate::state::state_api::StateReader;
+use crate::state::errors::StateError;
+use crate::state::state_api::{State, StateReader};
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::initial_test_state::test_state;
 use crate::test_utils::{CairoVersion, BALANCE};
+use crate::versioned_constants::VersionedConstants;
 
 #[test]
 fn test_pre_process_block() {
@@ -18,20 +28,33 @@ fn test_pre_process_block() {
 
     // Test the positive flow of pre_process_block inside the allowed block number interval
     let block_number = BlockNumber(constants::STORED_BLOCK_HASH_BUFFER);
+    let old_block_number = BlockNumber(0);
     let block_hash = felt!(20_u8);
     pre_process_block(
         &mut state,
-        Some(BlockNumberHashPair::new(block_number.0, block_hash)),
+        Some(BlockNumberHashPair::new(old_block_number.0, block_hash)),
         block_number,
     )
     .unwrap();
 
     let written_hash = state.get_storage_at(
         ContractAddress::from(constants::BLOCK_HASH_CONTRACT_ADDRESS),
-        StorageKey::from(block_number.0),
+        StorageKey::from(old_block_number.0),
     );
     assert_eq!(written_hash.unwrap(), block_hash);
 
+    // Test that only the hash of the block STORED_BLOCK_HASH_BUFFER blocks back can be written.
+    for (old_block_number, block_number) in [(1, constants::STORED_BLOCK_HASH_BUFFER), (0, 1)] {
+        assert_matches!(
+            pre_process_block(
+                &mut state,
+                Some(BlockNumberHashPair::new(old_block_number, block_hash)),
+                BlockNumber(block_number),
+            ),
+            Err(StateError::InvalidOldBlockNumber { .. })
+        );
+    }
+
     // Test that block pre-process with block hash None is successful only within the allowed
     // block number interval.
     let block_number = BlockNumber(constants::STORED_BLOCK_HASH_BUFFER - 1);
@@ -47,3 +70,43 @@ fn test_pre_process_block() {
         format!("{}", error.unwrap_err())
     );
 }
+
+#[test]
+fn test_pre_process_block_and_create_context() {
+    let mut state = test_state(&ChainInfo::create_for_testing(), BALANCE, &[]);
+    let parent_block_info = BlockInfo::create_for_testing();
+    let block_info = |block_number: u64, block_timestamp: u64| BlockInfo {
+        block_number: BlockNumber(block_number),
+        block_timestamp: BlockTimestamp(block_timestamp),
+        ..parent_block_info.clone()
+    };
+    let pre_process = |state: &mut dyn State, block_info| {
+        pre_process_block_and_create_context(
+            state,
+            Some(BlockNumberHashPair::new(
+                parent_block_info.block_number.0 + 1 - constants::STORED_BLOCK_HASH_BUFFER,
+                felt!(20_u8),
+            )),
+            Some(ParentBlockInfo::from(&parent_block_info)),
+            block_info,
+            ChainInfo::create_for_testing(),
+            VersionedConstants::create_for_testing(),
+            BouncerConfig::max(),
+        )
+    };
+
+    let parent_block_number = parent_block_info.block_number.0;
+    let parent_timestamp = parent_block_info.block_timestamp.0;
+    let block_context =
+        pre_process(&mut state, block_info(parent_block_number + 1, parent_timestamp)).unwrap();
+    assert_eq!(block_context.block_info().block_number.0, parent_block_number + 1);
+
+    assert_matches!(
+        pre_process(&mut state, block_info(parent_block_number + 2, parent_timestamp)),
+        Err(StateError::NonConsecutiveBlockNumber { .. })
+    );
+    assert_matches!(
+        pre_process(&mut state, block_info(parent_block_number + 1, parent_timestamp - 1)),
+        Err(StateError::DecreasingBlockTimestamp { .. })
+    );
+}
@@ -1,11 +1,16 @@
 #[derive(Debug, Default, Clone)]
 pub struct TransactionExecutorConfig {
     pub concurrency_config: ConcurrencyConfig,
+    /// Whether to record the keys each transaction reads, in its execution info.
+    pub record_read_sets: bool,
 }
 impl TransactionExecutorConfig {
     #[cfg(any(test, feature = "testing"))]
     pub fn create_for_testing() -> Self {
-        Self { concurrency_config: ConcurrencyConfig::create_for_testing() }
+        Self {
+            concurrency_config: ConcurrencyConfig::create_for_testing(),
+            record_read_sets: false,
+        }
     }
 }
 
@@ -17,6 +17,7 @@ use crate::bouncer::{Bouncer, BouncerWeights};
 #[cfg(feature = "concurrency")]
 use crate::concurrency::worker_logic::WorkerExecutor;
 use crate::context::BlockContext;
+use crate::execution::memory_pool;
 use crate::state::cached_state::{CachedState, CommitmentStateDiff, TransactionalState};
 use crate::state::errors::StateError;
 use crate::state::state_api::StateReader;
@@ -35,6 +36,8 @@ pub const BLOCK_STATE_ACCESS_ERR: &str = "Error: The block state should be `Some
 pub enum TransactionExecutorError {
     #[error("Transaction cannot be added to the current block, block capacity reached.")]
     BlockFull,
+    #[error("The execution of the transaction was interrupted.")]
+    ExecutionInterrupted,
     #[error(transparent)]
     StateError(#[from] StateError),
     #[error(transparent)]
@@ -83,18 +86,40 @@ impl<S: StateReader> TransactionExecutor<S> {
     /// Executes the given transaction on the state maintained by the executor.
     /// Returns the execution result (info or error) if there is room for the transaction;
     /// Otherwise, returns BlockFull error.
+    /// A transaction that fails or reverts after the execution interrupt of the block is triggered
+    /// may have been stopped by it, so it's rejected with an ExecutionInterrupted error rather than
+    /// added to the block.
     pub fn execute(
         &mut self,
         tx: &Transaction,
     ) -> TransactionExecutorResult<TransactionExecutionInfo> {
+        let execution_interrupt = &self.block_context.execution_interrupt;
+        if execution_interrupt.is_interrupted() {
+            return Err(TransactionExecutorError::ExecutionInterrupted);
+        }
+
         let mut transactional_state = TransactionalState::create_transactional(
             self.block_state.as_mut().expect(BLOCK_STATE_ACCESS_ERR),
         );
         // Executing a single transaction cannot be done in a concurrent mode.
-        let execution_flags =
-            ExecutionFlags { charge_fee: true, validate: true, concurrency_mode: false };
+        let execution_flags = ExecutionFlags {
+            charge_fee: true,
+            validate: true,
+            concurrency_mode: false,
+            record_read_set: self.config.record_read_sets,
+        };
         let tx_execution_result =
             tx.execute_raw(&mut transactional_state, &self.block_context, execution_flags);
+        memory_pool::reset();
+        let failed = match &tx_execution_result {
+            Ok(tx_execution_info) => tx_execution_info.is_reverted(),
+            Err(_) => true,
+        };
+        if failed && execution_interrupt.is_interrupted() {
+            transactional_state.abort();
+            return Err(TransactionExecutorError::ExecutionInterrupted);
+        }
+
         match tx_execution_result {
             Ok(tx_execution_info) => {
                 let tx_state_changes_keys =
@@ -124,6 +149,10 @@ impl<S: StateReader> TransactionExecutor<S> {
             match self.execute(tx) {
                 Ok(tx_execution_info) => results.push(Ok(tx_execution_info)),
                 Err(TransactionExecutorError::BlockFull) => break,
+                Err(TransactionExecutorError::ExecutionInterrupted) => {
+                    results.push(Err(TransactionExecutorError::ExecutionInterrupted));
+                    break;
+                }
                 Err(error) => results.push(Err(error)),
             }
         }
@@ -174,8 +203,9 @@ impl<S: StateReader> TransactionExecutor<S> {
 
 impl<S: StateReader + Send + Sync> TransactionExecutor<S> {
     /// Executes the given transactions on the state maintained by the executor.
-    /// Stops if and when there is no more room in the block, and returns the executed transactions'
-    /// results.
+    /// Stops if and when there is no more room in the block, or the execution is interrupted, and
+    /// returns the executed transactions' results. In the latter case, the last result is an
+    /// ExecutionInterrupted error, as in [`Self::execute_txs_sequentially`].
     pub fn execute_txs(
         &mut self,
         txs: &[Transaction],
@@ -201,9 +231,17 @@ impl<S: StateReader + Send + Sync> TransactionExecutor<S> {
             );
             txs.chunks(chunk_size)
                 .fold_while(Vec::new(), |mut results, chunk| {
+                    if self.block_context.execution_interrupt.is_interrupted() {
+                        results.push(Err(TransactionExecutorError::ExecutionInterrupted));
+                        return Done(results);
+                    }
                     let chunk_results = self.execute_chunk(chunk);
-                    if chunk_results.len() < chunk.len() {
-                        // Block is full.
+                    let interrupted = matches!(
+                        chunk_results.last(),
+                        Some(Err(TransactionExecutorError::ExecutionInterrupted))
+                    );
+                    if chunk_results.len() < chunk.len() || interrupted {
+                        // Block is full, or the execution was interrupted.
                         results.extend(chunk_results);
                         Done(results)
                     } else {
@@ -224,12 +262,14 @@ impl<S: StateReader + Send + Sync> TransactionExecutor<S> {
 
         let block_state = self.block_state.take().expect("The block state should be `Some`.");
 
-        let worker_executor = Arc::new(WorkerExecutor::initialize(
+        let mut worker_executor = WorkerExecutor::initialize(
             block_state,
             chunk,
             &self.block_context,
             Mutex::new(&mut self.bouncer),
-        ));
+        );
+        worker_executor.record_read_sets = self.config.record_read_sets;
+        let worker_executor = Arc::new(worker_executor);
 
         // No thread pool implementation is needed here since we already have our scheduler. The
         // initialized threads below will "busy wait" for new tasks using the `run` method until the
@@ -292,6 +332,12 @@ impl<S: StateReader + Send + Sync> TransactionExecutor<S> {
             .commit_chunk_and_recover_block_state(n_committed_txs, visited_pcs);
         self.block_state.replace(block_state_after_commit);
 
+        // The first transaction that wasn't committed may have been stopped by the interrupt.
+        if n_committed_txs < chunk.len() && self.block_context.execution_interrupt.is_interrupted()
+        {
+            tx_execution_results.push(Err(TransactionExecutorError::ExecutionInterrupted));
+        }
+
         tx_execution_results
     }
 }
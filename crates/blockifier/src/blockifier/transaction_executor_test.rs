@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use assert_matches::assert_matches;
 use pretty_assertions::assert_eq;
 use rstest::rstest;
@@ -5,7 +7,7 @@ use starknet_api::felt;
 use starknet_api::transaction::{Fee, TransactionVersion};
 use starknet_types_core::felt::Felt;
 
-use crate::blockifier::config::TransactionExecutorConfig;
+use crate::blockifier::config::{ConcurrencyConfig, TransactionExecutorConfig};
 use crate::blockifier::transaction_executor::{
     TransactionExecutor,
     TransactionExecutorError,
@@ -13,6 +15,7 @@ use crate::blockifier::transaction_executor::{
 };
 use crate::bouncer::{Bouncer, BouncerWeights};
 use crate::context::BlockContext;
+use crate::execution::interrupt::ExecutionInterrupt;
 use crate::state::cached_state::CachedState;
 use crate::state::state_api::StateReader;
 use crate::test_utils::contracts::FeatureContract;
@@ -353,3 +356,124 @@ fn test_execute_txs_bouncing() {
         nonce!(4_u32)
     );
 }
+
+#[rstest]
+fn test_interrupted_execution(
+    #[values(false, true)] deadline_passed: bool,
+    #[values(false, true)] concurrency_enabled: bool,
+) {
+    let execution_interrupt = if deadline_passed {
+        ExecutionInterrupt::with_deadline(Instant::now())
+    } else {
+        let execution_interrupt = ExecutionInterrupt::default();
+        execution_interrupt.interrupt();
+        execution_interrupt
+    };
+    let mut block_context = BlockContext::create_for_account_testing();
+    block_context.set_execution_interrupt(execution_interrupt);
+
+    let TestInitData { state, account_address, contract_address, .. } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo1);
+    let concurrency_config = if concurrency_enabled {
+        ConcurrencyConfig::create_for_testing()
+    } else {
+        ConcurrencyConfig::default()
+    };
+    let config = TransactionExecutorConfig { concurrency_config, ..Default::default() };
+    let mut tx_executor = TransactionExecutor::new(state, block_context, config);
+
+    let txs: Vec<Transaction> = (0..2_u32)
+        .map(|nonce| {
+            Transaction::AccountTransaction(emit_n_events_tx(
+                1,
+                account_address,
+                contract_address,
+                nonce!(nonce),
+            ))
+        })
+        .collect();
+    let results = tx_executor.execute_txs(&txs);
+
+    // The first transaction is rejected, and the execution stops.
+    assert_eq!(results.len(), 1);
+    assert_matches!(results[0], Err(TransactionExecutorError::ExecutionInterrupted));
+    assert_eq!(
+        tx_executor
+            .block_state
+            .as_ref()
+            .expect(BLOCK_STATE_ACCESS_ERR)
+            .get_nonce_at(account_address)
+            .unwrap(),
+        nonce!(0_u32)
+    );
+}
+
+// Triggers the interrupt before the chunk is executed, bypassing the check of `execute_txs`, so
+// the transactions are stopped at their first syscall and must not be committed.
+#[cfg(feature = "concurrency")]
+#[test]
+fn test_interrupted_chunk_execution() {
+    let mut block_context = BlockContext::create_for_account_testing();
+    let execution_interrupt = ExecutionInterrupt::default();
+    execution_interrupt.interrupt();
+    block_context.set_execution_interrupt(execution_interrupt);
+
+    let TestInitData { state, account_address, contract_address, .. } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo1);
+    let mut tx_executor = TransactionExecutor::new(
+        state,
+        block_context,
+        TransactionExecutorConfig::create_for_testing(),
+    );
+
+    let txs: Vec<Transaction> = (0..2_u32)
+        .map(|nonce| {
+            Transaction::AccountTransaction(emit_n_events_tx(
+                1,
+                account_address,
+                contract_address,
+                nonce!(nonce),
+            ))
+        })
+        .collect();
+    let results = tx_executor.execute_chunk(&txs);
+
+    assert_eq!(results.len(), 1);
+    assert_matches!(results[0], Err(TransactionExecutorError::ExecutionInterrupted));
+    assert_eq!(
+        tx_executor
+            .block_state
+            .as_ref()
+            .expect(BLOCK_STATE_ACCESS_ERR)
+            .get_nonce_at(account_address)
+            .unwrap(),
+        nonce!(0_u32)
+    );
+}
+
+#[rstest]
+fn test_record_read_sets(#[values(true, false)] record_read_sets: bool) {
+    let block_context = BlockContext::create_for_account_testing();
+    let TestInitData { state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo1);
+    let config = TransactionExecutorConfig { record_read_sets, ..Default::default() };
+    let mut tx_executor = TransactionExecutor::new(state, block_context, config);
+
+    let tx_execution_info = tx_executor
+        .execute(&Transaction::AccountTransaction(emit_n_events_tx(
+            1,
+            account_address,
+            contract_address,
+            nonce_manager.next(account_address),
+        )))
+        .unwrap();
+
+    match tx_execution_info.read_set {
+        Some(read_set) => {
+            assert!(record_read_sets);
+            assert!(read_set.nonces.contains(&account_address));
+            assert!(read_set.class_hashes.contains(&contract_address));
+        }
+        None => assert!(!record_read_sets),
+    }
+}
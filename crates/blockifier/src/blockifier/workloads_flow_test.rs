@@ -0,0 +1,16 @@
+use rstest::rstest;
+
+use crate::test_utils::workloads::{Workload, WorkloadGenerator, WorkloadGeneratorConfig};
+
+#[rstest]
+#[case::erc20_transfers(Workload::Erc20Transfers)]
+#[case::keccak_hashing(Workload::KeccakHashing)]
+#[case::deep_call_tree(Workload::DeepCallTree { depth: 10 })]
+#[case::large_calldata(Workload::LargeCalldata { calldata_length: 1000 })]
+fn workloads_flow_test(#[case] workload: Workload) {
+    let config = WorkloadGeneratorConfig { n_txs: 10, ..WorkloadGeneratorConfig::new(workload) };
+    let mut workload_generator = WorkloadGenerator::new(config);
+    // Executing a second batch checks that the state of the first one is kept, e.g. the nonces.
+    workload_generator.execute_batch();
+    workload_generator.execute_batch();
+}
@@ -1,7 +1,9 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use cairo_vm::types::builtin_name::BuiltinName;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use papyrus_config::dumping::{append_sub_config_name, ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use serde::{Deserialize, Serialize};
 use starknet_api::core::ClassHash;
 
@@ -19,6 +21,7 @@ use crate::transaction::objects::{
     TransactionExecutionResult,
     TransactionResources,
 };
+use crate::versioned_constants::VersionedConstants;
 
 #[cfg(test)]
 #[path = "bouncer_test.rs"]
@@ -40,14 +43,27 @@ macro_rules! impl_checked_sub {
 
 pub type HashMapWrapper = HashMap<BuiltinName, usize>;
 
+/// The fractions of the block capacity are in parts per million.
+pub const FULL_BLOCK_FRACTION: u128 = 1_000_000;
+
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct BouncerConfig {
     pub block_max_capacity: BouncerWeights,
+    /// The capacity of a block in builtin gas: the Cairo gas that its builtin instances weigh by
+    /// the builtin weights.
+    pub block_max_builtin_gas: u128,
+    /// The weights of the builtins, set by hand or by [`BouncerConfig::resolve_builtin_weights`].
+    /// Without them, the builtins weigh no gas.
+    pub builtin_weights: Option<BuiltinWeights>,
 }
 
 impl BouncerConfig {
     pub fn max() -> Self {
-        Self { block_max_capacity: BouncerWeights::max() }
+        Self {
+            block_max_capacity: BouncerWeights::max(),
+            block_max_builtin_gas: u128::MAX,
+            builtin_weights: None,
+        }
     }
 
     pub fn empty() -> Self {
@@ -56,6 +72,29 @@ impl BouncerConfig {
 
     pub fn has_room(&self, weights: BouncerWeights) -> bool {
         self.block_max_capacity.has_room(weights)
+            && self.builtin_gas(&weights.builtin_count) <= self.block_max_builtin_gas
+    }
+
+    /// Returns the Cairo gas that the builtin instances weigh.
+    pub fn builtin_gas(&self, builtin_count: &BuiltinCount) -> u128 {
+        self.builtin_weights.map_or(0, |builtin_weights| builtin_weights.gas(builtin_count))
+    }
+
+    /// Derives the builtin weights from the versioned constants of the executed blocks, unless
+    /// they're set by hand. Returns the builtins whose weights are set by hand to other weights
+    /// than the derived ones.
+    pub fn resolve_builtin_weights(
+        &mut self,
+        versioned_constants: &VersionedConstants,
+    ) -> Vec<BuiltinWeightDrift> {
+        let calibrated_weights = BuiltinWeights::from_versioned_constants(versioned_constants);
+        match self.builtin_weights {
+            Some(builtin_weights) => builtin_weights.drift_from(&calibrated_weights),
+            None => {
+                self.builtin_weights = Some(calibrated_weights);
+                vec![]
+            }
+        }
     }
 }
 
@@ -105,6 +144,83 @@ impl BouncerWeights {
             builtin_count: BuiltinCount::max(),
         }
     }
+
+    /// Returns the fraction of the block capacity that the weights use, by their most used
+    /// resource, in parts per million.
+    pub fn block_fraction(&self, block_max_capacity: &Self) -> u128 {
+        self.resources()
+            .into_iter()
+            .zip(block_max_capacity.resources())
+            .map(|(weight, capacity)| {
+                let weight = u128::try_from(weight).expect("usize should fit in u128.");
+                let capacity = u128::try_from(capacity).expect("usize should fit in u128.");
+                match capacity {
+                    0 if weight > 0 => FULL_BLOCK_FRACTION,
+                    0 => 0,
+                    _ => weight.saturating_mul(FULL_BLOCK_FRACTION) / capacity,
+                }
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn resources(&self) -> [usize; 15] {
+        let builtin_count = &self.builtin_count;
+        [
+            self.gas,
+            self.message_segment_length,
+            self.n_events,
+            self.n_steps,
+            self.state_diff_size,
+            builtin_count.add_mod,
+            builtin_count.bitwise,
+            builtin_count.ecdsa,
+            builtin_count.ec_op,
+            builtin_count.keccak,
+            builtin_count.mul_mod,
+            builtin_count.pedersen,
+            builtin_count.poseidon,
+            builtin_count.range_check,
+            builtin_count.range_check96,
+        ]
+    }
+}
+
+impl SerializeConfig for BouncerWeights {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        let members = BTreeMap::from_iter([
+            ser_param("gas", &self.gas, "The L1 gas.", ParamPrivacyInput::Public),
+            ser_param(
+                "message_segment_length",
+                &self.message_segment_length,
+                "The length of the segment of the messages to L1.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "n_events",
+                &self.n_events,
+                "The number of events.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "n_steps",
+                &self.n_steps,
+                "The number of Cairo steps.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "state_diff_size",
+                &self.state_diff_size,
+                "The size of the state diff.",
+                ParamPrivacyInput::Public,
+            ),
+        ]);
+
+        vec![members, append_sub_config_name(self.builtin_count.dump(), "builtin_count")]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
 }
 
 #[derive(
@@ -177,6 +293,33 @@ impl BuiltinCount {
     }
 }
 
+impl SerializeConfig for BuiltinCount {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        [
+            ("add_mod", self.add_mod),
+            ("bitwise", self.bitwise),
+            ("ecdsa", self.ecdsa),
+            ("ec_op", self.ec_op),
+            ("keccak", self.keccak),
+            ("mul_mod", self.mul_mod),
+            ("pedersen", self.pedersen),
+            ("poseidon", self.poseidon),
+            ("range_check", self.range_check),
+            ("range_check96", self.range_check96),
+        ]
+        .into_iter()
+        .map(|(name, count)| {
+            ser_param(
+                name,
+                &count,
+                &format!("The number of {name} builtin instances."),
+                ParamPrivacyInput::Public,
+            )
+        })
+        .collect()
+    }
+}
+
 impl From<HashMapWrapper> for BuiltinCount {
     fn from(mut data: HashMapWrapper) -> Self {
         // TODO(yael 24/3/24): replace the unwrap_or_default with expect, once the
@@ -203,6 +346,132 @@ impl From<HashMapWrapper> for BuiltinCount {
     }
 }
 
+/// The weight of an instance of each builtin, in Cairo gas.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BuiltinWeights {
+    pub add_mod: u64,
+    pub bitwise: u64,
+    pub ecdsa: u64,
+    pub ec_op: u64,
+    pub keccak: u64,
+    pub mul_mod: u64,
+    pub pedersen: u64,
+    pub poseidon: u64,
+    pub range_check: u64,
+    pub range_check96: u64,
+}
+
+impl BuiltinWeights {
+    /// Derives the weights from the gas costs of the versioned constants. A builtin that the OS
+    /// prices weighs its gas cost. The others (ecdsa, keccak and range_check96) are calibrated by
+    /// their fee cost relative to a Cairo step, times the gas cost of a step.
+    pub fn from_versioned_constants(versioned_constants: &VersionedConstants) -> Self {
+        let gas_costs = &versioned_constants.os_constants.gas_costs;
+        let fee_costs = versioned_constants.vm_resource_fee_cost();
+        let calibrated = |builtin: BuiltinName| {
+            let (Some(builtin_fee_cost), Some(step_fee_cost)) =
+                (fee_costs.get(builtin.to_str_with_suffix()), fee_costs.get("n_steps"))
+            else {
+                return 0;
+            };
+            if *step_fee_cost.numer() == 0 {
+                return 0;
+            }
+            let gas = (builtin_fee_cost / step_fee_cost * u128::from(gas_costs.step_gas_cost))
+                .ceil()
+                .to_integer();
+            u64::try_from(gas).unwrap_or(u64::MAX)
+        };
+        Self {
+            add_mod: gas_costs.add_mod_gas_cost,
+            bitwise: gas_costs.bitwise_builtin_gas_cost,
+            ecdsa: calibrated(BuiltinName::ecdsa),
+            ec_op: gas_costs.ecop_gas_cost,
+            keccak: calibrated(BuiltinName::keccak),
+            mul_mod: gas_costs.mul_mod_gas_cost,
+            pedersen: gas_costs.pedersen_gas_cost,
+            poseidon: gas_costs.poseidon_gas_cost,
+            range_check: gas_costs.range_check_gas_cost,
+            range_check96: calibrated(BuiltinName::range_check96),
+        }
+    }
+
+    /// Returns the weights with the names of their builtins.
+    pub fn named_weights(&self) -> [(&'static str, u64); 10] {
+        [
+            ("add_mod", self.add_mod),
+            ("bitwise", self.bitwise),
+            ("ecdsa", self.ecdsa),
+            ("ec_op", self.ec_op),
+            ("keccak", self.keccak),
+            ("mul_mod", self.mul_mod),
+            ("pedersen", self.pedersen),
+            ("poseidon", self.poseidon),
+            ("range_check", self.range_check),
+            ("range_check96", self.range_check96),
+        ]
+    }
+
+    /// Returns the total weight of the builtin instances, in Cairo gas.
+    pub fn gas(&self, builtin_count: &BuiltinCount) -> u128 {
+        let counts = [
+            builtin_count.add_mod,
+            builtin_count.bitwise,
+            builtin_count.ecdsa,
+            builtin_count.ec_op,
+            builtin_count.keccak,
+            builtin_count.mul_mod,
+            builtin_count.pedersen,
+            builtin_count.poseidon,
+            builtin_count.range_check,
+            builtin_count.range_check96,
+        ];
+        self.named_weights().into_iter().zip(counts).fold(0, |gas, ((_, weight), count)| {
+            let count = u128::try_from(count).expect("usize should fit in u128.");
+            gas.saturating_add(count.saturating_mul(u128::from(weight)))
+        })
+    }
+
+    /// Returns the builtins whose weights differ from the given calibration.
+    pub fn drift_from(&self, calibration: &Self) -> Vec<BuiltinWeightDrift> {
+        self.named_weights()
+            .into_iter()
+            .zip(calibration.named_weights())
+            .filter(|((_, weight), (_, calibrated_weight))| weight != calibrated_weight)
+            .map(|((builtin, weight), (_, calibrated_weight))| BuiltinWeightDrift {
+                builtin,
+                weight,
+                calibrated_weight,
+            })
+            .collect()
+    }
+}
+
+impl SerializeConfig for BuiltinWeights {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        self.named_weights()
+            .into_iter()
+            .map(|(name, weight)| {
+                ser_param(
+                    name,
+                    &weight,
+                    &format!("The weight of a {name} builtin instance, in Cairo gas."),
+                    ParamPrivacyInput::Public,
+                )
+            })
+            .collect()
+    }
+}
+
+/// A builtin whose weight differs from its calibration by the versioned constants.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BuiltinWeightDrift {
+    pub builtin: &'static str,
+    pub weight: u64,
+    /// The weight by [`BuiltinWeights::from_versioned_constants`].
+    pub calibrated_weight: u64,
+}
+
 #[derive(Debug, Default, PartialEq)]
 #[cfg_attr(test, derive(Clone))]
 pub struct Bouncer {
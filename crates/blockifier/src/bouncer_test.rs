@@ -10,13 +10,21 @@ use crate::blockifier::transaction_executor::{
     TransactionExecutorError,
     TransactionExecutorResult,
 };
-use crate::bouncer::{verify_tx_weights_in_bounds, Bouncer, BouncerWeights, BuiltinCount};
+use crate::bouncer::{
+    verify_tx_weights_in_bounds,
+    Bouncer,
+    BouncerWeights,
+    BuiltinCount,
+    BuiltinWeightDrift,
+    BuiltinWeights,
+};
 use crate::context::BlockContext;
 use crate::execution::call_info::ExecutionSummary;
 use crate::state::cached_state::{StateChangesKeys, TransactionalState};
 use crate::storage_key;
 use crate::test_utils::initial_test_state::test_state;
 use crate::transaction::errors::TransactionExecutionError;
+use crate::versioned_constants::VersionedConstants;
 
 #[test]
 fn test_block_weights_has_room() {
@@ -210,7 +218,7 @@ fn test_bouncer_try_update(
         n_events: 20,
         state_diff_size: 20,
     };
-    let bouncer_config = BouncerConfig { block_max_capacity };
+    let bouncer_config = BouncerConfig { block_max_capacity, ..BouncerConfig::max() };
 
     let accumulated_weights = BouncerWeights {
         builtin_count: BuiltinCount {
@@ -279,3 +287,66 @@ fn test_bouncer_try_update(
     // TODO(yael 27/3/24): compare the results without using string comparison.
     assert_eq!(format!("{:?}", result), format!("{:?}", expected_result));
 }
+
+#[test]
+fn test_builtin_weights_from_versioned_constants() {
+    let versioned_constants = VersionedConstants::latest_constants();
+    let gas_costs = &versioned_constants.os_constants.gas_costs;
+    let builtin_weights = BuiltinWeights::from_versioned_constants(versioned_constants);
+    assert_eq!(builtin_weights.pedersen, gas_costs.pedersen_gas_cost);
+    assert_eq!(builtin_weights.range_check, gas_costs.range_check_gas_cost);
+    // The fee cost of an ecdsa builtin instance is that of 2048 Cairo steps.
+    assert_eq!(builtin_weights.ecdsa, 2048 * gas_costs.step_gas_cost);
+
+    let builtin_count = BuiltinCount { pedersen: 2, range_check: 3, ..Default::default() };
+    assert_eq!(
+        builtin_weights.gas(&builtin_count),
+        u128::from(2 * gas_costs.pedersen_gas_cost + 3 * gas_costs.range_check_gas_cost)
+    );
+
+    assert_eq!(builtin_weights.drift_from(&builtin_weights), vec![]);
+    let configured_weights = BuiltinWeights { keccak: 1, ..builtin_weights };
+    assert_eq!(
+        configured_weights.drift_from(&builtin_weights),
+        vec![BuiltinWeightDrift {
+            builtin: "keccak",
+            weight: 1,
+            calibrated_weight: builtin_weights.keccak
+        }]
+    );
+}
+
+#[test]
+fn test_bouncer_config_builtin_gas() {
+    let versioned_constants = VersionedConstants::latest_constants();
+    let builtin_weights = BuiltinWeights::from_versioned_constants(versioned_constants);
+    let weights = BouncerWeights {
+        builtin_count: BuiltinCount { pedersen: 2, ..Default::default() },
+        ..Default::default()
+    };
+    let builtin_gas = builtin_weights.gas(&weights.builtin_count);
+
+    // Without weights, the builtins weigh no gas.
+    let mut bouncer_config = BouncerConfig { block_max_builtin_gas: 0, ..BouncerConfig::max() };
+    assert!(bouncer_config.has_room(weights));
+
+    assert_eq!(bouncer_config.resolve_builtin_weights(versioned_constants), vec![]);
+    assert_eq!(bouncer_config.builtin_weights, Some(builtin_weights));
+    assert!(!bouncer_config.has_room(weights));
+    bouncer_config.block_max_builtin_gas = builtin_gas;
+    assert!(bouncer_config.has_room(weights));
+
+    // Weights set by hand are kept, and their drift from the derived ones is reported.
+    let configured_weights = BuiltinWeights { pedersen: 1, ..builtin_weights };
+    let mut bouncer_config =
+        BouncerConfig { builtin_weights: Some(configured_weights), ..BouncerConfig::max() };
+    assert_eq!(
+        bouncer_config.resolve_builtin_weights(versioned_constants),
+        vec![BuiltinWeightDrift {
+            builtin: "pedersen",
+            weight: 1,
+            calibrated_weight: builtin_weights.pedersen
+        }]
+    );
+    assert_eq!(bouncer_config.builtin_weights, Some(configured_weights));
+}
@@ -27,15 +27,15 @@ pub fn complete_fee_transfer_flow(
     tx_execution_info: &mut TransactionExecutionInfo,
     state: &mut impl UpdatableState,
 ) {
-    if tx_context.is_sequencer_the_sender() {
-        // When the sequencer is the sender, we use the sequential (full) fee transfer.
+    if tx_context.is_fee_recipient_the_sender() {
+        // When the fee recipient is the sender, we use the sequential (full) fee transfer.
         return;
     }
 
     if let Some(fee_transfer_call_info) = tx_execution_info.fee_transfer_call_info.as_mut() {
         let sequencer_balance = state
         .get_fee_token_balance(
-            tx_context.block_context.block_info.sequencer_address,
+            tx_context.block_context.fee_recipient(),
             tx_context.fee_token_address()
         )
         // TODO(barak, 01/07/2024): Consider propagating the error.
@@ -47,11 +47,13 @@ pub fn complete_fee_transfer_flow(
 
         // Fix the transfer call info.
         fill_sequencer_balance_reads(fee_transfer_call_info, sequencer_balance);
-        // Update the balance.
+        // Update the balance. The fee split, if any, was already transferred.
+        let (recipient_fee, _) =
+            tx_context.block_context.chain_info.split_fee(tx_execution_info.receipt.fee);
         add_fee_to_sequencer_balance(
             tx_context.fee_token_address(),
             state,
-            tx_execution_info.receipt.fee,
+            recipient_fee,
             &tx_context.block_context,
             sequencer_balance,
         );
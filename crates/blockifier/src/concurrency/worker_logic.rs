@@ -15,6 +15,7 @@ use crate::concurrency::utils::lock_mutex_in_array;
 use crate::concurrency::versioned_state::ThreadSafeVersionedState;
 use crate::concurrency::TxIndex;
 use crate::context::BlockContext;
+use crate::execution::memory_pool;
 use crate::state::cached_state::{
     ContractClassMapping,
     StateChanges,
@@ -48,6 +49,8 @@ pub struct WorkerExecutor<'a, S: StateReader> {
     pub execution_outputs: Box<[Mutex<Option<ExecutionTaskOutput>>]>,
     pub block_context: &'a BlockContext,
     pub bouncer: Mutex<&'a mut Bouncer>,
+    /// Whether to record the keys each transaction reads, in its execution info.
+    pub record_read_sets: bool,
 }
 impl<'a, S: StateReader> WorkerExecutor<'a, S> {
     pub fn new(
@@ -60,7 +63,15 @@ impl<'a, S: StateReader> WorkerExecutor<'a, S> {
         let execution_outputs =
             std::iter::repeat_with(|| Mutex::new(None)).take(chunk.len()).collect();
 
-        WorkerExecutor { scheduler, state, chunk, execution_outputs, block_context, bouncer }
+        WorkerExecutor {
+            scheduler,
+            state,
+            chunk,
+            execution_outputs,
+            block_context,
+            bouncer,
+            record_read_sets: false,
+        }
     }
 
     // TODO(barak, 01/08/2024): Remove the `new` method or move it to test utils.
@@ -83,6 +94,7 @@ impl<'a, S: StateReader> WorkerExecutor<'a, S> {
             execution_outputs,
             block_context,
             bouncer,
+            record_read_sets: false,
         }
     }
 
@@ -129,10 +141,15 @@ impl<'a, S: StateReader> WorkerExecutor<'a, S> {
         let tx = &self.chunk[tx_index];
         let mut transactional_state =
             TransactionalState::create_transactional(&mut tx_versioned_state);
-        let execution_flags =
-            ExecutionFlags { charge_fee: true, validate: true, concurrency_mode: true };
+        let execution_flags = ExecutionFlags {
+            charge_fee: true,
+            validate: true,
+            concurrency_mode: true,
+            record_read_set: self.record_read_sets,
+        };
         let execution_result =
             tx.execute_raw(&mut transactional_state, self.block_context, execution_flags);
+        memory_pool::reset();
 
         if execution_result.is_ok() {
             // TODO(Noa, 15/05/2024): use `tx_versioned_state` when we add support to transactional
@@ -191,6 +208,8 @@ impl<'a, S: StateReader> WorkerExecutor<'a, S> {
     ///         - Else (no room), do not commit. The block should be closed without the transaction.
     ///     * Else (execution failed), commit the transaction without fixing the call info or
     ///       updating the sequencer balance.
+    ///     * In both cases, if the transaction failed or reverted after the execution interrupt of
+    ///       the block was triggered, do not commit: it may have been stopped by the interrupt.
     fn commit_tx(&self, tx_index: TxIndex) -> bool {
         let execution_output = lock_mutex_in_array(&self.execution_outputs, tx_index);
         let execution_output_ref = execution_output.as_ref().expect(EXECUTION_OUTPUTS_UNWRAP_ERROR);
@@ -229,6 +248,14 @@ impl<'a, S: StateReader> WorkerExecutor<'a, S> {
         let tx_result =
             &mut execution_output.as_mut().expect(EXECUTION_OUTPUTS_UNWRAP_ERROR).result;
 
+        let failed = match tx_result {
+            Ok(tx_execution_info) => tx_execution_info.is_reverted(),
+            Err(_) => true,
+        };
+        if failed && self.block_context.execution_interrupt.is_interrupted() {
+            return false;
+        }
+
         if let Ok(tx_execution_info) = tx_result.as_mut() {
             let tx_context = self
                 .block_context
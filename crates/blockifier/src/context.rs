@@ -1,22 +1,40 @@
 use std::collections::BTreeMap;
 
-use papyrus_config::dumping::{append_sub_config_name, ser_param, SerializeConfig};
+use papyrus_config::dumping::{
+    append_sub_config_name,
+    ser_optional_param,
+    ser_optional_sub_config,
+    ser_param,
+    SerializeConfig,
+};
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockNumber;
 use starknet_api::core::{ChainId, ContractAddress};
+use starknet_api::transaction::Fee;
 
 use crate::blockifier::block::BlockInfo;
 use crate::bouncer::BouncerConfig;
-use crate::transaction::errors::TransactionInfoCreationError;
+use crate::execution::interrupt::ExecutionInterrupt;
+use crate::fee::fee_multipliers::FeeMultipliers;
+use crate::transaction::errors::{TransactionFeeError, TransactionInfoCreationError};
+use crate::transaction::nonce_manager::{NonceConfig, NonceManager};
 use crate::transaction::objects::{
     FeeType,
     HasRelatedFeeType,
+    TransactionFeeResult,
     TransactionInfo,
     TransactionInfoCreator,
 };
+use crate::transaction::version_config::TransactionVersionConfig;
 use crate::versioned_constants::VersionedConstants;
 
-/// Create via [`crate::blockifier::block::pre_process_block`] to ensure correctness.
+#[cfg(test)]
+#[path = "context_test.rs"]
+mod test;
+
+/// Create via [`crate::blockifier::block::pre_process_block_and_create_context`] to ensure
+/// correctness.
 #[derive(Clone, Debug)]
 pub struct TransactionContext {
     pub block_context: BlockContext,
@@ -27,8 +45,8 @@ impl TransactionContext {
     pub fn fee_token_address(&self) -> ContractAddress {
         self.block_context.chain_info.fee_token_address(&self.tx_info.fee_type())
     }
-    pub fn is_sequencer_the_sender(&self) -> bool {
-        self.tx_info.sender_address() == self.block_context.block_info.sequencer_address
+    pub fn is_fee_recipient_the_sender(&self) -> bool {
+        self.tx_info.sender_address() == self.block_context.fee_recipient()
     }
 }
 
@@ -39,6 +57,7 @@ pub struct BlockContext {
     pub(crate) chain_info: ChainInfo,
     pub(crate) versioned_constants: VersionedConstants,
     pub(crate) bouncer_config: BouncerConfig,
+    pub(crate) execution_interrupt: ExecutionInterrupt,
 }
 
 impl BlockContext {
@@ -48,7 +67,13 @@ impl BlockContext {
         versioned_constants: VersionedConstants,
         bouncer_config: BouncerConfig,
     ) -> Self {
-        BlockContext { block_info, chain_info, versioned_constants, bouncer_config }
+        BlockContext {
+            block_info,
+            chain_info,
+            versioned_constants,
+            bouncer_config,
+            execution_interrupt: ExecutionInterrupt::default(),
+        }
     }
 
     pub fn block_info(&self) -> &BlockInfo {
@@ -63,6 +88,22 @@ impl BlockContext {
         &self.versioned_constants
     }
 
+    pub fn execution_interrupt(&self) -> &ExecutionInterrupt {
+        &self.execution_interrupt
+    }
+
+    /// Sets the interrupt that stops the execution of the transactions of the block, e.g. at the
+    /// deadline of the proposal they're executed for.
+    pub fn set_execution_interrupt(&mut self, execution_interrupt: ExecutionInterrupt) {
+        self.execution_interrupt = execution_interrupt;
+    }
+
+    /// The address that receives the transaction fees: the sequencer, unless the chain overrides
+    /// it.
+    pub fn fee_recipient(&self) -> ContractAddress {
+        self.chain_info.fee_recipient.unwrap_or(self.block_info.sequencer_address)
+    }
+
     // TODO(Nimrod): Don't return `Result`.
     pub fn to_tx_context(
         &self,
@@ -79,6 +120,21 @@ impl BlockContext {
 pub struct ChainInfo {
     pub chain_id: ChainId,
     pub fee_token_addresses: FeeTokenAddresses,
+    /// The first block in which transactions that pay their fee in ETH, the deprecated fee token,
+    /// are rejected.
+    #[serde(default)]
+    pub eth_fee_token_deprecation_height: Option<BlockNumber>,
+    /// Overrides the sequencer as the recipient of the transaction fees.
+    #[serde(default)]
+    pub fee_recipient: Option<ContractAddress>,
+    #[serde(default)]
+    pub fee_split: Option<FeeSplit>,
+    #[serde(default)]
+    pub fee_multipliers: FeeMultipliers,
+    #[serde(default)]
+    pub nonce_config: NonceConfig,
+    #[serde(default)]
+    pub tx_version_config: TransactionVersionConfig,
 }
 
 impl ChainInfo {
@@ -88,6 +144,39 @@ impl ChainInfo {
     pub fn fee_token_address(&self, fee_type: &FeeType) -> ContractAddress {
         self.fee_token_addresses.get_by_fee_type(fee_type)
     }
+
+    /// Returns the address of the fee token of the given type, for a transaction in the given
+    /// block. Fails for the ETH fee token from its deprecation height on.
+    pub fn fee_token_address_in_block(
+        &self,
+        fee_type: &FeeType,
+        block_number: BlockNumber,
+    ) -> TransactionFeeResult<ContractAddress> {
+        if let (FeeType::Eth, Some(deprecation_height)) =
+            (fee_type, self.eth_fee_token_deprecation_height)
+        {
+            if block_number >= deprecation_height {
+                return Err(TransactionFeeError::DeprecatedFeeToken {
+                    block_number,
+                    deprecation_height,
+                });
+            }
+        }
+        Ok(self.fee_token_address(fee_type))
+    }
+
+    /// Splits a transaction fee into the amount of the fee recipient and the amount of the fee
+    /// split recipient, if any.
+    pub fn split_fee(&self, fee: Fee) -> (Fee, Fee) {
+        match &self.fee_split {
+            Some(fee_split) => fee_split.split(fee),
+            None => (fee, Fee(0)),
+        }
+    }
+
+    pub fn nonce_manager(&self) -> NonceManager<'_> {
+        NonceManager::new(&self.nonce_config)
+    }
 }
 
 impl Default for ChainInfo {
@@ -95,6 +184,12 @@ impl Default for ChainInfo {
         ChainInfo {
             chain_id: ChainId::Other("0x0".to_string()),
             fee_token_addresses: FeeTokenAddresses::default(),
+            eth_fee_token_deprecation_height: None,
+            fee_recipient: None,
+            fee_split: None,
+            fee_multipliers: FeeMultipliers::default(),
+            nonce_config: NonceConfig::default(),
+            tx_version_config: TransactionVersionConfig::default(),
         }
     }
 }
@@ -111,6 +206,25 @@ impl SerializeConfig for ChainInfo {
         vec![
             members,
             append_sub_config_name(self.fee_token_addresses.dump(), "fee_token_addresses"),
+            ser_optional_param(
+                &self.eth_fee_token_deprecation_height,
+                BlockNumber::default(),
+                "eth_fee_token_deprecation_height",
+                "The first block in which transactions that pay their fee in ETH are rejected. If \
+                 not set, they're accepted.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_optional_param(
+                &self.fee_recipient,
+                ContractAddress::default(),
+                "fee_recipient",
+                "The recipient of the transaction fees. If not set, the fees go to the sequencer.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_optional_sub_config(&self.fee_split, "fee_split"),
+            append_sub_config_name(self.fee_multipliers.dump(), "fee_multipliers"),
+            append_sub_config_name(self.nonce_config.dump(), "nonce_config"),
+            append_sub_config_name(self.tx_version_config.dump(), "tx_version_config"),
         ]
         .into_iter()
         .flatten()
@@ -151,3 +265,42 @@ impl SerializeConfig for FeeTokenAddresses {
         ])
     }
 }
+
+/// A share of every transaction fee that goes to a recipient other than the fee recipient, e.g. a
+/// treasury contract.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct FeeSplit {
+    pub recipient: ContractAddress,
+    /// The percentage of the fee that goes to the recipient, at most 100.
+    pub percentage: u8,
+}
+
+impl FeeSplit {
+    /// Returns the amount of the fee recipient and the amount of the split recipient. The split is
+    /// rounded down. The percentage must be at most 100.
+    pub fn split(&self, fee: Fee) -> (Fee, Fee) {
+        let percentage = u128::from(self.percentage);
+        // Computed in parts to avoid overflowing.
+        let share = fee.0 / 100 * percentage + fee.0 % 100 * percentage / 100;
+        (Fee(fee.0 - share), Fee(share))
+    }
+}
+
+impl SerializeConfig for FeeSplit {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "recipient",
+                &self.recipient,
+                "The recipient of the fee split, e.g. a treasury contract.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "percentage",
+                &self.percentage,
+                "The percentage of every transaction fee that goes to the recipient.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
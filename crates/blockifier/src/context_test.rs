@@ -0,0 +1,53 @@
+use assert_matches::assert_matches;
+use starknet_api::block::BlockNumber;
+use starknet_api::transaction::Fee;
+
+use crate::context::{ChainInfo, FeeSplit};
+use crate::transaction::errors::TransactionFeeError;
+use crate::transaction::objects::FeeType;
+
+#[test]
+fn fee_split() {
+    let fee_split = FeeSplit { percentage: 30, ..Default::default() };
+    assert_eq!(fee_split.split(Fee(1000)), (Fee(700), Fee(300)));
+    // The split is rounded down.
+    assert_eq!(fee_split.split(Fee(15)), (Fee(11), Fee(4)));
+    // The largest fee doesn't overflow.
+    assert_eq!(
+        fee_split.split(Fee(u128::MAX)).1,
+        Fee(102_084_710_076_281_539_039_012_382_229_530_463_436)
+    );
+
+    let fee_split = FeeSplit { percentage: 100, ..Default::default() };
+    assert_eq!(fee_split.split(Fee(1000)), (Fee(0), Fee(1000)));
+}
+
+#[test]
+fn no_fee_split() {
+    assert_eq!(ChainInfo::default().split_fee(Fee(1000)), (Fee(1000), Fee(0)));
+}
+
+#[test]
+fn eth_fee_token_deprecation() {
+    let deprecation_height = BlockNumber(10);
+    let chain_info = ChainInfo {
+        eth_fee_token_deprecation_height: Some(deprecation_height),
+        ..ChainInfo::create_for_testing()
+    };
+    let eth_fee_token_address = chain_info.fee_token_address(&FeeType::Eth);
+
+    assert_eq!(
+        chain_info.fee_token_address_in_block(&FeeType::Eth, BlockNumber(9)).unwrap(),
+        eth_fee_token_address
+    );
+    assert_matches!(
+        chain_info.fee_token_address_in_block(&FeeType::Eth, deprecation_height),
+        Err(TransactionFeeError::DeprecatedFeeToken { block_number, .. })
+        if block_number == deprecation_height
+    );
+    // The STRK fee token isn't deprecated.
+    assert_eq!(
+        chain_info.fee_token_address_in_block(&FeeType::Strk, deprecation_height).unwrap(),
+        chain_info.fee_token_address(&FeeType::Strk)
+    );
+}
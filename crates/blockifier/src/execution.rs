@@ -2,6 +2,7 @@ pub mod call_info;
 pub mod common_hints;
 pub mod contract_address;
 pub mod contract_class;
+pub mod deprecated_class_usage;
 pub mod deprecated_entry_point_execution;
 pub mod deprecated_syscalls;
 pub mod entry_point;
@@ -9,5 +10,8 @@ pub mod entry_point_execution;
 pub mod errors;
 pub mod execution_utils;
 pub mod hint_code;
+pub mod interrupt;
+pub mod memory_pool;
+pub mod panic_reason;
 pub mod stack_trace;
 pub mod syscalls;
@@ -239,6 +239,35 @@ impl ContractClassV1 {
         get_visited_segments(&self.bytecode_segment_lengths, &mut reversed_visited_pcs, &mut 0)
     }
 
+    /// Checks that the structure of the class is consistent with its bytecode: the bytecode
+    /// segments cover the whole bytecode, with a segmentation depth of at most 1, and all the entry
+    /// points are within the bytecode.
+    pub fn validate(&self) -> ContractClassResult<()> {
+        let bytecode_length = self.bytecode_length();
+        let segments_length = total_segment_length(&self.bytecode_segment_lengths, 0)?;
+        if segments_length != bytecode_length {
+            return Err(ContractClassError::InvalidCasmClass(format!(
+                "The bytecode segments cover {segments_length} felts, but the bytecode length is \
+                 {bytecode_length}."
+            )));
+        }
+
+        for (entry_point_type, entry_points) in &self.entry_points_by_type {
+            for entry_point in entry_points {
+                if entry_point.pc() >= bytecode_length {
+                    return Err(ContractClassError::InvalidCasmClass(format!(
+                        "Entry point {:#064x} of type {entry_point_type:?} has offset {}, which \
+                         is out of the bytecode of length {bytecode_length}.",
+                        entry_point.selector.0,
+                        entry_point.pc()
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn try_from_json_string(raw_contract_class: &str) -> Result<ContractClassV1, ProgramError> {
         let casm_contract_class: CasmContractClass = serde_json::from_str(raw_contract_class)?;
         let contract_class: ContractClassV1 = casm_contract_class.try_into()?;
@@ -302,6 +331,30 @@ pub fn estimate_casm_hash_computation_resources(
     }
 }
 
+// Returns the sum of the given segment lengths, which may be nested up to depth 1.
+fn total_segment_length(
+    segment_lengths: &NestedIntList,
+    depth: usize,
+) -> ContractClassResult<usize> {
+    match segment_lengths {
+        NestedIntList::Leaf(length) => Ok(*length),
+        NestedIntList::Node(_) if depth > 0 => Err(ContractClassError::InvalidCasmClass(
+            "The bytecode segmentation depth must be at most 1.".to_string(),
+        )),
+        NestedIntList::Node(segments) => {
+            segments.iter().try_fold(0_usize, |total_length, segment| {
+                total_length.checked_add(total_segment_length(segment, depth + 1)?).ok_or_else(
+                    || {
+                        ContractClassError::InvalidCasmClass(
+                            "The bytecode segment lengths overflow.".to_string(),
+                        )
+                    },
+                )
+            })
+        }
+    }
+}
+
 // Returns the set of segments that were visited according to the given visited PCs and segment
 // lengths.
 // Each visited segment must have its starting PC visited, and is represented by it.
@@ -541,6 +594,10 @@ impl ClassInfo {
             ContractClass::V1(_) => (1, sierra_program_length > 0),
         };
 
+        if let ContractClass::V1(contract_class) = contract_class {
+            contract_class.validate()?;
+        }
+
         if condition {
             Ok(Self { contract_class: contract_class.clone(), sierra_program_length, abi_length })
         } else {
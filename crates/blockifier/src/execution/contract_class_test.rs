@@ -4,8 +4,17 @@ use std::sync::Arc;
 use assert_matches::assert_matches;
 use cairo_lang_starknet_classes::NestedIntList;
 use rstest::rstest;
+use starknet_api::deprecated_contract_class::EntryPointType;
 
-use crate::execution::contract_class::{ContractClassV1, ContractClassV1Inner};
+use crate::execution::contract_class::{
+    ClassInfo,
+    ContractClass,
+    ContractClassV1,
+    ContractClassV1Inner,
+};
+use crate::execution::errors::ContractClassError;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::CairoVersion;
 use crate::transaction::errors::TransactionExecutionError;
 
 #[rstest]
@@ -42,3 +51,47 @@ fn test_get_visited_segments() {
         TransactionExecutionError::InvalidSegmentStructure(907, 807)
     );
 }
+
+#[rstest]
+fn test_validate() {
+    let ContractClass::V1(contract_class) =
+        FeatureContract::TestContract(CairoVersion::Cairo1).get_class()
+    else {
+        panic!("Expected a Cairo 1 contract class.");
+    };
+    contract_class.validate().unwrap();
+    let bytecode_length = contract_class.bytecode_length();
+
+    let with_inner = |update_inner: &dyn Fn(&mut ContractClassV1Inner)| {
+        let mut inner = (*contract_class.0).clone();
+        update_inner(&mut inner);
+        ContractClassV1(Arc::new(inner))
+    };
+    let invalid_classes = [
+        // The segments don't cover the whole bytecode.
+        with_inner(&|inner| {
+            inner.bytecode_segment_lengths = NestedIntList::Leaf(bytecode_length - 1)
+        }),
+        // The segmentation is too deep.
+        with_inner(&|inner| {
+            inner.bytecode_segment_lengths =
+                NestedIntList::Node(vec![NestedIntList::Node(vec![NestedIntList::Leaf(
+                    bytecode_length,
+                )])])
+        }),
+        // An entry point is out of the bytecode.
+        with_inner(&|inner| {
+            let entry_points =
+                inner.entry_points_by_type.get_mut(&EntryPointType::External).unwrap();
+            entry_points[0].offset.0 = bytecode_length;
+        }),
+    ];
+    for invalid_class in invalid_classes {
+        assert_matches!(invalid_class.validate(), Err(ContractClassError::InvalidCasmClass(_)));
+        // The classes are validated once, when declared, rather than on every call.
+        assert_matches!(
+            ClassInfo::new(&ContractClass::V1(invalid_class), 1, 0),
+            Err(ContractClassError::InvalidCasmClass(_))
+        );
+    }
+}
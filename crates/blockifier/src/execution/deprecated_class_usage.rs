@@ -0,0 +1,54 @@
+//! The execution of deprecated (Cairo 0) classes.
+//!
+//! From the Starknet version whose constants set `disallow_deprecated_class_execution`, calls to
+//! contracts of deprecated classes, and library calls to deprecated classes, fail. Until then, the
+//! calls to deprecated classes are counted per called contract and class, so operators find the
+//! contracts that still depend on deprecated classes and plan their migration. The counts are
+//! process-wide, and each contract and class pair appears once in the report however many times
+//! it's called.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+use starknet_api::core::{ClassHash, ContractAddress};
+
+use crate::execution::errors::PreExecutionError;
+use crate::versioned_constants::VersionedConstants;
+
+#[cfg(test)]
+#[path = "deprecated_class_usage_test.rs"]
+mod deprecated_class_usage_test;
+
+static DEPRECATED_CLASS_USAGE: LazyLock<Mutex<HashMap<DeprecatedClassUsage, u64>>> =
+    LazyLock::new(Default::default);
+
+/// A contract called with a deprecated class, either its own or through a library call.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DeprecatedClassUsage {
+    pub class_hash: ClassHash,
+    pub storage_address: ContractAddress,
+}
+
+/// Checks a call to a contract with a deprecated class. Fails if the versioned constants disallow
+/// the execution of deprecated classes, and counts the call otherwise.
+pub fn check_deprecated_class_execution(
+    versioned_constants: &VersionedConstants,
+    class_hash: ClassHash,
+    storage_address: ContractAddress,
+) -> Result<(), PreExecutionError> {
+    if versioned_constants.disallow_deprecated_class_execution {
+        return Err(PreExecutionError::DeprecatedClassDisallowed { class_hash });
+    }
+    let mut usage = DEPRECATED_CLASS_USAGE.lock().expect("Deprecated class usage lock poisoned.");
+    *usage.entry(DeprecatedClassUsage { class_hash, storage_address }).or_default() += 1;
+    Ok(())
+}
+
+/// Returns the number of calls to each contract with a deprecated class since the process
+/// started, ordered by class and contract.
+pub fn deprecated_class_usage_report() -> Vec<(DeprecatedClassUsage, u64)> {
+    let usage = DEPRECATED_CLASS_USAGE.lock().expect("Deprecated class usage lock poisoned.");
+    let mut report: Vec<_> = usage.iter().map(|(usage, count)| (*usage, *count)).collect();
+    report.sort_unstable();
+    report
+}
@@ -0,0 +1,61 @@
+use assert_matches::assert_matches;
+use starknet_api::core::{ClassHash, ContractAddress, PatriciaKey};
+use starknet_api::{class_hash, contract_address, felt, patricia_key};
+
+use crate::execution::deprecated_class_usage::{
+    check_deprecated_class_execution,
+    deprecated_class_usage_report,
+    DeprecatedClassUsage,
+};
+use crate::execution::errors::PreExecutionError;
+use crate::versioned_constants::VersionedConstants;
+
+#[test]
+fn deprecated_classes_are_executed_by_default() {
+    let versioned_constants = VersionedConstants::latest_constants();
+    assert_matches!(
+        check_deprecated_class_execution(
+            versioned_constants,
+            ClassHash::default(),
+            ContractAddress::default()
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn deprecated_classes_are_disallowed_by_versioned_constants() {
+    let versioned_constants = VersionedConstants {
+        disallow_deprecated_class_execution: true,
+        ..VersionedConstants::latest_constants().clone()
+    };
+    let (class_hash, storage_address) = (class_hash!("0xdead"), contract_address!("0x1"));
+    assert_matches!(
+        check_deprecated_class_execution(&versioned_constants, class_hash, storage_address),
+        Err(PreExecutionError::DeprecatedClassDisallowed { class_hash: disallowed_class_hash })
+        if disallowed_class_hash == class_hash
+    );
+}
+
+#[test]
+fn deprecated_class_usage_is_reported_once_per_contract_and_class() {
+    // The report is process-wide, so the class is unique to this test.
+    let class_hash = class_hash!("0xc1a55");
+    let (first_contract, second_contract) = (contract_address!("0x1"), contract_address!("0x2"));
+    let versioned_constants = VersionedConstants::latest_constants();
+    for storage_address in [first_contract, first_contract, second_contract] {
+        check_deprecated_class_execution(versioned_constants, class_hash, storage_address).unwrap();
+    }
+
+    let report: Vec<_> = deprecated_class_usage_report()
+        .into_iter()
+        .filter(|(usage, _)| usage.class_hash == class_hash)
+        .collect();
+    assert_eq!(
+        report,
+        vec![
+            (DeprecatedClassUsage { class_hash, storage_address: first_contract }, 2),
+            (DeprecatedClassUsage { class_hash, storage_address: second_contract }, 1),
+        ]
+    );
+}
@@ -21,7 +21,13 @@ use crate::execution::entry_point::{
     EntryPointExecutionResult,
 };
 use crate::execution::errors::{PostExecutionError, PreExecutionError};
-use crate::execution::execution_utils::{read_execution_retdata, Args, ReadOnlySegments};
+use crate::execution::execution_utils::{
+    n_used_memory_cells,
+    read_execution_retdata,
+    Args,
+    ReadOnlySegments,
+};
+use crate::execution::memory_pool;
 use crate::state::state_api::State;
 
 pub struct VmExecutionContext<'a> {
@@ -194,13 +200,14 @@ pub fn prepare_call_arguments(
 
     // Prepare calldata arguments.
     let calldata = &call.calldata.0;
-    let calldata: Vec<MaybeRelocatable> =
-        calldata.iter().map(|&arg| MaybeRelocatable::from(arg)).collect();
     let calldata_length = MaybeRelocatable::from(calldata.len());
     args.push(CairoArg::from(calldata_length));
 
     let calldata_start_ptr =
-        MaybeRelocatable::from(read_only_segments.allocate(&mut runner.vm, &calldata)?);
+        MaybeRelocatable::from(memory_pool::with_args_buffer(|args_buffer| {
+            args_buffer.extend(calldata.iter().map(|&arg| MaybeRelocatable::from(arg)));
+            read_only_segments.allocate(&mut runner.vm, args_buffer)
+        })?);
     args.push(CairoArg::from(calldata_start_ptr));
 
     Ok((implicit_args, args))
@@ -216,15 +223,15 @@ pub fn run_entry_point(
     let verify_secure = true;
     let program_segment_size = None; // Infer size from program.
     let args: Vec<&CairoArg> = args.iter().collect();
-    let result = runner.run_from_entrypoint(
+    runner.run_from_entrypoint(
         entry_point_pc,
         &args,
         verify_secure,
         program_segment_size,
         hint_processor,
-    );
+    )?;
 
-    Ok(result?)
+    hint_processor.context.consume_memory_cells(n_used_memory_cells(runner))
 }
 
 pub fn finalize_execution(
@@ -96,6 +96,8 @@ pub enum DeprecatedSyscallExecutionError {
     FromBigUint(#[from] TryFromBigIntError<BigUint>),
     #[error(transparent)]
     FromStr(#[from] FromStrError),
+    #[error("The execution was interrupted.")]
+    ExecutionInterrupted,
     #[error("{error}")]
     LibraryCallExecutionError {
         class_hash: ClassHash,
@@ -241,6 +243,15 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
         Ok(())
     }
 
+    /// Returns an error if the owner of the execution interrupted it.
+    pub fn verify_not_interrupted(&self) -> DeprecatedSyscallResult<()> {
+        if self.context.tx_context.block_context.execution_interrupt.is_interrupted() {
+            return Err(DeprecatedSyscallExecutionError::ExecutionInterrupted);
+        }
+
+        Ok(())
+    }
+
     pub fn verify_syscall_ptr(&self, actual_ptr: Relocatable) -> DeprecatedSyscallResult<()> {
         if actual_ptr != self.syscall_ptr {
             return Err(DeprecatedSyscallExecutionError::BadSyscallPointer {
@@ -262,6 +273,7 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
     ) -> HintExecutionResult {
         let initial_syscall_ptr = get_ptr_from_var_name("syscall_ptr", vm, ids_data, ap_tracking)?;
         self.verify_syscall_ptr(initial_syscall_ptr)?;
+        self.verify_not_interrupted()?;
 
         let selector = DeprecatedSyscallSelector::try_from(self.read_next_syscall_selector(vm)?)?;
         self.increment_syscall_count(&selector);
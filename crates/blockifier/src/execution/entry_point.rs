@@ -15,6 +15,8 @@ use crate::abi::constants;
 use crate::context::{BlockContext, TransactionContext};
 use crate::execution::call_info::CallInfo;
 use crate::execution::common_hints::ExecutionMode;
+use crate::execution::contract_class::ContractClass;
+use crate::execution::deprecated_class_usage::check_deprecated_class_execution;
 use crate::execution::errors::{
     ConstructorEntryPointExecutionError,
     EntryPointExecutionError,
@@ -101,6 +103,13 @@ impl CallEntryPoint {
         // Add class hash to the call, that will appear in the output (call info).
         self.class_hash = Some(class_hash);
         let contract_class = state.get_compiled_contract_class(class_hash)?;
+        if matches!(contract_class, ContractClass::V0(_)) {
+            check_deprecated_class_execution(
+                &tx_context.block_context.versioned_constants,
+                class_hash,
+                self.storage_address,
+            )?;
+        }
 
         execute_entry_point_call(self, contract_class, state, resources, context)
     }
@@ -125,6 +134,8 @@ pub struct EntryPointExecutionContext {
     pub n_emitted_events: usize,
     /// Used for tracking L2-to-L1 messages order during the current execution.
     pub n_sent_messages_to_l1: usize,
+    /// The number of VM memory cells used by the entry points run so far in the current execution.
+    pub n_used_memory_cells: usize,
     // Managed by dedicated guard object.
     current_recursion_depth: Arc<RefCell<usize>>,
 
@@ -143,6 +154,7 @@ impl EntryPointExecutionContext {
             vm_run_resources: RunResources::new(max_steps),
             n_emitted_events: 0,
             n_sent_messages_to_l1: 0,
+            n_used_memory_cells: 0,
             tx_context: tx_context.clone(),
             current_recursion_depth: Default::default(),
             execution_mode: mode,
@@ -280,6 +292,21 @@ impl EntryPointExecutionContext {
         self.subtract_steps(validate_steps + overhead_steps)
     }
 
+    /// Adds the memory cells used by an entry point run to the execution, and fails if the
+    /// execution used more memory cells than the versioned constants allow.
+    pub fn consume_memory_cells(&mut self, n_memory_cells: usize) -> EntryPointExecutionResult<()> {
+        self.n_used_memory_cells = self.n_used_memory_cells.saturating_add(n_memory_cells);
+        match self.versioned_constants().tx_max_n_memory_cells {
+            Some(max_n_memory_cells) if self.n_used_memory_cells > max_n_memory_cells => {
+                Err(EntryPointExecutionError::OutOfMemory {
+                    n_memory_cells: self.n_used_memory_cells,
+                    max_n_memory_cells,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
     pub fn versioned_constants(&self) -> &VersionedConstants {
         &self.tx_context.block_context.versioned_constants
     }
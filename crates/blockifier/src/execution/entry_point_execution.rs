@@ -1,5 +1,3 @@
-use std::collections::HashSet;
-
 use cairo_vm::types::builtin_name::BuiltinName;
 use cairo_vm::types::layout_name::LayoutName;
 use cairo_vm::types::relocatable::{MaybeRelocatable, Relocatable};
@@ -22,6 +20,7 @@ use crate::execution::entry_point::{
 };
 use crate::execution::errors::{EntryPointExecutionError, PostExecutionError, PreExecutionError};
 use crate::execution::execution_utils::{
+    n_used_memory_cells,
     read_execution_retdata,
     write_felt,
     write_maybe_relocatable,
@@ -29,6 +28,7 @@ use crate::execution::execution_utils::{
     ReadOnlySegments,
     SEGMENT_ARENA_BUILTIN_SIZE,
 };
+use crate::execution::memory_pool;
 use crate::execution::syscalls::hint_processor::SyscallHintProcessor;
 use crate::state::state_api::State;
 use crate::versioned_constants::GasCosts;
@@ -121,28 +121,29 @@ fn register_visited_pcs(
     program_segment_size: usize,
     bytecode_length: usize,
 ) -> EntryPointExecutionResult<()> {
-    let mut class_visited_pcs = HashSet::new();
     // Relocate the trace, putting the program segment at address 1 and the execution segment right
     // after it.
     // TODO(lior): Avoid unnecessary relocation once the VM has a non-relocated `get_trace()`
     //   function.
     runner.relocate_trace(&[1, 1 + program_segment_size])?;
-    for trace_entry in runner.relocated_trace.as_ref().expect("Relocated trace not found") {
-        let pc = trace_entry.pc;
-        if pc < 1 {
-            return Err(EntryPointExecutionError::InternalError(format!(
-                "Invalid PC value {pc} in trace."
-            )));
-        }
-        let real_pc = pc - 1;
-        // Jumping to a PC that is not inside the bytecode is possible. For example, to obtain
-        // the builtin costs. Filter out these values.
-        if real_pc < bytecode_length {
-            class_visited_pcs.insert(real_pc);
+    memory_pool::with_visited_pcs_buffer(|class_visited_pcs| {
+        for trace_entry in runner.relocated_trace.as_ref().expect("Relocated trace not found") {
+            let pc = trace_entry.pc;
+            if pc < 1 {
+                return Err(EntryPointExecutionError::InternalError(format!(
+                    "Invalid PC value {pc} in trace."
+                )));
+            }
+            let real_pc = pc - 1;
+            // Jumping to a PC that is not inside the bytecode is possible. For example, to obtain
+            // the builtin costs. Filter out these values.
+            if real_pc < bytecode_length {
+                class_visited_pcs.insert(real_pc);
+            }
         }
-    }
-    state.add_visited_pcs(class_hash, &class_visited_pcs);
-    Ok(())
+        state.add_visited_pcs(class_hash, class_visited_pcs);
+        Ok(())
+    })
 }
 
 pub fn initialize_execution_context<'a>(
@@ -211,11 +212,10 @@ fn prepare_program_extra_data(
         gas_costs.mul_mod_gas_cost,
     ];
 
-    let data = builtin_price_array
-        .iter()
-        .map(|&x| MaybeRelocatable::from(Felt::from(x)))
-        .collect::<Vec<_>>();
-    let builtin_cost_segment_start = read_only_segments.allocate(&mut runner.vm, &data)?;
+    let builtin_cost_segment_start = memory_pool::with_args_buffer(|data| {
+        data.extend(builtin_price_array.iter().map(|&x| MaybeRelocatable::from(Felt::from(x))));
+        read_only_segments.allocate(&mut runner.vm, data)
+    })?;
 
     // Put a pointer to the builtin cost segment at the end of the program (after the
     // additional `ret` statement).
@@ -270,10 +270,10 @@ pub fn prepare_call_arguments(
 
     // Prepare calldata arguments.
     let calldata = &call.calldata.0;
-    let calldata: Vec<MaybeRelocatable> =
-        calldata.iter().map(|&arg| MaybeRelocatable::from(arg)).collect();
-
-    let calldata_start_ptr = read_only_segments.allocate(&mut runner.vm, &calldata)?;
+    let calldata_start_ptr = memory_pool::with_args_buffer(|args_buffer| {
+        args_buffer.extend(calldata.iter().map(|&arg| MaybeRelocatable::from(arg)));
+        read_only_segments.allocate(&mut runner.vm, args_buffer)
+    })?;
     let calldata_end_ptr = MaybeRelocatable::from((calldata_start_ptr + calldata.len())?);
     args.push(CairoArg::Single(MaybeRelocatable::from(calldata_start_ptr)));
     args.push(CairoArg::Single(calldata_end_ptr));
@@ -300,6 +300,7 @@ pub fn run_entry_point(
     )?;
 
     maybe_fill_holes(entry_point, runner)?;
+    hint_processor.context.consume_memory_cells(n_used_memory_cells(runner))?;
 
     verify_secure_runner(runner, false, Some(program_segment_size))
         .map_err(CairoRunError::VirtualMachine)?;
@@ -1,21 +1,28 @@
 use std::collections::HashSet;
+use std::sync::Arc;
 
+use assert_matches::assert_matches;
 use cairo_vm::types::builtin_name::BuiltinName;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use num_bigint::BigInt;
 use pretty_assertions::assert_eq;
+use rstest::rstest;
 use starknet_api::core::{EntryPointSelector, PatriciaKey};
 use starknet_api::transaction::Calldata;
 use starknet_api::{calldata, felt};
 
 use crate::abi::abi_utils::{get_storage_var_address, selector_from_name};
-use crate::context::ChainInfo;
+use crate::context::{BlockContext, ChainInfo, TransactionContext};
 use crate::execution::call_info::{CallExecution, CallInfo, Retdata};
-use crate::execution::entry_point::CallEntryPoint;
+use crate::execution::entry_point::{CallEntryPoint, EntryPointExecutionContext};
+use crate::execution::errors::EntryPointExecutionError;
+use crate::execution::interrupt::ExecutionInterrupt;
 use crate::state::cached_state::CachedState;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::dict_state_reader::DictStateReader;
 use crate::test_utils::initial_test_state::test_state;
 use crate::test_utils::{trivial_external_entry_point_new, CairoVersion, BALANCE};
+use crate::transaction::objects::{DeprecatedTransactionInfo, TransactionInfo};
 use crate::versioned_constants::VersionedConstants;
 use crate::{retdata, storage_key};
 
@@ -185,6 +192,86 @@ fn test_storage_var() {
     );
 }
 
+#[rstest]
+fn test_interrupted_execution(
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let mut state = test_state(&ChainInfo::create_for_testing(), BALANCE, &[(test_contract, 1)]);
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        calldata: calldata![felt!(1234_u16), felt!(18_u8)],
+        ..trivial_external_entry_point_new(test_contract)
+    };
+
+    let mut block_context = BlockContext::create_for_testing();
+    let execution_interrupt = ExecutionInterrupt::default();
+    block_context.set_execution_interrupt(execution_interrupt.clone());
+    execution_interrupt.interrupt();
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true);
+
+    let error = entry_point_call
+        .execute(&mut state, &mut ExecutionResources::default(), &mut context)
+        .unwrap_err();
+    assert!(error.to_string().contains("The execution was interrupted."), "{error}");
+}
+
+#[rstest]
+fn test_memory_limit(
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let mut state = test_state(&ChainInfo::create_for_testing(), BALANCE, &[(test_contract, 1)]);
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        calldata: calldata![felt!(1234_u16), felt!(18_u8)],
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let execute_with_memory_limit =
+        |tx_max_n_memory_cells, state: &mut CachedState<DictStateReader>| {
+            let mut block_context = BlockContext::create_for_testing();
+            block_context.versioned_constants.tx_max_n_memory_cells = tx_max_n_memory_cells;
+            let tx_context = TransactionContext {
+                block_context,
+                tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+            };
+            let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true);
+            let result = entry_point_call.clone().execute(
+                state,
+                &mut ExecutionResources::default(),
+                &mut context,
+            );
+            (result, context.n_used_memory_cells)
+        };
+
+    // Without a limit, the memory the execution uses is counted.
+    let (result, n_used_memory_cells) = execute_with_memory_limit(None, &mut state);
+    result.unwrap();
+    assert!(n_used_memory_cells > 0);
+
+    // The execution fails once it uses more memory cells than the limit.
+    let (result, _) = execute_with_memory_limit(Some(n_used_memory_cells), &mut state);
+    result.unwrap();
+    let (result, _) = execute_with_memory_limit(Some(n_used_memory_cells - 1), &mut state);
+    assert_matches!(
+        result,
+        Err(EntryPointExecutionError::OutOfMemory { n_memory_cells, max_n_memory_cells })
+        if n_memory_cells == n_used_memory_cells && max_n_memory_cells == n_used_memory_cells - 1
+    );
+
+    // The limit is checked once the entry point exits, whatever the memory it exceeds by.
+    let (result, _) = execute_with_memory_limit(Some(1), &mut state);
+    assert_matches!(
+        result,
+        Err(EntryPointExecutionError::OutOfMemory { n_memory_cells, max_n_memory_cells: 1 })
+        if n_memory_cells == n_used_memory_cells
+    );
+}
+
 /// Runs test scenarios that could fail the OS run and therefore must be caught in the Blockifier.
 fn run_security_test(
     state: &mut CachedState<DictStateReader>,
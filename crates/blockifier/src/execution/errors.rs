@@ -21,6 +21,8 @@ use crate::state::errors::StateError;
 
 #[derive(Debug, Error)]
 pub enum PreExecutionError {
+    #[error("Deprecated class {:#064x} isn't executed in this version.", **class_hash)]
+    DeprecatedClassDisallowed { class_hash: ClassHash },
     #[error("Entry point {:#064x} of type {typ:?} is not unique.", .selector.0)]
     DuplicatedEntryPointSelector { selector: EntryPointSelector, typ: EntryPointType },
     #[error("Entry point {0:?} not found in contract.")]
@@ -87,6 +89,11 @@ pub enum EntryPointExecutionError {
     InternalError(String),
     #[error("Invalid input: {input_descriptor}; {info}")]
     InvalidExecutionInput { input_descriptor: String, info: String },
+    #[error(
+        "Execution failed due to memory limit exceeded: used {n_memory_cells} memory cells, the \
+         limit is {max_n_memory_cells}."
+    )]
+    OutOfMemory { n_memory_cells: usize, max_n_memory_cells: usize },
     #[error(transparent)]
     PostExecutionError(#[from] PostExecutionError),
     #[error(transparent)]
@@ -139,4 +146,6 @@ pub enum ContractClassError {
         contract_class_version: u8,
         sierra_program_length: usize,
     },
+    #[error("Invalid Casm class: {0}")]
+    InvalidCasmClass(String),
 }
@@ -241,6 +241,12 @@ pub fn execute_deployment(
     )
 }
 
+/// Returns the number of memory cells used by the run of the runner, in all segments. The sizes
+/// of the segments are computed when the run ends.
+pub fn n_used_memory_cells(runner: &CairoRunner) -> usize {
+    runner.vm.segments.segment_used_sizes.as_ref().map_or(0, |sizes| sizes.iter().sum())
+}
+
 pub fn write_felt(
     vm: &mut VirtualMachine,
     ptr: &mut Relocatable,
@@ -0,0 +1,37 @@
+//! Cooperative cancellation of transaction execution.
+//!
+//! Execution can't be preempted, so it checks the [`ExecutionInterrupt`] of its block at every
+//! syscall, and fails once the interrupt is triggered or its deadline passes. This bounds the time
+//! a single transaction can hold back the block, e.g. when building a proposal under a deadline.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// A deadline and a cancellation flag shared by the owner of an execution and the execution itself.
+/// Clones share the flag, so triggering any of them interrupts all of them.
+#[derive(Clone, Debug, Default)]
+pub struct ExecutionInterrupt {
+    deadline: Option<Instant>,
+    interrupted: Arc<AtomicBool>,
+}
+
+impl ExecutionInterrupt {
+    /// An interrupt that is also triggered once the deadline passes.
+    pub fn with_deadline(deadline: Instant) -> Self {
+        Self { deadline: Some(deadline), ..Default::default() }
+    }
+
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    pub fn interrupt(&self) {
+        self.interrupted.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::Relaxed)
+            || self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
@@ -0,0 +1,92 @@
+//! Reusable buffers for setting up the VM of entry point calls.
+//!
+//! With the `memory_pools` feature, every thread that executes transactions, i.e., the sequential
+//! executor or a worker of the concurrent executor, keeps a pool of the buffers that are otherwise
+//! allocated and freed on every call: the call arguments that are loaded into the VM memory and the
+//! PCs visited by the call. The buffers are cleared before each use, and are reset between
+//! transactions, so that a single large transaction doesn't hold on to their memory for the rest of
+//! the block. Without the feature, a fresh buffer is allocated on every use.
+//!
+//! The `CairoRunner` of a call and its memory segments aren't pooled: cairo-vm has no way to reset
+//! a runner for reuse.
+//! TODO: Pool the runners once cairo-vm supports resetting them.
+//!
+//! The effect of the pools is measured by the `memory_pool` benchmark of `blockifier_bench`.
+
+#[cfg(test)]
+#[path = "memory_pool_test.rs"]
+mod memory_pool_test;
+
+#[cfg(feature = "memory_pools")]
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+use cairo_vm::types::relocatable::MaybeRelocatable;
+
+/// Buffers whose capacity grew beyond this number of elements are released on reset.
+pub const MAX_RETAINED_CAPACITY: usize = 1 << 16;
+
+#[derive(Debug, Default)]
+pub struct ExecutionMemoryPool {
+    pub args: Vec<MaybeRelocatable>,
+    pub visited_pcs: HashSet<usize>,
+}
+
+impl ExecutionMemoryPool {
+    /// Clears the buffers, and releases the ones that grew too large.
+    pub fn reset(&mut self) {
+        self.args.clear();
+        self.visited_pcs.clear();
+        if self.args.capacity() > MAX_RETAINED_CAPACITY {
+            self.args = Vec::new();
+        }
+        if self.visited_pcs.capacity() > MAX_RETAINED_CAPACITY {
+            self.visited_pcs = HashSet::new();
+        }
+    }
+}
+
+#[cfg(feature = "memory_pools")]
+thread_local! {
+    static MEMORY_POOL: RefCell<ExecutionMemoryPool> = RefCell::new(ExecutionMemoryPool::default());
+}
+
+/// Runs `f` with an empty buffer for the arguments of a call.
+pub fn with_args_buffer<R>(f: impl FnOnce(&mut Vec<MaybeRelocatable>) -> R) -> R {
+    with_pool(|pool| {
+        pool.args.clear();
+        f(&mut pool.args)
+    })
+}
+
+/// Runs `f` with an empty buffer for the PCs visited by a call.
+pub fn with_visited_pcs_buffer<R>(f: impl FnOnce(&mut HashSet<usize>) -> R) -> R {
+    with_pool(|pool| {
+        pool.visited_pcs.clear();
+        f(&mut pool.visited_pcs)
+    })
+}
+
+/// Resets the pool of the current thread. Called between transactions.
+pub fn reset() {
+    #[cfg(feature = "memory_pools")]
+    MEMORY_POOL.with(|pool| {
+        if let Ok(mut pool) = pool.try_borrow_mut() {
+            pool.reset();
+        }
+    });
+}
+
+#[cfg(feature = "memory_pools")]
+fn with_pool<R>(f: impl FnOnce(&mut ExecutionMemoryPool) -> R) -> R {
+    MEMORY_POOL.with(|pool| match pool.try_borrow_mut() {
+        Ok(mut pool) => f(&mut pool),
+        // The pool is in use higher up the stack.
+        Err(_) => f(&mut ExecutionMemoryPool::default()),
+    })
+}
+
+#[cfg(not(feature = "memory_pools"))]
+fn with_pool<R>(f: impl FnOnce(&mut ExecutionMemoryPool) -> R) -> R {
+    f(&mut ExecutionMemoryPool::default())
+}
@@ -0,0 +1,52 @@
+use cairo_vm::types::relocatable::MaybeRelocatable;
+use starknet_types_core::felt::Felt;
+
+use crate::execution::memory_pool::{
+    reset,
+    with_args_buffer,
+    with_visited_pcs_buffer,
+    ExecutionMemoryPool,
+    MAX_RETAINED_CAPACITY,
+};
+
+#[test]
+fn buffers_are_empty_on_use() {
+    with_args_buffer(|args| args.push(MaybeRelocatable::from(Felt::ONE)));
+    with_args_buffer(|args| assert!(args.is_empty()));
+
+    with_visited_pcs_buffer(|visited_pcs| visited_pcs.insert(1));
+    with_visited_pcs_buffer(|visited_pcs| assert!(visited_pcs.is_empty()));
+}
+
+#[test]
+fn nested_use_gets_a_buffer_of_its_own() {
+    with_args_buffer(|args| {
+        args.push(MaybeRelocatable::from(Felt::ONE));
+        with_args_buffer(|inner_args| assert!(inner_args.is_empty()));
+        assert_eq!(args, &vec![MaybeRelocatable::from(Felt::ONE)]);
+    });
+}
+
+#[cfg(feature = "memory_pools")]
+#[test]
+fn buffers_are_reused() {
+    reset();
+    with_args_buffer(|args| args.reserve(10));
+    let capacity = with_args_buffer(|args| args.capacity());
+    assert!(capacity >= 10);
+
+    // Small buffers are retained between transactions.
+    reset();
+    assert_eq!(with_args_buffer(|args| args.capacity()), capacity);
+}
+
+#[test]
+fn reset_releases_large_buffers() {
+    let mut pool = ExecutionMemoryPool::default();
+    pool.args.reserve(MAX_RETAINED_CAPACITY + 1);
+    pool.visited_pcs.insert(1);
+    pool.reset();
+
+    assert_eq!(pool.args.capacity(), 0);
+    assert!(pool.visited_pcs.is_empty());
+}
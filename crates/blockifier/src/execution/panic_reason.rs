@@ -0,0 +1,164 @@
+//! Decodes the panic data of failed Cairo 1 executions into readable revert reasons.
+//!
+//! The panic data is a sequence of felts, in which a contract may encode:
+//! * Short strings, e.g. `'ENTRYPOINT_FAILED'`, one per felt.
+//! * Byte arrays, e.g. the messages of `panic!` and `assert!`, serialized after
+//!   [`BYTE_ARRAY_MAGIC`].
+//! * Errors, as the selectors of their names; only the errors known to the [`PanicDecoder`] are
+//!   decoded.
+//!
+//! Any other felt is kept as is.
+
+#[cfg(test)]
+#[path = "panic_reason_test.rs"]
+mod panic_reason_test;
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use num_traits::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use starknet_types_core::felt::Felt;
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::execution::errors::{ConstructorEntryPointExecutionError, EntryPointExecutionError};
+use crate::transaction::errors::TransactionExecutionError;
+
+/// The felt that precedes a serialized `ByteArray` in panic data.
+pub const BYTE_ARRAY_MAGIC: &str =
+    "0x46a6158a16a947e5916b2a2ca68501a45e93d7110e81aa2d6438b1c57c879a3";
+const BYTES_IN_WORD: usize = 31;
+
+/// An item of the panic data of a failed execution.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PanicItem {
+    /// A known error, by its name.
+    Error(String),
+    ShortString(String),
+    ByteArray(String),
+    /// A felt that isn't readable as any of the above.
+    Felt(Felt),
+}
+
+/// The decoded panic data of a failed execution.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PanicReason {
+    pub items: Vec<PanicItem>,
+}
+
+impl Display for PanicReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let items: Vec<String> = self
+            .items
+            .iter()
+            .map(|item| match item {
+                PanicItem::Error(name) => name.clone(),
+                PanicItem::ShortString(string) => format!("'{string}'"),
+                PanicItem::ByteArray(string) => format!("\"{string}\""),
+                PanicItem::Felt(felt) => format!("{felt:#x}"),
+            })
+            .collect();
+        write!(f, "{}", items.join(", "))
+    }
+}
+
+/// Decodes panic data, recognizing the errors whose names it was given.
+#[derive(Clone, Debug, Default)]
+pub struct PanicDecoder {
+    errors_by_selector: HashMap<Felt, String>,
+}
+
+impl PanicDecoder {
+    pub fn new<'a>(error_names: impl IntoIterator<Item = &'a str>) -> Self {
+        Self {
+            errors_by_selector: error_names
+                .into_iter()
+                .map(|name| (selector_from_name(name).0, name.to_owned()))
+                .collect(),
+        }
+    }
+
+    pub fn decode(&self, panic_data: &[Felt]) -> PanicReason {
+        let byte_array_magic = Felt::from_hex_unchecked(BYTE_ARRAY_MAGIC);
+        let mut items = Vec::new();
+        let mut remaining_data = panic_data;
+        while let Some((&felt, rest)) = remaining_data.split_first() {
+            remaining_data = rest;
+            if felt == byte_array_magic {
+                if let Some((string, rest)) = decode_byte_array(remaining_data) {
+                    items.push(PanicItem::ByteArray(string));
+                    remaining_data = rest;
+                    continue;
+                }
+            }
+            let item = if let Some(name) = self.errors_by_selector.get(&felt) {
+                PanicItem::Error(name.clone())
+            } else if let Some(string) = decode_short_string(felt) {
+                PanicItem::ShortString(string)
+            } else {
+                PanicItem::Felt(felt)
+            };
+            items.push(item);
+        }
+        PanicReason { items }
+    }
+}
+
+/// Returns the panic data of the Cairo execution that failed the transaction, if it failed due to
+/// a Cairo panic.
+pub fn panic_data(error: &TransactionExecutionError) -> Option<&[Felt]> {
+    let entry_point_error = match error {
+        TransactionExecutionError::ExecutionError { error, .. }
+        | TransactionExecutionError::ValidateTransactionError { error, .. }
+        | TransactionExecutionError::ContractConstructorExecutionFailed(
+            ConstructorEntryPointExecutionError::ExecutionError { error, .. },
+        ) => error,
+        _ => return None,
+    };
+    match entry_point_error {
+        EntryPointExecutionError::ExecutionFailed { error_data } => Some(error_data),
+        _ => None,
+    }
+}
+
+// A serialized `ByteArray` consists of the number of its full words, the full words, the pending
+// word and the number of bytes in the pending word. Returns the string and the rest of the data.
+fn decode_byte_array(data: &[Felt]) -> Option<(String, &[Felt])> {
+    let (n_full_words, data) = data.split_first()?;
+    let n_full_words = n_full_words.to_biguint().to_usize()?;
+    if data.len() < n_full_words.checked_add(2)? {
+        return None;
+    }
+    let (full_words, data) = data.split_at(n_full_words);
+    let [pending_word, pending_word_len, rest @ ..] = data else {
+        return None;
+    };
+    let pending_word_len = pending_word_len.to_biguint().to_usize()?;
+    if pending_word_len >= BYTES_IN_WORD {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(n_full_words * BYTES_IN_WORD + pending_word_len);
+    for word in full_words {
+        bytes.extend(word_bytes(word, BYTES_IN_WORD)?);
+    }
+    bytes.extend(word_bytes(pending_word, pending_word_len)?);
+    Some((String::from_utf8(bytes).ok()?, rest))
+}
+
+// The last `len` big-endian bytes of the word, if the word fits in them.
+fn word_bytes(word: &Felt, len: usize) -> Option<Vec<u8>> {
+    let bytes = word.to_bytes_be();
+    let (high_bytes, low_bytes) = bytes.split_at(bytes.len() - len);
+    high_bytes.iter().all(|&byte| byte == 0).then(|| low_bytes.to_vec())
+}
+
+fn decode_short_string(felt: Felt) -> Option<String> {
+    let bytes = word_bytes(&felt, BYTES_IN_WORD)?;
+    let first_byte = bytes.iter().position(|&byte| byte != 0)?;
+    let string_bytes = &bytes[first_byte..];
+    if !string_bytes.iter().all(|&byte| byte.is_ascii_graphic() || byte == b' ') {
+        return None;
+    }
+    String::from_utf8(string_bytes.to_vec()).ok()
+}
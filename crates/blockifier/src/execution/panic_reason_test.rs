@@ -0,0 +1,103 @@
+use starknet_api::core::{ClassHash, ContractAddress};
+use starknet_api::felt;
+use starknet_types_core::felt::Felt;
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::execution::errors::EntryPointExecutionError;
+use crate::execution::panic_reason::{
+    panic_data,
+    PanicDecoder,
+    PanicItem,
+    PanicReason,
+    BYTE_ARRAY_MAGIC,
+};
+use crate::transaction::errors::TransactionExecutionError;
+
+fn short_string(string: &str) -> Felt {
+    Felt::from_bytes_be_slice(string.as_bytes())
+}
+
+#[test]
+fn decode_short_strings_and_felts() {
+    let panic_reason =
+        PanicDecoder::default().decode(&[short_string("ENTRYPOINT_FAILED"), felt!(0x80_u8)]);
+
+    assert_eq!(
+        panic_reason,
+        PanicReason {
+            items: vec![
+                PanicItem::ShortString("ENTRYPOINT_FAILED".to_string()),
+                PanicItem::Felt(felt!(0x80_u8)),
+            ]
+        }
+    );
+    assert_eq!(panic_reason.to_string(), "'ENTRYPOINT_FAILED', 0x80");
+}
+
+#[test]
+fn decode_byte_array() {
+    let message = "A message longer than a single word of 31 bytes.";
+    let (full_word, pending_word) = message.as_bytes().split_at(31);
+    let panic_data = [
+        Felt::from_hex_unchecked(BYTE_ARRAY_MAGIC),
+        Felt::ONE,
+        Felt::from_bytes_be_slice(full_word),
+        Felt::from_bytes_be_slice(pending_word),
+        Felt::from(pending_word.len()),
+        short_string("ENTRYPOINT_FAILED"),
+    ];
+
+    let panic_reason = PanicDecoder::default().decode(&panic_data);
+
+    assert_eq!(
+        panic_reason.items,
+        vec![
+            PanicItem::ByteArray(message.to_string()),
+            PanicItem::ShortString("ENTRYPOINT_FAILED".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn truncated_byte_array_is_kept_as_felts() {
+    let panic_data = [Felt::from_hex_unchecked(BYTE_ARRAY_MAGIC), Felt::TWO];
+
+    assert_eq!(
+        PanicDecoder::default().decode(&panic_data).items,
+        vec![PanicItem::Felt(panic_data[0]), PanicItem::Felt(Felt::TWO)]
+    );
+}
+
+#[test]
+fn decode_known_errors() {
+    let decoder = PanicDecoder::new(["InsufficientBalance"]);
+    let panic_data = [selector_from_name("InsufficientBalance").0, selector_from_name("Unknown").0];
+
+    assert_eq!(
+        decoder.decode(&panic_data).items,
+        vec![
+            PanicItem::Error("InsufficientBalance".to_string()),
+            PanicItem::Felt(selector_from_name("Unknown").0),
+        ]
+    );
+}
+
+#[test]
+fn panic_data_of_execution_error() {
+    let error_data = vec![short_string("Out of gas")];
+    let error = TransactionExecutionError::ExecutionError {
+        error: EntryPointExecutionError::ExecutionFailed { error_data: error_data.clone() },
+        class_hash: ClassHash::default(),
+        storage_address: ContractAddress::default(),
+        selector: selector_from_name("__execute__"),
+    };
+    assert_eq!(panic_data(&error), Some(error_data.as_slice()));
+
+    let error = TransactionExecutionError::ExecutionError {
+        error: EntryPointExecutionError::InternalError("Internal error.".to_string()),
+        class_hash: ClassHash::default(),
+        storage_address: ContractAddress::default(),
+        selector: selector_from_name("__execute__"),
+    };
+    assert_eq!(panic_data(&error), None);
+}
@@ -88,6 +88,8 @@ pub enum SyscallExecutionError {
     ForbiddenClassReplacement { class_hash: ClassHash },
     #[error(transparent)]
     FromStr(#[from] FromStrError),
+    #[error("The execution was interrupted.")]
+    ExecutionInterrupted,
     #[error("Invalid address domain: {address_domain}.")]
     InvalidAddressDomain { address_domain: Felt },
     #[error(transparent)]
@@ -295,6 +297,15 @@ impl<'a> SyscallHintProcessor<'a> {
         self.execution_mode() == ExecutionMode::Validate
     }
 
+    /// Returns an error if the owner of the execution interrupted it.
+    pub fn verify_not_interrupted(&self) -> SyscallResult<()> {
+        if self.context.tx_context.block_context.execution_interrupt.is_interrupted() {
+            return Err(SyscallExecutionError::ExecutionInterrupted);
+        }
+
+        Ok(())
+    }
+
     pub fn verify_syscall_ptr(&self, actual_ptr: Relocatable) -> SyscallResult<()> {
         if actual_ptr != self.syscall_ptr {
             return Err(SyscallExecutionError::BadSyscallPointer {
@@ -320,6 +331,7 @@ impl<'a> SyscallHintProcessor<'a> {
         };
         let initial_syscall_ptr = get_ptr_from_res_operand_unchecked(vm, syscall);
         self.verify_syscall_ptr(initial_syscall_ptr)?;
+        self.verify_not_interrupted()?;
 
         let selector = SyscallSelector::try_from(self.read_next_syscall_selector(vm)?)?;
 
@@ -1,5 +1,7 @@
 pub mod actual_cost;
 pub mod eth_gas_constants;
 pub mod fee_checks;
+pub mod fee_market;
+pub mod fee_multipliers;
 pub mod fee_utils;
 pub mod gas_usage;
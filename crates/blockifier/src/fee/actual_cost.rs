@@ -4,8 +4,11 @@ use starknet_api::transaction::Fee;
 
 use crate::context::TransactionContext;
 use crate::execution::call_info::CallInfo;
+use crate::execution::deprecated_syscalls::hint_processor::SyscallCounter;
+use crate::execution::syscalls::SyscallSelector;
 use crate::state::cached_state::StateChanges;
 use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::errors::TransactionFeeError;
 use crate::transaction::objects::{
     GasVector,
     HasRelatedFeeType,
@@ -63,22 +66,47 @@ impl TransactionReceipt {
             reverted_steps,
         } = tx_receipt_params;
 
+        // Beyond the fee transfer, which the OS resources of the transaction account for, a fee
+        // split costs another transfer of the fee token and an update of the split recipient's
+        // balance.
+        let fee_split_recipient = match &tx_context.block_context.chain_info.fee_split {
+            Some(fee_split)
+                if fee_split.percentage > 0 && tx_type != TransactionType::L1Handler =>
+            {
+                Some(fee_split.recipient)
+            }
+            _ => None,
+        };
         let starknet_resources = StarknetResources::new(
             calldata_length,
             signature_length,
             code_size,
-            state_changes.count_for_fee_charge(sender_address, tx_context.fee_token_address()),
+            state_changes.count_for_fee_charge(
+                sender_address,
+                fee_split_recipient,
+                tx_context.fee_token_address(),
+            ),
             l1_handler_payload_size,
             call_infos,
         );
 
-        let cairo_resources = (execution_resources
+        let mut cairo_resources = execution_resources
             + &tx_context.block_context.versioned_constants.get_additional_os_tx_resources(
                 tx_type,
                 &starknet_resources,
                 tx_context.block_context.block_info.use_kzg_da,
-            )?)
-            .filter_unused_builtins();
+            )?;
+        if fee_split_recipient.is_some() {
+            cairo_resources += &tx_context
+                .block_context
+                .versioned_constants
+                .get_additional_os_syscall_resources(&SyscallCounter::from([(
+                    SyscallSelector::CallContract,
+                    1,
+                )]))
+                .map_err(|error| TransactionFeeError::ExecuteFeeTransferError(error.into()))?;
+        }
+        let cairo_resources = cairo_resources.filter_unused_builtins();
 
         let tx_resources = TransactionResources {
             starknet_resources,
@@ -90,6 +118,7 @@ impl TransactionReceipt {
             &tx_context.block_context.versioned_constants,
             tx_context.block_context.block_info.use_kzg_da,
         )?;
+        let gas = tx_context.block_context.chain_info.fee_multipliers.apply(tx_type, gas);
 
         // L1 handler transactions are not charged an L2 fee but it is compared to the L1 fee.
         let fee = if tx_context.tx_info.enforce_fee() || tx_type == TransactionType::L1Handler {
@@ -1,4 +1,4 @@
-use starknet_api::transaction::Fee;
+use starknet_api::transaction::{Fee, Resource, ValidResourceBounds};
 use starknet_types_core::felt::Felt;
 use thiserror::Error;
 
@@ -9,6 +9,7 @@ use crate::fee::gas_usage::compute_discounted_gas_from_gas_vector;
 use crate::state::state_api::StateReader;
 use crate::transaction::errors::TransactionExecutionError;
 use crate::transaction::objects::{
+    CurrentTransactionInfo,
     FeeType,
     GasVector,
     TransactionExecutionResult,
@@ -19,6 +20,10 @@ use crate::transaction::objects::{
 pub enum FeeCheckError {
     #[error("Insufficient max L1 gas: max amount: {max_amount}, actual used: {actual_amount}.")]
     MaxL1GasAmountExceeded { max_amount: u128, actual_amount: u128 },
+    #[error(
+        "Insufficient {resource:?} bounds: max amount: {max_amount}, actual used: {actual_amount}."
+    )]
+    InsufficientResourceBounds { resource: Resource, max_amount: u128, actual_amount: u128 },
     #[error("Insufficient max fee: max fee: {}, actual fee: {}.", max_fee.0, actual_fee.0)]
     MaxFeeExceeded { max_fee: Fee, actual_fee: Fee },
     #[error(
@@ -58,10 +63,11 @@ impl FeeCheckReport {
 
     /// Given a fee error and the current context, constructs and returns a report.
     pub fn from_fee_check_error(
-        actual_fee: Fee,
+        tx_receipt: &TransactionReceipt,
         error: FeeCheckError,
         tx_context: &TransactionContext,
     ) -> Self {
+        let TransactionReceipt { fee: actual_fee, gas, .. } = *tx_receipt;
         let recommended_fee = match error {
             // If the error is insufficient balance, the recommended fee is the actual fee.
             // This recommendation assumes (a) the pre-validation checks were applied and pass (i.e.
@@ -71,8 +77,25 @@ impl FeeCheckReport {
             // If the error is resource overdraft, the recommended fee is the resource bounds.
             // If the transaction passed pre-validation checks (i.e. balance initially covered the
             // resource bounds), the sender should be able to pay this fee.
-            FeeCheckError::MaxFeeExceeded { .. } | FeeCheckError::MaxL1GasAmountExceeded { .. } => {
+            FeeCheckError::MaxFeeExceeded { .. }
+            | FeeCheckError::MaxL1GasAmountExceeded { .. }
+            | FeeCheckError::InsufficientResourceBounds { .. } => {
                 match &tx_context.tx_info {
+                    // Each resource is charged up to its bound.
+                    TransactionInfo::Current(CurrentTransactionInfo {
+                        resource_bounds: ValidResourceBounds::AllResources(resource_bounds),
+                        ..
+                    }) => get_fee_by_gas_vector(
+                        &tx_context.block_context.block_info,
+                        GasVector {
+                            l1_gas: gas.l1_gas.min(resource_bounds.l1_gas.max_amount.into()),
+                            l1_data_gas: gas
+                                .l1_data_gas
+                                .min(resource_bounds.l1_data_gas.max_amount.into()),
+                            l2_gas: gas.l2_gas.min(resource_bounds.l2_gas.max_amount.into()),
+                        },
+                        &FeeType::Strk,
+                    ),
                     TransactionInfo::Current(info) => get_fee_by_gas_vector(
                         &tx_context.block_context.block_info,
                         GasVector::from_l1_gas(info.l1_resource_bounds().max_amount.into()),
@@ -98,6 +121,26 @@ impl FeeCheckReport {
         // sender.
         // TODO(Aner, 21/01/24) modify for 4844 (include check for blob_gas).
         match tx_info {
+            TransactionInfo::Current(CurrentTransactionInfo {
+                resource_bounds: ValidResourceBounds::AllResources(resource_bounds),
+                ..
+            }) => {
+                // Each resource is bounded separately, so the gas isn't discounted.
+                for (resource, actual_amount) in [
+                    (Resource::L1Gas, gas.l1_gas),
+                    (Resource::L1DataGas, gas.l1_data_gas),
+                    (Resource::L2Gas, gas.l2_gas),
+                ] {
+                    let max_amount = resource_bounds.get_bound(resource).max_amount.into();
+                    if actual_amount > max_amount {
+                        return Err(FeeCheckError::InsufficientResourceBounds {
+                            resource,
+                            max_amount,
+                            actual_amount,
+                        })?;
+                    }
+                }
+            }
             TransactionInfo::Current(context) => {
                 // Check L1 gas limit.
                 let max_l1_gas = context.l1_resource_bounds().max_amount.into();
@@ -213,7 +256,7 @@ impl PostExecutionReport {
                     // Found an error; set the recommended fee based on the error variant and
                     // current context, and return the report.
                     return Ok(Self(FeeCheckReport::from_fee_check_error(
-                        *fee,
+                        tx_receipt,
                         fee_check_error,
                         tx_context,
                     )));
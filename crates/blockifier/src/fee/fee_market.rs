@@ -0,0 +1,138 @@
+//! An EIP-1559-style fee market for the L2 gas price.
+//!
+//! The L2 gas price of each block is derived from the price of its parent block and how full the
+//! parent block was: the price rises when the parent used more than the target fraction of the
+//! block capacity, and falls when it used less, by up to `max_change_rate_percent` per block. The
+//! fullness of a block is the fraction of the capacity of its most used bouncer resource.
+
+use std::collections::BTreeMap;
+use std::num::NonZeroU128;
+
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+
+use crate::blockifier::block::GasPrices;
+use crate::bouncer::{BouncerConfig, BouncerWeights, FULL_BLOCK_FRACTION};
+use crate::transaction::objects::FeeType;
+
+#[cfg(test)]
+#[path = "fee_market_test.rs"]
+pub mod test;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FeeMarketConfig {
+    /// The fraction of the block capacity, in percents, at which the L2 gas price stays the same.
+    pub target_utilization_percent: u8,
+    /// The maximal change of the L2 gas price between consecutive blocks, in percents.
+    pub max_change_rate_percent: u8,
+    /// The minimal L2 gas price in wei.
+    pub min_eth_l2_gas_price: u128,
+    /// The minimal L2 gas price in fri.
+    pub min_strk_l2_gas_price: u128,
+}
+
+impl Default for FeeMarketConfig {
+    fn default() -> Self {
+        Self {
+            target_utilization_percent: 50,
+            max_change_rate_percent: 12,
+            min_eth_l2_gas_price: 1,
+            min_strk_l2_gas_price: 1,
+        }
+    }
+}
+
+impl SerializeConfig for FeeMarketConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "target_utilization_percent",
+                &self.target_utilization_percent,
+                "The fraction of the block capacity, in percents, at which the L2 gas price stays \
+                 the same.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_change_rate_percent",
+                &self.max_change_rate_percent,
+                "The maximal change of the L2 gas price between consecutive blocks, in percents.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "min_eth_l2_gas_price",
+                &self.min_eth_l2_gas_price,
+                "The minimal L2 gas price in wei.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "min_strk_l2_gas_price",
+                &self.min_strk_l2_gas_price,
+                "The minimal L2 gas price in fri.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+/// Adjusts the L2 gas price block over block by the [`FeeMarketConfig`].
+pub struct FeeMarket<'a> {
+    config: &'a FeeMarketConfig,
+}
+
+impl<'a> FeeMarket<'a> {
+    pub fn new(config: &'a FeeMarketConfig) -> Self {
+        Self { config }
+    }
+
+    /// Returns the gas prices of the next block: those of the parent block, with L2 gas prices
+    /// adjusted by the weights the parent block used out of its capacity.
+    pub fn next_gas_prices(
+        &self,
+        parent_gas_prices: &GasPrices,
+        parent_weights: &BouncerWeights,
+        bouncer_config: &BouncerConfig,
+    ) -> GasPrices {
+        let utilization = parent_weights.block_fraction(&bouncer_config.block_max_capacity);
+        parent_gas_prices.with_l2_gas_prices(
+            self.next_l2_gas_price(
+                parent_gas_prices.get_l2_gas_price_by_fee_type(&FeeType::Eth),
+                utilization,
+                self.config.min_eth_l2_gas_price,
+            ),
+            self.next_l2_gas_price(
+                parent_gas_prices.get_l2_gas_price_by_fee_type(&FeeType::Strk),
+                utilization,
+                self.config.min_strk_l2_gas_price,
+            ),
+        )
+    }
+
+    /// Returns the L2 gas price of the next block, given the price of the parent block and its
+    /// utilization, in parts per million of the block capacity.
+    pub fn next_l2_gas_price(
+        &self,
+        parent_gas_price: NonZeroU128,
+        utilization: u128,
+        min_gas_price: u128,
+    ) -> NonZeroU128 {
+        let parent_gas_price = parent_gas_price.get();
+        let target_utilization =
+            (u128::from(self.config.target_utilization_percent) * FULL_BLOCK_FRACTION / 100).max(1);
+        let utilization = utilization.min(FULL_BLOCK_FRACTION);
+        let max_change =
+            parent_gas_price.saturating_mul(u128::from(self.config.max_change_rate_percent)) / 100;
+        let change = |utilization_diff: u128| {
+            (max_change.saturating_mul(utilization_diff) / target_utilization).min(max_change)
+        };
+
+        let gas_price = if utilization > target_utilization {
+            // Like in EIP-1559, the price rises by at least 1, so it can't get stuck at low values.
+            let increase = change(utilization - target_utilization).max(1);
+            parent_gas_price.saturating_add(increase)
+        } else {
+            parent_gas_price.saturating_sub(change(target_utilization - utilization))
+        };
+        NonZeroU128::new(gas_price.max(min_gas_price)).unwrap_or(NonZeroU128::MIN)
+    }
+}
@@ -0,0 +1,72 @@
+use std::num::NonZeroU128;
+
+use rstest::rstest;
+
+use crate::blockifier::block::BlockInfo;
+use crate::bouncer::{BouncerConfig, BouncerWeights};
+use crate::fee::fee_market::{FeeMarket, FeeMarketConfig};
+use crate::transaction::objects::FeeType;
+
+const PARENT_GAS_PRICE: u128 = 1000;
+
+fn gas_price(gas_price: u128) -> NonZeroU128 {
+    NonZeroU128::new(gas_price).unwrap()
+}
+
+#[rstest]
+#[case::full_block(1_000_000, 1120)]
+#[case::above_target(750_000, 1060)]
+#[case::at_target(500_000, 1000)]
+#[case::below_target(250_000, 940)]
+#[case::empty_block(0, 880)]
+fn test_next_l2_gas_price(#[case] utilization: u128, #[case] expected_gas_price: u128) {
+    let config = FeeMarketConfig::default();
+    let fee_market = FeeMarket::new(&config);
+    assert_eq!(
+        fee_market.next_l2_gas_price(gas_price(PARENT_GAS_PRICE), utilization, 1),
+        gas_price(expected_gas_price)
+    );
+}
+
+#[test]
+fn test_next_l2_gas_price_bounds() {
+    let config = FeeMarketConfig { target_utilization_percent: 25, ..Default::default() };
+    let fee_market = FeeMarket::new(&config);
+
+    // The change is capped by the max change rate, even when the block is far above the target.
+    assert_eq!(
+        fee_market.next_l2_gas_price(gas_price(PARENT_GAS_PRICE), 1_000_000, 1),
+        gas_price(1120)
+    );
+    // The price doesn't fall below the floor.
+    assert_eq!(fee_market.next_l2_gas_price(gas_price(PARENT_GAS_PRICE), 0, 950), gas_price(950));
+    // A low price still rises.
+    assert_eq!(fee_market.next_l2_gas_price(gas_price(1), 1_000_000, 1), gas_price(2));
+}
+
+#[test]
+fn test_next_gas_prices() {
+    let config = FeeMarketConfig::default();
+    let fee_market = FeeMarket::new(&config);
+    let bouncer_config = BouncerConfig {
+        block_max_capacity: BouncerWeights { n_steps: 100, ..BouncerWeights::max() },
+        ..BouncerConfig::max()
+    };
+    let parent_gas_prices = BlockInfo::create_for_testing().gas_prices;
+
+    // The parent block used all of its steps.
+    let parent_weights = BouncerWeights { n_steps: 100, ..Default::default() };
+    let gas_prices =
+        fee_market.next_gas_prices(&parent_gas_prices, &parent_weights, &bouncer_config);
+    for fee_type in [FeeType::Eth, FeeType::Strk] {
+        let parent_l2_gas_price = parent_gas_prices.get_l2_gas_price_by_fee_type(&fee_type).get();
+        assert_eq!(
+            gas_prices.get_l2_gas_price_by_fee_type(&fee_type).get(),
+            parent_l2_gas_price + parent_l2_gas_price * 12 / 100
+        );
+        assert_eq!(
+            gas_prices.get_l1_gas_price_by_fee_type(&fee_type),
+            parent_gas_prices.get_l1_gas_price_by_fee_type(&fee_type)
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//! Per transaction type multipliers of the charged gas.
+//!
+//! A chain may charge some transaction types more or less than their resources cost, e.g. a
+//! surcharge on declares for the long-term cost of storing their classes, or a discount on L1
+//! handlers. The multipliers scale the gas vector of a transaction before its fee is computed, so
+//! the fee is the same whether the transaction is estimated, simulated or executed, and the
+//! minimal fee checked before validation is scaled alike.
+
+use std::collections::BTreeMap;
+
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+
+use crate::transaction::objects::GasVector;
+use crate::transaction::transaction_types::TransactionType;
+
+#[cfg(test)]
+#[path = "fee_multipliers_test.rs"]
+pub mod test;
+
+/// The percentage of its gas that each transaction type is charged for; 100 charges the gas as is.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FeeMultipliers {
+    pub declare_percent: u32,
+    pub deploy_account_percent: u32,
+    pub invoke_percent: u32,
+    pub l1_handler_percent: u32,
+}
+
+impl Default for FeeMultipliers {
+    fn default() -> Self {
+        Self {
+            declare_percent: 100,
+            deploy_account_percent: 100,
+            invoke_percent: 100,
+            l1_handler_percent: 100,
+        }
+    }
+}
+
+impl SerializeConfig for FeeMultipliers {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "declare_percent",
+                &self.declare_percent,
+                "The percentage of their gas that declare transactions are charged for.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "deploy_account_percent",
+                &self.deploy_account_percent,
+                "The percentage of their gas that deploy account transactions are charged for.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "invoke_percent",
+                &self.invoke_percent,
+                "The percentage of their gas that invoke transactions are charged for.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "l1_handler_percent",
+                &self.l1_handler_percent,
+                "The percentage of their gas that L1 handler transactions are charged for.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+impl FeeMultipliers {
+    pub fn percent(&self, tx_type: TransactionType) -> u32 {
+        match tx_type {
+            TransactionType::Declare => self.declare_percent,
+            TransactionType::DeployAccount => self.deploy_account_percent,
+            TransactionType::InvokeFunction => self.invoke_percent,
+            TransactionType::L1Handler => self.l1_handler_percent,
+        }
+    }
+
+    /// Returns the gas a transaction of the given type is charged for. Each resource is rounded
+    /// up, so a surcharge is never lost to rounding.
+    pub fn apply(&self, tx_type: TransactionType, gas: GasVector) -> GasVector {
+        let percent = u128::from(self.percent(tx_type));
+        if percent == 100 {
+            return gas;
+        }
+        let scale = |amount: u128| amount.saturating_mul(percent).div_ceil(100);
+        GasVector {
+            l1_gas: scale(gas.l1_gas),
+            l1_data_gas: scale(gas.l1_data_gas),
+            l2_gas: scale(gas.l2_gas),
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use rstest::rstest;
+use strum::IntoEnumIterator;
+
+use crate::fee::fee_multipliers::FeeMultipliers;
+use crate::transaction::objects::GasVector;
+use crate::transaction::transaction_types::TransactionType;
+
+fn multipliers() -> FeeMultipliers {
+    FeeMultipliers {
+        declare_percent: 150,
+        deploy_account_percent: 100,
+        invoke_percent: 100,
+        l1_handler_percent: 50,
+    }
+}
+
+fn gas(l1_gas: u128, l1_data_gas: u128, l2_gas: u128) -> GasVector {
+    GasVector { l1_gas, l1_data_gas, l2_gas }
+}
+
+#[rstest]
+#[case::declare_surcharge(TransactionType::Declare, gas(150, 15, 2))]
+#[case::deploy_account_unchanged(TransactionType::DeployAccount, gas(100, 10, 1))]
+#[case::invoke_unchanged(TransactionType::InvokeFunction, gas(100, 10, 1))]
+#[case::l1_handler_discount(TransactionType::L1Handler, gas(50, 5, 1))]
+fn multiplier_scales_gas_rounding_up(
+    #[case] tx_type: TransactionType,
+    #[case] expected_gas: GasVector,
+) {
+    assert_eq!(multipliers().apply(tx_type, gas(100, 10, 1)), expected_gas);
+}
+
+#[test]
+fn default_multipliers_charge_gas_as_is() {
+    for tx_type in TransactionType::iter() {
+        assert_eq!(FeeMultipliers::default().apply(tx_type, gas(7, 3, 1)), gas(7, 3, 1));
+    }
+}
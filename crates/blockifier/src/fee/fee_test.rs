@@ -4,14 +4,14 @@ use assert_matches::assert_matches;
 use cairo_vm::types::builtin_name::BuiltinName;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use rstest::rstest;
-use starknet_api::transaction::Fee;
+use starknet_api::transaction::{Fee, Resource, ResourceBounds};
 
 use crate::abi::constants::N_STEPS_RESOURCE;
 use crate::blockifier::block::GasPrices;
 use crate::context::BlockContext;
 use crate::fee::actual_cost::TransactionReceipt;
 use crate::fee::fee_checks::{FeeCheckError, FeeCheckReportFields, PostExecutionReport};
-use crate::fee::fee_utils::calculate_l1_gas_by_vm_usage;
+use crate::fee::fee_utils::{calculate_l1_gas_by_vm_usage, get_fee_by_gas_vector};
 use crate::invoke_tx_args;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::initial_test_state::test_state;
@@ -21,8 +21,8 @@ use crate::test_utils::{
     DEFAULT_ETH_L1_DATA_GAS_PRICE,
     DEFAULT_ETH_L1_GAS_PRICE,
 };
-use crate::transaction::objects::GasVector;
-use crate::transaction::test_utils::{account_invoke_tx, l1_resource_bounds};
+use crate::transaction::objects::{FeeType, GasVector};
+use crate::transaction::test_utils::{account_invoke_tx, all_resource_bounds, l1_resource_bounds};
 use crate::utils::u128_from_usize;
 use crate::versioned_constants::VersionedConstants;
 
@@ -180,3 +180,59 @@ fn test_discounted_gas_overdraft(
         assert_matches!(report.error(), None);
     }
 }
+
+#[rstest]
+#[case::within_bounds(GasVector { l1_gas: 10, l1_data_gas: 20, l2_gas: 30 }, None)]
+#[case::l1_gas_overdraft(
+    GasVector { l1_gas: 11, l1_data_gas: 20, l2_gas: 30 },
+    Some(Resource::L1Gas)
+)]
+#[case::l1_data_gas_overdraft(
+    GasVector { l1_gas: 10, l1_data_gas: 21, l2_gas: 30 },
+    Some(Resource::L1DataGas)
+)]
+#[case::l2_gas_overdraft(
+    GasVector { l1_gas: 10, l1_data_gas: 20, l2_gas: 31 },
+    Some(Resource::L2Gas)
+)]
+fn test_all_resource_bounds_overdraft(
+    #[case] gas: GasVector,
+    #[case] short_resource: Option<Resource>,
+) {
+    let block_context = BlockContext::create_for_account_testing();
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let mut state = test_state(&block_context.chain_info, BALANCE, &[(account, 1)]);
+    let bounds = |max_amount| ResourceBounds { max_amount, max_price_per_unit: 1 };
+    let tx = account_invoke_tx(invoke_tx_args! {
+        sender_address: account.get_instance_address(0),
+        resource_bounds: all_resource_bounds(bounds(10), bounds(20), bounds(30)),
+    });
+
+    let actual_fee = get_fee_by_gas_vector(&block_context.block_info, gas, &FeeType::Strk);
+    let tx_receipt = TransactionReceipt { fee: actual_fee, gas, ..Default::default() };
+    let charge_fee = true;
+    let report = PostExecutionReport::new(
+        &mut state,
+        &block_context.to_tx_context(&tx).unwrap(),
+        &tx_receipt,
+        charge_fee,
+    )
+    .unwrap();
+
+    let Some(short_resource) = short_resource else {
+        assert_matches!(report.error(), None);
+        assert_eq!(report.recommended_fee(), actual_fee);
+        return;
+    };
+    assert_matches!(
+        report.error().unwrap(),
+        FeeCheckError::InsufficientResourceBounds { resource, max_amount, actual_amount }
+        if resource == short_resource && actual_amount == max_amount + 1
+    );
+    // The fee is clamped to the bounds of each resource.
+    let clamped_gas = GasVector { l1_gas: 10, l1_data_gas: 20, l2_gas: 30 };
+    assert_eq!(
+        report.recommended_fee(),
+        get_fee_by_gas_vector(&block_context.block_info, clamped_gas, &FeeType::Strk)
+    );
+}
@@ -4,7 +4,7 @@ use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use num_bigint::BigUint;
 use starknet_api::core::ContractAddress;
 use starknet_api::state::StorageKey;
-use starknet_api::transaction::Fee;
+use starknet_api::transaction::{Fee, ValidResourceBounds};
 use starknet_types_core::felt::Felt;
 
 use crate::abi::abi_utils::get_fee_token_var_address;
@@ -15,6 +15,7 @@ use crate::context::{BlockContext, TransactionContext};
 use crate::state::state_api::StateReader;
 use crate::transaction::errors::TransactionFeeError;
 use crate::transaction::objects::{
+    CurrentTransactionInfo,
     ExecutionResourcesTraits,
     FeeType,
     GasVector,
@@ -106,13 +107,9 @@ pub fn verify_can_pay_committed_bounds(
 ) -> TransactionFeeResult<()> {
     let tx_info = &tx_context.tx_info;
     let committed_fee = match tx_info {
-        TransactionInfo::Current(context) => {
-            let l1_bounds = context.l1_resource_bounds();
-            let max_amount: u128 = l1_bounds.max_amount.into();
-            // Sender will not be charged by `max_price_per_unit`, but this check should not depend
-            // on the current gas price.
-            Fee(max_amount * l1_bounds.max_price_per_unit)
-        }
+        // Sender will not be charged by `max_price_per_unit`, but this check should not depend on
+        // the current gas price.
+        TransactionInfo::Current(context) => context.max_possible_fee(),
         TransactionInfo::Deprecated(context) => context.max_fee,
     };
     let (balance_low, balance_high, can_pay) =
@@ -121,6 +118,13 @@ pub fn verify_can_pay_committed_bounds(
         Ok(())
     } else {
         Err(match tx_info {
+            TransactionInfo::Current(CurrentTransactionInfo {
+                resource_bounds: ValidResourceBounds::AllResources(resource_bounds),
+                ..
+            }) => TransactionFeeError::ResourceBoundsExceedBalance {
+                resource_bounds: resource_bounds.clone(),
+                balance: balance_to_big_uint(&balance_low, &balance_high),
+            },
             TransactionInfo::Current(context) => {
                 let l1_bounds = context.l1_resource_bounds();
                 TransactionFeeError::L1GasBoundsExceedBalance {
@@ -137,9 +141,10 @@ pub fn verify_can_pay_committed_bounds(
     }
 }
 
+/// Returns the keys of the balance of the fee recipient, which is the sequencer unless the chain
+/// overrides it.
 pub fn get_sequencer_balance_keys(block_context: &BlockContext) -> (StorageKey, StorageKey) {
-    let sequencer_address = block_context.block_info.sequencer_address;
-    get_address_balance_keys(sequencer_address)
+    get_address_balance_keys(block_context.fee_recipient())
 }
 
 pub fn get_address_balance_keys(address: ContractAddress) -> (StorageKey, StorageKey) {
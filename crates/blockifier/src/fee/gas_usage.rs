@@ -146,7 +146,7 @@ pub fn estimate_minimal_gas_vector(
     tx: &AccountTransaction,
 ) -> TransactionPreValidationResult<GasVector> {
     // TODO(Dori, 1/8/2023): Give names to the constant VM step estimates and regression-test them.
-    let BlockContext { block_info, versioned_constants, .. } = block_context;
+    let BlockContext { block_info, chain_info, versioned_constants, .. } = block_context;
     let state_changes_by_account_transaction = match tx {
         // We consider the following state changes: sender balance update (storage update) + nonce
         // increment (contract modification) (we exclude the sequencer balance update and the ERC20
@@ -179,8 +179,9 @@ pub fn estimate_minimal_gas_vector(
             + versioned_constants.os_kzg_da_resources(data_segment_length).n_steps;
 
     let resources = ExecutionResources { n_steps: os_steps_for_type, ..Default::default() };
-    Ok(get_da_gas_cost(&state_changes_by_account_transaction, block_info.use_kzg_da)
-        + calculate_l1_gas_by_vm_usage(versioned_constants, &resources, 0)?)
+    let gas = get_da_gas_cost(&state_changes_by_account_transaction, block_info.use_kzg_da)
+        + calculate_l1_gas_by_vm_usage(versioned_constants, &resources, 0)?;
+    Ok(chain_info.fee_multipliers.apply(tx.tx_type(), gas))
 }
 
 /// Compute l1_gas estimation from gas_vector using the following formula:
@@ -34,6 +34,8 @@ pub struct CachedState<S: StateReader> {
     pub(crate) class_hash_to_class: RefCell<ContractClassMapping>,
     /// A map from class hash to the set of PC values that were visited in the class.
     pub visited_pcs: HashMap<ClassHash, HashSet<usize>>,
+    /// The keys read from the underlying state, if recording them.
+    read_set: RefCell<Option<ReadSet>>,
 }
 
 impl<S: StateReader> CachedState<S> {
@@ -43,6 +45,24 @@ impl<S: StateReader> CachedState<S> {
             cache: RefCell::new(StateCache::default()),
             class_hash_to_class: RefCell::new(HashMap::default()),
             visited_pcs: HashMap::default(),
+            read_set: RefCell::new(None),
+        }
+    }
+
+    /// Starts recording the keys read from the underlying state; see [`ReadSet`].
+    pub fn record_read_set(&mut self) {
+        *self.read_set.get_mut() = Some(ReadSet::default());
+    }
+
+    /// Returns the keys read from the underlying state since recording started, and stops the
+    /// recording. Returns [None] if the reads aren't recorded.
+    pub fn take_read_set(&mut self) -> Option<ReadSet> {
+        self.read_set.get_mut().take()
+    }
+
+    fn record_read(&self, record: impl FnOnce(&mut ReadSet)) {
+        if let Some(read_set) = self.read_set.borrow_mut().as_mut() {
+            record(read_set);
         }
     }
 
@@ -137,6 +157,9 @@ impl<S: StateReader> StateReader for CachedState<S> {
 
         if cache.get_storage_at(contract_address, key).is_none() {
             let storage_value = self.state.get_storage_at(contract_address, key)?;
+            self.record_read(|read_set| {
+                read_set.storage.insert((contract_address, key));
+            });
             cache.set_storage_initial_value(contract_address, key, storage_value);
         }
 
@@ -151,6 +174,9 @@ impl<S: StateReader> StateReader for CachedState<S> {
 
         if cache.get_nonce_at(contract_address).is_none() {
             let nonce = self.state.get_nonce_at(contract_address)?;
+            self.record_read(|read_set| {
+                read_set.nonces.insert(contract_address);
+            });
             cache.set_nonce_initial_value(contract_address, nonce);
         }
 
@@ -166,6 +192,9 @@ impl<S: StateReader> StateReader for CachedState<S> {
 
         if cache.get_class_hash_at(contract_address).is_none() {
             let class_hash = self.state.get_class_hash_at(contract_address)?;
+            self.record_read(|read_set| {
+                read_set.class_hashes.insert(contract_address);
+            });
             cache.set_class_hash_initial_value(contract_address, class_hash);
         }
 
@@ -182,6 +211,9 @@ impl<S: StateReader> StateReader for CachedState<S> {
         if let std::collections::hash_map::Entry::Vacant(vacant_entry) =
             class_hash_to_class.entry(class_hash)
         {
+            self.record_read(|read_set| {
+                read_set.declared_contracts.insert(class_hash);
+            });
             match self.state.get_compiled_contract_class(class_hash) {
                 Err(StateError::UndeclaredClassHash(class_hash)) => {
                     cache.set_declared_contract_initial_value(class_hash, false);
@@ -212,6 +244,9 @@ impl<S: StateReader> StateReader for CachedState<S> {
 
         if cache.get_compiled_class_hash(class_hash).is_none() {
             let compiled_class_hash = self.state.get_compiled_class_hash(class_hash)?;
+            self.record_read(|read_set| {
+                read_set.compiled_class_hashes.insert(class_hash);
+            });
             cache.set_compiled_class_hash_initial_value(class_hash, compiled_class_hash);
         }
 
@@ -288,6 +323,7 @@ impl Default for CachedState<crate::test_utils::dict_state_reader::DictStateRead
             cache: Default::default(),
             class_hash_to_class: Default::default(),
             visited_pcs: Default::default(),
+            read_set: Default::default(),
         }
     }
 }
@@ -353,6 +389,22 @@ impl StateMaps {
         }
     }
 }
+
+/// The keys a state read from its underlying state, e.g., the keys a transaction read from the
+/// block state, for detecting conflicts between transactions and for the OS input.
+/// Unlike the initial reads of [`StateCache`], it doesn't include the storage cells that were only
+/// written.
+#[cfg_attr(feature = "transaction_serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReadSet {
+    pub nonces: HashSet<ContractAddress>,
+    pub class_hashes: HashSet<ContractAddress>,
+    pub storage: HashSet<StorageEntry>,
+    pub compiled_class_hashes: HashSet<ClassHash>,
+    /// The class hashes whose classes were read.
+    pub declared_contracts: HashSet<ClassHash>,
+}
+
 /// Caches read and write requests.
 /// The tracked changes are needed for block state commitment.
 
@@ -617,8 +669,8 @@ impl StateChangesKeys {
         concurrency_mode: bool,
     ) {
         let actual_fee = tx_result.receipt.fee.0;
-        let sequencer_address = tx_context.block_context.block_info.sequencer_address;
-        if concurrency_mode && !tx_context.is_sequencer_the_sender() && actual_fee > 0 {
+        let sequencer_address = tx_context.block_context.fee_recipient();
+        if concurrency_mode && !tx_context.is_fee_recipient_the_sender() && actual_fee > 0 {
             // Add the deleted sequencer balance key to the storage keys.
             let sequencer_balance_low = get_fee_token_var_address(sequencer_address);
             self.storage_keys.insert((tx_context.fee_token_address(), sequencer_balance_low));
@@ -674,6 +726,7 @@ impl StateChanges {
     pub fn count_for_fee_charge(
         &self,
         sender_address: Option<ContractAddress>,
+        fee_split_recipient: Option<ContractAddress>,
         fee_token_address: ContractAddress,
     ) -> StateChangesCount {
         let mut modified_contracts = self.get_modified_contracts();
@@ -682,11 +735,12 @@ impl StateChanges {
         // the fee transfer, and the fee should cover the state changes that happen in the
         // fee transfer. The fee transfer is going to update the balance of the sequencer
         // and the balance of the sender contract, but we don't charge the sender for the
-        // sequencer balance change as it is amortized across the block.
+        // sequencer balance change as it is amortized across the block. A fee split also updates
+        // the balance of its recipient, which the sender is charged for.
         let mut n_storage_updates = self.0.storage.len();
-        if let Some(sender_address) = sender_address {
-            let sender_balance_key = get_fee_token_var_address(sender_address);
-            if !self.0.storage.contains_key(&(fee_token_address, sender_balance_key)) {
+        for address in sender_address.into_iter().chain(fee_split_recipient) {
+            let balance_key = get_fee_token_var_address(address);
+            if !self.0.storage.contains_key(&(fee_token_address, balance_key)) {
                 n_storage_updates += 1;
             }
         }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use assert_matches::assert_matches;
 use indexmap::indexmap;
@@ -335,7 +335,8 @@ fn test_from_state_changes_for_fee_charge(
     let fee_token_address = contract_address!("0x17");
     let state_changes =
         create_state_changes_for_test(&mut state, sender_address, fee_token_address);
-    let state_changes_count = state_changes.count_for_fee_charge(sender_address, fee_token_address);
+    let state_changes_count =
+        state_changes.count_for_fee_charge(sender_address, None, fee_token_address);
     let expected_state_changes_count = StateChangesCount {
         // 1 for storage update + 1 for sender balance update if sender is defined.
         n_storage_updates: 1 + usize::from(sender_address.is_some()),
@@ -603,3 +604,39 @@ fn test_state_maps() {
 
     assert_eq!(maps, empty);
 }
+
+#[test]
+fn test_read_set() {
+    let contract_address = contract_address!(CONTRACT_ADDRESS);
+    let (read_key, written_key) = (storage_key!(0x10_u16), storage_key!(0x20_u16));
+    let class_hash = class_hash!("0x10");
+    let mut parent_state: CachedState<DictStateReader> = CachedState::default();
+    let mut state = TransactionalState::create_transactional(&mut parent_state);
+    assert_eq!(state.take_read_set(), None);
+
+    state.record_read_set();
+    state.get_storage_at(contract_address, read_key).unwrap();
+    state.set_storage_at(contract_address, written_key, felt!("0x1")).unwrap();
+    // Reading a written cell doesn't read the underlying state.
+    state.get_storage_at(contract_address, written_key).unwrap();
+    state.increment_nonce(contract_address).unwrap();
+    state.get_class_hash_at(contract_address).unwrap();
+    assert_matches!(
+        state.get_compiled_contract_class(class_hash),
+        Err(StateError::UndeclaredClassHash(_))
+    );
+    // Fills the initial values of the written cells, which aren't reads.
+    state.to_state_diff().unwrap();
+
+    assert_eq!(
+        state.take_read_set(),
+        Some(ReadSet {
+            nonces: HashSet::from([contract_address]),
+            class_hashes: HashSet::from([contract_address]),
+            storage: HashSet::from([(contract_address, read_key)]),
+            declared_contracts: HashSet::from([class_hash]),
+            ..Default::default()
+        })
+    );
+    assert_eq!(state.take_read_set(), None);
+}
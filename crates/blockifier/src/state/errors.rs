@@ -1,5 +1,6 @@
 use cairo_vm::types::errors::program_errors::ProgramError;
 use num_bigint::{BigUint, TryFromBigIntError};
+use starknet_api::block::BlockNumber;
 use starknet_api::core::{ClassHash, ContractAddress};
 use starknet_api::StarknetApiError;
 use thiserror::Error;
@@ -15,6 +16,22 @@ pub enum StateError {
         constants::STORED_BLOCK_HASH_BUFFER
     )]
     OldBlockHashNotProvided,
+    #[error(
+        "Block {next_block_number} must store the hash of block {expected}, got the hash of block \
+         {actual}."
+    )]
+    InvalidOldBlockNumber {
+        next_block_number: BlockNumber,
+        expected: BlockNumber,
+        actual: BlockNumber,
+    },
+    #[error("Block {block_number} does not follow its parent block {parent_block_number}.")]
+    NonConsecutiveBlockNumber { block_number: BlockNumber, parent_block_number: BlockNumber },
+    #[error(
+        "The timestamp of block {block_number} ({timestamp}) is lower than the timestamp of its \
+         parent ({parent_timestamp})."
+    )]
+    DecreasingBlockTimestamp { block_number: BlockNumber, timestamp: u64, parent_timestamp: u64 },
     #[error("Cannot deploy contract at address 0.")]
     OutOfRangeContractAddress,
     #[error(transparent)]
@@ -1,41 +1,119 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex, MutexGuard};
 
 use cached::{Cached, SizedCache};
 use starknet_api::core::ClassHash;
 
+use crate::execution::call_info::CallInfo;
 use crate::execution::contract_class::ContractClass;
+use crate::transaction::objects::TransactionExecutionInfo;
+
+#[cfg(test)]
+#[path = "global_cache_test.rs"]
+pub mod test;
 
 // Note: `ContractClassLRUCache` key-value types must align with `ContractClassMapping`.
 type ContractClassLRUCache = SizedCache<ClassHash, ContractClass>;
 pub type LockedContractClassCache<'a> = MutexGuard<'a, ContractClassLRUCache>;
+type ClassUsageLRUCache = SizedCache<ClassHash, u64>;
 #[derive(Debug, Clone)]
 // Thread-safe LRU cache for contract classes, optimized for inter-language sharing when
 // `blockifier` compiles as a shared library.
+// The classes of the pinned class hashes (e.g., the fee token and account classes) are kept aside,
+// and are never evicted. The cache also counts the calls to each class, to find the hottest ones;
+// the counts of the least recently used classes are dropped, to bound the tracked classes.
 // TODO(Yoni, 1/1/2025): consider defining CachedStateReader.
-pub struct GlobalContractCache(pub Arc<Mutex<ContractClassLRUCache>>);
+pub struct GlobalContractCache {
+    cache: Arc<Mutex<ContractClassLRUCache>>,
+    pinned_class_hashes: Arc<HashSet<ClassHash>>,
+    pinned_classes: Arc<Mutex<HashMap<ClassHash, ContractClass>>>,
+    class_usage: Arc<Mutex<ClassUsageLRUCache>>,
+}
 
 pub const GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST: usize = 100;
 
 impl GlobalContractCache {
     /// Locks the cache for atomic access. Although conceptually shared, writing to this cache is
     /// only possible for one writer at a time.
+    /// Note: the classes of the pinned class hashes aren't part of the locked cache.
     pub fn lock(&self) -> LockedContractClassCache<'_> {
-        self.0.lock().expect("Global contract cache is poisoned.")
+        self.cache.lock().expect("Global contract cache is poisoned.")
+    }
+
+    fn lock_pinned_classes(&self) -> MutexGuard<'_, HashMap<ClassHash, ContractClass>> {
+        self.pinned_classes.lock().expect("Global contract cache is poisoned.")
+    }
+
+    fn lock_class_usage(&self) -> MutexGuard<'_, ClassUsageLRUCache> {
+        self.class_usage.lock().expect("Global contract cache is poisoned.")
     }
 
     pub fn get(&self, class_hash: &ClassHash) -> Option<ContractClass> {
+        if self.pinned_class_hashes.contains(class_hash) {
+            return self.lock_pinned_classes().get(class_hash).cloned();
+        }
         self.lock().cache_get(class_hash).cloned()
     }
 
     pub fn set(&self, class_hash: ClassHash, contract_class: ContractClass) {
+        if self.pinned_class_hashes.contains(&class_hash) {
+            self.lock_pinned_classes().insert(class_hash, contract_class);
+            return;
+        }
         self.lock().cache_set(class_hash, contract_class);
     }
 
+    /// Clears the cached classes. The classes of the pinned class hashes and the class usage
+    /// statistics are kept.
     pub fn clear(&mut self) {
         self.lock().cache_clear();
     }
 
+    /// Counts the calls to each class in the execution of the given transaction.
+    pub fn record_class_usage(&self, tx_execution_info: &TransactionExecutionInfo) {
+        let mut class_usage = self.lock_class_usage();
+        for call_info in tx_execution_info.non_optional_call_infos().flat_map(CallInfo::iter) {
+            if let Some(class_hash) = call_info.call.class_hash {
+                *class_usage.cache_get_or_set_with(class_hash, || 0) += 1;
+            }
+        }
+    }
+
+    /// Returns the (at most) `n_classes` classes with the most calls, with their number of calls,
+    /// from the hottest one.
+    pub fn hottest_classes(&self, n_classes: usize) -> Vec<(ClassHash, u64)> {
+        let class_usage_cache = self.lock_class_usage();
+        let mut class_usage: Vec<(ClassHash, u64)> = class_usage_cache
+            .key_order()
+            .zip(class_usage_cache.value_order())
+            .map(|(class_hash, n_calls)| (*class_hash, *n_calls))
+            .collect();
+        drop(class_usage_cache);
+        // Break ties by class hash, for a deterministic order.
+        class_usage.sort_unstable_by(|(class_hash_a, n_calls_a), (class_hash_b, n_calls_b)| {
+            n_calls_b.cmp(n_calls_a).then(class_hash_a.cmp(class_hash_b))
+        });
+        class_usage.truncate(n_classes);
+        class_usage
+    }
+
     pub fn new(cache_size: usize) -> Self {
-        Self(Arc::new(Mutex::new(ContractClassLRUCache::with_size(cache_size))))
+        Self::new_with_pinned_classes(cache_size, HashSet::new())
+    }
+
+    /// Creates a cache whose classes of the given class hashes are never evicted. These classes
+    /// don't count towards the cache size.
+    /// The usage of at most `cache_size` classes, besides the pinned ones, is tracked.
+    pub fn new_with_pinned_classes(
+        cache_size: usize,
+        pinned_class_hashes: HashSet<ClassHash>,
+    ) -> Self {
+        let class_usage_size = cache_size + pinned_class_hashes.len();
+        Self {
+            cache: Arc::new(Mutex::new(ContractClassLRUCache::with_size(cache_size))),
+            pinned_class_hashes: Arc::new(pinned_class_hashes),
+            pinned_classes: Arc::new(Mutex::new(HashMap::new())),
+            class_usage: Arc::new(Mutex::new(ClassUsageLRUCache::with_size(class_usage_size))),
+        }
     }
 }
@@ -0,0 +1,95 @@
+use std::collections::HashSet;
+
+use cached::Cached;
+use starknet_api::core::ClassHash;
+use starknet_api::{class_hash, felt};
+
+use crate::execution::call_info::CallInfo;
+use crate::execution::entry_point::CallEntryPoint;
+use crate::state::global_cache::GlobalContractCache;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::CairoVersion;
+use crate::transaction::objects::TransactionExecutionInfo;
+
+fn call_info(class_hash: ClassHash, inner_calls: Vec<CallInfo>) -> CallInfo {
+    CallInfo {
+        call: CallEntryPoint { class_hash: Some(class_hash), ..Default::default() },
+        inner_calls,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn pinned_classes_are_not_evicted() {
+    let pinned_class_hash = class_hash!(1_u8);
+    let global_cache =
+        GlobalContractCache::new_with_pinned_classes(1, HashSet::from([pinned_class_hash]));
+    let contract_class = FeatureContract::TestContract(CairoVersion::Cairo0).get_class();
+
+    global_cache.set(pinned_class_hash, contract_class.clone());
+    for class_hash in [class_hash!(2_u8), class_hash!(3_u8)] {
+        global_cache.set(class_hash, contract_class.clone());
+    }
+
+    assert_eq!(global_cache.get(&pinned_class_hash), Some(contract_class.clone()));
+    assert_eq!(global_cache.get(&class_hash!(2_u8)), None);
+    assert_eq!(global_cache.get(&class_hash!(3_u8)), Some(contract_class));
+    assert_eq!(global_cache.lock().cache_size(), 1);
+}
+
+#[test]
+fn clear_keeps_pinned_classes() {
+    let pinned_class_hash = class_hash!(1_u8);
+    let mut global_cache =
+        GlobalContractCache::new_with_pinned_classes(1, HashSet::from([pinned_class_hash]));
+    let contract_class = FeatureContract::TestContract(CairoVersion::Cairo0).get_class();
+    global_cache.set(pinned_class_hash, contract_class.clone());
+    global_cache.set(class_hash!(2_u8), contract_class.clone());
+
+    global_cache.clear();
+
+    assert_eq!(global_cache.get(&pinned_class_hash), Some(contract_class));
+    assert_eq!(global_cache.get(&class_hash!(2_u8)), None);
+}
+
+// Ties are broken by class hash.
+#[test]
+fn hottest_classes() {
+    let global_cache = GlobalContractCache::new(3);
+    let (account, token, contract) = (class_hash!(1_u8), class_hash!(2_u8), class_hash!(3_u8));
+    let tx_execution_info = TransactionExecutionInfo {
+        validate_call_info: Some(call_info(account, vec![])),
+        execute_call_info: Some(call_info(
+            account,
+            vec![call_info(contract, vec![call_info(token, vec![])])],
+        )),
+        fee_transfer_call_info: Some(call_info(token, vec![])),
+        ..Default::default()
+    };
+
+    global_cache.record_class_usage(&tx_execution_info);
+    assert_eq!(global_cache.hottest_classes(2), vec![(account, 2), (token, 2)]);
+
+    global_cache.record_class_usage(&TransactionExecutionInfo {
+        execute_call_info: Some(call_info(contract, vec![call_info(token, vec![])])),
+        ..Default::default()
+    });
+    assert_eq!(global_cache.hottest_classes(5), vec![(token, 3), (account, 2), (contract, 2)]);
+}
+
+// Only the usage of the most recently used classes is tracked.
+#[test]
+fn class_usage_is_bounded() {
+    let global_cache = GlobalContractCache::new(2);
+    for class_hash in [class_hash!(1_u8), class_hash!(1_u8), class_hash!(2_u8), class_hash!(3_u8)] {
+        global_cache.record_class_usage(&TransactionExecutionInfo {
+            execute_call_info: Some(call_info(class_hash, vec![])),
+            ..Default::default()
+        });
+    }
+
+    assert_eq!(
+        global_cache.hottest_classes(5),
+        vec![(class_hash!(2_u8), 1), (class_hash!(3_u8), 1)]
+    );
+}
@@ -8,6 +8,7 @@ pub mod invoke;
 pub mod prices;
 pub mod struct_impls;
 pub mod transfers_generator;
+pub mod workloads;
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
@@ -20,6 +20,8 @@ use crate::execution::entry_point::{
     EntryPointExecutionContext,
     EntryPointExecutionResult,
 };
+use crate::execution::interrupt::ExecutionInterrupt;
+use crate::fee::fee_multipliers::FeeMultipliers;
 use crate::fee::fee_utils::get_fee_by_gas_vector;
 use crate::state::state_api::State;
 use crate::test_utils::{
@@ -35,6 +37,7 @@ use crate::test_utils::{
     TEST_ERC20_CONTRACT_ADDRESS2,
     TEST_SEQUENCER_ADDRESS,
 };
+use crate::transaction::nonce_manager::NonceConfig;
 use crate::transaction::objects::{
     DeprecatedTransactionInfo,
     FeeType,
@@ -43,6 +46,7 @@ use crate::transaction::objects::{
     TransactionResources,
 };
 use crate::transaction::transactions::L1HandlerTransaction;
+use crate::transaction::version_config::TransactionVersionConfig;
 use crate::versioned_constants::{
     GasCosts,
     OsConstants,
@@ -146,6 +150,12 @@ impl ChainInfo {
                 eth_fee_token_address: contract_address!(TEST_ERC20_CONTRACT_ADDRESS),
                 strk_fee_token_address: contract_address!(TEST_ERC20_CONTRACT_ADDRESS2),
             },
+            eth_fee_token_deprecation_height: None,
+            fee_recipient: None,
+            fee_split: None,
+            fee_multipliers: FeeMultipliers::default(),
+            nonce_config: NonceConfig::default(),
+            tx_version_config: TransactionVersionConfig::default(),
         }
     }
 }
@@ -186,6 +196,7 @@ impl BlockContext {
             chain_info: ChainInfo::create_for_testing(),
             versioned_constants: VersionedConstants::create_for_testing(),
             bouncer_config: BouncerConfig::max(),
+            execution_interrupt: ExecutionInterrupt::default(),
         }
     }
 
@@ -195,6 +206,7 @@ impl BlockContext {
             chain_info: ChainInfo::create_for_testing(),
             versioned_constants: VersionedConstants::create_for_account_testing(),
             bouncer_config: BouncerConfig::max(),
+            execution_interrupt: ExecutionInterrupt::default(),
         }
     }
 
@@ -205,6 +217,7 @@ impl BlockContext {
                     n_events: max_n_events_in_block,
                     ..BouncerWeights::max()
                 },
+                ..BouncerConfig::max()
             },
             ..Self::create_for_account_testing()
         }
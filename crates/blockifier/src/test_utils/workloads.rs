@@ -0,0 +1,217 @@
+//! Representative workloads for benchmarking the transaction executor.
+//!
+//! Each workload is a stream of invoke transactions, sent round-robin from a set of funded
+//! accounts, that stresses a different part of the execution:
+//! * [`Workload::Erc20Transfers`] - ERC20 transfers, the most common transaction on Starknet.
+//! * [`Workload::KeccakHashing`] - hashing with the keccak syscall of the test contract.
+//! * [`Workload::DeepCallTree`] - a chain of nested contract calls.
+//! * [`Workload::LargeCalldata`] - a call whose calldata is deserialized and then ignored.
+
+use starknet_api::core::ContractAddress;
+use starknet_api::felt;
+use starknet_api::transaction::{Calldata, Fee, TransactionVersion};
+use starknet_types_core::felt::Felt;
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::blockifier::config::{ConcurrencyConfig, TransactionExecutorConfig};
+use crate::blockifier::transaction_executor::TransactionExecutor;
+use crate::context::{BlockContext, ChainInfo};
+use crate::invoke_tx_args;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::invoke::invoke_tx;
+use crate::test_utils::{create_calldata, CairoVersion, NonceManager, BALANCE, MAX_FEE};
+use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::constants::TRANSFER_ENTRY_POINT_NAME;
+use crate::transaction::transaction_execution::Transaction;
+
+const N_ACCOUNTS: u16 = 1000;
+const N_TXS: usize = 100;
+const CAIRO_VERSION: CairoVersion = CairoVersion::Cairo1;
+const N_WORKERS: usize = 4;
+const CHUNK_SIZE: usize = 100;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Workload {
+    Erc20Transfers,
+    // TODO: Add a Poseidon hashing workload once the test contract has an entry point that hashes
+    // with the Poseidon builtin.
+    KeccakHashing,
+    /// Each call calls the test contract again, down to the given depth.
+    DeepCallTree {
+        depth: u8,
+    },
+    LargeCalldata {
+        calldata_length: usize,
+    },
+}
+
+impl Workload {
+    /// A short name of the workload, for naming benchmarks.
+    pub fn name(&self) -> String {
+        match self {
+            Self::Erc20Transfers => "erc20_transfers".to_string(),
+            Self::KeccakHashing => "keccak_hashing".to_string(),
+            Self::DeepCallTree { depth } => format!("deep_call_tree_{depth}"),
+            Self::LargeCalldata { calldata_length } => format!("large_calldata_{calldata_length}"),
+        }
+    }
+}
+
+pub struct WorkloadGeneratorConfig {
+    pub workload: Workload,
+    pub n_accounts: u16,
+    /// The number of transactions executed in each batch.
+    pub n_txs: usize,
+    pub cairo_version: CairoVersion,
+    pub concurrency_config: ConcurrencyConfig,
+}
+
+impl WorkloadGeneratorConfig {
+    pub fn new(workload: Workload) -> Self {
+        Self {
+            workload,
+            n_accounts: N_ACCOUNTS,
+            n_txs: N_TXS,
+            cairo_version: CAIRO_VERSION,
+            concurrency_config: ConcurrencyConfig::default(),
+        }
+    }
+
+    /// Executes the transactions concurrently, with the default number of workers and chunk size.
+    pub fn concurrent(self) -> Self {
+        Self {
+            concurrency_config: ConcurrencyConfig {
+                enabled: true,
+                n_workers: N_WORKERS,
+                chunk_size: CHUNK_SIZE,
+            },
+            ..self
+        }
+    }
+}
+
+/// Generates the transactions of a [`Workload`] and executes them in batches on a single executor,
+/// whose state is kept between the batches.
+pub struct WorkloadGenerator {
+    account_addresses: Vec<ContractAddress>,
+    test_contract_address: ContractAddress,
+    chain_info: ChainInfo,
+    executor: TransactionExecutor<DictStateReader>,
+    nonce_manager: NonceManager,
+    sender_index: usize,
+    config: WorkloadGeneratorConfig,
+}
+
+impl WorkloadGenerator {
+    pub fn new(config: WorkloadGeneratorConfig) -> Self {
+        let account_contract = FeatureContract::AccountWithoutValidations(config.cairo_version);
+        let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+        let block_context = BlockContext::create_for_account_testing();
+        let chain_info = block_context.chain_info().clone();
+        let state = test_state(
+            &chain_info,
+            BALANCE * 1000,
+            &[(account_contract, config.n_accounts), (test_contract, 1)],
+        );
+        let executor_config = TransactionExecutorConfig {
+            concurrency_config: config.concurrency_config.clone(),
+            record_read_sets: false,
+        };
+        let executor = TransactionExecutor::new(state, block_context, executor_config);
+        let account_addresses = (0..config.n_accounts)
+            .map(|instance_id| account_contract.get_instance_address(instance_id))
+            .collect();
+        Self {
+            account_addresses,
+            test_contract_address: test_contract.get_instance_address(0),
+            chain_info,
+            executor,
+            nonce_manager: NonceManager::default(),
+            sender_index: 0,
+            config,
+        }
+    }
+
+    /// Generates the next batch of transactions and executes it, asserting that all of them
+    /// succeeded.
+    pub fn execute_batch(&mut self) {
+        let txs: Vec<Transaction> = (0..self.config.n_txs)
+            .map(|_| Transaction::AccountTransaction(self.generate_tx()))
+            .collect();
+        let results = self.executor.execute_txs(&txs);
+        assert_eq!(results.len(), self.config.n_txs);
+        for result in results {
+            let execution_info = result.unwrap();
+            assert!(
+                !execution_info.is_reverted(),
+                "A {} transaction reverted: {:?}",
+                self.config.workload.name(),
+                execution_info.revert_error
+            );
+        }
+    }
+
+    fn generate_tx(&mut self) -> AccountTransaction {
+        let sender_address = self.account_addresses[self.sender_index];
+        self.sender_index = (self.sender_index + 1) % self.account_addresses.len();
+        let calldata = self.execute_calldata();
+        let tx = invoke_tx(invoke_tx_args! {
+            max_fee: Fee(MAX_FEE),
+            sender_address,
+            calldata,
+            version: TransactionVersion::THREE,
+            nonce: self.nonce_manager.next(sender_address),
+        });
+        AccountTransaction::Invoke(tx)
+    }
+
+    // The calldata of the account's __execute__ for the next transaction.
+    fn execute_calldata(&self) -> Calldata {
+        let test_contract_address = *self.test_contract_address.0.key();
+        match self.config.workload {
+            Workload::Erc20Transfers => {
+                // Transfer to the next account, round-robin.
+                let recipient_address = self.account_addresses[self.sender_index];
+                create_calldata(
+                    self.chain_info.fee_token_addresses.strk_fee_token_address,
+                    TRANSFER_ENTRY_POINT_NAME,
+                    &[
+                        *recipient_address.0.key(), // Recipient.
+                        felt!(1_u8),                // LSB amount.
+                        felt!(0_u8),                // MSB amount.
+                    ],
+                )
+            }
+            Workload::KeccakHashing => {
+                create_calldata(self.test_contract_address, "test_keccak", &[])
+            }
+            Workload::DeepCallTree { depth } => create_calldata(
+                self.test_contract_address,
+                "recursive_syscall",
+                &[
+                    test_contract_address,                     // Contract address.
+                    selector_from_name("recursive_syscall").0, // Selector.
+                    felt!(depth),                              // Depth.
+                ],
+            ),
+            Workload::LargeCalldata { calldata_length } => {
+                // A call chain that ends with an inner call of an unknown selector, which returns
+                // without reading the rest of the chain.
+                let call_chain_length = calldata_length + 3;
+                let call_chain = [
+                    vec![
+                        felt!(u64::try_from(call_chain_length).unwrap()), // Array length.
+                        test_contract_address,                            // Contract address.
+                        Felt::ZERO,                                       // Selector.
+                        Felt::TWO,                                        // Call type.
+                    ],
+                    vec![Felt::ONE; calldata_length],
+                ]
+                .concat();
+                create_calldata(self.test_contract_address, "invoke_call_chain", &call_chain)
+            }
+        }
+    }
+}
@@ -3,6 +3,7 @@ pub mod constants;
 #[cfg(test)]
 pub mod error_format_test;
 pub mod errors;
+pub mod nonce_manager;
 pub mod objects;
 #[cfg(any(feature = "testing", test))]
 pub mod test_utils;
@@ -10,3 +11,4 @@ pub mod transaction_execution;
 pub mod transaction_types;
 pub mod transaction_utils;
 pub mod transactions;
+pub mod version_config;
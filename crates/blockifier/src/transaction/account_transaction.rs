@@ -7,9 +7,11 @@ use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::transaction::{
     Calldata,
     Fee,
+    Resource,
     ResourceBounds,
     TransactionHash,
     TransactionVersion,
+    ValidResourceBounds,
 };
 use starknet_types_core::felt::Felt;
 
@@ -18,6 +20,8 @@ use crate::context::{BlockContext, TransactionContext};
 use crate::execution::call_info::{CallInfo, Retdata};
 use crate::execution::contract_class::ContractClass;
 use crate::execution::entry_point::{CallEntryPoint, CallType, EntryPointExecutionContext};
+use crate::execution::panic_reason::panic_data;
+use crate::execution::syscalls::hint_processor::EmitEventError;
 use crate::fee::actual_cost::TransactionReceipt;
 use crate::fee::fee_checks::{FeeCheckReportFields, PostExecutionReport};
 use crate::fee::fee_utils::{
@@ -31,12 +35,13 @@ use crate::state::cached_state::{StateChanges, TransactionalState};
 use crate::state::state_api::{State, StateReader, UpdatableState};
 use crate::transaction::constants;
 use crate::transaction::errors::{
+    ResourceBoundsError,
     TransactionExecutionError,
     TransactionFeeError,
     TransactionInfoCreationError,
-    TransactionPreValidationError,
 };
 use crate::transaction::objects::{
+    CurrentTransactionInfo,
     DeprecatedTransactionInfo,
     HasRelatedFeeType,
     TransactionExecutionInfo,
@@ -56,6 +61,7 @@ use crate::transaction::transactions::{
     InvokeTransaction,
     ValidatableTransaction,
 };
+use crate::versioned_constants::VersionedConstants;
 
 #[cfg(test)]
 #[path = "account_transactions_test.rs"]
@@ -214,7 +220,17 @@ impl AccountTransaction {
         strict_nonce_check: bool,
     ) -> TransactionPreValidationResult<()> {
         let tx_info = &tx_context.tx_info;
-        Self::handle_nonce(state, tx_info, strict_nonce_check)?;
+        let block_context = &tx_context.block_context;
+        // Rejects a transaction that pays its fee in a deprecated fee token.
+        block_context.chain_info.fee_token_address_in_block(
+            &tx_info.fee_type(),
+            block_context.block_info.block_number,
+        )?;
+        block_context.chain_info.nonce_manager().handle_nonce(
+            state,
+            tx_info,
+            strict_nonce_check,
+        )?;
 
         if charge_fee && tx_info.enforce_fee() {
             self.check_fee_bounds(tx_context)?;
@@ -239,6 +255,53 @@ impl AccountTransaction {
         let block_info = &block_context.block_info;
         let fee_type = &tx_info.fee_type();
         match tx_info {
+            TransactionInfo::Current(CurrentTransactionInfo {
+                resource_bounds: ValidResourceBounds::AllResources(resource_bounds),
+                ..
+            }) => {
+                let gas_prices = &block_info.gas_prices;
+                // Unlike L1 gas bounds, each resource is bounded separately, so the minimal gas
+                // isn't discounted.
+                let bounds_and_minimal_usage = [
+                    (
+                        Resource::L1Gas,
+                        minimal_l1_gas_amount_vector.l1_gas,
+                        gas_prices.get_l1_gas_price_by_fee_type(fee_type),
+                    ),
+                    (
+                        Resource::L1DataGas,
+                        minimal_l1_gas_amount_vector.l1_data_gas,
+                        gas_prices.get_l1_data_gas_price_by_fee_type(fee_type),
+                    ),
+                    (
+                        Resource::L2Gas,
+                        minimal_l1_gas_amount_vector.l2_gas,
+                        gas_prices.get_l2_gas_price_by_fee_type(fee_type),
+                    ),
+                ];
+                let mut errors = Vec::new();
+                for (resource, minimal_gas_amount, actual_gas_price) in bounds_and_minimal_usage {
+                    let ResourceBounds { max_amount, max_price_per_unit } =
+                        resource_bounds.get_bound(resource);
+                    if u128::from(max_amount) < minimal_gas_amount {
+                        errors.push(ResourceBoundsError::MaxGasAmountTooLow {
+                            resource,
+                            max_gas_amount: max_amount,
+                            minimal_gas_amount,
+                        });
+                    }
+                    if max_price_per_unit < actual_gas_price.into() {
+                        errors.push(ResourceBoundsError::MaxGasPriceTooLow {
+                            resource,
+                            max_gas_price: max_price_per_unit,
+                            actual_gas_price: actual_gas_price.into(),
+                        });
+                    }
+                }
+                if !errors.is_empty() {
+                    return Err(TransactionFeeError::InsufficientResourceBounds { errors })?;
+                }
+            }
             TransactionInfo::Current(context) => {
                 let ResourceBounds {
                     max_amount: max_l1_gas_amount,
@@ -278,33 +341,6 @@ impl AccountTransaction {
         Ok(())
     }
 
-    fn handle_nonce(
-        state: &mut dyn State,
-        tx_info: &TransactionInfo,
-        strict: bool,
-    ) -> TransactionPreValidationResult<()> {
-        if tx_info.is_v0() {
-            return Ok(());
-        }
-
-        let address = tx_info.sender_address();
-        let account_nonce = state.get_nonce_at(address)?;
-        let incoming_tx_nonce = tx_info.nonce();
-        let valid_nonce = if strict {
-            account_nonce == incoming_tx_nonce
-        } else {
-            account_nonce <= incoming_tx_nonce
-        };
-        if valid_nonce {
-            return Ok(state.increment_nonce(address)?);
-        }
-        Err(TransactionPreValidationError::InvalidNonce {
-            address,
-            account_nonce,
-            incoming_tx_nonce,
-        })
-    }
-
     fn handle_validate_tx(
         &self,
         state: &mut dyn State,
@@ -324,15 +360,10 @@ impl AccountTransaction {
     fn assert_actual_fee_in_bounds(tx_context: &Arc<TransactionContext>, actual_fee: Fee) {
         match &tx_context.tx_info {
             TransactionInfo::Current(context) => {
-                let ResourceBounds {
-                    max_amount: max_l1_gas_amount,
-                    max_price_per_unit: max_l1_gas_price,
-                } = context.l1_resource_bounds();
-                if actual_fee > Fee(u128::from(max_l1_gas_amount) * max_l1_gas_price) {
+                if actual_fee > context.max_possible_fee() {
                     panic!(
-                        "Actual fee {:#?} exceeded bounds; max amount is {:#?}, max price is
-                         {:#?}.",
-                        actual_fee, max_l1_gas_amount, max_l1_gas_price
+                        "Actual fee {:#?} exceeded bounds; resource bounds are {:#?}.",
+                        actual_fee, context.resource_bounds
                     );
                 }
             }
@@ -354,27 +385,43 @@ impl AccountTransaction {
         actual_fee: Fee,
         charge_fee: bool,
         concurrency_mode: bool,
-    ) -> TransactionExecutionResult<Option<CallInfo>> {
+    ) -> TransactionExecutionResult<FeeTransferCallInfo> {
         if !charge_fee || actual_fee == Fee(0) {
             // Fee charging is not enforced in some transaction simulations and tests.
-            return Ok(None);
+            return Ok(FeeTransferCallInfo::default());
         }
 
         // TODO(Amos, 8/04/2024): Add test for this assert.
         Self::assert_actual_fee_in_bounds(&tx_context, actual_fee);
 
-        let fee_transfer_call_info = if concurrency_mode && !tx_context.is_sequencer_the_sender() {
-            Self::concurrency_execute_fee_transfer(state, tx_context, actual_fee)?
-        } else {
-            Self::execute_fee_transfer(state, tx_context, actual_fee)?
+        let (recipient_fee, split_fee) = tx_context.block_context.chain_info.split_fee(actual_fee);
+        let fee_transfer_call_info =
+            if concurrency_mode && !tx_context.is_fee_recipient_the_sender() {
+                Self::concurrency_execute_fee_transfer(state, tx_context.clone(), recipient_fee)?
+            } else {
+                let fee_recipient = tx_context.block_context.fee_recipient();
+                Self::execute_fee_transfer(state, tx_context.clone(), fee_recipient, recipient_fee)?
+            };
+        let fee_split_transfer_call_info = match &tx_context.block_context.chain_info.fee_split {
+            Some(fee_split) if split_fee > Fee(0) => Some(Self::execute_fee_transfer(
+                state,
+                tx_context.clone(),
+                fee_split.recipient,
+                split_fee,
+            )?),
+            _ => None,
         };
 
-        Ok(Some(fee_transfer_call_info))
+        Ok(FeeTransferCallInfo {
+            fee_transfer_call_info: Some(fee_transfer_call_info),
+            fee_split_transfer_call_info,
+        })
     }
 
     fn execute_fee_transfer(
         state: &mut dyn State,
         tx_context: Arc<TransactionContext>,
+        recipient: ContractAddress,
         actual_fee: Fee,
     ) -> TransactionExecutionResult<CallInfo> {
         // The least significant 128 bits of the amount transferred.
@@ -389,11 +436,7 @@ impl AccountTransaction {
             code_address: None,
             entry_point_type: EntryPointType::External,
             entry_point_selector: selector_from_name(constants::TRANSFER_ENTRY_POINT_NAME),
-            calldata: calldata![
-                *block_context.block_info.sequencer_address.0.key(), // Recipient.
-                lsb_amount,
-                msb_amount
-            ],
+            calldata: calldata![*recipient.0.key(), lsb_amount, msb_amount],
             storage_address,
             caller_address: tx_info.sender_address(),
             call_type: CallType::Call,
@@ -430,8 +473,13 @@ impl AccountTransaction {
             cache.set_storage_initial_value(fee_address, key, Felt::ZERO);
         }
 
-        let fee_transfer_call_info =
-            AccountTransaction::execute_fee_transfer(&mut transfer_state, tx_context, actual_fee);
+        let fee_recipient = tx_context.block_context.fee_recipient();
+        let fee_transfer_call_info = AccountTransaction::execute_fee_transfer(
+            &mut transfer_state,
+            tx_context,
+            fee_recipient,
+            actual_fee,
+        );
         // Commit without updating the sequencer balance.
         let storage_writes = &mut transfer_state.cache.get_mut().writes.storage;
         storage_writes.remove(&(fee_address, sequencer_balance_key_low));
@@ -495,6 +543,10 @@ impl AccountTransaction {
             execute_call_info =
                 self.run_execute(state, &mut resources, &mut execution_context, remaining_gas)?;
         }
+        verify_tx_event_limits(
+            tx_context.block_context.versioned_constants(),
+            validate_call_info.iter().chain(execute_call_info.iter()),
+        )?;
 
         let tx_receipt = TransactionReceipt::from_account_tx(
             self,
@@ -553,12 +605,20 @@ impl AccountTransaction {
         let mut execution_resources = resources.clone();
         let mut execution_state = TransactionalState::create_transactional(state);
 
-        let execution_result = self.run_execute(
-            &mut execution_state,
-            &mut execution_resources,
-            &mut execution_context,
-            remaining_gas,
-        );
+        let execution_result = self
+            .run_execute(
+                &mut execution_state,
+                &mut execution_resources,
+                &mut execution_context,
+                remaining_gas,
+            )
+            .and_then(|execute_call_info| {
+                verify_tx_event_limits(
+                    tx_context.block_context.versioned_constants(),
+                    validate_call_info.iter().chain(execute_call_info.iter()),
+                )?;
+                Ok(execute_call_info)
+            });
 
         // Pre-compute cost in case of revert.
         let execution_steps_consumed =
@@ -604,6 +664,7 @@ impl AccountTransaction {
                         Ok(ValidateExecuteCallInfo::new_reverted(
                             validate_call_info,
                             post_execution_error.to_string(),
+                            None,
                             TransactionReceipt {
                                 fee: post_execution_report.recommended_fee(),
                                 ..revert_cost
@@ -629,6 +690,7 @@ impl AccountTransaction {
                 Ok(ValidateExecuteCallInfo::new_reverted(
                     validate_call_info,
                     execution_error.to_string(),
+                    panic_data(&execution_error).map(<[Felt]>::to_vec),
                     TransactionReceipt {
                         fee: post_execution_report.recommended_fee(),
                         ..revert_cost
@@ -683,6 +745,11 @@ impl<U: UpdatableState> ExecutableTransaction<U> for AccountTransaction {
     ) -> TransactionExecutionResult<TransactionExecutionInfo> {
         let tx_context = Arc::new(block_context.to_tx_context(self)?);
         self.verify_tx_version(tx_context.tx_info.version())?;
+        block_context.chain_info.tx_version_config.validate_version(
+            self.tx_type(),
+            tx_context.tx_info.version(),
+            block_context.block_info.block_number,
+        )?;
 
         // Nonce and fee check should be done before running user code.
         let strict_nonce_check = true;
@@ -699,6 +766,7 @@ impl<U: UpdatableState> ExecutableTransaction<U> for AccountTransaction {
             validate_call_info,
             execute_call_info,
             revert_error,
+            revert_panic_data,
             final_cost:
                 TransactionReceipt {
                     fee: final_fee,
@@ -713,18 +781,20 @@ impl<U: UpdatableState> ExecutableTransaction<U> for AccountTransaction {
             execution_flags.validate,
             execution_flags.charge_fee,
         )?;
-        let fee_transfer_call_info = self.handle_fee(
-            state,
-            tx_context,
-            final_fee,
-            execution_flags.charge_fee,
-            execution_flags.concurrency_mode,
-        )?;
+        let FeeTransferCallInfo { fee_transfer_call_info, fee_split_transfer_call_info } = self
+            .handle_fee(
+                state,
+                tx_context,
+                final_fee,
+                execution_flags.charge_fee,
+                execution_flags.concurrency_mode,
+            )?;
 
         let tx_execution_info = TransactionExecutionInfo {
             validate_call_info,
             execute_call_info,
             fee_transfer_call_info,
+            fee_split_transfer_call_info,
             receipt: TransactionReceipt {
                 fee: final_fee,
                 da_gas: final_da_gas,
@@ -732,6 +802,8 @@ impl<U: UpdatableState> ExecutableTransaction<U> for AccountTransaction {
                 gas: total_gas,
             },
             revert_error,
+            revert_panic_data,
+            read_set: None,
         };
         Ok(tx_execution_info)
     }
@@ -748,11 +820,19 @@ impl TransactionInfoCreator for AccountTransaction {
     }
 }
 
+/// The call infos of the transfers of the transaction fee.
+#[derive(Default)]
+struct FeeTransferCallInfo {
+    fee_transfer_call_info: Option<CallInfo>,
+    fee_split_transfer_call_info: Option<CallInfo>,
+}
+
 /// Represents a bundle of validate-execute stage execution effects.
 struct ValidateExecuteCallInfo {
     validate_call_info: Option<CallInfo>,
     execute_call_info: Option<CallInfo>,
     revert_error: Option<String>,
+    revert_panic_data: Option<Vec<Felt>>,
     final_cost: TransactionReceipt,
 }
 
@@ -762,18 +842,26 @@ impl ValidateExecuteCallInfo {
         execute_call_info: Option<CallInfo>,
         final_cost: TransactionReceipt,
     ) -> Self {
-        Self { validate_call_info, execute_call_info, revert_error: None, final_cost }
+        Self {
+            validate_call_info,
+            execute_call_info,
+            revert_error: None,
+            revert_panic_data: None,
+            final_cost,
+        }
     }
 
     pub fn new_reverted(
         validate_call_info: Option<CallInfo>,
         revert_error: String,
+        revert_panic_data: Option<Vec<Felt>>,
         final_cost: TransactionReceipt,
     ) -> Self {
         Self {
             validate_call_info,
             execute_call_info: None,
             revert_error: Some(revert_error),
+            revert_panic_data,
             final_cost,
         }
     }
@@ -838,3 +926,26 @@ impl ValidatableTransaction for AccountTransaction {
         Ok(Some(validate_call_info))
     }
 }
+
+/// Verifies that the number of events emitted by the given calls of a transaction is within the
+/// limit, if the versioned constants limit the events of the transaction as a whole. The limits of
+/// each event, and of the events of each call, are verified when the events are emitted.
+fn verify_tx_event_limits<'a>(
+    versioned_constants: &VersionedConstants,
+    call_infos: impl Iterator<Item = &'a CallInfo>,
+) -> TransactionExecutionResult<()> {
+    if !versioned_constants.tx_event_limits_include_validation {
+        return Ok(());
+    }
+    let n_emitted_events: usize =
+        call_infos.flat_map(CallInfo::iter).map(|call_info| call_info.execution.events.len()).sum();
+    let max_n_emitted_events = versioned_constants.tx_event_limits.max_n_emitted_events;
+    if n_emitted_events > max_n_emitted_events {
+        return Err(EmitEventError::ExceedsMaxNumberOfEmittedEvents {
+            n_emitted_events,
+            max_n_emitted_events,
+        }
+        .into());
+    }
+    Ok(())
+}
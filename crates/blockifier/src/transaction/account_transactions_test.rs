@@ -25,7 +25,7 @@ use crate::abi::abi_utils::{
     get_storage_var_address,
     selector_from_name,
 };
-use crate::context::BlockContext;
+use crate::context::{BlockContext, FeeSplit};
 use crate::execution::contract_class::{ContractClass, ContractClassV1};
 use crate::execution::entry_point::EntryPointExecutionContext;
 use crate::execution::syscalls::SyscallSelector;
@@ -1145,8 +1145,12 @@ fn test_count_actual_storage_changes(
         nonce: nonce_manager.next(account_address),
     };
     let account_tx = account_invoke_tx(invoke_args.clone());
-    let execution_flags =
-        ExecutionFlags { charge_fee: true, validate: true, concurrency_mode: false };
+    let execution_flags = ExecutionFlags {
+        charge_fee: true,
+        validate: true,
+        concurrency_mode: false,
+        record_read_set: false,
+    };
     let execution_info =
         account_tx.execute_raw(&mut state, &block_context, execution_flags).unwrap();
 
@@ -1169,8 +1173,11 @@ fn test_count_actual_storage_changes(
         expected_sequencer_fee_update,
     ]);
 
-    let state_changes_count_1 =
-        state_changes_1.clone().count_for_fee_charge(Some(account_address), fee_token_address);
+    let state_changes_count_1 = state_changes_1.clone().count_for_fee_charge(
+        Some(account_address),
+        None,
+        fee_token_address,
+    );
     let expected_state_changes_count_1 = StateChangesCount {
         // See expected storage updates.
         n_storage_updates: 3,
@@ -1206,8 +1213,11 @@ fn test_count_actual_storage_changes(
     let expected_storage_updates_2 =
         HashMap::from([account_balance_storage_change, expected_sequencer_fee_update]);
 
-    let state_changes_count_2 =
-        state_changes_2.clone().count_for_fee_charge(Some(account_address), fee_token_address);
+    let state_changes_count_2 = state_changes_2.clone().count_for_fee_charge(
+        Some(account_address),
+        None,
+        fee_token_address,
+    );
     let expected_state_changes_count_2 = StateChangesCount {
         // See expected storage updates.
         n_storage_updates: 2,
@@ -1251,9 +1261,11 @@ fn test_count_actual_storage_changes(
         expected_sequencer_fee_update,
     ]);
 
-    let state_changes_count_3 = state_changes_transfer
-        .clone()
-        .count_for_fee_charge(Some(account_address), fee_token_address);
+    let state_changes_count_3 = state_changes_transfer.clone().count_for_fee_charge(
+        Some(account_address),
+        None,
+        fee_token_address,
+    );
     let expected_state_changes_count_3 = StateChangesCount {
         // See expected storage updates.
         n_storage_updates: 3,
@@ -1304,8 +1316,12 @@ fn test_concurrency_execute_fee_transfer(
     // Case 1: The transaction did not read form/ write to the sequenser balance before executing
     // fee transfer.
     let mut transactional_state = TransactionalState::create_transactional(state);
-    let execution_flags =
-        ExecutionFlags { charge_fee: true, validate: true, concurrency_mode: true };
+    let execution_flags = ExecutionFlags {
+        charge_fee: true,
+        validate: true,
+        concurrency_mode: true,
+        record_read_set: false,
+    };
     let result =
         account_tx.execute_raw(&mut transactional_state, &block_context, execution_flags).unwrap();
     assert!(!result.is_reverted());
@@ -1400,8 +1416,12 @@ fn test_concurrent_fee_transfer_when_sender_is_sequencer(
     let fee_token_address = block_context.chain_info.fee_token_address(fee_type);
 
     let mut transactional_state = TransactionalState::create_transactional(state);
-    let execution_flags =
-        ExecutionFlags { charge_fee: true, validate: true, concurrency_mode: true };
+    let execution_flags = ExecutionFlags {
+        charge_fee: true,
+        validate: true,
+        concurrency_mode: true,
+        record_read_set: false,
+    };
     let result =
         account_tx.execute_raw(&mut transactional_state, &block_context, execution_flags).unwrap();
     assert!(!result.is_reverted());
@@ -1412,3 +1432,76 @@ fn test_concurrent_fee_transfer_when_sender_is_sequencer(
         assert_eq!(state.get_storage_at(fee_token_address, seq_key).unwrap(), felt!(seq_value));
     }
 }
+
+// Check that the fee goes to the fee recipient of the chain instead of the sequencer, except for
+// the fee split, which goes to its own recipient.
+#[rstest]
+fn test_fee_recipient_and_fee_split(max_resource_bounds: DeprecatedResourceBoundsMapping) {
+    let fee_recipient = contract_address!("0x1234");
+    let treasury = contract_address!("0x5678");
+    let mut block_context = BlockContext::create_for_account_testing();
+    block_context.chain_info.fee_recipient = Some(fee_recipient);
+    block_context.chain_info.fee_split = Some(FeeSplit { recipient: treasury, percentage: 25 });
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let chain_info = &block_context.chain_info;
+    let state = &mut test_state(chain_info, BALANCE, &[(account, 1), (test_contract, 1)]);
+    let account_tx = account_invoke_tx(invoke_tx_args! {
+        sender_address: account.get_instance_address(0),
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+        resource_bounds: max_resource_bounds,
+        version: TransactionVersion::THREE
+    });
+    let fee_token_address = chain_info.fee_token_address(&account_tx.fee_type());
+    let sequencer_address = block_context.block_info.sequencer_address;
+    let initial_sequencer_balance =
+        state.get_fee_token_balance(sequencer_address, fee_token_address).unwrap();
+
+    let mut transactional_state = TransactionalState::create_transactional(state);
+    let execution_flags = ExecutionFlags {
+        charge_fee: true,
+        validate: true,
+        concurrency_mode: false,
+        record_read_set: false,
+    };
+    let execution_info = account_tx
+        .clone()
+        .execute_raw(&mut transactional_state, &block_context, execution_flags)
+        .unwrap();
+    assert!(!execution_info.is_reverted());
+    assert!(execution_info.fee_split_transfer_call_info.is_some());
+
+    // The sender pays for the split transfer.
+    let mut block_context_without_split = block_context.clone();
+    block_context_without_split.chain_info.fee_split = None;
+    let state_without_split =
+        &mut test_state(chain_info, BALANCE, &[(account, 1), (test_contract, 1)]);
+    let execution_info_without_split = account_tx
+        .execute_raw(
+            &mut TransactionalState::create_transactional(state_without_split),
+            &block_context_without_split,
+            execution_flags,
+        )
+        .unwrap();
+    let receipt_without_split = execution_info_without_split.receipt;
+    assert!(execution_info.receipt.fee > receipt_without_split.fee);
+    assert_eq!(
+        execution_info.receipt.resources.starknet_resources.state_changes_for_fee.n_storage_updates,
+        receipt_without_split.resources.starknet_resources.state_changes_for_fee.n_storage_updates
+            + 1
+    );
+
+    let (recipient_fee, split_fee) = block_context.chain_info.split_fee(execution_info.receipt.fee);
+    assert_eq!(recipient_fee.0 + split_fee.0, execution_info.receipt.fee.0);
+    assert_eq!(split_fee.0, execution_info.receipt.fee.0 / 4);
+    for (address, expected_balance) in [
+        (fee_recipient, felt!(recipient_fee.0)),
+        (treasury, felt!(split_fee.0)),
+        (sequencer_address, initial_sequencer_balance.0),
+    ] {
+        assert_eq!(
+            transactional_state.get_fee_token_balance(address, fee_token_address).unwrap(),
+            (expected_balance, Felt::ZERO)
+        );
+    }
+}
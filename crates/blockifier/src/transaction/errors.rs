@@ -1,7 +1,8 @@
 use cairo_vm::types::errors::program_errors::ProgramError;
 use num_bigint::BigUint;
+use starknet_api::block::BlockNumber;
 use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, Nonce};
-use starknet_api::transaction::{Fee, TransactionVersion};
+use starknet_api::transaction::{AllResourceBounds, Fee, Resource, TransactionVersion};
 use starknet_api::StarknetApiError;
 use starknet_types_core::felt::FromStrError;
 use thiserror::Error;
@@ -9,14 +10,21 @@ use thiserror::Error;
 use crate::execution::call_info::Retdata;
 use crate::execution::errors::{ConstructorEntryPointExecutionError, EntryPointExecutionError};
 use crate::execution::stack_trace::gen_transaction_execution_error_trace;
+use crate::execution::syscalls::hint_processor::EmitEventError;
 use crate::fee::fee_checks::FeeCheckError;
 use crate::state::errors::StateError;
+use crate::transaction::transaction_types::TransactionType;
 
 // TODO(Yoni, 1/9/2024): implement Display for Fee.
 #[derive(Debug, Error)]
 pub enum TransactionFeeError {
     #[error("Cairo resource names must be contained in fee cost dict.")]
     CairoResourcesNotContainedInFeeCosts,
+    #[error(
+        "The ETH fee token is deprecated from block {deprecation_height}; got a transaction that \
+         pays its fee in ETH in block {block_number}."
+    )]
+    DeprecatedFeeToken { block_number: BlockNumber, deprecation_height: BlockNumber },
     #[error(transparent)]
     ExecuteFeeTransferError(#[from] EntryPointExecutionError),
     #[error("Actual fee ({}) exceeded max fee ({}).", actual_fee.0, max_fee.0)]
@@ -44,10 +52,32 @@ pub enum TransactionFeeError {
     MaxL1GasAmountTooLow { max_l1_gas_amount: u64, minimal_l1_gas_amount: u64 },
     #[error("Missing L1 gas bounds in resource bounds.")]
     MissingL1GasBounds,
+    #[error(
+        "Insufficient resource bounds: {}",
+        errors.iter().map(ToString::to_string).collect::<Vec<_>>().join(" ")
+    )]
+    InsufficientResourceBounds { errors: Vec<ResourceBoundsError> },
+    #[error("Resource bounds ({resource_bounds:?}) exceed balance ({balance}).")]
+    ResourceBoundsExceedBalance { resource_bounds: AllResourceBounds, balance: BigUint },
     #[error(transparent)]
     StateError(#[from] StateError),
 }
 
+/// A resource whose bounds don't cover the transaction, found before it's executed.
+#[derive(Clone, Copy, Debug, Error, Eq, PartialEq)]
+pub enum ResourceBoundsError {
+    #[error(
+        "Max {resource:?} price ({max_gas_price}) is lower than the actual gas price: \
+         {actual_gas_price}."
+    )]
+    MaxGasPriceTooLow { resource: Resource, max_gas_price: u128, actual_gas_price: u128 },
+    #[error(
+        "Max {resource:?} amount ({max_gas_amount}) is lower than the minimal gas amount: \
+         {minimal_gas_amount}."
+    )]
+    MaxGasAmountTooLow { resource: Resource, max_gas_amount: u64, minimal_gas_amount: u128 },
+}
+
 #[derive(Debug, Error)]
 pub enum TransactionExecutionError {
     #[error(
@@ -62,6 +92,17 @@ pub enum TransactionExecutionError {
     ContractConstructorExecutionFailed(#[from] ConstructorEntryPointExecutionError),
     #[error("Class with hash {:#064x} is already declared.", **class_hash)]
     DeclareTransactionError { class_hash: ClassHash },
+    #[error(
+        "{tx_type:?} transactions of version {} are deprecated, the minimal version is {}.",
+        **version, **min_version
+    )]
+    DeprecatedVersion {
+        tx_type: TransactionType,
+        version: TransactionVersion,
+        min_version: TransactionVersion,
+    },
+    #[error(transparent)]
+    EmitEventError(#[from] EmitEventError),
     #[error(
         "Transaction execution has failed:\n{}",
         String::from(gen_transaction_execution_error_trace(self))
@@ -0,0 +1,161 @@
+//! Checking the nonces of account transactions.
+//!
+//! The nonce of an executed transaction must equal the nonce of its account, so the transactions
+//! of an account are executed in sequence and no nonce is skipped. A chain may configure this with
+//! a [`NonceConfig`]:
+//! - A nonce window bounds how far ahead of the account nonce the nonce of a transaction may be
+//!   when it's admitted, i.e. how many transactions of an account may wait for their turn.
+//! - System senders, e.g. accounts of the chain operator, skip the nonce checks entirely. Their
+//!   nonce still advances with every transaction.
+//!
+//! The gateway checks the nonces of the transactions it validates with the same [`NonceManager`],
+//! non-strictly, since transactions with future nonces wait in the mempool.
+
+use std::collections::BTreeMap;
+
+use papyrus_config::dumping::{ser_optional_param, ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize};
+use starknet_api::core::{ContractAddress, Nonce};
+use starknet_types_core::felt::Felt;
+
+use crate::state::state_api::State;
+use crate::transaction::errors::TransactionPreValidationError;
+use crate::transaction::objects::{TransactionInfo, TransactionPreValidationResult};
+
+#[cfg(test)]
+#[path = "nonce_manager_test.rs"]
+pub mod test;
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct NonceConfig {
+    /// The number of nonces, starting at the account nonce, that an admitted transaction may use.
+    /// Unbounded if not set. Must be positive.
+    pub nonce_window: Option<u64>,
+    /// Senders whose transactions skip the nonce checks.
+    #[serde(serialize_with = "serialize_addresses", deserialize_with = "deserialize_addresses")]
+    pub system_senders: Vec<ContractAddress>,
+}
+
+impl SerializeConfig for NonceConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        vec![
+            ser_optional_param(
+                &self.nonce_window,
+                1,
+                "nonce_window",
+                "The number of nonces, starting at the account nonce, that an admitted \
+                 transaction may use. If not set, any future nonce is admitted.",
+                ParamPrivacyInput::Public,
+            ),
+            BTreeMap::from_iter([ser_param(
+                "system_senders",
+                &serialize_addresses_to_string(&self.system_senders),
+                "A comma separated list of senders whose transactions skip the nonce checks.",
+                ParamPrivacyInput::Public,
+            )]),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+/// Checks the nonces of account transactions by the [`NonceConfig`] of the chain.
+pub struct NonceManager<'a> {
+    config: &'a NonceConfig,
+}
+
+impl<'a> NonceManager<'a> {
+    pub fn new(config: &'a NonceConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn is_system_sender(&self, address: ContractAddress) -> bool {
+        self.config.system_senders.contains(&address)
+    }
+
+    /// Checks the nonce of an incoming transaction against the nonce of its account. A strict
+    /// check, for execution, requires the nonce to equal the account nonce; otherwise, for
+    /// admission, any nonce in the window of the account nonce is valid.
+    pub fn validate_nonce(
+        &self,
+        address: ContractAddress,
+        account_nonce: Nonce,
+        incoming_tx_nonce: Nonce,
+        strict: bool,
+    ) -> TransactionPreValidationResult<()> {
+        if self.is_system_sender(address) {
+            return Ok(());
+        }
+
+        let valid_nonce = if strict {
+            account_nonce == incoming_tx_nonce
+        } else {
+            account_nonce <= incoming_tx_nonce
+                && self.config.nonce_window.map_or(true, |window| {
+                    incoming_tx_nonce.0 - account_nonce.0 < Felt::from(window)
+                })
+        };
+        if valid_nonce {
+            return Ok(());
+        }
+        Err(TransactionPreValidationError::InvalidNonce {
+            address,
+            account_nonce,
+            incoming_tx_nonce,
+        })
+    }
+
+    /// Validates the nonce of a transaction and advances the nonce of its account by one.
+    pub fn handle_nonce(
+        &self,
+        state: &mut dyn State,
+        tx_info: &TransactionInfo,
+        strict: bool,
+    ) -> TransactionPreValidationResult<()> {
+        if tx_info.is_v0() {
+            return Ok(());
+        }
+
+        let address = tx_info.sender_address();
+        let account_nonce = state.get_nonce_at(address)?;
+        let incoming_tx_nonce = tx_info.nonce();
+        self.validate_nonce(address, account_nonce, incoming_tx_nonce, strict)?;
+        Ok(state.increment_nonce(address)?)
+    }
+}
+
+fn serialize_addresses_to_string(addresses: &[ContractAddress]) -> String {
+    addresses.iter().map(|address| format!("{:#x}", address.0.key())).collect::<Vec<_>>().join(",")
+}
+
+fn serialize_addresses<S>(addresses: &[ContractAddress], ser: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    ser.serialize_str(&serialize_addresses_to_string(addresses))
+}
+
+// Deserializes a comma separated list of addresses. An empty string is deserialized as an empty
+// list.
+fn deserialize_addresses<'de, D>(de: D) -> Result<Vec<ContractAddress>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw_str = String::deserialize(de)?;
+    if raw_str.is_empty() {
+        return Ok(Vec::new());
+    }
+    raw_str
+        .split(',')
+        .map(|address| {
+            let address = address.trim();
+            Felt::from_hex(address)
+                .ok()
+                .and_then(|address| ContractAddress::try_from(address).ok())
+                .ok_or_else(|| D::Error::custom(format!("Invalid contract address '{address}'.")))
+        })
+        .collect()
+}
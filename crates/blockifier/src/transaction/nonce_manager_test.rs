@@ -0,0 +1,135 @@
+use assert_matches::assert_matches;
+use starknet_api::core::{ContractAddress, Nonce, PatriciaKey};
+use starknet_api::transaction::TransactionVersion;
+use starknet_api::{contract_address, patricia_key};
+use starknet_types_core::felt::Felt;
+
+use crate::state::cached_state::CachedState;
+use crate::state::state_api::StateReader;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::transaction::errors::TransactionPreValidationError;
+use crate::transaction::nonce_manager::{NonceConfig, NonceManager};
+use crate::transaction::objects::{
+    CommonAccountFields,
+    DeprecatedTransactionInfo,
+    TransactionInfo,
+};
+
+const SENDER: &str = "0x100";
+const SYSTEM_SENDER: &str = "0x200";
+
+fn nonce(nonce: u8) -> Nonce {
+    Nonce(Felt::from(nonce))
+}
+
+fn tx_info(sender_address: ContractAddress, nonce: Nonce) -> TransactionInfo {
+    TransactionInfo::Deprecated(DeprecatedTransactionInfo {
+        common_fields: CommonAccountFields {
+            version: TransactionVersion::ONE,
+            nonce,
+            sender_address,
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+}
+
+fn config(nonce_window: Option<u64>) -> NonceConfig {
+    NonceConfig { nonce_window, system_senders: vec![contract_address!(SYSTEM_SENDER)] }
+}
+
+#[test]
+fn strict_sequential_nonces() {
+    let config = NonceConfig::default();
+    let nonce_manager = NonceManager::new(&config);
+    let address = contract_address!(SENDER);
+
+    nonce_manager.validate_nonce(address, nonce(3), nonce(3), true).unwrap();
+    for incoming_tx_nonce in [nonce(2), nonce(4)] {
+        assert_matches!(
+            nonce_manager.validate_nonce(address, nonce(3), incoming_tx_nonce, true),
+            Err(TransactionPreValidationError::InvalidNonce { .. })
+        );
+    }
+
+    // Without a nonce window, a non-strict check accepts any future nonce.
+    nonce_manager.validate_nonce(address, nonce(3), nonce(200), false).unwrap();
+    assert_matches!(
+        nonce_manager.validate_nonce(address, nonce(3), nonce(2), false),
+        Err(TransactionPreValidationError::InvalidNonce { .. })
+    );
+}
+
+#[test]
+fn nonce_window() {
+    let config = config(Some(3));
+    let nonce_manager = NonceManager::new(&config);
+    let address = contract_address!(SENDER);
+
+    for incoming_tx_nonce in [nonce(3), nonce(4), nonce(5)] {
+        nonce_manager.validate_nonce(address, nonce(3), incoming_tx_nonce, false).unwrap();
+    }
+    for incoming_tx_nonce in [nonce(2), nonce(6)] {
+        assert_matches!(
+            nonce_manager.validate_nonce(address, nonce(3), incoming_tx_nonce, false),
+            Err(TransactionPreValidationError::InvalidNonce { .. })
+        );
+    }
+
+    // Execution stays sequential.
+    assert_matches!(
+        nonce_manager.validate_nonce(address, nonce(3), nonce(4), true),
+        Err(TransactionPreValidationError::InvalidNonce { .. })
+    );
+}
+
+#[test]
+fn system_senders_skip_nonce_checks() {
+    let config = config(Some(1));
+    let nonce_manager = NonceManager::new(&config);
+
+    nonce_manager
+        .validate_nonce(contract_address!(SYSTEM_SENDER), nonce(3), nonce(0), true)
+        .unwrap();
+    nonce_manager
+        .validate_nonce(contract_address!(SYSTEM_SENDER), nonce(3), nonce(10), true)
+        .unwrap();
+}
+
+#[test]
+fn handle_nonce_advances_the_account_nonce() {
+    let config = config(Some(3));
+    let nonce_manager = NonceManager::new(&config);
+    let address = contract_address!(SENDER);
+    let system_address = contract_address!(SYSTEM_SENDER);
+    let mut state = CachedState::from(DictStateReader::default());
+
+    // No nonce is skipped.
+    assert_matches!(
+        nonce_manager.handle_nonce(&mut state, &tx_info(address, nonce(2)), true),
+        Err(TransactionPreValidationError::InvalidNonce { .. })
+    );
+    nonce_manager.handle_nonce(&mut state, &tx_info(address, nonce(0)), true).unwrap();
+    assert_eq!(state.get_nonce_at(address).unwrap(), nonce(1));
+
+    // A non-strict check advances the nonce by one.
+    nonce_manager.handle_nonce(&mut state, &tx_info(address, nonce(2)), false).unwrap();
+    assert_eq!(state.get_nonce_at(address).unwrap(), nonce(2));
+
+    // The nonce of a system sender advances by one, whatever the nonce of the transaction.
+    nonce_manager.handle_nonce(&mut state, &tx_info(system_address, nonce(7)), true).unwrap();
+    assert_eq!(state.get_nonce_at(system_address).unwrap(), nonce(1));
+}
+
+#[test]
+fn nonce_config_serde() {
+    let config = config(Some(2));
+    let serialized = serde_json::to_value(&config).unwrap();
+    assert_eq!(serialized["system_senders"], serde_json::json!(SYSTEM_SENDER));
+    assert_eq!(serde_json::from_value::<NonceConfig>(serialized).unwrap(), config);
+
+    let config: NonceConfig =
+        serde_json::from_value(serde_json::json!({"nonce_window": null, "system_senders": ""}))
+            .unwrap();
+    assert_eq!(config, NonceConfig::default());
+}
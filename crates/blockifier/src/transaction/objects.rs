@@ -33,7 +33,7 @@ use crate::fee::gas_usage::{
     get_log_message_to_l1_emissions_cost,
     get_onchain_data_segment_length,
 };
-use crate::state::cached_state::StateChangesCount;
+use crate::state::cached_state::{ReadSet, StateChangesCount};
 use crate::transaction::constants;
 use crate::transaction::errors::{
     TransactionExecutionError,
@@ -103,11 +103,7 @@ impl TransactionInfo {
 
     pub fn enforce_fee(&self) -> bool {
         match self {
-            TransactionInfo::Current(context) => {
-                let l1_bounds = context.l1_resource_bounds();
-                let max_amount: u128 = l1_bounds.max_amount.into();
-                max_amount * l1_bounds.max_price_per_unit > 0
-            }
+            TransactionInfo::Current(context) => context.max_possible_fee() != Fee(0),
             TransactionInfo::Deprecated(context) => context.max_fee != Fee(0),
         }
     }
@@ -144,6 +140,24 @@ impl CurrentTransactionInfo {
             ValidResourceBounds::AllResources(AllResourceBounds { l1_gas, .. }) => l1_gas,
         }
     }
+
+    /// The fee the sender committed to pay at most: the sum, over the bounded resources, of their
+    /// max amount times their max price per unit.
+    pub fn max_possible_fee(&self) -> Fee {
+        let bounds_fee = |bounds: ResourceBounds| {
+            u128::from(bounds.max_amount).saturating_mul(bounds.max_price_per_unit)
+        };
+        match &self.resource_bounds {
+            ValidResourceBounds::L1Gas(bounds) => Fee(bounds_fee(*bounds)),
+            ValidResourceBounds::AllResources(AllResourceBounds {
+                l1_gas,
+                l2_gas,
+                l1_data_gas,
+            }) => Fee(bounds_fee(*l1_gas)
+                .saturating_add(bounds_fee(*l2_gas))
+                .saturating_add(bounds_fee(*l1_data_gas))),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -221,7 +235,12 @@ pub struct TransactionExecutionInfo {
     pub execute_call_info: Option<CallInfo>,
     /// Fee transfer call info; [None] for `L1Handler`.
     pub fee_transfer_call_info: Option<CallInfo>,
+    /// The call info of the transfer of the fee split; [None] if the chain doesn't split fees.
+    pub fee_split_transfer_call_info: Option<CallInfo>,
     pub revert_error: Option<String>,
+    /// The panic data of the execution that reverted the transaction, if it reverted due to a
+    /// Cairo panic; see [`PanicDecoder`](crate::execution::panic_reason::PanicDecoder).
+    pub revert_panic_data: Option<Vec<Felt>>,
     /// The receipt of the transaction.
     /// Including the actual fee that was charged (in units of the relevant fee token),
     /// actual gas consumption the transaction is charged for data availability,
@@ -229,6 +248,9 @@ pub struct TransactionExecutionInfo {
     /// (including L1 gas and additional OS resources estimation),
     /// and total gas consumed.
     pub receipt: TransactionReceipt,
+    /// The keys the transaction read from the state; [None] unless recorded, see
+    /// [`ExecutionFlags`](crate::transaction::transactions::ExecutionFlags).
+    pub read_set: Option<ReadSet>,
 }
 
 impl TransactionExecutionInfo {
@@ -237,6 +259,7 @@ impl TransactionExecutionInfo {
             .iter()
             .chain(self.execute_call_info.iter())
             .chain(self.fee_transfer_call_info.iter())
+            .chain(self.fee_split_transfer_call_info.iter())
     }
 
     pub fn is_reverted(&self) -> bool {
@@ -11,7 +11,10 @@ use starknet_api::transaction::{
 use starknet_api::{felt, patricia_key};
 use starknet_types_core::felt::Felt;
 
+use super::verify_tx_event_limits;
 use crate::context::{BlockContext, ChainInfo};
+use crate::execution::call_info::{CallExecution, CallInfo, OrderedEvent};
+use crate::execution::syscalls::hint_processor::EmitEventError;
 use crate::fee::fee_checks::FeeCheckError;
 use crate::invoke_tx_args;
 use crate::state::state_api::StateReader;
@@ -31,6 +34,7 @@ use crate::transaction::test_utils::{
     TestInitData,
 };
 use crate::transaction::transactions::ExecutableTransaction;
+use crate::versioned_constants::VersionedConstants;
 
 fn init_data_by_version(chain_info: &ChainInfo, cairo_version: CairoVersion) -> TestInitData {
     let test_contract = FeatureContract::TestContract(cairo_version);
@@ -315,3 +319,106 @@ fn test_revert_on_resource_overuse(
         );
     }
 }
+
+fn call_info_with_events(n_events: usize, inner_calls: Vec<CallInfo>) -> CallInfo {
+    CallInfo {
+        execution: CallExecution {
+            events: (0..n_events).map(|_| OrderedEvent::default()).collect(),
+            ..Default::default()
+        },
+        inner_calls,
+        ..Default::default()
+    }
+}
+
+/// Tests that the events of the validation and the execution of a transaction are limited
+/// together, if the versioned constants say so.
+#[rstest]
+#[case::within_limit(true, 3, None)]
+#[case::exceeds_limit(true, 4, Some(6))]
+#[case::limit_per_call(false, 4, None)]
+fn test_tx_event_limits_include_validation(
+    #[case] include_validation: bool,
+    #[case] n_execute_events: usize,
+    #[case] expected_n_emitted_events: Option<usize>,
+) {
+    let mut versioned_constants = VersionedConstants::create_for_testing();
+    versioned_constants.tx_event_limits_include_validation = include_validation;
+    versioned_constants.tx_event_limits.max_n_emitted_events = 5;
+    let validate_call_info = call_info_with_events(2, vec![]);
+    // The events of inner calls are counted as well.
+    let execute_call_info =
+        call_info_with_events(1, vec![call_info_with_events(n_execute_events - 1, vec![])]);
+
+    let result = verify_tx_event_limits(
+        &versioned_constants,
+        [&validate_call_info, &execute_call_info].into_iter(),
+    );
+    match expected_n_emitted_events {
+        None => result.unwrap(),
+        Some(expected_n_emitted_events) => assert_matches!(
+            result,
+            Err(TransactionExecutionError::EmitEventError(
+                EmitEventError::ExceedsMaxNumberOfEmittedEvents {
+                    n_emitted_events,
+                    max_n_emitted_events: 5,
+                }
+            )) if n_emitted_events == expected_n_emitted_events
+        ),
+    }
+}
+
+/// Tests that a transaction whose events exceed the limit of the transaction is reverted, if the
+/// versioned constants limit the events of the transaction as a whole.
+// The test accounts emit no events in their validation; counting those is covered by the test
+// above.
+#[rstest]
+#[case::within_limit(5, false)]
+#[case::exceeds_limit(6, true)]
+fn test_revert_on_tx_event_limits(
+    mut block_context: BlockContext,
+    max_resource_bounds: DeprecatedResourceBoundsMapping,
+    #[case] n_events: u8,
+    #[case] is_reverted: bool,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    block_context.versioned_constants.tx_event_limits_include_validation = true;
+    block_context.versioned_constants.tx_event_limits.max_n_emitted_events = 5;
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        init_data_by_version(&block_context.chain_info, cairo_version);
+    let calldata = create_calldata(
+        contract_address,
+        "test_emit_events",
+        &[
+            felt!(n_events), // Calldata: events number.
+            felt!(0_u8),     // Calldata: keys length.
+            felt!(0_u8),     // Calldata: data length.
+        ],
+    );
+
+    let execution_info = run_invoke_tx(
+        &mut state,
+        &block_context,
+        invoke_tx_args! {
+            sender_address: account_address,
+            calldata,
+            resource_bounds: max_resource_bounds,
+            version: TransactionVersion::THREE,
+            nonce: nonce_manager.next(account_address),
+        },
+    )
+    .unwrap();
+
+    assert_eq!(execution_info.is_reverted(), is_reverted);
+    if is_reverted {
+        let expected_error = EmitEventError::ExceedsMaxNumberOfEmittedEvents {
+            n_emitted_events: usize::from(n_events),
+            max_n_emitted_events: 5,
+        };
+        assert!(execution_info.revert_error.unwrap().contains(&expected_error.to_string()));
+        assert!(execution_info.execute_call_info.is_none());
+    }
+    // The nonce is incremented, and the fee is charged, for reverted transactions as well.
+    assert_eq!(state.get_nonce_at(account_address).unwrap(), nonce_manager.next(account_address));
+    assert!(execution_info.receipt.fee > Fee(0));
+}
@@ -306,6 +306,20 @@ pub fn l1_resource_bounds(max_amount: u64, max_price: u128) -> DeprecatedResourc
     .unwrap()
 }
 
+/// Creates a `ResourceBoundsMapping` that bounds the L1 gas, L1 data gas and L2 gas separately.
+pub fn all_resource_bounds(
+    l1_gas: ResourceBounds,
+    l1_data_gas: ResourceBounds,
+    l2_gas: ResourceBounds,
+) -> DeprecatedResourceBoundsMapping {
+    DeprecatedResourceBoundsMapping::try_from(vec![
+        (Resource::L1Gas, l1_gas),
+        (Resource::L1DataGas, l1_data_gas),
+        (Resource::L2Gas, l2_gas),
+    ])
+    .unwrap()
+}
+
 pub fn calculate_class_info_for_testing(contract_class: ContractClass) -> ClassInfo {
     let sierra_program_length = match contract_class {
         ContractClass::V0(_) => 0,
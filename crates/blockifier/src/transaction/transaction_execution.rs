@@ -148,6 +148,7 @@ impl<U: UpdatableState> ExecutableTransaction<U> for L1HandlerTransaction {
             validate_call_info: None,
             execute_call_info,
             fee_transfer_call_info: None,
+            fee_split_transfer_call_info: None,
             receipt: TransactionReceipt {
                 fee: Fee::default(),
                 da_gas,
@@ -155,6 +156,8 @@ impl<U: UpdatableState> ExecutableTransaction<U> for L1HandlerTransaction {
                 gas: total_gas,
             },
             revert_error: None,
+            revert_panic_data: None,
+            read_set: None,
         })
     }
 }
@@ -170,7 +173,10 @@ impl<U: UpdatableState> ExecutableTransaction<U> for Transaction {
         // types, since now running Transaction::execute_raw is not identical to
         // AccountTransaction::execute_raw.
         let concurrency_mode = execution_flags.concurrency_mode;
-        let tx_execution_info = match self {
+        if execution_flags.record_read_set {
+            state.record_read_set();
+        }
+        let mut tx_execution_info = match self {
             Self::AccountTransaction(account_tx) => {
                 account_tx.execute_raw(state, block_context, execution_flags)?
             }
@@ -196,6 +202,9 @@ impl<U: UpdatableState> ExecutableTransaction<U> for Transaction {
             &block_context.bouncer_config,
         )?;
 
+        if execution_flags.record_read_set {
+            tx_execution_info.read_set = state.take_read_set();
+        }
         Ok(tx_execution_info)
     }
 }
@@ -60,6 +60,8 @@ pub struct ExecutionFlags {
     pub charge_fee: bool,
     pub validate: bool,
     pub concurrency_mode: bool,
+    /// Whether to record the keys the transaction reads, in its execution info.
+    pub record_read_set: bool,
 }
 
 pub trait ExecutableTransaction<U: UpdatableState>: Sized {
@@ -74,7 +76,12 @@ pub trait ExecutableTransaction<U: UpdatableState>: Sized {
     ) -> TransactionExecutionResult<TransactionExecutionInfo> {
         log::debug!("Executing Transaction...");
         let mut transactional_state = TransactionalState::create_transactional(state);
-        let execution_flags = ExecutionFlags { charge_fee, validate, concurrency_mode: false };
+        let execution_flags = ExecutionFlags {
+            charge_fee,
+            validate,
+            concurrency_mode: false,
+            record_read_set: false,
+        };
         let execution_result =
             self.execute_raw(&mut transactional_state, block_context, execution_flags);
 
@@ -19,6 +19,8 @@ use starknet_api::transaction::{
     EventKey,
     Fee,
     L2ToL1Payload,
+    Resource,
+    ResourceBounds,
     TransactionSignature,
     TransactionVersion,
 };
@@ -87,6 +89,7 @@ use crate::test_utils::{
 use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::constants;
 use crate::transaction::errors::{
+    ResourceBoundsError,
     TransactionExecutionError,
     TransactionFeeError,
     TransactionPreValidationError,
@@ -102,6 +105,7 @@ use crate::transaction::objects::{
 };
 use crate::transaction::test_utils::{
     account_invoke_tx,
+    all_resource_bounds,
     block_context,
     calculate_class_info_for_testing,
     create_account_tx_for_validate_test,
@@ -120,6 +124,7 @@ use crate::transaction::test_utils::{
 };
 use crate::transaction::transaction_types::TransactionType;
 use crate::transaction::transactions::{ExecutableTransaction, L1HandlerTransaction};
+use crate::transaction::version_config::TransactionVersionConfig;
 use crate::versioned_constants::VersionedConstants;
 use crate::{
     check_transaction_execution_error_for_custom_hint,
@@ -514,6 +519,7 @@ fn test_invoke_tx(
         validate_call_info: expected_validate_call_info,
         execute_call_info: expected_execute_call_info,
         fee_transfer_call_info: expected_fee_transfer_call_info,
+        fee_split_transfer_call_info: None,
         receipt: TransactionReceipt {
             fee: expected_actual_fee,
             da_gas,
@@ -521,6 +527,8 @@ fn test_invoke_tx(
             gas: total_gas,
         },
         revert_error: None,
+        revert_panic_data: None,
+        read_set: None,
     };
 
     // Test execution info result.
@@ -971,6 +979,80 @@ fn test_insufficient_resource_bounds(
     );
 }
 
+#[rstest]
+fn test_insufficient_all_resource_bounds(
+    #[values(true, false)] use_kzg_da: bool,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] account_cairo_version: CairoVersion,
+) {
+    let block_context = &BlockContext::create_for_account_testing_with_kzg(use_kzg_da);
+    let account_contract = FeatureContract::AccountWithoutValidations(account_cairo_version);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let state = &mut test_state(
+        &block_context.chain_info,
+        BALANCE,
+        &[(account_contract, 1), (test_contract, 1)],
+    );
+    let valid_invoke_tx_args = invoke_tx_args! {
+        sender_address: account_contract.get_instance_address(0),
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+    };
+    let tx = &account_invoke_tx(valid_invoke_tx_args.clone());
+    let minimal_gas_vector = estimate_minimal_gas_vector(block_context, tx).unwrap();
+    let gas_prices = &block_context.block_info.gas_prices;
+    let l1_gas_price = u128::from(gas_prices.get_l1_gas_price_by_fee_type(&FeeType::Strk));
+    let l1_data_gas_price =
+        u128::from(gas_prices.get_l1_data_gas_price_by_fee_type(&FeeType::Strk));
+    let l2_gas_price = u128::from(gas_prices.get_l2_gas_price_by_fee_type(&FeeType::Strk));
+
+    // The data gas bounds are short on both the amount (if the data is posted as blobs) and the
+    // price, and the L2 gas price is short; the L1 gas bounds are sufficient.
+    let minimal_l1_data_gas = u64::try_from(minimal_gas_vector.l1_data_gas).unwrap();
+    let insufficient_l1_data_gas = minimal_l1_data_gas.saturating_sub(1);
+    let invalid_tx = account_invoke_tx(invoke_tx_args! {
+        resource_bounds: all_resource_bounds(
+            ResourceBounds {
+                max_amount: u64::try_from(minimal_gas_vector.l1_gas).unwrap(),
+                max_price_per_unit: l1_gas_price,
+            },
+            ResourceBounds {
+                max_amount: insufficient_l1_data_gas,
+                max_price_per_unit: l1_data_gas_price - 1,
+            },
+            ResourceBounds { max_amount: 0, max_price_per_unit: l2_gas_price - 1 },
+        ),
+        ..valid_invoke_tx_args
+    });
+    let execution_error = invalid_tx.execute(state, block_context, true, true).unwrap_err();
+
+    let mut expected_errors = Vec::new();
+    if use_kzg_da {
+        expected_errors.push(ResourceBoundsError::MaxGasAmountTooLow {
+            resource: Resource::L1DataGas,
+            max_gas_amount: insufficient_l1_data_gas,
+            minimal_gas_amount: minimal_gas_vector.l1_data_gas,
+        });
+    }
+    expected_errors.extend([
+        ResourceBoundsError::MaxGasPriceTooLow {
+            resource: Resource::L1DataGas,
+            max_gas_price: l1_data_gas_price - 1,
+            actual_gas_price: l1_data_gas_price,
+        },
+        ResourceBoundsError::MaxGasPriceTooLow {
+            resource: Resource::L2Gas,
+            max_gas_price: l2_gas_price - 1,
+            actual_gas_price: l2_gas_price,
+        },
+    ]);
+    assert_matches!(
+        execution_error,
+        TransactionExecutionError::TransactionPreValidationError(
+            TransactionPreValidationError::TransactionFeeError(
+                TransactionFeeError::InsufficientResourceBounds { errors }))
+        if errors == expected_errors
+    );
+}
+
 // TODO(Aner, 21/01/24) modify test for 4844.
 #[rstest]
 fn test_actual_fee_gt_resource_bounds(
@@ -1083,6 +1165,118 @@ fn test_invalid_nonce(
     );
 }
 
+#[rstest]
+fn test_deprecated_tx_version(
+    mut block_context: BlockContext,
+    max_resource_bounds: DeprecatedResourceBoundsMapping,
+) {
+    let account_contract = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let state = &mut test_state(
+        &block_context.chain_info,
+        BALANCE,
+        &[(account_contract, 1), (test_contract, 1)],
+    );
+    let invoke_tx_args = invoke_tx_args! {
+        sender_address: account_contract.get_instance_address(0),
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+        resource_bounds: max_resource_bounds,
+        max_fee: Fee(MAX_FEE),
+    };
+    let cutover_height = block_context.block_info.block_number.unchecked_next();
+    block_context.chain_info.tx_version_config =
+        TransactionVersionConfig { cutover_height, min_invoke_version: 3, ..Default::default() };
+
+    // Before the cutover height, deprecated versions are accepted.
+    account_invoke_tx(invoke_tx_args! {
+        version: TransactionVersion::ONE,
+        nonce: nonce!(0_u8),
+        ..invoke_tx_args.clone()
+    })
+    .execute(state, &block_context, true, true)
+    .unwrap();
+
+    // From the cutover height on, only the minimal version and above are.
+    block_context.block_info.block_number = cutover_height;
+    let error = account_invoke_tx(invoke_tx_args! {
+        version: TransactionVersion::ONE,
+        nonce: nonce!(1_u8),
+        ..invoke_tx_args.clone()
+    })
+    .execute(state, &block_context, true, true)
+    .unwrap_err();
+    assert_matches!(
+        error,
+        TransactionExecutionError::DeprecatedVersion {
+            tx_type: TransactionType::InvokeFunction,
+            version,
+            min_version,
+        } if version == TransactionVersion::ONE && min_version == TransactionVersion::THREE
+    );
+    account_invoke_tx(invoke_tx_args! {
+        version: TransactionVersion::THREE,
+        nonce: nonce!(1_u8),
+        ..invoke_tx_args
+    })
+    .execute(state, &block_context, true, true)
+    .unwrap();
+}
+
+#[rstest]
+fn test_deprecated_fee_token(
+    mut block_context: BlockContext,
+    max_resource_bounds: DeprecatedResourceBoundsMapping,
+) {
+    let account_contract = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let state = &mut test_state(
+        &block_context.chain_info,
+        BALANCE,
+        &[(account_contract, 1), (test_contract, 1)],
+    );
+    let invoke_tx_args = invoke_tx_args! {
+        sender_address: account_contract.get_instance_address(0),
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+        resource_bounds: max_resource_bounds,
+        max_fee: Fee(MAX_FEE),
+    };
+    let deprecation_height = block_context.block_info.block_number.unchecked_next();
+    block_context.chain_info.eth_fee_token_deprecation_height = Some(deprecation_height);
+
+    // Before the deprecation height, transactions that pay in ETH are accepted.
+    account_invoke_tx(invoke_tx_args! {
+        version: TransactionVersion::ONE,
+        nonce: nonce!(0_u8),
+        ..invoke_tx_args.clone()
+    })
+    .execute(state, &block_context, true, true)
+    .unwrap();
+
+    // From the deprecation height on, only transactions that pay in STRK are.
+    block_context.block_info.block_number = deprecation_height;
+    let error = account_invoke_tx(invoke_tx_args! {
+        version: TransactionVersion::ONE,
+        nonce: nonce!(1_u8),
+        ..invoke_tx_args.clone()
+    })
+    .execute(state, &block_context, true, true)
+    .unwrap_err();
+    assert_matches!(
+        error,
+        TransactionExecutionError::TransactionPreValidationError(
+            TransactionPreValidationError::TransactionFeeError(
+                TransactionFeeError::DeprecatedFeeToken { block_number, .. }))
+        if block_number == deprecation_height
+    );
+    account_invoke_tx(invoke_tx_args! {
+        version: TransactionVersion::THREE,
+        nonce: nonce!(1_u8),
+        ..invoke_tx_args
+    })
+    .execute(state, &block_context, true, true)
+    .unwrap();
+}
+
 /// Expected CallInfo for `__validate__` call in a declare transaction.
 fn declare_validate_callinfo(
     version: TransactionVersion,
@@ -1235,6 +1429,7 @@ fn test_declare_tx(
         validate_call_info: expected_validate_call_info,
         execute_call_info: None,
         fee_transfer_call_info: expected_fee_transfer_call_info,
+        fee_split_transfer_call_info: None,
         receipt: TransactionReceipt {
             fee: expected_actual_fee,
             da_gas,
@@ -1242,6 +1437,8 @@ fn test_declare_tx(
             gas: expected_total_gas,
         },
         revert_error: None,
+        revert_panic_data: None,
+        read_set: None,
     };
 
     // Test execution info result.
@@ -1404,6 +1601,7 @@ fn test_deploy_account_tx(
         validate_call_info: expected_validate_call_info,
         execute_call_info: expected_execute_call_info,
         fee_transfer_call_info: expected_fee_transfer_call_info,
+        fee_split_transfer_call_info: None,
         receipt: TransactionReceipt {
             fee: expected_actual_fee,
             da_gas,
@@ -1411,6 +1609,8 @@ fn test_deploy_account_tx(
             gas: expected_total_gas,
         },
         revert_error: None,
+        revert_panic_data: None,
+        read_set: None,
     };
 
     // Test execution info result.
@@ -1928,6 +2128,7 @@ fn test_l1_handler(#[values(false, true)] use_kzg_da: bool) {
         validate_call_info: None,
         execute_call_info: Some(expected_call_info),
         fee_transfer_call_info: None,
+        fee_split_transfer_call_info: None,
         receipt: TransactionReceipt {
             fee: Fee(0),
             da_gas: expected_da_gas,
@@ -1935,6 +2136,8 @@ fn test_l1_handler(#[values(false, true)] use_kzg_da: bool) {
             gas: total_gas,
         },
         revert_error: None,
+        revert_panic_data: None,
+        read_set: None,
     };
 
     // Check the actual returned execution info.
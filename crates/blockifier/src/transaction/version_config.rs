@@ -0,0 +1,103 @@
+//! The transaction versions a chain accepts.
+//!
+//! The blockifier supports several versions of each account transaction type. A chain may
+//! deprecate the older versions with a [`TransactionVersionConfig`]: from its cutover height on,
+//! transactions of a version below the minimal version of their type are rejected, both by the
+//! gateway and on execution. Before the cutover height, all the supported versions are accepted, so
+//! the blocks before it are re-executed as they were.
+
+use std::collections::BTreeMap;
+
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockNumber;
+use starknet_api::transaction::TransactionVersion;
+use starknet_types_core::felt::Felt;
+
+use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::objects::TransactionExecutionResult;
+use crate::transaction::transaction_types::TransactionType;
+
+#[cfg(test)]
+#[path = "version_config_test.rs"]
+pub mod test;
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct TransactionVersionConfig {
+    /// The first block in which the minimal versions are enforced.
+    pub cutover_height: BlockNumber,
+    pub min_declare_version: u8,
+    pub min_deploy_account_version: u8,
+    pub min_invoke_version: u8,
+}
+
+impl SerializeConfig for TransactionVersionConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "cutover_height",
+                &self.cutover_height,
+                "The first block in which the minimal transaction versions are enforced.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "min_declare_version",
+                &self.min_declare_version,
+                "The minimal version of declare transactions.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "min_deploy_account_version",
+                &self.min_deploy_account_version,
+                "The minimal version of deploy account transactions.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "min_invoke_version",
+                &self.min_invoke_version,
+                "The minimal version of invoke transactions.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+impl TransactionVersionConfig {
+    /// Returns the minimal version of the transaction type in the given block. L1 handler
+    /// transactions have a single version, and aren't restricted.
+    pub fn min_version(
+        &self,
+        tx_type: TransactionType,
+        block_number: BlockNumber,
+    ) -> TransactionVersion {
+        if block_number < self.cutover_height {
+            return TransactionVersion::ZERO;
+        }
+        let min_version = match tx_type {
+            TransactionType::Declare => self.min_declare_version,
+            TransactionType::DeployAccount => self.min_deploy_account_version,
+            TransactionType::InvokeFunction => self.min_invoke_version,
+            TransactionType::L1Handler => 0,
+        };
+        TransactionVersion(Felt::from(min_version))
+    }
+
+    /// Rejects a transaction whose version the chain deprecated by the given block.
+    pub fn validate_version(
+        &self,
+        tx_type: TransactionType,
+        version: TransactionVersion,
+        block_number: BlockNumber,
+    ) -> TransactionExecutionResult<()> {
+        let min_version = self.min_version(tx_type, block_number);
+        if version < min_version {
+            return Err(TransactionExecutionError::DeprecatedVersion {
+                tx_type,
+                version,
+                min_version,
+            });
+        }
+        Ok(())
+    }
+}
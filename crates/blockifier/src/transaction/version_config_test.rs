@@ -0,0 +1,58 @@
+use assert_matches::assert_matches;
+use rstest::rstest;
+use starknet_api::block::BlockNumber;
+use starknet_api::transaction::TransactionVersion;
+
+use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::transaction_types::TransactionType;
+use crate::transaction::version_config::TransactionVersionConfig;
+
+const CUTOVER_HEIGHT: BlockNumber = BlockNumber(10);
+
+fn config() -> TransactionVersionConfig {
+    TransactionVersionConfig {
+        cutover_height: CUTOVER_HEIGHT,
+        min_declare_version: 2,
+        min_deploy_account_version: 3,
+        min_invoke_version: 3,
+    }
+}
+
+#[rstest]
+#[case::declare(TransactionType::Declare, TransactionVersion::TWO)]
+#[case::deploy_account(TransactionType::DeployAccount, TransactionVersion::THREE)]
+#[case::invoke(TransactionType::InvokeFunction, TransactionVersion::THREE)]
+#[case::l1_handler(TransactionType::L1Handler, TransactionVersion::ZERO)]
+fn min_version_applies_from_cutover_height(
+    #[case] tx_type: TransactionType,
+    #[case] expected_min_version: TransactionVersion,
+) {
+    let config = config();
+    assert_eq!(config.min_version(tx_type, BlockNumber(9)), TransactionVersion::ZERO);
+    assert_eq!(config.min_version(tx_type, CUTOVER_HEIGHT), expected_min_version);
+    assert_eq!(config.min_version(tx_type, BlockNumber(11)), expected_min_version);
+}
+
+#[test]
+fn validate_version() {
+    let config = config();
+    let tx_type = TransactionType::InvokeFunction;
+    config.validate_version(tx_type, TransactionVersion::ONE, BlockNumber(9)).unwrap();
+    config.validate_version(tx_type, TransactionVersion::THREE, CUTOVER_HEIGHT).unwrap();
+    assert_matches!(
+        config.validate_version(tx_type, TransactionVersion::ONE, CUTOVER_HEIGHT),
+        Err(TransactionExecutionError::DeprecatedVersion {
+            tx_type: TransactionType::InvokeFunction,
+            version,
+            min_version,
+        }) if version == TransactionVersion::ONE && min_version == TransactionVersion::THREE
+    );
+}
+
+#[test]
+fn default_config_accepts_all_versions() {
+    let config = TransactionVersionConfig::default();
+    for tx_type in [TransactionType::Declare, TransactionType::InvokeFunction] {
+        config.validate_version(tx_type, TransactionVersion::ZERO, CUTOVER_HEIGHT).unwrap();
+    }
+}
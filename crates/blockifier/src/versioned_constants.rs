@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::io;
 use std::path::Path;
 use std::sync::{Arc, LazyLock};
@@ -9,8 +9,9 @@ use indexmap::{IndexMap, IndexSet};
 use num_rational::Ratio;
 use paste::paste;
 use serde::de::Error as DeserializationError;
-use serde::{Deserialize, Deserializer};
-use serde_json::{Map, Number, Value};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Map, Number, Value};
+use starknet_api::core::EntryPointSelector;
 use strum::IntoEnumIterator;
 use strum_macros::{EnumCount, EnumIter};
 use thiserror::Error;
@@ -69,6 +70,7 @@ define_versioned_constants! {
     (V0_13_1, "../resources/versioned_constants_13_1.json"),
     (V0_13_1_1, "../resources/versioned_constants_13_1_1.json"),
     (V0_13_2, "../resources/versioned_constants_13_2.json"),
+    (V0_13_3, "../resources/versioned_constants_13_3.json"),
     (Latest, "../resources/versioned_constants.json"),
 }
 
@@ -83,10 +85,18 @@ pub struct VersionedConstants {
     // Limits.
     #[serde(default = "EventLimits::max")]
     pub tx_event_limits: EventLimits,
+    // If true, the number of events is limited for the validation and the execution of a
+    // transaction together, rather than for each of them.
+    #[serde(default)]
+    pub tx_event_limits_include_validation: bool,
     pub invoke_tx_max_n_steps: u32,
     #[serde(default)]
     pub l2_resource_gas_costs: L2ResourceGasCosts,
     pub max_recursion_depth: usize,
+    // The maximal number of VM memory cells the entry points of a transaction may use, in each of
+    // its validation and execution. Unlimited if not set.
+    #[serde(default)]
+    pub tx_max_n_memory_cells: Option<usize>,
     pub validate_max_n_steps: u32,
     // BACKWARD COMPATIBILITY: If true, the segment_arena builtin instance counter will be
     // multiplied by 3. This offsets a bug in the old vm where the counter counted the number of
@@ -97,6 +107,9 @@ pub struct VersionedConstants {
     // Transactions settings.
     #[serde(default)]
     pub disable_cairo0_redeclaration: bool,
+    // If true, contracts with deprecated (Cairo 0) classes aren't executed.
+    #[serde(default)]
+    pub disallow_deprecated_class_execution: bool,
 
     // Cairo OS constants.
     // Note: if loaded from a json file, there are some assumptions made on its structure.
@@ -124,6 +137,67 @@ impl VersionedConstants {
         Self::get(StarknetVersion::Latest)
     }
 
+    /// Returns the constants of all the Starknet versions, from the oldest to the latest.
+    pub fn all() -> impl Iterator<Item = (StarknetVersion, &'static Self)> {
+        StarknetVersion::iter().map(|version| (version.clone(), Self::get(version)))
+    }
+
+    /// Returns the limits, OS constants and fee costs that differ in `other`, ordered by name.
+    pub fn diff(&self, other: &Self) -> Vec<ConstantDiff> {
+        let old_constants = self.comparable_constants();
+        let new_constants = other.comparable_constants();
+        let names: BTreeSet<&String> = old_constants.keys().chain(new_constants.keys()).collect();
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let old_value = old_constants.get(name);
+                let new_value = new_constants.get(name);
+                (old_value != new_value).then(|| ConstantDiff {
+                    name: name.clone(),
+                    old_value: old_value.cloned(),
+                    new_value: new_value.cloned(),
+                })
+            })
+            .collect()
+    }
+
+    // The constants that are compared between versions, by their path in the JSON file (e.g.,
+    // `os_constants.step_gas_cost`).
+    fn comparable_constants(&self) -> BTreeMap<String, Value> {
+        let mut os_constants = serde_json::to_value(&self.os_constants.gas_costs)
+            .expect("Gas costs should be serializable.");
+        let os_constants_map = os_constants.as_object_mut().expect("Gas costs are a struct.");
+        for value in [
+            serde_json::to_value(&self.os_constants.additional_constants),
+            serde_json::to_value(&self.os_constants.validate_rounding_consts)
+                .map(|value| json!({ "validate_rounding_consts": value })),
+        ] {
+            let Value::Object(values) = value.expect("OS constants should be serializable.") else {
+                unreachable!("OS constants are structs.");
+            };
+            os_constants_map.extend(values);
+        }
+        let vm_resource_fee_cost: BTreeMap<_, _> = self.vm_resource_fee_cost.iter().collect();
+        let constants = json!({
+            "tx_event_limits": self.tx_event_limits,
+            "tx_event_limits_include_validation": self.tx_event_limits_include_validation,
+            "invoke_tx_max_n_steps": self.invoke_tx_max_n_steps,
+            "l2_resource_gas_costs": self.l2_resource_gas_costs,
+            "max_recursion_depth": self.max_recursion_depth,
+            "tx_max_n_memory_cells": self.tx_max_n_memory_cells,
+            "validate_max_n_steps": self.validate_max_n_steps,
+            "segment_arena_cells": self.segment_arena_cells,
+            "disable_cairo0_redeclaration": self.disable_cairo0_redeclaration,
+            "disallow_deprecated_class_execution": self.disallow_deprecated_class_execution,
+            "os_constants": os_constants,
+            "vm_resource_fee_cost": vm_resource_fee_cost,
+        });
+
+        let mut flattened_constants = BTreeMap::new();
+        flatten_constants(String::new(), constants, &mut flattened_constants);
+        flattened_constants
+    }
+
     /// Converts from l1 gas cost to l2 gas cost with **upward rounding**
     pub fn l1_to_l2_gas_price_conversion(&self, l1_gas_price: u128) -> u128 {
         let l1_to_l2_gas_price_ratio: Ratio<u128> =
@@ -250,6 +324,34 @@ impl VersionedConstants {
     }
 }
 
+/// A constant whose value differs between two instances of the versioned constants.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConstantDiff {
+    /// The path of the constant in the JSON file, e.g., `os_constants.step_gas_cost`.
+    pub name: String,
+    /// [None] if the constant isn't defined in the old constants.
+    pub old_value: Option<Value>,
+    /// [None] if the constant isn't defined in the new constants.
+    pub new_value: Option<Value>,
+}
+
+// Adds the leaves of the JSON value to the constants, by their dot-separated path. Null values are
+// of undefined constants, and are skipped.
+fn flatten_constants(path: String, value: Value, constants: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Null => {}
+        Value::Object(values) => {
+            for (key, value) in values {
+                let inner_path = if path.is_empty() { key } else { format!("{path}.{key}") };
+                flatten_constants(inner_path, value, constants);
+            }
+        }
+        _ => {
+            constants.insert(path, value);
+        }
+    }
+}
+
 impl TryFrom<&Path> for VersionedConstants {
     type Error = VersionedConstantsError;
 
@@ -258,7 +360,7 @@ impl TryFrom<&Path> for VersionedConstants {
     }
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct L2ResourceGasCosts {
     // TODO(barak, 18/03/2024): Once we start charging per byte change to milligas_per_data_byte,
     // divide the value by 32 in the JSON file.
@@ -270,7 +372,7 @@ pub struct L2ResourceGasCosts {
     pub gas_per_code_byte: ResourceCost,
 }
 
-#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct EventLimits {
     pub max_data_length: usize,
     pub max_keys_length: usize,
@@ -452,7 +554,7 @@ impl<'de> Deserialize<'de> for OsResources {
 }
 
 /// Gas cost constants. For more documentation see in core/os/constants.cairo.
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct GasCosts {
     pub step_gas_cost: u64,
     pub memory_hole_gas_cost: u64,
@@ -512,6 +614,7 @@ pub struct GasCosts {
 pub struct OsConstants {
     pub gas_costs: GasCosts,
     pub validate_rounding_consts: ValidateRoundingConsts,
+    pub additional_constants: AdditionalOsConstants,
 }
 
 impl OsConstants {
@@ -550,6 +653,50 @@ impl OsConstants {
     ];
 }
 
+/// The OS constants that aren't used by the blockifier, typed for tooling and tests. For more
+/// documentation see in core/os/constants.cairo. Missing constants get their default values, so
+/// custom versioned constants may omit them.
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct AdditionalOsConstants {
+    pub block_hash_contract_address: u64,
+    pub constructor_entry_point_selector: EntryPointSelector,
+    pub default_entry_point_selector: u64,
+    pub entry_point_type_constructor: u64,
+    pub entry_point_type_external: u64,
+    pub entry_point_type_l1_handler: u64,
+    pub error_block_number_out_of_range: String,
+    pub error_invalid_input_len: String,
+    pub error_invalid_argument: String,
+    pub error_out_of_gas: String,
+    pub execute_entry_point_selector: EntryPointSelector,
+    pub l1_gas: String,
+    pub l1_gas_index: u64,
+    pub l1_handler_version: u64,
+    pub l2_gas: String,
+    pub l2_gas_index: u64,
+    // The L1 data gas resource is only defined in the latest version.
+    pub l1_data_gas: Option<String>,
+    pub l1_data_gas_index: Option<u64>,
+    pub nop_entry_point_offset: i64,
+    pub sierra_array_len_bound: u64,
+    pub stored_block_hash_buffer: u64,
+    pub transfer_entry_point_selector: EntryPointSelector,
+    pub validate_declare_entry_point_selector: EntryPointSelector,
+    pub validate_deploy_entry_point_selector: EntryPointSelector,
+    pub validate_entry_point_selector: EntryPointSelector,
+    pub validated: String,
+}
+
+impl TryFrom<&OsConstantsRawJson> for AdditionalOsConstants {
+    type Error = OsConstantsSerdeError;
+
+    fn try_from(raw_json_data: &OsConstantsRawJson) -> Result<Self, Self::Error> {
+        let raw_json_value = serde_json::to_value(&raw_json_data.raw_json_file_as_dict)?;
+        Ok(serde_json::from_value(raw_json_value)?)
+    }
+}
+
 impl TryFrom<&OsConstantsRawJson> for GasCosts {
     type Error = OsConstantsSerdeError;
 
@@ -565,8 +712,10 @@ impl TryFrom<OsConstantsRawJson> for OsConstants {
 
     fn try_from(raw_json_data: OsConstantsRawJson) -> Result<Self, Self::Error> {
         let gas_costs = GasCosts::try_from(&raw_json_data)?;
+        let additional_constants = AdditionalOsConstants::try_from(&raw_json_data)?;
         let validate_rounding_consts = raw_json_data.validate_rounding_consts;
-        let os_constants = OsConstants { gas_costs, validate_rounding_consts };
+        let os_constants =
+            OsConstants { gas_costs, validate_rounding_consts, additional_constants };
         Ok(os_constants)
     }
 }
@@ -731,7 +880,7 @@ impl TryFrom<ResourceParamsRaw> for ResourcesParams {
     }
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ValidateRoundingConsts {
     // Flooring factor for block number in validate mode.
     pub validate_block_number_rounding: u64,
@@ -3,6 +3,8 @@ use glob::{glob, Paths};
 use pretty_assertions::assert_eq;
 
 use super::*;
+use crate::abi::abi_utils::selector_from_name;
+use crate::transaction::constants::EXECUTE_ENTRY_POINT_NAME;
 
 // TODO: Test Starknet OS validation.
 // TODO: Add an unallowed field scenario for GasCost parsing.
@@ -81,7 +83,10 @@ fn get_json_value_without_defaults() -> serde_json::Value {
         .unwrap()
         .clone();
     // Remove defaults from OsConstants.
-    os_constants.as_object_mut().unwrap().remove("validate_rounding_consts");
+    let os_constants_object = os_constants.as_object_mut().unwrap();
+    for field in OsConstants::ADDITIONAL_FIELDS {
+        os_constants_object.remove(field);
+    }
 
     let mut json_value_without_defaults: Value = serde_json::from_str(json_data).unwrap();
     json_value_without_defaults
@@ -122,8 +127,13 @@ fn test_default_values() {
 
     assert_eq!(versioned_constants.get_validate_block_number_rounding(), 1);
     assert_eq!(versioned_constants.get_validate_timestamp_rounding(), 1);
+    assert_eq!(
+        versioned_constants.os_constants.additional_constants,
+        AdditionalOsConstants::default()
+    );
 
     assert_eq!(versioned_constants.tx_event_limits, EventLimits::max());
+    assert!(!versioned_constants.tx_event_limits_include_validation);
     assert_eq!(versioned_constants.l2_resource_gas_costs, L2ResourceGasCosts::default());
 
     // Calldata factor was initialized as 0, and did not affect the expected result, even if
@@ -143,6 +153,10 @@ fn test_default_values() {
     );
     // The default value of disabled_cairo0_redeclaration is false to allow backward compatibility.
     assert_eq!(versioned_constants.disable_cairo0_redeclaration, false);
+    // Versions that don't disallow deprecated classes execute them.
+    assert_eq!(versioned_constants.disallow_deprecated_class_execution, false);
+    // Versions that don't limit the memory of transactions run them without a limit.
+    assert_eq!(versioned_constants.tx_max_n_memory_cells, None);
 }
 
 #[test]
@@ -234,3 +248,83 @@ fn test_old_json_parsing() {
 fn test_all_jsons_in_enum() {
     assert_eq!(StarknetVersion::iter().count(), all_jsons_in_dir().count());
 }
+
+#[test]
+fn test_all_versions() {
+    let versions: Vec<StarknetVersion> =
+        VersionedConstants::all().map(|(version, _)| version).collect();
+    assert_eq!(versions, StarknetVersion::iter().collect::<Vec<_>>());
+    assert_eq!(versions.first(), Some(&StarknetVersion::V0_13_0));
+    assert_eq!(versions.last(), Some(&StarknetVersion::Latest));
+}
+
+#[test]
+fn test_additional_os_constants_parsing() {
+    for (version, versioned_constants) in VersionedConstants::all() {
+        let additional_constants = &versioned_constants.os_constants.additional_constants;
+        assert_eq!(
+            additional_constants.execute_entry_point_selector,
+            selector_from_name(EXECUTE_ENTRY_POINT_NAME),
+            "Unexpected execute selector in version {version:?}."
+        );
+        assert_eq!(additional_constants.nop_entry_point_offset, -1);
+        assert_eq!(additional_constants.l1_gas, "L1_GAS");
+    }
+
+    let additional_constants =
+        &VersionedConstants::get(StarknetVersion::V0_13_0).os_constants.additional_constants;
+    assert_eq!(additional_constants.l1_data_gas, None);
+    assert_eq!(additional_constants.l1_data_gas_index, None);
+
+    let additional_constants =
+        &VersionedConstants::latest_constants().os_constants.additional_constants;
+    assert_eq!(additional_constants.l1_data_gas.as_deref(), Some("L1_DATA"));
+    assert_eq!(additional_constants.l1_data_gas_index, Some(2));
+}
+
+#[test]
+fn test_diff_of_same_version_is_empty() {
+    for (_, versioned_constants) in VersionedConstants::all() {
+        assert_eq!(versioned_constants.diff(versioned_constants), vec![]);
+    }
+}
+
+#[test]
+fn test_diff_between_versions() {
+    let old_constants = VersionedConstants::get(StarknetVersion::V0_13_0);
+    let new_constants = VersionedConstants::latest_constants();
+    let diff = old_constants.diff(new_constants);
+
+    let find = |name: &str| diff.iter().find(|constant_diff| constant_diff.name == name);
+    assert_eq!(
+        find("os_constants.l1_data_gas"),
+        Some(&ConstantDiff {
+            name: "os_constants.l1_data_gas".to_string(),
+            old_value: None,
+            new_value: Some(Value::from("L1_DATA")),
+        })
+    );
+    assert_eq!(
+        find("disable_cairo0_redeclaration"),
+        Some(&ConstantDiff {
+            name: "disable_cairo0_redeclaration".to_string(),
+            old_value: Some(Value::from(false)),
+            new_value: Some(Value::from(true)),
+        })
+    );
+    // Unchanged constants aren't in the diff.
+    assert_eq!(find("os_constants.l1_gas"), None);
+
+    // The diff is ordered by name, and reversing it swaps the values.
+    assert!(diff.windows(2).all(|pair| pair[0].name < pair[1].name));
+    let reversed_diff: Vec<_> = new_constants
+        .diff(old_constants)
+        .into_iter()
+        .map(|ConstantDiff { name, old_value, new_value }| ConstantDiff {
+            name,
+            old_value: new_value,
+            new_value: old_value,
+        })
+        .collect();
+    assert_eq!(reversed_diff, diff);
+}
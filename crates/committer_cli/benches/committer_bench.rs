@@ -75,7 +75,7 @@ pub fn full_committer_flow_benchmark(criterion: &mut Criterion) {
                 let input =
                     parse_input(committer_input_string).expect("Failed to parse the given input.");
                 // Set the given log level if handle is passed.
-                commit(input, OUTPUT_PATH.to_owned())
+                commit(input, OUTPUT_PATH.to_owned(), None)
             });
         })
     });
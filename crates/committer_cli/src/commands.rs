@@ -6,11 +6,13 @@ use tracing_subscriber::reload::Handle;
 use tracing_subscriber::Registry;
 
 use crate::filled_tree_output::filled_forest::SerializedForest;
+use crate::os_input::{generate_os_input, ExecutedBlockInput};
 use crate::parse_input::read::{parse_input, write_to_file};
 
 pub async fn parse_and_commit(
     input_string: &str,
     output_path: String,
+    block_number: Option<u64>,
     log_filter_handle: Handle<LevelFilter, Registry>,
 ) {
     let input = parse_input(input_string).expect("Failed to parse the given input.");
@@ -23,13 +25,13 @@ pub async fn parse_and_commit(
     log_filter_handle
         .modify(|filter| *filter = input.config.logger_level())
         .expect("Failed to set the log level.");
-    commit(input, output_path).await;
+    commit(input, output_path, block_number).await;
 }
 
-pub async fn commit(input: Input<ConfigImpl>, output_path: String) {
+pub async fn commit(input: Input<ConfigImpl>, output_path: String, block_number: Option<u64>) {
     let serialized_filled_forest =
         SerializedForest(commit_block(input).await.expect("Failed to commit the given block."));
-    let output = serialized_filled_forest.forest_to_output();
+    let output = serialized_filled_forest.forest_to_output(block_number);
     write_to_file(&output_path, &output);
     info!(
         "Successfully committed given block. Updated Contracts Trie Root Hash: {:?},
@@ -37,3 +39,17 @@ pub async fn commit(input: Input<ConfigImpl>, output_path: String) {
         output.contract_storage_root_hash, output.compiled_class_root_hash,
     );
 }
+
+pub async fn parse_and_generate_os_input(input_string: &str, output_path: String) {
+    let executed_block: ExecutedBlockInput =
+        serde_json::from_str(input_string).expect("Failed to parse the given input.");
+    let n_transactions = executed_block.transactions.len();
+    let os_input =
+        generate_os_input(executed_block).await.expect("Failed to generate the OS input.");
+    write_to_file(&output_path, &os_input);
+    info!(
+        "Successfully generated the OS input of a block with {n_transactions} transactions. \
+         Updated Contracts Trie Root Hash: {}",
+        os_input.contract_state_commitment_info.updated_root,
+    );
+}
@@ -1,4 +1,5 @@
 use serde::Serialize;
+use starknet_committer::block_committer::storage_proof::{write_block_roots, BlockRoots};
 use starknet_committer::forest::filled_forest::FilledForest;
 use starknet_patricia::storage::map_storage::MapStorage;
 
@@ -17,9 +18,18 @@ pub struct Output {
 }
 
 impl SerializedForest {
-    pub fn forest_to_output(&self) -> Output {
+    /// Returns the output of the commitment. Given the number of the committed block, the output
+    /// storage also holds the roots of the block, so storage proofs are fetched by block number.
+    pub fn forest_to_output(&self, block_number: Option<u64>) -> Output {
         let mut storage = MapStorage::default();
         self.0.write_to_storage(&mut storage);
+        if let Some(block_number) = block_number {
+            let roots = BlockRoots {
+                contracts_trie_root_hash: self.0.get_contract_root_hash(),
+                classes_trie_root_hash: self.0.get_compiled_class_root_hash(),
+            };
+            write_block_roots(&mut storage, block_number, roots);
+        }
         let contract_storage_root_hash = self.0.get_contract_root_hash().0;
         let compiled_class_root_hash = self.0.get_compiled_class_root_hash().0;
         Output {
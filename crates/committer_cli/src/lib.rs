@@ -1,6 +1,7 @@
 pub mod block_hash;
 pub mod commands;
 pub mod filled_tree_output;
+pub mod os_input;
 pub mod parse_input;
 pub mod tests;
 pub mod tracing_utils;
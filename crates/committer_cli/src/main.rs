@@ -1,6 +1,6 @@
 use clap::{Args, Parser, Subcommand};
 use committer_cli::block_hash::{BlockCommitmentsInput, BlockHashInput};
-use committer_cli::commands::parse_and_commit;
+use committer_cli::commands::{parse_and_commit, parse_and_generate_os_input};
 use committer_cli::parse_input::read::{load_from_stdin, read_from_stdin, write_to_file};
 use committer_cli::tests::python_tests::PythonTest;
 use committer_cli::tracing_utils::configure_tracing;
@@ -40,6 +40,17 @@ enum Command {
         /// File path to output.
         #[clap(long, short = 'o', default_value = "stdout")]
         output_path: String,
+        /// The number of the committed block. If given, the roots of the block are written to the
+        /// output storage, so storage proofs against the block are fetched by its number.
+        #[clap(long)]
+        block_number: Option<u64>,
+    },
+    /// Given an executed block and the state it was executed on, generates the input of the
+    /// Starknet OS.
+    OsInput {
+        /// File path to output.
+        #[clap(long, short = 'o', default_value = "stdout")]
+        output_path: String,
     },
     PythonTest {
         /// File path to output.
@@ -66,9 +77,14 @@ async fn main() {
     info!("Starting committer-cli with args: \n{:?}", args);
 
     match args.command {
-        Command::Commit { output_path } => {
+        Command::Commit { output_path, block_number } => {
             // TODO(Aner, 15/7/24): try moving read_from_stdin into function.
-            parse_and_commit(&read_from_stdin(), output_path, log_filter_handle).await;
+            parse_and_commit(&read_from_stdin(), output_path, block_number, log_filter_handle)
+                .await;
+        }
+
+        Command::OsInput { output_path } => {
+            parse_and_generate_os_input(&read_from_stdin(), output_path).await;
         }
 
         Command::PythonTest { output_path, test_name } => {
@@ -0,0 +1,163 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use starknet_api::block::BlockHeaderWithoutHash;
+use starknet_api::core::{ChainId, ClassHash, CompiledClassHash};
+use starknet_api::transaction::{Transaction, TransactionHash};
+use starknet_committer::block_committer::commitment_info::{
+    commit_block_with_commitment_infos,
+    CommitmentInfo,
+    StateCommitmentInfos,
+};
+use starknet_committer::block_committer::errors::BlockCommitmentError;
+use starknet_committer::patricia_merkle_tree::leaf::leaf_impl::ContractState;
+use starknet_patricia::patricia_merkle_tree::types::SubTreeHeight;
+use starknet_patricia::storage::errors::DeserializationError;
+use thiserror::Error;
+
+use crate::parse_input::cast::{cast_storage_reads, InputImpl};
+use crate::parse_input::raw_input::{RawInput, RawStorageReads};
+
+#[cfg(test)]
+#[path = "os_input_test.rs"]
+pub mod os_input_test;
+
+#[derive(Debug, Error)]
+pub enum OsInputError {
+    #[error(transparent)]
+    Deserialization(#[from] DeserializationError),
+    #[error(transparent)]
+    BlockCommitment(#[from] BlockCommitmentError),
+}
+
+/// A transaction of the block, with its hash.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct OsTransaction {
+    pub transaction_hash: TransactionHash,
+    pub transaction: Transaction,
+}
+
+#[derive(Debug, Deserialize)]
+/// An executed block: the committer input of its state diff, with the rest of its execution.
+pub struct ExecutedBlockInput {
+    pub(crate) committer_input: RawInput,
+    /// The storage keys the block read without writing to them.
+    pub(crate) storage_reads: Vec<RawStorageReads>,
+    pub block_info: BlockHeaderWithoutHash,
+    pub chain_id: ChainId,
+    /// The transactions of the block, in execution order.
+    pub transactions: Vec<OsTransaction>,
+    /// The compiled class hashes of the Cairo 1 classes the block declared or executed.
+    pub class_hash_to_compiled_class_hash: BTreeMap<ClassHash, CompiledClassHash>,
+    /// The compiled Cairo 1 classes the block executed.
+    pub compiled_classes: BTreeMap<ClassHash, Value>,
+    /// The Cairo 0 classes the block executed.
+    pub deprecated_compiled_classes: BTreeMap<ClassHash, Value>,
+}
+
+#[derive(Debug, Serialize)]
+/// The commitment info of a tree, with hexadecimal felts.
+pub struct SerializedCommitmentInfo {
+    pub previous_root: String,
+    pub updated_root: String,
+    pub tree_height: u8,
+    /// The preimages of the inner nodes on the accessed paths, by their hash.
+    pub commitment_facts: BTreeMap<String, Vec<String>>,
+}
+
+impl From<CommitmentInfo> for SerializedCommitmentInfo {
+    fn from(commitment_info: CommitmentInfo) -> Self {
+        Self {
+            previous_root: commitment_info.previous_root.0.to_hex(),
+            updated_root: commitment_info.updated_root.0.to_hex(),
+            tree_height: commitment_info.tree_height.into(),
+            commitment_facts: commitment_info
+                .commitment_facts
+                .into_iter()
+                .map(|(hash, preimage)| {
+                    (hash.0.to_hex(), preimage.iter().map(|felt| felt.to_hex()).collect())
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SerializedStorageCommitmentTree {
+    pub root: String,
+    pub height: u8,
+}
+
+#[derive(Debug, Serialize)]
+/// The state of a contract, in the same layout as the contracts trie leaves.
+pub struct SerializedContractState {
+    pub contract_hash: String,
+    pub storage_commitment_tree: SerializedStorageCommitmentTree,
+    pub nonce: String,
+}
+
+impl From<ContractState> for SerializedContractState {
+    fn from(contract_state: ContractState) -> Self {
+        Self {
+            contract_hash: contract_state.class_hash.0.to_hex(),
+            storage_commitment_tree: SerializedStorageCommitmentTree {
+                root: contract_state.storage_root_hash.0.to_hex(),
+                height: SubTreeHeight::ACTUAL_HEIGHT.into(),
+            },
+            nonce: contract_state.nonce.0.to_hex(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+/// The input of the Starknet OS for an executed block.
+pub struct OsInput {
+    pub contract_state_commitment_info: SerializedCommitmentInfo,
+    pub contract_class_commitment_info: SerializedCommitmentInfo,
+    pub address_to_storage_commitment_info: BTreeMap<String, SerializedCommitmentInfo>,
+    /// The states of the accessed contracts before the block, by address.
+    pub contracts: BTreeMap<String, SerializedContractState>,
+    pub class_hash_to_compiled_class_hash: BTreeMap<ClassHash, CompiledClassHash>,
+    pub compiled_classes: BTreeMap<ClassHash, Value>,
+    pub deprecated_compiled_classes: BTreeMap<ClassHash, Value>,
+    pub transactions: Vec<OsTransaction>,
+    pub block_info: BlockHeaderWithoutHash,
+    pub chain_id: ChainId,
+}
+
+/// Commits the state diff of the executed block, and assembles its OS input from the commitment
+/// infos of the accessed leaves.
+pub async fn generate_os_input(
+    executed_block: ExecutedBlockInput,
+) -> Result<OsInput, OsInputError> {
+    let committer_input = InputImpl::try_from(executed_block.committer_input)?;
+    let storage_reads = cast_storage_reads(executed_block.storage_reads)?;
+    let (_, commitment_infos) =
+        commit_block_with_commitment_infos(committer_input, &storage_reads).await?;
+    let StateCommitmentInfos {
+        contracts_trie,
+        classes_trie,
+        storage_tries,
+        previous_contract_states,
+    } = commitment_infos;
+
+    Ok(OsInput {
+        contract_state_commitment_info: contracts_trie.into(),
+        contract_class_commitment_info: classes_trie.into(),
+        address_to_storage_commitment_info: storage_tries
+            .into_iter()
+            .map(|(address, commitment_info)| (address.0.to_hex(), commitment_info.into()))
+            .collect(),
+        contracts: previous_contract_states
+            .into_iter()
+            .map(|(address, contract_state)| (address.0.to_hex(), contract_state.into()))
+            .collect(),
+        class_hash_to_compiled_class_hash: executed_block.class_hash_to_compiled_class_hash,
+        compiled_classes: executed_block.compiled_classes,
+        deprecated_compiled_classes: executed_block.deprecated_compiled_classes,
+        transactions: executed_block.transactions,
+        block_info: executed_block.block_info,
+        chain_id: executed_block.chain_id,
+    })
+}
@@ -0,0 +1,76 @@
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use starknet_api::block::BlockHeaderWithoutHash;
+
+use super::{generate_os_input, ExecutedBlockInput};
+
+fn raw_felt(value: u8) -> [u8; 32] {
+    let mut felt = [0; 32];
+    felt[31] = value;
+    felt
+}
+
+#[tokio::test]
+async fn test_generate_os_input() {
+    // A block that writes a storage key of contract 0x1 and reads a key of contract 0x2, executed
+    // on an empty state.
+    let executed_block: ExecutedBlockInput = serde_json::from_value(json!({
+        "committer_input": {
+            "storage": [],
+            "state_diff": {
+                "address_to_class_hash": [],
+                "address_to_nonce": [{ "key": raw_felt(1), "value": raw_felt(1) }],
+                "class_hash_to_compiled_class_hash": [],
+                "storage_updates": [{
+                    "address": raw_felt(1),
+                    "storage_updates": [{ "key": raw_felt(10), "value": raw_felt(7) }],
+                }],
+            },
+            "contracts_trie_root_hash": raw_felt(0),
+            "classes_trie_root_hash": raw_felt(0),
+            "config": { "warn_on_trivial_modifications": false, "log_level": 20 },
+        },
+        "storage_reads": [{ "address": raw_felt(2), "keys": [raw_felt(11)] }],
+        "block_info": BlockHeaderWithoutHash::default(),
+        "chain_id": "SN_SEPOLIA",
+        "transactions": [],
+        "class_hash_to_compiled_class_hash": {},
+        "compiled_classes": {},
+        "deprecated_compiled_classes": {},
+    }))
+    .unwrap();
+
+    let os_input = generate_os_input(executed_block).await.unwrap();
+
+    let contracts_info = &os_input.contract_state_commitment_info;
+    assert_eq!(contracts_info.previous_root, "0x0");
+    assert_ne!(contracts_info.updated_root, "0x0");
+    assert_eq!(contracts_info.tree_height, 251);
+    // The facts include the preimage of the updated root.
+    assert!(contracts_info.commitment_facts.contains_key(&contracts_info.updated_root));
+    assert_eq!(os_input.contract_class_commitment_info.updated_root, "0x0");
+
+    assert_eq!(
+        os_input.address_to_storage_commitment_info.keys().collect::<Vec<_>>(),
+        vec!["0x1", "0x2"]
+    );
+    let read_only_info = &os_input.address_to_storage_commitment_info["0x2"];
+    assert_eq!(read_only_info.previous_root, "0x0");
+    assert_eq!(read_only_info.updated_root, "0x0");
+
+    assert_eq!(
+        serde_json::to_value(&os_input.contracts).unwrap(),
+        json!({
+            "0x1": {
+                "contract_hash": "0x0",
+                "storage_commitment_tree": { "root": "0x0", "height": 251 },
+                "nonce": "0x0",
+            },
+            "0x2": {
+                "contract_hash": "0x0",
+                "storage_commitment_tree": { "root": "0x0", "height": 251 },
+                "nonce": "0x0",
+            },
+        })
+    );
+}
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use starknet_committer::block_committer::commitment_info::StorageReads;
 use starknet_committer::block_committer::input::{
     ConfigImpl,
     ContractAddress,
@@ -14,7 +15,7 @@ use starknet_patricia::hash::hash_trait::HashOutput;
 use starknet_patricia::storage::errors::DeserializationError;
 use starknet_patricia::storage::storage_trait::{StorageKey, StorageValue};
 
-use crate::parse_input::raw_input::RawInput;
+use crate::parse_input::raw_input::{RawInput, RawStorageReads};
 
 pub type InputImpl = Input<ConfigImpl>;
 
@@ -95,6 +96,25 @@ impl TryFrom<RawInput> for InputImpl {
     }
 }
 
+pub(crate) fn cast_storage_reads(
+    raw_storage_reads: Vec<RawStorageReads>,
+) -> Result<StorageReads, DeserializationError> {
+    let mut storage_reads = HashMap::new();
+    for entry in raw_storage_reads {
+        add_unique(
+            &mut storage_reads,
+            "storage reads",
+            ContractAddress(Felt::from_bytes_be_slice(&entry.address)),
+            entry
+                .keys
+                .iter()
+                .map(|key| StarknetStorageKey(Felt::from_bytes_be_slice(key)))
+                .collect(),
+        )?;
+    }
+    Ok(storage_reads)
+}
+
 pub(crate) fn add_unique<K, V>(
     map: &mut HashMap<K, V>,
     map_name: &str,
@@ -81,3 +81,11 @@ pub(crate) struct RawStateDiff {
     /// Will be casted to HashMap<Felt, HashMap<Felt, Felt>>.
     pub storage_updates: Vec<RawStorageUpdates>,
 }
+
+#[derive(Deserialize, Debug)]
+/// Represents the storage keys of a contract that were read without being written. Later will be
+/// casted to HashMap<Felt, HashSet<Felt>> entry.
+pub(crate) struct RawStorageReads {
+    pub address: RawFelt,
+    pub keys: Vec<RawFelt>,
+}
@@ -655,7 +655,7 @@ fn test_storage_node(data: HashMap<String, String>) -> Result<String, PythonTest
 /// Generates a dummy random filled forest and serializes it to a JSON string.
 pub(crate) fn filled_forest_output_test() -> Result<String, PythonTestError> {
     let dummy_forest = SerializedForest(FilledForest::dummy_random(&mut rand::thread_rng(), None));
-    let output = dummy_forest.forest_to_output();
+    let output = dummy_forest.forest_to_output(None);
     let output_string = serde_json::to_string(&output).expect("Failed to serialize");
     Ok(output_string)
 }
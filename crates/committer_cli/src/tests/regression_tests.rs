@@ -141,7 +141,7 @@ pub async fn test_single_committer_flow(input: String, output_path: String) -> R
         expected_facts,
     } = serde_json::from_str(&input).unwrap();
     // Benchmark the committer flow test.
-    commit(committer_input.0, output_path.to_owned()).await;
+    commit(committer_input.0, output_path.to_owned(), None).await;
 
     // Assert correctness of the output of the committer flow test.
     let CommitterRegressionOutput {
@@ -0,0 +1,185 @@
+//! A short-lived cache of the state the gateway reads to admit transactions.
+//!
+//! Validating a transaction reads the nonce and class of its sender, and the sender's balances in
+//! the fee tokens. On the first read of an account, the cache reads all of them in a single batch,
+//! and keeps them, with the rest of the state read during validation, for the transactions that
+//! are validated against the same block. Bursts of transactions from many accounts thus don't read
+//! the state value by value, and the transactions of an account don't read it again. The cache is
+//! cleared once transactions are validated against a newer block.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use blockifier::abi::abi_utils::get_fee_token_var_address;
+use blockifier::abi::sierra_types::next_storage_key;
+use blockifier::blockifier::block::BlockInfo;
+use blockifier::context::ChainInfo;
+use blockifier::execution::contract_class::ContractClass;
+use blockifier::state::errors::StateError;
+use blockifier::state::state_api::{StateReader as BlockifierStateReader, StateResult};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::state::StorageKey;
+use starknet_types_core::felt::Felt;
+
+use crate::config::AdmissionCacheConfig;
+use crate::state_reader::{MempoolStateReader, StateReadKey, StateReaderFactory};
+
+#[cfg(test)]
+#[path = "admission_cache_test.rs"]
+mod admission_cache_test;
+
+/// The state values read for validating transactions against a single block.
+pub struct AdmissionCache {
+    max_cached_values: usize,
+    fee_token_addresses: [ContractAddress; 2],
+    block_state: Mutex<BlockState>,
+}
+
+#[derive(Default)]
+struct BlockState {
+    block_number: BlockNumber,
+    values: HashMap<StateReadKey, Felt>,
+}
+
+impl AdmissionCache {
+    pub fn new(config: &AdmissionCacheConfig, chain_info: &ChainInfo) -> Self {
+        let fee_token_addresses = &chain_info.fee_token_addresses;
+        Self {
+            max_cached_values: config.max_cached_values,
+            fee_token_addresses: [
+                fee_token_addresses.eth_fee_token_address,
+                fee_token_addresses.strk_fee_token_address,
+            ],
+            block_state: Mutex::new(BlockState::default()),
+        }
+    }
+
+    // Returns the value of the key at the given block, if it's cached. Reading a newer block than
+    // the cached one clears the cache.
+    fn get(&self, block_number: BlockNumber, key: &StateReadKey) -> Option<Felt> {
+        let mut block_state = self.block_state.lock().expect("Failed to lock the cache.");
+        if block_number > block_state.block_number {
+            *block_state = BlockState { block_number, values: HashMap::new() };
+        }
+        if block_number < block_state.block_number {
+            return None;
+        }
+        block_state.values.get(key).copied()
+    }
+
+    // Caches values read at the given block. Values of an older block than the cached one aren't
+    // cached.
+    fn insert(
+        &self,
+        block_number: BlockNumber,
+        values: impl IntoIterator<Item = (StateReadKey, Felt)>,
+    ) {
+        let mut block_state = self.block_state.lock().expect("Failed to lock the cache.");
+        if block_number != block_state.block_number {
+            return;
+        }
+        for (key, value) in values {
+            if block_state.values.len() >= self.max_cached_values {
+                block_state.values.clear();
+            }
+            block_state.values.insert(key, value);
+        }
+    }
+
+    // The state of an account that validating its transactions reads.
+    fn account_keys(&self, address: ContractAddress) -> StateResult<Vec<StateReadKey>> {
+        let balance_low_key = get_fee_token_var_address(address);
+        let balance_high_key = next_storage_key(&balance_low_key)?;
+        let mut keys = vec![StateReadKey::Nonce(address), StateReadKey::ClassHash(address)];
+        for fee_token_address in self.fee_token_addresses {
+            keys.push(StateReadKey::Storage(fee_token_address, balance_low_key));
+            keys.push(StateReadKey::Storage(fee_token_address, balance_high_key));
+        }
+        Ok(keys)
+    }
+}
+
+/// Reads the state of a block through an [`AdmissionCache`].
+pub struct CachedStateReader {
+    state_reader: Box<dyn MempoolStateReader>,
+    block_number: BlockNumber,
+    cache: Arc<AdmissionCache>,
+}
+
+impl CachedStateReader {
+    fn get(&self, key: StateReadKey) -> StateResult<Felt> {
+        if let Some(value) = self.cache.get(self.block_number, &key) {
+            return Ok(value);
+        }
+        // Reading the nonce or class of an account is the first read of its validation, so the
+        // rest of the account's state is read with it.
+        let keys = match key {
+            StateReadKey::Nonce(address) | StateReadKey::ClassHash(address) => {
+                self.cache.account_keys(address)?
+            }
+            StateReadKey::Storage(..) => vec![key],
+        };
+        let values = self.state_reader.get_batch(&keys)?;
+        let value = keys
+            .iter()
+            .zip(&values)
+            .find_map(|(read_key, value)| (*read_key == key).then_some(*value))
+            .ok_or_else(|| StateError::StateReadError(format!("{key:?} wasn't read.")))?;
+        self.cache.insert(self.block_number, keys.into_iter().zip(values));
+        Ok(value)
+    }
+}
+
+impl MempoolStateReader for CachedStateReader {
+    fn get_block_info(&self) -> Result<BlockInfo, StateError> {
+        self.state_reader.get_block_info()
+    }
+}
+
+impl BlockifierStateReader for CachedStateReader {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<Felt> {
+        self.get(StateReadKey::Storage(contract_address, key))
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        Ok(Nonce(self.get(StateReadKey::Nonce(contract_address))?))
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        Ok(ClassHash(self.get(StateReadKey::ClassHash(contract_address))?))
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        self.state_reader.get_compiled_contract_class(class_hash)
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        self.state_reader.get_compiled_class_hash(class_hash)
+    }
+}
+
+/// Creates state readers that read the state of a given block through an [`AdmissionCache`]. The
+/// state of the latest block, which changes as blocks are added, isn't cached.
+pub struct CachedStateReaderFactory {
+    pub state_reader_factory: Arc<dyn StateReaderFactory>,
+    pub cache: Arc<AdmissionCache>,
+}
+
+impl StateReaderFactory for CachedStateReaderFactory {
+    fn get_state_reader_from_latest_block(&self) -> Box<dyn MempoolStateReader> {
+        self.state_reader_factory.get_state_reader_from_latest_block()
+    }
+
+    fn get_state_reader(&self, block_number: BlockNumber) -> Box<dyn MempoolStateReader> {
+        Box::new(CachedStateReader {
+            state_reader: self.state_reader_factory.get_state_reader(block_number),
+            block_number,
+            cache: self.cache.clone(),
+        })
+    }
+}
@@ -0,0 +1,155 @@
+use std::sync::{Arc, Mutex};
+
+use blockifier::abi::abi_utils::get_fee_token_var_address;
+use blockifier::blockifier::block::BlockInfo;
+use blockifier::context::ChainInfo;
+use blockifier::execution::contract_class::ContractClass;
+use blockifier::state::errors::StateError;
+use blockifier::state::state_api::{StateReader as BlockifierStateReader, StateResult};
+use blockifier::test_utils::dict_state_reader::DictStateReader;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce, PatriciaKey};
+use starknet_api::state::StorageKey;
+use starknet_api::{class_hash, contract_address, felt, patricia_key};
+use starknet_types_core::felt::Felt;
+
+use crate::admission_cache::{AdmissionCache, CachedStateReaderFactory};
+use crate::config::AdmissionCacheConfig;
+use crate::state_reader::{MempoolStateReader, StateReadKey, StateReaderFactory};
+
+// A state reader that records the batches it reads.
+#[derive(Clone, Default)]
+struct RecordingStateReader {
+    state: DictStateReader,
+    batches: Arc<Mutex<Vec<Vec<StateReadKey>>>>,
+}
+
+impl RecordingStateReader {
+    fn num_batches(&self) -> usize {
+        self.batches.lock().unwrap().len()
+    }
+}
+
+impl MempoolStateReader for RecordingStateReader {
+    fn get_block_info(&self) -> Result<BlockInfo, StateError> {
+        Ok(BlockInfo::create_for_testing())
+    }
+
+    fn get_batch(&self, keys: &[StateReadKey]) -> StateResult<Vec<Felt>> {
+        self.batches.lock().unwrap().push(keys.to_vec());
+        keys.iter()
+            .map(|key| match *key {
+                StateReadKey::Storage(address, key) => self.get_storage_at(address, key),
+                StateReadKey::Nonce(address) => Ok(self.get_nonce_at(address)?.0),
+                StateReadKey::ClassHash(address) => Ok(self.get_class_hash_at(address)?.0),
+            })
+            .collect()
+    }
+}
+
+impl BlockifierStateReader for RecordingStateReader {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<Felt> {
+        self.state.get_storage_at(contract_address, key)
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        self.state.get_nonce_at(contract_address)
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        self.state.get_class_hash_at(contract_address)
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        self.state.get_compiled_contract_class(class_hash)
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        self.state.get_compiled_class_hash(class_hash)
+    }
+}
+
+struct RecordingStateReaderFactory {
+    state_reader: RecordingStateReader,
+}
+
+impl StateReaderFactory for RecordingStateReaderFactory {
+    fn get_state_reader_from_latest_block(&self) -> Box<dyn MempoolStateReader> {
+        Box::new(self.state_reader.clone())
+    }
+
+    fn get_state_reader(&self, _block_number: BlockNumber) -> Box<dyn MempoolStateReader> {
+        Box::new(self.state_reader.clone())
+    }
+}
+
+fn cached_factory(state_reader: &RecordingStateReader) -> CachedStateReaderFactory {
+    CachedStateReaderFactory {
+        state_reader_factory: Arc::new(RecordingStateReaderFactory {
+            state_reader: state_reader.clone(),
+        }),
+        cache: Arc::new(AdmissionCache::new(
+            &AdmissionCacheConfig::default(),
+            &ChainInfo::create_for_testing(),
+        )),
+    }
+}
+
+#[test]
+fn account_state_is_read_in_a_single_batch() {
+    let account = contract_address!("0x1");
+    let fee_token_address =
+        ChainInfo::create_for_testing().fee_token_addresses.strk_fee_token_address;
+    let balance_key = get_fee_token_var_address(account);
+    let mut state_reader = RecordingStateReader::default();
+    state_reader.state.address_to_nonce.insert(account, Nonce(felt!("0x2")));
+    state_reader.state.address_to_class_hash.insert(account, class_hash!("0x3"));
+    state_reader.state.storage_view.insert((fee_token_address, balance_key), felt!("0x4"));
+    let factory = cached_factory(&state_reader);
+
+    let cached_state_reader = factory.get_state_reader(BlockNumber(1));
+    assert_eq!(cached_state_reader.get_nonce_at(account).unwrap(), Nonce(felt!("0x2")));
+    // The nonce, the class hash, and the low and high balances in both fee tokens.
+    assert_eq!(state_reader.batches.lock().unwrap()[0].len(), 6);
+
+    assert_eq!(cached_state_reader.get_class_hash_at(account).unwrap(), class_hash!("0x3"));
+    assert_eq!(
+        cached_state_reader.get_fee_token_balance(account, fee_token_address).unwrap(),
+        (felt!("0x4"), Felt::ZERO)
+    );
+    assert_eq!(state_reader.num_batches(), 1);
+
+    // Other storage is read, and then cached, key by key.
+    let other_key = (contract_address!("0x5"), StorageKey::from(6_u32));
+    cached_state_reader.get_storage_at(other_key.0, other_key.1).unwrap();
+    cached_state_reader.get_storage_at(other_key.0, other_key.1).unwrap();
+    assert_eq!(
+        *state_reader.batches.lock().unwrap().last().unwrap(),
+        vec![StateReadKey::Storage(other_key.0, other_key.1)]
+    );
+    assert_eq!(state_reader.num_batches(), 2);
+}
+
+#[test]
+fn cache_is_kept_for_a_single_block() {
+    let account = contract_address!("0x1");
+    let state_reader = RecordingStateReader::default();
+    let factory = cached_factory(&state_reader);
+
+    factory.get_state_reader(BlockNumber(1)).get_nonce_at(account).unwrap();
+    factory.get_state_reader(BlockNumber(1)).get_nonce_at(account).unwrap();
+    assert_eq!(state_reader.num_batches(), 1);
+
+    // A newer block clears the cache.
+    factory.get_state_reader(BlockNumber(2)).get_nonce_at(account).unwrap();
+    assert_eq!(state_reader.num_batches(), 2);
+
+    // An older block isn't cached.
+    factory.get_state_reader(BlockNumber(1)).get_nonce_at(account).unwrap();
+    factory.get_state_reader(BlockNumber(1)).get_nonce_at(account).unwrap();
+    assert_eq!(state_reader.num_batches(), 4);
+}
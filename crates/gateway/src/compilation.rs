@@ -7,7 +7,9 @@ use starknet_api::core::CompiledClassHash;
 use starknet_api::rpc_transaction::RpcDeclareTransaction;
 use starknet_sierra_compile::cairo_lang_compiler::CairoLangSierraToCasmCompiler;
 use starknet_sierra_compile::command_line_compiler::CommandLineCompiler;
-use starknet_sierra_compile::config::SierraToCasmCompilationConfig;
+use starknet_sierra_compile::compilation_service::CompilationService;
+use starknet_sierra_compile::config::{CompilationServiceConfig, SierraToCasmCompilationConfig};
+use starknet_sierra_compile::errors::CompilationUtilError;
 use starknet_sierra_compile::utils::into_contract_class_for_compilation;
 use starknet_sierra_compile::SierraToCasmCompiler;
 use tracing::{debug, error};
@@ -29,6 +31,18 @@ impl GatewayCompiler {
         Self { sierra_to_casm_compiler: Arc::new(CommandLineCompiler::new(config)) }
     }
 
+    /// Compiles with the command line compiler, through a [`CompilationService`] that caches the
+    /// compiled classes.
+    pub fn new_compilation_service(
+        compiler_config: SierraToCasmCompilationConfig,
+        service_config: CompilationServiceConfig,
+    ) -> Result<Self, CompilationUtilError> {
+        let compiler = Arc::new(CommandLineCompiler::new(compiler_config));
+        Ok(Self {
+            sierra_to_casm_compiler: Arc::new(CompilationService::new(service_config, compiler)?),
+        })
+    }
+
     // TODO(Arni): Cosider deleting `CairoLangSierraToCasmCompiler`.
     pub fn new_cairo_lang_compiler(config: SierraToCasmCompilationConfig) -> Self {
         Self { sierra_to_casm_compiler: Arc::new(CairoLangSierraToCasmCompiler { config }) }
@@ -62,10 +76,24 @@ impl GatewayCompiler {
     ) -> GatewayResult<CasmContractClass> {
         match self.sierra_to_casm_compiler.compile(cairo_lang_contract_class) {
             Ok(casm_contract_class) => Ok(casm_contract_class),
-            Err(starknet_sierra_compile::errors::CompilationUtilError::UnexpectedError(error)) => {
+            Err(CompilationUtilError::UnexpectedError(error)) => {
                 error!("Compilation panicked. Error: {:?}", error);
                 Err(GatewaySpecError::UnexpectedError { data: "Internal server error.".to_owned() })
             }
+            Err(CompilationUtilError::QueueFull) => {
+                debug!("The compilation queue is full.");
+                Err(GatewaySpecError::UnexpectedError {
+                    data: "The compilation service is busy.".to_owned(),
+                })
+            }
+            // Unlike a failed compilation, a timeout doesn't mean the class is invalid; the
+            // compilation carries on, so a retry may be answered from the cache.
+            Err(CompilationUtilError::Timeout(timeout)) => {
+                debug!("The compilation didn't finish within {:?}.", timeout);
+                Err(GatewaySpecError::UnexpectedError {
+                    data: "The compilation timed out, retry later.".to_owned(),
+                })
+            }
             Err(e) => {
                 debug!("Compilation failed: {:?}", e);
                 Err(GatewaySpecError::CompilationFailed)
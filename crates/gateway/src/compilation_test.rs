@@ -1,4 +1,9 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use assert_matches::assert_matches;
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use cairo_lang_starknet_classes::contract_class::ContractClass;
 use mempool_test_utils::starknet_api_test_utils::{
     compiled_class_hash as test_contract_compiled_class_hash,
     declare_tx as rpc_declare_tx,
@@ -12,6 +17,7 @@ use starknet_api::rpc_transaction::{
 };
 use starknet_sierra_compile::config::SierraToCasmCompilationConfig;
 use starknet_sierra_compile::errors::CompilationUtilError;
+use starknet_sierra_compile::SierraToCasmCompiler;
 use tracing_test::traced_test;
 
 use crate::compilation::GatewayCompiler;
@@ -106,3 +112,20 @@ fn test_process_declare_tx_success(
     assert_eq!(class_info.sierra_program_length, sierra_program_length);
     assert_eq!(class_info.abi_length, abi_length);
 }
+
+struct TimingOutCompiler;
+
+impl SierraToCasmCompiler for TimingOutCompiler {
+    fn compile(&self, _: ContractClass) -> Result<CasmContractClass, CompilationUtilError> {
+        Err(CompilationUtilError::Timeout(Duration::from_secs(1)))
+    }
+}
+
+#[rstest]
+fn test_compilation_timeout_is_not_a_compilation_failure(declare_tx_v3: RpcDeclareTransactionV3) {
+    let gateway_compiler = GatewayCompiler { sierra_to_casm_compiler: Arc::new(TimingOutCompiler) };
+
+    let err =
+        gateway_compiler.process_declare_tx(&RpcDeclareTransaction::V3(declare_tx_v3)).unwrap_err();
+    assert_matches!(err, GatewaySpecError::UnexpectedError { data } if data.contains("timed out"));
+}
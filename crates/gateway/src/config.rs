@@ -2,25 +2,46 @@ use std::collections::BTreeMap;
 use std::net::IpAddr;
 
 use blockifier::context::ChainInfo;
+use blockifier::fee::fee_market::FeeMarketConfig;
 use papyrus_config::dumping::{append_sub_config_name, ser_param, SerializeConfig};
+use papyrus_config::validators::cross_param_validation_error;
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use serde::{Deserialize, Serialize};
-use starknet_api::core::Nonce;
+use starknet_api::core::{ChainId, ContractAddress, Nonce};
+use starknet_mempool_types::stateless_validation::StatelessValidationConfig;
 use starknet_types_core::felt::Felt;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
 use crate::compiler_version::VersionId;
 
+#[cfg(test)]
+#[path = "config_test.rs"]
+mod config_test;
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize, Validate, PartialEq)]
 pub struct GatewayConfig {
+    #[validate]
     pub network_config: GatewayNetworkConfig,
+    #[validate]
     pub stateless_tx_validator_config: StatelessTransactionValidatorConfig,
+    #[validate]
     pub stateful_tx_validator_config: StatefulTransactionValidatorConfig,
+    #[validate]
+    pub pipeline_config: GatewayPipelineConfig,
+    /// Whether the gateway serves snapshots of the transactions eligible for sequencing.
+    pub expose_mempool_snapshot: bool,
 }
 
 impl SerializeConfig for GatewayConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
         vec![
+            BTreeMap::from_iter([ser_param(
+                "expose_mempool_snapshot",
+                &self.expose_mempool_snapshot,
+                "If true, the gateway serves read-only snapshots of the transactions eligible for \
+                 sequencing, e.g. for external block builders.",
+                ParamPrivacyInput::Public,
+            )]),
             append_sub_config_name(self.network_config.dump(), "network_config"),
             append_sub_config_name(
                 self.stateless_tx_validator_config.dump(),
@@ -30,6 +51,7 @@ impl SerializeConfig for GatewayConfig {
                 self.stateful_tx_validator_config.dump(),
                 "stateful_tx_validator_config",
             ),
+            append_sub_config_name(self.pipeline_config.dump(), "pipeline_config"),
         ]
         .into_iter()
         .flatten()
@@ -64,13 +86,58 @@ impl Default for GatewayNetworkConfig {
     }
 }
 
+/// The numbers of workers of the stages of processing a transaction. See [`GatewayPipeline`].
+///
+/// [`GatewayPipeline`]: crate::pipeline::GatewayPipeline
+#[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
+pub struct GatewayPipelineConfig {
+    #[validate(range(min = 1))]
+    pub n_stateless_validation_workers: usize,
+    #[validate(range(min = 1))]
+    pub n_compilation_workers: usize,
+    #[validate(range(min = 1))]
+    pub n_stateful_validation_workers: usize,
+}
+
+impl SerializeConfig for GatewayPipelineConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "n_stateless_validation_workers",
+                &self.n_stateless_validation_workers,
+                "The maximal number of transactions validated statelessly at once.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "n_compilation_workers",
+                &self.n_compilation_workers,
+                "The maximal number of classes of declare transactions compiled at once.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "n_stateful_validation_workers",
+                &self.n_stateful_validation_workers,
+                "The maximal number of transactions validated against the state at once.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+impl Default for GatewayPipelineConfig {
+    fn default() -> Self {
+        Self {
+            n_stateless_validation_workers: 64,
+            n_compilation_workers: 4,
+            n_stateful_validation_workers: 32,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
 pub struct StatelessTransactionValidatorConfig {
-    // If true, validates that the resource bounds are not zero.
-    pub validate_non_zero_l1_gas_fee: bool,
-    pub validate_non_zero_l2_gas_fee: bool,
-    pub max_calldata_length: usize,
-    pub max_signature_length: usize,
+    // The checks shared with the batcher.
+    pub stateless_validation: StatelessValidationConfig,
 
     // Declare txs specific config.
     pub max_contract_class_object_size: usize,
@@ -81,10 +148,7 @@ pub struct StatelessTransactionValidatorConfig {
 impl Default for StatelessTransactionValidatorConfig {
     fn default() -> Self {
         StatelessTransactionValidatorConfig {
-            validate_non_zero_l1_gas_fee: true,
-            validate_non_zero_l2_gas_fee: false,
-            max_calldata_length: 4000,
-            max_signature_length: 4000,
+            stateless_validation: StatelessValidationConfig::default(),
             max_contract_class_object_size: 4089446,
             min_sierra_version: VersionId::new(1, 1, 0),
             max_sierra_version: VersionId::new(1, 5, usize::MAX),
@@ -94,40 +158,15 @@ impl Default for StatelessTransactionValidatorConfig {
 
 impl SerializeConfig for StatelessTransactionValidatorConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
-        let members = BTreeMap::from_iter([
-            ser_param(
-                "validate_non_zero_l1_gas_fee",
-                &self.validate_non_zero_l1_gas_fee,
-                "If true, validates that a transaction has non-zero L1 resource bounds.",
-                ParamPrivacyInput::Public,
-            ),
-            ser_param(
-                "validate_non_zero_l2_gas_fee",
-                &self.validate_non_zero_l2_gas_fee,
-                "If true, validates that a transaction has non-zero L2 resource bounds.",
-                ParamPrivacyInput::Public,
-            ),
-            ser_param(
-                "max_signature_length",
-                &self.max_signature_length,
-                "Limitation of signature length.",
-                ParamPrivacyInput::Public,
-            ),
-            ser_param(
-                "max_calldata_length",
-                &self.max_calldata_length,
-                "Limitation of calldata length.",
-                ParamPrivacyInput::Public,
-            ),
-            ser_param(
-                "max_contract_class_object_size",
-                &self.max_contract_class_object_size,
-                "Limitation of contract class object size.",
-                ParamPrivacyInput::Public,
-            ),
-        ]);
+        let members = BTreeMap::from_iter([ser_param(
+            "max_contract_class_object_size",
+            &self.max_contract_class_object_size,
+            "Limitation of contract class object size.",
+            ParamPrivacyInput::Public,
+        )]);
         vec![
             members,
+            append_sub_config_name(self.stateless_validation.dump(), "stateless_validation"),
             append_sub_config_name(self.min_sierra_version.dump(), "min_sierra_version"),
             append_sub_config_name(self.max_sierra_version.dump(), "max_sierra_version"),
         ]
@@ -165,11 +204,17 @@ impl SerializeConfig for RpcStateReaderConfig {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
+#[validate(schema(function = "validate_fee_token_addresses", skip_on_field_errors = false))]
+#[validate(schema(function = "validate_fee_split", skip_on_field_errors = false))]
+#[validate(schema(function = "validate_nonce_window", skip_on_field_errors = false))]
 pub struct StatefulTransactionValidatorConfig {
     pub max_nonce_for_validation_skip: Nonce,
     pub validate_max_n_steps: u32,
     pub max_recursion_depth: usize,
     pub chain_info: ChainInfo,
+    pub gas_price_floor_config: GasPriceFloorConfig,
+    pub admission_cache_config: AdmissionCacheConfig,
+    pub declare_antispam_config: DeclareAntispamConfig,
 }
 
 impl Default for StatefulTransactionValidatorConfig {
@@ -179,6 +224,9 @@ impl Default for StatefulTransactionValidatorConfig {
             validate_max_n_steps: 1_000_000,
             max_recursion_depth: 50,
             chain_info: ChainInfo::default(),
+            gas_price_floor_config: GasPriceFloorConfig::default(),
+            admission_cache_config: AdmissionCacheConfig::default(),
+            declare_antispam_config: DeclareAntispamConfig::default(),
         }
     }
 }
@@ -205,9 +253,208 @@ impl SerializeConfig for StatefulTransactionValidatorConfig {
                 ParamPrivacyInput::Public,
             ),
         ]);
-        let sub_configs = append_sub_config_name(self.chain_info.dump(), "chain_info");
-        vec![members, sub_configs].into_iter().flatten().collect()
+        vec![
+            members,
+            append_sub_config_name(self.chain_info.dump(), "chain_info"),
+            append_sub_config_name(self.gas_price_floor_config.dump(), "gas_price_floor_config"),
+            append_sub_config_name(self.admission_cache_config.dump(), "admission_cache_config"),
+            append_sub_config_name(self.declare_antispam_config.dump(), "declare_antispam_config"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+/// The minimal L2 gas price of incoming transactions. A transaction must be able to pay the lowest
+/// base fee the next block may have, as predicted by the fee market, less a grace margin.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct GasPriceFloorConfig {
+    pub enabled: bool,
+    pub grace_margin_percent: u8,
+    pub fee_market_config: FeeMarketConfig,
+}
+
+impl Default for GasPriceFloorConfig {
+    fn default() -> Self {
+        Self { enabled: false, grace_margin_percent: 10, fee_market_config: Default::default() }
+    }
+}
+
+impl SerializeConfig for GasPriceFloorConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        let members = BTreeMap::from_iter([
+            ser_param(
+                "enabled",
+                &self.enabled,
+                "If true, transactions whose L2 gas price is below the floor are rejected.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "grace_margin_percent",
+                &self.grace_margin_percent,
+                "The margin, in percents, below the predicted base fee that is still accepted.",
+                ParamPrivacyInput::Public,
+            ),
+        ]);
+        vec![members, append_sub_config_name(self.fee_market_config.dump(), "fee_market_config")]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+}
+
+/// The cache of the state read for validating transactions against the latest block. See
+/// [`AdmissionCache`](crate::admission_cache::AdmissionCache).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AdmissionCacheConfig {
+    pub enabled: bool,
+    pub max_cached_values: usize,
+}
+
+impl Default for AdmissionCacheConfig {
+    fn default() -> Self {
+        Self { enabled: true, max_cached_values: 100_000 }
+    }
+}
+
+impl SerializeConfig for AdmissionCacheConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "enabled",
+                &self.enabled,
+                "If true, the state read for validating transactions is cached until the next \
+                 block, and the state of each account is read in a single batch.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_cached_values",
+                &self.max_cached_values,
+                "The maximal number of cached state values. The cache is cleared once it's full.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+/// The requirement a declare transaction must meet to be admitted, on top of being able to pay its
+/// fee. Declared classes are stored forever, so a chain may make spamming it with classes costly.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DeclareAntispamPolicy {
+    #[default]
+    Disabled,
+    /// The declare must commit to pay a fee of at least `min_committed_fee`. The sender must hold
+    /// the committed fee as a deposit, but is charged only the actual fee.
+    MinCommittedFee,
+    /// The sender must hold a fee token balance of at least `balance_to_fee_ratio` times the fee
+    /// the declare commits to pay.
+    MinBalance,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DeclareAntispamConfig {
+    pub policy: DeclareAntispamPolicy,
+    pub min_committed_fee: u128,
+    pub balance_to_fee_ratio: u128,
+}
+
+impl Default for DeclareAntispamConfig {
+    fn default() -> Self {
+        Self {
+            policy: DeclareAntispamPolicy::Disabled,
+            min_committed_fee: 1_000_000_000_000_000_000,
+            balance_to_fee_ratio: 10,
+        }
+    }
+}
+
+impl SerializeConfig for DeclareAntispamConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "policy",
+                &self.policy,
+                "The requirement a declare transaction must meet to be admitted: Disabled, \
+                 MinCommittedFee or MinBalance.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "min_committed_fee",
+                &self.min_committed_fee,
+                "The minimal fee, in fri, a declare transaction must commit to pay under the \
+                 MinCommittedFee policy.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "balance_to_fee_ratio",
+                &self.balance_to_fee_ratio,
+                "The minimal ratio between the sender's fee token balance and the fee a declare \
+                 transaction commits to pay under the MinBalance policy.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+/// Validates that the fee token addresses are set on the public chains, where transactions pay
+/// their fees.
+fn validate_fee_token_addresses(
+    config: &StatefulTransactionValidatorConfig,
+) -> Result<(), ValidationError> {
+    let chain_info = &config.chain_info;
+    if !matches!(chain_info.chain_id, ChainId::Mainnet | ChainId::Sepolia) {
+        return Ok(());
+    }
+    let fee_token_addresses = &chain_info.fee_token_addresses;
+    let unset_addresses: Vec<&str> = [
+        (
+            "chain_info.fee_token_addresses.strk_fee_token_address",
+            fee_token_addresses.strk_fee_token_address,
+        ),
+        (
+            "chain_info.fee_token_addresses.eth_fee_token_address",
+            fee_token_addresses.eth_fee_token_address,
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, address)| *address == ContractAddress::default())
+    .map(|(param_path, _)| param_path)
+    .collect();
+    if !unset_addresses.is_empty() {
+        return Err(cross_param_validation_error(
+            "fee token address not set",
+            &unset_addresses,
+            format!("The fee token addresses must be set on chain {}.", chain_info.chain_id),
+        ));
+    }
+    Ok(())
+}
+
+/// Validates that the fee split doesn't exceed the whole fee.
+fn validate_fee_split(config: &StatefulTransactionValidatorConfig) -> Result<(), ValidationError> {
+    match &config.chain_info.fee_split {
+        Some(fee_split) if fee_split.percentage > 100 => Err(cross_param_validation_error(
+            "fee split percentage out of range",
+            &["chain_info.fee_split.percentage"],
+            format!("The fee split percentage must be at most 100, got {}.", fee_split.percentage),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Validates that the nonce window, if set, admits at least the account nonce.
+fn validate_nonce_window(
+    config: &StatefulTransactionValidatorConfig,
+) -> Result<(), ValidationError> {
+    if config.chain_info.nonce_config.nonce_window == Some(0) {
+        return Err(cross_param_validation_error(
+            "nonce window out of range",
+            &["chain_info.nonce_config.nonce_window"],
+            "The nonce window must be positive.".to_owned(),
+        ));
     }
+    Ok(())
 }
 
 impl StatefulTransactionValidatorConfig {
@@ -218,6 +465,9 @@ impl StatefulTransactionValidatorConfig {
             validate_max_n_steps: 1000000,
             max_recursion_depth: 50,
             chain_info: ChainInfo::create_for_testing(),
+            gas_price_floor_config: GasPriceFloorConfig::default(),
+            admission_cache_config: AdmissionCacheConfig::default(),
+            declare_antispam_config: DeclareAntispamConfig::default(),
         }
     }
 }
@@ -0,0 +1,54 @@
+use assert_matches::assert_matches;
+use blockifier::context::FeeSplit;
+use papyrus_config::validators::config_validate;
+use papyrus_config::ConfigError;
+use starknet_api::contract_address;
+use starknet_api::core::ChainId;
+
+use crate::config::StatefulTransactionValidatorConfig;
+
+#[test]
+fn fee_token_addresses_are_required_on_public_chains() {
+    let mut config = StatefulTransactionValidatorConfig::default();
+    assert_matches!(config_validate(&config), Ok(()));
+
+    config.chain_info.chain_id = ChainId::Mainnet;
+    config.chain_info.fee_token_addresses.eth_fee_token_address = contract_address!("0x1");
+    assert_matches!(
+        config_validate(&config),
+        Err(ConfigError::ConfigValidationError(errors))
+        if errors.0.len() == 1
+            && errors.0[0].param_path == "chain_info.fee_token_addresses.strk_fee_token_address"
+    );
+
+    config.chain_info.fee_token_addresses.strk_fee_token_address = contract_address!("0x2");
+    assert_matches!(config_validate(&config), Ok(()));
+}
+
+#[test]
+fn fee_split_percentage_is_at_most_100() {
+    let mut config = StatefulTransactionValidatorConfig::default();
+    config.chain_info.fee_split = Some(FeeSplit { percentage: 100, ..Default::default() });
+    assert_matches!(config_validate(&config), Ok(()));
+
+    config.chain_info.fee_split = Some(FeeSplit { percentage: 101, ..Default::default() });
+    assert_matches!(
+        config_validate(&config),
+        Err(ConfigError::ConfigValidationError(errors))
+        if errors.0.len() == 1 && errors.0[0].param_path == "chain_info.fee_split.percentage"
+    );
+}
+
+#[test]
+fn nonce_window_is_positive() {
+    let mut config = StatefulTransactionValidatorConfig::default();
+    config.chain_info.nonce_config.nonce_window = Some(1);
+    assert_matches!(config_validate(&config), Ok(()));
+
+    config.chain_info.nonce_config.nonce_window = Some(0);
+    assert_matches!(
+        config_validate(&config),
+        Err(ConfigError::ConfigValidationError(errors))
+        if errors.0.len() == 1 && errors.0[0].param_path == "chain_info.nonce_config.nonce_window"
+    );
+}
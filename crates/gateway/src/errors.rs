@@ -23,7 +23,7 @@ use papyrus_rpc::error::{
 };
 use serde_json::{Error as SerdeError, Value};
 use starknet_api::block::GasPrice;
-use starknet_api::transaction::{Resource, ResourceBounds};
+use starknet_mempool_types::stateless_validation::StatelessValidationError;
 use thiserror::Error;
 
 use crate::compiler_version::{VersionId, VersionIdError};
@@ -50,11 +50,6 @@ impl IntoResponse for GatewaySpecError {
 #[derive(Debug, Error)]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum StatelessTransactionValidatorError {
-    #[error(
-        "Calldata length exceeded maximum: length {calldata_length}
-        (allowed length: {max_calldata_length})."
-    )]
-    CalldataTooLong { calldata_length: usize, max_calldata_length: usize },
     #[error(
         "Cannot declare contract class with size of {contract_class_object_size}; max allowed \
          size: {max_contract_class_object_size}."
@@ -67,18 +62,13 @@ pub enum StatelessTransactionValidatorError {
     EntryPointsNotUniquelySorted,
     #[error(transparent)]
     InvalidSierraVersion(#[from] VersionIdError),
-    #[error(
-        "Signature length exceeded maximum: length {signature_length}
-        (allowed length: {max_signature_length})."
-    )]
-    SignatureTooLong { signature_length: usize, max_signature_length: usize },
     #[error(
         "Sierra versions older than {min_version} or newer than {max_version} are not supported. \
          The Sierra version of the declared contract is {version}."
     )]
     UnsupportedSierraVersion { version: VersionId, min_version: VersionId, max_version: VersionId },
-    #[error("Expected a positive amount of {resource:?}. Got {resource_bounds:?}.")]
-    ZeroResourceBounds { resource: Resource, resource_bounds: ResourceBounds },
+    #[error(transparent)]
+    StatelessValidationError(#[from] StatelessValidationError),
 }
 
 impl From<StatelessTransactionValidatorError> for GatewaySpecError {
@@ -90,11 +80,12 @@ impl From<StatelessTransactionValidatorError> for GatewaySpecError {
             StatelessTransactionValidatorError::UnsupportedSierraVersion { .. } => {
                 GatewaySpecError::UnsupportedContractClassVersion
             }
-            StatelessTransactionValidatorError::CalldataTooLong { .. }
-            | StatelessTransactionValidatorError::EntryPointsNotUniquelySorted
+            StatelessTransactionValidatorError::StatelessValidationError(
+                StatelessValidationError::UnsupportedTxVersion { .. },
+            ) => GatewaySpecError::UnsupportedTxVersion,
+            StatelessTransactionValidatorError::EntryPointsNotUniquelySorted
             | StatelessTransactionValidatorError::InvalidSierraVersion(..)
-            | StatelessTransactionValidatorError::SignatureTooLong { .. }
-            | StatelessTransactionValidatorError::ZeroResourceBounds { .. } => {
+            | StatelessTransactionValidatorError::StatelessValidationError(..) => {
                 GatewaySpecError::ValidationFailure { data: e.to_string() }
             }
         }
@@ -122,6 +113,8 @@ pub enum RPCStateReaderError {
     ContractAddressNotFound(Value),
     #[error("Failed to parse gas price {:?}", 0)]
     GasPriceParsingFailure(GasPrice),
+    #[error("No response to request {0} in the batch")]
+    MissingBatchResponse(Value),
     #[error("RPC error: {0}")]
     RPCError(StatusCode),
     #[error(transparent)]
@@ -1,45 +1,79 @@
 use std::clone::Clone;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Instant;
 
 use async_trait::async_trait;
-use axum::extract::State;
+use axum::extract::{Query, State};
 use axum::routing::{get, post};
 use axum::{Json, Router};
+use blockifier::blockifier::block::BlockInfo;
 use blockifier::execution::contract_class::ClassInfo;
+use serde::{Deserialize, Serialize};
 use starknet_api::executable_transaction::Transaction;
-use starknet_api::rpc_transaction::RpcTransaction;
+use starknet_api::rpc_transaction::{RpcDeclareTransaction, RpcTransaction};
 use starknet_api::transaction::TransactionHash;
 use starknet_mempool_infra::component_runner::{ComponentStartError, ComponentStarter};
-use starknet_mempool_types::communication::SharedMempoolClient;
-use starknet_mempool_types::mempool_types::{Account, AccountState, MempoolInput};
-use starknet_sierra_compile::config::SierraToCasmCompilationConfig;
+use starknet_mempool_infra::metrics::{
+    ACCEPTED,
+    COMPONENT_LABEL,
+    GATEWAY_ADD_TX_LATENCY_SECS,
+    GATEWAY_TRANSACTIONS_RECEIVED,
+    REJECTED,
+    RESULT_LABEL,
+};
+use starknet_mempool_types::communication::{MempoolClientError, SharedMempoolClient};
+use starknet_mempool_types::errors::MempoolError;
+use starknet_mempool_types::mempool_types::{
+    Account,
+    AccountState,
+    MempoolInput,
+    TransactionStatus,
+};
+use starknet_sierra_compile::config::{CompilationServiceConfig, SierraToCasmCompilationConfig};
+use tokio::sync::watch;
 use tracing::{error, info, instrument};
 
+use crate::admission_cache::{AdmissionCache, CachedStateReaderFactory};
 use crate::compilation::GatewayCompiler;
-use crate::config::{GatewayConfig, GatewayNetworkConfig, RpcStateReaderConfig};
+use crate::config::{
+    GatewayConfig,
+    GatewayNetworkConfig,
+    RpcStateReaderConfig,
+    StatefulTransactionValidatorConfig,
+    StatelessTransactionValidatorConfig,
+};
 use crate::errors::{GatewayResult, GatewayRunError, GatewaySpecError};
+use crate::pipeline::GatewayPipeline;
 use crate::rpc_state_reader::RpcStateReaderFactory;
 use crate::state_reader::StateReaderFactory;
-use crate::stateful_transaction_validator::StatefulTransactionValidator;
+use crate::stateful_transaction_validator::{get_latest_block_info, StatefulTransactionValidator};
 use crate::stateless_transaction_validator::StatelessTransactionValidator;
+use crate::utils::calculate_tx_hash;
 
 #[cfg(test)]
 #[path = "gateway_test.rs"]
 pub mod gateway_test;
 
+pub(crate) const GATEWAY_COMPONENT: &str = "gateway";
+
 pub struct Gateway {
     pub config: GatewayConfig,
     app_state: AppState,
+    stateless_tx_validator_config_sender: Arc<watch::Sender<StatelessTransactionValidatorConfig>>,
+    stateful_tx_validator_config_sender: Arc<watch::Sender<StatefulTransactionValidatorConfig>>,
 }
 
 #[derive(Clone)]
 pub struct AppState {
-    pub stateless_tx_validator: StatelessTransactionValidator,
-    pub stateful_tx_validator: Arc<StatefulTransactionValidator>,
+    /// The stateless validation config, which can be replaced while the gateway runs.
+    pub stateless_tx_validator_config: watch::Receiver<StatelessTransactionValidatorConfig>,
+    /// The stateful validation config, which can be replaced while the gateway runs.
+    pub stateful_tx_validator_config: watch::Receiver<StatefulTransactionValidatorConfig>,
     pub state_reader_factory: Arc<dyn StateReaderFactory>,
     pub gateway_compiler: GatewayCompiler,
     pub mempool_client: SharedMempoolClient,
+    pub pipeline: GatewayPipeline,
 }
 
 impl Gateway {
@@ -49,18 +83,39 @@ impl Gateway {
         gateway_compiler: GatewayCompiler,
         mempool_client: SharedMempoolClient,
     ) -> Self {
+        let (stateless_tx_validator_config_sender, stateless_tx_validator_config) =
+            watch::channel(config.stateless_tx_validator_config.clone());
+        let (stateful_tx_validator_config_sender, stateful_tx_validator_config) =
+            watch::channel(config.stateful_tx_validator_config.clone());
         let app_state = AppState {
-            stateless_tx_validator: StatelessTransactionValidator {
-                config: config.stateless_tx_validator_config.clone(),
-            },
-            stateful_tx_validator: Arc::new(StatefulTransactionValidator {
-                config: config.stateful_tx_validator_config.clone(),
-            }),
+            stateless_tx_validator_config,
+            stateful_tx_validator_config,
             state_reader_factory,
             gateway_compiler,
             mempool_client,
+            pipeline: GatewayPipeline::new(&config.pipeline_config),
         };
-        Gateway { config, app_state }
+        Gateway {
+            config,
+            app_state,
+            stateless_tx_validator_config_sender: Arc::new(stateless_tx_validator_config_sender),
+            stateful_tx_validator_config_sender: Arc::new(stateful_tx_validator_config_sender),
+        }
+    }
+
+    /// Returns a handle for replacing the stateless validation config of the running gateway. The
+    /// new config applies to the transactions received after it's sent.
+    pub fn stateless_tx_validator_config_sender(
+        &self,
+    ) -> Arc<watch::Sender<StatelessTransactionValidatorConfig>> {
+        self.stateless_tx_validator_config_sender.clone()
+    }
+
+    /// Like [`Gateway::stateless_tx_validator_config_sender`], for the stateful validation config.
+    pub fn stateful_tx_validator_config_sender(
+        &self,
+    ) -> Arc<watch::Sender<StatefulTransactionValidatorConfig>> {
+        self.stateful_tx_validator_config_sender.clone()
     }
 
     pub async fn run(&mut self) -> Result<(), GatewayRunError> {
@@ -74,10 +129,12 @@ impl Gateway {
     }
 
     pub fn app(&self) -> Router {
-        Router::new()
-            .route("/is_alive", get(is_alive))
-            .route("/add_tx", post(add_tx))
-            .with_state(self.app_state.clone())
+        let mut router =
+            Router::new().route("/is_alive", get(is_alive)).route("/add_tx", post(add_tx));
+        if self.config.expose_mempool_snapshot {
+            router = router.route("/mempool_snapshot", get(mempool_snapshot));
+        }
+        router.with_state(self.app_state.clone())
     }
 }
 
@@ -92,56 +149,189 @@ async fn is_alive() -> GatewayResult<String> {
 async fn add_tx(
     State(app_state): State<AppState>,
     Json(tx): Json<RpcTransaction>,
-) -> GatewayResult<Json<TransactionHash>> {
-    let mempool_input = tokio::task::spawn_blocking(move || {
-        process_tx(
-            app_state.stateless_tx_validator,
-            app_state.stateful_tx_validator.as_ref(),
-            app_state.state_reader_factory.as_ref(),
-            app_state.gateway_compiler,
-            tx,
-        )
-    })
-    .await
-    .map_err(|join_err| {
-        error!("Failed to process tx: {}", join_err);
+) -> GatewayResult<Json<AddTxResponse>> {
+    let start = Instant::now();
+    let result = process_and_add_tx(app_state, tx).await;
+    let result_label = if result.is_ok() { ACCEPTED } else { REJECTED };
+    metrics::increment_counter!(
+        GATEWAY_TRANSACTIONS_RECEIVED,
+        COMPONENT_LABEL => GATEWAY_COMPONENT,
+        RESULT_LABEL => result_label
+    );
+    metrics::histogram!(
+        GATEWAY_ADD_TX_LATENCY_SECS,
+        start.elapsed().as_secs_f64(),
+        COMPONENT_LABEL => GATEWAY_COMPONENT
+    );
+    result
+}
+
+/// The response to a transaction added to the gateway.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AddTxResponse {
+    pub transaction_hash: TransactionHash,
+    /// The status of the transaction in the mempool. A transaction that was added before is
+    /// answered with its current status instead of being added again.
+    pub status: TransactionStatus,
+}
+
+// The most transactions a mempool snapshot returns, so a request can't make the mempool copy all of
+// its transactions.
+pub(crate) const MAX_MEMPOOL_SNAPSHOT_TXS: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+struct MempoolSnapshotParams {
+    n_txs: usize,
+}
+
+/// Returns up to `n_txs`, and at most [`MAX_MEMPOOL_SNAPSHOT_TXS`], of the transactions the mempool
+/// would sequence next, in order, without removing them.
+#[instrument(skip(app_state))]
+async fn mempool_snapshot(
+    State(app_state): State<AppState>,
+    Query(MempoolSnapshotParams { n_txs }): Query<MempoolSnapshotParams>,
+) -> GatewayResult<Json<Vec<Transaction>>> {
+    let n_txs = n_txs.min(MAX_MEMPOOL_SNAPSHOT_TXS);
+    let txs = app_state.mempool_client.get_txs_snapshot(n_txs).await.map_err(|e| {
+        error!("Failed to get a snapshot of the mempool: {}", e);
         GatewaySpecError::UnexpectedError { data: "Internal server error".to_owned() }
-    })??;
+    })?;
+    Ok(Json(txs))
+}
 
-    let tx_hash = mempool_input.tx.tx_hash();
+async fn process_and_add_tx(
+    app_state: AppState,
+    tx: RpcTransaction,
+) -> GatewayResult<Json<AddTxResponse>> {
+    let chain_id = app_state.stateful_tx_validator_config.borrow().chain_info.chain_id.clone();
+    let tx_hash = calculate_tx_hash(&tx, &chain_id)?;
 
-    app_state.mempool_client.add_tx(mempool_input).await.map_err(|e| {
-        error!("Failed to send tx to mempool: {}", e);
+    // A transaction the mempool already received, or recently committed, isn't processed again.
+    let tx_status = app_state.mempool_client.get_tx_status(tx_hash).await.map_err(|e| {
+        error!("Failed to get the tx status from the mempool: {}", e);
         GatewaySpecError::UnexpectedError { data: "Internal server error".to_owned() }
     })?;
+    if let Some(tx_status) = tx_status {
+        info!("Transaction {} was already added, its status is {:?}.", tx_hash, tx_status);
+        return Ok(Json(AddTxResponse { transaction_hash: tx_hash, status: tx_status }));
+    }
+
+    let mempool_input = process_tx(&app_state, tx).await?;
+
+    match app_state.mempool_client.add_tx(mempool_input).await {
+        Ok(()) => {}
+        // The same transaction was added while this one was processed.
+        Err(MempoolClientError::MempoolError(MempoolError::DuplicateTransaction { .. })) => {
+            info!("Transaction {} was added while it was processed.", tx_hash);
+        }
+        Err(e) => {
+            error!("Failed to send tx to mempool: {}", e);
+            return Err(GatewaySpecError::UnexpectedError {
+                data: "Internal server error".to_owned(),
+            });
+        }
+    }
     // TODO: Also return `ContractAddress` for deploy and `ClassHash` for Declare.
-    Ok(Json(tx_hash))
+    Ok(Json(AddTxResponse { transaction_hash: tx_hash, status: TransactionStatus::Received }))
 }
 
-fn process_tx(
-    stateless_tx_validator: StatelessTransactionValidator,
-    stateful_tx_validator: &StatefulTransactionValidator,
-    state_reader_factory: &dyn StateReaderFactory,
-    gateway_compiler: GatewayCompiler,
-    tx: RpcTransaction,
-) -> GatewayResult<MempoolInput> {
+// Processes the transaction through the stages of the pipeline, see [`GatewayPipeline`].
+async fn process_tx(app_state: &AppState, tx: RpcTransaction) -> GatewayResult<MempoolInput> {
     // TODO(Arni, 1/5/2024): Perform congestion control.
-
-    // Perform stateless validations.
-    stateless_tx_validator.validate(&tx)?;
+    let pipeline = &app_state.pipeline;
+    let stateless_tx_validator = StatelessTransactionValidator {
+        config: app_state.stateless_tx_validator_config.borrow().clone(),
+    };
+    // Both validations of the transaction use the config it was received with.
+    let stateful_tx_validator = Arc::new(StatefulTransactionValidator {
+        config: app_state.stateful_tx_validator_config.borrow().clone(),
+    });
+    let stateless_stage_validator = stateful_tx_validator.clone();
+    let state_reader_factory = app_state.state_reader_factory.clone();
+    let (tx, latest_block_info) = pipeline
+        .stateless_validation
+        .run(move || {
+            let latest_block_info = validate_stateless(
+                &stateless_tx_validator,
+                stateless_stage_validator.as_ref(),
+                state_reader_factory.as_ref(),
+                &tx,
+            )?;
+            Ok((tx, latest_block_info))
+        })
+        .await?;
 
     // Compile Sierra to Casm.
-    let optional_class_info = match &tx {
-        RpcTransaction::Declare(declare_tx) => Some(
-            ClassInfo::try_from(gateway_compiler.process_declare_tx(declare_tx)?).map_err(|e| {
-                error!("Failed to convert Starknet API ClassInfo to Blockifier ClassInfo: {:?}", e);
-                GatewaySpecError::UnexpectedError { data: "Internal server error.".to_owned() }
-            })?,
-        ),
-        _ => None,
+    let (tx, optional_class_info) = match tx {
+        RpcTransaction::Declare(declare_tx) => {
+            let gateway_compiler = app_state.gateway_compiler.clone();
+            pipeline
+                .compilation
+                .run(move || {
+                    let class_info = compile_class(&gateway_compiler, &declare_tx)?;
+                    Ok((RpcTransaction::Declare(declare_tx), Some(class_info)))
+                })
+                .await?
+        }
+        tx => (tx, None),
     };
 
-    let validator = stateful_tx_validator.instantiate_validator(state_reader_factory)?;
+    let state_reader_factory = app_state.state_reader_factory.clone();
+    pipeline
+        .stateful_validation
+        .run(move || {
+            validate_stateful(
+                stateful_tx_validator.as_ref(),
+                state_reader_factory.as_ref(),
+                tx,
+                optional_class_info,
+                latest_block_info,
+            )
+        })
+        .await
+}
+
+// Validates the transaction statelessly, and against the latest block, which it returns.
+fn validate_stateless(
+    stateless_tx_validator: &StatelessTransactionValidator,
+    stateful_tx_validator: &StatefulTransactionValidator,
+    state_reader_factory: &dyn StateReaderFactory,
+    tx: &RpcTransaction,
+) -> GatewayResult<BlockInfo> {
+    stateless_tx_validator.validate(tx)?;
+
+    // Reject deprecated versions, underpriced transactions and class spam before the costlier
+    // stages.
+    let latest_block_info = get_latest_block_info(state_reader_factory)?;
+    stateful_tx_validator.validate_tx_version(tx, &latest_block_info)?;
+    stateful_tx_validator.validate_l2_gas_price(tx, &latest_block_info.gas_prices)?;
+    stateful_tx_validator.validate_declare_antispam(
+        tx,
+        state_reader_factory,
+        &latest_block_info,
+    )?;
+    Ok(latest_block_info)
+}
+
+fn compile_class(
+    gateway_compiler: &GatewayCompiler,
+    declare_tx: &RpcDeclareTransaction,
+) -> GatewayResult<ClassInfo> {
+    ClassInfo::try_from(gateway_compiler.process_declare_tx(declare_tx)?).map_err(|e| {
+        error!("Failed to convert Starknet API ClassInfo to Blockifier ClassInfo: {:?}", e);
+        GatewaySpecError::UnexpectedError { data: "Internal server error.".to_owned() }
+    })
+}
+
+fn validate_stateful(
+    stateful_tx_validator: &StatefulTransactionValidator,
+    state_reader_factory: &dyn StateReaderFactory,
+    tx: RpcTransaction,
+    optional_class_info: Option<ClassInfo>,
+    latest_block_info: BlockInfo,
+) -> GatewayResult<MempoolInput> {
+    let validator =
+        stateful_tx_validator.instantiate_validator(state_reader_factory, latest_block_info)?;
     // TODO(Yael 31/7/24): refactor after IntrnalTransaction is ready, delete validate_info and
     // compute all the info outside of run_validate.
     let validate_info = stateful_tx_validator.run_validate(&tx, optional_class_info, validator)?;
@@ -160,10 +350,23 @@ pub fn create_gateway(
     config: GatewayConfig,
     rpc_state_reader_config: RpcStateReaderConfig,
     compiler_config: SierraToCasmCompilationConfig,
+    compilation_service_config: CompilationServiceConfig,
     mempool_client: SharedMempoolClient,
 ) -> Gateway {
-    let state_reader_factory = Arc::new(RpcStateReaderFactory { config: rpc_state_reader_config });
-    let gateway_compiler = GatewayCompiler::new_command_line_compiler(compiler_config);
+    let mut state_reader_factory: Arc<dyn StateReaderFactory> =
+        Arc::new(RpcStateReaderFactory { config: rpc_state_reader_config });
+    let stateful_tx_validator_config = &config.stateful_tx_validator_config;
+    if stateful_tx_validator_config.admission_cache_config.enabled {
+        let cache = AdmissionCache::new(
+            &stateful_tx_validator_config.admission_cache_config,
+            &stateful_tx_validator_config.chain_info,
+        );
+        state_reader_factory =
+            Arc::new(CachedStateReaderFactory { state_reader_factory, cache: Arc::new(cache) });
+    }
+    let gateway_compiler =
+        GatewayCompiler::new_compilation_service(compiler_config, compilation_service_config)
+            .expect("Failed to create the compilation service.");
 
     Gateway::new(config, state_reader_factory, gateway_compiler, mempool_client)
 }
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use axum::body::{Bytes, HttpBody};
-use axum::extract::State;
+use axum::extract::{Query, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use blockifier::context::ChainInfo;
@@ -9,18 +9,37 @@ use blockifier::test_utils::CairoVersion;
 use mempool_test_utils::starknet_api_test_utils::{create_executable_tx, invoke_tx};
 use mockall::predicate::eq;
 use starknet_api::core::ContractAddress;
+use starknet_api::executable_transaction::Transaction;
 use starknet_api::rpc_transaction::RpcTransaction;
 use starknet_api::transaction::TransactionHash;
-use starknet_mempool_types::communication::MockMempoolClient;
-use starknet_mempool_types::mempool_types::{Account, AccountState, MempoolInput};
+use starknet_mempool_types::communication::{MempoolClientError, MockMempoolClient};
+use starknet_mempool_types::errors::MempoolError;
+use starknet_mempool_types::mempool_types::{
+    Account,
+    AccountState,
+    MempoolInput,
+    TransactionStatus,
+};
 use starknet_sierra_compile::config::SierraToCasmCompilationConfig;
+use tokio::sync::watch;
 
 use crate::compilation::GatewayCompiler;
-use crate::config::{StatefulTransactionValidatorConfig, StatelessTransactionValidatorConfig};
-use crate::gateway::{add_tx, AppState, SharedMempoolClient};
+use crate::config::{
+    GatewayPipelineConfig,
+    StatefulTransactionValidatorConfig,
+    StatelessTransactionValidatorConfig,
+};
+use crate::gateway::{
+    add_tx,
+    mempool_snapshot,
+    AddTxResponse,
+    AppState,
+    MempoolSnapshotParams,
+    SharedMempoolClient,
+    MAX_MEMPOOL_SNAPSHOT_TXS,
+};
+use crate::pipeline::GatewayPipeline;
 use crate::state_reader_test_utils::{local_test_state_reader_factory, TestStateReaderFactory};
-use crate::stateful_transaction_validator::StatefulTransactionValidator;
-use crate::stateless_transaction_validator::StatelessTransactionValidator;
 use crate::utils::rpc_tx_to_account_tx;
 
 pub fn app_state(
@@ -28,17 +47,20 @@ pub fn app_state(
     state_reader_factory: TestStateReaderFactory,
 ) -> AppState {
     AppState {
-        stateless_tx_validator: StatelessTransactionValidator {
-            config: StatelessTransactionValidatorConfig::default(),
-        },
-        stateful_tx_validator: Arc::new(StatefulTransactionValidator {
-            config: StatefulTransactionValidatorConfig::create_for_testing(),
-        }),
+        stateless_tx_validator_config: watch::channel(
+            StatelessTransactionValidatorConfig::default(),
+        )
+        .1,
+        stateful_tx_validator_config: watch::channel(
+            StatefulTransactionValidatorConfig::create_for_testing(),
+        )
+        .1,
         gateway_compiler: GatewayCompiler::new_command_line_compiler(
             SierraToCasmCompilationConfig::default(),
         ),
         state_reader_factory: Arc::new(state_reader_factory),
         mempool_client,
+        pipeline: GatewayPipeline::new(&GatewayPipelineConfig::default()),
     }
 }
 
@@ -61,6 +83,7 @@ async fn test_add_tx() {
     let tx_hash = calculate_hash(&tx);
 
     let mut mock_mempool_client = MockMempoolClient::new();
+    mock_mempool_client.expect_get_tx_status().with(eq(tx_hash)).return_once(|_| Ok(None));
     mock_mempool_client
         .expect_add_tx()
         .once()
@@ -86,7 +109,118 @@ async fn test_add_tx() {
     let response_bytes = &to_bytes(response).await;
 
     assert_eq!(status_code, StatusCode::OK, "{response_bytes:?}");
-    assert_eq!(tx_hash, serde_json::from_slice(response_bytes).unwrap());
+    assert_eq!(
+        serde_json::from_slice::<AddTxResponse>(response_bytes).unwrap(),
+        AddTxResponse { transaction_hash: tx_hash, status: TransactionStatus::Received }
+    );
+}
+
+#[tokio::test]
+async fn test_add_duplicate_tx() {
+    let (tx, _sender_address) = create_tx();
+    let tx_hash = calculate_hash(&tx);
+
+    // The transaction is answered with its status, without being added to the mempool again.
+    let mut mock_mempool_client = MockMempoolClient::new();
+    mock_mempool_client
+        .expect_get_tx_status()
+        .once()
+        .with(eq(tx_hash))
+        .return_once(|_| Ok(Some(TransactionStatus::Committed)));
+    mock_mempool_client.expect_add_tx().never();
+    let state_reader_factory = local_test_state_reader_factory(CairoVersion::Cairo1, false);
+    let app_state = app_state(Arc::new(mock_mempool_client), state_reader_factory);
+
+    let response = add_tx(State(app_state), tx.into()).await.into_response();
+
+    let status_code = response.status();
+    let response_bytes = &to_bytes(response).await;
+
+    assert_eq!(status_code, StatusCode::OK, "{response_bytes:?}");
+    assert_eq!(
+        serde_json::from_slice::<AddTxResponse>(response_bytes).unwrap(),
+        AddTxResponse { transaction_hash: tx_hash, status: TransactionStatus::Committed }
+    );
+}
+
+#[tokio::test]
+async fn test_add_tx_added_while_processed() {
+    let (tx, _sender_address) = create_tx();
+    let tx_hash = calculate_hash(&tx);
+
+    let mut mock_mempool_client = MockMempoolClient::new();
+    mock_mempool_client.expect_get_tx_status().return_once(|_| Ok(None));
+    mock_mempool_client.expect_add_tx().once().return_once(move |_| {
+        Err(MempoolClientError::MempoolError(MempoolError::DuplicateTransaction { tx_hash }))
+    });
+    let state_reader_factory = local_test_state_reader_factory(CairoVersion::Cairo1, false);
+    let app_state = app_state(Arc::new(mock_mempool_client), state_reader_factory);
+
+    let response = add_tx(State(app_state), tx.into()).await.into_response();
+
+    let status_code = response.status();
+    let response_bytes = &to_bytes(response).await;
+
+    assert_eq!(status_code, StatusCode::OK, "{response_bytes:?}");
+    assert_eq!(
+        serde_json::from_slice::<AddTxResponse>(response_bytes).unwrap(),
+        AddTxResponse { transaction_hash: tx_hash, status: TransactionStatus::Received }
+    );
+}
+
+#[tokio::test]
+async fn test_mempool_snapshot() {
+    let (tx, sender_address) = create_tx();
+    let tx_hash = calculate_hash(&tx);
+    let snapshot_txs = vec![create_executable_tx(
+        sender_address,
+        tx_hash,
+        *tx.tip(),
+        *tx.nonce(),
+        tx.resource_bounds().clone().into(),
+    )];
+
+    let mut mock_mempool_client = MockMempoolClient::new();
+    let returned_txs = snapshot_txs.clone();
+    mock_mempool_client
+        .expect_get_txs_snapshot()
+        .once()
+        .with(eq(2))
+        .return_once(move |_| Ok(returned_txs));
+    let state_reader_factory = local_test_state_reader_factory(CairoVersion::Cairo1, false);
+    let app_state = app_state(Arc::new(mock_mempool_client), state_reader_factory);
+
+    let response = mempool_snapshot(State(app_state), Query(MempoolSnapshotParams { n_txs: 2 }))
+        .await
+        .into_response();
+
+    let status_code = response.status();
+    let response_bytes = &to_bytes(response).await;
+
+    assert_eq!(status_code, StatusCode::OK, "{response_bytes:?}");
+    let txs: Vec<Transaction> = serde_json::from_slice(response_bytes).unwrap();
+    assert_eq!(txs, snapshot_txs);
+}
+
+#[tokio::test]
+async fn test_mempool_snapshot_is_capped() {
+    let mut mock_mempool_client = MockMempoolClient::new();
+    mock_mempool_client
+        .expect_get_txs_snapshot()
+        .once()
+        .with(eq(MAX_MEMPOOL_SNAPSHOT_TXS))
+        .return_once(|_| Ok(vec![]));
+    let state_reader_factory = local_test_state_reader_factory(CairoVersion::Cairo1, false);
+    let app_state = app_state(Arc::new(mock_mempool_client), state_reader_factory);
+
+    let response = mempool_snapshot(
+        State(app_state),
+        Query(MempoolSnapshotParams { n_txs: MAX_MEMPOOL_SNAPSHOT_TXS + 1 }),
+    )
+    .await
+    .into_response();
+
+    assert_eq!(response.status(), StatusCode::OK);
 }
 
 async fn to_bytes(res: Response) -> Bytes {
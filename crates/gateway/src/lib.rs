@@ -1,9 +1,11 @@
+pub mod admission_cache;
 pub mod communication;
 pub mod compilation;
 mod compiler_version;
 pub mod config;
 pub mod errors;
 pub mod gateway;
+pub mod pipeline;
 mod rpc_objects;
 mod rpc_state_reader;
 #[cfg(test)]
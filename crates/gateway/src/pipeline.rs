@@ -0,0 +1,116 @@
+//! The pipeline that processes the transactions the gateway receives.
+//!
+//! A transaction passes through the stages:
+//! 1. Stateless validation, which also rejects the transactions the latest block makes invalid
+//!    regardless of their accounts, e.g. underpriced ones, before the costlier stages.
+//! 2. Compilation of the class, for a declare transaction.
+//! 3. Stateful validation, which runs the validation of the account.
+//!
+//! Then, the transaction is added to the mempool.
+//!
+//! Each stage runs on a bounded number of workers, and the transactions wait for a free worker of
+//! a stage in a queue of their own. Hence, slow compilations don't hold back the transactions in
+//! the other stages, and a burst of transactions doesn't exhaust the blocking threads.
+
+use std::sync::Arc;
+
+use starknet_mempool_infra::metrics::{COMPONENT_LABEL, GATEWAY_QUEUED_TRANSACTIONS, STAGE_LABEL};
+use tokio::sync::Semaphore;
+use tracing::error;
+
+use crate::config::GatewayPipelineConfig;
+use crate::errors::{GatewayResult, GatewaySpecError};
+use crate::gateway::GATEWAY_COMPONENT;
+
+#[cfg(test)]
+#[path = "pipeline_test.rs"]
+mod pipeline_test;
+
+/// The stages of processing a transaction in the gateway.
+#[derive(Clone)]
+pub struct GatewayPipeline {
+    pub stateless_validation: PipelineStage,
+    pub compilation: PipelineStage,
+    pub stateful_validation: PipelineStage,
+}
+
+impl GatewayPipeline {
+    pub fn new(config: &GatewayPipelineConfig) -> Self {
+        Self {
+            stateless_validation: PipelineStage::new(
+                "stateless_validation",
+                config.n_stateless_validation_workers,
+            ),
+            compilation: PipelineStage::new("compilation", config.n_compilation_workers),
+            stateful_validation: PipelineStage::new(
+                "stateful_validation",
+                config.n_stateful_validation_workers,
+            ),
+        }
+    }
+}
+
+/// A stage of processing transactions, which runs on a bounded number of workers.
+#[derive(Clone)]
+pub struct PipelineStage {
+    name: &'static str,
+    workers: Arc<Semaphore>,
+}
+
+impl PipelineStage {
+    pub fn new(name: &'static str, n_workers: usize) -> Self {
+        Self { name, workers: Arc::new(Semaphore::new(n_workers)) }
+    }
+
+    /// Runs `f` on a blocking thread once a worker of the stage is free.
+    pub async fn run<T, F>(&self, f: F) -> GatewayResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> GatewayResult<T> + Send + 'static,
+    {
+        let queued = QueuedTransaction::new(self.name);
+        let worker =
+            self.workers.clone().acquire_owned().await.expect("The workers are never closed.");
+        drop(queued);
+
+        tokio::task::spawn_blocking(move || {
+            let result = f();
+            drop(worker);
+            result
+        })
+        .await
+        .map_err(|join_err| {
+            error!("Failed to process tx: {}", join_err);
+            GatewaySpecError::UnexpectedError { data: "Internal server error".to_owned() }
+        })?
+    }
+}
+
+// Counts a transaction in the queue of a stage while it exists, including when the request of the
+// transaction is dropped while it waits.
+struct QueuedTransaction {
+    stage: &'static str,
+}
+
+impl QueuedTransaction {
+    fn new(stage: &'static str) -> Self {
+        metrics::increment_gauge!(
+            GATEWAY_QUEUED_TRANSACTIONS,
+            1.0,
+            COMPONENT_LABEL => GATEWAY_COMPONENT,
+            STAGE_LABEL => stage
+        );
+        Self { stage }
+    }
+}
+
+impl Drop for QueuedTransaction {
+    fn drop(&mut self) {
+        metrics::decrement_gauge!(
+            GATEWAY_QUEUED_TRANSACTIONS,
+            1.0,
+            COMPONENT_LABEL => GATEWAY_COMPONENT,
+            STAGE_LABEL => self.stage
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use assert_matches::assert_matches;
+
+use crate::errors::GatewaySpecError;
+use crate::pipeline::PipelineStage;
+
+#[tokio::test]
+async fn stage_runs_on_bounded_workers() {
+    const N_WORKERS: usize = 2;
+    let stage = PipelineStage::new("stage", N_WORKERS);
+    let running = Arc::new(AtomicUsize::new(0));
+    let max_running = Arc::new(AtomicUsize::new(0));
+
+    let tasks: Vec<_> = (0..8)
+        .map(|i| {
+            let stage = stage.clone();
+            let running = running.clone();
+            let max_running = max_running.clone();
+            tokio::spawn(async move {
+                stage
+                    .run(move || {
+                        let n_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_running.fetch_max(n_running, Ordering::SeqCst);
+                        std::thread::sleep(Duration::from_millis(10));
+                        running.fetch_sub(1, Ordering::SeqCst);
+                        Ok(i)
+                    })
+                    .await
+            })
+        })
+        .collect();
+
+    for (i, task) in tasks.into_iter().enumerate() {
+        assert_eq!(task.await.unwrap().unwrap(), i);
+    }
+    assert!(max_running.load(Ordering::SeqCst) <= N_WORKERS);
+}
+
+#[tokio::test]
+async fn stage_returns_the_error_of_the_task() {
+    let stage = PipelineStage::new("stage", 1);
+    let result =
+        stage.run(|| -> Result<(), _> { Err(GatewaySpecError::UnsupportedTxVersion) }).await;
+    assert_matches!(result, Err(GatewaySpecError::UnsupportedTxVersion));
+
+    // A failed task frees its worker.
+    assert_eq!(stage.run(|| Ok(1)).await.unwrap(), 1);
+}
@@ -121,6 +121,9 @@ pub struct RpcSuccessResponse {
 pub struct RpcErrorResponse {
     pub jsonrpc: Option<String>,
     pub error: RpcSpecError,
+    /// The id of the failed request, if it could be parsed.
+    #[serde(default)]
+    pub id: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default)]
@@ -1,9 +1,12 @@
+use std::collections::HashMap;
+
 use blockifier::blockifier::block::BlockInfo;
 use blockifier::execution::contract_class::{ContractClass, ContractClassV0, ContractClassV1};
 use blockifier::state::errors::StateError;
 use blockifier::state::state_api::{StateReader as BlockifierStateReader, StateResult};
 use papyrus_rpc::CompiledContractClass;
 use reqwest::blocking::Client as BlockingClient;
+use serde::de::DeserializeOwned;
 use serde::Serialize;
 use serde_json::{json, Value};
 use starknet_api::block::BlockNumber;
@@ -26,7 +29,7 @@ use crate::rpc_objects::{
     RPC_ERROR_BLOCK_NOT_FOUND,
     RPC_ERROR_CONTRACT_ADDRESS_NOT_FOUND,
 };
-use crate::state_reader::{MempoolStateReader, StateReaderFactory};
+use crate::state_reader::{MempoolStateReader, StateReadKey, StateReaderFactory};
 
 pub struct RpcStateReader {
     pub config: RpcStateReaderConfig,
@@ -47,42 +50,102 @@ impl RpcStateReader {
         method: &str,
         params: impl Serialize,
     ) -> RPCStateReaderResult<Value> {
-        let request_body = json!({
+        let request_body = self.request_body(0, method, params);
+        let rpc_response: RpcResponse = self.post(&request_body)?;
+        rpc_response_to_result(rpc_response, request_body)
+    }
+
+    /// Sends the requests in a single JSON-RPC batch, and returns their results in the order of the
+    /// requests. Blocking, like [`RpcStateReader::send_rpc_request`].
+    pub fn send_rpc_batch(
+        &self,
+        requests: Vec<(&str, Value)>,
+    ) -> RPCStateReaderResult<Vec<RPCStateReaderResult<Value>>> {
+        let request_bodies: Vec<_> = (0..)
+            .zip(requests)
+            .map(|(id, (method, params))| self.request_body(id, method, params))
+            .collect();
+        let rpc_responses: Vec<RpcResponse> = self.post(&request_bodies)?;
+
+        // The responses to a batch may be in any order, so they're matched to the requests by id.
+        let mut responses_by_id: HashMap<_, _> = rpc_responses
+            .into_iter()
+            .filter_map(|rpc_response| {
+                let id = match &rpc_response {
+                    RpcResponse::Success(success_response) => Some(success_response.id),
+                    RpcResponse::Error(error_response) => error_response.id,
+                }?;
+                Some((id, rpc_response))
+            })
+            .collect();
+        Ok((0..)
+            .zip(request_bodies)
+            .map(|(id, request_body)| match responses_by_id.remove(&id) {
+                Some(rpc_response) => rpc_response_to_result(rpc_response, request_body),
+                None => Err(RPCStateReaderError::MissingBatchResponse(request_body)),
+            })
+            .collect())
+    }
+
+    fn request_body(&self, id: u32, method: &str, params: impl Serialize) -> Value {
+        json!({
             "jsonrpc": self.config.json_rpc_version,
-            "id": 0,
+            "id": id,
             "method": method,
             "params": json!(params),
-        });
+        })
+    }
 
+    fn post<T: DeserializeOwned>(&self, request_body: &impl Serialize) -> RPCStateReaderResult<T> {
         let client = BlockingClient::new();
         let response = client
             .post(self.config.url.clone())
             .header("Content-Type", "application/json")
-            .json(&request_body)
+            .json(request_body)
             .send()?;
 
         if !response.status().is_success() {
             return Err(RPCStateReaderError::RPCError(response.status()));
         }
 
-        let rpc_response: RpcResponse = response.json::<RpcResponse>()?;
-
-        match rpc_response {
-            RpcResponse::Success(rpc_success_response) => Ok(rpc_success_response.result),
-            RpcResponse::Error(rpc_error_response) => match rpc_error_response.error.code {
-                RPC_ERROR_BLOCK_NOT_FOUND => Err(RPCStateReaderError::BlockNotFound(request_body)),
-                RPC_ERROR_CONTRACT_ADDRESS_NOT_FOUND => {
-                    Err(RPCStateReaderError::ContractAddressNotFound(request_body))
-                }
-                RPC_CLASS_HASH_NOT_FOUND => {
-                    Err(RPCStateReaderError::ClassHashNotFound(request_body))
-                }
-                _ => Err(RPCStateReaderError::UnexpectedErrorCode(rpc_error_response.error.code)),
-            },
+        Ok(response.json::<T>()?)
+    }
+
+    fn read_request(&self, key: &StateReadKey) -> (&'static str, Value) {
+        match *key {
+            StateReadKey::Storage(contract_address, key) => (
+                "starknet_getStorageAt",
+                json!(GetStorageAtParams { block_id: self.block_id, contract_address, key }),
+            ),
+            StateReadKey::Nonce(contract_address) => (
+                "starknet_getNonce",
+                json!(GetNonceParams { block_id: self.block_id, contract_address }),
+            ),
+            StateReadKey::ClassHash(contract_address) => (
+                "starknet_getClassHashAt",
+                json!(GetClassHashAtParams { contract_address, block_id: self.block_id }),
+            ),
         }
     }
 }
 
+fn rpc_response_to_result(
+    rpc_response: RpcResponse,
+    request_body: Value,
+) -> RPCStateReaderResult<Value> {
+    match rpc_response {
+        RpcResponse::Success(rpc_success_response) => Ok(rpc_success_response.result),
+        RpcResponse::Error(rpc_error_response) => match rpc_error_response.error.code {
+            RPC_ERROR_BLOCK_NOT_FOUND => Err(RPCStateReaderError::BlockNotFound(request_body)),
+            RPC_ERROR_CONTRACT_ADDRESS_NOT_FOUND => {
+                Err(RPCStateReaderError::ContractAddressNotFound(request_body))
+            }
+            RPC_CLASS_HASH_NOT_FOUND => Err(RPCStateReaderError::ClassHashNotFound(request_body)),
+            _ => Err(RPCStateReaderError::UnexpectedErrorCode(rpc_error_response.error.code)),
+        },
+    }
+}
+
 impl MempoolStateReader for RpcStateReader {
     fn get_block_info(&self) -> StateResult<BlockInfo> {
         let get_block_params = GetBlockWithTxHashesParams { block_id: self.block_id };
@@ -95,6 +158,26 @@ impl MempoolStateReader for RpcStateReader {
         let block_info = block_header.try_into()?;
         Ok(block_info)
     }
+
+    fn get_batch(&self, keys: &[StateReadKey]) -> StateResult<Vec<Felt>> {
+        let requests = keys.iter().map(|key| self.read_request(key)).collect();
+        let results = self.send_rpc_batch(requests)?;
+        keys.iter()
+            .zip(results)
+            .map(|(key, result)| {
+                let value = match (key, result) {
+                    // Like in the single reads, an undeployed contract has a zero nonce and class
+                    // hash.
+                    (
+                        StateReadKey::Nonce(_) | StateReadKey::ClassHash(_),
+                        Err(RPCStateReaderError::ContractAddressNotFound(_)),
+                    ) => return Ok(Felt::ZERO),
+                    (_, result) => result?,
+                };
+                serde_json::from_value(value).map_err(serde_err_to_state_err)
+            })
+            .collect()
+    }
 }
 
 impl BlockifierStateReader for RpcStateReader {
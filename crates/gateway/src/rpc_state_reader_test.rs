@@ -7,6 +7,7 @@ use serde_json::json;
 use starknet_api::block::{BlockNumber, GasPrice};
 use starknet_api::core::{ClassHash, ContractAddress, Nonce, PatriciaKey};
 use starknet_api::{class_hash, contract_address, felt, patricia_key};
+use starknet_types_core::felt::Felt;
 
 use crate::config::RpcStateReaderConfig;
 use crate::rpc_objects::{
@@ -18,11 +19,14 @@ use crate::rpc_objects::{
     GetNonceParams,
     GetStorageAtParams,
     ResourcePrice,
+    RpcErrorResponse,
     RpcResponse,
+    RpcSpecError,
     RpcSuccessResponse,
+    RPC_ERROR_CONTRACT_ADDRESS_NOT_FOUND,
 };
 use crate::rpc_state_reader::RpcStateReader;
-use crate::state_reader::MempoolStateReader;
+use crate::state_reader::{MempoolStateReader, StateReadKey};
 
 async fn run_rpc_server() -> mockito::ServerGuard {
     mockito::Server::new_async().await
@@ -210,3 +214,77 @@ async fn test_get_class_hash_at() {
     assert_eq!(result, expected_result);
     mock.assert_async().await;
 }
+
+#[tokio::test]
+async fn test_get_batch() {
+    let mut server = run_rpc_server().await;
+    let config = RpcStateReaderConfig { url: server.url(), ..Default::default() };
+
+    let request = |id: u32, method: &str, params: serde_json::Value| {
+        json!({
+            "jsonrpc": config.json_rpc_version,
+            "id": id,
+            "method": method,
+            "params": params,
+        })
+    };
+    let request_body = json!([
+        request(
+            0,
+            "starknet_getNonce",
+            json!(GetNonceParams {
+                block_id: BlockId::Latest,
+                contract_address: contract_address!("0x1")
+            })
+        ),
+        request(
+            1,
+            "starknet_getStorageAt",
+            json!(GetStorageAtParams {
+                block_id: BlockId::Latest,
+                contract_address: contract_address!("0x2"),
+                key: starknet_api::state::StorageKey::from(3u32),
+            })
+        ),
+    ]);
+    // The responses are matched to the requests by id, and an undeployed contract has a zero
+    // nonce.
+    let responses = vec![
+        RpcResponse::Success(RpcSuccessResponse {
+            result: serde_json::to_value(felt!("0x999")).unwrap(),
+            id: 1,
+            ..Default::default()
+        }),
+        RpcResponse::Error(RpcErrorResponse {
+            error: RpcSpecError {
+                code: RPC_ERROR_CONTRACT_ADDRESS_NOT_FOUND,
+                ..Default::default()
+            },
+            id: Some(0),
+            ..Default::default()
+        }),
+    ];
+    let mock = server
+        .mock("POST", "/")
+        .match_header("Content-Type", "application/json")
+        .match_body(mockito::Matcher::Json(request_body))
+        .with_status(201)
+        .with_body(serde_json::to_string(&responses).unwrap())
+        .create();
+
+    let client = RpcStateReader::from_latest(&config);
+    let result = tokio::task::spawn_blocking(move || {
+        client.get_batch(&[
+            StateReadKey::Nonce(contract_address!("0x1")),
+            StateReadKey::Storage(
+                contract_address!("0x2"),
+                starknet_api::state::StorageKey::from(3u32),
+            ),
+        ])
+    })
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(result, vec![Felt::ZERO, felt!("0x999")]);
+    mock.assert_async().await;
+}
@@ -9,8 +9,32 @@ use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::state::StorageKey;
 use starknet_types_core::felt::Felt;
 
+/// A value of the state, read by [`MempoolStateReader::get_batch`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum StateReadKey {
+    Storage(ContractAddress, StorageKey),
+    Nonce(ContractAddress),
+    ClassHash(ContractAddress),
+}
+
 pub trait MempoolStateReader: BlockifierStateReader + Send + Sync {
     fn get_block_info(&self) -> Result<BlockInfo, StateError>;
+
+    /// Reads the values of the given keys, in order. Readers that can read several values at once
+    /// should override the default, which reads them one by one.
+    fn get_batch(&self, keys: &[StateReadKey]) -> StateResult<Vec<Felt>> {
+        keys.iter()
+            .map(|key| match *key {
+                StateReadKey::Storage(contract_address, storage_key) => {
+                    self.get_storage_at(contract_address, storage_key)
+                }
+                StateReadKey::Nonce(contract_address) => Ok(self.get_nonce_at(contract_address)?.0),
+                StateReadKey::ClassHash(contract_address) => {
+                    Ok(self.get_class_hash_at(contract_address)?.0)
+                }
+            })
+            .collect()
+    }
 }
 
 #[cfg_attr(test, automock)]
@@ -26,6 +50,10 @@ impl MempoolStateReader for Box<dyn MempoolStateReader> {
     fn get_block_info(&self) -> Result<BlockInfo, StateError> {
         self.as_ref().get_block_info()
     }
+
+    fn get_batch(&self, keys: &[StateReadKey]) -> StateResult<Vec<Felt>> {
+        self.as_ref().get_batch(keys)
+    }
 }
 
 impl BlockifierStateReader for Box<dyn MempoolStateReader> {
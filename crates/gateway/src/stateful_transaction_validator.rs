@@ -1,4 +1,4 @@
-use blockifier::blockifier::block::BlockInfo;
+use blockifier::blockifier::block::{BlockInfo, GasPrices};
 use blockifier::blockifier::stateful_validator::{
     StatefulValidator,
     StatefulValidatorResult as BlockifierStatefulValidatorResult,
@@ -6,18 +6,27 @@ use blockifier::blockifier::stateful_validator::{
 use blockifier::bouncer::BouncerConfig;
 use blockifier::context::BlockContext;
 use blockifier::execution::contract_class::ClassInfo;
+use blockifier::fee::fee_market::FeeMarket;
 use blockifier::state::cached_state::CachedState;
+use blockifier::state::state_api::StateReader;
 use blockifier::transaction::account_transaction::AccountTransaction;
+use blockifier::transaction::objects::FeeType;
+use blockifier::transaction::transaction_types::TransactionType;
 use blockifier::versioned_constants::VersionedConstants;
 #[cfg(test)]
 use mockall::automock;
 use starknet_api::core::{ContractAddress, Nonce};
-use starknet_api::rpc_transaction::{RpcInvokeTransaction, RpcTransaction};
-use starknet_api::transaction::TransactionHash;
+use starknet_api::rpc_transaction::{RpcDeclareTransaction, RpcInvokeTransaction, RpcTransaction};
+use starknet_api::transaction::{
+    AllResourceBounds,
+    ResourceBounds,
+    TransactionHash,
+    TransactionVersion,
+};
 use starknet_types_core::felt::Felt;
 use tracing::error;
 
-use crate::config::StatefulTransactionValidatorConfig;
+use crate::config::{DeclareAntispamPolicy, StatefulTransactionValidatorConfig};
 use crate::errors::{GatewaySpecError, StatefulTransactionValidatorResult};
 use crate::state_reader::{MempoolStateReader, StateReaderFactory};
 use crate::utils::{get_sender_address, rpc_tx_to_account_tx};
@@ -88,13 +97,128 @@ impl StatefulTransactionValidator {
         Ok(ValidateInfo { tx_hash, sender_address, account_nonce })
     }
 
+    /// Rejects a transaction whose L2 gas price is below the lowest base fee the next block may
+    /// have, less the grace margin. The transactions that pass may still wait in the mempool until
+    /// the base fee falls to their price.
+    pub fn validate_l2_gas_price(
+        &self,
+        rpc_tx: &RpcTransaction,
+        latest_gas_prices: &GasPrices,
+    ) -> StatefulTransactionValidatorResult<()> {
+        let config = &self.config.gas_price_floor_config;
+        if !config.enabled {
+            return Ok(());
+        }
+
+        let fee_market = FeeMarket::new(&config.fee_market_config);
+        let base_fee = latest_gas_prices.get_l2_gas_price_by_fee_type(&FeeType::Strk);
+        // The base fee falls the most if the latest block is empty.
+        let min_next_base_fee = fee_market.next_l2_gas_price(
+            base_fee,
+            0,
+            config.fee_market_config.min_strk_l2_gas_price,
+        );
+        let grace_margin_percent = u128::from(config.grace_margin_percent.min(100));
+        let min_l2_gas_price =
+            base_fee.min(min_next_base_fee).get().saturating_mul(100 - grace_margin_percent) / 100;
+
+        let l2_gas_price = rpc_tx.resource_bounds().l2_gas.max_price_per_unit;
+        if l2_gas_price < min_l2_gas_price {
+            return Err(GatewaySpecError::ValidationFailure {
+                data: format!(
+                    "The L2 gas price {l2_gas_price} is lower than the minimal L2 gas price \
+                     {min_l2_gas_price}."
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Rejects a transaction whose version the chain deprecated by the next block.
+    pub fn validate_tx_version(
+        &self,
+        rpc_tx: &RpcTransaction,
+        latest_block_info: &BlockInfo,
+    ) -> StatefulTransactionValidatorResult<()> {
+        let tx_type = match rpc_tx {
+            RpcTransaction::Declare(_) => TransactionType::Declare,
+            RpcTransaction::DeployAccount(_) => TransactionType::DeployAccount,
+            RpcTransaction::Invoke(_) => TransactionType::InvokeFunction,
+        };
+        // Only transactions of version 3 are received through the RPC.
+        let version = TransactionVersion::THREE;
+        self.config
+            .chain_info
+            .tx_version_config
+            .validate_version(tx_type, version, latest_block_info.block_number.unchecked_next())
+            .map_err(|e| {
+                error!("Rejected a transaction of a deprecated version: {}", e);
+                GatewaySpecError::UnsupportedTxVersion
+            })
+    }
+
+    /// Rejects a declare transaction that doesn't meet the declare antispam policy of the chain.
+    pub fn validate_declare_antispam(
+        &self,
+        rpc_tx: &RpcTransaction,
+        state_reader_factory: &dyn StateReaderFactory,
+        latest_block_info: &BlockInfo,
+    ) -> StatefulTransactionValidatorResult<()> {
+        let RpcTransaction::Declare(RpcDeclareTransaction::V3(declare_tx)) = rpc_tx else {
+            return Ok(());
+        };
+        let config = &self.config.declare_antispam_config;
+        let committed_fee = max_possible_fee(&declare_tx.resource_bounds);
+        match config.policy {
+            DeclareAntispamPolicy::Disabled => Ok(()),
+            DeclareAntispamPolicy::MinCommittedFee => {
+                if committed_fee < config.min_committed_fee {
+                    return Err(GatewaySpecError::ValidationFailure {
+                        data: format!(
+                            "A declare transaction must commit to pay a fee of at least {}. Got \
+                             {committed_fee}.",
+                            config.min_committed_fee
+                        ),
+                    });
+                }
+                Ok(())
+            }
+            DeclareAntispamPolicy::MinBalance => {
+                let min_balance = committed_fee.saturating_mul(config.balance_to_fee_ratio);
+                let state_reader =
+                    state_reader_factory.get_state_reader(latest_block_info.block_number);
+                let fee_token_address = self.config.chain_info.fee_token_address(&FeeType::Strk);
+                let (balance_low, balance_high) = state_reader
+                    .get_fee_token_balance(declare_tx.sender_address, fee_token_address)
+                    .map_err(|e| {
+                        error!(
+                            "Failed to get the balance of sender address {}: {}",
+                            declare_tx.sender_address, e
+                        );
+                        GatewaySpecError::UnexpectedError {
+                            data: "Internal server error.".to_owned(),
+                        }
+                    })?;
+                if balance_high == Felt::ZERO && balance_low < Felt::from(min_balance) {
+                    return Err(GatewaySpecError::ValidationFailure {
+                        data: format!(
+                            "The sender of a declare transaction must hold a balance of at least \
+                             {min_balance}. Got {balance_low}."
+                        ),
+                    });
+                }
+                Ok(())
+            }
+        }
+    }
+
     pub fn instantiate_validator(
         &self,
         state_reader_factory: &dyn StateReaderFactory,
+        latest_block_info: BlockInfo,
     ) -> StatefulTransactionValidatorResult<BlockifierStatefulValidator> {
         // TODO(yael 6/5/2024): consider storing the block_info as part of the
         // StatefulTransactionValidator and update it only once a new block is created.
-        let latest_block_info = get_latest_block_info(state_reader_factory)?;
         let state_reader = state_reader_factory.get_state_reader(latest_block_info.block_number);
         let state = CachedState::new(state_reader);
         let versioned_constants = VersionedConstants::latest_constants_with_overrides(
@@ -131,6 +255,16 @@ fn skip_stateful_validations(tx: &RpcTransaction, account_nonce: Nonce) -> bool
     }
 }
 
+// The fee a transaction commits to pay: the sum of the maximal costs of its resources.
+fn max_possible_fee(resource_bounds: &AllResourceBounds) -> u128 {
+    let bounds_fee = |bounds: &ResourceBounds| {
+        u128::from(bounds.max_amount).saturating_mul(bounds.max_price_per_unit)
+    };
+    bounds_fee(&resource_bounds.l1_gas)
+        .saturating_add(bounds_fee(&resource_bounds.l2_gas))
+        .saturating_add(bounds_fee(&resource_bounds.l1_data_gas))
+}
+
 pub fn get_latest_block_info(
     state_reader_factory: &dyn StateReaderFactory,
 ) -> StatefulTransactionValidatorResult<BlockInfo> {
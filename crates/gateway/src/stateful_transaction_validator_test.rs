@@ -1,35 +1,51 @@
+use std::num::NonZeroU128;
+
+use assert_matches::assert_matches;
+use blockifier::blockifier::block::BlockInfo;
 use blockifier::blockifier::stateful_validator::{
     StatefulValidatorError as BlockifierStatefulValidatorError,
     StatefulValidatorResult as BlockifierStatefulValidatorResult,
 };
 use blockifier::context::BlockContext;
-use blockifier::test_utils::CairoVersion;
+use blockifier::fee::fee_market::FeeMarketConfig;
+use blockifier::test_utils::contracts::FeatureContract;
+use blockifier::test_utils::{CairoVersion, BALANCE};
 use blockifier::transaction::errors::{TransactionFeeError, TransactionPreValidationError};
-use mempool_test_utils::invoke_tx_args;
+use blockifier::transaction::version_config::TransactionVersionConfig;
 use mempool_test_utils::starknet_api_test_utils::{
+    create_resource_bounds_mapping,
     deploy_account_tx,
     invoke_tx,
+    rpc_declare_tx,
     rpc_invoke_tx,
     TEST_SENDER_ADDRESS,
     VALID_L1_GAS_MAX_AMOUNT,
     VALID_L1_GAS_MAX_PRICE_PER_UNIT,
 };
+use mempool_test_utils::{declare_tx_args, invoke_tx_args};
 use mockall::predicate::eq;
 use num_bigint::BigUint;
 use pretty_assertions::assert_eq;
 use rstest::{fixture, rstest};
+use starknet_api::block::BlockNumber;
 use starknet_api::core::{ContractAddress, Nonce, PatriciaKey};
 use starknet_api::rpc_transaction::RpcTransaction;
-use starknet_api::transaction::TransactionHash;
+use starknet_api::transaction::{ResourceBounds, TransactionHash};
 use starknet_api::{contract_address, felt, patricia_key};
 use starknet_types_core::felt::Felt;
 
 use super::ValidateInfo;
-use crate::config::StatefulTransactionValidatorConfig;
+use crate::config::{
+    DeclareAntispamConfig,
+    DeclareAntispamPolicy,
+    GasPriceFloorConfig,
+    StatefulTransactionValidatorConfig,
+};
 use crate::errors::GatewaySpecError;
 use crate::state_reader::{MockStateReaderFactory, StateReaderFactory};
 use crate::state_reader_test_utils::local_test_state_reader_factory;
 use crate::stateful_transaction_validator::{
+    get_latest_block_info,
     MockStatefulTransactionValidatorTrait,
     StatefulTransactionValidator,
 };
@@ -58,6 +74,7 @@ fn stateful_validator(block_context: BlockContext) -> StatefulTransactionValidat
             validate_max_n_steps: block_context.versioned_constants().validate_max_n_steps,
             max_recursion_depth: block_context.versioned_constants().max_recursion_depth,
             chain_info: block_context.chain_info().clone(),
+            ..Default::default()
         },
     }
 }
@@ -121,9 +138,12 @@ fn test_instantiate_validator() {
             validate_max_n_steps: block_context.versioned_constants().validate_max_n_steps,
             max_recursion_depth: block_context.versioned_constants().max_recursion_depth,
             chain_info: block_context.chain_info().clone(),
+            ..Default::default()
         },
     };
-    let blockifier_validator = stateful_validator.instantiate_validator(&mock_state_reader_factory);
+    let latest_block_info = get_latest_block_info(&mock_state_reader_factory).unwrap();
+    let blockifier_validator =
+        stateful_validator.instantiate_validator(&mock_state_reader_factory, latest_block_info);
     assert!(blockifier_validator.is_ok());
 }
 
@@ -162,3 +182,123 @@ fn test_skip_stateful_validation(
         .returning(|_, _| Ok(()));
     let _ = stateful_validator.run_validate(&rpc_tx, None, mock_validator);
 }
+
+#[rstest]
+#[case::above_floor(false, 792, true)]
+#[case::below_floor(false, 791, false)]
+#[case::floor_disabled(true, 1, true)]
+fn test_validate_l2_gas_price(
+    #[case] disabled: bool,
+    #[case] l2_gas_price: u128,
+    #[case] expected_valid: bool,
+    block_context: BlockContext,
+) {
+    let stateful_validator = StatefulTransactionValidator {
+        config: StatefulTransactionValidatorConfig {
+            gas_price_floor_config: GasPriceFloorConfig {
+                enabled: !disabled,
+                grace_margin_percent: 10,
+                fee_market_config: FeeMarketConfig {
+                    max_change_rate_percent: 12,
+                    ..Default::default()
+                },
+            },
+            ..Default::default()
+        },
+    };
+    let l2_gas_price_1000 = NonZeroU128::new(1000).unwrap();
+    let gas_prices = block_context
+        .block_info()
+        .gas_prices
+        .with_l2_gas_prices(l2_gas_price_1000, l2_gas_price_1000);
+    let resource_bounds = create_resource_bounds_mapping(
+        ResourceBounds::default(),
+        ResourceBounds { max_amount: 1, max_price_per_unit: l2_gas_price },
+        ResourceBounds::default(),
+    );
+    let rpc_tx = rpc_invoke_tx(invoke_tx_args!(resource_bounds));
+
+    // The base fee of the next block is at least 880, so the floor, with the grace margin, is 792.
+    let result = stateful_validator.validate_l2_gas_price(&rpc_tx, &gas_prices);
+    if expected_valid {
+        result.unwrap();
+    } else {
+        assert_matches!(result, Err(GatewaySpecError::ValidationFailure { .. }));
+    }
+}
+
+#[rstest]
+#[case::disabled(DeclareAntispamPolicy::Disabled, 1, false, true)]
+#[case::committed_fee_above_min(DeclareAntispamPolicy::MinCommittedFee, 1000, false, true)]
+#[case::committed_fee_below_min(DeclareAntispamPolicy::MinCommittedFee, 999, false, false)]
+#[case::balance_above_min(DeclareAntispamPolicy::MinBalance, BALANCE / 10, false, true)]
+#[case::balance_below_min(DeclareAntispamPolicy::MinBalance, BALANCE / 10 + 1, false, false)]
+#[case::zero_balance(DeclareAntispamPolicy::MinBalance, 1, true, false)]
+fn test_validate_declare_antispam(
+    #[case] policy: DeclareAntispamPolicy,
+    #[case] committed_fee: u128,
+    #[case] zero_balance: bool,
+    #[case] expected_valid: bool,
+    block_context: BlockContext,
+) {
+    let stateful_validator = StatefulTransactionValidator {
+        config: StatefulTransactionValidatorConfig {
+            chain_info: block_context.chain_info().clone(),
+            declare_antispam_config: DeclareAntispamConfig {
+                policy,
+                min_committed_fee: 1000,
+                balance_to_fee_ratio: 10,
+            },
+            ..Default::default()
+        },
+    };
+    let state_reader_factory = local_test_state_reader_factory(CairoVersion::Cairo1, zero_balance);
+    let resource_bounds = create_resource_bounds_mapping(
+        ResourceBounds::default(),
+        ResourceBounds { max_amount: 1, max_price_per_unit: committed_fee },
+        ResourceBounds::default(),
+    );
+    let sender_address =
+        FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1).get_instance_address(0);
+    let rpc_tx = rpc_declare_tx(declare_tx_args!(sender_address, resource_bounds));
+
+    let result = stateful_validator.validate_declare_antispam(
+        &rpc_tx,
+        &state_reader_factory,
+        block_context.block_info(),
+    );
+    if expected_valid {
+        result.unwrap();
+    } else {
+        assert_matches!(result, Err(GatewaySpecError::ValidationFailure { .. }));
+    }
+}
+
+#[rstest]
+#[case::before_cutover(BlockNumber(11), 4, true)]
+#[case::supported_version(BlockNumber(10), 3, true)]
+#[case::deprecated_version(BlockNumber(10), 4, false)]
+fn test_validate_tx_version(
+    #[case] cutover_height: BlockNumber,
+    #[case] min_invoke_version: u8,
+    #[case] expected_valid: bool,
+    block_context: BlockContext,
+) {
+    let mut chain_info = block_context.chain_info().clone();
+    chain_info.tx_version_config =
+        TransactionVersionConfig { cutover_height, min_invoke_version, ..Default::default() };
+    let stateful_validator = StatefulTransactionValidator {
+        config: StatefulTransactionValidatorConfig { chain_info, ..Default::default() },
+    };
+    // The transaction is validated for the block after the latest block.
+    let latest_block_info =
+        BlockInfo { block_number: BlockNumber(9), ..BlockInfo::create_for_testing() };
+
+    let result = stateful_validator
+        .validate_tx_version(&invoke_tx(CairoVersion::Cairo1), &latest_block_info);
+    if expected_valid {
+        result.unwrap();
+    } else {
+        assert_matches!(result, Err(GatewaySpecError::UnsupportedTxVersion));
+    }
+}
@@ -5,7 +5,14 @@ use starknet_api::rpc_transaction::{
     RpcTransaction,
 };
 use starknet_api::state::EntryPoint;
-use starknet_api::transaction::{AllResourceBounds, Resource};
+use starknet_api::transaction::{Resource, TransactionVersion};
+use starknet_mempool_types::stateless_validation::{
+    validate_calldata_length,
+    validate_contract_address,
+    validate_resource_is_non_zero,
+    validate_signature_length,
+    validate_tx_version,
+};
 use starknet_types_core::felt::Felt;
 use tracing::{instrument, Level};
 
@@ -26,8 +33,9 @@ impl StatelessTransactionValidator {
     #[instrument(skip(self), level = Level::INFO, err)]
     pub fn validate(&self, tx: &RpcTransaction) -> StatelessTransactionValidatorResult<()> {
         // TODO(Arni, 1/5/2024): Add a mechanism that validate the sender address is not blocked.
-        // TODO(Arni, 1/5/2024): Validate transaction version.
 
+        self.validate_tx_version(tx)?;
+        self.validate_sender_address(tx)?;
         self.validate_resource_bounds(tx)?;
         self.validate_tx_size(tx)?;
 
@@ -37,17 +45,51 @@ impl StatelessTransactionValidator {
         Ok(())
     }
 
+    fn validate_tx_version(&self, tx: &RpcTransaction) -> StatelessTransactionValidatorResult<()> {
+        // The version of an RPC transaction is determined by its variant.
+        let version = match tx {
+            RpcTransaction::Declare(RpcDeclareTransaction::V3(_))
+            | RpcTransaction::DeployAccount(RpcDeployAccountTransaction::V3(_))
+            | RpcTransaction::Invoke(RpcInvokeTransaction::V3(_)) => TransactionVersion::THREE,
+        };
+        validate_tx_version(version)?;
+
+        Ok(())
+    }
+
+    fn validate_sender_address(
+        &self,
+        tx: &RpcTransaction,
+    ) -> StatelessTransactionValidatorResult<()> {
+        let sender_address = match tx {
+            RpcTransaction::Declare(RpcDeclareTransaction::V3(tx)) => tx.sender_address,
+            // The address of the deployed account is derived from the transaction.
+            RpcTransaction::DeployAccount(_) => return Ok(()),
+            RpcTransaction::Invoke(RpcInvokeTransaction::V3(tx)) => tx.sender_address,
+        };
+        validate_contract_address(sender_address)?;
+
+        Ok(())
+    }
+
     fn validate_resource_bounds(
         &self,
         tx: &RpcTransaction,
     ) -> StatelessTransactionValidatorResult<()> {
+        let config = &self.config.stateless_validation;
         let resource_bounds_mapping = tx.resource_bounds();
 
-        if self.config.validate_non_zero_l1_gas_fee {
-            validate_resource_is_non_zero(resource_bounds_mapping, Resource::L1Gas)?;
+        if config.validate_non_zero_l1_gas_fee {
+            validate_resource_is_non_zero(
+                Resource::L1Gas,
+                resource_bounds_mapping.get_bound(Resource::L1Gas),
+            )?;
         }
-        if self.config.validate_non_zero_l2_gas_fee {
-            validate_resource_is_non_zero(resource_bounds_mapping, Resource::L2Gas)?;
+        if config.validate_non_zero_l2_gas_fee {
+            validate_resource_is_non_zero(
+                Resource::L2Gas,
+                resource_bounds_mapping.get_bound(Resource::L2Gas),
+            )?;
         }
 
         Ok(())
@@ -75,13 +117,7 @@ impl StatelessTransactionValidator {
             RpcTransaction::Invoke(RpcInvokeTransaction::V3(tx)) => &tx.calldata,
         };
 
-        let calldata_length = calldata.0.len();
-        if calldata_length > self.config.max_calldata_length {
-            return Err(StatelessTransactionValidatorError::CalldataTooLong {
-                calldata_length,
-                max_calldata_length: self.config.max_calldata_length,
-            });
-        }
+        validate_calldata_length(calldata, self.config.stateless_validation.max_calldata_length)?;
 
         Ok(())
     }
@@ -90,15 +126,10 @@ impl StatelessTransactionValidator {
         &self,
         tx: &RpcTransaction,
     ) -> StatelessTransactionValidatorResult<()> {
-        let signature = tx.signature();
-
-        let signature_length = signature.0.len();
-        if signature_length > self.config.max_signature_length {
-            return Err(StatelessTransactionValidatorError::SignatureTooLong {
-                signature_length,
-                max_signature_length: self.config.max_signature_length,
-            });
-        }
+        validate_signature_length(
+            tx.signature(),
+            self.config.stateless_validation.max_signature_length,
+        )?;
 
         Ok(())
     }
@@ -173,18 +204,3 @@ impl StatelessTransactionValidator {
         Err(StatelessTransactionValidatorError::EntryPointsNotUniquelySorted)
     }
 }
-
-fn validate_resource_is_non_zero(
-    all_resource_bounds: &AllResourceBounds,
-    resource: Resource,
-) -> StatelessTransactionValidatorResult<()> {
-    let resource_bounds = all_resource_bounds.get_bound(resource);
-    if resource_bounds.max_amount == 0 || resource_bounds.max_price_per_unit == 0 {
-        return Err(StatelessTransactionValidatorError::ZeroResourceBounds {
-            resource,
-            resource_bounds,
-        });
-    }
-
-    Ok(())
-}
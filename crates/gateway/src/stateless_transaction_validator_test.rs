@@ -23,6 +23,10 @@ use starknet_api::transaction::{
     TransactionSignature,
 };
 use starknet_api::{calldata, felt};
+use starknet_mempool_types::stateless_validation::{
+    StatelessValidationConfig,
+    StatelessValidationError,
+};
 use starknet_types_core::felt::Felt;
 
 use crate::compiler_version::{VersionId, VersionIdError};
@@ -46,33 +50,42 @@ fn default_validator_config_for_testing() -> &'static StatelessTransactionValida
     static DEFAULT_VALIDATOR_CONFIG_FOR_TESTING: OnceLock<StatelessTransactionValidatorConfig> =
         OnceLock::new();
     DEFAULT_VALIDATOR_CONFIG_FOR_TESTING.get_or_init(|| StatelessTransactionValidatorConfig {
-        validate_non_zero_l1_gas_fee: false,
-        validate_non_zero_l2_gas_fee: false,
-        max_calldata_length: 1,
-        max_signature_length: 1,
+        stateless_validation: StatelessValidationConfig {
+            validate_non_zero_l1_gas_fee: false,
+            validate_non_zero_l2_gas_fee: false,
+            max_calldata_length: 1,
+            max_signature_length: 1,
+        },
         max_contract_class_object_size: 100000,
         min_sierra_version: *min_sierra_version(),
         max_sierra_version: *max_sierra_version(),
     })
 }
 
+fn resource_bounds_validator_config(
+    validate_non_zero_l1_gas_fee: bool,
+    validate_non_zero_l2_gas_fee: bool,
+) -> StatelessTransactionValidatorConfig {
+    let default_config = default_validator_config_for_testing().clone();
+    StatelessTransactionValidatorConfig {
+        stateless_validation: StatelessValidationConfig {
+            validate_non_zero_l1_gas_fee,
+            validate_non_zero_l2_gas_fee,
+            ..default_config.stateless_validation
+        },
+        ..default_config
+    }
+}
+
 #[rstest]
 #[case::ignore_resource_bounds(
-    StatelessTransactionValidatorConfig{
-        validate_non_zero_l1_gas_fee: false,
-        validate_non_zero_l2_gas_fee: false,
-        ..default_validator_config_for_testing().clone()
-    },
+    resource_bounds_validator_config(false, false),
     zero_resource_bounds_mapping(),
     calldata![],
     TransactionSignature::default()
 )]
 #[case::valid_l1_gas(
-    StatelessTransactionValidatorConfig{
-        validate_non_zero_l1_gas_fee: true,
-        validate_non_zero_l2_gas_fee: false,
-        ..default_validator_config_for_testing().clone()
-    },
+    resource_bounds_validator_config(true, false),
     create_resource_bounds_mapping(
         NON_EMPTY_RESOURCE_BOUNDS,
         ResourceBounds::default(),
@@ -82,11 +95,7 @@ fn default_validator_config_for_testing() -> &'static StatelessTransactionValida
     TransactionSignature::default()
 )]
 #[case::valid_l2_gas(
-    StatelessTransactionValidatorConfig{
-        validate_non_zero_l1_gas_fee: false,
-        validate_non_zero_l2_gas_fee: true,
-        ..default_validator_config_for_testing().clone()
-    },
+    resource_bounds_validator_config(false, true),
     create_resource_bounds_mapping(
         ResourceBounds::default(),
         NON_EMPTY_RESOURCE_BOUNDS,
@@ -96,11 +105,7 @@ fn default_validator_config_for_testing() -> &'static StatelessTransactionValida
     TransactionSignature::default()
 )]
 #[case::valid_l1_and_l2_gas(
-    StatelessTransactionValidatorConfig{
-        validate_non_zero_l1_gas_fee: true,
-        validate_non_zero_l2_gas_fee: true,
-        ..default_validator_config_for_testing().clone()
-    },
+    resource_bounds_validator_config(true, true),
     create_resource_bounds_mapping(
         NON_EMPTY_RESOURCE_BOUNDS,
         NON_EMPTY_RESOURCE_BOUNDS,
@@ -143,30 +148,22 @@ fn test_positive_flow(
 
 #[rstest]
 #[case::zero_l1_gas_resource_bounds(
-    StatelessTransactionValidatorConfig{
-        validate_non_zero_l1_gas_fee: true,
-        validate_non_zero_l2_gas_fee: false,
-        ..default_validator_config_for_testing().clone()
-    },
+    resource_bounds_validator_config(true, false),
     zero_resource_bounds_mapping(),
-    StatelessTransactionValidatorError::ZeroResourceBounds{
+    StatelessValidationError::ZeroResourceBounds{
         resource: Resource::L1Gas, resource_bounds: ResourceBounds::default()
-    }
+    }.into()
 )]
 #[case::zero_l2_gas_resource_bounds(
-    StatelessTransactionValidatorConfig{
-        validate_non_zero_l1_gas_fee: false,
-        validate_non_zero_l2_gas_fee: true,
-        ..default_validator_config_for_testing().clone()
-    },
+    resource_bounds_validator_config(false, true),
     create_resource_bounds_mapping(
         NON_EMPTY_RESOURCE_BOUNDS,
         ResourceBounds::default(),
         ResourceBounds::default(),
     ),
-    StatelessTransactionValidatorError::ZeroResourceBounds{
+    StatelessValidationError::ZeroResourceBounds{
         resource: Resource::L2Gas, resource_bounds: ResourceBounds::default()
-    }
+    }.into()
 )]
 fn test_invalid_resource_bounds(
     #[case] config: StatelessTransactionValidatorConfig,
@@ -197,10 +194,10 @@ fn test_calldata_too_long(
 
     assert_eq!(
         tx_validator.validate(&tx).unwrap_err(),
-        StatelessTransactionValidatorError::CalldataTooLong {
+        StatelessTransactionValidatorError::from(StatelessValidationError::CalldataTooLong {
             calldata_length: 2,
             max_calldata_length: 1
-        }
+        })
     );
 }
 
@@ -220,10 +217,10 @@ fn test_signature_too_long(
 
     assert_eq!(
         tx_validator.validate(&tx).unwrap_err(),
-        StatelessTransactionValidatorError::SignatureTooLong {
+        StatelessTransactionValidatorError::from(StatelessValidationError::SignatureTooLong {
             signature_length: 2,
             max_signature_length: 1
-        }
+        })
     );
 }
 
@@ -8,8 +8,11 @@ use blockifier::transaction::transactions::{
 use starknet_api::core::{calculate_contract_address, ChainId, ClassHash, ContractAddress};
 use starknet_api::rpc_transaction::{
     RpcDeclareTransaction,
+    RpcDeclareTransactionV3,
     RpcDeployAccountTransaction,
+    RpcDeployAccountTransactionV3,
     RpcInvokeTransaction,
+    RpcInvokeTransactionV3,
     RpcTransaction,
 };
 use starknet_api::transaction::{
@@ -17,63 +20,63 @@ use starknet_api::transaction::{
     DeclareTransactionV3,
     DeployAccountTransaction,
     DeployAccountTransactionV3,
+    InvokeTransaction,
     InvokeTransactionV3,
+    TransactionHash,
     TransactionHasher,
 };
 use tracing::error;
 
 use crate::errors::{GatewaySpecError, StatefulTransactionValidatorResult};
 
+/// Calculates the hash of the transaction, as the sequencer will, for the given chain.
+pub fn calculate_tx_hash(
+    rpc_tx: &RpcTransaction,
+    chain_id: &ChainId,
+) -> StatefulTransactionValidatorResult<TransactionHash> {
+    match rpc_tx {
+        RpcTransaction::Declare(RpcDeclareTransaction::V3(tx)) => {
+            let declare_tx = declare_tx_from_rpc(tx);
+            declare_tx.calculate_transaction_hash(chain_id, &declare_tx.version())
+        }
+        RpcTransaction::DeployAccount(RpcDeployAccountTransaction::V3(tx)) => {
+            let deploy_account_tx = deploy_account_tx_from_rpc(tx);
+            deploy_account_tx.calculate_transaction_hash(chain_id, &deploy_account_tx.version())
+        }
+        RpcTransaction::Invoke(RpcInvokeTransaction::V3(tx)) => {
+            let invoke_tx = invoke_tx_from_rpc(tx);
+            invoke_tx.calculate_transaction_hash(chain_id, &invoke_tx.version())
+        }
+    }
+    .map_err(|e| {
+        error!("Failed to calculate tx hash: {}", e);
+        GatewaySpecError::UnexpectedError { data: "Internal server error".to_owned() }
+    })
+}
+
 pub fn rpc_tx_to_account_tx(
     rpc_tx: &RpcTransaction,
     // FIXME(yael 15/4/24): calculate class_info inside the function once compilation code is ready
     optional_class_info: Option<ClassInfo>,
     chain_id: &ChainId,
 ) -> StatefulTransactionValidatorResult<AccountTransaction> {
+    let tx_hash = calculate_tx_hash(rpc_tx, chain_id)?;
     match rpc_tx {
         RpcTransaction::Declare(RpcDeclareTransaction::V3(tx)) => {
-            let declare_tx = DeclareTransaction::V3(DeclareTransactionV3 {
-                class_hash: ClassHash::default(), /* FIXME(yael 15/4/24): call the starknet-api
-                                                   * function once ready */
-                resource_bounds: tx.resource_bounds.clone().into(),
-                tip: tx.tip,
-                signature: tx.signature.clone(),
-                nonce: tx.nonce,
-                compiled_class_hash: tx.compiled_class_hash,
-                sender_address: tx.sender_address,
-                nonce_data_availability_mode: tx.nonce_data_availability_mode,
-                fee_data_availability_mode: tx.fee_data_availability_mode,
-                paymaster_data: tx.paymaster_data.clone(),
-                account_deployment_data: tx.account_deployment_data.clone(),
-            });
-            let tx_hash = declare_tx
-                .calculate_transaction_hash(chain_id, &declare_tx.version())
-                .map_err(|e| {
-                    error!("Failed to calculate tx hash: {}", e);
-                    GatewaySpecError::UnexpectedError { data: "Internal server error".to_owned() }
-                })?;
             let class_info =
                 optional_class_info.expect("declare transaction should contain class info");
-            let declare_tx = BlockifierDeclareTransaction::new(declare_tx, tx_hash, class_info)
-                .map_err(|e| {
-                    error!("Failed to convert declare tx hash to blockifier tx type: {}", e);
-                    GatewaySpecError::UnexpectedError { data: "Internal server error".to_owned() }
-                })?;
+            let declare_tx =
+                BlockifierDeclareTransaction::new(declare_tx_from_rpc(tx), tx_hash, class_info)
+                    .map_err(|e| {
+                        error!("Failed to convert declare tx hash to blockifier tx type: {}", e);
+                        GatewaySpecError::UnexpectedError {
+                            data: "Internal server error".to_owned(),
+                        }
+                    })?;
             Ok(AccountTransaction::Declare(declare_tx))
         }
         RpcTransaction::DeployAccount(RpcDeployAccountTransaction::V3(tx)) => {
-            let deploy_account_tx = DeployAccountTransaction::V3(DeployAccountTransactionV3 {
-                resource_bounds: tx.resource_bounds.clone().into(),
-                tip: tx.tip,
-                signature: tx.signature.clone(),
-                nonce: tx.nonce,
-                class_hash: tx.class_hash,
-                contract_address_salt: tx.contract_address_salt,
-                constructor_calldata: tx.constructor_calldata.clone(),
-                nonce_data_availability_mode: tx.nonce_data_availability_mode,
-                fee_data_availability_mode: tx.fee_data_availability_mode,
-                paymaster_data: tx.paymaster_data.clone(),
-            });
+            let deploy_account_tx = deploy_account_tx_from_rpc(tx);
             let contract_address = calculate_contract_address(
                 deploy_account_tx.contract_address_salt(),
                 deploy_account_tx.class_hash(),
@@ -84,12 +87,6 @@ pub fn rpc_tx_to_account_tx(
                 error!("Failed to calculate contract address: {}", e);
                 GatewaySpecError::UnexpectedError { data: "Internal server error".to_owned() }
             })?;
-            let tx_hash = deploy_account_tx
-                .calculate_transaction_hash(chain_id, &deploy_account_tx.version())
-                .map_err(|e| {
-                    error!("Failed to calculate tx hash: {}", e);
-                    GatewaySpecError::UnexpectedError { data: "Internal server error".to_owned() }
-                })?;
             let deploy_account_tx = BlockifierDeployAccountTransaction::new(
                 deploy_account_tx,
                 tx_hash,
@@ -98,30 +95,59 @@ pub fn rpc_tx_to_account_tx(
             Ok(AccountTransaction::DeployAccount(deploy_account_tx))
         }
         RpcTransaction::Invoke(RpcInvokeTransaction::V3(tx)) => {
-            let invoke_tx = starknet_api::transaction::InvokeTransaction::V3(InvokeTransactionV3 {
-                resource_bounds: tx.resource_bounds.clone().into(),
-                tip: tx.tip,
-                signature: tx.signature.clone(),
-                nonce: tx.nonce,
-                sender_address: tx.sender_address,
-                calldata: tx.calldata.clone(),
-                nonce_data_availability_mode: tx.nonce_data_availability_mode,
-                fee_data_availability_mode: tx.fee_data_availability_mode,
-                paymaster_data: tx.paymaster_data.clone(),
-                account_deployment_data: tx.account_deployment_data.clone(),
-            });
-            let tx_hash = invoke_tx
-                .calculate_transaction_hash(chain_id, &invoke_tx.version())
-                .map_err(|e| {
-                    error!("Failed to calculate tx hash: {}", e);
-                    GatewaySpecError::UnexpectedError { data: "Internal server error".to_owned() }
-                })?;
-            let invoke_tx = BlockifierInvokeTransaction::new(invoke_tx, tx_hash);
+            let invoke_tx = BlockifierInvokeTransaction::new(invoke_tx_from_rpc(tx), tx_hash);
             Ok(AccountTransaction::Invoke(invoke_tx))
         }
     }
 }
 
+fn declare_tx_from_rpc(tx: &RpcDeclareTransactionV3) -> DeclareTransaction {
+    DeclareTransaction::V3(DeclareTransactionV3 {
+        class_hash: ClassHash::default(), /* FIXME(yael 15/4/24): call the starknet-api
+                                           * function once ready */
+        resource_bounds: tx.resource_bounds.clone().into(),
+        tip: tx.tip,
+        signature: tx.signature.clone(),
+        nonce: tx.nonce,
+        compiled_class_hash: tx.compiled_class_hash,
+        sender_address: tx.sender_address,
+        nonce_data_availability_mode: tx.nonce_data_availability_mode,
+        fee_data_availability_mode: tx.fee_data_availability_mode,
+        paymaster_data: tx.paymaster_data.clone(),
+        account_deployment_data: tx.account_deployment_data.clone(),
+    })
+}
+
+fn deploy_account_tx_from_rpc(tx: &RpcDeployAccountTransactionV3) -> DeployAccountTransaction {
+    DeployAccountTransaction::V3(DeployAccountTransactionV3 {
+        resource_bounds: tx.resource_bounds.clone().into(),
+        tip: tx.tip,
+        signature: tx.signature.clone(),
+        nonce: tx.nonce,
+        class_hash: tx.class_hash,
+        contract_address_salt: tx.contract_address_salt,
+        constructor_calldata: tx.constructor_calldata.clone(),
+        nonce_data_availability_mode: tx.nonce_data_availability_mode,
+        fee_data_availability_mode: tx.fee_data_availability_mode,
+        paymaster_data: tx.paymaster_data.clone(),
+    })
+}
+
+fn invoke_tx_from_rpc(tx: &RpcInvokeTransactionV3) -> InvokeTransaction {
+    InvokeTransaction::V3(InvokeTransactionV3 {
+        resource_bounds: tx.resource_bounds.clone().into(),
+        tip: tx.tip,
+        signature: tx.signature.clone(),
+        nonce: tx.nonce,
+        sender_address: tx.sender_address,
+        calldata: tx.calldata.clone(),
+        nonce_data_availability_mode: tx.nonce_data_availability_mode,
+        fee_data_availability_mode: tx.fee_data_availability_mode,
+        paymaster_data: tx.paymaster_data.clone(),
+        account_deployment_data: tx.account_deployment_data.clone(),
+    })
+}
+
 // TODO(yael 9/5/54): Should be implemented as part of InternalTransaction in starknet-api
 pub fn get_sender_address(tx: &AccountTransaction) -> ContractAddress {
     match tx {
@@ -131,7 +157,7 @@ pub fn get_sender_address(tx: &AccountTransaction) -> ContractAddress {
         },
         AccountTransaction::DeployAccount(tx) => tx.contract_address(),
         AccountTransaction::Invoke(tx) => match &tx.tx() {
-            starknet_api::transaction::InvokeTransaction::V3(tx) => tx.sender_address,
+            InvokeTransaction::V3(tx) => tx.sender_address,
             _ => panic!("Unsupported transaction version"),
         },
     }
@@ -1,25 +1,47 @@
+use std::collections::HashMap;
 use std::net::IpAddr;
 
 use async_trait::async_trait;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::ContractAddress;
 use starknet_api::executable_transaction::Transaction;
+use starknet_api::transaction::TransactionHash;
 use starknet_mempool_infra::component_definitions::ComponentRequestHandler;
 use starknet_mempool_infra::component_runner::ComponentStarter;
-use starknet_mempool_infra::component_server::{LocalComponentServer, RemoteComponentServer};
+use starknet_mempool_infra::component_server::{GrpcComponentServer, LocalComponentServer};
+use starknet_mempool_infra::metrics::{
+    ACCEPTED,
+    COMPONENT_LABEL,
+    MEMPOOL_SIZE,
+    MEMPOOL_TRANSACTIONS_ADDED,
+    MEMPOOL_TRANSACTIONS_SENT,
+    REJECTED,
+    RESULT_LABEL,
+};
 use starknet_mempool_types::communication::{
     MempoolRequest,
     MempoolRequestAndResponseSender,
     MempoolResponse,
 };
-use starknet_mempool_types::mempool_types::{MempoolInput, MempoolResult};
+use starknet_mempool_types::mempool_types::{
+    AccountState,
+    LeaseId,
+    MempoolInput,
+    MempoolResult,
+    TransactionStatus,
+};
 use tokio::sync::mpsc::Receiver;
 
 use crate::mempool::Mempool;
 
+const MEMPOOL_COMPONENT: &str = "mempool";
+
 pub type MempoolServer =
     LocalComponentServer<MempoolCommunicationWrapper, MempoolRequest, MempoolResponse>;
 
+/// The server of a standalone mempool, which serves it over gRPC.
 pub type RemoteMempoolServer =
-    RemoteComponentServer<MempoolCommunicationWrapper, MempoolRequest, MempoolResponse>;
+    GrpcComponentServer<MempoolCommunicationWrapper, MempoolRequest, MempoolResponse>;
 
 pub fn create_mempool_server(
     mempool: Mempool,
@@ -35,7 +57,7 @@ pub fn create_remote_mempool_server(
     port: u16,
 ) -> RemoteMempoolServer {
     let communication_wrapper = MempoolCommunicationWrapper::new(mempool);
-    RemoteComponentServer::new(communication_wrapper, ip_address, port)
+    GrpcComponentServer::new(communication_wrapper, ip_address, port)
 }
 
 /// Wraps the mempool to enable inbound async communication from other components.
@@ -49,17 +71,94 @@ impl MempoolCommunicationWrapper {
     }
 
     fn add_tx(&mut self, mempool_input: MempoolInput) -> MempoolResult<()> {
-        self.mempool.add_tx(mempool_input)
+        let result = self.mempool.add_tx(mempool_input);
+        let result_label = if result.is_ok() { ACCEPTED } else { REJECTED };
+        metrics::increment_counter!(
+            MEMPOOL_TRANSACTIONS_ADDED,
+            COMPONENT_LABEL => MEMPOOL_COMPONENT,
+            RESULT_LABEL => result_label
+        );
+        self.update_size_metric();
+        result
     }
 
     fn get_txs(&mut self, n_txs: usize) -> MempoolResult<Vec<Transaction>> {
-        self.mempool.get_txs(n_txs)
+        let result = self.mempool.get_txs(n_txs);
+        if let Ok(txs) = &result {
+            metrics::counter!(
+                MEMPOOL_TRANSACTIONS_SENT,
+                txs.len() as u64,
+                COMPONENT_LABEL => MEMPOOL_COMPONENT
+            );
+        }
+        self.update_size_metric();
+        result
+    }
+
+    fn get_txs_snapshot(&self, n_txs: usize) -> MempoolResult<Vec<Transaction>> {
+        self.mempool.get_txs_snapshot(n_txs)
+    }
+
+    fn open_lease(&mut self, height: BlockNumber) -> MempoolResult<LeaseId> {
+        let lease_id = self.mempool.open_lease(height);
+        self.update_size_metric();
+        Ok(lease_id)
+    }
+
+    fn lease_txs(&mut self, lease_id: LeaseId, n_txs: usize) -> MempoolResult<Vec<Transaction>> {
+        let result = self.mempool.lease_txs(lease_id, n_txs);
+        if let Ok(txs) = &result {
+            metrics::counter!(
+                MEMPOOL_TRANSACTIONS_SENT,
+                txs.len() as u64,
+                COMPONENT_LABEL => MEMPOOL_COMPONENT
+            );
+        }
+        self.update_size_metric();
+        result
+    }
+
+    fn release_lease(&mut self, lease_id: LeaseId) -> MempoolResult<()> {
+        let result = self.mempool.release_lease(lease_id);
+        self.update_size_metric();
+        result
+    }
+
+    fn commit_block(
+        &mut self,
+        height: BlockNumber,
+        state_changes: HashMap<ContractAddress, AccountState>,
+    ) -> MempoolResult<()> {
+        let result = self.mempool.commit_block(height, state_changes);
+        self.update_size_metric();
+        result
+    }
+
+    fn update_gas_price_threshold(&mut self, threshold: u128) -> MempoolResult<()> {
+        self.mempool.update_gas_price_threshold(threshold);
+        self.update_size_metric();
+        Ok(())
+    }
+
+    fn get_tx_status(&self, tx_hash: TransactionHash) -> MempoolResult<Option<TransactionStatus>> {
+        Ok(self.mempool.tx_status(tx_hash))
+    }
+
+    fn flush(&mut self) -> MempoolResult<usize> {
+        let n_flushed_txs = self.mempool.flush();
+        self.update_size_metric();
+        Ok(n_flushed_txs)
+    }
+
+    fn update_size_metric(&self) {
+        metrics::gauge!(MEMPOOL_SIZE, self.mempool.n_txs() as f64, COMPONENT_LABEL => MEMPOOL_COMPONENT);
     }
 }
 
 #[async_trait]
 impl ComponentRequestHandler<MempoolRequest, MempoolResponse> for MempoolCommunicationWrapper {
     async fn handle_request(&mut self, request: MempoolRequest) -> MempoolResponse {
+        self.mempool.apply_config_updates();
         match request {
             MempoolRequest::AddTransaction(mempool_input) => {
                 MempoolResponse::AddTransaction(self.add_tx(mempool_input))
@@ -67,6 +166,28 @@ impl ComponentRequestHandler<MempoolRequest, MempoolResponse> for MempoolCommuni
             MempoolRequest::GetTransactions(n_txs) => {
                 MempoolResponse::GetTransactions(self.get_txs(n_txs))
             }
+            MempoolRequest::GetTransactionsSnapshot(n_txs) => {
+                MempoolResponse::GetTransactionsSnapshot(self.get_txs_snapshot(n_txs))
+            }
+            MempoolRequest::OpenLease(height) => {
+                MempoolResponse::OpenLease(self.open_lease(height))
+            }
+            MempoolRequest::LeaseTransactions(lease_id, n_txs) => {
+                MempoolResponse::LeaseTransactions(self.lease_txs(lease_id, n_txs))
+            }
+            MempoolRequest::ReleaseLease(lease_id) => {
+                MempoolResponse::ReleaseLease(self.release_lease(lease_id))
+            }
+            MempoolRequest::CommitBlock(height, state_changes) => {
+                MempoolResponse::CommitBlock(self.commit_block(height, state_changes))
+            }
+            MempoolRequest::UpdateGasPriceThreshold(threshold) => {
+                MempoolResponse::UpdateGasPriceThreshold(self.update_gas_price_threshold(threshold))
+            }
+            MempoolRequest::GetTransactionStatus(tx_hash) => {
+                MempoolResponse::GetTransactionStatus(self.get_tx_status(tx_hash))
+            }
+            MempoolRequest::Flush => MempoolResponse::Flush(self.flush()),
         }
     }
 }
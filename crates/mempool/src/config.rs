@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// The mempool related configuration.
+#[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
+pub struct MempoolConfig {
+    /// If true, a deploy-account transaction is handed out for sequencing together with the next
+    /// transaction of its account, so that both are included in the same block.
+    pub bundle_deploy_account_txs: bool,
+    /// How the transactions to hand out for sequencing are selected among the senders.
+    pub tx_selection: TransactionSelection,
+    /// The maximal number of transactions of a sender that are handed out for a block, when
+    /// selecting round-robin.
+    #[validate(range(min = 1))]
+    pub max_txs_per_sender_per_block: usize,
+}
+
+impl Default for MempoolConfig {
+    fn default() -> Self {
+        Self {
+            bundle_deploy_account_txs: false,
+            tx_selection: TransactionSelection::default(),
+            max_txs_per_sender_per_block: 10,
+        }
+    }
+}
+
+/// How the transactions to hand out for sequencing are selected among the senders.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub enum TransactionSelection {
+    /// By the tip of the next transaction of each sender, so senders that pay more are sequenced
+    /// first.
+    #[default]
+    FeePriority,
+    /// In rounds, one transaction of each sender per round, with up to
+    /// `max_txs_per_sender_per_block` transactions of a sender per block. Within a round, senders
+    /// are ordered by tip. Suits chains that prioritize fairness over fee auctions.
+    RoundRobin,
+}
+
+impl SerializeConfig for MempoolConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "bundle_deploy_account_txs",
+                &self.bundle_deploy_account_txs,
+                "If true, a deploy-account transaction is sequenced together with the next \
+                 transaction of its account, so that both are included in the same block",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "tx_selection",
+                &self.tx_selection,
+                "How the transactions to sequence are selected among the senders: FeePriority, by \
+                 tip, or RoundRobin, one transaction of each sender per round",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_txs_per_sender_per_block",
+                &self.max_txs_per_sender_per_block,
+                "The maximal number of transactions of a sender that are sequenced in a block, \
+                 when selecting round-robin",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
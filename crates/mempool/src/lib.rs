@@ -1,4 +1,5 @@
 pub mod communication;
+pub mod config;
 pub mod mempool;
 pub(crate) mod suspended_transaction_pool;
 pub(crate) mod transaction_pool;
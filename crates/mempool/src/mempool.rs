@@ -1,11 +1,22 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::Arc;
 
+use starknet_api::block::BlockNumber;
 use starknet_api::core::{ContractAddress, Nonce};
 use starknet_api::executable_transaction::Transaction;
 use starknet_api::transaction::{DeprecatedResourceBoundsMapping, Resource, Tip, TransactionHash};
 use starknet_mempool_types::errors::MempoolError;
-use starknet_mempool_types::mempool_types::{Account, AccountState, MempoolInput, MempoolResult};
-
+use starknet_mempool_types::mempool_types::{
+    Account,
+    AccountState,
+    LeaseId,
+    MempoolInput,
+    MempoolResult,
+    TransactionStatus,
+};
+use tokio::sync::watch;
+
+use crate::config::{MempoolConfig, TransactionSelection};
 use crate::suspended_transaction_pool::SuspendedTransactionPool;
 use crate::transaction_pool::TransactionPool;
 use crate::transaction_queue::TransactionQueue;
@@ -16,8 +27,15 @@ pub mod mempool_test;
 
 type AccountToNonce = HashMap<ContractAddress, Nonce>;
 
+// The number of the most recently committed transactions whose hashes the mempool keeps, to answer
+// on their status.
+const MAX_COMMITTED_TX_HASHES: usize = 100_000;
+
 #[derive(Debug, Default)]
 pub struct Mempool {
+    config: MempoolConfig,
+    // Set once a handle for replacing the config is taken, see `config_sender`.
+    config_sender: Option<Arc<watch::Sender<MempoolConfig>>>,
     // TODO: add docstring explaining visibility and coupling of the fields.
     // All transactions currently held in the mempool.
     tx_pool: TransactionPool,
@@ -29,47 +47,176 @@ pub struct Mempool {
     mempool_state: HashMap<ContractAddress, AccountState>,
     // The most recent account nonces received, for all account in the pool.
     _account_nonces: AccountToNonce,
+    // Transactions returned by `get_txs` since the last committed block.
+    staged_txs: Vec<TransactionReference>,
+    // The hashes of the transactions of the recently committed blocks, oldest first.
+    committed_tx_hashes: VecDeque<TransactionHash>,
+    committed_tx_hashes_set: HashSet<TransactionHash>,
+    // The open leases; their transactions are removed from the pool until the lease ends, when
+    // it's released or a block of its height is committed.
+    leases: HashMap<LeaseId, Lease>,
+    // The hashes of the staged and leased transactions, to answer on their status.
+    handed_out_tx_hashes: HashSet<TransactionHash>,
+    next_lease_id: LeaseId,
+    // The number of transactions of each sender handed out since the last committed block.
+    sender_tx_counts: HashMap<ContractAddress, usize>,
+    // When bundling deploy-account transactions: the nonces of the deploy-account transactions
+    // handed out since the last committed block without the next transaction of their account, by
+    // their account.
+    unbundled_deploy_accounts: HashMap<ContractAddress, Nonce>,
+    // The transactions that arrived after the deploy-account transaction of their account was
+    // handed out without them; they're handed out before any other transaction.
+    late_bundled_txs: Vec<TransactionReference>,
+}
+
+#[derive(Debug)]
+struct Lease {
+    txs: Vec<Transaction>,
+    // The height of the block the transactions are leased for.
+    height: BlockNumber,
 }
 
 impl Mempool {
+    pub fn new(config: MempoolConfig) -> Self {
+        Mempool { config, ..Default::default() }
+    }
+
     pub fn empty() -> Self {
         Mempool::default()
     }
 
+    /// Returns a handle for replacing the config of the running mempool. The new config applies
+    /// once [`Mempool::apply_config_updates`] is called.
+    pub fn config_sender(&mut self) -> Arc<watch::Sender<MempoolConfig>> {
+        self.config_sender
+            .get_or_insert_with(|| Arc::new(watch::Sender::new(self.config.clone())))
+            .clone()
+    }
+
+    /// Applies the config last sent to the handle of [`Mempool::config_sender`].
+    pub fn apply_config_updates(&mut self) {
+        if let Some(config_sender) = &self.config_sender {
+            self.config = config_sender.borrow().clone();
+        }
+    }
+
     /// Returns an iterator of the current eligible transactions for sequencing, ordered by their
     /// priority.
     pub fn iter(&self) -> impl Iterator<Item = &TransactionReference> {
         self.tx_queue.iter_over_ready_txs()
     }
 
+    /// Returns the number of transactions held in the mempool.
+    pub fn n_txs(&self) -> usize {
+        self.tx_pool.n_txs()
+    }
+
     /// Retrieves up to `n_txs` transactions with the highest priority from the mempool.
     /// Transactions are guaranteed to be unique across calls until `commit_block` is invoked.
+    /// When bundling deploy-account transactions, a deploy-account transaction is followed by the
+    /// next transaction of its account, and the two count as two of the `n_txs`; if only one is
+    /// left, the two are deferred. The next transaction of an account whose deploy-account
+    /// transaction was handed out without it is handed out first once it arrives.
     // TODO: the last part about commit_block is incorrect if we delete txs in get_txs and then push
     // back. TODO: Consider renaming to `pop_txs` to be more consistent with the standard
     // library.
     pub fn get_txs(&mut self, n_txs: usize) -> MempoolResult<Vec<Transaction>> {
-        let mut eligible_tx_references: Vec<TransactionReference> = Vec::with_capacity(n_txs);
-        let mut n_remaining_txs = n_txs;
+        let (eligible_tx_references, eligible_txs) = self.pop_eligible_txs(n_txs)?;
+        self.staged_txs.extend(eligible_tx_references);
 
-        while n_remaining_txs > 0 && !self.tx_queue.has_ready_txs() {
-            let chunk = self.tx_queue.pop_ready_chunk(n_remaining_txs);
-            self.enqueue_next_eligible_txs(&chunk)?;
-            n_remaining_txs -= chunk.len();
-            eligible_tx_references.extend(chunk);
+        Ok(eligible_txs)
+    }
+
+    /// Opens a lease on transactions of the mempool for the block of the given height; see
+    /// [`Mempool::lease_txs`].
+    pub fn open_lease(&mut self, height: BlockNumber) -> LeaseId {
+        let lease_id = self.next_lease_id;
+        self.next_lease_id += 1;
+        self.leases.insert(lease_id, Lease { txs: Vec::new(), height });
+        lease_id
+    }
+
+    /// Like `get_txs`, but the transactions are handed out under the given lease, which holds them
+    /// until the block of its height is committed or the lease is released. Once the lease is
+    /// released, its transactions become available again; once the block is committed, so do its
+    /// transactions that weren't included in the block.
+    pub fn lease_txs(
+        &mut self,
+        lease_id: LeaseId,
+        n_txs: usize,
+    ) -> MempoolResult<Vec<Transaction>> {
+        if !self.leases.contains_key(&lease_id) {
+            return Err(MempoolError::LeaseNotFound { lease_id });
         }
+        let (_, eligible_txs) = self.pop_eligible_txs(n_txs)?;
+
+        let lease = self.leases.get_mut(&lease_id).expect("The lease exists.");
+        lease.txs.extend(eligible_txs.iter().cloned());
+        Ok(eligible_txs)
+    }
+
+    /// Ends the lease, and makes its transactions available again.
+    pub fn release_lease(&mut self, lease_id: LeaseId) -> MempoolResult<()> {
+        let lease =
+            self.leases.remove(&lease_id).ok_or(MempoolError::LeaseNotFound { lease_id })?;
+        for tx in &lease.txs {
+            self.handed_out_tx_hashes.remove(&tx.tx_hash());
+        }
+        self.return_txs(lease.txs);
+        Ok(())
+    }
+
+    // Removes up to `n_txs` transactions with the highest priority from the mempool.
+    fn pop_eligible_txs(
+        &mut self,
+        n_txs: usize,
+    ) -> MempoolResult<(Vec<TransactionReference>, Vec<Transaction>)> {
+        let eligible_tx_references = select_txs(
+            &self.config,
+            &self.tx_pool,
+            &mut self.tx_queue,
+            &mut self.sender_tx_counts,
+            &mut self.late_bundled_txs,
+            n_txs,
+        )?;
 
         let mut eligible_txs: Vec<Transaction> = Vec::with_capacity(n_txs);
         for tx_ref in &eligible_tx_references {
             let tx = self.tx_pool.remove(tx_ref.tx_hash)?;
+            self.handed_out_tx_hashes.insert(tx_ref.tx_hash);
             eligible_txs.push(tx);
         }
+        if self.config.bundle_deploy_account_txs {
+            self.record_unbundled_deploy_accounts(&eligible_txs);
+        }
 
         // Update the mempool state with the given transactions' nonces.
         for tx in &eligible_txs {
             self.mempool_state.entry(tx.contract_address()).or_default().nonce = tx.nonce();
         }
 
-        Ok(eligible_txs)
+        Ok((eligible_tx_references, eligible_txs))
+    }
+
+    /// Returns up to `n_txs` of the transactions `get_txs` would return now, in the same order,
+    /// without removing them from the mempool. Used to plan a block without committing to it.
+    pub fn get_txs_snapshot(&self, n_txs: usize) -> MempoolResult<Vec<Transaction>> {
+        let mut tx_queue = self.tx_queue.selection_candidates(n_txs, &self.sender_tx_counts);
+        let mut sender_tx_counts = self.sender_tx_counts.clone();
+        let mut late_bundled_txs = self.late_bundled_txs.clone();
+        let snapshot_tx_references = select_txs(
+            &self.config,
+            &self.tx_pool,
+            &mut tx_queue,
+            &mut sender_tx_counts,
+            &mut late_bundled_txs,
+            n_txs,
+        )?;
+
+        snapshot_tx_references
+            .iter()
+            .map(|tx_reference| Ok(self.tx_pool.get_by_tx_hash(tx_reference.tx_hash)?.clone()))
+            .collect()
     }
 
     /// Adds a new transaction to the mempool.
@@ -79,19 +226,54 @@ impl Mempool {
         self.validate_input(&input)?;
         let MempoolInput { tx, account: Account { sender_address, state: AccountState { nonce } } } =
             input;
+        let tx_reference = TransactionReference::new(&tx);
         self.tx_pool.insert(tx)?;
         self.align_to_account_state(sender_address, nonce);
+        if self.completes_unbundled_deploy_account(&tx_reference) {
+            self.unbundled_deploy_accounts.remove(&sender_address);
+            self.late_bundled_txs.push(tx_reference);
+        }
         Ok(())
     }
 
+    // Records the handed out deploy-account transactions that weren't followed by the next
+    // transaction of their account.
+    fn record_unbundled_deploy_accounts(&mut self, handed_out_txs: &[Transaction]) {
+        for (i, tx) in handed_out_txs.iter().enumerate() {
+            let is_bundled = handed_out_txs
+                .get(i + 1)
+                .is_some_and(|next_tx| next_tx.contract_address() == tx.contract_address());
+            if matches!(tx, Transaction::DeployAccount(_)) && !is_bundled {
+                self.unbundled_deploy_accounts.insert(tx.contract_address(), tx.nonce());
+            }
+        }
+    }
+
+    // Whether the transaction is the next one of an account whose deploy-account transaction was
+    // handed out without it, and can be bundled with it.
+    fn completes_unbundled_deploy_account(&self, tx_reference: &TransactionReference) -> bool {
+        let Some(deploy_account_nonce) =
+            self.unbundled_deploy_accounts.get(&tx_reference.sender_address)
+        else {
+            return false;
+        };
+        deploy_account_nonce
+            .try_increment()
+            .is_ok_and(|next_nonce| next_nonce == tx_reference.nonce)
+            && self.tx_queue.get_nonce(tx_reference.sender_address).is_none()
+            && tx_reference.get_l2_gas_price() >= self.tx_queue.gas_price_threshold()
+    }
+
     /// Update the mempool's internal state according to the committed block (resolves nonce gaps,
     /// updates account balances).
     // TODO: the part about resolving nonce gaps is incorrect if we delete txs in get_txs and then
     // push back.
+    // height: the height of the committed block; the leases up to it end.
     // state_changes: a map that associates each account address with the state of the committed
     // block.
     pub fn commit_block(
         &mut self,
+        height: BlockNumber,
         state_changes: HashMap<ContractAddress, AccountState>,
     ) -> MempoolResult<()> {
         for (&address, AccountState { nonce }) in &state_changes {
@@ -99,21 +281,163 @@ impl Mempool {
             self.align_to_account_state(address, next_nonce);
         }
 
-        // Rewind nonces of addresses that were not included in block.
-        let addresses_not_included_in_block =
-            self.mempool_state.keys().filter(|&key| !state_changes.contains_key(key));
+        // The staged and leased transactions up to the committed nonce of their account were
+        // included in the block. The transactions that weren't, of the leases that end, are
+        // returned to the pool; the leases of later heights keep theirs.
+        let is_committed = |tx_reference: &TransactionReference| {
+            state_changes
+                .get(&tx_reference.sender_address)
+                .is_some_and(|AccountState { nonce }| tx_reference.nonce <= *nonce)
+        };
+        for tx_reference in std::mem::take(&mut self.staged_txs) {
+            if is_committed(&tx_reference) {
+                self.record_committed_tx(tx_reference.tx_hash);
+            }
+        }
+        let mut uncommitted_leased_txs = Vec::new();
+        let mut leases = std::mem::take(&mut self.leases);
+        for lease in leases.values_mut() {
+            for tx in std::mem::take(&mut lease.txs) {
+                if is_committed(&TransactionReference::new(&tx)) {
+                    self.record_committed_tx(tx.tx_hash());
+                } else if lease.height <= height {
+                    uncommitted_leased_txs.push(tx);
+                } else {
+                    lease.txs.push(tx);
+                }
+            }
+        }
+        leases.retain(|_, lease| lease.height > height);
+        self.leases = leases;
+
+        // Rewind nonces of addresses that were not included in block, unless they're still leased.
+        let addresses_not_included_in_block: Vec<ContractAddress> = self
+            .mempool_state
+            .keys()
+            .filter(|&key| !state_changes.contains_key(key))
+            .filter(|&&key| !self.leased_txs().any(|tx| tx.contract_address() == key))
+            .copied()
+            .collect();
         for address in addresses_not_included_in_block {
-            self.tx_queue.remove(*address);
+            self.tx_queue.remove(address);
         }
 
         self.mempool_state.clear();
+        self.sender_tx_counts.clear();
+        self.handed_out_tx_hashes.clear();
+        // The transactions that weren't handed out yet are queued by the committed nonces.
+        self.unbundled_deploy_accounts.clear();
+        self.late_bundled_txs.clear();
+        // The transactions of the remaining leases stay handed out.
+        let still_leased_txs: Vec<TransactionReference> =
+            self.leased_txs().map(TransactionReference::new).collect();
+        for tx_reference in still_leased_txs {
+            self.handed_out_tx_hashes.insert(tx_reference.tx_hash);
+            *self.sender_tx_counts.entry(tx_reference.sender_address).or_default() += 1;
+            let AccountState { nonce } =
+                self.mempool_state.entry(tx_reference.sender_address).or_default();
+            *nonce = (*nonce).max(tx_reference.nonce);
+        }
+        self.return_txs(uncommitted_leased_txs);
 
         Ok(())
     }
 
-    // TODO(Mohammad): Rename this method once consensus API is added.
-    fn _update_gas_price_threshold(&mut self, threshold: u128) {
-        self.tx_queue._update_gas_price_threshold(threshold);
+    /// Sets the minimal L2 gas price of the transactions that are eligible for sequencing, e.g.
+    /// the base fee of the next block. Transactions below it are parked until it falls back under
+    /// their price.
+    pub fn update_gas_price_threshold(&mut self, threshold: u128) {
+        self.tx_queue.update_gas_price_threshold(threshold);
+    }
+
+    /// Drops the transactions that wait in the mempool, e.g. to recover from a flood of
+    /// transactions, and returns their number. The transactions that were handed out for
+    /// sequencing are kept, and so is the gas price threshold.
+    pub fn flush(&mut self) -> usize {
+        let n_flushed_txs = self.tx_pool.n_txs();
+        self.tx_pool = TransactionPool::default();
+        let gas_price_threshold = self.tx_queue.gas_price_threshold();
+        self.tx_queue = TransactionQueue::default();
+        self.tx_queue.update_gas_price_threshold(gas_price_threshold);
+        n_flushed_txs
+    }
+
+    /// Returns the status of the transaction, if it's held in the mempool or was included in one
+    /// of the recently committed blocks.
+    pub fn tx_status(&self, tx_hash: TransactionHash) -> Option<TransactionStatus> {
+        if self.tx_pool.get_by_tx_hash(tx_hash).is_ok()
+            || self.handed_out_tx_hashes.contains(&tx_hash)
+        {
+            return Some(TransactionStatus::Received);
+        }
+        self.committed_tx_hashes_set.contains(&tx_hash).then_some(TransactionStatus::Committed)
+    }
+
+    // Returns transactions that were handed out under a lease to the pool, and makes the first
+    // returned transaction of each account eligible for sequencing again.
+    fn return_txs(&mut self, txs: Vec<Transaction>) {
+        let mut first_returned_txs: BTreeMap<ContractAddress, TransactionReference> =
+            BTreeMap::new();
+        for tx in txs {
+            let tx_reference = TransactionReference::new(&tx);
+            if self.tx_pool.insert(tx).is_err() {
+                continue;
+            }
+            if let Some(n_sender_txs) = self.sender_tx_counts.get_mut(&tx_reference.sender_address)
+            {
+                *n_sender_txs = n_sender_txs.saturating_sub(1);
+            }
+            first_returned_txs
+                .entry(tx_reference.sender_address)
+                .and_modify(|first_tx_reference| {
+                    if tx_reference.nonce < first_tx_reference.nonce {
+                        *first_tx_reference = tx_reference.clone();
+                    }
+                })
+                .or_insert(tx_reference);
+        }
+
+        for (address, tx_reference) in first_returned_txs {
+            // The returned deploy-account transaction is bundled once it's handed out again.
+            if self.unbundled_deploy_accounts.get(&address) == Some(&tx_reference.nonce) {
+                self.unbundled_deploy_accounts.remove(&address);
+            }
+            self.late_bundled_txs.retain(|late_tx_reference| {
+                late_tx_reference.sender_address != address
+                    || late_tx_reference.nonce < tx_reference.nonce
+            });
+            if self.tx_queue.get_nonce(address).is_some_and(|nonce| nonce < tx_reference.nonce) {
+                continue;
+            }
+            self.tx_queue.remove(address);
+            self.tx_queue.insert(tx_reference);
+            // The account's nonces from the returned one on can be handed out again, unless
+            // transactions of the account are still handed out.
+            if !self.is_handed_out(address) {
+                self.mempool_state.remove(&address);
+            }
+        }
+    }
+
+    fn is_handed_out(&self, address: ContractAddress) -> bool {
+        self.staged_txs.iter().any(|tx_reference| tx_reference.sender_address == address)
+            || self.leased_txs().any(|tx| tx.contract_address() == address)
+    }
+
+    fn leased_txs(&self) -> impl Iterator<Item = &Transaction> {
+        self.leases.values().flat_map(|lease| lease.txs.iter())
+    }
+
+    fn record_committed_tx(&mut self, tx_hash: TransactionHash) {
+        if !self.committed_tx_hashes_set.insert(tx_hash) {
+            return;
+        }
+        self.committed_tx_hashes.push_back(tx_hash);
+        if self.committed_tx_hashes.len() > MAX_COMMITTED_TX_HASHES {
+            let oldest_tx_hash =
+                self.committed_tx_hashes.pop_front().expect("The committed hashes aren't empty.");
+            self.committed_tx_hashes_set.remove(&oldest_tx_hash);
+        }
     }
 
     fn validate_input(&self, input: &MempoolInput) -> MempoolResult<()> {
@@ -153,23 +477,6 @@ impl Mempool {
         Ok(())
     }
 
-    fn enqueue_next_eligible_txs(&mut self, txs: &[TransactionReference]) -> MempoolResult<()> {
-        for tx in txs {
-            let current_account_state = Account {
-                sender_address: tx.sender_address,
-                state: AccountState { nonce: tx.nonce },
-            };
-
-            if let Some(next_tx_reference) =
-                self.tx_pool.get_next_eligible_tx(current_account_state)?
-            {
-                self.tx_queue.insert(next_tx_reference.clone());
-            }
-        }
-
-        Ok(())
-    }
-
     // TODO: Consider creating an abstraction for the (address, nonce) tuple that is passed
     // throughout the code.
     fn align_to_account_state(&mut self, address: ContractAddress, nonce: Nonce) {
@@ -180,6 +487,9 @@ impl Mempool {
         }
 
         self.tx_pool.remove_up_to_nonce(address, nonce);
+        self.late_bundled_txs.retain(|tx_reference| {
+            tx_reference.sender_address != address || tx_reference.nonce >= nonce
+        });
 
         // Maybe close nonce gap.
         if self.tx_queue.get_nonce(address).is_none() {
@@ -195,6 +505,121 @@ impl Mempool {
     }
 }
 
+// Pops the next chunk of up to `n_txs` transactions to hand out, by the configured selection.
+fn pop_chunk(
+    config: &MempoolConfig,
+    tx_queue: &mut TransactionQueue,
+    n_txs: usize,
+    sender_tx_counts: &HashMap<ContractAddress, usize>,
+) -> Vec<TransactionReference> {
+    match config.tx_selection {
+        TransactionSelection::FeePriority => tx_queue.pop_ready_chunk(n_txs),
+        TransactionSelection::RoundRobin => tx_queue.pop_round_robin_chunk(
+            n_txs,
+            sender_tx_counts,
+            config.max_txs_per_sender_per_block,
+        ),
+    }
+}
+
+// Selects up to `n_txs` transactions, in the order they're handed out: first the late bundled
+// transactions, then the transactions of the queue. Updates the queue and the number of
+// transactions handed out to each sender accordingly. Once a transaction is selected, the next
+// transaction of its account joins the queue.
+fn select_txs(
+    config: &MempoolConfig,
+    tx_pool: &TransactionPool,
+    tx_queue: &mut TransactionQueue,
+    sender_tx_counts: &mut HashMap<ContractAddress, usize>,
+    late_bundled_txs: &mut Vec<TransactionReference>,
+    n_txs: usize,
+) -> MempoolResult<Vec<TransactionReference>> {
+    let mut selected_tx_references: Vec<TransactionReference> = Vec::with_capacity(n_txs);
+    let n_late_bundled_txs = late_bundled_txs.len().min(n_txs);
+    for tx_reference in late_bundled_txs.drain(..n_late_bundled_txs) {
+        *sender_tx_counts.entry(tx_reference.sender_address).or_default() += 1;
+        enqueue_next_tx(tx_pool, tx_queue, &tx_reference)?;
+        selected_tx_references.push(tx_reference);
+    }
+
+    // The deploy-account transactions whose bundle doesn't fit in the remaining transactions;
+    // they're queued again once the selection is done.
+    let mut deferred_tx_references = Vec::new();
+    loop {
+        let n_remaining_txs = n_txs - selected_tx_references.len();
+        if n_remaining_txs == 0 || tx_queue.has_ready_txs() {
+            break;
+        }
+        let chunk = pop_chunk(config, tx_queue, n_remaining_txs, sender_tx_counts);
+        if chunk.is_empty() {
+            break;
+        }
+        for tx_reference in chunk {
+            let bundled_tx_reference =
+                bundled_tx(config, tx_pool, tx_queue.gas_price_threshold(), &tx_reference)?
+                    .cloned();
+            let n_bundle_txs = 1 + usize::from(bundled_tx_reference.is_some());
+            if selected_tx_references.len() + n_bundle_txs > n_txs {
+                deferred_tx_references.push(tx_reference);
+                continue;
+            }
+            *sender_tx_counts.entry(tx_reference.sender_address).or_default() += n_bundle_txs;
+
+            // The account continues after the last of its selected transactions.
+            let last_account_tx = bundled_tx_reference.as_ref().unwrap_or(&tx_reference);
+            enqueue_next_tx(tx_pool, tx_queue, last_account_tx)?;
+
+            selected_tx_references.push(tx_reference);
+            selected_tx_references.extend(bundled_tx_reference);
+        }
+    }
+    for tx_reference in deferred_tx_references {
+        tx_queue.insert(tx_reference);
+    }
+
+    Ok(selected_tx_references)
+}
+
+// Queues the transaction of the account that follows the given one, if it's in the pool.
+fn enqueue_next_tx(
+    tx_pool: &TransactionPool,
+    tx_queue: &mut TransactionQueue,
+    tx_reference: &TransactionReference,
+) -> MempoolResult<()> {
+    let current_account_state = Account {
+        sender_address: tx_reference.sender_address,
+        state: AccountState { nonce: tx_reference.nonce },
+    };
+    if let Some(next_tx_reference) = tx_pool.get_next_eligible_tx(current_account_state)? {
+        tx_queue.insert(next_tx_reference.clone());
+    }
+    Ok(())
+}
+
+// The transaction that is sequenced together with the given one, if any: when bundling
+// deploy-account transactions, the next transaction of a deployed account, unless it's below the
+// gas price threshold.
+fn bundled_tx<'a>(
+    config: &MempoolConfig,
+    tx_pool: &'a TransactionPool,
+    gas_price_threshold: u128,
+    tx_reference: &TransactionReference,
+) -> MempoolResult<Option<&'a TransactionReference>> {
+    if !config.bundle_deploy_account_txs
+        || !matches!(tx_pool.get_by_tx_hash(tx_reference.tx_hash)?, Transaction::DeployAccount(_))
+    {
+        return Ok(None);
+    }
+
+    let current_account_state = Account {
+        sender_address: tx_reference.sender_address,
+        state: AccountState { nonce: tx_reference.nonce },
+    };
+    let next_tx_reference = tx_pool.get_next_eligible_tx(current_account_state)?;
+    Ok(next_tx_reference
+        .filter(|next_tx_reference| next_tx_reference.get_l2_gas_price() >= gas_price_threshold))
+}
+
 /// Provides a lightweight representation of a transaction for mempool usage (e.g., excluding
 /// execution fields).
 /// TODO(Mohammad): rename this struct to `ThinTransaction` once that name
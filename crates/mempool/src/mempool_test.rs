@@ -8,15 +8,22 @@ use mempool_test_utils::starknet_api_test_utils::{
 };
 use pretty_assertions::assert_eq;
 use rstest::{fixture, rstest};
+use starknet_api::block::BlockNumber;
 use starknet_api::core::{ContractAddress, Nonce, PatriciaKey};
-use starknet_api::executable_transaction::Transaction;
+use starknet_api::executable_transaction::{
+    DeployAccountTransaction,
+    InvokeTransaction,
+    Transaction,
+};
 use starknet_api::hash::StarkHash;
-use starknet_api::transaction::{Tip, TransactionHash};
+use starknet_api::transaction::{DeployAccountTransactionV3, Tip, TransactionHash};
 use starknet_api::{contract_address, felt, patricia_key};
 use starknet_mempool_types::errors::MempoolError;
-use starknet_mempool_types::mempool_types::{Account, AccountState};
+use starknet_mempool_types::mempool_types::{Account, AccountState, TransactionStatus};
 use starknet_types_core::felt::Felt;
+use validator::Validate;
 
+use crate::config::{MempoolConfig, TransactionSelection};
 use crate::mempool::{AccountToNonce, Mempool, MempoolInput, TransactionReference};
 use crate::transaction_pool::TransactionPool;
 use crate::transaction_queue::TransactionQueue;
@@ -107,6 +114,7 @@ impl From<MempoolContent> for Mempool {
             _suspended_tx_pool: Default::default(),
             mempool_state: Default::default(),
             _account_nonces: account_nonces.unwrap_or_default(),
+            ..Default::default()
         }
     }
 }
@@ -141,6 +149,33 @@ fn add_tx_expect_error(mempool: &mut Mempool, input: &MempoolInput, expected_err
     assert_eq!(mempool.add_tx(input.clone()), Err(expected_error));
 }
 
+/// Turns an invoke transaction into a deploy-account transaction with the same fields.
+fn deploy_account_tx(invoke_tx: Transaction) -> Transaction {
+    let Transaction::Invoke(InvokeTransaction {
+        tx: starknet_api::transaction::InvokeTransaction::V3(tx),
+        tx_hash,
+    }) = invoke_tx
+    else {
+        panic!("Expected an invoke V3 transaction.");
+    };
+    Transaction::DeployAccount(DeployAccountTransaction {
+        tx: starknet_api::transaction::DeployAccountTransaction::V3(DeployAccountTransactionV3 {
+            resource_bounds: tx.resource_bounds,
+            tip: tx.tip,
+            signature: tx.signature,
+            nonce: tx.nonce,
+            class_hash: Default::default(),
+            contract_address_salt: Default::default(),
+            constructor_calldata: Default::default(),
+            nonce_data_availability_mode: tx.nonce_data_availability_mode,
+            fee_data_availability_mode: tx.fee_data_availability_mode,
+            paymaster_data: tx.paymaster_data,
+        }),
+        tx_hash,
+        contract_address: tx.sender_address,
+    })
+}
+
 /// Creates a valid input for mempool's `add_tx` with optional default values.
 /// Usage:
 /// 1. add_tx_input!(tip: 1, tx_hash: 2, sender_address: 3_u8, tx_nonce: 4, account_nonce: 3)
@@ -377,14 +412,14 @@ fn test_get_txs_while_decreasing_gas_price_threshold() {
 
     // Test.
     // High gas price threshold, no transactions should be returned.
-    mempool._update_gas_price_threshold(1000000000000);
+    mempool.update_gas_price_threshold(1000000000000);
     let txs = mempool.get_txs(1).unwrap();
     assert!(txs.is_empty());
 
     // Updating the gas price threshold should happen in a new block creation.
-    assert!(mempool.commit_block(HashMap::default()).is_ok());
+    assert!(mempool.commit_block(BlockNumber(0), HashMap::default()).is_ok());
     // Low gas price threshold, the transaction should be returned.
-    mempool._update_gas_price_threshold(100);
+    mempool.update_gas_price_threshold(100);
     let txs = mempool.get_txs(1).unwrap();
 
     assert_eq!(txs, &[input_tx.tx]);
@@ -403,18 +438,273 @@ fn test_get_txs_while_increasing_gas_price_threshold() {
 
     // Test.
     // Low gas price threshold, the transaction should be returned.
-    mempool._update_gas_price_threshold(100);
+    mempool.update_gas_price_threshold(100);
     let txs = mempool.get_txs(1).unwrap();
     assert_eq!(txs, &[input_tx_nonce_0.tx]);
 
     // Updating the gas price threshold should happen in a new block creation.
-    assert!(mempool.commit_block(HashMap::default()).is_ok());
+    assert!(mempool.commit_block(BlockNumber(0), HashMap::default()).is_ok());
     // High gas price threshold, no transactions should be returned.
-    mempool._update_gas_price_threshold(1000000000000);
+    mempool.update_gas_price_threshold(1000000000000);
     let txs = mempool.get_txs(1).unwrap();
     assert!(txs.is_empty());
 }
 
+#[rstest]
+#[case::bundled(true, 3)]
+#[case::not_bundled(false, 2)]
+fn test_get_txs_bundles_deploy_account_tx(
+    #[case] bundle_deploy_account_txs: bool,
+    #[case] n_txs: usize,
+) {
+    // Setup.
+    let deploy_account_tx = deploy_account_tx(
+        add_tx_input!(tip: 10, tx_hash: 1, sender_address: "0x0", tx_nonce: 0_u8, account_nonce: 0_u8).tx,
+    );
+    let invoke_tx =
+        add_tx_input!(tip: 10, tx_hash: 2, sender_address: "0x0", tx_nonce: 1_u8, account_nonce: 0_u8).tx;
+    let other_account_tx =
+        add_tx_input!(tip: 20, tx_hash: 3, sender_address: "0x1", tx_nonce: 0_u8, account_nonce: 0_u8).tx;
+
+    let queue_txs = [&deploy_account_tx, &other_account_tx].map(TransactionReference::new);
+    let pool_txs = [&deploy_account_tx, &invoke_tx, &other_account_tx].map(|tx| tx.clone());
+    let mut mempool = Mempool {
+        config: MempoolConfig { bundle_deploy_account_txs, ..Default::default() },
+        ..MempoolContent::with_pool_and_queue(pool_txs, queue_txs).into()
+    };
+
+    // Test.
+    let snapshot_txs = mempool.get_txs_snapshot(n_txs).unwrap();
+    let txs = mempool.get_txs(n_txs).unwrap();
+
+    // Assert: when bundled, the invoke transaction follows the deploy-account transaction.
+    assert_eq!(snapshot_txs, txs);
+    if bundle_deploy_account_txs {
+        assert_eq!(txs, [other_account_tx, deploy_account_tx, invoke_tx]);
+        let expected_mempool_content = MempoolContent::with_pool_and_queue([], []);
+        expected_mempool_content.assert_eq_pool_and_queue_content(&mempool);
+    } else {
+        assert_eq!(txs, [other_account_tx, deploy_account_tx]);
+        let expected_queue_txs = [TransactionReference::new(&invoke_tx)];
+        let expected_mempool_content =
+            MempoolContent::with_pool_and_queue([invoke_tx], expected_queue_txs);
+        expected_mempool_content.assert_eq_pool_and_queue_content(&mempool);
+    }
+}
+
+#[rstest]
+fn test_get_txs_defers_deploy_account_bundle_when_one_tx_is_left() {
+    // Setup.
+    let deploy_account_tx = deploy_account_tx(
+        add_tx_input!(tip: 10, tx_hash: 1, sender_address: "0x0", tx_nonce: 0_u8, account_nonce: 0_u8).tx,
+    );
+    let invoke_tx =
+        add_tx_input!(tip: 10, tx_hash: 2, sender_address: "0x0", tx_nonce: 1_u8, account_nonce: 0_u8).tx;
+    let other_account_tx =
+        add_tx_input!(tip: 20, tx_hash: 3, sender_address: "0x1", tx_nonce: 0_u8, account_nonce: 0_u8).tx;
+
+    let queue_txs = [&deploy_account_tx, &other_account_tx].map(TransactionReference::new);
+    let pool_txs = [&deploy_account_tx, &invoke_tx, &other_account_tx].map(|tx| tx.clone());
+    let mut mempool = Mempool {
+        config: MempoolConfig { bundle_deploy_account_txs: true, ..Default::default() },
+        ..MempoolContent::with_pool_and_queue(pool_txs, queue_txs).into()
+    };
+
+    // Test and assert: the bundle doesn't fit in the single transaction left, so it's deferred to
+    // the next call.
+    let snapshot_txs = mempool.get_txs_snapshot(2).unwrap();
+    let txs = mempool.get_txs(2).unwrap();
+    assert_eq!(snapshot_txs, txs);
+    assert_eq!(txs, [other_account_tx]);
+
+    let txs = mempool.get_txs(2).unwrap();
+    assert_eq!(txs, [deploy_account_tx, invoke_tx]);
+}
+
+#[rstest]
+fn test_get_txs_bundles_invoke_tx_arriving_after_deploy_account_tx() {
+    // Setup.
+    let deploy_account_tx = deploy_account_tx(
+        add_tx_input!(tip: 10, tx_hash: 1, sender_address: "0x0", tx_nonce: 0_u8, account_nonce: 0_u8).tx,
+    );
+    let mut mempool = Mempool {
+        config: MempoolConfig { bundle_deploy_account_txs: true, ..Default::default() },
+        ..MempoolContent::with_pool_and_queue(
+            [deploy_account_tx.clone()],
+            [TransactionReference::new(&deploy_account_tx)],
+        )
+        .into()
+    };
+    let txs = mempool.get_txs(2).unwrap();
+    assert_eq!(txs, [deploy_account_tx]);
+
+    // Test: the invoke transaction arrives after its deploy-account transaction was handed out.
+    let invoke_tx_input = add_tx_input!(tip: 10, tx_hash: 2, sender_address: "0x0", tx_nonce: 1_u8, account_nonce: 0_u8);
+    let other_account_tx_input = add_tx_input!(tip: 20, tx_hash: 3, sender_address: "0x1", tx_nonce: 0_u8, account_nonce: 0_u8);
+    for input in [&invoke_tx_input, &other_account_tx_input] {
+        add_tx(&mut mempool, input);
+    }
+
+    // Assert: the invoke transaction is handed out first, despite its lower tip.
+    let snapshot_txs = mempool.get_txs_snapshot(1).unwrap();
+    let txs = mempool.get_txs(1).unwrap();
+    assert_eq!(snapshot_txs, txs);
+    assert_eq!(txs, [invoke_tx_input.tx]);
+
+    let txs = mempool.get_txs(1).unwrap();
+    assert_eq!(txs, [other_account_tx_input.tx]);
+}
+
+// Transaction selection tests.
+
+#[rstest]
+#[case::fee_priority(TransactionSelection::FeePriority, [(0, 0), (0, 1), (0, 2)])]
+#[case::round_robin(TransactionSelection::RoundRobin, [(0, 0), (1, 0), (0, 1)])]
+fn test_get_txs_by_tx_selection(
+    #[case] tx_selection: TransactionSelection,
+    #[case] expected_txs: [(usize, usize); 3],
+) {
+    // Setup: two senders, one paying a higher tip.
+    let txs = [
+        [
+            add_tx_input!(tip: 30, tx_hash: 1, sender_address: "0x0", tx_nonce: 0_u8, account_nonce: 0_u8).tx,
+            add_tx_input!(tip: 30, tx_hash: 2, sender_address: "0x0", tx_nonce: 1_u8, account_nonce: 0_u8).tx,
+            add_tx_input!(tip: 30, tx_hash: 3, sender_address: "0x0", tx_nonce: 2_u8, account_nonce: 0_u8).tx,
+        ],
+        [
+            add_tx_input!(tip: 20, tx_hash: 4, sender_address: "0x1", tx_nonce: 0_u8, account_nonce: 0_u8).tx,
+            add_tx_input!(tip: 20, tx_hash: 5, sender_address: "0x1", tx_nonce: 1_u8, account_nonce: 0_u8).tx,
+            add_tx_input!(tip: 20, tx_hash: 6, sender_address: "0x1", tx_nonce: 2_u8, account_nonce: 0_u8).tx,
+        ],
+    ];
+    let queue_txs = [&txs[0][0], &txs[1][0]].map(TransactionReference::new);
+    let mut mempool = Mempool {
+        config: MempoolConfig { tx_selection, ..Default::default() },
+        ..MempoolContent::with_pool_and_queue(txs.concat(), queue_txs).into()
+    };
+
+    // Test: the transactions are requested one at a time.
+    let handed_out_txs: Vec<Transaction> =
+        (0..3).flat_map(|_| mempool.get_txs(1).unwrap()).collect();
+
+    // Assert.
+    let expected_txs: Vec<Transaction> = expected_txs
+        .into_iter()
+        .map(|(sender_index, nonce)| txs[sender_index][nonce].clone())
+        .collect();
+    assert_eq!(handed_out_txs, expected_txs);
+}
+
+#[rstest]
+fn test_round_robin_caps_txs_per_sender_per_block() {
+    // Setup.
+    let tx_address_0_nonce_0 = add_tx_input!(tip: 30, tx_hash: 1, sender_address: "0x0").tx;
+    let tx_address_0_nonce_1 =
+        add_tx_input!(tip: 30, tx_hash: 2, sender_address: "0x0", tx_nonce: 1_u8, account_nonce: 0_u8).tx;
+    let tx_address_1_nonce_0 = add_tx_input!(tip: 20, tx_hash: 3, sender_address: "0x1").tx;
+    let queue_txs = [&tx_address_0_nonce_0, &tx_address_1_nonce_0].map(TransactionReference::new);
+    let pool_txs =
+        [tx_address_0_nonce_0.clone(), tx_address_0_nonce_1.clone(), tx_address_1_nonce_0.clone()];
+    let config = MempoolConfig {
+        tx_selection: TransactionSelection::RoundRobin,
+        max_txs_per_sender_per_block: 1,
+        ..Default::default()
+    };
+    let mut mempool =
+        Mempool { config, ..MempoolContent::with_pool_and_queue(pool_txs, queue_txs).into() };
+
+    // Test and assert: a single transaction of each sender is handed out for the block.
+    assert_eq!(
+        mempool.get_txs_snapshot(3).unwrap(),
+        [tx_address_0_nonce_0.clone(), tx_address_1_nonce_0.clone()]
+    );
+    assert_eq!(mempool.get_txs(3).unwrap(), [tx_address_0_nonce_0, tx_address_1_nonce_0]);
+    assert!(mempool.get_txs(3).unwrap().is_empty());
+
+    // Test and assert: the cap applies per block.
+    let state_changes = HashMap::from([
+        (contract_address!("0x0"), AccountState { nonce: Nonce(felt!(0_u8)) }),
+        (contract_address!("0x1"), AccountState { nonce: Nonce(felt!(0_u8)) }),
+    ]);
+    assert!(mempool.commit_block(BlockNumber(0), state_changes).is_ok());
+    assert_eq!(mempool.get_txs(3).unwrap(), [tx_address_0_nonce_1]);
+}
+
+#[test]
+fn test_round_robin_requires_txs_per_sender_per_block() {
+    let config = MempoolConfig { max_txs_per_sender_per_block: 0, ..Default::default() };
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_config_updates_apply_once_applied() {
+    let mut mempool = Mempool::new(MempoolConfig::default());
+    let config = MempoolConfig { max_txs_per_sender_per_block: 1, ..Default::default() };
+
+    mempool.config_sender().send_replace(config.clone());
+    assert_ne!(mempool.config, config);
+    mempool.apply_config_updates();
+    assert_eq!(mempool.config, config);
+}
+
+// get_txs_snapshot tests.
+
+#[rstest]
+#[case::all_txs(4)]
+#[case::some_txs(2)]
+fn test_get_txs_snapshot_matches_get_txs(#[case] n_txs: usize) {
+    // Setup.
+    let tx_address_0_nonce_0 =
+        add_tx_input!(tip: 30, tx_hash: 1, sender_address: "0x0", tx_nonce: 0_u8, account_nonce: 0_u8).tx;
+    let tx_address_0_nonce_1 =
+        add_tx_input!(tip: 30, tx_hash: 2, sender_address: "0x0", tx_nonce: 1_u8, account_nonce: 0_u8).tx;
+    let tx_address_1_nonce_0 =
+        add_tx_input!(tip: 20, tx_hash: 3, sender_address: "0x1", tx_nonce: 0_u8, account_nonce: 0_u8).tx;
+    let tx_address_2_nonce_1 =
+        add_tx_input!(tip: 40, tx_hash: 4, sender_address: "0x2", tx_nonce: 1_u8, account_nonce: 0_u8).tx;
+
+    let queue_txs = [&tx_address_0_nonce_0, &tx_address_1_nonce_0].map(TransactionReference::new);
+    let pool_txs =
+        [tx_address_0_nonce_0, tx_address_0_nonce_1, tx_address_1_nonce_0, tx_address_2_nonce_1];
+    let mut mempool: Mempool =
+        MempoolContent::with_pool_and_queue(pool_txs.clone(), queue_txs.clone()).into();
+
+    // Test.
+    let snapshot_txs = mempool.get_txs_snapshot(n_txs).unwrap();
+
+    // Assert: the snapshot doesn't change the mempool, and is what `get_txs` returns.
+    let expected_mempool_content = MempoolContent::with_pool_and_queue(pool_txs, queue_txs);
+    expected_mempool_content.assert_eq_pool_and_queue_content(&mempool);
+    assert_eq!(snapshot_txs, mempool.get_txs(n_txs).unwrap());
+}
+
+#[rstest]
+fn test_get_txs_snapshot_matches_get_txs_after_handing_out(
+    #[values(TransactionSelection::FeePriority, TransactionSelection::RoundRobin)]
+    tx_selection: TransactionSelection,
+    #[values(1, 3, 5)] n_txs: usize,
+) {
+    // Setup: three senders, the first of which was already handed out a transaction.
+    let txs = [
+        add_tx_input!(tip: 30, tx_hash: 1, sender_address: "0x0", tx_nonce: 0_u8, account_nonce: 0_u8).tx,
+        add_tx_input!(tip: 30, tx_hash: 2, sender_address: "0x0", tx_nonce: 1_u8, account_nonce: 0_u8).tx,
+        add_tx_input!(tip: 30, tx_hash: 3, sender_address: "0x0", tx_nonce: 2_u8, account_nonce: 0_u8).tx,
+        add_tx_input!(tip: 20, tx_hash: 4, sender_address: "0x1", tx_nonce: 0_u8, account_nonce: 0_u8).tx,
+        add_tx_input!(tip: 20, tx_hash: 5, sender_address: "0x1", tx_nonce: 1_u8, account_nonce: 0_u8).tx,
+        add_tx_input!(tip: 10, tx_hash: 6, sender_address: "0x2", tx_nonce: 0_u8, account_nonce: 0_u8).tx,
+    ];
+    let queue_txs = [&txs[0], &txs[3], &txs[5]].map(TransactionReference::new);
+    let mut mempool = Mempool {
+        config: MempoolConfig { tx_selection, ..Default::default() },
+        ..MempoolContent::with_pool_and_queue(txs, queue_txs).into()
+    };
+    mempool.get_txs(1).unwrap();
+
+    // Test and assert.
+    let snapshot_txs = mempool.get_txs_snapshot(n_txs).unwrap();
+    assert_eq!(snapshot_txs, mempool.get_txs(n_txs).unwrap());
+}
+
 // add_tx tests.
 
 #[rstest]
@@ -702,7 +992,7 @@ fn test_commit_block_includes_all_txs() {
         (contract_address!("0x0"), AccountState { nonce: Nonce(felt!(3_u16)) }),
         (contract_address!("0x1"), AccountState { nonce: Nonce(felt!(2_u16)) }),
     ]);
-    assert!(mempool.commit_block(state_changes).is_ok());
+    assert!(mempool.commit_block(BlockNumber(0), state_changes).is_ok());
 
     // Assert.
     let expected_mempool_content = MempoolContent::with_pool_and_queue(pool_txs, queue_txs);
@@ -723,7 +1013,7 @@ fn test_commit_block_rewinds_nonce() {
         (contract_address!("0x0"), AccountState { nonce: Nonce(felt!(3_u16)) }),
         (contract_address!("0x1"), AccountState { nonce: Nonce(felt!(3_u16)) }),
     ]);
-    assert!(mempool.commit_block(state_changes).is_ok());
+    assert!(mempool.commit_block(BlockNumber(0), state_changes).is_ok());
 
     // Assert.
     let expected_mempool_content = MempoolContent::with_queue([]);
@@ -750,7 +1040,7 @@ fn test_commit_block_from_different_leader() {
         (contract_address!("0x1"), AccountState { nonce: Nonce(felt!(0_u16)) }),
         (contract_address!("0x2"), AccountState { nonce: Nonce(felt!(1_u16)) }),
     ]);
-    assert!(mempool.commit_block(state_changes).is_ok());
+    assert!(mempool.commit_block(BlockNumber(0), state_changes).is_ok());
 
     // Assert.
     let expected_queue_txs = [&tx_address0_nonce6].map(TransactionReference::new);
@@ -831,7 +1121,7 @@ fn test_flow_partial_commit_block() {
         (contract_address!("0x0"), AccountState { nonce: Nonce(felt!(3_u16)) }),
         (contract_address!("0x1"), AccountState { nonce: Nonce(felt!(0_u16)) }),
     ]);
-    assert!(mempool.commit_block(state_changes).is_ok());
+    assert!(mempool.commit_block(BlockNumber(0), state_changes).is_ok());
 
     // Assert.
     let expected_pool_txs = [tx_address0_nonce5, tx_address0_nonce6, tx_address1_nonce2];
@@ -855,7 +1145,7 @@ fn test_flow_commit_block_closes_hole() {
     // Test.
     let state_changes =
         HashMap::from([(contract_address!("0x0"), AccountState { nonce: Nonce(felt!(4_u8)) })]);
-    assert!(mempool.commit_block(state_changes).is_ok());
+    assert!(mempool.commit_block(BlockNumber(0), state_changes).is_ok());
 
     // Assert: hole was indeed closed.
     let expected_queue_txs = [&tx_nonce5].map(TransactionReference::new);
@@ -892,7 +1182,7 @@ fn test_flow_send_same_nonce_tx_after_previous_not_included() {
     // Transaction with nonce 4 is not included in the block.
     let state_changes =
         HashMap::from([(contract_address!("0x0"), AccountState { nonce: Nonce(felt!(3_u16)) })]);
-    assert!(mempool.commit_block(state_changes).is_ok());
+    assert!(mempool.commit_block(BlockNumber(0), state_changes).is_ok());
 
     add_tx(&mut mempool, &tx_input_nonce4);
     let txs = mempool.get_txs(1).unwrap();
@@ -928,10 +1218,158 @@ fn test_tx_pool_capacity(mut mempool: Mempool) {
     // changes.
     let state_changes =
         HashMap::from([(contract_address!("0x0"), AccountState { nonce: Nonce(felt!(4_u8)) })]);
-    assert!(mempool.commit_block(state_changes).is_ok());
+    assert!(mempool.commit_block(BlockNumber(0), state_changes).is_ok());
     assert_eq!(mempool.tx_pool().n_txs(), 1);
 
     // Test and assert: remove the transactions, counter does not go below 0.
     mempool.get_txs(2).unwrap();
     assert_eq!(mempool.tx_pool().n_txs(), 0);
 }
+
+// Lease tests.
+
+#[rstest]
+fn test_leased_txs_are_handed_out_once_until_released(mut mempool: Mempool) {
+    let input_tip_20 = add_tx_input!(tip: 20, tx_hash: 1, sender_address: "0x0");
+    let input_tip_10 = add_tx_input!(tip: 10, tx_hash: 2, sender_address: "0x1");
+    for input in [&input_tip_20, &input_tip_10] {
+        add_tx(&mut mempool, input);
+    }
+    let first_lease = mempool.open_lease(BlockNumber(0));
+    let second_lease = mempool.open_lease(BlockNumber(0));
+
+    // Test and assert: each lease gets its own transactions.
+    assert_eq!(mempool.lease_txs(first_lease, 1).unwrap(), vec![input_tip_20.tx.clone()]);
+    assert_eq!(mempool.lease_txs(second_lease, 2).unwrap(), vec![input_tip_10.tx]);
+    assert_eq!(mempool.tx_status(input_tip_20.tx.tx_hash()), Some(TransactionStatus::Received));
+
+    // Test and assert: the transactions of a released lease are handed out again.
+    assert_eq!(mempool.release_lease(first_lease), Ok(()));
+    assert_eq!(mempool.lease_txs(second_lease, 2).unwrap(), vec![input_tip_20.tx]);
+    assert_eq!(
+        mempool.lease_txs(first_lease, 2),
+        Err(MempoolError::LeaseNotFound { lease_id: first_lease })
+    );
+}
+
+#[rstest]
+fn test_commit_block_keeps_leases_of_later_heights(mut mempool: Mempool) {
+    let included_input =
+        add_tx_input!(tx_hash: 1, sender_address: "0x0", tx_nonce: 0_u8, account_nonce: 0_u8);
+    let leased_input =
+        add_tx_input!(tx_hash: 2, sender_address: "0x0", tx_nonce: 1_u8, account_nonce: 0_u8);
+    add_tx(&mut mempool, &included_input);
+    add_tx(&mut mempool, &leased_input);
+    let lease_id = mempool.open_lease(BlockNumber(1));
+    assert_eq!(
+        mempool.lease_txs(lease_id, 2).unwrap(),
+        vec![included_input.tx.clone(), leased_input.tx.clone()]
+    );
+
+    // Test: a block of an earlier height is committed.
+    let state_changes =
+        HashMap::from([(contract_address!("0x0"), AccountState { nonce: Nonce(felt!(0_u8)) })]);
+    assert!(mempool.commit_block(BlockNumber(0), state_changes).is_ok());
+
+    // Assert: the lease holds on to the transaction that wasn't included in the block.
+    assert_eq!(mempool.tx_status(included_input.tx.tx_hash()), Some(TransactionStatus::Committed));
+    assert_eq!(mempool.tx_status(leased_input.tx.tx_hash()), Some(TransactionStatus::Received));
+    assert_eq!(mempool.get_txs(1).unwrap(), vec![]);
+    assert_eq!(mempool.lease_txs(lease_id, 1).unwrap(), vec![]);
+
+    // Test and assert: the lease ends once the block of its height is committed.
+    assert!(mempool.commit_block(BlockNumber(1), HashMap::default()).is_ok());
+    assert_eq!(mempool.lease_txs(lease_id, 1), Err(MempoolError::LeaseNotFound { lease_id }));
+    assert_eq!(mempool.get_txs(1).unwrap(), vec![leased_input.tx]);
+}
+
+#[rstest]
+fn test_commit_block_ends_leases(mut mempool: Mempool) {
+    let included_input =
+        add_tx_input!(tx_hash: 1, sender_address: "0x0", tx_nonce: 0_u8, account_nonce: 0_u8);
+    let excluded_input =
+        add_tx_input!(tx_hash: 2, sender_address: "0x0", tx_nonce: 1_u8, account_nonce: 0_u8);
+    add_tx(&mut mempool, &included_input);
+    add_tx(&mut mempool, &excluded_input);
+    let lease_id = mempool.open_lease(BlockNumber(0));
+    assert_eq!(mempool.lease_txs(lease_id, 2).unwrap().len(), 2);
+
+    // Test.
+    let state_changes =
+        HashMap::from([(contract_address!("0x0"), AccountState { nonce: Nonce(felt!(0_u8)) })]);
+    assert!(mempool.commit_block(BlockNumber(0), state_changes).is_ok());
+
+    // Assert: the transaction that wasn't included in the block is available again.
+    assert_eq!(mempool.tx_status(included_input.tx.tx_hash()), Some(TransactionStatus::Committed));
+    let expected_mempool_content = MempoolContent::with_pool_and_queue(
+        [excluded_input.tx.clone()],
+        [TransactionReference::new(&excluded_input.tx)],
+    );
+    expected_mempool_content.assert_eq_pool_and_queue_content(&mempool);
+    assert_eq!(mempool.lease_txs(lease_id, 1), Err(MempoolError::LeaseNotFound { lease_id }));
+}
+
+// tx_status tests.
+
+#[rstest]
+fn test_tx_status_through_commit_block(mut mempool: Mempool) {
+    let included_input =
+        add_tx_input!(tx_hash: 1, sender_address: "0x0", tx_nonce: 0_u8, account_nonce: 0_u8);
+    let excluded_input =
+        add_tx_input!(tx_hash: 2, sender_address: "0x0", tx_nonce: 1_u8, account_nonce: 0_u8);
+    let included_tx_hash = included_input.tx.tx_hash();
+    let excluded_tx_hash = excluded_input.tx.tx_hash();
+    assert_eq!(mempool.tx_status(included_tx_hash), None);
+
+    // Test and assert: the transactions are received until a block is committed.
+    add_tx(&mut mempool, &included_input);
+    add_tx(&mut mempool, &excluded_input);
+    assert_eq!(mempool.tx_status(included_tx_hash), Some(TransactionStatus::Received));
+    mempool.get_txs(2).unwrap();
+    assert_eq!(mempool.tx_status(included_tx_hash), Some(TransactionStatus::Received));
+    assert_eq!(mempool.tx_status(excluded_tx_hash), Some(TransactionStatus::Received));
+
+    // Test and assert: only the transaction up to the committed nonce was included.
+    let state_changes =
+        HashMap::from([(contract_address!("0x0"), AccountState { nonce: Nonce(felt!(0_u8)) })]);
+    assert!(mempool.commit_block(BlockNumber(0), state_changes).is_ok());
+    assert_eq!(mempool.tx_status(included_tx_hash), Some(TransactionStatus::Committed));
+    assert_eq!(mempool.tx_status(excluded_tx_hash), None);
+}
+
+#[rstest]
+fn test_tx_status_through_lease(mut mempool: Mempool) {
+    let input = add_tx_input!(tx_hash: 1, sender_address: "0x0");
+    let tx_hash = input.tx.tx_hash();
+    add_tx(&mut mempool, &input);
+
+    // Test and assert: the transaction is received while it's leased, and after it's returned.
+    let lease_id = mempool.open_lease(BlockNumber(0));
+    assert_eq!(mempool.lease_txs(lease_id, 1).unwrap(), vec![input.tx]);
+    assert_eq!(mempool.tx_status(tx_hash), Some(TransactionStatus::Received));
+    mempool.release_lease(lease_id).unwrap();
+    assert_eq!(mempool.tx_status(tx_hash), Some(TransactionStatus::Received));
+
+    // Test and assert: the status of a flushed transaction is unknown.
+    mempool.flush();
+    assert_eq!(mempool.tx_status(tx_hash), None);
+}
+
+// flush tests.
+
+#[rstest]
+fn test_flush_keeps_handed_out_txs(mut mempool: Mempool) {
+    let handed_out_input = add_tx_input!(tip: 20, tx_hash: 1, sender_address: "0x0");
+    let waiting_input = add_tx_input!(tip: 10, tx_hash: 2, sender_address: "0x1");
+    for input in [&handed_out_input, &waiting_input] {
+        add_tx(&mut mempool, input);
+    }
+    assert_eq!(mempool.get_txs(1).unwrap(), vec![handed_out_input.tx.clone()]);
+
+    // Test and assert: only the waiting transaction is dropped.
+    assert_eq!(mempool.flush(), 1);
+    let expected_mempool_content = MempoolContent::with_pool_and_queue([], []);
+    expected_mempool_content.assert_eq_pool_and_queue_content(&mempool);
+    assert_eq!(mempool.tx_status(handed_out_input.tx.tx_hash()), Some(TransactionStatus::Received));
+    assert_eq!(mempool.tx_status(waiting_input.tx.tx_hash()), None);
+}
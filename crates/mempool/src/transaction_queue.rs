@@ -1,5 +1,5 @@
 use std::cmp::Ordering;
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use starknet_api::core::{ContractAddress, Nonce};
 use starknet_api::transaction::{Resource, ResourceBounds};
@@ -9,7 +9,7 @@ use crate::mempool::TransactionReference;
 // A queue holding the transaction that with nonces that match account nonces.
 // Note: the derived comparison functionality considers the order guaranteed by the data structures
 // used.
-#[derive(Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct TransactionQueue {
     gas_price_threshold: u128,
     // Transactions with gas price above gas price threshold (sorted by tip).
@@ -54,6 +54,74 @@ impl TransactionQueue {
         txs
     }
 
+    /// Pops up to `n_txs` transactions of the senders that were handed out the fewest transactions
+    /// so far, by priority among senders that were handed out as many. Senders that were handed out
+    /// `max_txs_per_sender` transactions are skipped.
+    pub fn pop_round_robin_chunk(
+        &mut self,
+        n_txs: usize,
+        sender_tx_counts: &HashMap<ContractAddress, usize>,
+        max_txs_per_sender: usize,
+    ) -> Vec<TransactionReference> {
+        // The highest priority transactions of the senders by the number of transactions they were
+        // handed out. Each bucket keeps at most `n_txs` transactions, and the scan stops once the
+        // senders that weren't handed out any transaction fill the chunk.
+        let mut buckets: BTreeMap<usize, Vec<&TransactionReference>> = BTreeMap::new();
+        for tx in self.iter_over_ready_txs() {
+            let n_sender_txs =
+                sender_tx_counts.get(&tx.sender_address).copied().unwrap_or_default();
+            if n_sender_txs >= max_txs_per_sender {
+                continue;
+            }
+            let bucket = buckets.entry(n_sender_txs).or_default();
+            if bucket.len() < n_txs {
+                bucket.push(tx);
+            }
+            if n_sender_txs == 0 && bucket.len() == n_txs {
+                break;
+            }
+        }
+        let txs: Vec<TransactionReference> =
+            buckets.into_values().flatten().take(n_txs).cloned().collect();
+        for tx in &txs {
+            self.remove(tx.sender_address);
+        }
+
+        txs
+    }
+
+    /// Returns a queue of the ready transactions that selecting up to `n_txs` transactions may pop,
+    /// by either selection: the `n_txs` highest priority transactions of the senders that were
+    /// handed out each number of transactions. Used to preview a selection without copying the
+    /// whole queue.
+    pub fn selection_candidates(
+        &self,
+        n_txs: usize,
+        sender_tx_counts: &HashMap<ContractAddress, usize>,
+    ) -> TransactionQueue {
+        let mut candidates = TransactionQueue {
+            gas_price_threshold: self.gas_price_threshold,
+            ..Default::default()
+        };
+        let mut n_candidates_by_sender_tx_count: HashMap<usize, usize> = HashMap::new();
+        for tx in self.iter_over_ready_txs() {
+            let n_sender_txs =
+                sender_tx_counts.get(&tx.sender_address).copied().unwrap_or_default();
+            let n_candidates = n_candidates_by_sender_tx_count.entry(n_sender_txs).or_default();
+            if *n_candidates < n_txs {
+                *n_candidates += 1;
+                candidates.insert(tx.clone());
+            }
+            // Neither selection pops the lower priority transactions of the senders that weren't
+            // handed out any transaction.
+            if n_sender_txs == 0 && *n_candidates == n_txs {
+                break;
+            }
+        }
+
+        candidates
+    }
+
     /// Returns an iterator of the current eligible transactions for sequencing, ordered by their
     /// priority.
     pub fn iter_over_ready_txs(&self) -> impl Iterator<Item = &TransactionReference> {
@@ -79,17 +147,23 @@ impl TransactionQueue {
         self.priority_queue.is_empty()
     }
 
-    pub fn _update_gas_price_threshold(&mut self, threshold: u128) {
+    pub fn gas_price_threshold(&self) -> u128 {
+        self.gas_price_threshold
+    }
+
+    /// Moves the transactions between the priority and pending queues by their L2 gas price
+    /// relative to the new threshold.
+    pub fn update_gas_price_threshold(&mut self, threshold: u128) {
         match threshold.cmp(&self.gas_price_threshold) {
-            Ordering::Less => self._promote_txs_to_priority(threshold),
-            Ordering::Greater => self._demote_txs_to_pending(threshold),
+            Ordering::Less => self.promote_txs_to_priority(threshold),
+            Ordering::Greater => self.demote_txs_to_pending(threshold),
             Ordering::Equal => {}
         }
 
         self.gas_price_threshold = threshold;
     }
 
-    fn _promote_txs_to_priority(&mut self, threshold: u128) {
+    fn promote_txs_to_priority(&mut self, threshold: u128) {
         let tmp_split_tx = PendingTransaction(TransactionReference {
             resource_bounds: vec![
                 (Resource::L1Gas, ResourceBounds::default()),
@@ -110,7 +184,7 @@ impl TransactionQueue {
         self.priority_queue.extend(txs_over_threshold.map(|tx| tx.0.into()));
     }
 
-    fn _demote_txs_to_pending(&mut self, threshold: u128) {
+    fn demote_txs_to_pending(&mut self, threshold: u128) {
         let mut to_remove = Vec::new();
 
         // Remove all transactions from the priority queue that are below the threshold.
@@ -0,0 +1,288 @@
+//! The admin server of the node, for operating it while it runs. It only listens on a loopback
+//! address, so it's reachable from the node's host alone.
+//!
+//! The log levels of targets are managed on [`LOG_LEVEL_PATH`]:
+//! - `GET` returns the levels set through the server, a `target=level` line per target.
+//! - `PUT` with the `target` and `level` query parameters sets the level of a target, e.g. `PUT
+//!   /log_level?target=consensus&level=trace`. The target is either a target or the name of a
+//!   component in [`COMPONENT_LOG_TARGETS`].
+//! - `DELETE` with the `target` query parameter resets the level of a target to the level
+//!   `RUST_LOG` sets.
+//!
+//! The [`AdminCommand`]s are given on [`COMMAND_PATH`], by `POST` with the `name` query parameter,
+//! e.g. `POST /command?name=flush_mempool`. Each command is handled by the component it's
+//! addressed to on every chain that runs in the node, through the [`AdminCommandHandler`] of the
+//! node, and the outcome on each chain is returned in a `chain_id: outcome` line.
+//!
+//! [`COMPONENT_LOG_TARGETS`]: crate::trace_util::COMPONENT_LOG_TARGETS
+
+#[cfg(test)]
+#[path = "admin_test.rs"]
+mod admin_test;
+
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::fmt::{Display, Formatter};
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use tracing::metadata::LevelFilter;
+use validator::{Validate, ValidationError};
+
+use crate::trace_util::{LogLevelError, LogLevelHandle};
+
+pub const LOG_LEVEL_PATH: &str = "/log_level";
+pub const COMMAND_PATH: &str = "/command";
+
+/// The configuration of the admin server.
+#[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
+pub struct AdminConfig {
+    pub run_admin_server: bool,
+    #[validate(custom = "validate_loopback_ip")]
+    pub ip: IpAddr,
+    pub port: u16,
+}
+
+impl SerializeConfig for AdminConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "run_admin_server",
+                &self.run_admin_server,
+                "If true, run the admin server, which changes the log levels of the node and \
+                 applies operational commands to its components.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "ip",
+                &self.ip.to_string(),
+                "The admin server ip. Must be a loopback address.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param("port", &self.port, "The admin server port.", ParamPrivacyInput::Public),
+        ])
+    }
+}
+
+impl Default for AdminConfig {
+    fn default() -> Self {
+        Self { run_admin_server: false, ip: "127.0.0.1".parse().unwrap(), port: 8083 }
+    }
+}
+
+fn validate_loopback_ip(ip: &IpAddr) -> Result<(), ValidationError> {
+    if !ip.is_loopback() {
+        return Err(ValidationError::new("The admin server must listen on a loopback address."));
+    }
+    Ok(())
+}
+
+/// An operational command of the admin server.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AdminCommand {
+    /// Stops proposing blocks; the proposals of others are still validated. Not supported until
+    /// the batcher generates the proposals of the node.
+    PauseProposing,
+    ResumeProposing,
+    /// Drops the transactions that wait in the mempool.
+    FlushMempool,
+    /// Moves consensus to the next round of the current height. This and the other consensus
+    /// commands are not supported until consensus runs in the consensus manager.
+    SkipRound,
+    DumpConsensusState,
+    /// Replaces the signing keys of consensus by the ones in the key store.
+    RotateKeys,
+}
+
+impl AdminCommand {
+    pub const ALL: [Self; 6] = [
+        Self::PauseProposing,
+        Self::ResumeProposing,
+        Self::FlushMempool,
+        Self::SkipRound,
+        Self::DumpConsensusState,
+        Self::RotateKeys,
+    ];
+
+    /// The name the command is given by on [`COMMAND_PATH`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PauseProposing => "pause_proposing",
+            Self::ResumeProposing => "resume_proposing",
+            Self::FlushMempool => "flush_mempool",
+            Self::SkipRound => "skip_round",
+            Self::DumpConsensusState => "dump_consensus_state",
+            Self::RotateKeys => "rotate_keys",
+        }
+    }
+}
+
+impl Display for AdminCommand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for AdminCommand {
+    type Err = AdminCommandError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|command| command.name() == name)
+            .ok_or_else(|| AdminCommandError::UnknownCommand(name.to_owned()))
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum AdminCommandError {
+    #[error("Unknown command: {0:?}.")]
+    UnknownCommand(String),
+    #[error("The {component} that handles {command} doesn't run in the node.")]
+    ComponentNotRunning { command: AdminCommand, component: &'static str },
+    #[error("The {component} failed to handle {command}: {error}")]
+    ComponentError { command: AdminCommand, component: &'static str, error: String },
+    #[error("{command} is not supported yet: {reason}")]
+    Unsupported { command: AdminCommand, reason: &'static str },
+}
+
+/// The outcome of a command on each chain that runs in the node, by chain id. The outcome is
+/// a report for the operator, or the reason the command failed on the chain.
+pub type AdminCommandReports = BTreeMap<String, Result<String, AdminCommandError>>;
+
+/// Applies the admin commands to the components of the node.
+#[async_trait]
+pub trait AdminCommandHandler: Send + Sync {
+    /// Applies the command to every chain that runs in the node, and returns its outcome on each.
+    async fn handle(&self, command: AdminCommand) -> AdminCommandReports;
+}
+
+/// Serves the admin requests, applying them with the given handles. Runs indefinitely.
+pub async fn run_admin_server(
+    config: &AdminConfig,
+    log_level_handle: LogLevelHandle,
+    command_handler: Arc<dyn AdminCommandHandler>,
+) -> Result<(), hyper::Error> {
+    let make_svc = make_service_fn(move |_conn| {
+        let log_level_handle = log_level_handle.clone();
+        let command_handler = command_handler.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let log_level_handle = log_level_handle.clone();
+                let command_handler = command_handler.clone();
+                async move {
+                    let response = match request.uri().path() {
+                        COMMAND_PATH => command_response(&request, command_handler.as_ref()).await,
+                        _ => admin_response(&request, &log_level_handle),
+                    };
+                    Ok::<_, Infallible>(response)
+                }
+            }))
+        }
+    });
+
+    Server::bind(&SocketAddr::new(config.ip, config.port)).serve(make_svc).await
+}
+
+async fn command_response(
+    request: &Request<Body>,
+    command_handler: &dyn AdminCommandHandler,
+) -> Response<Body> {
+    if request.method() != Method::POST {
+        return response(StatusCode::NOT_FOUND, String::new());
+    }
+    let Some(name) = query_param(request, "name") else {
+        return response(StatusCode::BAD_REQUEST, "Missing name.".to_owned());
+    };
+    let command = match AdminCommand::from_str(name) {
+        Ok(command) => command,
+        Err(err) => return response(StatusCode::BAD_REQUEST, err.to_string()),
+    };
+    info!("Handling the admin command {command}.");
+    let reports = command_handler.handle(command).await;
+    if reports.is_empty() {
+        return response(StatusCode::SERVICE_UNAVAILABLE, "No chain runs in the node.".to_owned());
+    }
+    // The status is the one of the first chain the command failed on, if any.
+    let status = reports
+        .values()
+        .find_map(|outcome| outcome.as_ref().err())
+        .map_or(StatusCode::OK, command_error_status);
+    let lines: Vec<String> = reports
+        .iter()
+        .map(|(chain_id, outcome)| match outcome {
+            Ok(report) => format!("{chain_id}: {report}\n"),
+            Err(err) => format!("{chain_id}: {err}\n"),
+        })
+        .collect();
+    response(status, lines.concat())
+}
+
+fn command_error_status(err: &AdminCommandError) -> StatusCode {
+    match err {
+        AdminCommandError::ComponentError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+        AdminCommandError::Unsupported { .. } => StatusCode::NOT_IMPLEMENTED,
+        AdminCommandError::UnknownCommand(_) | AdminCommandError::ComponentNotRunning { .. } => {
+            StatusCode::BAD_REQUEST
+        }
+    }
+}
+
+fn admin_response(request: &Request<Body>, log_level_handle: &LogLevelHandle) -> Response<Body> {
+    if request.uri().path() != LOG_LEVEL_PATH {
+        return response(StatusCode::NOT_FOUND, String::new());
+    }
+    let target = query_param(request, "target");
+    let result = match (request.method(), target) {
+        (&Method::GET, _) => {
+            let target_levels = log_level_handle.target_levels();
+            let lines: Vec<String> =
+                target_levels.iter().map(|(target, level)| format!("{target}={level}\n")).collect();
+            return response(StatusCode::OK, lines.concat());
+        }
+        (&Method::PUT, Some(target)) => {
+            let Some(level) = query_param(request, "level") else {
+                return response(StatusCode::BAD_REQUEST, "Missing level.".to_owned());
+            };
+            let Ok(level) = LevelFilter::from_str(level) else {
+                return response(StatusCode::BAD_REQUEST, format!("Invalid level: {level:?}."));
+            };
+            info!("Setting the log level of {target} to {level}.");
+            log_level_handle.set_level(target, level)
+        }
+        (&Method::DELETE, Some(target)) => {
+            info!("Resetting the log level of {target}.");
+            log_level_handle.reset_level(target)
+        }
+        (&Method::PUT | &Method::DELETE, None) => {
+            return response(StatusCode::BAD_REQUEST, "Missing target.".to_owned());
+        }
+        _ => return response(StatusCode::NOT_FOUND, String::new()),
+    };
+    match result {
+        Ok(()) => response(StatusCode::OK, String::new()),
+        Err(err @ LogLevelError::InvalidTarget(_)) => {
+            response(StatusCode::BAD_REQUEST, err.to_string())
+        }
+        Err(err) => response(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+fn query_param<'a>(request: &'a Request<Body>, name: &str) -> Option<&'a str> {
+    request.uri().query()?.split('&').find_map(|param| param.strip_prefix(name)?.strip_prefix('='))
+}
+
+fn response(status: StatusCode, body: String) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .body(Body::from(body))
+        .expect("Response building should succeed")
+}
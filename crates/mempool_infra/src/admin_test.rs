@@ -0,0 +1,156 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use hyper::{Body, Method, Request, Response, StatusCode};
+use tracing::metadata::LevelFilter;
+use validator::Validate;
+
+use super::{
+    admin_response,
+    command_response,
+    AdminCommand,
+    AdminCommandError,
+    AdminCommandHandler,
+    AdminCommandReports,
+    AdminConfig,
+    COMMAND_PATH,
+    LOG_LEVEL_PATH,
+};
+use crate::trace_util::LogLevelHandle;
+
+fn request(method: Method, uri: &str) -> Request<Body> {
+    Request::builder().method(method).uri(uri).body(Body::empty()).unwrap()
+}
+
+async fn body(response: Response<Body>) -> String {
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn set_and_reset_log_levels() {
+    // The filter layer is kept alive for the handle to reload it.
+    let (_filter_layer, log_level_handle) = LogLevelHandle::new("info".to_owned());
+
+    let uri = format!("{LOG_LEVEL_PATH}?target=consensus&level=trace");
+    let response = admin_response(&request(Method::PUT, &uri), &log_level_handle);
+    assert_eq!(response.status(), StatusCode::OK);
+    let uri = format!("{LOG_LEVEL_PATH}?target=starknet_mempool::mempool&level=debug");
+    let response = admin_response(&request(Method::PUT, &uri), &log_level_handle);
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = admin_response(&request(Method::GET, LOG_LEVEL_PATH), &log_level_handle);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        body(response).await,
+        format!(
+            "papyrus_consensus={}\nstarknet_mempool::mempool={}\n",
+            LevelFilter::TRACE,
+            LevelFilter::DEBUG
+        )
+    );
+
+    let uri = format!("{LOG_LEVEL_PATH}?target=consensus");
+    let response = admin_response(&request(Method::DELETE, &uri), &log_level_handle);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        log_level_handle.target_levels().into_iter().collect::<Vec<_>>(),
+        vec![("starknet_mempool::mempool".to_owned(), LevelFilter::DEBUG)]
+    );
+}
+
+#[test]
+fn invalid_log_level_requests() {
+    let (_filter_layer, log_level_handle) = LogLevelHandle::new(String::new());
+    for uri in [
+        format!("{LOG_LEVEL_PATH}?target=consensus"),
+        format!("{LOG_LEVEL_PATH}?target=consensus&level=loud"),
+        format!("{LOG_LEVEL_PATH}?target=a=trace,b&level=trace"),
+        format!("{LOG_LEVEL_PATH}?level=trace"),
+    ] {
+        let response = admin_response(&request(Method::PUT, &uri), &log_level_handle);
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{uri}");
+    }
+    assert!(log_level_handle.target_levels().is_empty());
+
+    let response = admin_response(&request(Method::GET, "/other"), &log_level_handle);
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+// Runs two chains: a chain that flushes its mempool, and a chain without a mempool. The consensus
+// commands are unsupported.
+struct TwoChainsHandler;
+
+#[async_trait]
+impl AdminCommandHandler for TwoChainsHandler {
+    async fn handle(&self, command: AdminCommand) -> AdminCommandReports {
+        let outcome = |with_mempool| match command {
+            AdminCommand::FlushMempool if with_mempool => Ok("Flushed 3 transactions.".to_owned()),
+            AdminCommand::FlushMempool => {
+                Err(AdminCommandError::ComponentNotRunning { command, component: "mempool" })
+            }
+            _ => Err(AdminCommandError::Unsupported { command, reason: "Not implemented." }),
+        };
+        AdminCommandReports::from([
+            ("SN_MAIN".to_owned(), outcome(true)),
+            ("SN_SEPOLIA".to_owned(), outcome(false)),
+        ])
+    }
+}
+
+// Runs no chain.
+struct NoChainsHandler;
+
+#[async_trait]
+impl AdminCommandHandler for NoChainsHandler {
+    async fn handle(&self, _command: AdminCommand) -> AdminCommandReports {
+        AdminCommandReports::new()
+    }
+}
+
+#[tokio::test]
+async fn handle_commands() {
+    // The command fails on one chain only, and its outcome is reported per chain.
+    let uri = format!("{COMMAND_PATH}?name=flush_mempool");
+    let response = command_response(&request(Method::POST, &uri), &TwoChainsHandler).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        body(response).await,
+        "SN_MAIN: Flushed 3 transactions.\nSN_SEPOLIA: The mempool that handles flush_mempool \
+         doesn't run in the node.\n"
+    );
+
+    let uri = format!("{COMMAND_PATH}?name=pause_proposing");
+    let response = command_response(&request(Method::POST, &uri), &TwoChainsHandler).await;
+    assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+
+    let uri = format!("{COMMAND_PATH}?name=flush_mempool");
+    let response = command_response(&request(Method::POST, &uri), &NoChainsHandler).await;
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    for uri in [format!("{COMMAND_PATH}?name=reboot"), COMMAND_PATH.to_owned()] {
+        let response = command_response(&request(Method::POST, &uri), &TwoChainsHandler).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{uri}");
+    }
+    let uri = format!("{COMMAND_PATH}?name=flush_mempool");
+    let response = command_response(&request(Method::GET, &uri), &TwoChainsHandler).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn command_names() {
+    for command in AdminCommand::ALL {
+        assert_eq!(AdminCommand::from_str(command.name()), Ok(command));
+    }
+    assert_eq!(
+        AdminCommand::from_str("reboot"),
+        Err(AdminCommandError::UnknownCommand("reboot".to_owned()))
+    );
+}
+
+#[test]
+fn admin_server_listens_on_loopback_only() {
+    assert!(AdminConfig::default().validate().is_ok());
+    let config = AdminConfig { ip: "0.0.0.0".parse().unwrap(), ..Default::default() };
+    assert!(config.validate().is_err());
+}
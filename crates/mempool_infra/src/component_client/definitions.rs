@@ -3,7 +3,7 @@ use std::sync::Arc;
 use hyper::StatusCode;
 use thiserror::Error;
 
-use crate::component_definitions::ServerError;
+use crate::component_definitions::{GrpcStatus, ServerError};
 
 #[derive(Clone, Debug, Error)]
 pub enum ClientError {
@@ -17,6 +17,10 @@ pub enum ClientError {
     ResponseError(StatusCode, ServerError),
     #[error("Got an unexpected response type: {0}")]
     UnexpectedResponse(String),
+    #[error(transparent)]
+    GrpcError(GrpcStatus),
+    #[error("Could not decode the gRPC response: {0}")]
+    ResponseDecodingFailure(String),
 }
 
 pub type ClientResult<T> = Result<T, ClientError>;
@@ -0,0 +1,138 @@
+use std::marker::PhantomData;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use hyper::body::HttpBody;
+use hyper::header::{CONTENT_TYPE, TE};
+use hyper::{Body, Client, Request as HyperRequest, Response as HyperResponse, StatusCode};
+
+use super::definitions::{ClientError, ClientResult};
+use crate::component_definitions::{
+    frame_grpc_message,
+    unframe_grpc_message,
+    GrpcRequest,
+    GrpcResponse,
+    GrpcStatus,
+    APPLICATION_GRPC,
+};
+
+/// A client of a component that is served over gRPC by a
+/// [`crate::component_server::GrpcComponentServer`].
+pub struct GrpcComponentClient<Request, Response>
+where
+    Request: GrpcRequest,
+    Response: GrpcResponse,
+{
+    // The URI of the server, without a path.
+    server_uri: String,
+    client: Client<hyper::client::HttpConnector>,
+    max_retries: usize,
+    _req: PhantomData<Request>,
+    _res: PhantomData<Response>,
+}
+
+impl<Request, Response> GrpcComponentClient<Request, Response>
+where
+    Request: GrpcRequest,
+    Response: GrpcResponse,
+{
+    pub fn new(ip_address: IpAddr, port: u16, max_retries: usize) -> Self {
+        let server_uri = match ip_address {
+            IpAddr::V4(ip_address) => format!("http://{}:{}", ip_address, port),
+            IpAddr::V6(ip_address) => format!("http://[{}]:{}", ip_address, port),
+        };
+        let client =
+            Client::builder().http2_only(true).pool_max_idle_per_host(usize::MAX).build_http();
+        Self { server_uri, client, max_retries, _req: PhantomData, _res: PhantomData }
+    }
+
+    pub async fn send(&self, component_request: Request) -> ClientResult<Response> {
+        // Send the request up to 'max_retries' more times while the server can't be reached. A
+        // request that reached the server isn't sent again, since it may have been handled.
+        for _ in 0..self.max_retries {
+            let http_request = self.construct_http_request(&component_request);
+            match self.try_send(http_request, component_request.method_path()).await {
+                Err(ClientError::CommunicationFailure(_)) => continue,
+                res => return res,
+            }
+        }
+        let http_request = self.construct_http_request(&component_request);
+        self.try_send(http_request, component_request.method_path()).await
+    }
+
+    fn construct_http_request(&self, component_request: &Request) -> HyperRequest<Body> {
+        HyperRequest::post(format!("{}{}", self.server_uri, component_request.method_path()))
+            .header(CONTENT_TYPE, APPLICATION_GRPC)
+            .header(TE, "trailers")
+            .body(Body::from(frame_grpc_message(component_request.encode_message())))
+            .expect("Request building should succeed")
+    }
+
+    async fn try_send(
+        &self,
+        http_request: HyperRequest<Body>,
+        method_path: &str,
+    ) -> ClientResult<Response> {
+        let http_response = self
+            .client
+            .request(http_request)
+            .await
+            .map_err(|e| ClientError::CommunicationFailure(Arc::new(e)))?;
+        let message = get_response_message(http_response).await?;
+        Response::decode_message(method_path, &message)
+            .map_err(ClientError::ResponseDecodingFailure)
+    }
+}
+
+// Returns the message of a successful call, or its status if it failed.
+async fn get_response_message(http_response: HyperResponse<Body>) -> ClientResult<Vec<u8>> {
+    if http_response.status() != StatusCode::OK {
+        return Err(ClientError::GrpcError(GrpcStatus::new(
+            GrpcStatus::UNKNOWN,
+            format!("Got HTTP status {}.", http_response.status()),
+        )));
+    }
+    // A call that failed before its response was sent has its status in the headers.
+    if let Some(status) = GrpcStatus::from_headers(http_response.headers()) {
+        let status = status.map_err(ClientError::ResponseDecodingFailure)?;
+        return Err(ClientError::GrpcError(status));
+    }
+
+    let mut body = http_response.into_body();
+    let mut body_bytes = Vec::new();
+    while let Some(data) = body.data().await {
+        body_bytes.extend(data.map_err(|e| ClientError::ResponseParsingFailure(Arc::new(e)))?);
+    }
+    let trailers = body
+        .trailers()
+        .await
+        .map_err(|e| ClientError::ResponseParsingFailure(Arc::new(e)))?
+        .unwrap_or_default();
+    let status = GrpcStatus::from_headers(&trailers)
+        .ok_or_else(|| ClientError::ResponseDecodingFailure("Missing gRPC status.".to_owned()))?
+        .map_err(ClientError::ResponseDecodingFailure)?;
+    if status.code != GrpcStatus::OK {
+        return Err(ClientError::GrpcError(status));
+    }
+    unframe_grpc_message(&body_bytes)
+        .map(<[u8]>::to_vec)
+        .map_err(ClientError::ResponseDecodingFailure)
+}
+
+// Can't derive because derive forces the generics to also be `Clone`, which we prefer not to do
+// since it'll require the generic Request and Response types to be cloneable.
+impl<Request, Response> Clone for GrpcComponentClient<Request, Response>
+where
+    Request: GrpcRequest,
+    Response: GrpcResponse,
+{
+    fn clone(&self) -> Self {
+        Self {
+            server_uri: self.server_uri.clone(),
+            client: self.client.clone(),
+            max_retries: self.max_retries,
+            _req: PhantomData,
+            _res: PhantomData,
+        }
+    }
+}
@@ -1,7 +1,9 @@
 mod definitions;
+mod grpc_component_client;
 mod local_component_client;
 mod remote_component_client;
 
 pub use definitions::*;
+pub use grpc_component_client::*;
 pub use local_component_client::*;
 pub use remote_component_client::*;
@@ -2,6 +2,7 @@ use std::collections::BTreeMap;
 use std::net::IpAddr;
 
 use async_trait::async_trait;
+use hyper::header::{HeaderMap, HeaderValue};
 use papyrus_config::dumping::{ser_param, SerializeConfig};
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use serde::{Deserialize, Serialize};
@@ -46,6 +47,11 @@ where
 }
 
 pub const APPLICATION_OCTET_STREAM: &str = "application/octet-stream";
+pub const APPLICATION_GRPC: &str = "application/grpc";
+pub const GRPC_STATUS: &str = "grpc-status";
+pub const GRPC_MESSAGE: &str = "grpc-message";
+// The length of the prefix of a gRPC message: a compression flag and the length of the message.
+const GRPC_MESSAGE_PREFIX_LENGTH: usize = 5;
 
 #[derive(Debug, Error, Deserialize, Serialize, Clone)]
 pub enum ServerError {
@@ -53,6 +59,134 @@ pub enum ServerError {
     RequestDeserializationFailure(String),
 }
 
+/// A request of a component that is served over gRPC, as a call to one of the methods of the
+/// component's service.
+pub trait GrpcRequest: Sized {
+    /// The path of the called method, e.g. `/Mempool/AddTransaction`.
+    fn method_path(&self) -> &'static str;
+    fn encode_message(&self) -> Vec<u8>;
+    fn decode_message(method_path: &str, message: &[u8]) -> Result<Self, GrpcStatus>;
+}
+
+/// The response to a [`GrpcRequest`], whose message type is determined by the called method.
+pub trait GrpcResponse: Sized {
+    fn encode_message(&self) -> Vec<u8>;
+    fn decode_message(method_path: &str, message: &[u8]) -> Result<Self, String>;
+}
+
+/// The status of a gRPC call; see https://grpc.github.io/grpc/core/md_doc_statuscodes.html.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[error("gRPC status {code}: {message}")]
+pub struct GrpcStatus {
+    pub code: u32,
+    pub message: String,
+}
+
+impl GrpcStatus {
+    pub const OK: u32 = 0;
+    pub const UNKNOWN: u32 = 2;
+    pub const INVALID_ARGUMENT: u32 = 3;
+    pub const UNIMPLEMENTED: u32 = 12;
+
+    pub fn new(code: u32, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+
+    /// Returns the status in the headers of a response or in its trailers, if it's there.
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Result<Self, String>> {
+        let code = headers.get(GRPC_STATUS)?;
+        let code = code
+            .to_str()
+            .ok()
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| format!("Invalid gRPC status {code:?}."));
+        let message = headers
+            .get(GRPC_MESSAGE)
+            .map(|message| percent_decode(message.as_bytes()))
+            .unwrap_or_default();
+        Some(code.map(|code| Self { code, message }))
+    }
+
+    pub(crate) fn to_headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(GRPC_STATUS, HeaderValue::from(self.code));
+        if !self.message.is_empty() {
+            headers.insert(
+                GRPC_MESSAGE,
+                HeaderValue::from_str(&percent_encode(&self.message))
+                    .expect("A percent-encoded message should be a valid header value."),
+            );
+        }
+        headers
+    }
+}
+
+/// Prefixes a message with the uncompressed flag and its length, as it's sent in a gRPC call.
+pub(crate) fn frame_grpc_message(message: Vec<u8>) -> Vec<u8> {
+    let length = u32::try_from(message.len()).expect("A gRPC message should fit in 4GB.");
+    let mut framed_message = Vec::with_capacity(GRPC_MESSAGE_PREFIX_LENGTH + message.len());
+    framed_message.push(0);
+    framed_message.extend_from_slice(&length.to_be_bytes());
+    framed_message.extend(message);
+    framed_message
+}
+
+/// Returns the single message of the body of a gRPC request or response.
+pub(crate) fn unframe_grpc_message(body: &[u8]) -> Result<&[u8], String> {
+    if body.len() < GRPC_MESSAGE_PREFIX_LENGTH {
+        return Err(format!(
+            "A gRPC message should have a prefix of {GRPC_MESSAGE_PREFIX_LENGTH} bytes."
+        ));
+    }
+    let (prefix, message) = body.split_at(GRPC_MESSAGE_PREFIX_LENGTH);
+    if prefix[0] != 0 {
+        return Err("Compressed gRPC messages are not supported.".to_owned());
+    }
+    let length = u32::from_be_bytes(prefix[1..].try_into().expect("The length should be 4 bytes."));
+    if usize::try_from(length).ok() != Some(message.len()) {
+        return Err(format!(
+            "Expected a single gRPC message of {length} bytes, got {} bytes.",
+            message.len()
+        ));
+    }
+    Ok(message)
+}
+
+// Percent-encodes the bytes of a gRPC message that aren't printable ASCII, as the gRPC protocol
+// requires.
+fn percent_encode(message: &str) -> String {
+    message
+        .bytes()
+        .map(|byte| match byte {
+            b' '..=b'~' if byte != b'%' => char::from(byte).to_string(),
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+fn percent_decode(message: &[u8]) -> String {
+    let mut decoded = Vec::with_capacity(message.len());
+    let mut i = 0;
+    while i < message.len() {
+        let escaped_byte = message
+            .get(i + 1..i + 3)
+            .filter(|_| message[i] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped_byte {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(message[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
 // The communication configuration of the local component.
 #[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
 pub struct LocalComponentCommunicationConfig {
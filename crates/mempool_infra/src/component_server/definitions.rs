@@ -8,6 +8,12 @@ use crate::component_runner::ComponentStarter;
 #[async_trait]
 pub trait ComponentServerStarter: Send + Sync {
     async fn start(&mut self);
+
+    /// Whether the server can be started again after it stopped. A server that lost what it serves
+    /// from, e.g. whose request channel was closed, would stop right away if it were restarted.
+    fn is_restartable(&self) -> bool {
+        true
+    }
 }
 
 pub async fn start_component<Component>(component: &mut Component) -> bool
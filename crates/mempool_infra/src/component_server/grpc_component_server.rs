@@ -0,0 +1,130 @@
+use std::marker::PhantomData;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use hyper::body::to_bytes;
+use hyper::header::CONTENT_TYPE;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request as HyperRequest, Response as HyperResponse, Server, StatusCode};
+use tokio::sync::Mutex;
+
+use super::definitions::ComponentServerStarter;
+use crate::component_definitions::{
+    frame_grpc_message,
+    unframe_grpc_message,
+    ComponentRequestHandler,
+    GrpcRequest,
+    GrpcResponse,
+    GrpcStatus,
+    APPLICATION_GRPC,
+};
+
+/// A server that serves the requests of a component over gRPC, for clients on other nodes. Each
+/// request is a call to a method of the component's service, and its method and message are given
+/// by the request's [`GrpcRequest`] implementation. See
+/// [`crate::component_client::GrpcComponentClient`] for its client.
+pub struct GrpcComponentServer<Component, Request, Response>
+where
+    Component: ComponentRequestHandler<Request, Response> + Send + 'static,
+    Request: GrpcRequest + Send + 'static,
+    Response: GrpcResponse + 'static,
+{
+    socket: SocketAddr,
+    component: Arc<Mutex<Component>>,
+    _req: PhantomData<Request>,
+    _res: PhantomData<Response>,
+}
+
+impl<Component, Request, Response> GrpcComponentServer<Component, Request, Response>
+where
+    Component: ComponentRequestHandler<Request, Response> + Send + 'static,
+    Request: GrpcRequest + Send + 'static,
+    Response: GrpcResponse + 'static,
+{
+    pub fn new(component: Component, ip_address: IpAddr, port: u16) -> Self {
+        Self {
+            component: Arc::new(Mutex::new(component)),
+            socket: SocketAddr::new(ip_address, port),
+            _req: PhantomData,
+            _res: PhantomData,
+        }
+    }
+
+    async fn handler(
+        http_request: HyperRequest<Body>,
+        component: Arc<Mutex<Component>>,
+    ) -> Result<HyperResponse<Body>, hyper::Error> {
+        let is_grpc_request = http_request
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .is_some_and(|content_type| content_type.starts_with(APPLICATION_GRPC));
+        if !is_grpc_request {
+            return Ok(HyperResponse::builder()
+                .status(StatusCode::UNSUPPORTED_MEDIA_TYPE)
+                .body(Body::empty())
+                .expect("Response building should succeed"));
+        }
+
+        let method_path = http_request.uri().path().to_owned();
+        let body_bytes = to_bytes(http_request.into_body()).await?;
+        let component_request = unframe_grpc_message(&body_bytes)
+            .map_err(|error| GrpcStatus::new(GrpcStatus::INVALID_ARGUMENT, error))
+            .and_then(|message| Request::decode_message(&method_path, message));
+        let component_request = match component_request {
+            Ok(component_request) => component_request,
+            // A call that fails before its response is sent has its status in the headers.
+            Err(status) => {
+                let mut http_response = HyperResponse::builder()
+                    .status(StatusCode::OK)
+                    .header(CONTENT_TYPE, APPLICATION_GRPC)
+                    .body(Body::empty())
+                    .expect("Response building should succeed");
+                http_response.headers_mut().extend(status.to_headers());
+                return Ok(http_response);
+            }
+        };
+
+        // Acquire the lock for component computation, release afterwards.
+        let component_response = { component.lock().await.handle_request(component_request).await };
+        let message = frame_grpc_message(component_response.encode_message());
+        let (mut body_sender, body) = Body::channel();
+        tokio::spawn(async move {
+            // The status of a call is sent in the trailers, after its response. If the client is
+            // gone, there's no one to send them to.
+            if body_sender.send_data(message.into()).await.is_ok() {
+                let ok_status = GrpcStatus::new(GrpcStatus::OK, "");
+                let _ = body_sender.send_trailers(ok_status.to_headers()).await;
+            }
+        });
+        Ok(HyperResponse::builder()
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, APPLICATION_GRPC)
+            .body(body)
+            .expect("Response building should succeed"))
+    }
+}
+
+#[async_trait]
+impl<Component, Request, Response> ComponentServerStarter
+    for GrpcComponentServer<Component, Request, Response>
+where
+    Component: ComponentRequestHandler<Request, Response> + Send + 'static,
+    Request: GrpcRequest + Send + Sync + 'static,
+    Response: GrpcResponse + Send + Sync + 'static,
+{
+    async fn start(&mut self) {
+        let make_svc = make_service_fn(|_conn| {
+            let component = Arc::clone(&self.component);
+            async {
+                Ok::<_, hyper::Error>(service_fn(move |req| {
+                    Self::handler(req, Arc::clone(&component))
+                }))
+            }
+        });
+
+        // gRPC runs over HTTP/2 only.
+        Server::bind(&self.socket.clone()).http2_only(true).serve(make_svc).await.unwrap();
+    }
+}
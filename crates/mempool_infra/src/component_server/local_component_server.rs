@@ -31,7 +31,7 @@ use crate::component_runner::ComponentStarter;
 /// # Example
 /// ```rust
 /// // Example usage of the LocalComponentServer
-/// use std::sync::mpsc::{channel, Receiver};
+/// use std::sync::mpsc::{Receiver, channel};
 ///
 /// use async_trait::async_trait;
 /// use starknet_mempool_infra::component_runner::{ComponentStartError, ComponentStarter};
@@ -115,6 +115,8 @@ where
 {
     component: Component,
     rx: Receiver<ComponentRequestAndResponseSender<Request, Response>>,
+    // Set once all the senders of `rx` are dropped, after which there are no requests to serve.
+    rx_closed: bool,
 }
 
 impl<Component, Request, Response> LocalComponentServer<Component, Request, Response>
@@ -127,7 +129,7 @@ where
         component: Component,
         rx: Receiver<ComponentRequestAndResponseSender<Request, Response>>,
     ) -> Self {
-        Self { component, rx }
+        Self { component, rx, rx_closed: false }
     }
 }
 
@@ -142,8 +144,13 @@ where
     async fn start(&mut self) {
         if start_component(&mut self.component).await {
             request_response_loop(&mut self.rx, &mut self.component).await;
+            self.rx_closed = true;
         }
     }
+
+    fn is_restartable(&self) -> bool {
+        !self.rx_closed
+    }
 }
 
 pub struct LocalActiveComponentServer<Component, Request, Response>
@@ -154,6 +161,8 @@ where
 {
     component: Component,
     rx: Receiver<ComponentRequestAndResponseSender<Request, Response>>,
+    // Set once all the senders of `rx` are dropped, after which there are no requests to serve.
+    rx_closed: bool,
 }
 
 impl<Component, Request, Response> LocalActiveComponentServer<Component, Request, Response>
@@ -166,7 +175,7 @@ where
         component: Component,
         rx: Receiver<ComponentRequestAndResponseSender<Request, Response>>,
     ) -> Self {
-        Self { component, rx }
+        Self { component, rx, rx_closed: false }
     }
 }
 
@@ -183,14 +192,21 @@ where
         let component_future = async move { component.start().await };
         let request_response_future = request_response_loop(&mut self.rx, &mut self.component);
 
-        tokio::select! {
+        let rx_closed = tokio::select! {
             _res = component_future => {
                 error!("Component stopped.");
+                false
             }
             _res = request_response_future => {
                 error!("Server stopped.");
+                true
             }
         };
+        self.rx_closed = rx_closed;
         error!("Server ended with unexpected Ok.");
     }
+
+    fn is_restartable(&self) -> bool {
+        !self.rx_closed
+    }
 }
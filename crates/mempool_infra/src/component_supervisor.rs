@@ -0,0 +1,214 @@
+//! Supervision of the servers of a node's components.
+//!
+//! The [`ComponentSupervisor`] starts the servers in the order they're added, so that a component
+//! is started after the components it depends on. A server that stops, either by returning or by
+//! panicking, has crashed, and is restarted according to the [`RestartPolicy`]. Once a server can't
+//! be restarted, or the node is asked to shut down, all the servers are stopped in the reverse
+//! order of their startup.
+
+#[cfg(test)]
+#[path = "component_supervisor_test.rs"]
+mod component_supervisor_test;
+
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::time::Duration;
+
+use futures::future::select_all;
+use futures::FutureExt;
+use papyrus_config::converters::deserialize_milliseconds_to_duration;
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{error, info, warn};
+use validator::Validate;
+
+use crate::component_server::ComponentServerStarter;
+
+/// What to do when the server of a component crashes.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub enum RestartPolicy {
+    /// Shut the node down.
+    Never,
+    /// Restart the server, up to the configured number of restarts within the restart window.
+    Always,
+}
+
+/// The configuration of the supervision of the components' servers.
+#[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
+pub struct SupervisionConfig {
+    pub restart_policy: RestartPolicy,
+    pub max_restarts: usize,
+    #[serde(deserialize_with = "deserialize_milliseconds_to_duration")]
+    pub restart_window: Duration,
+    #[serde(deserialize_with = "deserialize_milliseconds_to_duration")]
+    pub restart_delay: Duration,
+    #[serde(deserialize_with = "deserialize_milliseconds_to_duration")]
+    pub shutdown_timeout: Duration,
+}
+
+impl SerializeConfig for SupervisionConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "restart_policy",
+                &self.restart_policy,
+                "What to do when the server of a component crashes. Either Never, which shuts the \
+                 node down, or Always, which restarts the server.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_restarts",
+                &self.max_restarts,
+                "The maximal number of times the server of a component is restarted within the \
+                 restart window before the node shuts down.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "restart_window",
+                &self.restart_window.as_millis(),
+                "The time (milliseconds) over which the restarts of a server are counted against \
+                 the maximal number of restarts.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "restart_delay",
+                &self.restart_delay.as_millis(),
+                "The time (milliseconds) to wait before restarting a crashed server.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "shutdown_timeout",
+                &self.shutdown_timeout.as_millis(),
+                "The time (milliseconds) to wait for each server to stop when the node shuts down.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+impl Default for SupervisionConfig {
+    fn default() -> Self {
+        Self {
+            restart_policy: RestartPolicy::Never,
+            max_restarts: 3,
+            restart_window: Duration::from_millis(60000),
+            restart_delay: Duration::from_millis(1000),
+            shutdown_timeout: Duration::from_millis(5000),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Clone)]
+pub enum SupervisorError {
+    #[error(
+        "The {name} server crashed after being restarted {num_restarts} times within the restart \
+         window."
+    )]
+    ComponentCrashed { name: String, num_restarts: usize },
+    #[error("The {name} server stopped and can't be restarted.")]
+    ComponentNotRestartable { name: String },
+}
+
+/// Runs the servers of a node's components, and restarts them when they crash.
+pub struct ComponentSupervisor {
+    config: SupervisionConfig,
+    // The supervised servers, in the order they were started.
+    servers: Vec<(String, JoinHandle<SupervisorError>)>,
+}
+
+impl ComponentSupervisor {
+    pub fn new(config: SupervisionConfig) -> Self {
+        Self { config, servers: Vec::new() }
+    }
+
+    /// Starts the given server under supervision. Servers are stopped in the reverse order of their
+    /// startup, so a server should be started after the servers of the components it depends on.
+    pub fn start(&mut self, name: &str, server: Box<dyn ComponentServerStarter>) {
+        info!("Starting the {name} server.");
+        let handle = tokio::spawn(supervise(name.to_owned(), server, self.config.clone()));
+        self.servers.push((name.to_owned(), handle));
+    }
+
+    /// Runs until either a server crashes and can't be restarted, or `shutdown_signal` resolves.
+    /// Then, stops all the servers.
+    pub async fn run(
+        mut self,
+        shutdown_signal: impl Future<Output = ()>,
+    ) -> Result<(), SupervisorError> {
+        let crash = if self.servers.is_empty() {
+            shutdown_signal.await;
+            None
+        } else {
+            let handles = self.servers.iter_mut().map(|(_, handle)| handle);
+            tokio::select! {
+                (result, _, _) = select_all(handles) => {
+                    Some(result.expect("Supervising a server should not panic."))
+                }
+                _ = shutdown_signal => None,
+            }
+        };
+        match &crash {
+            Some(error) => error!("Shutting down the node: {error}"),
+            None => info!("Shutting down the node."),
+        }
+        self.shutdown().await;
+        crash.map_or(Ok(()), Err)
+    }
+
+    // Stops the servers in the reverse order of their startup.
+    async fn shutdown(self) {
+        let shutdown_timeout = self.config.shutdown_timeout;
+        for (name, handle) in self.servers.into_iter().rev() {
+            info!("Stopping the {name} server.");
+            handle.abort();
+            if tokio::time::timeout(shutdown_timeout, handle).await.is_err() {
+                warn!("The {name} server didn't stop within {shutdown_timeout:?}.");
+            }
+        }
+    }
+}
+
+// Runs the server, and restarts it according to the config whenever it crashes. Returns once the
+// server crashed and can't be restarted.
+async fn supervise(
+    name: String,
+    mut server: Box<dyn ComponentServerStarter>,
+    config: SupervisionConfig,
+) -> SupervisorError {
+    // The times of the restarts within the restart window, oldest first.
+    let mut restart_times = VecDeque::new();
+    loop {
+        // The server is kept after a panic, so it can be restarted.
+        match AssertUnwindSafe(server.start()).catch_unwind().await {
+            Ok(()) => error!("The {name} server stopped."),
+            Err(_) => error!("The {name} server panicked."),
+        }
+        let now = Instant::now();
+        while restart_times
+            .front()
+            .is_some_and(|restart_time| now.duration_since(*restart_time) > config.restart_window)
+        {
+            restart_times.pop_front();
+        }
+        let num_restarts = restart_times.len();
+        if config.restart_policy == RestartPolicy::Never || num_restarts >= config.max_restarts {
+            return SupervisorError::ComponentCrashed { name, num_restarts };
+        }
+        if !server.is_restartable() {
+            return SupervisorError::ComponentNotRestartable { name };
+        }
+        restart_times.push_back(now);
+        warn!(
+            "Restarting the {name} server in {:?} (restart {} of {} within {:?}).",
+            config.restart_delay,
+            num_restarts + 1,
+            config.max_restarts,
+            config.restart_window
+        );
+        tokio::time::sleep(config.restart_delay).await;
+    }
+}
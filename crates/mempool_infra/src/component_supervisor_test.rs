@@ -0,0 +1,194 @@
+use std::future::{pending, ready};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use rstest::rstest;
+use tokio::sync::Notify;
+
+use crate::component_server::ComponentServerStarter;
+use crate::component_supervisor::{
+    ComponentSupervisor,
+    RestartPolicy,
+    SupervisionConfig,
+    SupervisorError,
+};
+
+const SERVER_NAME: &str = "Test";
+
+// A server that crashes whenever it's started.
+struct CrashingServer {
+    num_starts: Arc<AtomicUsize>,
+    panic: bool,
+}
+
+#[async_trait]
+impl ComponentServerStarter for CrashingServer {
+    async fn start(&mut self) {
+        self.num_starts.fetch_add(1, Ordering::SeqCst);
+        if self.panic {
+            panic!("The server crashed.");
+        }
+    }
+}
+
+// A server that crashes the first `num_crashes` times it's started, and then runs and notifies
+// `running`.
+struct FlakyServer {
+    num_starts: Arc<AtomicUsize>,
+    num_crashes: usize,
+    running: Arc<Notify>,
+}
+
+#[async_trait]
+impl ComponentServerStarter for FlakyServer {
+    async fn start(&mut self) {
+        if self.num_starts.fetch_add(1, Ordering::SeqCst) < self.num_crashes {
+            panic!("The server crashed.");
+        }
+        self.running.notify_one();
+        pending().await
+    }
+}
+
+// A server that stops whenever it's started, and can't be restarted.
+struct NotRestartableServer {
+    num_starts: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl ComponentServerStarter for NotRestartableServer {
+    async fn start(&mut self) {
+        self.num_starts.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn is_restartable(&self) -> bool {
+        false
+    }
+}
+
+// A server that runs until it's stopped, and records when it's stopped.
+struct RunningServer {
+    name: &'static str,
+    stopped_servers: Arc<Mutex<Vec<&'static str>>>,
+}
+
+#[async_trait]
+impl ComponentServerStarter for RunningServer {
+    async fn start(&mut self) {
+        pending().await
+    }
+}
+
+impl Drop for RunningServer {
+    fn drop(&mut self) {
+        self.stopped_servers.lock().unwrap().push(self.name);
+    }
+}
+
+fn supervision_config(restart_policy: RestartPolicy, max_restarts: usize) -> SupervisionConfig {
+    SupervisionConfig {
+        restart_policy,
+        max_restarts,
+        restart_delay: Duration::ZERO,
+        ..SupervisionConfig::default()
+    }
+}
+
+#[rstest]
+#[case::never(RestartPolicy::Never, 0)]
+#[case::always(RestartPolicy::Always, 2)]
+#[tokio::test]
+async fn crashed_server_is_restarted_by_policy(
+    #[case] restart_policy: RestartPolicy,
+    #[case] expected_num_restarts: usize,
+    #[values(true, false)] panic: bool,
+) {
+    let num_starts = Arc::new(AtomicUsize::new(0));
+    let mut supervisor = ComponentSupervisor::new(supervision_config(restart_policy, 2));
+    supervisor
+        .start(SERVER_NAME, Box::new(CrashingServer { num_starts: num_starts.clone(), panic }));
+
+    assert_eq!(
+        supervisor.run(pending()).await,
+        Err(SupervisorError::ComponentCrashed {
+            name: SERVER_NAME.to_owned(),
+            num_restarts: expected_num_restarts
+        })
+    );
+    assert_eq!(num_starts.load(Ordering::SeqCst), expected_num_restarts + 1);
+}
+
+#[tokio::test]
+async fn crash_stops_other_servers() {
+    let stopped_servers = Arc::new(Mutex::new(Vec::new()));
+    let mut supervisor = ComponentSupervisor::new(supervision_config(RestartPolicy::Never, 0));
+    supervisor.start(
+        "Running",
+        Box::new(RunningServer { name: "Running", stopped_servers: stopped_servers.clone() }),
+    );
+    supervisor.start(
+        SERVER_NAME,
+        Box::new(CrashingServer { num_starts: Arc::new(AtomicUsize::new(0)), panic: true }),
+    );
+
+    assert!(supervisor.run(pending()).await.is_err());
+    assert_eq!(*stopped_servers.lock().unwrap(), vec!["Running"]);
+}
+
+#[tokio::test]
+async fn shutdown_stops_servers_in_reverse_order() {
+    let stopped_servers = Arc::new(Mutex::new(Vec::new()));
+    let mut supervisor = ComponentSupervisor::new(SupervisionConfig::default());
+    for name in ["First", "Second", "Third"] {
+        supervisor.start(
+            name,
+            Box::new(RunningServer { name, stopped_servers: stopped_servers.clone() }),
+        );
+    }
+    // Let the servers start before shutting down.
+    tokio::task::yield_now().await;
+
+    assert_eq!(supervisor.run(ready(())).await, Ok(()));
+    assert_eq!(*stopped_servers.lock().unwrap(), vec!["Third", "Second", "First"]);
+}
+
+#[tokio::test]
+async fn restarts_are_counted_within_the_restart_window() {
+    let num_starts = Arc::new(AtomicUsize::new(0));
+    let running = Arc::new(Notify::new());
+    // Each restart is out of the window by the time of the next crash, so the server is restarted
+    // more times than the maximal number of restarts.
+    let config = SupervisionConfig {
+        restart_window: Duration::from_millis(1),
+        restart_delay: Duration::from_millis(10),
+        ..supervision_config(RestartPolicy::Always, 1)
+    };
+    let mut supervisor = ComponentSupervisor::new(config);
+    supervisor.start(
+        SERVER_NAME,
+        Box::new(FlakyServer {
+            num_starts: num_starts.clone(),
+            num_crashes: 3,
+            running: running.clone(),
+        }),
+    );
+
+    assert_eq!(supervisor.run(running.notified()).await, Ok(()));
+    assert_eq!(num_starts.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn stopped_server_that_is_not_restartable_is_not_restarted() {
+    let num_starts = Arc::new(AtomicUsize::new(0));
+    let mut supervisor = ComponentSupervisor::new(supervision_config(RestartPolicy::Always, 2));
+    supervisor
+        .start(SERVER_NAME, Box::new(NotRestartableServer { num_starts: num_starts.clone() }));
+
+    assert_eq!(
+        supervisor.run(pending()).await,
+        Err(SupervisorError::ComponentNotRestartable { name: SERVER_NAME.to_owned() })
+    );
+    assert_eq!(num_starts.load(Ordering::SeqCst), 1);
+}
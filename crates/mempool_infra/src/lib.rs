@@ -1,5 +1,8 @@
+pub mod admin;
 pub mod component_client;
 pub mod component_definitions;
 pub mod component_runner;
 pub mod component_server;
+pub mod component_supervisor;
+pub mod metrics;
 pub mod trace_util;
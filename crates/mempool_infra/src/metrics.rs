@@ -0,0 +1,239 @@
+//! The metrics of the node's components and the server that exposes them.
+//!
+//! All the components of a node record their metrics to a single global recorder, which is exposed
+//! in the Prometheus format on [`METRICS_PATH`]. Every metric is labeled with the component that
+//! recorded it under [`COMPONENT_LABEL`], and the recorder labels all of them with the chain id
+//! under [`CHAIN_ID_LABEL`]; in a node that runs several chains, with the chain of the thread that
+//! recorded them. Metrics that count the transactions of a block are also labeled with
+//! its height under [`HEIGHT_LABEL`].
+//!
+//! Consensus, the storage and the network don't run in this node yet; the papyrus node exposes
+//! their metrics with the same labels.
+
+#[cfg(test)]
+#[path = "metrics_test.rs"]
+mod metrics_test;
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use metrics::{
+    Counter,
+    Gauge,
+    Histogram,
+    Key,
+    KeyName,
+    Label,
+    Recorder,
+    SetRecorderError,
+    SharedString,
+    Unit,
+};
+pub use metrics_exporter_prometheus::PrometheusHandle;
+use metrics_exporter_prometheus::{BuildError, PrometheusBuilder, PrometheusRecorder};
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+pub const METRICS_PATH: &str = "/metrics";
+
+/// The label of the component that recorded a metric.
+pub const COMPONENT_LABEL: &str = "component";
+/// The label of the chain the node runs on. Added to all the metrics by the recorder.
+pub const CHAIN_ID_LABEL: &str = "chain_id";
+/// The label of the height a metric was recorded for.
+pub const HEIGHT_LABEL: &str = "height";
+/// The label of the stage of processing a transaction in the gateway.
+pub const STAGE_LABEL: &str = "stage";
+/// The label of the outcome of a request, either [`ACCEPTED`] or [`REJECTED`].
+pub const RESULT_LABEL: &str = "result";
+pub const ACCEPTED: &str = "accepted";
+pub const REJECTED: &str = "rejected";
+/// The label of the reason a transaction was rejected, e.g. `contract_policy`.
+pub const REASON_LABEL: &str = "reason";
+/// The label of the gas a price is of, e.g. `ETH L1 gas`.
+pub const GAS_LABEL: &str = "gas";
+/// The label of a resource of the block capacity, e.g. `n_steps`.
+pub const RESOURCE_LABEL: &str = "resource";
+
+/// The number of transactions the gateway received.
+pub const GATEWAY_TRANSACTIONS_RECEIVED: &str = "gateway_transactions_received";
+
+/// The latency, in seconds, of processing a transaction in the gateway.
+pub const GATEWAY_ADD_TX_LATENCY_SECS: &str = "gateway_add_tx_latency_seconds";
+
+/// The number of transactions waiting for a worker of a stage of the gateway, by [`STAGE_LABEL`].
+pub const GATEWAY_QUEUED_TRANSACTIONS: &str = "gateway_queued_transactions";
+
+/// The number of transactions that were added to the mempool.
+pub const MEMPOOL_TRANSACTIONS_ADDED: &str = "mempool_transactions_added";
+
+/// The number of transactions the mempool handed out for sequencing.
+pub const MEMPOOL_TRANSACTIONS_SENT: &str = "mempool_transactions_sent";
+
+/// The number of transactions in the mempool.
+pub const MEMPOOL_SIZE: &str = "mempool_size";
+
+/// The number of transactions that were added to block proposals, by [`HEIGHT_LABEL`].
+pub const BATCHER_PROPOSAL_TRANSACTIONS: &str = "batcher_proposal_transactions";
+
+/// How much the gas prices of received proposals deviate from the local ones, in percents, by
+/// [`GAS_LABEL`].
+pub const BATCHER_GAS_PRICE_DEVIATION_PERCENT: &str = "batcher_gas_price_deviation_percent";
+
+/// The time from adding the first transaction of a proposal chunk until the chunk is streamed, in
+/// seconds.
+pub const BATCHER_PROPOSAL_CHUNK_LATENCY_SECS: &str = "batcher_proposal_chunk_latency_seconds";
+
+/// The estimated size of the streamed proposal chunks, in bytes.
+pub const BATCHER_PROPOSAL_CHUNK_BYTES: &str = "batcher_proposal_chunk_bytes";
+
+/// The number of transactions that weren't added to block proposals, by [`REASON_LABEL`] and
+/// [`HEIGHT_LABEL`].
+pub const BATCHER_REJECTED_TRANSACTIONS: &str = "batcher_rejected_transactions";
+
+/// The time that was left until the deadline of a proposal when its block was closed, in seconds.
+pub const BATCHER_DEADLINE_SLACK_SECS: &str = "batcher_deadline_slack_seconds";
+
+/// The percentage of the capacity of each resource that a closed block used, by
+/// [`RESOURCE_LABEL`].
+pub const BATCHER_CAPACITY_USED_PERCENT: &str = "batcher_capacity_used_percent";
+
+/// The estimated lag of the local clock behind the clocks of the proposers of the received
+/// proposals, in seconds.
+pub const BATCHER_CLOCK_SKEW_SECS: &str = "batcher_clock_skew_seconds";
+
+/// The configuration of the metrics server.
+#[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
+pub struct MetricsConfig {
+    pub collect_metrics: bool,
+    pub ip: IpAddr,
+    pub port: u16,
+}
+
+impl SerializeConfig for MetricsConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "collect_metrics",
+                &self.collect_metrics,
+                "If true, collect the metrics of the components and expose them.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "ip",
+                &self.ip.to_string(),
+                "The metrics server ip.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param("port", &self.port, "The metrics server port.", ParamPrivacyInput::Public),
+        ])
+    }
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self { collect_metrics: false, ip: "0.0.0.0".parse().unwrap(), port: 8082 }
+    }
+}
+
+/// Installs the global recorder that collects the metrics of all the components.
+pub fn install_metrics_recorder(chain_id: &str) -> Result<PrometheusHandle, BuildError> {
+    PrometheusBuilder::new().add_global_label(CHAIN_ID_LABEL, chain_id).install_recorder()
+}
+
+thread_local! {
+    // The chain id the metrics that the thread records are labeled with, in a multi-chain node.
+    static THREAD_CHAIN_ID: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Labels the metrics that the current thread records with the given chain id, if the recorder was
+/// installed by [`install_multi_chain_metrics_recorder`].
+pub fn set_thread_chain_id(chain_id: &str) {
+    THREAD_CHAIN_ID
+        .with(|thread_chain_id| *thread_chain_id.borrow_mut() = Some(chain_id.to_owned()));
+}
+
+/// Installs the global recorder of a node that runs several chains, which labels each metric with
+/// the chain id of the thread that recorded it; see [`set_thread_chain_id`].
+pub fn install_multi_chain_metrics_recorder() -> Result<PrometheusHandle, SetRecorderError> {
+    let recorder = ChainLabelRecorder(PrometheusBuilder::new().build_recorder());
+    let prometheus_handle = recorder.0.handle();
+    metrics::set_boxed_recorder(Box::new(recorder))?;
+    Ok(prometheus_handle)
+}
+
+// Records the metrics labeled with the chain id of the recording thread, if it has one.
+struct ChainLabelRecorder(PrometheusRecorder);
+
+impl ChainLabelRecorder {
+    fn labeled(key: &Key) -> Key {
+        THREAD_CHAIN_ID.with(|thread_chain_id| match thread_chain_id.borrow().as_ref() {
+            Some(chain_id) => {
+                key.with_extra_labels(vec![Label::new(CHAIN_ID_LABEL, chain_id.clone())])
+            }
+            None => key.clone(),
+        })
+    }
+}
+
+impl Recorder for ChainLabelRecorder {
+    fn describe_counter(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.0.describe_counter(key, unit, description)
+    }
+
+    fn describe_gauge(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.0.describe_gauge(key, unit, description)
+    }
+
+    fn describe_histogram(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.0.describe_histogram(key, unit, description)
+    }
+
+    fn register_counter(&self, key: &Key) -> Counter {
+        self.0.register_counter(&Self::labeled(key))
+    }
+
+    fn register_gauge(&self, key: &Key) -> Gauge {
+        self.0.register_gauge(&Self::labeled(key))
+    }
+
+    fn register_histogram(&self, key: &Key) -> Histogram {
+        self.0.register_histogram(&Self::labeled(key))
+    }
+}
+
+/// Serves the metrics of the given recorder on [`METRICS_PATH`]. Runs indefinitely.
+pub async fn run_metrics_server(
+    config: &MetricsConfig,
+    prometheus_handle: PrometheusHandle,
+) -> Result<(), hyper::Error> {
+    let make_svc = make_service_fn(move |_conn| {
+        let prometheus_handle = prometheus_handle.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let response = metrics_response(&request, &prometheus_handle);
+                async move { Ok::<_, Infallible>(response) }
+            }))
+        }
+    });
+
+    Server::bind(&SocketAddr::new(config.ip, config.port)).serve(make_svc).await
+}
+
+fn metrics_response(
+    request: &Request<Body>,
+    prometheus_handle: &PrometheusHandle,
+) -> Response<Body> {
+    let response = if request.method() == Method::GET && request.uri().path() == METRICS_PATH {
+        Response::builder().status(StatusCode::OK).body(Body::from(prometheus_handle.render()))
+    } else {
+        Response::builder().status(StatusCode::NOT_FOUND).body(Body::empty())
+    };
+    response.expect("Response building should succeed")
+}
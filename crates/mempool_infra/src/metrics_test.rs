@@ -0,0 +1,59 @@
+use hyper::{Body, Method, Request, StatusCode};
+use metrics::{increment_counter, Key, Label, Recorder};
+use metrics_exporter_prometheus::PrometheusBuilder;
+
+use super::{
+    install_metrics_recorder,
+    metrics_response,
+    set_thread_chain_id,
+    ChainLabelRecorder,
+    COMPONENT_LABEL,
+    METRICS_PATH,
+};
+
+fn request(method: Method, path: &str) -> Request<Body> {
+    Request::builder().method(method).uri(path).body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+async fn metrics_are_rendered_with_labels() {
+    let prometheus_handle = install_metrics_recorder("SN_MAIN").unwrap();
+    increment_counter!("metric_name", COMPONENT_LABEL => "gateway");
+
+    let response = metrics_response(&request(Method::GET, METRICS_PATH), &prometheus_handle);
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body.contains("metric_name{component=\"gateway\",chain_id=\"SN_MAIN\"} 1"));
+}
+
+#[test]
+fn unknown_path_is_not_found() {
+    let prometheus_handle = PrometheusBuilder::new().build_recorder().handle();
+    let response = metrics_response(&request(Method::GET, "/other"), &prometheus_handle);
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = metrics_response(&request(Method::POST, METRICS_PATH), &prometheus_handle);
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn metrics_are_labeled_with_the_chain_of_the_thread() {
+    let recorder = ChainLabelRecorder(PrometheusBuilder::new().build_recorder());
+    let key = Key::from_parts("metric_name", vec![Label::new(COMPONENT_LABEL, "gateway")]);
+
+    recorder.register_counter(&key).increment(1);
+    std::thread::scope(|scope| {
+        for (chain_id, n_increments) in [("A", 2), ("B", 3)] {
+            let (recorder, key) = (&recorder, &key);
+            scope.spawn(move || {
+                set_thread_chain_id(chain_id);
+                recorder.register_counter(key).increment(n_increments);
+            });
+        }
+    });
+
+    let rendered = recorder.0.handle().render();
+    assert!(rendered.contains("metric_name{component=\"gateway\"} 1"));
+    assert!(rendered.contains("metric_name{component=\"gateway\",chain_id=\"A\"} 2"));
+    assert!(rendered.contains("metric_name{component=\"gateway\",chain_id=\"B\"} 3"));
+}
@@ -1,9 +1,161 @@
+//! The tracing of the node's components.
+//!
+//! Spans and events are logged to stdout with their targets, filtered by `RUST_LOG`. The log levels
+//! of targets can change while the node runs through a [`LogLevelHandle`], e.g. to log the events
+//! of consensus at `TRACE` during an incident. If configured, the spans are also exported over OTLP
+//! to a collector, e.g. Jaeger or Tempo.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use opentelemetry::trace::TraceError;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::Tracer;
+use opentelemetry_sdk::{runtime, trace, Resource};
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
 use tracing::metadata::LevelFilter;
 use tracing_subscriber::prelude::*;
-use tracing_subscriber::{fmt, EnvFilter};
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+use validator::Validate;
 
 const DEFAULT_LEVEL: LevelFilter = LevelFilter::INFO;
 
+/// The log targets of the components, by the names operators refer to them with. The events of a
+/// component are logged under the target of its crate, or of one of its modules.
+pub const COMPONENT_LOG_TARGETS: [(&str, &str); 5] = [
+    ("batcher", "starknet_batcher"),
+    ("consensus", "papyrus_consensus"),
+    ("gateway", "starknet_gateway"),
+    ("mempool", "starknet_mempool"),
+    ("storage", "papyrus_storage"),
+];
+
+// The resource attribute that names the service that emitted the spans.
+const SERVICE_NAME_KEY: &str = "service.name";
+
+/// The configuration of the export of the spans.
+#[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
+pub struct TracingConfig {
+    pub export_traces: bool,
+    pub otlp_endpoint: String,
+    pub service_name: String,
+}
+
+impl SerializeConfig for TracingConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "export_traces",
+                &self.export_traces,
+                "If true, export the spans of the components over OTLP.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "otlp_endpoint",
+                &self.otlp_endpoint,
+                "The gRPC endpoint of the OTLP collector the spans are exported to.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "service_name",
+                &self.service_name,
+                "The name of the service the exported spans are attributed to.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+impl Default for TracingConfig {
+    fn default() -> Self {
+        Self {
+            export_traces: false,
+            otlp_endpoint: "http://localhost:4317".to_owned(),
+            service_name: "starknet_sequencer".to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum LogLevelError {
+    #[error("Invalid log target: {0:?}.")]
+    InvalidTarget(String),
+    #[error(transparent)]
+    Reload(#[from] reload::Error),
+}
+
+/// Changes the log levels of targets while the node runs, on top of the levels `RUST_LOG` sets.
+#[derive(Clone, Debug)]
+pub struct LogLevelHandle {
+    base_directives: String,
+    target_levels: Arc<Mutex<BTreeMap<String, LevelFilter>>>,
+    reload_handle: reload::Handle<EnvFilter, Registry>,
+}
+
+impl LogLevelHandle {
+    /// Returns a handle, and the filter layer it controls. Until a level is set, the layer filters
+    /// the events by `base_directives`, given in the format of `RUST_LOG`.
+    pub fn new(base_directives: String) -> (reload::Layer<EnvFilter, Registry>, Self) {
+        let (filter_layer, reload_handle) = reload::Layer::new(env_filter(&base_directives));
+        (filter_layer, Self { base_directives, target_levels: Default::default(), reload_handle })
+    }
+
+    /// Returns the levels set through the handle.
+    pub fn target_levels(&self) -> BTreeMap<String, LevelFilter> {
+        self.lock_target_levels().clone()
+    }
+
+    /// Sets the level of a target. `target` is either a target, e.g. `papyrus_consensus::manager`,
+    /// or the name of a component in [`COMPONENT_LOG_TARGETS`].
+    pub fn set_level(&self, target: &str, level: LevelFilter) -> Result<(), LogLevelError> {
+        let mut target_levels = self.lock_target_levels();
+        target_levels.insert(resolve_target(target)?, level);
+        self.reload(&target_levels)
+    }
+
+    /// Resets the level of a target to the level `RUST_LOG` sets.
+    pub fn reset_level(&self, target: &str) -> Result<(), LogLevelError> {
+        let mut target_levels = self.lock_target_levels();
+        target_levels.remove(&resolve_target(target)?);
+        self.reload(&target_levels)
+    }
+
+    fn lock_target_levels(&self) -> MutexGuard<'_, BTreeMap<String, LevelFilter>> {
+        self.target_levels.lock().expect("Log levels are poisoned.")
+    }
+
+    fn reload(&self, target_levels: &BTreeMap<String, LevelFilter>) -> Result<(), LogLevelError> {
+        // A directive of a target overrides the base directive of the same target.
+        let directives: Vec<String> = std::iter::once(self.base_directives.clone())
+            .chain(target_levels.iter().map(|(target, level)| format!("{target}={level}")))
+            .filter(|directive| !directive.is_empty())
+            .collect();
+        Ok(self.reload_handle.reload(env_filter(&directives.join(",")))?)
+    }
+}
+
+fn env_filter(directives: &str) -> EnvFilter {
+    EnvFilter::builder().with_default_directive(DEFAULT_LEVEL.into()).parse_lossy(directives)
+}
+
+fn resolve_target(target: &str) -> Result<String, LogLevelError> {
+    if let Some((_, component_target)) =
+        COMPONENT_LOG_TARGETS.iter().find(|(component, _)| *component == target)
+    {
+        return Ok(component_target.to_string());
+    }
+    let is_valid = !target.is_empty()
+        && target.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':');
+    match is_valid {
+        true => Ok(target.to_owned()),
+        false => Err(LogLevelError::InvalidTarget(target.to_owned())),
+    }
+}
+
 pub fn configure_tracing() {
     let fmt_layer = fmt::layer().compact().with_target(false);
     let level_filter_layer =
@@ -13,3 +165,36 @@ pub fn configure_tracing() {
     // subscriber for some threads and use set_global_default instead of init.
     tracing_subscriber::registry().with(fmt_layer).with(level_filter_layer).init();
 }
+
+/// Like [`configure_tracing`], and also logs the targets of the events, exports the spans over
+/// OTLP if `config` says so, and returns a handle for changing the log levels. Must be called from
+/// within a Tokio runtime, which runs the export in the background.
+pub fn configure_tracing_with_export(config: &TracingConfig) -> Result<LogLevelHandle, TraceError> {
+    let fmt_layer = fmt::layer().compact().with_target(true);
+    let base_directives = std::env::var(EnvFilter::DEFAULT_ENV).unwrap_or_default();
+    let (level_filter_layer, log_level_handle) = LogLevelHandle::new(base_directives);
+    let otlp_layer = match config.export_traces {
+        true => Some(tracing_opentelemetry::layer().with_tracer(otlp_tracer(config)?)),
+        false => None,
+    };
+
+    // The filter is the innermost layer, so the handle can reload it.
+    tracing_subscriber::registry().with(level_filter_layer).with(fmt_layer).with(otlp_layer).init();
+    Ok(log_level_handle)
+}
+
+/// Exports the spans that weren't exported yet. Should be called before the node exits.
+pub fn shutdown_tracing() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+fn otlp_tracer(config: &TracingConfig) -> Result<Tracer, TraceError> {
+    let exporter =
+        opentelemetry_otlp::new_exporter().tonic().with_endpoint(config.otlp_endpoint.clone());
+    let resource = Resource::new([KeyValue::new(SERVICE_NAME_KEY, config.service_name.clone())]);
+    opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(exporter)
+        .with_trace_config(trace::config().with_resource(resource))
+        .install_batch(runtime::Tokio)
+}
@@ -0,0 +1,160 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use assert_matches::assert_matches;
+use async_trait::async_trait;
+use rstest::rstest;
+use starknet_mempool_infra::component_client::{ClientError, GrpcComponentClient};
+use starknet_mempool_infra::component_definitions::{
+    ComponentRequestHandler,
+    GrpcRequest,
+    GrpcResponse,
+    GrpcStatus,
+};
+use starknet_mempool_infra::component_server::{ComponentServerStarter, GrpcComponentServer};
+use tokio::net::{TcpListener, TcpStream};
+
+const LOCAL_IP: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+const MAX_RETRIES: usize = 0;
+const ADD_PATH: &str = "/Counter/Add";
+const GET_PATH: &str = "/Counter/Get";
+
+#[derive(Debug)]
+enum CounterRequest {
+    Add(u32),
+    Get,
+    // Requests the client of a different version of the service could send.
+    MalformedAdd,
+    Unknown,
+}
+
+#[derive(Debug, PartialEq)]
+enum CounterResponse {
+    Add,
+    Get(u32),
+}
+
+impl GrpcRequest for CounterRequest {
+    fn method_path(&self) -> &'static str {
+        match self {
+            CounterRequest::Add(_) | CounterRequest::MalformedAdd => ADD_PATH,
+            CounterRequest::Get => GET_PATH,
+            CounterRequest::Unknown => "/Counter/Unknown",
+        }
+    }
+
+    fn encode_message(&self) -> Vec<u8> {
+        match self {
+            CounterRequest::Add(value) => value.to_be_bytes().to_vec(),
+            CounterRequest::Get | CounterRequest::MalformedAdd | CounterRequest::Unknown => vec![],
+        }
+    }
+
+    fn decode_message(method_path: &str, message: &[u8]) -> Result<Self, GrpcStatus> {
+        match method_path {
+            ADD_PATH => message
+                .try_into()
+                .map(|value| CounterRequest::Add(u32::from_be_bytes(value)))
+                .map_err(|_| GrpcStatus::new(GrpcStatus::INVALID_ARGUMENT, "Invalid value.")),
+            GET_PATH => Ok(CounterRequest::Get),
+            _ => Err(GrpcStatus::new(GrpcStatus::UNIMPLEMENTED, method_path)),
+        }
+    }
+}
+
+impl GrpcResponse for CounterResponse {
+    fn encode_message(&self) -> Vec<u8> {
+        match self {
+            CounterResponse::Add => vec![],
+            CounterResponse::Get(value) => value.to_be_bytes().to_vec(),
+        }
+    }
+
+    fn decode_message(method_path: &str, message: &[u8]) -> Result<Self, String> {
+        match method_path {
+            ADD_PATH => Ok(CounterResponse::Add),
+            GET_PATH => message
+                .try_into()
+                .map(|value| CounterResponse::Get(u32::from_be_bytes(value)))
+                .map_err(|_| "Invalid value.".to_owned()),
+            _ => Err(format!("Unknown method {method_path}.")),
+        }
+    }
+}
+
+struct Counter {
+    value: u32,
+}
+
+#[async_trait]
+impl ComponentRequestHandler<CounterRequest, CounterResponse> for Counter {
+    async fn handle_request(&mut self, request: CounterRequest) -> CounterResponse {
+        match request {
+            CounterRequest::Add(value) => {
+                self.value += value;
+                CounterResponse::Add
+            }
+            CounterRequest::Get => CounterResponse::Get(self.value),
+            CounterRequest::MalformedAdd | CounterRequest::Unknown => {
+                unreachable!("The server should not decode {request:?}.")
+            }
+        }
+    }
+}
+
+type CounterClient = GrpcComponentClient<CounterRequest, CounterResponse>;
+
+async fn available_port() -> u16 {
+    TcpListener::bind((LOCAL_IP, 0))
+        .await
+        .expect("Failed to bind to address")
+        .local_addr()
+        .expect("Failed to get local address")
+        .port()
+}
+
+// Runs a counter server and returns a client of it, once the server listens.
+async fn setup() -> CounterClient {
+    let port = available_port().await;
+    let mut server = GrpcComponentServer::new(Counter { value: 0 }, LOCAL_IP, port);
+    tokio::spawn(async move { server.start().await });
+    while TcpStream::connect(SocketAddr::new(LOCAL_IP, port)).await.is_err() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    CounterClient::new(LOCAL_IP, port, MAX_RETRIES)
+}
+
+#[tokio::test]
+async fn component_is_served_over_grpc() {
+    let client = setup().await;
+
+    assert_eq!(client.send(CounterRequest::Add(2)).await.unwrap(), CounterResponse::Add);
+    assert_eq!(client.send(CounterRequest::Add(3)).await.unwrap(), CounterResponse::Add);
+    assert_eq!(client.send(CounterRequest::Get).await.unwrap(), CounterResponse::Get(5));
+}
+
+#[rstest]
+#[case::malformed_message(CounterRequest::MalformedAdd, GrpcStatus::INVALID_ARGUMENT)]
+#[case::unknown_method(CounterRequest::Unknown, GrpcStatus::UNIMPLEMENTED)]
+#[tokio::test]
+async fn undecodable_request_fails_with_status(
+    #[case] request: CounterRequest,
+    #[case] expected_code: u32,
+) {
+    let client = setup().await;
+
+    assert_matches!(
+        client.send(request).await,
+        Err(ClientError::GrpcError(GrpcStatus { code, .. })) if code == expected_code
+    );
+}
+
+#[tokio::test]
+async fn unconnected_server() {
+    let client = CounterClient::new(LOCAL_IP, available_port().await, MAX_RETRIES);
+
+    assert_matches!(
+        client.send(CounterRequest::Get).await,
+        Err(ClientError::CommunicationFailure(_))
+    );
+}
@@ -0,0 +1,77 @@
+//! Applies the commands of the admin server to the components of the chains that run in the node.
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use starknet_mempool_infra::admin::{
+    AdminCommand,
+    AdminCommandError,
+    AdminCommandHandler,
+    AdminCommandReports,
+};
+
+use crate::communication::MempoolNodeClients;
+
+/// Sends the admin commands to the components of the chains that run in the node. Each chain
+/// registers the clients of its components once it starts, and a command is applied to each of the
+/// registered chains, whether it failed on the others or not.
+#[derive(Clone, Default)]
+pub struct NodeAdminCommandHandler {
+    chains: Arc<Mutex<Vec<(String, MempoolNodeClients)>>>,
+}
+
+impl NodeAdminCommandHandler {
+    pub fn register_chain(&self, chain_id: String, clients: MempoolNodeClients) {
+        self.chains
+            .lock()
+            .expect("The chains lock shouldn't be poisoned.")
+            .push((chain_id, clients));
+    }
+}
+
+#[async_trait]
+impl AdminCommandHandler for NodeAdminCommandHandler {
+    async fn handle(&self, command: AdminCommand) -> AdminCommandReports {
+        let chains = self.chains.lock().expect("The chains lock shouldn't be poisoned.").clone();
+        let mut reports = AdminCommandReports::new();
+        for (chain_id, clients) in chains {
+            let outcome = handle_chain_command(&clients, command).await;
+            reports.insert(chain_id, outcome);
+        }
+        reports
+    }
+}
+
+async fn handle_chain_command(
+    clients: &MempoolNodeClients,
+    command: AdminCommand,
+) -> Result<String, AdminCommandError> {
+    match command {
+        AdminCommand::PauseProposing | AdminCommand::ResumeProposing => {
+            Err(AdminCommandError::Unsupported {
+                command,
+                reason: "The batcher doesn't generate the proposals of the node yet.",
+            })
+        }
+        AdminCommand::FlushMempool => {
+            let component = "mempool";
+            let mempool_client = clients
+                .get_mempool_client()
+                .ok_or(AdminCommandError::ComponentNotRunning { command, component })?;
+            let n_flushed_txs = mempool_client.flush().await.map_err(|error| {
+                AdminCommandError::ComponentError { command, component, error: error.to_string() }
+            })?;
+            Ok(format!("Flushed {n_flushed_txs} transactions."))
+        }
+        AdminCommand::SkipRound | AdminCommand::DumpConsensusState => {
+            Err(AdminCommandError::Unsupported {
+                command,
+                reason: "Consensus doesn't run in the consensus manager yet.",
+            })
+        }
+        AdminCommand::RotateKeys => Err(AdminCommandError::Unsupported {
+            command,
+            reason: "Consensus doesn't sign its votes with keys of its own yet.",
+        }),
+    }
+}
@@ -0,0 +1,57 @@
+//! Runs several chains in one node process; see [`starknet_mempool_node::multi_chain`].
+//!
+//! Each chain is given by a config file, which overrides the default config, e.g.:
+//! mempool_multi_chain_node --chain_config_file chain_a.json --chain_config_file chain_b.json
+
+use std::process::exit;
+use std::sync::Arc;
+
+use clap::{Arg, ArgAction, Command};
+use starknet_mempool_infra::trace_util::{configure_tracing_with_export, shutdown_tracing};
+use starknet_mempool_node::admin_commands::NodeAdminCommandHandler;
+use starknet_mempool_node::multi_chain::{run_chains, validate_chains, Chain};
+use starknet_mempool_node::servers::{shutdown_signal, spawn_monitoring_servers};
+use starknet_mempool_node::version::VERSION_FULL;
+use tracing::error;
+
+const CHAIN_CONFIG_FILE_ARG: &str = "chain_config_file";
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let arg_matches = Command::new("Mempool multi-chain")
+        .version(VERSION_FULL)
+        .about("Runs several Starknet chains in one mempool node process.")
+        .arg(
+            Arg::new(CHAIN_CONFIG_FILE_ARG)
+                .long(CHAIN_CONFIG_FILE_ARG)
+                .help("The config file of a chain. Given once per chain.")
+                .required(true)
+                .action(ArgAction::Append),
+        )
+        .get_matches();
+    let chains = arg_matches
+        .get_many::<String>(CHAIN_CONFIG_FILE_ARG)
+        .expect("The arg is required.")
+        .map(|config_file| Chain::load(config_file))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // The chains configure the services of the process the same; see `validate_chains`.
+    let process_config = &chains[0].config;
+    let log_level_handle = configure_tracing_with_export(&process_config.tracing_config)?;
+    if let Err(error) = validate_chains(&chains) {
+        error!("{}", error);
+        exit(1);
+    }
+    let admin_command_handler = NodeAdminCommandHandler::default();
+    spawn_monitoring_servers(
+        process_config,
+        log_level_handle,
+        Arc::new(admin_command_handler.clone()),
+        // The metrics are labeled by the chain that records them.
+        None,
+    )?;
+
+    let result = run_chains(chains, shutdown_signal(), admin_command_handler).await;
+    shutdown_tracing();
+    result
+}
@@ -1,24 +1,34 @@
+#[cfg(test)]
+#[path = "communication_test.rs"]
+mod communication_test;
+
 use std::sync::Arc;
 
 use starknet_batcher_types::communication::{
     BatcherRequestAndResponseSender,
     LocalBatcherClientImpl,
+    RemoteBatcherClientImpl,
     SharedBatcherClient,
 };
 use starknet_consensus_manager_types::communication::{
     ConsensusManagerRequestAndResponseSender,
     LocalConsensusManagerClientImpl,
+    RemoteConsensusManagerClientImpl,
     SharedConsensusManagerClient,
 };
-use starknet_mempool_infra::component_definitions::ComponentCommunication;
+use starknet_mempool_infra::component_definitions::{
+    ComponentCommunication,
+    RemoteComponentCommunicationConfig,
+};
 use starknet_mempool_types::communication::{
     LocalMempoolClientImpl,
     MempoolRequestAndResponseSender,
+    RemoteMempoolClientImpl,
     SharedMempoolClient,
 };
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 
-use crate::config::MempoolNodeConfig;
+use crate::config::{ComponentExecutionConfig, LocationType, MempoolNodeConfig};
 
 pub struct MempoolNodeCommunication {
     batcher_channel: ComponentCommunication<BatcherRequestAndResponseSender>,
@@ -56,16 +66,17 @@ impl MempoolNodeCommunication {
     }
 }
 
-pub fn create_node_channels() -> MempoolNodeCommunication {
-    const DEFAULT_INVOCATIONS_QUEUE_SIZE: usize = 32;
+pub fn create_node_channels(config: &MempoolNodeConfig) -> MempoolNodeCommunication {
     let (tx_mempool, rx_mempool) =
-        channel::<MempoolRequestAndResponseSender>(DEFAULT_INVOCATIONS_QUEUE_SIZE);
+        channel::<MempoolRequestAndResponseSender>(channel_buffer_size(&config.components.mempool));
 
     let (tx_batcher, rx_batcher) =
-        channel::<BatcherRequestAndResponseSender>(DEFAULT_INVOCATIONS_QUEUE_SIZE);
+        channel::<BatcherRequestAndResponseSender>(channel_buffer_size(&config.components.batcher));
 
     let (tx_consensus_manager, rx_consensus_manager) =
-        channel::<ConsensusManagerRequestAndResponseSender>(DEFAULT_INVOCATIONS_QUEUE_SIZE);
+        channel::<ConsensusManagerRequestAndResponseSender>(channel_buffer_size(
+            &config.components.consensus_manager,
+        ));
 
     MempoolNodeCommunication {
         mempool_channel: ComponentCommunication::new(Some(tx_mempool), Some(rx_mempool)),
@@ -77,6 +88,7 @@ pub fn create_node_channels() -> MempoolNodeCommunication {
     }
 }
 
+#[derive(Clone)]
 pub struct MempoolNodeClients {
     batcher_client: Option<SharedBatcherClient>,
     consensus_manager_client: Option<SharedConsensusManagerClient>,
@@ -98,24 +110,64 @@ impl MempoolNodeClients {
     }
 }
 
+/// Creates the clients of the components. A component located in this process is reached through
+/// its channel, and is only reachable if it's executed. A remote component is reached through its
+/// server, whether it's executed in this process or in another one.
 pub fn create_node_clients(
     config: &MempoolNodeConfig,
     channels: &mut MempoolNodeCommunication,
 ) -> MempoolNodeClients {
-    let batcher_client: Option<SharedBatcherClient> = match config.components.batcher.execute {
-        true => Some(Arc::new(LocalBatcherClientImpl::new(channels.take_batcher_tx()))),
-        false => None,
+    let batcher_config = &config.components.batcher;
+    let batcher_client: Option<SharedBatcherClient> = match batcher_config.location {
+        LocationType::Local if batcher_config.execute => {
+            Some(Arc::new(LocalBatcherClientImpl::new(channels.take_batcher_tx())))
+        }
+        LocationType::Local => None,
+        LocationType::Remote => {
+            let RemoteComponentCommunicationConfig { ip, port, retries } =
+                remote_config(batcher_config);
+            Some(Arc::new(RemoteBatcherClientImpl::new(*ip, *port, *retries)))
+        }
     };
+
+    let consensus_manager_config = &config.components.consensus_manager;
     let consensus_manager_client: Option<SharedConsensusManagerClient> =
-        match config.components.consensus_manager.execute {
-            true => Some(Arc::new(LocalConsensusManagerClientImpl::new(
-                channels.take_consensus_manager_tx(),
-            ))),
-            false => None,
+        match consensus_manager_config.location {
+            LocationType::Local if consensus_manager_config.execute => Some(Arc::new(
+                LocalConsensusManagerClientImpl::new(channels.take_consensus_manager_tx()),
+            )),
+            LocationType::Local => None,
+            LocationType::Remote => {
+                let RemoteComponentCommunicationConfig { ip, port, retries } =
+                    remote_config(consensus_manager_config);
+                Some(Arc::new(RemoteConsensusManagerClientImpl::new(*ip, *port, *retries)))
+            }
         };
-    let mempool_client: Option<SharedMempoolClient> = match config.components.gateway.execute {
-        true => Some(Arc::new(LocalMempoolClientImpl::new(channels.take_mempool_tx()))),
-        false => None,
+
+    let mempool_config = &config.components.mempool;
+    let mempool_client: Option<SharedMempoolClient> = match mempool_config.location {
+        LocationType::Local if mempool_config.execute => {
+            Some(Arc::new(LocalMempoolClientImpl::new(channels.take_mempool_tx())))
+        }
+        LocationType::Local => None,
+        LocationType::Remote => {
+            let RemoteComponentCommunicationConfig { ip, port, retries } =
+                remote_config(mempool_config);
+            Some(Arc::new(RemoteMempoolClientImpl::new(*ip, *port, *retries)))
+        }
     };
+
     MempoolNodeClients { batcher_client, consensus_manager_client, mempool_client }
 }
+
+fn channel_buffer_size(config: &ComponentExecutionConfig) -> usize {
+    config.local_config.clone().unwrap_or_default().channel_buffer_size
+}
+
+/// Returns the communication config of a remote component; its presence is validated when the
+/// config is loaded.
+pub(crate) fn remote_config(
+    config: &ComponentExecutionConfig,
+) -> &RemoteComponentCommunicationConfig {
+    config.remote_config.as_ref().expect("Remote communication config should be available.")
+}
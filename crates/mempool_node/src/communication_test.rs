@@ -0,0 +1,120 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use mempool_test_utils::starknet_api_test_utils::{
+    create_executable_tx,
+    test_resource_bounds_mapping,
+};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ContractAddress, Nonce, PatriciaKey};
+use starknet_api::hash::StarkHash;
+use starknet_api::transaction::{Tip, TransactionHash};
+use starknet_api::{contract_address, felt, patricia_key};
+use starknet_mempool_infra::component_definitions::RemoteComponentCommunicationConfig;
+use starknet_mempool_infra::component_server::ComponentServerStarter;
+use starknet_mempool_types::mempool_types::{Account, AccountState, MempoolInput};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::config::{ComponentExecutionConfig, LocationType, MempoolNodeConfig};
+use crate::utils::create_clients_servers_from_config;
+
+// A config in which only the mempool may be executed.
+fn mempool_only_config() -> MempoolNodeConfig {
+    let mut config = MempoolNodeConfig::default();
+    config.components.batcher.execute = false;
+    config.components.consensus_manager.execute = false;
+    config.components.gateway.execute = false;
+    config
+}
+
+fn remote_mempool_config(execute: bool, port: u16) -> MempoolNodeConfig {
+    let mut config = mempool_only_config();
+    config.components.mempool = ComponentExecutionConfig {
+        execute,
+        location: LocationType::Remote,
+        local_config: None,
+        remote_config: Some(RemoteComponentCommunicationConfig {
+            ip: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            port,
+            retries: 3,
+        }),
+        ..ComponentExecutionConfig::mempool_default_config()
+    };
+    config
+}
+
+fn mempool_input(sender_address: u8) -> MempoolInput {
+    let tx_hash = TransactionHash(StarkHash::from(sender_address));
+    let sender_address = contract_address!(sender_address);
+    let tx = create_executable_tx(
+        sender_address,
+        tx_hash,
+        Tip(1),
+        Nonce::default(),
+        test_resource_bounds_mapping().into(),
+    );
+    MempoolInput { tx, account: Account { sender_address, state: AccountState::default() } }
+}
+
+// Runs a node that executes the mempool behind its remote server, on a port that the OS picked as
+// available, and returns the port once the server listens on it.
+async fn run_remote_mempool() -> u16 {
+    let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .expect("Failed to bind to address")
+        .local_addr()
+        .expect("Failed to get local address")
+        .port();
+    let (_, servers, _) = create_clients_servers_from_config(&remote_mempool_config(true, port));
+    let mut mempool_server = servers.mempool.expect("Mempool server should be created.");
+    tokio::spawn(async move { mempool_server.start().await });
+
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    while TcpStream::connect(address).await.is_err() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    port
+}
+
+#[test]
+fn local_component_is_not_reachable_unless_executed() {
+    let mut config = mempool_only_config();
+    config.components.mempool.execute = false;
+
+    let (clients, servers, _) = create_clients_servers_from_config(&config);
+    assert!(clients.get_mempool_client().is_none());
+    assert!(servers.mempool.is_none());
+}
+
+#[tokio::test]
+async fn remote_component_is_reachable_from_another_node() {
+    let port = run_remote_mempool().await;
+
+    // A node that doesn't execute the mempool reaches it through its server.
+    let (clients, servers, _) =
+        create_clients_servers_from_config(&remote_mempool_config(false, port));
+    assert!(servers.mempool.is_none());
+    let mempool_client = clients.get_mempool_client().expect("Mempool client should be created.");
+    assert!(mempool_client.get_txs(1).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn remote_mempool_is_shared_between_nodes() {
+    let port = run_remote_mempool().await;
+    let [first_client, second_client] = [(); 2].map(|_| {
+        let (clients, _, _) =
+            create_clients_servers_from_config(&remote_mempool_config(false, port));
+        clients.get_mempool_client().expect("Mempool client should be created.")
+    });
+
+    // Transactions added through one node are handed out once, through either node.
+    let inputs = [mempool_input(1), mempool_input(2)];
+    first_client.add_tx(inputs[0].clone()).await.unwrap();
+    second_client.add_tx(inputs[1].clone()).await.unwrap();
+    assert_eq!(first_client.get_txs(2).await.unwrap().len(), 2);
+    assert!(second_client.get_txs(2).await.unwrap().is_empty());
+
+    let state_changes =
+        inputs.iter().map(|input| (input.account.sender_address, input.account.state)).collect();
+    second_client.commit_block(BlockNumber(0), state_changes).await.unwrap();
+}
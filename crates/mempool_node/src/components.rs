@@ -38,13 +38,18 @@ pub fn create_components(config: &MempoolNodeConfig, clients: &MempoolNodeClient
             config.gateway_config.clone(),
             config.rpc_state_reader_config.clone(),
             config.compiler_config.clone(),
+            config.compilation_service_config.clone(),
             mempool_client,
         ))
     } else {
         None
     };
 
-    let mempool = if config.components.mempool.execute { Some(Mempool::empty()) } else { None };
+    let mempool = if config.components.mempool.execute {
+        Some(Mempool::new(config.mempool_config.clone()))
+    } else {
+        None
+    };
 
     Components { batcher, consensus_manager, gateway, mempool }
 }
@@ -15,6 +15,7 @@ use starknet_mempool_infra::component_definitions::{
 use validator::{Validate, ValidationErrors};
 
 use crate::config::{
+    chain_presets,
     ComponentConfig,
     ComponentExecutionConfig,
     LocationType,
@@ -198,3 +199,22 @@ fn default_config_file_is_up_to_date() {
     println!("Diffs shown below.");
     assert_json_eq!(from_default_config_file, from_code)
 }
+
+/// Test that the chain presets load to valid configs of their chains.
+#[test]
+fn chain_presets_are_valid() {
+    env::set_current_dir(get_absolute_path("")).expect("Couldn't set working dir.");
+    for preset in chain_presets() {
+        let config = MempoolNodeConfig::load_and_process(vec![
+            "Mempool".to_owned(),
+            "--preset".to_owned(),
+            preset.name.to_owned(),
+        ])
+        .unwrap();
+        assert_matches!(config.validate(), Ok(()));
+        assert_eq!(
+            config.gateway_config.stateful_tx_validator_config.chain_info.chain_id.to_string(),
+            preset.name
+        );
+    }
+}
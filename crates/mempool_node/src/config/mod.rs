@@ -12,17 +12,24 @@ use papyrus_config::dumping::{
     ser_param,
     SerializeConfig,
 };
-use papyrus_config::loading::load_and_process_config;
+use papyrus_config::loading::{load_and_process_config_with_options, LoadingOptions};
+use papyrus_config::presets::ConfigPreset;
+use papyrus_config::secrets::{CommandSecretResolver, SecretResolver};
 use papyrus_config::{ConfigError, ParamPath, ParamPrivacyInput, SerializedParam};
 use serde::{Deserialize, Serialize};
 use starknet_batcher::config::BatcherConfig;
 use starknet_consensus_manager::config::ConsensusManagerConfig;
 use starknet_gateway::config::{GatewayConfig, RpcStateReaderConfig};
+use starknet_mempool::config::MempoolConfig;
+use starknet_mempool_infra::admin::AdminConfig;
 use starknet_mempool_infra::component_definitions::{
     LocalComponentCommunicationConfig,
     RemoteComponentCommunicationConfig,
 };
-use starknet_sierra_compile::config::SierraToCasmCompilationConfig;
+use starknet_mempool_infra::component_supervisor::SupervisionConfig;
+use starknet_mempool_infra::metrics::MetricsConfig;
+use starknet_mempool_infra::trace_util::TracingConfig;
+use starknet_sierra_compile::config::{CompilationServiceConfig, SierraToCasmCompilationConfig};
 use validator::{Validate, ValidationError};
 
 use crate::version::VERSION_FULL;
@@ -229,6 +236,8 @@ pub fn validate_components_config(components: &ComponentConfig) -> Result<(), Va
 /// The configurations of the various components of the node.
 #[derive(Debug, Deserialize, Default, Serialize, Clone, PartialEq, Validate)]
 pub struct MempoolNodeConfig {
+    #[validate]
+    pub admin_config: AdminConfig,
     #[validate]
     pub components: ComponentConfig,
     #[validate]
@@ -238,15 +247,26 @@ pub struct MempoolNodeConfig {
     #[validate]
     pub gateway_config: GatewayConfig,
     #[validate]
+    pub mempool_config: MempoolConfig,
+    #[validate]
     pub rpc_state_reader_config: RpcStateReaderConfig,
     #[validate]
     pub compiler_config: SierraToCasmCompilationConfig,
+    #[validate]
+    pub compilation_service_config: CompilationServiceConfig,
+    #[validate]
+    pub metrics_config: MetricsConfig,
+    #[validate]
+    pub supervision_config: SupervisionConfig,
+    #[validate]
+    pub tracing_config: TracingConfig,
 }
 
 impl SerializeConfig for MempoolNodeConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
         #[allow(unused_mut)]
         let mut sub_configs = vec![
+            append_sub_config_name(self.admin_config.dump(), "admin_config"),
             append_sub_config_name(self.components.dump(), "components"),
             append_sub_config_name(self.batcher_config.dump(), "batcher_config"),
             append_sub_config_name(
@@ -254,8 +274,16 @@ impl SerializeConfig for MempoolNodeConfig {
                 "consensus_manager_config",
             ),
             append_sub_config_name(self.gateway_config.dump(), "gateway_config"),
+            append_sub_config_name(self.mempool_config.dump(), "mempool_config"),
             append_sub_config_name(self.rpc_state_reader_config.dump(), "rpc_state_reader_config"),
             append_sub_config_name(self.compiler_config.dump(), "compiler_config"),
+            append_sub_config_name(
+                self.compilation_service_config.dump(),
+                "compilation_service_config",
+            ),
+            append_sub_config_name(self.metrics_config.dump(), "metrics_config"),
+            append_sub_config_name(self.supervision_config.dump(), "supervision_config"),
+            append_sub_config_name(self.tracing_config.dump(), "tracing_config"),
         ];
 
         sub_configs.into_iter().flatten().collect()
@@ -275,7 +303,15 @@ impl MempoolNodeConfig {
         };
 
         let default_config_file = File::open(Path::new(config_file_name))?;
-        load_and_process_config(default_config_file, node_command(), args)
+        let kms_resolver = CommandSecretResolver::kms_from_env();
+        let options = LoadingOptions {
+            secret_resolvers: kms_resolver
+                .iter()
+                .map(|resolver| resolver as &dyn SecretResolver)
+                .collect(),
+            presets: chain_presets(),
+        };
+        load_and_process_config_with_options(default_config_file, node_command(), args, &options)
     }
 
     pub fn load_and_process(args: Vec<String>) -> Result<Self, ConfigError> {
@@ -289,6 +325,24 @@ impl MempoolNodeConfig {
     }
 }
 
+/// The presets of the known chains, selected by their chain id, e.g. `--preset SN_MAIN`.
+pub fn chain_presets() -> Vec<ConfigPreset> {
+    vec![
+        ConfigPreset {
+            name: "SN_MAIN",
+            values: include_str!("../../../../config/mempool/presets/mainnet.json"),
+        },
+        ConfigPreset {
+            name: "SN_SEPOLIA",
+            values: include_str!("../../../../config/mempool/presets/sepolia_testnet.json"),
+        },
+        ConfigPreset {
+            name: "SN_INTEGRATION_SEPOLIA",
+            values: include_str!("../../../../config/mempool/presets/sepolia_integration.json"),
+        },
+    ]
+}
+
 /// The command line interface of this node.
 pub fn node_command() -> Command {
     Command::new("Mempool")
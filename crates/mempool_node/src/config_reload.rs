@@ -0,0 +1,97 @@
+//! Re-loads the node's configuration while it runs.
+//!
+//! On SIGHUP, the node re-reads its configuration from the same sources it was started with, and
+//! applies the changes to the running components. Only the parameters in [`HOT_RELOADABLE_PARAMS`]
+//! can change this way; a configuration that changes any other parameter is rejected as a whole,
+//! and the node keeps running with its current configuration.
+
+#[cfg(test)]
+#[path = "config_reload_test.rs"]
+mod config_reload_test;
+
+use std::sync::Arc;
+
+use papyrus_config::reloading::validate_reload;
+use papyrus_config::validators::config_validate;
+use papyrus_config::{ConfigError, ParamPath};
+use starknet_gateway::config::{
+    StatefulTransactionValidatorConfig,
+    StatelessTransactionValidatorConfig,
+};
+use starknet_mempool::config::MempoolConfig;
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::watch;
+use tracing::{error, info};
+
+use crate::config::MempoolNodeConfig;
+
+/// The parameters, or sub-configs, that can change without restarting the node. Log levels change
+/// through the admin server instead.
+pub const HOT_RELOADABLE_PARAMS: [&str; 3] = [
+    "gateway_config.stateless_tx_validator_config",
+    "gateway_config.stateful_tx_validator_config.gas_price_floor_config",
+    "mempool_config.max_txs_per_sender_per_block",
+];
+
+/// Handles for replacing the configuration of the running components. A component that doesn't
+/// run in the node has no handle.
+#[derive(Clone, Debug, Default)]
+pub struct ConfigReloadHandles {
+    pub gateway_stateless_tx_validator_config:
+        Option<Arc<watch::Sender<StatelessTransactionValidatorConfig>>>,
+    pub gateway_stateful_tx_validator_config:
+        Option<Arc<watch::Sender<StatefulTransactionValidatorConfig>>>,
+    pub mempool_config: Option<Arc<watch::Sender<MempoolConfig>>>,
+}
+
+pub struct ConfigReloader {
+    config: MempoolNodeConfig,
+    args: Vec<String>,
+    handles: ConfigReloadHandles,
+}
+
+impl ConfigReloader {
+    /// `args` are the command line arguments the node was started with.
+    pub fn new(config: MempoolNodeConfig, args: Vec<String>, handles: ConfigReloadHandles) -> Self {
+        Self { config, args, handles }
+    }
+
+    pub fn config(&self) -> &MempoolNodeConfig {
+        &self.config
+    }
+
+    /// Applies the re-loaded configuration to the running components, and returns the paths of the
+    /// changed parameters.
+    pub fn apply(&mut self, reloaded: MempoolNodeConfig) -> Result<Vec<ParamPath>, ConfigError> {
+        config_validate(&reloaded)?;
+        let changed_params = validate_reload(&self.config, &reloaded, &HOT_RELOADABLE_PARAMS)?;
+        if let Some(sender) = &self.handles.gateway_stateless_tx_validator_config {
+            sender.send_replace(reloaded.gateway_config.stateless_tx_validator_config.clone());
+        }
+        if let Some(sender) = &self.handles.gateway_stateful_tx_validator_config {
+            sender.send_replace(reloaded.gateway_config.stateful_tx_validator_config.clone());
+        }
+        if let Some(sender) = &self.handles.mempool_config {
+            sender.send_replace(reloaded.mempool_config.clone());
+        }
+        self.config = reloaded;
+        Ok(changed_params)
+    }
+
+    /// Re-loads the configuration on every SIGHUP. Runs indefinitely.
+    pub async fn run(mut self) -> std::io::Result<()> {
+        let mut hangups = signal(SignalKind::hangup())?;
+        while hangups.recv().await.is_some() {
+            info!("Received SIGHUP, re-loading the configuration.");
+            let result = MempoolNodeConfig::load_and_process(self.args.clone())
+                .and_then(|reloaded| self.apply(reloaded));
+            match result {
+                Ok(changed_params) => {
+                    info!("Re-loaded the configuration. Changed params: {:?}.", changed_params)
+                }
+                Err(err) => error!("Failed to re-load the configuration: {}", err),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use assert_matches::assert_matches;
+use papyrus_config::ConfigError;
+use tokio::sync::watch;
+
+use crate::config::MempoolNodeConfig;
+use crate::config_reload::{ConfigReloadHandles, ConfigReloader};
+
+#[test]
+fn apply_hot_reloadable_change() {
+    let config = MempoolNodeConfig::default();
+    let (sender, receiver) =
+        watch::channel(config.gateway_config.stateless_tx_validator_config.clone());
+    let handles = ConfigReloadHandles {
+        gateway_stateless_tx_validator_config: Some(Arc::new(sender)),
+        ..Default::default()
+    };
+    let mut reloader = ConfigReloader::new(config.clone(), vec![], handles);
+
+    let mut reloaded = config;
+    reloaded
+        .gateway_config
+        .stateless_tx_validator_config
+        .stateless_validation
+        .max_calldata_length += 1;
+    assert_eq!(
+        reloader.apply(reloaded.clone()).unwrap(),
+        vec![
+            "gateway_config.stateless_tx_validator_config.stateless_validation.max_calldata_length"
+                .to_owned()
+        ]
+    );
+    assert_eq!(*receiver.borrow(), reloaded.gateway_config.stateless_tx_validator_config);
+    assert_eq!(reloader.config(), &reloaded);
+}
+
+#[test]
+fn apply_mempool_and_gas_price_floor_changes() {
+    let config = MempoolNodeConfig::default();
+    let (stateful_sender, stateful_receiver) =
+        watch::channel(config.gateway_config.stateful_tx_validator_config.clone());
+    let (mempool_sender, mempool_receiver) = watch::channel(config.mempool_config.clone());
+    let handles = ConfigReloadHandles {
+        gateway_stateful_tx_validator_config: Some(Arc::new(stateful_sender)),
+        mempool_config: Some(Arc::new(mempool_sender)),
+        ..Default::default()
+    };
+    let mut reloader = ConfigReloader::new(config.clone(), vec![], handles);
+
+    let mut reloaded = config;
+    reloaded.gateway_config.stateful_tx_validator_config.gas_price_floor_config.enabled ^= true;
+    reloaded.mempool_config.max_txs_per_sender_per_block += 1;
+    assert_eq!(
+        reloader.apply(reloaded.clone()).unwrap(),
+        vec![
+            "gateway_config.stateful_tx_validator_config.gas_price_floor_config.enabled".to_owned(),
+            "mempool_config.max_txs_per_sender_per_block".to_owned(),
+        ]
+    );
+    assert_eq!(*stateful_receiver.borrow(), reloaded.gateway_config.stateful_tx_validator_config);
+    assert_eq!(*mempool_receiver.borrow(), reloaded.mempool_config);
+}
+
+#[test]
+fn reject_immutable_change() {
+    let config = MempoolNodeConfig::default();
+    let mut reloader = ConfigReloader::new(config.clone(), vec![], ConfigReloadHandles::default());
+
+    let mut reloaded = config.clone();
+    reloaded
+        .gateway_config
+        .stateless_tx_validator_config
+        .stateless_validation
+        .max_calldata_length += 1;
+    reloaded.gateway_config.network_config.port += 1;
+    reloaded.mempool_config.bundle_deploy_account_txs ^= true;
+    assert_matches!(
+        reloader.apply(reloaded),
+        Err(ConfigError::ImmutableParamsChanged { param_paths })
+        if param_paths == vec![
+            "gateway_config.network_config.port".to_owned(),
+            "mempool_config.bundle_deploy_account_txs".to_owned(),
+        ]
+    );
+    assert_eq!(reloader.config(), &config);
+}
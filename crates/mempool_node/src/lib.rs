@@ -1,6 +1,9 @@
+pub mod admin_commands;
 pub mod communication;
 pub mod components;
 pub mod config;
+pub mod config_reload;
+pub mod multi_chain;
 pub mod servers;
 pub mod utils;
 pub mod version;
@@ -1,33 +1,45 @@
 use std::env::args;
 use std::process::exit;
+use std::sync::Arc;
 
 use papyrus_config::validators::config_validate;
 use papyrus_config::ConfigError;
-use starknet_mempool_infra::trace_util::configure_tracing;
+use starknet_mempool_infra::trace_util::{configure_tracing_with_export, shutdown_tracing};
+use starknet_mempool_node::admin_commands::NodeAdminCommandHandler;
 use starknet_mempool_node::config::MempoolNodeConfig;
-use starknet_mempool_node::servers::run_component_servers;
-use starknet_mempool_node::utils::create_clients_servers_from_config;
+use starknet_mempool_node::multi_chain::Chain;
+use starknet_mempool_node::servers::{shutdown_signal, spawn_monitoring_servers};
 use tracing::{error, info};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    configure_tracing();
-
-    let config = MempoolNodeConfig::load_and_process(args().collect());
+    let args: Vec<String> = args().collect();
+    let config = MempoolNodeConfig::load_and_process(args.clone());
     if let Err(ConfigError::CommandInput(clap_err)) = config {
         clap_err.exit();
     }
 
     let config = config?;
+    // Tracing depends on the config, so it's configured once the config is loaded.
+    let log_level_handle = configure_tracing_with_export(&config.tracing_config)?;
     if let Err(error) = config_validate(&config) {
         error!("{}", error);
         exit(1);
     }
 
-    let (_, servers) = create_clients_servers_from_config(&config);
+    let chain = Chain { config, args };
+    let admin_command_handler = NodeAdminCommandHandler::default();
+    spawn_monitoring_servers(
+        &chain.config,
+        log_level_handle,
+        Arc::new(admin_command_handler.clone()),
+        Some(&chain.chain_id()),
+    )?;
 
     info!("Starting components!");
-    run_component_servers(&config, servers).await?;
+    let result = chain.run(shutdown_signal(), admin_command_handler).await;
+    shutdown_tracing();
+    result?;
 
     Ok(())
 }
@@ -0,0 +1,252 @@
+//! Runs several independent chains in one node process, e.g. for an operator that hosts many
+//! small appchains.
+//!
+//! Each chain has a config of its own, with a distinct chain id, and its components listen on
+//! addresses of their own. Each chain runs on a runtime of its own, whose threads label the metrics
+//! they record with the chain id. A chain whose components crash doesn't stop the other chains.
+//!
+//! The services of the process, i.e. tracing, the admin server and the metrics server, serve all
+//! the chains, so the chains must configure them the same.
+
+#[cfg(test)]
+#[path = "multi_chain_test.rs"]
+mod multi_chain_test;
+
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+
+use anyhow::{anyhow, bail};
+use futures::future::join_all;
+use futures::Future;
+use papyrus_config::validators::config_validate;
+use papyrus_config::ConfigError;
+use starknet_mempool_infra::metrics::set_thread_chain_id;
+use tokio::sync::{oneshot, watch};
+use tracing::{error, info, info_span, Instrument, Span};
+
+use crate::admin_commands::NodeAdminCommandHandler;
+use crate::communication::remote_config;
+use crate::config::{ComponentExecutionConfig, LocationType, MempoolNodeConfig};
+use crate::config_reload::ConfigReloader;
+use crate::servers::run_component_servers;
+use crate::utils::create_clients_servers_from_config;
+
+/// A chain that runs in the node.
+#[derive(Clone, Debug)]
+pub struct Chain {
+    pub config: MempoolNodeConfig,
+    /// The command line arguments the config of the chain is loaded from, for re-loading it.
+    pub args: Vec<String>,
+}
+
+impl Chain {
+    /// Loads the config of a chain from the default config, overridden by the given config file.
+    pub fn load(config_file: &str) -> Result<Self, ConfigError> {
+        let args =
+            vec!["mempool_node".to_owned(), "--config_file".to_owned(), config_file.to_owned()];
+        let config = MempoolNodeConfig::load_and_process(args.clone())?;
+        Ok(Self { config, args })
+    }
+
+    pub fn chain_id(&self) -> String {
+        self.config.gateway_config.stateful_tx_validator_config.chain_info.chain_id.to_string()
+    }
+
+    /// Runs the components of the chain until they stop; see [`run_component_servers`]. The config
+    /// of the chain is re-loaded on SIGHUP, and its components are registered with the admin
+    /// command handler.
+    pub async fn run(
+        self,
+        shutdown_signal: impl Future<Output = ()>,
+        admin_command_handler: NodeAdminCommandHandler,
+    ) -> anyhow::Result<()> {
+        let (clients, servers, config_reload_handles) =
+            create_clients_servers_from_config(&self.config);
+        admin_command_handler.register_chain(self.chain_id(), clients);
+
+        let config_reloader =
+            ConfigReloader::new(self.config.clone(), self.args, config_reload_handles);
+        tokio::spawn(
+            async move {
+                if let Err(err) = config_reloader.run().await {
+                    error!("Config reloading stopped: {}", err);
+                }
+            }
+            .instrument(Span::current()),
+        );
+
+        run_component_servers(&self.config, servers, shutdown_signal).await
+    }
+}
+
+/// Validates the config of each chain, and that the chains can run side by side: their chain ids
+/// are distinct, their components don't listen on the same address or share a cache directory,
+/// and they configure the services of the process the same.
+pub fn validate_chains(chains: &[Chain]) -> anyhow::Result<()> {
+    let Some(first_chain) = chains.first() else {
+        bail!("No chain to run.");
+    };
+
+    let mut chain_ids = HashSet::new();
+    let mut addresses: Vec<(String, SocketAddr)> = Vec::new();
+    let mut cache_dirs = HashMap::new();
+    for chain in chains {
+        let chain_id = chain.chain_id();
+        config_validate(&chain.config)
+            .map_err(|err| anyhow!("Invalid config of chain {chain_id}: {err}"))?;
+        if !chain_ids.insert(chain_id.clone()) {
+            bail!("Chain {chain_id} is configured more than once.");
+        }
+        if let Some(service) = differing_process_service(&first_chain.config, &chain.config) {
+            bail!(
+                "Chains {} and {chain_id} configure the {service} differently.",
+                first_chain.chain_id()
+            );
+        }
+
+        for address in listening_addresses(&chain.config) {
+            if let Some((other_chain_id, _)) =
+                addresses.iter().find(|(_, other)| addresses_conflict(address, *other))
+            {
+                bail!("Chains {other_chain_id} and {chain_id} both listen on {address}.");
+            }
+            addresses.push((chain_id.clone(), address));
+        }
+
+        if let Some(cache_dir) = &chain.config.compilation_service_config.cache_dir {
+            if let Some(other_chain_id) = cache_dirs.insert(cache_dir.clone(), chain_id.clone()) {
+                bail!(
+                    "Chains {other_chain_id} and {chain_id} both cache the compiled classes in \
+                     {cache_dir:?}."
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Runs the given chains until all of them stop, each on a runtime of its own. The chains stop once
+/// `shutdown_signal` resolves.
+pub async fn run_chains(
+    chains: Vec<Chain>,
+    shutdown_signal: impl Future<Output = ()>,
+    admin_command_handler: NodeAdminCommandHandler,
+) -> anyhow::Result<()> {
+    let chain_ids: Vec<String> = chains.iter().map(Chain::chain_id).collect();
+    info!("Starting chains: {:?}.", chain_ids);
+
+    let (shutdown_sender, shutdown_receiver) = watch::channel(false);
+    let runs = chains
+        .into_iter()
+        .map(|chain| spawn_chain(chain, shutdown_receiver.clone(), admin_command_handler.clone()))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let mut runs = Box::pin(join_all(runs));
+    let results = tokio::select! {
+        results = &mut runs => results,
+        () = shutdown_signal => {
+            shutdown_sender.send_replace(true);
+            runs.await
+        }
+    };
+    let results = results
+        .into_iter()
+        .map(|result| result.unwrap_or_else(|_| Err(anyhow!("The chain's thread panicked."))));
+
+    let failed_chain_ids: Vec<&String> = chain_ids
+        .iter()
+        .zip(results)
+        .filter_map(|(chain_id, result)| {
+            let err = result.err()?;
+            error!("Chain {chain_id} stopped: {err}");
+            Some(chain_id)
+        })
+        .collect();
+    if !failed_chain_ids.is_empty() {
+        bail!("Chains {failed_chain_ids:?} stopped due to an error.");
+    }
+    Ok(())
+}
+
+// Runs the chain on a thread and a runtime of its own, whose threads label the metrics they record
+// with the chain id. Returns a receiver of the result of the run.
+fn spawn_chain(
+    chain: Chain,
+    mut shutdown_receiver: watch::Receiver<bool>,
+    admin_command_handler: NodeAdminCommandHandler,
+) -> anyhow::Result<oneshot::Receiver<anyhow::Result<()>>> {
+    let chain_id = chain.chain_id();
+    let (result_sender, result_receiver) = oneshot::channel();
+    std::thread::Builder::new().name(format!("chain-{chain_id}")).spawn(move || {
+        set_thread_chain_id(&chain_id);
+        let thread_chain_id = chain_id.clone();
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .on_thread_start(move || set_thread_chain_id(&thread_chain_id))
+            .build();
+        let result = match runtime {
+            Ok(runtime) => {
+                let shutdown_signal = async move {
+                    // The sender is dropped only once all the chains stopped.
+                    let _ = shutdown_receiver.wait_for(|shutdown| *shutdown).await;
+                };
+                let span = info_span!("chain", chain_id = %chain_id);
+                runtime.block_on(chain.run(shutdown_signal, admin_command_handler).instrument(span))
+            }
+            Err(err) => Err(anyhow!("Failed to build the runtime of chain {chain_id}: {err}")),
+        };
+        // `run_chains` waits for the results of all the chains.
+        let _ = result_sender.send(result);
+    })?;
+    Ok(result_receiver)
+}
+
+// The name of a service of the process that the given chain configs configure differently, if any.
+fn differing_process_service(
+    config: &MempoolNodeConfig,
+    other_config: &MempoolNodeConfig,
+) -> Option<&'static str> {
+    if config.tracing_config != other_config.tracing_config {
+        return Some("tracing");
+    }
+    if config.admin_config != other_config.admin_config {
+        return Some("admin server");
+    }
+    if config.metrics_config != other_config.metrics_config {
+        return Some("metrics server");
+    }
+    None
+}
+
+// The addresses the components of the chain listen on.
+fn listening_addresses(config: &MempoolNodeConfig) -> Vec<SocketAddr> {
+    let mut addresses = Vec::new();
+    if config.components.gateway.execute {
+        let network_config = &config.gateway_config.network_config;
+        addresses.push(SocketAddr::new(network_config.ip, network_config.port));
+    }
+    let components = &config.components;
+    // The gateway has no remote server.
+    let served_components =
+        [&components.batcher, &components.consensus_manager, &components.mempool];
+    addresses.extend(
+        served_components.into_iter().filter(|component| is_remotely_served(component)).map(
+            |component| {
+                let remote_config = remote_config(component);
+                SocketAddr::new(remote_config.ip, remote_config.port)
+            },
+        ),
+    );
+    addresses
+}
+
+fn is_remotely_served(component: &ComponentExecutionConfig) -> bool {
+    component.execute && component.location == LocationType::Remote
+}
+
+// Whether listening on both addresses fails, e.g. on `0.0.0.0:8080` and `127.0.0.1:8080`.
+fn addresses_conflict(address: SocketAddr, other: SocketAddr) -> bool {
+    address.port() == other.port()
+        && (address.ip() == other.ip()
+            || address.ip().is_unspecified()
+            || other.ip().is_unspecified())
+}
@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use rstest::rstest;
+use starknet_api::core::ChainId;
+
+use crate::config::MempoolNodeConfig;
+use crate::multi_chain::{validate_chains, Chain};
+
+fn chain(chain_id: &str, gateway_port: u16) -> Chain {
+    let mut config = MempoolNodeConfig::default();
+    config.gateway_config.stateful_tx_validator_config.chain_info.chain_id =
+        ChainId::Other(chain_id.to_owned());
+    config.gateway_config.network_config.port = gateway_port;
+    Chain { config, args: vec![] }
+}
+
+#[test]
+fn independent_chains() {
+    validate_chains(&[chain("A", 8080), chain("B", 8081)]).unwrap();
+}
+
+#[rstest]
+#[case::no_chains(vec![], "No chain to run.")]
+#[case::same_chain_id(
+    vec![chain("A", 8080), chain("A", 8081)],
+    "Chain A is configured more than once."
+)]
+#[case::same_address(
+    vec![chain("A", 8080), chain("B", 8080)],
+    "Chains A and B both listen on 0.0.0.0:8080."
+)]
+fn conflicting_chains(#[case] chains: Vec<Chain>, #[case] expected_error: &str) {
+    assert_eq!(validate_chains(&chains).unwrap_err().to_string(), expected_error);
+}
+
+#[test]
+fn conflicting_addresses() {
+    let mut other_chain = chain("B", 8080);
+    other_chain.config.gateway_config.network_config.ip = "127.0.0.1".parse().unwrap();
+    assert!(validate_chains(&[chain("A", 8080), other_chain.clone()]).is_err());
+
+    // Unless the gateway doesn't run.
+    other_chain.config.components.gateway.execute = false;
+    validate_chains(&[chain("A", 8080), other_chain]).unwrap();
+}
+
+#[test]
+fn shared_cache_dir() {
+    let cache_dir = Some(PathBuf::from("./compiled_classes"));
+    let mut chains = [chain("A", 8080), chain("B", 8081)];
+    for chain in &mut chains {
+        chain.config.compilation_service_config.cache_dir = cache_dir.clone();
+    }
+    assert!(validate_chains(&chains).is_err());
+}
+
+#[test]
+fn differently_configured_process_services() {
+    let mut other_chain = chain("B", 8081);
+    other_chain.config.metrics_config.port = 8083;
+    assert_eq!(
+        validate_chains(&[chain("A", 8080), other_chain]).unwrap_err().to_string(),
+        "Chains A and B configure the metrics server differently."
+    );
+}
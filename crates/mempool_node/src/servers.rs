@@ -1,26 +1,41 @@
 use std::future::pending;
 use std::pin::Pin;
+use std::sync::Arc;
 
+use async_trait::async_trait;
 use futures::{Future, FutureExt};
-use starknet_batcher::communication::{create_local_batcher_server, LocalBatcherServer};
+use starknet_batcher::communication::{create_local_batcher_server, create_remote_batcher_server};
 use starknet_consensus_manager::communication::{
     create_local_consensus_manager_server,
-    LocalConsensusManagerServer,
+    create_remote_consensus_manager_server,
 };
-use starknet_gateway::communication::{create_gateway_server, GatewayServer};
-use starknet_mempool::communication::{create_mempool_server, MempoolServer};
+use starknet_gateway::communication::create_gateway_server;
+use starknet_mempool::communication::{create_mempool_server, create_remote_mempool_server};
+use starknet_mempool_infra::admin::{run_admin_server, AdminCommandHandler, AdminConfig};
 use starknet_mempool_infra::component_server::ComponentServerStarter;
-use tracing::error;
+use starknet_mempool_infra::component_supervisor::ComponentSupervisor;
+use starknet_mempool_infra::metrics::{
+    install_metrics_recorder,
+    install_multi_chain_metrics_recorder,
+    run_metrics_server,
+    MetricsConfig,
+    PrometheusHandle,
+};
+use starknet_mempool_infra::trace_util::LogLevelHandle;
+use tokio::signal::unix::{signal, SignalKind};
+use tracing::{error, info};
 
-use crate::communication::MempoolNodeCommunication;
+use crate::communication::{remote_config, MempoolNodeCommunication};
 use crate::components::Components;
-use crate::config::MempoolNodeConfig;
+use crate::config::{LocationType, MempoolNodeConfig};
 
+/// The servers of the components executed by the node. A local component is served through its
+/// channel, and a remote one through an HTTP server listening on its configured address.
 pub struct Servers {
-    pub batcher: Option<Box<LocalBatcherServer>>,
-    pub consensus_manager: Option<Box<LocalConsensusManagerServer>>,
-    pub gateway: Option<Box<GatewayServer>>,
-    pub mempool: Option<Box<MempoolServer>>,
+    pub batcher: Option<Box<dyn ComponentServerStarter>>,
+    pub consensus_manager: Option<Box<dyn ComponentServerStarter>>,
+    pub gateway: Option<Box<dyn ComponentServerStarter>>,
+    pub mempool: Option<Box<dyn ComponentServerStarter>>,
 }
 
 pub fn create_servers(
@@ -28,38 +43,75 @@ pub fn create_servers(
     communication: &mut MempoolNodeCommunication,
     components: Components,
 ) -> Servers {
-    let batcher_server = if config.components.batcher.execute {
-        Some(Box::new(create_local_batcher_server(
-            components.batcher.expect("Batcher is not initialized."),
-            communication.take_batcher_rx(),
-        )))
-    } else {
-        None
-    };
-    let consensus_manager_server = if config.components.consensus_manager.execute {
-        Some(Box::new(create_local_consensus_manager_server(
-            components.consensus_manager.expect("Consensus Manager is not initialized."),
-            communication.take_consensus_manager_rx(),
-        )))
-    } else {
-        None
-    };
-    let gateway_server = if config.components.gateway.execute {
-        Some(Box::new(create_gateway_server(
-            components.gateway.expect("Gateway is not initialized."),
-        )))
-    } else {
-        None
-    };
+    let batcher_server: Option<Box<dyn ComponentServerStarter>> =
+        if config.components.batcher.execute {
+            let batcher = components.batcher.expect("Batcher is not initialized.");
+            match config.components.batcher.location {
+                LocationType::Local => Some(Box::new(create_local_batcher_server(
+                    batcher,
+                    communication.take_batcher_rx(),
+                ))),
+                LocationType::Remote => {
+                    let remote_config = remote_config(&config.components.batcher);
+                    Some(Box::new(create_remote_batcher_server(
+                        batcher,
+                        remote_config.ip,
+                        remote_config.port,
+                    )))
+                }
+            }
+        } else {
+            None
+        };
+    let consensus_manager_server: Option<Box<dyn ComponentServerStarter>> =
+        if config.components.consensus_manager.execute {
+            let consensus_manager =
+                components.consensus_manager.expect("Consensus Manager is not initialized.");
+            match config.components.consensus_manager.location {
+                LocationType::Local => Some(Box::new(create_local_consensus_manager_server(
+                    consensus_manager,
+                    communication.take_consensus_manager_rx(),
+                ))),
+                LocationType::Remote => {
+                    let remote_config = remote_config(&config.components.consensus_manager);
+                    Some(Box::new(create_remote_consensus_manager_server(
+                        consensus_manager,
+                        remote_config.ip,
+                        remote_config.port,
+                    )))
+                }
+            }
+        } else {
+            None
+        };
+    let gateway_server: Option<Box<dyn ComponentServerStarter>> =
+        if config.components.gateway.execute {
+            Some(Box::new(create_gateway_server(
+                components.gateway.expect("Gateway is not initialized."),
+            )))
+        } else {
+            None
+        };
 
-    let mempool_server = if config.components.mempool.execute {
-        Some(Box::new(create_mempool_server(
-            components.mempool.expect("Mempool is not initialized."),
-            communication.take_mempool_rx(),
-        )))
-    } else {
-        None
-    };
+    let mempool_server: Option<Box<dyn ComponentServerStarter>> =
+        if config.components.mempool.execute {
+            let mempool = components.mempool.expect("Mempool is not initialized.");
+            match config.components.mempool.location {
+                LocationType::Local => {
+                    Some(Box::new(create_mempool_server(mempool, communication.take_mempool_rx())))
+                }
+                LocationType::Remote => {
+                    let remote_config = remote_config(&config.components.mempool);
+                    Some(Box::new(create_remote_mempool_server(
+                        mempool,
+                        remote_config.ip,
+                        remote_config.port,
+                    )))
+                }
+            }
+        } else {
+            None
+        };
 
     Servers {
         batcher: batcher_server,
@@ -69,62 +121,47 @@ pub fn create_servers(
     }
 }
 
+/// Runs the servers of the components under supervision until either one of them crashes and can't
+/// be restarted, or `shutdown_signal` resolves.
 pub async fn run_component_servers(
     config: &MempoolNodeConfig,
     servers: Servers,
+    shutdown_signal: impl Future<Output = ()>,
 ) -> anyhow::Result<()> {
-    // Batcher server.
-    let batcher_future =
-        get_server_future("Batcher", config.components.batcher.execute, servers.batcher);
+    let mut supervisor = ComponentSupervisor::new(config.supervision_config.clone());
 
-    // Consensus Manager server.
-    let consensus_manager_future = get_server_future(
+    // A component is started after the components it sends requests to, and stopped before them.
+    start_server(&mut supervisor, "Mempool", config.components.mempool.execute, servers.mempool);
+    start_server(&mut supervisor, "Batcher", config.components.batcher.execute, servers.batcher);
+    start_server(
+        &mut supervisor,
         "Consensus Manager",
         config.components.consensus_manager.execute,
         servers.consensus_manager,
     );
+    start_server(&mut supervisor, "Gateway", config.components.gateway.execute, servers.gateway);
 
-    // Gateway server.
-    let gateway_future =
-        get_server_future("Gateway", config.components.gateway.execute, servers.gateway);
-
-    // Mempool server.
-    let mempool_future =
-        get_server_future("Mempool", config.components.mempool.execute, servers.mempool);
-
-    // Start servers.
-    let batcher_handle = tokio::spawn(batcher_future);
-    let consensus_manager_handle = tokio::spawn(consensus_manager_future);
-    let gateway_handle = tokio::spawn(gateway_future);
-    let mempool_handle = tokio::spawn(mempool_future);
-
-    tokio::select! {
-        res = batcher_handle => {
-            error!("Batcher Server stopped.");
-            res?
-        }
-        res = consensus_manager_handle => {
-            error!("Consensus Manager Server stopped.");
-            res?
-        }
-        res = gateway_handle => {
-            error!("Gateway Server stopped.");
-            res?
-        }
-        res = mempool_handle => {
-            error!("Mempool Server stopped.");
-            res?
-        }
-    };
-    error!("Servers ended with unexpected Ok.");
-
+    supervisor.run(shutdown_signal).await?;
     Ok(())
 }
 
+fn start_server(
+    supervisor: &mut ComponentSupervisor,
+    name: &str,
+    execute_flag: bool,
+    server: Option<Box<dyn ComponentServerStarter>>,
+) {
+    if !execute_flag {
+        return;
+    }
+    let server = server.unwrap_or_else(|| panic!("{} component is not initialized.", name));
+    supervisor.start(name, server);
+}
+
 pub fn get_server_future(
     name: &str,
     execute_flag: bool,
-    server: Option<Box<impl ComponentServerStarter + 'static>>,
+    server: Option<Box<dyn ComponentServerStarter>>,
 ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
     let server_future = match execute_flag {
         true => {
@@ -138,3 +175,86 @@ pub fn get_server_future(
     };
     server_future
 }
+
+/// Spawns the admin and the metrics servers of the node, if the config says so, under a supervisor
+/// of their own. The metrics are labeled by `chain_id`, or, if it's [None], by the chain of the
+/// thread that records them; see [`install_multi_chain_metrics_recorder`]. The monitoring servers
+/// serve the whole process, so once they can't be restarted, the chains keep running without them.
+pub fn spawn_monitoring_servers(
+    config: &MempoolNodeConfig,
+    log_level_handle: LogLevelHandle,
+    admin_command_handler: Arc<dyn AdminCommandHandler>,
+    chain_id: Option<&str>,
+) -> anyhow::Result<()> {
+    let mut supervisor = ComponentSupervisor::new(config.supervision_config.clone());
+    if config.admin_config.run_admin_server {
+        supervisor.start(
+            "Admin",
+            Box::new(AdminServer {
+                config: config.admin_config.clone(),
+                log_level_handle,
+                admin_command_handler,
+            }),
+        );
+    }
+    if config.metrics_config.collect_metrics {
+        let prometheus_handle = match chain_id {
+            Some(chain_id) => install_metrics_recorder(chain_id)?,
+            None => install_multi_chain_metrics_recorder()?,
+        };
+        supervisor.start(
+            "Metrics",
+            Box::new(MetricsServer { config: config.metrics_config.clone(), prometheus_handle }),
+        );
+    }
+
+    tokio::spawn(async move {
+        if let Err(err) = supervisor.run(pending()).await {
+            error!("Monitoring stopped: {}", err);
+        }
+    });
+    Ok(())
+}
+
+struct AdminServer {
+    config: AdminConfig,
+    log_level_handle: LogLevelHandle,
+    admin_command_handler: Arc<dyn AdminCommandHandler>,
+}
+
+#[async_trait]
+impl ComponentServerStarter for AdminServer {
+    async fn start(&mut self) {
+        let server = run_admin_server(
+            &self.config,
+            self.log_level_handle.clone(),
+            self.admin_command_handler.clone(),
+        );
+        if let Err(err) = server.await {
+            error!("Admin server stopped: {}", err);
+        }
+    }
+}
+
+struct MetricsServer {
+    config: MetricsConfig,
+    prometheus_handle: PrometheusHandle,
+}
+
+#[async_trait]
+impl ComponentServerStarter for MetricsServer {
+    async fn start(&mut self) {
+        if let Err(err) = run_metrics_server(&self.config, self.prometheus_handle.clone()).await {
+            error!("Metrics server stopped: {}", err);
+        }
+    }
+}
+
+/// Resolves once the node is asked to shut down, by either SIGINT or SIGTERM.
+pub async fn shutdown_signal() {
+    let mut terminations = signal(SignalKind::terminate()).expect("Failed to listen to SIGTERM.");
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => info!("Received SIGINT."),
+        _ = terminations.recv() => info!("Received SIGTERM."),
+    }
+}
@@ -1,15 +1,27 @@
 use crate::communication::{create_node_channels, create_node_clients, MempoolNodeClients};
 use crate::components::create_components;
 use crate::config::MempoolNodeConfig;
+use crate::config_reload::ConfigReloadHandles;
 use crate::servers::{create_servers, Servers};
 
 pub fn create_clients_servers_from_config(
     config: &MempoolNodeConfig,
-) -> (MempoolNodeClients, Servers) {
-    let mut channels = create_node_channels();
+) -> (MempoolNodeClients, Servers, ConfigReloadHandles) {
+    let mut channels = create_node_channels(config);
     let clients = create_node_clients(config, &mut channels);
-    let components = create_components(config, &clients);
+    let mut components = create_components(config, &clients);
+    let config_reload_handles = ConfigReloadHandles {
+        gateway_stateless_tx_validator_config: components
+            .gateway
+            .as_ref()
+            .map(|gateway| gateway.stateless_tx_validator_config_sender()),
+        gateway_stateful_tx_validator_config: components
+            .gateway
+            .as_ref()
+            .map(|gateway| gateway.stateful_tx_validator_config_sender()),
+        mempool_config: components.mempool.as_mut().map(|mempool| mempool.config_sender()),
+    };
     let servers = create_servers(config, &mut channels, components);
 
-    (clients, servers)
+    (clients, servers, config_reload_handles)
 }
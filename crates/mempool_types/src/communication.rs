@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -5,20 +6,24 @@ use mockall::predicate::*;
 use mockall::*;
 use papyrus_proc_macros::handle_response_variants;
 use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::ContractAddress;
 use starknet_api::executable_transaction::Transaction;
+use starknet_api::transaction::TransactionHash;
 use starknet_mempool_infra::component_client::{
     ClientError,
+    GrpcComponentClient,
     LocalComponentClient,
-    RemoteComponentClient,
 };
 use starknet_mempool_infra::component_definitions::ComponentRequestAndResponseSender;
 use thiserror::Error;
 
 use crate::errors::MempoolError;
-use crate::mempool_types::MempoolInput;
+use crate::mempool_types::{AccountState, LeaseId, MempoolInput, TransactionStatus};
 
 pub type LocalMempoolClientImpl = LocalComponentClient<MempoolRequest, MempoolResponse>;
-pub type RemoteMempoolClientImpl = RemoteComponentClient<MempoolRequest, MempoolResponse>;
+/// A client of a standalone mempool, which is served over gRPC.
+pub type RemoteMempoolClientImpl = GrpcComponentClient<MempoolRequest, MempoolResponse>;
 pub type MempoolResult<T> = Result<T, MempoolError>;
 pub type MempoolClientResult<T> = Result<T, MempoolClientError>;
 pub type MempoolRequestAndResponseSender =
@@ -32,18 +37,67 @@ pub type SharedMempoolClient = Arc<dyn MempoolClient>;
 pub trait MempoolClient: Send + Sync {
     async fn add_tx(&self, mempool_input: MempoolInput) -> MempoolClientResult<()>;
     async fn get_txs(&self, n_txs: usize) -> MempoolClientResult<Vec<Transaction>>;
+    /// Returns up to `n_txs` of the transactions `get_txs` would return, in the same order, without
+    /// removing them from the mempool.
+    async fn get_txs_snapshot(&self, n_txs: usize) -> MempoolClientResult<Vec<Transaction>>;
+    /// Opens a lease for the block of the given height, through which several batchers can take
+    /// transactions from the mempool concurrently.
+    async fn open_lease(&self, height: BlockNumber) -> MempoolClientResult<LeaseId>;
+    /// Like `get_txs`, but the returned transactions are only locked for the lease: if the lease is
+    /// released, the transactions that weren't committed become available again. Committing the
+    /// block of the lease's height, or of a later one, ends the lease.
+    async fn lease_txs(
+        &self,
+        lease_id: LeaseId,
+        n_txs: usize,
+    ) -> MempoolClientResult<Vec<Transaction>>;
+    /// Ends the lease, e.g. when its proposal is abandoned.
+    async fn release_lease(&self, lease_id: LeaseId) -> MempoolClientResult<()>;
+    /// Updates the mempool with the state of the accounts that were changed by the committed block
+    /// of the given height.
+    async fn commit_block(
+        &self,
+        height: BlockNumber,
+        state_changes: HashMap<ContractAddress, AccountState>,
+    ) -> MempoolClientResult<()>;
+    /// Sets the minimal L2 gas price of the transactions that are eligible for sequencing.
+    async fn update_gas_price_threshold(&self, threshold: u128) -> MempoolClientResult<()>;
+    /// Returns the status of the transaction, or `None` if the mempool doesn't know of it.
+    async fn get_tx_status(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> MempoolClientResult<Option<TransactionStatus>>;
+    /// Drops the transactions that wait in the mempool, and returns their number. Transactions that
+    /// were handed out for sequencing aren't dropped.
+    async fn flush(&self) -> MempoolClientResult<usize>;
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum MempoolRequest {
     AddTransaction(MempoolInput),
     GetTransactions(usize),
+    GetTransactionsSnapshot(usize),
+    OpenLease(BlockNumber),
+    LeaseTransactions(LeaseId, usize),
+    ReleaseLease(LeaseId),
+    CommitBlock(BlockNumber, HashMap<ContractAddress, AccountState>),
+    UpdateGasPriceThreshold(u128),
+    GetTransactionStatus(TransactionHash),
+    Flush,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum MempoolResponse {
     AddTransaction(MempoolResult<()>),
     GetTransactions(MempoolResult<Vec<Transaction>>),
+    GetTransactionsSnapshot(MempoolResult<Vec<Transaction>>),
+    OpenLease(MempoolResult<LeaseId>),
+    LeaseTransactions(MempoolResult<Vec<Transaction>>),
+    ReleaseLease(MempoolResult<()>),
+    CommitBlock(MempoolResult<()>),
+    UpdateGasPriceThreshold(MempoolResult<()>),
+    GetTransactionStatus(MempoolResult<Option<TransactionStatus>>),
+    Flush(MempoolResult<usize>),
 }
 
 #[derive(Clone, Debug, Error)]
@@ -72,6 +126,85 @@ impl MempoolClient for LocalMempoolClientImpl {
             MempoolError
         )
     }
+
+    async fn get_txs_snapshot(&self, n_txs: usize) -> MempoolClientResult<Vec<Transaction>> {
+        let request = MempoolRequest::GetTransactionsSnapshot(n_txs);
+        let response = self.send(request).await;
+        handle_response_variants!(
+            MempoolResponse,
+            GetTransactionsSnapshot,
+            MempoolClientError,
+            MempoolError
+        )
+    }
+
+    async fn open_lease(&self, height: BlockNumber) -> MempoolClientResult<LeaseId> {
+        let request = MempoolRequest::OpenLease(height);
+        let response = self.send(request).await;
+        handle_response_variants!(MempoolResponse, OpenLease, MempoolClientError, MempoolError)
+    }
+
+    async fn lease_txs(
+        &self,
+        lease_id: LeaseId,
+        n_txs: usize,
+    ) -> MempoolClientResult<Vec<Transaction>> {
+        let request = MempoolRequest::LeaseTransactions(lease_id, n_txs);
+        let response = self.send(request).await;
+        handle_response_variants!(
+            MempoolResponse,
+            LeaseTransactions,
+            MempoolClientError,
+            MempoolError
+        )
+    }
+
+    async fn release_lease(&self, lease_id: LeaseId) -> MempoolClientResult<()> {
+        let request = MempoolRequest::ReleaseLease(lease_id);
+        let response = self.send(request).await;
+        handle_response_variants!(MempoolResponse, ReleaseLease, MempoolClientError, MempoolError)
+    }
+
+    async fn commit_block(
+        &self,
+        height: BlockNumber,
+        state_changes: HashMap<ContractAddress, AccountState>,
+    ) -> MempoolClientResult<()> {
+        let request = MempoolRequest::CommitBlock(height, state_changes);
+        let response = self.send(request).await;
+        handle_response_variants!(MempoolResponse, CommitBlock, MempoolClientError, MempoolError)
+    }
+
+    async fn update_gas_price_threshold(&self, threshold: u128) -> MempoolClientResult<()> {
+        let request = MempoolRequest::UpdateGasPriceThreshold(threshold);
+        let response = self.send(request).await;
+        handle_response_variants!(
+            MempoolResponse,
+            UpdateGasPriceThreshold,
+            MempoolClientError,
+            MempoolError
+        )
+    }
+
+    async fn get_tx_status(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> MempoolClientResult<Option<TransactionStatus>> {
+        let request = MempoolRequest::GetTransactionStatus(tx_hash);
+        let response = self.send(request).await;
+        handle_response_variants!(
+            MempoolResponse,
+            GetTransactionStatus,
+            MempoolClientError,
+            MempoolError
+        )
+    }
+
+    async fn flush(&self) -> MempoolClientResult<usize> {
+        let request = MempoolRequest::Flush;
+        let response = self.send(request).await;
+        handle_response_variants!(MempoolResponse, Flush, MempoolClientError, MempoolError)
+    }
 }
 
 #[async_trait]
@@ -92,4 +225,83 @@ impl MempoolClient for RemoteMempoolClientImpl {
             MempoolError
         )
     }
+
+    async fn get_txs_snapshot(&self, n_txs: usize) -> MempoolClientResult<Vec<Transaction>> {
+        let request = MempoolRequest::GetTransactionsSnapshot(n_txs);
+        let response = self.send(request).await?;
+        handle_response_variants!(
+            MempoolResponse,
+            GetTransactionsSnapshot,
+            MempoolClientError,
+            MempoolError
+        )
+    }
+
+    async fn open_lease(&self, height: BlockNumber) -> MempoolClientResult<LeaseId> {
+        let request = MempoolRequest::OpenLease(height);
+        let response = self.send(request).await?;
+        handle_response_variants!(MempoolResponse, OpenLease, MempoolClientError, MempoolError)
+    }
+
+    async fn lease_txs(
+        &self,
+        lease_id: LeaseId,
+        n_txs: usize,
+    ) -> MempoolClientResult<Vec<Transaction>> {
+        let request = MempoolRequest::LeaseTransactions(lease_id, n_txs);
+        let response = self.send(request).await?;
+        handle_response_variants!(
+            MempoolResponse,
+            LeaseTransactions,
+            MempoolClientError,
+            MempoolError
+        )
+    }
+
+    async fn release_lease(&self, lease_id: LeaseId) -> MempoolClientResult<()> {
+        let request = MempoolRequest::ReleaseLease(lease_id);
+        let response = self.send(request).await?;
+        handle_response_variants!(MempoolResponse, ReleaseLease, MempoolClientError, MempoolError)
+    }
+
+    async fn commit_block(
+        &self,
+        height: BlockNumber,
+        state_changes: HashMap<ContractAddress, AccountState>,
+    ) -> MempoolClientResult<()> {
+        let request = MempoolRequest::CommitBlock(height, state_changes);
+        let response = self.send(request).await?;
+        handle_response_variants!(MempoolResponse, CommitBlock, MempoolClientError, MempoolError)
+    }
+
+    async fn update_gas_price_threshold(&self, threshold: u128) -> MempoolClientResult<()> {
+        let request = MempoolRequest::UpdateGasPriceThreshold(threshold);
+        let response = self.send(request).await?;
+        handle_response_variants!(
+            MempoolResponse,
+            UpdateGasPriceThreshold,
+            MempoolClientError,
+            MempoolError
+        )
+    }
+
+    async fn get_tx_status(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> MempoolClientResult<Option<TransactionStatus>> {
+        let request = MempoolRequest::GetTransactionStatus(tx_hash);
+        let response = self.send(request).await?;
+        handle_response_variants!(
+            MempoolResponse,
+            GetTransactionStatus,
+            MempoolClientError,
+            MempoolError
+        )
+    }
+
+    async fn flush(&self) -> MempoolClientResult<usize> {
+        let request = MempoolRequest::Flush;
+        let response = self.send(request).await?;
+        handle_response_variants!(MempoolResponse, Flush, MempoolClientError, MempoolError)
+    }
 }
@@ -3,12 +3,18 @@ use starknet_api::core::{ContractAddress, Nonce};
 use starknet_api::transaction::TransactionHash;
 use thiserror::Error;
 
+use crate::mempool_types::LeaseId;
+
 #[derive(Clone, Debug, Error, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MempoolError {
     #[error("Duplicate transaction, sender address: {address}, nonce: {:?}", nonce)]
     DuplicateNonce { address: ContractAddress, nonce: Nonce },
     #[error("Duplicate transaction, with hash: {tx_hash}")]
     DuplicateTransaction { tx_hash: TransactionHash },
+    #[error(
+        "Lease {lease_id} not found; it was released, or the block of its height was committed."
+    )]
+    LeaseNotFound { lease_id: LeaseId },
     #[error("Transaction with hash: {tx_hash} not found")]
     TransactionNotFound { tx_hash: TransactionHash },
     // TODO(Mohammad): Consider using `StarknetApiError` once it implements `PartialEq`.
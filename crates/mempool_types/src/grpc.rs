@@ -0,0 +1,391 @@
+//! The messages of the mempool's gRPC service, see `mempool.proto` in `papyrus_protobuf`, through
+//! which a standalone mempool is shared by the nodes that run its gateways and batchers.
+
+#[cfg(test)]
+#[path = "grpc_test.rs"]
+mod grpc_test;
+
+use std::collections::HashMap;
+
+use papyrus_protobuf::converters::ProtobufConversionError;
+use papyrus_protobuf::protobuf;
+use papyrus_protobuf::protobuf::mempool_get_transaction_status_response::Status as ProtobufStatus;
+use prost::Message;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ContractAddress, Nonce};
+use starknet_api::executable_transaction::Transaction;
+use starknet_api::hash::StarkHash;
+use starknet_api::transaction::TransactionHash;
+use starknet_mempool_infra::component_definitions::{GrpcRequest, GrpcResponse, GrpcStatus};
+
+use crate::communication::{MempoolRequest, MempoolResponse};
+use crate::errors::MempoolError;
+use crate::mempool_types::{Account, AccountState, MempoolInput, MempoolResult, TransactionStatus};
+
+const ADD_TRANSACTION_PATH: &str = "/Mempool/AddTransaction";
+const GET_TRANSACTIONS_PATH: &str = "/Mempool/GetTransactions";
+const GET_TRANSACTIONS_SNAPSHOT_PATH: &str = "/Mempool/GetTransactionsSnapshot";
+const OPEN_LEASE_PATH: &str = "/Mempool/OpenLease";
+const LEASE_TRANSACTIONS_PATH: &str = "/Mempool/LeaseTransactions";
+const RELEASE_LEASE_PATH: &str = "/Mempool/ReleaseLease";
+const COMMIT_BLOCK_PATH: &str = "/Mempool/CommitBlock";
+const GET_TRANSACTION_STATUS_PATH: &str = "/Mempool/GetTransactionStatus";
+const FLUSH_PATH: &str = "/Mempool/Flush";
+
+impl GrpcRequest for MempoolRequest {
+    fn method_path(&self) -> &'static str {
+        match self {
+            MempoolRequest::AddTransaction(_) => ADD_TRANSACTION_PATH,
+            MempoolRequest::GetTransactions(_) => GET_TRANSACTIONS_PATH,
+            MempoolRequest::GetTransactionsSnapshot(_) => GET_TRANSACTIONS_SNAPSHOT_PATH,
+            MempoolRequest::OpenLease(_) => OPEN_LEASE_PATH,
+            MempoolRequest::LeaseTransactions(_, _) => LEASE_TRANSACTIONS_PATH,
+            MempoolRequest::ReleaseLease(_) => RELEASE_LEASE_PATH,
+            MempoolRequest::CommitBlock(..) => COMMIT_BLOCK_PATH,
+            MempoolRequest::GetTransactionStatus(_) => GET_TRANSACTION_STATUS_PATH,
+            MempoolRequest::Flush => FLUSH_PATH,
+        }
+    }
+
+    fn encode_message(&self) -> Vec<u8> {
+        match self {
+            MempoolRequest::AddTransaction(MempoolInput { tx, account }) => {
+                protobuf::MempoolAddTransactionRequest {
+                    transaction: Some(tx.clone().into()),
+                    account: Some(account_state_to_protobuf(account.sender_address, account.state)),
+                }
+                .encode_to_vec()
+            }
+            MempoolRequest::GetTransactions(n_txs)
+            | MempoolRequest::GetTransactionsSnapshot(n_txs) => {
+                protobuf::MempoolGetTransactionsRequest { n_txs: *n_txs as u64 }.encode_to_vec()
+            }
+            MempoolRequest::OpenLease(height) => {
+                protobuf::MempoolOpenLeaseRequest { height: height.0 }.encode_to_vec()
+            }
+            MempoolRequest::LeaseTransactions(lease_id, n_txs) => {
+                protobuf::MempoolLeaseTransactionsRequest {
+                    lease_id: *lease_id,
+                    n_txs: *n_txs as u64,
+                }
+                .encode_to_vec()
+            }
+            MempoolRequest::ReleaseLease(lease_id) => {
+                protobuf::MempoolReleaseLeaseRequest { lease_id: *lease_id }.encode_to_vec()
+            }
+            MempoolRequest::CommitBlock(height, state_changes) => {
+                protobuf::MempoolCommitBlockRequest {
+                    state_changes: state_changes
+                        .iter()
+                        .map(|(address, state)| account_state_to_protobuf(*address, *state))
+                        .collect(),
+                    height: height.0,
+                }
+                .encode_to_vec()
+            }
+            MempoolRequest::GetTransactionStatus(tx_hash) => {
+                protobuf::MempoolGetTransactionStatusRequest {
+                    transaction_hash: Some(tx_hash.0.into()),
+                }
+                .encode_to_vec()
+            }
+            MempoolRequest::Flush => protobuf::MempoolFlushRequest {}.encode_to_vec(),
+        }
+    }
+
+    fn decode_message(method_path: &str, message: &[u8]) -> Result<Self, GrpcStatus> {
+        decode_request(method_path, message).unwrap_or_else(|| {
+            Err(GrpcStatus::new(
+                GrpcStatus::UNIMPLEMENTED,
+                format!("The mempool has no method {method_path}."),
+            ))
+        })
+    }
+}
+
+// Decodes the request of the given method, or returns `None` if there's no such method.
+fn decode_request(method_path: &str, message: &[u8]) -> Option<Result<MempoolRequest, GrpcStatus>> {
+    let invalid_argument = |error: ProtobufConversionError| {
+        GrpcStatus::new(GrpcStatus::INVALID_ARGUMENT, error.to_string())
+    };
+    let request = match method_path {
+        ADD_TRANSACTION_PATH => {
+            decode::<protobuf::MempoolAddTransactionRequest>(message).and_then(|request| {
+                let tx = Transaction::try_from(request.transaction.ok_or(
+                    ProtobufConversionError::MissingField {
+                        field_description: "MempoolAddTransactionRequest::transaction",
+                    },
+                )?)?;
+                let (sender_address, state) = account_state_from_protobuf(request.account.ok_or(
+                    ProtobufConversionError::MissingField {
+                        field_description: "MempoolAddTransactionRequest::account",
+                    },
+                )?)?;
+                Ok(MempoolRequest::AddTransaction(MempoolInput {
+                    tx,
+                    account: Account { sender_address, state },
+                }))
+            })
+        }
+        GET_TRANSACTIONS_PATH => decode::<protobuf::MempoolGetTransactionsRequest>(message)
+            .and_then(|request| Ok(MempoolRequest::GetTransactions(to_usize(request.n_txs)?))),
+        GET_TRANSACTIONS_SNAPSHOT_PATH => {
+            decode::<protobuf::MempoolGetTransactionsRequest>(message).and_then(|request| {
+                Ok(MempoolRequest::GetTransactionsSnapshot(to_usize(request.n_txs)?))
+            })
+        }
+        OPEN_LEASE_PATH => decode::<protobuf::MempoolOpenLeaseRequest>(message)
+            .map(|request| MempoolRequest::OpenLease(BlockNumber(request.height))),
+        LEASE_TRANSACTIONS_PATH => decode::<protobuf::MempoolLeaseTransactionsRequest>(message)
+            .and_then(|request| {
+                Ok(MempoolRequest::LeaseTransactions(request.lease_id, to_usize(request.n_txs)?))
+            }),
+        RELEASE_LEASE_PATH => decode::<protobuf::MempoolReleaseLeaseRequest>(message)
+            .map(|request| MempoolRequest::ReleaseLease(request.lease_id)),
+        COMMIT_BLOCK_PATH => {
+            decode::<protobuf::MempoolCommitBlockRequest>(message).and_then(|request| {
+                let state_changes = request
+                    .state_changes
+                    .into_iter()
+                    .map(account_state_from_protobuf)
+                    .collect::<Result<HashMap<_, _>, _>>()?;
+                Ok(MempoolRequest::CommitBlock(BlockNumber(request.height), state_changes))
+            })
+        }
+        GET_TRANSACTION_STATUS_PATH => {
+            decode::<protobuf::MempoolGetTransactionStatusRequest>(message).and_then(|request| {
+                let tx_hash = tx_hash_from_protobuf(request.transaction_hash.ok_or(
+                    ProtobufConversionError::MissingField {
+                        field_description: "MempoolGetTransactionStatusRequest::transaction_hash",
+                    },
+                )?)?;
+                Ok(MempoolRequest::GetTransactionStatus(tx_hash))
+            })
+        }
+        FLUSH_PATH => {
+            decode::<protobuf::MempoolFlushRequest>(message).map(|_| MempoolRequest::Flush)
+        }
+        _ => return None,
+    };
+    Some(request.map_err(invalid_argument))
+}
+
+impl GrpcResponse for MempoolResponse {
+    fn encode_message(&self) -> Vec<u8> {
+        match self {
+            MempoolResponse::AddTransaction(result)
+            | MempoolResponse::ReleaseLease(result)
+            | MempoolResponse::CommitBlock(result) => {
+                protobuf::MempoolEmptyResponse { error: result.clone().err().map(Into::into) }
+                    .encode_to_vec()
+            }
+            MempoolResponse::GetTransactions(result)
+            | MempoolResponse::GetTransactionsSnapshot(result)
+            | MempoolResponse::LeaseTransactions(result) => {
+                let (txs, error) = split_result(result.clone());
+                protobuf::MempoolTransactionsResponse {
+                    transactions: txs.unwrap_or_default().into_iter().map(Into::into).collect(),
+                    error,
+                }
+                .encode_to_vec()
+            }
+            MempoolResponse::OpenLease(result) => {
+                let (lease_id, error) = split_result(result.clone());
+                protobuf::MempoolOpenLeaseResponse { lease_id: lease_id.unwrap_or_default(), error }
+                    .encode_to_vec()
+            }
+            MempoolResponse::GetTransactionStatus(result) => {
+                let (status, error) = split_result(result.clone());
+                let status = match status.flatten() {
+                    None => ProtobufStatus::Unknown,
+                    Some(TransactionStatus::Received) => ProtobufStatus::Received,
+                    Some(TransactionStatus::Committed) => ProtobufStatus::Committed,
+                };
+                protobuf::MempoolGetTransactionStatusResponse { status: status.into(), error }
+                    .encode_to_vec()
+            }
+            MempoolResponse::Flush(result) => {
+                let (n_flushed_txs, error) = split_result(result.clone());
+                protobuf::MempoolFlushResponse {
+                    n_flushed_txs: n_flushed_txs.unwrap_or_default() as u64,
+                    error,
+                }
+                .encode_to_vec()
+            }
+        }
+    }
+
+    fn decode_message(method_path: &str, message: &[u8]) -> Result<Self, String> {
+        let response = match method_path {
+            ADD_TRANSACTION_PATH => {
+                decode_empty_response(message).map(MempoolResponse::AddTransaction)
+            }
+            RELEASE_LEASE_PATH => decode_empty_response(message).map(MempoolResponse::ReleaseLease),
+            COMMIT_BLOCK_PATH => decode_empty_response(message).map(MempoolResponse::CommitBlock),
+            GET_TRANSACTIONS_PATH => {
+                decode_transactions_response(message).map(MempoolResponse::GetTransactions)
+            }
+            GET_TRANSACTIONS_SNAPSHOT_PATH => {
+                decode_transactions_response(message).map(MempoolResponse::GetTransactionsSnapshot)
+            }
+            LEASE_TRANSACTIONS_PATH => {
+                decode_transactions_response(message).map(MempoolResponse::LeaseTransactions)
+            }
+            OPEN_LEASE_PATH => {
+                decode::<protobuf::MempoolOpenLeaseResponse>(message).and_then(|response| {
+                    Ok(MempoolResponse::OpenLease(join_result(response.lease_id, response.error)?))
+                })
+            }
+            GET_TRANSACTION_STATUS_PATH => decode::<protobuf::MempoolGetTransactionStatusResponse>(
+                message,
+            )
+            .and_then(|response| {
+                let status = match ProtobufStatus::try_from(response.status) {
+                    Ok(ProtobufStatus::Unknown) => None,
+                    Ok(ProtobufStatus::Received) => Some(TransactionStatus::Received),
+                    Ok(ProtobufStatus::Committed) => Some(TransactionStatus::Committed),
+                    Err(_) => {
+                        return Err(ProtobufConversionError::OutOfRangeValue {
+                            type_description: "MempoolGetTransactionStatusResponse::status",
+                            value_as_str: response.status.to_string(),
+                        });
+                    }
+                };
+                Ok(MempoolResponse::GetTransactionStatus(join_result(status, response.error)?))
+            }),
+            FLUSH_PATH => decode::<protobuf::MempoolFlushResponse>(message).and_then(|response| {
+                let n_flushed_txs = to_usize(response.n_flushed_txs)?;
+                Ok(MempoolResponse::Flush(join_result(n_flushed_txs, response.error)?))
+            }),
+            _ => return Err(format!("The mempool has no method {method_path}.")),
+        };
+        response.map_err(|error| error.to_string())
+    }
+}
+
+impl From<MempoolError> for protobuf::MempoolError {
+    fn from(value: MempoolError) -> Self {
+        use protobuf::mempool_error::{Error, FeltOutOfRange};
+        let error = match value {
+            MempoolError::DuplicateNonce { address, nonce } => {
+                Error::DuplicateNonce(account_state_to_protobuf(address, AccountState { nonce }))
+            }
+            MempoolError::DuplicateTransaction { tx_hash } => {
+                Error::DuplicateTransaction(tx_hash.0.into())
+            }
+            MempoolError::LeaseNotFound { lease_id } => Error::LeaseNotFound(lease_id),
+            MempoolError::TransactionNotFound { tx_hash } => {
+                Error::TransactionNotFound(tx_hash.0.into())
+            }
+            MempoolError::FeltOutOfRange => Error::FeltOutOfRange(FeltOutOfRange {}),
+        };
+        Self { error: Some(error) }
+    }
+}
+
+impl TryFrom<protobuf::MempoolError> for MempoolError {
+    type Error = ProtobufConversionError;
+    fn try_from(value: protobuf::MempoolError) -> Result<Self, Self::Error> {
+        use protobuf::mempool_error::Error;
+        let error = value.error.ok_or(ProtobufConversionError::MissingField {
+            field_description: "MempoolError::error",
+        })?;
+        Ok(match error {
+            Error::DuplicateNonce(account_state) => {
+                let (address, AccountState { nonce }) = account_state_from_protobuf(account_state)?;
+                MempoolError::DuplicateNonce { address, nonce }
+            }
+            Error::DuplicateTransaction(tx_hash) => {
+                MempoolError::DuplicateTransaction { tx_hash: tx_hash_from_protobuf(tx_hash)? }
+            }
+            Error::LeaseNotFound(lease_id) => MempoolError::LeaseNotFound { lease_id },
+            Error::TransactionNotFound(tx_hash) => {
+                MempoolError::TransactionNotFound { tx_hash: tx_hash_from_protobuf(tx_hash)? }
+            }
+            Error::FeltOutOfRange(_) => MempoolError::FeltOutOfRange,
+        })
+    }
+}
+
+fn decode<T: Message + Default>(message: &[u8]) -> Result<T, ProtobufConversionError> {
+    Ok(T::decode(message)?)
+}
+
+fn decode_empty_response(message: &[u8]) -> Result<MempoolResult<()>, ProtobufConversionError> {
+    let response = decode::<protobuf::MempoolEmptyResponse>(message)?;
+    join_result((), response.error)
+}
+
+fn decode_transactions_response(
+    message: &[u8],
+) -> Result<MempoolResult<Vec<Transaction>>, ProtobufConversionError> {
+    let response = decode::<protobuf::MempoolTransactionsResponse>(message)?;
+    let txs = response
+        .transactions
+        .into_iter()
+        .map(Transaction::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    join_result(txs, response.error)
+}
+
+// Splits a result to the value and the error of a response message.
+fn split_result<T>(result: MempoolResult<T>) -> (Option<T>, Option<protobuf::MempoolError>) {
+    match result {
+        Ok(value) => (Some(value), None),
+        Err(error) => (None, Some(error.into())),
+    }
+}
+
+// Joins the value and the error of a response message to a result.
+fn join_result<T>(
+    value: T,
+    error: Option<protobuf::MempoolError>,
+) -> Result<MempoolResult<T>, ProtobufConversionError> {
+    match error {
+        None => Ok(Ok(value)),
+        Some(error) => Ok(Err(error.try_into()?)),
+    }
+}
+
+fn account_state_to_protobuf(
+    address: ContractAddress,
+    state: AccountState,
+) -> protobuf::MempoolAccountState {
+    protobuf::MempoolAccountState {
+        address: Some(address.into()),
+        nonce: Some(state.nonce.0.into()),
+    }
+}
+
+fn account_state_from_protobuf(
+    value: protobuf::MempoolAccountState,
+) -> Result<(ContractAddress, AccountState), ProtobufConversionError> {
+    let address = value
+        .address
+        .ok_or(ProtobufConversionError::MissingField {
+            field_description: "MempoolAccountState::address",
+        })?
+        .try_into()?;
+    let nonce = Nonce(
+        value
+            .nonce
+            .ok_or(ProtobufConversionError::MissingField {
+                field_description: "MempoolAccountState::nonce",
+            })?
+            .try_into()?,
+    );
+    Ok((address, AccountState { nonce }))
+}
+
+fn tx_hash_from_protobuf(
+    value: protobuf::Hash,
+) -> Result<TransactionHash, ProtobufConversionError> {
+    Ok(TransactionHash(StarkHash::try_from(value)?))
+}
+
+fn to_usize(value: u64) -> Result<usize, ProtobufConversionError> {
+    usize::try_from(value).map_err(|_| ProtobufConversionError::OutOfRangeValue {
+        type_description: "usize",
+        value_as_str: value.to_string(),
+    })
+}
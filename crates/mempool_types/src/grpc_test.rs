@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use rstest::rstest;
+use starknet_api::block::BlockNumber;
+use starknet_api::contract_class::ClassInfo;
+use starknet_api::core::{ContractAddress, Nonce, PatriciaKey};
+use starknet_api::executable_transaction::{DeclareTransaction, InvokeTransaction, Transaction};
+use starknet_api::transaction::{DeclareTransactionV2, InvokeTransactionV1, TransactionHash};
+use starknet_api::{contract_address, felt, patricia_key};
+use starknet_mempool_infra::component_definitions::{GrpcRequest, GrpcResponse, GrpcStatus};
+
+use crate::communication::{MempoolRequest, MempoolResponse};
+use crate::errors::MempoolError;
+use crate::grpc::{
+    ADD_TRANSACTION_PATH,
+    COMMIT_BLOCK_PATH,
+    FLUSH_PATH,
+    GET_TRANSACTIONS_PATH,
+    GET_TRANSACTION_STATUS_PATH,
+    OPEN_LEASE_PATH,
+};
+use crate::mempool_types::{Account, AccountState, MempoolInput, TransactionStatus};
+
+fn invoke_tx() -> Transaction {
+    Transaction::Invoke(InvokeTransaction {
+        tx: starknet_api::transaction::InvokeTransaction::V1(InvokeTransactionV1::default()),
+        tx_hash: TransactionHash(felt!(1_u8)),
+    })
+}
+
+fn declare_tx() -> Transaction {
+    Transaction::Declare(DeclareTransaction {
+        tx: starknet_api::transaction::DeclareTransaction::V2(DeclareTransactionV2::default()),
+        tx_hash: TransactionHash(felt!(2_u8)),
+        class_info: ClassInfo {
+            casm_contract_class: CasmContractClass::default(),
+            sierra_program_length: 1,
+            abi_length: 2,
+        },
+    })
+}
+
+#[rstest]
+#[case::add_transaction(MempoolRequest::AddTransaction(MempoolInput {
+    tx: declare_tx(),
+    account: Account {
+        sender_address: contract_address!("0x1"),
+        state: AccountState { nonce: Nonce(felt!(3_u8)) },
+    },
+}))]
+#[case::get_transactions(MempoolRequest::GetTransactions(2))]
+#[case::get_transactions_snapshot(MempoolRequest::GetTransactionsSnapshot(2))]
+#[case::open_lease(MempoolRequest::OpenLease(BlockNumber(5)))]
+#[case::lease_transactions(MempoolRequest::LeaseTransactions(1, 2))]
+#[case::release_lease(MempoolRequest::ReleaseLease(1))]
+#[case::commit_block(MempoolRequest::CommitBlock(BlockNumber(5), HashMap::from([
+    (contract_address!("0x1"), AccountState { nonce: Nonce(felt!(3_u8)) }),
+    (contract_address!("0x2"), AccountState { nonce: Nonce(felt!(4_u8)) }),
+])))]
+#[case::get_transaction_status(MempoolRequest::GetTransactionStatus(TransactionHash(felt!(1_u8))))]
+#[case::flush(MempoolRequest::Flush)]
+fn request_is_encoded_and_decoded(#[case] request: MempoolRequest) {
+    let message = request.encode_message();
+    assert_eq!(MempoolRequest::decode_message(request.method_path(), &message), Ok(request));
+}
+
+#[test]
+fn request_of_unknown_method_is_unimplemented() {
+    assert_eq!(
+        MempoolRequest::decode_message("/Mempool/Unknown", &[]).unwrap_err().code,
+        GrpcStatus::UNIMPLEMENTED
+    );
+}
+
+#[rstest]
+#[case::empty(ADD_TRANSACTION_PATH, MempoolResponse::AddTransaction(Ok(())))]
+#[case::error(
+    COMMIT_BLOCK_PATH,
+    MempoolResponse::CommitBlock(Err(MempoolError::DuplicateNonce {
+        address: contract_address!("0x1"),
+        nonce: Nonce(felt!(3_u8)),
+    }))
+)]
+#[case::transactions(
+    GET_TRANSACTIONS_PATH,
+    MempoolResponse::GetTransactions(Ok(vec![invoke_tx(), declare_tx()]))
+)]
+#[case::transactions_error(
+    GET_TRANSACTIONS_PATH,
+    MempoolResponse::GetTransactions(Err(MempoolError::LeaseNotFound { lease_id: 1 }))
+)]
+#[case::lease_id(OPEN_LEASE_PATH, MempoolResponse::OpenLease(Ok(7)))]
+#[case::known_status(
+    GET_TRANSACTION_STATUS_PATH,
+    MempoolResponse::GetTransactionStatus(Ok(Some(TransactionStatus::Committed)))
+)]
+#[case::unknown_status(
+    GET_TRANSACTION_STATUS_PATH,
+    MempoolResponse::GetTransactionStatus(Ok(None))
+)]
+#[case::n_flushed_txs(FLUSH_PATH, MempoolResponse::Flush(Ok(3)))]
+fn response_is_encoded_and_decoded(#[case] method_path: &str, #[case] response: MempoolResponse) {
+    let message = response.encode_message();
+    assert_eq!(MempoolResponse::decode_message(method_path, &message), Ok(response));
+}
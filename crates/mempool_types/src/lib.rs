@@ -1,3 +1,5 @@
 pub mod communication;
 pub mod errors;
+pub mod grpc;
 pub mod mempool_types;
+pub mod stateless_validation;
@@ -24,3 +24,15 @@ pub struct MempoolInput {
 }
 
 pub type MempoolResult<T> = Result<T, MempoolError>;
+
+/// Identifies a lease on transactions of the mempool, see `MempoolClient::open_lease`.
+pub type LeaseId = u64;
+
+/// The status of a transaction that the mempool received.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TransactionStatus {
+    /// The transaction is waiting in the mempool to be included in a block.
+    Received,
+    /// The transaction was included in one of the recently committed blocks.
+    Committed,
+}
@@ -0,0 +1,194 @@
+//! Checks of transactions that don't depend on the state, shared by the components that receive
+//! transactions from outside the node. The gateway runs them on the transactions sent by users;
+//! `validate_tx` runs them on executable transactions, for the components that receive those.
+//!
+//! The checks of declared classes (Sierra version, class size, entry points) are done by the
+//! gateway, which receives the Sierra classes.
+
+#[cfg(test)]
+#[path = "stateless_validation_test.rs"]
+mod stateless_validation_test;
+
+use std::collections::BTreeMap;
+
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+use starknet_api::core::{ContractAddress, L2_ADDRESS_UPPER_BOUND};
+use starknet_api::executable_transaction::Transaction;
+use starknet_api::hash::StarkHash;
+use starknet_api::transaction::{
+    Calldata,
+    Resource,
+    ResourceBounds,
+    TransactionSignature,
+    TransactionVersion,
+};
+use thiserror::Error;
+
+/// The only transaction version the sequencer accepts.
+pub const SUPPORTED_TX_VERSION: TransactionVersion = TransactionVersion::THREE;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct StatelessValidationConfig {
+    // If true, validates that the resource bounds are not zero.
+    pub validate_non_zero_l1_gas_fee: bool,
+    pub validate_non_zero_l2_gas_fee: bool,
+    pub max_calldata_length: usize,
+    pub max_signature_length: usize,
+}
+
+impl Default for StatelessValidationConfig {
+    fn default() -> Self {
+        StatelessValidationConfig {
+            validate_non_zero_l1_gas_fee: true,
+            validate_non_zero_l2_gas_fee: false,
+            max_calldata_length: 4000,
+            max_signature_length: 4000,
+        }
+    }
+}
+
+impl SerializeConfig for StatelessValidationConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "validate_non_zero_l1_gas_fee",
+                &self.validate_non_zero_l1_gas_fee,
+                "If true, validates that a transaction has non-zero L1 resource bounds.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "validate_non_zero_l2_gas_fee",
+                &self.validate_non_zero_l2_gas_fee,
+                "If true, validates that a transaction has non-zero L2 resource bounds.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_signature_length",
+                &self.max_signature_length,
+                "Limitation of signature length.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_calldata_length",
+                &self.max_calldata_length,
+                "Limitation of calldata length.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum StatelessValidationError {
+    #[error(
+        "Calldata length exceeded maximum: length {calldata_length}
+        (allowed length: {max_calldata_length})."
+    )]
+    CalldataTooLong { calldata_length: usize, max_calldata_length: usize },
+    #[error("Contract address {address} is out of the range of L2 addresses.")]
+    ContractAddressOutOfRange { address: ContractAddress },
+    #[error(
+        "Signature length exceeded maximum: length {signature_length}
+        (allowed length: {max_signature_length})."
+    )]
+    SignatureTooLong { signature_length: usize, max_signature_length: usize },
+    #[error("Transaction version {version:?} is not supported.")]
+    UnsupportedTxVersion { version: TransactionVersion },
+    #[error("Expected a positive amount of {resource:?}. Got {resource_bounds:?}.")]
+    ZeroResourceBounds { resource: Resource, resource_bounds: ResourceBounds },
+}
+
+pub type StatelessValidationResult<T> = Result<T, StatelessValidationError>;
+
+/// Runs all the stateless checks of an executable transaction.
+pub fn validate_tx(
+    config: &StatelessValidationConfig,
+    tx: &Transaction,
+) -> StatelessValidationResult<()> {
+    let (version, signature, calldata) = match tx {
+        Transaction::Declare(tx) => (tx.tx.version(), tx.tx.signature(), None),
+        Transaction::DeployAccount(tx) => {
+            (tx.version(), tx.signature(), Some(tx.constructor_calldata()))
+        }
+        Transaction::Invoke(tx) => (tx.version(), tx.signature(), Some(tx.calldata())),
+    };
+
+    validate_tx_version(version)?;
+    validate_contract_address(tx.contract_address())?;
+    if let Some(resource_bounds) = tx.resource_bounds() {
+        let get_bound = |resource| resource_bounds.0.get(&resource).copied().unwrap_or_default();
+        if config.validate_non_zero_l1_gas_fee {
+            validate_resource_is_non_zero(Resource::L1Gas, get_bound(Resource::L1Gas))?;
+        }
+        if config.validate_non_zero_l2_gas_fee {
+            validate_resource_is_non_zero(Resource::L2Gas, get_bound(Resource::L2Gas))?;
+        }
+    }
+    if let Some(calldata) = calldata {
+        validate_calldata_length(&calldata, config.max_calldata_length)?;
+    }
+    validate_signature_length(&signature, config.max_signature_length)?;
+
+    Ok(())
+}
+
+pub fn validate_tx_version(version: TransactionVersion) -> StatelessValidationResult<()> {
+    if version != SUPPORTED_TX_VERSION {
+        return Err(StatelessValidationError::UnsupportedTxVersion { version });
+    }
+
+    Ok(())
+}
+
+/// Validates that the address is below the L2 address upper bound, so that it can't collide with
+/// the addresses of storage variables.
+pub fn validate_contract_address(address: ContractAddress) -> StatelessValidationResult<()> {
+    if **address >= StarkHash::from(*L2_ADDRESS_UPPER_BOUND) {
+        return Err(StatelessValidationError::ContractAddressOutOfRange { address });
+    }
+
+    Ok(())
+}
+
+pub fn validate_resource_is_non_zero(
+    resource: Resource,
+    resource_bounds: ResourceBounds,
+) -> StatelessValidationResult<()> {
+    if resource_bounds.max_amount == 0 || resource_bounds.max_price_per_unit == 0 {
+        return Err(StatelessValidationError::ZeroResourceBounds { resource, resource_bounds });
+    }
+
+    Ok(())
+}
+
+pub fn validate_calldata_length(
+    calldata: &Calldata,
+    max_calldata_length: usize,
+) -> StatelessValidationResult<()> {
+    let calldata_length = calldata.0.len();
+    if calldata_length > max_calldata_length {
+        return Err(StatelessValidationError::CalldataTooLong {
+            calldata_length,
+            max_calldata_length,
+        });
+    }
+
+    Ok(())
+}
+
+pub fn validate_signature_length(
+    signature: &TransactionSignature,
+    max_signature_length: usize,
+) -> StatelessValidationResult<()> {
+    let signature_length = signature.0.len();
+    if signature_length > max_signature_length {
+        return Err(StatelessValidationError::SignatureTooLong {
+            signature_length,
+            max_signature_length,
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,271 @@
+use std::collections::BTreeMap;
+
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use rstest::rstest;
+use starknet_api::contract_class::ClassInfo;
+use starknet_api::core::{ContractAddress, PatriciaKey, L2_ADDRESS_UPPER_BOUND};
+use starknet_api::data_availability::DataAvailabilityMode;
+use starknet_api::executable_transaction::{
+    DeclareTransaction,
+    DeployAccountTransaction,
+    InvokeTransaction,
+    Transaction,
+};
+use starknet_api::hash::StarkHash;
+use starknet_api::transaction::{
+    Calldata,
+    DeclareTransactionV2,
+    DeclareTransactionV3,
+    DeployAccountTransactionV1,
+    DeployAccountTransactionV3,
+    DeprecatedResourceBoundsMapping,
+    InvokeTransactionV1,
+    InvokeTransactionV3,
+    Resource,
+    ResourceBounds,
+    TransactionHash,
+    TransactionSignature,
+    TransactionVersion,
+};
+use starknet_api::{calldata, contract_address, felt, patricia_key};
+
+use crate::stateless_validation::{
+    validate_tx,
+    StatelessValidationConfig,
+    StatelessValidationError,
+};
+
+const NON_EMPTY_RESOURCE_BOUNDS: ResourceBounds =
+    ResourceBounds { max_amount: 1, max_price_per_unit: 1 };
+
+#[derive(Clone, Copy, Debug)]
+enum TxType {
+    Declare,
+    DeployAccount,
+    Invoke,
+}
+
+struct TxArgs {
+    sender_address: ContractAddress,
+    l1_gas_bounds: ResourceBounds,
+    l2_gas_bounds: ResourceBounds,
+    calldata: Calldata,
+    signature: TransactionSignature,
+}
+
+impl Default for TxArgs {
+    fn default() -> Self {
+        Self {
+            sender_address: contract_address!("0x100"),
+            l1_gas_bounds: NON_EMPTY_RESOURCE_BOUNDS,
+            l2_gas_bounds: NON_EMPTY_RESOURCE_BOUNDS,
+            calldata: calldata![],
+            signature: TransactionSignature::default(),
+        }
+    }
+}
+
+fn config() -> StatelessValidationConfig {
+    StatelessValidationConfig {
+        validate_non_zero_l1_gas_fee: true,
+        validate_non_zero_l2_gas_fee: true,
+        max_calldata_length: 1,
+        max_signature_length: 1,
+    }
+}
+
+fn class_info() -> ClassInfo {
+    ClassInfo {
+        casm_contract_class: CasmContractClass::default(),
+        sierra_program_length: 0,
+        abi_length: 0,
+    }
+}
+
+fn v3_tx(tx_type: TxType, args: TxArgs) -> Transaction {
+    let TxArgs { sender_address, l1_gas_bounds, l2_gas_bounds, calldata, signature } = args;
+    let resource_bounds = DeprecatedResourceBoundsMapping(BTreeMap::from([
+        (Resource::L1Gas, l1_gas_bounds),
+        (Resource::L2Gas, l2_gas_bounds),
+    ]));
+    match tx_type {
+        TxType::Declare => Transaction::Declare(DeclareTransaction {
+            tx: starknet_api::transaction::DeclareTransaction::V3(DeclareTransactionV3 {
+                resource_bounds,
+                tip: Default::default(),
+                signature,
+                nonce: Default::default(),
+                class_hash: Default::default(),
+                compiled_class_hash: Default::default(),
+                sender_address,
+                nonce_data_availability_mode: DataAvailabilityMode::L1,
+                fee_data_availability_mode: DataAvailabilityMode::L1,
+                paymaster_data: Default::default(),
+                account_deployment_data: Default::default(),
+            }),
+            tx_hash: TransactionHash::default(),
+            class_info: class_info(),
+        }),
+        TxType::DeployAccount => Transaction::DeployAccount(DeployAccountTransaction {
+            tx: starknet_api::transaction::DeployAccountTransaction::V3(
+                DeployAccountTransactionV3 {
+                    resource_bounds,
+                    tip: Default::default(),
+                    signature,
+                    nonce: Default::default(),
+                    class_hash: Default::default(),
+                    contract_address_salt: Default::default(),
+                    constructor_calldata: calldata,
+                    nonce_data_availability_mode: DataAvailabilityMode::L1,
+                    fee_data_availability_mode: DataAvailabilityMode::L1,
+                    paymaster_data: Default::default(),
+                },
+            ),
+            tx_hash: TransactionHash::default(),
+            contract_address: sender_address,
+        }),
+        TxType::Invoke => Transaction::Invoke(InvokeTransaction {
+            tx: starknet_api::transaction::InvokeTransaction::V3(InvokeTransactionV3 {
+                resource_bounds,
+                tip: Default::default(),
+                signature,
+                nonce: Default::default(),
+                sender_address,
+                calldata,
+                nonce_data_availability_mode: DataAvailabilityMode::L1,
+                fee_data_availability_mode: DataAvailabilityMode::L1,
+                paymaster_data: Default::default(),
+                account_deployment_data: Default::default(),
+            }),
+            tx_hash: TransactionHash::default(),
+        }),
+    }
+}
+
+#[rstest]
+fn valid_tx(#[values(TxType::Declare, TxType::DeployAccount, TxType::Invoke)] tx_type: TxType) {
+    assert_eq!(validate_tx(&config(), &v3_tx(tx_type, TxArgs::default())), Ok(()));
+
+    let args = TxArgs {
+        calldata: calldata![felt!(1_u8)],
+        signature: TransactionSignature(vec![felt!(1_u8)]),
+        ..Default::default()
+    };
+    assert_eq!(validate_tx(&config(), &v3_tx(tx_type, args)), Ok(()));
+}
+
+#[rstest]
+#[case::declare(
+    Transaction::Declare(DeclareTransaction {
+        tx: starknet_api::transaction::DeclareTransaction::V2(DeclareTransactionV2::default()),
+        tx_hash: TransactionHash::default(),
+        class_info: class_info(),
+    }),
+    TransactionVersion::TWO
+)]
+#[case::deploy_account(
+    Transaction::DeployAccount(DeployAccountTransaction {
+        tx: starknet_api::transaction::DeployAccountTransaction::V1(
+            DeployAccountTransactionV1::default()
+        ),
+        tx_hash: TransactionHash::default(),
+        contract_address: ContractAddress::default(),
+    }),
+    TransactionVersion::ONE
+)]
+#[case::invoke(
+    Transaction::Invoke(InvokeTransaction {
+        tx: starknet_api::transaction::InvokeTransaction::V1(InvokeTransactionV1::default()),
+        tx_hash: TransactionHash::default(),
+    }),
+    TransactionVersion::ONE
+)]
+fn unsupported_tx_version(#[case] tx: Transaction, #[case] version: TransactionVersion) {
+    assert_eq!(
+        validate_tx(&config(), &tx),
+        Err(StatelessValidationError::UnsupportedTxVersion { version })
+    );
+}
+
+#[rstest]
+fn contract_address_out_of_range(
+    #[values(TxType::Declare, TxType::DeployAccount, TxType::Invoke)] tx_type: TxType,
+) {
+    let address = ContractAddress::try_from(StarkHash::from(*L2_ADDRESS_UPPER_BOUND)).unwrap();
+    let tx = v3_tx(tx_type, TxArgs { sender_address: address, ..Default::default() });
+
+    assert_eq!(
+        validate_tx(&config(), &tx),
+        Err(StatelessValidationError::ContractAddressOutOfRange { address })
+    );
+}
+
+#[rstest]
+#[case::zero_l1_gas(Resource::L1Gas)]
+#[case::zero_l2_gas(Resource::L2Gas)]
+fn zero_resource_bounds(
+    #[case] resource: Resource,
+    #[values(TxType::Declare, TxType::DeployAccount, TxType::Invoke)] tx_type: TxType,
+) {
+    let args = match resource {
+        Resource::L1Gas => {
+            TxArgs { l1_gas_bounds: ResourceBounds::default(), ..Default::default() }
+        }
+        _ => TxArgs { l2_gas_bounds: ResourceBounds::default(), ..Default::default() },
+    };
+    let tx = v3_tx(tx_type, args);
+
+    assert_eq!(
+        validate_tx(&config(), &tx),
+        Err(StatelessValidationError::ZeroResourceBounds {
+            resource,
+            resource_bounds: ResourceBounds::default()
+        })
+    );
+
+    // Zero resource bounds are allowed when not validated.
+    let config = StatelessValidationConfig {
+        validate_non_zero_l1_gas_fee: false,
+        validate_non_zero_l2_gas_fee: false,
+        ..config()
+    };
+    assert_eq!(validate_tx(&config, &tx), Ok(()));
+}
+
+#[rstest]
+fn calldata_too_long(#[values(TxType::DeployAccount, TxType::Invoke)] tx_type: TxType) {
+    let args = TxArgs { calldata: calldata![felt!(1_u8), felt!(2_u8)], ..Default::default() };
+
+    assert_eq!(
+        validate_tx(&config(), &v3_tx(tx_type, args)),
+        Err(StatelessValidationError::CalldataTooLong {
+            calldata_length: 2,
+            max_calldata_length: 1
+        })
+    );
+}
+
+#[test]
+fn declare_tx_has_no_calldata() {
+    let args = TxArgs { calldata: calldata![felt!(1_u8), felt!(2_u8)], ..Default::default() };
+
+    assert_eq!(validate_tx(&config(), &v3_tx(TxType::Declare, args)), Ok(()));
+}
+
+#[rstest]
+fn signature_too_long(
+    #[values(TxType::Declare, TxType::DeployAccount, TxType::Invoke)] tx_type: TxType,
+) {
+    let args = TxArgs {
+        signature: TransactionSignature(vec![felt!(1_u8), felt!(2_u8)]),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        validate_tx(&config(), &v3_tx(tx_type, args)),
+        Err(StatelessValidationError::SignatureTooLong {
+            signature_length: 2,
+            max_signature_length: 1
+        })
+    );
+}
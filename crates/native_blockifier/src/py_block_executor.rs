@@ -1,27 +1,44 @@
 use std::collections::HashMap;
-
-use blockifier::blockifier::block::pre_process_block;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use blockifier::blockifier::block::{
+    pre_process_block_and_create_context,
+    BlockInfo,
+    ParentBlockInfo,
+};
 use blockifier::blockifier::config::TransactionExecutorConfig;
 use blockifier::blockifier::transaction_executor::{TransactionExecutor, TransactionExecutorError};
 use blockifier::bouncer::BouncerConfig;
 use blockifier::context::{BlockContext, ChainInfo, FeeTokenAddresses};
 use blockifier::execution::call_info::CallInfo;
-use blockifier::state::cached_state::CachedState;
+use blockifier::execution::interrupt::ExecutionInterrupt;
+use blockifier::execution::panic_reason::{PanicDecoder, PanicReason};
+use blockifier::fee::fee_multipliers::FeeMultipliers;
+use blockifier::state::cached_state::{CachedState, ReadSet};
 use blockifier::state::global_cache::GlobalContractCache;
+use blockifier::transaction::nonce_manager::NonceConfig;
 use blockifier::transaction::objects::{GasVector, ResourcesMapping, TransactionExecutionInfo};
 use blockifier::transaction::transaction_execution::Transaction;
+use blockifier::transaction::version_config::TransactionVersionConfig;
 use blockifier::versioned_constants::VersionedConstants;
+use papyrus_storage::header::HeaderStorageReader;
 use pyo3::prelude::*;
 use pyo3::types::{PyBytes, PyList};
 use pyo3::{FromPyObject, PyAny, Python};
 use serde::Serialize;
 use starknet_api::block::BlockNumber;
-use starknet_api::core::{ChainId, ContractAddress};
+use starknet_api::core::{ChainId, ClassHash, ContractAddress};
 use starknet_api::transaction::Fee;
 use starknet_types_core::felt::Felt;
 
 use crate::errors::{NativeBlockifierError, NativeBlockifierResult};
-use crate::py_objects::{PyBouncerConfig, PyConcurrencyConfig, PyVersionedConstantsOverrides};
+use crate::py_objects::{
+    hash_map_into_builtin_weights,
+    PyBouncerConfig,
+    PyConcurrencyConfig,
+    PyVersionedConstantsOverrides,
+};
 use crate::py_state_diff::{PyBlockInfo, PyStateDiff};
 use crate::py_transaction::{py_tx, PyClassInfo, PY_TX_PARSING_ERR};
 use crate::py_utils::{int_to_chain_id, into_block_number_hash_pair, PyFelt};
@@ -48,13 +65,18 @@ pub(crate) struct ThinTransactionExecutionInfo {
     pub da_gas: GasVector,
     pub actual_resources: ResourcesMapping,
     pub revert_error: Option<String>,
+    /// The decoded panic data of the execution that reverted the transaction, if it reverted due
+    /// to a Cairo panic.
+    pub revert_reason: Option<PanicReason>,
     pub total_gas: GasVector,
+    pub read_set: Option<ReadSet>,
 }
 
 impl ThinTransactionExecutionInfo {
     pub fn from_tx_execution_info(
         block_context: &BlockContext,
         tx_execution_info: TransactionExecutionInfo,
+        panic_decoder: &PanicDecoder,
     ) -> Self {
         Self {
             validate_call_info: tx_execution_info.validate_call_info,
@@ -68,7 +90,12 @@ impl ThinTransactionExecutionInfo {
                 true,
             ),
             revert_error: tx_execution_info.revert_error,
+            revert_reason: tx_execution_info
+                .revert_panic_data
+                .as_deref()
+                .map(|panic_data| panic_decoder.decode(panic_data)),
             total_gas: tx_execution_info.receipt.gas,
+            read_set: tx_execution_info.read_set,
         }
     }
     pub fn serialize(self) -> RawTransactionExecutionResult {
@@ -86,12 +113,14 @@ pub struct PyBlockExecutor {
     /// `Send` trait is required for `pyclass` compatibility as Python objects must be threadsafe.
     pub storage: Box<dyn Storage + Send>,
     pub global_contract_cache: GlobalContractCache,
+    /// Decodes the panic data of reverted transactions, recognizing the configured errors.
+    pub panic_decoder: Arc<PanicDecoder>,
 }
 
 #[pymethods]
 impl PyBlockExecutor {
     #[new]
-    #[pyo3(signature = (bouncer_config, concurrency_config, os_config, global_contract_cache_size, target_storage_config, py_versioned_constants_overrides))]
+    #[pyo3(signature = (bouncer_config, concurrency_config, os_config, global_contract_cache_size, target_storage_config, py_versioned_constants_overrides, pinned_class_hashes = Vec::new(), record_read_sets = false, revert_error_names = Vec::new(), builtin_weights = None))]
     pub fn create(
         bouncer_config: PyBouncerConfig,
         concurrency_config: PyConcurrencyConfig,
@@ -99,24 +128,50 @@ impl PyBlockExecutor {
         global_contract_cache_size: usize,
         target_storage_config: StorageConfig,
         py_versioned_constants_overrides: PyVersionedConstantsOverrides,
+        pinned_class_hashes: Vec<PyFelt>,
+        record_read_sets: bool,
+        revert_error_names: Vec<String>,
+        builtin_weights: Option<HashMap<String, u64>>,
     ) -> Self {
         log::debug!("Initializing Block Executor...");
         let storage =
             PapyrusStorage::new(target_storage_config).expect("Failed to initialize storage.");
         let versioned_constants =
             VersionedConstants::get_versioned_constants(py_versioned_constants_overrides.into());
+        let mut bouncer_config: BouncerConfig =
+            bouncer_config.try_into().expect("Failed to parse bouncer config.");
+        bouncer_config.builtin_weights = builtin_weights.map(|builtin_weights| {
+            hash_map_into_builtin_weights(builtin_weights)
+                .expect("Failed to parse builtin weights.")
+        });
+        for drift in bouncer_config.resolve_builtin_weights(&versioned_constants) {
+            log::warn!(
+                "The weight of the {} builtin is {}, but the versioned constants calibrate it to \
+                 {}.",
+                drift.builtin,
+                drift.weight,
+                drift.calibrated_weight
+            );
+        }
         log::debug!("Initialized Block Executor.");
 
         Self {
-            bouncer_config: bouncer_config.try_into().expect("Failed to parse bouncer config."),
+            bouncer_config,
             tx_executor_config: TransactionExecutorConfig {
                 concurrency_config: concurrency_config.into(),
+                record_read_sets,
             },
             chain_info: os_config.into_chain_info(),
             versioned_constants,
             tx_executor: None,
             storage: Box::new(storage),
-            global_contract_cache: GlobalContractCache::new(global_contract_cache_size),
+            global_contract_cache: GlobalContractCache::new_with_pinned_classes(
+                global_contract_cache_size,
+                pinned_class_hashes.into_iter().map(|class_hash| ClassHash(class_hash.0)).collect(),
+            ),
+            panic_decoder: Arc::new(PanicDecoder::new(
+                revert_error_names.iter().map(String::as_str),
+            )),
         }
     }
 
@@ -129,23 +184,23 @@ impl PyBlockExecutor {
         next_block_info: PyBlockInfo,
         old_block_number_and_hash: Option<(u64, PyFelt)>,
     ) -> NativeBlockifierResult<()> {
-        // Create block context.
-        let block_context = BlockContext::new(
-            next_block_info.try_into()?,
-            self.chain_info.clone(),
-            self.versioned_constants.clone(),
-            self.bouncer_config.clone(),
-        );
-        let next_block_number = block_context.block_info().block_number;
+        let next_block_info: BlockInfo = next_block_info.try_into()?;
+        let next_block_number = next_block_info.block_number;
 
         // Create state reader.
         let papyrus_reader = self.get_aligned_reader(next_block_number);
         let mut state = CachedState::new(papyrus_reader);
 
-        pre_process_block(
+        // Create block context.
+        let parent_block_info = self.get_parent_block_info(next_block_number)?;
+        let block_context = pre_process_block_and_create_context(
             &mut state,
             into_block_number_hash_pair(old_block_number_and_hash),
-            next_block_number,
+            parent_block_info,
+            next_block_info,
+            self.chain_info.clone(),
+            self.versioned_constants.clone(),
+            self.bouncer_config.clone(),
         )?;
 
         let tx_executor =
@@ -167,9 +222,12 @@ impl PyBlockExecutor {
     ) -> NativeBlockifierResult<Py<PyBytes>> {
         let tx: Transaction = py_tx(tx, optional_py_class_info).expect(PY_TX_PARSING_ERR);
         let tx_execution_info = self.tx_executor().execute(&tx)?;
+        self.global_contract_cache.record_class_usage(&tx_execution_info);
+        let panic_decoder = self.panic_decoder.clone();
         let thin_tx_execution_info = ThinTransactionExecutionInfo::from_tx_execution_info(
             &self.tx_executor().block_context,
             tx_execution_info,
+            &panic_decoder,
         );
 
         // Serialize and convert to PyBytes.
@@ -178,12 +236,14 @@ impl PyBlockExecutor {
     }
 
     /// Executes the given transactions on the Blockifier state.
-    /// Stops if and when there is no more room in the block, and returns the executed transactions'
-    /// results as a PyList of (success (bool), serialized result (bytes)) tuples.
-    #[pyo3(signature = (txs_with_class_infos))]
+    /// Stops if and when there is no more room in the block, or `timeout_secs` pass, and returns
+    /// the executed transactions' results as a PyList of (success (bool), serialized result
+    /// (bytes)) tuples. A transaction that is stopped by the timeout is the last result, and fails.
+    #[pyo3(signature = (txs_with_class_infos, timeout_secs=None))]
     pub fn execute_txs(
         &mut self,
         txs_with_class_infos: Vec<(&PyAny, Option<PyClassInfo>)>,
+        timeout_secs: Option<f64>,
     ) -> Py<PyList> {
         // Parse Py transactions.
         let txs: Vec<Transaction> = txs_with_class_infos
@@ -193,12 +253,25 @@ impl PyBlockExecutor {
             })
             .collect();
 
+        let execution_interrupt = match timeout_secs {
+            // A deadline that already passed interrupts the execution right away.
+            Some(timeout_secs) => ExecutionInterrupt::with_deadline(
+                Instant::now() + Duration::try_from_secs_f64(timeout_secs).unwrap_or_default(),
+            ),
+            None => ExecutionInterrupt::default(),
+        };
+        self.tx_executor().block_context.set_execution_interrupt(execution_interrupt);
+
         // Run.
         let results =
             Python::with_gil(|py| py.allow_threads(|| self.tx_executor().execute_txs(&txs)));
 
         // Process results.
+        for tx_execution_info in results.iter().flatten() {
+            self.global_contract_cache.record_class_usage(tx_execution_info);
+        }
         // TODO(Yoni, 15/5/2024): serialize concurrently.
+        let panic_decoder = self.panic_decoder.clone();
         let block_context = &self.tx_executor().block_context;
         let serialized_results: Vec<(bool, RawTransactionExecutionResult)> = results
             .into_iter()
@@ -209,6 +282,7 @@ impl PyBlockExecutor {
                     ThinTransactionExecutionInfo::from_tx_execution_info(
                         block_context,
                         tx_execution_info,
+                        &panic_decoder,
                     )
                     .serialize(),
                 ),
@@ -313,6 +387,17 @@ impl PyBlockExecutor {
         self.storage.validate_aligned(source_block_number);
     }
 
+    /// Returns the (at most) `n_classes` classes with the most calls since the executor was
+    /// created, with their number of calls, from the hottest one.
+    #[pyo3(signature = (n_classes))]
+    pub fn get_hottest_classes(&self, n_classes: usize) -> Vec<(PyFelt, u64)> {
+        self.global_contract_cache
+            .hottest_classes(n_classes)
+            .into_iter()
+            .map(|(class_hash, n_calls)| (PyFelt::from(class_hash), n_calls))
+            .collect()
+    }
+
     /// Atomically reverts block header and state diff of given block number.
     /// If header exists without a state diff (usually the case), only the header is reverted.
     /// (this is true for every partial existence of information at tables).
@@ -353,15 +438,18 @@ impl PyBlockExecutor {
                     state_diff_size: max_state_diff_size,
                     ..BouncerWeights::max()
                 },
+                ..BouncerConfig::max()
             },
             tx_executor_config: TransactionExecutorConfig {
                 concurrency_config: concurrency_config.into(),
+                record_read_sets: false,
             },
             storage: Box::new(PapyrusStorage::new_for_testing(path, &os_config.chain_id)),
             chain_info: os_config.into_chain_info(),
             versioned_constants,
             tx_executor: None,
             global_contract_cache: GlobalContractCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST),
+            panic_decoder: Arc::default(),
         }
     }
 }
@@ -381,6 +469,22 @@ impl PyBlockExecutor {
         )
     }
 
+    // The storage is aligned to the block being set up, so it holds the parent block, if any.
+    fn get_parent_block_info(
+        &self,
+        next_block_number: BlockNumber,
+    ) -> NativeBlockifierResult<Option<ParentBlockInfo>> {
+        let Some(parent_block_number) = next_block_number.prev() else {
+            return Ok(None);
+        };
+        let parent_header =
+            self.storage.reader().begin_ro_txn()?.get_block_header(parent_block_number)?;
+        Ok(parent_header.map(|header| ParentBlockInfo {
+            block_number: header.block_number,
+            block_timestamp: header.timestamp,
+        }))
+    }
+
     #[cfg(any(feature = "testing", test))]
     pub fn create_for_testing_with_storage(storage: impl Storage + Send + 'static) -> Self {
         use blockifier::state::global_cache::GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST;
@@ -392,6 +496,7 @@ impl PyBlockExecutor {
             versioned_constants: VersionedConstants::latest_constants().clone(),
             tx_executor: None,
             global_contract_cache: GlobalContractCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST),
+            panic_decoder: Arc::default(),
         }
     }
 
@@ -434,6 +539,12 @@ impl TryFrom<PyOsConfig> for ChainInfo {
                     py_os_config.fee_token_address.0,
                 )?,
             },
+            eth_fee_token_deprecation_height: None,
+            fee_recipient: None,
+            fee_split: None,
+            fee_multipliers: FeeMultipliers::default(),
+            nonce_config: NonceConfig::default(),
+            tx_version_config: TransactionVersionConfig::default(),
         })
     }
 }
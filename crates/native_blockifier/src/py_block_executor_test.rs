@@ -1,16 +1,23 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
+use blockifier::abi::abi_utils::selector_from_name;
 use blockifier::blockifier::transaction_executor::BLOCK_STATE_ACCESS_ERR;
+use blockifier::context::BlockContext;
 use blockifier::execution::contract_class::{ContractClass, ContractClassV1};
+use blockifier::execution::panic_reason::{PanicDecoder, PanicItem, PanicReason};
+use blockifier::state::cached_state::ReadSet;
+use blockifier::state::errors::StateError;
 use blockifier::state::state_api::StateReader;
+use blockifier::transaction::objects::TransactionExecutionInfo;
 use cached::Cached;
 use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use pretty_assertions::assert_eq;
-use starknet_api::core::ClassHash;
+use starknet_api::core::{ClassHash, ContractAddress};
 use starknet_api::{class_hash, felt};
 use starknet_types_core::felt::Felt;
 
-use crate::py_block_executor::{PyBlockExecutor, PyOsConfig};
+use crate::errors::NativeBlockifierError;
+use crate::py_block_executor::{PyBlockExecutor, PyOsConfig, ThinTransactionExecutionInfo};
 use crate::py_objects::PyConcurrencyConfig;
 use crate::py_state_diff::{PyBlockInfo, PyStateDiff};
 use crate::py_utils::PyFelt;
@@ -66,6 +73,52 @@ fn global_contract_cache_update() {
     assert_eq!(block_executor.global_contract_cache.lock().cache_size(), 1);
 }
 
+#[test]
+fn setup_block_execution_validates_against_parent_block() {
+    let temp_storage_path = tempfile::tempdir().unwrap().into_path();
+    let mut block_executor = PyBlockExecutor::create_for_testing(
+        PyConcurrencyConfig::default(),
+        PyOsConfig::default(),
+        temp_storage_path,
+        4000,
+    );
+    let parent_timestamp = 10;
+    block_executor
+        .append_block(
+            0,
+            None,
+            PyBlockInfo { block_timestamp: parent_timestamp, ..PyBlockInfo::default() },
+            PyStateDiff::default(),
+            HashMap::default(),
+            HashMap::default(),
+        )
+        .unwrap();
+
+    let result = block_executor.setup_block_execution(
+        PyBlockInfo {
+            block_number: 1,
+            block_timestamp: parent_timestamp - 1,
+            ..PyBlockInfo::default()
+        },
+        None,
+    );
+    assert!(matches!(
+        result,
+        Err(NativeBlockifierError::StateError(StateError::DecreasingBlockTimestamp { .. }))
+    ));
+
+    block_executor
+        .setup_block_execution(
+            PyBlockInfo {
+                block_number: 1,
+                block_timestamp: parent_timestamp,
+                ..PyBlockInfo::default()
+            },
+            None,
+        )
+        .unwrap();
+}
+
 #[test]
 fn get_block_id() {
     let max_class_hash = [
@@ -84,3 +137,48 @@ fn get_block_id() {
         expected_max_class_hash_as_py_felt
     );
 }
+
+#[test]
+fn serialized_execution_info_includes_read_set() {
+    let read_set =
+        ReadSet { nonces: HashSet::from([ContractAddress::from(1_u128)]), ..Default::default() };
+    let tx_execution_info =
+        TransactionExecutionInfo { read_set: Some(read_set.clone()), ..Default::default() };
+
+    let serialized_tx_execution_info = ThinTransactionExecutionInfo::from_tx_execution_info(
+        &BlockContext::create_for_testing(),
+        tx_execution_info,
+        &PanicDecoder::default(),
+    )
+    .serialize();
+
+    let serialized_tx_execution_info: serde_json::Value =
+        serde_json::from_slice(&serialized_tx_execution_info).unwrap();
+    assert_eq!(serialized_tx_execution_info["read_set"], serde_json::to_value(read_set).unwrap());
+}
+
+#[test]
+fn serialized_execution_info_includes_decoded_revert_reason() {
+    let error_selector = selector_from_name("InsufficientBalance").0;
+    let tx_execution_info = TransactionExecutionInfo {
+        revert_error: Some("Execution failed.".to_string()),
+        revert_panic_data: Some(vec![error_selector]),
+        ..Default::default()
+    };
+
+    let serialized_tx_execution_info = ThinTransactionExecutionInfo::from_tx_execution_info(
+        &BlockContext::create_for_testing(),
+        tx_execution_info,
+        &PanicDecoder::new(["InsufficientBalance"]),
+    )
+    .serialize();
+
+    let serialized_tx_execution_info: serde_json::Value =
+        serde_json::from_slice(&serialized_tx_execution_info).unwrap();
+    let expected_revert_reason =
+        PanicReason { items: vec![PanicItem::Error("InsufficientBalance".to_string())] };
+    assert_eq!(
+        serialized_tx_execution_info["revert_reason"],
+        serde_json::to_value(expected_revert_reason).unwrap()
+    );
+}
@@ -2,7 +2,13 @@ use std::collections::HashMap;
 
 use blockifier::abi::constants;
 use blockifier::blockifier::config::ConcurrencyConfig;
-use blockifier::bouncer::{BouncerConfig, BouncerWeights, BuiltinCount, HashMapWrapper};
+use blockifier::bouncer::{
+    BouncerConfig,
+    BouncerWeights,
+    BuiltinCount,
+    BuiltinWeights,
+    HashMapWrapper,
+};
 use blockifier::versioned_constants::VersionedConstantsOverrides;
 use cairo_vm::types::builtin_name::BuiltinName;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
@@ -84,14 +90,43 @@ pub struct PyBouncerConfig {
 impl TryFrom<PyBouncerConfig> for BouncerConfig {
     type Error = NativeBlockifierError;
     fn try_from(py_bouncer_config: PyBouncerConfig) -> Result<Self, Self::Error> {
+        let mut full_total_weights = py_bouncer_config.full_total_weights;
+        let block_max_builtin_gas = full_total_weights
+            .remove(constants::BUILTIN_GAS)
+            .map_or(u128::MAX, |builtin_gas| builtin_gas as u128);
         Ok(BouncerConfig {
-            block_max_capacity: hash_map_into_bouncer_weights(
-                py_bouncer_config.full_total_weights.clone(),
-            )?,
+            block_max_capacity: hash_map_into_bouncer_weights(full_total_weights)?,
+            block_max_builtin_gas,
+            // Derived from the versioned constants of the executor, unless set by it.
+            builtin_weights: None,
         })
     }
 }
 
+/// Parses the hand-maintained builtin weights; a builtin missing from the table weighs nothing.
+pub fn hash_map_into_builtin_weights(
+    weights: HashMap<String, u64>,
+) -> Result<BuiltinWeights, NativeBlockifierInputError> {
+    let mut builtin_weights = BuiltinWeights::default();
+    for (builtin_name, weight) in weights {
+        let field = match BuiltinName::from_str_with_suffix(&builtin_name) {
+            Some(BuiltinName::add_mod) => &mut builtin_weights.add_mod,
+            Some(BuiltinName::bitwise) => &mut builtin_weights.bitwise,
+            Some(BuiltinName::ecdsa) => &mut builtin_weights.ecdsa,
+            Some(BuiltinName::ec_op) => &mut builtin_weights.ec_op,
+            Some(BuiltinName::keccak) => &mut builtin_weights.keccak,
+            Some(BuiltinName::mul_mod) => &mut builtin_weights.mul_mod,
+            Some(BuiltinName::pedersen) => &mut builtin_weights.pedersen,
+            Some(BuiltinName::poseidon) => &mut builtin_weights.poseidon,
+            Some(BuiltinName::range_check) => &mut builtin_weights.range_check,
+            Some(BuiltinName::range_check96) => &mut builtin_weights.range_check96,
+            _ => return Err(NativeBlockifierInputError::UnknownBuiltin(builtin_name)),
+        };
+        *field = weight;
+    }
+    Ok(builtin_weights)
+}
+
 fn hash_map_into_builtin_count(
     builtins: HashMap<String, usize>,
 ) -> Result<BuiltinCount, NativeBlockifierInputError> {
@@ -4,12 +4,11 @@ use std::path::PathBuf;
 
 use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use indexmap::IndexMap;
-use papyrus_storage::class::ClassStorageWriter;
-use papyrus_storage::compiled_class::CasmStorageWriter;
+use papyrus_storage::block::{BlockStorageWriter, FullBlock};
 use papyrus_storage::header::{HeaderStorageReader, HeaderStorageWriter};
 use papyrus_storage::state::{StateStorageReader, StateStorageWriter};
 use pyo3::prelude::*;
-use starknet_api::block::{BlockHash, BlockHeader, BlockNumber};
+use starknet_api::block::{BlockHash, BlockHeader, BlockNumber, BlockTimestamp};
 use starknet_api::core::{ChainId, ClassHash, CompiledClassHash, ContractAddress};
 use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
 use starknet_api::hash::StarkHash;
@@ -51,6 +50,7 @@ impl PapyrusStorage {
                 growth_step: 2 << 30,     // 2GB
                 max_object_size: 1 << 30, // 1GB
             },
+            indices: papyrus_storage::body::indices::IndicesConfig::default(),
         };
         let (reader, writer) = papyrus_storage::open_storage(storage_config)?;
         log::debug!("Initialized Blockifier storage.");
@@ -166,7 +166,7 @@ impl Storage for PapyrusStorage {
         });
 
         let mut declared_classes = IndexMap::<ClassHash, (CompiledClassHash, ContractClass)>::new();
-        let mut undeclared_casm_contracts = Vec::<(ClassHash, CasmContractClass)>::new();
+        let mut undeclared_casm_contracts = IndexMap::<ClassHash, CasmContractClass>::new();
         for (class_hash, (compiled_class_hash, raw_class)) in declared_class_hash_to_class {
             let class_hash = ClassHash(class_hash.0);
             let class_undeclared = self
@@ -182,15 +182,10 @@ impl Storage for PapyrusStorage {
                     (CompiledClassHash(compiled_class_hash.0), ContractClass::default()),
                 );
                 let contract_class: CasmContractClass = serde_json::from_str(&raw_class)?;
-                undeclared_casm_contracts.push((class_hash, contract_class));
+                undeclared_casm_contracts.insert(class_hash, contract_class);
             }
         }
 
-        let mut append_txn = self.writer().begin_rw_txn()?;
-        for (class_hash, contract_class) in undeclared_casm_contracts {
-            append_txn = append_txn.append_casm(&class_hash, &contract_class)?;
-        }
-
         // Construct state diff; manually add declared classes.
         let mut state_diff = StateDiff::try_from(py_state_diff)?;
         state_diff.deprecated_declared_classes = deprecated_declared_classes;
@@ -200,28 +195,25 @@ impl Storage for PapyrusStorage {
         let (thin_state_diff, declared_classes, deprecated_declared_classes) =
             ThinStateDiff::from_state_diff(state_diff);
 
-        append_txn = append_txn.append_state_diff(block_number, thin_state_diff)?.append_classes(
-            block_number,
-            &declared_classes
-                .iter()
-                .map(|(class_hash, contract_class)| (*class_hash, contract_class))
-                .collect::<Vec<_>>(),
-            &deprecated_declared_classes
-                .iter()
-                .map(|(class_hash, contract_class)| (*class_hash, contract_class))
-                .collect::<Vec<_>>(),
-        )?;
-
         let previous_block_id = previous_block_id.unwrap_or_else(|| PyFelt::from(GENESIS_BLOCK_ID));
         let block_header = BlockHeader {
             block_hash: BlockHash(StarkHash::from(block_id)),
             parent_hash: BlockHash(previous_block_id.0),
             block_number,
+            timestamp: BlockTimestamp(py_block_info.block_timestamp),
             ..Default::default()
         };
-        append_txn = append_txn.append_header(block_number, &block_header)?;
-
-        append_txn.commit()?;
+        // This storage doesn't track the block bodies.
+        let block = FullBlock {
+            header: block_header,
+            signature: None,
+            body: None,
+            state_diff: thin_state_diff,
+            classes: declared_classes,
+            deprecated_classes: deprecated_declared_classes,
+            casms: undeclared_casm_contracts,
+        };
+        self.writer().begin_rw_txn()?.append_block(block)?.commit()?;
         Ok(())
     }
 
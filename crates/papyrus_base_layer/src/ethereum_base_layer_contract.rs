@@ -2,20 +2,31 @@ use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use ethers::abi::{Abi, AbiEncode};
+use ethers::abi::{Abi, AbiEncode, ParamType, Token};
 use ethers::contract::Contract;
 use ethers::prelude::{AbiError, Address, ContractError, Http, Middleware, Provider};
 use ethers::providers::ProviderError;
-use ethers::types::{I256, U256};
+use ethers::types::{Filter, Log, H256, I256, U256};
 use papyrus_config::dumping::{ser_param, ser_required_param, SerializeConfig};
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializationType, SerializedParam};
 use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockHash, BlockNumber};
+use starknet_api::core::{ContractAddress, EntryPointSelector, Nonce};
 use starknet_api::hash::StarkHash;
+use starknet_api::transaction::{Calldata, Fee, L1HandlerTransaction, TransactionVersion};
 use starknet_api::StarknetApiError;
 use url::ParseError;
 
-use crate::BaseLayerContract;
+use crate::{BaseLayerContract, L1Event, PriceSample};
+
+// The parameters of the blob base fee calculation, as defined in EIP-4844.
+const MIN_BLOB_BASE_FEE: u64 = 1;
+const BLOB_BASE_FEE_UPDATE_FRACTION: u64 = 3338477;
+
+const LOG_MESSAGE_TO_L2_EVENT_SIGNATURE: &str =
+    "LogMessageToL2(address,uint256,uint256,uint256[],uint256,uint256)";
+const MESSAGE_TO_L2_CANCELED_EVENT_SIGNATURE: &str =
+    "MessageToL2Canceled(address,uint256,uint256,uint256[],uint256)";
 
 #[derive(thiserror::Error, Debug)]
 pub enum EthereumBaseLayerError {
@@ -33,6 +44,8 @@ pub enum EthereumBaseLayerError {
     BadContract(#[from] ContractError<Provider<Http>>),
     #[error(transparent)]
     StarknetApi(#[from] StarknetApiError),
+    #[error("Unexpected log of the Starknet contract: {0:?}.")]
+    UnexpectedLog(Box<Log>),
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
@@ -115,4 +128,117 @@ impl BaseLayerContract for EthereumBaseLayerContract {
             BlockHash(StarkHash::from_hex_unchecked(state_block_hash.encode_hex().as_str())),
         )))
     }
+
+    async fn latest_l1_block_number(&self) -> Result<u64, Self::Error> {
+        Ok(self.contract.client().get_block_number().await?.as_u64())
+    }
+
+    async fn get_price_sample(
+        &self,
+        block_number: u64,
+    ) -> Result<Option<PriceSample>, Self::Error> {
+        let Some(block) = self.contract.client().get_block(block_number).await? else {
+            return Ok(None);
+        };
+        let Some(base_fee_per_gas) = block.base_fee_per_gas else {
+            return Ok(None);
+        };
+        // Blocks before the introduction of blobs have no excess blob gas, and their blob fee is
+        // the minimal one.
+        let blob_fee = calculate_blob_base_fee(block.excess_blob_gas.unwrap_or_default());
+        Ok(Some(PriceSample {
+            timestamp: block.timestamp.as_u64(),
+            base_fee_per_gas: base_fee_per_gas.as_u128(),
+            blob_fee: blob_fee.try_into().unwrap_or(u128::MAX),
+        }))
+    }
+
+    async fn events(&self, from_block: u64, to_block: u64) -> Result<Vec<L1Event>, Self::Error> {
+        let filter = Filter::new()
+            .address(self.contract.address())
+            .events([LOG_MESSAGE_TO_L2_EVENT_SIGNATURE, MESSAGE_TO_L2_CANCELED_EVENT_SIGNATURE])
+            .from_block(from_block)
+            .to_block(to_block);
+        let logs = self.contract.client().get_logs(&filter).await?;
+        logs.into_iter().map(parse_event).collect()
+    }
+}
+
+// Parses a log of one of the Starknet contract's events:
+// LogMessageToL2(address indexed fromAddress, uint256 indexed toAddress, uint256 indexed selector,
+//     uint256[] payload, uint256 nonce, uint256 fee)
+// MessageToL2Canceled(address indexed fromAddress, uint256 indexed toAddress,
+//     uint256 indexed selector, uint256[] payload, uint256 nonce)
+fn parse_event(log: Log) -> Result<L1Event, EthereumBaseLayerError> {
+    let unexpected_log = || EthereumBaseLayerError::UnexpectedLog(Box::new(log.clone()));
+    let [signature, from_address, to_address, selector] = log.topics.as_slice() else {
+        return Err(unexpected_log());
+    };
+    let is_message_to_l2 = *signature == event_signature_hash(LOG_MESSAGE_TO_L2_EVENT_SIGNATURE);
+    let is_cancellation =
+        *signature == event_signature_hash(MESSAGE_TO_L2_CANCELED_EVENT_SIGNATURE);
+    let mut data_types =
+        vec![ParamType::Array(Box::new(ParamType::Uint(256))), ParamType::Uint(256)];
+    if is_message_to_l2 {
+        data_types.push(ParamType::Uint(256));
+    } else if !is_cancellation {
+        return Err(unexpected_log());
+    }
+    let data = ethers::abi::decode(&data_types, &log.data).map_err(AbiError::from)?;
+    let uint = |token: &Token| token.clone().into_uint().ok_or_else(unexpected_log);
+
+    let payload = data[0].clone().into_array().ok_or_else(unexpected_log)?;
+    let calldata = std::iter::once(Ok(h256_to_felt(from_address)))
+        .chain(payload.iter().map(|token| uint(token).map(u256_to_felt)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let tx = L1HandlerTransaction {
+        version: TransactionVersion::ZERO,
+        nonce: Nonce(u256_to_felt(uint(&data[1])?)),
+        contract_address: ContractAddress::try_from(h256_to_felt(to_address))?,
+        entry_point_selector: EntryPointSelector(h256_to_felt(selector)),
+        calldata: Calldata(Arc::new(calldata)),
+    };
+    if is_cancellation {
+        return Ok(L1Event::MessageToL2Canceled(tx));
+    }
+    let fee = Fee(uint(&data[2])?.try_into().map_err(|_| unexpected_log())?);
+    Ok(L1Event::LogMessageToL2 { tx, fee })
+}
+
+fn event_signature_hash(signature: &str) -> H256 {
+    H256(ethers::utils::keccak256(signature))
+}
+
+fn h256_to_felt(value: &H256) -> StarkHash {
+    StarkHash::from_bytes_be(&value.0)
+}
+
+fn u256_to_felt(value: U256) -> StarkHash {
+    let mut bytes = [0_u8; 32];
+    value.to_big_endian(&mut bytes);
+    StarkHash::from_bytes_be(&bytes)
+}
+
+// The blob base fee of a block, by its excess blob gas (see `get_base_fee_per_blob_gas` in
+// EIP-4844).
+pub(crate) fn calculate_blob_base_fee(excess_blob_gas: U256) -> U256 {
+    fake_exponential(
+        U256::from(MIN_BLOB_BASE_FEE),
+        excess_blob_gas,
+        U256::from(BLOB_BASE_FEE_UPDATE_FRACTION),
+    )
+}
+
+// Approximates `factor * e ** (numerator / denominator)` using a Taylor expansion, as defined in
+// EIP-4844.
+fn fake_exponential(factor: U256, numerator: U256, denominator: U256) -> U256 {
+    let mut i = U256::one();
+    let mut output = U256::zero();
+    let mut numerator_accum = factor * denominator;
+    while !numerator_accum.is_zero() {
+        output = output.saturating_add(numerator_accum);
+        numerator_accum = numerator_accum.saturating_mul(numerator) / (denominator * i);
+        i += U256::one();
+    }
+    output / denominator
 }
@@ -0,0 +1,162 @@
+//! Tracks the finality of L2 blocks on the base layer.
+//!
+//! A block is accepted on L2 once it's decided, sent to L1 once the [`L1Publisher`] submitted its
+//! state update, and accepted on L1 once the base layer proved it with enough confirmations (see
+//! [`BlockFinalityStatus`]). The tracker records the last two in the storage, as the markers of the
+//! first block that wasn't sent and of the first block that wasn't accepted on L1.
+//!
+//! A reorg of the base layer can revert the proof of blocks that were already accepted on L1. The
+//! tracker follows the latest proved block, also when it goes back, so these blocks return to being
+//! sent to L1 until they're proved again.
+//!
+//! [`L1Publisher`]: crate::l1_publisher::L1Publisher
+//! [`BlockFinalityStatus`]: papyrus_storage::base_layer::BlockFinalityStatus
+
+#[cfg(test)]
+#[path = "l1_finality_test.rs"]
+mod l1_finality_test;
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::time::Duration;
+
+use papyrus_config::converters::deserialize_seconds_to_duration;
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use papyrus_storage::base_layer::{BaseLayerStorageReader, BaseLayerStorageWriter};
+use papyrus_storage::header::HeaderStorageReader;
+use papyrus_storage::{StorageError, StorageWriter};
+use serde::{Deserialize, Serialize};
+use starknet_api::block::{BlockHash, BlockNumber};
+use tracing::{debug, info, warn};
+
+use crate::BaseLayerContract;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct L1FinalityTrackerConfig {
+    #[serde(deserialize_with = "deserialize_seconds_to_duration")]
+    pub polling_interval: Duration,
+    pub min_confirmations: u64,
+}
+
+impl SerializeConfig for L1FinalityTrackerConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "polling_interval",
+                &self.polling_interval.as_secs(),
+                "Time in seconds between polls of the base layer for the latest proved block.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "min_confirmations",
+                &self.min_confirmations,
+                "The number of confirmations the proof of a block needs before the block is \
+                 accepted on L1.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+impl Default for L1FinalityTrackerConfig {
+    fn default() -> Self {
+        Self { polling_interval: Duration::from_secs(12), min_confirmations: 10 }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum L1FinalityTrackerError<E: Debug> {
+    #[error("Base layer error: {0:?}")]
+    BaseLayer(E),
+    #[error(
+        "Block {block_number} is proved on the base layer with hash {base_layer_hash}, but its \
+         hash is {l2_hash}."
+    )]
+    BlockHashMismatch { block_number: BlockNumber, base_layer_hash: BlockHash, l2_hash: BlockHash },
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+}
+
+pub struct L1FinalityTracker<B: BaseLayerContract> {
+    config: L1FinalityTrackerConfig,
+    base_layer: B,
+}
+
+impl<B: BaseLayerContract> L1FinalityTracker<B>
+where
+    B::Error: Debug,
+{
+    pub fn new(config: L1FinalityTrackerConfig, base_layer: B) -> Self {
+        Self { config, base_layer }
+    }
+
+    /// The time between updates of the blocks that are accepted on L1.
+    pub fn polling_interval(&self) -> Duration {
+        self.config.polling_interval
+    }
+
+    /// Updates the marker of the blocks that are accepted on L1 to follow the latest proved block,
+    /// and returns it. The marker goes back if a reorg of the base layer reverted proofs, and stays
+    /// if no block is proved with enough confirmations.
+    pub async fn update_accepted_on_l1(
+        &mut self,
+        storage_writer: &mut StorageWriter,
+    ) -> Result<BlockNumber, L1FinalityTrackerError<B::Error>> {
+        let latest_proved_block = self
+            .base_layer
+            .latest_proved_block(Some(self.config.min_confirmations))
+            .await
+            .map_err(L1FinalityTrackerError::BaseLayer)?;
+        let txn = storage_writer.begin_rw_txn()?;
+        let marker = txn.get_base_layer_block_marker()?;
+        let Some((block_number, base_layer_hash)) = latest_proved_block else {
+            debug!("No block is proved on the base layer with enough confirmations.");
+            return Ok(marker);
+        };
+        let Some(header) = txn.get_block_header(block_number)? else {
+            debug!("Block {block_number} is proved on the base layer but isn't stored yet.");
+            return Ok(marker);
+        };
+        if header.block_hash != base_layer_hash {
+            return Err(L1FinalityTrackerError::BlockHashMismatch {
+                block_number,
+                base_layer_hash,
+                l2_hash: header.block_hash,
+            });
+        }
+
+        let new_marker = block_number.unchecked_next();
+        if new_marker < marker {
+            warn!(
+                "A base layer reorg reverted the proofs of blocks {new_marker} to {}.",
+                block_before(marker)
+            );
+        } else if new_marker > marker {
+            info!("Blocks {marker} to {} are accepted on L1.", block_before(new_marker));
+        }
+        if new_marker != marker {
+            txn.update_base_layer_block_marker(&new_marker)?.commit()?;
+        }
+        Ok(new_marker)
+    }
+
+    /// Records that the state updates of the blocks below `sent_marker` were sent to L1.
+    pub fn update_sent_to_l1(
+        &self,
+        storage_writer: &mut StorageWriter,
+        sent_marker: BlockNumber,
+    ) -> Result<(), L1FinalityTrackerError<B::Error>> {
+        let txn = storage_writer.begin_rw_txn()?;
+        let marker = txn.get_sent_to_base_layer_block_marker()?;
+        if sent_marker > marker {
+            debug!("Blocks {marker} to {} were sent to L1.", block_before(sent_marker));
+            txn.update_sent_to_base_layer_block_marker(&sent_marker)?.commit()?;
+        }
+        Ok(())
+    }
+}
+
+fn block_before(marker: BlockNumber) -> BlockNumber {
+    marker.prev().expect("A positive marker should have a previous block.")
+}
@@ -0,0 +1,117 @@
+use assert_matches::assert_matches;
+use papyrus_storage::base_layer::{BaseLayerStorageReader, BlockFinalityStatus};
+use papyrus_storage::header::HeaderStorageWriter;
+use papyrus_storage::test_utils::get_test_storage;
+use papyrus_storage::{StorageReader, StorageWriter};
+use starknet_api::block::{BlockHash, BlockHeader, BlockNumber};
+use starknet_api::felt;
+use tempfile::TempDir;
+
+use crate::l1_finality::{L1FinalityTracker, L1FinalityTrackerConfig, L1FinalityTrackerError};
+use crate::test_utils::FakeBaseLayer;
+
+const N_BLOCKS: u64 = 5;
+
+fn block_hash(block_number: u64) -> BlockHash {
+    BlockHash(felt!(block_number + 1))
+}
+
+fn finality_status(reader: &StorageReader, block_number: u64) -> BlockFinalityStatus {
+    reader
+        .begin_ro_txn()
+        .unwrap()
+        .get_block_finality_status(BlockNumber(block_number))
+        .unwrap()
+        .unwrap()
+}
+
+// Returns a tracker, and a storage with `N_BLOCKS` blocks.
+fn tracker_with_blocks(
+    base_layer: FakeBaseLayer,
+) -> (L1FinalityTracker<FakeBaseLayer>, StorageReader, StorageWriter, TempDir) {
+    let ((reader, mut writer), temp_dir) = get_test_storage();
+    let mut txn = writer.begin_rw_txn().unwrap();
+    for block_number in 0..N_BLOCKS {
+        let header = BlockHeader {
+            block_hash: block_hash(block_number),
+            block_number: BlockNumber(block_number),
+            ..Default::default()
+        };
+        txn = txn.append_header(header.block_number, &header).unwrap();
+    }
+    txn.commit().unwrap();
+    let tracker = L1FinalityTracker::new(L1FinalityTrackerConfig::default(), base_layer);
+    (tracker, reader, writer, temp_dir)
+}
+
+#[tokio::test]
+async fn track_finality_through_reorg() {
+    let base_layer = FakeBaseLayer::default();
+    let (mut tracker, reader, mut writer, _temp_dir) = tracker_with_blocks(base_layer.clone());
+
+    tracker.update_sent_to_l1(&mut writer, BlockNumber(4)).unwrap();
+    base_layer.set_latest_proved_block(Some((BlockNumber(2), block_hash(2))));
+    assert_eq!(tracker.update_accepted_on_l1(&mut writer).await.unwrap(), BlockNumber(3));
+    let statuses = |reader: &StorageReader| {
+        (0..N_BLOCKS).map(|block_number| finality_status(reader, block_number)).collect::<Vec<_>>()
+    };
+    assert_eq!(
+        statuses(&reader),
+        vec![
+            BlockFinalityStatus::AcceptedOnL1,
+            BlockFinalityStatus::AcceptedOnL1,
+            BlockFinalityStatus::AcceptedOnL1,
+            BlockFinalityStatus::SentToL1,
+            BlockFinalityStatus::AcceptedOnL2,
+        ]
+    );
+
+    // A base layer reorg reverts the proof of block 2.
+    base_layer.set_latest_proved_block(Some((BlockNumber(1), block_hash(1))));
+    assert_eq!(tracker.update_accepted_on_l1(&mut writer).await.unwrap(), BlockNumber(2));
+    assert_eq!(finality_status(&reader, 2), BlockFinalityStatus::SentToL1);
+
+    // A base layer without a proved block with enough confirmations leaves the marker.
+    base_layer.set_latest_proved_block(None);
+    assert_eq!(tracker.update_accepted_on_l1(&mut writer).await.unwrap(), BlockNumber(2));
+    assert_eq!(finality_status(&reader, 1), BlockFinalityStatus::AcceptedOnL1);
+}
+
+#[tokio::test]
+async fn proved_block_not_stored_yet() {
+    let base_layer = FakeBaseLayer::default();
+    let (mut tracker, reader, mut writer, _temp_dir) = tracker_with_blocks(base_layer.clone());
+
+    base_layer.set_latest_proved_block(Some((BlockNumber(1), block_hash(1))));
+    tracker.update_accepted_on_l1(&mut writer).await.unwrap();
+    base_layer.set_latest_proved_block(Some((BlockNumber(N_BLOCKS), block_hash(N_BLOCKS))));
+    assert_eq!(tracker.update_accepted_on_l1(&mut writer).await.unwrap(), BlockNumber(2));
+    assert_eq!(
+        reader.begin_ro_txn().unwrap().get_base_layer_block_marker().unwrap(),
+        BlockNumber(2)
+    );
+}
+
+#[tokio::test]
+async fn proved_block_hash_mismatch() {
+    let base_layer = FakeBaseLayer::default();
+    let (mut tracker, _reader, mut writer, _temp_dir) = tracker_with_blocks(base_layer.clone());
+
+    base_layer.set_latest_proved_block(Some((BlockNumber(1), block_hash(2))));
+    assert_matches!(
+        tracker.update_accepted_on_l1(&mut writer).await,
+        Err(L1FinalityTrackerError::BlockHashMismatch { block_number: BlockNumber(1), .. })
+    );
+}
+
+#[test]
+fn sent_marker_only_advances() {
+    let (tracker, reader, mut writer, _temp_dir) = tracker_with_blocks(FakeBaseLayer::default());
+
+    tracker.update_sent_to_l1(&mut writer, BlockNumber(3)).unwrap();
+    tracker.update_sent_to_l1(&mut writer, BlockNumber(2)).unwrap();
+    assert_eq!(
+        reader.begin_ro_txn().unwrap().get_sent_to_base_layer_block_marker().unwrap(),
+        BlockNumber(3)
+    );
+}
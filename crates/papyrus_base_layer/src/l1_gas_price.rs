@@ -0,0 +1,185 @@
+//! Samples the gas prices of the base layer, for pricing the L1 gas and L1 data gas of blocks.
+//!
+//! The sampler polls the base layer for new blocks and keeps the base fee and blob fee of the
+//! latest `number_of_blocks_for_mean` blocks. The prices published to the [`GasPriceProvider`] are
+//! the means of the sampled prices, in wei, and their conversions to fri.
+
+#[cfg(test)]
+#[path = "l1_gas_price_test.rs"]
+mod l1_gas_price_test;
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Debug;
+use std::time::Duration;
+
+use papyrus_config::converters::deserialize_seconds_to_duration;
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+use starknet_api::block::{GasPrice, GasPricePerToken};
+use tokio::sync::watch;
+use tracing::{debug, warn};
+use validator::Validate;
+
+use crate::{BaseLayerContract, PriceSample};
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Validate)]
+pub struct L1GasPriceSamplerConfig {
+    #[serde(deserialize_with = "deserialize_seconds_to_duration")]
+    pub polling_interval: Duration,
+    #[validate(range(min = 1))]
+    pub number_of_blocks_for_mean: u64,
+    pub wei_to_fri_rate: u128,
+}
+
+impl SerializeConfig for L1GasPriceSamplerConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "polling_interval",
+                &self.polling_interval.as_secs(),
+                "Time in seconds between polls of the base layer for new blocks.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "number_of_blocks_for_mean",
+                &self.number_of_blocks_for_mean,
+                "The number of latest base layer blocks whose gas prices are averaged.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "wei_to_fri_rate",
+                &self.wei_to_fri_rate,
+                "The number of fri a wei is worth, for converting the gas prices to fri.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+impl Default for L1GasPriceSamplerConfig {
+    fn default() -> Self {
+        Self {
+            polling_interval: Duration::from_secs(12),
+            number_of_blocks_for_mean: 300,
+            wei_to_fri_rate: 5000,
+        }
+    }
+}
+
+/// The gas prices of the base layer, in the form a block's header holds them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct L1GasPrices {
+    pub l1_gas_price: GasPricePerToken,
+    pub l1_data_gas_price: GasPricePerToken,
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum GasPriceProviderError {
+    #[error("No gas prices were sampled from the base layer yet.")]
+    NoSamples,
+}
+
+/// A handle to the latest gas prices of the base layer, for the component that builds blocks.
+#[derive(Clone, Debug)]
+pub struct GasPriceProvider {
+    receiver: watch::Receiver<Option<L1GasPrices>>,
+}
+
+impl GasPriceProvider {
+    /// A provider of gas prices that are set rather than sampled, e.g. for a network without a
+    /// base layer.
+    pub fn fixed(gas_prices: L1GasPrices) -> Self {
+        let (_, receiver) = watch::channel(Some(gas_prices));
+        Self { receiver }
+    }
+
+    pub fn get_gas_prices(&self) -> Result<L1GasPrices, GasPriceProviderError> {
+        (*self.receiver.borrow()).ok_or(GasPriceProviderError::NoSamples)
+    }
+}
+
+pub struct L1GasPriceSampler<B: BaseLayerContract> {
+    config: L1GasPriceSamplerConfig,
+    base_layer: B,
+    samples: VecDeque<PriceSample>,
+    last_sampled_block: Option<u64>,
+    sender: watch::Sender<Option<L1GasPrices>>,
+}
+
+impl<B: BaseLayerContract> L1GasPriceSampler<B> {
+    pub fn new(config: L1GasPriceSamplerConfig, base_layer: B) -> (Self, GasPriceProvider) {
+        let (sender, receiver) = watch::channel(None);
+        let sampler =
+            Self { config, base_layer, samples: VecDeque::new(), last_sampled_block: None, sender };
+        (sampler, GasPriceProvider { receiver })
+    }
+
+    /// Samples the blocks added to the base layer since the last sampling, and publishes the
+    /// updated gas prices.
+    pub async fn sample(&mut self) -> Result<(), B::Error> {
+        let latest_block = self.base_layer.latest_l1_block_number().await?;
+        // Only the blocks in the window of the mean are sampled.
+        let first_block_in_window =
+            latest_block.saturating_sub(self.config.number_of_blocks_for_mean.saturating_sub(1));
+        let first_block = match self.last_sampled_block {
+            Some(last_sampled_block) => first_block_in_window.max(last_sampled_block + 1),
+            None => first_block_in_window,
+        };
+
+        for block_number in first_block..=latest_block {
+            if let Some(sample) = self.base_layer.get_price_sample(block_number).await? {
+                self.samples.push_back(sample);
+            }
+            self.last_sampled_block = Some(block_number);
+        }
+        let window_size = usize::try_from(self.config.number_of_blocks_for_mean)
+            .expect("The number of blocks for the mean should fit in usize.");
+        while self.samples.len() > window_size {
+            self.samples.pop_front();
+        }
+
+        if let Some(gas_prices) = self.gas_prices() {
+            debug!("Sampled the base layer up to block {latest_block}. Gas prices: {gas_prices:?}");
+            self.sender.send_replace(Some(gas_prices));
+        }
+        Ok(())
+    }
+
+    /// Samples the base layer every polling interval. Runs indefinitely.
+    pub async fn run(mut self)
+    where
+        B::Error: Debug,
+    {
+        loop {
+            if let Err(err) = self.sample().await {
+                warn!("Failed to sample the gas prices of the base layer: {err:?}");
+            }
+            tokio::time::sleep(self.config.polling_interval).await;
+        }
+    }
+
+    // The means of the sampled prices, or None if there are no samples.
+    fn gas_prices(&self) -> Option<L1GasPrices> {
+        let n_samples = u128::try_from(self.samples.len()).expect("usize should fit in u128");
+        if n_samples == 0 {
+            return None;
+        }
+        let mean = |price: fn(&PriceSample) -> u128| {
+            self.samples.iter().map(price).fold(0_u128, u128::saturating_add) / n_samples
+        };
+        Some(L1GasPrices {
+            l1_gas_price: self.price_per_token(mean(|sample| sample.base_fee_per_gas)),
+            l1_data_gas_price: self.price_per_token(mean(|sample| sample.blob_fee)),
+        })
+    }
+
+    // Blocks must have non-zero gas prices.
+    fn price_per_token(&self, price_in_wei: u128) -> GasPricePerToken {
+        let price_in_wei = price_in_wei.max(1);
+        GasPricePerToken {
+            price_in_wei: GasPrice(price_in_wei),
+            price_in_fri: GasPrice(price_in_wei.saturating_mul(self.config.wei_to_fri_rate).max(1)),
+        }
+    }
+}
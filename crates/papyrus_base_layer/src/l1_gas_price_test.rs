@@ -0,0 +1,93 @@
+use ethers::types::U256;
+use starknet_api::block::{GasPrice, GasPricePerToken};
+
+use crate::ethereum_base_layer_contract::calculate_blob_base_fee;
+use crate::l1_gas_price::{
+    GasPriceProvider,
+    GasPriceProviderError,
+    L1GasPriceSampler,
+    L1GasPriceSamplerConfig,
+    L1GasPrices,
+};
+use crate::test_utils::{FakeBaseLayer, FakeBlock};
+use crate::PriceSample;
+
+fn add_block(base_layer: &FakeBaseLayer, base_fee_per_gas: u128, blob_fee: u128) {
+    let price_sample = PriceSample { timestamp: 0, base_fee_per_gas, blob_fee };
+    base_layer.add_block(FakeBlock { price_sample, ..Default::default() });
+}
+
+fn gas_prices(
+    l1_gas_price_in_wei: u128,
+    l1_data_gas_price_in_wei: u128,
+    rate: u128,
+) -> L1GasPrices {
+    L1GasPrices {
+        l1_gas_price: GasPricePerToken {
+            price_in_wei: GasPrice(l1_gas_price_in_wei),
+            price_in_fri: GasPrice(l1_gas_price_in_wei * rate),
+        },
+        l1_data_gas_price: GasPricePerToken {
+            price_in_wei: GasPrice(l1_data_gas_price_in_wei),
+            price_in_fri: GasPrice(l1_data_gas_price_in_wei * rate),
+        },
+    }
+}
+
+#[tokio::test]
+async fn mean_of_the_latest_blocks() {
+    let base_layer = FakeBaseLayer::default();
+    let config = L1GasPriceSamplerConfig {
+        number_of_blocks_for_mean: 3,
+        wei_to_fri_rate: 10,
+        ..Default::default()
+    };
+    let (mut sampler, provider) = L1GasPriceSampler::new(config, base_layer.clone());
+    assert_eq!(provider.get_gas_prices(), Err(GasPriceProviderError::NoSamples));
+
+    add_block(&base_layer, 100, 10);
+    add_block(&base_layer, 200, 20);
+    sampler.sample().await.unwrap();
+    assert_eq!(provider.get_gas_prices(), Ok(gas_prices(150, 15, 10)));
+
+    // The oldest block leaves the window.
+    add_block(&base_layer, 300, 30);
+    add_block(&base_layer, 400, 40);
+    sampler.sample().await.unwrap();
+    assert_eq!(provider.get_gas_prices(), Ok(gas_prices(300, 30, 10)));
+
+    // No new blocks.
+    sampler.sample().await.unwrap();
+    assert_eq!(provider.get_gas_prices(), Ok(gas_prices(300, 30, 10)));
+}
+
+#[tokio::test]
+async fn gas_prices_are_non_zero() {
+    let base_layer = FakeBaseLayer::default();
+    add_block(&base_layer, 0, 0);
+    let config = L1GasPriceSamplerConfig { wei_to_fri_rate: 0, ..Default::default() };
+    let (mut sampler, provider) = L1GasPriceSampler::new(config, base_layer);
+
+    sampler.sample().await.unwrap();
+    let min_price = GasPricePerToken { price_in_wei: GasPrice(1), price_in_fri: GasPrice(1) };
+    assert_eq!(
+        provider.get_gas_prices(),
+        Ok(L1GasPrices { l1_gas_price: min_price, l1_data_gas_price: min_price })
+    );
+}
+
+#[test]
+fn blob_base_fee() {
+    assert_eq!(calculate_blob_base_fee(U256::zero()), U256::one());
+    // The blob base fee increases by a factor of e every `BLOB_BASE_FEE_UPDATE_FRACTION` excess
+    // blob gas.
+    assert_eq!(calculate_blob_base_fee(U256::from(3338477)), U256::from(2));
+    assert_eq!(calculate_blob_base_fee(U256::from(10 * 3338477)), U256::from(22026));
+}
+
+#[test]
+fn fixed_gas_prices() {
+    let fixed_gas_prices = gas_prices(100, 10, 1);
+    let provider = GasPriceProvider::fixed(fixed_gas_prices);
+    assert_eq!(provider.get_gas_prices(), Ok(fixed_gas_prices));
+}
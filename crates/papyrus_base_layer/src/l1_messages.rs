@@ -0,0 +1,196 @@
+//! Ingests the messages sent from L1 to L2.
+//!
+//! The scraper polls the Starknet contract for the messages sent to L2 in finalized base layer
+//! blocks, and queues their L1 handler transactions in an [`L1HandlerQueue`]. A message that is
+//! canceled on L1 before it's sequenced is removed from the queue.
+//!
+//! The queue is shared with the component that builds blocks: a proposer takes the pending
+//! transactions in the order of their message nonces, a validator accepts only transactions that
+//! are pending, and once a block is decided its transactions are committed, so every node includes
+//! the same messages in the same order.
+
+#[cfg(test)]
+#[path = "l1_messages_test.rs"]
+mod l1_messages_test;
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+
+use papyrus_config::converters::deserialize_seconds_to_duration;
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+use starknet_api::core::Nonce;
+use starknet_api::transaction::{Fee, L1HandlerTransaction};
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+use validator::Validate;
+
+use crate::{BaseLayerContract, L1Event};
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Validate)]
+pub struct L1MessagesScraperConfig {
+    #[serde(deserialize_with = "deserialize_seconds_to_duration")]
+    pub polling_interval: Duration,
+    pub finality: u64,
+    pub start_l1_block_number: u64,
+    #[validate(range(min = 1))]
+    pub max_blocks_per_scrape: u64,
+}
+
+impl SerializeConfig for L1MessagesScraperConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "polling_interval",
+                &self.polling_interval.as_secs(),
+                "Time in seconds between polls of the base layer for new messages.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "finality",
+                &self.finality,
+                "The number of confirmations a base layer block needs before its messages are \
+                 queued.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "start_l1_block_number",
+                &self.start_l1_block_number,
+                "The base layer block to start scraping messages from.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_blocks_per_scrape",
+                &self.max_blocks_per_scrape,
+                "Max amount of base layer blocks to get the messages of in a single request.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+impl Default for L1MessagesScraperConfig {
+    fn default() -> Self {
+        Self {
+            polling_interval: Duration::from_secs(12),
+            finality: 10,
+            start_l1_block_number: 0,
+            max_blocks_per_scrape: 1000,
+        }
+    }
+}
+
+/// The L1 handler transaction of a message to L2, and the fee paid for it on L1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct L1HandlerMessage {
+    pub tx: L1HandlerTransaction,
+    pub paid_fee_on_l1: Fee,
+}
+
+/// A handle to the queue of the messages to L2 that weren't sequenced yet.
+pub type SharedL1HandlerQueue = Arc<Mutex<L1HandlerQueue>>;
+
+/// The messages to L2 that weren't sequenced yet, ordered by their nonces.
+#[derive(Debug, Default)]
+pub struct L1HandlerQueue {
+    pending: BTreeMap<Nonce, L1HandlerMessage>,
+}
+
+impl L1HandlerQueue {
+    pub fn add(&mut self, message: L1HandlerMessage) {
+        self.pending.entry(message.tx.nonce).or_insert(message);
+    }
+
+    pub fn cancel(&mut self, tx: &L1HandlerTransaction) {
+        if self.pending.get(&tx.nonce).is_some_and(|message| message.tx == *tx) {
+            self.pending.remove(&tx.nonce);
+        }
+    }
+
+    /// Returns up to `n_txs` pending messages, lowest nonces first, for a proposal. The messages
+    /// stay pending until the block that includes them is committed.
+    pub fn get_txs(&self, n_txs: usize) -> Vec<L1HandlerMessage> {
+        self.pending.values().take(n_txs).cloned().collect()
+    }
+
+    /// Whether the transaction, in a proposal, handles a pending message.
+    pub fn validate(&self, tx: &L1HandlerTransaction) -> bool {
+        self.pending.get(&tx.nonce).is_some_and(|message| message.tx == *tx)
+    }
+
+    /// Removes the messages that the transactions of a decided block handled.
+    pub fn commit_block(&mut self, txs: &[L1HandlerTransaction]) {
+        for tx in txs {
+            self.pending.remove(&tx.nonce);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+pub struct L1MessagesScraper<B: BaseLayerContract> {
+    config: L1MessagesScraperConfig,
+    base_layer: B,
+    next_block: u64,
+    queue: SharedL1HandlerQueue,
+}
+
+impl<B: BaseLayerContract> L1MessagesScraper<B> {
+    pub fn new(config: L1MessagesScraperConfig, base_layer: B) -> (Self, SharedL1HandlerQueue) {
+        let queue = SharedL1HandlerQueue::default();
+        let next_block = config.start_l1_block_number;
+        (Self { config, base_layer, next_block, queue: queue.clone() }, queue)
+    }
+
+    /// Queues the messages of the base layer blocks that became final since the last scrape.
+    pub async fn scrape(&mut self) -> Result<(), B::Error> {
+        let latest_block = self.base_layer.latest_l1_block_number().await?;
+        let Some(last_final_block) = latest_block.checked_sub(self.config.finality) else {
+            return Ok(());
+        };
+        while self.next_block <= last_final_block {
+            let to_block = last_final_block
+                .min(self.next_block.saturating_add(self.config.max_blocks_per_scrape - 1));
+            let events = self.base_layer.events(self.next_block, to_block).await?;
+            debug!(
+                "Got {} events of base layer blocks {} to {to_block}.",
+                events.len(),
+                self.next_block
+            );
+
+            let mut queue = self.queue.lock().await;
+            for event in events {
+                match event {
+                    L1Event::LogMessageToL2 { tx, fee } => {
+                        queue.add(L1HandlerMessage { tx, paid_fee_on_l1: fee })
+                    }
+                    L1Event::MessageToL2Canceled(tx) => queue.cancel(&tx),
+                }
+            }
+            self.next_block = to_block + 1;
+        }
+        Ok(())
+    }
+
+    /// Scrapes the base layer every polling interval. Runs indefinitely.
+    pub async fn run(mut self)
+    where
+        B::Error: Debug,
+    {
+        loop {
+            if let Err(err) = self.scrape().await {
+                warn!("Failed to scrape the messages to L2 from the base layer: {err:?}");
+            }
+            tokio::time::sleep(self.config.polling_interval).await;
+        }
+    }
+}
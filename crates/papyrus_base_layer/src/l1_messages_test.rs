@@ -0,0 +1,72 @@
+use starknet_api::core::Nonce;
+use starknet_api::transaction::{Fee, L1HandlerTransaction};
+use starknet_types_core::felt::Felt;
+
+use crate::l1_messages::{
+    L1HandlerMessage,
+    L1HandlerQueue,
+    L1MessagesScraper,
+    L1MessagesScraperConfig,
+};
+use crate::test_utils::{FakeBaseLayer, FakeBlock};
+use crate::L1Event;
+
+fn l1_handler_message(nonce: u64) -> L1HandlerMessage {
+    L1HandlerMessage {
+        tx: L1HandlerTransaction { nonce: Nonce(Felt::from(nonce)), ..Default::default() },
+        paid_fee_on_l1: Fee(1),
+    }
+}
+
+fn message_to_l2(nonce: u64) -> L1Event {
+    let L1HandlerMessage { tx, paid_fee_on_l1 } = l1_handler_message(nonce);
+    L1Event::LogMessageToL2 { tx, fee: paid_fee_on_l1 }
+}
+
+fn add_block(base_layer: &FakeBaseLayer, events: Vec<L1Event>) {
+    base_layer.add_block(FakeBlock { events, ..Default::default() });
+}
+
+#[tokio::test]
+async fn scrape_final_blocks() {
+    let base_layer = FakeBaseLayer::default();
+    let config =
+        L1MessagesScraperConfig { finality: 1, max_blocks_per_scrape: 2, ..Default::default() };
+    let (mut scraper, queue) = L1MessagesScraper::new(config, base_layer.clone());
+
+    add_block(&base_layer, vec![message_to_l2(1)]);
+    add_block(&base_layer, vec![message_to_l2(0), message_to_l2(2)]);
+    add_block(&base_layer, vec![message_to_l2(3)]);
+    add_block(&base_layer, vec![message_to_l2(4)]);
+    scraper.scrape().await.unwrap();
+    // The last block isn't final yet.
+    assert_eq!(queue.lock().await.get_txs(10), (0..4).map(l1_handler_message).collect::<Vec<_>>());
+
+    add_block(&base_layer, vec![L1Event::MessageToL2Canceled(l1_handler_message(2).tx)]);
+    scraper.scrape().await.unwrap();
+    assert_eq!(
+        queue.lock().await.get_txs(10),
+        [0, 1, 3, 4].into_iter().map(l1_handler_message).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn queue_commits_decided_transactions() {
+    let mut queue = L1HandlerQueue::default();
+    for nonce in [2, 0, 1] {
+        queue.add(l1_handler_message(nonce));
+    }
+    // A message is queued once.
+    queue.add(l1_handler_message(0));
+    assert_eq!(queue.len(), 3);
+
+    let proposed_txs: Vec<L1HandlerTransaction> =
+        queue.get_txs(2).into_iter().map(|message| message.tx).collect();
+    assert_eq!(proposed_txs, vec![l1_handler_message(0).tx, l1_handler_message(1).tx]);
+    assert!(proposed_txs.iter().all(|tx| queue.validate(tx)));
+    assert!(!queue.validate(&l1_handler_message(3).tx));
+
+    queue.commit_block(&proposed_txs);
+    assert_eq!(queue.get_txs(2), vec![l1_handler_message(2)]);
+    assert!(!queue.validate(&l1_handler_message(0).tx));
+}
@@ -0,0 +1,354 @@
+//! Publishes the state diffs of decided blocks to the base layer.
+//!
+//! The publisher collects the state diffs of the decided blocks, in order, and submits them in
+//! batches of up to `max_blocks_per_batch` blocks. A batch is encoded as on-chain data, to be sent
+//! in calldata or in blobs, and submitted by a [`StateUpdateSubmitter`]. A failed submission is
+//! retried with a higher gas price, and the nonce of the submitting account is re-read from the
+//! base layer, in case the failure left it out of sync.
+
+#[cfg(test)]
+#[path = "l1_publisher_test.rs"]
+mod l1_publisher_test;
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::Debug;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use papyrus_config::converters::deserialize_milliseconds_to_duration;
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::ContractAddress;
+use starknet_api::data_availability::L1DataAvailabilityMode;
+use starknet_api::hash::StarkHash;
+use starknet_api::state::ThinStateDiff;
+use tokio::sync::{mpsc, watch};
+use tracing::{info, warn};
+use validator::Validate;
+
+/// The number of field elements in a blob, as defined in EIP-4844.
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Validate)]
+pub struct L1PublisherConfig {
+    pub l1_da_mode: L1DataAvailabilityMode,
+    #[validate(range(min = 1))]
+    pub max_blocks_per_batch: usize,
+    pub max_retries: usize,
+    #[serde(deserialize_with = "deserialize_milliseconds_to_duration")]
+    pub retry_interval: Duration,
+    pub gas_price_bump_percentage: u64,
+}
+
+impl SerializeConfig for L1PublisherConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "l1_da_mode",
+                &self.l1_da_mode,
+                "Whether the state diffs are published in calldata or in blobs.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_blocks_per_batch",
+                &self.max_blocks_per_batch,
+                "Max amount of blocks whose state diffs are published in a single state update.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_retries",
+                &self.max_retries,
+                "Max amount of times to retry a failed state update submission.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "retry_interval",
+                &self.retry_interval.as_millis(),
+                "Time in milliseconds to wait before retrying a failed state update submission.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "gas_price_bump_percentage",
+                &self.gas_price_bump_percentage,
+                "The percentage by which the gas price is raised on every retry of a submission.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+impl Default for L1PublisherConfig {
+    fn default() -> Self {
+        Self {
+            l1_da_mode: L1DataAvailabilityMode::Blob,
+            max_blocks_per_batch: 10,
+            max_retries: 5,
+            retry_interval: Duration::from_millis(12000),
+            gas_price_bump_percentage: 10,
+        }
+    }
+}
+
+/// The state diffs of consecutive blocks, encoded for the base layer.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EncodedStateUpdate {
+    pub first_block: BlockNumber,
+    pub last_block: BlockNumber,
+    pub l1_da_mode: L1DataAvailabilityMode,
+    pub data: Vec<StarkHash>,
+}
+
+impl EncodedStateUpdate {
+    /// The data split into blobs. The last blob is padded with zeros.
+    pub fn blobs(&self) -> Vec<Vec<StarkHash>> {
+        self.data
+            .chunks(FIELD_ELEMENTS_PER_BLOB)
+            .map(|chunk| {
+                let mut blob = chunk.to_vec();
+                blob.resize(FIELD_ELEMENTS_PER_BLOB, StarkHash::ZERO);
+                blob
+            })
+            .collect()
+    }
+}
+
+/// Submits state updates to the Starknet contract on the base layer.
+#[async_trait]
+pub trait StateUpdateSubmitter {
+    type Error: Debug + Send;
+
+    /// The nonce of the next transaction of the submitting account, by the base layer.
+    async fn next_nonce(&self) -> Result<u64, Self::Error>;
+
+    /// Sends the transaction that submits the state update with the given nonce, and waits for
+    /// its inclusion. The gas price is raised by `gas_price_bump_percentage` percent over the
+    /// price the base layer currently suggests.
+    async fn submit(
+        &self,
+        state_update: &EncodedStateUpdate,
+        nonce: u64,
+        gas_price_bump_percentage: u64,
+    ) -> Result<(), Self::Error>;
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum L1PublisherError<E: Debug> {
+    #[error("Expected the state diff of block {expected}, got block {got}.")]
+    UnexpectedBlock { expected: BlockNumber, got: BlockNumber },
+    #[error(
+        "Failed to submit the state update of blocks {first_block} to {last_block} after \
+         {attempts} attempts. Last error: {last_error:?}."
+    )]
+    SubmissionFailed {
+        first_block: BlockNumber,
+        last_block: BlockNumber,
+        attempts: usize,
+        last_error: E,
+    },
+}
+
+pub struct L1Publisher<S: StateUpdateSubmitter> {
+    config: L1PublisherConfig,
+    submitter: S,
+    next_block: BlockNumber,
+    pending_state_diffs: VecDeque<ThinStateDiff>,
+    // The nonce of the next submission, if known to be in sync with the base layer.
+    nonce: Option<u64>,
+    // The first block whose state diff wasn't submitted yet.
+    sent_marker_sender: watch::Sender<BlockNumber>,
+}
+
+impl<S: StateUpdateSubmitter> L1Publisher<S> {
+    /// `first_block` is the first block whose state diff wasn't published yet.
+    pub fn new(config: L1PublisherConfig, submitter: S, first_block: BlockNumber) -> Self {
+        Self {
+            config,
+            submitter,
+            next_block: first_block,
+            pending_state_diffs: VecDeque::new(),
+            nonce: None,
+            sent_marker_sender: watch::Sender::new(first_block),
+        }
+    }
+
+    /// Returns a receiver of the first block whose state diff wasn't submitted yet, updated after
+    /// every successful submission.
+    pub fn subscribe_sent_marker(&self) -> watch::Receiver<BlockNumber> {
+        self.sent_marker_sender.subscribe()
+    }
+
+    /// Adds the state diff of the next decided block.
+    pub fn add_decided_block(
+        &mut self,
+        block_number: BlockNumber,
+        state_diff: ThinStateDiff,
+    ) -> Result<(), L1PublisherError<S::Error>> {
+        let expected = self.next_pending_block();
+        if block_number != expected {
+            return Err(L1PublisherError::UnexpectedBlock { expected, got: block_number });
+        }
+        self.pending_state_diffs.push_back(state_diff);
+        Ok(())
+    }
+
+    /// Submits the state diffs of the oldest pending blocks, if any, and returns the submitted
+    /// state update.
+    pub async fn publish_batch(
+        &mut self,
+    ) -> Result<Option<EncodedStateUpdate>, L1PublisherError<S::Error>> {
+        if self.pending_state_diffs.is_empty() {
+            return Ok(None);
+        }
+        let n_blocks = self.pending_state_diffs.len().min(self.config.max_blocks_per_batch);
+        let state_update = encode_state_update(
+            self.next_block,
+            self.pending_state_diffs.range(..n_blocks),
+            self.config.l1_da_mode,
+        );
+
+        let mut attempt = 0;
+        loop {
+            match self.submit(&state_update, attempt).await {
+                Ok(()) => break,
+                Err(err) if attempt < self.config.max_retries => {
+                    warn!(
+                        "Failed to submit the state update of blocks {} to {}: {err:?}. Retrying.",
+                        state_update.first_block, state_update.last_block
+                    );
+                    self.nonce = None;
+                    attempt += 1;
+                    tokio::time::sleep(self.config.retry_interval).await;
+                }
+                Err(last_error) => {
+                    self.nonce = None;
+                    return Err(L1PublisherError::SubmissionFailed {
+                        first_block: state_update.first_block,
+                        last_block: state_update.last_block,
+                        attempts: attempt + 1,
+                        last_error,
+                    });
+                }
+            }
+        }
+
+        info!(
+            "Submitted the state update of blocks {} to {}.",
+            state_update.first_block, state_update.last_block
+        );
+        self.pending_state_diffs.drain(..n_blocks);
+        self.next_block = state_update.last_block.unchecked_next();
+        self.sent_marker_sender.send_replace(self.next_block);
+        Ok(Some(state_update))
+    }
+
+    /// Publishes the state diffs of the decided blocks it receives. Runs until the channel is
+    /// closed and all the received blocks are published, or until a submission fails.
+    pub async fn run(
+        mut self,
+        mut decided_blocks_receiver: mpsc::Receiver<(BlockNumber, ThinStateDiff)>,
+    ) -> Result<(), L1PublisherError<S::Error>> {
+        while let Some((block_number, state_diff)) = decided_blocks_receiver.recv().await {
+            self.add_decided_block(block_number, state_diff)?;
+            // Batch the blocks that were decided while the previous batch was submitted.
+            while let Ok((block_number, state_diff)) = decided_blocks_receiver.try_recv() {
+                self.add_decided_block(block_number, state_diff)?;
+            }
+            while self.publish_batch().await?.is_some() {}
+        }
+        Ok(())
+    }
+
+    fn next_pending_block(&self) -> BlockNumber {
+        let n_pending_blocks =
+            u64::try_from(self.pending_state_diffs.len()).expect("usize should fit in u64");
+        BlockNumber(self.next_block.0 + n_pending_blocks)
+    }
+
+    async fn submit(
+        &mut self,
+        state_update: &EncodedStateUpdate,
+        attempt: usize,
+    ) -> Result<(), S::Error> {
+        let nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => self.submitter.next_nonce().await?,
+        };
+        let gas_price_bump_percentage = self.config.gas_price_bump_percentage
+            * u64::try_from(attempt).expect("usize should fit in u64");
+        self.submitter.submit(state_update, nonce, gas_price_bump_percentage).await?;
+        self.nonce = Some(nonce + 1);
+        Ok(())
+    }
+}
+
+/// Encodes the state diffs of consecutive blocks, starting at `first_block`, as the concatenation
+/// of their encodings.
+pub fn encode_state_update<'a>(
+    first_block: BlockNumber,
+    state_diffs: impl ExactSizeIterator<Item = &'a ThinStateDiff>,
+    l1_da_mode: L1DataAvailabilityMode,
+) -> EncodedStateUpdate {
+    let n_blocks = u64::try_from(state_diffs.len()).expect("usize should fit in u64");
+    let last_block = BlockNumber(first_block.0 + n_blocks.saturating_sub(1));
+    let data = state_diffs.flat_map(encode_state_diff).collect();
+    EncodedStateUpdate { first_block, last_block, l1_da_mode, data }
+}
+
+/// Encodes a state diff in the layout of Starknet's on-chain data:
+/// ```text
+/// number of updated contracts,
+/// for every updated contract, by address:
+///     address,
+///     class_updated * 2^128 + nonce * 2^64 + number of storage updates,
+///     class hash, if class_updated,
+///     key and value of every storage update,
+/// number of declared classes,
+/// class hash and compiled class hash of every declared class.
+/// ```
+/// A contract whose nonce isn't updated by the state diff has 0 in its nonce field. Cairo 0
+/// classes aren't part of the on-chain data.
+pub fn encode_state_diff(state_diff: &ThinStateDiff) -> Vec<StarkHash> {
+    let updated_contracts: BTreeSet<&ContractAddress> = state_diff
+        .deployed_contracts
+        .keys()
+        .chain(state_diff.replaced_classes.keys())
+        .chain(state_diff.nonces.keys())
+        .chain(state_diff.storage_diffs.keys())
+        .collect();
+
+    let mut data = vec![usize_to_felt(updated_contracts.len())];
+    for address in updated_contracts {
+        let class_hash = state_diff
+            .replaced_classes
+            .get(address)
+            .or_else(|| state_diff.deployed_contracts.get(address));
+        let nonce = state_diff.nonces.get(address).map(|nonce| nonce.0).unwrap_or_default();
+        let storage_diff = state_diff.storage_diffs.get(address);
+        let n_storage_updates = storage_diff.map(|storage_diff| storage_diff.len()).unwrap_or(0);
+
+        let class_updated = StarkHash::from(u8::from(class_hash.is_some()));
+        let header = class_updated * StarkHash::TWO.pow(128_u8)
+            + nonce * StarkHash::TWO.pow(64_u8)
+            + usize_to_felt(n_storage_updates);
+        data.push(*address.0.key());
+        data.push(header);
+        data.extend(class_hash.map(|class_hash| class_hash.0));
+        for (key, value) in storage_diff.into_iter().flatten() {
+            data.push(*key.0.key());
+            data.push(*value);
+        }
+    }
+
+    data.push(usize_to_felt(state_diff.declared_classes.len()));
+    for (class_hash, compiled_class_hash) in &state_diff.declared_classes {
+        data.push(class_hash.0);
+        data.push(compiled_class_hash.0);
+    }
+    data
+}
+
+fn usize_to_felt(value: usize) -> StarkHash {
+    StarkHash::from(u64::try_from(value).expect("usize should fit in u64"))
+}
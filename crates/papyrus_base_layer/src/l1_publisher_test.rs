@@ -0,0 +1,211 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use assert_matches::assert_matches;
+use async_trait::async_trait;
+use indexmap::indexmap;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce, PatriciaKey};
+use starknet_api::data_availability::L1DataAvailabilityMode;
+use starknet_api::hash::StarkHash;
+use starknet_api::state::{StorageKey, ThinStateDiff};
+use starknet_api::{contract_address, felt, patricia_key};
+
+use crate::l1_publisher::{
+    encode_state_diff,
+    EncodedStateUpdate,
+    L1Publisher,
+    L1PublisherConfig,
+    L1PublisherError,
+    StateUpdateSubmitter,
+    FIELD_ELEMENTS_PER_BLOB,
+};
+
+#[derive(Debug, PartialEq)]
+struct Submission {
+    first_block: BlockNumber,
+    last_block: BlockNumber,
+    nonce: u64,
+    gas_price_bump_percentage: u64,
+}
+
+// A submitter that fails its first `n_failures` submissions, and records the successful ones.
+#[derive(Clone, Default)]
+struct FakeSubmitter {
+    n_failures: Arc<Mutex<usize>>,
+    base_layer_nonce: Arc<Mutex<u64>>,
+    submissions: Arc<Mutex<Vec<Submission>>>,
+}
+
+#[async_trait]
+impl StateUpdateSubmitter for FakeSubmitter {
+    type Error = String;
+
+    async fn next_nonce(&self) -> Result<u64, Self::Error> {
+        Ok(*self.base_layer_nonce.lock().unwrap())
+    }
+
+    async fn submit(
+        &self,
+        state_update: &EncodedStateUpdate,
+        nonce: u64,
+        gas_price_bump_percentage: u64,
+    ) -> Result<(), Self::Error> {
+        let mut n_failures = self.n_failures.lock().unwrap();
+        if *n_failures > 0 {
+            *n_failures -= 1;
+            return Err("Submission failed.".to_owned());
+        }
+        *self.base_layer_nonce.lock().unwrap() = nonce + 1;
+        self.submissions.lock().unwrap().push(Submission {
+            first_block: state_update.first_block,
+            last_block: state_update.last_block,
+            nonce,
+            gas_price_bump_percentage,
+        });
+        Ok(())
+    }
+}
+
+fn config(max_blocks_per_batch: usize, max_retries: usize) -> L1PublisherConfig {
+    L1PublisherConfig {
+        max_blocks_per_batch,
+        max_retries,
+        retry_interval: Duration::ZERO,
+        gas_price_bump_percentage: 10,
+        ..Default::default()
+    }
+}
+
+fn add_decided_blocks(publisher: &mut L1Publisher<FakeSubmitter>, block_numbers: Vec<u64>) {
+    for block_number in block_numbers {
+        publisher.add_decided_block(BlockNumber(block_number), ThinStateDiff::default()).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn publish_in_batches() {
+    let submitter = FakeSubmitter::default();
+    *submitter.base_layer_nonce.lock().unwrap() = 7;
+    let mut publisher = L1Publisher::new(config(2, 0), submitter.clone(), BlockNumber(5));
+    let sent_marker_receiver = publisher.subscribe_sent_marker();
+
+    add_decided_blocks(&mut publisher, vec![5, 6, 7]);
+    assert_matches!(
+        publisher.add_decided_block(BlockNumber(9), ThinStateDiff::default()),
+        Err(L1PublisherError::UnexpectedBlock { expected: BlockNumber(8), got: BlockNumber(9) })
+    );
+    while publisher.publish_batch().await.unwrap().is_some() {}
+
+    assert_eq!(
+        *submitter.submissions.lock().unwrap(),
+        vec![
+            Submission {
+                first_block: BlockNumber(5),
+                last_block: BlockNumber(6),
+                nonce: 7,
+                gas_price_bump_percentage: 0,
+            },
+            Submission {
+                first_block: BlockNumber(7),
+                last_block: BlockNumber(7),
+                nonce: 8,
+                gas_price_bump_percentage: 0,
+            },
+        ]
+    );
+    assert_eq!(*sent_marker_receiver.borrow(), BlockNumber(8));
+}
+
+#[tokio::test]
+async fn retry_with_higher_gas_price() {
+    let submitter = FakeSubmitter::default();
+    *submitter.n_failures.lock().unwrap() = 2;
+    let mut publisher = L1Publisher::new(config(10, 2), submitter.clone(), BlockNumber(0));
+
+    add_decided_blocks(&mut publisher, vec![0]);
+    publisher.publish_batch().await.unwrap();
+    assert_eq!(
+        *submitter.submissions.lock().unwrap(),
+        vec![Submission {
+            first_block: BlockNumber(0),
+            last_block: BlockNumber(0),
+            nonce: 0,
+            gas_price_bump_percentage: 20,
+        }]
+    );
+}
+
+#[tokio::test]
+async fn keep_blocks_of_failed_submission() {
+    let submitter = FakeSubmitter::default();
+    *submitter.n_failures.lock().unwrap() = 2;
+    let mut publisher = L1Publisher::new(config(10, 1), submitter.clone(), BlockNumber(0));
+
+    add_decided_blocks(&mut publisher, vec![0, 1]);
+    assert_matches!(
+        publisher.publish_batch().await,
+        Err(L1PublisherError::SubmissionFailed { attempts: 2, .. })
+    );
+    assert!(submitter.submissions.lock().unwrap().is_empty());
+
+    // The blocks are submitted in the next attempt to publish them.
+    assert_matches!(
+        publisher.publish_batch().await,
+        Ok(Some(EncodedStateUpdate {
+            first_block: BlockNumber(0),
+            last_block: BlockNumber(1),
+            ..
+        }))
+    );
+}
+
+#[test]
+fn encode_state_diff_layout() {
+    let storage_diff = indexmap! { StorageKey(patricia_key!("0x10")) => felt!("0x11") };
+    let state_diff = ThinStateDiff {
+        deployed_contracts: indexmap! { contract_address!("0x2") => ClassHash(felt!("0x20")) },
+        storage_diffs: indexmap! { contract_address!("0x1") => storage_diff },
+        declared_classes: indexmap! {
+            ClassHash(felt!("0x30")) => CompiledClassHash(felt!("0x31")),
+        },
+        nonces: indexmap! { contract_address!("0x1") => Nonce(felt!("0x3")) },
+        ..Default::default()
+    };
+    let two_pow = |exponent: u8| StarkHash::TWO.pow(exponent);
+
+    assert_eq!(
+        encode_state_diff(&state_diff),
+        vec![
+            felt!("0x2"),
+            // Contract 0x1: nonce 3, one storage update.
+            felt!("0x1"),
+            felt!("0x3") * two_pow(64) + felt!("0x1"),
+            felt!("0x10"),
+            felt!("0x11"),
+            // Contract 0x2: deployed.
+            felt!("0x2"),
+            two_pow(128),
+            felt!("0x20"),
+            // Declared classes.
+            felt!("0x1"),
+            felt!("0x30"),
+            felt!("0x31"),
+        ]
+    );
+}
+
+#[test]
+fn split_to_blobs() {
+    let state_update = EncodedStateUpdate {
+        l1_da_mode: L1DataAvailabilityMode::Blob,
+        data: vec![StarkHash::ONE; FIELD_ELEMENTS_PER_BLOB + 1],
+        ..Default::default()
+    };
+    let blobs = state_update.blobs();
+    assert_eq!(blobs.len(), 2);
+    assert_eq!(blobs[0], vec![StarkHash::ONE; FIELD_ELEMENTS_PER_BLOB]);
+    assert_eq!(blobs[1][0], StarkHash::ONE);
+    assert!(blobs[1][1..].iter().all(|element| *element == StarkHash::ZERO));
+    assert_eq!(blobs[1].len(), FIELD_ELEMENTS_PER_BLOB);
+}
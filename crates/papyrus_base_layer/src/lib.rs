@@ -1,10 +1,17 @@
 use async_trait::async_trait;
 use starknet_api::block::{BlockHash, BlockNumber};
+use starknet_api::transaction::{Fee, L1HandlerTransaction};
 
 #[cfg(test)]
 mod base_layer_test;
+#[cfg(test)]
+mod test_utils;
 
 pub mod ethereum_base_layer_contract;
+pub mod l1_finality;
+pub mod l1_gas_price;
+pub mod l1_messages;
+pub mod l1_publisher;
 
 /// Interface for getting data from the Starknet base contract.
 #[async_trait]
@@ -17,4 +24,32 @@ pub trait BaseLayerContract {
         &self,
         min_confirmations: Option<u64>,
     ) -> Result<Option<(BlockNumber, BlockHash)>, Self::Error>;
+
+    /// Get the number of the latest block of the base layer.
+    async fn latest_l1_block_number(&self) -> Result<u64, Self::Error>;
+
+    /// Get the gas prices of a block of the base layer, or None if there's no such block or it
+    /// predates the base fee.
+    async fn get_price_sample(&self, block_number: u64)
+    -> Result<Option<PriceSample>, Self::Error>;
+
+    /// Get the events of the messages sent to L2 in a range of base layer blocks, inclusive.
+    async fn events(&self, from_block: u64, to_block: u64) -> Result<Vec<L1Event>, Self::Error>;
+}
+
+/// An event of the Starknet contract about a message sent from L1 to L2.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum L1Event {
+    /// A message was sent to L2, paying `fee` for its L1 handler transaction.
+    LogMessageToL2 { tx: L1HandlerTransaction, fee: Fee },
+    /// The sender canceled a message to L2.
+    MessageToL2Canceled(L1HandlerTransaction),
+}
+
+/// The gas prices of a block of the base layer, in wei.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PriceSample {
+    pub timestamp: u64,
+    pub base_fee_per_gas: u128,
+    pub blob_fee: u128,
 }
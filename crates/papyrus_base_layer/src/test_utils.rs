@@ -0,0 +1,61 @@
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use starknet_api::block::{BlockHash, BlockNumber};
+
+use crate::{BaseLayerContract, L1Event, PriceSample};
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct FakeBlock {
+    pub price_sample: PriceSample,
+    pub events: Vec<L1Event>,
+}
+
+// A base layer whose blocks are given by the test.
+#[derive(Clone, Default)]
+pub(crate) struct FakeBaseLayer {
+    blocks: Arc<Mutex<Vec<FakeBlock>>>,
+    latest_proved_block: Arc<Mutex<Option<(BlockNumber, BlockHash)>>>,
+}
+
+impl FakeBaseLayer {
+    pub fn add_block(&self, block: FakeBlock) {
+        self.blocks.lock().unwrap().push(block);
+    }
+
+    pub fn set_latest_proved_block(&self, latest_proved_block: Option<(BlockNumber, BlockHash)>) {
+        *self.latest_proved_block.lock().unwrap() = latest_proved_block;
+    }
+}
+
+#[async_trait]
+impl BaseLayerContract for FakeBaseLayer {
+    type Error = Infallible;
+
+    async fn latest_proved_block(
+        &self,
+        _min_confirmations: Option<u64>,
+    ) -> Result<Option<(BlockNumber, BlockHash)>, Self::Error> {
+        Ok(*self.latest_proved_block.lock().unwrap())
+    }
+
+    async fn latest_l1_block_number(&self) -> Result<u64, Self::Error> {
+        Ok(u64::try_from(self.blocks.lock().unwrap().len()).unwrap().saturating_sub(1))
+    }
+
+    async fn get_price_sample(
+        &self,
+        block_number: u64,
+    ) -> Result<Option<PriceSample>, Self::Error> {
+        let blocks = self.blocks.lock().unwrap();
+        Ok(blocks.get(usize::try_from(block_number).unwrap()).map(|block| block.price_sample))
+    }
+
+    async fn events(&self, from_block: u64, to_block: u64) -> Result<Vec<L1Event>, Self::Error> {
+        let blocks = self.blocks.lock().unwrap();
+        let from_block = usize::try_from(from_block).unwrap();
+        let to_block = usize::try_from(to_block).unwrap();
+        Ok(blocks[from_block..=to_block].iter().flat_map(|block| block.events.clone()).collect())
+    }
+}
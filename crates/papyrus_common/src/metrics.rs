@@ -1,5 +1,15 @@
 use std::sync::OnceLock;
 
+/// The label of the component that recorded a metric, e.g. `consensus`. The sequencer components
+/// use the same labels, so that the metrics of all the nodes can be queried alike.
+pub const COMPONENT_LABEL: &str = "component";
+
+/// The label of the chain the node runs on. Added to all the metrics by the recorder.
+pub const CHAIN_ID_LABEL: &str = "chain_id";
+
+/// The label of the height a metric was recorded for.
+pub const HEIGHT_LABEL: &str = "height";
+
 /// The central marker is the first block number that doesn't exist yet.
 pub const PAPYRUS_CENTRAL_BLOCK_MARKER: &str = "papyrus_central_block_marker";
 
@@ -27,6 +37,20 @@ pub const PAPYRUS_HEADER_LATENCY_SEC: &str = "papyrus_header_latency";
 /// The number of peers this node is connected to.
 pub const PAPYRUS_NUM_CONNECTED_PEERS: &str = "papyrus_num_connected_peers";
 
+/// The number of peers in the address book of this node, i.e. peers that were discovered and
+/// weren't blacklisted.
+pub const PAPYRUS_NUM_DISCOVERED_PEERS: &str = "papyrus_num_discovered_peers";
+
+/// The number of bytes of each sqmr protocol and gossipsub topic that were sent or received.
+pub const PAPYRUS_NETWORK_TRAFFIC_BYTES: &str = "papyrus_network_traffic_bytes";
+
+/// The number of inbound sessions and broadcasted messages that were dropped because their
+/// protocol or topic exceeded its bandwidth cap.
+pub const PAPYRUS_NETWORK_SHED_MESSAGES: &str = "papyrus_network_shed_messages";
+
+/// The number of bytes that were saved by compressing the messages of sqmr sessions.
+pub const PAPYRUS_COMPRESSION_SAVED_BYTES: &str = "papyrus_compression_saved_bytes";
+
 /// The number of active sessions this peer has in which it sends data.
 pub const PAPYRUS_NUM_ACTIVE_INBOUND_SESSIONS: &str = "papyrus_num_active_inbound_sessions";
 
@@ -43,3 +67,6 @@ pub const PAPYRUS_CONSENSUS_HEIGHT: &str = "papyrus_consensus_height";
 
 /// The number of times consensus has progressed due to the sync protocol.
 pub const PAPYRUS_CONSENSUS_SYNC_COUNT: &str = "papyrus_consensus_sync_count";
+
+/// The number of decided blocks consensus proceeded past before they were committed.
+pub const PAPYRUS_CONSENSUS_PENDING_COMMITS: &str = "papyrus_consensus_pending_commits";
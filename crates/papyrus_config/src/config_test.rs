@@ -12,7 +12,7 @@ use papyrus_test_utils::get_absolute_path;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tempfile::TempDir;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
 use crate::command::{get_command_matches, update_config_map_by_command_args};
 use crate::converters::deserialize_milliseconds_to_duration;
@@ -30,12 +30,18 @@ use crate::dumping::{
 use crate::loading::{
     load,
     load_and_process_config,
+    load_and_process_config_with_options,
     split_pointers_map,
     split_values_and_types,
     update_config_map_by_pointers,
     update_optional_values,
+    LoadingOptions,
 };
 use crate::presentation::get_config_presentation;
+use crate::presets::ConfigPreset;
+use crate::reloading::{get_changed_params, validate_reload};
+use crate::secrets::{CommandSecretResolver, KMS_SCHEME};
+use crate::validators::{config_validate, cross_param_validation_error};
 use crate::{
     ConfigError,
     ParamPath,
@@ -113,6 +119,48 @@ fn test_validation() {
     assert!(outer_config.validate().is_err());
 }
 
+#[derive(Validate)]
+#[validate(schema(function = "validate_bounds_order", skip_on_field_errors = false))]
+struct BoundsConfig {
+    #[validate(range(max = 10))]
+    lower: usize,
+    upper: usize,
+}
+
+fn validate_bounds_order(config: &BoundsConfig) -> Result<(), ValidationError> {
+    if config.lower > config.upper {
+        return Err(cross_param_validation_error(
+            "bounds out of order",
+            &["lower", "upper"],
+            "lower must not exceed upper.".to_owned(),
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Validate)]
+struct BoundedConfig {
+    #[validate]
+    bounds: BoundsConfig,
+}
+
+#[test]
+fn cross_param_validation_reports_all_violations() {
+    let config = BoundedConfig { bounds: BoundsConfig { lower: 20, upper: 5 } };
+    let Err(ConfigError::ConfigValidationError(errors)) = config_validate(&config) else {
+        panic!("Expected a validation error.");
+    };
+    let mut param_paths: Vec<&str> =
+        errors.0.iter().map(|error| error.param_path.as_str()).collect();
+    param_paths.sort();
+    assert_eq!(param_paths, vec!["bounds.lower", "bounds.lower, bounds.upper"]);
+
+    let cross_param_error =
+        errors.0.iter().find(|error| error.code == "bounds out of order").unwrap();
+    assert_eq!(cross_param_error.message.as_deref(), Some("lower must not exceed upper."));
+    assert!(cross_param_error.params.is_empty());
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 struct TypicalConfig {
     #[serde(deserialize_with = "deserialize_milliseconds_to_duration")]
@@ -459,6 +507,46 @@ fn test_required_param_from_command() {
     assert_eq!(param_path, "1234");
 }
 
+// Loads CustomConfig from args, with a preset that sets param_path.
+fn load_custom_config_with_preset(args: Vec<&str>) -> Result<CustomConfig, ConfigError> {
+    let dir = TempDir::new().unwrap();
+    let file_path = dir.path().join("config.json");
+    CustomConfig { param_path: "default value".to_owned(), seed: 5 }
+        .dump_to_file(&vec![], file_path.to_str().unwrap())
+        .unwrap();
+
+    let options = LoadingOptions {
+        presets: vec![ConfigPreset { name: "chain", values: r#"{"param_path": "preset value"}"# }],
+        ..Default::default()
+    };
+    load_and_process_config_with_options::<CustomConfig>(
+        File::open(file_path).unwrap(),
+        Command::new("Program"),
+        args.into_iter().map(|s| s.to_owned()).collect(),
+        &options,
+    )
+}
+
+#[test]
+fn load_preset() {
+    assert_eq!(
+        load_custom_config_with_preset(vec!["Testing"]).unwrap().param_path,
+        "default value"
+    );
+    assert_eq!(
+        load_custom_config_with_preset(vec!["Testing", "--preset", "chain"]).unwrap().param_path,
+        "preset value"
+    );
+    // The args override the preset.
+    let args = vec!["Testing", "--preset", "chain", "--param_path", "arg value"];
+    assert_eq!(load_custom_config_with_preset(args).unwrap().param_path, "arg value");
+
+    assert_matches!(
+        load_custom_config_with_preset(vec!["Testing", "--preset", "other"]),
+        Err(ConfigError::CommandInput(_))
+    );
+}
+
 #[test]
 fn test_required_param_from_file() {
     let args = vec!["Testing", "--config_file", CUSTOM_CONFIG_PATH.to_str().unwrap()];
@@ -593,3 +681,141 @@ fn deeply_nested_optionals() {
         }
     );
 }
+
+#[test]
+fn changed_params() {
+    let current = OuterConfig {
+        opt_elem: None,
+        opt_config: Some(InnerConfig { o: 1 }),
+        inner_config: InnerConfig { o: 2 },
+    };
+    assert!(get_changed_params(&current, &current.clone()).is_empty());
+
+    let reloaded =
+        OuterConfig { opt_elem: Some(1), opt_config: None, inner_config: InnerConfig { o: 3 } };
+    assert_eq!(
+        get_changed_params(&current, &reloaded),
+        vec![
+            "inner_config.o".to_owned(),
+            "opt_config.#is_none".to_owned(),
+            "opt_config.o".to_owned(),
+            "opt_elem.#is_none".to_owned(),
+        ]
+    );
+}
+
+#[test]
+fn reload_changes_only_hot_reloadable_params() {
+    let current =
+        OuterConfig { opt_elem: None, opt_config: None, inner_config: InnerConfig { o: 2 } };
+    let reloaded = OuterConfig { inner_config: InnerConfig { o: 3 }, ..current.clone() };
+    assert_eq!(
+        validate_reload(&current, &reloaded, &["inner_config"]).unwrap(),
+        vec!["inner_config.o".to_owned()]
+    );
+    assert_eq!(
+        validate_reload(&current, &reloaded, &["inner_config.o"]).unwrap(),
+        vec!["inner_config.o".to_owned()]
+    );
+
+    // A param is not hot-reloadable because its path starts with a hot-reloadable param's path.
+    assert_matches!(
+        validate_reload(&current, &reloaded, &["inner"]),
+        Err(ConfigError::ImmutableParamsChanged { param_paths })
+        if param_paths == vec!["inner_config.o".to_owned()]
+    );
+
+    let reloaded = OuterConfig { opt_elem: Some(1), ..reloaded };
+    assert_matches!(
+        validate_reload(&current, &reloaded, &["inner_config"]),
+        Err(ConfigError::ImmutableParamsChanged { param_paths })
+        if param_paths == vec!["opt_elem.#is_none".to_owned()]
+    );
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+struct SecretsConfig {
+    env_secret: String,
+    file_secret: String,
+    kms_secret: String,
+    public_param: String,
+}
+
+impl SerializeConfig for SecretsConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from([
+            ser_param(
+                "env_secret",
+                &self.env_secret,
+                "This is env_secret.",
+                ParamPrivacyInput::Private,
+            ),
+            ser_param(
+                "file_secret",
+                &self.file_secret,
+                "This is file_secret.",
+                ParamPrivacyInput::Private,
+            ),
+            ser_param(
+                "kms_secret",
+                &self.kms_secret,
+                "This is kms_secret.",
+                ParamPrivacyInput::Private,
+            ),
+            ser_param(
+                "public_param",
+                &self.public_param,
+                "This is public_param.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+#[test]
+fn load_secrets() {
+    let dir = TempDir::new().unwrap();
+    let config_file_path = dir.path().join("config.json");
+    let secret_file_path = dir.path().join("secret");
+    std::fs::write(&secret_file_path, "file secret\n").unwrap();
+    SecretsConfig {
+        // Set by cargo when running the tests.
+        env_secret: "${env:CARGO_PKG_NAME}".to_owned(),
+        file_secret: "default".to_owned(),
+        kms_secret: "${kms:key_id}".to_owned(),
+        public_param: "${env:CARGO_PKG_NAME}".to_owned(),
+    }
+    .dump_to_file(&vec![], config_file_path.to_str().unwrap())
+    .unwrap();
+
+    let file_secret_reference = format!("${{file:{}}}", secret_file_path.to_str().unwrap());
+    let args = vec!["Testing", "--file_secret", file_secret_reference.as_str()];
+    // A KMS whose secrets are their references.
+    let kms_resolver = CommandSecretResolver::new(KMS_SCHEME, "echo");
+    let loaded_config = load_and_process_config_with_options::<SecretsConfig>(
+        File::open(&config_file_path).unwrap(),
+        Command::new("Testing"),
+        args.iter().map(|arg| arg.to_string()).collect(),
+        &LoadingOptions { secret_resolvers: vec![&kms_resolver], ..Default::default() },
+    )
+    .unwrap();
+    assert_eq!(
+        loaded_config,
+        SecretsConfig {
+            env_secret: env!("CARGO_PKG_NAME").to_owned(),
+            file_secret: "file secret".to_owned(),
+            kms_secret: "key_id".to_owned(),
+            // References in public params are not resolved.
+            public_param: "${env:CARGO_PKG_NAME}".to_owned(),
+        }
+    );
+
+    // Without a resolver for its scheme, a reference fails the loading.
+    let err = load_and_process_config::<SecretsConfig>(
+        File::open(&config_file_path).unwrap(),
+        Command::new("Testing"),
+        vec!["Testing".to_owned()],
+    )
+    .unwrap_err();
+    assert_matches!(err, ConfigError::SecretResolution { param_path, .. } if param_path == "kms_secret");
+}
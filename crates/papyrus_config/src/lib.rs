@@ -48,6 +48,7 @@
 //! ```
 
 use clap::parser::MatchesError;
+use secrets::SecretResolutionError;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use validator::ValidationError;
@@ -68,6 +69,9 @@ pub mod converters;
 pub mod dumping;
 pub mod loading;
 pub mod presentation;
+pub mod presets;
+pub mod reloading;
+pub mod secrets;
 pub mod validators;
 
 /// The privacy level of a config parameter, that received as input from the configs.
@@ -179,6 +183,10 @@ pub enum ConfigError {
     PointerSourceNotFound { pointing_param: String },
     #[error("Changing {param_path} from required type {required} to {given} is not allowed.")]
     ChangeRequiredParamType { param_path: String, required: SerializationType, given: Value },
+    #[error("Changing {param_paths:?} requires restarting the node.")]
+    ImmutableParamsChanged { param_paths: Vec<ParamPath> },
+    #[error("Failed to resolve the secret of {param_path}: {error}")]
+    SecretResolution { param_path: ParamPath, error: SecretResolutionError },
     #[error(transparent)]
     ValidationError(#[from] ValidationError),
     #[error(transparent)]
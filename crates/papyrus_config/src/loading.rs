@@ -2,9 +2,13 @@
 //! * Command line arguments.
 //! * Environment variables (capital letters).
 //! * Custom config files, separated by ',' (comma), from last to first.
+//! * Preset, if one is selected. See [`crate::presets`].
 //! * Default config file.
+//!
+//! The values of private params can refer to secrets, which are resolved after the values are set.
+//! See [`crate::secrets`].
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::File;
 use std::ops::IndexMut;
 use std::path::PathBuf;
@@ -16,11 +20,14 @@ use itertools::any;
 use serde::Deserialize;
 use serde_json::{json, Map, Value};
 
+use crate::presets::{preset_arg, update_config_map_by_preset, ConfigPreset, PRESET_ARG};
+use crate::secrets::{resolve_secrets, SecretResolver};
 use crate::validators::validate_path_exists;
 use crate::{
     command,
     ConfigError,
     ParamPath,
+    ParamPrivacy,
     SerializationType,
     SerializedContent,
     SerializedParam,
@@ -50,6 +57,30 @@ pub fn load_and_process_config<T: for<'a> Deserialize<'a>>(
     default_config_file: File,
     command: Command,
     args: Vec<String>,
+) -> Result<T, ConfigError> {
+    load_and_process_config_with_options(
+        default_config_file,
+        command,
+        args,
+        &LoadingOptions::default(),
+    )
+}
+
+/// Optional extensions of the configuration loading.
+#[derive(Default)]
+pub struct LoadingOptions<'a> {
+    /// Resolvers of the secret references of schemes other than the built-in ones.
+    pub secret_resolvers: Vec<&'a dyn SecretResolver>,
+    /// The presets that can be selected with the `--preset` flag.
+    pub presets: Vec<ConfigPreset>,
+}
+
+/// Same as [`load_and_process_config`], with the given extensions.
+pub fn load_and_process_config_with_options<T: for<'a> Deserialize<'a>>(
+    default_config_file: File,
+    mut command: Command,
+    args: Vec<String>,
+    options: &LoadingOptions<'_>,
 ) -> Result<T, ConfigError> {
     let deserialized_default_config: Map<String, Value> =
         serde_json::from_reader(default_config_file)?;
@@ -57,15 +88,34 @@ pub fn load_and_process_config<T: for<'a> Deserialize<'a>>(
     // Store the pointers separately from the default values. The pointers will receive a value
     // only at the end of the process.
     let (default_config_map, pointers_map) = split_pointers_map(deserialized_default_config);
+    let private_params: BTreeSet<ParamPath> = default_config_map
+        .iter()
+        .filter(|(_, serialized_param)| serialized_param.privacy == ParamPrivacy::Private)
+        .map(|(param_path, _)| param_path.clone())
+        .collect();
+    if !options.presets.is_empty() {
+        command = command.arg(preset_arg(&options.presets));
+    }
     // Take param paths with corresponding descriptions, and get the matching arguments.
     let mut arg_matches = get_command_matches(&default_config_map, command, args)?;
     let (mut values_map, types_map) = split_values_and_types(default_config_map);
+    // If the preset arg is given, updates the values map according to the preset.
+    if let Some(preset_name) = arg_matches.remove_one::<String>(PRESET_ARG) {
+        let preset = options
+            .presets
+            .iter()
+            .find(|preset| preset.name == preset_name)
+            .expect("The preset arg accepts only the names of the presets.");
+        update_config_map_by_preset(&mut values_map, &types_map, preset)?;
+    }
     // If the config_file arg is given, updates the values map according to this files.
     if let Some(custom_config_paths) = arg_matches.remove_many::<PathBuf>("config_file") {
         update_config_map_by_custom_configs(&mut values_map, &types_map, custom_config_paths)?;
     };
     // Updates the values map according to the args.
     update_config_map_by_command_args(&mut values_map, &types_map, &arg_matches)?;
+    // Replace the secret references with the secrets.
+    resolve_secrets(&mut values_map, &private_params, &options.secret_resolvers)?;
     // Set values to the pointers.
     update_config_map_by_pointers(&mut values_map, &pointers_map)?;
     // Set values according to the is-none marks.
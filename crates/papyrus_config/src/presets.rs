@@ -0,0 +1,47 @@
+//! Presets of configuration values, embedded in the binary.
+//!
+//! A preset holds the values of a known setup, e.g. the chain id, fee token addresses and URLs of a
+//! Starknet chain, so running the node against it requires selecting the preset with the
+//! `--preset` flag only. The values of the preset override the default values, and are overridden
+//! by the custom config files, the environment variables and the command line arguments.
+
+use std::collections::BTreeMap;
+
+use clap::builder::PossibleValuesParser;
+use clap::Arg;
+use serde_json::{Map, Value};
+
+use crate::loading::update_config_map;
+use crate::{ConfigError, ParamPath, SerializationType};
+
+pub(crate) const PRESET_ARG: &str = "preset";
+
+/// A named set of configuration values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigPreset {
+    /// The name the preset is selected by.
+    pub name: &'static str,
+    /// A JSON map from param paths to values, in the format of a custom config file.
+    pub values: &'static str,
+}
+
+// The command line flag for selecting one of the presets.
+pub(crate) fn preset_arg(presets: &[ConfigPreset]) -> Arg {
+    Arg::new(PRESET_ARG)
+        .long(PRESET_ARG)
+        .help("Optionally sets a preset of configuration values to use")
+        .value_parser(PossibleValuesParser::new(presets.iter().map(|preset| preset.name)))
+}
+
+// Updates the config map by the values of the preset.
+pub(crate) fn update_config_map_by_preset(
+    config_map: &mut BTreeMap<ParamPath, Value>,
+    types_map: &BTreeMap<ParamPath, SerializationType>,
+    preset: &ConfigPreset,
+) -> Result<(), ConfigError> {
+    let preset_values: Map<String, Value> = serde_json::from_str(preset.values)?;
+    for (param_path, json_value) in preset_values {
+        update_config_map(config_map, types_map, param_path.as_str(), json_value)?;
+    }
+    Ok(())
+}
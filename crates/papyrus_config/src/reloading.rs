@@ -0,0 +1,54 @@
+//! Validates a configuration that was re-loaded while the node runs.
+//!
+//! A running node can apply only changes to the parameters its components keep reading (e.g.
+//! timeouts and limits). Changes to the rest of the parameters, such as the chain id, take effect
+//! only after a restart, so a re-loaded configuration that changes them is rejected.
+
+use std::collections::BTreeSet;
+
+use crate::dumping::SerializeConfig;
+use crate::{ConfigError, ParamPath};
+
+/// Returns the paths of the parameters whose values differ between the two configurations.
+pub fn get_changed_params<T: SerializeConfig>(current: &T, reloaded: &T) -> Vec<ParamPath> {
+    let current_dump = current.dump();
+    let reloaded_dump = reloaded.dump();
+    let param_paths: BTreeSet<&ParamPath> =
+        current_dump.keys().chain(reloaded_dump.keys()).collect();
+    param_paths
+        .into_iter()
+        .filter(|param_path| {
+            current_dump.get(*param_path).map(|param| &param.content)
+                != reloaded_dump.get(*param_path).map(|param| &param.content)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Validates that the re-loaded configuration changes only hot-reloadable parameters, and returns
+/// the paths of the changed parameters. A parameter is hot-reloadable if its path, or the path of
+/// a sub-config that contains it, is in `hot_reloadable_params`.
+pub fn validate_reload<T: SerializeConfig>(
+    current: &T,
+    reloaded: &T,
+    hot_reloadable_params: &[&str],
+) -> Result<Vec<ParamPath>, ConfigError> {
+    let changed_params = get_changed_params(current, reloaded);
+    let immutable_params: Vec<ParamPath> = changed_params
+        .iter()
+        .filter(|param_path| !is_hot_reloadable(param_path, hot_reloadable_params))
+        .cloned()
+        .collect();
+    if !immutable_params.is_empty() {
+        return Err(ConfigError::ImmutableParamsChanged { param_paths: immutable_params });
+    }
+    Ok(changed_params)
+}
+
+fn is_hot_reloadable(param_path: &str, hot_reloadable_params: &[&str]) -> bool {
+    hot_reloadable_params.iter().any(|hot_reloadable_param| {
+        param_path
+            .strip_prefix(hot_reloadable_param)
+            .is_some_and(|suffix| suffix.is_empty() || suffix.starts_with('.'))
+    })
+}
@@ -0,0 +1,128 @@
+//! Resolves the values of private parameters from secret sources.
+//!
+//! Instead of a secret value, a private parameter can be given a reference to the secret in the
+//! form `${<scheme>:<reference>}`, from any of the configuration sources. The reference is
+//! resolved when the configuration is loaded, according to its scheme:
+//! * `env` - the value of the environment variable named `<reference>`.
+//! * `file` - the content of the file in the path `<reference>`, without a trailing newline. For
+//!   example, a secret mounted to the node's container.
+//! * Any other scheme is resolved by the [`SecretResolver`] of that scheme, if one was given when
+//!   loading the configuration. For example, a [`CommandSecretResolver`] that fetches the secrets
+//!   from an external KMS.
+//!
+//! References in public parameters aren't resolved.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::process::Command;
+
+use serde_json::Value;
+
+use crate::{ConfigError, ParamPath};
+
+/// The error of resolving a secret.
+pub type SecretResolutionError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Resolves the secret references of a scheme, e.g. by fetching the secrets from an external KMS.
+pub trait SecretResolver {
+    /// The scheme of the references this resolver resolves.
+    fn scheme(&self) -> &str;
+
+    /// Returns the secret the reference refers to.
+    fn resolve(&self, reference: &str) -> Result<String, SecretResolutionError>;
+}
+
+/// The scheme of the references to secrets in an external KMS.
+pub const KMS_SCHEME: &str = "kms";
+/// The environment variable with the command that fetches the secrets from an external KMS.
+pub const KMS_COMMAND_ENV_VAR: &str = "CONFIG_SECRETS_KMS_COMMAND";
+
+/// Resolves the secret references of a scheme by running a command with the reference as its
+/// argument, e.g. the CLI of an external KMS. The secret is the output of the command, without a
+/// trailing newline.
+#[derive(Clone, Debug)]
+pub struct CommandSecretResolver {
+    scheme: String,
+    program: PathBuf,
+}
+
+impl CommandSecretResolver {
+    /// Creates a resolver of the references of the scheme, which runs the program to resolve them.
+    pub fn new(scheme: impl Into<String>, program: impl Into<PathBuf>) -> Self {
+        Self { scheme: scheme.into(), program: program.into() }
+    }
+
+    /// Returns the resolver of the [`KMS_SCHEME`] references, if the command that fetches them is
+    /// set in the [`KMS_COMMAND_ENV_VAR`] environment variable.
+    pub fn kms_from_env() -> Option<Self> {
+        std::env::var_os(KMS_COMMAND_ENV_VAR).map(|program| Self::new(KMS_SCHEME, program))
+    }
+}
+
+impl SecretResolver for CommandSecretResolver {
+    fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    fn resolve(&self, reference: &str) -> Result<String, SecretResolutionError> {
+        let output = Command::new(&self.program).arg(reference).output()?;
+        if !output.status.success() {
+            return Err(format!(
+                "{} exited with {}: {}",
+                self.program.display(),
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim_end()
+            )
+            .into());
+        }
+        let secret = String::from_utf8(output.stdout)?;
+        Ok(secret.trim_end_matches(&['\r', '\n'][..]).to_owned())
+    }
+}
+
+const ENV_SCHEME: &str = "env";
+const FILE_SCHEME: &str = "file";
+
+/// Replaces the secret references in the values of the private params with the secrets.
+pub(crate) fn resolve_secrets(
+    config_map: &mut BTreeMap<ParamPath, Value>,
+    private_params: &BTreeSet<ParamPath>,
+    secret_resolvers: &[&dyn SecretResolver],
+) -> Result<(), ConfigError> {
+    for param_path in private_params {
+        let Some(Value::String(value)) = config_map.get(param_path) else {
+            continue;
+        };
+        let Some((scheme, reference)) = parse_secret_reference(value) else {
+            continue;
+        };
+        let secret = resolve_secret(scheme, reference, secret_resolvers).map_err(|error| {
+            ConfigError::SecretResolution { param_path: param_path.clone(), error }
+        })?;
+        config_map.insert(param_path.clone(), Value::String(secret));
+    }
+    Ok(())
+}
+
+// Splits a secret reference of the form "${scheme:reference}" to its scheme and reference.
+fn parse_secret_reference(value: &str) -> Option<(&str, &str)> {
+    value.strip_prefix("${")?.strip_suffix('}')?.split_once(':')
+}
+
+fn resolve_secret(
+    scheme: &str,
+    reference: &str,
+    secret_resolvers: &[&dyn SecretResolver],
+) -> Result<String, SecretResolutionError> {
+    match scheme {
+        ENV_SCHEME => Ok(std::env::var(reference)?),
+        FILE_SCHEME => {
+            let content = std::fs::read_to_string(reference)?;
+            Ok(content.trim_end_matches(&['\r', '\n'][..]).to_owned())
+        }
+        _ => match secret_resolvers.iter().find(|resolver| resolver.scheme() == scheme) {
+            Some(resolver) => resolver.resolve(reference),
+            None => Err(format!("No secret resolver for the scheme '{scheme}'.").into()),
+        },
+    }
+}
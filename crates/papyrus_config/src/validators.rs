@@ -7,6 +7,11 @@ use validator::{Validate, ValidationError, ValidationErrors, ValidationErrorsKin
 
 use crate::ConfigError;
 
+// The key under which the validation error of a constraint between params stores their paths.
+const PARAM_PATHS_KEY: &str = "param_paths";
+// The key under which the validator crate stores the errors of struct level validations.
+const STRUCT_ERRORS_KEY: &str = "__all__";
+
 /// Custom validation for ASCII string.
 pub fn validate_ascii(name: &impl ToString) -> Result<(), ValidationError> {
     if !name.to_string().is_ascii() {
@@ -35,10 +40,30 @@ pub fn validate_vec_u256(vec: &[u8]) -> Result<(), ValidationError> {
     Ok(())
 }
 
+/// Creates the error of a constraint between several params of a config, to be returned from a
+/// schema validation function of the config struct. `param_paths` are the paths of the params,
+/// relative to the struct, and the parsed error is reported under them instead of under the
+/// struct.
+///
+/// To report the violations of all the constraints at once, validate each constraint with a
+/// separate schema function that doesn't skip on field errors:
+/// `#[validate(schema(function = "...", skip_on_field_errors = false))]`.
+pub fn cross_param_validation_error(
+    code: &'static str,
+    param_paths: &[&str],
+    message: String,
+) -> ValidationError {
+    let mut error = ValidationError::new(code);
+    error.add_param(PARAM_PATHS_KEY.into(), &param_paths);
+    error.message = Some(message.into());
+    error
+}
+
 /// Struct for parsing a validation error.
 #[derive(Debug)]
 pub struct ParsedValidationError {
-    /// The path of the field that failed validation.
+    /// The path of the field that failed validation, or the comma separated paths of the fields
+    /// that failed a validation of a constraint between them.
     pub param_path: String,
     /// The error code.
     pub code: String,
@@ -125,14 +150,34 @@ fn parse_validation_error(
             }
             ValidationErrorsKind::Field(errors) => {
                 for error in errors {
+                    let param_path = match error.params.get(PARAM_PATHS_KEY) {
+                        Some(serde_json::Value::Array(param_paths))
+                            if *field == STRUCT_ERRORS_KEY =>
+                        {
+                            param_paths
+                                .iter()
+                                .filter_map(|param_path| param_path.as_str())
+                                .map(|param_path| {
+                                    if current_path.is_empty() {
+                                        param_path.to_owned()
+                                    } else {
+                                        format!("{}.{}", current_path, param_path)
+                                    }
+                                })
+                                .collect::<Vec<String>>()
+                                .join(", ")
+                        }
+                        _ => new_path.to_owned(),
+                    };
                     let parsed_error = ParsedValidationError {
-                        param_path: new_path.to_owned(),
+                        param_path,
                         code: error.code.to_string(),
                         message: error.message.as_ref().map(|cow_string| cow_string.to_string()),
                         params: {
                             let params = &error.params;
                             params
                                 .iter()
+                                .filter(|(k, _v)| *k != PARAM_PATHS_KEY)
                                 .map(|(_k, v)| v.to_string().replace('\"', ""))
                                 .collect::<Vec<String>>()
                                 .join(", ")
@@ -6,8 +6,11 @@ use assert_matches::assert_matches;
 use blockifier::abi::abi_utils::get_storage_var_address;
 use blockifier::execution::call_info::Retdata;
 use blockifier::execution::errors::ConstructorEntryPointExecutionError;
+use blockifier::execution::panic_reason::{PanicItem, PanicReason};
 use blockifier::execution::stack_trace::gen_transaction_execution_error_trace;
+use blockifier::fee::fee_multipliers::FeeMultipliers;
 use blockifier::transaction::errors::TransactionExecutionError as BlockifierTransactionExecutionError;
+use blockifier::transaction::objects::TransactionExecutionInfo;
 use indexmap::indexmap;
 use papyrus_storage::test_utils::get_test_storage;
 use pretty_assertions::assert_eq;
@@ -54,10 +57,13 @@ use crate::test_utils::{
 };
 use crate::testing_instances::get_test_execution_config;
 use crate::{
+    decode_revert_reason,
     estimate_fee,
     execute_call,
     get_versioned_constants,
+    panic_decoder,
     ExecutableTransactionInput,
+    ExecutionConfig,
     ExecutionError,
     FeeEstimationResult,
     RevertedTransaction,
@@ -242,10 +248,61 @@ fn estimate_fee_reverted() {
         .collect();
 
     let failed_estimation = estimate_fees(txs).expect_err("Fee estimation should fail.");
-    assert_matches!(failed_estimation, RevertedTransaction { index: 1, revert_reason: _ })
+    assert_matches!(failed_estimation, RevertedTransaction { index: 1, .. })
+}
+
+#[test]
+fn estimate_fee_applies_fee_multipliers() {
+    let tx = TxsScenarioBuilder::default()
+        .invoke_deprecated(*ACCOUNT_ADDRESS, *DEPRECATED_CONTRACT_ADDRESS, None, false)
+        .collect();
+    let fee = estimate_fees(tx.clone()).expect("Fee estimation should succeed.").remove(0);
+
+    let execution_config = ExecutionConfig {
+        fee_multipliers: FeeMultipliers { invoke_percent: 200, ..Default::default() },
+        ..get_test_execution_config()
+    };
+    let doubled_fee = estimate_fees_with_config(tx, &execution_config)
+        .expect("Fee estimation should succeed.")
+        .remove(0);
+    assert_eq!(doubled_fee.gas_consumed, fee.gas_consumed * Felt::TWO);
+    assert_eq!(doubled_fee.data_gas_consumed, fee.data_gas_consumed * Felt::TWO);
+    assert_eq!(doubled_fee.overall_fee, Fee(fee.overall_fee.0 * 2));
+}
+
+#[test]
+fn revert_reason_is_decoded_with_the_configured_error_names() {
+    let execution_config = ExecutionConfig {
+        revert_error_names: vec!["InsufficientBalance".to_owned()],
+        ..get_test_execution_config()
+    };
+    let execution_info = TransactionExecutionInfo {
+        revert_error: Some("Execution failed.".to_owned()),
+        revert_panic_data: Some(vec![selector_from_name("InsufficientBalance").0]),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        decode_revert_reason(&execution_info, &panic_decoder(&execution_config)),
+        Some(PanicReason { items: vec![PanicItem::Error("InsufficientBalance".to_owned())] })
+    );
+    assert_eq!(
+        decode_revert_reason(
+            &TransactionExecutionInfo::default(),
+            &panic_decoder(&execution_config)
+        ),
+        None
+    );
 }
 
 fn estimate_fees(txs: Vec<ExecutableTransactionInput>) -> FeeEstimationResult {
+    estimate_fees_with_config(txs, &get_test_execution_config())
+}
+
+fn estimate_fees_with_config(
+    txs: Vec<ExecutableTransactionInput>,
+    execution_config: &ExecutionConfig,
+) -> FeeEstimationResult {
     let ((storage_reader, storage_writer), _temp_dir) = get_test_storage();
     prepare_storage(storage_writer);
 
@@ -256,7 +313,7 @@ fn estimate_fees(txs: Vec<ExecutableTransactionInput>) -> FeeEstimationResult {
         None,
         StateNumber::unchecked_right_after_block(BlockNumber(0)),
         BlockNumber(1),
-        &get_test_execution_config(),
+        execution_config,
         false,
         // TODO(yair): Add test for blob fee estimation.
         true,
@@ -304,6 +361,7 @@ fn simulate_invoke() {
                 validate_invocation: None,
                 execute_invocation: FunctionInvocationResult::Ok(_),
                 fee_transfer_invocation: None,
+                fee_split_transfer_invocation: None,
             }
         );
 
@@ -316,6 +374,7 @@ fn simulate_invoke() {
                 validate_invocation: Some(_),
                 execute_invocation: FunctionInvocationResult::Ok(_),
                 fee_transfer_invocation: None,
+                fee_split_transfer_invocation: None,
             }
         );
 
@@ -328,6 +387,7 @@ fn simulate_invoke() {
                 validate_invocation: None,
                 execute_invocation: FunctionInvocationResult::Ok(_),
                 fee_transfer_invocation: Some(_),
+                fee_split_transfer_invocation: None,
             }
         );
         assert_eq!(charge_fee.fee_estimation.gas_price, GAS_PRICE.price_in_wei);
@@ -345,6 +405,7 @@ fn simulate_invoke() {
                 validate_invocation: Some(_),
                 execute_invocation: FunctionInvocationResult::Ok(_),
                 fee_transfer_invocation: Some(_),
+                fee_split_transfer_invocation: None,
             }
         );
 
@@ -377,7 +438,11 @@ fn simulate_declare_deprecated() {
         };
         assert_matches!(
             exec_only_trace,
-            DeclareTransactionTrace { validate_invocation: None, fee_transfer_invocation: None }
+            DeclareTransactionTrace {
+                validate_invocation: None,
+                fee_transfer_invocation: None,
+                fee_split_transfer_invocation: None,
+            }
         );
 
         let TransactionTrace::Declare(validate_trace) = &validate.transaction_trace else {
@@ -385,7 +450,11 @@ fn simulate_declare_deprecated() {
         };
         assert_matches!(
             validate_trace,
-            DeclareTransactionTrace { validate_invocation: Some(_), fee_transfer_invocation: None }
+            DeclareTransactionTrace {
+                validate_invocation: Some(_),
+                fee_transfer_invocation: None,
+                fee_split_transfer_invocation: None,
+            }
         );
 
         let TransactionTrace::Declare(charge_fee_trace) = &charge_fee.transaction_trace else {
@@ -393,7 +462,11 @@ fn simulate_declare_deprecated() {
         };
         assert_matches!(
             charge_fee_trace,
-            DeclareTransactionTrace { validate_invocation: None, fee_transfer_invocation: Some(_) }
+            DeclareTransactionTrace {
+                validate_invocation: None,
+                fee_transfer_invocation: Some(_),
+                fee_split_transfer_invocation: None,
+            }
         );
 
         let TransactionTrace::Declare(charge_fee_validate_trace) =
@@ -406,6 +479,7 @@ fn simulate_declare_deprecated() {
             DeclareTransactionTrace {
                 validate_invocation: Some(_),
                 fee_transfer_invocation: Some(_),
+                fee_split_transfer_invocation: None,
             }
         );
 
@@ -438,7 +512,11 @@ fn simulate_declare() {
         };
         assert_matches!(
             exec_only_trace,
-            DeclareTransactionTrace { validate_invocation: None, fee_transfer_invocation: None }
+            DeclareTransactionTrace {
+                validate_invocation: None,
+                fee_transfer_invocation: None,
+                fee_split_transfer_invocation: None,
+            }
         );
 
         let TransactionTrace::Declare(validate_trace) = &validate.transaction_trace else {
@@ -446,7 +524,11 @@ fn simulate_declare() {
         };
         assert_matches!(
             validate_trace,
-            DeclareTransactionTrace { validate_invocation: Some(_), fee_transfer_invocation: None }
+            DeclareTransactionTrace {
+                validate_invocation: Some(_),
+                fee_transfer_invocation: None,
+                fee_split_transfer_invocation: None,
+            }
         );
 
         let TransactionTrace::Declare(charge_fee_trace) = &charge_fee.transaction_trace else {
@@ -454,7 +536,11 @@ fn simulate_declare() {
         };
         assert_matches!(
             charge_fee_trace,
-            DeclareTransactionTrace { validate_invocation: None, fee_transfer_invocation: Some(_) }
+            DeclareTransactionTrace {
+                validate_invocation: None,
+                fee_transfer_invocation: Some(_),
+                fee_split_transfer_invocation: None,
+            }
         );
 
         let TransactionTrace::Declare(charge_fee_validate_trace) =
@@ -467,6 +553,7 @@ fn simulate_declare() {
             DeclareTransactionTrace {
                 validate_invocation: Some(_),
                 fee_transfer_invocation: Some(_),
+                fee_split_transfer_invocation: None,
             }
         );
 
@@ -502,6 +589,7 @@ fn simulate_deploy_account() {
             DeployAccountTransactionTrace {
                 validate_invocation: None,
                 fee_transfer_invocation: None,
+                fee_split_transfer_invocation: None,
                 constructor_invocation: _,
             }
         );
@@ -514,6 +602,7 @@ fn simulate_deploy_account() {
             DeployAccountTransactionTrace {
                 validate_invocation: Some(_),
                 fee_transfer_invocation: None,
+                fee_split_transfer_invocation: None,
                 constructor_invocation: _
             }
         );
@@ -527,6 +616,7 @@ fn simulate_deploy_account() {
             DeployAccountTransactionTrace {
                 validate_invocation: None,
                 fee_transfer_invocation: Some(_),
+                fee_split_transfer_invocation: None,
                 constructor_invocation: _
             }
         );
@@ -541,6 +631,7 @@ fn simulate_deploy_account() {
             DeployAccountTransactionTrace {
                 validate_invocation: Some(_),
                 fee_transfer_invocation: Some(_),
+                fee_split_transfer_invocation: None,
                 constructor_invocation: _
             }
         );
@@ -12,6 +12,7 @@ use blockifier::execution::contract_class::{
 use blockifier::state::cached_state::{CachedState, CommitmentStateDiff, MutRefState};
 use blockifier::state::state_api::StateReader;
 use blockifier::transaction::objects::TransactionExecutionInfo;
+use blockifier::transaction::transaction_types::TransactionType;
 use cairo_vm::types::errors::program_errors::ProgramError;
 use indexmap::IndexMap;
 use papyrus_common::state::{DeployedContract, ReplacedClass, StorageEntry};
@@ -89,27 +90,21 @@ pub fn get_trace_constructor(
     tx: &ExecutableTransactionInput,
 ) -> fn(TransactionExecutionInfo) -> ExecutionResult<TransactionTrace> {
     match tx {
-        ExecutableTransactionInput::Invoke(..) => {
-            |execution_info| Ok(TransactionTrace::Invoke(execution_info.try_into()?))
-        }
-        ExecutableTransactionInput::DeclareV0(..) => {
-            |execution_info| Ok(TransactionTrace::Declare(execution_info.try_into()?))
-        }
-        ExecutableTransactionInput::DeclareV1(..) => {
-            |execution_info| Ok(TransactionTrace::Declare(execution_info.try_into()?))
-        }
-        ExecutableTransactionInput::DeclareV2(..) => {
-            |execution_info| Ok(TransactionTrace::Declare(execution_info.try_into()?))
-        }
-        ExecutableTransactionInput::DeclareV3(..) => {
-            |execution_info| Ok(TransactionTrace::Declare(execution_info.try_into()?))
-        }
-        ExecutableTransactionInput::DeployAccount(..) => {
-            |execution_info| Ok(TransactionTrace::DeployAccount(execution_info.try_into()?))
-        }
-        ExecutableTransactionInput::L1Handler(..) => {
-            |execution_info| Ok(TransactionTrace::L1Handler(execution_info.try_into()?))
-        }
+        ExecutableTransactionInput::Invoke(..) => |execution_info| {
+            TransactionTrace::from_execution_info(TransactionType::InvokeFunction, execution_info)
+        },
+        ExecutableTransactionInput::DeclareV0(..)
+        | ExecutableTransactionInput::DeclareV1(..)
+        | ExecutableTransactionInput::DeclareV2(..)
+        | ExecutableTransactionInput::DeclareV3(..) => |execution_info| {
+            TransactionTrace::from_execution_info(TransactionType::Declare, execution_info)
+        },
+        ExecutableTransactionInput::DeployAccount(..) => |execution_info| {
+            TransactionTrace::from_execution_info(TransactionType::DeployAccount, execution_info)
+        },
+        ExecutableTransactionInput::L1Handler(..) => |execution_info| {
+            TransactionTrace::from_execution_info(TransactionType::L1Handler, execution_info)
+        },
     }
 }
 
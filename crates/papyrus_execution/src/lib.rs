@@ -19,23 +19,37 @@ mod test_utils;
 pub mod testing_instances;
 
 pub mod objects;
+pub mod receipt;
 use std::cell::Cell;
 use std::collections::BTreeMap;
 use std::num::NonZeroU128;
 use std::sync::Arc;
 
-use blockifier::blockifier::block::{pre_process_block, BlockInfo, BlockNumberHashPair, GasPrices};
+use blockifier::blockifier::block::{
+    pre_process_block_and_create_context,
+    BlockInfo,
+    BlockNumberHashPair,
+    GasPrices,
+    ParentBlockInfo,
+};
 use blockifier::bouncer::BouncerConfig;
 use blockifier::context::{BlockContext, ChainInfo, FeeTokenAddresses, TransactionContext};
 use blockifier::execution::call_info::CallExecution;
 use blockifier::execution::contract_class::{ClassInfo, ContractClass as BlockifierContractClass};
+pub use blockifier::execution::deprecated_class_usage::{
+    deprecated_class_usage_report,
+    DeprecatedClassUsage,
+};
 use blockifier::execution::entry_point::{
     CallEntryPoint,
     CallType as BlockifierCallType,
     EntryPointExecutionContext,
 };
+use blockifier::execution::panic_reason::{PanicDecoder, PanicReason};
+use blockifier::fee::fee_multipliers::FeeMultipliers;
 use blockifier::state::cached_state::CachedState;
 use blockifier::transaction::errors::TransactionExecutionError as BlockifierTransactionExecutionError;
+use blockifier::transaction::nonce_manager::NonceConfig;
 use blockifier::transaction::objects::{
     DeprecatedTransactionInfo,
     TransactionExecutionInfo,
@@ -43,6 +57,7 @@ use blockifier::transaction::objects::{
 };
 use blockifier::transaction::transaction_execution::Transaction as BlockifierTransaction;
 use blockifier::transaction::transactions::ExecutableTransaction;
+use blockifier::transaction::version_config::TransactionVersionConfig;
 use blockifier::versioned_constants::{
     StarknetVersion as BlockifierStarknetVersion,
     VersionedConstants,
@@ -54,11 +69,12 @@ use execution_utils::{get_trace_constructor, induced_state_diff};
 use objects::{PriceUnit, TransactionSimulationOutput};
 use papyrus_common::transaction_hash::get_transaction_hash;
 use papyrus_common::TransactionOptions;
-use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::dumping::{append_sub_config_name, ser_param, SerializeConfig};
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use papyrus_storage::header::HeaderStorageReader;
 use papyrus_storage::{StorageError, StorageReader};
-use serde::{Deserialize, Serialize};
+use receipt::price_unit;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use starknet_api::block::{BlockNumber, StarknetVersion};
 use starknet_api::core::{ChainId, ClassHash, ContractAddress, EntryPointSelector, PatriciaKey};
 use starknet_api::data_availability::L1DataAvailabilityMode;
@@ -91,6 +107,7 @@ use crate::objects::{tx_execution_output_to_fee_estimation, FeeEstimation, Pendi
 const STARKNET_VERSION_O_13_0: &str = "0.13.0";
 const STARKNET_VERSION_O_13_1: &str = "0.13.1";
 const STARKNET_VERSION_O_13_2: &str = "0.13.2";
+const STARKNET_VERSION_O_13_3: &str = "0.13.3";
 const STRK_FEE_CONTRACT_ADDRESS: &str =
     "0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d";
 const ETH_FEE_CONTRACT_ADDRESS: &str =
@@ -100,7 +117,7 @@ const INITIAL_GAS_COST: u64 = 10000000000;
 /// Result type for execution functions.
 pub type ExecutionResult<T> = Result<T, ExecutionError>;
 
-#[derive(Copy, Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 /// Parameters that are needed for execution.
 pub struct ExecutionConfig {
     /// The strk address to receive fees
@@ -109,6 +126,18 @@ pub struct ExecutionConfig {
     pub eth_fee_contract_address: ContractAddress,
     /// The initial gas cost for a transaction
     pub initial_gas_cost: u64,
+    /// The multipliers of the charged gas per transaction type. Must match the ones the chain's
+    /// sequencer charges, so that estimations and simulations return the fees it charges.
+    #[serde(default)]
+    pub fee_multipliers: FeeMultipliers,
+    /// The names of the errors that contracts panic with, by their selectors, which are decoded in
+    /// the revert reasons of reverted transactions.
+    #[serde(
+        default,
+        serialize_with = "serialize_error_names",
+        deserialize_with = "deserialize_error_names"
+    )]
+    pub revert_error_names: Vec<String>,
 }
 
 impl Default for ExecutionConfig {
@@ -117,13 +146,15 @@ impl Default for ExecutionConfig {
             strk_fee_contract_address: contract_address!(STRK_FEE_CONTRACT_ADDRESS),
             eth_fee_contract_address: contract_address!(ETH_FEE_CONTRACT_ADDRESS),
             initial_gas_cost: INITIAL_GAS_COST,
+            fee_multipliers: FeeMultipliers::default(),
+            revert_error_names: Vec::new(),
         }
     }
 }
 
 impl SerializeConfig for ExecutionConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
-        BTreeMap::from_iter([
+        let mut dump = BTreeMap::from_iter([
             ser_param(
                 "strk_fee_contract_address",
                 &self.strk_fee_contract_address,
@@ -142,10 +173,33 @@ impl SerializeConfig for ExecutionConfig {
                 "The initial gas cost for a transaction",
                 ParamPrivacyInput::Public,
             ),
-        ])
+            ser_param(
+                "revert_error_names",
+                &self.revert_error_names.join(","),
+                "A comma separated list of the names of the errors that contracts panic with, by \
+                 their selectors, which are decoded in the revert reasons of transactions",
+                ParamPrivacyInput::Public,
+            ),
+        ]);
+        dump.extend(append_sub_config_name(self.fee_multipliers.dump(), "fee_multipliers"));
+        dump
     }
 }
 
+fn serialize_error_names<S: Serializer>(error_names: &[String], ser: S) -> Result<S::Ok, S::Error> {
+    ser.serialize_str(&error_names.join(","))
+}
+
+fn deserialize_error_names<'de, D: Deserializer<'de>>(de: D) -> Result<Vec<String>, D::Error> {
+    let error_names = String::deserialize(de)?;
+    Ok(error_names
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_owned)
+        .collect())
+}
+
 #[allow(missing_docs)]
 /// The error type for the execution module.
 #[derive(thiserror::Error, Debug)]
@@ -162,6 +216,8 @@ pub enum ExecutionError {
     ConfigFileError(#[from] std::io::Error),
     #[error(transparent)]
     ConfigSerdeError(#[from] serde_json::Error),
+    #[error("Failed to calculate the address of a deployed contract.")]
+    ContractAddressCalculationFailed(StarknetApiError),
     #[error(transparent)]
     ContractError(#[from] BlockifierError),
     #[error(
@@ -329,7 +385,9 @@ fn create_block_context(
             )
         }
     };
-    let ten_blocks_ago = get_10_blocks_ago(&block_context_number, cached_state)?;
+    // The hash stored by the block is relative to the block itself, which is the one after the
+    // block context when executing on top of pending data.
+    let ten_blocks_ago = get_10_blocks_ago(&block_number, cached_state)?;
 
     let use_kzg_da = if override_kzg_da_to_false {
         false
@@ -362,22 +420,33 @@ fn create_block_context(
             strk_fee_token_address: execution_config.strk_fee_contract_address,
             eth_fee_token_address: execution_config.eth_fee_contract_address,
         },
+        eth_fee_token_deprecation_height: None,
+        fee_recipient: None,
+        fee_split: None,
+        fee_multipliers: execution_config.fee_multipliers,
+        nonce_config: NonceConfig::default(),
+        tx_version_config: TransactionVersionConfig::default(),
     };
-    let starknet_version: Option<StarknetVersion> =
-        storage_reader.begin_ro_txn()?.get_starknet_version(block_number)?;
+    let txn = storage_reader.begin_ro_txn()?;
+    let starknet_version: Option<StarknetVersion> = txn.get_starknet_version(block_number)?;
     let versioned_constants: &VersionedConstants =
         get_versioned_constants(starknet_version.as_ref())?;
+    let parent_block_info = match block_number.prev() {
+        Some(parent_block_number) => txn.get_block_header(parent_block_number)?.map(|header| {
+            ParentBlockInfo { block_number: header.block_number, block_timestamp: header.timestamp }
+        }),
+        None => None,
+    };
 
-    let block_context = BlockContext::new(
+    Ok(pre_process_block_and_create_context(
+        cached_state,
+        ten_blocks_ago,
+        parent_block_info,
         block_info,
         chain_info,
         versioned_constants.clone(),
         BouncerConfig::max(),
-    );
-    let next_block_number = block_context.block_info().block_number;
-
-    pre_process_block(cached_state, ten_blocks_ago, next_block_number)?;
-    Ok(block_context)
+    )?)
 }
 
 /// The size of the json string representing the abi of a class or deprecated class.
@@ -527,6 +596,9 @@ pub struct RevertedTransaction {
     pub index: usize,
     /// The revert reason.
     pub revert_reason: String,
+    /// The decoded revert reason, if the transaction reverted due to a Cairo panic.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decoded_revert_reason: Option<PanicReason>,
 }
 
 /// Valid output for fee estimation for a series of transactions can be either a list of fees or the
@@ -559,11 +631,14 @@ pub fn estimate_fee(
         validate,
         override_kzg_da_to_false,
     )?;
+    let panic_decoder = panic_decoder(execution_config);
     let mut result = Vec::new();
     for (index, tx_execution_output) in txs_execution_info.into_iter().enumerate() {
         // If the transaction reverted, fail the entire estimation.
+        let decoded_revert_reason =
+            decode_revert_reason(&tx_execution_output.execution_info, &panic_decoder);
         if let Some(revert_reason) = tx_execution_output.execution_info.revert_error {
-            return Ok(Err(RevertedTransaction { index, revert_reason }));
+            return Ok(Err(RevertedTransaction { index, revert_reason, decoded_revert_reason }));
         } else {
             result
                 .push(tx_execution_output_to_fee_estimation(&tx_execution_output, &block_context)?);
@@ -624,16 +699,7 @@ fn execute_transactions(
     let mut res = vec![];
     for (transaction_index, (tx, tx_hash)) in txs.into_iter().zip(tx_hashes.into_iter()).enumerate()
     {
-        let transaction_version = tx.transaction_version();
-        // TODO: consider supporting match instead.
-        let price_unit = if transaction_version == TransactionVersion::ZERO
-            || transaction_version == TransactionVersion::ONE
-            || transaction_version == TransactionVersion::TWO
-        {
-            PriceUnit::Wei
-        } else {
-            PriceUnit::Fri
-        };
+        let price_unit = price_unit(tx.transaction_version());
         let mut transactional_state = CachedState::create_transactional(&mut cached_state);
         let deprecated_declared_class_hash = match &tx {
             ExecutableTransactionInput::DeclareV0(
@@ -867,6 +933,8 @@ fn get_versioned_constants(
                 BlockifierStarknetVersion::V0_13_1
             } else if version == STARKNET_VERSION_O_13_2 {
                 BlockifierStarknetVersion::V0_13_2
+            } else if version == STARKNET_VERSION_O_13_3 {
+                BlockifierStarknetVersion::V0_13_3
             } else {
                 BlockifierStarknetVersion::Latest
             };
@@ -907,20 +975,37 @@ pub fn simulate_transactions(
         validate,
         override_kzg_da_to_false,
     )?;
+    let panic_decoder = panic_decoder(execution_config);
     execution_results
         .into_iter()
         .zip(trace_constructors)
         .map(|(tx_execution_output, trace_constructor)| {
             let fee_estimation =
                 tx_execution_output_to_fee_estimation(&tx_execution_output, &block_context)?;
+            let revert_reason =
+                decode_revert_reason(&tx_execution_output.execution_info, &panic_decoder);
             match trace_constructor(tx_execution_output.execution_info) {
                 Ok(transaction_trace) => Ok(TransactionSimulationOutput {
                     transaction_trace,
                     induced_state_diff: tx_execution_output.induced_state_diff,
                     fee_estimation,
+                    revert_reason,
                 }),
                 Err(e) => Err(e),
             }
         })
         .collect()
 }
+
+// A decoder of panic data that recognizes the errors of the config.
+fn panic_decoder(execution_config: &ExecutionConfig) -> PanicDecoder {
+    PanicDecoder::new(execution_config.revert_error_names.iter().map(String::as_str))
+}
+
+// Decodes the panic data of a transaction that reverted due to a Cairo panic.
+fn decode_revert_reason(
+    execution_info: &TransactionExecutionInfo,
+    panic_decoder: &PanicDecoder,
+) -> Option<PanicReason> {
+    execution_info.revert_panic_data.as_deref().map(|panic_data| panic_decoder.decode(panic_data))
+}
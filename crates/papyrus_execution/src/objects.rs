@@ -1,4 +1,8 @@
 //! Execution objects.
+#[cfg(test)]
+#[path = "objects_test.rs"]
+mod objects_test;
+
 use std::collections::HashMap;
 
 use blockifier::context::BlockContext;
@@ -9,7 +13,9 @@ use blockifier::execution::call_info::{
     Retdata as BlockifierRetdata,
 };
 use blockifier::execution::entry_point::CallType as BlockifierCallType;
+use blockifier::execution::panic_reason::PanicReason;
 use blockifier::transaction::objects::{FeeType, GasVector, TransactionExecutionInfo};
+use blockifier::transaction::transaction_types::TransactionType;
 use cairo_vm::types::builtin_name::BuiltinName;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
 use indexmap::IndexMap;
@@ -54,6 +60,9 @@ pub struct TransactionSimulationOutput {
     pub induced_state_diff: ThinStateDiff,
     /// The details of the fees charged by the transaction.
     pub fee_estimation: FeeEstimation,
+    /// The decoded revert reason, if the transaction reverted due to a Cairo panic.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<PanicReason>,
 }
 
 /// The execution trace of a transaction.
@@ -71,6 +80,23 @@ pub enum TransactionTrace {
     DeployAccount(DeployAccountTransactionTrace),
 }
 
+impl TransactionTrace {
+    /// Converts the execution info of a transaction of the given type into its trace.
+    pub fn from_execution_info(
+        transaction_type: TransactionType,
+        transaction_execution_info: TransactionExecutionInfo,
+    ) -> ExecutionResult<Self> {
+        Ok(match transaction_type {
+            TransactionType::Declare => Self::Declare(transaction_execution_info.try_into()?),
+            TransactionType::DeployAccount => {
+                Self::DeployAccount(transaction_execution_info.try_into()?)
+            }
+            TransactionType::InvokeFunction => Self::Invoke(transaction_execution_info.try_into()?),
+            TransactionType::L1Handler => Self::L1Handler(transaction_execution_info.try_into()?),
+        })
+    }
+}
+
 /// The execution trace of an Invoke transaction.
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub struct InvokeTransactionTrace {
@@ -82,6 +108,9 @@ pub struct InvokeTransactionTrace {
     #[serde(skip_serializing_if = "Option::is_none")]
     /// The trace of the __fee_transfer__ call.
     pub fee_transfer_invocation: Option<FunctionInvocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The trace of the transfer of the fee split to its recipient, if the chain splits the fee.
+    pub fee_split_transfer_invocation: Option<FunctionInvocation>,
 }
 
 /// Output for successful fee estimation.
@@ -144,6 +173,14 @@ impl TryFrom<TransactionExecutionInfo> for InvokeTransactionTrace {
                     Some((call_info, transaction_execution_info.receipt.da_gas).try_into()?)
                 }
             },
+            fee_split_transfer_invocation: match transaction_execution_info
+                .fee_split_transfer_call_info
+            {
+                None => None,
+                Some(call_info) => {
+                    Some((call_info, transaction_execution_info.receipt.da_gas).try_into()?)
+                }
+            },
         })
     }
 }
@@ -185,6 +222,9 @@ pub struct DeclareTransactionTrace {
     /// The trace of the __fee_transfer__ call.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fee_transfer_invocation: Option<FunctionInvocation>,
+    /// The trace of the transfer of the fee split to its recipient, if the chain splits the fee.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_split_transfer_invocation: Option<FunctionInvocation>,
 }
 
 impl TryFrom<TransactionExecutionInfo> for DeclareTransactionTrace {
@@ -203,6 +243,14 @@ impl TryFrom<TransactionExecutionInfo> for DeclareTransactionTrace {
                     Some((call_info, transaction_execution_info.receipt.da_gas).try_into()?)
                 }
             },
+            fee_split_transfer_invocation: match transaction_execution_info
+                .fee_split_transfer_call_info
+            {
+                None => None,
+                Some(call_info) => {
+                    Some((call_info, transaction_execution_info.receipt.da_gas).try_into()?)
+                }
+            },
         })
     }
 }
@@ -218,6 +266,9 @@ pub struct DeployAccountTransactionTrace {
     /// The trace of the __fee_transfer__ call.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fee_transfer_invocation: Option<FunctionInvocation>,
+    /// The trace of the transfer of the fee split to its recipient, if the chain splits the fee.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_split_transfer_invocation: Option<FunctionInvocation>,
 }
 
 impl TryFrom<TransactionExecutionInfo> for DeployAccountTransactionTrace {
@@ -244,6 +295,14 @@ impl TryFrom<TransactionExecutionInfo> for DeployAccountTransactionTrace {
                     Some((call_info, transaction_execution_info.receipt.da_gas).try_into()?)
                 }
             },
+            fee_split_transfer_invocation: match transaction_execution_info
+                .fee_split_transfer_call_info
+            {
+                None => None,
+                Some(call_info) => {
+                    Some((call_info, transaction_execution_info.receipt.da_gas).try_into()?)
+                }
+            },
         })
     }
 }
@@ -352,7 +411,7 @@ impl TryFrom<(CallInfo, GasVector)> for FunctionInvocation {
 }
 
 // Can't implement `TryFrom` because both types are from external crates.
-fn vm_resources_to_execution_resources(
+pub(crate) fn vm_resources_to_execution_resources(
     vm_resources: VmExecutionResources,
     GasVector { l1_gas, l1_data_gas, .. }: GasVector,
 ) -> ExecutionResult<ExecutionResources> {
@@ -0,0 +1,159 @@
+use assert_matches::assert_matches;
+use blockifier::execution::call_info::{
+    OrderedEvent as BlockifierOrderedEvent,
+    OrderedL2ToL1Message as BlockifierOrderedL2ToL1Message,
+};
+use blockifier::transaction::objects::GasVector;
+use blockifier::transaction::transaction_types::TransactionType;
+use starknet_api::core::{ClassHash, ContractAddress, EthAddress, PatriciaKey};
+use starknet_api::transaction::MessageToL1;
+use starknet_api::{class_hash, contract_address, felt, patricia_key};
+
+use crate::objects::{
+    DeclareTransactionTrace,
+    DeployAccountTransactionTrace,
+    FunctionInvocation,
+    FunctionInvocationResult,
+    InvokeTransactionTrace,
+    L1HandlerTransactionTrace,
+    OrderedEvent,
+    OrderedL2ToL1Message,
+    RevertReason,
+    TransactionTrace,
+};
+use crate::test_utils::{test_call_info, test_event, test_execution_info, test_message};
+
+fn message(order: usize, from_address: ContractAddress, payload: u8) -> OrderedL2ToL1Message {
+    OrderedL2ToL1Message {
+        order,
+        message: MessageToL1 {
+            from_address,
+            to_address: EthAddress::default(),
+            payload: test_message(payload).payload,
+        },
+    }
+}
+
+#[test]
+fn function_invocation_of_nested_calls() {
+    let execution_info = test_execution_info(None);
+    let invocation = FunctionInvocation::try_from((
+        execution_info.execute_call_info.unwrap(),
+        execution_info.receipt.da_gas,
+    ))
+    .unwrap();
+
+    assert_eq!(invocation.function_call.contract_address, contract_address!("0x1"));
+    assert_eq!(invocation.class_hash, class_hash!("0x1"));
+    assert_eq!(invocation.events, vec![OrderedEvent { order: 1, event: test_event(1) }]);
+    assert_eq!(invocation.messages, vec![message(0, contract_address!("0x1"), 0)]);
+    assert_eq!(invocation.execution_resources.da_gas_consumed.l1_data_gas, 2);
+
+    let [inner_invocation] = invocation.calls.as_slice() else {
+        panic!("Expected a single inner call, got {:?}.", invocation.calls);
+    };
+    assert_eq!(inner_invocation.function_call.contract_address, contract_address!("0x2"));
+    assert_eq!(inner_invocation.events, vec![OrderedEvent { order: 0, event: test_event(0) }]);
+    assert_eq!(inner_invocation.messages, vec![message(1, contract_address!("0x2"), 1)]);
+}
+
+#[test]
+fn function_invocation_events_and_messages_are_ordered() {
+    let call_info = test_call_info(
+        contract_address!("0x1"),
+        vec![
+            BlockifierOrderedEvent { order: 2, event: test_event(2) },
+            BlockifierOrderedEvent { order: 1, event: test_event(1) },
+        ],
+        vec![
+            BlockifierOrderedL2ToL1Message { order: 1, message: test_message(1) },
+            BlockifierOrderedL2ToL1Message { order: 0, message: test_message(0) },
+        ],
+        vec![],
+    );
+    let invocation = FunctionInvocation::try_from((call_info, GasVector::default())).unwrap();
+
+    assert_eq!(
+        invocation.events,
+        vec![
+            OrderedEvent { order: 1, event: test_event(1) },
+            OrderedEvent { order: 2, event: test_event(2) },
+        ]
+    );
+    assert_eq!(
+        invocation.messages,
+        vec![message(0, contract_address!("0x1"), 0), message(1, contract_address!("0x1"), 1)]
+    );
+}
+
+#[test]
+fn trace_by_transaction_type() {
+    assert_matches!(
+        TransactionTrace::from_execution_info(
+            TransactionType::InvokeFunction,
+            test_execution_info(None)
+        ),
+        Ok(TransactionTrace::Invoke(InvokeTransactionTrace {
+            validate_invocation: None,
+            execute_invocation: FunctionInvocationResult::Ok(_),
+            fee_transfer_invocation: Some(_),
+            fee_split_transfer_invocation: None,
+        }))
+    );
+    assert_matches!(
+        TransactionTrace::from_execution_info(TransactionType::Declare, test_execution_info(None)),
+        Ok(TransactionTrace::Declare(DeclareTransactionTrace {
+            validate_invocation: None,
+            fee_transfer_invocation: Some(_),
+            fee_split_transfer_invocation: None,
+        }))
+    );
+    assert_matches!(
+        TransactionTrace::from_execution_info(
+            TransactionType::DeployAccount,
+            test_execution_info(None)
+        ),
+        Ok(TransactionTrace::DeployAccount(DeployAccountTransactionTrace {
+            constructor_invocation,
+            ..
+        })) if constructor_invocation.function_call.contract_address == contract_address!("0x1")
+    );
+    assert_matches!(
+        TransactionTrace::from_execution_info(
+            TransactionType::L1Handler,
+            test_execution_info(None)
+        ),
+        Ok(TransactionTrace::L1Handler(L1HandlerTransactionTrace { function_invocation }))
+            if function_invocation.function_call.contract_address == contract_address!("0x1")
+    );
+}
+
+#[test]
+fn reverted_invoke_trace() {
+    let revert_reason = "Out of gas.".to_owned();
+    assert_matches!(
+        TransactionTrace::from_execution_info(
+            TransactionType::InvokeFunction,
+            test_execution_info(Some(revert_reason.clone())),
+        ),
+        Ok(TransactionTrace::Invoke(InvokeTransactionTrace {
+            execute_invocation: FunctionInvocationResult::Err(RevertReason::RevertReason(reason)),
+            fee_transfer_invocation: Some(_),
+            ..
+        })) if reason == revert_reason
+    );
+}
+
+#[test]
+fn fee_split_transfer_trace() {
+    let mut execution_info = test_execution_info(None);
+    execution_info.fee_split_transfer_call_info =
+        Some(test_call_info(contract_address!("0x3"), vec![], vec![], vec![]));
+    assert_matches!(
+        TransactionTrace::from_execution_info(TransactionType::InvokeFunction, execution_info),
+        Ok(TransactionTrace::Invoke(InvokeTransactionTrace {
+            fee_split_transfer_invocation: Some(invocation),
+            ..
+        })) if invocation.function_call.contract_address == contract_address!("0x3")
+    );
+}
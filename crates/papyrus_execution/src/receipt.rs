@@ -0,0 +1,209 @@
+//! Building the outputs of executed transactions, as they appear in their receipts.
+//!
+//! The output of a transaction holds the content of its receipt in the Starknet feeder gateway: the
+//! actual fee, the events and the messages to L1 in the order they were emitted, the execution
+//! resources, and the revert reason of a reverted transaction. Storing the outputs in the body of
+//! the block (see [`append_executed_block_body`]) is enough for the RPC to answer
+//! `starknet_getTransactionReceipt`, which takes the unit of the fee from the transaction version
+//! (see [`price_unit`]).
+#[cfg(test)]
+#[path = "receipt_test.rs"]
+mod receipt_test;
+
+use blockifier::execution::call_info::CallInfo;
+use blockifier::transaction::objects::TransactionExecutionInfo;
+use itertools::Itertools;
+use papyrus_storage::body::BodyStorageWriter;
+use papyrus_storage::StorageWriter;
+use starknet_api::block::{BlockBody, BlockNumber};
+use starknet_api::core::{calculate_contract_address, ContractAddress};
+use starknet_api::execution_resources::{ExecutionResources, GasVector as StarknetApiGasVector};
+use starknet_api::transaction::{
+    DeclareTransactionOutput,
+    DeployAccountTransactionOutput,
+    DeployTransactionOutput,
+    Event,
+    InvokeTransactionOutput,
+    L1HandlerTransactionOutput,
+    MessageToL1,
+    RevertedTransactionExecutionStatus,
+    Transaction,
+    TransactionExecutionStatus,
+    TransactionHash,
+    TransactionOutput,
+    TransactionVersion,
+};
+
+use crate::objects::{vm_resources_to_execution_resources, PriceUnit};
+use crate::{ExecutionError, ExecutionResult};
+
+/// Returns the unit of the fee of a transaction with the given version.
+pub fn price_unit(transaction_version: TransactionVersion) -> PriceUnit {
+    // TODO: consider supporting match instead.
+    if transaction_version == TransactionVersion::ZERO
+        || transaction_version == TransactionVersion::ONE
+        || transaction_version == TransactionVersion::TWO
+    {
+        PriceUnit::Wei
+    } else {
+        PriceUnit::Fri
+    }
+}
+
+/// Builds the output of an executed transaction.
+pub fn build_transaction_output(
+    transaction: &Transaction,
+    execution_info: TransactionExecutionInfo,
+) -> ExecutionResult<TransactionOutput> {
+    let call_infos = || execution_info.non_optional_call_infos();
+    let actual_fee = execution_info.receipt.fee;
+    let messages_sent = call_infos().flat_map(ordered_messages).collect();
+    let events = call_infos().flat_map(ordered_events).collect();
+    let execution_status = match execution_info.revert_error {
+        None => TransactionExecutionStatus::Succeeded,
+        Some(revert_reason) => {
+            TransactionExecutionStatus::Reverted(RevertedTransactionExecutionStatus {
+                revert_reason,
+            })
+        }
+    };
+
+    let transaction_resources = execution_info.receipt.resources;
+    let mut execution_resources = vm_resources_to_execution_resources(
+        transaction_resources.vm_resources,
+        execution_info.receipt.da_gas,
+    )?;
+    // The steps of the reverted part of the execution are charged as well.
+    execution_resources.steps += u64::try_from(transaction_resources.n_reverted_steps)
+        .expect("The number of steps should fit in u64.");
+    execution_resources.gas_consumed = StarknetApiGasVector {
+        l1_gas: execution_info
+            .receipt
+            .gas
+            .l1_gas
+            .try_into()
+            .map_err(|_| ExecutionError::GasConsumedOutOfRange)?,
+        l1_data_gas: execution_info
+            .receipt
+            .gas
+            .l1_data_gas
+            .try_into()
+            .map_err(|_| ExecutionError::GasConsumedOutOfRange)?,
+    };
+
+    Ok(match transaction {
+        Transaction::Declare(_) => TransactionOutput::Declare(DeclareTransactionOutput {
+            actual_fee,
+            messages_sent,
+            events,
+            execution_status,
+            execution_resources,
+        }),
+        Transaction::Deploy(tx) => TransactionOutput::Deploy(DeployTransactionOutput {
+            actual_fee,
+            messages_sent,
+            events,
+            contract_address: calculate_contract_address(
+                tx.contract_address_salt,
+                tx.class_hash,
+                &tx.constructor_calldata,
+                ContractAddress::default(),
+            )
+            .map_err(ExecutionError::ContractAddressCalculationFailed)?,
+            execution_status,
+            execution_resources,
+        }),
+        Transaction::DeployAccount(tx) => {
+            TransactionOutput::DeployAccount(DeployAccountTransactionOutput {
+                actual_fee,
+                messages_sent,
+                events,
+                contract_address: calculate_contract_address(
+                    tx.contract_address_salt(),
+                    tx.class_hash(),
+                    &tx.constructor_calldata(),
+                    ContractAddress::default(),
+                )
+                .map_err(ExecutionError::ContractAddressCalculationFailed)?,
+                execution_status,
+                execution_resources,
+            })
+        }
+        Transaction::Invoke(_) => TransactionOutput::Invoke(InvokeTransactionOutput {
+            actual_fee,
+            messages_sent,
+            events,
+            execution_status,
+            execution_resources,
+        }),
+        Transaction::L1Handler(_) => TransactionOutput::L1Handler(L1HandlerTransactionOutput {
+            actual_fee,
+            messages_sent,
+            events,
+            execution_status,
+            execution_resources,
+        }),
+    })
+}
+
+/// Builds the body of a block from its executed transactions, in order.
+pub fn build_block_body(
+    executed_transactions: Vec<(Transaction, TransactionHash, TransactionExecutionInfo)>,
+) -> ExecutionResult<BlockBody> {
+    let mut body = BlockBody::default();
+    for (transaction, transaction_hash, execution_info) in executed_transactions {
+        body.transaction_outputs.push(build_transaction_output(&transaction, execution_info)?);
+        body.transactions.push(transaction);
+        body.transaction_hashes.push(transaction_hash);
+    }
+    Ok(body)
+}
+
+/// Stores the body of a block built from its executed transactions, in order, so that the receipts
+/// of its transactions are read from the storage.
+pub fn append_executed_block_body(
+    storage_writer: &mut StorageWriter,
+    block_number: BlockNumber,
+    executed_transactions: Vec<(Transaction, TransactionHash, TransactionExecutionInfo)>,
+) -> ExecutionResult<()> {
+    let body = build_block_body(executed_transactions)?;
+    storage_writer.begin_rw_txn()?.append_body(block_number, body)?.commit()?;
+    Ok(())
+}
+
+// The events of a call and its inner calls, in the order they were emitted.
+fn ordered_events(call_info: &CallInfo) -> Vec<Event> {
+    call_info
+        .iter()
+        .flat_map(|call| {
+            call.execution.events.iter().map(|ordered_event| {
+                let event = Event {
+                    from_address: call.call.storage_address,
+                    content: ordered_event.event.clone(),
+                };
+                (ordered_event.order, event)
+            })
+        })
+        .sorted_by_key(|(order, _)| *order)
+        .map(|(_, event)| event)
+        .collect()
+}
+
+// The messages to L1 that a call and its inner calls sent, in the order they were sent.
+fn ordered_messages(call_info: &CallInfo) -> Vec<MessageToL1> {
+    call_info
+        .iter()
+        .flat_map(|call| {
+            call.execution.l2_to_l1_messages.iter().map(|ordered_message| {
+                let message = MessageToL1 {
+                    from_address: call.call.storage_address,
+                    to_address: ordered_message.message.to_address,
+                    payload: ordered_message.message.payload.clone(),
+                };
+                (ordered_message.order, message)
+            })
+        })
+        .sorted_by_key(|(order, _)| *order)
+        .map(|(_, message)| message)
+        .collect()
+}
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use papyrus_storage::body::BodyStorageReader;
+use papyrus_storage::test_utils::get_test_storage;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{calculate_contract_address, ContractAddress, EthAddress, PatriciaKey};
+use starknet_api::execution_resources::{Builtin, ExecutionResources, GasVector as ApiGasVector};
+use starknet_api::transaction::{
+    Calldata,
+    DeployAccountTransaction,
+    DeployAccountTransactionV1,
+    Event,
+    Fee,
+    InvokeTransaction,
+    InvokeTransactionV1,
+    L2ToL1Payload,
+    MessageToL1,
+    RevertedTransactionExecutionStatus,
+    Transaction,
+    TransactionExecutionStatus,
+    TransactionHash,
+    TransactionOutput,
+    TransactionVersion,
+};
+use starknet_api::{calldata, contract_address, felt, patricia_key};
+
+use crate::objects::PriceUnit;
+use crate::receipt::{
+    append_executed_block_body,
+    build_block_body,
+    build_transaction_output,
+    price_unit,
+};
+use crate::test_utils::{test_event, test_execution_info};
+
+#[test]
+fn invoke_output() {
+    let transaction = Transaction::Invoke(InvokeTransaction::V1(InvokeTransactionV1::default()));
+    let output = build_transaction_output(&transaction, test_execution_info(None)).unwrap();
+
+    let expected_event = |from_address: &str, data| Event {
+        from_address: contract_address!(from_address),
+        content: test_event(data),
+    };
+    let expected_message = |from_address: &str, payload: u8| MessageToL1 {
+        from_address: contract_address!(from_address),
+        to_address: EthAddress::default(),
+        payload: L2ToL1Payload(vec![felt!(payload)]),
+    };
+    let TransactionOutput::Invoke(output) = output else {
+        panic!("Expected an invoke output, got {output:?}.");
+    };
+    assert_eq!(output.actual_fee, Fee(100));
+    assert_eq!(
+        output.events,
+        vec![expected_event("0x2", 0), expected_event("0x1", 1), expected_event("0x3", 2)]
+    );
+    assert_eq!(output.messages_sent, vec![expected_message("0x1", 0), expected_message("0x2", 1)]);
+    assert_eq!(output.execution_status, TransactionExecutionStatus::Succeeded);
+    assert_eq!(
+        output.execution_resources,
+        ExecutionResources {
+            steps: 55,
+            builtin_instance_counter: HashMap::from([(Builtin::Pedersen, 4)]),
+            memory_holes: 3,
+            da_gas_consumed: ApiGasVector { l1_gas: 1, l1_data_gas: 2 },
+            gas_consumed: ApiGasVector { l1_gas: 10, l1_data_gas: 20 },
+        }
+    );
+}
+
+#[test]
+fn reverted_output() {
+    let transaction = Transaction::Invoke(InvokeTransaction::V1(InvokeTransactionV1::default()));
+    let output =
+        build_transaction_output(&transaction, test_execution_info(Some("Out of gas.".to_owned())))
+            .unwrap();
+    assert_eq!(
+        *output.execution_status(),
+        TransactionExecutionStatus::Reverted(RevertedTransactionExecutionStatus {
+            revert_reason: "Out of gas.".to_owned()
+        })
+    );
+}
+
+#[test]
+fn deploy_account_output() {
+    let tx = DeployAccountTransactionV1 {
+        constructor_calldata: calldata![felt!(1_u8)],
+        ..Default::default()
+    };
+    let expected_address = calculate_contract_address(
+        tx.contract_address_salt,
+        tx.class_hash,
+        &tx.constructor_calldata,
+        ContractAddress::default(),
+    )
+    .unwrap();
+    let transaction = Transaction::DeployAccount(DeployAccountTransaction::V1(tx));
+    let output = build_transaction_output(&transaction, test_execution_info(None)).unwrap();
+    let TransactionOutput::DeployAccount(output) = output else {
+        panic!("Expected a deploy account output, got {output:?}.");
+    };
+    assert_eq!(output.contract_address, expected_address);
+}
+
+#[test]
+fn block_body_of_executed_transactions() {
+    let transaction = Transaction::Invoke(InvokeTransaction::V1(InvokeTransactionV1::default()));
+    let transaction_hash = TransactionHash(felt!("0x1234"));
+    let body =
+        build_block_body(vec![(transaction.clone(), transaction_hash, test_execution_info(None))])
+            .unwrap();
+    assert_eq!(body.transactions, vec![transaction.clone()]);
+    assert_eq!(body.transaction_hashes, vec![transaction_hash]);
+    assert_eq!(
+        body.transaction_outputs,
+        vec![build_transaction_output(&transaction, test_execution_info(None)).unwrap()]
+    );
+}
+
+#[test]
+fn receipts_are_read_from_the_stored_body() {
+    let ((storage_reader, mut storage_writer), _temp_dir) = get_test_storage();
+    let transaction = Transaction::Invoke(InvokeTransaction::V1(InvokeTransactionV1::default()));
+    let transaction_hash = TransactionHash(felt!("0x1234"));
+    append_executed_block_body(
+        &mut storage_writer,
+        BlockNumber(0),
+        vec![(transaction.clone(), transaction_hash, test_execution_info(None))],
+    )
+    .unwrap();
+
+    let txn = storage_reader.begin_ro_txn().unwrap();
+    let transaction_index = txn.get_transaction_idx_by_hash(&transaction_hash).unwrap().unwrap();
+    assert_eq!(
+        txn.get_transaction_output(transaction_index).unwrap(),
+        Some(build_transaction_output(&transaction, test_execution_info(None)).unwrap())
+    );
+}
+
+#[test]
+fn fee_unit_by_version() {
+    assert_eq!(price_unit(TransactionVersion::ONE), PriceUnit::Wei);
+    assert_eq!(price_unit(TransactionVersion::THREE), PriceUnit::Fri);
+}
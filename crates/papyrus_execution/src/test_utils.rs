@@ -1,7 +1,19 @@
 use std::collections::HashMap;
 
 use blockifier::abi::abi_utils::get_storage_var_address;
+use blockifier::execution::call_info::{
+    CallExecution,
+    CallInfo,
+    MessageToL1,
+    OrderedEvent,
+    OrderedL2ToL1Message,
+};
+use blockifier::execution::entry_point::CallEntryPoint;
+use blockifier::fee::actual_cost::TransactionReceipt;
+use blockifier::transaction::objects::{GasVector, TransactionExecutionInfo, TransactionResources};
 use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use cairo_vm::types::builtin_name::BuiltinName;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
 use indexmap::indexmap;
 use lazy_static::lazy_static;
 use papyrus_storage::body::BodyStorageWriter;
@@ -38,9 +50,12 @@ use starknet_api::transaction::{
     DeclareTransactionV2,
     DeployAccountTransaction,
     DeployAccountTransactionV1,
+    EventContent,
+    EventData,
     Fee,
     InvokeTransaction,
     InvokeTransactionV1,
+    L2ToL1Payload,
     TransactionHash,
 };
 use starknet_api::{calldata, class_hash, contract_address, felt, patricia_key};
@@ -348,3 +363,75 @@ impl TxsScenarioBuilder {
         class_hash
     }
 }
+
+pub fn test_event(data: u8) -> EventContent {
+    EventContent { data: EventData(vec![felt!(data)]), ..Default::default() }
+}
+
+pub fn test_message(payload: u8) -> MessageToL1 {
+    MessageToL1 { to_address: Default::default(), payload: L2ToL1Payload(vec![felt!(payload)]) }
+}
+
+pub fn test_call_info(
+    address: ContractAddress,
+    events: Vec<OrderedEvent>,
+    l2_to_l1_messages: Vec<OrderedL2ToL1Message>,
+    inner_calls: Vec<CallInfo>,
+) -> CallInfo {
+    CallInfo {
+        call: CallEntryPoint {
+            storage_address: address,
+            class_hash: Some(ClassHash(*address.0.key())),
+            ..Default::default()
+        },
+        execution: CallExecution { events, l2_to_l1_messages, ..Default::default() },
+        inner_calls,
+        ..Default::default()
+    }
+}
+
+// The execution info of a transaction whose calls emit events and send messages in a different
+// order than the order of the calls.
+pub fn test_execution_info(revert_error: Option<String>) -> TransactionExecutionInfo {
+    let inner_call = test_call_info(
+        contract_address!("0x2"),
+        vec![OrderedEvent { order: 0, event: test_event(0) }],
+        vec![OrderedL2ToL1Message { order: 1, message: test_message(1) }],
+        vec![],
+    );
+    let execute_call_info = test_call_info(
+        contract_address!("0x1"),
+        vec![OrderedEvent { order: 1, event: test_event(1) }],
+        vec![OrderedL2ToL1Message { order: 0, message: test_message(0) }],
+        vec![inner_call],
+    );
+    let fee_transfer_call_info = test_call_info(
+        contract_address!("0x3"),
+        vec![OrderedEvent { order: 0, event: test_event(2) }],
+        vec![],
+        vec![],
+    );
+    TransactionExecutionInfo {
+        validate_call_info: None,
+        execute_call_info: (revert_error.is_none()).then_some(execute_call_info),
+        fee_transfer_call_info: Some(fee_transfer_call_info),
+        fee_split_transfer_call_info: None,
+        revert_error,
+        revert_panic_data: None,
+        receipt: TransactionReceipt {
+            fee: Fee(100),
+            gas: GasVector { l1_gas: 10, l1_data_gas: 20, l2_gas: 0 },
+            da_gas: GasVector { l1_gas: 1, l1_data_gas: 2, l2_gas: 0 },
+            resources: TransactionResources {
+                vm_resources: VmExecutionResources {
+                    n_steps: 50,
+                    n_memory_holes: 3,
+                    builtin_instance_counter: HashMap::from([(BuiltinName::pedersen, 4)]),
+                },
+                n_reverted_steps: 5,
+                ..Default::default()
+            },
+        },
+        read_set: None,
+    }
+}
@@ -3,6 +3,7 @@
 
 /// Returns the storage key of a storage variable.
 pub use blockifier::abi::abi_utils::get_storage_var_address;
+use blockifier::fee::fee_multipliers::FeeMultipliers;
 use papyrus_test_utils::{auto_impl_get_test_instance, get_number_of_variants, GetTestInstance};
 use starknet_api::block::GasPrice;
 use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, PatriciaKey};
@@ -37,6 +38,8 @@ pub fn get_test_execution_config() -> ExecutionConfig {
         strk_fee_contract_address: contract_address!("0x1001"),
         eth_fee_contract_address: contract_address!("0x1001"),
         initial_gas_cost: 10_u64.pow(10),
+        fee_multipliers: FeeMultipliers::default(),
+        revert_error_names: Vec::new(),
     }
 }
 
@@ -51,15 +54,18 @@ auto_impl_get_test_instance! {
         pub validate_invocation: Option<FunctionInvocation>,
         pub execute_invocation: FunctionInvocationResult,
         pub fee_transfer_invocation: Option<FunctionInvocation>,
+        pub fee_split_transfer_invocation: Option<FunctionInvocation>,
     }
     pub struct DeclareTransactionTrace {
         pub validate_invocation: Option<FunctionInvocation>,
         pub fee_transfer_invocation: Option<FunctionInvocation>,
+        pub fee_split_transfer_invocation: Option<FunctionInvocation>,
     }
     pub struct DeployAccountTransactionTrace {
         pub validate_invocation: Option<FunctionInvocation>,
         pub constructor_invocation: FunctionInvocation,
         pub fee_transfer_invocation: Option<FunctionInvocation>,
+        pub fee_split_transfer_invocation: Option<FunctionInvocation>,
     }
     pub struct L1HandlerTransactionTrace {
         pub function_invocation: FunctionInvocation,
@@ -1,4 +1,5 @@
 use std::net::{SocketAddr, TcpListener};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use axum::body::Body;
@@ -8,13 +9,28 @@ use axum::Router;
 use http_body::combinators::UnsyncBoxBody;
 use metrics::{absolute_counter, describe_counter, register_counter};
 use metrics_exporter_prometheus::PrometheusBuilder;
+use papyrus_common::BlockHashAndNumber;
+use papyrus_storage::header::HeaderStorageWriter;
 use papyrus_storage::{table_names, test_utils};
 use pretty_assertions::assert_eq;
 use serde_json::{json, Value};
+use starknet_api::block::{BlockHeader, BlockNumber};
 use starknet_client::reader::MockStarknetReader;
 use starknet_client::writer::MockStarknetWriter;
+use tokio::sync::RwLock;
 use tower::ServiceExt;
 
+use crate::health::{
+    get_node_health,
+    read_storage_status,
+    update_storage_status,
+    ConsensusStatus,
+    HealthSources,
+    NetworkStatus,
+    NodeHealth,
+    StorageStatus,
+    SyncStatus,
+};
 use crate::{app, is_ready, MONITORING_PREFIX};
 
 const TEST_CONFIG_PRESENTATION: &str = "full_general_config_presentation";
@@ -22,10 +38,12 @@ const PUBLIC_TEST_CONFIG_PRESENTATION: &str = "public_general_config_presentatio
 const SECRET: &str = "abcd";
 const TEST_VERSION: &str = "1.2.3-dev";
 const TEST_PEER_ID: &str = "peer_id";
+const READINESS_MAX_BLOCKS_BEHIND: u64 = 10;
 
 // TODO(dan): consider using a proper fixture.
 fn setup_app() -> Router {
     let ((storage_reader, _), _temp_dir) = test_utils::get_test_storage();
+    let storage_status = Arc::new(RwLock::new(read_storage_status(&storage_reader)));
     app(
         String::from("https://default_url"),
         storage_reader,
@@ -35,6 +53,9 @@ fn setup_app() -> Router {
         SECRET.to_string(),
         None,
         TEST_PEER_ID.to_string(),
+        HealthSources::default(),
+        storage_status,
+        READINESS_MAX_BLOCKS_BEHIND,
     )
 }
 
@@ -156,6 +177,133 @@ async fn ready() {
     assert_eq!(response, StatusCode::OK.to_string());
 }
 
+#[tokio::test]
+async fn health() {
+    let app = setup_app();
+    let response = request_app(app, "health").await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let body: NodeHealth = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        body,
+        NodeHealth {
+            healthy: true,
+            ready: true,
+            storage: StorageStatus { available: true, header_marker: BlockNumber(0) },
+            ..Default::default()
+        }
+    );
+}
+
+#[tokio::test]
+async fn node_health_aggregates_components() {
+    let ((storage_reader, mut storage_writer), _temp_dir) = test_utils::get_test_storage();
+    let mut txn = storage_writer.begin_rw_txn().unwrap();
+    for block_number in 0..20 {
+        txn = txn.append_header(BlockNumber(block_number), &BlockHeader::default()).unwrap();
+    }
+    txn.commit().unwrap();
+
+    let highest_block = Arc::new(RwLock::new(None));
+    let consensus_height = Arc::new(AtomicU64::new(15));
+    let num_connected_peers = Arc::new(AtomicUsize::new(3));
+    let sources = HealthSources {
+        highest_block: Some(highest_block.clone()),
+        consensus_height: Some(consensus_height.clone()),
+        num_connected_peers: Some(num_connected_peers.clone()),
+    };
+    let storage_status = RwLock::new(read_storage_status(&storage_reader));
+
+    // The node isn't ready until the sync knows the latest block.
+    let node_health = get_node_health(&storage_status, &sources, READINESS_MAX_BLOCKS_BEHIND).await;
+    assert_eq!(
+        node_health,
+        NodeHealth {
+            healthy: true,
+            ready: false,
+            storage: StorageStatus { available: true, header_marker: BlockNumber(20) },
+            sync: Some(SyncStatus { blocks_behind: None }),
+            consensus: Some(ConsensusStatus { height: BlockNumber(15), height_lag: 5 }),
+            network: Some(NetworkStatus { num_connected_peers: 3 }),
+        }
+    );
+
+    *highest_block.write().await =
+        Some(BlockHashAndNumber { block_number: BlockNumber(25), ..Default::default() });
+    let node_health = get_node_health(&storage_status, &sources, READINESS_MAX_BLOCKS_BEHIND).await;
+    assert_eq!(node_health.sync, Some(SyncStatus { blocks_behind: Some(6) }));
+    assert!(node_health.ready);
+
+    // Without peers the node isn't ready.
+    num_connected_peers.store(0, Ordering::Relaxed);
+    assert!(!get_node_health(&storage_status, &sources, READINESS_MAX_BLOCKS_BEHIND).await.ready);
+    num_connected_peers.store(3, Ordering::Relaxed);
+
+    // Consensus that fell behind the storage isn't ready.
+    consensus_height.store(5, Ordering::Relaxed);
+    let node_health = get_node_health(&storage_status, &sources, READINESS_MAX_BLOCKS_BEHIND).await;
+    assert_eq!(
+        node_health.consensus,
+        Some(ConsensusStatus { height: BlockNumber(5), height_lag: 15 })
+    );
+    assert!(!node_health.ready);
+    assert!(node_health.healthy);
+}
+
+#[tokio::test]
+async fn storage_status_is_updated_on_commit() {
+    let ((storage_reader, mut storage_writer), _temp_dir) = test_utils::get_test_storage();
+    let storage_status = Arc::new(RwLock::new(read_storage_status(&storage_reader)));
+    tokio::spawn(update_storage_status(storage_reader, storage_status.clone()));
+    // Let the updater subscribe before committing.
+    tokio::task::yield_now().await;
+
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(BlockNumber(0), &BlockHeader::default())
+        .unwrap()
+        .commit()
+        .unwrap();
+    // Wait for the updater to handle the commit.
+    while storage_status.read().await.header_marker == BlockNumber(0) {
+        tokio::task::yield_now().await;
+    }
+    assert_eq!(
+        *storage_status.read().await,
+        StorageStatus { available: true, header_marker: BlockNumber(1) }
+    );
+}
+
+#[tokio::test]
+async fn not_ready_node_returns_service_unavailable() {
+    let ((storage_reader, _), _temp_dir) = test_utils::get_test_storage();
+    let storage_status = Arc::new(RwLock::new(read_storage_status(&storage_reader)));
+    let app = app(
+        String::from("https://default_url"),
+        storage_reader,
+        TEST_VERSION,
+        serde_json::Value::default(),
+        serde_json::Value::default(),
+        String::new(),
+        None,
+        TEST_PEER_ID.to_string(),
+        HealthSources {
+            num_connected_peers: Some(Arc::new(AtomicUsize::new(0))),
+            ..Default::default()
+        },
+        storage_status,
+        READINESS_MAX_BLOCKS_BEHIND,
+    );
+    let response = request_app(app, "ready").await;
+
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let body: NodeHealth = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body.network, Some(NetworkStatus { num_connected_peers: 0 }));
+}
+
 #[tokio::test]
 async fn without_metrics() {
     let app = setup_app();
@@ -170,6 +318,7 @@ async fn without_metrics() {
 async fn with_metrics() {
     // Creates an app with prometheus handle.
     let ((storage_reader, _), _temp_dir) = test_utils::get_test_storage();
+    let storage_status = Arc::new(RwLock::new(read_storage_status(&storage_reader)));
     let prometheus_handle = PrometheusBuilder::new().install_recorder().unwrap();
     let app = app(
         String::from("https://default_url"),
@@ -180,6 +329,9 @@ async fn with_metrics() {
         String::new(),
         Some(prometheus_handle),
         TEST_PEER_ID.to_string(),
+        HealthSources::default(),
+        storage_status,
+        READINESS_MAX_BLOCKS_BEHIND,
     );
 
     // Register a metric.
@@ -0,0 +1,139 @@
+//! The status of the node's components, as reported by the health and readiness endpoints.
+//!
+//! The node is healthy as long as its storage is available. It's ready once, in addition, the
+//! components that run in it caught up: the sync and consensus aren't too many blocks behind and
+//! the network is connected to peers.
+//!
+//! The endpoints are polled often, so they don't read the storage. Its status is cached and
+//! refreshed by [`update_storage_status`] instead.
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use papyrus_common::BlockHashAndNumber;
+use papyrus_storage::header::HeaderStorageReader;
+use papyrus_storage::StorageReader;
+use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockNumber;
+use tokio::sync::RwLock;
+
+/// Handles to the live state of the node's components. A component that doesn't run in the node
+/// has no handle and isn't reported.
+#[derive(Clone, Debug, Default)]
+pub struct HealthSources {
+    /// The latest block known to the sync.
+    pub highest_block: Option<Arc<RwLock<Option<BlockHashAndNumber>>>>,
+    /// The height consensus is currently working on.
+    pub consensus_height: Option<Arc<AtomicU64>>,
+    /// The number of peers the node is connected to.
+    pub num_connected_peers: Option<Arc<AtomicUsize>>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NodeHealth {
+    pub healthy: bool,
+    pub ready: bool,
+    pub storage: StorageStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sync: Option<SyncStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consensus: Option<ConsensusStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<NetworkStatus>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StorageStatus {
+    /// Whether the storage could be read.
+    pub available: bool,
+    /// The first block number for which the node doesn't have a header.
+    pub header_marker: BlockNumber,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SyncStatus {
+    /// The number of blocks the storage is behind the latest known block. None until the latest
+    /// block is known.
+    pub blocks_behind: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ConsensusStatus {
+    pub height: BlockNumber,
+    /// The number of blocks the storage has beyond the height consensus is working on.
+    pub height_lag: u64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NetworkStatus {
+    pub num_connected_peers: usize,
+}
+
+// Commits of a writer in another process aren't notified, so without commits the status is
+// refreshed periodically.
+const STORAGE_STATUS_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+pub(crate) fn read_storage_status(storage_reader: &StorageReader) -> StorageStatus {
+    match storage_reader.begin_ro_txn().and_then(|txn| txn.get_header_marker()) {
+        Ok(header_marker) => StorageStatus { available: true, header_marker },
+        Err(_) => StorageStatus::default(),
+    }
+}
+
+/// Refreshes `storage_status` whenever the storage commits, and at least every
+/// [`STORAGE_STATUS_REFRESH_INTERVAL`].
+pub(crate) async fn update_storage_status(
+    storage_reader: StorageReader,
+    storage_status: Arc<RwLock<StorageStatus>>,
+) {
+    let mut commits = storage_reader.subscribe_to_commits();
+    loop {
+        // The reader holds the sender, so the channel is never closed.
+        let _ = tokio::time::timeout(STORAGE_STATUS_REFRESH_INTERVAL, commits.changed()).await;
+        *storage_status.write().await = read_storage_status(&storage_reader);
+    }
+}
+
+/// Collects the status of the node's components. The node is ready if none of the sync and
+/// consensus is more than `max_blocks_behind` blocks behind.
+pub(crate) async fn get_node_health(
+    storage_status: &RwLock<StorageStatus>,
+    sources: &HealthSources,
+    max_blocks_behind: u64,
+) -> NodeHealth {
+    let storage = storage_status.read().await.clone();
+
+    let sync = match &sources.highest_block {
+        Some(highest_block) => Some(SyncStatus {
+            blocks_behind: highest_block.read().await.as_ref().map(|highest_block| {
+                highest_block
+                    .block_number
+                    .unchecked_next()
+                    .0
+                    .saturating_sub(storage.header_marker.0)
+            }),
+        }),
+        None => None,
+    };
+    let consensus = sources.consensus_height.as_ref().map(|consensus_height| {
+        let height = consensus_height.load(Ordering::Relaxed);
+        ConsensusStatus {
+            height: BlockNumber(height),
+            height_lag: storage.header_marker.0.saturating_sub(height),
+        }
+    });
+    let network = sources.num_connected_peers.as_ref().map(|num_connected_peers| NetworkStatus {
+        num_connected_peers: num_connected_peers.load(Ordering::Relaxed),
+    });
+
+    let healthy = storage.available;
+    let ready = healthy
+        && sync.as_ref().map_or(true, |sync| {
+            sync.blocks_behind.is_some_and(|blocks_behind| blocks_behind <= max_blocks_behind)
+        })
+        && consensus.as_ref().map_or(true, |consensus| consensus.height_lag <= max_blocks_behind)
+        && network.as_ref().map_or(true, |network| network.num_connected_peers > 0);
+
+    NodeHealth { healthy, ready, storage, sync, consensus, network }
+}
@@ -4,6 +4,7 @@
 
 #[cfg(test)]
 mod gateway_test;
+pub mod health;
 
 use std::collections::{BTreeMap, HashMap};
 use std::fmt::Display;
@@ -18,6 +19,7 @@ use axum::routing::get;
 use axum::{Json, Router};
 use metrics_exporter_prometheus::{BuildError, PrometheusBuilder, PrometheusHandle};
 use metrics_process::Collector;
+use papyrus_common::metrics::CHAIN_ID_LABEL;
 use papyrus_config::converters::{deserialize_optional_map, serialize_optional_map};
 use papyrus_config::dumping::{ser_generated_param, ser_param, SerializeConfig};
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializationType, SerializedParam};
@@ -26,12 +28,22 @@ use papyrus_storage::{DbStats, StorageError, StorageReader};
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
 use serde::{Deserialize, Serialize};
+use starknet_api::core::ChainId;
 use starknet_client::reader::{StarknetFeederGatewayClient, StarknetReader};
 use starknet_client::writer::{StarknetGatewayClient, StarknetWriter};
 use starknet_client::RetryConfig;
+use tokio::sync::RwLock;
 use tracing::{debug, info, instrument};
 use validator::Validate;
 
+use crate::health::{
+    get_node_health,
+    read_storage_status,
+    update_storage_status,
+    HealthSources,
+    StorageStatus,
+};
+
 const MONITORING_PREFIX: &str = "monitoring";
 const PROCESS_METRICS_PREFIX: &str = "papyrus_";
 
@@ -45,6 +57,9 @@ pub struct MonitoringGatewayConfig {
     #[serde(default = "random_secret")]
     pub present_full_config_secret: String,
     pub starknet_url: String,
+    pub readiness_max_blocks_behind: u64,
+    /// Labels all the metrics under [`CHAIN_ID_LABEL`].
+    pub chain_id: ChainId,
 }
 
 fn random_secret() -> String {
@@ -62,6 +77,8 @@ impl Default for MonitoringGatewayConfig {
             // A constant value for testing purposes.
             present_full_config_secret: String::from("qwerty"),
             starknet_url: String::from("https://alpha-mainnet.starknet.io/"),
+            readiness_max_blocks_behind: 10,
+            chain_id: ChainId::Mainnet,
         }
     }
 }
@@ -99,6 +116,19 @@ impl SerializeConfig for MonitoringGatewayConfig {
                 "The URL of a centralized Starknet gateway.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "readiness_max_blocks_behind",
+                &self.readiness_max_blocks_behind,
+                "The maximal number of blocks the sync and consensus may be behind for the node \
+                 to be ready.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "chain_id",
+                &self.chain_id,
+                "The chain to follow. For more details see https://docs.starknet.io/documentation/architecture_and_concepts/Blocks/transactions/#chain-id.",
+                ParamPrivacyInput::Public,
+            ),
         ])
     }
 }
@@ -120,6 +150,7 @@ pub struct MonitoringServer {
     version: &'static str,
     prometheus_handle: Option<PrometheusHandle>,
     own_peer_id: String,
+    health_sources: HealthSources,
 }
 
 impl MonitoringServer {
@@ -130,9 +161,11 @@ impl MonitoringServer {
         storage_reader: StorageReader,
         version: &'static str,
         own_peer_id: String,
+        health_sources: HealthSources,
     ) -> Result<Self, BuildError> {
         let prometheus_handle = if config.collect_metrics {
-            let mut builder = PrometheusBuilder::new();
+            let mut builder = PrometheusBuilder::new()
+                .add_global_label(CHAIN_ID_LABEL, config.chain_id.to_string());
             if let Some(metric_labels) = &config.metric_labels {
                 for (label, value) in metric_labels {
                     builder = builder.add_global_label(label, value);
@@ -150,6 +183,7 @@ impl MonitoringServer {
             version,
             prometheus_handle,
             own_peer_id,
+            health_sources,
         })
     }
 
@@ -162,14 +196,14 @@ impl MonitoringServer {
         skip(self),
         fields(
             version = %self.version,
-            config = %self.config,
-            full_general_config_presentation = %self.full_general_config_presentation,
-            public_general_config_presentation = %self.public_general_config_presentation,
-            present_full_config_secret = %self.config.present_full_config_secret),
+            server_address = %self.config.server_address,
+            public_general_config_presentation = %self.public_general_config_presentation),
         level = "debug")]
     async fn run_server(&self) -> std::result::Result<(), hyper::Error> {
         let server_address = SocketAddr::from_str(&self.config.server_address)
             .expect("Configuration value for monitor server address should be valid");
+        let storage_status = Arc::new(RwLock::new(read_storage_status(&self.storage_reader)));
+        tokio::spawn(update_storage_status(self.storage_reader.clone(), storage_status.clone()));
         let app = app(
             self.config.starknet_url.clone(),
             self.storage_reader.clone(),
@@ -179,6 +213,9 @@ impl MonitoringServer {
             self.config.present_full_config_secret.clone(),
             self.prometheus_handle.clone(),
             self.own_peer_id.clone(),
+            self.health_sources.clone(),
+            storage_status,
+            self.config.readiness_max_blocks_behind,
         );
         debug!("Starting monitoring gateway.");
         axum::Server::bind(&server_address).serve(app.into_make_service()).await
@@ -195,6 +232,9 @@ fn app(
     present_full_config_secret: String,
     prometheus_handle: Option<PrometheusHandle>,
     own_peer_id: String,
+    health_sources: HealthSources,
+    storage_status: Arc<RwLock<StorageStatus>>,
+    readiness_max_blocks_behind: u64,
 ) -> Router {
     let is_ready_retry_config =
         RetryConfig { retry_base_millis: 50, retry_max_delay_millis: 1000, max_retries: 0 };
@@ -213,7 +253,9 @@ fn app(
     );
 
     let db_tables_stats_reader = storage_reader.clone();
-    let mmap_files_stats_reader = storage_reader.clone();
+    let mmap_files_stats_reader = storage_reader;
+    let storage_status_for_ready = storage_status.clone();
+    let health_sources_for_ready = health_sources.clone();
 
     Router::new()
         .route(
@@ -251,13 +293,60 @@ fn app(
             format!("/{MONITORING_PREFIX}/metrics").as_str(),
             get(move || metrics(prometheus_handle)),
         )
+        .route(
+            format!("/{MONITORING_PREFIX}/health").as_str(),
+            get(move || health(storage_status, health_sources, readiness_max_blocks_behind)),
+        )
         .route(
             format!("/{MONITORING_PREFIX}/ready").as_str(),
-            get(move || is_ready(starknet_client, starknet_feeder_client)),
+            get(move || {
+                ready(
+                    storage_status_for_ready,
+                    health_sources_for_ready,
+                    readiness_max_blocks_behind,
+                    starknet_client,
+                    starknet_feeder_client,
+                )
+            }),
         )
         .route(format!("/{MONITORING_PREFIX}/peer_id").as_str(), get(move || async { own_peer_id }))
 }
 
+/// Returns the status of the node's components, with status code 503: service unavailable if the
+/// node isn't healthy.
+#[instrument(skip(storage_status, health_sources), level = "debug")]
+async fn health(
+    storage_status: Arc<RwLock<StorageStatus>>,
+    health_sources: HealthSources,
+    readiness_max_blocks_behind: u64,
+) -> Response {
+    let node_health =
+        get_node_health(&storage_status, &health_sources, readiness_max_blocks_behind).await;
+    let status = if node_health.healthy { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status, Json(node_health)).into_response()
+}
+
+/// Returns status code 200 if the node is ready to serve requests. Otherwise, returns the status of
+/// the node's components with status code 503: service unavailable.
+#[instrument(
+    skip(storage_status, health_sources, starknet_client, starknet_feeder_client),
+    level = "debug"
+)]
+async fn ready<TStarknetWriter: StarknetWriter, TStarknetReader: StarknetReader>(
+    storage_status: Arc<RwLock<StorageStatus>>,
+    health_sources: HealthSources,
+    readiness_max_blocks_behind: u64,
+    starknet_client: Arc<TStarknetWriter>,
+    starknet_feeder_client: Arc<TStarknetReader>,
+) -> Response {
+    let node_health =
+        get_node_health(&storage_status, &health_sources, readiness_max_blocks_behind).await;
+    if !node_health.ready {
+        return (StatusCode::SERVICE_UNAVAILABLE, Json(node_health)).into_response();
+    }
+    is_ready(starknet_client, starknet_feeder_client).await.into_response()
+}
+
 async fn is_ready<TStarknetWriter: StarknetWriter, TStarknetReader: StarknetReader>(
     starknet_client: Arc<TStarknetWriter>,
     starknet_feeder_client: Arc<TStarknetReader>,
@@ -24,6 +24,7 @@ use tokio::sync::Mutex;
 use tokio::time::timeout;
 use void::Void;
 
+use super::identify_impl::IdentifyToOtherBehaviourEvent;
 use super::kad_impl::KadToOtherBehaviourEvent;
 use super::{Behaviour, DiscoveryConfig, RetryConfig, ToOtherBehaviourEvent};
 use crate::mixed_behaviour;
@@ -40,6 +41,8 @@ const CONFIG: DiscoveryConfig = DiscoveryConfig {
         factor: 1,
     },
     heartbeat_interval: Duration::ZERO,
+    rebootstrap_interval: Duration::from_secs(3600),
+    address_book_path: None,
 };
 
 impl Unpin for Behaviour {}
@@ -69,7 +72,7 @@ async fn discovery_outputs_dial_request_on_start_without_query() {
     let bootstrap_peer_id = PeerId::random();
     let bootstrap_peer_address = Multiaddr::empty();
 
-    let mut behaviour = Behaviour::new(CONFIG, bootstrap_peer_id, bootstrap_peer_address);
+    let mut behaviour = Behaviour::new(CONFIG, vec![(bootstrap_peer_id, bootstrap_peer_address)]);
 
     let event = timeout(TIMEOUT, behaviour.next()).await.unwrap().unwrap();
     assert_matches!(
@@ -80,6 +83,79 @@ async fn discovery_outputs_dial_request_on_start_without_query() {
     assert_no_event(&mut behaviour);
 }
 
+#[tokio::test]
+async fn discovery_dials_all_bootstrap_peers() {
+    let bootstrap_peer_ids = [PeerId::random(), PeerId::random()];
+    let bootstrap_peers =
+        bootstrap_peer_ids.iter().map(|peer_id| (*peer_id, Multiaddr::empty())).collect();
+
+    let mut behaviour = Behaviour::new(CONFIG, bootstrap_peers);
+
+    for bootstrap_peer_id in bootstrap_peer_ids {
+        let event = timeout(TIMEOUT, behaviour.next()).await.unwrap().unwrap();
+        assert_matches!(
+            event,
+            ToSwarm::Dial{opts} if opts.get_peer_id() == Some(bootstrap_peer_id)
+        );
+    }
+
+    assert_no_event(&mut behaviour);
+}
+
+#[tokio::test]
+async fn discovery_reports_peers_from_address_book_on_start() {
+    let peer_id = PeerId::random();
+    let address: Multiaddr = "/ip4/127.0.0.1/tcp/10000".parse().unwrap();
+    let address_book_file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(
+        address_book_file.path(),
+        format!("{}\n", address.clone().with_p2p(peer_id).unwrap()),
+    )
+    .unwrap();
+    let mut config = CONFIG;
+    config.address_book_path = Some(address_book_file.path().to_path_buf());
+    let bootstrap_peer_id = PeerId::random();
+
+    let mut behaviour = Behaviour::new(config, vec![(bootstrap_peer_id, Multiaddr::empty())]);
+
+    let event = timeout(TIMEOUT, behaviour.next()).await.unwrap().unwrap();
+    assert_matches!(
+        event,
+        ToSwarm::GenerateEvent(ToOtherBehaviourEvent::FoundListenAddresses {
+                peer_id: event_peer_id,
+                listen_addresses,
+            }
+        ) if event_peer_id == peer_id && listen_addresses == vec![address]
+    );
+    let event = timeout(TIMEOUT, behaviour.next()).await.unwrap().unwrap();
+    assert_matches!(
+        event,
+        ToSwarm::Dial{opts} if opts.get_peer_id() == Some(bootstrap_peer_id)
+    );
+}
+
+#[tokio::test]
+async fn address_book_is_persisted_on_rebootstrap() {
+    let address_book_file = tempfile::NamedTempFile::new().unwrap();
+    let mut config = CONFIG;
+    config.address_book_path = Some(address_book_file.path().to_path_buf());
+    let peer_id = PeerId::random();
+    let address: Multiaddr = "/ip4/127.0.0.1/tcp/10000".parse().unwrap();
+
+    let mut behaviour = Behaviour::new(config.clone(), vec![]);
+    behaviour.on_other_behaviour_event(&mixed_behaviour::ToOtherBehaviourEvent::Identify(
+        IdentifyToOtherBehaviourEvent::FoundListenAddresses {
+            peer_id,
+            listen_addresses: vec![address.clone()],
+        },
+    ));
+    behaviour.rebootstrap();
+    behaviour.address_book_write_task.take().unwrap().await.unwrap();
+
+    let behaviour = Behaviour::new(config, vec![]);
+    assert_eq!(behaviour.peers_to_report, vec![(peer_id, address)]);
+}
+
 async fn check_event_happens_after_given_duration(
     behaviour: &mut Behaviour,
     duration: Duration,
@@ -102,7 +178,7 @@ async fn discovery_redials_on_dial_failure() {
     let bootstrap_peer_id = PeerId::random();
     let bootstrap_peer_address = Multiaddr::empty();
 
-    let mut behaviour = Behaviour::new(CONFIG, bootstrap_peer_id, bootstrap_peer_address);
+    let mut behaviour = Behaviour::new(CONFIG, vec![(bootstrap_peer_id, bootstrap_peer_address)]);
 
     let event = timeout(TIMEOUT, behaviour.next()).await.unwrap().unwrap();
     assert_matches!(
@@ -132,10 +208,10 @@ async fn discovery_redials_when_all_connections_closed() {
     timeout(TIMEOUT, behaviour.next()).await.unwrap();
 
     behaviour.on_swarm_event(FromSwarm::ConnectionClosed(ConnectionClosed {
-        peer_id: behaviour.bootstrap_peer_id(),
+        peer_id: behaviour.bootstrap_peer_id().unwrap(),
         connection_id: ConnectionId::new_unchecked(0),
         endpoint: &ConnectedPoint::Dialer {
-            address: behaviour.bootstrap_peer_address().clone(),
+            address: behaviour.bootstrap_peer_address().unwrap().clone(),
             role_override: Endpoint::Dialer,
         },
         remaining_established: 0,
@@ -144,7 +220,7 @@ async fn discovery_redials_when_all_connections_closed() {
     let event = timeout(TIMEOUT, behaviour.next()).await.unwrap().unwrap();
     assert_matches!(
         event,
-        ToSwarm::Dial{opts} if opts.get_peer_id() == Some(behaviour.bootstrap_peer_id())
+        ToSwarm::Dial{opts} if opts.get_peer_id() == behaviour.bootstrap_peer_id()
     );
 }
 
@@ -156,10 +232,10 @@ async fn discovery_doesnt_redial_when_one_connection_closes() {
     timeout(TIMEOUT, behaviour.next()).await.unwrap();
 
     behaviour.on_swarm_event(FromSwarm::ConnectionEstablished(ConnectionEstablished {
-        peer_id: behaviour.bootstrap_peer_id(),
+        peer_id: behaviour.bootstrap_peer_id().unwrap(),
         connection_id: ConnectionId::new_unchecked(1),
         endpoint: &ConnectedPoint::Dialer {
-            address: behaviour.bootstrap_peer_address().clone(),
+            address: behaviour.bootstrap_peer_address().unwrap().clone(),
             role_override: Endpoint::Dialer,
         },
         failed_addresses: &[],
@@ -167,10 +243,10 @@ async fn discovery_doesnt_redial_when_one_connection_closes() {
     }));
 
     behaviour.on_swarm_event(FromSwarm::ConnectionClosed(ConnectionClosed {
-        peer_id: behaviour.bootstrap_peer_id(),
+        peer_id: behaviour.bootstrap_peer_id().unwrap(),
         connection_id: ConnectionId::new_unchecked(0),
         endpoint: &ConnectedPoint::Dialer {
-            address: behaviour.bootstrap_peer_address().clone(),
+            address: behaviour.bootstrap_peer_address().unwrap().clone(),
             role_override: Endpoint::Dialer,
         },
         remaining_established: 1,
@@ -183,7 +259,8 @@ async fn create_behaviour_and_connect_to_bootstrap_node(config: DiscoveryConfig)
     let bootstrap_peer_id = PeerId::random();
     let bootstrap_peer_address = Multiaddr::empty();
 
-    let mut behaviour = Behaviour::new(config, bootstrap_peer_id, bootstrap_peer_address.clone());
+    let mut behaviour =
+        Behaviour::new(config, vec![(bootstrap_peer_id, bootstrap_peer_address.clone())]);
 
     // Consume the dial event.
     timeout(TIMEOUT, behaviour.next()).await.unwrap();
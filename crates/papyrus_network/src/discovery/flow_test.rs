@@ -27,7 +27,8 @@ impl DiscoveryMixedBehaviour {
     pub fn new(key: Keypair, bootstrap_peer_multiaddr: Option<Multiaddr>) -> Self {
         let mixed_behaviour = MixedBehaviour::new(
             key,
-            bootstrap_peer_multiaddr,
+            bootstrap_peer_multiaddr.map(|address| vec![address]),
+            Default::default(),
             Default::default(),
             ChainId::Mainnet,
             None,
@@ -1,3 +1,15 @@
+//! Peer discovery using the Kademlia DHT.
+//!
+//! The node dials the configured bootstrap peers, adds them to the Kademlia routing table and
+//! periodically runs Kademlia queries for random peer ids in order to find more peers. The
+//! bootstrap peers are redialed whenever the node disconnects from them, and every
+//! `rebootstrap_interval` they're re-added to the routing table in case they were evicted from it.
+//!
+//! The listen addresses of the peers that were found are kept in an address book. If
+//! `address_book_path` is set, the address book is persisted to that file every
+//! `rebootstrap_interval`, and the peers in it are reported to the other behaviours when the node
+//! starts, so that it can connect to the network even if the bootstrap peers are unavailable.
+
 #[cfg(test)]
 mod discovery_test;
 #[cfg(test)]
@@ -5,12 +17,16 @@ mod flow_test;
 pub mod identify_impl;
 pub mod kad_impl;
 
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::task::{ready, Context, Poll, Waker};
 use std::time::Duration;
 
 use futures::future::BoxFuture;
 use futures::{pin_mut, Future, FutureExt};
+use identify_impl::IdentifyToOtherBehaviourEvent;
 use kad_impl::KadToOtherBehaviourEvent;
+use libp2p::core::multiaddr::Protocol;
 use libp2p::core::Endpoint;
 use libp2p::swarm::behaviour::ConnectionEstablished;
 use libp2p::swarm::dial_opts::{DialOpts, PeerCondition};
@@ -27,25 +43,79 @@ use libp2p::swarm::{
     ToSwarm,
 };
 use libp2p::{Multiaddr, PeerId};
+use metrics::gauge;
+use papyrus_common::metrics as papyrus_metrics;
+use papyrus_config::converters::{
+    deserialize_milliseconds_to_duration,
+    deserialize_seconds_to_duration,
+};
+use papyrus_config::dumping::{
+    append_sub_config_name,
+    ser_optional_param,
+    ser_param,
+    SerializeConfig,
+};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
 use tokio_retry::strategy::ExponentialBackoff;
+use tracing::{debug, warn};
 
-use crate::mixed_behaviour;
 use crate::mixed_behaviour::BridgedBehaviour;
+use crate::{mixed_behaviour, peer_manager, NETWORK_COMPONENT};
 
 pub struct Behaviour {
     config: DiscoveryConfig,
     // TODO(shahak): Consider running several queries in parallel
     is_query_running: bool,
-    bootstrap_peer_address: Multiaddr,
-    bootstrap_peer_id: PeerId,
-    is_dialing_to_bootstrap_peer: bool,
-    // This needs to be boxed to allow polling it from a &mut.
-    sleep_future_for_dialing_bootstrap_peer: Option<BoxFuture<'static, ()>>,
-    is_connected_to_bootstrap_peer: bool,
-    is_bootstrap_in_kad_routing_table: bool,
+    bootstrap_peers: Vec<BootstrapPeer>,
     wakers_waiting_for_query_to_finish: Vec<Waker>,
-    bootstrap_dial_retry_strategy: ExponentialBackoff,
     query_sleep_future: Option<BoxFuture<'static, ()>>,
+    rebootstrap_sleep_future: Option<BoxFuture<'static, ()>>,
+    address_book: HashMap<PeerId, Multiaddr>,
+    // The task that writes the address book to its file. The file is written on a blocking task
+    // so that the swarm isn't blocked on the file system.
+    address_book_write_task: Option<JoinHandle<()>>,
+    // Peers that were loaded from the address book file and weren't reported yet.
+    peers_to_report: Vec<(PeerId, Multiaddr)>,
+}
+
+struct BootstrapPeer {
+    peer_id: PeerId,
+    address: Multiaddr,
+    is_dialing: bool,
+    // This needs to be boxed to allow polling it from a &mut.
+    sleep_future_for_dialing: Option<BoxFuture<'static, ()>>,
+    is_connected: bool,
+    is_in_kad_routing_table: bool,
+    dial_retry_strategy: ExponentialBackoff,
+}
+
+impl BootstrapPeer {
+    fn on_dial_failure(&mut self) {
+        self.is_dialing = false;
+        // For the case that the reason for failure is consistent (e.g the bootstrap peer is down),
+        // we sleep before redialing.
+        self.sleep_future_for_dialing = Some(
+            tokio::time::sleep(
+                self.dial_retry_strategy
+                    .next()
+                    .expect("Dial sleep strategy ended even though it's an infinite iterator."),
+            )
+            .boxed(),
+        );
+    }
+
+    fn on_connection_established(&mut self, retry_config: &RetryConfig) {
+        self.is_connected = true;
+        self.is_dialing = false;
+        self.dial_retry_strategy = retry_config.strategy();
+    }
+
+    fn on_all_connections_closed(&mut self) {
+        self.is_connected = false;
+        self.is_dialing = false;
+    }
 }
 
 #[derive(Debug)]
@@ -80,39 +150,28 @@ impl NetworkBehaviour for Behaviour {
 
     fn on_swarm_event(&mut self, event: FromSwarm<'_>) {
         match event {
-            FromSwarm::DialFailure(DialFailure { peer_id: Some(peer_id), .. })
-                if peer_id == self.bootstrap_peer_id =>
-            {
-                self.is_dialing_to_bootstrap_peer = false;
-                // For the case that the reason for failure is consistent (e.g the bootstrap peer
-                // is down), we sleep before redialing
-                // TODO(shahak): Consider increasing the time after each failure, the same way we
-                // do in starknet client.
-                self.sleep_future_for_dialing_bootstrap_peer = Some(
-                    tokio::time::sleep(self.bootstrap_dial_retry_strategy.next().expect(
-                        "Dial sleep strategy ended even though it's an infinite iterator.",
-                    ))
-                    .boxed(),
-                );
+            FromSwarm::DialFailure(DialFailure { peer_id: Some(peer_id), .. }) => {
+                if let Some(bootstrap_peer) = self.bootstrap_peer_mut(peer_id) {
+                    bootstrap_peer.on_dial_failure();
+                }
             }
-            FromSwarm::ConnectionEstablished(ConnectionEstablished { peer_id, .. })
-                if peer_id == self.bootstrap_peer_id =>
-            {
-                self.is_connected_to_bootstrap_peer = true;
-                self.is_dialing_to_bootstrap_peer = false;
-                self.bootstrap_dial_retry_strategy =
-                    self.config.bootstrap_dial_retry_config.strategy();
+            FromSwarm::ConnectionEstablished(ConnectionEstablished { peer_id, .. }) => {
+                let retry_config = self.config.bootstrap_dial_retry_config;
+                if let Some(bootstrap_peer) = self.bootstrap_peer_mut(peer_id) {
+                    bootstrap_peer.on_connection_established(&retry_config);
+                }
             }
             FromSwarm::ConnectionClosed(ConnectionClosed {
                 peer_id,
                 remaining_established,
                 ..
-            }) if peer_id == self.bootstrap_peer_id && remaining_established == 0 => {
-                self.is_connected_to_bootstrap_peer = false;
-                self.is_dialing_to_bootstrap_peer = false;
+            }) if remaining_established == 0 => {
+                if let Some(bootstrap_peer) = self.bootstrap_peer_mut(peer_id) {
+                    bootstrap_peer.on_all_connections_closed();
+                }
             }
             FromSwarm::AddressChange(AddressChange { peer_id, .. })
-                if peer_id == self.bootstrap_peer_id =>
+                if self.bootstrap_peers.iter().any(|peer| peer.peer_id == peer_id) =>
             {
                 todo!();
             }
@@ -133,16 +192,42 @@ impl NetworkBehaviour for Behaviour {
         cx: &mut Context<'_>,
     ) -> Poll<ToSwarm<Self::ToSwarm, <Self::ConnectionHandler as ConnectionHandler>::FromBehaviour>>
     {
-        if !self.is_dialing_to_bootstrap_peer && !self.is_connected_to_bootstrap_peer {
-            if let Some(sleep_future) = &mut self.sleep_future_for_dialing_bootstrap_peer {
-                pin_mut!(sleep_future);
-                ready!(sleep_future.poll(cx));
+        if let Some((peer_id, address)) = self.peers_to_report.pop() {
+            return Poll::Ready(ToSwarm::GenerateEvent(
+                ToOtherBehaviourEvent::FoundListenAddresses {
+                    peer_id,
+                    listen_addresses: vec![address],
+                },
+            ));
+        }
+
+        let rebootstrap_interval = self.config.rebootstrap_interval;
+        let rebootstrap_sleep_future = self
+            .rebootstrap_sleep_future
+            .get_or_insert_with(|| tokio::time::sleep(rebootstrap_interval).boxed());
+        if rebootstrap_sleep_future.poll_unpin(cx).is_ready() {
+            let mut next_rebootstrap_sleep_future =
+                tokio::time::sleep(rebootstrap_interval).boxed();
+            // Polling the new sleep so that we'll be woken up when it ends.
+            let _ = next_rebootstrap_sleep_future.poll_unpin(cx);
+            self.rebootstrap_sleep_future = Some(next_rebootstrap_sleep_future);
+            self.rebootstrap();
+        }
+
+        for bootstrap_peer in &mut self.bootstrap_peers {
+            if bootstrap_peer.is_dialing || bootstrap_peer.is_connected {
+                continue;
+            }
+            if let Some(sleep_future) = &mut bootstrap_peer.sleep_future_for_dialing {
+                if sleep_future.poll_unpin(cx).is_pending() {
+                    continue;
+                }
             }
-            self.is_dialing_to_bootstrap_peer = true;
-            self.sleep_future_for_dialing_bootstrap_peer = None;
+            bootstrap_peer.is_dialing = true;
+            bootstrap_peer.sleep_future_for_dialing = None;
             return Poll::Ready(ToSwarm::Dial {
-                opts: DialOpts::peer_id(self.bootstrap_peer_id)
-                    .addresses(vec![self.bootstrap_peer_address.clone()])
+                opts: DialOpts::peer_id(bootstrap_peer.peer_id)
+                    .addresses(vec![bootstrap_peer.address.clone()])
                     // The peer manager might also be dialing to the bootstrap node.
                     .condition(PeerCondition::DisconnectedAndNotDialing)
                     .build(),
@@ -152,15 +237,17 @@ impl NetworkBehaviour for Behaviour {
         // If we're not connected to any node, then each Kademlia query we make will automatically
         // return without any peers. Running queries in that mode will add unnecessary overload to
         // the swarm.
-        if !self.is_connected_to_bootstrap_peer {
+        if !self.bootstrap_peers.iter().any(|bootstrap_peer| bootstrap_peer.is_connected) {
             return Poll::Pending;
         }
-        if !self.is_bootstrap_in_kad_routing_table {
-            self.is_bootstrap_in_kad_routing_table = true;
+        if let Some(bootstrap_peer) = self.bootstrap_peers.iter_mut().find(|bootstrap_peer| {
+            bootstrap_peer.is_connected && !bootstrap_peer.is_in_kad_routing_table
+        }) {
+            bootstrap_peer.is_in_kad_routing_table = true;
             return Poll::Ready(ToSwarm::GenerateEvent(
                 ToOtherBehaviourEvent::FoundListenAddresses {
-                    peer_id: self.bootstrap_peer_id,
-                    listen_addresses: vec![self.bootstrap_peer_address.clone()],
+                    peer_id: bootstrap_peer.peer_id,
+                    listen_addresses: vec![bootstrap_peer.address.clone()],
                 },
             ));
         }
@@ -181,10 +268,14 @@ impl NetworkBehaviour for Behaviour {
     }
 }
 
-// TODO(alon): add to NetworkConfig
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct DiscoveryConfig {
     pub bootstrap_dial_retry_config: RetryConfig,
+    #[serde(deserialize_with = "deserialize_milliseconds_to_duration")]
     pub heartbeat_interval: Duration,
+    #[serde(deserialize_with = "deserialize_seconds_to_duration")]
+    pub rebootstrap_interval: Duration,
+    pub address_book_path: Option<PathBuf>,
 }
 
 impl Default for DiscoveryConfig {
@@ -192,13 +283,50 @@ impl Default for DiscoveryConfig {
         Self {
             bootstrap_dial_retry_config: RetryConfig::default(),
             heartbeat_interval: Duration::from_millis(100),
+            rebootstrap_interval: Duration::from_secs(300),
+            address_book_path: None,
         }
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+impl SerializeConfig for DiscoveryConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        let mut config = BTreeMap::from_iter([
+            ser_param(
+                "heartbeat_interval",
+                &self.heartbeat_interval.as_millis(),
+                "The interval in milliseconds between the end of a Kademlia query and the start \
+                 of the next one.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "rebootstrap_interval",
+                &self.rebootstrap_interval.as_secs(),
+                "The interval in seconds in which the bootstrap peers are re-added to the routing \
+                 table and the address book is persisted.",
+                ParamPrivacyInput::Public,
+            ),
+        ]);
+        config.extend(ser_optional_param(
+            &self.address_book_path,
+            PathBuf::from("./data/address_book"),
+            "address_book_path",
+            "A file in which the addresses of the discovered peers are persisted, in order to \
+             connect to them when the node restarts.",
+            ParamPrivacyInput::Public,
+        ));
+        config.extend(append_sub_config_name(
+            self.bootstrap_dial_retry_config.dump(),
+            "bootstrap_dial_retry_config",
+        ));
+        config
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct RetryConfig {
     pub base_delay_millis: u64,
+    #[serde(deserialize_with = "deserialize_milliseconds_to_duration")]
     pub max_delay: Duration,
     pub factor: u64,
 }
@@ -209,6 +337,31 @@ impl Default for RetryConfig {
     }
 }
 
+impl SerializeConfig for RetryConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "base_delay_millis",
+                &self.base_delay_millis,
+                "The base delay in milliseconds for the exponential backoff strategy.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_delay",
+                &self.max_delay.as_millis(),
+                "The maximum delay in milliseconds for the exponential backoff strategy.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "factor",
+                &self.factor,
+                "The factor for the exponential backoff strategy.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
 impl RetryConfig {
     fn strategy(&self) -> ExponentialBackoff {
         ExponentialBackoff::from_millis(self.base_delay_millis)
@@ -218,40 +371,122 @@ impl RetryConfig {
 }
 
 impl Behaviour {
-    // TODO(shahak): Add support to discovery from multiple bootstrap nodes.
     // TODO(shahak): Add support to multiple addresses for bootstrap node.
-    pub fn new(
-        config: DiscoveryConfig,
-        bootstrap_peer_id: PeerId,
-        bootstrap_peer_address: Multiaddr,
-    ) -> Self {
-        let bootstrap_dial_retry_strategy = config.bootstrap_dial_retry_config.strategy();
+    pub fn new(config: DiscoveryConfig, bootstrap_peers: Vec<(PeerId, Multiaddr)>) -> Self {
+        let bootstrap_peers = bootstrap_peers
+            .into_iter()
+            .map(|(peer_id, address)| BootstrapPeer {
+                peer_id,
+                address,
+                is_dialing: false,
+                sleep_future_for_dialing: None,
+                is_connected: false,
+                is_in_kad_routing_table: false,
+                dial_retry_strategy: config.bootstrap_dial_retry_config.strategy(),
+            })
+            .collect();
+        let address_book =
+            config.address_book_path.as_deref().map(load_address_book).unwrap_or_default();
+        gauge!(
+            papyrus_metrics::PAPYRUS_NUM_DISCOVERED_PEERS,
+            address_book.len() as f64,
+            papyrus_metrics::COMPONENT_LABEL => NETWORK_COMPONENT
+        );
+        let peers_to_report =
+            address_book.iter().map(|(peer_id, address)| (*peer_id, address.clone())).collect();
         Self {
             config,
             is_query_running: false,
-            bootstrap_peer_id,
-            bootstrap_peer_address,
-            is_dialing_to_bootstrap_peer: false,
-            sleep_future_for_dialing_bootstrap_peer: None,
-            is_connected_to_bootstrap_peer: false,
-            is_bootstrap_in_kad_routing_table: false,
+            bootstrap_peers,
             wakers_waiting_for_query_to_finish: Vec::new(),
-            bootstrap_dial_retry_strategy,
             query_sleep_future: None,
+            rebootstrap_sleep_future: None,
+            address_book,
+            address_book_write_task: None,
+            peers_to_report,
         }
     }
 
     #[cfg(test)]
-    pub fn bootstrap_peer_id(&self) -> PeerId {
-        self.bootstrap_peer_id
+    pub fn bootstrap_peer_id(&self) -> Option<PeerId> {
+        self.bootstrap_peers.first().map(|bootstrap_peer| bootstrap_peer.peer_id)
     }
 
     #[cfg(test)]
-    pub fn bootstrap_peer_address(&self) -> &Multiaddr {
-        &self.bootstrap_peer_address
+    pub fn bootstrap_peer_address(&self) -> Option<&Multiaddr> {
+        self.bootstrap_peers.first().map(|bootstrap_peer| &bootstrap_peer.address)
+    }
+
+    fn bootstrap_peer_mut(&mut self, peer_id: PeerId) -> Option<&mut BootstrapPeer> {
+        self.bootstrap_peers.iter_mut().find(|bootstrap_peer| bootstrap_peer.peer_id == peer_id)
+    }
+
+    // Re-adds the connected bootstrap peers to the routing table in case they were evicted from
+    // it, redials the disconnected ones without waiting for the retry delay and persists the
+    // address book.
+    fn rebootstrap(&mut self) {
+        debug!("Re-bootstrapping peer discovery.");
+        for bootstrap_peer in &mut self.bootstrap_peers {
+            if bootstrap_peer.is_connected {
+                bootstrap_peer.is_in_kad_routing_table = false;
+            } else {
+                bootstrap_peer.sleep_future_for_dialing = None;
+            }
+        }
+        self.save_address_book();
+    }
+
+    fn save_address_book(&mut self) {
+        let Some(path) = self.config.address_book_path.clone() else {
+            return;
+        };
+        // The address book is written again on the next rebootstrap, so there's no need to queue
+        // writes behind a slow one.
+        if self.address_book_write_task.as_ref().is_some_and(|task| !task.is_finished()) {
+            warn!("Skipping the write of the address book since the previous one didn't finish.");
+            return;
+        }
+        let contents = self
+            .address_book
+            .iter()
+            .filter_map(|(peer_id, address)| address.clone().with_p2p(*peer_id).ok())
+            .map(|address| format!("{address}\n"))
+            .collect::<String>();
+        self.address_book_write_task = Some(tokio::task::spawn_blocking(move || {
+            if let Err(error) = std::fs::write(&path, contents) {
+                warn!("Failed to write the address book to {path:?}: {error}");
+            }
+        }));
     }
 }
 
+// Reads the address book from the given file, in which each line is the multiaddress of a peer,
+// ending with its peer id.
+fn load_address_book(path: &Path) -> HashMap<PeerId, Multiaddr> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) => {
+            if error.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to read the address book from {path:?}: {error}");
+            }
+            return HashMap::new();
+        }
+    };
+    let mut address_book = HashMap::new();
+    for line in contents.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let Ok(mut address) = line.parse::<Multiaddr>() else {
+            warn!("Ignoring an invalid multiaddress in the address book: {line}");
+            continue;
+        };
+        let Some(Protocol::P2p(peer_id)) = address.pop() else {
+            warn!("Ignoring a multiaddress without a peer id in the address book: {line}");
+            continue;
+        };
+        address_book.insert(peer_id, address);
+    }
+    address_book
+}
+
 impl From<ToOtherBehaviourEvent> for mixed_behaviour::Event {
     fn from(event: ToOtherBehaviourEvent) -> Self {
         mixed_behaviour::Event::ToOtherBehaviourEvent(
@@ -262,15 +497,45 @@ impl From<ToOtherBehaviourEvent> for mixed_behaviour::Event {
 
 impl BridgedBehaviour for Behaviour {
     fn on_other_behaviour_event(&mut self, event: &mixed_behaviour::ToOtherBehaviourEvent) {
-        let mixed_behaviour::ToOtherBehaviourEvent::Kad(KadToOtherBehaviourEvent::KadQueryFinished) =
-            event
-        else {
-            return;
-        };
-        for waker in self.wakers_waiting_for_query_to_finish.drain(..) {
-            waker.wake();
+        match event {
+            mixed_behaviour::ToOtherBehaviourEvent::Kad(
+                KadToOtherBehaviourEvent::KadQueryFinished,
+            ) => {
+                for waker in self.wakers_waiting_for_query_to_finish.drain(..) {
+                    waker.wake();
+                }
+                self.query_sleep_future =
+                    Some(tokio::time::sleep(self.config.heartbeat_interval).boxed());
+                self.is_query_running = false;
+            }
+            mixed_behaviour::ToOtherBehaviourEvent::Identify(
+                IdentifyToOtherBehaviourEvent::FoundListenAddresses { peer_id, listen_addresses },
+            )
+            | mixed_behaviour::ToOtherBehaviourEvent::Discovery(
+                ToOtherBehaviourEvent::FoundListenAddresses { peer_id, listen_addresses },
+            ) => {
+                let Some(address) = listen_addresses.first() else {
+                    return;
+                };
+                self.address_book.insert(*peer_id, address.clone());
+                gauge!(
+                    papyrus_metrics::PAPYRUS_NUM_DISCOVERED_PEERS,
+                    self.address_book.len() as f64,
+                    papyrus_metrics::COMPONENT_LABEL => NETWORK_COMPONENT
+                );
+            }
+            mixed_behaviour::ToOtherBehaviourEvent::PeerManager(
+                peer_manager::ToOtherBehaviourEvent::PeerBlacklisted { peer_id },
+            ) => {
+                if self.address_book.remove(peer_id).is_some() {
+                    gauge!(
+                        papyrus_metrics::PAPYRUS_NUM_DISCOVERED_PEERS,
+                        self.address_book.len() as f64,
+                        papyrus_metrics::COMPONENT_LABEL => NETWORK_COMPONENT
+                    );
+                }
+            }
+            _ => {}
         }
-        self.query_sleep_future = Some(tokio::time::sleep(self.config.heartbeat_interval).boxed());
-        self.is_query_running = false;
     }
 }
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::time::Duration;
 
 use futures::{FutureExt, SinkExt, StreamExt};
@@ -7,6 +8,7 @@ use libp2p::{Multiaddr, Swarm};
 use libp2p_swarm_test::SwarmExt;
 use starknet_api::core::ChainId;
 
+use crate::discovery::DiscoveryConfig;
 use crate::gossipsub_impl::Topic;
 use crate::mixed_behaviour::MixedBehaviour;
 use crate::network_manager::GenericNetworkManager;
@@ -19,7 +21,8 @@ async fn create_swarm(bootstrap_peer_multiaddr: Option<Multiaddr>) -> Swarm<Mixe
     let mut swarm = Swarm::new_ephemeral(|keypair| {
         MixedBehaviour::new(
             keypair.clone(),
-            bootstrap_peer_multiaddr,
+            bootstrap_peer_multiaddr.map(|address| vec![address]),
+            DiscoveryConfig::default(),
             sqmr::Config::default(),
             ChainId::Mainnet,
             None,
@@ -45,7 +48,7 @@ async fn create_swarm(bootstrap_peer_multiaddr: Option<Multiaddr>) -> Swarm<Mixe
 fn create_network_manager(
     swarm: Swarm<MixedBehaviour>,
 ) -> GenericNetworkManager<Swarm<MixedBehaviour>> {
-    GenericNetworkManager::generic_new(swarm, None)
+    GenericNetworkManager::generic_new(swarm, None, &HashMap::new())
 }
 
 const BUFFER_SIZE: usize = 100;
@@ -3,7 +3,7 @@
 ///
 /// [`Starknet p2p specs`]: https://github.com/starknet-io/starknet-p2p-specs/
 mod bin_utils;
-mod discovery;
+pub mod discovery;
 #[cfg(test)]
 mod e2e_broadcast_test;
 pub mod gossipsub_impl;
@@ -15,7 +15,7 @@ mod sqmr;
 mod test_utils;
 mod utils;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 
 use libp2p::Multiaddr;
@@ -24,13 +24,25 @@ use papyrus_config::converters::{
     deserialize_seconds_to_duration,
     serialize_optional_vec_u8,
 };
-use papyrus_config::dumping::{ser_optional_param, ser_param, SerializeConfig};
+use papyrus_config::dumping::{
+    append_sub_config_name,
+    ser_optional_param,
+    ser_param,
+    SerializeConfig,
+};
 use papyrus_config::validators::validate_vec_u256;
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
-use serde::{Deserialize, Serialize};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize};
+pub use sqmr::MAX_MESSAGE_SIZE;
 use starknet_api::core::ChainId;
 use validator::Validate;
 
+use crate::discovery::DiscoveryConfig;
+
+// The component label of the metrics of the network.
+pub(crate) const NETWORK_COMPONENT: &str = "network";
+
 // TODO: add peer manager config to the network config
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Validate)]
 pub struct NetworkConfig {
@@ -40,12 +52,17 @@ pub struct NetworkConfig {
     pub session_timeout: Duration,
     #[serde(deserialize_with = "deserialize_seconds_to_duration")]
     pub idle_connection_timeout: Duration,
-    pub bootstrap_peer_multiaddr: Option<Multiaddr>,
+    #[serde(deserialize_with = "deserialize_optional_multiaddrs")]
+    pub bootstrap_peer_multiaddr: Option<Vec<Multiaddr>>,
     #[validate(custom = "validate_vec_u256")]
     #[serde(deserialize_with = "deserialize_optional_vec_u8")]
     pub(crate) secret_key: Option<Vec<u8>>,
     pub advertised_multiaddr: Option<Multiaddr>,
     pub chain_id: ChainId,
+    pub discovery_config: DiscoveryConfig,
+    pub compression_threshold: Option<usize>,
+    #[serde(deserialize_with = "deserialize_bandwidth_caps")]
+    pub bandwidth_caps: HashMap<String, u64>,
 }
 
 impl SerializeConfig for NetworkConfig {
@@ -82,12 +99,24 @@ impl SerializeConfig for NetworkConfig {
                 "The chain to follow. For more details see https://docs.starknet.io/documentation/architecture_and_concepts/Blocks/transactions/#chain-id.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "bandwidth_caps",
+                &serialize_bandwidth_caps(&self.bandwidth_caps),
+                "Comma separated caps on the traffic of sqmr protocols and gossipsub topics, each \
+                 of the form <protocol or topic>=<bytes per second>. When the traffic of a \
+                 protocol exceeds its cap, new inbound sessions of it are closed without a \
+                 response, and when the traffic of a topic exceeds its cap, received messages of \
+                 it are dropped.",
+                ParamPrivacyInput::Public,
+            ),
         ]);
         config.extend(ser_optional_param(
-            &self.bootstrap_peer_multiaddr,
-            Multiaddr::empty(),
+            &self.bootstrap_peer_multiaddr.as_ref().map(|addresses| {
+                addresses.iter().map(Multiaddr::to_string).collect::<Vec<_>>().join(",")
+            }),
+            String::new(),
             "bootstrap_peer_multiaddr",
-            "The multiaddress of the peer node. It should include the peer's id. For more info: https://docs.libp2p.io/concepts/fundamentals/peers/",
+            "Comma separated multiaddresses of the bootstrap peers. Each of them should include the peer's id. For more info: https://docs.libp2p.io/concepts/fundamentals/peers/",
             ParamPrivacyInput::Public,
         ));
         config.extend([ser_param(
@@ -98,7 +127,7 @@ impl SerializeConfig for NetworkConfig {
             ParamPrivacyInput::Private,
         )]);
         config.extend(ser_optional_param(
-            &self.bootstrap_peer_multiaddr,
+            &self.advertised_multiaddr,
             Multiaddr::empty(),
             "advertised_multiaddr",
             "The external address other peers see this node. If this is set, the node will not \
@@ -106,6 +135,15 @@ impl SerializeConfig for NetworkConfig {
              instead",
             ParamPrivacyInput::Public,
         ));
+        config.extend(ser_optional_param(
+            &self.compression_threshold,
+            1024,
+            "compression_threshold",
+            "The minimal size in bytes of a query or a response to compress with zstd. Sessions \
+             are compressed only if both peers set this value.",
+            ParamPrivacyInput::Public,
+        ));
+        config.extend(append_sub_config_name(self.discovery_config.dump(), "discovery_config"));
         config
     }
 }
@@ -121,6 +159,63 @@ impl Default for NetworkConfig {
             secret_key: None,
             advertised_multiaddr: None,
             chain_id: ChainId::Mainnet,
+            discovery_config: DiscoveryConfig::default(),
+            compression_threshold: Some(1024),
+            bandwidth_caps: HashMap::new(),
         }
     }
 }
+
+// Deserializes a comma separated list of multiaddresses. An empty string is deserialized as None.
+fn deserialize_optional_multiaddrs<'de, D>(de: D) -> Result<Option<Vec<Multiaddr>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(raw_str) = Option::<String>::deserialize(de)? else {
+        return Ok(None);
+    };
+    if raw_str.is_empty() {
+        return Ok(None);
+    }
+    raw_str
+        .split(',')
+        .map(|address| address.trim().parse::<Multiaddr>().map_err(D::Error::custom))
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+// Serializes the bandwidth caps as a comma separated list of <name>=<bytes per second>, sorted by
+// name.
+fn serialize_bandwidth_caps(bandwidth_caps: &HashMap<String, u64>) -> String {
+    let mut bandwidth_caps = bandwidth_caps
+        .iter()
+        .map(|(name, bytes_per_second)| format!("{name}={bytes_per_second}"))
+        .collect::<Vec<_>>();
+    bandwidth_caps.sort();
+    bandwidth_caps.join(",")
+}
+
+// Deserializes a comma separated list of <name>=<bytes per second>.
+fn deserialize_bandwidth_caps<'de, D>(de: D) -> Result<HashMap<String, u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw_str = String::deserialize(de)?;
+    if raw_str.is_empty() {
+        return Ok(HashMap::new());
+    }
+    raw_str
+        .split(',')
+        .map(|bandwidth_cap| {
+            let (name, bytes_per_second) =
+                bandwidth_cap.trim().rsplit_once('=').ok_or_else(|| {
+                    D::Error::custom(format!(
+                        "Bandwidth cap '{bandwidth_cap}' should be of the form <name>=<bytes per \
+                         second>."
+                    ))
+                })?;
+            let bytes_per_second = bytes_per_second.parse::<u64>().map_err(D::Error::custom)?;
+            Ok((name.to_owned(), bytes_per_second))
+        })
+        .collect()
+}
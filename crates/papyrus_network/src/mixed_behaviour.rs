@@ -57,10 +57,11 @@ pub trait BridgedBehaviour {
 
 impl MixedBehaviour {
     // TODO: get config details from network manager config
-    /// Panics if bootstrap_peer_multiaddr doesn't have a peer id.
+    /// Panics if one of the bootstrap peer multiaddresses doesn't have a peer id.
     pub fn new(
         keypair: Keypair,
-        bootstrap_peer_multiaddr: Option<Multiaddr>,
+        bootstrap_peer_multiaddrs: Option<Vec<Multiaddr>>,
+        discovery_config: DiscoveryConfig,
         streamed_bytes_config: sqmr::Config,
         chain_id: ChainId,
         node_version: Option<String>,
@@ -74,15 +75,19 @@ impl MixedBehaviour {
         ]);
         Self {
             peer_manager: peer_manager::PeerManager::new(PeerManagerConfig::default()),
-            discovery: bootstrap_peer_multiaddr
-                .map(|bootstrap_peer_multiaddr| {
-                    discovery::Behaviour::new(
-                        DiscoveryConfig::default(),
-                        DialOpts::from(bootstrap_peer_multiaddr.clone())
-                            .get_peer_id()
-                            .expect("bootstrap_peer_multiaddr doesn't have a peer id"),
-                        bootstrap_peer_multiaddr.clone(),
-                    )
+            discovery: bootstrap_peer_multiaddrs
+                .map(|bootstrap_peer_multiaddrs| {
+                    let bootstrap_peers = bootstrap_peer_multiaddrs
+                        .into_iter()
+                        .map(|bootstrap_peer_multiaddr| {
+                            let bootstrap_peer_id =
+                                DialOpts::from(bootstrap_peer_multiaddr.clone())
+                                    .get_peer_id()
+                                    .expect("bootstrap_peer_multiaddr doesn't have a peer id");
+                            (bootstrap_peer_id, bootstrap_peer_multiaddr)
+                        })
+                        .collect();
+                    discovery::Behaviour::new(discovery_config, bootstrap_peers)
                 })
                 .into(),
             identify: match node_version {
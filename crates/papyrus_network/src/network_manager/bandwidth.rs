@@ -0,0 +1,126 @@
+//! Accounting of the traffic of each sqmr protocol and gossipsub topic.
+//!
+//! All the traffic is reported to metrics. Protocols and topics with a configured cap (in bytes per
+//! second) also have a token bucket that holds up to a second of traffic. Traffic we can't refuse
+//! (e.g. responses to sessions we already accepted) is always consumed from the bucket, even into
+//! a deficit, while new inbound traffic (new sessions and broadcasted messages) is shed when the
+//! bucket doesn't have enough tokens for it.
+
+#[cfg(test)]
+#[path = "bandwidth_test.rs"]
+mod bandwidth_test;
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use metrics::{counter, increment_counter};
+use papyrus_common::metrics as papyrus_metrics;
+
+use crate::NETWORK_COMPONENT;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum TrafficDirection {
+    Inbound,
+    Outbound,
+}
+
+impl TrafficDirection {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TrafficDirection::Inbound => "inbound",
+            TrafficDirection::Outbound => "outbound",
+        }
+    }
+}
+
+pub(crate) struct BandwidthMeter {
+    // Keyed by the name of the protocol or topic.
+    buckets: HashMap<String, TokenBucket>,
+}
+
+impl BandwidthMeter {
+    pub fn new(caps: &HashMap<String, u64>) -> Self {
+        let now = Instant::now();
+        Self {
+            buckets: caps
+                .iter()
+                .map(|(name, bytes_per_second)| {
+                    (name.clone(), TokenBucket::new(*bytes_per_second, now))
+                })
+                .collect(),
+        }
+    }
+
+    /// Records traffic that can't be shed.
+    pub fn record(&mut self, name: &str, direction: TrafficDirection, n_bytes: usize) {
+        report_traffic(name, direction, n_bytes);
+        if let Some(bucket) = self.buckets.get_mut(name) {
+            bucket.consume(n_bytes as u64, Instant::now());
+        }
+    }
+
+    /// Records inbound traffic if it's within the cap of its protocol or topic. Returns false if
+    /// the traffic should be shed.
+    pub fn try_record_inbound(&mut self, name: &str, n_bytes: usize) -> bool {
+        if let Some(bucket) = self.buckets.get_mut(name) {
+            if !bucket.try_consume(n_bytes as u64, Instant::now()) {
+                increment_counter!(
+                    papyrus_metrics::PAPYRUS_NETWORK_SHED_MESSAGES,
+                    papyrus_metrics::COMPONENT_LABEL => NETWORK_COMPONENT,
+                    "protocol" => name.to_owned()
+                );
+                return false;
+            }
+        }
+        report_traffic(name, TrafficDirection::Inbound, n_bytes);
+        true
+    }
+}
+
+fn report_traffic(name: &str, direction: TrafficDirection, n_bytes: usize) {
+    counter!(
+        papyrus_metrics::PAPYRUS_NETWORK_TRAFFIC_BYTES,
+        n_bytes as u64,
+        papyrus_metrics::COMPONENT_LABEL => NETWORK_COMPONENT,
+        "protocol" => name.to_owned(),
+        "direction" => direction.as_str()
+    );
+}
+
+pub(crate) struct TokenBucket {
+    bytes_per_second: u64,
+    // Negative when traffic that can't be shed exceeded the cap.
+    tokens: i128,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(bytes_per_second: u64, now: Instant) -> Self {
+        Self { bytes_per_second, tokens: bytes_per_second.into(), last_refill: now }
+    }
+
+    pub fn consume(&mut self, n_bytes: u64, now: Instant) {
+        self.refill(now);
+        self.tokens -= i128::from(n_bytes);
+    }
+
+    pub fn try_consume(&mut self, n_bytes: u64, now: Instant) -> bool {
+        self.refill(now);
+        if self.tokens < i128::from(n_bytes) {
+            return false;
+        }
+        self.tokens -= i128::from(n_bytes);
+        true
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        let refilled_tokens =
+            elapsed.as_nanos() * u128::from(self.bytes_per_second) / 1_000_000_000;
+        if refilled_tokens == 0 {
+            return;
+        }
+        self.tokens = (self.tokens + refilled_tokens as i128).min(self.bytes_per_second.into());
+        self.last_refill = now;
+    }
+}
@@ -0,0 +1,44 @@
+use std::time::{Duration, Instant};
+
+use super::TokenBucket;
+
+const BYTES_PER_SECOND: u64 = 100;
+
+#[test]
+fn try_consume_within_cap() {
+    let now = Instant::now();
+    let mut bucket = TokenBucket::new(BYTES_PER_SECOND, now);
+    assert!(bucket.try_consume(60, now));
+    assert!(bucket.try_consume(40, now));
+    assert!(!bucket.try_consume(1, now));
+}
+
+#[test]
+fn tokens_are_refilled_up_to_the_cap() {
+    let now = Instant::now();
+    let mut bucket = TokenBucket::new(BYTES_PER_SECOND, now);
+    assert!(bucket.try_consume(100, now));
+
+    let now = now + Duration::from_millis(500);
+    assert!(bucket.try_consume(50, now));
+    assert!(!bucket.try_consume(1, now));
+
+    // Waiting longer than a second doesn't let more than a second of traffic through.
+    let now = now + Duration::from_secs(10);
+    assert!(!bucket.try_consume(101, now));
+    assert!(bucket.try_consume(100, now));
+}
+
+#[test]
+fn consume_beyond_cap_delays_shedable_traffic() {
+    let now = Instant::now();
+    let mut bucket = TokenBucket::new(BYTES_PER_SECOND, now);
+    bucket.consume(300, now);
+    assert!(!bucket.try_consume(1, now));
+
+    // The deficit of 200 bytes is refilled after 2 seconds.
+    let now = now + Duration::from_secs(2);
+    assert!(!bucket.try_consume(1, now));
+    let now = now + Duration::from_millis(10);
+    assert!(bucket.try_consume(1, now));
+}
@@ -1,3 +1,4 @@
+mod bandwidth;
 mod swarm_trait;
 
 #[cfg(test)]
@@ -7,6 +8,8 @@ pub mod test_utils;
 
 use std::collections::HashMap;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use futures::channel::mpsc::{Receiver, SendError, Sender};
@@ -23,14 +26,16 @@ use papyrus_common::metrics as papyrus_metrics;
 use sqmr::Bytes;
 use tracing::{debug, error, info, trace, warn};
 
+use self::bandwidth::{BandwidthMeter, TrafficDirection};
 use self::swarm_trait::SwarmTrait;
 use crate::bin_utils::build_swarm;
 use crate::gossipsub_impl::Topic;
 use crate::mixed_behaviour::{self, BridgedBehaviour};
+pub use crate::peer_manager::ReputationModifier;
 use crate::sqmr::behaviour::SessionError;
 use crate::sqmr::{self, InboundSessionId, OutboundSessionId, SessionId};
 use crate::utils::{is_localhost, StreamHashMap};
-use crate::{gossipsub_impl, NetworkConfig};
+use crate::{gossipsub_impl, NetworkConfig, NETWORK_COMPONENT};
 
 #[derive(thiserror::Error, Debug)]
 pub enum NetworkError {
@@ -38,48 +43,85 @@ pub enum NetworkError {
     DialError(#[from] libp2p::swarm::DialError),
 }
 
+/// The priority of the messages this node sends on a broadcast topic.
+///
+/// Messages of high priority are always sent before messages of low priority and before the
+/// responses to sync queries, so that e.g. a peer syncing from this node can't delay its consensus
+/// votes. Queries this node sends and its responses to queries are always of low priority.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MessagePriority {
+    High,
+    Low,
+}
+
 pub struct GenericNetworkManager<SwarmT: SwarmTrait> {
     swarm: SwarmT,
     inbound_protocol_to_buffer_size: HashMap<StreamProtocol, usize>,
     sqmr_inbound_response_receivers: StreamHashMap<InboundSessionId, ResponsesReceiver>,
     sqmr_inbound_payload_senders: HashMap<StreamProtocol, SqmrServerSender>,
+    sqmr_inbound_session_protocols: HashMap<InboundSessionId, StreamProtocol>,
 
     sqmr_outbound_payload_receivers: StreamHashMap<StreamProtocol, SqmrClientReceiver>,
-    sqmr_outbound_response_senders: HashMap<OutboundSessionId, ResponsesSender>,
+    sqmr_outbound_response_senders: HashMap<OutboundSessionId, (StreamProtocol, ResponsesSender)>,
     sqmr_outbound_report_receivers_awaiting_assignment: HashMap<OutboundSessionId, ReportReceiver>,
     // Splitting the broadcast receivers from the broadcasted senders in order to poll all
     // receivers simultaneously.
-    // Each receiver has a matching sender and vice versa (i.e the maps have the same keys).
-    messages_to_broadcast_receivers: StreamHashMap<TopicHash, Receiver<Bytes>>,
+    // Each receiver has a matching sender and vice versa (i.e the keys of the receiver maps are
+    // the keys of the sender map).
+    high_priority_messages_to_broadcast_receivers: StreamHashMap<TopicHash, Receiver<Bytes>>,
+    low_priority_messages_to_broadcast_receivers: StreamHashMap<TopicHash, Receiver<Bytes>>,
     broadcasted_messages_senders: HashMap<TopicHash, Sender<(Bytes, BroadcastedMessageManager)>>,
-    reported_peer_receivers: FuturesUnordered<BoxFuture<'static, Option<PeerId>>>,
+    topic_names: HashMap<TopicHash, String>,
+    reported_peer_receivers:
+        FuturesUnordered<BoxFuture<'static, Option<(PeerId, ReputationModifier)>>>,
     advertised_multiaddr: Option<Multiaddr>,
+    bandwidth_meter: BandwidthMeter,
     // Fields for metrics
     num_active_inbound_sessions: usize,
     num_active_outbound_sessions: usize,
+    num_connected_peers: Arc<AtomicUsize>,
 }
 
 impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
     pub async fn run(mut self) -> Result<(), NetworkError> {
         loop {
+            // The branches are polled in order, so the queues of high priority messages are
+            // drained before the responses to sync queries, which a syncing peer can fill faster
+            // than they're sent.
             tokio::select! {
+                biased;
+                Some((topic_hash, message)) =
+                    self.high_priority_messages_to_broadcast_receivers.next() => {
+                    self.broadcast_message(message, topic_hash);
+                }
                 Some(event) = self.swarm.next() => self.handle_swarm_event(event),
+                Some(Some((peer_id, reason))) = self.reported_peer_receivers.next() => {
+                    self.swarm.report_peer(peer_id, reason)
+                }
+                Some((topic_hash, message)) =
+                    self.low_priority_messages_to_broadcast_receivers.next() => {
+                    self.broadcast_message(message, topic_hash);
+                }
                 Some(res) = self.sqmr_inbound_response_receivers.next() => self.handle_response_for_inbound_query(res),
                 Some((protocol, client_payload)) = self.sqmr_outbound_payload_receivers.next() => {
                     self.handle_local_sqmr_payload(protocol, client_payload)
                 }
-                Some((topic_hash, message)) = self.messages_to_broadcast_receivers.next() => {
-                    self.broadcast_message(message, topic_hash);
-                }
-                Some(Some(peer_id)) = self.reported_peer_receivers.next() => self.swarm.report_peer(peer_id),
             }
         }
     }
 
     // TODO(shahak): remove the advertised_multiaddr arg once we manage external addresses
     // in a behaviour.
-    pub(crate) fn generic_new(mut swarm: SwarmT, advertised_multiaddr: Option<Multiaddr>) -> Self {
-        gauge!(papyrus_metrics::PAPYRUS_NUM_CONNECTED_PEERS, 0f64);
+    pub(crate) fn generic_new(
+        mut swarm: SwarmT,
+        advertised_multiaddr: Option<Multiaddr>,
+        bandwidth_caps: &HashMap<String, u64>,
+    ) -> Self {
+        gauge!(
+            papyrus_metrics::PAPYRUS_NUM_CONNECTED_PEERS,
+            0f64,
+            papyrus_metrics::COMPONENT_LABEL => NETWORK_COMPONENT
+        );
         let reported_peer_receivers = FuturesUnordered::new();
         reported_peer_receivers.push(futures::future::pending().boxed());
         if let Some(address) = advertised_multiaddr.clone() {
@@ -90,18 +132,29 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
             inbound_protocol_to_buffer_size: HashMap::new(),
             sqmr_inbound_response_receivers: StreamHashMap::new(HashMap::new()),
             sqmr_inbound_payload_senders: HashMap::new(),
+            sqmr_inbound_session_protocols: HashMap::new(),
             sqmr_outbound_payload_receivers: StreamHashMap::new(HashMap::new()),
             sqmr_outbound_response_senders: HashMap::new(),
             sqmr_outbound_report_receivers_awaiting_assignment: HashMap::new(),
-            messages_to_broadcast_receivers: StreamHashMap::new(HashMap::new()),
+            high_priority_messages_to_broadcast_receivers: StreamHashMap::new(HashMap::new()),
+            low_priority_messages_to_broadcast_receivers: StreamHashMap::new(HashMap::new()),
             broadcasted_messages_senders: HashMap::new(),
+            topic_names: HashMap::new(),
             reported_peer_receivers,
             advertised_multiaddr,
+            bandwidth_meter: BandwidthMeter::new(bandwidth_caps),
             num_active_inbound_sessions: 0,
             num_active_outbound_sessions: 0,
+            num_connected_peers: Arc::new(AtomicUsize::new(0)),
         }
     }
 
+    /// Returns a handle to the number of peers this node is connected to, which is kept up to date
+    /// while the network manager runs.
+    pub fn get_num_connected_peers_handle(&self) -> Arc<AtomicUsize> {
+        self.num_connected_peers.clone()
+    }
+
     // TODO: Support multiple protocols where they're all different versions of the same protocol
     pub fn register_sqmr_protocol_server<Query, Response>(
         &mut self,
@@ -167,12 +220,29 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
     }
 
     /// Register a new subscriber for broadcasting and receiving broadcasts for a given topic.
+    /// The messages of the topic are sent with [`MessagePriority::High`].
     /// Panics if this topic is already subscribed.
     pub fn register_broadcast_topic<T>(
         &mut self,
         topic: Topic,
         buffer_size: usize,
     ) -> Result<BroadcastTopicChannels<T>, SubscriptionError>
+    where
+        T: TryFrom<Bytes>,
+        Bytes: From<T>,
+    {
+        self.register_broadcast_topic_with_priority(topic, buffer_size, MessagePriority::High)
+    }
+
+    /// Register a new subscriber for broadcasting and receiving broadcasts for a given topic, whose
+    /// messages are sent with the given priority.
+    /// Panics if this topic is already subscribed.
+    pub fn register_broadcast_topic_with_priority<T>(
+        &mut self,
+        topic: Topic,
+        buffer_size: usize,
+        priority: MessagePriority,
+    ) -> Result<BroadcastTopicChannels<T>, SubscriptionError>
     where
         T: TryFrom<Bytes>,
         Bytes: From<T>,
@@ -186,12 +256,14 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
         let (broadcasted_messages_sender, broadcasted_messages_receiver) =
             futures::channel::mpsc::channel(buffer_size);
 
-        let insert_result = self
-            .messages_to_broadcast_receivers
-            .insert(topic_hash.clone(), messages_to_broadcast_receiver);
-        if insert_result.is_some() {
+        if self.broadcasted_messages_senders.contains_key(&topic_hash) {
             panic!("Topic '{}' has already been registered.", topic);
         }
+        let messages_to_broadcast_receivers = match priority {
+            MessagePriority::High => &mut self.high_priority_messages_to_broadcast_receivers,
+            MessagePriority::Low => &mut self.low_priority_messages_to_broadcast_receivers,
+        };
+        messages_to_broadcast_receivers.insert(topic_hash.clone(), messages_to_broadcast_receiver);
 
         let insert_result = self
             .broadcasted_messages_senders
@@ -199,6 +271,7 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
         if insert_result.is_some() {
             panic!("Topic '{}' has already been registered.", topic);
         }
+        self.topic_names.insert(topic_hash.clone(), topic.to_string());
 
         let messages_to_broadcast_fn: fn(T) -> Ready<Result<Bytes, SendError>> =
             |x| ready(Ok(Bytes::from(x)));
@@ -213,14 +286,21 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
         Ok(BroadcastTopicChannels { messages_to_broadcast_sender, broadcasted_messages_receiver })
     }
 
+    fn update_num_connected_peers(&self) {
+        let num_connected_peers = self.swarm.num_connected_peers();
+        self.num_connected_peers.store(num_connected_peers, Ordering::Relaxed);
+        gauge!(
+            papyrus_metrics::PAPYRUS_NUM_CONNECTED_PEERS,
+            num_connected_peers as f64,
+            papyrus_metrics::COMPONENT_LABEL => NETWORK_COMPONENT
+        );
+    }
+
     fn handle_swarm_event(&mut self, event: SwarmEvent<mixed_behaviour::Event>) {
         match event {
             SwarmEvent::ConnectionEstablished { peer_id, .. } => {
                 debug!("Connected to peer id: {peer_id:?}");
-                gauge!(
-                    papyrus_metrics::PAPYRUS_NUM_CONNECTED_PEERS,
-                    self.swarm.num_connected_peers() as f64
-                );
+                self.update_num_connected_peers();
             }
             SwarmEvent::ConnectionClosed { peer_id, cause, .. } => {
                 match cause {
@@ -229,10 +309,7 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
                     }
                     None => debug!("Connection to {peer_id:?} closed."),
                 }
-                gauge!(
-                    papyrus_metrics::PAPYRUS_NUM_CONNECTED_PEERS,
-                    self.swarm.num_connected_peers() as f64
-                );
+                self.update_num_connected_peers();
             }
             SwarmEvent::Behaviour(event) => {
                 self.handle_behaviour_event(event);
@@ -346,9 +423,23 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
         self.num_active_inbound_sessions += 1;
         gauge!(
             papyrus_metrics::PAPYRUS_NUM_ACTIVE_INBOUND_SESSIONS,
-            self.num_active_inbound_sessions as f64
+            self.num_active_inbound_sessions as f64,
+            papyrus_metrics::COMPONENT_LABEL => NETWORK_COMPONENT
         );
-        let (report_sender, report_receiver) = oneshot::channel::<()>();
+        if !self.bandwidth_meter.try_record_inbound(protocol_name.as_ref(), query.len()) {
+            debug!(
+                "Protocol {protocol_name} exceeded its bandwidth cap. Closing inbound session \
+                 {inbound_session_id:?} without responding."
+            );
+            self.swarm.close_inbound_session(inbound_session_id).unwrap_or_else(|e| {
+                error!(
+                    "Failed to close a session that exceeded the bandwidth cap. Session id: \
+                     {inbound_session_id:?} not found error: {e:?}"
+                )
+            });
+            return;
+        }
+        let (report_sender, report_receiver) = oneshot::channel::<ReputationModifier>();
         self.handle_new_report_receiver(peer_id, report_receiver);
         // TODO: consider returning error instead of panic.
         let Some(query_sender) = self.sqmr_inbound_payload_senders.get_mut(&protocol_name) else {
@@ -361,6 +452,7 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
                 .expect("A protocol is registered in NetworkManager but it has no buffer size."),
         );
         let responses_sender = Box::new(responses_sender);
+        self.sqmr_inbound_session_protocols.insert(inbound_session_id, protocol_name);
         self.sqmr_inbound_response_receivers.insert(
             inbound_session_id,
             // Adding a None at the end of the stream so that we will receive a message
@@ -394,9 +486,14 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
         {
             self.handle_new_report_receiver(peer_id, report_receiver)
         }
-        if let Some(response_sender) =
+        if let Some((protocol, response_sender)) =
             self.sqmr_outbound_response_senders.get_mut(&outbound_session_id)
         {
+            self.bandwidth_meter.record(
+                protocol.as_ref(),
+                TrafficDirection::Inbound,
+                response.len(),
+            );
             // TODO(shahak): Close the channel if the buffer is full.
             send_now(
                 response_sender,
@@ -445,7 +542,7 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
     fn handle_gossipsub_behaviour_event(&mut self, event: gossipsub_impl::ExternalEvent) {
         let gossipsub_impl::ExternalEvent::Received { originated_peer_id, message, topic_hash } =
             event;
-        let (report_sender, report_receiver) = oneshot::channel::<()>();
+        let (report_sender, report_receiver) = oneshot::channel::<ReputationModifier>();
         let broadcasted_message_manager = BroadcastedMessageManager { report_sender };
         self.handle_new_report_receiver(originated_peer_id, report_receiver);
         let Some(sender) = self.broadcasted_messages_senders.get_mut(&topic_hash) else {
@@ -454,6 +551,13 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
             );
             return;
         };
+        let topic_name = self.topic_names.get(&topic_hash).expect(
+            "Topic is registered in broadcasted_messages_senders but it has no name in topic_names",
+        );
+        if !self.bandwidth_meter.try_record_inbound(topic_name, message.len()) {
+            debug!("Topic {topic_name} exceeded its bandwidth cap. Dropping broadcasted message.");
+            return;
+        }
         let send_result = sender.try_send((message, broadcasted_message_manager));
         if let Err(e) = send_result {
             if e.is_disconnected() {
@@ -471,6 +575,14 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
         let (inbound_session_id, maybe_response) = res;
         match maybe_response {
             Some(response) => {
+                if let Some(protocol) = self.sqmr_inbound_session_protocols.get(&inbound_session_id)
+                {
+                    self.bandwidth_meter.record(
+                        protocol.as_ref(),
+                        TrafficDirection::Outbound,
+                        response.len(),
+                    );
+                }
                 self.swarm.send_response(response, inbound_session_id).unwrap_or_else(|e| {
                     error!(
                         "Failed to send response to peer. Session id: {inbound_session_id:?} not \
@@ -481,6 +593,7 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
             // The None is inserted by the network manager after the receiver end terminated so
             // that we'll know here when it terminated.
             None => {
+                self.sqmr_inbound_session_protocols.remove(&inbound_session_id);
                 self.swarm.close_inbound_session(inbound_session_id).unwrap_or_else(|e| {
                     error!(
                         "Failed to close session after sending all response. Session id: \
@@ -497,6 +610,7 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
         client_payload: SqmrClientPayload,
     ) {
         let SqmrClientPayload { query, report_receiver, responses_sender } = client_payload;
+        self.bandwidth_meter.record(protocol.as_ref(), TrafficDirection::Outbound, query.len());
         match self.swarm.send_query(query, PeerId::random(), protocol.clone()) {
             Ok(outbound_session_id) => {
                 debug!(
@@ -506,9 +620,11 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
                 self.num_active_outbound_sessions += 1;
                 gauge!(
                     papyrus_metrics::PAPYRUS_NUM_ACTIVE_OUTBOUND_SESSIONS,
-                    self.num_active_outbound_sessions as f64
+                    self.num_active_outbound_sessions as f64,
+                    papyrus_metrics::COMPONENT_LABEL => NETWORK_COMPONENT
                 );
-                self.sqmr_outbound_response_senders.insert(outbound_session_id, responses_sender);
+                self.sqmr_outbound_response_senders
+                    .insert(outbound_session_id, (protocol, responses_sender));
                 self.sqmr_outbound_report_receivers_awaiting_assignment
                     .insert(outbound_session_id, report_receiver);
             }
@@ -522,6 +638,9 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
     }
 
     fn broadcast_message(&mut self, message: Bytes, topic_hash: TopicHash) {
+        if let Some(topic_name) = self.topic_names.get(&topic_hash) {
+            self.bandwidth_meter.record(topic_name, TrafficDirection::Outbound, message.len());
+        }
         self.swarm.broadcast_message(message, topic_hash);
     }
 
@@ -531,23 +650,25 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
                 self.num_active_inbound_sessions -= 1;
                 gauge!(
                     papyrus_metrics::PAPYRUS_NUM_ACTIVE_INBOUND_SESSIONS,
-                    self.num_active_inbound_sessions as f64
+                    self.num_active_inbound_sessions as f64,
+                    papyrus_metrics::COMPONENT_LABEL => NETWORK_COMPONENT
                 );
             }
             SessionId::OutboundSessionId(_) => {
                 self.num_active_outbound_sessions += 1;
                 gauge!(
                     papyrus_metrics::PAPYRUS_NUM_ACTIVE_OUTBOUND_SESSIONS,
-                    self.num_active_outbound_sessions as f64
+                    self.num_active_outbound_sessions as f64,
+                    papyrus_metrics::COMPONENT_LABEL => NETWORK_COMPONENT
                 );
             }
         }
     }
-    fn handle_new_report_receiver(&self, peer_id: PeerId, report_receiver: oneshot::Receiver<()>) {
+    fn handle_new_report_receiver(&self, peer_id: PeerId, report_receiver: ReportReceiver) {
         self.reported_peer_receivers.push(
             report_receiver
                 .map(move |result| match result {
-                    Ok(_) => Some(peer_id),
+                    Ok(reason) => Some((peer_id, reason)),
                     Err(_) => None,
                 })
                 .boxed(),
@@ -581,6 +702,9 @@ impl NetworkManager {
             advertised_multiaddr,
             secret_key,
             chain_id,
+            discovery_config,
+            compression_threshold,
+            bandwidth_caps,
         } = config;
 
         let listen_addresses = vec![
@@ -593,7 +717,8 @@ impl NetworkManager {
             mixed_behaviour::MixedBehaviour::new(
                 key,
                 bootstrap_peer_multiaddr.clone(),
-                sqmr::Config { session_timeout },
+                discovery_config.clone(),
+                sqmr::Config { session_timeout, compression_threshold },
                 chain_id,
                 node_version,
             )
@@ -603,7 +728,7 @@ impl NetworkManager {
                 .with_p2p(*swarm.local_peer_id())
                 .expect("advertised_multiaddr has a peer id different than the local peer id")
         });
-        Self::generic_new(swarm, advertised_multiaddr)
+        Self::generic_new(swarm, advertised_multiaddr, &bandwidth_caps)
     }
 
     pub fn get_local_peer_id(&self) -> String {
@@ -611,8 +736,8 @@ impl NetworkManager {
     }
 }
 
-pub type ReportSender = oneshot::Sender<()>;
-type ReportReceiver = oneshot::Receiver<()>;
+pub type ReportSender = oneshot::Sender<ReputationModifier>;
+type ReportReceiver = oneshot::Receiver<ReputationModifier>;
 
 type GenericSender<T> = Box<dyn Sink<T, Error = SendError> + Unpin + Send>;
 // Box<S> implements Stream only if S: Stream + Unpin
@@ -673,7 +798,7 @@ where
         &mut self,
         query: Query,
     ) -> Result<ClientResponsesManager<Response>, SendError> {
-        let (report_sender, report_receiver) = oneshot::channel::<()>();
+        let (report_sender, report_receiver) = oneshot::channel::<ReputationModifier>();
         let (responses_sender, responses_receiver) =
             futures::channel::mpsc::channel(self.buffer_size);
         let responses_receiver = Box::new(responses_receiver);
@@ -692,9 +817,9 @@ pub struct ClientResponsesManager<Response: TryFrom<Bytes>> {
 }
 
 impl<Response: TryFrom<Bytes>> ClientResponsesManager<Response> {
-    pub fn report_peer(self) {
-        warn!("Reporting peer");
-        if let Err(e) = self.report_sender.send(()) {
+    pub fn report_peer(self, reason: ReputationModifier) {
+        warn!("Reporting peer for a {reason:?} misbehavior");
+        if let Err(e) = self.report_sender.send(reason) {
             error!("Failed to report peer. Error: {e:?}");
         }
     }
@@ -755,9 +880,9 @@ where
         &self.query
     }
 
-    pub fn report_peer(self) {
-        debug!("Reporting peer from server to network");
-        if let Err(e) = self.report_sender.send(()) {
+    pub fn report_peer(self, reason: ReputationModifier) {
+        debug!("Reporting peer from server to network for a {reason:?} misbehavior");
+        if let Err(e) = self.report_sender.send(reason) {
             error!("Failed to report peer. Error: {e:?}");
         }
     }
@@ -812,9 +937,9 @@ pub struct BroadcastedMessageManager {
     report_sender: ReportSender,
 }
 impl BroadcastedMessageManager {
-    pub fn report_peer(self) {
-        warn!("Reporting peer");
-        if let Err(e) = self.report_sender.send(()) {
+    pub fn report_peer(self, reason: ReputationModifier) {
+        warn!("Reporting peer for a {reason:?} misbehavior");
+        if let Err(e) = self.report_sender.send(reason) {
             error!("Failed to report peer. Error: {e:?}");
         }
     }
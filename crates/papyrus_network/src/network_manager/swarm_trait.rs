@@ -49,7 +49,7 @@ pub trait SwarmTrait: Stream<Item = Event> + Unpin {
 
     fn broadcast_message(&mut self, message: Bytes, topic_hash: TopicHash);
 
-    fn report_peer(&mut self, peer_id: PeerId);
+    fn report_peer(&mut self, peer_id: PeerId, reason: ReputationModifier);
 
     fn add_new_supported_inbound_protocol(&mut self, protocol_name: StreamProtocol);
 }
@@ -122,8 +122,8 @@ impl SwarmTrait for Swarm<mixed_behaviour::MixedBehaviour> {
         }
     }
 
-    fn report_peer(&mut self, peer_id: PeerId) {
-        let _ = self.behaviour_mut().peer_manager.report_peer(peer_id, ReputationModifier::Bad {});
+    fn report_peer(&mut self, peer_id: PeerId, reason: ReputationModifier) {
+        let _ = self.behaviour_mut().peer_manager.report_peer(peer_id, reason);
     }
 
     fn add_new_supported_inbound_protocol(&mut self, protocol: StreamProtocol) {
@@ -21,7 +21,7 @@ use tokio::sync::Mutex;
 use tokio::time::sleep;
 
 use super::swarm_trait::{Event, SwarmTrait};
-use super::GenericNetworkManager;
+use super::{GenericNetworkManager, MessagePriority, ReputationModifier};
 use crate::gossipsub_impl::{self, Topic};
 use crate::mixed_behaviour;
 use crate::network_manager::ServerQueryManager;
@@ -41,7 +41,7 @@ struct MockSwarm {
     pub pending_events: Queue<Event>,
     pub subscribed_topics: HashSet<TopicHash>,
     broadcasted_messages_senders: Vec<UnboundedSender<(Bytes, TopicHash)>>,
-    reported_peer_senders: Vec<UnboundedSender<PeerId>>,
+    reported_peer_senders: Vec<UnboundedSender<(PeerId, ReputationModifier)>>,
     supported_inbound_protocols_senders: Vec<UnboundedSender<StreamProtocol>>,
     inbound_session_id_to_response_sender: HashMap<InboundSessionId, UnboundedSender<Bytes>>,
     next_outbound_session_id: usize,
@@ -72,6 +72,13 @@ impl MockSwarm {
         &mut self,
         inbound_session_id: InboundSessionId,
     ) -> impl Future<Output = Vec<Bytes>> {
+        self.stream_responses_sent_to_inbound_session(inbound_session_id).collect()
+    }
+
+    pub fn stream_responses_sent_to_inbound_session(
+        &mut self,
+        inbound_session_id: InboundSessionId,
+    ) -> impl Stream<Item = Bytes> {
         let (responses_sender, responses_receiver) = unbounded();
         if self
             .inbound_session_id_to_response_sender
@@ -80,7 +87,7 @@ impl MockSwarm {
         {
             panic!("Called get_responses_sent_to_inbound_session on {inbound_session_id:?} twice");
         }
-        responses_receiver.collect()
+        responses_receiver
     }
 
     pub fn stream_messages_we_broadcasted(&mut self) -> impl Stream<Item = (Bytes, TopicHash)> {
@@ -89,7 +96,9 @@ impl MockSwarm {
         receiver
     }
 
-    pub fn get_reported_peers_stream(&mut self) -> impl Stream<Item = PeerId> {
+    pub fn get_reported_peers_stream(
+        &mut self,
+    ) -> impl Stream<Item = (PeerId, ReputationModifier)> {
         let (sender, receiver) = unbounded();
         self.reported_peer_senders.push(sender);
         receiver
@@ -185,9 +194,9 @@ impl SwarmTrait for MockSwarm {
         }
     }
 
-    fn report_peer(&mut self, peer_id: PeerId) {
+    fn report_peer(&mut self, peer_id: PeerId, reason: ReputationModifier) {
         for sender in &self.reported_peer_senders {
-            sender.unbounded_send(peer_id).unwrap();
+            sender.unbounded_send((peer_id, reason)).unwrap();
         }
     }
     fn add_new_supported_inbound_protocol(&mut self, protocol_name: StreamProtocol) {
@@ -217,7 +226,7 @@ async fn register_sqmr_protocol_client_and_use_channels() {
     mock_swarm.first_polled_event_notifier = Some(event_notifier);
 
     // network manager to register subscriber
-    let mut network_manager = GenericNetworkManager::generic_new(mock_swarm, None);
+    let mut network_manager = GenericNetworkManager::generic_new(mock_swarm, None, &HashMap::new());
 
     // register subscriber and send payload
     let mut payload_sender = network_manager.register_sqmr_protocol_client::<Vec<u8>, Vec<u8>>(
@@ -279,7 +288,7 @@ async fn process_incoming_query() {
     let get_responses_fut = mock_swarm.get_responses_sent_to_inbound_session(inbound_session_id);
     let mut get_supported_inbound_protocol_fut = mock_swarm.get_supported_inbound_protocol();
 
-    let mut network_manager = GenericNetworkManager::generic_new(mock_swarm, None);
+    let mut network_manager = GenericNetworkManager::generic_new(mock_swarm, None, &HashMap::new());
 
     let mut inbound_payload_receiver = network_manager
         .register_sqmr_protocol_server::<Vec<u8>, Vec<u8>>(protocol.to_string(), BUFFER_SIZE);
@@ -315,7 +324,7 @@ async fn broadcast_message() {
     let mut mock_swarm = MockSwarm::default();
     let mut messages_we_broadcasted_stream = mock_swarm.stream_messages_we_broadcasted();
 
-    let mut network_manager = GenericNetworkManager::generic_new(mock_swarm, None);
+    let mut network_manager = GenericNetworkManager::generic_new(mock_swarm, None, &HashMap::new());
 
     let mut messages_to_broadcast_sender = network_manager
         .register_broadcast_topic(topic.clone(), BUFFER_SIZE)
@@ -335,6 +344,72 @@ async fn broadcast_message() {
     }
 }
 
+#[tokio::test]
+async fn high_priority_messages_are_sent_before_sync_responses() {
+    let high_priority_topic = Topic::new("HIGH_PRIORITY_TOPIC");
+    let low_priority_topic = Topic::new("LOW_PRIORITY_TOPIC");
+    let protocol: StreamProtocol = SIGNED_BLOCK_HEADER_PROTOCOL;
+    let inbound_session_id = InboundSessionId { value: 0 };
+
+    let mut mock_swarm = MockSwarm::default();
+    mock_swarm.pending_events.push(Event::Behaviour(mixed_behaviour::Event::ExternalEvent(
+        mixed_behaviour::ExternalEvent::Sqmr(GenericEvent::NewInboundSession {
+            query: VEC1.clone(),
+            inbound_session_id,
+            peer_id: PeerId::random(),
+            protocol_name: protocol.clone(),
+        }),
+    )));
+    let mut messages_we_broadcasted_stream = mock_swarm.stream_messages_we_broadcasted();
+    let mut responses_stream =
+        mock_swarm.stream_responses_sent_to_inbound_session(inbound_session_id);
+
+    let mut network_manager = GenericNetworkManager::generic_new(mock_swarm, None, &HashMap::new());
+
+    let mut inbound_payload_receiver = network_manager
+        .register_sqmr_protocol_server::<Vec<u8>, Vec<u8>>(protocol.to_string(), BUFFER_SIZE);
+    let mut high_priority_sender = network_manager
+        .register_broadcast_topic::<Bytes>(high_priority_topic.clone(), BUFFER_SIZE)
+        .unwrap()
+        .messages_to_broadcast_sender;
+    let mut low_priority_sender = network_manager
+        .register_broadcast_topic_with_priority::<Bytes>(
+            low_priority_topic.clone(),
+            BUFFER_SIZE,
+            MessagePriority::Low,
+        )
+        .unwrap()
+        .messages_to_broadcast_sender;
+
+    tokio::select! {
+        _ = network_manager.run() => panic!("network manager ended"),
+        _ = async {
+            let mut server_query_manager = inbound_payload_receiver.next().await.unwrap();
+            // Queue the responses before the messages, without letting the network manager run in
+            // between.
+            for response in [VEC1.clone(), VEC2.clone(), VEC3.clone()] {
+                server_query_manager.responses_sender.feed(response).await.unwrap();
+            }
+            low_priority_sender.feed(VEC1.clone()).await.unwrap();
+            high_priority_sender.feed(VEC2.clone()).await.unwrap();
+            high_priority_sender.feed(VEC3.clone()).await.unwrap();
+
+            assert_eq!(responses_stream.next().await.unwrap(), *VEC1);
+            let messages_we_broadcasted: Vec<_> =
+                messages_we_broadcasted_stream.take(3).collect().now_or_never().unwrap();
+            assert_eq!(
+                messages_we_broadcasted,
+                vec![
+                    (VEC2.clone(), high_priority_topic.hash()),
+                    (VEC3.clone(), high_priority_topic.hash()),
+                    (VEC1.clone(), low_priority_topic.hash()),
+                ]
+            );
+        } => {}
+        _ = sleep(TIMEOUT) => panic!("Test timed out"),
+    }
+}
+
 #[tokio::test]
 async fn receive_broadcasted_message_and_report_it() {
     let topic = Topic::new("TOPIC");
@@ -351,7 +426,7 @@ async fn receive_broadcasted_message_and_report_it() {
     )));
     let mut reported_peer_receiver = mock_swarm.get_reported_peers_stream();
 
-    let mut network_manager = GenericNetworkManager::generic_new(mock_swarm, None);
+    let mut network_manager = GenericNetworkManager::generic_new(mock_swarm, None, &HashMap::new());
 
     let mut broadcasted_messages_receiver = network_manager
         .register_broadcast_topic::<Bytes>(topic.clone(), BUFFER_SIZE)
@@ -366,10 +441,13 @@ async fn receive_broadcasted_message_and_report_it() {
             .then(|result| {
                 let (message_result, broadcasted_message_manager) = result.unwrap().unwrap();
                 assert_eq!(message, message_result.unwrap());
-                broadcasted_message_manager.report_peer();
+                broadcasted_message_manager.report_peer(ReputationModifier::Medium);
                 tokio::time::timeout(TIMEOUT, reported_peer_receiver.next())
             }) => {
-            assert_eq!(originated_peer_id, reported_peer_result.unwrap().unwrap());
+            assert_eq!(
+                (originated_peer_id, ReputationModifier::Medium),
+                reported_peer_result.unwrap().unwrap()
+            );
         }
     }
 }
@@ -387,3 +465,44 @@ fn get_test_connection_established_event(mock_peer_id: PeerId) -> Event {
         established_in: Duration::from_secs(0),
     }
 }
+
+#[tokio::test]
+async fn drop_broadcasted_messages_above_topic_bandwidth_cap() {
+    let topic = Topic::new("TOPIC");
+    let messages = vec![vec![1u8, 2u8, 3u8], vec![4u8, 5u8, 6u8]];
+
+    let mut mock_swarm = MockSwarm::default();
+    for message in &messages {
+        mock_swarm.pending_events.push(Event::Behaviour(mixed_behaviour::Event::ExternalEvent(
+            mixed_behaviour::ExternalEvent::GossipSub(gossipsub_impl::ExternalEvent::Received {
+                originated_peer_id: PeerId::random(),
+                message: message.clone(),
+                topic_hash: topic.hash(),
+            }),
+        )));
+    }
+
+    // The cap allows only the first message.
+    let bandwidth_caps = HashMap::from([(topic.to_string(), 4)]);
+    let mut network_manager = GenericNetworkManager::generic_new(mock_swarm, None, &bandwidth_caps);
+
+    let mut broadcasted_messages_receiver = network_manager
+        .register_broadcast_topic::<Bytes>(topic.clone(), BUFFER_SIZE)
+        .unwrap()
+        .broadcasted_messages_receiver;
+
+    tokio::select! {
+        _ = network_manager.run() => panic!("network manager ended"),
+        _ = async {
+            let (message_result, _) =
+                tokio::time::timeout(TIMEOUT, broadcasted_messages_receiver.next())
+                    .await
+                    .unwrap()
+                    .unwrap();
+            assert_eq!(messages[0], message_result.unwrap());
+            assert!(
+                tokio::time::timeout(TIMEOUT, broadcasted_messages_receiver.next()).await.is_err()
+            );
+        } => {}
+    }
+}
@@ -10,6 +10,7 @@ use super::{
     BroadcastedMessageManager,
     GenericReceiver,
     ReportReceiver,
+    ReputationModifier,
     ServerQueryManager,
     ServerResponsesSender,
     SqmrClientPayload,
@@ -62,7 +63,7 @@ where
     Query: TryFrom<Bytes>,
     Response: Send + 'static,
 {
-    let (report_sender, report_receiver) = oneshot::channel::<()>();
+    let (report_sender, report_receiver) = oneshot::channel::<ReputationModifier>();
     let (responses_sender, responses_receiver) = futures::channel::mpsc::channel::<Response>(1);
     let responses_sender = ServerResponsesSender { sender: Box::new(responses_sender) };
     let responses_receiver = Box::new(responses_receiver);
@@ -74,7 +75,7 @@ where
 }
 
 pub fn create_test_broadcasted_message_manager() -> BroadcastedMessageManager {
-    let (report_sender, _report_receiver) = oneshot::channel::<()>();
+    let (report_sender, _report_receiver) = oneshot::channel::<ReputationModifier>();
     BroadcastedMessageManager { report_sender }
 }
 
@@ -20,12 +20,35 @@ pub(crate) mod peer;
 #[cfg(test)]
 mod test;
 
-#[cfg_attr(test, derive(Debug, PartialEq))]
+/// The severity of a misbehavior of a peer, e.g. sending invalid data. Misbehaviors accumulate a
+/// penalty on the peer that decays over time. Peers with a penalty are assigned sessions only if
+/// there are no other unblocked peers, and a peer whose penalty reaches [`MAX_PENALTY`] is blocked.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ReputationModifier {
-    // TODO: Implement this enum
+    /// A misbehavior that an honest peer may commit, e.g. sending a message that is no longer
+    /// relevant.
+    Mild,
+    /// A misbehavior that an honest peer is unlikely to commit, e.g. sending data that fails
+    /// validation.
+    Medium,
+    /// A misbehavior that only a malicious peer commits, e.g. sending data with an invalid
+    /// signature. The peer is blocked immediately.
     Bad,
 }
 
+/// The penalty at which a peer is blocked.
+pub(crate) const MAX_PENALTY: u32 = 100;
+
+impl ReputationModifier {
+    pub(crate) fn penalty(self) -> u32 {
+        match self {
+            ReputationModifier::Mild => 5,
+            ReputationModifier::Medium => 25,
+            ReputationModifier::Bad => MAX_PENALTY,
+        }
+    }
+}
+
 pub struct PeerManager<P: PeerTrait + 'static> {
     peers: HashMap<PeerId, P>,
     // TODO: consider implementing a cleanup mechanism to not store all queries forever
@@ -108,13 +131,19 @@ where
             self.sessions_received_when_no_peers.push(outbound_session_id);
             return None;
         }
-        let peer = self
-            .peers
-            .iter()
-            .skip(self.last_peer_index)
+        let last_peer_index = self.last_peer_index;
+        let peers_from_last_index = || {
+            self.peers.iter().skip(last_peer_index).chain(self.peers.iter().take(last_peer_index))
+        };
+        // Peers that misbehaved recently are assigned only if there are no other unblocked peers,
+        // and the one with the lowest penalty is preferred among them.
+        let peer = peers_from_last_index()
+            .filter(|(_, peer)| peer.penalty() == 0)
             .find(|(_, peer)| !peer.is_blocked())
             .or_else(|| {
-                self.peers.iter().take(self.last_peer_index).find(|(_, peer)| !peer.is_blocked())
+                peers_from_last_index()
+                    .filter(|(_, peer)| peer.penalty() > 0 && !peer.is_blocked())
+                    .min_by_key(|(_, peer)| peer.penalty())
             });
         self.last_peer_index = (self.last_peer_index + 1) % self.peers.len();
         if peer.is_none() {
@@ -181,10 +210,12 @@ where
         peer_id: PeerId,
         reason: ReputationModifier,
     ) -> Result<(), PeerManagerError> {
-        self.pending_events
-            .push(ToSwarm::GenerateEvent(ToOtherBehaviourEvent::PeerBlacklisted { peer_id }));
         if let Some(peer) = self.peers.get_mut(&peer_id) {
-            peer.update_reputation(reason);
+            if peer.update_reputation(reason) {
+                self.pending_events.push(ToSwarm::GenerateEvent(
+                    ToOtherBehaviourEvent::PeerBlacklisted { peer_id },
+                ));
+            }
             Ok(())
         } else {
             Err(PeerManagerError::NoSuchPeer(peer_id))
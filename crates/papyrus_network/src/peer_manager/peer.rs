@@ -6,13 +6,21 @@ use libp2p::{Multiaddr, PeerId};
 use mockall::automock;
 use tracing::{error, info};
 
-use super::ReputationModifier;
+use super::{ReputationModifier, MAX_PENALTY};
+
+// The time it takes for a single point of the penalty of a peer to be forgiven.
+const PENALTY_DECAY_INTERVAL: Duration = Duration::from_secs(30);
 
 #[cfg_attr(test, automock)]
 pub trait PeerTrait {
     fn new(peer_id: PeerId, multiaddr: Multiaddr) -> Self;
 
-    fn update_reputation(&mut self, reason: ReputationModifier);
+    /// Adds the penalty of the misbehavior to the peer, and blocks it if the penalty reached
+    /// [`MAX_PENALTY`]. Returns true if the peer was blocked.
+    fn update_reputation(&mut self, reason: ReputationModifier) -> bool;
+
+    /// The penalty of the peer for its recent misbehaviors. Zero if it didn't misbehave recently.
+    fn penalty(&self) -> u32;
 
     fn peer_id(&self) -> PeerId;
 
@@ -39,6 +47,8 @@ pub struct Peer {
     timed_out_until: Option<Instant>,
     timeout_duration: Option<Duration>,
     connection_ids: Vec<ConnectionId>,
+    penalty: u32,
+    penalty_updated_at: Instant,
 }
 
 impl PeerTrait for Peer {
@@ -49,10 +59,20 @@ impl PeerTrait for Peer {
             timeout_duration: None,
             timed_out_until: None,
             connection_ids: Vec::new(),
+            penalty: 0,
+            penalty_updated_at: Instant::now(),
         }
     }
 
-    fn update_reputation(&mut self, _reason: ReputationModifier) {
+    fn update_reputation(&mut self, reason: ReputationModifier) -> bool {
+        let penalty = self.penalty().saturating_add(reason.penalty());
+        self.penalty_updated_at = Instant::now();
+        if penalty < MAX_PENALTY {
+            info!("Peer {:?} misbehaved ({reason:?}). Its penalty is now {penalty}.", self.peer_id);
+            self.penalty = penalty;
+            return false;
+        }
+        self.penalty = 0;
         if let Some(timeout_duration) = self.timeout_duration {
             info!(
                 "Peer {:?} misbehaved. Blacklisting it for {:.3} seconds.",
@@ -66,6 +86,13 @@ impl PeerTrait for Peer {
                 self.peer_id
             );
         }
+        true
+    }
+
+    fn penalty(&self) -> u32 {
+        let decayed_points =
+            self.penalty_updated_at.elapsed().as_secs() / PENALTY_DECAY_INTERVAL.as_secs();
+        self.penalty.saturating_sub(u32::try_from(decayed_points).unwrap_or(u32::MAX))
     }
 
     fn peer_id(&self) -> PeerId {
@@ -20,7 +20,7 @@ use crate::discovery::identify_impl::IdentifyToOtherBehaviourEvent;
 use crate::mixed_behaviour;
 use crate::mixed_behaviour::BridgedBehaviour;
 use crate::peer_manager::peer::{MockPeerTrait, Peer, PeerTrait};
-use crate::peer_manager::{PeerManager, PeerManagerConfig, ReputationModifier};
+use crate::peer_manager::{PeerManager, PeerManagerConfig, ReputationModifier, MAX_PENALTY};
 use crate::sqmr::OutboundSessionId;
 
 impl<P: PeerTrait> Unpin for PeerManager<P> {}
@@ -242,6 +242,43 @@ async fn peer_block_realeased_after_timeout() {
     assert!(!peer.is_blocked());
 }
 
+#[test]
+fn mild_misbehaviors_accumulate_until_peer_is_blocked() {
+    let mut peer = Peer::new(PeerId::random(), Multiaddr::empty());
+    peer.set_timeout_duration(Duration::from_secs(60));
+    let n_reports_to_block = MAX_PENALTY / ReputationModifier::Mild.penalty();
+    for _ in 1..n_reports_to_block {
+        assert!(!peer.update_reputation(ReputationModifier::Mild));
+        assert!(!peer.is_blocked());
+    }
+    assert_eq!(peer.penalty(), MAX_PENALTY - ReputationModifier::Mild.penalty());
+    assert!(peer.update_reputation(ReputationModifier::Mild));
+    assert!(peer.is_blocked());
+}
+
+#[test]
+fn penalized_peer_is_deprioritized() {
+    let mut peer_manager = PeerManager::new(PeerManagerConfig::default());
+    let peer1 = Peer::new(PeerId::random(), Multiaddr::empty());
+    let peer2 = Peer::new(PeerId::random(), Multiaddr::empty());
+    peer_manager.add_peer(peer1.clone());
+    peer_manager.add_peer(peer2.clone());
+
+    // A misbehavior that doesn't block the peer doesn't notify Kademlia.
+    peer_manager.report_peer(peer1.peer_id(), ReputationModifier::Medium).unwrap();
+    assert!(peer_manager.pending_events.is_empty());
+
+    for value in 0..3 {
+        let res = peer_manager.assign_peer_to_session(OutboundSessionId { value });
+        assert_eq!(res, Some(peer2.peer_id()));
+    }
+
+    // The penalized peer is assigned if it's the only unblocked peer.
+    peer_manager.report_peer(peer2.peer_id(), ReputationModifier::Bad).unwrap();
+    let res = peer_manager.assign_peer_to_session(OutboundSessionId { value: 3 });
+    assert_eq!(res, Some(peer1.peer_id()));
+}
+
 #[test]
 fn report_peer_on_unknown_peer_id() {
     // Create a new peer manager
@@ -372,9 +409,10 @@ fn create_mock_peer(
         peer.expect_update_reputation()
             .times(1)
             .with(eq(ReputationModifier::Bad {}))
-            .return_once(|_| ())
+            .return_once(|_| true)
             .in_sequence(&mut mockall_seq);
     }
+    peer.expect_penalty().return_const(0u32);
     peer.expect_connection_ids().return_const(connection_id.map(|x| vec![x]).unwrap_or_default());
 
     (peer, peer_id)
@@ -0,0 +1,139 @@
+//! Negotiation and application of the compression of the messages of a session.
+//!
+//! Every supported protocol has a compressed variant, whose name is the protocol name with
+//! [`ZSTD_PROTOCOL_SUFFIX`]. A node with compression enabled supports both variants of its inbound
+//! protocols and prefers the compressed variant when it opens an outbound session, so that the
+//! compression is used only if both peers enable it.
+//!
+//! In a compressed session, each message (the query and the responses) starts with a byte that
+//! states whether the rest of the message is compressed. A message is compressed only if its size
+//! is at least the compression threshold of its sender and if compressing it reduces its size.
+
+#[cfg(test)]
+#[path = "compression_test.rs"]
+mod compression_test;
+
+use std::io;
+
+use libp2p::StreamProtocol;
+use metrics::counter;
+use papyrus_common::metrics as papyrus_metrics;
+
+use super::messages::MAX_MESSAGE_SIZE;
+use super::Bytes;
+use crate::NETWORK_COMPONENT;
+
+pub const ZSTD_PROTOCOL_SUFFIX: &str = "/zstd";
+
+const UNCOMPRESSED_MESSAGE_FLAG: u8 = 0;
+const ZSTD_COMPRESSED_MESSAGE_FLAG: u8 = 1;
+const ZSTD_COMPRESSION_LEVEL: i32 = 3;
+
+/// The compression of the messages of a session.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Compression {
+    Identity,
+    /// Messages of at least `threshold` bytes are compressed with zstd.
+    Zstd {
+        threshold: usize,
+    },
+}
+
+impl Compression {
+    /// Returns the protocol names to offer when opening an outbound session of the given protocol,
+    /// by order of preference.
+    pub fn outbound_protocol_names(
+        protocol_name: &StreamProtocol,
+        compression_threshold: Option<usize>,
+    ) -> Vec<StreamProtocol> {
+        match compression_threshold {
+            Some(_) => vec![zstd_protocol_name(protocol_name), protocol_name.clone()],
+            None => vec![protocol_name.clone()],
+        }
+    }
+
+    /// Returns the protocol names to accept for inbound sessions of the given protocols.
+    pub fn inbound_protocol_names(
+        protocol_names: &[StreamProtocol],
+        compression_threshold: Option<usize>,
+    ) -> Vec<StreamProtocol> {
+        let mut inbound_protocol_names = protocol_names.to_vec();
+        if compression_threshold.is_some() {
+            inbound_protocol_names.extend(protocol_names.iter().map(zstd_protocol_name));
+        }
+        inbound_protocol_names
+    }
+
+    /// Splits a negotiated protocol name to the name of the protocol and the compression of the
+    /// session.
+    pub fn from_negotiated_protocol_name(
+        negotiated_protocol_name: StreamProtocol,
+        compression_threshold: Option<usize>,
+    ) -> (StreamProtocol, Self) {
+        let Some(protocol_name) =
+            negotiated_protocol_name.as_ref().strip_suffix(ZSTD_PROTOCOL_SUFFIX)
+        else {
+            return (negotiated_protocol_name, Self::Identity);
+        };
+        let protocol_name = StreamProtocol::try_from_owned(protocol_name.to_owned())
+            .expect("Stripping a suffix from a protocol name should give a valid protocol name.");
+        // The peer may compress its messages even if we didn't set a threshold, so we decompress
+        // them regardless. We don't compress our own messages in that case.
+        (protocol_name, Self::Zstd { threshold: compression_threshold.unwrap_or(usize::MAX) })
+    }
+
+    /// Prepares a message to be sent in a session with this compression.
+    pub fn encode(&self, message: Bytes) -> Bytes {
+        let Self::Zstd { threshold } = self else {
+            return message;
+        };
+        if message.len() >= *threshold {
+            match zstd::bulk::compress(&message, ZSTD_COMPRESSION_LEVEL) {
+                Ok(compressed_message) if compressed_message.len() < message.len() => {
+                    counter!(
+                        papyrus_metrics::PAPYRUS_COMPRESSION_SAVED_BYTES,
+                        (message.len() - compressed_message.len()) as u64,
+                        papyrus_metrics::COMPONENT_LABEL => NETWORK_COMPONENT
+                    );
+                    return with_flag(ZSTD_COMPRESSED_MESSAGE_FLAG, compressed_message);
+                }
+                // Sending the message uncompressed if the compression failed or didn't help.
+                _ => {}
+            }
+        }
+        with_flag(UNCOMPRESSED_MESSAGE_FLAG, message)
+    }
+
+    /// Restores a message that was received in a session with this compression.
+    pub fn decode(&self, message: Bytes) -> Result<Bytes, io::Error> {
+        let Self::Zstd { .. } = self else {
+            return Ok(message);
+        };
+        let Some((flag, content)) = message.split_first() else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Received an empty message in a compressed session.",
+            ));
+        };
+        match *flag {
+            UNCOMPRESSED_MESSAGE_FLAG => Ok(content.to_vec()),
+            ZSTD_COMPRESSED_MESSAGE_FLAG => zstd::bulk::decompress(content, MAX_MESSAGE_SIZE),
+            flag => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Received a message with an unknown compression flag {flag}."),
+            )),
+        }
+    }
+}
+
+fn zstd_protocol_name(protocol_name: &StreamProtocol) -> StreamProtocol {
+    StreamProtocol::try_from_owned(format!("{}{ZSTD_PROTOCOL_SUFFIX}", protocol_name.as_ref()))
+        .expect("Adding a suffix to a protocol name should give a valid protocol name.")
+}
+
+fn with_flag(flag: u8, content: Bytes) -> Bytes {
+    let mut message = Vec::with_capacity(content.len() + 1);
+    message.push(flag);
+    message.extend(content);
+    message
+}
@@ -0,0 +1,63 @@
+use libp2p::StreamProtocol;
+use pretty_assertions::assert_eq;
+
+use super::{Compression, UNCOMPRESSED_MESSAGE_FLAG, ZSTD_COMPRESSED_MESSAGE_FLAG};
+
+const PROTOCOL_NAME: StreamProtocol = StreamProtocol::new("/example/1.0.0");
+const COMPRESSED_PROTOCOL_NAME: StreamProtocol = StreamProtocol::new("/example/1.0.0/zstd");
+
+#[test]
+fn split_negotiated_protocol_name() {
+    assert_eq!(
+        Compression::from_negotiated_protocol_name(PROTOCOL_NAME, Some(10)),
+        (PROTOCOL_NAME, Compression::Identity)
+    );
+    assert_eq!(
+        Compression::from_negotiated_protocol_name(COMPRESSED_PROTOCOL_NAME, Some(10)),
+        (PROTOCOL_NAME, Compression::Zstd { threshold: 10 })
+    );
+    assert_eq!(
+        Compression::from_negotiated_protocol_name(COMPRESSED_PROTOCOL_NAME, None),
+        (PROTOCOL_NAME, Compression::Zstd { threshold: usize::MAX })
+    );
+}
+
+#[test]
+fn identity_doesnt_change_messages() {
+    let message = vec![1u8; 100];
+    assert_eq!(Compression::Identity.encode(message.clone()), message);
+    assert_eq!(Compression::Identity.decode(message.clone()).unwrap(), message);
+}
+
+#[test]
+fn zstd_compresses_only_messages_above_threshold() {
+    let compression = Compression::Zstd { threshold: 100 };
+
+    let small_message = vec![1u8; 99];
+    let encoded_small_message = compression.encode(small_message.clone());
+    assert_eq!(encoded_small_message[0], UNCOMPRESSED_MESSAGE_FLAG);
+    assert_eq!(compression.decode(encoded_small_message).unwrap(), small_message);
+
+    let large_message = vec![1u8; 1000];
+    let encoded_large_message = compression.encode(large_message.clone());
+    assert_eq!(encoded_large_message[0], ZSTD_COMPRESSED_MESSAGE_FLAG);
+    assert!(encoded_large_message.len() < large_message.len());
+    assert_eq!(compression.decode(encoded_large_message).unwrap(), large_message);
+}
+
+#[test]
+fn zstd_doesnt_compress_when_compression_increases_size() {
+    let compression = Compression::Zstd { threshold: 0 };
+    let message = vec![1u8, 2u8, 3u8];
+    let encoded_message = compression.encode(message.clone());
+    assert_eq!(encoded_message[0], UNCOMPRESSED_MESSAGE_FLAG);
+    assert_eq!(compression.decode(encoded_message).unwrap(), message);
+}
+
+#[test]
+fn zstd_decode_rejects_invalid_messages() {
+    let compression = Compression::Zstd { threshold: 0 };
+    assert!(compression.decode(vec![]).is_err());
+    assert!(compression.decode(vec![2u8, 1u8]).is_err());
+    assert!(compression.decode(vec![ZSTD_COMPRESSED_MESSAGE_FLAG, 1u8, 2u8]).is_err());
+}
@@ -224,7 +224,10 @@ fn get_response_from_indices(peer_id1: PeerId, peer_id2: PeerId, message_index:
 async fn everyone_sends_to_everyone() {
     let (mut swarms_stream, connection_ids) =
         create_fully_connected_swarms_stream(NUM_PEERS, || {
-            let mut behaviour = Behaviour::new(Config { session_timeout: Duration::from_secs(5) });
+            let mut behaviour = Behaviour::new(Config {
+                session_timeout: Duration::from_secs(5),
+                compression_threshold: Some(0),
+            });
             let supported_inbound_protocols = vec![PROTOCOL_NAME, OTHER_PROTOCOL_NAME];
             for protocol in supported_inbound_protocols {
                 behaviour.add_new_supported_inbound_protocol(protocol);
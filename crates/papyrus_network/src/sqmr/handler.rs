@@ -160,7 +160,10 @@ impl ConnectionHandler for Handler {
         let supported_inbound_protocols_vec =
             self.supported_inbound_protocols.iter().cloned().collect();
         SubstreamProtocol::new(
-            InboundProtocol::new(supported_inbound_protocols_vec),
+            InboundProtocol::new(
+                supported_inbound_protocols_vec,
+                self.config.compression_threshold,
+            ),
             InboundSessionId { value: self.next_inbound_session_id.fetch_add(1, Ordering::AcqRel) },
         )
         .with_timeout(self.config.session_timeout)
@@ -258,7 +261,11 @@ impl ConnectionHandler for Handler {
                 // on_behaviour_event. See https://github.com/libp2p/rust-libp2p/issues/5147
                 self.pending_events.push_back(ConnectionHandlerEvent::OutboundSubstreamRequest {
                     protocol: SubstreamProtocol::new(
-                        OutboundProtocol { query, protocol_name },
+                        OutboundProtocol {
+                            query,
+                            protocol_name,
+                            compression_threshold: self.config.compression_threshold,
+                        },
                         outbound_session_id,
                     )
                     .with_timeout(self.config.session_timeout),
@@ -331,7 +338,7 @@ impl ConnectionHandler for Handler {
     ) {
         match event {
             ConnectionEvent::FullyNegotiatedOutbound(FullyNegotiatedOutbound {
-                protocol: mut read_stream,
+                protocol: (mut read_stream, compression),
                 info: outbound_session_id,
             }) => {
                 if self.dropped_outbound_sessions_non_negotiated.remove(&outbound_session_id) {
@@ -343,7 +350,7 @@ impl ConnectionHandler for Handler {
                         loop {
                             let result_opt = read_message(&mut read_stream).await;
                             let result = match result_opt {
-                                Ok(Some(response)) => Ok(response),
+                                Ok(Some(response)) => compression.decode(response),
                                 Ok(None) => break,
                                 Err(error) => Err(error),
                             };
@@ -358,7 +365,7 @@ impl ConnectionHandler for Handler {
                 );
             }
             ConnectionEvent::FullyNegotiatedInbound(FullyNegotiatedInbound {
-                protocol: (query, write_stream, protocol_name),
+                protocol: (query, write_stream, protocol_name, compression),
                 info: inbound_session_id,
             }) => {
                 // No need to wake because the swarm guarantees that `poll` will be called after
@@ -372,7 +379,7 @@ impl ConnectionHandler for Handler {
                     }),
                 ));
                 self.id_to_inbound_session
-                    .insert(inbound_session_id, InboundSession::new(write_stream));
+                    .insert(inbound_session_id, InboundSession::new(write_stream, compression));
             }
             ConnectionEvent::DialUpgradeError(DialUpgradeError {
                 info: outbound_session_id,
@@ -10,6 +10,7 @@ use futures::{AsyncWriteExt, FutureExt};
 use libp2p::swarm::Stream;
 use replace_with::replace_with_or_abort;
 
+use super::super::compression::Compression;
 use super::super::messages::write_message;
 use super::super::Bytes;
 
@@ -17,6 +18,7 @@ pub(super) struct InboundSession {
     pending_messages: VecDeque<Bytes>,
     current_task: WriteMessageTask,
     wakers_waiting_for_new_message: Vec<Waker>,
+    compression: Compression,
 }
 
 enum FinishReason {
@@ -31,11 +33,12 @@ enum WriteMessageTask {
 }
 
 impl InboundSession {
-    pub fn new(write_stream: WriteHalf<Stream>) -> Self {
+    pub fn new(write_stream: WriteHalf<Stream>, compression: Compression) -> Self {
         Self {
             pending_messages: Default::default(),
             current_task: WriteMessageTask::Waiting(write_stream),
             wakers_waiting_for_new_message: Default::default(),
+            compression,
         }
     }
 
@@ -62,6 +65,7 @@ impl InboundSession {
 
     fn handle_waiting(&mut self, cx: &mut Context<'_>) -> Poll<()> {
         if let Some(data) = self.pending_messages.pop_front() {
+            let data = self.compression.encode(data);
             replace_with_or_abort(&mut self.current_task, |current_task| {
                 let WriteMessageTask::Waiting(mut write_stream) = current_task else {
                     panic!("Called handle_waiting while not waiting.");
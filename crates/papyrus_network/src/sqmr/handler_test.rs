@@ -17,6 +17,7 @@ use libp2p::swarm::handler::{
 use libp2p::swarm::{ConnectionHandler, ConnectionHandlerEvent, Stream, StreamUpgradeError};
 use libp2p::{PeerId, StreamProtocol};
 
+use super::super::compression::Compression;
 use super::super::messages::{read_message, write_message};
 use super::super::{Bytes, Config, GenericEvent, InboundSessionId, OutboundSessionId, SessionId};
 use super::{
@@ -90,7 +91,7 @@ fn simulate_negotiated_inbound_session_from_swarm(
     inbound_session_id: InboundSessionId,
 ) {
     handler.on_connection_event(ConnectionEvent::FullyNegotiatedInbound(FullyNegotiatedInbound {
-        protocol: (query, inbound_stream.split().1, PROTOCOL_NAME.clone()),
+        protocol: (query, inbound_stream.split().1, PROTOCOL_NAME.clone(), Compression::Identity),
         info: inbound_session_id,
     }));
 }
@@ -101,7 +102,10 @@ fn simulate_negotiated_outbound_session_from_swarm(
     outbound_session_id: OutboundSessionId,
 ) {
     handler.on_connection_event(ConnectionEvent::FullyNegotiatedOutbound(
-        FullyNegotiatedOutbound { protocol: outbound_stream.split().0, info: outbound_session_id },
+        FullyNegotiatedOutbound {
+            protocol: (outbound_stream.split().0, Compression::Identity),
+            info: outbound_session_id,
+        },
     ));
 }
 
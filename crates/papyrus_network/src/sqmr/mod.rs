@@ -1,4 +1,5 @@
 pub mod behaviour;
+pub mod compression;
 pub mod handler;
 mod messages;
 pub mod protocol;
@@ -11,6 +12,7 @@ use std::time::Duration;
 pub use behaviour::{Behaviour, ToOtherBehaviourEvent};
 use derive_more::Display;
 use libp2p::{PeerId, StreamProtocol};
+pub use messages::MAX_MESSAGE_SIZE;
 
 pub type Bytes = Vec<u8>;
 
@@ -67,4 +69,6 @@ pub enum GenericEvent<SessionError> {
 #[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
 pub struct Config {
     pub session_timeout: Duration,
+    /// The minimal size in bytes of a message to compress. If None, compression isn't negotiated.
+    pub compression_threshold: Option<usize>,
 }
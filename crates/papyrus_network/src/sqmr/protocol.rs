@@ -2,7 +2,7 @@
 #[path = "protocol_test.rs"]
 mod protocol_test;
 
-use std::{io, iter};
+use std::io;
 
 use futures::future::BoxFuture;
 use futures::io::{ReadHalf, WriteHalf};
@@ -10,16 +10,21 @@ use futures::{AsyncRead, AsyncReadExt, AsyncWrite, FutureExt};
 use libp2p::core::upgrade::{InboundUpgrade, OutboundUpgrade, UpgradeInfo};
 use libp2p::StreamProtocol;
 
+use super::compression::Compression;
 use super::messages::{read_message_without_length_prefix, write_message_without_length_prefix};
 use super::Bytes;
 
 pub struct InboundProtocol {
     supported_protocols: Vec<StreamProtocol>,
+    compression_threshold: Option<usize>,
 }
 
 impl InboundProtocol {
-    pub fn new(supported_protocols: Vec<StreamProtocol>) -> Self {
-        Self { supported_protocols }
+    pub fn new(
+        supported_protocols: Vec<StreamProtocol>,
+        compression_threshold: Option<usize>,
+    ) -> Self {
+        Self { supported_protocols, compression_threshold }
     }
 }
 
@@ -28,7 +33,7 @@ impl UpgradeInfo for InboundProtocol {
     type InfoIter = Vec<Self::Info>;
 
     fn protocol_info(&self) -> Self::InfoIter {
-        self.supported_protocols.clone()
+        Compression::inbound_protocol_names(&self.supported_protocols, self.compression_threshold)
     }
 }
 
@@ -36,15 +41,20 @@ impl<Stream> InboundUpgrade<Stream> for InboundProtocol
 where
     Stream: AsyncRead + AsyncWrite + Unpin + Send + 'static,
 {
-    type Output = (Bytes, WriteHalf<Stream>, StreamProtocol);
+    type Output = (Bytes, WriteHalf<Stream>, StreamProtocol, Compression);
     type Error = io::Error;
     type Future = BoxFuture<'static, Result<Self::Output, Self::Error>>;
 
-    fn upgrade_inbound(self, stream: Stream, protocol_name: Self::Info) -> Self::Future {
+    fn upgrade_inbound(self, stream: Stream, negotiated_protocol_name: Self::Info) -> Self::Future {
+        let (protocol_name, compression) = Compression::from_negotiated_protocol_name(
+            negotiated_protocol_name,
+            self.compression_threshold,
+        );
         async move {
             let (read_half, write_half) = stream.split();
             let request = read_message_without_length_prefix(read_half).await?;
-            Ok((request, write_half, protocol_name))
+            let request = compression.decode(request)?;
+            Ok((request, write_half, protocol_name, compression))
         }
         .boxed()
     }
@@ -54,14 +64,17 @@ where
 pub struct OutboundProtocol {
     pub query: Bytes,
     pub protocol_name: StreamProtocol,
+    /// If set, the compressed variant of the protocol is preferred and the query is compressed if
+    /// it's at least this size in bytes.
+    pub compression_threshold: Option<usize>,
 }
 
 impl UpgradeInfo for OutboundProtocol {
     type Info = StreamProtocol;
-    type InfoIter = iter::Once<Self::Info>;
+    type InfoIter = Vec<Self::Info>;
 
     fn protocol_info(&self) -> Self::InfoIter {
-        iter::once(self.protocol_name.clone())
+        Compression::outbound_protocol_names(&self.protocol_name, self.compression_threshold)
     }
 }
 
@@ -69,15 +82,24 @@ impl<Stream> OutboundUpgrade<Stream> for OutboundProtocol
 where
     Stream: AsyncRead + AsyncWrite + Unpin + Send + 'static,
 {
-    type Output = ReadHalf<Stream>;
+    type Output = (ReadHalf<Stream>, Compression);
     type Error = io::Error;
     type Future = BoxFuture<'static, Result<Self::Output, Self::Error>>;
 
-    fn upgrade_outbound(self, stream: Stream, _: Self::Info) -> Self::Future {
+    fn upgrade_outbound(
+        self,
+        stream: Stream,
+        negotiated_protocol_name: Self::Info,
+    ) -> Self::Future {
+        let (_, compression) = Compression::from_negotiated_protocol_name(
+            negotiated_protocol_name,
+            self.compression_threshold,
+        );
         async move {
             let (read_half, write_half) = stream.split();
-            write_message_without_length_prefix(&self.query, write_half).await?;
-            Ok(read_half)
+            let query = compression.encode(self.query);
+            write_message_without_length_prefix(&query, write_half).await?;
+            Ok((read_half, compression))
         }
         .boxed()
     }
@@ -3,47 +3,79 @@ use libp2p::core::UpgradeInfo;
 use libp2p::StreamProtocol;
 use pretty_assertions::assert_eq;
 
+use super::super::compression::{Compression, ZSTD_PROTOCOL_SUFFIX};
 use super::super::messages::{read_message, write_message};
 use super::{InboundProtocol, OutboundProtocol};
 use crate::test_utils::{dummy_data, get_connected_streams};
 
 pub const PROTOCOL_NAME: StreamProtocol = StreamProtocol::new("/example/1.0.0");
+const COMPRESSED_PROTOCOL_NAME: StreamProtocol = StreamProtocol::new("/example/1.0.0/zstd");
 
 #[test]
 fn outbound_protocol_info() {
-    let outbound_protocol =
-        OutboundProtocol { query: Default::default(), protocol_name: PROTOCOL_NAME };
-    assert_eq!(outbound_protocol.protocol_info().collect::<Vec<_>>(), vec![PROTOCOL_NAME]);
+    let outbound_protocol = OutboundProtocol {
+        query: Default::default(),
+        protocol_name: PROTOCOL_NAME,
+        compression_threshold: None,
+    };
+    assert_eq!(outbound_protocol.protocol_info(), vec![PROTOCOL_NAME]);
+}
+
+#[test]
+fn outbound_protocol_info_prefers_compression() {
+    let outbound_protocol = OutboundProtocol {
+        query: Default::default(),
+        protocol_name: PROTOCOL_NAME,
+        compression_threshold: Some(0),
+    };
+    assert_eq!(outbound_protocol.protocol_info(), vec![COMPRESSED_PROTOCOL_NAME, PROTOCOL_NAME]);
 }
 
 #[test]
 fn inbound_protocol_info() {
     let protocol_names = vec![PROTOCOL_NAME, StreamProtocol::new("/example/2.0.0")];
-    let inbound_protocol = InboundProtocol::new(protocol_names.clone());
+    let inbound_protocol = InboundProtocol::new(protocol_names.clone(), None);
     assert_eq!(inbound_protocol.protocol_info(), protocol_names);
 }
 
+#[test]
+fn inbound_protocol_info_with_compression() {
+    let protocol_names = vec![PROTOCOL_NAME, StreamProtocol::new("/example/2.0.0")];
+    let inbound_protocol = InboundProtocol::new(protocol_names.clone(), Some(0));
+    let mut expected_protocol_names = protocol_names.clone();
+    expected_protocol_names.extend(protocol_names.iter().map(|protocol_name| {
+        StreamProtocol::try_from_owned(format!("{protocol_name}{ZSTD_PROTOCOL_SUFFIX}")).unwrap()
+    }));
+    assert_eq!(inbound_protocol.protocol_info(), expected_protocol_names);
+}
+
 #[tokio::test]
 async fn positive_flow() {
     let (inbound_stream, outbound_stream, _) = get_connected_streams().await;
 
     let query = vec![1u8, 2u8, 3u8];
-    let outbound_protocol = OutboundProtocol { query: query.clone(), protocol_name: PROTOCOL_NAME };
-    let inbound_protocol = InboundProtocol::new(vec![PROTOCOL_NAME]);
+    let outbound_protocol = OutboundProtocol {
+        query: query.clone(),
+        protocol_name: PROTOCOL_NAME,
+        compression_threshold: None,
+    };
+    let inbound_protocol = InboundProtocol::new(vec![PROTOCOL_NAME], None);
 
     tokio::join!(
         async move {
-            let (received_query, mut stream, protocol_name) =
+            let (received_query, mut stream, protocol_name, compression) =
                 inbound_protocol.upgrade_inbound(inbound_stream, PROTOCOL_NAME).await.unwrap();
             assert_eq!(query, received_query);
             assert_eq!(protocol_name, PROTOCOL_NAME);
+            assert_eq!(compression, Compression::Identity);
             for response in dummy_data() {
                 write_message(&response, &mut stream).await.unwrap();
             }
         },
         async move {
-            let mut stream =
+            let (mut stream, compression) =
                 outbound_protocol.upgrade_outbound(outbound_stream, PROTOCOL_NAME).await.unwrap();
+            assert_eq!(compression, Compression::Identity);
             for expected_response in dummy_data() {
                 let response = read_message(&mut stream).await.unwrap().unwrap();
                 assert_eq!(response, expected_response);
@@ -52,10 +84,56 @@ async fn positive_flow() {
     );
 }
 
+#[tokio::test]
+async fn compressed_positive_flow() {
+    let (inbound_stream, outbound_stream, _) = get_connected_streams().await;
+
+    let query = vec![7u8; 100];
+    let responses = vec![vec![1u8], vec![8u8; 1000]];
+    let compression = Compression::Zstd { threshold: 10 };
+    let outbound_protocol = OutboundProtocol {
+        query: query.clone(),
+        protocol_name: PROTOCOL_NAME,
+        compression_threshold: Some(10),
+    };
+    let inbound_protocol = InboundProtocol::new(vec![PROTOCOL_NAME], Some(10));
+    let expected_responses = responses.clone();
+
+    tokio::join!(
+        async move {
+            let (received_query, mut stream, protocol_name, inbound_compression) = inbound_protocol
+                .upgrade_inbound(inbound_stream, COMPRESSED_PROTOCOL_NAME)
+                .await
+                .unwrap();
+            assert_eq!(query, received_query);
+            assert_eq!(protocol_name, PROTOCOL_NAME);
+            assert_eq!(inbound_compression, compression);
+            for response in responses {
+                write_message(&compression.encode(response), &mut stream).await.unwrap();
+            }
+        },
+        async move {
+            let (mut stream, outbound_compression) = outbound_protocol
+                .upgrade_outbound(outbound_stream, COMPRESSED_PROTOCOL_NAME)
+                .await
+                .unwrap();
+            assert_eq!(outbound_compression, compression);
+            for expected_response in expected_responses {
+                let response = read_message(&mut stream).await.unwrap().unwrap();
+                assert_eq!(compression.decode(response).unwrap(), expected_response);
+            }
+        }
+    );
+}
+
 #[tokio::test]
 async fn inbound_dropped() {
     let (inbound_stream, outbound_stream, _) = get_connected_streams().await;
-    let outbound_protocol = OutboundProtocol { query: vec![0u8], protocol_name: PROTOCOL_NAME };
+    let outbound_protocol = OutboundProtocol {
+        query: vec![0u8],
+        protocol_name: PROTOCOL_NAME,
+        compression_threshold: None,
+    };
 
     drop(inbound_stream);
 
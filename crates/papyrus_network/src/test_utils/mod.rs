@@ -48,7 +48,7 @@ pub(crate) fn dummy_data() -> Vec<Bytes> {
 
 impl crate::sqmr::Config {
     pub fn get_test_config() -> Self {
-        Self { session_timeout: Duration::MAX }
+        Self { session_timeout: Duration::MAX, compression_threshold: None }
     }
 }
 // TODO(eitan): create a lazy static constant of SUPPORTED_PROTOCOLS which is this vec
@@ -6,6 +6,7 @@ use std::io::{BufWriter, Read, Write};
 use std::ops::IndexMut;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
 
 use assert_json_diff::assert_json_eq;
 use colored::Colorize;
@@ -13,7 +14,9 @@ use itertools::Itertools;
 use papyrus_base_layer::ethereum_base_layer_contract::EthereumBaseLayerConfig;
 use papyrus_config::dumping::SerializeConfig;
 use papyrus_config::presentation::get_config_presentation;
-use papyrus_config::{SerializationType, SerializedContent, SerializedParam};
+use papyrus_config::validators::config_validate;
+use papyrus_config::{ConfigError, SerializationType, SerializedContent, SerializedParam};
+use papyrus_consensus::config::ConsensusConfig;
 use papyrus_monitoring_gateway::MonitoringGatewayConfig;
 use papyrus_test_utils::get_absolute_path;
 use pretty_assertions::assert_eq;
@@ -24,7 +27,7 @@ use validator::Validate;
 
 #[cfg(feature = "rpc")]
 use crate::config::pointers::CONFIG_POINTERS;
-use crate::config::{node_command, NodeConfig, DEFAULT_CONFIG_PATH};
+use crate::config::{chain_presets, node_command, NodeConfig, DEFAULT_CONFIG_PATH};
 
 // Returns the required and generated params in config/papyrus/default_config.json with the default
 // value from the config presentation.
@@ -77,6 +80,27 @@ fn load_default_config() {
     NodeConfig::load_and_process(get_args(vec![])).expect("Failed to load the config.");
 }
 
+#[test]
+fn load_chain_presets() {
+    env::set_current_dir(get_absolute_path("")).expect("Couldn't set working dir.");
+    for preset in chain_presets() {
+        let config = NodeConfig::load_and_process(get_args(vec!["--preset", preset.name]))
+            .expect("Failed to load the config.");
+        assert_eq!(config.storage.db_config.chain_id.to_string(), preset.name);
+    }
+
+    // The user's config overrides the preset.
+    let config = NodeConfig::load_and_process(get_args(vec![
+        "--preset",
+        "SN_SEPOLIA",
+        "--starknet_url",
+        "https://custom-feeder.io/",
+    ]))
+    .unwrap();
+    assert_eq!(config.storage.db_config.chain_id, ChainId::Sepolia);
+    assert_eq!(config.central.url, "https://custom-feeder.io/");
+}
+
 #[test]
 fn load_http_headers() {
     let args = get_args(vec!["--central.http_headers", "NAME_1:VALUE_1 NAME_2:VALUE_2"]);
@@ -106,6 +130,29 @@ fn test_dump_default_config() {
     default_config.validate().unwrap();
 }
 
+#[test]
+fn all_validation_errors_are_reported() {
+    let mut config = NodeConfig::default();
+    config.storage.db_config.path_prefix = PathBuf::from("non_existing_dir");
+    let mut consensus_config = ConsensusConfig::default();
+    consensus_config.timeouts.precommit_timeout =
+        consensus_config.timeouts.proposal_timeout + Duration::from_secs(1);
+    config.consensus = Some(consensus_config);
+
+    let Err(ConfigError::ConfigValidationError(errors)) = config_validate(&config) else {
+        panic!("Expected a validation error.");
+    };
+    let param_paths: Vec<&str> =
+        errors.0.iter().map(|error| error.param_path.as_str()).sorted().collect();
+    assert_eq!(
+        param_paths,
+        vec![
+            "consensus.timeouts.proposal_timeout, consensus.timeouts.precommit_timeout",
+            "storage.db_config.path_prefix",
+        ]
+    );
+}
+
 #[test]
 fn test_default_config_process() {
     env::set_current_dir(get_absolute_path("")).expect("Couldn't set working dir.");
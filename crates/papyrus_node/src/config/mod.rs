@@ -23,7 +23,9 @@ use papyrus_config::dumping::{
     ser_pointer_target_param,
     SerializeConfig,
 };
-use papyrus_config::loading::load_and_process_config;
+use papyrus_config::loading::{load_and_process_config_with_options, LoadingOptions};
+use papyrus_config::presets::ConfigPreset;
+use papyrus_config::secrets::{CommandSecretResolver, SecretResolver};
 use papyrus_config::{ConfigError, ParamPath, ParamPrivacyInput, SerializedParam};
 use papyrus_consensus::config::ConsensusConfig;
 use papyrus_monitoring_gateway::MonitoringGatewayConfig;
@@ -63,10 +65,13 @@ pub struct NodeConfig {
     /// If P2P sync is active, then network must be active too.
     // TODO(yair): Change NodeConfig to have an option of enum of SyncConfig or P2PSyncConfig.
     pub p2p_sync: Option<P2PSyncClientConfig>,
+    #[validate]
     pub consensus: Option<ConsensusConfig>,
     // TODO(shahak): Make network non-optional once it's developed enough.
     pub network: Option<NetworkConfig>,
     pub collect_profiling_metrics: bool,
+    /// If true, the storage, which another node writes, is opened read-only and only served.
+    pub read_replica: bool,
 }
 
 // Default configuration values.
@@ -84,6 +89,7 @@ impl Default for NodeConfig {
             consensus: None,
             network: None,
             collect_profiling_metrics: false,
+            read_replica: false,
         }
     }
 }
@@ -100,12 +106,22 @@ impl SerializeConfig for NodeConfig {
             ser_optional_sub_config(&self.p2p_sync, "p2p_sync"),
             ser_optional_sub_config(&self.consensus, "consensus"),
             ser_optional_sub_config(&self.network, "network"),
-            BTreeMap::from_iter([ser_param(
-                "collect_profiling_metrics",
-                &self.collect_profiling_metrics,
-                "If true, collect profiling metrics for the node.",
-                ParamPrivacyInput::Public,
-            )]),
+            BTreeMap::from_iter([
+                ser_param(
+                    "collect_profiling_metrics",
+                    &self.collect_profiling_metrics,
+                    "If true, collect profiling metrics for the node.",
+                    ParamPrivacyInput::Public,
+                ),
+                ser_param(
+                    "read_replica",
+                    &self.read_replica,
+                    "If true, the node opens the storage that another node writes read-only and \
+                     serves it over the RPC and the monitoring gateway. Sync, P2P sync, consensus \
+                     and network must be turned off.",
+                    ParamPrivacyInput::Public,
+                ),
+            ]),
         ];
         #[cfg(feature = "rpc")]
         sub_configs.push(append_sub_config_name(self.rpc.dump(), "rpc"));
@@ -119,10 +135,36 @@ impl NodeConfig {
     /// higher priority.
     pub fn load_and_process(args: Vec<String>) -> Result<Self, ConfigError> {
         let default_config_file = std::fs::File::open(Path::new(DEFAULT_CONFIG_PATH))?;
-        load_and_process_config(default_config_file, node_command(), args)
+        let kms_resolver = CommandSecretResolver::kms_from_env();
+        let options = LoadingOptions {
+            secret_resolvers: kms_resolver
+                .iter()
+                .map(|resolver| resolver as &dyn SecretResolver)
+                .collect(),
+            presets: chain_presets(),
+        };
+        load_and_process_config_with_options(default_config_file, node_command(), args, &options)
     }
 }
 
+/// The presets of the known chains, selected by their chain id, e.g. `--preset SN_MAIN`.
+pub fn chain_presets() -> Vec<ConfigPreset> {
+    vec![
+        ConfigPreset {
+            name: "SN_MAIN",
+            values: include_str!("../../../../config/papyrus/presets/mainnet.json"),
+        },
+        ConfigPreset {
+            name: "SN_SEPOLIA",
+            values: include_str!("../../../../config/papyrus/presets/sepolia_testnet.json"),
+        },
+        ConfigPreset {
+            name: "SN_INTEGRATION_SEPOLIA",
+            values: include_str!("../../../../config/papyrus/presets/sepolia_integration.json"),
+        },
+    ]
+}
+
 /// The command line interface of this node.
 pub fn node_command() -> Command {
     Command::new("Papyrus")
@@ -4,25 +4,32 @@ mod main_test;
 use std::env::args;
 use std::future::{pending, Future};
 use std::process::exit;
+use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
 use std::time::Duration;
 
 use futures::stream::StreamExt;
 use futures::FutureExt;
-use papyrus_base_layer::ethereum_base_layer_contract::EthereumBaseLayerConfig;
+use papyrus_base_layer::ethereum_base_layer_contract::{
+    EthereumBaseLayerConfig,
+    EthereumBaseLayerContract,
+};
+use papyrus_base_layer::l1_finality::L1FinalityTracker;
 use papyrus_common::metrics::COLLECT_PROFILING_METRICS;
 use papyrus_common::pending_classes::PendingClasses;
 use papyrus_common::BlockHashAndNumber;
 use papyrus_config::presentation::get_config_presentation;
 use papyrus_config::validators::config_validate;
 use papyrus_config::ConfigError;
+use papyrus_consensus::class_fetcher::ClassFetcher;
 use papyrus_consensus::config::ConsensusConfig;
 use papyrus_consensus::papyrus_consensus_context::PapyrusConsensusContext;
 use papyrus_consensus::simulation_network_receiver::NetworkReceiver;
-use papyrus_consensus::types::ConsensusError;
+use papyrus_consensus::types::{ConsensusError, ValidatorId};
+use papyrus_monitoring_gateway::health::HealthSources;
 use papyrus_monitoring_gateway::MonitoringServer;
 use papyrus_network::gossipsub_impl::Topic;
-use papyrus_network::network_manager::NetworkManager;
+use papyrus_network::network_manager::{MessagePriority, NetworkManager};
 use papyrus_network::{network_manager, NetworkConfig};
 use papyrus_node::config::NodeConfig;
 use papyrus_node::version::VERSION_FULL;
@@ -30,18 +37,20 @@ use papyrus_p2p_sync::client::{
     P2PSyncClient,
     P2PSyncClientChannels,
     P2PSyncClientConfig,
-    P2PSyncClientError,
+    ValidatorSetProvider,
 };
 use papyrus_p2p_sync::server::{P2PSyncServer, P2PSyncServerChannels};
 use papyrus_p2p_sync::{Protocol, BUFFER_SIZE};
 #[cfg(feature = "rpc")]
-use papyrus_rpc::run_server;
+use papyrus_rpc::{run_server, update_deprecated_class_metrics};
+use papyrus_storage::read_replica::open_read_replica;
 use papyrus_storage::{open_storage, update_storage_metrics, StorageReader, StorageWriter};
 use papyrus_sync::sources::base_layer::{BaseLayerSourceError, EthereumBaseLayerSource};
 use papyrus_sync::sources::central::{CentralError, CentralSource, CentralSourceConfig};
 use papyrus_sync::sources::pending::PendingSource;
 use papyrus_sync::{StateSync, StateSyncError, SyncConfig};
 use starknet_api::block::{BlockHash, BlockNumber};
+use starknet_api::core::ContractAddress;
 use starknet_api::felt;
 use starknet_client::reader::objects::pending_data::{PendingBlock, PendingBlockOrDeprecated};
 use starknet_client::reader::PendingData;
@@ -95,10 +104,33 @@ async fn create_rpc_server_future(
     Ok(pending())
 }
 
+// The validators of the heights decided by consensus, which are the validators consensus runs with.
+// TODO: Read the validator set of each height from the state once it's stored there.
+struct ConsensusValidatorSet {
+    start_height: BlockNumber,
+    validators: Vec<ValidatorId>,
+}
+
+impl ConsensusValidatorSet {
+    fn new(config: &ConsensusConfig) -> Self {
+        Self {
+            start_height: config.start_height,
+            validators: (0..config.num_validators).map(ContractAddress::from).collect(),
+        }
+    }
+}
+
+impl ValidatorSetProvider for ConsensusValidatorSet {
+    fn validators(&self, height: BlockNumber) -> Option<Vec<ValidatorId>> {
+        (height >= self.start_height).then(|| self.validators.clone())
+    }
+}
+
 fn run_consensus(
     config: Option<&ConsensusConfig>,
     storage_reader: StorageReader,
     network_manager: Option<&mut NetworkManager>,
+    consensus_height: Arc<AtomicU64>,
 ) -> anyhow::Result<JoinHandle<Result<(), ConsensusError>>> {
     let (Some(config), Some(network_manager)) = (config, network_manager) else {
         info!("Consensus is disabled.");
@@ -108,16 +140,27 @@ fn run_consensus(
 
     let network_channels = network_manager
         .register_broadcast_topic(Topic::new(config.network_topic.clone()), BUFFER_SIZE)?;
+    let class_by_hash_sender =
+        network_manager.register_sqmr_protocol_client(Protocol::ClassByHash.into(), BUFFER_SIZE);
+    let class_fetcher = Arc::new(ClassFetcher::new(
+        storage_reader.clone(),
+        class_by_hash_sender,
+        config.max_fetched_sierra_program_length,
+    ));
     // TODO(matan): connect this to an actual channel.
     if let Some(test_config) = config.test.as_ref() {
-        let sync_channels = network_manager
-            .register_broadcast_topic(Topic::new(test_config.sync_topic.clone()), BUFFER_SIZE)?;
+        let sync_channels = network_manager.register_broadcast_topic_with_priority(
+            Topic::new(test_config.sync_topic.clone()),
+            BUFFER_SIZE,
+            MessagePriority::Low,
+        )?;
         let context = PapyrusConsensusContext::new(
             storage_reader.clone(),
             network_channels.messages_to_broadcast_sender,
             config.num_validators,
             Some(sync_channels.messages_to_broadcast_sender),
-        );
+        )
+        .with_class_fetcher(class_fetcher);
         let network_receiver = NetworkReceiver::new(
             network_channels.broadcasted_messages_receiver,
             test_config.cache_size,
@@ -135,8 +178,11 @@ fn run_consensus(
             config.validator_id,
             config.consensus_delay,
             config.timeouts.clone(),
+            config.trust_finality,
+            config.max_pending_commits,
             network_receiver,
             sync_receiver,
+            consensus_height,
         )))
     } else {
         let context = PapyrusConsensusContext::new(
@@ -144,20 +190,27 @@ fn run_consensus(
             network_channels.messages_to_broadcast_sender,
             config.num_validators,
             None,
-        );
+        )
+        .with_class_fetcher(class_fetcher);
         Ok(tokio::spawn(papyrus_consensus::run_consensus(
             context,
             config.start_height,
             config.validator_id,
             config.consensus_delay,
             config.timeouts.clone(),
+            config.trust_finality,
+            config.max_pending_commits,
             network_channels.broadcasted_messages_receiver,
             futures::stream::pending(),
+            consensus_height,
         )))
     }
 }
 
 async fn run_threads(config: NodeConfig) -> anyhow::Result<()> {
+    if config.read_replica {
+        return run_read_replica(config).await;
+    }
     let (storage_reader, storage_writer) = open_storage(config.storage.clone())?;
 
     let storage_metrics_handle = if config.monitoring_gateway.collect_metrics {
@@ -173,10 +226,15 @@ async fn run_threads(config: NodeConfig) -> anyhow::Result<()> {
         maybe_sync_server_channels,
         local_peer_id,
     ) = register_to_network(config.network.clone())?;
+    let num_connected_peers =
+        maybe_network_manager.as_ref().map(|manager| manager.get_num_connected_peers_handle());
+    let consensus_height = (config.consensus.is_some() && maybe_network_manager.is_some())
+        .then(|| Arc::new(AtomicU64::default()));
     let consensus_handle = run_consensus(
         config.consensus.as_ref(),
         storage_reader.clone(),
         maybe_network_manager.as_mut(),
+        consensus_height.clone().unwrap_or_default(),
     )?;
     let network_handle = tokio::spawn(async move {
         match maybe_network_manager {
@@ -185,7 +243,16 @@ async fn run_threads(config: NodeConfig) -> anyhow::Result<()> {
         }
     });
 
+    // The sync is the only writer of the syncing state.
+    let shared_highest_block = Arc::new(RwLock::new(None));
+
     // Monitoring server.
+    let health_sources = HealthSources {
+        // Only the central sync tracks the highest block.
+        highest_block: config.sync.is_some().then(|| shared_highest_block.clone()),
+        consensus_height,
+        num_connected_peers,
+    };
     let monitoring_server = MonitoringServer::new(
         config.monitoring_gateway.clone(),
         get_config_presentation(&config, true)?,
@@ -193,11 +260,9 @@ async fn run_threads(config: NodeConfig) -> anyhow::Result<()> {
         storage_reader.clone(),
         VERSION_FULL,
         local_peer_id,
+        health_sources,
     )?;
     let monitoring_server_handle = monitoring_server.spawn_server().await;
-
-    // The sync is the only writer of the syncing state.
-    let shared_highest_block = Arc::new(RwLock::new(None));
     let pending_data = Arc::new(RwLock::new(PendingData {
         // The pending data might change later to DeprecatedPendingBlock, depending on the response
         // from the feeder gateway.
@@ -245,13 +310,22 @@ async fn run_threads(config: NodeConfig) -> anyhow::Result<()> {
         (None, Some(p2p_sync_client_config)) => {
             let p2p_sync_client_channels = maybe_sync_client_channels
                 .expect("If p2p sync is enabled, network needs to be enabled too");
+            let validator_set_provider =
+                p2p_sync_client_config.verify_decision_certificates.then(|| {
+                    let consensus_config = config.consensus.as_ref().expect(
+                        "If decision certificates are verified, consensus needs to be enabled too",
+                    );
+                    Arc::new(ConsensusValidatorSet::new(consensus_config))
+                        as Arc<dyn ValidatorSetProvider>
+                });
             (
                 pending().boxed(),
                 run_p2p_sync_client(
-                    p2p_sync_client_config,
+                    (p2p_sync_client_config, config.base_layer),
                     storage_reader.clone(),
                     storage_writer,
                     p2p_sync_client_channels,
+                    validator_set_provider,
                 )
                 .boxed(),
             )
@@ -329,21 +403,94 @@ async fn run_threads(config: NodeConfig) -> anyhow::Result<()> {
     }
 
     async fn run_p2p_sync_client(
-        p2p_sync_client_config: P2PSyncClientConfig,
+        configs: (P2PSyncClientConfig, EthereumBaseLayerConfig),
         storage_reader: StorageReader,
         storage_writer: StorageWriter,
         p2p_sync_client_channels: P2PSyncClientChannels,
-    ) -> Result<(), P2PSyncClientError> {
-        let p2p_sync = P2PSyncClient::new(
+        validator_set_provider: Option<Arc<dyn ValidatorSetProvider>>,
+    ) -> anyhow::Result<()> {
+        let (p2p_sync_client_config, base_layer_config) = configs;
+        let l1_finality_tracker = match p2p_sync_client_config.l1_finality.clone() {
+            Some(l1_finality_config) => Some(L1FinalityTracker::new(
+                l1_finality_config,
+                EthereumBaseLayerContract::new(base_layer_config)?,
+            )),
+            None => None,
+        };
+        let mut p2p_sync = P2PSyncClient::new(
             p2p_sync_client_config,
             storage_reader,
             storage_writer,
             p2p_sync_client_channels,
         );
-        p2p_sync.run().await
+        if let Some(validator_set_provider) = validator_set_provider {
+            p2p_sync = p2p_sync.with_validator_set_provider(validator_set_provider);
+        }
+        if let Some(l1_finality_tracker) = l1_finality_tracker {
+            p2p_sync = p2p_sync.with_l1_finality_tracker(l1_finality_tracker);
+        }
+        Ok(p2p_sync.run().await?)
     }
 }
 
+// Serves the storage that another node writes over the RPC and the monitoring gateway. The storage
+// is opened read-only, so none of the components that write it run.
+async fn run_read_replica(config: NodeConfig) -> anyhow::Result<()> {
+    if config.sync.is_some()
+        || config.p2p_sync.is_some()
+        || config.consensus.is_some()
+        || config.network.is_some()
+    {
+        anyhow::bail!(
+            "A read replica doesn't write the storage. Turn off sync, p2p_sync, consensus and \
+             network."
+        );
+    }
+    let storage_reader = open_read_replica(config.storage.clone())?;
+
+    let storage_metrics_handle = if config.monitoring_gateway.collect_metrics {
+        spawn_storage_metrics_collector(storage_reader.clone(), STORAGE_METRICS_UPDATE_INTERVAL)
+    } else {
+        tokio::spawn(pending())
+    };
+    let monitoring_server = MonitoringServer::new(
+        config.monitoring_gateway.clone(),
+        get_config_presentation(&config, true)?,
+        get_config_presentation(&config, false)?,
+        storage_reader.clone(),
+        VERSION_FULL,
+        "".to_string(),
+        HealthSources::default(),
+    )?;
+    let monitoring_server_handle = monitoring_server.spawn_server().await;
+    // A read replica has no pending data and doesn't track the highest block of the chain.
+    let server_handle_future = create_rpc_server_future(
+        &config,
+        Arc::new(RwLock::new(None)),
+        Arc::new(RwLock::new(PendingData::default())),
+        Arc::new(RwLock::new(PendingClasses::default())),
+        storage_reader,
+    )
+    .await?;
+
+    tokio::select! {
+        res = storage_metrics_handle => {
+            error!("collecting storage metrics stopped.");
+            res?
+        }
+        res = server_handle_future => {
+            error!("RPC server stopped.");
+            res?
+        }
+        res = monitoring_server_handle => {
+            error!("Monitoring server stopped.");
+            res??
+        }
+    };
+    error!("Task ended with unexpected Ok.");
+    Ok(())
+}
+
 type NetworkRunReturn =
     (Option<NetworkManager>, Option<P2PSyncClientChannels>, Option<P2PSyncServerChannels>, String);
 
@@ -363,10 +510,16 @@ fn register_to_network(network_config: Option<NetworkConfig>) -> anyhow::Result<
         network_manager.register_sqmr_protocol_client(Protocol::StateDiff.into(), BUFFER_SIZE);
     let transaction_client_sender =
         network_manager.register_sqmr_protocol_client(Protocol::Transaction.into(), BUFFER_SIZE);
+    let class_client_sender =
+        network_manager.register_sqmr_protocol_client(Protocol::Class.into(), BUFFER_SIZE);
+    let state_snapshot_client_sender =
+        network_manager.register_sqmr_protocol_client(Protocol::StateSnapshot.into(), BUFFER_SIZE);
     let p2p_sync_client_channels = P2PSyncClientChannels::new(
         header_client_sender,
         state_diff_client_sender,
         transaction_client_sender,
+        class_client_sender,
+        state_snapshot_client_sender,
     );
 
     let header_server_receiver = network_manager
@@ -379,12 +532,18 @@ fn register_to_network(network_config: Option<NetworkConfig>) -> anyhow::Result<
         network_manager.register_sqmr_protocol_server(Protocol::Class.into(), BUFFER_SIZE);
     let event_server_receiver =
         network_manager.register_sqmr_protocol_server(Protocol::Event.into(), BUFFER_SIZE);
+    let state_snapshot_server_receiver =
+        network_manager.register_sqmr_protocol_server(Protocol::StateSnapshot.into(), BUFFER_SIZE);
+    let class_by_hash_server_receiver =
+        network_manager.register_sqmr_protocol_server(Protocol::ClassByHash.into(), BUFFER_SIZE);
     let p2p_sync_server_channels = P2PSyncServerChannels::new(
         header_server_receiver,
         state_diff_server_receiver,
         transaction_server_receiver,
         class_server_receiver,
         event_server_receiver,
+        state_snapshot_server_receiver,
+        class_by_hash_server_receiver,
     );
 
     Ok((
@@ -418,6 +577,8 @@ fn spawn_storage_metrics_collector(
                 if let Err(error) = update_storage_metrics(&storage_reader) {
                     warn!("Failed to update storage metrics: {error}");
                 }
+                #[cfg(feature = "rpc")]
+                update_deprecated_class_metrics();
                 tokio::time::sleep(update_interval).await;
             }
         }
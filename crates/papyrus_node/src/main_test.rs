@@ -23,6 +23,18 @@ async fn run_threads_stop() {
     assert_eq!("relative URL without a base", error.to_string());
 }
 
+#[tokio::test]
+async fn read_replica_doesnt_run_storage_writers() {
+    let mut config = NodeConfig::default();
+    let temp_dir = TempDir::new().unwrap();
+    config.storage.db_config.path_prefix = temp_dir.path().into();
+    config.read_replica = true;
+
+    // The sync is turned on by default.
+    let error = run_threads(config).await.expect_err("Should be an error.");
+    assert!(error.to_string().starts_with("A read replica doesn't write the storage."));
+}
+
 // TODO(dvir): use here metrics names from the storage instead of hard-coded ones. This will be done
 // only after changes to the metrics structure in papyrus.
 #[tokio::test]
@@ -0,0 +1,130 @@
+use std::collections::HashSet;
+
+use futures::future::BoxFuture;
+use futures::{FutureExt, StreamExt};
+use papyrus_common::class_hash::calculate_class_hash;
+use papyrus_common::pending_classes::ApiContractClass;
+use papyrus_network::network_manager::ClientResponsesManager;
+use papyrus_protobuf::sync::DataOrFin;
+use papyrus_storage::class::{ClassStorageReader, ClassStorageWriter};
+use papyrus_storage::state::StateStorageReader;
+use papyrus_storage::{StorageError, StorageReader, StorageWriter};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::ClassHash;
+use starknet_api::state::{DeclaredClasses, DeprecatedDeclaredClasses};
+
+use super::stream_builder::{BlockData, BlockNumberLimit, DataStreamBuilder};
+use super::{P2PSyncClientError, NETWORK_DATA_TIMEOUT};
+
+impl BlockData for (DeclaredClasses, DeprecatedDeclaredClasses, BlockNumber) {
+    fn write_to_storage(
+        self: Box<Self>,
+        storage_writer: &mut StorageWriter,
+    ) -> Result<(), StorageError> {
+        let (declared_classes, deprecated_declared_classes, block_number) = *self;
+        storage_writer
+            .begin_rw_txn()?
+            .append_classes(
+                block_number,
+                &declared_classes
+                    .iter()
+                    .map(|(class_hash, class)| (*class_hash, class))
+                    .collect::<Vec<_>>(),
+                &deprecated_declared_classes
+                    .iter()
+                    .map(|(class_hash, class)| (*class_hash, class))
+                    .collect::<Vec<_>>(),
+            )?
+            .commit()
+    }
+}
+
+pub(crate) struct ClassStreamBuilder;
+
+impl DataStreamBuilder<(ApiContractClass, ClassHash)> for ClassStreamBuilder {
+    type Output = (DeclaredClasses, DeprecatedDeclaredClasses, BlockNumber);
+
+    const TYPE_DESCRIPTION: &'static str = "classes";
+    const BLOCK_NUMBER_LIMIT: BlockNumberLimit = BlockNumberLimit::StateDiffMarker;
+
+    fn parse_data_for_block<'a>(
+        classes_response_manager: &'a mut ClientResponsesManager<
+            DataOrFin<(ApiContractClass, ClassHash)>,
+        >,
+        block_number: BlockNumber,
+        storage_reader: &'a StorageReader,
+    ) -> BoxFuture<'a, Result<Option<Self::Output>, P2PSyncClientError>> {
+        async move {
+            // The classes are verified against the state diff of the block, which was already
+            // verified against its header, and against their hashes.
+            let state_diff = storage_reader
+                .begin_ro_txn()?
+                .get_state_diff(block_number)?
+                .expect("A state diff with number lower than the state diff marker is missing");
+            let mut expected_classes =
+                state_diff.declared_classes.keys().copied().collect::<HashSet<_>>();
+            let mut expected_deprecated_classes =
+                state_diff.deprecated_declared_classes.into_iter().collect::<HashSet<_>>();
+            let target_classes_len = expected_classes.len() + expected_deprecated_classes.len();
+
+            let mut declared_classes = DeclaredClasses::new();
+            let mut deprecated_declared_classes = DeprecatedDeclaredClasses::new();
+            let mut current_classes_len = 0;
+            while current_classes_len < target_classes_len {
+                let maybe_class =
+                    tokio::time::timeout(NETWORK_DATA_TIMEOUT, classes_response_manager.next())
+                        .await?
+                        .ok_or(P2PSyncClientError::ReceiverChannelTerminated {
+                            type_description: Self::TYPE_DESCRIPTION,
+                        })?;
+                let Some((api_contract_class, class_hash)) = maybe_class?.0 else {
+                    if current_classes_len == 0 {
+                        return Ok(None);
+                    } else {
+                        return Err(P2PSyncClientError::NotEnoughClasses {
+                            expected: target_classes_len,
+                            actual: current_classes_len,
+                            block_number: block_number.0,
+                        });
+                    }
+                };
+                // Removing the class hash from the expected ones also rejects duplicate classes.
+                match api_contract_class {
+                    ApiContractClass::ContractClass(class)
+                        if expected_classes.remove(&class_hash) =>
+                    {
+                        let calculated_class_hash = calculate_class_hash(&class);
+                        if calculated_class_hash != class_hash {
+                            return Err(P2PSyncClientError::WrongClassHash {
+                                expected: class_hash,
+                                actual: calculated_class_hash,
+                                block_number,
+                            });
+                        }
+                        declared_classes.insert(class_hash, class);
+                    }
+                    // TODO(shahak): Verify the hashes of deprecated classes once their hash
+                    // calculation is available.
+                    ApiContractClass::DeprecatedContractClass(class)
+                        if expected_deprecated_classes.remove(&class_hash) =>
+                    {
+                        deprecated_declared_classes.insert(class_hash, class);
+                    }
+                    _ => {
+                        return Err(P2PSyncClientError::ClassNotInStateDiff {
+                            class_hash,
+                            block_number,
+                        });
+                    }
+                }
+                current_classes_len += 1;
+            }
+            Ok(Some((declared_classes, deprecated_declared_classes, block_number)))
+        }
+        .boxed()
+    }
+
+    fn get_start_block_number(storage_reader: &StorageReader) -> Result<BlockNumber, StorageError> {
+        storage_reader.begin_ro_txn()?.get_class_marker()
+    }
+}
@@ -0,0 +1,225 @@
+use assert_matches::assert_matches;
+use futures::StreamExt;
+use papyrus_common::class_hash::calculate_class_hash;
+use papyrus_common::pending_classes::ApiContractClass;
+use papyrus_protobuf::sync::{
+    BlockHashOrNumber,
+    ClassQuery,
+    DataOrFin,
+    DeclaredClass,
+    DeprecatedDeclaredClass,
+    Direction,
+    Query,
+    SignedBlockHeader,
+    StateDiffChunk,
+};
+use papyrus_storage::class::ClassStorageReader;
+use papyrus_storage::StorageReader;
+use starknet_api::block::{BlockHeader, BlockNumber};
+use starknet_api::core::{ClassHash, CompiledClassHash};
+use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
+use starknet_api::state::ContractClass;
+use starknet_types_core::felt::Felt;
+
+use super::test_utils::{
+    create_block_hashes_and_signatures,
+    setup,
+    TestArgs,
+    SLEEP_DURATION_TO_LET_SYNC_ADVANCE,
+    WAIT_PERIOD_FOR_NEW_DATA,
+};
+use super::P2PSyncClientError;
+
+const DEPRECATED_DECLARED_CLASS_HASH: ClassHash = ClassHash(Felt::TWO);
+
+fn declared_class_hash() -> ClassHash {
+    calculate_class_hash(&ContractClass::default())
+}
+
+// The chunks of the state diff of block 0, which declares a class and a deprecated class.
+fn state_diff_chunks() -> Vec<StateDiffChunk> {
+    vec![
+        StateDiffChunk::DeclaredClass(DeclaredClass {
+            class_hash: declared_class_hash(),
+            compiled_class_hash: CompiledClassHash::default(),
+        }),
+        StateDiffChunk::DeprecatedDeclaredClass(DeprecatedDeclaredClass {
+            class_hash: DEPRECATED_DECLARED_CLASS_HASH,
+        }),
+    ]
+}
+
+#[tokio::test]
+async fn class_basic_flow() {
+    let classes = vec![
+        Some((ApiContractClass::ContractClass(ContractClass::default()), declared_class_hash())),
+        Some((
+            ApiContractClass::DeprecatedContractClass(DeprecatedContractClass::default()),
+            DEPRECATED_DECLARED_CLASS_HASH,
+        )),
+        None,
+    ];
+    run_class_test(classes, |sync_result, storage_reader| {
+        assert_matches!(sync_result, None);
+        let txn = storage_reader.begin_ro_txn().unwrap();
+        assert_eq!(txn.get_class_marker().unwrap(), BlockNumber(1));
+        assert_eq!(txn.get_class(&declared_class_hash()).unwrap(), Some(ContractClass::default()));
+        assert_eq!(
+            txn.get_deprecated_class(&DEPRECATED_DECLARED_CLASS_HASH).unwrap(),
+            Some(DeprecatedContractClass::default())
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn class_not_in_state_diff() {
+    let classes = vec![Some((
+        ApiContractClass::DeprecatedContractClass(DeprecatedContractClass::default()),
+        declared_class_hash(),
+    ))];
+    run_class_test(classes, |sync_result, _| {
+        assert_matches!(
+            sync_result,
+            Some(P2PSyncClientError::ClassNotInStateDiff { class_hash, block_number })
+            if class_hash == declared_class_hash() && block_number == BlockNumber(0)
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn class_with_wrong_hash() {
+    let class = ContractClass { abi: "[]".to_owned(), ..Default::default() };
+    let classes =
+        vec![Some((ApiContractClass::ContractClass(class.clone()), declared_class_hash()))];
+    run_class_test(classes, |sync_result, _| {
+        assert_matches!(
+            sync_result,
+            Some(P2PSyncClientError::WrongClassHash { expected, actual, block_number })
+            if expected == declared_class_hash()
+                && actual == calculate_class_hash(&class)
+                && block_number == BlockNumber(0)
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn class_received_twice() {
+    let classes = vec![
+        Some((ApiContractClass::ContractClass(ContractClass::default()), declared_class_hash())),
+        Some((ApiContractClass::ContractClass(ContractClass::default()), declared_class_hash())),
+    ];
+    run_class_test(classes, |sync_result, _| {
+        assert_matches!(sync_result, Some(P2PSyncClientError::ClassNotInStateDiff { .. }));
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn class_stopped_in_middle() {
+    let classes = vec![
+        Some((ApiContractClass::ContractClass(ContractClass::default()), declared_class_hash())),
+        None,
+    ];
+    run_class_test(classes, |sync_result, storage_reader| {
+        assert_matches!(
+            sync_result,
+            Some(P2PSyncClientError::NotEnoughClasses { expected: 2, actual: 1, block_number: 0 })
+        );
+        let txn = storage_reader.begin_ro_txn().unwrap();
+        assert_eq!(txn.get_class_marker().unwrap(), BlockNumber(0));
+    })
+    .await;
+}
+
+// Syncs the header and the state diff of block 0, sends the given classes as the response to the
+// class query and passes the error of the sync (None if it didn't fail) to the validator.
+async fn run_class_test(
+    classes: Vec<Option<(ApiContractClass, ClassHash)>>,
+    validator: impl Fn(Option<P2PSyncClientError>, &StorageReader),
+) {
+    let TestArgs {
+        p2p_sync,
+        storage_reader,
+        mut header_receiver,
+        mut state_diff_receiver,
+        mut class_receiver,
+        // The test will fail if we drop these
+        transaction_receiver: _mock_transaction_responses_manager,
+        ..
+    } = setup();
+
+    let (block_hash, block_signature) = *create_block_hashes_and_signatures(1).first().unwrap();
+    let validation_storage_reader = storage_reader.clone();
+
+    // Create a future that will receive queries, send responses and validate the results.
+    let parse_queries_future = async move {
+        let mut mock_header_responses_manager = header_receiver.next().await.unwrap();
+        mock_header_responses_manager
+            .send_response(DataOrFin(Some(SignedBlockHeader {
+                block_header: BlockHeader {
+                    block_number: BlockNumber(0),
+                    block_hash,
+                    state_diff_length: Some(state_diff_chunks().len()),
+                    ..Default::default()
+                },
+                signatures: vec![block_signature],
+                precommits: vec![],
+            })))
+            .await
+            .unwrap();
+
+        // We wait for the header sync to write the new headers.
+        tokio::time::sleep(SLEEP_DURATION_TO_LET_SYNC_ADVANCE).await;
+
+        // Simulate time has passed so that state diff sync will resend query after it waited for
+        // new header
+        tokio::time::pause();
+        tokio::time::advance(WAIT_PERIOD_FOR_NEW_DATA).await;
+        tokio::time::resume();
+
+        let mut mock_state_diff_responses_manager = state_diff_receiver.next().await.unwrap();
+        for state_diff_chunk in state_diff_chunks() {
+            mock_state_diff_responses_manager
+                .send_response(DataOrFin(Some(state_diff_chunk)))
+                .await
+                .unwrap();
+        }
+
+        // We wait for the state diff sync to write the new state diff.
+        tokio::time::sleep(SLEEP_DURATION_TO_LET_SYNC_ADVANCE).await;
+
+        // Simulate time has passed so that class sync will resend query after it waited for new
+        // state diff
+        tokio::time::pause();
+        tokio::time::advance(WAIT_PERIOD_FOR_NEW_DATA).await;
+        tokio::time::resume();
+
+        let mut mock_class_responses_manager = class_receiver.next().await.unwrap();
+        assert_eq!(
+            *mock_class_responses_manager.query(),
+            Ok(ClassQuery(Query {
+                start_block: BlockHashOrNumber::Number(BlockNumber(0)),
+                direction: Direction::Forward,
+                limit: 1,
+                step: 1,
+            }))
+        );
+        for class in classes {
+            mock_class_responses_manager.send_response(DataOrFin(class)).await.unwrap();
+        }
+        // We wait for the class sync to write the classes or to fail.
+        tokio::time::sleep(SLEEP_DURATION_TO_LET_SYNC_ADVANCE).await;
+    };
+
+    tokio::select! {
+        sync_result = p2p_sync.run() => {
+            validator(Some(sync_result.unwrap_err()), &validation_storage_reader);
+        }
+        _ = parse_queries_future => {
+            validator(None, &validation_storage_reader);
+        }
+    }
+}
@@ -3,6 +3,7 @@ use futures::future::BoxFuture;
 use futures::{FutureExt, StreamExt};
 use metrics::gauge;
 use papyrus_common::metrics as papyrus_metrics;
+use papyrus_consensus::decision_certificate::DecisionCertificate;
 use papyrus_network::network_manager::ClientResponsesManager;
 use papyrus_protobuf::sync::{DataOrFin, SignedBlockHeader};
 use papyrus_storage::header::{HeaderStorageReader, HeaderStorageWriter};
@@ -11,9 +12,35 @@ use starknet_api::block::BlockNumber;
 use tracing::debug;
 
 use super::stream_builder::{BlockData, BlockNumberLimit, DataStreamBuilder};
-use super::{P2PSyncClientError, ALLOWED_SIGNATURES_LENGTH, NETWORK_DATA_TIMEOUT};
+use super::{
+    P2PSyncClientError,
+    ValidatorSetProvider,
+    ALLOWED_SIGNATURES_LENGTH,
+    NETWORK_DATA_TIMEOUT,
+};
 
 impl BlockData for SignedBlockHeader {
+    fn verify_decision_certificate(
+        &self,
+        validator_set_provider: &dyn ValidatorSetProvider,
+    ) -> Result<(), P2PSyncClientError> {
+        let block_number = self.block_header.block_number;
+        let Some(validators) = validator_set_provider.validators(block_number) else {
+            return Ok(());
+        };
+        // The certificate is of the round of its precommits. A certificate without precommits
+        // fails the verification for not having a quorum.
+        let certificate = DecisionCertificate {
+            height: block_number,
+            round: self.precommits.first().map_or(0, |precommit| precommit.round),
+            block_hash: self.block_header.block_hash,
+            precommits: self.precommits.clone(),
+        };
+        certificate
+            .verify(&validators)
+            .map_err(|error| P2PSyncClientError::InvalidDecisionCertificate { block_number, error })
+    }
+
     fn write_to_storage(
         self: Box<Self>,
         storage_writer: &mut StorageWriter,
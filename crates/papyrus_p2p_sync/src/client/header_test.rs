@@ -1,4 +1,11 @@
+use std::sync::Arc;
+
+use assert_matches::assert_matches;
+use futures::future::pending;
 use futures::StreamExt;
+use papyrus_consensus::decision_certificate::DecisionCertificateError;
+use papyrus_consensus::types::ValidatorId;
+use papyrus_protobuf::consensus::{Vote, VoteType};
 use papyrus_protobuf::sync::{
     BlockHashOrNumber,
     DataOrFin,
@@ -8,7 +15,8 @@ use papyrus_protobuf::sync::{
     SignedBlockHeader,
 };
 use papyrus_storage::header::HeaderStorageReader;
-use starknet_api::block::{BlockHeader, BlockNumber};
+use starknet_api::block::{BlockHash, BlockHeader, BlockNumber};
+use starknet_api::core::ContractAddress;
 use tokio::time::timeout;
 
 use super::test_utils::{
@@ -20,6 +28,7 @@ use super::test_utils::{
     TIMEOUT_FOR_NEW_QUERY_AFTER_PARTIAL_RESPONSE,
     WAIT_PERIOD_FOR_NEW_DATA,
 };
+use super::{P2PSyncClientError, ValidatorSetProvider};
 
 #[tokio::test]
 async fn signed_headers_basic_flow() {
@@ -32,6 +41,7 @@ async fn signed_headers_basic_flow() {
         // The test will fail if we drop these
         state_diff_receiver: _state_diff_query_receiver,
         transaction_receiver: _transaction_query_receiver,
+        class_receiver: _class_query_receiver,
         ..
     } = setup();
     let block_hashes_and_signatures =
@@ -71,6 +81,7 @@ async fn signed_headers_basic_flow() {
                             ..Default::default()
                         },
                         signatures: vec![*block_signature],
+                        precommits: vec![],
                     })))
                     .await
                     .unwrap();
@@ -114,6 +125,7 @@ async fn sync_sends_new_header_query_if_it_got_partial_responses() {
         // The test will fail if we drop these
         state_diff_receiver: _state_diff_query_receiver,
         transaction_receiver: _transaction_query_receiver,
+        class_receiver: _class_query_receiver,
         ..
     } = setup();
     let block_hashes_and_signatures = create_block_hashes_and_signatures(NUM_ACTUAL_RESPONSES);
@@ -132,6 +144,7 @@ async fn sync_sends_new_header_query_if_it_got_partial_responses() {
                         ..Default::default()
                     },
                     signatures: vec![signature],
+                    precommits: vec![],
                 })))
                 .await
                 .unwrap();
@@ -172,4 +185,83 @@ async fn sync_sends_new_header_query_if_it_got_partial_responses() {
     }
 }
 
+// Consensus decided the blocks from 1 on, with 4 validators.
+struct TestValidatorSet;
+
+impl ValidatorSetProvider for TestValidatorSet {
+    fn validators(&self, height: BlockNumber) -> Option<Vec<ValidatorId>> {
+        (height >= BlockNumber(1)).then(|| (0..4_u64).map(ContractAddress::from).collect())
+    }
+}
+
+fn precommits(block_number: BlockNumber, block_hash: BlockHash, num_voters: u64) -> Vec<Vote> {
+    (0..num_voters)
+        .map(|voter| Vote {
+            vote_type: VoteType::Precommit,
+            height: block_number.0,
+            round: 0,
+            block_hash: Some(block_hash),
+            voter: ContractAddress::from(voter),
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn headers_without_quorum_of_precommits_are_rejected() {
+    let TestArgs {
+        p2p_sync,
+        storage_reader,
+        mut header_receiver,
+        // The test will fail if we drop these
+        state_diff_receiver: _state_diff_query_receiver,
+        transaction_receiver: _transaction_query_receiver,
+        class_receiver: _class_query_receiver,
+        ..
+    } = setup();
+    let p2p_sync = p2p_sync.with_validator_set_provider(Arc::new(TestValidatorSet));
+    let block_hashes_and_signatures = create_block_hashes_and_signatures(3);
+
+    // Block 0 wasn't decided by consensus, block 1 is certified by a quorum of the validators and
+    // block 2 is precommitted by only 2 of them.
+    let send_headers_future = async move {
+        let mut mock_header_responses_manager = header_receiver.next().await.unwrap();
+        for (i, (block_hash, signature)) in block_hashes_and_signatures.into_iter().enumerate() {
+            let block_number = BlockNumber(i.try_into().unwrap());
+            let num_voters = match i {
+                0 => 0,
+                1 => 3,
+                _ => 2,
+            };
+            mock_header_responses_manager
+                .send_response(DataOrFin(Some(SignedBlockHeader {
+                    block_header: BlockHeader {
+                        block_number,
+                        block_hash,
+                        state_diff_length: Some(0),
+                        ..Default::default()
+                    },
+                    signatures: vec![signature],
+                    precommits: precommits(block_number, block_hash, num_voters),
+                })))
+                .await
+                .unwrap();
+        }
+        pending::<()>().await
+    };
+
+    let sync_result = tokio::select! {
+        sync_result = p2p_sync.run() => sync_result,
+        _ = send_headers_future => unreachable!("Sending the headers should never end"),
+    };
+    assert_matches!(
+        sync_result,
+        Err(P2PSyncClientError::InvalidDecisionCertificate {
+            block_number: BlockNumber(2),
+            error: DecisionCertificateError::NoQuorum { num_precommits: 2, quorum: 3 },
+        })
+    );
+    let txn = storage_reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_header_marker().unwrap(), BlockNumber(2));
+}
+
 // TODO(shahak): Add negative tests.
@@ -1,6 +1,12 @@
+mod class;
+#[cfg(test)]
+mod class_test;
 mod header;
 #[cfg(test)]
 mod header_test;
+mod snap_sync;
+#[cfg(test)]
+mod snap_sync_test;
 mod state_diff;
 #[cfg(test)]
 mod state_diff_test;
@@ -10,32 +16,54 @@ mod test_utils;
 mod transaction;
 
 use std::collections::BTreeMap;
+use std::future::pending;
+use std::sync::Arc;
 use std::time::Duration;
 
+use class::ClassStreamBuilder;
 use futures::channel::mpsc::SendError;
 use futures::Stream;
 use header::HeaderStreamBuilder;
+use papyrus_base_layer::ethereum_base_layer_contract::EthereumBaseLayerContract;
+use papyrus_base_layer::l1_finality::{L1FinalityTracker, L1FinalityTrackerConfig};
+use papyrus_common::pending_classes::ApiContractClass;
 use papyrus_config::converters::deserialize_seconds_to_duration;
-use papyrus_config::dumping::{ser_optional_param, ser_param, SerializeConfig};
+use papyrus_config::dumping::{
+    ser_optional_param,
+    ser_optional_sub_config,
+    ser_param,
+    SerializeConfig,
+};
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use papyrus_consensus::decision_certificate::DecisionCertificateError;
+use papyrus_consensus::types::ValidatorId;
 use papyrus_network::network_manager::SqmrClientSender;
 use papyrus_protobuf::converters::ProtobufConversionError;
 use papyrus_protobuf::sync::{
+    ClassQuery,
     DataOrFin,
     HeaderQuery,
     SignedBlockHeader,
     StateDiffChunk,
     StateDiffQuery,
+    StateSnapshotChunk,
+    StateSnapshotQuery,
     TransactionQuery,
 };
+use papyrus_storage::body::indices::{backfill_indices, index_backfill_interval};
+use papyrus_storage::header::HeaderStorageReader;
 use papyrus_storage::{StorageError, StorageReader, StorageWriter};
 use serde::{Deserialize, Serialize};
-use starknet_api::block::{BlockNumber, BlockSignature};
+use snap_sync::run_snap_sync;
+pub use snap_sync::SnapSyncConfig;
+use starknet_api::block::{BlockHash, BlockNumber, BlockSignature};
+use starknet_api::core::{ClassHash, GlobalRoot};
 use starknet_api::transaction::FullTransaction;
+use starknet_committer::block_committer::errors::BlockCommitmentError;
 use state_diff::StateDiffStreamBuilder;
 use stream_builder::{DataStreamBuilder, DataStreamResult};
 use tokio_stream::StreamExt;
-use tracing::instrument;
+use tracing::{instrument, warn};
 use transaction::TransactionStreamFactory;
 const STEP: u64 = 1;
 const ALLOWED_SIGNATURES_LENGTH: usize = 1;
@@ -47,10 +75,14 @@ pub struct P2PSyncClientConfig {
     pub num_headers_per_query: u64,
     pub num_block_state_diffs_per_query: u64,
     pub num_transactions_per_query: u64,
+    pub num_block_classes_per_query: u64,
     #[serde(deserialize_with = "deserialize_seconds_to_duration")]
     pub wait_period_for_new_data: Duration,
     pub buffer_size: usize,
     pub stop_sync_at_block_number: Option<BlockNumber>,
+    pub snap_sync: Option<SnapSyncConfig>,
+    pub verify_decision_certificates: bool,
+    pub l1_finality: Option<L1FinalityTrackerConfig>,
 }
 
 impl SerializeConfig for P2PSyncClientConfig {
@@ -75,6 +107,13 @@ impl SerializeConfig for P2PSyncClientConfig {
                  iteration.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "num_block_classes_per_query",
+                &self.num_block_classes_per_query,
+                "The maximum amount of blocks to ask their declared classes from peers in each \
+                 iteration.",
+                ParamPrivacyInput::Public,
+            ),
             ser_param(
                 "wait_period_for_new_data",
                 &self.wait_period_for_new_data.as_secs(),
@@ -88,6 +127,14 @@ impl SerializeConfig for P2PSyncClientConfig {
                 "Size of the buffer for read from the storage and for incoming responses.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "verify_decision_certificates",
+                &self.verify_decision_certificates,
+                "Whether to verify that the headers of the blocks decided by consensus are \
+                 certified by the precommits of a quorum of the validators. Requires consensus to \
+                 be configured.",
+                ParamPrivacyInput::Public,
+            ),
         ]);
         config.extend(ser_optional_param(
             &self.stop_sync_at_block_number,
@@ -97,6 +144,8 @@ impl SerializeConfig for P2PSyncClientConfig {
              profiling on the node.",
             ParamPrivacyInput::Public,
         ));
+        config.extend(ser_optional_sub_config(&self.snap_sync, "snap_sync"));
+        config.extend(ser_optional_sub_config(&self.l1_finality, "l1_finality"));
         config
     }
 }
@@ -109,10 +158,15 @@ impl Default for P2PSyncClientConfig {
             // messages in the network buffers.
             num_block_state_diffs_per_query: 100,
             num_transactions_per_query: 100,
+            // Classes are big, so we ask for the classes of a few blocks in each query.
+            num_block_classes_per_query: 10,
             wait_period_for_new_data: Duration::from_secs(5),
             // TODO(eitan): split this by protocol
             buffer_size: 100000,
             stop_sync_at_block_number: None,
+            snap_sync: None,
+            verify_decision_certificates: false,
+            l1_finality: None,
         }
     }
 }
@@ -132,6 +186,27 @@ pub enum P2PSyncClientError {
     )]
     // TODO(eitan): Remove this and report to network on invalid data once that's possible.
     NotEnoughTransactions { expected: usize, actual: usize, block_number: u64 },
+    // TODO(shahak): Remove this and report to network on invalid data once that's possible.
+    #[error(
+        "Expected to receive {expected} classes for {block_number} from the network. Got {actual} \
+         instead."
+    )]
+    NotEnoughClasses { expected: usize, actual: usize, block_number: u64 },
+    // TODO(shahak): Remove this and report to network on invalid data once that's possible.
+    #[error(
+        "Received the class {class_hash} for {block_number} from the network, but it's not \
+         declared with this type in the block's state diff, or it was received twice."
+    )]
+    ClassNotInStateDiff { class_hash: ClassHash, block_number: BlockNumber },
+    // TODO(shahak): Remove this and report to network on invalid data once that's possible.
+    #[error(
+        "Received the class {expected} for {block_number} from the network, but its hash is \
+         {actual}."
+    )]
+    WrongClassHash { expected: ClassHash, actual: ClassHash, block_number: BlockNumber },
+    // TODO: Remove this and report to network on invalid data once that's possible.
+    #[error("The header of {block_number} isn't certified by consensus: {error}")]
+    InvalidDecisionCertificate { block_number: BlockNumber, error: DecisionCertificateError },
     #[error("Expected to receive one signature from the network. got {signatures:?} instead.")]
     // TODO(shahak): Remove this and report to network on invalid data once that's possible.
     // Right now we support only one signature. In the future we will support many signatures.
@@ -162,6 +237,39 @@ pub enum P2PSyncClientError {
          field."
     )]
     OldHeaderInStorage { block_number: BlockNumber, missing_field: &'static str },
+    // TODO(shahak): Remove this and report to network on invalid data once that's possible.
+    #[error("The network didn't return the header of the snap sync block {block_number}.")]
+    SnapSyncMissingHeader { block_number: BlockNumber },
+    #[error("Expected the snap sync block to have the hash {expected}. Got {actual} instead.")]
+    SnapSyncWrongBlockHash { expected: BlockHash, actual: BlockHash },
+    #[error(
+        "Can't verify the hash of the snap sync block {block_number}. Snap sync is supported only \
+         from blocks of Starknet version 0.13.2 and above."
+    )]
+    SnapSyncUnsupportedBlock { block_number: BlockNumber },
+    // TODO(shahak): Remove this and report to network on invalid data once that's possible.
+    #[error(
+        "The state downloaded for snap sync has the root {actual}, but the block's state root is \
+         {expected}."
+    )]
+    SnapSyncWrongStateRoot { expected: GlobalRoot, actual: GlobalRoot },
+    // TODO(shahak): Remove this and report to network on invalid data once that's possible.
+    #[error("Received a state snapshot part of a different type than the one asked for.")]
+    SnapSyncUnexpectedChunk,
+    // TODO(shahak): Remove this and report to network on invalid data once that's possible.
+    #[error("Received state snapshot parts that are not in ascending order from the query start.")]
+    SnapSyncUnorderedChunks,
+    // TODO(shahak): Remove this and report to network on invalid data once that's possible.
+    #[error(
+        "Received the class {class_hash} for snap sync with a compiled class hash although it's a \
+         deprecated class, or without one although it's not."
+    )]
+    SnapSyncInvalidClass { class_hash: ClassHash },
+    // TODO(shahak): Remove this and report to network on invalid data once that's possible.
+    #[error("Received the class {expected} for snap sync, but its hash is {actual}.")]
+    SnapSyncWrongClassHash { expected: ClassHash, actual: ClassHash },
+    #[error(transparent)]
+    BlockCommitmentError(#[from] BlockCommitmentError),
     #[error("The sender end of the response receivers for {type_description:?} was closed.")]
     ReceiverChannelTerminated { type_description: &'static str },
     #[error(transparent)]
@@ -175,11 +283,15 @@ pub enum P2PSyncClientError {
 type HeaderSqmrSender = SqmrClientSender<HeaderQuery, DataOrFin<SignedBlockHeader>>;
 type StateSqmrDiffSender = SqmrClientSender<StateDiffQuery, DataOrFin<StateDiffChunk>>;
 type TransactionSqmrSender = SqmrClientSender<TransactionQuery, DataOrFin<FullTransaction>>;
+type ClassSqmrSender = SqmrClientSender<ClassQuery, DataOrFin<(ApiContractClass, ClassHash)>>;
+type StateSnapshotSqmrSender = SqmrClientSender<StateSnapshotQuery, DataOrFin<StateSnapshotChunk>>;
 
 pub struct P2PSyncClientChannels {
     header_sender: HeaderSqmrSender,
     state_diff_sender: StateSqmrDiffSender,
     transaction_sender: TransactionSqmrSender,
+    class_sender: ClassSqmrSender,
+    state_snapshot_sender: StateSnapshotSqmrSender,
 }
 
 impl P2PSyncClientChannels {
@@ -187,8 +299,16 @@ impl P2PSyncClientChannels {
         header_sender: HeaderSqmrSender,
         state_diff_sender: StateSqmrDiffSender,
         transaction_sender: TransactionSqmrSender,
+        class_sender: ClassSqmrSender,
+        state_snapshot_sender: StateSnapshotSqmrSender,
     ) -> Self {
-        Self { header_sender, state_diff_sender, transaction_sender }
+        Self {
+            header_sender,
+            state_diff_sender,
+            transaction_sender,
+            class_sender,
+            state_snapshot_sender,
+        }
     }
     pub(crate) fn create_stream(
         self,
@@ -219,15 +339,33 @@ impl P2PSyncClientChannels {
             config.stop_sync_at_block_number,
         );
 
-        header_stream.merge(state_diff_stream).merge(transaction_stream)
+        let class_stream = ClassStreamBuilder::create_stream(
+            self.class_sender,
+            storage_reader.clone(),
+            config.wait_period_for_new_data,
+            config.num_block_classes_per_query,
+            config.stop_sync_at_block_number,
+        );
+
+        header_stream.merge(state_diff_stream).merge(transaction_stream).merge(class_stream)
     }
 }
 
+/// Provides the validators of the heights decided by consensus, to verify the decision
+/// certificates of the synced headers against.
+pub trait ValidatorSetProvider: Send + Sync {
+    /// Returns the validators of the given height, or `None` if the height wasn't decided by
+    /// consensus, in which case its header isn't certified.
+    fn validators(&self, height: BlockNumber) -> Option<Vec<ValidatorId>>;
+}
+
 pub struct P2PSyncClient {
     config: P2PSyncClientConfig,
     storage_reader: StorageReader,
     storage_writer: StorageWriter,
     p2p_sync_channels: P2PSyncClientChannels,
+    validator_set_provider: Option<Arc<dyn ValidatorSetProvider>>,
+    l1_finality_tracker: Option<L1FinalityTracker<EthereumBaseLayerContract>>,
 }
 
 impl P2PSyncClient {
@@ -237,17 +375,99 @@ impl P2PSyncClient {
         storage_writer: StorageWriter,
         p2p_sync_channels: P2PSyncClientChannels,
     ) -> Self {
-        Self { config, storage_reader, storage_writer, p2p_sync_channels }
+        Self {
+            config,
+            storage_reader,
+            storage_writer,
+            p2p_sync_channels,
+            validator_set_provider: None,
+            l1_finality_tracker: None,
+        }
+    }
+
+    /// Verifies the decision certificates of the synced headers against the validators of the
+    /// given provider before writing them to the storage.
+    pub fn with_validator_set_provider(
+        mut self,
+        validator_set_provider: Arc<dyn ValidatorSetProvider>,
+    ) -> Self {
+        self.validator_set_provider = Some(validator_set_provider);
+        self
+    }
+
+    /// Follows the finality of the synced blocks on the base layer with the given tracker, which
+    /// writes through the sync's storage writer.
+    pub fn with_l1_finality_tracker(
+        mut self,
+        l1_finality_tracker: L1FinalityTracker<EthereumBaseLayerContract>,
+    ) -> Self {
+        self.l1_finality_tracker = Some(l1_finality_tracker);
+        self
     }
 
     #[instrument(skip(self), level = "debug", err)]
     pub async fn run(mut self) -> Result<(), P2PSyncClientError> {
+        // Snap sync only starts a new node. Once there are blocks, the sync continues from them.
+        if let Some(snap_sync_config) = self.config.snap_sync {
+            if self.storage_reader.begin_ro_txn()?.get_header_marker()? == BlockNumber(0) {
+                run_snap_sync(
+                    snap_sync_config,
+                    &mut self.p2p_sync_channels.header_sender,
+                    &mut self.p2p_sync_channels.state_snapshot_sender,
+                    &mut self.storage_writer,
+                )
+                .await?;
+            }
+        }
+
         let mut data_stream =
             self.p2p_sync_channels.create_stream(self.storage_reader.clone(), self.config);
 
+        let mut l1_finality_interval = self
+            .l1_finality_tracker
+            .as_ref()
+            .map(|tracker| tokio::time::interval(tracker.polling_interval()));
+        // Backfills the storage indices of the stored blocks, if they weren't built on opening the
+        // storage, until they cover all the blocks.
+        let mut backfill_interval =
+            index_backfill_interval(&self.storage_reader).map(tokio::time::interval);
+
         loop {
-            let data = data_stream.next().await.expect("Sync data stream should never end")?;
-            data.write_to_storage(&mut self.storage_writer)?;
+            let l1_finality_tick = async {
+                match &mut l1_finality_interval {
+                    Some(interval) => interval.tick().await,
+                    None => pending().await,
+                }
+            };
+            let backfill_tick = async {
+                match &mut backfill_interval {
+                    Some(interval) => interval.tick().await,
+                    None => pending().await,
+                }
+            };
+            tokio::select! {
+                data = data_stream.next() => {
+                    let data = data.expect("Sync data stream should never end")?;
+                    if let Some(validator_set_provider) = &self.validator_set_provider {
+                        data.verify_decision_certificate(validator_set_provider.as_ref())?;
+                    }
+                    data.write_to_storage(&mut self.storage_writer)?;
+                }
+                _ = l1_finality_tick => {
+                    let Some(tracker) = &mut self.l1_finality_tracker else {
+                        continue;
+                    };
+                    if let Err(err) = tracker.update_accepted_on_l1(&mut self.storage_writer).await
+                    {
+                        warn!("Failed to update the blocks that are accepted on L1: {err}");
+                    }
+                }
+                _ = backfill_tick => {
+                    if !backfill_indices(&mut self.storage_writer)? {
+                        backfill_interval = None;
+                    }
+                }
+            }
         }
     }
 }
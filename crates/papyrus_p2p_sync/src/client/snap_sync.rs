@@ -0,0 +1,482 @@
+use std::collections::{BTreeMap, HashMap};
+
+use indexmap::IndexMap;
+use papyrus_common::class_hash::calculate_class_hash;
+use papyrus_common::pending_classes::ApiContractClass;
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use papyrus_network::network_manager::ClientResponsesManager;
+use papyrus_protobuf::converters::ProtobufConversionError;
+use papyrus_protobuf::sync::{
+    BlockHashOrNumber,
+    DataOrFin,
+    Direction,
+    HeaderQuery,
+    Query,
+    SignedBlockHeader,
+    StateSnapshotChunk,
+    StateSnapshotQuery,
+    StateSnapshotStart,
+};
+use papyrus_storage::snap_sync::SnapSyncStorageWriter;
+use papyrus_storage::StorageWriter;
+use serde::{Deserialize, Serialize};
+use starknet_api::block::{BlockHash, BlockHeader, BlockNumber};
+use starknet_api::block_hash::block_hash_calculator::calculate_block_hash_of_header;
+use starknet_api::core::{ClassHash, ContractAddress, GlobalRoot};
+use starknet_api::state::{StorageKey, ThinStateDiff};
+use starknet_committer::block_committer::commit::commit_state_diff;
+use starknet_committer::block_committer::errors::BlockCommitmentError;
+use starknet_committer::block_committer::input::{
+    ConfigImpl,
+    ContractAddress as CommitterContractAddress,
+    StarknetStorageKey,
+    StarknetStorageValue,
+    StateDiff,
+};
+use starknet_committer::hash_function::hash::calculate_global_root_hash;
+use starknet_committer::patricia_merkle_tree::types::{
+    ClassHash as CommitterClassHash,
+    CompiledClassHash as CommitterCompiledClassHash,
+    Nonce as CommitterNonce,
+};
+use starknet_patricia::hash::hash_trait::HashOutput;
+use starknet_patricia::patricia_merkle_tree::filled_tree::tree::FilledTree;
+use starknet_patricia::storage::map_storage::MapStorage;
+use starknet_patricia::storage::storage_trait::Storage;
+use starknet_types_core::felt::Felt;
+use tokio_stream::StreamExt;
+use tracing::level_filters::LevelFilter;
+use tracing::{debug, info};
+
+use super::{
+    HeaderSqmrSender,
+    P2PSyncClientError,
+    StateSnapshotSqmrSender,
+    ALLOWED_SIGNATURES_LENGTH,
+    NETWORK_DATA_TIMEOUT,
+    STEP,
+};
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SnapSyncConfig {
+    pub block_number: BlockNumber,
+    pub block_hash: BlockHash,
+    pub num_contracts_per_query: u64,
+    pub num_classes_per_query: u64,
+}
+
+impl Default for SnapSyncConfig {
+    fn default() -> Self {
+        SnapSyncConfig {
+            block_number: BlockNumber::default(),
+            block_hash: BlockHash::default(),
+            num_contracts_per_query: 1000,
+            // Classes are big, so we ask for a few of them in each query.
+            num_classes_per_query: 10,
+        }
+    }
+}
+
+impl SerializeConfig for SnapSyncConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "block_number",
+                &self.block_number,
+                "The block whose state is downloaded. Blocks after it are synced one by one.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "block_hash",
+                &self.block_hash,
+                "The trusted hash of the block whose state is downloaded. The downloaded state is \
+                 verified against the state root of this block.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "num_contracts_per_query",
+                &self.num_contracts_per_query,
+                "The maximum amount of contracts to ask from peers in each iteration.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "num_classes_per_query",
+                &self.num_classes_per_query,
+                "The maximum amount of classes to ask from peers in each iteration.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+// Downloads the header of the configured block and the state after it, verifies them against the
+// configured block hash and writes them to the storage. The state is downloaded in pages, each of
+// which is committed on top of the previous ones and written to the storage when it arrives, and
+// the root of the whole state is verified against the header at the end.
+pub(crate) async fn run_snap_sync(
+    config: SnapSyncConfig,
+    header_sender: &mut HeaderSqmrSender,
+    state_snapshot_sender: &mut StateSnapshotSqmrSender,
+    storage_writer: &mut StorageWriter,
+) -> Result<(), P2PSyncClientError> {
+    info!("Snap syncing the state after block {}.", config.block_number);
+    // A previous snap sync may have failed after writing a part of its state.
+    storage_writer.begin_rw_txn()?.discard_state_snapshot_chunks()?.commit()?;
+
+    let signed_header = download_header(header_sender, config.block_number).await?;
+    let header = &signed_header.block_header;
+    verify_block_hash(header, config.block_hash)?;
+
+    let mut state_commitment = StateCommitment::default();
+    download_contracts(state_snapshot_sender, storage_writer, config, &mut state_commitment)
+        .await?;
+    let declarations =
+        download_classes(state_snapshot_sender, storage_writer, config, &mut state_commitment)
+            .await?;
+    let state_root = state_commitment.global_root();
+    if state_root != header.state_root {
+        return Err(P2PSyncClientError::SnapSyncWrongStateRoot {
+            expected: header.state_root,
+            actual: state_root,
+        });
+    }
+
+    storage_writer
+        .begin_rw_txn()?
+        .write_state_snapshot(header, &signed_header.signatures[0], declarations, &[], &[])?
+        .commit()?;
+    info!("Snap synced the state after block {}.", config.block_number);
+    Ok(())
+}
+
+async fn download_header(
+    header_sender: &mut HeaderSqmrSender,
+    block_number: BlockNumber,
+) -> Result<SignedBlockHeader, P2PSyncClientError> {
+    let mut client_response_manager = header_sender
+        .send_new_query(HeaderQuery(Query {
+            start_block: BlockHashOrNumber::Number(block_number),
+            direction: Direction::Forward,
+            limit: 1,
+            step: STEP,
+        }))
+        .await?;
+    let mut signed_headers = Vec::new();
+    while let Some(signed_header) = next_response(&mut client_response_manager, "headers").await? {
+        signed_headers.push(signed_header);
+    }
+    let signed_header = match signed_headers.len() {
+        0 => return Err(P2PSyncClientError::SnapSyncMissingHeader { block_number }),
+        1 => signed_headers.remove(0),
+        _ => return Err(P2PSyncClientError::TooManyResponses),
+    };
+    if signed_header.block_header.block_number != block_number {
+        return Err(P2PSyncClientError::HeadersUnordered {
+            expected_block_number: block_number,
+            actual_block_number: signed_header.block_header.block_number,
+        });
+    }
+    if signed_header.signatures.len() != ALLOWED_SIGNATURES_LENGTH {
+        return Err(P2PSyncClientError::WrongSignaturesLength {
+            signatures: signed_header.signatures,
+        });
+    }
+    Ok(signed_header)
+}
+
+fn verify_block_hash(
+    header: &BlockHeader,
+    block_hash: BlockHash,
+) -> Result<(), P2PSyncClientError> {
+    if header.block_hash != block_hash {
+        return Err(P2PSyncClientError::SnapSyncWrongBlockHash {
+            expected: block_hash,
+            actual: header.block_hash,
+        });
+    }
+    // The block hash commits to the state root, so the state is verified through the header.
+    let calculated_block_hash = calculate_block_hash_of_header(header).ok_or(
+        P2PSyncClientError::SnapSyncUnsupportedBlock { block_number: header.block_number },
+    )?;
+    if calculated_block_hash != block_hash {
+        return Err(P2PSyncClientError::SnapSyncWrongBlockHash {
+            expected: block_hash,
+            actual: calculated_block_hash,
+        });
+    }
+    Ok(())
+}
+
+// Downloads the deployed contracts, nonces and storage of the state, and commits and writes them
+// page by page.
+async fn download_contracts(
+    state_snapshot_sender: &mut StateSnapshotSqmrSender,
+    storage_writer: &mut StorageWriter,
+    config: SnapSyncConfig,
+    state_commitment: &mut StateCommitment,
+) -> Result<(), P2PSyncClientError> {
+    let mut start = ContractAddress::default();
+    loop {
+        let mut client_response_manager = state_snapshot_sender
+            .send_new_query(StateSnapshotQuery {
+                block_number: config.block_number,
+                start: StateSnapshotStart::Contracts(start),
+                limit: config.num_contracts_per_query,
+            })
+            .await?;
+        let mut page = ThinStateDiff::default();
+        let mut n_contracts = 0;
+        let mut last_address = None;
+        while let Some(chunk) =
+            next_response(&mut client_response_manager, "state snapshot").await?
+        {
+            let StateSnapshotChunk::Contract(contract_diff) = chunk else {
+                return Err(P2PSyncClientError::SnapSyncUnexpectedChunk);
+            };
+            let address = contract_diff.contract_address;
+            // The storage of a big contract is sent in consecutive chunks, and only the first one
+            // has its class hash and nonce.
+            if last_address == Some(address) {
+                if contract_diff.class_hash.is_some() || contract_diff.nonce.is_some() {
+                    return Err(P2PSyncClientError::SnapSyncUnorderedChunks);
+                }
+            } else {
+                if address < start
+                    || last_address.is_some_and(|last_address| address <= last_address)
+                {
+                    return Err(P2PSyncClientError::SnapSyncUnorderedChunks);
+                }
+                n_contracts += 1;
+                if n_contracts > config.num_contracts_per_query {
+                    return Err(P2PSyncClientError::TooManyResponses);
+                }
+            }
+            last_address = Some(address);
+            if let Some(class_hash) = contract_diff.class_hash {
+                page.deployed_contracts.insert(address, class_hash);
+            }
+            if let Some(nonce) = contract_diff.nonce {
+                page.nonces.insert(address, nonce);
+            }
+            if !contract_diff.storage_diffs.is_empty() {
+                page.storage_diffs.entry(address).or_default().extend(contract_diff.storage_diffs);
+            }
+        }
+        let Some(last_address) = last_address else {
+            return Ok(());
+        };
+        state_commitment.commit(&page).await?;
+        storage_writer
+            .begin_rw_txn()?
+            .write_state_snapshot_chunk(config.block_number, &page, &[], &[])?
+            .commit()?;
+        debug!("Downloaded the state of the contracts up to {last_address:?}.");
+        // The last address has no successor, so there are no more contracts to download.
+        let Ok(next_address) = ContractAddress::try_from(*last_address.key() + Felt::ONE) else {
+            return Ok(());
+        };
+        start = next_address;
+    }
+}
+
+// Downloads the classes of the state, verifies their hashes, and commits and writes them page by
+// page. Returns the declarations of the classes, which are written with the snap sync block.
+async fn download_classes(
+    state_snapshot_sender: &mut StateSnapshotSqmrSender,
+    storage_writer: &mut StorageWriter,
+    config: SnapSyncConfig,
+    state_commitment: &mut StateCommitment,
+) -> Result<ThinStateDiff, P2PSyncClientError> {
+    let mut declarations = ThinStateDiff::default();
+    let mut start = ClassHash::default();
+    loop {
+        let mut client_response_manager = state_snapshot_sender
+            .send_new_query(StateSnapshotQuery {
+                block_number: config.block_number,
+                start: StateSnapshotStart::Classes(start),
+                limit: config.num_classes_per_query,
+            })
+            .await?;
+        let mut page = ThinStateDiff::default();
+        let mut classes = Vec::new();
+        let mut deprecated_classes = Vec::new();
+        let mut n_classes = 0;
+        let mut last_class_hash = None;
+        while let Some(chunk) =
+            next_response(&mut client_response_manager, "state snapshot").await?
+        {
+            let StateSnapshotChunk::Class(snapshot_class) = chunk else {
+                return Err(P2PSyncClientError::SnapSyncUnexpectedChunk);
+            };
+            let class_hash = snapshot_class.class_hash;
+            if class_hash < start
+                || last_class_hash.is_some_and(|last_class_hash| class_hash <= last_class_hash)
+            {
+                return Err(P2PSyncClientError::SnapSyncUnorderedChunks);
+            }
+            last_class_hash = Some(class_hash);
+            n_classes += 1;
+            if n_classes > config.num_classes_per_query {
+                return Err(P2PSyncClientError::TooManyResponses);
+            }
+            match (snapshot_class.class, snapshot_class.compiled_class_hash) {
+                (ApiContractClass::ContractClass(class), Some(compiled_class_hash)) => {
+                    // The state root commits only to the compiled class hashes, so the classes
+                    // themselves are verified by their hashes.
+                    let actual_class_hash = calculate_class_hash(&class);
+                    if actual_class_hash != class_hash {
+                        return Err(P2PSyncClientError::SnapSyncWrongClassHash {
+                            expected: class_hash,
+                            actual: actual_class_hash,
+                        });
+                    }
+                    page.declared_classes.insert(class_hash, compiled_class_hash);
+                    classes.push((class_hash, class));
+                }
+                // TODO(shahak): Verify the hashes of the deprecated classes once their hash
+                // calculation is available.
+                (ApiContractClass::DeprecatedContractClass(class), None) => {
+                    page.deprecated_declared_classes.push(class_hash);
+                    deprecated_classes.push((class_hash, class));
+                }
+                _ => return Err(P2PSyncClientError::SnapSyncInvalidClass { class_hash }),
+            }
+        }
+        let Some(last_class_hash) = last_class_hash else {
+            return Ok(declarations);
+        };
+        state_commitment.commit(&page).await?;
+        let classes =
+            classes.iter().map(|(class_hash, class)| (*class_hash, class)).collect::<Vec<_>>();
+        let deprecated_classes = deprecated_classes
+            .iter()
+            .map(|(class_hash, class)| (*class_hash, class))
+            .collect::<Vec<_>>();
+        storage_writer
+            .begin_rw_txn()?
+            .write_state_snapshot_chunk(
+                config.block_number,
+                &ThinStateDiff::default(),
+                &classes,
+                &deprecated_classes,
+            )?
+            .commit()?;
+        declarations.declared_classes.extend(page.declared_classes);
+        declarations.deprecated_declared_classes.extend(page.deprecated_declared_classes);
+        debug!("Downloaded the classes of the state up to {last_class_hash:?}.");
+        // The last class hash has no successor, so there are no more classes to download.
+        if last_class_hash.0 == Felt::MAX {
+            return Ok(declarations);
+        }
+        start = ClassHash(last_class_hash.0 + Felt::ONE);
+    }
+}
+
+// Returns the next response of a query, or None if the query finished.
+async fn next_response<Response>(
+    client_response_manager: &mut ClientResponsesManager<DataOrFin<Response>>,
+    type_description: &'static str,
+) -> Result<Option<Response>, P2PSyncClientError>
+where
+    DataOrFin<Response>: TryFrom<Vec<u8>, Error = ProtobufConversionError>,
+{
+    let response = tokio::time::timeout(NETWORK_DATA_TIMEOUT, client_response_manager.next())
+        .await?
+        .ok_or(P2PSyncClientError::ReceiverChannelTerminated { type_description })?;
+    Ok(response?.0)
+}
+
+// The roots of the tries of the part of the state that was downloaded so far, with the facts
+// needed to commit more of the state on top of them.
+#[derive(Default)]
+struct StateCommitment {
+    contracts_trie_root_hash: HashOutput,
+    classes_trie_root_hash: HashOutput,
+    // The facts of the contracts trie and the classes trie. The storage tries are not kept, since
+    // all the storage of a contract is downloaded in a single page.
+    facts: MapStorage,
+}
+
+impl StateCommitment {
+    // Commits a part of the state, given as a state diff from the empty state, on top of the parts
+    // that were committed before it. The parts should not share contracts or classes.
+    async fn commit(&mut self, state: &ThinStateDiff) -> Result<(), BlockCommitmentError> {
+        let filled_forest = commit_state_diff(
+            &self.facts,
+            &to_committer_state_diff(state),
+            self.contracts_trie_root_hash,
+            self.classes_trie_root_hash,
+            &ConfigImpl::new(false, LevelFilter::INFO),
+        )
+        .await?;
+        self.facts.mset(filled_forest.contracts_trie.serialize());
+        self.facts.mset(filled_forest.classes_trie.serialize());
+        self.contracts_trie_root_hash = filled_forest.get_contract_root_hash();
+        self.classes_trie_root_hash = filled_forest.get_compiled_class_root_hash();
+        Ok(())
+    }
+
+    fn global_root(&self) -> GlobalRoot {
+        let global_root_hash =
+            calculate_global_root_hash(self.contracts_trie_root_hash, self.classes_trie_root_hash);
+        GlobalRoot(global_root_hash.0.into())
+    }
+}
+
+// Computes the root of the state, given as a state diff from the empty state.
+#[cfg(test)]
+pub(crate) async fn calculate_state_root(
+    state: &ThinStateDiff,
+) -> Result<GlobalRoot, BlockCommitmentError> {
+    let mut state_commitment = StateCommitment::default();
+    state_commitment.commit(state).await?;
+    Ok(state_commitment.global_root())
+}
+
+fn to_committer_state_diff(state: &ThinStateDiff) -> StateDiff {
+    let to_committer_address =
+        |address: &ContractAddress| CommitterContractAddress((*address.key()).into());
+    StateDiff {
+        address_to_class_hash: state
+            .deployed_contracts
+            .iter()
+            .map(|(address, class_hash)| {
+                (to_committer_address(address), CommitterClassHash(class_hash.0.into()))
+            })
+            .collect(),
+        address_to_nonce: state
+            .nonces
+            .iter()
+            .map(|(address, nonce)| (to_committer_address(address), CommitterNonce(nonce.0.into())))
+            .collect(),
+        class_hash_to_compiled_class_hash: state
+            .declared_classes
+            .iter()
+            .map(|(class_hash, compiled_class_hash)| {
+                (
+                    CommitterClassHash(class_hash.0.into()),
+                    CommitterCompiledClassHash(compiled_class_hash.0.into()),
+                )
+            })
+            .collect(),
+        storage_updates: state
+            .storage_diffs
+            .iter()
+            .map(|(address, storage_diffs)| {
+                (to_committer_address(address), to_committer_storage(storage_diffs))
+            })
+            .collect(),
+    }
+}
+
+fn to_committer_storage(
+    storage_diffs: &IndexMap<StorageKey, Felt>,
+) -> HashMap<StarknetStorageKey, StarknetStorageValue> {
+    storage_diffs
+        .iter()
+        .map(|(key, value)| {
+            (StarknetStorageKey((*key.key()).into()), StarknetStorageValue((*value).into()))
+        })
+        .collect()
+}
@@ -0,0 +1,466 @@
+use assert_matches::assert_matches;
+use futures::StreamExt;
+use indexmap::indexmap;
+use papyrus_common::class_hash::calculate_class_hash;
+use papyrus_common::pending_classes::ApiContractClass;
+use papyrus_protobuf::sync::{
+    BlockHashOrNumber,
+    ContractDiff,
+    DataOrFin,
+    Direction,
+    HeaderQuery,
+    Query,
+    SignedBlockHeader,
+    SnapshotClass,
+    StateSnapshotChunk,
+    StateSnapshotQuery,
+    StateSnapshotStart,
+};
+use papyrus_storage::header::HeaderStorageReader;
+use papyrus_storage::state::StateStorageReader;
+use starknet_api::block::{BlockHash, BlockHeader, BlockNumber, BlockSignature, StarknetVersion};
+use starknet_api::block_hash::block_hash_calculator::calculate_block_hash_of_header;
+use starknet_api::core::{
+    ClassHash,
+    CompiledClassHash,
+    ContractAddress,
+    EventCommitment,
+    Nonce,
+    PatriciaKey,
+    ReceiptCommitment,
+    StateDiffCommitment,
+    TransactionCommitment,
+};
+use starknet_api::state::{ContractClass, StateNumber, StorageKey, ThinStateDiff};
+use starknet_types_core::felt::Felt;
+
+use super::snap_sync::{calculate_state_root, SnapSyncConfig};
+use super::test_utils::{setup, TestArgs};
+use super::P2PSyncClientError;
+
+const BLOCK_NUMBER: BlockNumber = BlockNumber(5);
+const NUM_CONTRACTS_PER_QUERY: u64 = 2;
+const NUM_CLASSES_PER_QUERY: u64 = 1;
+
+fn contract_address(address: u8) -> ContractAddress {
+    ContractAddress(PatriciaKey::from(address))
+}
+
+// Returns the classes of the state, in the order of their hashes.
+fn get_classes() -> Vec<(ClassHash, ContractClass)> {
+    let mut classes = ["", "[]"]
+        .map(|abi| {
+            let class = ContractClass { abi: abi.to_owned(), ..Default::default() };
+            (calculate_class_hash(&class), class)
+        })
+        .to_vec();
+    classes.sort_by_key(|(class_hash, _)| *class_hash);
+    classes
+}
+
+fn get_state() -> ThinStateDiff {
+    let classes = get_classes();
+    let (first_class_hash, second_class_hash) = (classes[0].0, classes[1].0);
+    ThinStateDiff {
+        deployed_contracts: indexmap! {
+            contract_address(0x10) => first_class_hash,
+            contract_address(0x20) => first_class_hash,
+            contract_address(0x30) => second_class_hash,
+        },
+        storage_diffs: indexmap! {
+            contract_address(0x10) => indexmap! {
+                StorageKey(PatriciaKey::from(1_u8)) => Felt::from(7_u8),
+                StorageKey(PatriciaKey::from(2_u8)) => Felt::from(8_u8),
+            },
+        },
+        declared_classes: indexmap! {
+            first_class_hash => CompiledClassHash(Felt::from(0x101_u16)),
+            second_class_hash => CompiledClassHash(Felt::from(0x201_u16)),
+        },
+        nonces: indexmap! { contract_address(0x10) => Nonce(Felt::ONE) },
+        ..Default::default()
+    }
+}
+
+async fn get_header(state: &ThinStateDiff) -> BlockHeader {
+    let header = BlockHeader {
+        block_number: BLOCK_NUMBER,
+        state_root: calculate_state_root(state).await.unwrap(),
+        starknet_version: StarknetVersion("0.13.2".to_owned()),
+        transaction_commitment: Some(TransactionCommitment::default()),
+        event_commitment: Some(EventCommitment::default()),
+        receipt_commitment: Some(ReceiptCommitment::default()),
+        state_diff_commitment: Some(StateDiffCommitment::default()),
+        state_diff_length: Some(0),
+        ..Default::default()
+    };
+    BlockHeader { block_hash: calculate_block_hash_of_header(&header).unwrap(), ..header }
+}
+
+fn get_contract_diffs(state: &ThinStateDiff) -> Vec<ContractDiff> {
+    state
+        .deployed_contracts
+        .iter()
+        .map(|(address, class_hash)| ContractDiff {
+            contract_address: *address,
+            class_hash: Some(*class_hash),
+            nonce: state.nonces.get(address).copied(),
+            storage_diffs: state.storage_diffs.get(address).cloned().unwrap_or_default(),
+        })
+        .collect()
+}
+
+fn get_snapshot_classes(state: &ThinStateDiff) -> Vec<SnapshotClass> {
+    get_classes()
+        .into_iter()
+        .map(|(class_hash, class)| SnapshotClass {
+            class_hash,
+            compiled_class_hash: Some(state.declared_classes[&class_hash]),
+            class: ApiContractClass::ContractClass(class),
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn snap_sync_basic_flow() {
+    let TestArgs {
+        mut p2p_sync,
+        storage_reader,
+        mut header_receiver,
+        mut state_snapshot_receiver,
+        // The test will fail if we drop these
+        state_diff_receiver: _state_diff_query_receiver,
+        transaction_receiver: _transaction_query_receiver,
+        class_receiver: _class_query_receiver,
+    } = setup();
+    let state = get_state();
+    let header = get_header(&state).await;
+    p2p_sync.config.snap_sync = Some(SnapSyncConfig {
+        block_number: BLOCK_NUMBER,
+        block_hash: header.block_hash,
+        num_contracts_per_query: NUM_CONTRACTS_PER_QUERY,
+        num_classes_per_query: NUM_CLASSES_PER_QUERY,
+    });
+
+    let contract_diffs = get_contract_diffs(&state);
+    let snapshot_classes = get_snapshot_classes(&state);
+    let parse_queries_future = async move {
+        let mut mock_header_responses_manager = header_receiver.next().await.unwrap();
+        assert_eq!(
+            *mock_header_responses_manager.query(),
+            Ok(HeaderQuery(Query {
+                start_block: BlockHashOrNumber::Number(BLOCK_NUMBER),
+                direction: Direction::Forward,
+                limit: 1,
+                step: 1,
+            }))
+        );
+        mock_header_responses_manager
+            .send_response(DataOrFin(Some(SignedBlockHeader {
+                block_header: header.clone(),
+                signatures: vec![BlockSignature::default()],
+                precommits: vec![],
+            })))
+            .await
+            .unwrap();
+        mock_header_responses_manager.send_response(DataOrFin(None)).await.unwrap();
+
+        // The contracts are sent in pages, each one continuing from the last contract of the
+        // previous one, until a page is empty. The storage of the first contract is split between
+        // two chunks, which count as a single contract of the page.
+        let (first_storage_key, first_storage_value) =
+            contract_diffs[0].storage_diffs.first().unwrap();
+        let first_contract_chunks = [
+            ContractDiff {
+                storage_diffs: indexmap! { *first_storage_key => *first_storage_value },
+                ..contract_diffs[0].clone()
+            },
+            ContractDiff {
+                contract_address: contract_address(0x10),
+                class_hash: None,
+                nonce: None,
+                storage_diffs: contract_diffs[0].storage_diffs.clone().split_off(1),
+            },
+        ];
+        let first_page = first_contract_chunks
+            .into_iter()
+            .chain([contract_diffs[1].clone()])
+            .collect::<Vec<_>>();
+        let contract_pages = [
+            (contract_address(0), &first_page[..]),
+            (contract_address(0x21), &contract_diffs[2..]),
+            (contract_address(0x31), &[][..]),
+        ];
+        for (start, page) in contract_pages {
+            let mut mock_state_snapshot_responses_manager =
+                state_snapshot_receiver.next().await.unwrap();
+            assert_eq!(
+                *mock_state_snapshot_responses_manager.query(),
+                Ok(StateSnapshotQuery {
+                    block_number: BLOCK_NUMBER,
+                    start: StateSnapshotStart::Contracts(start),
+                    limit: NUM_CONTRACTS_PER_QUERY,
+                })
+            );
+            for contract_diff in page {
+                mock_state_snapshot_responses_manager
+                    .send_response(DataOrFin(Some(StateSnapshotChunk::Contract(
+                        contract_diff.clone(),
+                    ))))
+                    .await
+                    .unwrap();
+            }
+            mock_state_snapshot_responses_manager.send_response(DataOrFin(None)).await.unwrap();
+        }
+
+        let class_pages = [
+            (ClassHash::default(), &snapshot_classes[..1]),
+            (ClassHash(snapshot_classes[0].class_hash.0 + Felt::ONE), &snapshot_classes[1..]),
+            (ClassHash(snapshot_classes[1].class_hash.0 + Felt::ONE), &[][..]),
+        ];
+        for (start, page) in class_pages {
+            let mut mock_state_snapshot_responses_manager =
+                state_snapshot_receiver.next().await.unwrap();
+            assert_eq!(
+                *mock_state_snapshot_responses_manager.query(),
+                Ok(StateSnapshotQuery {
+                    block_number: BLOCK_NUMBER,
+                    start: StateSnapshotStart::Classes(start),
+                    limit: NUM_CLASSES_PER_QUERY,
+                })
+            );
+            for snapshot_class in page {
+                mock_state_snapshot_responses_manager
+                    .send_response(DataOrFin(Some(StateSnapshotChunk::Class(
+                        snapshot_class.clone(),
+                    ))))
+                    .await
+                    .unwrap();
+            }
+            mock_state_snapshot_responses_manager.send_response(DataOrFin(None)).await.unwrap();
+        }
+
+        // After the snap sync, the headers are synced from the block after the snap sync block.
+        let mock_header_responses_manager = header_receiver.next().await.unwrap();
+        assert_matches!(
+            mock_header_responses_manager.query(),
+            Ok(HeaderQuery(Query { start_block: BlockHashOrNumber::Number(block_number), .. }))
+                if *block_number == BLOCK_NUMBER.unchecked_next()
+        );
+    };
+
+    tokio::select! {
+        sync_result = p2p_sync.run() => {
+            sync_result.unwrap();
+            panic!("P2P sync aborted with no failure.");
+        }
+        _ = parse_queries_future => {}
+    }
+
+    let txn = storage_reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_header_marker().unwrap(), BLOCK_NUMBER.unchecked_next());
+    assert_eq!(txn.get_state_marker().unwrap(), BLOCK_NUMBER.unchecked_next());
+    let state_reader = txn.get_state_reader().unwrap();
+    let state_number = StateNumber::unchecked_right_after_block(BLOCK_NUMBER);
+    let key = StorageKey(PatriciaKey::from(1_u8));
+    assert_eq!(
+        state_reader.get_storage_at(state_number, &contract_address(0x10), &key).unwrap(),
+        Felt::from(7_u8)
+    );
+    assert_eq!(
+        state_reader
+            .get_storage_at(
+                state_number,
+                &contract_address(0x10),
+                &StorageKey(PatriciaKey::from(2_u8))
+            )
+            .unwrap(),
+        Felt::from(8_u8)
+    );
+    assert_eq!(
+        state_reader.get_nonce_at(state_number, &contract_address(0x10)).unwrap(),
+        Some(Nonce(Felt::ONE))
+    );
+    let (class_hash, class) = get_classes().remove(1);
+    assert_eq!(
+        state_reader.get_class_definition_at(state_number, &class_hash).unwrap(),
+        Some(class)
+    );
+}
+
+#[tokio::test]
+async fn snap_sync_fails_on_wrong_state_root() {
+    let TestArgs {
+        mut p2p_sync,
+        mut header_receiver,
+        mut state_snapshot_receiver,
+        // The test will fail if we drop these
+        state_diff_receiver: _state_diff_query_receiver,
+        transaction_receiver: _transaction_query_receiver,
+        class_receiver: _class_query_receiver,
+        ..
+    } = setup();
+    let state = get_state();
+    let header = get_header(&state).await;
+    p2p_sync.config.snap_sync = Some(SnapSyncConfig {
+        block_number: BLOCK_NUMBER,
+        block_hash: header.block_hash,
+        num_contracts_per_query: NUM_CONTRACTS_PER_QUERY,
+        num_classes_per_query: NUM_CLASSES_PER_QUERY,
+    });
+
+    // Send all the contracts but one in a single page, and no classes.
+    let contract_diffs = get_contract_diffs(&state);
+    let parse_queries_future = async move {
+        let mut mock_header_responses_manager = header_receiver.next().await.unwrap();
+        mock_header_responses_manager
+            .send_response(DataOrFin(Some(SignedBlockHeader {
+                block_header: header,
+                signatures: vec![BlockSignature::default()],
+                precommits: vec![],
+            })))
+            .await
+            .unwrap();
+        mock_header_responses_manager.send_response(DataOrFin(None)).await.unwrap();
+
+        for page in [&contract_diffs[..1], &[][..], &[][..]] {
+            let mut mock_state_snapshot_responses_manager =
+                state_snapshot_receiver.next().await.unwrap();
+            for contract_diff in page {
+                mock_state_snapshot_responses_manager
+                    .send_response(DataOrFin(Some(StateSnapshotChunk::Contract(
+                        contract_diff.clone(),
+                    ))))
+                    .await
+                    .unwrap();
+            }
+            mock_state_snapshot_responses_manager.send_response(DataOrFin(None)).await.unwrap();
+        }
+        // Keep the receivers alive until the sync fails.
+        futures::future::pending::<()>().await;
+    };
+
+    tokio::select! {
+        sync_result = p2p_sync.run() => {
+            assert_matches!(sync_result, Err(P2PSyncClientError::SnapSyncWrongStateRoot { .. }));
+        }
+        _ = parse_queries_future => {
+            panic!("The queries future should never end.");
+        }
+    }
+}
+
+#[tokio::test]
+async fn snap_sync_fails_on_wrong_class_hash() {
+    let TestArgs {
+        mut p2p_sync,
+        mut header_receiver,
+        mut state_snapshot_receiver,
+        // The test will fail if we drop these
+        state_diff_receiver: _state_diff_query_receiver,
+        transaction_receiver: _transaction_query_receiver,
+        class_receiver: _class_query_receiver,
+        ..
+    } = setup();
+    let state = get_state();
+    let header = get_header(&state).await;
+    p2p_sync.config.snap_sync = Some(SnapSyncConfig {
+        block_number: BLOCK_NUMBER,
+        block_hash: header.block_hash,
+        num_contracts_per_query: NUM_CONTRACTS_PER_QUERY,
+        num_classes_per_query: NUM_CLASSES_PER_QUERY,
+    });
+
+    // Send no contracts, and a class under the hash of another class.
+    let snapshot_classes = get_snapshot_classes(&state);
+    let wrong_class =
+        SnapshotClass { class_hash: snapshot_classes[0].class_hash, ..snapshot_classes[1].clone() };
+    let parse_queries_future = async move {
+        let mut mock_header_responses_manager = header_receiver.next().await.unwrap();
+        mock_header_responses_manager
+            .send_response(DataOrFin(Some(SignedBlockHeader {
+                block_header: header,
+                signatures: vec![BlockSignature::default()],
+                precommits: vec![],
+            })))
+            .await
+            .unwrap();
+        mock_header_responses_manager.send_response(DataOrFin(None)).await.unwrap();
+
+        let mut mock_state_snapshot_responses_manager =
+            state_snapshot_receiver.next().await.unwrap();
+        mock_state_snapshot_responses_manager.send_response(DataOrFin(None)).await.unwrap();
+
+        let mut mock_state_snapshot_responses_manager =
+            state_snapshot_receiver.next().await.unwrap();
+        mock_state_snapshot_responses_manager
+            .send_response(DataOrFin(Some(StateSnapshotChunk::Class(wrong_class))))
+            .await
+            .unwrap();
+        // Keep the receivers alive until the sync fails.
+        futures::future::pending::<()>().await;
+    };
+
+    tokio::select! {
+        sync_result = p2p_sync.run() => {
+            assert_matches!(
+                sync_result,
+                Err(P2PSyncClientError::SnapSyncWrongClassHash { expected, actual })
+                    if expected == snapshot_classes[0].class_hash
+                        && actual == snapshot_classes[1].class_hash
+            );
+        }
+        _ = parse_queries_future => {
+            panic!("The queries future should never end.");
+        }
+    }
+}
+
+#[tokio::test]
+async fn snap_sync_fails_on_wrong_block_hash() {
+    let TestArgs {
+        mut p2p_sync,
+        mut header_receiver,
+        // The test will fail if we drop these
+        state_diff_receiver: _state_diff_query_receiver,
+        transaction_receiver: _transaction_query_receiver,
+        class_receiver: _class_query_receiver,
+        state_snapshot_receiver: _state_snapshot_receiver,
+        ..
+    } = setup();
+    let state = get_state();
+    let header = get_header(&state).await;
+    p2p_sync.config.snap_sync = Some(SnapSyncConfig {
+        block_number: BLOCK_NUMBER,
+        block_hash: BlockHash(Felt::ONE),
+        num_contracts_per_query: NUM_CONTRACTS_PER_QUERY,
+        num_classes_per_query: NUM_CLASSES_PER_QUERY,
+    });
+
+    let parse_queries_future = async move {
+        let mut mock_header_responses_manager = header_receiver.next().await.unwrap();
+        mock_header_responses_manager
+            .send_response(DataOrFin(Some(SignedBlockHeader {
+                block_header: header,
+                signatures: vec![BlockSignature::default()],
+                precommits: vec![],
+            })))
+            .await
+            .unwrap();
+        mock_header_responses_manager.send_response(DataOrFin(None)).await.unwrap();
+        futures::future::pending::<()>().await;
+    };
+
+    tokio::select! {
+        sync_result = p2p_sync.run() => {
+            assert_matches!(
+                sync_result,
+                Err(P2PSyncClientError::SnapSyncWrongBlockHash { expected, .. })
+                    if expected == BlockHash(Felt::ONE)
+            );
+        }
+        _ = parse_queries_future => {
+            panic!("The queries future should never end.");
+        }
+    }
+}
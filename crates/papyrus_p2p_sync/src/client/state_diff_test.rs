@@ -51,6 +51,7 @@ async fn state_diff_basic_flow() {
         mut header_receiver,
         // The test will fail if we drop these
         transaction_receiver: _mock_transaction_responses_manager,
+        class_receiver: _mock_class_responses_manager,
         ..
     } = setup();
 
@@ -86,6 +87,7 @@ async fn state_diff_basic_flow() {
                         ..Default::default()
                     },
                     signatures: vec![*block_signature],
+                    precommits: vec![],
                 })))
                 .await
                 .unwrap();
@@ -322,6 +324,7 @@ async fn validate_state_diff_fails(
         mut header_receiver,
         // The test will fail if we drop these
         transaction_receiver: _mock_transaction_responses_manager,
+        class_receiver: _mock_class_responses_manager,
         ..
     } = setup();
 
@@ -340,6 +343,7 @@ async fn validate_state_diff_fails(
                     ..Default::default()
                 },
                 signatures: vec![block_signature],
+                precommits: vec![],
             })))
             .await
             .unwrap();
@@ -9,15 +9,25 @@ use papyrus_network::network_manager::{ClientResponsesManager, SqmrClientSender}
 use papyrus_protobuf::converters::ProtobufConversionError;
 use papyrus_protobuf::sync::{BlockHashOrNumber, DataOrFin, Direction, Query};
 use papyrus_storage::header::HeaderStorageReader;
+use papyrus_storage::state::StateStorageReader;
 use papyrus_storage::{StorageError, StorageReader, StorageWriter};
 use starknet_api::block::BlockNumber;
 use tracing::{debug, info};
 
-use super::{P2PSyncClientError, STEP};
+use super::{P2PSyncClientError, ValidatorSetProvider, STEP};
 
 pub type DataStreamResult = Result<Box<dyn BlockData>, P2PSyncClientError>;
 
 pub(crate) trait BlockData: Send {
+    /// Verifies that consensus decided on the block, for data that carries the certificate of the
+    /// decision.
+    fn verify_decision_certificate(
+        &self,
+        _validator_set_provider: &dyn ValidatorSetProvider,
+    ) -> Result<(), P2PSyncClientError> {
+        Ok(())
+    }
+
     fn write_to_storage(
         // This is Box<Self> in order to allow using it with `Box<dyn BlockData>`.
         self: Box<Self>,
@@ -25,10 +35,11 @@ pub(crate) trait BlockData: Send {
     ) -> Result<(), StorageError>;
 }
 
+#[derive(Debug)]
 pub(crate) enum BlockNumberLimit {
     Unlimited,
     HeaderMarker,
-    // TODO(shahak): Add variant for state diff marker once we support classes sync.
+    StateDiffMarker,
 }
 
 pub(crate) trait DataStreamBuilder<InputFromNetwork>
@@ -64,16 +75,28 @@ where
         stream! {
             let mut current_block_number = Self::get_start_block_number(&storage_reader)?;
             'send_query_and_parse_responses: loop {
-                let limit = match Self::BLOCK_NUMBER_LIMIT {
-                    BlockNumberLimit::Unlimited => num_blocks_per_query,
+                let last_block_number = match Self::BLOCK_NUMBER_LIMIT {
+                    BlockNumberLimit::Unlimited => None,
                     BlockNumberLimit::HeaderMarker => {
-                        let last_block_number = storage_reader.begin_ro_txn()?.get_header_marker()?;
+                        Some(storage_reader.begin_ro_txn()?.get_header_marker()?)
+                    }
+                    BlockNumberLimit::StateDiffMarker => {
+                        Some(storage_reader.begin_ro_txn()?.get_state_marker()?)
+                    }
+                };
+                let limit = match last_block_number {
+                    None => num_blocks_per_query,
+                    Some(last_block_number) => {
                         let limit = min(
                             last_block_number.0 - current_block_number.0,
                             num_blocks_per_query,
                         );
                         if limit == 0 {
-                            debug!("{:?} sync is waiting for a new header", Self::TYPE_DESCRIPTION);
+                            debug!(
+                                "{:?} sync is waiting for the {:?} to advance",
+                                Self::TYPE_DESCRIPTION,
+                                Self::BLOCK_NUMBER_LIMIT
+                            );
                             tokio::time::sleep(wait_period_for_new_data).await;
                             continue;
                         }
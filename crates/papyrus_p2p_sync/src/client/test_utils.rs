@@ -1,22 +1,27 @@
 use std::time::Duration;
 
 use lazy_static::lazy_static;
+use papyrus_common::pending_classes::ApiContractClass;
 use papyrus_network::network_manager::test_utils::{
     mock_register_sqmr_protocol_client,
     MockClientResponsesManager,
 };
 use papyrus_network::network_manager::GenericReceiver;
 use papyrus_protobuf::sync::{
+    ClassQuery,
     DataOrFin,
     HeaderQuery,
     SignedBlockHeader,
     StateDiffChunk,
     StateDiffQuery,
+    StateSnapshotChunk,
+    StateSnapshotQuery,
     TransactionQuery,
 };
 use papyrus_storage::test_utils::get_test_storage;
 use papyrus_storage::StorageReader;
 use starknet_api::block::{BlockHash, BlockSignature};
+use starknet_api::core::ClassHash;
 use starknet_api::crypto::utils::Signature;
 use starknet_api::hash::StarkHash;
 use starknet_api::transaction::FullTransaction;
@@ -28,6 +33,7 @@ pub const BUFFER_SIZE: usize = 1000;
 pub const HEADER_QUERY_LENGTH: u64 = 5;
 pub const STATE_DIFF_QUERY_LENGTH: u64 = 3;
 pub const TRANSACTION_QUERY_LENGTH: u64 = 3;
+pub const CLASS_QUERY_LENGTH: u64 = 3;
 pub const SLEEP_DURATION_TO_LET_SYNC_ADVANCE: Duration = Duration::from_millis(10);
 pub const WAIT_PERIOD_FOR_NEW_DATA: Duration = Duration::from_secs(1);
 pub const TIMEOUT_FOR_NEW_QUERY_AFTER_PARTIAL_RESPONSE: Duration =
@@ -38,15 +44,23 @@ lazy_static! {
         num_headers_per_query: HEADER_QUERY_LENGTH,
         num_block_state_diffs_per_query: STATE_DIFF_QUERY_LENGTH,
         num_transactions_per_query: TRANSACTION_QUERY_LENGTH,
+        num_block_classes_per_query: CLASS_QUERY_LENGTH,
         wait_period_for_new_data: WAIT_PERIOD_FOR_NEW_DATA,
         buffer_size: BUFFER_SIZE,
         stop_sync_at_block_number: None,
+        snap_sync: None,
+        verify_decision_certificates: false,
+        l1_finality: None,
     };
 }
 type HeaderTestPayload = MockClientResponsesManager<HeaderQuery, DataOrFin<SignedBlockHeader>>;
 type StateDiffTestPayload = MockClientResponsesManager<StateDiffQuery, DataOrFin<StateDiffChunk>>;
 type TransactionTestPayload =
     MockClientResponsesManager<TransactionQuery, DataOrFin<FullTransaction>>;
+type ClassTestPayload =
+    MockClientResponsesManager<ClassQuery, DataOrFin<(ApiContractClass, ClassHash)>>;
+type StateSnapshotTestPayload =
+    MockClientResponsesManager<StateSnapshotQuery, DataOrFin<StateSnapshotChunk>>;
 
 // TODO(Eitan): Use SqmrSubscriberChannels once there is a utility function for testing
 pub struct TestArgs {
@@ -57,6 +71,8 @@ pub struct TestArgs {
     pub state_diff_receiver: GenericReceiver<StateDiffTestPayload>,
     #[allow(dead_code)]
     pub transaction_receiver: GenericReceiver<TransactionTestPayload>,
+    pub class_receiver: GenericReceiver<ClassTestPayload>,
+    pub state_snapshot_receiver: GenericReceiver<StateSnapshotTestPayload>,
 }
 
 pub fn setup() -> TestArgs {
@@ -67,8 +83,16 @@ pub fn setup() -> TestArgs {
     let (state_diff_sender, state_diff_receiver) = mock_register_sqmr_protocol_client(buffer_size);
     let (transaction_sender, transaction_receiver) =
         mock_register_sqmr_protocol_client(buffer_size);
-    let p2p_sync_channels =
-        P2PSyncClientChannels { header_sender, state_diff_sender, transaction_sender };
+    let (class_sender, class_receiver) = mock_register_sqmr_protocol_client(buffer_size);
+    let (state_snapshot_sender, state_snapshot_receiver) =
+        mock_register_sqmr_protocol_client(buffer_size);
+    let p2p_sync_channels = P2PSyncClientChannels {
+        header_sender,
+        state_diff_sender,
+        transaction_sender,
+        class_sender,
+        state_snapshot_sender,
+    };
     let p2p_sync = P2PSyncClient::new(
         p2p_sync_config,
         storage_reader.clone(),
@@ -81,6 +105,8 @@ pub fn setup() -> TestArgs {
         header_receiver,
         state_diff_receiver,
         transaction_receiver,
+        class_receiver,
+        state_snapshot_receiver,
     }
 }
 
@@ -13,6 +13,8 @@ pub enum Protocol {
     Transaction,
     Class,
     Event,
+    StateSnapshot,
+    ClassByHash,
 }
 
 impl Protocol {
@@ -23,6 +25,8 @@ impl Protocol {
             Protocol::Transaction => "/starknet/transactions/0.1.0-rc.0",
             Protocol::Class => "/starknet/classes/0.1.0-rc.0",
             Protocol::Event => "/starknet/events/0.1.0-rc.0",
+            Protocol::StateSnapshot => "/starknet/state_snapshot/0.1.0-rc.0",
+            Protocol::ClassByHash => "/starknet/class_by_hash/0.1.0-rc.0",
         }
     }
 }
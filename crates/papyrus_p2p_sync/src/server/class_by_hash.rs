@@ -0,0 +1,89 @@
+use papyrus_common::pending_classes::ApiContractClass;
+use papyrus_network::network_manager::{ReputationModifier, ServerQueryManager};
+use papyrus_protobuf::sync::{ClassHashesQuery, DataOrFin};
+use papyrus_storage::class::ClassStorageReader;
+use papyrus_storage::state::StateStorageReader;
+use papyrus_storage::StorageReader;
+use starknet_api::core::ClassHash;
+use starknet_api::state::StateNumber;
+use tracing::{debug, error, info};
+
+use super::P2PSyncServerError;
+
+/// The maximal number of classes sent for a single class by hash query. The rest of the hashes
+/// in the query are ignored.
+const MAX_CLASSES_PER_QUERY: usize = 100;
+
+pub(crate) type ClassByHashServerQueryManager =
+    ServerQueryManager<ClassHashesQuery, DataOrFin<(ApiContractClass, ClassHash)>>;
+
+pub(crate) fn register_class_by_hash_query(
+    storage_reader: StorageReader,
+    server_query_manager: ClassByHashServerQueryManager,
+) {
+    let query = match server_query_manager.query().clone() {
+        Ok(query) => query,
+        Err(error) => {
+            error!("Failed to parse inbound class by hash query: {error:?}");
+            server_query_manager.report_peer(ReputationModifier::Medium);
+            return;
+        }
+    };
+    info!("Sync server received a new inbound class by hash query {query:?}");
+    tokio::task::spawn(async move {
+        let result = send_classes_by_hash(&storage_reader, server_query_manager, &query).await;
+        if let Err(error) = &result {
+            if error.should_log_in_error_level() {
+                error!("Running inbound class by hash query {query:?} failed on {error:?}");
+            }
+        }
+        result
+    });
+}
+
+async fn send_classes_by_hash(
+    storage_reader: &StorageReader,
+    mut server_query_manager: ClassByHashServerQueryManager,
+    query: &ClassHashesQuery,
+) -> Result<(), P2PSyncServerError> {
+    // If reading the classes fails, we still want to send fin before failing.
+    let result = match read_classes(storage_reader, query) {
+        Ok(classes) => {
+            for class in classes {
+                server_query_manager.send_response(DataOrFin(Some(class))).await?;
+            }
+            Ok(())
+        }
+        Err(error) => Err(error),
+    };
+    info!("Sending fin message for inbound class by hash query");
+    server_query_manager.send_response(DataOrFin(None)).await?;
+    result
+}
+
+// Reads the classes of the query in its order, up to the first one that isn't in the storage.
+fn read_classes(
+    storage_reader: &StorageReader,
+    query: &ClassHashesQuery,
+) -> Result<Vec<(ApiContractClass, ClassHash)>, P2PSyncServerError> {
+    let txn = storage_reader.begin_ro_txn()?;
+    let state_number = StateNumber(txn.get_class_marker()?);
+    let state_reader = txn.get_state_reader()?;
+
+    let mut classes = Vec::new();
+    for class_hash in query.0.iter().take(MAX_CLASSES_PER_QUERY) {
+        let class =
+            if let Some(class) = state_reader.get_class_definition_at(state_number, class_hash)? {
+                ApiContractClass::ContractClass(class)
+            } else if let Some(class) =
+                state_reader.get_deprecated_class_definition_at(state_number, class_hash)?
+            {
+                ApiContractClass::DeprecatedContractClass(class)
+            } else {
+                debug!("Class {class_hash} of inbound class by hash query isn't in the storage.");
+                break;
+            };
+        classes.push((class, *class_hash));
+    }
+    Ok(classes)
+}
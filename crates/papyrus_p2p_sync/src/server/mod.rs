@@ -2,10 +2,15 @@ use std::fmt::Debug;
 
 use futures::StreamExt;
 use papyrus_common::pending_classes::ApiContractClass;
-use papyrus_network::network_manager::{ServerQueryManager, SqmrServerReceiver};
+use papyrus_network::network_manager::{
+    ReputationModifier,
+    ServerQueryManager,
+    SqmrServerReceiver,
+};
 use papyrus_protobuf::converters::ProtobufConversionError;
 use papyrus_protobuf::sync::{
     BlockHashOrNumber,
+    ClassHashesQuery,
     ClassQuery,
     ContractDiff,
     DataOrFin,
@@ -17,6 +22,8 @@ use papyrus_protobuf::sync::{
     SignedBlockHeader,
     StateDiffChunk,
     StateDiffQuery,
+    StateSnapshotChunk,
+    StateSnapshotQuery,
     TransactionQuery,
 };
 use papyrus_storage::body::BodyStorageReader;
@@ -33,6 +40,8 @@ use tracing::{error, info};
 #[cfg(test)]
 mod test;
 
+mod class_by_hash;
+mod state_snapshot;
 mod utils;
 
 #[derive(thiserror::Error, Debug)]
@@ -73,6 +82,9 @@ type StateDiffReceiver = SqmrServerReceiver<StateDiffQuery, DataOrFin<StateDiffC
 type TransactionReceiver = SqmrServerReceiver<TransactionQuery, DataOrFin<FullTransaction>>;
 type ClassReceiver = SqmrServerReceiver<ClassQuery, DataOrFin<(ApiContractClass, ClassHash)>>;
 type EventReceiver = SqmrServerReceiver<EventQuery, DataOrFin<(Event, TransactionHash)>>;
+type StateSnapshotReceiver = SqmrServerReceiver<StateSnapshotQuery, DataOrFin<StateSnapshotChunk>>;
+type ClassByHashReceiver =
+    SqmrServerReceiver<ClassHashesQuery, DataOrFin<(ApiContractClass, ClassHash)>>;
 
 pub struct P2PSyncServerChannels {
     header_receiver: HeaderReceiver,
@@ -80,6 +92,8 @@ pub struct P2PSyncServerChannels {
     transaction_receiver: TransactionReceiver,
     class_receiver: ClassReceiver,
     event_receiver: EventReceiver,
+    state_snapshot_receiver: StateSnapshotReceiver,
+    class_by_hash_receiver: ClassByHashReceiver,
 }
 
 impl P2PSyncServerChannels {
@@ -89,6 +103,8 @@ impl P2PSyncServerChannels {
         transaction_receiver: TransactionReceiver,
         class_receiver: ClassReceiver,
         event_receiver: EventReceiver,
+        state_snapshot_receiver: StateSnapshotReceiver,
+        class_by_hash_receiver: ClassByHashReceiver,
     ) -> Self {
         Self {
             header_receiver,
@@ -96,6 +112,8 @@ impl P2PSyncServerChannels {
             transaction_receiver,
             class_receiver,
             event_receiver,
+            state_snapshot_receiver,
+            class_by_hash_receiver,
         }
     }
 }
@@ -114,6 +132,8 @@ impl P2PSyncServer {
             mut transaction_receiver,
             mut class_receiver,
             mut event_receiver,
+            mut state_snapshot_receiver,
+            mut class_by_hash_receiver,
         } = self.p2p_sync_channels;
         loop {
             tokio::select! {
@@ -147,6 +167,24 @@ impl P2PSyncServer {
                     );
                     register_query(self.storage_reader.clone(), server_query_manager);
                 }
+                maybe_server_query_manager = state_snapshot_receiver.next() => {
+                    let server_query_manager = maybe_server_query_manager.expect(
+                        "State snapshot queries sender was unexpectedly dropped."
+                    );
+                    state_snapshot::register_state_snapshot_query(
+                        self.storage_reader.clone(),
+                        server_query_manager,
+                    );
+                }
+                maybe_server_query_manager = class_by_hash_receiver.next() => {
+                    let server_query_manager = maybe_server_query_manager.expect(
+                        "Class by hash queries sender was unexpectedly dropped."
+                    );
+                    class_by_hash::register_class_by_hash_query(
+                        self.storage_reader.clone(),
+                        server_query_manager,
+                    );
+                }
             };
         }
     }
@@ -181,7 +219,7 @@ fn register_query<Data, TQuery>(
         }
         Err(error) => {
             error!("Failed to parse inbound query: {error:?}");
-            server_query_manager.report_peer()
+            server_query_manager.report_peer(ReputationModifier::Medium)
         }
     }
 }
@@ -215,7 +253,12 @@ impl FetchBlockDataFromDb for SignedBlockHeader {
         let signature = txn
             .get_block_signature(block_number)?
             .ok_or(P2PSyncServerError::SignatureNotFound { block_number })?;
-        Ok(vec![SignedBlockHeader { block_header: header, signatures: vec![signature] }])
+        // TODO: Send the precommits of the blocks decided by consensus once they're stored.
+        Ok(vec![SignedBlockHeader {
+            block_header: header,
+            signatures: vec![signature],
+            precommits: vec![],
+        }])
     }
 }
 
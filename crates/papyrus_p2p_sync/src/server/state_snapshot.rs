@@ -0,0 +1,190 @@
+use papyrus_common::pending_classes::ApiContractClass;
+use papyrus_network::network_manager::{ReputationModifier, ServerQueryManager};
+use papyrus_protobuf::sync::{
+    BlockHashOrNumber,
+    ContractDiff,
+    DataOrFin,
+    SnapshotClass,
+    StateDiffChunk,
+    StateSnapshotChunk,
+    StateSnapshotQuery,
+    StateSnapshotStart,
+};
+use papyrus_storage::class::ClassStorageReader;
+use papyrus_storage::state::StateStorageReader;
+use papyrus_storage::StorageReader;
+use starknet_api::block::BlockNumber;
+use starknet_api::state::StateNumber;
+use tracing::{error, info};
+
+use super::{split_thin_state_diff, P2PSyncServerError};
+
+/// The maximal number of contracts sent for a single state snapshot query. A client that asked
+/// for more continues from the last one it received.
+const MAX_STATE_SNAPSHOT_CONTRACTS_PER_QUERY: u64 = 1000;
+/// The maximal number of classes sent for a single state snapshot query. Classes are big, so fewer
+/// of them are sent than contracts.
+const MAX_STATE_SNAPSHOT_CLASSES_PER_QUERY: u64 = 100;
+/// The maximal number of storage entries in a single contract chunk. The storage of a contract
+/// with more entries is sent in consecutive chunks of that contract.
+pub(crate) const MAX_STORAGE_ENTRIES_PER_STATE_SNAPSHOT_CHUNK: usize = 1000;
+/// The number of contracts or classes read from the storage at once. Their chunks are sent before
+/// the next ones are read, so a query is never held in memory as a whole.
+const STATE_SNAPSHOT_READ_BATCH_SIZE: usize = 10;
+
+pub(crate) type StateSnapshotServerQueryManager =
+    ServerQueryManager<StateSnapshotQuery, DataOrFin<StateSnapshotChunk>>;
+
+pub(crate) fn register_state_snapshot_query(
+    storage_reader: StorageReader,
+    server_query_manager: StateSnapshotServerQueryManager,
+) {
+    let query = match server_query_manager.query().clone() {
+        Ok(query) => query,
+        Err(error) => {
+            error!("Failed to parse inbound state snapshot query: {error:?}");
+            server_query_manager.report_peer(ReputationModifier::Medium);
+            return;
+        }
+    };
+    info!("Sync server received a new inbound state snapshot query {query:?}");
+    tokio::task::spawn(async move {
+        let result = send_state_snapshot(&storage_reader, server_query_manager, &query).await;
+        if let Err(error) = &result {
+            if error.should_log_in_error_level() {
+                error!("Running inbound state snapshot query {query:?} failed on {error:?}");
+            }
+        }
+        result
+    });
+}
+
+async fn send_state_snapshot(
+    storage_reader: &StorageReader,
+    mut server_query_manager: StateSnapshotServerQueryManager,
+    query: &StateSnapshotQuery,
+) -> Result<(), P2PSyncServerError> {
+    // If reading the state fails, we still want to send fin before failing.
+    let result = send_state_snapshot_chunks(storage_reader, &mut server_query_manager, query).await;
+    info!("Sending fin message for inbound state snapshot query");
+    server_query_manager.send_response(DataOrFin(None)).await?;
+    result
+}
+
+// Sends the contracts or the classes of the state after the block of the query, reading a batch of
+// them from the storage at a time.
+async fn send_state_snapshot_chunks(
+    storage_reader: &StorageReader,
+    server_query_manager: &mut StateSnapshotServerQueryManager,
+    query: &StateSnapshotQuery,
+) -> Result<(), P2PSyncServerError> {
+    let max_limit = match query.start {
+        StateSnapshotStart::Contracts(_) => MAX_STATE_SNAPSHOT_CONTRACTS_PER_QUERY,
+        StateSnapshotStart::Classes(_) => MAX_STATE_SNAPSHOT_CLASSES_PER_QUERY,
+    };
+    let mut remaining = usize::try_from(query.limit.min(max_limit))
+        .expect("The state snapshot chunks limit should fit in usize.");
+    let mut start = Some(query.start);
+    while let Some(batch_start) = start.filter(|_| remaining > 0) {
+        let batch_size = remaining.min(STATE_SNAPSHOT_READ_BATCH_SIZE);
+        let (chunks, next_start) =
+            read_state_snapshot(storage_reader, query.block_number, batch_start, batch_size)?;
+        for chunk in chunks {
+            server_query_manager.send_response(DataOrFin(Some(chunk))).await?;
+        }
+        remaining -= batch_size;
+        start = next_start;
+    }
+    Ok(())
+}
+
+// Reads at most `limit` contracts or classes of the state after the given block, in the order of
+// their addresses or hashes. Returns them with the start of the ones after them, if there are any.
+fn read_state_snapshot(
+    storage_reader: &StorageReader,
+    block_number: BlockNumber,
+    start: StateSnapshotStart,
+    limit: usize,
+) -> Result<(Vec<StateSnapshotChunk>, Option<StateSnapshotStart>), P2PSyncServerError> {
+    let txn = storage_reader.begin_ro_txn()?;
+    // The classes are the last part of the state to be stored.
+    if txn.get_class_marker()? <= block_number {
+        return Err(P2PSyncServerError::BlockNotFound {
+            block_hash_or_number: BlockHashOrNumber::Number(block_number),
+        });
+    }
+    let state_number = StateNumber::unchecked_right_after_block(block_number);
+    let state_reader = txn.get_state_reader()?;
+
+    match start {
+        StateSnapshotStart::Contracts(start) => {
+            let (state, next_address) =
+                state_reader.get_contracts_state(state_number, start, limit)?;
+            let mut contract_diffs = split_thin_state_diff(state)
+                .into_iter()
+                .filter_map(|chunk| match chunk {
+                    StateDiffChunk::ContractDiff(contract_diff) => Some(contract_diff),
+                    _ => None,
+                })
+                .collect::<Vec<ContractDiff>>();
+            contract_diffs.sort_by_key(|contract_diff| contract_diff.contract_address);
+            let chunks = contract_diffs
+                .into_iter()
+                .flat_map(split_contract_storage)
+                .map(StateSnapshotChunk::Contract)
+                .collect();
+            Ok((chunks, next_address.map(StateSnapshotStart::Contracts)))
+        }
+        StateSnapshotStart::Classes(start) => {
+            let (state, next_class_hash) =
+                state_reader.get_declared_classes_state(state_number, start, limit)?;
+            let mut classes = Vec::new();
+            for (class_hash, compiled_class_hash) in state.declared_classes {
+                let class = state_reader
+                    .get_class_definition_at(state_number, &class_hash)?
+                    .ok_or(P2PSyncServerError::ClassNotFound { class_hash })?;
+                classes.push(SnapshotClass {
+                    class_hash,
+                    compiled_class_hash: Some(compiled_class_hash),
+                    class: ApiContractClass::ContractClass(class),
+                });
+            }
+            for class_hash in state.deprecated_declared_classes {
+                let class = state_reader
+                    .get_deprecated_class_definition_at(state_number, &class_hash)?
+                    .ok_or(P2PSyncServerError::ClassNotFound { class_hash })?;
+                classes.push(SnapshotClass {
+                    class_hash,
+                    compiled_class_hash: None,
+                    class: ApiContractClass::DeprecatedContractClass(class),
+                });
+            }
+            classes.sort_by_key(|class| class.class_hash);
+            let chunks = classes.into_iter().map(StateSnapshotChunk::Class).collect();
+            Ok((chunks, next_class_hash.map(StateSnapshotStart::Classes)))
+        }
+    }
+}
+
+// Splits the storage of a contract into chunks of at most
+// MAX_STORAGE_ENTRIES_PER_STATE_SNAPSHOT_CHUNK entries. The first chunk holds the class hash and
+// the nonce of the contract.
+fn split_contract_storage(contract_diff: ContractDiff) -> Vec<ContractDiff> {
+    let ContractDiff { contract_address, mut class_hash, mut nonce, storage_diffs } = contract_diff;
+    let mut storage_entries = storage_diffs.into_iter().peekable();
+    let mut chunks = Vec::new();
+    loop {
+        chunks.push(ContractDiff {
+            contract_address,
+            class_hash: class_hash.take(),
+            nonce: nonce.take(),
+            storage_diffs: storage_entries
+                .by_ref()
+                .take(MAX_STORAGE_ENTRIES_PER_STATE_SNAPSHOT_CHUNK)
+                .collect(),
+        });
+        if storage_entries.peek().is_none() {
+            return chunks;
+        }
+    }
+}
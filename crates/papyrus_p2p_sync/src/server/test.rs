@@ -1,7 +1,8 @@
 use std::fmt::Debug;
 
 use futures::channel::mpsc::Sender;
-use futures::StreamExt;
+use futures::{SinkExt, StreamExt};
+use indexmap::{indexmap, IndexMap};
 use lazy_static::lazy_static;
 use papyrus_common::pending_classes::ApiContractClass;
 use papyrus_common::state::create_random_state_diff;
@@ -13,15 +14,21 @@ use papyrus_network::network_manager::ServerQueryManager;
 use papyrus_protobuf::converters::ProtobufConversionError;
 use papyrus_protobuf::sync::{
     BlockHashOrNumber,
+    ClassHashesQuery,
     ClassQuery,
+    ContractDiff,
     DataOrFin,
     Direction,
     EventQuery,
     HeaderQuery,
     Query,
     SignedBlockHeader,
+    SnapshotClass,
     StateDiffChunk,
     StateDiffQuery,
+    StateSnapshotChunk,
+    StateSnapshotQuery,
+    StateSnapshotStart,
     TransactionQuery,
 };
 use papyrus_storage::body::BodyStorageWriter;
@@ -33,8 +40,9 @@ use papyrus_storage::{StorageReader, StorageWriter};
 use papyrus_test_utils::{get_rng, get_test_body, GetTestInstance};
 use rand::random;
 use starknet_api::block::{BlockBody, BlockHash, BlockHeader, BlockNumber, BlockSignature};
+use starknet_api::core::{CompiledClassHash, ContractAddress, Nonce, PatriciaKey};
 use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
-use starknet_api::state::ContractClass;
+use starknet_api::state::{ContractClass, StorageKey, ThinStateDiff};
 use starknet_api::transaction::{
     Event,
     FullTransaction,
@@ -42,7 +50,9 @@ use starknet_api::transaction::{
     TransactionHash,
     TransactionOutput,
 };
+use starknet_types_core::felt::Felt;
 
+use super::state_snapshot::MAX_STORAGE_ENTRIES_PER_STATE_SNAPSHOT_CHUNK;
 use super::{split_thin_state_diff, FetchBlockDataFromDb, P2PSyncServer, P2PSyncServerChannels};
 use crate::server::register_query;
 const BUFFER_SIZE: usize = 10;
@@ -302,6 +312,222 @@ async fn class_query_some_blocks_are_missing() {
     .await;
 }
 
+#[tokio::test]
+async fn state_snapshot_query_positive_flow() {
+    let TestArgs { p2p_sync_server, mut storage_writer, mut state_snapshot_sender, .. } = setup();
+    let contract_address = ContractAddress(PatriciaKey::from(0x10_u8));
+    let class_hash = ClassHash(0x1_u8.into());
+    let compiled_class_hash = CompiledClassHash(0x2_u8.into());
+    let deprecated_class_hash = ClassHash(0x3_u8.into());
+    let storage_diffs = indexmap!(StorageKey(PatriciaKey::from(0x5_u8)) => 0x6_u8.into());
+    let state_diff = ThinStateDiff {
+        deployed_contracts: indexmap!(contract_address => class_hash),
+        storage_diffs: indexmap!(contract_address => storage_diffs.clone()),
+        declared_classes: indexmap!(class_hash => compiled_class_hash),
+        deprecated_declared_classes: vec![deprecated_class_hash],
+        ..Default::default()
+    };
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_state_diff(BlockNumber(0), state_diff)
+        .unwrap()
+        .append_classes(
+            BlockNumber(0),
+            &[(class_hash, &ContractClass::default())],
+            &[(deprecated_class_hash, &DeprecatedContractClass::default())],
+        )
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let run_queries = async {
+        let mut responses = Vec::new();
+        for start in [
+            StateSnapshotStart::Contracts(ContractAddress::default()),
+            StateSnapshotStart::Classes(ClassHash::default()),
+        ] {
+            let query = StateSnapshotQuery { block_number: BlockNumber(0), start, limit: 10 };
+            let (server_query_manager, _report_receiver, response_receiver) =
+                create_test_server_query_manager(query);
+            state_snapshot_sender.send(server_query_manager).await.unwrap();
+            responses.push(response_receiver.collect::<Vec<_>>().await);
+        }
+        responses
+    };
+
+    tokio::select! {
+        _ = p2p_sync_server.run() => {
+            panic!("p2p_sync_server should never finish its run.");
+        },
+        responses = run_queries => {
+            let contracts = vec![
+                DataOrFin(Some(StateSnapshotChunk::Contract(ContractDiff {
+                    contract_address,
+                    class_hash: Some(class_hash),
+                    nonce: Some(Nonce::default()),
+                    storage_diffs,
+                }))),
+                DataOrFin(None),
+            ];
+            let classes = vec![
+                DataOrFin(Some(StateSnapshotChunk::Class(SnapshotClass {
+                    class_hash,
+                    compiled_class_hash: Some(compiled_class_hash),
+                    class: ApiContractClass::ContractClass(ContractClass::default()),
+                }))),
+                DataOrFin(Some(StateSnapshotChunk::Class(SnapshotClass {
+                    class_hash: deprecated_class_hash,
+                    compiled_class_hash: None,
+                    class: ApiContractClass::DeprecatedContractClass(
+                        DeprecatedContractClass::default(),
+                    ),
+                }))),
+                DataOrFin(None),
+            ];
+            assert_eq!(responses, vec![contracts, classes]);
+        }
+    }
+}
+
+#[tokio::test]
+async fn state_snapshot_query_splits_big_contracts_and_caps_limit() {
+    let TestArgs { p2p_sync_server, mut storage_writer, mut state_snapshot_sender, .. } = setup();
+    const N_CONTRACTS: u8 = 25;
+    const LIMIT: u64 = 22;
+    let big_contract_address = ContractAddress(PatriciaKey::from(1_u8));
+    let big_contract_storage = (1..=MAX_STORAGE_ENTRIES_PER_STATE_SNAPSHOT_CHUNK + 1)
+        .map(|key| (StorageKey(PatriciaKey::from(u64::try_from(key).unwrap())), Felt::ONE))
+        .collect::<IndexMap<_, _>>();
+    let class_hash = ClassHash(0x1_u8.into());
+    let state_diff = ThinStateDiff {
+        deployed_contracts: (1..=N_CONTRACTS)
+            .map(|address| (ContractAddress(PatriciaKey::from(address)), class_hash))
+            .collect(),
+        storage_diffs: indexmap!(big_contract_address => big_contract_storage.clone()),
+        ..Default::default()
+    };
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_state_diff(BlockNumber(0), state_diff)
+        .unwrap()
+        .append_classes(BlockNumber(0), &[], &[])
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let query = StateSnapshotQuery {
+        block_number: BlockNumber(0),
+        start: StateSnapshotStart::Contracts(ContractAddress::default()),
+        limit: LIMIT,
+    };
+    let (server_query_manager, _report_receiver, response_receiver) =
+        create_test_server_query_manager(query);
+    state_snapshot_sender.send(server_query_manager).await.unwrap();
+
+    tokio::select! {
+        _ = p2p_sync_server.run() => {
+            panic!("p2p_sync_server should never finish its run.");
+        },
+        responses = response_receiver.collect::<Vec<_>>() => {
+            let mut expected_responses = vec![
+                ContractDiff {
+                    contract_address: big_contract_address,
+                    class_hash: Some(class_hash),
+                    nonce: Some(Nonce::default()),
+                    storage_diffs: big_contract_storage
+                        .clone()
+                        .into_iter()
+                        .take(MAX_STORAGE_ENTRIES_PER_STATE_SNAPSHOT_CHUNK)
+                        .collect(),
+                },
+                ContractDiff {
+                    contract_address: big_contract_address,
+                    class_hash: None,
+                    nonce: None,
+                    storage_diffs: big_contract_storage
+                        .into_iter()
+                        .skip(MAX_STORAGE_ENTRIES_PER_STATE_SNAPSHOT_CHUNK)
+                        .collect(),
+                },
+            ];
+            // The contracts are read in batches, and the query limit counts contracts, not chunks.
+            expected_responses.extend((2..=u8::try_from(LIMIT).unwrap()).map(|address| {
+                ContractDiff {
+                    contract_address: ContractAddress(PatriciaKey::from(address)),
+                    class_hash: Some(class_hash),
+                    nonce: Some(Nonce::default()),
+                    storage_diffs: IndexMap::new(),
+                }
+            }));
+            let expected_responses = expected_responses
+                .into_iter()
+                .map(|contract_diff| DataOrFin(Some(StateSnapshotChunk::Contract(contract_diff))))
+                .chain(std::iter::once(DataOrFin(None)))
+                .collect::<Vec<_>>();
+            assert_eq!(responses, expected_responses);
+        }
+    }
+}
+
+#[tokio::test]
+async fn class_by_hash_query_positive_flow() {
+    let TestArgs { p2p_sync_server, mut storage_writer, mut class_by_hash_sender, .. } = setup();
+    let class_hash = ClassHash(0x1_u8.into());
+    let deprecated_class_hash = ClassHash(0x2_u8.into());
+    let missing_class_hash = ClassHash(0x3_u8.into());
+    let state_diff = ThinStateDiff {
+        declared_classes: indexmap!(class_hash => CompiledClassHash::default()),
+        deprecated_declared_classes: vec![deprecated_class_hash],
+        ..Default::default()
+    };
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_state_diff(BlockNumber(0), state_diff)
+        .unwrap()
+        .append_classes(
+            BlockNumber(0),
+            &[(class_hash, &ContractClass::default())],
+            &[(deprecated_class_hash, &DeprecatedContractClass::default())],
+        )
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    // The classes are sent in the order of the query, up to the first missing one.
+    let query =
+        ClassHashesQuery(vec![deprecated_class_hash, class_hash, missing_class_hash, class_hash]);
+    let (server_query_manager, _report_receiver, response_receiver) =
+        create_test_server_query_manager(query);
+    class_by_hash_sender.send(server_query_manager).await.unwrap();
+
+    tokio::select! {
+        _ = p2p_sync_server.run() => {
+            panic!("p2p_sync_server should never finish its run.");
+        },
+        responses = response_receiver.collect::<Vec<_>>() => {
+            assert_eq!(
+                responses,
+                vec![
+                    DataOrFin(Some((
+                        ApiContractClass::DeprecatedContractClass(
+                            DeprecatedContractClass::default(),
+                        ),
+                        deprecated_class_hash,
+                    ))),
+                    DataOrFin(Some((
+                        ApiContractClass::ContractClass(ContractClass::default()),
+                        class_hash,
+                    ))),
+                    DataOrFin(None),
+                ]
+            );
+        }
+    }
+}
+
 async fn run_test<T, F, TQuery>(
     assert_fn: F,
     start_block_number: u64,
@@ -327,6 +553,8 @@ async fn run_test<T, F, TQuery>(
         transaction_sender: _transaction_sender,
         class_sender: _class_sender,
         event_sender: _event_sender,
+        state_snapshot_sender: _state_snapshot_sender,
+        class_by_hash_sender: _class_by_hash_sender,
     } = setup();
 
     // put some data in the storage.
@@ -379,6 +607,10 @@ pub struct TestArgs {
     pub class_sender:
         Sender<ServerQueryManager<ClassQuery, DataOrFin<(ApiContractClass, ClassHash)>>>,
     pub event_sender: Sender<ServerQueryManager<EventQuery, DataOrFin<(Event, TransactionHash)>>>,
+    pub state_snapshot_sender:
+        Sender<ServerQueryManager<StateSnapshotQuery, DataOrFin<StateSnapshotChunk>>>,
+    pub class_by_hash_sender:
+        Sender<ServerQueryManager<ClassHashesQuery, DataOrFin<(ApiContractClass, ClassHash)>>>,
 }
 
 #[allow(clippy::type_complexity)]
@@ -390,12 +622,18 @@ fn setup() -> TestArgs {
         mock_register_sqmr_protocol_server(BUFFER_SIZE);
     let (class_receiver, class_sender) = mock_register_sqmr_protocol_server(BUFFER_SIZE);
     let (event_receiver, event_sender) = mock_register_sqmr_protocol_server(BUFFER_SIZE);
+    let (state_snapshot_receiver, state_snapshot_sender) =
+        mock_register_sqmr_protocol_server(BUFFER_SIZE);
+    let (class_by_hash_receiver, class_by_hash_sender) =
+        mock_register_sqmr_protocol_server(BUFFER_SIZE);
     let p2p_sync_server_channels = P2PSyncServerChannels {
         header_receiver,
         state_diff_receiver,
         transaction_receiver,
         class_receiver,
         event_receiver,
+        state_snapshot_receiver,
+        class_by_hash_receiver,
     };
 
     let p2p_sync_server =
@@ -409,6 +647,8 @@ fn setup() -> TestArgs {
         transaction_sender,
         class_sender,
         event_sender,
+        state_snapshot_sender,
+        class_by_hash_sender,
     }
 }
 use starknet_api::core::ClassHash;
@@ -10,9 +10,11 @@ fn main() -> Result<()> {
             "src/proto/p2p/proto/class.proto",
             "src/proto/p2p/proto/event.proto",
             "src/proto/p2p/proto/header.proto",
+            "src/proto/p2p/proto/snapshot.proto",
             "src/proto/p2p/proto/state.proto",
             "src/proto/p2p/proto/transaction.proto",
             "src/proto/p2p/proto/consensus.proto",
+            "src/proto/mempool/mempool.proto",
         ],
         &["src/proto/"],
     )?;
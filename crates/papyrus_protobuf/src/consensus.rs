@@ -1,7 +1,19 @@
+#[cfg(any(feature = "testing", test))]
+use papyrus_test_utils::{auto_impl_get_test_instance, get_number_of_variants, GetTestInstance};
 use starknet_api::block::BlockHash;
 use starknet_api::core::ContractAddress;
 use starknet_api::transaction::Transaction;
 
+/// The version of the consensus protocol, sent in every [`ConsensusMessage`]. Increment it when
+/// the content or the meaning of the consensus messages changes.
+pub const CONSENSUS_PROTOCOL_VERSION: u32 = 1;
+
+/// Nodes interoperate with peers of adjacent protocol versions, so that the network can be upgraded
+/// one node at a time. Messages of other versions are rejected.
+pub fn is_compatible_protocol_version(version: u32) -> bool {
+    version.abs_diff(CONSENSUS_PROTOCOL_VERSION) <= 1
+}
+
 #[derive(Debug, Default, Hash, Clone, Eq, PartialEq)]
 pub struct Proposal {
     pub height: u64,
@@ -11,6 +23,35 @@ pub struct Proposal {
     pub block_hash: BlockHash,
 }
 
+/// The first part of a streamed proposal.
+#[derive(Debug, Default, Hash, Clone, Eq, PartialEq)]
+pub struct ProposalInit {
+    pub height: u64,
+    pub round: u32,
+    pub proposer: ContractAddress,
+}
+
+/// A batch of the transactions of a streamed proposal.
+#[derive(Debug, Default, Hash, Clone, Eq, PartialEq)]
+pub struct TransactionBatch {
+    pub transactions: Vec<Transaction>,
+}
+
+/// The last part of a streamed proposal.
+#[derive(Debug, Default, Hash, Clone, Eq, PartialEq)]
+pub struct ProposalFin {
+    pub block_hash: BlockHash,
+}
+
+/// A part of a streamed proposal: a [`ProposalInit`], followed by any number of
+/// [`TransactionBatch`]es and a [`ProposalFin`].
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub enum ProposalPart {
+    Init(ProposalInit),
+    Transactions(TransactionBatch),
+    Fin(ProposalFin),
+}
+
 #[derive(Debug, Default, Hash, Clone, Eq, PartialEq)]
 pub enum VoteType {
     Prevote,
@@ -41,3 +82,18 @@ impl ConsensusMessage {
         }
     }
 }
+
+#[cfg(any(feature = "testing", test))]
+auto_impl_get_test_instance! {
+    pub enum VoteType {
+        Prevote = 0,
+        Precommit = 1,
+    }
+    pub struct Vote {
+        pub vote_type: VoteType,
+        pub height: u64,
+        pub round: u32,
+        pub block_hash: Option<BlockHash>,
+        pub voter: ContractAddress,
+    }
+}
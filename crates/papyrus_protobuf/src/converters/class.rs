@@ -1,3 +1,7 @@
+#[cfg(test)]
+#[path = "class_test.rs"]
+mod class_test;
+
 use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
 
@@ -10,7 +14,7 @@ use starknet_types_core::felt::Felt;
 
 use super::common::volition_domain_to_enum_int;
 use super::ProtobufConversionError;
-use crate::sync::{ClassQuery, DataOrFin, Query};
+use crate::sync::{ClassHashesQuery, ClassQuery, DataOrFin, Query};
 use crate::{auto_impl_into_and_try_from_vec_u8, protobuf};
 
 pub const DOMAIN: DataAvailabilityMode = DataAvailabilityMode::L1;
@@ -353,3 +357,26 @@ impl From<ClassQuery> for protobuf::ClassesRequest {
 }
 
 auto_impl_into_and_try_from_vec_u8!(ClassQuery, protobuf::ClassesRequest);
+
+impl TryFrom<protobuf::ClassesByHashRequest> for ClassHashesQuery {
+    type Error = ProtobufConversionError;
+    fn try_from(value: protobuf::ClassesByHashRequest) -> Result<Self, Self::Error> {
+        Ok(ClassHashesQuery(
+            value
+                .class_hashes
+                .into_iter()
+                .map(|class_hash| Ok(ClassHash(class_hash.try_into()?)))
+                .collect::<Result<_, ProtobufConversionError>>()?,
+        ))
+    }
+}
+
+impl From<ClassHashesQuery> for protobuf::ClassesByHashRequest {
+    fn from(value: ClassHashesQuery) -> Self {
+        protobuf::ClassesByHashRequest {
+            class_hashes: value.0.into_iter().map(|class_hash| class_hash.0.into()).collect(),
+        }
+    }
+}
+
+auto_impl_into_and_try_from_vec_u8!(ClassHashesQuery, protobuf::ClassesByHashRequest);
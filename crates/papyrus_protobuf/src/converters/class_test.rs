@@ -0,0 +1,13 @@
+use papyrus_test_utils::{get_rng, GetTestInstance};
+
+use crate::sync::ClassHashesQuery;
+
+#[test]
+fn convert_class_hashes_query_to_vec_u8_and_back() {
+    let mut rng = get_rng();
+    let class_hashes_query = ClassHashesQuery::get_test_instance(&mut rng);
+
+    let bytes_data = Vec::<u8>::from(class_hashes_query.clone());
+    let res_data = ClassHashesQuery::try_from(bytes_data).unwrap();
+    assert_eq!(class_hashes_query, res_data);
+}
@@ -1,3 +1,7 @@
+#[cfg(test)]
+#[path = "consensus_test.rs"]
+mod consensus_test;
+
 use std::convert::{TryFrom, TryInto};
 
 use prost::Message;
@@ -5,7 +9,18 @@ use starknet_api::block::BlockHash;
 use starknet_api::hash::StarkHash;
 use starknet_api::transaction::Transaction;
 
-use crate::consensus::{ConsensusMessage, Proposal, Vote, VoteType};
+use crate::consensus::{
+    is_compatible_protocol_version,
+    ConsensusMessage,
+    Proposal,
+    ProposalFin,
+    ProposalInit,
+    ProposalPart,
+    TransactionBatch,
+    Vote,
+    VoteType,
+    CONSENSUS_PROTOCOL_VERSION,
+};
 use crate::converters::ProtobufConversionError;
 use crate::{auto_impl_into_and_try_from_vec_u8, protobuf};
 
@@ -49,6 +64,105 @@ impl From<Proposal> for protobuf::Proposal {
     }
 }
 
+impl TryFrom<protobuf::ProposalInit> for ProposalInit {
+    type Error = ProtobufConversionError;
+
+    fn try_from(value: protobuf::ProposalInit) -> Result<Self, Self::Error> {
+        let height = value.height;
+        let round = value.round;
+        let proposer = value
+            .proposer
+            .ok_or(ProtobufConversionError::MissingField { field_description: "proposer" })?
+            .try_into()?;
+
+        Ok(ProposalInit { height, round, proposer })
+    }
+}
+
+impl From<ProposalInit> for protobuf::ProposalInit {
+    fn from(value: ProposalInit) -> Self {
+        protobuf::ProposalInit {
+            height: value.height,
+            round: value.round,
+            proposer: Some(value.proposer.into()),
+        }
+    }
+}
+
+impl TryFrom<protobuf::TransactionBatch> for TransactionBatch {
+    type Error = ProtobufConversionError;
+
+    fn try_from(value: protobuf::TransactionBatch) -> Result<Self, Self::Error> {
+        let transactions = value
+            .transactions
+            .into_iter()
+            .map(|tx| tx.try_into())
+            .collect::<Result<Vec<Transaction>, ProtobufConversionError>>()?;
+
+        Ok(TransactionBatch { transactions })
+    }
+}
+
+impl From<TransactionBatch> for protobuf::TransactionBatch {
+    fn from(value: TransactionBatch) -> Self {
+        protobuf::TransactionBatch {
+            transactions: value.transactions.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<protobuf::ProposalFin> for ProposalFin {
+    type Error = ProtobufConversionError;
+
+    fn try_from(value: protobuf::ProposalFin) -> Result<Self, Self::Error> {
+        let block_hash: StarkHash = value
+            .block_hash
+            .ok_or(ProtobufConversionError::MissingField { field_description: "block_hash" })?
+            .try_into()?;
+
+        Ok(ProposalFin { block_hash: BlockHash(block_hash) })
+    }
+}
+
+impl From<ProposalFin> for protobuf::ProposalFin {
+    fn from(value: ProposalFin) -> Self {
+        protobuf::ProposalFin { block_hash: Some(value.block_hash.0.into()) }
+    }
+}
+
+impl TryFrom<protobuf::ProposalPart> for ProposalPart {
+    type Error = ProtobufConversionError;
+
+    fn try_from(value: protobuf::ProposalPart) -> Result<Self, Self::Error> {
+        use protobuf::proposal_part::Message;
+
+        let Some(message) = value.message else {
+            return Err(ProtobufConversionError::MissingField { field_description: "message" });
+        };
+
+        match message {
+            Message::Init(init) => Ok(ProposalPart::Init(init.try_into()?)),
+            Message::Transactions(batch) => Ok(ProposalPart::Transactions(batch.try_into()?)),
+            Message::Fin(fin) => Ok(ProposalPart::Fin(fin.try_into()?)),
+        }
+    }
+}
+
+impl From<ProposalPart> for protobuf::ProposalPart {
+    fn from(value: ProposalPart) -> Self {
+        use protobuf::proposal_part::Message;
+
+        let message = match value {
+            ProposalPart::Init(init) => Message::Init(init.into()),
+            ProposalPart::Transactions(batch) => Message::Transactions(batch.into()),
+            ProposalPart::Fin(fin) => Message::Fin(fin.into()),
+        };
+        protobuf::ProposalPart { message: Some(message) }
+    }
+}
+
+auto_impl_into_and_try_from_vec_u8!(ProposalPart, protobuf::ProposalPart);
+
 impl TryFrom<protobuf::vote::VoteType> for VoteType {
     type Error = ProtobufConversionError;
 
@@ -113,6 +227,15 @@ impl TryFrom<protobuf::ConsensusMessage> for ConsensusMessage {
     fn try_from(value: protobuf::ConsensusMessage) -> Result<Self, Self::Error> {
         use protobuf::consensus_message::Message;
 
+        // The version is checked first, since the content of a message of an incompatible version
+        // may not be convertible.
+        if !is_compatible_protocol_version(value.protocol_version) {
+            return Err(ProtobufConversionError::IncompatibleProtocolVersion {
+                version: value.protocol_version,
+                supported_version: CONSENSUS_PROTOCOL_VERSION,
+            });
+        }
+
         let Some(message) = value.message else {
             return Err(ProtobufConversionError::MissingField { field_description: "message" });
         };
@@ -129,9 +252,11 @@ impl From<ConsensusMessage> for protobuf::ConsensusMessage {
         match value {
             ConsensusMessage::Proposal(proposal) => protobuf::ConsensusMessage {
                 message: Some(protobuf::consensus_message::Message::Proposal(proposal.into())),
+                protocol_version: CONSENSUS_PROTOCOL_VERSION,
             },
             ConsensusMessage::Vote(vote) => protobuf::ConsensusMessage {
                 message: Some(protobuf::consensus_message::Message::Vote(vote.into())),
+                protocol_version: CONSENSUS_PROTOCOL_VERSION,
             },
         }
     }
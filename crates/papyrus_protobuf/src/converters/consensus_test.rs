@@ -0,0 +1,84 @@
+use papyrus_test_utils::{get_rng, GetTestInstance};
+use prost::Message;
+use starknet_api::block::BlockHash;
+use starknet_api::core::ContractAddress;
+use starknet_api::transaction::{DeployTransaction, L1HandlerTransaction, Transaction};
+
+use crate::consensus::{
+    ConsensusMessage,
+    ProposalFin,
+    ProposalInit,
+    ProposalPart,
+    TransactionBatch,
+    Vote,
+    CONSENSUS_PROTOCOL_VERSION,
+};
+use crate::converters::ProtobufConversionError;
+use crate::protobuf;
+
+fn convert_proposal_part_to_vec_u8_and_back(proposal_part: ProposalPart) {
+    let bytes_data = Vec::<u8>::from(proposal_part.clone());
+    let res_data = ProposalPart::try_from(bytes_data).unwrap();
+    assert_eq!(proposal_part, res_data);
+}
+
+#[test]
+fn convert_proposal_init_to_vec_u8_and_back() {
+    let mut rng = get_rng();
+    let proposal_init = ProposalInit {
+        height: 1,
+        round: 2,
+        proposer: ContractAddress::get_test_instance(&mut rng),
+    };
+    convert_proposal_part_to_vec_u8_and_back(ProposalPart::Init(proposal_init));
+}
+
+#[test]
+fn convert_transaction_batch_to_vec_u8_and_back() {
+    let mut rng = get_rng();
+    let transactions = vec![
+        Transaction::L1Handler(L1HandlerTransaction::get_test_instance(&mut rng)),
+        Transaction::Deploy(DeployTransaction::get_test_instance(&mut rng)),
+    ];
+    convert_proposal_part_to_vec_u8_and_back(ProposalPart::Transactions(TransactionBatch {
+        transactions,
+    }));
+}
+
+#[test]
+fn convert_proposal_fin_to_vec_u8_and_back() {
+    let mut rng = get_rng();
+    let proposal_fin = ProposalFin { block_hash: BlockHash::get_test_instance(&mut rng) };
+    convert_proposal_part_to_vec_u8_and_back(ProposalPart::Fin(proposal_fin));
+}
+
+fn encode_with_protocol_version(message: ConsensusMessage, protocol_version: u32) -> Vec<u8> {
+    let mut protobuf_message = protobuf::ConsensusMessage::from(message);
+    protobuf_message.protocol_version = protocol_version;
+    protobuf_message.encode_to_vec()
+}
+
+#[test]
+fn accept_adjacent_protocol_versions() {
+    let message = ConsensusMessage::Vote(Vote { height: 1, ..Default::default() });
+    for protocol_version in
+        [CONSENSUS_PROTOCOL_VERSION - 1, CONSENSUS_PROTOCOL_VERSION, CONSENSUS_PROTOCOL_VERSION + 1]
+    {
+        let bytes_data = encode_with_protocol_version(message.clone(), protocol_version);
+        assert_eq!(ConsensusMessage::try_from(bytes_data).unwrap(), message);
+    }
+}
+
+#[test]
+fn reject_incompatible_protocol_version() {
+    let message = ConsensusMessage::Vote(Vote::default());
+    let protocol_version = CONSENSUS_PROTOCOL_VERSION + 2;
+    let bytes_data = encode_with_protocol_version(message, protocol_version);
+    assert_eq!(
+        ConsensusMessage::try_from(bytes_data).unwrap_err(),
+        ProtobufConversionError::IncompatibleProtocolVersion {
+            version: protocol_version,
+            supported_version: CONSENSUS_PROTOCOL_VERSION,
+        }
+    );
+}
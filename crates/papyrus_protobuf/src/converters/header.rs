@@ -25,6 +25,7 @@ use starknet_api::hash::PoseidonHash;
 
 use super::common::{enum_int_to_l1_data_availability_mode, l1_data_availability_mode_to_enum_int};
 use super::ProtobufConversionError;
+use crate::consensus::Vote;
 use crate::sync::{DataOrFin, HeaderQuery, Query, SignedBlockHeader};
 use crate::{auto_impl_into_and_try_from_vec_u8, protobuf};
 
@@ -217,6 +218,11 @@ impl TryFrom<protobuf::SignedBlockHeader> for SignedBlockHeader {
                 .into_iter()
                 .map(starknet_api::block::BlockSignature::try_from)
                 .collect::<Result<Vec<_>, _>>()?,
+            precommits: value
+                .precommits
+                .into_iter()
+                .map(Vote::try_from)
+                .collect::<Result<Vec<_>, _>>()?,
         })
     }
 }
@@ -227,8 +233,10 @@ impl From<DataOrFin<SignedBlockHeader>> for protobuf::BlockHeadersResponse {
     }
 }
 
-impl From<(BlockHeader, Vec<BlockSignature>)> for protobuf::SignedBlockHeader {
-    fn from((header, signatures): (BlockHeader, Vec<BlockSignature>)) -> Self {
+impl From<(BlockHeader, Vec<BlockSignature>, Vec<Vote>)> for protobuf::SignedBlockHeader {
+    fn from(
+        (header, signatures, precommits): (BlockHeader, Vec<BlockSignature>, Vec<Vote>),
+    ) -> Self {
         let state_diff_commitment =
             header.state_diff_length.map(|state_diff_length| protobuf::StateDiffCommitment {
                 state_diff_length: state_diff_length
@@ -266,6 +274,7 @@ impl From<(BlockHeader, Vec<BlockSignature>)> for protobuf::SignedBlockHeader {
             data_gas_price_fri: Some(header.l1_data_gas_price.price_in_fri.0.into()),
             l1_data_availability_mode: l1_data_availability_mode_to_enum_int(header.l1_da_mode),
             signatures: signatures.iter().map(|signature| (*signature).into()).collect(),
+            precommits: precommits.into_iter().map(protobuf::Vote::from).collect(),
         }
     }
 }
@@ -299,10 +308,10 @@ impl From<starknet_api::block::BlockSignature> for protobuf::ConsensusSignature
 impl From<Option<SignedBlockHeader>> for protobuf::BlockHeadersResponse {
     fn from(data: Option<SignedBlockHeader>) -> Self {
         match data {
-            Some(SignedBlockHeader { block_header, signatures }) => {
+            Some(SignedBlockHeader { block_header, signatures, precommits }) => {
                 protobuf::BlockHeadersResponse {
                     header_message: Some(protobuf::block_headers_response::HeaderMessage::Header(
-                        (block_header, signatures).into(),
+                        (block_header, signatures, precommits).into(),
                     )),
                 }
             }
@@ -0,0 +1,126 @@
+#[cfg(test)]
+#[path = "mempool_test.rs"]
+mod mempool_test;
+
+use starknet_api::contract_class::ClassInfo;
+use starknet_api::executable_transaction::{
+    DeclareTransaction,
+    DeployAccountTransaction,
+    InvokeTransaction,
+    Transaction as ExecutableTransaction,
+};
+use starknet_api::transaction::{Transaction, TransactionHash};
+
+use super::ProtobufConversionError;
+use crate::protobuf;
+
+impl From<ExecutableTransaction> for protobuf::MempoolTransaction {
+    fn from(value: ExecutableTransaction) -> Self {
+        match value {
+            ExecutableTransaction::Declare(DeclareTransaction { tx, tx_hash, class_info }) => {
+                Self {
+                    transaction: Some((Transaction::Declare(tx), tx_hash).into()),
+                    contract_address: None,
+                    class_info: Some(class_info.into()),
+                }
+            }
+            ExecutableTransaction::DeployAccount(DeployAccountTransaction {
+                tx,
+                tx_hash,
+                contract_address,
+            }) => Self {
+                transaction: Some((Transaction::DeployAccount(tx), tx_hash).into()),
+                contract_address: Some(contract_address.into()),
+                class_info: None,
+            },
+            ExecutableTransaction::Invoke(InvokeTransaction { tx, tx_hash }) => Self {
+                transaction: Some((Transaction::Invoke(tx), tx_hash).into()),
+                contract_address: None,
+                class_info: None,
+            },
+        }
+    }
+}
+
+impl TryFrom<protobuf::MempoolTransaction> for ExecutableTransaction {
+    type Error = ProtobufConversionError;
+    fn try_from(value: protobuf::MempoolTransaction) -> Result<Self, Self::Error> {
+        let (tx, tx_hash): (Transaction, TransactionHash) = value
+            .transaction
+            .ok_or(ProtobufConversionError::MissingField {
+                field_description: "MempoolTransaction::transaction",
+            })?
+            .try_into()?;
+        Ok(match tx {
+            Transaction::Declare(tx) => {
+                let class_info = value
+                    .class_info
+                    .ok_or(ProtobufConversionError::MissingField {
+                        field_description: "MempoolTransaction::class_info",
+                    })?
+                    .try_into()?;
+                ExecutableTransaction::Declare(DeclareTransaction { tx, tx_hash, class_info })
+            }
+            Transaction::DeployAccount(tx) => {
+                let contract_address = value
+                    .contract_address
+                    .ok_or(ProtobufConversionError::MissingField {
+                        field_description: "MempoolTransaction::contract_address",
+                    })?
+                    .try_into()?;
+                ExecutableTransaction::DeployAccount(DeployAccountTransaction {
+                    tx,
+                    tx_hash,
+                    contract_address,
+                })
+            }
+            Transaction::Invoke(tx) => {
+                ExecutableTransaction::Invoke(InvokeTransaction { tx, tx_hash })
+            }
+            Transaction::Deploy(_) | Transaction::L1Handler(_) => {
+                return Err(ProtobufConversionError::OutOfRangeValue {
+                    type_description: "MempoolTransaction::transaction",
+                    value_as_str: format!("{tx:?}"),
+                });
+            }
+        })
+    }
+}
+
+impl From<ClassInfo> for protobuf::MempoolClassInfo {
+    fn from(value: ClassInfo) -> Self {
+        Self {
+            casm_contract_class: serde_json::to_vec(&value.casm_contract_class)
+                .expect("Serializing a compiled class should succeed."),
+            sierra_program_length: value.sierra_program_length as u64,
+            abi_length: value.abi_length as u64,
+        }
+    }
+}
+
+impl TryFrom<protobuf::MempoolClassInfo> for ClassInfo {
+    type Error = ProtobufConversionError;
+    fn try_from(value: protobuf::MempoolClassInfo) -> Result<Self, Self::Error> {
+        let casm_contract_class =
+            serde_json::from_slice(&value.casm_contract_class).map_err(|error| {
+                ProtobufConversionError::OutOfRangeValue {
+                    type_description: "MempoolClassInfo::casm_contract_class",
+                    value_as_str: error.to_string(),
+                }
+            })?;
+        let to_usize = |value: u64, type_description: &'static str| {
+            usize::try_from(value).map_err(|_| ProtobufConversionError::OutOfRangeValue {
+                type_description,
+                value_as_str: value.to_string(),
+            })
+        };
+        Ok(Self {
+            casm_contract_class,
+            sierra_program_length: to_usize(
+                value.sierra_program_length,
+                "MempoolClassInfo::sierra_program_length",
+            )?,
+            abi_length: to_usize(value.abi_length, "MempoolClassInfo::abi_length")?,
+        })
+    }
+}
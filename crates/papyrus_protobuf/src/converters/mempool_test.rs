@@ -0,0 +1,50 @@
+use papyrus_test_utils::{get_rng, GetTestInstance};
+use prost::Message;
+use starknet_api::core::{ContractAddress, PatriciaKey};
+use starknet_api::executable_transaction::{
+    DeployAccountTransaction,
+    InvokeTransaction,
+    Transaction as ExecutableTransaction,
+};
+use starknet_api::transaction::{DeployAccountTransactionV1, InvokeTransactionV1, TransactionHash};
+use starknet_api::{contract_address, felt, patricia_key};
+
+use crate::protobuf;
+
+#[test]
+fn convert_invoke_mempool_transaction_to_vec_u8_and_back() {
+    let mut rng = get_rng();
+    let tx = starknet_api::transaction::InvokeTransaction::V1(
+        InvokeTransactionV1::get_test_instance(&mut rng),
+    );
+    let transaction = ExecutableTransaction::Invoke(InvokeTransaction {
+        tx,
+        tx_hash: TransactionHash(felt!(1_u8)),
+    });
+
+    convert_mempool_transaction_to_vec_u8_and_back(transaction);
+}
+
+#[test]
+fn convert_deploy_account_mempool_transaction_to_vec_u8_and_back() {
+    let mut rng = get_rng();
+    let tx = starknet_api::transaction::DeployAccountTransaction::V1(
+        DeployAccountTransactionV1::get_test_instance(&mut rng),
+    );
+    let transaction = ExecutableTransaction::DeployAccount(DeployAccountTransaction {
+        tx,
+        tx_hash: TransactionHash(felt!(1_u8)),
+        contract_address: contract_address!("0x2"),
+    });
+
+    convert_mempool_transaction_to_vec_u8_and_back(transaction);
+}
+
+fn convert_mempool_transaction_to_vec_u8_and_back(transaction: ExecutableTransaction) {
+    let bytes_data = protobuf::MempoolTransaction::from(transaction.clone()).encode_to_vec();
+    let res_data = ExecutableTransaction::try_from(
+        protobuf::MempoolTransaction::decode(&bytes_data[..]).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(transaction, res_data);
+}
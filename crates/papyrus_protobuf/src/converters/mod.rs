@@ -4,9 +4,11 @@ pub mod common;
 pub mod consensus;
 mod event;
 mod header;
+mod mempool;
 mod receipt;
 // TODO(shahak): Internalize this once network doesn't depend on protobuf.
 pub mod state_diff;
+mod state_snapshot;
 mod transaction;
 
 use prost::DecodeError;
@@ -19,6 +21,11 @@ pub enum ProtobufConversionError {
     MissingField { field_description: &'static str },
     #[error("Type `{type_description}` should be {num_expected} bytes but it got {value:?}.")]
     BytesDataLengthMismatch { type_description: &'static str, num_expected: usize, value: Vec<u8> },
+    #[error(
+        "Incompatible consensus protocol version {version}, expected a version adjacent to \
+         {supported_version}."
+    )]
+    IncompatibleProtocolVersion { version: u32, supported_version: u32 },
     #[error(transparent)]
     DecodeError(#[from] DecodeError),
 }
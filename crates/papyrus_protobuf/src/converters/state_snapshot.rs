@@ -0,0 +1,126 @@
+#[cfg(test)]
+#[path = "state_snapshot_test.rs"]
+mod state_snapshot_test;
+
+use papyrus_common::pending_classes::ApiContractClass;
+use prost::Message;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ClassHash, CompiledClassHash};
+
+use super::ProtobufConversionError;
+use crate::sync::{
+    DataOrFin,
+    SnapshotClass,
+    StateSnapshotChunk,
+    StateSnapshotQuery,
+    StateSnapshotStart,
+};
+use crate::{auto_impl_into_and_try_from_vec_u8, protobuf};
+
+impl TryFrom<protobuf::StateSnapshotRequest> for StateSnapshotQuery {
+    type Error = ProtobufConversionError;
+    fn try_from(value: protobuf::StateSnapshotRequest) -> Result<Self, Self::Error> {
+        let start = match value.start {
+            Some(protobuf::state_snapshot_request::Start::ContractsStart(address)) => {
+                StateSnapshotStart::Contracts(address.try_into()?)
+            }
+            Some(protobuf::state_snapshot_request::Start::ClassesStart(class_hash)) => {
+                StateSnapshotStart::Classes(ClassHash(class_hash.try_into()?))
+            }
+            None => {
+                return Err(ProtobufConversionError::MissingField {
+                    field_description: "StateSnapshotRequest::start",
+                });
+            }
+        };
+        Ok(StateSnapshotQuery {
+            block_number: BlockNumber(value.block_number),
+            start,
+            limit: value.limit,
+        })
+    }
+}
+
+impl From<StateSnapshotQuery> for protobuf::StateSnapshotRequest {
+    fn from(value: StateSnapshotQuery) -> Self {
+        let start = match value.start {
+            StateSnapshotStart::Contracts(address) => {
+                protobuf::state_snapshot_request::Start::ContractsStart(address.into())
+            }
+            StateSnapshotStart::Classes(class_hash) => {
+                protobuf::state_snapshot_request::Start::ClassesStart(class_hash.0.into())
+            }
+        };
+        protobuf::StateSnapshotRequest {
+            block_number: value.block_number.0,
+            start: Some(start),
+            limit: value.limit,
+        }
+    }
+}
+
+auto_impl_into_and_try_from_vec_u8!(StateSnapshotQuery, protobuf::StateSnapshotRequest);
+
+impl TryFrom<protobuf::StateSnapshotResponse> for DataOrFin<StateSnapshotChunk> {
+    type Error = ProtobufConversionError;
+    fn try_from(value: protobuf::StateSnapshotResponse) -> Result<Self, Self::Error> {
+        match value.snapshot_message {
+            Some(protobuf::state_snapshot_response::SnapshotMessage::Contract(contract_diff)) => {
+                Ok(DataOrFin(Some(StateSnapshotChunk::Contract(contract_diff.try_into()?))))
+            }
+            Some(protobuf::state_snapshot_response::SnapshotMessage::Class(class)) => {
+                Ok(DataOrFin(Some(StateSnapshotChunk::Class(class.try_into()?))))
+            }
+            Some(protobuf::state_snapshot_response::SnapshotMessage::Fin(_)) => Ok(DataOrFin(None)),
+            None => Err(ProtobufConversionError::MissingField {
+                field_description: "StateSnapshotResponse::snapshot_message",
+            }),
+        }
+    }
+}
+
+impl From<DataOrFin<StateSnapshotChunk>> for protobuf::StateSnapshotResponse {
+    fn from(value: DataOrFin<StateSnapshotChunk>) -> Self {
+        let snapshot_message = match value.0 {
+            Some(StateSnapshotChunk::Contract(contract_diff)) => {
+                protobuf::state_snapshot_response::SnapshotMessage::Contract(contract_diff.into())
+            }
+            Some(StateSnapshotChunk::Class(class)) => {
+                protobuf::state_snapshot_response::SnapshotMessage::Class(class.into())
+            }
+            None => protobuf::state_snapshot_response::SnapshotMessage::Fin(protobuf::Fin {}),
+        };
+        protobuf::StateSnapshotResponse { snapshot_message: Some(snapshot_message) }
+    }
+}
+
+auto_impl_into_and_try_from_vec_u8!(DataOrFin<StateSnapshotChunk>, protobuf::StateSnapshotResponse);
+
+impl TryFrom<protobuf::SnapshotClass> for SnapshotClass {
+    type Error = ProtobufConversionError;
+    fn try_from(value: protobuf::SnapshotClass) -> Result<Self, Self::Error> {
+        let (class, class_hash) = <(ApiContractClass, ClassHash)>::try_from(value.class.ok_or(
+            ProtobufConversionError::MissingField { field_description: "SnapshotClass::class" },
+        )?)?;
+        // According to the P2P specs, if compiled_class_hash is missing, the class is a cairo-0
+        // class.
+        let compiled_class_hash = value
+            .compiled_class_hash
+            .map(|compiled_class_hash| {
+                Ok::<_, ProtobufConversionError>(CompiledClassHash(compiled_class_hash.try_into()?))
+            })
+            .transpose()?;
+        Ok(SnapshotClass { class_hash, compiled_class_hash, class })
+    }
+}
+
+impl From<SnapshotClass> for protobuf::SnapshotClass {
+    fn from(value: SnapshotClass) -> Self {
+        protobuf::SnapshotClass {
+            class: Some((value.class, value.class_hash).into()),
+            compiled_class_hash: value
+                .compiled_class_hash
+                .map(|compiled_class_hash| compiled_class_hash.0.into()),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use papyrus_common::pending_classes::ApiContractClass;
+use papyrus_test_utils::{get_rng, GetTestInstance};
+use starknet_api::core::{ClassHash, CompiledClassHash};
+use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
+use starknet_api::felt;
+use starknet_api::state::ContractClass;
+
+use crate::sync::{ContractDiff, DataOrFin, SnapshotClass, StateSnapshotChunk, StateSnapshotQuery};
+
+#[test]
+fn convert_state_snapshot_query_to_vec_u8_and_back() {
+    let mut rng = get_rng();
+    let state_snapshot_query = StateSnapshotQuery::get_test_instance(&mut rng);
+
+    let bytes_data = Vec::<u8>::from(state_snapshot_query.clone());
+    let res_data = StateSnapshotQuery::try_from(bytes_data).unwrap();
+    assert_eq!(state_snapshot_query, res_data);
+}
+
+#[test]
+fn convert_state_snapshot_chunk_contract_to_vec_u8_and_back() {
+    let mut rng = get_rng();
+    let state_snapshot_chunk =
+        StateSnapshotChunk::Contract(ContractDiff::get_test_instance(&mut rng));
+
+    let data = DataOrFin(Some(state_snapshot_chunk));
+    let bytes_data = Vec::<u8>::from(data.clone());
+    let res_data = DataOrFin::try_from(bytes_data).unwrap();
+    assert_eq!(data, res_data);
+}
+
+#[test]
+fn convert_state_snapshot_chunk_class_to_vec_u8_and_back() {
+    let classes = [
+        SnapshotClass {
+            class_hash: ClassHash(felt!("0x1")),
+            compiled_class_hash: Some(CompiledClassHash(felt!("0x2"))),
+            class: ApiContractClass::ContractClass(ContractClass::default()),
+        },
+        SnapshotClass {
+            class_hash: ClassHash(felt!("0x3")),
+            compiled_class_hash: None,
+            class: ApiContractClass::DeprecatedContractClass(DeprecatedContractClass::default()),
+        },
+    ];
+    for class in classes {
+        let data = DataOrFin(Some(StateSnapshotChunk::Class(class)));
+        let bytes_data = Vec::<u8>::from(data.clone());
+        let res_data = DataOrFin::try_from(bytes_data).unwrap();
+        assert_eq!(data, res_data);
+    }
+}
+
+#[test]
+fn convert_fin_state_snapshot_chunk_to_vec_u8_and_back() {
+    let data = DataOrFin::<StateSnapshotChunk>(None);
+    let bytes_data = Vec::<u8>::from(data.clone());
+    let res_data = DataOrFin::try_from(bytes_data).unwrap();
+    assert_eq!(data, res_data);
+}
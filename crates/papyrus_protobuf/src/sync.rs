@@ -1,6 +1,7 @@
 use std::fmt::Debug;
 
 use indexmap::IndexMap;
+use papyrus_common::pending_classes::ApiContractClass;
 #[cfg(any(feature = "testing", test))]
 use papyrus_test_utils::{auto_impl_get_test_instance, get_number_of_variants, GetTestInstance};
 use starknet_api::block::{BlockHash, BlockHeader, BlockNumber, BlockSignature};
@@ -8,6 +9,8 @@ use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::state::StorageKey;
 use starknet_types_core::felt::Felt;
 
+use crate::consensus::Vote;
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash)]
 pub enum Direction {
     #[default]
@@ -100,6 +103,10 @@ impl From<Query> for ClassQuery {
     }
 }
 
+/// Asks for the classes with the given hashes. They're returned in the order of the hashes.
+#[derive(Default, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ClassHashesQuery(pub Vec<ClassHash>);
+
 #[derive(Default, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct EventQuery(pub Query);
 
@@ -109,10 +116,50 @@ impl From<Query> for EventQuery {
     }
 }
 
+/// Where a [`StateSnapshotQuery`] starts: the contracts from the given address on, or the classes
+/// from the given class hash on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StateSnapshotStart {
+    Contracts(ContractAddress),
+    Classes(ClassHash),
+}
+
+impl Default for StateSnapshotStart {
+    fn default() -> Self {
+        Self::Contracts(ContractAddress::default())
+    }
+}
+
+/// A query for the state after a block, ordered by the contract addresses or the class hashes.
+#[derive(Default, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StateSnapshotQuery {
+    pub block_number: BlockNumber,
+    pub start: StateSnapshotStart,
+    pub limit: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotClass {
+    pub class_hash: ClassHash,
+    // Has value only if the class is a Cairo 1 class.
+    pub compiled_class_hash: Option<CompiledClassHash>,
+    pub class: ApiContractClass,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateSnapshotChunk {
+    // The whole state of a contract, with only its non-zero storage values.
+    Contract(ContractDiff),
+    Class(SnapshotClass),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SignedBlockHeader {
     pub block_header: BlockHeader,
     pub signatures: Vec<BlockSignature>,
+    /// The precommits that certify the consensus decision on the block. Empty for blocks that
+    /// weren't decided by consensus.
+    pub precommits: Vec<Vote>,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
@@ -207,8 +254,19 @@ auto_impl_get_test_instance! {
         Backward=1,
     }
     pub struct HeaderQuery(pub Query);
+    pub struct ClassHashesQuery(pub Vec<ClassHash>);
+    pub struct StateSnapshotQuery {
+        pub block_number: BlockNumber,
+        pub start: StateSnapshotStart,
+        pub limit: u64,
+    }
+    pub enum StateSnapshotStart {
+        Contracts(ContractAddress) = 0,
+        Classes(ClassHash) = 1,
+    }
     pub struct SignedBlockHeader {
         pub block_header: BlockHeader,
         pub signatures: Vec<BlockSignature>,
+        pub precommits: Vec<Vote>,
     }
 }
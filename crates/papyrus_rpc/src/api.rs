@@ -11,8 +11,9 @@ use starknet_api::core::{ChainId, ContractAddress, EntryPointSelector};
 use starknet_api::transaction::Calldata;
 use starknet_client::reader::PendingData;
 use starknet_client::writer::StarknetWriter;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, Semaphore};
 
+use crate::gateway_client::LocalGatewayClient;
 use crate::v0_6::api::api_impl::JsonRpcServerImpl as JsonRpcServerV0_6Impl;
 use crate::v0_7::api::api_impl::JsonRpcServerImpl as JsonRpcServerV0_7Impl;
 use crate::version_config;
@@ -64,6 +65,8 @@ pub fn get_methods_from_supported_apis(
     pending_data: Arc<RwLock<PendingData>>,
     pending_classes: Arc<RwLock<PendingClasses>>,
     starknet_writer: Arc<dyn StarknetWriter>,
+    local_gateway_client: Option<Arc<LocalGatewayClient>>,
+    execution_semaphore: Arc<Semaphore>,
 ) -> Methods {
     let mut methods: Methods = Methods::new();
     let server_gen = JsonRpcServerImplGenerator {
@@ -77,6 +80,8 @@ pub fn get_methods_from_supported_apis(
         pending_data,
         pending_classes,
         starknet_writer,
+        local_gateway_client,
+        execution_semaphore,
     };
     version_config::VERSION_CONFIG
         .iter()
@@ -119,6 +124,8 @@ pub trait JsonRpcServerTrait: Sized {
         pending_data: Arc<RwLock<PendingData>>,
         pending_classes: Arc<RwLock<PendingClasses>>,
         starknet_writer: Arc<dyn StarknetWriter>,
+        local_gateway_client: Option<Arc<LocalGatewayClient>>,
+        execution_semaphore: Arc<Semaphore>,
     ) -> Self;
 
     fn into_rpc_module(self) -> RpcModule<Self>;
@@ -137,6 +144,10 @@ struct JsonRpcServerImplGenerator {
     pending_classes: Arc<RwLock<PendingClasses>>,
     // TODO(shahak): Change this struct to be with a generic type of StarknetWriter.
     starknet_writer: Arc<dyn StarknetWriter>,
+    // When set, transactions are added through it instead of through `starknet_writer`.
+    local_gateway_client: Option<Arc<LocalGatewayClient>>,
+    // Shared by all the versions in order to bound the total number of concurrent executions.
+    execution_semaphore: Arc<Semaphore>,
 }
 
 type JsonRpcServerImplParams = (
@@ -150,6 +161,8 @@ type JsonRpcServerImplParams = (
     Arc<RwLock<PendingData>>,
     Arc<RwLock<PendingClasses>>,
     Arc<dyn StarknetWriter>,
+    Option<Arc<LocalGatewayClient>>,
+    Arc<Semaphore>,
 );
 
 impl JsonRpcServerImplGenerator {
@@ -165,6 +178,8 @@ impl JsonRpcServerImplGenerator {
             self.pending_data,
             self.pending_classes,
             self.starknet_writer,
+            self.local_gateway_client,
+            self.execution_semaphore,
         )
     }
 
@@ -183,6 +198,8 @@ impl JsonRpcServerImplGenerator {
             pending_data,
             pending_classes,
             starknet_writer,
+            local_gateway_client,
+            execution_semaphore,
         ) = self.get_params();
         Into::<Methods>::into(
             T::new(
@@ -196,6 +213,8 @@ impl JsonRpcServerImplGenerator {
                 pending_data,
                 pending_classes,
                 starknet_writer,
+                local_gateway_client,
+                execution_semaphore,
             )
             .into_rpc_module(),
         )
@@ -0,0 +1,151 @@
+//! A client that forwards the transactions added through the JSON-RPC server to the gateway of the
+//! local sequencer, instead of to the Starknet gateway.
+//!
+//! The local gateway accepts only V3 transactions in the format of the [`Starknet specs`], so the
+//! transactions received by the JSON-RPC server are forwarded as they are, without passing through
+//! the Starknet gateway format.
+//!
+//! [`Starknet specs`]: https://github.com/starkware-libs/starknet-specs/blob/master/api/starknet_api_openrpc.json
+
+#[cfg(test)]
+#[path = "gateway_client_test.rs"]
+mod gateway_client_test;
+
+use papyrus_common::class_hash::calculate_class_hash;
+use reqwest::{Client, StatusCode, Url};
+use starknet_api::core::ClassHash;
+use starknet_api::rpc_transaction::{
+    ContractClass,
+    RpcDeclareTransaction,
+    RpcDeclareTransactionV3,
+    RpcDeployAccountTransaction,
+    RpcDeployAccountTransactionV3,
+    RpcInvokeTransaction,
+    RpcInvokeTransactionV3,
+    RpcTransaction,
+};
+use starknet_api::state::{ContractClass as StateContractClass, EntryPointType};
+use starknet_api::transaction::TransactionHash;
+use starknet_client::writer::objects::response::{
+    DeclareResponse,
+    DeployAccountResponse,
+    InvokeResponse,
+    SuccessfulStarknetErrorCode,
+};
+use starknet_client::writer::{WriterClientError, WriterClientResult};
+use starknet_client::{
+    ClientCreationError,
+    ClientError,
+    KnownStarknetErrorCode,
+    StarknetError,
+    StarknetErrorCode,
+};
+use tracing::instrument;
+
+const ADD_TX_URL_SUFFIX: &str = "add_tx";
+
+/// Adds transactions through the gateway of the local sequencer.
+///
+/// The responses are in the format of the Starknet gateway, so that the JSON-RPC server handles
+/// them the same way regardless of where the transactions are added.
+pub struct LocalGatewayClient {
+    add_tx_url: Url,
+    client: Client,
+}
+
+impl LocalGatewayClient {
+    pub fn new(gateway_url: &str) -> Result<Self, ClientCreationError> {
+        Ok(LocalGatewayClient {
+            add_tx_url: Url::parse(gateway_url)?.join(ADD_TX_URL_SUFFIX)?,
+            client: Client::new(),
+        })
+    }
+
+    #[instrument(skip(self, tx), level = "debug")]
+    pub async fn add_invoke_transaction(
+        &self,
+        tx: impl TryInto<RpcInvokeTransactionV3, Error = WriterClientError>,
+    ) -> WriterClientResult<InvokeResponse> {
+        let rpc_tx = RpcTransaction::Invoke(RpcInvokeTransaction::V3(tx.try_into()?));
+        let transaction_hash = self.add_tx(rpc_tx).await?;
+        Ok(InvokeResponse {
+            code: SuccessfulStarknetErrorCode::TransactionReceived,
+            transaction_hash,
+        })
+    }
+
+    #[instrument(skip(self, tx), level = "debug")]
+    pub async fn add_deploy_account_transaction(
+        &self,
+        tx: impl TryInto<RpcDeployAccountTransactionV3, Error = WriterClientError>,
+    ) -> WriterClientResult<DeployAccountResponse> {
+        let rpc_tx = RpcTransaction::DeployAccount(RpcDeployAccountTransaction::V3(tx.try_into()?));
+        let address = rpc_tx.calculate_sender_address().map_err(|err| {
+            WriterClientError::from(ClientError::StarknetError(StarknetError {
+                code: StarknetErrorCode::KnownErrorCode(KnownStarknetErrorCode::MalformedRequest),
+                message: err.to_string(),
+            }))
+        })?;
+        let transaction_hash = self.add_tx(rpc_tx).await?;
+        Ok(DeployAccountResponse {
+            code: SuccessfulStarknetErrorCode::TransactionReceived,
+            transaction_hash,
+            address,
+        })
+    }
+
+    #[instrument(skip(self, tx), level = "debug")]
+    pub async fn add_declare_transaction(
+        &self,
+        tx: impl TryInto<RpcDeclareTransactionV3, Error = WriterClientError>,
+    ) -> WriterClientResult<DeclareResponse> {
+        let rpc_declare_tx = tx.try_into()?;
+        let class_hash = calculate_rpc_class_hash(&rpc_declare_tx.contract_class);
+        let transaction_hash =
+            self.add_tx(RpcTransaction::Declare(RpcDeclareTransaction::V3(rpc_declare_tx))).await?;
+        Ok(DeclareResponse {
+            code: SuccessfulStarknetErrorCode::TransactionReceived,
+            transaction_hash,
+            class_hash,
+        })
+    }
+
+    async fn add_tx(&self, tx: RpcTransaction) -> WriterClientResult<TransactionHash> {
+        let response = self
+            .client
+            .post(self.add_tx_url.clone())
+            .json(&tx)
+            .send()
+            .await
+            .map_err(ClientError::from)?;
+        let code = response.status();
+        let message = response.text().await.map_err(ClientError::from)?;
+        if code != StatusCode::OK {
+            return Err(ClientError::BadResponseStatus { code, message }.into());
+        }
+        Ok(serde_json::from_str(&message)?)
+    }
+}
+
+/// The error returned when a transaction that isn't V3 is added through the local gateway.
+pub(crate) fn unsupported_version_error() -> WriterClientError {
+    ClientError::StarknetError(StarknetError {
+        code: StarknetErrorCode::KnownErrorCode(KnownStarknetErrorCode::InvalidTransactionVersion),
+        message: "The local gateway accepts only V3 transactions.".to_string(),
+    })
+    .into()
+}
+
+fn calculate_rpc_class_hash(contract_class: &ContractClass) -> ClassHash {
+    let entry_points_by_type = &contract_class.entry_points_by_type;
+    calculate_class_hash(&StateContractClass {
+        sierra_program: contract_class.sierra_program.clone(),
+        entry_points_by_type: [
+            (EntryPointType::Constructor, entry_points_by_type.constructor.clone()),
+            (EntryPointType::External, entry_points_by_type.external.clone()),
+            (EntryPointType::L1Handler, entry_points_by_type.l1handler.clone()),
+        ]
+        .into(),
+        abi: contract_class.abi.clone(),
+    })
+}
@@ -0,0 +1,165 @@
+use assert_matches::assert_matches;
+use mockito::Matcher;
+use starknet_api::core::{ContractAddress, Nonce};
+use starknet_api::data_availability::DataAvailabilityMode;
+use starknet_api::rpc_transaction::{
+    RpcDeclareTransactionV3,
+    RpcInvokeTransaction,
+    RpcInvokeTransactionV3,
+    RpcTransaction,
+};
+use starknet_api::state::EntryPoint;
+use starknet_api::transaction::{
+    AccountDeploymentData,
+    Calldata,
+    PaymasterData,
+    ResourceBounds,
+    Tip,
+    TransactionHash,
+    TransactionSignature,
+};
+use starknet_api::{calldata, contract_address, felt};
+use starknet_client::writer::WriterClientError;
+use starknet_client::{ClientError, KnownStarknetErrorCode, StarknetError, StarknetErrorCode};
+
+use super::LocalGatewayClient;
+use crate::v0_7::broadcasted_transaction::{
+    BroadcastedDeclareTransaction,
+    BroadcastedDeclareV3Transaction,
+    DeclareType,
+};
+use crate::v0_7::state::{ContractClass, EntryPointByType};
+use crate::v0_7::transaction::{
+    InvokeTransaction,
+    InvokeTransactionV1,
+    InvokeTransactionV3,
+    ResourceBoundsMapping,
+    TransactionVersion3,
+    TypedInvokeTransaction,
+};
+
+const L1_GAS_BOUNDS: ResourceBounds = ResourceBounds { max_amount: 10, max_price_per_unit: 20 };
+const L2_GAS_BOUNDS: ResourceBounds = ResourceBounds { max_amount: 30, max_price_per_unit: 40 };
+
+fn resource_bounds() -> ResourceBoundsMapping {
+    ResourceBoundsMapping { l1_gas: L1_GAS_BOUNDS, l2_gas: L2_GAS_BOUNDS }
+}
+
+fn invoke_v3() -> TypedInvokeTransaction {
+    TypedInvokeTransaction::Invoke(InvokeTransaction::Version3(InvokeTransactionV3 {
+        sender_address: contract_address!("0x3"),
+        calldata: calldata![felt!(2_u8)],
+        version: TransactionVersion3::Version3,
+        signature: TransactionSignature(vec![felt!(5_u8)]),
+        nonce: Nonce(felt!(4_u8)),
+        resource_bounds: resource_bounds(),
+        tip: Tip(1),
+        paymaster_data: PaymasterData::default(),
+        account_deployment_data: AccountDeploymentData::default(),
+        nonce_data_availability_mode: DataAvailabilityMode::L2,
+        fee_data_availability_mode: DataAvailabilityMode::L1,
+    }))
+}
+
+#[test]
+fn invoke_v3_conversion() {
+    let rpc_tx = RpcInvokeTransactionV3::try_from(invoke_v3()).unwrap();
+    assert_eq!(rpc_tx.sender_address, contract_address!("0x3"));
+    assert_eq!(rpc_tx.resource_bounds.l1_gas, L1_GAS_BOUNDS);
+    assert_eq!(rpc_tx.resource_bounds.l2_gas, L2_GAS_BOUNDS);
+    // The L1 data gas isn't bounded in this version of the specs.
+    assert_eq!(rpc_tx.resource_bounds.l1_data_gas, ResourceBounds::default());
+    assert_eq!(rpc_tx.nonce_data_availability_mode, DataAvailabilityMode::L2);
+    assert_eq!(rpc_tx.fee_data_availability_mode, DataAvailabilityMode::L1);
+}
+
+#[test]
+fn old_transaction_versions_are_unsupported() {
+    let invoke_v1 =
+        TypedInvokeTransaction::Invoke(InvokeTransaction::Version1(InvokeTransactionV1::default()));
+    assert_matches!(
+        RpcInvokeTransactionV3::try_from(invoke_v1),
+        Err(WriterClientError::ClientError(ClientError::StarknetError(StarknetError {
+            code: StarknetErrorCode::KnownErrorCode(
+                KnownStarknetErrorCode::InvalidTransactionVersion
+            ),
+            ..
+        })))
+    );
+}
+
+#[test]
+fn declare_v3_conversion_keeps_contract_class() {
+    let sierra_program = vec![felt!(1_u8), felt!(2_u8)];
+    let entry_point = EntryPoint::default();
+    let declare_v3 = BroadcastedDeclareTransaction::V3(BroadcastedDeclareV3Transaction {
+        r#type: DeclareType::Declare,
+        sender_address: ContractAddress::default(),
+        compiled_class_hash: Default::default(),
+        signature: TransactionSignature::default(),
+        nonce: Nonce::default(),
+        contract_class: ContractClass {
+            sierra_program: sierra_program.clone(),
+            contract_class_version: "0.1.0".to_string(),
+            entry_points_by_type: EntryPointByType {
+                external: vec![entry_point.clone()],
+                ..Default::default()
+            },
+            abi: String::new(),
+        },
+        resource_bounds: resource_bounds(),
+        tip: Tip::default(),
+        paymaster_data: PaymasterData::default(),
+        account_deployment_data: AccountDeploymentData::default(),
+        nonce_data_availability_mode: DataAvailabilityMode::L1,
+        fee_data_availability_mode: DataAvailabilityMode::L2,
+    });
+
+    let rpc_tx = RpcDeclareTransactionV3::try_from(declare_v3).unwrap();
+    assert_eq!(rpc_tx.fee_data_availability_mode, DataAvailabilityMode::L2);
+    let contract_class = rpc_tx.contract_class;
+    assert_eq!(contract_class.sierra_program, sierra_program);
+    assert_eq!(contract_class.contract_class_version, "0.1.0");
+    assert_eq!(contract_class.entry_points_by_type.external, vec![entry_point]);
+    assert!(contract_class.entry_points_by_type.constructor.is_empty());
+    assert!(contract_class.entry_points_by_type.l1handler.is_empty());
+}
+
+#[tokio::test]
+async fn add_invoke_transaction_posts_to_local_gateway() {
+    let expected_rpc_tx = RpcTransaction::Invoke(RpcInvokeTransaction::V3(
+        RpcInvokeTransactionV3::try_from(invoke_v3()).unwrap(),
+    ));
+    let transaction_hash = TransactionHash(felt!(6_u8));
+
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/add_tx")
+        .match_body(Matcher::Json(serde_json::to_value(&expected_rpc_tx).unwrap()))
+        .with_body(serde_json::to_string(&transaction_hash).unwrap())
+        .create_async()
+        .await;
+
+    let client = LocalGatewayClient::new(&server.url()).unwrap();
+    let response = client.add_invoke_transaction(invoke_v3()).await.unwrap();
+    assert_eq!(response.transaction_hash, transaction_hash);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn local_gateway_rejection_is_returned() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/add_tx")
+        .with_status(400)
+        .with_body("Invalid transaction nonce")
+        .create_async()
+        .await;
+
+    let client = LocalGatewayClient::new(&server.url()).unwrap();
+    assert_matches!(
+        client.add_invoke_transaction(invoke_v3()).await,
+        Err(WriterClientError::ClientError(ClientError::BadResponseStatus { .. }))
+    );
+    mock.assert_async().await;
+}
@@ -4,11 +4,13 @@
 
 mod api;
 mod compression_utils;
+mod gateway_client;
 mod middleware;
 mod pending;
 mod rpc_metrics;
 #[cfg(test)]
 mod rpc_test;
+mod subscription;
 mod syncing_state;
 #[cfg(test)]
 mod test_utils;
@@ -29,23 +31,29 @@ use jsonrpsee::types::ErrorObjectOwned;
 pub use latest::error;
 use papyrus_common::pending_classes::PendingClasses;
 use papyrus_common::BlockHashAndNumber;
-use papyrus_config::dumping::{append_sub_config_name, ser_param, SerializeConfig};
+use papyrus_config::dumping::{
+    append_sub_config_name,
+    ser_optional_param,
+    ser_param,
+    SerializeConfig,
+};
 use papyrus_config::validators::validate_ascii;
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use papyrus_execution::ExecutionConfig;
-use papyrus_storage::base_layer::BaseLayerStorageReader;
+use papyrus_storage::base_layer::{BaseLayerStorageReader, BlockFinalityStatus};
 use papyrus_storage::body::events::EventIndex;
 use papyrus_storage::db::TransactionKind;
 use papyrus_storage::state::StateStorageReader;
 use papyrus_storage::{StorageReader, StorageScope, StorageTxn};
+pub use rpc_metrics::update_deprecated_class_metrics;
 use rpc_metrics::MetricLogger;
 use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockNumber, BlockStatus};
 use starknet_api::core::ChainId;
 use starknet_client::reader::PendingData;
-use starknet_client::writer::StarknetGatewayClient;
+use starknet_client::writer::{StarknetGatewayClient, StarknetWriter};
 use starknet_client::RetryConfig;
-use tokio::sync::RwLock;
+use tokio::sync::{OwnedSemaphorePermit, RwLock, Semaphore};
 use tracing::{debug, error, info, instrument};
 // Aliasing the latest version of the RPC.
 use v0_7 as latest;
@@ -53,7 +61,9 @@ pub use v0_7::api::CompiledContractClass;
 use validator::Validate;
 
 use crate::api::get_methods_from_supported_apis;
+use crate::gateway_client::LocalGatewayClient;
 use crate::middleware::{deny_requests_with_unsupported_path, proxy_rpc_request};
+use crate::subscription::{blocks_updates_channel, publish_blocks_updates, subscription_module};
 use crate::syncing_state::get_last_synced_block;
 pub use crate::v0_6::transaction::{
     InvokeTransaction as InvokeTransactionRPC0_6,
@@ -78,7 +88,13 @@ pub struct RpcConfig {
     pub max_events_chunk_size: usize,
     pub max_events_keys: usize,
     pub collect_metrics: bool,
+    pub max_subscriptions_per_connection: u32,
+    #[validate(range(min = 1))]
+    pub max_concurrent_executions: usize,
     pub starknet_url: String,
+    // If set, the add-transaction methods are forwarded to the gateway of the local sequencer at
+    // this URL instead of to the Starknet gateway at `starknet_url`.
+    pub gateway_url: Option<String>,
     pub starknet_gateway_retry_config: RetryConfig,
     pub execution_config: ExecutionConfig,
 }
@@ -91,7 +107,10 @@ impl Default for RpcConfig {
             max_events_chunk_size: 1000,
             max_events_keys: 100,
             collect_metrics: false,
+            max_subscriptions_per_connection: 1024,
+            max_concurrent_executions: 4,
             starknet_url: String::from("https://alpha-mainnet.starknet.io/"),
+            gateway_url: None,
             starknet_gateway_retry_config: RetryConfig {
                 retry_base_millis: 50,
                 retry_max_delay_millis: 1000,
@@ -135,6 +154,20 @@ impl SerializeConfig for RpcConfig {
                 "If true, collect metrics for the rpc.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "max_subscriptions_per_connection",
+                &self.max_subscriptions_per_connection,
+                "Maximum number of subscriptions a single WebSocket connection may have.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_concurrent_executions",
+                &self.max_concurrent_executions,
+                "Maximum number of requests that execute transactions (calls, fee estimations, \
+                 simulations and traces) at the same time. Other such requests wait for their \
+                 turn.",
+                ParamPrivacyInput::Public,
+            ),
             ser_param(
                 "starknet_url",
                 &self.starknet_url,
@@ -142,6 +175,14 @@ impl SerializeConfig for RpcConfig {
                 ParamPrivacyInput::Public,
             ),
         ]);
+        self_params_dump.extend(ser_optional_param(
+            &self.gateway_url,
+            String::from("http://localhost:8080/"),
+            "gateway_url",
+            "URL of the gateway of the local sequencer. If set, write_api methods add the \
+             transactions through it instead of through starknet_url.",
+            ParamPrivacyInput::Public,
+        ));
 
         self_params_dump
             .append(&mut append_sub_config_name(self.execution_config.dump(), "execution_config"));
@@ -171,6 +212,13 @@ fn internal_server_error_with_msg(err: impl Display) -> ErrorObjectOwned {
     ErrorObjectOwned::owned(InternalError.code(), err.to_string(), None::<()>)
 }
 
+// The permit should be held until the execution is done, even if the request is dropped.
+async fn acquire_execution_permit(
+    execution_semaphore: &Arc<Semaphore>,
+) -> RpcResult<OwnedSemaphorePermit> {
+    execution_semaphore.clone().acquire_owned().await.map_err(internal_server_error)
+}
+
 fn verify_storage_scope(storage_reader: &StorageReader) -> RpcResult<()> {
     match storage_reader.get_scope() {
         StorageScope::StateOnly => {
@@ -191,11 +239,15 @@ fn get_block_status<Mode: TransactionKind>(
     txn: &StorageTxn<'_, Mode>,
     block_number: BlockNumber,
 ) -> Result<BlockStatus, ErrorObjectOwned> {
-    let base_layer_tip = txn.get_base_layer_block_marker().map_err(internal_server_error)?;
-    let status = if block_number < base_layer_tip {
-        BlockStatus::AcceptedOnL1
-    } else {
-        BlockStatus::AcceptedOnL2
+    let finality_status =
+        txn.get_block_finality_status(block_number).map_err(internal_server_error)?;
+    // The specs don't distinguish blocks that were sent to the base layer from blocks that are
+    // only accepted on L2.
+    let status = match finality_status {
+        Some(BlockFinalityStatus::AcceptedOnL1) => BlockStatus::AcceptedOnL1,
+        Some(BlockFinalityStatus::SentToL1 | BlockFinalityStatus::AcceptedOnL2) | None => {
+            BlockStatus::AcceptedOnL2
+        }
     };
 
     Ok(status)
@@ -215,26 +267,41 @@ pub async fn run_server(
 ) -> anyhow::Result<(SocketAddr, ServerHandle)> {
     let starting_block = get_last_synced_block(storage_reader.clone())?;
     debug!("Starting JSON-RPC.");
-    let methods = get_methods_from_supported_apis(
+    let writer_client: Arc<dyn StarknetWriter> = Arc::new(StarknetGatewayClient::new(
+        &config.starknet_url,
+        node_version,
+        config.starknet_gateway_retry_config,
+    )?);
+    let local_gateway_client = match &config.gateway_url {
+        Some(gateway_url) => Some(Arc::new(LocalGatewayClient::new(gateway_url)?)),
+        None => None,
+    };
+    let blocks_updates_sender = blocks_updates_channel();
+    let mut methods = get_methods_from_supported_apis(
         &config.chain_id,
-        config.execution_config,
-        storage_reader,
+        config.execution_config.clone(),
+        storage_reader.clone(),
         config.max_events_chunk_size,
         config.max_events_keys,
         starting_block,
         shared_highest_block,
-        pending_data,
+        pending_data.clone(),
         pending_classes,
-        Arc::new(StarknetGatewayClient::new(
-            &config.starknet_url,
-            node_version,
-            config.starknet_gateway_retry_config,
-        )?),
+        writer_client,
+        local_gateway_client,
+        Arc::new(Semaphore::new(config.max_concurrent_executions)),
     );
+    methods.merge(subscription_module(
+        storage_reader.clone(),
+        pending_data,
+        blocks_updates_sender.clone(),
+    ))?;
     let addr;
     let handle;
-    let server_builder =
-        ServerBuilder::default().max_request_body_size(SERVER_MAX_BODY_SIZE).set_middleware(
+    let server_builder = ServerBuilder::default()
+        .max_request_body_size(SERVER_MAX_BODY_SIZE)
+        .max_subscriptions_per_connection(config.max_subscriptions_per_connection)
+        .set_middleware(
             tower::ServiceBuilder::new()
                 .filter_async(deny_requests_with_unsupported_path)
                 .filter_async(proxy_rpc_request),
@@ -252,6 +319,13 @@ pub async fn run_server(
         addr = server.local_addr()?;
         handle = server.start(methods);
     }
+    let server_handle = handle.clone();
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = publish_blocks_updates(storage_reader, blocks_updates_sender) => {},
+            _ = server_handle.stopped() => {},
+        }
+    });
     info!(local_address = %addr, "JSON-RPC is running.");
     Ok((addr, handle))
 }
@@ -1,4 +1,4 @@
-use hyper::{Body, Request};
+use hyper::{header, Body, Request};
 use jsonrpsee::core::http_helpers::read_body;
 use regex::Regex;
 use tower::BoxError;
@@ -11,6 +11,8 @@ use crate::SERVER_MAX_BODY_SIZE;
 /// The middleware reads the JsonRPC request body and request path
 /// then prefixes the method name with the appropriate version identifier.
 /// It returns a new [`hyper::Request`] object with the new method name.
+/// WebSocket upgrade requests are passed as is, since WebSocket connections serve only the
+/// unversioned subscription methods.
 ///
 /// # Arguments
 /// * req - [`hyper::Request`] object passed by the server.
@@ -18,6 +20,9 @@ use crate::SERVER_MAX_BODY_SIZE;
 /// [`Tower`]: https://crates.io/crates/tower
 pub(crate) async fn proxy_rpc_request(req: Request<Body>) -> Result<Request<Body>, BoxError> {
     debug!("proxy_rpc_request -> Request received: {:?}", req);
+    if is_websocket_upgrade(&req) {
+        return Ok(req);
+    }
     let uri = &req.uri().clone();
     let prefix = get_version_as_prefix(uri.path())?;
     let (parts, body) = req.into_parts();
@@ -55,6 +60,12 @@ pub(crate) async fn deny_requests_with_unsupported_path(
     }
 }
 
+fn is_websocket_upgrade(req: &Request<Body>) -> bool {
+    req.headers()
+        .get(header::UPGRADE)
+        .is_some_and(|upgrade| upgrade.as_bytes().eq_ignore_ascii_case(b"websocket"))
+}
+
 fn add_version_to_method_name_in_body(
     mut vec_body: Vec<jsonrpsee::types::Request<'_>>,
     prefix: &str,
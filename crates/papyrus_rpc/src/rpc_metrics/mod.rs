@@ -8,17 +8,27 @@ use std::time::Instant;
 use jsonrpsee::server::logger::{HttpRequest, Logger, MethodKind, TransportProtocol};
 use jsonrpsee::types::Params;
 use jsonrpsee::Methods;
-use metrics::{histogram, increment_counter, register_counter, register_histogram};
+use metrics::{
+    absolute_counter,
+    histogram,
+    increment_counter,
+    register_counter,
+    register_histogram,
+};
+use papyrus_execution::{deprecated_class_usage_report, DeprecatedClassUsage};
 
 // Name of the metrics.
 const INCOMING_REQUEST: &str = "rpc_incoming_requests";
 const FAILED_REQUESTS: &str = "rpc_failed_requests";
 const REQUEST_LATENCY: &str = "rpc_request_latency_seconds";
+const DEPRECATED_CLASS_EXECUTIONS: &str = "rpc_deprecated_class_executions";
 
 // Labels for the metrics.
 const METHOD_LABEL: &str = "method";
 const VERSION_LABEL: &str = "version";
 const ILLEGAL_METHOD: &str = "illegal_method";
+const CLASS_HASH_LABEL: &str = "class_hash";
+const CONTRACT_ADDRESS_LABEL: &str = "contract_address";
 
 // Register the metrics and returns a set of the method names.
 fn init_metrics(methods: &Methods) -> HashSet<String> {
@@ -104,6 +114,21 @@ impl Logger for MetricLogger {
     fn on_disconnect(&self, _remote_addr: SocketAddr, _transport: TransportProtocol) {}
 }
 
+/// Updates the number of executions of contracts with deprecated (Cairo 0) classes, with a metric
+/// per contract and class, labelled by both.
+pub fn update_deprecated_class_metrics() {
+    for (DeprecatedClassUsage { class_hash, storage_address }, count) in
+        deprecated_class_usage_report()
+    {
+        absolute_counter!(
+            DEPRECATED_CLASS_EXECUTIONS,
+            count,
+            CLASS_HASH_LABEL => format!("{:#064x}", class_hash.0),
+            CONTRACT_ADDRESS_LABEL => format!("{:#064x}", storage_address.0.key())
+        );
+    }
+}
+
 // Given method_name returns (method, version).
 // Example: method_name: starknet_V0_6_0_blockNumber; output: (blockNumber, V0_6_0).
 fn get_method_and_version(method_name: &str) -> (String, String) {
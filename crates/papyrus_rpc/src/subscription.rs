@@ -0,0 +1,467 @@
+//! Subscriptions of WebSocket clients to new data.
+//!
+//! A single task publishes the changes in the blocks of the storage on a broadcast channel. It
+//! reads the storage whenever a write transaction is committed to it. Each subscription to new
+//! heads or to events (see [`SubscriptionRequest`]) reads the changes from the channel and notifies
+//! its client about the data it subscribed to, and about blocks that were reverted. A notification
+//! is sent only after the client's connection has room for it, so a slow client falls behind the
+//! channel. A subscription whose client fell behind by more than [`NEW_BLOCKS_CHANNEL_CAPACITY`]
+//! changes is closed.
+//!
+//! A subscription to the status of a transaction reads the status whenever a write transaction is
+//! committed to the storage, and notifies its client whenever the status changes.
+
+#[cfg(test)]
+#[path = "subscription_test.rs"]
+mod subscription_test;
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+
+use jsonrpsee::core::{StringError, SubscriptionResult};
+use jsonrpsee::{RpcModule, SubscriptionMessage, SubscriptionSink};
+use papyrus_storage::base_layer::{BaseLayerStorageReader, BlockFinalityStatus};
+use papyrus_storage::body::{BodyStorageReader, TransactionIndex};
+use papyrus_storage::header::HeaderStorageReader;
+use papyrus_storage::state::StateStorageReader;
+use papyrus_storage::{StorageError, StorageReader};
+use serde::{Deserialize, Serialize};
+use starknet_api::block::{BlockHash, BlockNumber};
+use starknet_api::core::ContractAddress;
+use starknet_api::transaction::{
+    EventKey,
+    TransactionExecutionStatus,
+    TransactionHash,
+    TransactionOutput,
+};
+use starknet_client::reader::PendingData;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::{self, Receiver, Sender};
+use tokio::sync::RwLock;
+use tracing::{debug, error};
+
+use crate::latest::block::BlockHeader;
+use crate::latest::transaction::Event;
+
+pub(crate) const SUBSCRIBE_METHOD: &str = "starknet_subscribe";
+pub(crate) const NOTIFICATION_METHOD: &str = "starknet_subscription";
+pub(crate) const UNSUBSCRIBE_METHOD: &str = "starknet_unsubscribe";
+
+/// The number of changes in the blocks a subscription may fall behind before it's closed. This is
+/// also the number of latest blocks whose reverts are detected.
+pub(crate) const NEW_BLOCKS_CHANNEL_CAPACITY: usize = 128;
+
+/// The data a client can subscribe to.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(tag = "type")]
+pub enum SubscriptionRequest {
+    /// The header of every new block.
+    #[serde(rename = "newHeads")]
+    NewHeads,
+    /// The events of new blocks that match the filter. The filter has the same semantics as the
+    /// filter of `starknet_getEvents`.
+    #[serde(rename = "events")]
+    Events {
+        #[serde(default)]
+        address: Option<ContractAddress>,
+        #[serde(default)]
+        keys: Vec<HashSet<EventKey>>,
+    },
+    /// Every change in the status of a transaction. The subscription is closed once the
+    /// transaction is accepted on L1.
+    #[serde(rename = "transactionStatus")]
+    TransactionStatus { transaction_hash: TransactionHash },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum SubscriptionNotification {
+    /// The blocks in the range were reverted. The data that was notified about them is no longer
+    /// valid.
+    Reorg {
+        first_reverted_block_number: BlockNumber,
+        last_reverted_block_number: BlockNumber,
+    },
+    NewHead(BlockHeader),
+    Event(Event),
+    TransactionStatus {
+        transaction_hash: TransactionHash,
+        status: SubscribedTransactionStatus,
+    },
+}
+
+/// The finality status of a transaction a client subscribed to.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Eq, PartialEq)]
+pub enum SubscribedTransactionFinalityStatus {
+    /// The transaction isn't in any block, including the pending block. A transaction gets this
+    /// status after the block that included it was reverted.
+    #[serde(rename = "NOT_FOUND")]
+    NotFound,
+    /// The transaction is in the pending block.
+    #[serde(rename = "PENDING")]
+    Pending,
+    #[serde(rename = "ACCEPTED_ON_L2")]
+    AcceptedOnL2,
+    #[serde(rename = "ACCEPTED_ON_L1")]
+    AcceptedOnL1,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+pub struct SubscribedTransactionStatus {
+    pub finality_status: SubscribedTransactionFinalityStatus,
+    // Missing for a transaction that wasn't found.
+    #[serde(flatten, default, skip_serializing_if = "Option::is_none")]
+    pub execution_status: Option<TransactionExecutionStatus>,
+}
+
+/// A block that was added to the storage, as published to the subscriptions.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct NewBlock {
+    pub header: BlockHeader,
+    pub transactions: Vec<(TransactionHash, TransactionOutput)>,
+}
+
+/// A change in the blocks of the storage, as published to the subscriptions.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum BlocksUpdate {
+    NewBlock(NewBlock),
+    Reorg { first_reverted_block_number: BlockNumber, last_reverted_block_number: BlockNumber },
+}
+
+#[derive(Clone)]
+pub(crate) struct SubscriptionContext {
+    storage_reader: StorageReader,
+    pending_data: Arc<RwLock<PendingData>>,
+    blocks_updates_sender: Sender<Arc<BlocksUpdate>>,
+}
+
+/// Returns the module that handles the subscription methods. The subscriptions to new heads and
+/// events are fed from the given sender.
+pub(crate) fn subscription_module(
+    storage_reader: StorageReader,
+    pending_data: Arc<RwLock<PendingData>>,
+    blocks_updates_sender: Sender<Arc<BlocksUpdate>>,
+) -> RpcModule<SubscriptionContext> {
+    let mut module =
+        RpcModule::new(SubscriptionContext { storage_reader, pending_data, blocks_updates_sender });
+    module
+        .register_subscription(
+            SUBSCRIBE_METHOD,
+            NOTIFICATION_METHOD,
+            UNSUBSCRIBE_METHOD,
+            |params, pending, context| async move {
+                let request = match params.one::<SubscriptionRequest>() {
+                    Ok(request) => request,
+                    Err(err) => {
+                        pending.reject(err).await;
+                        return Ok(());
+                    }
+                };
+                match request {
+                    SubscriptionRequest::TransactionStatus { transaction_hash } => {
+                        let sink = pending.accept().await?;
+                        run_transaction_status_subscription(transaction_hash, sink, &context).await
+                    }
+                    request => {
+                        // Subscribing before accepting so that no block is missed.
+                        let blocks_updates_receiver = context.blocks_updates_sender.subscribe();
+                        let sink = pending.accept().await?;
+                        run_blocks_subscription(request, sink, blocks_updates_receiver).await
+                    }
+                }
+            },
+        )
+        .expect("The subscription methods should have unique names.");
+    module
+}
+
+async fn run_blocks_subscription(
+    request: SubscriptionRequest,
+    sink: SubscriptionSink,
+    mut blocks_updates_receiver: Receiver<Arc<BlocksUpdate>>,
+) -> SubscriptionResult {
+    loop {
+        let blocks_update = tokio::select! {
+            _ = sink.closed() => return Ok(()),
+            blocks_update = blocks_updates_receiver.recv() => match blocks_update {
+                Ok(blocks_update) => blocks_update,
+                Err(RecvError::Lagged(n_skipped)) => {
+                    debug!(
+                        "Closing subscription {:?} that fell behind by {n_skipped} updates.",
+                        sink.subscription_id()
+                    );
+                    return Err(StringError::from(format!(
+                        "The subscription fell behind by {n_skipped} updates."
+                    )));
+                }
+                Err(RecvError::Closed) => return Ok(()),
+            },
+        };
+        for notification in get_notifications(&request, &blocks_update) {
+            send(&sink, &notification).await?;
+        }
+    }
+}
+
+async fn run_transaction_status_subscription(
+    transaction_hash: TransactionHash,
+    sink: SubscriptionSink,
+    context: &SubscriptionContext,
+) -> SubscriptionResult {
+    // Subscribing before reading the status so that no change is missed.
+    let mut commits_receiver = context.storage_reader.subscribe_to_commits();
+    let mut last_notified_status = None;
+    loop {
+        let status = get_transaction_status(
+            &context.storage_reader,
+            &context.pending_data,
+            &transaction_hash,
+        )
+        .await?;
+        // A transaction that wasn't found since the subscription started has no status to notify.
+        let was_found = last_notified_status.is_some()
+            || status.finality_status != SubscribedTransactionFinalityStatus::NotFound;
+        if was_found && last_notified_status.as_ref() != Some(&status) {
+            send(
+                &sink,
+                &SubscriptionNotification::TransactionStatus {
+                    transaction_hash,
+                    status: status.clone(),
+                },
+            )
+            .await?;
+            if status.finality_status == SubscribedTransactionFinalityStatus::AcceptedOnL1 {
+                return Ok(());
+            }
+            last_notified_status = Some(status);
+        }
+        tokio::select! {
+            _ = sink.closed() => return Ok(()),
+            changed = commits_receiver.changed() => {
+                if changed.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+// Waits until the connection has room for the notification.
+async fn send(
+    sink: &SubscriptionSink,
+    notification: &SubscriptionNotification,
+) -> SubscriptionResult {
+    sink.send(SubscriptionMessage::from_json(notification)?).await?;
+    Ok(())
+}
+
+pub(crate) fn get_notifications(
+    request: &SubscriptionRequest,
+    blocks_update: &BlocksUpdate,
+) -> Vec<SubscriptionNotification> {
+    let new_block = match blocks_update {
+        BlocksUpdate::NewBlock(new_block) => new_block,
+        BlocksUpdate::Reorg { first_reverted_block_number, last_reverted_block_number } => {
+            return vec![SubscriptionNotification::Reorg {
+                first_reverted_block_number: *first_reverted_block_number,
+                last_reverted_block_number: *last_reverted_block_number,
+            }];
+        }
+    };
+    match request {
+        SubscriptionRequest::NewHeads => {
+            vec![SubscriptionNotification::NewHead(new_block.header.clone())]
+        }
+        SubscriptionRequest::Events { address, keys } => new_block
+            .transactions
+            .iter()
+            .flat_map(|(transaction_hash, output)| {
+                output.events().iter().map(move |event| (transaction_hash, event))
+            })
+            .filter(|(_, event)| {
+                address.map_or(true, |address| address == event.from_address)
+                    && do_event_keys_match(&event.content.keys, keys)
+            })
+            .map(|(transaction_hash, event)| {
+                SubscriptionNotification::Event(Event {
+                    block_hash: Some(new_block.header.block_hash),
+                    block_number: Some(new_block.header.block_number),
+                    transaction_hash: *transaction_hash,
+                    event: event.clone(),
+                })
+            })
+            .collect(),
+        // Transaction statuses aren't read from the published blocks.
+        SubscriptionRequest::TransactionStatus { .. } => vec![],
+    }
+}
+
+fn do_event_keys_match(event_keys: &[EventKey], filter_keys: &[HashSet<EventKey>]) -> bool {
+    filter_keys
+        .iter()
+        .enumerate()
+        .all(|(i, keys)| event_keys.len() > i && (keys.is_empty() || keys.contains(&event_keys[i])))
+}
+
+pub(crate) async fn get_transaction_status(
+    storage_reader: &StorageReader,
+    pending_data: &RwLock<PendingData>,
+    transaction_hash: &TransactionHash,
+) -> Result<SubscribedTransactionStatus, StorageError> {
+    let txn = storage_reader.begin_ro_txn()?;
+    if let Some(transaction_index) = txn.get_transaction_idx_by_hash(transaction_hash)? {
+        let TransactionIndex(block_number, _) = transaction_index;
+        // A transaction is accepted only once the state diff of its block is stored.
+        if block_number < txn.get_state_marker()? {
+            if let Some(output) = txn.get_transaction_output(transaction_index)? {
+                let finality_status = match txn.get_block_finality_status(block_number)? {
+                    Some(BlockFinalityStatus::AcceptedOnL1) => {
+                        SubscribedTransactionFinalityStatus::AcceptedOnL1
+                    }
+                    Some(BlockFinalityStatus::SentToL1 | BlockFinalityStatus::AcceptedOnL2)
+                    | None => SubscribedTransactionFinalityStatus::AcceptedOnL2,
+                };
+                return Ok(SubscribedTransactionStatus {
+                    finality_status,
+                    execution_status: Some(output.execution_status().clone()),
+                });
+            }
+        }
+    }
+
+    let latest_block_hash = match txn.get_state_marker()?.prev() {
+        Some(latest_block_number) => {
+            txn.get_block_header(latest_block_number)?.map(|header| header.block_hash)
+        }
+        None => None,
+    };
+    let pending_data = pending_data.read().await;
+    // The pending block is outdated if it isn't built on top of the latest block.
+    if Some(pending_data.block.parent_block_hash()) == latest_block_hash {
+        let pending_transaction = pending_data
+            .block
+            .transactions()
+            .iter()
+            .zip(pending_data.block.transaction_receipts())
+            .find(|(transaction, _)| transaction.transaction_hash() == *transaction_hash);
+        if let Some((transaction, receipt)) = pending_transaction {
+            let output = receipt.clone().into_starknet_api_transaction_output(transaction);
+            return Ok(SubscribedTransactionStatus {
+                finality_status: SubscribedTransactionFinalityStatus::Pending,
+                execution_status: Some(output.execution_status().clone()),
+            });
+        }
+    }
+    Ok(SubscribedTransactionStatus {
+        finality_status: SubscribedTransactionFinalityStatus::NotFound,
+        execution_status: None,
+    })
+}
+
+/// Publishes every change in the blocks of the storage (with their state diffs) from now on.
+pub(crate) async fn publish_blocks_updates(
+    storage_reader: StorageReader,
+    blocks_updates_sender: Sender<Arc<BlocksUpdate>>,
+) {
+    // Subscribing before reading the state marker so that no commit is missed.
+    let mut commits_receiver = storage_reader.subscribe_to_commits();
+    let mut published_blocks =
+        match storage_reader.begin_ro_txn().and_then(|txn| txn.get_state_marker()) {
+            Ok(state_marker) => PublishedBlocks::new(state_marker),
+            Err(err) => {
+                error!("Failed reading the state marker, subscriptions won't be notified: {err}");
+                return;
+            }
+        };
+    while commits_receiver.changed().await.is_ok() {
+        match published_blocks.read_updates(&storage_reader) {
+            Ok(blocks_updates) => {
+                for blocks_update in blocks_updates {
+                    // An error means there are no subscriptions at the moment.
+                    let _ = blocks_updates_sender.send(Arc::new(blocks_update));
+                }
+            }
+            Err(err) => error!("Failed reading new blocks for subscriptions: {err}"),
+        }
+    }
+}
+
+/// The blocks that were published to the subscriptions.
+pub(crate) struct PublishedBlocks {
+    next_block_number: BlockNumber,
+    // The hashes of the latest published blocks, from the oldest to the newest. Used to detect
+    // reverted blocks.
+    latest_block_hashes: VecDeque<BlockHash>,
+}
+
+impl PublishedBlocks {
+    pub(crate) fn new(next_block_number: BlockNumber) -> Self {
+        Self { next_block_number, latest_block_hashes: VecDeque::new() }
+    }
+
+    /// Reads the blocks that were reverted and the blocks that were added since the last read.
+    pub(crate) fn read_updates(
+        &mut self,
+        storage_reader: &StorageReader,
+    ) -> Result<Vec<BlocksUpdate>, StorageError> {
+        let txn = storage_reader.begin_ro_txn()?;
+        let state_marker = txn.get_state_marker()?;
+        let mut blocks_updates = Vec::new();
+
+        // A published block was reverted if it's no longer in the storage or if another block was
+        // stored in its place.
+        let first_unpublished_block_number = self.next_block_number;
+        while let (Some(block_hash), Some(block_number)) =
+            (self.latest_block_hashes.back(), self.next_block_number.prev())
+        {
+            let stored_block_hash =
+                txn.get_block_header(block_number)?.map(|header| header.block_hash);
+            if block_number < state_marker && stored_block_hash == Some(*block_hash) {
+                break;
+            }
+            self.latest_block_hashes.pop_back();
+            self.next_block_number = block_number;
+        }
+        if self.latest_block_hashes.is_empty() && state_marker < self.next_block_number {
+            self.next_block_number = state_marker;
+        }
+        if let Some(last_reverted_block_number) = first_unpublished_block_number.prev() {
+            if self.next_block_number <= last_reverted_block_number {
+                blocks_updates.push(BlocksUpdate::Reorg {
+                    first_reverted_block_number: self.next_block_number,
+                    last_reverted_block_number,
+                });
+            }
+        }
+
+        while self.next_block_number < state_marker {
+            let block_number = self.next_block_number;
+            let header = txn.get_block_header(block_number)?.ok_or_else(|| {
+                StorageError::DBInconsistency {
+                    msg: format!(
+                        "The header of block {block_number} below the state marker is missing."
+                    ),
+                }
+            })?;
+            let transaction_hashes =
+                txn.get_block_transaction_hashes(block_number)?.unwrap_or_default();
+            let transaction_outputs =
+                txn.get_block_transaction_outputs(block_number)?.unwrap_or_default();
+            self.latest_block_hashes.push_back(header.block_hash);
+            if self.latest_block_hashes.len() > NEW_BLOCKS_CHANNEL_CAPACITY {
+                self.latest_block_hashes.pop_front();
+            }
+            blocks_updates.push(BlocksUpdate::NewBlock(NewBlock {
+                header: header.into(),
+                transactions: transaction_hashes.into_iter().zip(transaction_outputs).collect(),
+            }));
+            self.next_block_number = block_number.unchecked_next();
+        }
+        Ok(blocks_updates)
+    }
+}
+
+/// Creates the channel that feeds the subscriptions to new heads and events.
+pub(crate) fn blocks_updates_channel() -> Sender<Arc<BlocksUpdate>> {
+    broadcast::channel(NEW_BLOCKS_CHANNEL_CAPACITY).0
+}
@@ -0,0 +1,372 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use jsonrpsee::core::client::{Subscription, SubscriptionClientT};
+use jsonrpsee::rpc_params;
+use jsonrpsee::ws_client::WsClientBuilder;
+use papyrus_storage::body::BodyStorageWriter;
+use papyrus_storage::header::HeaderStorageWriter;
+use papyrus_storage::state::StateStorageWriter;
+use papyrus_storage::test_utils::get_test_storage;
+use papyrus_storage::StorageWriter;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use starknet_api::block::{BlockBody, BlockHash, BlockHeader, BlockNumber};
+use starknet_api::core::ContractAddress;
+use starknet_api::state::ThinStateDiff;
+use starknet_api::transaction::{
+    Event as StarknetApiEvent,
+    EventContent,
+    EventKey,
+    InvokeTransaction,
+    InvokeTransactionOutput,
+    InvokeTransactionV1,
+    Transaction,
+    TransactionExecutionStatus,
+    TransactionHash,
+    TransactionOutput,
+};
+use starknet_api::{contract_address, felt};
+use starknet_client::reader::objects::transaction::{
+    IntermediateInvokeTransaction,
+    Transaction as ClientTransaction,
+    TransactionReceipt as ClientTransactionReceipt,
+};
+use starknet_types_core::felt::Felt;
+
+use super::{
+    get_notifications,
+    get_transaction_status,
+    BlocksUpdate,
+    NewBlock,
+    PublishedBlocks,
+    SubscribedTransactionFinalityStatus,
+    SubscribedTransactionStatus,
+    SubscriptionNotification,
+    SubscriptionRequest,
+    SUBSCRIBE_METHOD,
+    UNSUBSCRIBE_METHOD,
+};
+use crate::latest::transaction::Event;
+use crate::run_server;
+use crate::test_utils::{
+    get_test_highest_block,
+    get_test_pending_classes,
+    get_test_pending_data,
+    get_test_rpc_config,
+};
+
+const TRANSACTION_HASH: TransactionHash = TransactionHash(Felt::ONE);
+
+fn event(from_address: ContractAddress, keys: Vec<EventKey>) -> StarknetApiEvent {
+    StarknetApiEvent { from_address, content: EventContent { keys, ..Default::default() } }
+}
+
+fn block_body(events: Vec<StarknetApiEvent>) -> BlockBody {
+    BlockBody {
+        transactions: vec![Transaction::Invoke(InvokeTransaction::V1(
+            InvokeTransactionV1::default(),
+        ))],
+        transaction_outputs: vec![TransactionOutput::Invoke(InvokeTransactionOutput {
+            events,
+            ..Default::default()
+        })],
+        transaction_hashes: vec![TRANSACTION_HASH],
+    }
+}
+
+fn append_block(storage_writer: &mut StorageWriter, block_number: BlockNumber, body: BlockBody) {
+    append_block_with_hash(storage_writer, block_number, BlockHash(block_number.0.into()), body);
+}
+
+fn append_block_with_hash(
+    storage_writer: &mut StorageWriter,
+    block_number: BlockNumber,
+    block_hash: BlockHash,
+    body: BlockBody,
+) {
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(
+            block_number,
+            &BlockHeader { block_number, block_hash, ..Default::default() },
+        )
+        .unwrap()
+        .append_body(block_number, body)
+        .unwrap()
+        .append_state_diff(block_number, ThinStateDiff::default())
+        .unwrap()
+        .commit()
+        .unwrap();
+}
+
+fn revert_block(storage_writer: &mut StorageWriter, block_number: BlockNumber) {
+    let txn = storage_writer.begin_rw_txn().unwrap();
+    let (txn, _) = txn.revert_state_diff(block_number).unwrap();
+    let (txn, _) = txn.revert_body(block_number).unwrap();
+    let (txn, _, _) = txn.revert_header(block_number).unwrap();
+    txn.commit().unwrap();
+}
+
+fn new_block(events: Vec<StarknetApiEvent>) -> NewBlock {
+    let body = block_body(events);
+    NewBlock {
+        header: BlockHeader::default().into(),
+        transactions: body.transaction_hashes.into_iter().zip(body.transaction_outputs).collect(),
+    }
+}
+
+#[test]
+fn new_heads_notification() {
+    let new_block = new_block(vec![]);
+    assert_eq!(
+        get_notifications(
+            &SubscriptionRequest::NewHeads,
+            &BlocksUpdate::NewBlock(new_block.clone())
+        ),
+        vec![SubscriptionNotification::NewHead(new_block.header.clone())]
+    );
+}
+
+#[test]
+fn events_notifications_are_filtered() {
+    let address = contract_address!("0x1");
+    let key = EventKey(felt!(2_u8));
+    let matching_event = event(address, vec![key.clone()]);
+    let new_block = new_block(vec![
+        matching_event.clone(),
+        event(contract_address!("0x3"), vec![key.clone()]),
+        event(address, vec![EventKey(felt!(4_u8))]),
+        event(address, vec![]),
+    ]);
+
+    let request =
+        SubscriptionRequest::Events { address: Some(address), keys: vec![HashSet::from([key])] };
+    assert_eq!(
+        get_notifications(&request, &BlocksUpdate::NewBlock(new_block.clone())),
+        vec![SubscriptionNotification::Event(Event {
+            block_hash: Some(new_block.header.block_hash),
+            block_number: Some(new_block.header.block_number),
+            transaction_hash: TRANSACTION_HASH,
+            event: matching_event,
+        })]
+    );
+}
+
+#[test]
+fn reorg_notification() {
+    let reorg = BlocksUpdate::Reorg {
+        first_reverted_block_number: BlockNumber(1),
+        last_reverted_block_number: BlockNumber(2),
+    };
+    let expected_notification = SubscriptionNotification::Reorg {
+        first_reverted_block_number: BlockNumber(1),
+        last_reverted_block_number: BlockNumber(2),
+    };
+    assert_eq!(
+        get_notifications(&SubscriptionRequest::NewHeads, &reorg),
+        vec![expected_notification.clone()]
+    );
+    let request = SubscriptionRequest::Events { address: None, keys: vec![] };
+    assert_eq!(get_notifications(&request, &reorg), vec![expected_notification]);
+}
+
+#[test]
+fn read_new_blocks_until_state_marker() {
+    let ((storage_reader, mut storage_writer), _temp_dir) = get_test_storage();
+    append_block(&mut storage_writer, BlockNumber(0), block_body(vec![]));
+    append_block(&mut storage_writer, BlockNumber(1), block_body(vec![]));
+    // A block without a state diff isn't published yet.
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(BlockNumber(2), &BlockHeader::default())
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let blocks_updates =
+        PublishedBlocks::new(BlockNumber(1)).read_updates(&storage_reader).unwrap();
+    assert_eq!(blocks_updates.len(), 1);
+    let BlocksUpdate::NewBlock(new_block) = &blocks_updates[0] else {
+        panic!("Expected a new block, got {:?}.", blocks_updates[0]);
+    };
+    assert_eq!(new_block.header.block_number, BlockNumber(1));
+    assert_eq!(new_block.transactions[0].0, TRANSACTION_HASH);
+}
+
+#[test]
+fn read_reverted_blocks() {
+    let ((storage_reader, mut storage_writer), _temp_dir) = get_test_storage();
+    append_block(&mut storage_writer, BlockNumber(0), block_body(vec![]));
+    append_block(&mut storage_writer, BlockNumber(1), block_body(vec![]));
+    let mut published_blocks = PublishedBlocks::new(BlockNumber(0));
+    assert_eq!(published_blocks.read_updates(&storage_reader).unwrap().len(), 2);
+
+    // Block 1 is replaced by another block between the reads.
+    revert_block(&mut storage_writer, BlockNumber(1));
+    let other_block_hash = BlockHash(felt!(5_u8));
+    append_block_with_hash(
+        &mut storage_writer,
+        BlockNumber(1),
+        other_block_hash,
+        block_body(vec![]),
+    );
+    let blocks_updates = published_blocks.read_updates(&storage_reader).unwrap();
+    assert_eq!(blocks_updates.len(), 2);
+    assert_eq!(
+        blocks_updates[0],
+        BlocksUpdate::Reorg {
+            first_reverted_block_number: BlockNumber(1),
+            last_reverted_block_number: BlockNumber(1),
+        }
+    );
+    let BlocksUpdate::NewBlock(new_block) = &blocks_updates[1] else {
+        panic!("Expected a new block, got {:?}.", blocks_updates[1]);
+    };
+    assert_eq!(new_block.header.block_hash, other_block_hash);
+
+    // Both blocks are reverted.
+    revert_block(&mut storage_writer, BlockNumber(1));
+    revert_block(&mut storage_writer, BlockNumber(0));
+    assert_eq!(
+        published_blocks.read_updates(&storage_reader).unwrap(),
+        vec![BlocksUpdate::Reorg {
+            first_reverted_block_number: BlockNumber(0),
+            last_reverted_block_number: BlockNumber(1),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn transaction_status() {
+    let ((storage_reader, mut storage_writer), _temp_dir) = get_test_storage();
+    let pending_data = get_test_pending_data();
+    let not_found = SubscribedTransactionStatus {
+        finality_status: SubscribedTransactionFinalityStatus::NotFound,
+        execution_status: None,
+    };
+    assert_eq!(
+        get_transaction_status(&storage_reader, &pending_data, &TRANSACTION_HASH).await.unwrap(),
+        not_found
+    );
+
+    // The pending block is built on top of block 0.
+    append_block(&mut storage_writer, BlockNumber(0), block_body(vec![]));
+    let pending_transaction_hash = TransactionHash(felt!(5_u8));
+    {
+        let pending_block = &mut pending_data.write().await.block;
+        *pending_block.parent_block_hash_mutable() = BlockHash(Felt::ZERO);
+        pending_block.transactions_mutable().push(ClientTransaction::Invoke(
+            IntermediateInvokeTransaction {
+                transaction_hash: pending_transaction_hash,
+                ..Default::default()
+            },
+        ));
+        pending_block.transaction_receipts_mutable().push(ClientTransactionReceipt {
+            transaction_hash: pending_transaction_hash,
+            ..Default::default()
+        });
+    }
+    assert_eq!(
+        get_transaction_status(&storage_reader, &pending_data, &pending_transaction_hash)
+            .await
+            .unwrap(),
+        SubscribedTransactionStatus {
+            finality_status: SubscribedTransactionFinalityStatus::Pending,
+            execution_status: Some(TransactionExecutionStatus::Succeeded),
+        }
+    );
+    assert_eq!(
+        get_transaction_status(&storage_reader, &pending_data, &TRANSACTION_HASH).await.unwrap(),
+        SubscribedTransactionStatus {
+            finality_status: SubscribedTransactionFinalityStatus::AcceptedOnL2,
+            execution_status: Some(TransactionExecutionStatus::Succeeded),
+        }
+    );
+
+    // The pending block is outdated once block 0 is reverted.
+    revert_block(&mut storage_writer, BlockNumber(0));
+    for transaction_hash in [TRANSACTION_HASH, pending_transaction_hash] {
+        assert_eq!(
+            get_transaction_status(&storage_reader, &pending_data, &transaction_hash)
+                .await
+                .unwrap(),
+            not_found
+        );
+    }
+}
+
+#[tokio::test]
+async fn subscribe_over_websocket() {
+    let ((storage_reader, mut storage_writer), _temp_dir) = get_test_storage();
+    let (addr, _handle) = run_server(
+        &get_test_rpc_config(),
+        get_test_highest_block(),
+        get_test_pending_data(),
+        get_test_pending_classes(),
+        storage_reader,
+        "NODE VERSION",
+    )
+    .await
+    .unwrap();
+    let client = WsClientBuilder::default().build(format!("ws://{addr}/rpc/v0_7")).await.unwrap();
+
+    let mut new_heads: Subscription<SubscriptionNotification> = client
+        .subscribe(SUBSCRIBE_METHOD, rpc_params![json!({"type": "newHeads"})], UNSUBSCRIBE_METHOD)
+        .await
+        .unwrap();
+    let mut transaction_status: Subscription<SubscriptionNotification> = client
+        .subscribe(
+            SUBSCRIBE_METHOD,
+            rpc_params![json!({"type": "transactionStatus", "transaction_hash": TRANSACTION_HASH})],
+            UNSUBSCRIBE_METHOD,
+        )
+        .await
+        .unwrap();
+    append_block(&mut storage_writer, BlockNumber(0), block_body(vec![]));
+
+    let timeout = Duration::from_secs(5);
+    let notification =
+        tokio::time::timeout(timeout, new_heads.next()).await.unwrap().unwrap().unwrap();
+    assert_eq!(
+        notification,
+        SubscriptionNotification::NewHead(
+            BlockHeader { block_hash: BlockHash(Felt::ZERO), ..Default::default() }.into()
+        )
+    );
+    let accepted_on_l2 = SubscriptionNotification::TransactionStatus {
+        transaction_hash: TRANSACTION_HASH,
+        status: SubscribedTransactionStatus {
+            finality_status: SubscribedTransactionFinalityStatus::AcceptedOnL2,
+            execution_status: Some(TransactionExecutionStatus::Succeeded),
+        },
+    };
+    let notification =
+        tokio::time::timeout(timeout, transaction_status.next()).await.unwrap().unwrap().unwrap();
+    assert_eq!(notification, accepted_on_l2);
+
+    revert_block(&mut storage_writer, BlockNumber(0));
+    let notification =
+        tokio::time::timeout(timeout, new_heads.next()).await.unwrap().unwrap().unwrap();
+    assert_eq!(
+        notification,
+        SubscriptionNotification::Reorg {
+            first_reverted_block_number: BlockNumber(0),
+            last_reverted_block_number: BlockNumber(0),
+        }
+    );
+    let notification =
+        tokio::time::timeout(timeout, transaction_status.next()).await.unwrap().unwrap().unwrap();
+    assert_eq!(
+        notification,
+        SubscriptionNotification::TransactionStatus {
+            transaction_hash: TRANSACTION_HASH,
+            status: SubscribedTransactionStatus {
+                finality_status: SubscribedTransactionFinalityStatus::NotFound,
+                execution_status: None,
+            },
+        }
+    );
+}
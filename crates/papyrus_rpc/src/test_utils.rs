@@ -21,7 +21,7 @@ use starknet_client::reader::PendingData;
 use starknet_client::writer::MockStarknetWriter;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, Semaphore};
 
 use crate::api::JsonRpcServerTrait;
 use crate::version_config::{VersionId, VERSION_PATTERN};
@@ -34,6 +34,7 @@ pub fn get_test_rpc_config() -> RpcConfig {
             eth_fee_contract_address: contract_address!("0x1001"),
             strk_fee_contract_address: contract_address!("0x1001"),
             initial_gas_cost: 10000000000,
+            ..Default::default()
         },
         server_address: String::from("127.0.0.1:0"),
         max_events_chunk_size: 10,
@@ -66,6 +67,28 @@ pub(crate) fn get_test_rpc_server_and_storage_writer_from_params<T: JsonRpcServe
     pending_data: Option<Arc<RwLock<PendingData>>>,
     pending_classes: Option<Arc<RwLock<PendingClasses>>>,
     storage_scope: Option<StorageScope>,
+) -> (RpcModule<T>, StorageWriter) {
+    let execution_semaphore =
+        Arc::new(Semaphore::new(get_test_rpc_config().max_concurrent_executions));
+    get_test_rpc_server_with_execution_semaphore(
+        mock_client,
+        shared_highest_block,
+        pending_data,
+        pending_classes,
+        storage_scope,
+        execution_semaphore,
+    )
+}
+
+// Like `get_test_rpc_server_and_storage_writer_from_params`, with the semaphore that bounds the
+// concurrent executions of the server, so that tests can take its permits.
+pub(crate) fn get_test_rpc_server_with_execution_semaphore<T: JsonRpcServerTrait>(
+    mock_client: Option<MockStarknetWriter>,
+    shared_highest_block: Option<Arc<RwLock<Option<BlockHashAndNumber>>>>,
+    pending_data: Option<Arc<RwLock<PendingData>>>,
+    pending_classes: Option<Arc<RwLock<PendingClasses>>>,
+    storage_scope: Option<StorageScope>,
+    execution_semaphore: Arc<Semaphore>,
 ) -> (RpcModule<T>, StorageWriter) {
     let mock_client = mock_client.unwrap_or_default();
     let shared_highest_block = shared_highest_block.unwrap_or(get_test_highest_block());
@@ -88,6 +111,8 @@ pub(crate) fn get_test_rpc_server_and_storage_writer_from_params<T: JsonRpcServe
             pending_data,
             pending_classes,
             mock_client_arc,
+            None,
+            execution_semaphore,
         )
         .into_rpc_module(),
         storage_writer,
@@ -19,6 +19,7 @@ use papyrus_storage::body::events::{EventIndex, EventsReader};
 use papyrus_storage::body::{BodyStorageReader, TransactionIndex};
 use papyrus_storage::compiled_class::CasmStorageReader;
 use papyrus_storage::db::{TransactionKind, RO};
+use papyrus_storage::read_replica::StorageWatermark;
 use papyrus_storage::state::StateStorageReader;
 use papyrus_storage::{StorageError, StorageReader, StorageTxn};
 use starknet_api::block::{BlockHash, BlockNumber, BlockStatus};
@@ -28,6 +29,7 @@ use starknet_api::state::{StateNumber, StorageKey};
 use starknet_api::transaction::{
     EventContent,
     EventIndexInTransactionOutput,
+    EventKey,
     Fee,
     Transaction as StarknetApiTransaction,
     TransactionHash,
@@ -47,7 +49,7 @@ use starknet_client::reader::PendingData;
 use starknet_client::writer::{StarknetWriter, WriterClientError};
 use starknet_client::ClientError;
 use starknet_types_core::felt::Felt;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, Semaphore};
 use tracing::{instrument, trace, warn};
 
 use super::super::block::{
@@ -128,10 +130,12 @@ use super::{
     TransactionTraceWithHash,
 };
 use crate::api::{BlockHashOrNumber, JsonRpcServerTrait, Tag};
+use crate::gateway_client::LocalGatewayClient;
 use crate::pending::client_pending_data_to_execution_pending_data;
 use crate::syncing_state::{get_last_synced_block, SyncStatus, SyncingState};
 use crate::version_config::VERSION_0_7 as VERSION;
 use crate::{
+    acquire_execution_permit,
     get_block_status,
     get_latest_block_number,
     internal_server_error,
@@ -159,6 +163,8 @@ pub struct JsonRpcServerImpl {
     pub pending_data: Arc<RwLock<PendingData>>,
     pub pending_classes: Arc<RwLock<PendingClasses>>,
     pub writer_client: Arc<dyn StarknetWriter>,
+    pub local_gateway_client: Option<Arc<LocalGatewayClient>>,
+    pub execution_semaphore: Arc<Semaphore>,
 }
 
 #[async_trait]
@@ -745,10 +751,19 @@ impl JsonRpcServer for JsonRpcServerImpl {
         // pointing to the next relevant event. Otherwise, we return a continuation token None.
         let mut filtered_events = vec![];
         if start_event_index.0.0 <= latest_block_number {
-            for ((from_address, event_index), content) in txn
-                .iter_events(filter.address, start_event_index, to_block_number)
-                .map_err(internal_server_error)?
-            {
+            let indexed_events = match indexed_event_key(&filter) {
+                Some((address, key)) => txn
+                    .iter_events_by_key(address, key, start_event_index, to_block_number)
+                    .map_err(internal_server_error)?,
+                None => None,
+            };
+            let events = match indexed_events {
+                Some(events) => events,
+                None => txn
+                    .iter_events(filter.address, start_event_index, to_block_number)
+                    .map_err(internal_server_error)?,
+            };
+            for ((from_address, event_index), content) in events {
                 let block_number = (event_index.0).0;
                 if block_number > to_block_number {
                     break;
@@ -877,13 +892,15 @@ impl JsonRpcServer for JsonRpcServerImpl {
         let block_not_reverted_validator = BlockNotRevertedValidator::new(block_number, &txn)?;
         drop(txn);
         let state_number = StateNumber::unchecked_right_after_block(block_number);
-        let execution_config = self.execution_config;
+        let execution_config = self.execution_config.clone();
 
         let chain_id = self.chain_id.clone();
         let reader = self.storage_reader.clone();
         let contract_address_copy = request.contract_address;
 
+        let execution_permit = acquire_execution_permit(&self.execution_semaphore).await?;
         let res = tokio::task::spawn_blocking(move || {
+            let _execution_permit = execution_permit;
             execute_call(
                 reader,
                 maybe_pending_data,
@@ -911,7 +928,12 @@ impl JsonRpcServer for JsonRpcServerImpl {
         &self,
         invoke_transaction: TypedInvokeTransaction,
     ) -> RpcResult<AddInvokeOkResult> {
-        let result = self.writer_client.add_invoke_transaction(&invoke_transaction.into()).await;
+        let result = match &self.local_gateway_client {
+            Some(local_gateway_client) => {
+                local_gateway_client.add_invoke_transaction(invoke_transaction).await
+            }
+            None => self.writer_client.add_invoke_transaction(&invoke_transaction.into()).await,
+        };
         match result {
             Ok(res) => Ok(res.into()),
             Err(WriterClientError::ClientError(ClientError::StarknetError(starknet_error))) => {
@@ -926,10 +948,18 @@ impl JsonRpcServer for JsonRpcServerImpl {
         &self,
         deploy_account_transaction: TypedDeployAccountTransaction,
     ) -> RpcResult<AddDeployAccountOkResult> {
-        let result = self
-            .writer_client
-            .add_deploy_account_transaction(&deploy_account_transaction.into())
-            .await;
+        let result = match &self.local_gateway_client {
+            Some(local_gateway_client) => {
+                local_gateway_client
+                    .add_deploy_account_transaction(deploy_account_transaction)
+                    .await
+            }
+            None => {
+                self.writer_client
+                    .add_deploy_account_transaction(&deploy_account_transaction.into())
+                    .await
+            }
+        };
         match result {
             Ok(res) => Ok(res.into()),
             Err(WriterClientError::ClientError(ClientError::StarknetError(starknet_error))) => {
@@ -944,12 +974,18 @@ impl JsonRpcServer for JsonRpcServerImpl {
         &self,
         declare_transaction: BroadcastedDeclareTransaction,
     ) -> RpcResult<AddDeclareOkResult> {
-        let result = self
-            .writer_client
-            .add_declare_transaction(
-                &declare_transaction.try_into().map_err(internal_server_error)?,
-            )
-            .await;
+        let result = match &self.local_gateway_client {
+            Some(local_gateway_client) => {
+                local_gateway_client.add_declare_transaction(declare_transaction).await
+            }
+            None => {
+                self.writer_client
+                    .add_declare_transaction(
+                        &declare_transaction.try_into().map_err(internal_server_error)?,
+                    )
+                    .await
+            }
+        };
         match result {
             Ok(res) => Ok(res.into()),
             Err(WriterClientError::ClientError(ClientError::StarknetError(starknet_error))) => {
@@ -988,12 +1024,14 @@ impl JsonRpcServer for JsonRpcServerImpl {
             BlockNotRevertedValidator::new(block_number, &storage_txn)?;
         drop(storage_txn);
         let state_number = StateNumber::unchecked_right_after_block(block_number);
-        let execution_config = self.execution_config;
+        let execution_config = self.execution_config.clone();
 
         let chain_id = self.chain_id.clone();
         let reader = self.storage_reader.clone();
 
+        let execution_permit = acquire_execution_permit(&self.execution_semaphore).await?;
         let estimate_fee_result = tokio::task::spawn_blocking(move || {
+            let _execution_permit = execution_permit;
             exec_estimate_fee(
                 executable_txns,
                 &chain_id,
@@ -1052,7 +1090,7 @@ impl JsonRpcServer for JsonRpcServerImpl {
             BlockNotRevertedValidator::new(block_number, &storage_txn)?;
         drop(storage_txn);
         let state_number = StateNumber::unchecked_right_after_block(block_number);
-        let execution_config = self.execution_config;
+        let execution_config = self.execution_config.clone();
 
         let chain_id = self.chain_id.clone();
         let reader = self.storage_reader.clone();
@@ -1060,7 +1098,9 @@ impl JsonRpcServer for JsonRpcServerImpl {
         let charge_fee = !simulation_flags.contains(&SimulationFlag::SkipFeeCharge);
         let validate = !simulation_flags.contains(&SimulationFlag::SkipValidate);
 
+        let execution_permit = acquire_execution_permit(&self.execution_semaphore).await?;
         let simulation_results = tokio::task::spawn_blocking(move || {
+            let _execution_permit = execution_permit;
             exec_simulate_transactions(
                 executable_txns,
                 None,
@@ -1203,12 +1243,14 @@ impl JsonRpcServer for JsonRpcServerImpl {
 
         drop(storage_txn);
 
-        let execution_config = self.execution_config;
+        let execution_config = self.execution_config.clone();
 
         let chain_id = self.chain_id.clone();
         let reader = self.storage_reader.clone();
 
+        let execution_permit = acquire_execution_permit(&self.execution_semaphore).await?;
         let mut simulation_results = tokio::task::spawn_blocking(move || {
+            let _execution_permit = execution_permit;
             exec_simulate_transactions(
                 executable_transactions,
                 Some(transaction_hashes),
@@ -1317,13 +1359,15 @@ impl JsonRpcServer for JsonRpcServerImpl {
 
         drop(storage_txn);
 
-        let execution_config = self.execution_config;
+        let execution_config = self.execution_config.clone();
 
         let chain_id = self.chain_id.clone();
         let reader = self.storage_reader.clone();
         let transaction_hashes_clone = transaction_hashes.clone();
 
+        let execution_permit = acquire_execution_permit(&self.execution_semaphore).await?;
         let simulation_results = tokio::task::spawn_blocking(move || {
+            let _execution_permit = execution_permit;
             exec_simulate_transactions(
                 executable_txns,
                 Some(transaction_hashes_clone),
@@ -1386,12 +1430,14 @@ impl JsonRpcServer for JsonRpcServerImpl {
             BlockNotRevertedValidator::new(block_number, &storage_txn)?;
         drop(storage_txn);
         let state_number = StateNumber::unchecked_right_after_block(block_number);
-        let execution_config = self.execution_config;
+        let execution_config = self.execution_config.clone();
 
         let chain_id = self.chain_id.clone();
         let reader = self.storage_reader.clone();
 
+        let execution_permit = acquire_execution_permit(&self.execution_semaphore).await?;
         let estimate_fee_result = tokio::task::spawn_blocking(move || {
+            let _execution_permit = execution_permit;
             exec_estimate_fee(
                 executable_txns,
                 &chain_id,
@@ -1468,6 +1514,11 @@ impl JsonRpcServer for JsonRpcServerImpl {
             .ok_or_else(|| ErrorObjectOwned::from(CLASS_HASH_NOT_FOUND))?;
         Ok(CompiledContractClass::V0(deprecated_compiled_contract_class))
     }
+
+    #[instrument(skip(self), level = "debug", err, ret)]
+    fn get_storage_watermark(&self) -> RpcResult<StorageWatermark> {
+        self.storage_reader.watermark().map_err(internal_server_error)
+    }
 }
 
 async fn read_pending_data<Mode: TransactionKind>(
@@ -1612,6 +1663,17 @@ fn client_receipt_to_rpc_pending_receipt(
     }))
 }
 
+// Returns the contract and the key to look up in the event keys index, if the filter requires a
+// single first key of the events of a specific contract.
+fn indexed_event_key(filter: &EventFilter) -> Option<(ContractAddress, EventKey)> {
+    let address = filter.address?;
+    let first_keys = filter.keys.first()?;
+    if first_keys.len() != 1 {
+        return None;
+    }
+    first_keys.iter().next().map(|key| (address, key.clone()))
+}
+
 fn do_event_keys_match_filter(event_content: &EventContent, filter: &EventFilter) -> bool {
     filter.keys.iter().enumerate().all(|(i, keys)| {
         event_content.keys.len() > i && (keys.is_empty() || keys.contains(&event_content.keys[i]))
@@ -1630,6 +1692,8 @@ impl JsonRpcServerTrait for JsonRpcServerImpl {
         pending_data: Arc<RwLock<PendingData>>,
         pending_classes: Arc<RwLock<PendingClasses>>,
         writer_client: Arc<dyn StarknetWriter>,
+        local_gateway_client: Option<Arc<LocalGatewayClient>>,
+        execution_semaphore: Arc<Semaphore>,
     ) -> Self {
         Self {
             chain_id,
@@ -1642,6 +1706,8 @@ impl JsonRpcServerTrait for JsonRpcServerImpl {
             pending_data,
             pending_classes,
             writer_client,
+            local_gateway_client,
+            execution_semaphore,
         }
     }
 
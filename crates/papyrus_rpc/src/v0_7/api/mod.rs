@@ -15,6 +15,7 @@ use papyrus_proc_macros::versioned_rpc;
 use papyrus_storage::compiled_class::CasmStorageReader;
 use papyrus_storage::db::serialization::StorageSerdeError;
 use papyrus_storage::db::RO;
+use papyrus_storage::read_replica::StorageWatermark;
 use papyrus_storage::state::StateStorageReader;
 use papyrus_storage::StorageTxn;
 use serde::{Deserialize, Serialize};
@@ -264,6 +265,11 @@ pub trait JsonRpc {
         block_id: BlockId,
         class_hash: ClassHash,
     ) -> RpcResult<CompiledContractClass>;
+
+    /// Returns the progress of the storage the node serves. Nodes that serve a storage written by
+    /// another node, e.g. read replicas, may be behind it.
+    #[method(name = "getStorageWatermark")]
+    fn get_storage_watermark(&self) -> RpcResult<StorageWatermark>;
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
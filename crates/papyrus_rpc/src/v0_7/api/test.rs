@@ -22,6 +22,7 @@ use papyrus_storage::body::{BodyStorageWriter, TransactionIndex};
 use papyrus_storage::class::ClassStorageWriter;
 use papyrus_storage::compiled_class::CasmStorageWriter;
 use papyrus_storage::header::HeaderStorageWriter;
+use papyrus_storage::read_replica::StorageWatermark;
 use papyrus_storage::state::StateStorageWriter;
 use papyrus_storage::test_utils::get_test_storage;
 use papyrus_storage::StorageScope;
@@ -3632,6 +3633,33 @@ async fn get_compiled_contract_class() {
     assert_matches!(err, Error::Call(err) if err == CLASS_HASH_NOT_FOUND.into());
 }
 
+#[tokio::test]
+async fn get_storage_watermark() {
+    let method_name = "starknet_V0_7_getStorageWatermark";
+    let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
+        JsonRpcServerImpl,
+    >(None, None, None, None, None);
+    let header = BlockHeader { timestamp: BlockTimestamp(1000), ..Default::default() };
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(BlockNumber(0), &header)
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let res = module.call::<_, StorageWatermark>(method_name, ()).await.unwrap();
+    assert_eq!(
+        res,
+        StorageWatermark {
+            header_marker: BlockNumber(1),
+            body_marker: BlockNumber(0),
+            state_marker: BlockNumber(0),
+            last_block_timestamp: Some(BlockTimestamp(1000)),
+        }
+    );
+}
+
 #[async_trait]
 trait AddTransactionTest
 where
@@ -14,6 +14,7 @@ use papyrus_storage::db::serialization::StorageSerdeError;
 use serde::{Deserialize, Serialize};
 use starknet_api::core::{CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::data_availability::DataAvailabilityMode;
+use starknet_api::rpc_transaction::RpcDeclareTransactionV3;
 use starknet_api::transaction::{
     AccountDeploymentData,
     Fee,
@@ -24,10 +25,12 @@ use starknet_api::transaction::{
 };
 use starknet_client::writer::objects::transaction as client_transaction;
 use starknet_client::writer::objects::transaction::DeprecatedContractClass;
+use starknet_client::writer::WriterClientError;
 
 use super::state::ContractClass;
 use super::transaction::{DeployAccountTransaction, InvokeTransaction, ResourceBoundsMapping};
 use crate::compression_utils::compress_and_encode;
+use crate::gateway_client::unsupported_version_error;
 
 /// Transactions that are ready to be broadcasted to the network and are not included in a block.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -193,3 +196,26 @@ impl TryFrom<BroadcastedDeclareTransaction> for client_transaction::DeclareTrans
         }
     }
 }
+
+impl TryFrom<BroadcastedDeclareTransaction> for RpcDeclareTransactionV3 {
+    type Error = WriterClientError;
+
+    fn try_from(value: BroadcastedDeclareTransaction) -> Result<Self, Self::Error> {
+        let BroadcastedDeclareTransaction::V3(declare_v3) = value else {
+            return Err(unsupported_version_error());
+        };
+        Ok(Self {
+            sender_address: declare_v3.sender_address,
+            compiled_class_hash: declare_v3.compiled_class_hash,
+            signature: declare_v3.signature,
+            nonce: declare_v3.nonce,
+            contract_class: declare_v3.contract_class.into(),
+            resource_bounds: declare_v3.resource_bounds.into(),
+            tip: declare_v3.tip,
+            paymaster_data: declare_v3.paymaster_data,
+            account_deployment_data: declare_v3.account_deployment_data,
+            nonce_data_availability_mode: declare_v3.nonce_data_availability_mode,
+            fee_data_availability_mode: declare_v3.fee_data_availability_mode,
+        })
+    }
+}
@@ -170,6 +170,12 @@ impl From<(ExecutionTransactionTrace, StarknetApiThinStateDiff)> for Transaction
                     trace.fee_transfer_invocation.as_ref().map(|invocation| {
                         ExecutionResources::from(invocation.execution_resources.clone())
                     });
+                // The fee split transfer has no place in the trace of the specs, but its resources
+                // are part of the resources of the transaction.
+                let fee_split_transfer_execution_resources =
+                    trace.fee_split_transfer_invocation.as_ref().map(|invocation| {
+                        ExecutionResources::from(invocation.execution_resources.clone())
+                    });
 
                 Self::Invoke(InvokeTransactionTrace {
                     validate_invocation: trace.validate_invocation.map(Into::into),
@@ -178,7 +184,8 @@ impl From<(ExecutionTransactionTrace, StarknetApiThinStateDiff)> for Transaction
                     state_diff,
                     execution_resources: validate_execution_resources.unwrap_or_default()
                         + execute_execution_resources
-                        + fee_transfer_execution_resources.unwrap_or_default(),
+                        + fee_transfer_execution_resources.unwrap_or_default()
+                        + fee_split_transfer_execution_resources.unwrap_or_default(),
                 })
             }
             ExecutionTransactionTrace::Declare(trace) => {
@@ -190,12 +197,17 @@ impl From<(ExecutionTransactionTrace, StarknetApiThinStateDiff)> for Transaction
                     trace.fee_transfer_invocation.as_ref().map(|invocation| {
                         ExecutionResources::from(invocation.execution_resources.clone())
                     });
+                let fee_split_transfer_execution_resources =
+                    trace.fee_split_transfer_invocation.as_ref().map(|invocation| {
+                        ExecutionResources::from(invocation.execution_resources.clone())
+                    });
                 Self::Declare(DeclareTransactionTrace {
                     validate_invocation: trace.validate_invocation.map(Into::into),
                     fee_transfer_invocation: trace.fee_transfer_invocation.map(Into::into),
                     state_diff,
                     execution_resources: validate_execution_resources.unwrap_or_default()
-                        + fee_transfer_execution_resources.unwrap_or_default(),
+                        + fee_transfer_execution_resources.unwrap_or_default()
+                        + fee_split_transfer_execution_resources.unwrap_or_default(),
                 })
             }
             ExecutionTransactionTrace::DeployAccount(trace) => {
@@ -210,6 +222,10 @@ impl From<(ExecutionTransactionTrace, StarknetApiThinStateDiff)> for Transaction
                     trace.fee_transfer_invocation.as_ref().map(|invocation| {
                         ExecutionResources::from(invocation.execution_resources.clone())
                     });
+                let fee_split_transfer_execution_resources =
+                    trace.fee_split_transfer_invocation.as_ref().map(|invocation| {
+                        ExecutionResources::from(invocation.execution_resources.clone())
+                    });
                 Self::DeployAccount(DeployAccountTransactionTrace {
                     validate_invocation: trace.validate_invocation.map(Into::into),
                     constructor_invocation: trace.constructor_invocation.into(),
@@ -217,7 +233,8 @@ impl From<(ExecutionTransactionTrace, StarknetApiThinStateDiff)> for Transaction
                     state_diff,
                     execution_resources: validate_execution_resources.unwrap_or_default()
                         + constructor_execution_resources
-                        + fee_transfer_execution_resources.unwrap_or_default(),
+                        + fee_transfer_execution_resources.unwrap_or_default()
+                        + fee_split_transfer_execution_resources.unwrap_or_default(),
                 })
             }
         }
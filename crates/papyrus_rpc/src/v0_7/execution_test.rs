@@ -2,6 +2,7 @@ use std::env;
 use std::fs::read_to_string;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use assert_matches::assert_matches;
 use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
@@ -90,7 +91,7 @@ use starknet_client::reader::objects::transaction::{
 };
 use starknet_client::reader::PendingData;
 use starknet_types_core::felt::Felt;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, Semaphore};
 
 use super::api::api_impl::JsonRpcServerImpl;
 use super::api::{
@@ -143,6 +144,7 @@ use crate::test_utils::{
     get_test_rpc_config,
     get_test_rpc_server_and_storage_writer,
     get_test_rpc_server_and_storage_writer_from_params,
+    get_test_rpc_server_with_execution_semaphore,
     validate_schema,
     SpecFile,
 };
@@ -1183,6 +1185,69 @@ async fn pending_trace_block_transactions_and_trace_transaction_execution_contex
     validate_result(res[1].trace_root.clone());
 }
 
+#[tokio::test]
+async fn trace_block_transactions_waits_for_an_execution_permit() {
+    let execution_semaphore = Arc::new(Semaphore::new(1));
+    let (module, storage_writer): (RpcModule<JsonRpcServerImpl>, _) =
+        get_test_rpc_server_with_execution_semaphore(
+            None,
+            None,
+            None,
+            None,
+            None,
+            execution_semaphore.clone(),
+        );
+    prepare_storage_for_execution(storage_writer);
+
+    // The trace waits while all the execution permits are taken.
+    let execution_permit = execution_semaphore.clone().acquire_owned().await.unwrap();
+    let trace = module.call::<_, Vec<TransactionTraceWithHash>>(
+        "starknet_V0_7_traceBlockTransactions",
+        [BlockId::HashOrNumber(BlockHashOrNumber::Number(BlockNumber(1)))],
+    );
+    tokio::pin!(trace);
+    assert!(tokio::time::timeout(Duration::from_millis(100), &mut trace).await.is_err());
+
+    drop(execution_permit);
+    assert!(trace.await.unwrap().is_empty());
+    assert_eq!(execution_semaphore.available_permits(), 1);
+}
+
+#[tokio::test]
+async fn estimate_fee_and_simulate_wait_for_an_execution_permit() {
+    let execution_semaphore = Arc::new(Semaphore::new(1));
+    let (module, storage_writer): (RpcModule<JsonRpcServerImpl>, _) =
+        get_test_rpc_server_with_execution_semaphore(
+            None,
+            None,
+            None,
+            None,
+            None,
+            execution_semaphore.clone(),
+        );
+    prepare_storage_for_execution(storage_writer);
+    let block_id = BlockId::HashOrNumber(BlockHashOrNumber::Number(BlockNumber(0)));
+
+    // The executions wait while all the execution permits are taken.
+    let execution_permit = execution_semaphore.clone().acquire_owned().await.unwrap();
+    let estimate_fee = module.call::<_, Vec<FeeEstimation>>(
+        "starknet_V0_7_estimateFee",
+        (Vec::<BroadcastedTransaction>::new(), Vec::<SimulationFlag>::new(), block_id),
+    );
+    let simulate = module.call::<_, Vec<SimulatedTransaction>>(
+        "starknet_V0_7_simulateTransactions",
+        (block_id, Vec::<BroadcastedTransaction>::new(), Vec::<SimulationFlag>::new()),
+    );
+    tokio::pin!(estimate_fee, simulate);
+    assert!(tokio::time::timeout(Duration::from_millis(100), &mut estimate_fee).await.is_err());
+    assert!(tokio::time::timeout(Duration::from_millis(100), &mut simulate).await.is_err());
+
+    drop(execution_permit);
+    assert!(estimate_fee.await.unwrap().is_empty());
+    assert!(simulate.await.unwrap().is_empty());
+    assert_eq!(execution_semaphore.available_permits(), 1);
+}
+
 #[test]
 fn message_from_l1_to_l1_handler_tx() {
     let l1_handler_tx = L1HandlerTransaction::from(MESSAGE_FROM_L1.clone());
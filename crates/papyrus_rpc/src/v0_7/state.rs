@@ -218,6 +218,21 @@ pub struct ContractClass {
     pub abi: String,
 }
 
+impl From<ContractClass> for starknet_api::rpc_transaction::ContractClass {
+    fn from(class: ContractClass) -> Self {
+        Self {
+            sierra_program: class.sierra_program,
+            contract_class_version: class.contract_class_version,
+            entry_points_by_type: starknet_api::rpc_transaction::EntryPointByType {
+                constructor: class.entry_points_by_type.constructor,
+                external: class.entry_points_by_type.external,
+                l1handler: class.entry_points_by_type.l1handler,
+            },
+            abi: class.abi,
+        }
+    }
+}
+
 impl From<starknet_api::state::ContractClass> for ContractClass {
     fn from(class: starknet_api::state::ContractClass) -> Self {
         Self {
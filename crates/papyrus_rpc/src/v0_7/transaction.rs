@@ -26,9 +26,11 @@ use starknet_api::core::{
     Nonce,
 };
 use starknet_api::data_availability::DataAvailabilityMode;
+use starknet_api::rpc_transaction::{RpcDeployAccountTransactionV3, RpcInvokeTransactionV3};
 use starknet_api::serde_utils::bytes_from_hex_str;
 use starknet_api::transaction::{
     AccountDeploymentData,
+    AllResourceBounds,
     Calldata,
     ContractAddressSalt,
     DeployTransaction,
@@ -45,9 +47,11 @@ use starknet_api::transaction::{
     TransactionVersion,
 };
 use starknet_client::writer::objects::transaction as client_transaction;
+use starknet_client::writer::WriterClientError;
 use starknet_types_core::felt::Felt;
 
 use super::error::BLOCK_NOT_FOUND;
+use crate::gateway_client::unsupported_version_error;
 use crate::internal_server_error;
 
 #[derive(
@@ -160,6 +164,13 @@ impl From<ResourceBoundsMapping> for starknet_api::transaction::DeprecatedResour
     }
 }
 
+// The L1 data gas isn't bounded in this version of the specs.
+impl From<ResourceBoundsMapping> for AllResourceBounds {
+    fn from(value: ResourceBoundsMapping) -> Self {
+        Self { l1_gas: value.l1_gas, l2_gas: value.l2_gas, l1_data_gas: ResourceBounds::default() }
+    }
+}
+
 impl From<starknet_api::transaction::DeprecatedResourceBoundsMapping> for ResourceBoundsMapping {
     fn from(value: starknet_api::transaction::DeprecatedResourceBoundsMapping) -> Self {
         Self {
@@ -1301,3 +1312,49 @@ impl From<TypedDeployAccountTransaction> for client_transaction::DeployAccountTr
         tx.into()
     }
 }
+
+impl TryFrom<TypedInvokeTransaction> for RpcInvokeTransactionV3 {
+    type Error = WriterClientError;
+
+    fn try_from(tx: TypedInvokeTransaction) -> Result<Self, Self::Error> {
+        let TypedInvokeTransaction::Invoke(InvokeTransaction::Version3(tx)) = tx else {
+            return Err(unsupported_version_error());
+        };
+        Ok(Self {
+            sender_address: tx.sender_address,
+            calldata: tx.calldata,
+            signature: tx.signature,
+            nonce: tx.nonce,
+            resource_bounds: tx.resource_bounds.into(),
+            tip: tx.tip,
+            paymaster_data: tx.paymaster_data,
+            account_deployment_data: tx.account_deployment_data,
+            nonce_data_availability_mode: tx.nonce_data_availability_mode,
+            fee_data_availability_mode: tx.fee_data_availability_mode,
+        })
+    }
+}
+
+impl TryFrom<TypedDeployAccountTransaction> for RpcDeployAccountTransactionV3 {
+    type Error = WriterClientError;
+
+    fn try_from(tx: TypedDeployAccountTransaction) -> Result<Self, Self::Error> {
+        let TypedDeployAccountTransaction::DeployAccount(DeployAccountTransaction::Version3(tx)) =
+            tx
+        else {
+            return Err(unsupported_version_error());
+        };
+        Ok(Self {
+            signature: tx.signature,
+            nonce: tx.nonce,
+            class_hash: tx.class_hash,
+            contract_address_salt: tx.contract_address_salt,
+            constructor_calldata: tx.constructor_calldata,
+            resource_bounds: tx.resource_bounds.into(),
+            tip: tx.tip,
+            paymaster_data: tx.paymaster_data,
+            nonce_data_availability_mode: tx.nonce_data_availability_mode,
+            fee_data_availability_mode: tx.fee_data_availability_mode,
+        })
+    }
+}
@@ -43,12 +43,33 @@ use starknet_api::block::BlockNumber;
 
 use crate::db::table_types::Table;
 use crate::db::{TransactionKind, RW};
+use crate::header::HeaderStorageReader;
 use crate::{MarkerKind, StorageResult, StorageTxn};
 
+/// The finality status of a block, from the point of view of the base layer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BlockFinalityStatus {
+    /// The block is accepted on L2, and its state update wasn't sent to the base layer yet.
+    AcceptedOnL2,
+    /// The state update of the block was sent to the base layer, but isn't accepted there yet.
+    SentToL1,
+    /// The block is proved on the base layer.
+    AcceptedOnL1,
+}
+
 /// Interface for reading data related to the base layer.
 pub trait BaseLayerStorageReader {
     /// The block number marker is the first block number that doesn't exist yet in the base layer.
     fn get_base_layer_block_marker(&self) -> StorageResult<BlockNumber>;
+
+    /// The first block number whose state update wasn't sent to the base layer yet.
+    fn get_sent_to_base_layer_block_marker(&self) -> StorageResult<BlockNumber>;
+
+    /// Returns the finality status of a block, or None if the block doesn't exist yet.
+    fn get_block_finality_status(
+        &self,
+        block_number: BlockNumber,
+    ) -> StorageResult<Option<BlockFinalityStatus>>;
 }
 
 /// Interface for writing data related to the base layer.
@@ -60,8 +81,15 @@ where
     // To enforce that no commit happen after a failure, we consume and return Self on success.
     fn update_base_layer_block_marker(self, block_number: &BlockNumber) -> StorageResult<Self>;
 
-    /// When reverting a block, if the base layer marker points to the block afterward, revert the
-    /// marker.
+    /// Updates the marker of the blocks whose state updates were sent to the base layer.
+    // To enforce that no commit happen after a failure, we consume and return Self on success.
+    fn update_sent_to_base_layer_block_marker(
+        self,
+        block_number: &BlockNumber,
+    ) -> StorageResult<Self>;
+
+    /// When reverting a block, if the base layer markers point to the block afterward, revert the
+    /// markers.
     fn try_revert_base_layer_marker(
         self,
         reverted_block_number: BlockNumber,
@@ -73,6 +101,28 @@ impl<'env, Mode: TransactionKind> BaseLayerStorageReader for StorageTxn<'env, Mo
         let markers_table = self.open_table(&self.tables.markers)?;
         Ok(markers_table.get(&self.txn, &MarkerKind::BaseLayerBlock)?.unwrap_or_default())
     }
+
+    fn get_sent_to_base_layer_block_marker(&self) -> StorageResult<BlockNumber> {
+        let markers_table = self.open_table(&self.tables.markers)?;
+        Ok(markers_table.get(&self.txn, &MarkerKind::SentToBaseLayerBlock)?.unwrap_or_default())
+    }
+
+    fn get_block_finality_status(
+        &self,
+        block_number: BlockNumber,
+    ) -> StorageResult<Option<BlockFinalityStatus>> {
+        if block_number >= self.get_header_marker()? {
+            return Ok(None);
+        }
+        let status = if block_number < self.get_base_layer_block_marker()? {
+            BlockFinalityStatus::AcceptedOnL1
+        } else if block_number < self.get_sent_to_base_layer_block_marker()? {
+            BlockFinalityStatus::SentToL1
+        } else {
+            BlockFinalityStatus::AcceptedOnL2
+        };
+        Ok(Some(status))
+    }
 }
 
 impl<'env> BaseLayerStorageWriter for StorageTxn<'env, RW> {
@@ -82,16 +132,27 @@ impl<'env> BaseLayerStorageWriter for StorageTxn<'env, RW> {
         Ok(self)
     }
 
+    fn update_sent_to_base_layer_block_marker(
+        self,
+        block_number: &BlockNumber,
+    ) -> StorageResult<Self> {
+        let markers_table = self.open_table(&self.tables.markers)?;
+        markers_table.upsert(&self.txn, &MarkerKind::SentToBaseLayerBlock, block_number)?;
+        Ok(self)
+    }
+
     fn try_revert_base_layer_marker(
         self,
         reverted_block_number: BlockNumber,
     ) -> StorageResult<Self> {
-        let cur_marker = self.get_base_layer_block_marker()?;
+        let mut txn = self;
         // Revert only if we revert a block that is the last block in the base layer we know about.
-        if cur_marker == reverted_block_number.unchecked_next() {
-            Ok(self.update_base_layer_block_marker(&reverted_block_number)?)
-        } else {
-            Ok(self)
+        if txn.get_base_layer_block_marker()? == reverted_block_number.unchecked_next() {
+            txn = txn.update_base_layer_block_marker(&reverted_block_number)?;
+        }
+        if txn.get_sent_to_base_layer_block_marker()? == reverted_block_number.unchecked_next() {
+            txn = txn.update_sent_to_base_layer_block_marker(&reverted_block_number)?;
         }
+        Ok(txn)
     }
 }
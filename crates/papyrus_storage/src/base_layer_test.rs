@@ -1,7 +1,9 @@
-use starknet_api::block::BlockNumber;
+use starknet_api::block::{BlockHeader, BlockNumber};
 
-use crate::base_layer::{BaseLayerStorageReader, BaseLayerStorageWriter};
+use crate::base_layer::{BaseLayerStorageReader, BaseLayerStorageWriter, BlockFinalityStatus};
+use crate::header::HeaderStorageWriter;
 use crate::test_utils::get_test_storage;
+use crate::StorageReader;
 
 #[tokio::test]
 async fn rw_base_layer_tip_marker() {
@@ -50,3 +52,46 @@ fn try_revert_base_layer_marker() {
     let cur_marker = reader.begin_ro_txn().unwrap().get_base_layer_block_marker().unwrap();
     assert_eq!(cur_marker, BlockNumber(1));
 }
+
+#[test]
+fn block_finality_status() {
+    let (reader, mut writer) = get_test_storage().0;
+
+    let mut txn = writer.begin_rw_txn().unwrap();
+    for block_number in 0..4 {
+        txn = txn.append_header(BlockNumber(block_number), &BlockHeader::default()).unwrap();
+    }
+    txn.update_sent_to_base_layer_block_marker(&BlockNumber(3))
+        .unwrap()
+        .update_base_layer_block_marker(&BlockNumber(1))
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let statuses = |reader: &StorageReader| {
+        let txn = reader.begin_ro_txn().unwrap();
+        (0..5)
+            .map(|block_number| txn.get_block_finality_status(BlockNumber(block_number)).unwrap())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        statuses(&reader),
+        vec![
+            Some(BlockFinalityStatus::AcceptedOnL1),
+            Some(BlockFinalityStatus::SentToL1),
+            Some(BlockFinalityStatus::SentToL1),
+            Some(BlockFinalityStatus::AcceptedOnL2),
+            None,
+        ]
+    );
+
+    // A base layer reorg un-finalizes a block.
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .update_base_layer_block_marker(&BlockNumber(0))
+        .unwrap()
+        .commit()
+        .unwrap();
+    assert_eq!(statuses(&reader)[0], Some(BlockFinalityStatus::SentToL1));
+}
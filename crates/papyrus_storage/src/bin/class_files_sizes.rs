@@ -0,0 +1,67 @@
+use clap::{Arg, Command};
+use papyrus_storage::db::DbConfig;
+use papyrus_storage::utils::class_files_sizes;
+use papyrus_storage::StorageConfig;
+use starknet_api::core::ChainId;
+
+/// This executable prints the sizes of the class files in the storage, before and after the
+/// deduplication of identical classes.
+fn main() {
+    let cli_params = get_cli_params();
+    let db_config = DbConfig {
+        path_prefix: cli_params.db_path.into(),
+        chain_id: cli_params.chain_id,
+        enforce_file_exists: true,
+        ..Default::default()
+    };
+    let config = StorageConfig { db_config, ..Default::default() };
+    let (reader, _writer) =
+        papyrus_storage::open_storage(config).expect("Should be able to open storage");
+
+    let sizes = class_files_sizes(&reader).expect("Should be able to read the class files");
+    for (file_name, file_sizes) in &sizes {
+        let saved_bytes = file_sizes.total_bytes - file_sizes.stored_bytes;
+        println!(
+            "{file_name}: {} classes ({} distinct), {} bytes stored out of {} ({saved_bytes} \
+             saved).",
+            file_sizes.classes,
+            file_sizes.distinct_classes,
+            file_sizes.stored_bytes,
+            file_sizes.total_bytes,
+        );
+    }
+    println!(
+        "{}",
+        serde_json::to_string(&sizes).expect("Should be able to serialize the class files sizes")
+    );
+}
+
+struct CliParams {
+    db_path: String,
+    chain_id: ChainId,
+}
+
+fn get_cli_params() -> CliParams {
+    let matches = Command::new("Class files sizes")
+        .arg(
+            Arg::new("db_path")
+                .short('d')
+                .long("db_path")
+                .required(true)
+                .help("The path to the database"),
+        )
+        .arg(
+            Arg::new("chain_id")
+                .short('c')
+                .long("chain_id")
+                .required(true)
+                .help("The chain id SN_MAIN/SN_SEPOLIA for example"),
+        )
+        .get_matches();
+
+    let db_path = matches.get_one::<String>("db_path").expect("Missing db_path").to_string();
+    let chain_id =
+        matches.get_one::<String>("chain_id").expect("Missing parse chain_id").to_string();
+
+    CliParams { db_path, chain_id: chain_id.into() }
+}
@@ -0,0 +1,53 @@
+use clap::{Arg, Command};
+use papyrus_storage::db::DbConfig;
+use papyrus_storage::StorageConfig;
+use starknet_api::core::ChainId;
+
+/// This executable trains the compression dictionaries of the classes from the classes in the
+/// storage. The classes that the node stores from then on are compressed with them.
+fn main() {
+    let cli_params = get_cli_params();
+    let db_config = DbConfig {
+        path_prefix: cli_params.db_path.into(),
+        chain_id: cli_params.chain_id,
+        enforce_file_exists: true,
+        ..Default::default()
+    };
+    let config = StorageConfig { db_config, ..Default::default() };
+    let (_reader, mut writer) =
+        papyrus_storage::open_storage(config).expect("Should be able to open storage");
+
+    let segments =
+        writer.train_class_dictionaries().expect("Should be able to train the dictionaries");
+    println!("Trained dictionaries for {segments:?}.");
+}
+
+struct CliParams {
+    db_path: String,
+    chain_id: ChainId,
+}
+
+fn get_cli_params() -> CliParams {
+    let matches = Command::new("Train class dictionaries")
+        .arg(
+            Arg::new("db_path")
+                .short('d')
+                .long("db_path")
+                .required(true)
+                .help("The path to the database"),
+        )
+        .arg(
+            Arg::new("chain_id")
+                .short('c')
+                .long("chain_id")
+                .required(true)
+                .help("The chain id SN_MAIN/SN_SEPOLIA for example"),
+        )
+        .get_matches();
+
+    let db_path = matches.get_one::<String>("db_path").expect("Missing db_path").to_string();
+    let chain_id =
+        matches.get_one::<String>("chain_id").expect("Missing parse chain_id").to_string();
+
+    CliParams { db_path, chain_id: chain_id.into() }
+}
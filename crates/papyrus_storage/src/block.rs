@@ -0,0 +1,150 @@
+//! Interface for appending whole blocks to the storage.
+//!
+//! Appending a [`FullBlock`] stores its header, signature, body (including the transaction
+//! outputs), state diff, classes and casms in a single transaction, so a block is either stored
+//! entirely or not at all.
+//!
+//! The writes keep the storage consistent after a crash at any point:
+//! 1. Before writing, the header, body, state, class and compiled class markers must all be at the
+//!    block, i.e. no part of the block is stored. Otherwise, nothing is written. The body marker
+//!    isn't checked for blocks without a body, which storages that don't track bodies append.
+//! 2. The data is appended to the files first, and only its locations are written to the database.
+//!    Data appended to a file without a location in the database is ignored and overwritten.
+//! 3. The files are flushed before the database transaction commits, and the markers move only as
+//!    part of that commit.
+//!
+//! Writers that get a whole block at once, like the native blockifier storage, should use this
+//! interface. The sync receives the headers, state diffs and casms in separate streams, so it keeps
+//! appending each part with its own writer.
+//!
+//! # Example
+//!
+//! ```
+//! # use papyrus_storage::{db::DbConfig, StorageConfig};
+//! # use starknet_api::core::ChainId;
+//! use papyrus_storage::block::{BlockStorageWriter, FullBlock};
+//! use papyrus_storage::body::BodyStorageReader;
+//! use papyrus_storage::header::HeaderStorageReader;
+//! use papyrus_storage::open_storage;
+//! use papyrus_storage::state::StateStorageReader;
+//! use starknet_api::block::BlockNumber;
+//!
+//! # let dir_handle = tempfile::tempdir().unwrap();
+//! # let dir = dir_handle.path().to_path_buf();
+//! # let db_config = DbConfig {
+//! #     path_prefix: dir,
+//! #     chain_id: ChainId::Mainnet,
+//! #     enforce_file_exists: false,
+//! #     min_size: 1 << 20,    // 1MB
+//! #     max_size: 1 << 35,    // 32GB
+//! #     growth_step: 1 << 26, // 64MB
+//! # };
+//! # let storage_config = StorageConfig{db_config, ..Default::default()};
+//! let (reader, mut writer) = open_storage(storage_config)?;
+//! let block = FullBlock { body: Some(Default::default()), ..Default::default() };
+//! writer.begin_rw_txn()?.append_block(block)?.commit()?;
+//!
+//! let txn = reader.begin_ro_txn()?;
+//! assert_eq!(txn.get_header_marker()?, BlockNumber(1));
+//! assert_eq!(txn.get_body_marker()?, BlockNumber(1));
+//! assert_eq!(txn.get_state_marker()?, BlockNumber(1));
+//! # Ok::<(), papyrus_storage::StorageError>(())
+//! ```
+
+#[cfg(test)]
+#[path = "block_test.rs"]
+mod block_test;
+
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use indexmap::IndexMap;
+use papyrus_proc_macros::latency_histogram;
+use starknet_api::block::{BlockBody, BlockHeader, BlockSignature};
+use starknet_api::core::ClassHash;
+use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
+use starknet_api::state::{ContractClass, ThinStateDiff};
+
+use crate::body::{BodyStorageReader, BodyStorageWriter};
+use crate::class::{ClassStorageReader, ClassStorageWriter};
+use crate::compiled_class::{CasmStorageReader, CasmStorageWriter};
+use crate::db::RW;
+use crate::header::{HeaderStorageReader, HeaderStorageWriter};
+use crate::state::{StateStorageReader, StateStorageWriter};
+use crate::{StorageError, StorageResult, StorageTxn};
+
+/// All the data of a block.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FullBlock {
+    /// The header of the block. Its block number is the number of the block.
+    pub header: BlockHeader,
+    /// The signature of the block, if it's signed.
+    pub signature: Option<BlockSignature>,
+    /// The transactions of the block, with their outputs. None if the storage doesn't track the
+    /// bodies, in which case the body marker is left as is.
+    pub body: Option<BlockBody>,
+    /// The state diff of the block, without the classes.
+    pub state_diff: ThinStateDiff,
+    /// The classes declared in the block.
+    pub classes: IndexMap<ClassHash, ContractClass>,
+    /// The deprecated classes declared in the block, and those of contracts deployed in it.
+    pub deprecated_classes: IndexMap<ClassHash, DeprecatedContractClass>,
+    /// The Cairo assembly of the classes declared in the block.
+    pub casms: IndexMap<ClassHash, CasmContractClass>,
+}
+
+/// Interface for appending whole blocks to the storage.
+pub trait BlockStorageWriter
+where
+    Self: Sized,
+{
+    /// Appends all the data of a block. Returns [`StorageError::MarkerMismatch`] without writing
+    /// anything if a part of the block is already stored, or a previous block isn't.
+    // To enforce that no commit happen after a failure, we consume and return Self on success.
+    fn append_block(self, block: FullBlock) -> StorageResult<Self>;
+}
+
+impl<'env> BlockStorageWriter for StorageTxn<'env, RW> {
+    #[latency_histogram("storage_append_block_latency_seconds", false)]
+    fn append_block(self, block: FullBlock) -> StorageResult<Self> {
+        let block_number = block.header.block_number;
+        let mut markers = vec![
+            self.get_header_marker()?,
+            self.get_state_marker()?,
+            self.get_class_marker()?,
+            self.get_compiled_class_marker()?,
+        ];
+        if block.body.is_some() {
+            markers.push(self.get_body_marker()?);
+        }
+        for marker in markers {
+            if marker != block_number {
+                return Err(StorageError::MarkerMismatch { expected: marker, found: block_number });
+            }
+        }
+
+        let mut txn = self.append_header(block_number, &block.header)?;
+        if let Some(signature) = &block.signature {
+            txn = txn.append_block_signature(block_number, signature)?;
+        }
+        if let Some(body) = block.body {
+            txn = txn.append_body(block_number, body)?;
+        }
+        let mut txn = txn.append_state_diff(block_number, block.state_diff)?.append_classes(
+            block_number,
+            &block
+                .classes
+                .iter()
+                .map(|(class_hash, class)| (*class_hash, class))
+                .collect::<Vec<_>>(),
+            &block
+                .deprecated_classes
+                .iter()
+                .map(|(class_hash, deprecated_class)| (*class_hash, deprecated_class))
+                .collect::<Vec<_>>(),
+        )?;
+        // The casms are appended after the state diff, which the compiled class marker follows.
+        for (class_hash, casm) in &block.casms {
+            txn = txn.append_casm(class_hash, casm)?;
+        }
+        Ok(txn)
+    }
+}
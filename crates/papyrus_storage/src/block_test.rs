@@ -0,0 +1,128 @@
+use assert_matches::assert_matches;
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use indexmap::indexmap;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockBody, BlockHash, BlockHeader, BlockNumber, BlockSignature};
+use starknet_api::core::{ClassHash, CompiledClassHash};
+use starknet_api::felt;
+use starknet_api::state::{ContractClass, ThinStateDiff};
+
+use crate::block::{BlockStorageWriter, FullBlock};
+use crate::body::BodyStorageReader;
+use crate::class::ClassStorageReader;
+use crate::compiled_class::CasmStorageReader;
+use crate::header::{HeaderStorageReader, HeaderStorageWriter};
+use crate::state::StateStorageReader;
+use crate::test_utils::get_test_storage;
+use crate::StorageError;
+
+fn block_with_class(block_number: BlockNumber) -> FullBlock {
+    let class_hash = ClassHash(felt!(block_number.0 + 1));
+    FullBlock {
+        header: BlockHeader {
+            block_hash: BlockHash(felt!(block_number.0 + 1)),
+            block_number,
+            ..Default::default()
+        },
+        signature: Some(BlockSignature::default()),
+        body: Some(BlockBody::default()),
+        state_diff: ThinStateDiff {
+            declared_classes: indexmap! { class_hash => CompiledClassHash::default() },
+            ..Default::default()
+        },
+        classes: indexmap! { class_hash => ContractClass::default() },
+        casms: indexmap! { class_hash => CasmContractClass::default() },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn append_block() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    for i in 0..2 {
+        writer
+            .begin_rw_txn()
+            .unwrap()
+            .append_block(block_with_class(BlockNumber(i)))
+            .unwrap()
+            .commit()
+            .unwrap();
+    }
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_header_marker().unwrap(), BlockNumber(2));
+    assert_eq!(txn.get_body_marker().unwrap(), BlockNumber(2));
+    assert_eq!(txn.get_state_marker().unwrap(), BlockNumber(2));
+    assert_eq!(txn.get_class_marker().unwrap(), BlockNumber(2));
+    assert_eq!(txn.get_compiled_class_marker().unwrap(), BlockNumber(2));
+
+    let block = block_with_class(BlockNumber(1));
+    assert_eq!(txn.get_block_header(BlockNumber(1)).unwrap(), Some(block.header));
+    assert_eq!(txn.get_block_signature(BlockNumber(1)).unwrap(), block.signature);
+    assert_eq!(txn.get_state_diff(BlockNumber(1)).unwrap(), Some(block.state_diff));
+    let class_hash = ClassHash(felt!(2_u8));
+    assert_eq!(txn.get_class(&class_hash).unwrap(), Some(ContractClass::default()));
+    assert_eq!(txn.get_casm(&class_hash).unwrap(), Some(CasmContractClass::default()));
+}
+
+#[test]
+fn append_partially_stored_block() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    let block = block_with_class(BlockNumber(0));
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(BlockNumber(0), &block.header)
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let result = writer.begin_rw_txn().unwrap().append_block(block.clone());
+    assert_matches!(
+        result,
+        Err(StorageError::MarkerMismatch { expected: BlockNumber(1), found: BlockNumber(0) })
+    );
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_header_marker().unwrap(), BlockNumber(1));
+    assert_eq!(txn.get_body_marker().unwrap(), BlockNumber(0));
+    assert_eq!(txn.get_state_marker().unwrap(), BlockNumber(0));
+    assert_eq!(txn.get_casm(&ClassHash(felt!(1_u8))).unwrap(), None);
+}
+
+#[test]
+fn append_block_after_missing_block() {
+    let ((_reader, mut writer), _temp_dir) = get_test_storage();
+    let result = writer.begin_rw_txn().unwrap().append_block(block_with_class(BlockNumber(1)));
+    assert_matches!(
+        result,
+        Err(StorageError::MarkerMismatch { expected: BlockNumber(0), found: BlockNumber(1) })
+    );
+}
+
+#[test]
+fn append_block_without_body() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    let block = FullBlock { body: None, ..block_with_class(BlockNumber(0)) };
+    writer.begin_rw_txn().unwrap().append_block(block).unwrap().commit().unwrap();
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_header_marker().unwrap(), BlockNumber(1));
+    assert_eq!(txn.get_body_marker().unwrap(), BlockNumber(0));
+    assert_eq!(txn.get_state_marker().unwrap(), BlockNumber(1));
+    assert_eq!(txn.get_compiled_class_marker().unwrap(), BlockNumber(1));
+}
+
+#[test]
+fn append_block_with_missing_casms() {
+    let ((_reader, mut writer), _temp_dir) = get_test_storage();
+    // The casms of the first block are missing, so the compiled class marker stays behind.
+    let block = FullBlock { casms: Default::default(), ..block_with_class(BlockNumber(0)) };
+    writer.begin_rw_txn().unwrap().append_block(block).unwrap().commit().unwrap();
+
+    let result = writer.begin_rw_txn().unwrap().append_block(block_with_class(BlockNumber(1)));
+    assert_matches!(
+        result,
+        Err(StorageError::MarkerMismatch { expected: BlockNumber(0), found: BlockNumber(1) })
+    );
+}
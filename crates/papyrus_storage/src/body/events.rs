@@ -14,6 +14,7 @@
 //! use starknet_api::core::ContractAddress;
 //! use starknet_api::transaction::TransactionOffsetInBlock;
 //! use starknet_api::transaction::EventIndexInTransactionOutput;
+//! use starknet_api::transaction::EventKey;
 //!
 //! # let dir_handle = tempfile::tempdir().unwrap();
 //! # let dir = dir_handle.path().to_path_buf();
@@ -43,6 +44,16 @@
 //! for ((contract_address, event_index), event_content) in contract_events_iterator {
 //!    // Do something with the event.
 //! }
+//! // iterate events from a specific contract with a specific first key. This requires the event
+//! // keys index, see the indices module.
+//! let event_key = EventKey::default();
+//! if let Some(key_events_iterator) =
+//!     txn.iter_events_by_key(ContractAddress::default(), event_key, event_index, BlockNumber(0))?
+//! {
+//!     for ((contract_address, event_index), event_content) in key_events_iterator {
+//!         // Do something with the event.
+//!     }
+//! }
 //! # Ok::<(), papyrus_storage::StorageError>(())
 #[cfg(test)]
 #[path = "events_test.rs"]
@@ -57,9 +68,11 @@ use starknet_api::transaction::{
     Event,
     EventContent,
     EventIndexInTransactionOutput,
+    EventKey,
     TransactionOutput,
 };
 
+use super::indices::{BodyIndex, EventKeysTableKey};
 use super::TransactionMetadataTable;
 use crate::body::{EventsTableKey, TransactionIndex};
 use crate::db::serialization::{NoVersionValueWrapper, VersionZeroWrapper};
@@ -91,6 +104,26 @@ pub trait EventsReader<'txn, 'env> {
         event_index: EventIndex,
         to_block_number: BlockNumber,
     ) -> StorageResult<EventIter<'txn, 'env>>;
+
+    /// Returns an iterator over the events emitted by the given contract whose first key is the
+    /// given key, by the order of the event index. Returns None if the event keys index doesn't
+    /// cover the blocks up to `to_block_number`, see [`indices`](super::indices).
+    ///
+    /// # Arguments
+    /// * address - contract address that emitted the events.
+    /// * key - the first key of the events.
+    /// * event_index - event index to start iterate from it.
+    /// * to_block_number - block number to stop iterate at it.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`](crate::StorageError) if there was an error.
+    fn iter_events_by_key(
+        &'env self,
+        address: ContractAddress,
+        key: EventKey,
+        event_index: EventIndex,
+        to_block_number: BlockNumber,
+    ) -> StorageResult<Option<EventIter<'txn, 'env>>>;
 }
 
 // TODO: support all read transactions (including RW).
@@ -109,20 +142,35 @@ impl<'txn, 'env> EventsReader<'txn, 'env> for StorageTxn<'env, RO> {
 
         Ok(EventIter::ByEventIndex(self.iter_events_by_event_index(event_index, to_block_number)?))
     }
+
+    fn iter_events_by_key(
+        &'env self,
+        address: ContractAddress,
+        key: EventKey,
+        event_index: EventIndex,
+        to_block_number: BlockNumber,
+    ) -> StorageResult<Option<EventIter<'txn, 'env>>> {
+        if self.get_index_marker(BodyIndex::EventKeys)? <= to_block_number {
+            return Ok(None);
+        }
+        Ok(Some(EventIter::ByEventKey(self.iter_events_by_event_key(address, key, event_index)?)))
+    }
 }
 
 // TODO(dvir): add transaction hash to the return value. In the RPC when returning events this is
 // with the transaction hash. We can do it efficiently here because we anyway read the relevant
 // entry in the transaction_metadata table..
 #[allow(missing_docs)]
-/// A wrapper of two iterators [`EventIterByContractAddress`] and [`EventIterByEventIndex`].
+/// A wrapper of the iterators [`EventIterByContractAddress`], [`EventIterByEventIndex`] and
+/// [`EventIterByEventKey`].
 pub enum EventIter<'txn, 'env> {
     ByContractAddress(EventIterByContractAddress<'env, 'txn>),
     ByEventIndex(EventIterByEventIndex<'txn>),
+    ByEventKey(EventIterByEventKey<'env, 'txn>),
 }
 
-/// This iterator is a wrapper of two iterators [`EventIterByContractAddress`]
-/// and [`EventIterByEventIndex`].
+/// This iterator is a wrapper of the iterators [`EventIterByContractAddress`],
+/// [`EventIterByEventIndex`] and [`EventIterByEventKey`].
 /// With this wrapper we can execute the same code, regardless the
 /// type of iteration used.
 impl Iterator for EventIter<'_, '_> {
@@ -132,6 +180,7 @@ impl Iterator for EventIter<'_, '_> {
         match self {
             EventIter::ByContractAddress(it) => it.next(),
             EventIter::ByEventIndex(it) => it.next(),
+            EventIter::ByEventKey(it) => it.next(),
         }
         .unwrap_or(None)
     }
@@ -181,6 +230,54 @@ impl<'env, 'txn> EventIterByContractAddress<'env, 'txn> {
     }
 }
 
+/// This iterator goes over the events of a contract with a given first key, using the event keys
+/// index. The events are iterated by the order of the event index.
+pub struct EventIterByEventKey<'env, 'txn> {
+    txn: &'txn DbTransaction<'env, RO>,
+    file_handles: &'txn FileHandlers<RO>,
+    contract_and_key: (ContractAddress, EventKey),
+    // The next transaction in the event keys table with relevant events. If it is None there are
+    // no more events.
+    next_tx_index: Option<TransactionIndex>,
+    // Queue of events to return from the iterator. When this queue is empty, we need to fetch more
+    // events.
+    events_queue: VecDeque<((ContractAddress, EventIndex), EventContent)>,
+    cursor: EventKeysTableCursor<'txn>,
+    transaction_metadata_table: TransactionMetadataTable<'env>,
+}
+
+impl<'env, 'txn> EventIterByEventKey<'env, 'txn> {
+    /// Returns the next event. If there are no more events, returns None.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`](crate::StorageError) if there was an error.
+    fn next(&mut self) -> StorageResult<Option<((ContractAddress, EventIndex), EventContent)>> {
+        while self.events_queue.is_empty() {
+            let Some(tx_index) = self.next_tx_index.take() else {
+                return Ok(None);
+            };
+            let tx_metadata =
+                self.transaction_metadata_table.get(self.txn, &tx_index)?.unwrap_or_else(|| {
+                    panic!("Transaction metadata not found for transaction index: {tx_index:?}")
+                });
+            let tx_output = self
+                .file_handles
+                .get_transaction_output_unchecked(tx_metadata.tx_output_location)?;
+            let (contract_address, key) = &self.contract_and_key;
+            self.events_queue = get_events_with_key_from_tx(
+                tx_output.events().into(),
+                tx_index,
+                *contract_address,
+                key,
+                0,
+            );
+            self.next_tx_index = next_tx_index_with_key(&mut self.cursor, &self.contract_and_key)?;
+        }
+
+        Ok(Some(self.events_queue.pop_front().expect("events_queue should not be empty.")))
+    }
+}
+
 /// This iterator goes over the events in the order of the event index.
 /// That is, the events are iterated by the order they are emitted.
 /// First by the block number, then by the transaction offset in the block,
@@ -303,6 +400,58 @@ where
         })
     }
 
+    /// Returns an events iterator that iterates the events of the contract with the given first key
+    /// from the given event index, using the event keys index.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`](crate::StorageError) if there was an error.
+    fn iter_events_by_event_key(
+        &'env self,
+        address: ContractAddress,
+        key: EventKey,
+        event_index: EventIndex,
+    ) -> StorageResult<EventIterByEventKey<'env, 'txn>> {
+        let transaction_metadata_table = self.open_table(&self.tables.transaction_metadata)?;
+        let event_keys_table = self.open_table(&self.tables.event_keys)?;
+        let mut cursor = event_keys_table.cursor(&self.txn)?;
+        let contract_and_key = (address, key);
+        let mut events_queue = VecDeque::new();
+        if let Some(((current_contract_and_key, tx_index), _)) =
+            cursor.lower_bound(&(contract_and_key.clone(), event_index.0))?
+        {
+            if current_contract_and_key == contract_and_key {
+                let tx_metadata =
+                    transaction_metadata_table.get(&self.txn, &tx_index)?.unwrap_or_else(|| {
+                        panic!("Transaction metadata not found for transaction index: {tx_index:?}")
+                    });
+                let tx_output = self
+                    .file_handlers
+                    .get_transaction_output_unchecked(tx_metadata.tx_output_location)?;
+                // In case of we get tx_index different from the key, it means we need to start a
+                // new transaction which means the first event.
+                let start_event_index = if tx_index == event_index.0 { event_index.1.0 } else { 0 };
+                events_queue = get_events_with_key_from_tx(
+                    tx_output.events().into(),
+                    tx_index,
+                    address,
+                    &contract_and_key.1,
+                    start_event_index,
+                );
+            }
+        }
+        let next_tx_index = next_tx_index_with_key(&mut cursor, &contract_and_key)?;
+
+        Ok(EventIterByEventKey {
+            txn: &self.txn,
+            file_handles: &self.file_handlers,
+            contract_and_key,
+            next_tx_index,
+            events_queue,
+            cursor,
+            transaction_metadata_table,
+        })
+    }
+
     /// Returns an events iterator that iterates events by event index from the given event index.
     ///
     /// # Arguments
@@ -357,6 +506,35 @@ fn get_events_from_tx(
     events
 }
 
+// Like get_events_from_tx, but returns only the events whose first key is the given key.
+fn get_events_with_key_from_tx(
+    events_list: Vec<Event>,
+    tx_index: TransactionIndex,
+    contract_address: ContractAddress,
+    first_key: &EventKey,
+    start_index: usize,
+) -> VecDeque<((ContractAddress, EventIndex), EventContent)> {
+    get_events_from_tx(events_list, tx_index, contract_address, start_index)
+        .into_iter()
+        .filter(|(_, content)| content.keys.first() == Some(first_key))
+        .collect()
+}
+
+// Returns the next transaction in the event keys table, if it has events of the given contract
+// and key.
+fn next_tx_index_with_key(
+    cursor: &mut EventKeysTableCursor<'_>,
+    contract_and_key: &(ContractAddress, EventKey),
+) -> StorageResult<Option<TransactionIndex>> {
+    Ok(cursor
+        .next()?
+        .filter(|((current_contract_and_key, _), _)| current_contract_and_key == contract_and_key)
+        .map(|((_, tx_index), _)| tx_index))
+}
+
+/// A cursor of the event keys table.
+type EventKeysTableCursor<'txn> =
+    DbCursor<'txn, RO, EventKeysTableKey, NoVersionValueWrapper<NoValue>, CommonPrefix>;
 /// A cursor of the events table.
 type EventsTableCursor<'txn> =
     DbCursor<'txn, RO, EventsTableKey, NoVersionValueWrapper<NoValue>, CommonPrefix>;
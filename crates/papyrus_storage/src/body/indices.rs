@@ -0,0 +1,365 @@
+//! Optional secondary indices of the block bodies.
+//!
+//! - The transaction hashes index maps a transaction hash to its [`TransactionIndex`]. Until it
+//!   covers all the block bodies,
+//!   [`get_transaction_idx_by_hash`](super::BodyStorageReader::get_transaction_idx_by_hash) fails
+//!   for transactions that it doesn't hold.
+//! - The event keys index maps a contract address and the first key of an event emitted by it to
+//!   the transactions that emitted such events. It's used by
+//!   [`iter_events_by_key`](super::events::EventsReader::iter_events_by_key).
+//!
+//! Each index has a marker, which is the first block whose transactions aren't indexed. An index
+//! is maintained when a block body is appended only if it's enabled in the [`IndicesConfig`]. When
+//! the storage is opened with an enabled index that doesn't cover all the block bodies, the missing
+//! blocks are indexed before the storage is returned, unless the config defers them to the
+//! background: the sync then calls [`backfill_indices`] every [`index_backfill_interval`], and the
+//! index catches up with the bodies, including the ones appended meanwhile.
+//!
+//! Only the indices are backfilled. The receipts and the traces of a block aren't derived data of
+//! the storage: they're stored with the block by the sync, and nothing recomputes them.
+//!
+//! The transaction hashes were always indexed before the index became optional, so
+//! [`IndexMarkersMigration`] initializes its marker to the body marker in storages of an older
+//! blocks version.
+
+#[cfg(test)]
+#[path = "indices_test.rs"]
+mod indices_test;
+
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+
+use papyrus_config::converters::deserialize_milliseconds_to_duration;
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::ContractAddress;
+use starknet_api::transaction::{
+    EventKey,
+    TransactionHash,
+    TransactionOffsetInBlock,
+    TransactionOutput,
+};
+use tracing::info;
+
+use super::{BodyStorageReader, TransactionIndex};
+use crate::db::serialization::NoVersionValueWrapper;
+use crate::db::table_types::{CommonPrefix, NoValue, Table};
+use crate::db::{TableHandle, TransactionKind, RW};
+use crate::migration::Migration;
+use crate::version::{Version, VersionedComponent};
+use crate::{MarkerKind, StorageReader, StorageResult, StorageScope, StorageTxn, StorageWriter};
+
+/// The first blocks version in which the indices of the block bodies have markers.
+pub(crate) const INDEX_MARKERS_BLOCKS_VERSION: Version = Version { major: 2, minor: 1 };
+
+// The maximal number of blocks that are indexed in a single transaction when building an index.
+const INDEX_BUILD_BATCH_SIZE: u64 = 100;
+
+/// The configuration of the optional indices of the block bodies.
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct IndicesConfig {
+    /// Whether to maintain the index from a transaction hash to its transaction index.
+    pub transaction_hashes: bool,
+    /// Whether to maintain the index from a contract address and an event key to the transactions
+    /// with such events.
+    pub event_keys: bool,
+    /// Whether to index the blocks that are missing from the enabled indices when the storage is
+    /// opened. Otherwise, they're left to [`backfill_indices`].
+    pub build_on_open: bool,
+    /// The maximal number of blocks that [`backfill_indices`] indexes at a time.
+    pub backfill_batch_size: u64,
+    /// The time between the calls to [`backfill_indices`], which throttles the backfill.
+    #[serde(deserialize_with = "deserialize_milliseconds_to_duration")]
+    pub backfill_interval: Duration,
+}
+
+impl Default for IndicesConfig {
+    fn default() -> Self {
+        Self {
+            transaction_hashes: true,
+            event_keys: false,
+            build_on_open: true,
+            backfill_batch_size: 100,
+            backfill_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+impl SerializeConfig for IndicesConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "transaction_hashes",
+                &self.transaction_hashes,
+                "Whether to maintain an index from transaction hashes to transactions. Without \
+                 it, transactions can't be looked up by their hash.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "event_keys",
+                &self.event_keys,
+                "Whether to maintain an index from a contract address and the first key of its \
+                 events to the transactions that emitted them, for filtering events by key.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "build_on_open",
+                &self.build_on_open,
+                "Whether to index the blocks that are missing from the enabled indices when the \
+                 storage is opened, which delays the start of the node. Otherwise, the sync \
+                 indexes them in the background.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "backfill_batch_size",
+                &self.backfill_batch_size,
+                "Max amount of stored blocks to add to the indices in a single batch, when the \
+                 sync indexes them in the background.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "backfill_interval",
+                &self.backfill_interval.as_millis(),
+                "Time in milliseconds between the batches of blocks that the sync adds to the \
+                 indices in the background, which throttles it.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+pub(crate) type EventKeysTableKey = ((ContractAddress, EventKey), TransactionIndex);
+pub(crate) type EventKeysTable<'env> =
+    TableHandle<'env, EventKeysTableKey, NoVersionValueWrapper<NoValue>, CommonPrefix>;
+
+/// An optional index of the block bodies.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum BodyIndex {
+    TransactionHashes,
+    EventKeys,
+}
+
+pub(crate) const BODY_INDICES: [BodyIndex; 2] =
+    [BodyIndex::TransactionHashes, BodyIndex::EventKeys];
+
+impl BodyIndex {
+    fn marker_kind(self) -> MarkerKind {
+        match self {
+            BodyIndex::TransactionHashes => MarkerKind::TransactionHashesIndex,
+            BodyIndex::EventKeys => MarkerKind::EventKeysIndex,
+        }
+    }
+
+    fn is_enabled(self, config: &IndicesConfig) -> bool {
+        match self {
+            BodyIndex::TransactionHashes => config.transaction_hashes,
+            BodyIndex::EventKeys => config.event_keys,
+        }
+    }
+}
+
+impl<Mode: TransactionKind> StorageTxn<'_, Mode> {
+    /// Returns the first block whose transactions aren't in the index.
+    pub(crate) fn get_index_marker(&self, index: BodyIndex) -> StorageResult<BlockNumber> {
+        let markers_table = self.open_table(&self.tables.markers)?;
+        Ok(markers_table.get(&self.txn, &index.marker_kind())?.unwrap_or_default())
+    }
+}
+
+/// Adds the transactions of the block to the index if the index is enabled and covers all the
+/// previous blocks.
+pub(crate) fn index_block(
+    txn: &StorageTxn<'_, RW>,
+    index: BodyIndex,
+    block_number: BlockNumber,
+    transaction_hashes: &[TransactionHash],
+    transaction_outputs: &[TransactionOutput],
+) -> StorageResult<()> {
+    if !index.is_enabled(&txn.indices) || txn.get_index_marker(index)? != block_number {
+        return Ok(());
+    }
+    match index {
+        BodyIndex::TransactionHashes => {
+            let transaction_hash_to_idx_table =
+                txn.open_table(&txn.tables.transaction_hash_to_idx)?;
+            for (offset, tx_hash) in transaction_hashes.iter().enumerate() {
+                let tx_index = TransactionIndex(block_number, TransactionOffsetInBlock(offset));
+                transaction_hash_to_idx_table.insert(&txn.txn, tx_hash, &tx_index)?;
+            }
+        }
+        BodyIndex::EventKeys => {
+            let event_keys_table = txn.open_table(&txn.tables.event_keys)?;
+            for (offset, tx_output) in transaction_outputs.iter().enumerate() {
+                let tx_index = TransactionIndex(block_number, TransactionOffsetInBlock(offset));
+                for contract_and_key in first_event_keys(tx_output) {
+                    // The blocks are indexed in order, so the transaction index is greater than the
+                    // indexed ones.
+                    event_keys_table.append_greater_sub_key(
+                        &txn.txn,
+                        &(contract_and_key, tx_index),
+                        &NoValue,
+                    )?;
+                }
+            }
+        }
+    }
+    let markers_table = txn.open_table(&txn.tables.markers)?;
+    markers_table.upsert(&txn.txn, &index.marker_kind(), &block_number.unchecked_next())?;
+    Ok(())
+}
+
+/// Removes the transactions of the block from the index if they were indexed. The block must be the
+/// last one in the storage.
+pub(crate) fn unindex_block(
+    txn: &StorageTxn<'_, RW>,
+    index: BodyIndex,
+    block_number: BlockNumber,
+    transaction_hashes: &[TransactionHash],
+    transaction_outputs: &[TransactionOutput],
+) -> StorageResult<()> {
+    if txn.get_index_marker(index)? <= block_number {
+        return Ok(());
+    }
+    match index {
+        BodyIndex::TransactionHashes => {
+            let transaction_hash_to_idx_table =
+                txn.open_table(&txn.tables.transaction_hash_to_idx)?;
+            for tx_hash in transaction_hashes {
+                transaction_hash_to_idx_table.delete(&txn.txn, tx_hash)?;
+            }
+        }
+        BodyIndex::EventKeys => {
+            let event_keys_table = txn.open_table(&txn.tables.event_keys)?;
+            for (offset, tx_output) in transaction_outputs.iter().enumerate() {
+                let tx_index = TransactionIndex(block_number, TransactionOffsetInBlock(offset));
+                for contract_and_key in first_event_keys(tx_output) {
+                    event_keys_table.delete(&txn.txn, &(contract_and_key, tx_index))?;
+                }
+            }
+        }
+    }
+    let markers_table = txn.open_table(&txn.tables.markers)?;
+    markers_table.upsert(&txn.txn, &index.marker_kind(), &block_number)?;
+    Ok(())
+}
+
+/// Indexes the block bodies that aren't covered by the enabled indices when the storage is opened,
+/// unless the config defers them to [`backfill_indices`].
+pub(crate) fn build_indices(writer: &mut StorageWriter) -> StorageResult<()> {
+    if !writer.indices.build_on_open {
+        return Ok(());
+    }
+    while index_batch(writer, INDEX_BUILD_BATCH_SIZE)? {}
+    Ok(())
+}
+
+/// The time between the calls to [`backfill_indices`], if the config defers the blocks that are
+/// missing from the enabled indices to the background.
+pub fn index_backfill_interval(reader: &StorageReader) -> Option<Duration> {
+    if reader.indices.build_on_open || reader.scope == StorageScope::StateOnly {
+        return None;
+    }
+    Some(reader.indices.backfill_interval)
+}
+
+/// Whether some enabled index doesn't cover all the block bodies.
+pub fn indices_lag_behind_bodies(reader: &StorageReader) -> StorageResult<bool> {
+    if reader.scope == StorageScope::StateOnly {
+        return Ok(false);
+    }
+    let txn = reader.begin_ro_txn()?;
+    let body_marker = txn.get_body_marker()?;
+    for index in BODY_INDICES {
+        if index.is_enabled(&reader.indices) && txn.get_index_marker(index)? < body_marker {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Indexes a batch of the block bodies that aren't covered by the enabled indices, of the size in
+/// the config. Returns false if the enabled indices cover all the block bodies.
+pub fn backfill_indices(writer: &mut StorageWriter) -> StorageResult<bool> {
+    index_batch(writer, writer.indices.backfill_batch_size)
+}
+
+// Indexes up to `max_blocks` block bodies that aren't covered by the first enabled index that lags
+// behind the bodies. The batch is committed with the advanced marker of the index, so an
+// interrupted build continues from the last committed batch. Returns false if the enabled indices
+// cover all the block bodies.
+fn index_batch(writer: &mut StorageWriter, max_blocks: u64) -> StorageResult<bool> {
+    if writer.scope == StorageScope::StateOnly {
+        return Ok(false);
+    }
+    for index in BODY_INDICES {
+        if !index.is_enabled(&writer.indices) {
+            continue;
+        }
+        let txn = writer.begin_rw_txn()?;
+        let index_marker = txn.get_index_marker(index)?;
+        let body_marker = txn.get_body_marker()?;
+        if index_marker >= body_marker {
+            continue;
+        }
+        let batch_end = BlockNumber((index_marker.0 + max_blocks.max(1)).min(body_marker.0));
+        for block_number in index_marker.iter_up_to(batch_end) {
+            let transaction_hashes = txn
+                .get_block_transaction_hashes(block_number)?
+                .unwrap_or_else(|| panic!("Missing transaction hashes for block {block_number}."));
+            let transaction_outputs = txn
+                .get_block_transaction_outputs(block_number)?
+                .unwrap_or_else(|| panic!("Missing transaction outputs for block {block_number}."));
+            index_block(&txn, index, block_number, &transaction_hashes, &transaction_outputs)?;
+        }
+        txn.commit()?;
+        info!("Built the {index:?} index up to block {batch_end} out of {body_marker}.");
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+/// Sets the marker of the transaction hashes index to the body marker, since the transaction hashes
+/// of all the stored blocks were indexed.
+pub(crate) struct IndexMarkersMigration;
+
+impl Migration for IndexMarkersMigration {
+    fn name(&self) -> &'static str {
+        "index_markers"
+    }
+
+    fn component(&self) -> VersionedComponent {
+        VersionedComponent::Blocks
+    }
+
+    fn target_version(&self) -> Version {
+        INDEX_MARKERS_BLOCKS_VERSION
+    }
+
+    fn migrate_batch(
+        &self,
+        txn: &StorageTxn<'_, RW>,
+        _checkpoint: Option<Vec<u8>>,
+        _batch_size: usize,
+    ) -> StorageResult<(u64, Option<Vec<u8>>)> {
+        let body_marker = txn.get_body_marker()?;
+        let markers_table = txn.open_table(&txn.tables.markers)?;
+        markers_table.upsert(
+            &txn.txn,
+            &BodyIndex::TransactionHashes.marker_kind(),
+            &body_marker,
+        )?;
+        Ok((1, None))
+    }
+}
+
+// Returns the distinct pairs of an emitting contract and the first key of the events of the
+// transaction.
+fn first_event_keys(tx_output: &TransactionOutput) -> HashSet<(ContractAddress, EventKey)> {
+    tx_output
+        .events()
+        .iter()
+        .filter_map(|event| event.content.keys.first().map(|key| (event.from_address, key.clone())))
+        .collect()
+}
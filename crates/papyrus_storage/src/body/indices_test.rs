@@ -0,0 +1,209 @@
+use assert_matches::assert_matches;
+use papyrus_test_utils::get_test_block;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockBody, BlockNumber};
+use starknet_api::core::ContractAddress;
+use starknet_api::felt;
+use starknet_api::transaction::{
+    EventContent,
+    EventIndexInTransactionOutput,
+    EventKey,
+    TransactionHash,
+    TransactionOffsetInBlock,
+};
+
+use crate::body::events::{EventIndex, EventsReader};
+use crate::body::indices::{
+    backfill_indices,
+    index_backfill_interval,
+    indices_lag_behind_bodies,
+    BodyIndex,
+};
+use crate::body::{BodyStorageReader, BodyStorageWriter, TransactionIndex};
+use crate::db::table_types::Table;
+use crate::test_utils::get_test_config;
+use crate::{open_storage, StorageError, StorageReader, StorageWriter};
+
+const N_BLOCKS: u64 = 2;
+const N_TRANSACTIONS: usize = 3;
+
+// Returns block bodies whose events are emitted by two contracts with one of two first keys. The
+// transaction hashes are unique across the blocks.
+fn test_bodies() -> Vec<BlockBody> {
+    let from_addresses = vec![ContractAddress::from(1u32), ContractAddress::from(2u32)];
+    let keys = vec![vec![EventKey(felt!("0x1")), EventKey(felt!("0x2"))]];
+    (0..N_BLOCKS)
+        .map(|block_number| {
+            let mut body = get_test_block(
+                N_TRANSACTIONS,
+                Some(4),
+                Some(from_addresses.clone()),
+                Some(keys.clone()),
+            )
+            .body;
+            for (offset, tx_hash) in body.transaction_hashes.iter_mut().enumerate() {
+                *tx_hash =
+                    TransactionHash((block_number * N_TRANSACTIONS as u64 + offset as u64).into());
+            }
+            body
+        })
+        .collect()
+}
+
+fn append_bodies(writer: &mut StorageWriter, bodies: &[BlockBody]) {
+    for (block_number, body) in bodies.iter().enumerate() {
+        writer
+            .begin_rw_txn()
+            .unwrap()
+            .append_body(BlockNumber(block_number as u64), body.clone())
+            .unwrap()
+            .commit()
+            .unwrap();
+    }
+}
+
+// Returns the events of the contract with the given first key, by the order of the event index.
+fn expected_events(
+    bodies: &[BlockBody],
+    address: ContractAddress,
+    key: &EventKey,
+) -> Vec<((ContractAddress, EventIndex), EventContent)> {
+    let mut events = vec![];
+    for (block_number, body) in bodies.iter().enumerate() {
+        for (tx_offset, tx_output) in body.transaction_outputs.iter().enumerate() {
+            for (event_offset, event) in tx_output.events().iter().enumerate() {
+                if event.from_address == address && event.content.keys.first() == Some(key) {
+                    let event_index = EventIndex(
+                        TransactionIndex(
+                            BlockNumber(block_number as u64),
+                            TransactionOffsetInBlock(tx_offset),
+                        ),
+                        EventIndexInTransactionOutput(event_offset),
+                    );
+                    events.push(((address, event_index), event.content.clone()));
+                }
+            }
+        }
+    }
+    events
+}
+
+fn events_by_key(
+    reader: &StorageReader,
+    address: ContractAddress,
+    key: &EventKey,
+    to_block_number: BlockNumber,
+) -> Option<Vec<((ContractAddress, EventIndex), EventContent)>> {
+    let txn = reader.begin_ro_txn().unwrap();
+    let first_event_index = EventIndex(
+        TransactionIndex(BlockNumber(0), TransactionOffsetInBlock(0)),
+        EventIndexInTransactionOutput(0),
+    );
+    txn.iter_events_by_key(address, key.clone(), first_event_index, to_block_number)
+        .unwrap()
+        .map(|events| events.collect())
+}
+
+#[test]
+fn get_transaction_idx_by_hash_fails_without_index() {
+    let (mut config, _temp_dir) = get_test_config(None);
+    config.indices.transaction_hashes = false;
+    let (reader, mut writer) = open_storage(config).unwrap();
+    let bodies = test_bodies();
+    append_bodies(&mut writer, &bodies);
+
+    let txn = reader.begin_ro_txn().unwrap();
+    let tx_hash = bodies[1].transaction_hashes[2];
+    let transaction_hash_to_idx_table =
+        txn.open_table(&txn.tables.transaction_hash_to_idx).unwrap();
+    assert_eq!(transaction_hash_to_idx_table.get(&txn.txn, &tx_hash).unwrap(), None);
+    assert_matches!(
+        txn.get_transaction_idx_by_hash(&tx_hash),
+        Err(StorageError::TransactionHashesNotIndexed { index_marker, body_marker })
+        if index_marker == BlockNumber(0) && body_marker == BlockNumber(N_BLOCKS)
+    );
+}
+
+#[test]
+fn event_keys_index_is_built_when_enabled() {
+    let (mut config, _temp_dir) = get_test_config(None);
+    let (reader, mut writer) = open_storage(config.clone()).unwrap();
+    let bodies = test_bodies();
+    append_bodies(&mut writer, &bodies);
+    let address = ContractAddress::from(1u32);
+    let key = EventKey(felt!("0x2"));
+    assert_eq!(events_by_key(&reader, address, &key, BlockNumber(N_BLOCKS - 1)), None);
+    drop(reader);
+    drop(writer);
+
+    config.indices.event_keys = true;
+    let (reader, _writer) = open_storage(config).unwrap();
+    assert_eq!(
+        reader.begin_ro_txn().unwrap().get_index_marker(BodyIndex::EventKeys).unwrap(),
+        BlockNumber(N_BLOCKS)
+    );
+    assert_eq!(
+        events_by_key(&reader, address, &key, BlockNumber(N_BLOCKS - 1)),
+        Some(expected_events(&bodies, address, &key))
+    );
+}
+
+#[test]
+fn event_keys_index_is_backfilled_when_not_built_on_open() {
+    let (mut config, _temp_dir) = get_test_config(None);
+    let (reader, mut writer) = open_storage(config.clone()).unwrap();
+    let bodies = test_bodies();
+    append_bodies(&mut writer, &bodies);
+    drop(reader);
+    drop(writer);
+
+    config.indices.event_keys = true;
+    config.indices.build_on_open = false;
+    config.indices.backfill_batch_size = 1;
+    let (reader, mut writer) = open_storage(config.clone()).unwrap();
+    assert_eq!(index_backfill_interval(&reader), Some(config.indices.backfill_interval));
+    let index_marker =
+        || reader.begin_ro_txn().unwrap().get_index_marker(BodyIndex::EventKeys).unwrap();
+    assert_eq!(index_marker(), BlockNumber(0));
+    assert!(indices_lag_behind_bodies(&reader).unwrap());
+
+    assert!(backfill_indices(&mut writer).unwrap());
+    assert_eq!(index_marker(), BlockNumber(1));
+    assert!(backfill_indices(&mut writer).unwrap());
+    assert_eq!(index_marker(), BlockNumber(N_BLOCKS));
+    assert!(!indices_lag_behind_bodies(&reader).unwrap());
+    assert!(!backfill_indices(&mut writer).unwrap());
+
+    let address = ContractAddress::from(1u32);
+    let key = EventKey(felt!("0x2"));
+    assert_eq!(
+        events_by_key(&reader, address, &key, BlockNumber(N_BLOCKS - 1)),
+        Some(expected_events(&bodies, address, &key))
+    );
+}
+
+#[test]
+fn revert_body_removes_indexed_transactions() {
+    let (mut config, _temp_dir) = get_test_config(None);
+    config.indices.event_keys = true;
+    let (reader, mut writer) = open_storage(config).unwrap();
+    let bodies = test_bodies();
+    append_bodies(&mut writer, &bodies);
+    let last_block_number = BlockNumber(N_BLOCKS - 1);
+
+    let (txn, _) = writer.begin_rw_txn().unwrap().revert_body(last_block_number).unwrap();
+    txn.commit().unwrap();
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_index_marker(BodyIndex::TransactionHashes).unwrap(), last_block_number);
+    assert_eq!(txn.get_index_marker(BodyIndex::EventKeys).unwrap(), last_block_number);
+    assert_eq!(txn.get_transaction_idx_by_hash(&bodies[1].transaction_hashes[0]).unwrap(), None);
+    let address = ContractAddress::from(2u32);
+    let key = EventKey(felt!("0x1"));
+    assert_eq!(events_by_key(&reader, address, &key, last_block_number), None);
+    let remaining_block_number = BlockNumber(N_BLOCKS - 2);
+    assert_eq!(
+        events_by_key(&reader, address, &key, remaining_block_number),
+        Some(expected_events(&bodies[..1], address, &key))
+    );
+}
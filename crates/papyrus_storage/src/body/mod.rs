@@ -5,7 +5,8 @@
 //! Import [`BodyStorageReader`] and [`BodyStorageWriter`] to read and write data related
 //! to the block bodies using a [`StorageTxn`].
 //!
-//! See [`events`] module for the interface for handling events.
+//! See [`events`] module for the interface for handling events, and [`indices`] for the optional
+//! indices of the block bodies.
 //!
 //!  # Example
 //! ```
@@ -41,6 +42,7 @@
 #[cfg(test)]
 mod body_test;
 pub mod events;
+pub mod indices;
 
 use std::collections::HashSet;
 use std::fmt::Debug;
@@ -57,6 +59,7 @@ use starknet_api::transaction::{
 };
 use tracing::debug;
 
+use crate::body::indices::{index_block, unindex_block, BodyIndex, BODY_INDICES};
 use crate::db::serialization::{NoVersionValueWrapper, VersionZeroWrapper};
 use crate::db::table_types::{CommonPrefix, DbCursorTrait, NoValue, SimpleTable, Table};
 use crate::db::{DbTransaction, TableHandle, TransactionKind, RW};
@@ -76,8 +79,6 @@ type FileOffsetsTable<'env> =
     TableHandle<'env, OffsetKind, NoVersionValueWrapper<usize>, SimpleTable>;
 type TransactionMetadataTable<'env> =
     TableHandle<'env, TransactionIndex, VersionZeroWrapper<TransactionMetadata>, SimpleTable>;
-type TransactionHashToIdxTable<'env> =
-    TableHandle<'env, TransactionHash, NoVersionValueWrapper<TransactionIndex>, SimpleTable>;
 type EventsTableKey = (ContractAddress, TransactionIndex);
 type EventsTable<'env> =
     TableHandle<'env, EventsTableKey, NoVersionValueWrapper<NoValue>, CommonPrefix>;
@@ -105,6 +106,10 @@ pub trait BodyStorageReader {
     ) -> StorageResult<Option<TransactionOutput>>;
 
     /// Returns the index of the transaction with the given hash.
+    ///
+    /// # Errors
+    /// Returns [`StorageError::TransactionHashesNotIndexed`] if the transaction isn't indexed and
+    /// the transaction hashes index doesn't cover all the block bodies, e.g. when it's disabled.
     fn get_transaction_idx_by_hash(
         &self,
         tx_hash: &TransactionHash,
@@ -202,8 +207,18 @@ impl<'env, Mode: TransactionKind> BodyStorageReader for StorageTxn<'env, Mode> {
     ) -> StorageResult<Option<TransactionIndex>> {
         let transaction_hash_to_idx_table =
             self.open_table(&self.tables.transaction_hash_to_idx)?;
-        let idx = transaction_hash_to_idx_table.get(&self.txn, tx_hash)?;
-        Ok(idx)
+        if let Some(idx) = transaction_hash_to_idx_table.get(&self.txn, tx_hash)? {
+            return Ok(Some(idx));
+        }
+
+        // The transactions of the blocks from the index marker aren't indexed, so the transaction
+        // may be in one of them.
+        let index_marker = self.get_index_marker(BodyIndex::TransactionHashes)?;
+        let body_marker = self.get_body_marker()?;
+        if index_marker < body_marker {
+            return Err(StorageError::TransactionHashesNotIndexed { index_marker, body_marker });
+        }
+        Ok(None)
     }
 
     fn get_transaction_hash_by_idx(
@@ -349,8 +364,6 @@ impl<'env> BodyStorageWriter for StorageTxn<'env, RW> {
 
         if self.scope != StorageScope::StateOnly {
             let events_table = self.open_table(&self.tables.events)?;
-            let transaction_hash_to_idx_table =
-                self.open_table(&self.tables.transaction_hash_to_idx)?;
             let transaction_metadata_table = self.open_table(&self.tables.transaction_metadata)?;
             let file_offset_table = self.txn.open_table(&self.tables.file_offsets)?;
 
@@ -359,11 +372,19 @@ impl<'env> BodyStorageWriter for StorageTxn<'env, RW> {
                 &self.txn,
                 &self.file_handlers,
                 &file_offset_table,
-                &transaction_hash_to_idx_table,
                 &transaction_metadata_table,
                 &events_table,
                 block_number,
             )?;
+            for index in BODY_INDICES {
+                index_block(
+                    &self,
+                    index,
+                    block_number,
+                    &block_body.transaction_hashes,
+                    &block_body.transaction_outputs,
+                )?;
+            }
         }
 
         Ok(self)
@@ -395,8 +416,6 @@ impl<'env> BodyStorageWriter for StorageTxn<'env, RW> {
             }
 
             let transaction_metadata_table = self.open_table(&self.tables.transaction_metadata)?;
-            let transaction_hash_to_idx_table =
-                self.open_table(&self.tables.transaction_hash_to_idx)?;
             let events_table = self.open_table(&self.tables.events)?;
 
             let transactions = self
@@ -410,15 +429,21 @@ impl<'env> BodyStorageWriter for StorageTxn<'env, RW> {
                 .unwrap_or_else(|| panic!("Missing transaction hashes for block {block_number}."));
 
             // Delete the transactions data.
-            for (offset, (tx_hash, tx_output)) in
-                transaction_hashes.iter().zip(transaction_outputs.iter()).enumerate()
-            {
+            for index in BODY_INDICES {
+                unindex_block(
+                    &self,
+                    index,
+                    block_number,
+                    &transaction_hashes,
+                    &transaction_outputs,
+                )?;
+            }
+            for (offset, tx_output) in transaction_outputs.iter().enumerate() {
                 let tx_index = TransactionIndex(block_number, TransactionOffsetInBlock(offset));
 
                 for event in tx_output.events().iter() {
                     events_table.delete(&self.txn, &(event.from_address, tx_index))?;
                 }
-                transaction_hash_to_idx_table.delete(&self.txn, tx_hash)?;
                 transaction_metadata_table.delete(&self.txn, &tx_index)?;
             }
             Some((transactions, transaction_outputs, transaction_hashes))
@@ -432,13 +457,11 @@ impl<'env> BodyStorageWriter for StorageTxn<'env, RW> {
 
 // TODO(dvir): consider enforcing that the block_body transactions, transaction_outputs and
 // transaction_hashes to be the same size.
-#[allow(clippy::too_many_arguments)]
 fn write_transactions<'env>(
     block_body: &BlockBody,
     txn: &DbTransaction<'env, RW>,
     file_handlers: &FileHandlers<RW>,
     file_offset_table: &'env FileOffsetsTable<'env>,
-    transaction_hash_to_idx_table: &'env TransactionHashToIdxTable<'env>,
     transaction_metadata_table: &'env TransactionMetadataTable<'env>,
     events_table: &'env EventsTable<'env>,
     block_number: BlockNumber,
@@ -455,7 +478,6 @@ fn write_transactions<'env>(
         let tx_location = file_handlers.append_transaction(tx);
         let tx_output_location = file_handlers.append_transaction_output(tx_output);
         write_events(tx_output, txn, events_table, transaction_index)?;
-        transaction_hash_to_idx_table.insert(txn, tx_hash, &transaction_index)?;
         transaction_metadata_table.append(
             txn,
             &transaction_index,
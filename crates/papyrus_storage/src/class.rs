@@ -75,6 +75,7 @@ use starknet_api::state::ContractClass;
 
 use crate::db::table_types::Table;
 use crate::db::{TransactionKind, RW};
+use crate::deduplication::{append_deduplicated, ClassContentLocationsTable};
 use crate::state::{DeclaredClassesTable, DeprecatedDeclaredClassesTable, FileOffsetTable};
 use crate::{
     DbTransaction,
@@ -166,6 +167,8 @@ impl<'env> ClassStorageWriter for StorageTxn<'env, RW> {
         let deprecated_declared_classes_table =
             self.open_table(&self.tables.deprecated_declared_classes)?;
         let file_offset_table = self.txn.open_table(&self.tables.file_offsets)?;
+        let class_content_locations_table =
+            self.open_table(&self.tables.class_content_locations)?;
         let markers_table = self.open_table(&self.tables.markers)?;
 
         let marker_block_number =
@@ -183,6 +186,7 @@ impl<'env> ClassStorageWriter for StorageTxn<'env, RW> {
             &declared_classes_table,
             &self.file_handlers,
             &file_offset_table,
+            &class_content_locations_table,
         )?;
 
         write_deprecated_classes(
@@ -192,6 +196,7 @@ impl<'env> ClassStorageWriter for StorageTxn<'env, RW> {
             &deprecated_declared_classes_table,
             &self.file_handlers,
             &file_offset_table,
+            &class_content_locations_table,
         )?;
 
         markers_table.upsert(&self.txn, &MarkerKind::Class, &block_number.unchecked_next())?;
@@ -200,40 +205,52 @@ impl<'env> ClassStorageWriter for StorageTxn<'env, RW> {
     }
 }
 
-fn write_classes<'env>(
+pub(crate) fn write_classes<'env>(
     classes: &[(ClassHash, &ContractClass)],
     txn: &DbTransaction<'env, RW>,
     declared_classes_table: &'env DeclaredClassesTable<'env>,
     file_handlers: &FileHandlers<RW>,
     file_offset_table: &'env FileOffsetTable<'env>,
+    class_content_locations_table: &'env ClassContentLocationsTable<'env>,
 ) -> StorageResult<()> {
     for (class_hash, contract_class) in classes {
-        let location = file_handlers.append_contract_class(contract_class);
+        let location = append_deduplicated(
+            txn,
+            class_content_locations_table,
+            file_offset_table,
+            &file_handlers.contract_class,
+            &file_handlers.class_dictionaries,
+            OffsetKind::ContractClass,
+            contract_class,
+        )?;
         declared_classes_table.insert(txn, class_hash, &location)?;
-        file_offset_table.upsert(txn, &OffsetKind::ContractClass, &location.next_offset())?;
     }
     Ok(())
 }
 
-fn write_deprecated_classes<'env>(
+pub(crate) fn write_deprecated_classes<'env>(
     deprecated_classes: &[(ClassHash, &DeprecatedContractClass)],
     txn: &DbTransaction<'env, RW>,
     block_number: BlockNumber,
     deprecated_declared_classes_table: &'env DeprecatedDeclaredClassesTable<'env>,
     file_handlers: &FileHandlers<RW>,
     file_offset_table: &'env FileOffsetTable<'env>,
+    class_content_locations_table: &'env ClassContentLocationsTable<'env>,
 ) -> StorageResult<()> {
     for (class_hash, deprecated_contract_class) in deprecated_classes {
         if deprecated_declared_classes_table.get(txn, class_hash)?.is_some() {
             continue;
         }
-        let location = file_handlers.append_deprecated_contract_class(deprecated_contract_class);
-        let value = IndexedDeprecatedContractClass { block_number, location_in_file: location };
-        file_offset_table.upsert(
+        let location = append_deduplicated(
             txn,
-            &OffsetKind::DeprecatedContractClass,
-            &location.next_offset(),
+            class_content_locations_table,
+            file_offset_table,
+            &file_handlers.deprecated_contract_class,
+            &file_handlers.class_dictionaries,
+            OffsetKind::DeprecatedContractClass,
+            deprecated_contract_class,
         )?;
+        let value = IndexedDeprecatedContractClass { block_number, location_in_file: location };
         deprecated_declared_classes_table.insert(txn, class_hash, &value)?;
     }
     Ok(())
@@ -0,0 +1,383 @@
+//! Compression of the classes with zstd dictionaries.
+//!
+//! The classes are compressed in segments (see [`ClassSegment`]). The segments of different classes
+//! share most of their content, e.g. the same library functions and hints, so a zstd dictionary
+//! trained on the stored segments of a kind compresses the new segments of that kind much better
+//! than compressing each segment on its own.
+//!
+//! The dictionaries are trained from the stored classes, either by
+//! [`StorageWriter::train_class_dictionaries`] or when the storage is migrated to
+//! [`CLASS_DICTIONARIES_STATE_VERSION`], and are stored in the `class_dictionaries` table. The
+//! latest dictionary of each segment compresses the new segments of its kind. A compressed segment
+//! records the ID of its dictionary, so the segments that were compressed without a dictionary, or
+//! with an older one, are still decompressed. The stored classes aren't compressed again.
+//!
+//! Since the serialization of the classes has no access to the storage, the file handlers of the
+//! storage read and write the classes within the scope of its dictionaries
+//! ([`ClassDictionaries::scope`]), and the class segments are compressed with the dictionaries of
+//! the scope. Outside of a scope, the segments are compressed without a
+//! dictionary.
+//!
+//! A read replica (see [`crate::read_replica`]) doesn't share the dictionaries of its writer, which
+//! may train new ones after the replica opened. The replica reloads the stored dictionaries when it
+//! reads a segment that was compressed with a dictionary it doesn't know.
+
+#[cfg(test)]
+#[path = "class_dictionaries_test.rs"]
+mod class_dictionaries_test;
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::io::{Error, ErrorKind};
+use std::sync::{Arc, RwLock};
+
+use serde::{Deserialize, Serialize};
+use starknet_api::core::ClassHash;
+use tracing::{debug, info, warn};
+use zstd::bulk::{Compressor, Decompressor};
+use zstd::dict::{DecoderDictionary, EncoderDictionary};
+use zstd::zstd_safe;
+
+use crate::compression_utils::{compress, decompress_from_reader, COMPRESSION_LEVEL};
+use crate::db::serialization::VersionZeroWrapper;
+use crate::db::table_types::{DbCursorTrait, SimpleTable, Table};
+use crate::db::{DbReader, TableHandle, RW};
+use crate::deduplication::{class_locations, serialized_class};
+use crate::migration::Migration;
+use crate::version::{Version, VersionedComponent};
+use crate::{
+    OffsetKind,
+    StorageError,
+    StorageReader,
+    StorageResult,
+    StorageTxn,
+    StorageWriter,
+    Tables,
+};
+
+/// The first state version in which the classes are compressed with dictionaries.
+pub(crate) const CLASS_DICTIONARIES_STATE_VERSION: Version = Version { major: 1, minor: 3 };
+
+// The maximal size of a dictionary, as in the zstd command line tool.
+const MAX_DICTIONARY_SIZE: usize = 112_640;
+// The maximal number of stored segments that a dictionary is trained on.
+const MAX_TRAINING_SAMPLES: usize = 2000;
+// The minimal number of stored segments to train a dictionary on.
+pub(crate) const MIN_TRAINING_SAMPLES: usize = 8;
+
+/// A part of a class that is compressed on its own.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ClassSegment {
+    /// The Sierra program of a contract class.
+    SierraProgram,
+    /// The program and the ABI of a deprecated contract class.
+    DeprecatedProgram,
+    /// A CASM.
+    Casm,
+}
+
+impl ClassSegment {
+    const ALL: [ClassSegment; 3] =
+        [ClassSegment::SierraProgram, ClassSegment::DeprecatedProgram, ClassSegment::Casm];
+}
+
+/// The dictionaries of each segment, by their index in the order in which they were trained.
+pub(crate) type ClassDictionariesTable<'env> =
+    TableHandle<'env, (ClassSegment, u32), VersionZeroWrapper<Vec<u8>>, SimpleTable>;
+
+#[derive(Default)]
+struct RegisteredDictionaries {
+    // The dictionary that compresses the new segments of each kind.
+    latest: HashMap<ClassSegment, Arc<EncoderDictionary<'static>>>,
+    // The dictionaries that decompress the segments, by their ID.
+    by_id: HashMap<u32, Arc<DecoderDictionary<'static>>>,
+    // Where the dictionaries are reloaded from when a segment was compressed with an unknown one.
+    source: Option<DictionariesSource>,
+}
+
+// The storage whose stored dictionaries are registered.
+#[derive(Clone)]
+struct DictionariesSource {
+    db_reader: DbReader,
+    tables: Arc<Tables>,
+}
+
+/// The dictionaries of the classes of a storage, shared by its reader and writer.
+#[derive(Clone, Default)]
+pub(crate) struct ClassDictionaries(Arc<RwLock<RegisteredDictionaries>>);
+
+impl Debug for ClassDictionaries {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let registered = self.0.read().expect("Lock should not be poisoned");
+        f.debug_struct("ClassDictionaries")
+            .field("segments", &registered.latest.keys().collect::<Vec<_>>())
+            .field("ids", &registered.by_id.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+thread_local! {
+    // The dictionaries of the storage whose classes are being serialized or deserialized.
+    static CURRENT_DICTIONARIES: RefCell<Option<ClassDictionaries>> = const { RefCell::new(None) };
+    // Whether the class segments serialized by the current thread are left uncompressed.
+    static UNCOMPRESSED_SEGMENTS: Cell<bool> = const { Cell::new(false) };
+}
+
+// Restores the dictionaries of the enclosing scope when dropped.
+struct ScopeGuard(Option<ClassDictionaries>);
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        CURRENT_DICTIONARIES.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+// Restores whether the class segments are left uncompressed when dropped.
+struct UncompressedSegmentsGuard(bool);
+
+impl Drop for UncompressedSegmentsGuard {
+    fn drop(&mut self) {
+        UNCOMPRESSED_SEGMENTS.with(|uncompressed| uncompressed.set(self.0));
+    }
+}
+
+impl ClassDictionaries {
+    // Registers a dictionary as the latest of its segment.
+    fn register(&self, segment: ClassSegment, dictionary: &[u8]) -> StorageResult<()> {
+        let Some(dictionary_id) = zstd_safe::get_dict_id_from_dict(dictionary) else {
+            return Err(StorageError::DBInconsistency {
+                msg: format!("The dictionary of {segment:?} has no ID."),
+            });
+        };
+        let mut registered = self.0.write().expect("Lock should not be poisoned");
+        registered
+            .latest
+            .insert(segment, Arc::new(EncoderDictionary::copy(dictionary, COMPRESSION_LEVEL)));
+        registered.by_id.insert(dictionary_id.get(), Arc::new(DecoderDictionary::copy(dictionary)));
+        Ok(())
+    }
+
+    // Registers the dictionaries stored in the storage.
+    fn load(&self, db_reader: &DbReader, tables: &Tables) -> StorageResult<()> {
+        let txn = db_reader.begin_ro_txn()?;
+        let class_dictionaries_table = txn.open_table(&tables.class_dictionaries)?;
+        let mut cursor = class_dictionaries_table.cursor(&txn)?;
+        // The dictionaries of each segment are ordered by their index, so the latest one is
+        // registered last.
+        while let Some(((segment, _index), dictionary)) = cursor.next()? {
+            self.register(segment, &dictionary)?;
+        }
+        Ok(())
+    }
+
+    /// Registers the dictionaries stored in the storage, and registers them again whenever a
+    /// segment compressed with an unknown dictionary is read, since another process may have
+    /// trained it since.
+    pub(crate) fn follow(&self, db_reader: DbReader, tables: Arc<Tables>) -> StorageResult<()> {
+        self.load(&db_reader, &tables)?;
+        self.0.write().expect("Lock should not be poisoned").source =
+            Some(DictionariesSource { db_reader, tables });
+        Ok(())
+    }
+
+    // Returns the dictionary of the given ID, reloading the stored dictionaries if it's unknown
+    // and they have a source.
+    fn decoder(
+        &self,
+        dictionary_id: u32,
+    ) -> StorageResult<Option<Arc<DecoderDictionary<'static>>>> {
+        let source = {
+            let registered = self.0.read().expect("Lock should not be poisoned");
+            if let Some(dictionary) = registered.by_id.get(&dictionary_id) {
+                return Ok(Some(dictionary.clone()));
+            }
+            registered.source.clone()
+        };
+        let Some(DictionariesSource { db_reader, tables }) = source else {
+            return Ok(None);
+        };
+        debug!("Reloading the class dictionaries for the unknown dictionary {dictionary_id}.");
+        self.load(&db_reader, &tables)?;
+        let registered = self.0.read().expect("Lock should not be poisoned");
+        Ok(registered.by_id.get(&dictionary_id).cloned())
+    }
+
+    /// Runs `f` with these dictionaries compressing and decompressing the class segments of the
+    /// current thread.
+    pub(crate) fn scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        let previous = CURRENT_DICTIONARIES.with(|current| current.replace(Some(self.clone())));
+        let _guard = ScopeGuard(previous);
+        f()
+    }
+}
+
+/// Runs `f` with the class segments that the current thread serializes left uncompressed, e.g. for
+/// digesting the content of a class regardless of the dictionaries. Such serializations aren't
+/// deserializable.
+pub(crate) fn with_uncompressed_segments<T>(f: impl FnOnce() -> T) -> T {
+    let previous = UNCOMPRESSED_SEGMENTS.with(|uncompressed| uncompressed.replace(true));
+    let _guard = UncompressedSegmentsGuard(previous);
+    f()
+}
+
+/// Compresses a segment of a class with the latest dictionary of its kind, if there is one.
+pub(crate) fn compress_class_segment(segment: ClassSegment, data: &[u8]) -> Result<Vec<u8>, Error> {
+    if UNCOMPRESSED_SEGMENTS.with(Cell::get) {
+        return Ok(data.to_vec());
+    }
+    let dictionary = CURRENT_DICTIONARIES.with(|current| {
+        let current = current.borrow();
+        let registered = current.as_ref()?.0.read().expect("Lock should not be poisoned");
+        registered.latest.get(&segment).cloned()
+    });
+    match dictionary {
+        Some(dictionary) => Compressor::with_prepared_dictionary(&dictionary)?.compress(data),
+        None => compress(data),
+    }
+}
+
+/// Decompresses data that was compressed with the dictionary of the given ID.
+pub(crate) fn decompress_with_dictionary(
+    dictionary_id: u32,
+    data: &[u8],
+    capacity: usize,
+) -> Result<Vec<u8>, Error> {
+    let class_dictionaries = CURRENT_DICTIONARIES.with(|current| current.borrow().clone());
+    let dictionary = match class_dictionaries {
+        Some(class_dictionaries) => class_dictionaries
+            .decoder(dictionary_id)
+            .map_err(|error| Error::new(ErrorKind::Other, error.to_string()))?,
+        None => None,
+    };
+    let dictionary = dictionary.ok_or_else(|| {
+        Error::new(ErrorKind::NotFound, format!("Unknown compression dictionary {dictionary_id}."))
+    })?;
+    Decompressor::with_prepared_dictionary(&dictionary)?.decompress(data, capacity)
+}
+
+/// Registers the stored dictionaries in the class dictionaries of the storage.
+pub(crate) fn load_class_dictionaries(reader: &StorageReader) -> StorageResult<()> {
+    reader.file_readers.class_dictionaries.load(&reader.db_reader, &reader.tables)
+}
+
+impl StorageWriter {
+    /// Trains a compression dictionary for each class segment from the stored classes, and
+    /// compresses the classes that are stored from now on with them. Returns the segments for
+    /// which a dictionary was trained: a segment needs at least a few stored classes.
+    pub fn train_class_dictionaries(&mut self) -> StorageResult<Vec<ClassSegment>> {
+        let txn = self.begin_rw_txn()?;
+        let dictionaries = train_and_store_dictionaries(&txn)?;
+        txn.commit()?;
+        for (segment, dictionary) in &dictionaries {
+            self.file_writers.class_dictionaries.register(*segment, dictionary)?;
+        }
+        Ok(dictionaries.into_iter().map(|(segment, _dictionary)| segment).collect())
+    }
+}
+
+// Trains a dictionary for each segment that has enough stored classes and stores it. Returns the
+// trained dictionaries.
+fn train_and_store_dictionaries(
+    txn: &StorageTxn<'_, RW>,
+) -> StorageResult<Vec<(ClassSegment, Vec<u8>)>> {
+    let class_dictionaries_table = txn.open_table(&txn.tables.class_dictionaries)?;
+    let mut dictionaries = Vec::new();
+    for segment in ClassSegment::ALL {
+        let samples = segment_samples(txn, segment)?;
+        if samples.len() < MIN_TRAINING_SAMPLES {
+            debug!("Not training a dictionary for {segment:?}: only {} classes.", samples.len());
+            continue;
+        }
+        let dictionary = match zstd::dict::from_samples(&samples, MAX_DICTIONARY_SIZE) {
+            Ok(dictionary) => dictionary,
+            Err(error) => {
+                warn!("Failed to train a dictionary for {segment:?}: {error}.");
+                continue;
+            }
+        };
+        let index = n_dictionaries(&class_dictionaries_table, txn, segment)?;
+        class_dictionaries_table.insert(&txn.txn, &(segment, index), &dictionary)?;
+        info!(
+            "Trained a dictionary of {} bytes for {segment:?} from {} classes.",
+            dictionary.len(),
+            samples.len()
+        );
+        dictionaries.push((segment, dictionary));
+    }
+    Ok(dictionaries)
+}
+
+fn n_dictionaries(
+    class_dictionaries_table: &ClassDictionariesTable<'_>,
+    txn: &StorageTxn<'_, RW>,
+    segment: ClassSegment,
+) -> StorageResult<u32> {
+    let mut cursor = class_dictionaries_table.cursor(&txn.txn)?;
+    let mut current = cursor.lower_bound(&(segment, 0))?;
+    let mut n_dictionaries = 0;
+    while let Some(((current_segment, _index), _dictionary)) = current {
+        if current_segment != segment {
+            break;
+        }
+        n_dictionaries += 1;
+        current = cursor.next()?;
+    }
+    Ok(n_dictionaries)
+}
+
+// Returns the uncompressed segments of up to MAX_TRAINING_SAMPLES stored classes.
+fn segment_samples(txn: &StorageTxn<'_, RW>, segment: ClassSegment) -> StorageResult<Vec<Vec<u8>>> {
+    let offset_kind = match segment {
+        ClassSegment::SierraProgram => OffsetKind::ContractClass,
+        ClassSegment::DeprecatedProgram => OffsetKind::DeprecatedContractClass,
+        ClassSegment::Casm => OffsetKind::Casm,
+    };
+    let (locations, _next_class_hash) =
+        class_locations(txn, offset_kind, &ClassHash::default(), MAX_TRAINING_SAMPLES)?;
+    let mut samples = Vec::with_capacity(locations.len());
+    for location in locations {
+        // The serialized class starts with the version of its value, followed by the compressed
+        // segment.
+        let serialized = serialized_class(txn, offset_kind, location)?;
+        let sample = txn
+            .file_handlers
+            .class_dictionaries
+            .scope(|| serialized.get(1..).and_then(|mut value| decompress_from_reader(&mut value)));
+        samples.push(sample.ok_or_else(|| StorageError::DBInconsistency {
+            msg: format!("Failed to decompress the {segment:?} of the class at {location:?}."),
+        })?);
+    }
+    Ok(samples)
+}
+
+/// Trains the class dictionaries of a storage that was created before the classes were compressed
+/// with dictionaries. A storage that has only a few classes remains without dictionaries, until
+/// they're trained by [`StorageWriter::train_class_dictionaries`].
+pub(crate) struct TrainClassDictionaries;
+
+impl Migration for TrainClassDictionaries {
+    fn name(&self) -> &'static str {
+        "train_class_dictionaries"
+    }
+
+    fn component(&self) -> VersionedComponent {
+        VersionedComponent::State
+    }
+
+    fn target_version(&self) -> Version {
+        CLASS_DICTIONARIES_STATE_VERSION
+    }
+
+    fn migrate_batch(
+        &self,
+        txn: &StorageTxn<'_, RW>,
+        _checkpoint: Option<Vec<u8>>,
+        _batch_size: usize,
+    ) -> StorageResult<(u64, Option<Vec<u8>>)> {
+        // The dictionaries are registered when the storage finishes opening.
+        let dictionaries = train_and_store_dictionaries(txn)?;
+        Ok((dictionaries.len() as u64, None))
+    }
+}
@@ -0,0 +1,137 @@
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use cairo_lang_utils::bigint::BigUintAsHex;
+use num_bigint::BigUint;
+use papyrus_test_utils::read_json_file;
+use pretty_assertions::assert_eq;
+use starknet_api::core::ClassHash;
+use starknet_api::felt;
+
+use crate::class_dictionaries::{ClassSegment, MIN_TRAINING_SAMPLES};
+use crate::compiled_class::{CasmStorageReader, CasmStorageWriter};
+use crate::db::serialization::{ValueSerde, VersionZeroWrapper};
+use crate::db::table_types::Table;
+use crate::mmap_file::LocationInFile;
+use crate::read_replica::read_replica;
+use crate::test_utils::{get_test_config, get_test_storage};
+use crate::{open_storage, StorageReader, StorageWriter};
+
+// Returns a CASM that differs from the other CASMs of different indices.
+fn casm(index: u64) -> CasmContractClass {
+    let mut casm: CasmContractClass =
+        serde_json::from_value(read_json_file("compiled_class.json")).unwrap();
+    casm.compiler_version = format!("{}.{index}", casm.compiler_version);
+    casm.bytecode.push(BigUintAsHex { value: BigUint::from(index) });
+    casm
+}
+
+fn class_hash(index: u64) -> ClassHash {
+    ClassHash(felt!(index + 1))
+}
+
+fn append_casms(writer: &mut StorageWriter, indices: impl Iterator<Item = u64>) {
+    for index in indices {
+        writer
+            .begin_rw_txn()
+            .unwrap()
+            .append_casm(&class_hash(index), &casm(index))
+            .unwrap()
+            .commit()
+            .unwrap();
+    }
+}
+
+fn casm_location(reader: &StorageReader, class_hash: &ClassHash) -> LocationInFile {
+    let txn = reader.begin_ro_txn().unwrap();
+    let casms_table = txn.open_table(&txn.tables.casms).unwrap();
+    casms_table.get(&txn.txn, class_hash).unwrap().unwrap()
+}
+
+fn n_training_samples() -> u64 {
+    MIN_TRAINING_SAMPLES.try_into().unwrap()
+}
+
+#[test]
+fn no_dictionary_is_trained_from_few_classes() {
+    let ((_reader, mut writer), _temp_dir) = get_test_storage();
+    append_casms(&mut writer, 0..n_training_samples() - 1);
+    assert_eq!(writer.train_class_dictionaries().unwrap(), vec![]);
+}
+
+#[test]
+fn classes_are_compressed_with_the_trained_dictionary() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    append_casms(&mut writer, 0..n_training_samples());
+    assert_eq!(writer.train_class_dictionaries().unwrap(), vec![ClassSegment::Casm]);
+
+    let new_index = n_training_samples();
+    append_casms(&mut writer, [new_index].into_iter());
+
+    // Without the dictionary of the storage, the CASM is compressed on its own.
+    let compressed_without_dictionary =
+        VersionZeroWrapper::<CasmContractClass>::serialize(&casm(new_index)).unwrap();
+    assert!(
+        casm_location(&reader, &class_hash(new_index)).len < compressed_without_dictionary.len()
+    );
+
+    // Both the CASMs that were compressed before the dictionary was trained and the ones that
+    // were compressed with it are read.
+    let txn = reader.begin_ro_txn().unwrap();
+    for index in 0..=new_index {
+        assert_eq!(txn.get_casm(&class_hash(index)).unwrap(), Some(casm(index)));
+    }
+}
+
+#[test]
+fn identical_classes_are_deduplicated_across_dictionaries() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    append_casms(&mut writer, 0..n_training_samples());
+    writer.train_class_dictionaries().unwrap();
+
+    // The CASM is compressed with the new dictionary, but its content is already stored.
+    let duplicate_class_hash = class_hash(n_training_samples());
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_casm(&duplicate_class_hash, &casm(0))
+        .unwrap()
+        .commit()
+        .unwrap();
+    assert_eq!(
+        casm_location(&reader, &duplicate_class_hash),
+        casm_location(&reader, &class_hash(0))
+    );
+}
+
+#[test]
+fn dictionaries_are_loaded_when_the_storage_is_opened() {
+    let (config, _temp_dir) = get_test_config(None);
+    let new_index = n_training_samples();
+    {
+        let (_reader, mut writer) = open_storage(config.clone()).unwrap();
+        append_casms(&mut writer, 0..n_training_samples());
+        writer.train_class_dictionaries().unwrap();
+        append_casms(&mut writer, [new_index].into_iter());
+    }
+
+    let (reader, _writer) = open_storage(config).unwrap();
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_casm(&class_hash(new_index)).unwrap(), Some(casm(new_index)));
+}
+
+#[test]
+fn read_replica_reloads_the_dictionaries_trained_after_it_opened() {
+    let (config, _temp_dir) = get_test_config(None);
+    let (reader, mut writer) = open_storage(config.clone()).unwrap();
+    append_casms(&mut writer, 0..n_training_samples());
+    // The replica doesn't share the dictionaries of the writer, as if it were in another process.
+    let replica = read_replica(config, reader.db_reader.clone(), reader.tables.clone()).unwrap();
+
+    writer.train_class_dictionaries().unwrap();
+    let new_index = n_training_samples();
+    append_casms(&mut writer, [new_index].into_iter());
+
+    let txn = replica.begin_ro_txn().unwrap();
+    for index in 0..=new_index {
+        assert_eq!(txn.get_casm(&class_hash(index)).unwrap(), Some(casm(index)));
+    }
+}
@@ -45,6 +45,7 @@ use starknet_api::core::ClassHash;
 use crate::db::serialization::VersionZeroWrapper;
 use crate::db::table_types::{SimpleTable, Table};
 use crate::db::{DbTransaction, TableHandle, TransactionKind, RW};
+use crate::deduplication::append_deduplicated;
 use crate::mmap_file::LocationInFile;
 use crate::{FileHandlers, MarkerKind, MarkersTable, OffsetKind, StorageResult, StorageTxn};
 
@@ -89,10 +90,19 @@ impl<'env> CasmStorageWriter for StorageTxn<'env, RW> {
         let markers_table = self.open_table(&self.tables.markers)?;
         let state_diff_table = self.open_table(&self.tables.state_diffs)?;
         let file_offset_table = self.txn.open_table(&self.tables.file_offsets)?;
+        let class_content_locations_table =
+            self.open_table(&self.tables.class_content_locations)?;
 
-        let location = self.file_handlers.append_casm(casm);
+        let location = append_deduplicated(
+            &self.txn,
+            &class_content_locations_table,
+            &file_offset_table,
+            &self.file_handlers.casm,
+            &self.file_handlers.class_dictionaries,
+            OffsetKind::Casm,
+            casm,
+        )?;
         casm_table.insert(&self.txn, class_hash, &location)?;
-        file_offset_table.upsert(&self.txn, &OffsetKind::Casm, &location.next_offset())?;
         update_marker(
             &self.txn,
             &markers_table,
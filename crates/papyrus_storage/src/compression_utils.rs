@@ -2,6 +2,9 @@
 #[path = "compression_utils_test.rs"]
 mod compression_utils_test;
 
+use zstd::zstd_safe;
+
+use crate::class_dictionaries::decompress_with_dictionary;
 use crate::db::serialization::{StorageSerde, StorageSerdeError};
 
 // TODO(dvir): create one compressor/decompressor only once (maybe only once per thread) to prevent
@@ -13,7 +16,7 @@ use crate::db::serialization::{StorageSerde, StorageSerdeError};
 // decompress function.
 pub(crate) const MAX_DECOMPRESSED_SIZE: usize = 1 << 28; // 256 MB
 // The compression level to use. Higher levels are slower but compress better.
-const COMPRESSION_LEVEL: i32 = zstd::DEFAULT_COMPRESSION_LEVEL;
+pub(crate) const COMPRESSION_LEVEL: i32 = zstd::DEFAULT_COMPRESSION_LEVEL;
 
 /// Returns the compressed data in a vector.
 ///
@@ -39,15 +42,22 @@ pub fn serialize_and_compress(object: &impl StorageSerde) -> Result<Vec<u8>, Sto
     Ok(compress(buf.as_slice())?)
 }
 
-/// Decompress data and returns it as bytes in a vector.
+/// Decompress data and returns it as bytes in a vector. Data that was compressed with a dictionary
+/// (see [`crate::class_dictionaries`]) is decompressed with it.
 ///
 /// # Arguments
 /// * data - bytes to decompress.
 
 /// # Errors
-/// Returns [`std::io::Error`] if any read error is encountered.
+/// Returns [`std::io::Error`] if any read error is encountered, or if the dictionary of the data
+/// isn't registered.
 pub fn decompress(data: &[u8]) -> Result<Vec<u8>, std::io::Error> {
-    zstd::bulk::decompress(data, MAX_DECOMPRESSED_SIZE)
+    match zstd_safe::get_dict_id_from_frame(data) {
+        Some(dictionary_id) => {
+            decompress_with_dictionary(dictionary_id.get(), data, MAX_DECOMPRESSED_SIZE)
+        }
+        None => zstd::bulk::decompress(data, MAX_DECOMPRESSED_SIZE),
+    }
 }
 
 /// Decompress a vector directly from a reader.
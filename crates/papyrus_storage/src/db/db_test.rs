@@ -5,8 +5,9 @@ use tempfile::TempDir;
 
 use crate::db::serialization::{NoVersionValueWrapper, ValueSerde, VersionZeroWrapper};
 use crate::db::table_types::Table;
-use crate::db::{get_page_size, open_env, DbError, DbIter, DbReader, DbResult, DbWriter};
-use crate::test_utils::get_test_config;
+use crate::db::{get_page_size, open_env, DbError, DbIter, DbReader, DbResult, DbWriter, MAX_DBS};
+use crate::table_names;
+use crate::test_utils::{get_test_config, get_test_storage};
 
 pub(crate) fn get_test_env() -> ((DbReader, DbWriter), TempDir) {
     let (config, temp_dir) = get_test_config(None);
@@ -18,6 +19,15 @@ fn open_env_scenario() {
     get_test_env();
 }
 
+#[test]
+fn open_fresh_storage_with_all_tables() {
+    assert!(table_names().len() <= MAX_DBS, "MAX_DBS should cover all the tables.");
+    let ((reader, _writer), _temp_dir) = get_test_storage();
+    // Reading the stats of a table that wasn't created fails.
+    let stats = reader.db_tables_stats().unwrap();
+    assert_eq!(stats.tables_stats.len(), table_names().len());
+}
+
 #[test]
 fn open_env_with_enforce_file_exists() {
     let (config, _temp_dir) = get_test_config(None);
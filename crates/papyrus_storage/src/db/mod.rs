@@ -29,7 +29,7 @@ use std::path::PathBuf;
 use std::result;
 use std::sync::Arc;
 
-use libmdbx::{DatabaseFlags, Geometry, PageSize, WriteMap};
+use libmdbx::{DatabaseFlags, Geometry, Mode, PageSize, WriteMap};
 use papyrus_config::dumping::{ser_param, SerializeConfig};
 use papyrus_config::validators::{validate_ascii, validate_path_exists};
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
@@ -39,11 +39,12 @@ use starknet_api::core::ChainId;
 use validator::Validate;
 
 use self::serialization::{Key, ValueSerde};
-use self::table_types::{DbCursor, DbCursorTrait};
+use self::table_types::{CommonPrefix, DbCursor, DbCursorTrait, SimpleTable};
 use crate::db::table_types::TableType;
 
-// Maximum number of Sub-Databases.
-const MAX_DBS: usize = 18;
+// Maximum number of Sub-Databases. Must be at least the number of tables of the storage.
+pub(crate) const MAX_DBS: usize = 32;
+const MAX_READERS: u32 = 1 << 13; // 8K readers
 
 // Note that NO_TLS mode is used by default.
 type EnvironmentKind = WriteMap;
@@ -165,6 +166,9 @@ pub enum DbError {
     /// An error that occurred when trying to append a key when it is not the last.
     #[error("Append error. The key is not the last in the table.")]
     Append,
+    /// An error that occurred when accessing a table that wasn't created.
+    #[error("The table '{0}' does not exist.")]
+    TableNotFound(String),
 }
 
 type DbResult<V> = result::Result<V, DbError>;
@@ -196,7 +200,6 @@ pub(crate) fn open_env(config: &DbConfig) -> DbResult<(DbReader, DbWriter)> {
     if config.enforce_file_exists && !db_file_path.exists() {
         return Err(DbError::FileDoesNotExist(db_file_path));
     }
-    const MAX_READERS: u32 = 1 << 13; // 8K readers
     let env = Arc::new(
         Environment::new()
             .set_geometry(Geometry {
@@ -220,6 +223,21 @@ pub(crate) fn open_env(config: &DbConfig) -> DbResult<(DbReader, DbWriter)> {
     Ok((DbReader { env: env.clone() }, DbWriter { env }))
 }
 
+/// Opens an existing MDBX environment read-only and returns a reader to it. The environment can
+/// be written by a writer in another process, and the reader's transactions see its commits.
+pub(crate) fn open_env_read_only(config: &DbConfig) -> DbResult<DbReader> {
+    let db_file_path = config.path().join("mdbx.dat");
+    if !db_file_path.exists() {
+        return Err(DbError::FileDoesNotExist(db_file_path));
+    }
+    let env = Environment::new()
+        .set_max_tables(MAX_DBS)
+        .set_max_readers(MAX_READERS)
+        .set_flags(DatabaseFlags { mode: Mode::ReadOnly, no_rdahead: true, ..Default::default() })
+        .open(&config.path())?;
+    Ok(DbReader { env: Arc::new(env) })
+}
+
 // Size in bytes.
 const MDBX_MIN_PAGESIZE: usize = 256;
 const MDBX_MAX_PAGESIZE: usize = 65536; // 64KB
@@ -261,6 +279,72 @@ impl DbWriter {
 
 type DbWriteTransaction<'env> = DbTransaction<'env, RW>;
 
+// Provides the identifiers of the tables in the database: the writer creates the tables that
+// don't exist yet, and a read-only reader checks that they exist.
+pub(crate) trait TableProvider {
+    fn simple_table<K: Key + Debug, V: ValueSerde + Debug>(
+        &mut self,
+        name: &'static str,
+    ) -> DbResult<TableIdentifier<K, V, SimpleTable>>;
+
+    fn common_prefix_table<MainKey: Key + Debug, SubKey: Key + Debug, V: ValueSerde + Debug>(
+        &mut self,
+        name: &'static str,
+    ) -> DbResult<TableIdentifier<(MainKey, SubKey), V, CommonPrefix>>
+    where
+        (MainKey, SubKey): Key + Debug;
+}
+
+impl TableProvider for DbWriter {
+    fn simple_table<K: Key + Debug, V: ValueSerde + Debug>(
+        &mut self,
+        name: &'static str,
+    ) -> DbResult<TableIdentifier<K, V, SimpleTable>> {
+        self.create_simple_table(name)
+    }
+
+    fn common_prefix_table<MainKey: Key + Debug, SubKey: Key + Debug, V: ValueSerde + Debug>(
+        &mut self,
+        name: &'static str,
+    ) -> DbResult<TableIdentifier<(MainKey, SubKey), V, CommonPrefix>>
+    where
+        (MainKey, SubKey): Key + Debug,
+    {
+        self.create_common_prefix_table(name)
+    }
+}
+
+impl TableProvider for DbReader {
+    fn simple_table<K: Key + Debug, V: ValueSerde + Debug>(
+        &mut self,
+        name: &'static str,
+    ) -> DbResult<TableIdentifier<K, V, SimpleTable>> {
+        self.verify_table_exists(name)?;
+        Ok(TableIdentifier::new(name))
+    }
+
+    fn common_prefix_table<MainKey: Key + Debug, SubKey: Key + Debug, V: ValueSerde + Debug>(
+        &mut self,
+        name: &'static str,
+    ) -> DbResult<TableIdentifier<(MainKey, SubKey), V, CommonPrefix>>
+    where
+        (MainKey, SubKey): Key + Debug,
+    {
+        self.verify_table_exists(name)?;
+        Ok(TableIdentifier::new(name))
+    }
+}
+
+impl DbReader {
+    fn verify_table_exists(&self, name: &'static str) -> DbResult<()> {
+        match self.env.begin_ro_txn()?.open_table(Some(name)) {
+            Ok(_) => Ok(()),
+            Err(libmdbx::Error::NotFound) => Err(DbError::TableNotFound(name.to_owned())),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
 impl<'a> DbWriteTransaction<'a> {
     #[latency_histogram("storage_commit_inner_db_latency_seconds", false)]
     pub(crate) fn commit(self) -> DbResult<()> {
@@ -301,6 +385,17 @@ pub(crate) struct TableIdentifier<K: Key + Debug, V: ValueSerde + Debug, T: Tabl
     _table_type: PhantomData<T>,
 }
 
+impl<K: Key + Debug, V: ValueSerde + Debug, T: TableType> TableIdentifier<K, V, T> {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _key_type: PhantomData {},
+            _value_type: PhantomData {},
+            _table_type: PhantomData {},
+        }
+    }
+}
+
 pub(crate) struct TableHandle<'env, K: Key + Debug, V: ValueSerde + Debug, T: TableType> {
     database: libmdbx::Table<'env>,
     name: &'static str,
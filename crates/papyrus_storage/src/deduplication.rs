@@ -0,0 +1,243 @@
+//! Deduplication of the classes stored in the mmap files.
+//!
+//! Identical class contents are common, e.g. CASMs whose Sierra classes differ only in their ABI,
+//! or classes that are declared again after their block was reverted. Before appending a class to
+//! its file, the digest of its content is looked up in the `class_content_locations` table, and if
+//! the content was already stored, the existing location is returned instead.
+//!
+//! The digest covers the kind of the file, so the same table holds the locations of all the class
+//! files. It covers the class segments uncompressed, so identical classes have the same digest
+//! whichever dictionaries compress them (see [`crate::class_dictionaries`]).
+
+#[cfg(test)]
+#[path = "deduplication_test.rs"]
+mod deduplication_test;
+
+use std::fmt::Debug;
+
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use sha2::{Digest, Sha256};
+use starknet_api::core::ClassHash;
+use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
+use starknet_api::state::ContractClass;
+use tracing::debug;
+
+use crate::class_dictionaries::{with_uncompressed_segments, ClassDictionaries};
+use crate::db::serialization::{StorageSerde, ValueSerde, VersionZeroWrapper};
+use crate::db::table_types::{DbCursorTrait, SimpleTable, Table};
+use crate::db::{DbError, DbTransaction, TableHandle, RW};
+use crate::migration::Migration;
+use crate::mmap_file::{FileHandler, LocationInFile};
+use crate::state::FileOffsetTable;
+use crate::version::{Version, VersionedComponent};
+use crate::{OffsetKind, StorageError, StorageResult, StorageTxn};
+
+/// The first state version in which identical class contents are stored once.
+pub(crate) const DEDUPLICATION_STATE_VERSION: Version = Version { major: 1, minor: 2 };
+
+/// The SHA-256 digest of the kind of a file and the content of an object in it.
+pub(crate) type ContentDigest = [u8; 32];
+
+pub(crate) type ClassContentLocationsTable<'env> =
+    TableHandle<'env, ContentDigest, VersionZeroWrapper<LocationInFile>, SimpleTable>;
+
+pub(crate) fn content_digest<V: ValueSerde>(
+    offset_kind: OffsetKind,
+    value: &V::Value,
+) -> StorageResult<ContentDigest> {
+    let content = with_uncompressed_segments(|| V::serialize(value))?;
+    let mut hasher = Sha256::new();
+    hasher.update([offset_kind as u8]);
+    hasher.update(content);
+    Ok(hasher.finalize().into())
+}
+
+/// Appends the value to the file unless an identical value was already appended to it, and
+/// returns the location of the value. The file offset is updated only if the value was appended.
+pub(crate) fn append_deduplicated<'env, V: ValueSerde + Debug>(
+    txn: &DbTransaction<'env, RW>,
+    class_content_locations_table: &'env ClassContentLocationsTable<'env>,
+    file_offset_table: &'env FileOffsetTable<'env>,
+    file_handler: &FileHandler<V, RW>,
+    class_dictionaries: &ClassDictionaries,
+    offset_kind: OffsetKind,
+    value: &V::Value,
+) -> StorageResult<LocationInFile> {
+    let digest = content_digest::<V>(offset_kind, value)?;
+    if let Some(location) = class_content_locations_table.get(txn, &digest)? {
+        debug!("Reusing the stored content of {offset_kind:?} at {location:?}.");
+        return Ok(location);
+    }
+    let serialized = class_dictionaries.scope(|| V::serialize(value))?;
+    let location = file_handler.clone().append_serialized(&serialized);
+    class_content_locations_table.insert(txn, &digest, &location)?;
+    file_offset_table.upsert(txn, &offset_kind, &location.next_offset())?;
+    Ok(location)
+}
+
+// The class files in the order in which their contents are indexed by [`IndexClassContents`].
+const CLASS_FILES: [OffsetKind; 3] =
+    [OffsetKind::ContractClass, OffsetKind::DeprecatedContractClass, OffsetKind::Casm];
+
+/// Indexes the contents of the classes that were stored before the classes were deduplicated.
+/// Duplicates that are already stored remain in the files, but new duplicates of them won't be
+/// stored.
+///
+/// The checkpoint is the file and the class hash to continue from.
+pub(crate) struct IndexClassContents;
+
+impl Migration for IndexClassContents {
+    fn name(&self) -> &'static str {
+        "index_class_contents"
+    }
+
+    fn component(&self) -> VersionedComponent {
+        VersionedComponent::State
+    }
+
+    fn target_version(&self) -> Version {
+        DEDUPLICATION_STATE_VERSION
+    }
+
+    fn migrate_batch(
+        &self,
+        txn: &StorageTxn<'_, RW>,
+        checkpoint: Option<Vec<u8>>,
+        batch_size: usize,
+    ) -> StorageResult<(u64, Option<Vec<u8>>)> {
+        let (mut file_index, mut start) = match checkpoint {
+            None => (0, ClassHash::default()),
+            Some(checkpoint) => decode_checkpoint(&checkpoint)?,
+        };
+        let class_content_locations_table = txn.open_table(&txn.tables.class_content_locations)?;
+        let mut migrated_entries = 0;
+        while file_index < CLASS_FILES.len() {
+            let offset_kind = CLASS_FILES[file_index];
+            let (locations, next_class_hash) =
+                class_locations(txn, offset_kind, &start, batch_size - migrated_entries)?;
+            for location in locations {
+                let digest = stored_content_digest(txn, offset_kind, location)?;
+                if class_content_locations_table.get(&txn.txn, &digest)?.is_none() {
+                    class_content_locations_table.insert(&txn.txn, &digest, &location)?;
+                }
+                migrated_entries += 1;
+            }
+            match next_class_hash {
+                Some(next_class_hash) => start = next_class_hash,
+                None => {
+                    file_index += 1;
+                    start = ClassHash::default();
+                }
+            }
+            if migrated_entries == batch_size && file_index < CLASS_FILES.len() {
+                return Ok((migrated_entries as u64, Some(encode_checkpoint(file_index, &start)?)));
+            }
+        }
+        Ok((migrated_entries as u64, None))
+    }
+}
+
+// Returns the content digest of the class at the location in the class file.
+fn stored_content_digest(
+    txn: &StorageTxn<'_, RW>,
+    offset_kind: OffsetKind,
+    location: LocationInFile,
+) -> StorageResult<ContentDigest> {
+    let file_handlers = &txn.file_handlers;
+    match offset_kind {
+        OffsetKind::ContractClass => content_digest::<VersionZeroWrapper<ContractClass>>(
+            offset_kind,
+            &file_handlers.get_contract_class_unchecked(location)?,
+        ),
+        OffsetKind::DeprecatedContractClass => {
+            content_digest::<VersionZeroWrapper<DeprecatedContractClass>>(
+                offset_kind,
+                &file_handlers.get_deprecated_contract_class_unchecked(location)?,
+            )
+        }
+        _ => content_digest::<VersionZeroWrapper<CasmContractClass>>(
+            offset_kind,
+            &file_handlers.get_casm_unchecked(location)?,
+        ),
+    }
+}
+
+// Returns the serialized class at the location in the class file.
+pub(crate) fn serialized_class<'txn>(
+    txn: &'txn StorageTxn<'_, RW>,
+    offset_kind: OffsetKind,
+    location: LocationInFile,
+) -> StorageResult<&'txn [u8]> {
+    Ok(match offset_kind {
+        OffsetKind::ContractClass => txn.file_handlers.contract_class.get_serialized(location)?,
+        OffsetKind::DeprecatedContractClass => {
+            txn.file_handlers.deprecated_contract_class.get_serialized(location)?
+        }
+        _ => txn.file_handlers.casm.get_serialized(location)?,
+    })
+}
+
+// Returns the locations of up to `limit` classes of the file, starting from the given class hash,
+// and the class hash to continue from, if there are more classes.
+pub(crate) fn class_locations(
+    txn: &StorageTxn<'_, RW>,
+    offset_kind: OffsetKind,
+    start: &ClassHash,
+    limit: usize,
+) -> StorageResult<(Vec<LocationInFile>, Option<ClassHash>)> {
+    let mut locations = Vec::new();
+    match offset_kind {
+        OffsetKind::ContractClass => {
+            let table = txn.open_table(&txn.tables.declared_classes)?;
+            let mut cursor = table.cursor(&txn.txn)?;
+            let mut current = cursor.lower_bound(start)?;
+            while let Some((class_hash, location)) = current {
+                if locations.len() == limit {
+                    return Ok((locations, Some(class_hash)));
+                }
+                locations.push(location);
+                current = cursor.next()?;
+            }
+        }
+        OffsetKind::DeprecatedContractClass => {
+            let table = txn.open_table(&txn.tables.deprecated_declared_classes)?;
+            let mut cursor = table.cursor(&txn.txn)?;
+            let mut current = cursor.lower_bound(start)?;
+            while let Some((class_hash, indexed_class)) = current {
+                if locations.len() == limit {
+                    return Ok((locations, Some(class_hash)));
+                }
+                locations.push(indexed_class.location_in_file);
+                current = cursor.next()?;
+            }
+        }
+        _ => {
+            let table = txn.open_table(&txn.tables.casms)?;
+            let mut cursor = table.cursor(&txn.txn)?;
+            let mut current = cursor.lower_bound(start)?;
+            while let Some((class_hash, location)) = current {
+                if locations.len() == limit {
+                    return Ok((locations, Some(class_hash)));
+                }
+                locations.push(location);
+                current = cursor.next()?;
+            }
+        }
+    }
+    Ok((locations, None))
+}
+
+fn encode_checkpoint(file_index: usize, class_hash: &ClassHash) -> StorageResult<Vec<u8>> {
+    let mut checkpoint = Vec::new();
+    file_index.serialize_into(&mut checkpoint).map_err(|_| DbError::Serialization)?;
+    class_hash.serialize_into(&mut checkpoint).map_err(|_| DbError::Serialization)?;
+    Ok(checkpoint)
+}
+
+fn decode_checkpoint(mut checkpoint: &[u8]) -> StorageResult<(usize, ClassHash)> {
+    let file_index = usize::deserialize_from(&mut checkpoint);
+    let class_hash = ClassHash::deserialize_from(&mut checkpoint);
+    file_index.zip(class_hash).ok_or(StorageError::DBInconsistency {
+        msg: "Invalid checkpoint of the class contents indexing.".to_string(),
+    })
+}
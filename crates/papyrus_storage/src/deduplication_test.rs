@@ -0,0 +1,110 @@
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use papyrus_test_utils::read_json_file;
+use pretty_assertions::assert_eq;
+use starknet_api::core::ClassHash;
+use starknet_api::felt;
+
+use crate::compiled_class::{CasmStorageReader, CasmStorageWriter};
+use crate::db::table_types::{DbCursorTrait, Table};
+use crate::deduplication::IndexClassContents;
+use crate::migration::Migration;
+use crate::mmap_file::LocationInFile;
+use crate::test_utils::get_test_storage;
+use crate::{OffsetKind, StorageReader};
+
+fn casm_location(reader: &StorageReader, class_hash: &ClassHash) -> LocationInFile {
+    let txn = reader.begin_ro_txn().unwrap();
+    let casms_table = txn.open_table(&txn.tables.casms).unwrap();
+    casms_table.get(&txn.txn, class_hash).unwrap().unwrap()
+}
+
+fn casm_file_offset(reader: &StorageReader) -> usize {
+    let txn = reader.begin_ro_txn().unwrap();
+    let file_offsets_table = txn.open_table(&txn.tables.file_offsets).unwrap();
+    file_offsets_table.get(&txn.txn, &OffsetKind::Casm).unwrap().unwrap()
+}
+
+#[test]
+fn identical_casms_are_stored_once() {
+    let casm: CasmContractClass =
+        serde_json::from_value(read_json_file("compiled_class.json")).unwrap();
+    let first_class_hash = ClassHash(felt!("0x1"));
+    let second_class_hash = ClassHash(felt!("0x2"));
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+
+    writer.begin_rw_txn().unwrap().append_casm(&first_class_hash, &casm).unwrap().commit().unwrap();
+    let file_offset = casm_file_offset(&reader);
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_casm(&second_class_hash, &casm)
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    assert_eq!(
+        casm_location(&reader, &first_class_hash),
+        casm_location(&reader, &second_class_hash)
+    );
+    assert_eq!(casm_file_offset(&reader), file_offset);
+    assert_eq!(reader.begin_ro_txn().unwrap().get_casm(&second_class_hash).unwrap(), Some(casm));
+
+    // A different CASM is appended to the file.
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_casm(&ClassHash(felt!("0x3")), &CasmContractClass::default())
+        .unwrap()
+        .commit()
+        .unwrap();
+    assert!(casm_file_offset(&reader) > file_offset);
+}
+
+#[test]
+fn index_classes_stored_before_deduplication() {
+    let first_class_hash = ClassHash(felt!("0x1"));
+    let second_class_hash = ClassHash(felt!("0x2"));
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_casm(&first_class_hash, &CasmContractClass::default())
+        .unwrap()
+        .commit()
+        .unwrap();
+    // Simulate a storage that was written before the classes were deduplicated.
+    let txn = writer.begin_rw_txn().unwrap();
+    let class_content_locations_table =
+        txn.open_table(&txn.tables.class_content_locations).unwrap();
+    let mut cursor = class_content_locations_table.cursor(&txn.txn).unwrap();
+    let mut digests = Vec::new();
+    while let Some((digest, _location)) = cursor.next().unwrap() {
+        digests.push(digest);
+    }
+    drop(cursor);
+    for digest in digests {
+        class_content_locations_table.delete(&txn.txn, &digest).unwrap();
+    }
+    txn.commit().unwrap();
+
+    let txn = writer.begin_rw_txn().unwrap();
+    let (migrated_entries, checkpoint) = IndexClassContents.migrate_batch(&txn, None, 10).unwrap();
+    txn.commit().unwrap();
+    assert_eq!(migrated_entries, 1);
+    assert!(checkpoint.is_none());
+
+    let file_offset = casm_file_offset(&reader);
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_casm(&second_class_hash, &CasmContractClass::default())
+        .unwrap()
+        .commit()
+        .unwrap();
+    assert_eq!(
+        casm_location(&reader, &first_class_hash),
+        casm_location(&reader, &second_class_hash)
+    );
+    assert_eq!(casm_file_offset(&reader), file_offset);
+}
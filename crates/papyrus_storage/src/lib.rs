@@ -53,8 +53,12 @@
 //!
 //! The storage version is composed of two components: [`STORAGE_VERSION_STATE`] for the state and
 //! [`STORAGE_VERSION_BLOCKS`] for blocks. Each version consists of a major and a minor version. A
-//! higher major version indicates that a re-sync is necessary, while a higher minor version
-//! indicates a change that is migratable.
+//! higher major version indicates that a re-sync is necessary, unless a migration to it exists,
+//! while a higher minor version indicates a change that is migratable.
+//!
+//! When a storage is opened, the migrations that apply to its versions run before the versions are
+//! verified. A migration upgrades the stored data in batches and can be resumed if the node is
+//! stopped in the middle of it.
 //!
 //! When a storage is opened with [`StorageScope::StateOnly`], only the state version must match.
 //! For storage opened with [`StorageScope::FullArchive`], both versions must match the crate's
@@ -76,8 +80,10 @@
 //! [`libmdbx`]: https://docs.rs/libmdbx/latest/libmdbx/
 
 pub mod base_layer;
+pub mod block;
 pub mod body;
 pub mod class;
+pub mod class_dictionaries;
 pub mod compiled_class;
 #[cfg(feature = "document_calls")]
 pub mod document_calls;
@@ -86,9 +92,15 @@ pub mod utils;
 #[doc(hidden)]
 pub mod compression_utils;
 pub mod db;
+mod deduplication;
 pub mod header;
+mod migration;
 pub mod mmap_file;
+pub mod read_replica;
+pub mod revert;
 mod serialization;
+pub mod snap_sync;
+pub mod snapshot;
 pub mod state;
 mod version;
 
@@ -111,6 +123,7 @@ use db::serialization::{Key, NoVersionValueWrapper, ValueSerde, VersionZeroWrapp
 use db::table_types::{CommonPrefix, NoValue, Table, TableType};
 use mmap_file::{
     open_file,
+    open_file_read_only,
     FileHandler,
     LocationInFile,
     MMapFileError,
@@ -128,11 +141,14 @@ use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContract
 use starknet_api::state::{ContractClass, StateNumber, StorageKey, ThinStateDiff};
 use starknet_api::transaction::{Transaction, TransactionHash, TransactionOutput};
 use starknet_types_core::felt::Felt;
+use tokio::sync::watch;
 use tracing::{debug, warn};
 use validator::Validate;
 use version::{StorageVersionError, Version};
 
+use crate::body::indices::{build_indices, EventKeysTableKey, IndicesConfig};
 use crate::body::TransactionIndex;
+use crate::class_dictionaries::{load_class_dictionaries, ClassDictionaries, ClassSegment};
 use crate::db::table_types::SimpleTable;
 use crate::db::{
     open_env,
@@ -143,11 +159,14 @@ use crate::db::{
     DbWriter,
     TableHandle,
     TableIdentifier,
+    TableProvider,
     TransactionKind,
     RO,
     RW,
 };
+use crate::deduplication::ContentDigest;
 use crate::header::StorageBlockHeader;
+use crate::migration::{run_migrations, MigrationProgress};
 use crate::mmap_file::MMapFileStats;
 use crate::state::data::IndexedDeprecatedContractClass;
 pub use crate::utils::update_storage_metrics;
@@ -155,38 +174,16 @@ use crate::version::{VersionStorageReader, VersionStorageWriter};
 
 // For more details on the storage version, see the module documentation.
 /// The current version of the storage state code.
-pub const STORAGE_VERSION_STATE: Version = Version { major: 1, minor: 1 };
+pub const STORAGE_VERSION_STATE: Version = Version { major: 1, minor: 3 };
 /// The current version of the storage blocks code.
-pub const STORAGE_VERSION_BLOCKS: Version = Version { major: 2, minor: 0 };
+pub const STORAGE_VERSION_BLOCKS: Version = Version { major: 2, minor: 1 };
 
 /// Opens a storage and returns a [`StorageReader`] and a [`StorageWriter`].
 pub fn open_storage(
     storage_config: StorageConfig,
 ) -> StorageResult<(StorageReader, StorageWriter)> {
     let (db_reader, mut db_writer) = open_env(&storage_config.db_config)?;
-    let tables = Arc::new(Tables {
-        block_hash_to_number: db_writer.create_simple_table("block_hash_to_number")?,
-        block_signatures: db_writer.create_simple_table("block_signatures")?,
-        casms: db_writer.create_simple_table("casms")?,
-        contract_storage: db_writer.create_common_prefix_table("contract_storage")?,
-        declared_classes: db_writer.create_simple_table("declared_classes")?,
-        declared_classes_block: db_writer.create_simple_table("declared_classes_block")?,
-        deprecated_declared_classes: db_writer
-            .create_simple_table("deprecated_declared_classes")?,
-        deployed_contracts: db_writer.create_simple_table("deployed_contracts")?,
-        events: db_writer.create_common_prefix_table("events")?,
-        headers: db_writer.create_simple_table("headers")?,
-        markers: db_writer.create_simple_table("markers")?,
-        nonces: db_writer.create_common_prefix_table("nonces")?,
-        file_offsets: db_writer.create_simple_table("file_offsets")?,
-        state_diffs: db_writer.create_simple_table("state_diffs")?,
-        transaction_hash_to_idx: db_writer.create_simple_table("transaction_hash_to_idx")?,
-        transaction_metadata: db_writer.create_simple_table("transaction_metadata")?,
-
-        // Version tables
-        starknet_version: db_writer.create_simple_table("starknet_version")?,
-        storage_version: db_writer.create_simple_table("storage_version")?,
-    });
+    let tables = Arc::new(open_tables(&mut db_writer)?);
     let (file_writers, file_readers) = open_storage_files(
         &storage_config.db_config,
         storage_config.mmap_file_config,
@@ -194,19 +191,61 @@ pub fn open_storage(
         &tables.file_offsets,
     )?;
 
+    let commits_sender = Arc::new(watch::Sender::new(()));
     let reader = StorageReader {
         db_reader,
         tables: tables.clone(),
         scope: storage_config.scope,
+        indices: storage_config.indices,
         file_readers,
+        commits_sender: commits_sender.clone(),
+    };
+    let writer = StorageWriter {
+        db_writer,
+        tables,
+        scope: storage_config.scope,
+        indices: storage_config.indices,
+        file_writers,
+        commits_sender,
     };
-    let writer = StorageWriter { db_writer, tables, scope: storage_config.scope, file_writers };
 
-    let writer = set_version_if_needed(reader.clone(), writer)?;
+    let mut writer = set_version_if_needed(reader.clone(), writer)?;
     verify_storage_version(reader.clone())?;
+    load_class_dictionaries(&reader)?;
+    build_indices(&mut writer)?;
     Ok((reader, writer))
 }
 
+// Returns the identifiers of the tables of the storage.
+fn open_tables(provider: &mut impl TableProvider) -> StorageResult<Tables> {
+    Ok(Tables {
+        block_hash_to_number: provider.simple_table("block_hash_to_number")?,
+        block_signatures: provider.simple_table("block_signatures")?,
+        casms: provider.simple_table("casms")?,
+        class_content_locations: provider.simple_table("class_content_locations")?,
+        class_dictionaries: provider.simple_table("class_dictionaries")?,
+        contract_storage: provider.common_prefix_table("contract_storage")?,
+        declared_classes: provider.simple_table("declared_classes")?,
+        declared_classes_block: provider.simple_table("declared_classes_block")?,
+        deprecated_declared_classes: provider.simple_table("deprecated_declared_classes")?,
+        deployed_contracts: provider.simple_table("deployed_contracts")?,
+        event_keys: provider.common_prefix_table("event_keys")?,
+        events: provider.common_prefix_table("events")?,
+        headers: provider.simple_table("headers")?,
+        markers: provider.simple_table("markers")?,
+        migrations: provider.simple_table("migrations")?,
+        nonces: provider.common_prefix_table("nonces")?,
+        file_offsets: provider.simple_table("file_offsets")?,
+        state_diffs: provider.simple_table("state_diffs")?,
+        transaction_hash_to_idx: provider.simple_table("transaction_hash_to_idx")?,
+        transaction_metadata: provider.simple_table("transaction_metadata")?,
+
+        // Version tables
+        starknet_version: provider.simple_table("starknet_version")?,
+        storage_version: provider.simple_table("storage_version")?,
+    })
+}
+
 // In case storage version does not exist, set it to the crate version.
 // Expected to happen once - when the node is launched for the first time.
 // If the storage scope has changed, update accordingly.
@@ -214,7 +253,7 @@ fn set_version_if_needed(
     reader: StorageReader,
     mut writer: StorageWriter,
 ) -> StorageResult<StorageWriter> {
-    let Some(existing_storage_version) = get_storage_version(reader)? else {
+    let Some(existing_storage_version) = get_storage_version(reader.clone())? else {
         // Initialize the storage version.
         writer.begin_rw_txn()?.set_state_version(&STORAGE_VERSION_STATE)?.commit()?;
         // If in full-archive mode, also set the block version.
@@ -250,6 +289,10 @@ fn set_version_if_needed(
             }
         }
     }
+    // Migrate the stored data to the versions that changed its format.
+    run_migrations(&mut writer)?;
+    let existing_storage_version =
+        get_storage_version(reader)?.expect("Storage version should be initialized.");
     // Update the version if it's lower than the crate version.
     let mut wtxn = writer.begin_rw_txn()?;
     match existing_storage_version {
@@ -406,20 +449,32 @@ pub struct StorageReader {
     file_readers: FileHandlers<RO>,
     tables: Arc<Tables>,
     scope: StorageScope,
+    indices: IndicesConfig,
+    commits_sender: Arc<watch::Sender<()>>,
 }
 
 impl StorageReader {
     /// Takes a snapshot of the current state of the storage and returns a [`StorageTxn`] for
     /// reading data from the storage.
+    #[latency_histogram("storage_begin_ro_txn_latency_seconds", true)]
     pub fn begin_ro_txn(&self) -> StorageResult<StorageTxn<'_, RO>> {
         Ok(StorageTxn {
             txn: self.db_reader.begin_ro_txn()?,
             file_handlers: self.file_readers.clone(),
             tables: self.tables.clone(),
             scope: self.scope,
+            indices: self.indices,
+            commits_sender: None,
         })
     }
 
+    /// Returns a receiver that is notified whenever a [`StorageTxn`] of the [`StorageWriter`] is
+    /// committed. Commits of a writer in another process (see
+    /// [`read_replica::open_read_replica`]) aren't notified.
+    pub fn subscribe_to_commits(&self) -> watch::Receiver<()> {
+        self.commits_sender.subscribe()
+    }
+
     /// Returns metadata about the tables in the storage.
     pub fn db_tables_stats(&self) -> StorageResult<DbStats> {
         let mut tables_stats = BTreeMap::new();
@@ -448,17 +503,22 @@ pub struct StorageWriter {
     file_writers: FileHandlers<RW>,
     tables: Arc<Tables>,
     scope: StorageScope,
+    indices: IndicesConfig,
+    commits_sender: Arc<watch::Sender<()>>,
 }
 
 impl StorageWriter {
     /// Takes a snapshot of the current state of the storage and returns a [`StorageTxn`] for
     /// reading and modifying data in the storage.
+    #[latency_histogram("storage_begin_rw_txn_latency_seconds", false)]
     pub fn begin_rw_txn(&mut self) -> StorageResult<StorageTxn<'_, RW>> {
         Ok(StorageTxn {
             txn: self.db_writer.begin_rw_txn()?,
             file_handlers: self.file_writers.clone(),
             tables: self.tables.clone(),
             scope: self.scope,
+            indices: self.indices,
+            commits_sender: Some(self.commits_sender.clone()),
         })
     }
 }
@@ -470,6 +530,9 @@ pub struct StorageTxn<'env, Mode: TransactionKind> {
     file_handlers: FileHandlers<Mode>,
     tables: Arc<Tables>,
     scope: StorageScope,
+    indices: IndicesConfig,
+    // Set only in write transactions.
+    commits_sender: Option<Arc<watch::Sender<()>>>,
 }
 
 impl<'env> StorageTxn<'env, RW> {
@@ -477,7 +540,11 @@ impl<'env> StorageTxn<'env, RW> {
     #[latency_histogram("storage_commit_latency_seconds", false)]
     pub fn commit(self) -> StorageResult<()> {
         self.file_handlers.flush();
-        Ok(self.txn.commit()?)
+        self.txn.commit()?;
+        if let Some(commits_sender) = self.commits_sender {
+            commits_sender.send_replace(());
+        }
+        Ok(())
     }
 }
 
@@ -488,6 +555,7 @@ impl<'env, Mode: TransactionKind> StorageTxn<'env, Mode> {
     ) -> StorageResult<TableHandle<'_, K, V, T>> {
         if self.scope == StorageScope::StateOnly {
             let unused_tables = [
+                self.tables.event_keys.name,
                 self.tables.events.name,
                 self.tables.transaction_hash_to_idx.name,
                 self.tables.transaction_metadata.name,
@@ -513,6 +581,8 @@ struct_field_names! {
         block_hash_to_number: TableIdentifier<BlockHash, NoVersionValueWrapper<BlockNumber>, SimpleTable>,
         block_signatures: TableIdentifier<BlockNumber, VersionZeroWrapper<BlockSignature>, SimpleTable>,
         casms: TableIdentifier<ClassHash, VersionZeroWrapper<LocationInFile>, SimpleTable>,
+        class_content_locations: TableIdentifier<ContentDigest, VersionZeroWrapper<LocationInFile>, SimpleTable>,
+        class_dictionaries: TableIdentifier<(ClassSegment, u32), VersionZeroWrapper<Vec<u8>>, SimpleTable>,
         // Empirically, defining the common prefix as (ContractAddress, StorageKey) is better space-wise than defining the
         // common prefix only as ContractAddress.
         contract_storage: TableIdentifier<((ContractAddress, StorageKey), BlockNumber), NoVersionValueWrapper<Felt>, CommonPrefix>,
@@ -521,9 +591,11 @@ struct_field_names! {
         deprecated_declared_classes: TableIdentifier<ClassHash, VersionZeroWrapper<IndexedDeprecatedContractClass>, SimpleTable>,
         // TODO(dvir): consider use here also the CommonPrefix table type.
         deployed_contracts: TableIdentifier<(ContractAddress, BlockNumber), VersionZeroWrapper<ClassHash>, SimpleTable>,
+        event_keys: TableIdentifier<EventKeysTableKey, NoVersionValueWrapper<NoValue>, CommonPrefix>,
         events: TableIdentifier<(ContractAddress, TransactionIndex), NoVersionValueWrapper<NoValue>, CommonPrefix>,
         headers: TableIdentifier<BlockNumber, VersionZeroWrapper<StorageBlockHeader>, SimpleTable>,
         markers: TableIdentifier<MarkerKind, VersionZeroWrapper<BlockNumber>, SimpleTable>,
+        migrations: TableIdentifier<String, NoVersionValueWrapper<MigrationProgress>, SimpleTable>,
         nonces: TableIdentifier<(ContractAddress, BlockNumber), VersionZeroWrapper<Nonce>, CommonPrefix>,
         file_offsets: TableIdentifier<OffsetKind, NoVersionValueWrapper<usize>, SimpleTable>,
         state_diffs: TableIdentifier<BlockNumber, VersionZeroWrapper<LocationInFile>, SimpleTable>,
@@ -603,6 +675,25 @@ pub enum StorageError {
          {block_number}."
     )]
     BlockSignatureForNonExistingBlock { block_number: BlockNumber, block_signature: BlockSignature },
+    #[error(
+        "Can't pin a snapshot at block {block_number} since the state marker is {state_marker}."
+    )]
+    SnapshotAboveStateMarker { block_number: BlockNumber, state_marker: BlockNumber },
+    #[error("The snapshot at block {block_number} expired after {age:?}.")]
+    SnapshotExpired { block_number: BlockNumber, age: std::time::Duration },
+    #[error("The blocks of the snapshot at block {block_number} were reverted.")]
+    SnapshotReverted { block_number: BlockNumber },
+    #[error(
+        "Can't write a state snapshot to a storage with blocks (header marker {header_marker})."
+    )]
+    StorageNotEmpty { header_marker: BlockNumber },
+    #[error("Can't open a read replica of a storage that a writer didn't initialize.")]
+    UninitializedStorage,
+    #[error(
+        "The transaction hashes index covers the blocks below {index_marker}, but there are block \
+         bodies below {body_marker}."
+    )]
+    TransactionHashesNotIndexed { index_marker: BlockNumber, body_marker: BlockNumber },
 }
 
 /// A type alias that maps to std::result::Result<T, StorageError>.
@@ -617,6 +708,7 @@ pub struct StorageConfig {
     #[validate]
     pub mmap_file_config: MmapFileConfig,
     pub scope: StorageScope,
+    pub indices: IndicesConfig,
 }
 
 impl SerializeConfig for StorageConfig {
@@ -630,6 +722,7 @@ impl SerializeConfig for StorageConfig {
         dumped_config
             .extend(append_sub_config_name(self.mmap_file_config.dump(), "mmap_file_config"));
         dumped_config.extend(append_sub_config_name(self.db_config.dump(), "db_config"));
+        dumped_config.extend(append_sub_config_name(self.indices.dump(), "indices"));
         dumped_config
     }
 }
@@ -648,7 +741,8 @@ pub struct DbStats {
 // Invariants:
 // - CompiledClass <= Class <= State <= Header
 // - Body <= Header
-// - BaseLayerBlock <= Header
+// - BaseLayerBlock <= Header, SentToBaseLayerBlock <= Header
+// - TransactionHashesIndex <= Body, EventKeysIndex <= Body
 // Event is currently unsupported.
 pub(crate) enum MarkerKind {
     Header,
@@ -658,6 +752,9 @@ pub(crate) enum MarkerKind {
     Class,
     CompiledClass,
     BaseLayerBlock,
+    TransactionHashesIndex,
+    EventKeysIndex,
+    SentToBaseLayerBlock,
 }
 
 pub(crate) type MarkersTable<'env> =
@@ -671,6 +768,7 @@ struct FileHandlers<Mode: TransactionKind> {
     deprecated_contract_class: FileHandler<VersionZeroWrapper<DeprecatedContractClass>, Mode>,
     transaction_output: FileHandler<VersionZeroWrapper<TransactionOutput>, Mode>,
     transaction: FileHandler<VersionZeroWrapper<Transaction>, Mode>,
+    class_dictionaries: ClassDictionaries,
 }
 
 impl FileHandlers<RW> {
@@ -680,23 +778,7 @@ impl FileHandlers<RW> {
         self.clone().thin_state_diff.append(thin_state_diff)
     }
 
-    // Appends a contract class to the corresponding file and returns its location.
-    fn append_contract_class(&self, contract_class: &ContractClass) -> LocationInFile {
-        self.clone().contract_class.append(contract_class)
-    }
-
-    // Appends a CASM to the corresponding file and returns its location.
-    fn append_casm(&self, casm: &CasmContractClass) -> LocationInFile {
-        self.clone().casm.append(casm)
-    }
-
-    // Appends a deprecated contract class to the corresponding file and returns its location.
-    fn append_deprecated_contract_class(
-        &self,
-        deprecated_contract_class: &DeprecatedContractClass,
-    ) -> LocationInFile {
-        self.clone().deprecated_contract_class.append(deprecated_contract_class)
-    }
+    // The classes are appended through `deduplication::append_deduplicated`.
 
     // Appends a thin transaction output to the corresponding file and returns its location.
     fn append_transaction_output(&self, transaction_output: &TransactionOutput) -> LocationInFile {
@@ -749,16 +831,20 @@ impl<Mode: TransactionKind> FileHandlers<Mode> {
         &self,
         location: LocationInFile,
     ) -> StorageResult<ContractClass> {
-        self.contract_class.get(location)?.ok_or(StorageError::DBInconsistency {
-            msg: format!("ContractClass at location {:?} not found.", location),
-        })
+        self.class_dictionaries.scope(|| self.contract_class.get(location))?.ok_or(
+            StorageError::DBInconsistency {
+                msg: format!("ContractClass at location {:?} not found.", location),
+            },
+        )
     }
 
     // Returns the CASM at the given location or an error in case it doesn't exist.
     fn get_casm_unchecked(&self, location: LocationInFile) -> StorageResult<CasmContractClass> {
-        self.casm.get(location)?.ok_or(StorageError::DBInconsistency {
-            msg: format!("CasmContractClass at location {:?} not found.", location),
-        })
+        self.class_dictionaries.scope(|| self.casm.get(location))?.ok_or(
+            StorageError::DBInconsistency {
+                msg: format!("CasmContractClass at location {:?} not found.", location),
+            },
+        )
     }
 
     // Returns the deprecated contract class at the given location or an error in case it doesn't
@@ -767,9 +853,11 @@ impl<Mode: TransactionKind> FileHandlers<Mode> {
         &self,
         location: LocationInFile,
     ) -> StorageResult<DeprecatedContractClass> {
-        self.deprecated_contract_class.get(location)?.ok_or(StorageError::DBInconsistency {
-            msg: format!("DeprecatedContractClass at location {:?} not found.", location),
-        })
+        self.class_dictionaries.scope(|| self.deprecated_contract_class.get(location))?.ok_or(
+            StorageError::DBInconsistency {
+                msg: format!("DeprecatedContractClass at location {:?} not found.", location),
+            },
+        )
     }
 
     // Returns the transaction output at the given location or an error in case it doesn't
@@ -842,6 +930,9 @@ fn open_storage_files(
     let (transaction_writer, transaction_reader) =
         open_file(mmap_file_config, db_config.path().join("transaction.dat"), transaction_offset)?;
 
+    // The reader and the writer share the dictionaries, so the classes that the writer compresses
+    // with a new dictionary are decompressed by the reader.
+    let class_dictionaries = ClassDictionaries::default();
     Ok((
         FileHandlers {
             thin_state_diff: thin_state_diff_writer,
@@ -850,6 +941,7 @@ fn open_storage_files(
             deprecated_contract_class: deprecated_contract_class_writer,
             transaction_output: transaction_output_writer,
             transaction: transaction_writer,
+            class_dictionaries: class_dictionaries.clone(),
         },
         FileHandlers {
             thin_state_diff: thin_state_diff_reader,
@@ -858,10 +950,60 @@ fn open_storage_files(
             deprecated_contract_class: deprecated_contract_class_reader,
             transaction_output: transaction_output_reader,
             transaction: transaction_reader,
+            class_dictionaries,
         },
     ))
 }
 
+// Opens the files of the storage read-only, at the offsets committed by the writer.
+fn open_storage_files_read_only(
+    db_config: &DbConfig,
+    mmap_file_config: MmapFileConfig,
+    db_reader: &DbReader,
+    file_offsets_table: &TableIdentifier<OffsetKind, NoVersionValueWrapper<usize>, SimpleTable>,
+) -> StorageResult<FileHandlers<RO>> {
+    let db_transaction = db_reader.begin_ro_txn()?;
+    let table = db_transaction.open_table(file_offsets_table)?;
+    let offset = |offset_kind: OffsetKind| -> StorageResult<usize> {
+        Ok(table.get(&db_transaction, &offset_kind)?.unwrap_or_default())
+    };
+    let path = |file_name: &str| db_config.path().join(file_name);
+
+    Ok(FileHandlers {
+        thin_state_diff: open_file_read_only(
+            mmap_file_config.clone(),
+            path("thin_state_diff.dat"),
+            offset(OffsetKind::ThinStateDiff)?,
+        )?,
+        contract_class: open_file_read_only(
+            mmap_file_config.clone(),
+            path("contract_class.dat"),
+            offset(OffsetKind::ContractClass)?,
+        )?,
+        casm: open_file_read_only(
+            mmap_file_config.clone(),
+            path("casm.dat"),
+            offset(OffsetKind::Casm)?,
+        )?,
+        deprecated_contract_class: open_file_read_only(
+            mmap_file_config.clone(),
+            path("deprecated_contract_class.dat"),
+            offset(OffsetKind::DeprecatedContractClass)?,
+        )?,
+        transaction_output: open_file_read_only(
+            mmap_file_config.clone(),
+            path("transaction_output.dat"),
+            offset(OffsetKind::TransactionOutput)?,
+        )?,
+        transaction: open_file_read_only(
+            mmap_file_config,
+            path("transaction.dat"),
+            offset(OffsetKind::Transaction)?,
+        )?,
+        class_dictionaries: ClassDictionaries::default(),
+    })
+}
+
 /// Represents a kind of mmap file.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord)]
 pub enum OffsetKind {
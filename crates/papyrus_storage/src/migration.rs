@@ -0,0 +1,141 @@
+//! Upgrades of the stored data between storage versions.
+//!
+//! Instead of requiring a re-sync whenever the format of the stored data changes, a [`Migration`]
+//! upgrades a versioned component of the storage (the state or the blocks) to the version that
+//! introduced the change, e.g. by re-encoding values, moving data between tables or building a new
+//! index. The migrations run when the storage is opened, in the order of [`MIGRATIONS`].
+//!
+//! A migration applies to a storage whose version of the component is lower than the target
+//! version of the migration, and either has the same major version, or is of the previous major
+//! version if the migration targets the first minor version of a new major version.
+//!
+//! Migrations process the data in batches. Each batch is committed in its own transaction, together
+//! with the progress of the migration (in the `migrations` table), so an interrupted migration
+//! resumes from its last committed batch the next time the storage is opened. The version of the
+//! component is updated in the transaction of the last batch.
+//!
+//! Values that can be converted from their older encoding on read don't need a migration, see
+//! [`VersionWrapper`](crate::db::serialization::VersionWrapper).
+
+#[cfg(test)]
+#[path = "migration_test.rs"]
+mod migration_test;
+
+use metrics::gauge;
+use tracing::info;
+
+use crate::body::indices::IndexMarkersMigration;
+use crate::class_dictionaries::TrainClassDictionaries;
+use crate::db::table_types::Table;
+use crate::db::RW;
+use crate::deduplication::IndexClassContents;
+use crate::version::{Version, VersionStorageReader, VersionStorageWriter, VersionedComponent};
+use crate::{StorageResult, StorageTxn, StorageWriter};
+
+/// The migrations of the storage, in the order in which they are applied.
+pub(crate) static MIGRATIONS: &[&dyn Migration] =
+    &[&IndexClassContents, &TrainClassDictionaries, &IndexMarkersMigration];
+
+// The maximal number of entries that a migration processes in a single transaction.
+const MIGRATION_BATCH_SIZE: usize = 1000;
+
+/// An upgrade of the stored data of a versioned component of the storage.
+pub(crate) trait Migration: Sync {
+    /// A unique name of the migration, under which its progress is stored.
+    fn name(&self) -> &'static str;
+
+    /// The component of the storage that the migration upgrades.
+    fn component(&self) -> VersionedComponent;
+
+    /// The version of the component after the migration.
+    fn target_version(&self) -> Version;
+
+    /// Migrates up to `batch_size` entries, starting from the checkpoint returned by the previous
+    /// batch (None for the first batch). Returns the number of migrated entries and the checkpoint
+    /// to continue from, or None if the migration is done.
+    fn migrate_batch(
+        &self,
+        txn: &StorageTxn<'_, RW>,
+        checkpoint: Option<Vec<u8>>,
+        batch_size: usize,
+    ) -> StorageResult<(u64, Option<Vec<u8>>)>;
+}
+
+/// The progress of a migration that didn't finish yet.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct MigrationProgress {
+    /// The number of entries that were migrated so far.
+    pub migrated_entries: u64,
+    /// The checkpoint to continue the migration from.
+    pub checkpoint: Option<Vec<u8>>,
+}
+
+/// Runs all the migrations that apply to the storage.
+pub(crate) fn run_migrations(writer: &mut StorageWriter) -> StorageResult<()> {
+    for migration in MIGRATIONS {
+        run_migration(writer, *migration, MIGRATION_BATCH_SIZE)?;
+    }
+    Ok(())
+}
+
+fn run_migration(
+    writer: &mut StorageWriter,
+    migration: &dyn Migration,
+    batch_size: usize,
+) -> StorageResult<()> {
+    while migrate_batch(writer, migration, batch_size)? {}
+    Ok(())
+}
+
+// Migrates a single batch and commits it. Returns true if there are more batches to migrate.
+fn migrate_batch(
+    writer: &mut StorageWriter,
+    migration: &dyn Migration,
+    batch_size: usize,
+) -> StorageResult<bool> {
+    let txn = writer.begin_rw_txn()?;
+    let target_version = migration.target_version();
+    let Some(version) = txn.get_component_version(migration.component())? else {
+        return Ok(false);
+    };
+    if !upgrades(&version, &target_version) {
+        return Ok(false);
+    }
+
+    let migrations_table = txn.open_table(&txn.tables.migrations)?;
+    let name = migration.name().to_string();
+    let mut progress = migrations_table.get(&txn.txn, &name)?.unwrap_or_default();
+    if progress == MigrationProgress::default() {
+        info!(
+            "Starting the migration {name} of the {:?} from version {version} to {target_version}.",
+            migration.component()
+        );
+    }
+
+    let (migrated_entries, checkpoint) =
+        migration.migrate_batch(&txn, progress.checkpoint.take(), batch_size)?;
+    progress.migrated_entries += migrated_entries;
+    gauge!(
+        "storage_migration_migrated_entries",
+        progress.migrated_entries as f64,
+        "migration" => migration.name()
+    );
+
+    let Some(checkpoint) = checkpoint else {
+        migrations_table.delete(&txn.txn, &name)?;
+        txn.set_migrated_version(migration.component(), &target_version)?.commit()?;
+        info!("Finished the migration {name} after {} entries.", progress.migrated_entries);
+        return Ok(false);
+    };
+    progress.checkpoint = Some(checkpoint);
+    migrations_table.upsert(&txn.txn, &name, &progress)?;
+    txn.commit()?;
+    info!("Migration {name} in progress: {} entries were migrated.", progress.migrated_entries);
+    Ok(true)
+}
+
+// Returns true if a migration to the target version applies to a storage of the given version.
+fn upgrades(version: &Version, target_version: &Version) -> bool {
+    (version.major == target_version.major && version.minor < target_version.minor)
+        || (version.major + 1 == target_version.major && target_version.minor == 0)
+}
@@ -0,0 +1,104 @@
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use papyrus_test_utils::read_json_file;
+use pretty_assertions::assert_eq;
+use starknet_api::core::ClassHash;
+use starknet_api::felt;
+use test_case::test_case;
+
+use crate::compiled_class::CasmStorageWriter;
+use crate::db::table_types::{DbCursorTrait, Table};
+use crate::deduplication::{IndexClassContents, DEDUPLICATION_STATE_VERSION};
+use crate::migration::{migrate_batch, run_migration, upgrades, Migration, MigrationProgress};
+use crate::test_utils::get_test_storage;
+use crate::version::{Version, VersionStorageReader, VERSION_STATE_KEY};
+use crate::{StorageReader, StorageWriter};
+
+#[test_case(Version { major: 1, minor: 1 }, Version { major: 1, minor: 2 }, true; "lower minor")]
+#[test_case(Version { major: 1, minor: 2 }, Version { major: 1, minor: 2 }, false; "same version")]
+#[test_case(Version { major: 1, minor: 3 }, Version { major: 1, minor: 2 }, false; "higher minor")]
+#[test_case(Version { major: 1, minor: 5 }, Version { major: 2, minor: 0 }, true; "next major")]
+#[test_case(Version { major: 1, minor: 5 }, Version { major: 2, minor: 1 }, false; "next major with minor")]
+#[test_case(Version { major: 0, minor: 5 }, Version { major: 2, minor: 0 }, false; "skipped major")]
+fn migration_applies(version: Version, target_version: Version, expected: bool) {
+    assert_eq!(upgrades(&version, &target_version), expected);
+}
+
+// Clears the class contents index and sets the state version to the one before it was introduced.
+fn set_storage_before_deduplication(writer: &mut StorageWriter) {
+    let txn = writer.begin_rw_txn().unwrap();
+    let class_content_locations_table =
+        txn.open_table(&txn.tables.class_content_locations).unwrap();
+    let mut cursor = class_content_locations_table.cursor(&txn.txn).unwrap();
+    let mut digests = Vec::new();
+    while let Some((digest, _location)) = cursor.next().unwrap() {
+        digests.push(digest);
+    }
+    drop(cursor);
+    for digest in digests {
+        class_content_locations_table.delete(&txn.txn, &digest).unwrap();
+    }
+    let version_table = txn.open_table(&txn.tables.storage_version).unwrap();
+    version_table
+        .upsert(
+            &txn.txn,
+            &VERSION_STATE_KEY.to_string(),
+            &Version {
+                major: DEDUPLICATION_STATE_VERSION.major,
+                minor: DEDUPLICATION_STATE_VERSION.minor - 1,
+            },
+        )
+        .unwrap();
+    txn.commit().unwrap();
+}
+
+fn migration_progress(reader: &StorageReader) -> Option<MigrationProgress> {
+    let txn = reader.begin_ro_txn().unwrap();
+    let migrations_table = txn.open_table(&txn.tables.migrations).unwrap();
+    migrations_table.get(&txn.txn, &IndexClassContents.name().to_string()).unwrap()
+}
+
+fn indexed_contents(reader: &StorageReader) -> usize {
+    let txn = reader.begin_ro_txn().unwrap();
+    let class_content_locations_table =
+        txn.open_table(&txn.tables.class_content_locations).unwrap();
+    let mut cursor = class_content_locations_table.cursor(&txn.txn).unwrap();
+    let mut n_contents = 0;
+    while cursor.next().unwrap().is_some() {
+        n_contents += 1;
+    }
+    n_contents
+}
+
+#[test]
+fn resume_interrupted_migration() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    let json_casm: CasmContractClass =
+        serde_json::from_value(read_json_file("compiled_class.json")).unwrap();
+    let casms = [
+        (ClassHash(felt!("0x1")), CasmContractClass::default()),
+        (ClassHash(felt!("0x2")), json_casm),
+        (ClassHash(felt!("0x3")), CasmContractClass::default()),
+    ];
+    for (class_hash, casm) in &casms {
+        writer.begin_rw_txn().unwrap().append_casm(class_hash, casm).unwrap().commit().unwrap();
+    }
+    set_storage_before_deduplication(&mut writer);
+
+    // Migrate a single batch, as if the node was stopped after it.
+    assert!(migrate_batch(&mut writer, &IndexClassContents, 2).unwrap());
+    let progress = migration_progress(&reader).unwrap();
+    assert_eq!(progress.migrated_entries, 2);
+    assert!(progress.checkpoint.is_some());
+    let state_version = reader.begin_ro_txn().unwrap().get_state_version().unwrap().unwrap();
+    assert_eq!(state_version.minor, DEDUPLICATION_STATE_VERSION.minor - 1);
+
+    // Resume the migration.
+    run_migration(&mut writer, &IndexClassContents, 2).unwrap();
+    assert_eq!(migration_progress(&reader), None);
+    assert_eq!(indexed_contents(&reader), 2);
+    let state_version = reader.begin_ro_txn().unwrap().get_state_version().unwrap().unwrap();
+    assert_eq!(state_version, DEDUPLICATION_STATE_VERSION);
+
+    // The migration doesn't run again.
+    assert!(!migrate_batch(&mut writer, &IndexClassContents, 2).unwrap());
+}
@@ -15,7 +15,7 @@ use std::path::PathBuf;
 use std::result;
 use std::sync::{Arc, Mutex};
 
-use memmap2::{MmapMut, MmapOptions};
+use memmap2::{MmapOptions, MmapRaw};
 use papyrus_config::dumping::{ser_param, SerializeConfig};
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 #[cfg(test)]
@@ -118,12 +118,12 @@ pub(crate) trait Reader<V: ValueSerde> {
 }
 
 /// Represents a location in the file.
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub struct LocationInFile {
     /// Offset in the file.
-    offset: usize,
+    pub(crate) offset: usize,
     /// Length of the object.
-    len: usize,
+    pub(crate) len: usize,
 }
 
 impl LocationInFile {
@@ -139,7 +139,9 @@ struct MMapFile<V: ValueSerde> {
     config: MmapFileConfig,
     file: File,
     size: usize,
-    mmap: MmapMut,
+    // Mapped read-write for the writer's files, and read-only for the files of a read replica.
+    // Only the handlers of files that were mapped read-write write through the mapping.
+    mmap: MmapRaw,
     offset: usize,
     should_flush: bool,
     _value_type: PhantomData<V>,
@@ -172,7 +174,7 @@ pub(crate) fn open_file<V: ValueSerde>(
 ) -> MmapFileResult<(FileHandler<V, RW>, FileHandler<V, RO>)> {
     let file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
     let size = file.metadata()?.len();
-    let mmap = unsafe { MmapOptions::new().len(config.max_size).map_mut(&file)? };
+    let mmap = MmapRaw::from(unsafe { MmapOptions::new().len(config.max_size).map_mut(&file)? });
     let mmap_ptr = mmap.as_ptr();
     let mmap_file = MMapFile {
         config,
@@ -198,6 +200,36 @@ pub(crate) fn open_file<V: ValueSerde>(
     Ok((write_file_handler, read_file_handler))
 }
 
+/// Open an existing memory mapped file read-only. The file can be appended to by a writer in
+/// another process; the objects it appended are readable once their location is committed.
+#[instrument(level = "debug", err)]
+pub(crate) fn open_file_read_only<V: ValueSerde>(
+    config: MmapFileConfig,
+    path: PathBuf,
+    offset: usize,
+) -> MmapFileResult<FileHandler<V, RO>> {
+    let file = OpenOptions::new().read(true).open(path)?;
+    let size = file.metadata()?.len();
+    // The mapping covers the maximal size of the file, so it covers what the writer appends.
+    let mmap = MmapRaw::from(unsafe { MmapOptions::new().len(config.max_size).map(&file)? });
+    let mmap_ptr = mmap.as_ptr();
+    let mmap_file = MMapFile {
+        config,
+        file,
+        mmap,
+        size: size.try_into().expect("size should fit in usize"),
+        offset,
+        should_flush: false,
+        _value_type: PhantomData {},
+    };
+
+    Ok(FileHandler {
+        memory_ptr: mmap_ptr,
+        mmap_file: Arc::new(Mutex::new(mmap_file)),
+        _mode: PhantomData,
+    })
+}
+
 /// A wrapper around `MMapFile` that provides both write and read interfaces.
 #[derive(Clone, Debug)]
 pub(crate) struct FileHandler<V: ValueSerde, Mode: TransactionKind> {
@@ -220,20 +252,26 @@ impl<V: ValueSerde> FileHandler<V, RW> {
             mmap_file.grow();
         }
     }
-}
 
-impl<V: ValueSerde + Debug> Writer<V> for FileHandler<V, RW> {
-    fn append(&mut self, val: &V::Value) -> LocationInFile {
-        trace!("Inserting object: {:?}", val);
-        let serialized = V::serialize(val).expect("Should be able to serialize");
+    /// Inserts an already serialized object to the file, returns the [`LocationInFile`] of the
+    /// object.
+    pub(crate) fn append_serialized(&mut self, serialized: &[u8]) -> LocationInFile {
         let len = serialized.len();
         let offset;
         {
             let mut mmap_file = self.mmap_file.lock().expect("Lock should not be poisoned");
             offset = mmap_file.offset;
             trace!("Inserting object at offset: {}", offset);
-            let mmap_slice = &mut mmap_file.mmap[offset..];
-            mmap_slice[..len].copy_from_slice(&serialized);
+            assert!(offset + len <= mmap_file.mmap.len(), "The object exceeds the mmap.");
+            // Safety: write handlers are only created by `open_file`, which maps the file
+            // read-write, and the object is within the mapping.
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    serialized.as_ptr(),
+                    mmap_file.mmap.as_mut_ptr().add(offset),
+                    len,
+                );
+            }
             mmap_file
                 .mmap
                 .flush_async_range(offset, len)
@@ -245,6 +283,14 @@ impl<V: ValueSerde + Debug> Writer<V> for FileHandler<V, RW> {
         self.grow_file_if_needed(location.next_offset());
         location
     }
+}
+
+impl<V: ValueSerde + Debug> Writer<V> for FileHandler<V, RW> {
+    fn append(&mut self, val: &V::Value) -> LocationInFile {
+        trace!("Inserting object: {:?}", val);
+        let serialized = V::serialize(val).expect("Should be able to serialize");
+        self.append_serialized(&serialized)
+    }
 
     fn flush(&self) {
         let mut mmap_file = self.mmap_file.lock().expect("Lock should not be poisoned");
@@ -258,12 +304,7 @@ impl<V: ValueSerde, Mode: TransactionKind> Reader<V> for FileHandler<V, Mode> {
     /// Returns an object from the file.
     fn get(&self, location: LocationInFile) -> MmapFileResult<Option<V::Value>> {
         trace!("Reading object at location: {:?}", location);
-        let mut bytes = unsafe {
-            std::slice::from_raw_parts(
-                self.memory_ptr.offset(location.offset.try_into()?),
-                location.len,
-            )
-        };
+        let mut bytes = self.get_serialized(location)?;
         trace!("Deserializing object: {:?}", bytes);
         Ok(V::deserialize(&mut bytes))
     }
@@ -273,9 +314,9 @@ impl<V: ValueSerde, Mode: TransactionKind> Reader<V> for FileHandler<V, Mode> {
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord)]
 pub struct MMapFileStats {
     // The current size of the file.
-    size: usize,
+    pub(crate) size: usize,
     // The amount of data that has been written to the file.
-    offset: usize,
+    pub(crate) offset: usize,
 }
 
 impl<V: ValueSerde, Mode: TransactionKind> FileHandler<V, Mode> {
@@ -283,6 +324,16 @@ impl<V: ValueSerde, Mode: TransactionKind> FileHandler<V, Mode> {
         let mmap_file = self.mmap_file.lock().expect("Lock should not be poisoned");
         MMapFileStats { size: mmap_file.size, offset: mmap_file.offset }
     }
+
+    /// Returns the serialized object at the given location, without deserializing it.
+    pub(crate) fn get_serialized(&self, location: LocationInFile) -> MmapFileResult<&[u8]> {
+        Ok(unsafe {
+            std::slice::from_raw_parts(
+                self.memory_ptr.offset(location.offset.try_into()?),
+                location.len,
+            )
+        })
+    }
 }
 
 // This serialization writes the offset as 6 bytes and the length as 4 bytes.
@@ -0,0 +1,177 @@
+//! Interface for serving reads from a read-only replica of a storage.
+//!
+//! A read replica is a second process that opens the storage of a node read-only, e.g. to serve
+//! heavy RPC traffic without competing with the node that writes the storage. The replica never
+//! writes: it doesn't create tables, set the storage version or run migrations, so the storage
+//! must be initialized by its writer first, and the versions must match. Each read transaction of
+//! the replica sees the blocks that the writer committed when it began, so the replica follows the
+//! writer's markers without syncing itself.
+//!
+//! Since the writer keeps appending blocks, the data that the replica serves may be behind the
+//! chain. A [`StorageWatermark`], read in the same transaction as the data, tells up to which block
+//! the data is, so that stale reads are detectable.
+//!
+//! # Example
+//!
+//! ```
+//! use papyrus_storage::header::{HeaderStorageReader, HeaderStorageWriter};
+//! use papyrus_storage::open_storage;
+//! use papyrus_storage::read_replica::open_read_replica;
+//! # use papyrus_storage::{db::DbConfig, StorageConfig};
+//! # use starknet_api::core::ChainId;
+//! use starknet_api::block::{BlockHeader, BlockNumber};
+//!
+//! # let dir_handle = tempfile::tempdir().unwrap();
+//! # let dir = dir_handle.path().to_path_buf();
+//! # let db_config = DbConfig {
+//! #     path_prefix: dir,
+//! #     chain_id: ChainId::Mainnet,
+//! #     enforce_file_exists: false,
+//! #     min_size: 1 << 20,    // 1MB
+//! #     max_size: 1 << 35,    // 32GB
+//! #     growth_step: 1 << 26, // 64MB
+//! # };
+//! # let storage_config = StorageConfig{db_config, ..Default::default()};
+//! // The writer initializes the storage (usually in another process).
+//! let (_, mut writer) = open_storage(storage_config.clone())?;
+//! writer.begin_rw_txn()?.append_header(BlockNumber(0), &BlockHeader::default())?.commit()?;
+//! # drop(writer);
+//!
+//! let replica = open_read_replica(storage_config)?;
+//! let txn = replica.begin_ro_txn()?;
+//! let header = txn.get_block_header(BlockNumber(0))?;
+//! assert_eq!(header, Some(BlockHeader::default()));
+//! assert_eq!(txn.get_watermark()?.header_marker, BlockNumber(1));
+//! # Ok::<(), papyrus_storage::StorageError>(())
+//! ```
+
+#[cfg(test)]
+#[path = "read_replica_test.rs"]
+mod read_replica_test;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use starknet_api::block::{BlockNumber, BlockTimestamp};
+use tokio::sync::watch;
+
+use crate::body::BodyStorageReader;
+use crate::db::{open_env_read_only, DbReader, TransactionKind};
+use crate::header::HeaderStorageReader;
+use crate::state::StateStorageReader;
+use crate::{
+    get_storage_version,
+    open_storage_files_read_only,
+    open_tables,
+    verify_storage_version,
+    StorageConfig,
+    StorageError,
+    StorageReader,
+    StorageResult,
+    StorageTxn,
+    Tables,
+};
+
+/// Opens a storage that a writer initialized, possibly in another process, read-only and returns
+/// a [`StorageReader`] to it.
+///
+/// # Errors
+/// Returns [`StorageError::UninitializedStorage`] if the writer didn't initialize the storage, and
+/// [`StorageError::StorageVersionInconsistency`] if the storage version doesn't match the crate
+/// version, e.g. before the writer migrated the storage.
+pub fn open_read_replica(storage_config: StorageConfig) -> StorageResult<StorageReader> {
+    let mut db_reader = open_env_read_only(&storage_config.db_config)?;
+    let tables = Arc::new(open_tables(&mut db_reader)?);
+    read_replica(storage_config, db_reader, tables)
+}
+
+// Returns a replica reader of the storage of `db_reader`. Since MDBX opens a storage once per
+// process, the tests build the replicas of the storages that they open for writing with it.
+pub(crate) fn read_replica(
+    storage_config: StorageConfig,
+    db_reader: DbReader,
+    tables: Arc<Tables>,
+) -> StorageResult<StorageReader> {
+    let file_readers = open_storage_files_read_only(
+        &storage_config.db_config,
+        storage_config.mmap_file_config,
+        &db_reader,
+        &tables.file_offsets,
+    )?;
+    let reader = StorageReader {
+        db_reader,
+        tables,
+        scope: storage_config.scope,
+        indices: storage_config.indices,
+        file_readers,
+        // Nothing is committed through this process.
+        commits_sender: Arc::new(watch::Sender::new(())),
+    };
+
+    if get_storage_version(reader.clone())?.is_none() {
+        return Err(StorageError::UninitializedStorage);
+    }
+    verify_storage_version(reader.clone())?;
+    // The writer may train new class dictionaries after the replica opened.
+    reader
+        .file_readers
+        .class_dictionaries
+        .follow(reader.db_reader.clone(), reader.tables.clone())?;
+    Ok(reader)
+}
+
+/// The progress of the storage as seen by a read transaction. The data read in the transaction is
+/// up to the markers of its watermark.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StorageWatermark {
+    /// The first block whose header isn't in the storage.
+    pub header_marker: BlockNumber,
+    /// The first block whose body isn't in the storage.
+    pub body_marker: BlockNumber,
+    /// The first block whose state diff isn't in the storage.
+    pub state_marker: BlockNumber,
+    /// The timestamp of the last block whose header is in the storage.
+    pub last_block_timestamp: Option<BlockTimestamp>,
+}
+
+impl StorageWatermark {
+    /// Returns true if the state after `block_number` was applied is in the storage.
+    pub fn has_state_after(&self, block_number: BlockNumber) -> bool {
+        block_number < self.state_marker
+    }
+
+    /// Returns how long before `now`, in seconds since the epoch, the last block in the storage
+    /// was created, or None if the storage has no blocks.
+    pub fn lag(&self, now: u64) -> Option<Duration> {
+        self.last_block_timestamp
+            .map(|BlockTimestamp(timestamp)| Duration::from_secs(now.saturating_sub(timestamp)))
+    }
+}
+
+impl<'env, Mode: TransactionKind> StorageTxn<'env, Mode> {
+    /// Returns the watermark of the data that this transaction reads.
+    pub fn get_watermark(&self) -> StorageResult<StorageWatermark> {
+        let header_marker = self.get_header_marker()?;
+        let last_block_timestamp = match header_marker.prev() {
+            Some(last_block_number) => {
+                self.get_block_header(last_block_number)?.map(|header| header.timestamp)
+            }
+            None => None,
+        };
+        Ok(StorageWatermark {
+            header_marker,
+            body_marker: self.get_body_marker()?,
+            state_marker: self.get_state_marker()?,
+            last_block_timestamp,
+        })
+    }
+}
+
+impl StorageReader {
+    /// Returns the watermark of the storage now. To check the freshness of data, use the watermark
+    /// of the transaction that read it ([`StorageTxn::get_watermark`]).
+    pub fn watermark(&self) -> StorageResult<StorageWatermark> {
+        self.begin_ro_txn()?.get_watermark()
+    }
+}
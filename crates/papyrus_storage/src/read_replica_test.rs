@@ -0,0 +1,86 @@
+use assert_matches::assert_matches;
+use indexmap::indexmap;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockHeader, BlockNumber, BlockTimestamp};
+use starknet_api::core::{ContractAddress, Nonce, PatriciaKey};
+use starknet_api::state::{StateNumber, ThinStateDiff};
+use starknet_api::{contract_address, felt, patricia_key};
+
+use crate::db::DbError;
+use crate::header::HeaderStorageWriter;
+use crate::read_replica::{open_read_replica, StorageWatermark};
+use crate::state::{StateStorageReader, StateStorageWriter};
+use crate::test_utils::get_test_config;
+use crate::{open_storage, StorageError};
+
+#[test]
+fn open_read_replica_of_missing_storage() {
+    let (config, _temp_dir) = get_test_config(None);
+    assert_matches!(
+        open_read_replica(config),
+        Err(StorageError::InnerError(DbError::FileDoesNotExist(_)))
+    );
+}
+
+#[test]
+fn read_replica_reads_the_writer_data() {
+    let (config, _temp_dir) = get_test_config(None);
+    let address = contract_address!("0x1");
+    let header = BlockHeader { timestamp: BlockTimestamp(1000), ..Default::default() };
+    let state_diff =
+        ThinStateDiff { nonces: indexmap! { address => Nonce(felt!(1_u8)) }, ..Default::default() };
+    {
+        let (_, mut writer) = open_storage(config.clone()).unwrap();
+        writer
+            .begin_rw_txn()
+            .unwrap()
+            .append_header(BlockNumber(0), &header)
+            .unwrap()
+            .append_state_diff(BlockNumber(0), state_diff.clone())
+            .unwrap()
+            .commit()
+            .unwrap();
+    }
+
+    let replica = open_read_replica(config).unwrap();
+    let txn = replica.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_state_diff(BlockNumber(0)).unwrap(), Some(state_diff));
+    let nonce = txn
+        .get_state_reader()
+        .unwrap()
+        .get_nonce_at(StateNumber(BlockNumber(1)), &address)
+        .unwrap();
+    assert_eq!(nonce, Some(Nonce(felt!(1_u8))));
+    assert_eq!(
+        txn.get_watermark().unwrap(),
+        StorageWatermark {
+            header_marker: BlockNumber(1),
+            body_marker: BlockNumber(0),
+            state_marker: BlockNumber(1),
+            last_block_timestamp: Some(BlockTimestamp(1000)),
+        }
+    );
+}
+
+#[test]
+fn watermark_freshness() {
+    let watermark = StorageWatermark {
+        header_marker: BlockNumber(5),
+        body_marker: BlockNumber(5),
+        state_marker: BlockNumber(4),
+        last_block_timestamp: Some(BlockTimestamp(1000)),
+    };
+    assert!(watermark.has_state_after(BlockNumber(3)));
+    assert!(!watermark.has_state_after(BlockNumber(4)));
+    assert_eq!(watermark.lag(1012), Some(std::time::Duration::from_secs(12)));
+    // A clock behind the block timestamp doesn't underflow.
+    assert_eq!(watermark.lag(990), Some(std::time::Duration::ZERO));
+
+    let empty_watermark = StorageWatermark {
+        header_marker: BlockNumber(0),
+        body_marker: BlockNumber(0),
+        state_marker: BlockNumber(0),
+        last_block_timestamp: None,
+    };
+    assert_eq!(empty_watermark.lag(1012), None);
+}
@@ -0,0 +1,122 @@
+//! Interface for reverting blocks from the storage, e.g. on chain reorgs.
+//!
+//! Reverting a block removes its header, signature, body and state diff (including the classes
+//! declared in it) and moves the markers back, in a single transaction. Blocks can only be reverted
+//! from the tip of the chain, so reverting back to a given height reverts the blocks one by one,
+//! starting from the last one.
+//!
+//! The returned [`RevertedBlock`]s describe the removed data so that other components (e.g. the
+//! mempool and consensus) can re-adjust.
+//!
+//! # Example
+//!
+//! ```
+//! # use papyrus_storage::{db::DbConfig, StorageConfig};
+//! # use starknet_api::core::ChainId;
+//! use papyrus_storage::header::{HeaderStorageReader, HeaderStorageWriter};
+//! use papyrus_storage::open_storage;
+//! use papyrus_storage::revert::RevertStorageWriter;
+//! use starknet_api::block::{BlockHeader, BlockNumber};
+//!
+//! # let dir_handle = tempfile::tempdir().unwrap();
+//! # let dir = dir_handle.path().to_path_buf();
+//! # let db_config = DbConfig {
+//! #     path_prefix: dir,
+//! #     chain_id: ChainId::Mainnet,
+//! #     enforce_file_exists: false,
+//! #     min_size: 1 << 20,    // 1MB
+//! #     max_size: 1 << 35,    // 32GB
+//! #     growth_step: 1 << 26, // 64MB
+//! # };
+//! # let storage_config = StorageConfig{db_config, ..Default::default()};
+//! let (reader, mut writer) = open_storage(storage_config)?;
+//! writer.begin_rw_txn()?.append_header(BlockNumber(0), &BlockHeader::default())?.commit()?;
+//!
+//! let (txn, reverted_blocks) = writer.begin_rw_txn()?.revert_state(BlockNumber(0))?;
+//! txn.commit()?;
+//! assert_eq!(reverted_blocks.len(), 1);
+//! assert_eq!(reader.begin_ro_txn()?.get_header_marker()?, BlockNumber(0));
+//! # Ok::<(), papyrus_storage::StorageError>(())
+//! ```
+
+#[cfg(test)]
+#[path = "revert_test.rs"]
+mod revert_test;
+
+use starknet_api::block::{BlockHeader, BlockNumber};
+use starknet_api::state::ThinStateDiff;
+use starknet_api::transaction::TransactionHash;
+use tracing::debug;
+
+use crate::base_layer::BaseLayerStorageWriter;
+use crate::body::BodyStorageWriter;
+use crate::db::RW;
+use crate::header::{HeaderStorageReader, HeaderStorageWriter};
+use crate::state::StateStorageWriter;
+use crate::{StorageResult, StorageTxn};
+
+/// The data that was removed from the storage when a block was reverted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RevertedBlock {
+    /// The number of the reverted block.
+    pub block_number: BlockNumber,
+    /// The header of the reverted block.
+    pub header: BlockHeader,
+    /// The hashes of the transactions of the reverted block. Empty if the body wasn't stored.
+    pub transaction_hashes: Vec<TransactionHash>,
+    /// The state diff of the reverted block, if it was stored.
+    pub state_diff: Option<ThinStateDiff>,
+}
+
+/// Interface for reverting whole blocks from the storage.
+pub trait RevertStorageWriter
+where
+    Self: Sized,
+{
+    /// Reverts all the data of the last block in the storage and returns the removed data.
+    /// If `block_number` isn't the last block, returns without an action.
+    fn revert_block(
+        self,
+        block_number: BlockNumber,
+    ) -> StorageResult<(Self, Option<RevertedBlock>)>;
+
+    /// Reverts all the blocks from the last block in the storage down to `block_number`
+    /// (inclusive), so that `block_number` becomes the header marker. Returns the removed data,
+    /// starting from the last block.
+    fn revert_state(self, block_number: BlockNumber) -> StorageResult<(Self, Vec<RevertedBlock>)>;
+}
+
+impl<'env> RevertStorageWriter for StorageTxn<'env, RW> {
+    fn revert_block(
+        self,
+        block_number: BlockNumber,
+    ) -> StorageResult<(Self, Option<RevertedBlock>)> {
+        let txn = self.try_revert_base_layer_marker(block_number)?;
+        let (txn, reverted_header, _) = txn.revert_header(block_number)?;
+        let Some(header) = reverted_header else {
+            return Ok((txn, None));
+        };
+        let (txn, reverted_body) = txn.revert_body(block_number)?;
+        let (txn, reverted_state_diff) = txn.revert_state_diff(block_number)?;
+        debug!("Reverted block {block_number} with hash {}.", header.block_hash);
+
+        let transaction_hashes = reverted_body
+            .map(|(_transactions, _transaction_outputs, transaction_hashes)| transaction_hashes)
+            .unwrap_or_default();
+        let state_diff = reverted_state_diff.map(|(thin_state_diff, ..)| thin_state_diff);
+        Ok((txn, Some(RevertedBlock { block_number, header, transaction_hashes, state_diff })))
+    }
+
+    fn revert_state(self, block_number: BlockNumber) -> StorageResult<(Self, Vec<RevertedBlock>)> {
+        let mut txn = self;
+        let mut reverted_blocks = Vec::new();
+        let mut last_block = txn.get_header_marker()?.prev();
+        while let Some(current_block) = last_block.filter(|current| *current >= block_number) {
+            let (new_txn, reverted_block) = txn.revert_block(current_block)?;
+            txn = new_txn;
+            reverted_blocks.extend(reverted_block);
+            last_block = current_block.prev();
+        }
+        Ok((txn, reverted_blocks))
+    }
+}
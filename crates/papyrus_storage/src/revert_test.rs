@@ -0,0 +1,87 @@
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockHash, BlockHeader, BlockNumber};
+use starknet_api::felt;
+use starknet_api::state::ThinStateDiff;
+
+use crate::body::{BodyStorageReader, BodyStorageWriter};
+use crate::header::{HeaderStorageReader, HeaderStorageWriter};
+use crate::revert::RevertStorageWriter;
+use crate::state::{StateStorageReader, StateStorageWriter};
+use crate::test_utils::get_test_storage;
+use crate::StorageWriter;
+
+// Appends the blocks [0, n_blocks), each with a header, an empty body and an empty state diff.
+fn append_blocks(writer: &mut StorageWriter, n_blocks: u64) {
+    for i in 0..n_blocks {
+        let block_number = BlockNumber(i);
+        let header = BlockHeader {
+            block_hash: BlockHash(felt!(i + 1)),
+            parent_hash: BlockHash(felt!(i)),
+            block_number,
+            ..Default::default()
+        };
+        writer
+            .begin_rw_txn()
+            .unwrap()
+            .append_header(block_number, &header)
+            .unwrap()
+            .append_body(block_number, Default::default())
+            .unwrap()
+            .append_state_diff(block_number, ThinStateDiff::default())
+            .unwrap()
+            .commit()
+            .unwrap();
+    }
+}
+
+#[test]
+fn revert_last_block() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    append_blocks(&mut writer, 2);
+
+    // Reverting a block which is not the last one does nothing.
+    let (txn, reverted_block) =
+        writer.begin_rw_txn().unwrap().revert_block(BlockNumber(0)).unwrap();
+    txn.commit().unwrap();
+    assert!(reverted_block.is_none());
+
+    let (txn, reverted_block) =
+        writer.begin_rw_txn().unwrap().revert_block(BlockNumber(1)).unwrap();
+    txn.commit().unwrap();
+    let reverted_block = reverted_block.unwrap();
+    assert_eq!(reverted_block.block_number, BlockNumber(1));
+    assert_eq!(reverted_block.header.block_hash, BlockHash(felt!(2_u8)));
+    assert_eq!(reverted_block.state_diff, Some(ThinStateDiff::default()));
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_header_marker().unwrap(), BlockNumber(1));
+    assert_eq!(txn.get_body_marker().unwrap(), BlockNumber(1));
+    assert_eq!(txn.get_state_marker().unwrap(), BlockNumber(1));
+}
+
+#[test]
+fn revert_state_to_height() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    append_blocks(&mut writer, 5);
+
+    let (txn, reverted_blocks) =
+        writer.begin_rw_txn().unwrap().revert_state(BlockNumber(2)).unwrap();
+    txn.commit().unwrap();
+    assert_eq!(
+        reverted_blocks
+            .iter()
+            .map(|reverted_block| reverted_block.block_number)
+            .collect::<Vec<_>>(),
+        vec![BlockNumber(4), BlockNumber(3), BlockNumber(2)]
+    );
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_header_marker().unwrap(), BlockNumber(2));
+    assert_eq!(txn.get_body_marker().unwrap(), BlockNumber(2));
+    assert_eq!(txn.get_state_marker().unwrap(), BlockNumber(2));
+    assert!(txn.get_block_header(BlockNumber(1)).unwrap().is_some());
+
+    // Reverting above the header marker does nothing.
+    let (_, reverted_blocks) = writer.begin_rw_txn().unwrap().revert_state(BlockNumber(3)).unwrap();
+    assert!(reverted_blocks.is_empty());
+}
@@ -125,6 +125,7 @@ use tracing::warn;
 
 use crate::body::events::EventIndex;
 use crate::body::TransactionIndex;
+use crate::class_dictionaries::{compress_class_segment, ClassSegment};
 use crate::compression_utils::{
     compress,
     decompress,
@@ -135,6 +136,7 @@ use crate::compression_utils::{
 use crate::db::serialization::{StorageSerde, StorageSerdeError};
 use crate::db::table_types::NoValue;
 use crate::header::StorageBlockHeader;
+use crate::migration::MigrationProgress;
 use crate::mmap_file::LocationInFile;
 #[cfg(test)]
 use crate::serialization::serializers_test::{create_storage_serde_test, StorageSerdeTest};
@@ -177,6 +179,11 @@ auto_storage_serde! {
     pub struct Calldata(pub Arc<Vec<Felt>>);
     pub struct CompiledClassHash(pub StarkHash);
     pub struct ClassHash(pub StarkHash);
+    pub enum ClassSegment {
+        SierraProgram = 0,
+        DeprecatedProgram = 1,
+        Casm = 2,
+    }
     pub struct ContractAddressSalt(pub StarkHash);
     pub enum ContractClassAbiEntry {
         Event(EventAbiEntry) = 0,
@@ -314,6 +321,9 @@ auto_storage_serde! {
         Class = 4,
         CompiledClass = 5,
         BaseLayerBlock = 6,
+        TransactionHashesIndex = 7,
+        EventKeysIndex = 8,
+        SentToBaseLayerBlock = 9,
     }
     pub struct MessageToL1 {
         pub to_address: EthAddress,
@@ -324,6 +334,10 @@ auto_storage_serde! {
         pub from_address: EthAddress,
         pub payload: L1ToL2Payload,
     }
+    pub struct MigrationProgress {
+        pub migrated_entries: u64,
+        pub checkpoint: Option<Vec<u8>>,
+    }
     pub enum NestedIntList {
         Leaf(usize) = 0,
         Node(Vec<NestedIntList>) = 1,
@@ -469,11 +483,14 @@ auto_storage_serde! {
 
     (BlockNumber, TransactionOffsetInBlock);
     (BlockHash, ClassHash);
+    (ClassSegment, u32);
     (ContractAddress, BlockHash);
     (ContractAddress, BlockNumber);
+    (ContractAddress, EventKey);
     (ContractAddress, Nonce);
     (ContractAddress, StorageKey);
     (ContractAddress, TransactionIndex);
+    ((ContractAddress, EventKey), TransactionIndex);
     ((ContractAddress, StorageKey), BlockNumber);
     (usize, Vec<Hint>);
     (usize, Vec<String>);
@@ -938,7 +955,10 @@ impl StorageSerde for TransactionOffsetInBlock {
 ////////////////////////////////////////////////////////////////////////
 impl StorageSerde for ContractClass {
     fn serialize_into(&self, res: &mut impl std::io::Write) -> Result<(), StorageSerdeError> {
-        serialize_and_compress(&self.sierra_program)?.serialize_into(res)?;
+        let mut sierra_program = Vec::new();
+        self.sierra_program.serialize_into(&mut sierra_program)?;
+        compress_class_segment(ClassSegment::SierraProgram, &sierra_program)?
+            .serialize_into(res)?;
         self.entry_points_by_type.serialize_into(res)?;
         serialize_and_compress(&self.abi)?.serialize_into(res)?;
         Ok(())
@@ -972,7 +992,7 @@ impl StorageSerde for DeprecatedContractClass {
                 to_compress.len()
             );
         }
-        let compressed = compress(to_compress.as_slice())?;
+        let compressed = compress_class_segment(ClassSegment::DeprecatedProgram, &to_compress)?;
         compressed.serialize_into(res)?;
         self.entry_points_by_type.serialize_into(res)?;
         Ok(())
@@ -1033,7 +1053,7 @@ impl StorageSerde for CasmContractClass {
                 to_compress.len()
             );
         }
-        let compressed = compress(to_compress.as_slice())?;
+        let compressed = compress_class_segment(ClassSegment::Casm, &to_compress)?;
         compressed.serialize_into(res)?;
 
         Ok(())
@@ -0,0 +1,255 @@
+//! Interface for writing the state of a block to an empty storage, without the blocks before it.
+//!
+//! A node that snap syncs downloads the state after a trusted block instead of replaying all the
+//! blocks up to it. The state is written as the state diff of that block from the empty state, and
+//! the block becomes the first one in the storage: the markers are moved to it, so the syncs of
+//! the following blocks continue from there. The bodies and events of the blocks up to it, and of
+//! the block itself, are never stored.
+//!
+//! A big state is written in chunks at the block with [`write_state_snapshot_chunk`], which doesn't
+//! move the markers, and then finalized with [`write_state_snapshot`].
+//!
+//! [`write_state_snapshot_chunk`]: SnapSyncStorageWriter::write_state_snapshot_chunk
+//! [`write_state_snapshot`]: SnapSyncStorageWriter::write_state_snapshot
+//!
+//! # Example
+//!
+//! ```
+//! # use papyrus_storage::{db::DbConfig, StorageConfig};
+//! # use starknet_api::core::ChainId;
+//! use papyrus_storage::header::HeaderStorageReader;
+//! use papyrus_storage::open_storage;
+//! use papyrus_storage::snap_sync::SnapSyncStorageWriter;
+//! use papyrus_storage::state::StateStorageReader;
+//! use starknet_api::block::{BlockHeader, BlockNumber, BlockSignature};
+//! use starknet_api::state::ThinStateDiff;
+//!
+//! # let dir_handle = tempfile::tempdir().unwrap();
+//! # let dir = dir_handle.path().to_path_buf();
+//! # let db_config = DbConfig {
+//! #     path_prefix: dir,
+//! #     chain_id: ChainId::Mainnet,
+//! #     enforce_file_exists: false,
+//! #     min_size: 1 << 20,    // 1MB
+//! #     max_size: 1 << 35,    // 32GB
+//! #     growth_step: 1 << 26, // 64MB
+//! # };
+//! # let storage_config = StorageConfig{db_config, ..Default::default()};
+//! let (reader, mut writer) = open_storage(storage_config)?;
+//! let header = BlockHeader { block_number: BlockNumber(5), ..Default::default() };
+//! writer
+//!     .begin_rw_txn()?
+//!     .write_state_snapshot(
+//!         &header,
+//!         &BlockSignature::default(),
+//!         ThinStateDiff::default(),
+//!         &[],
+//!         &[],
+//!     )?
+//!     .commit()?;
+//!
+//! let txn = reader.begin_ro_txn()?;
+//! assert_eq!(txn.get_header_marker()?, BlockNumber(6));
+//! assert_eq!(txn.get_state_marker()?, BlockNumber(6));
+//! # Ok::<(), papyrus_storage::StorageError>(())
+//! ```
+
+#[cfg(test)]
+#[path = "snap_sync_test.rs"]
+mod snap_sync_test;
+
+use starknet_api::block::{BlockHeader, BlockNumber, BlockSignature};
+use starknet_api::core::{ClassHash, Nonce};
+use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
+use starknet_api::state::{ContractClass, ThinStateDiff};
+
+use crate::class::{write_classes, write_deprecated_classes, ClassStorageWriter};
+use crate::db::table_types::{DbCursor, DbCursorTrait, Table};
+use crate::db::{DbTransaction, RW};
+use crate::header::{HeaderStorageReader, HeaderStorageWriter};
+use crate::state::StateStorageWriter;
+use crate::{MarkerKind, StorageError, StorageResult, StorageTxn};
+
+/// Interface for writing the state of a block to an empty storage.
+pub trait SnapSyncStorageWriter
+where
+    Self: Sized,
+{
+    /// Writes the header and signature of a block with the state after it, given as a state diff
+    /// from the empty state, and the classes declared up to it. The compiled classes of the
+    /// declared classes are synced from this block on, like those of any other block.
+    ///
+    /// Returns [`StorageError::StorageNotEmpty`] if the storage already holds a block.
+    fn write_state_snapshot(
+        self,
+        header: &BlockHeader,
+        signature: &BlockSignature,
+        state: ThinStateDiff,
+        classes: &[(ClassHash, &ContractClass)],
+        deprecated_classes: &[(ClassHash, &DeprecatedContractClass)],
+    ) -> StorageResult<Self>;
+
+    /// Writes a part of the state after a block, given as a state diff from the empty state, and
+    /// some of the classes declared up to it, without moving the markers. The declarations of the
+    /// classes are written when the snapshot is finalized with [`write_state_snapshot`], which
+    /// should get them in its state diff.
+    ///
+    /// Writing a chunk again overwrites it, so a snap sync that was interrupted can start over
+    /// from the first chunk of the same block.
+    ///
+    /// Returns [`StorageError::StorageNotEmpty`] if the storage already holds a block.
+    ///
+    /// [`write_state_snapshot`]: SnapSyncStorageWriter::write_state_snapshot
+    fn write_state_snapshot_chunk(
+        self,
+        block_number: BlockNumber,
+        state: &ThinStateDiff,
+        classes: &[(ClassHash, &ContractClass)],
+        deprecated_classes: &[(ClassHash, &DeprecatedContractClass)],
+    ) -> StorageResult<Self>;
+
+    /// Deletes the state and the classes that were written by [`write_state_snapshot_chunk`], such
+    /// as the chunks of a snap sync that failed before the snapshot was finalized.
+    ///
+    /// Returns [`StorageError::StorageNotEmpty`] if the storage already holds a block.
+    ///
+    /// [`write_state_snapshot_chunk`]: SnapSyncStorageWriter::write_state_snapshot_chunk
+    fn discard_state_snapshot_chunks(self) -> StorageResult<Self>;
+}
+
+impl<'env> SnapSyncStorageWriter for StorageTxn<'env, RW> {
+    fn write_state_snapshot(
+        self,
+        header: &BlockHeader,
+        signature: &BlockSignature,
+        state: ThinStateDiff,
+        classes: &[(ClassHash, &ContractClass)],
+        deprecated_classes: &[(ClassHash, &DeprecatedContractClass)],
+    ) -> StorageResult<Self> {
+        let header_marker = self.get_header_marker()?;
+        if header_marker.0 != 0 {
+            return Err(StorageError::StorageNotEmpty { header_marker });
+        }
+
+        let block_number = header.block_number;
+        let next_block_number = block_number.unchecked_next();
+        let markers_table = self.open_table(&self.tables.markers)?;
+        // The header, state and classes of the block are written below, which advances their
+        // markers past it.
+        for marker_kind in
+            [MarkerKind::Header, MarkerKind::State, MarkerKind::Class, MarkerKind::CompiledClass]
+        {
+            markers_table.upsert(&self.txn, &marker_kind, &block_number)?;
+        }
+        for marker_kind in [
+            MarkerKind::Body,
+            MarkerKind::Event,
+            MarkerKind::TransactionHashesIndex,
+            MarkerKind::EventKeysIndex,
+        ] {
+            markers_table.upsert(&self.txn, &marker_kind, &next_block_number)?;
+        }
+
+        self.append_header(block_number, header)?
+            .append_block_signature(block_number, signature)?
+            .append_state_diff(block_number, state)?
+            .append_classes(block_number, classes, deprecated_classes)
+    }
+}
+
+    fn write_state_snapshot_chunk(
+        self,
+        block_number: BlockNumber,
+        state: &ThinStateDiff,
+        classes: &[(ClassHash, &ContractClass)],
+        deprecated_classes: &[(ClassHash, &DeprecatedContractClass)],
+    ) -> StorageResult<Self> {
+        let header_marker = self.get_header_marker()?;
+        if header_marker.0 != 0 {
+            return Err(StorageError::StorageNotEmpty { header_marker });
+        }
+
+        let deployed_contracts_table = self.open_table(&self.tables.deployed_contracts)?;
+        let nonces_table = self.open_table(&self.tables.nonces)?;
+        let storage_table = self.open_table(&self.tables.contract_storage)?;
+        let declared_classes_table = self.open_table(&self.tables.declared_classes)?;
+        let deprecated_declared_classes_table =
+            self.open_table(&self.tables.deprecated_declared_classes)?;
+        let file_offset_table = self.txn.open_table(&self.tables.file_offsets)?;
+        let class_content_locations_table =
+            self.open_table(&self.tables.class_content_locations)?;
+
+        for (address, class_hash) in &state.deployed_contracts {
+            deployed_contracts_table.upsert(&self.txn, &(*address, block_number), class_hash)?;
+            if !state.nonces.contains_key(address) {
+                nonces_table.upsert(&self.txn, &(*address, block_number), &Nonce::default())?;
+            }
+        }
+        for (address, nonce) in &state.nonces {
+            nonces_table.upsert(&self.txn, &(*address, block_number), nonce)?;
+        }
+        for (address, storage_entries) in &state.storage_diffs {
+            for (key, value) in storage_entries {
+                storage_table.upsert(&self.txn, &((*address, *key), block_number), value)?;
+            }
+        }
+
+        // The classes of a previous attempt are already stored, and their content is not
+        // rewritten.
+        let mut new_classes = Vec::with_capacity(classes.len());
+        for (class_hash, class) in classes {
+            if declared_classes_table.get(&self.txn, class_hash)?.is_none() {
+                new_classes.push((*class_hash, *class));
+            }
+        }
+        write_classes(
+            &new_classes,
+            &self.txn,
+            &declared_classes_table,
+            &self.file_handlers,
+            &file_offset_table,
+            &class_content_locations_table,
+        )?;
+        write_deprecated_classes(
+            deprecated_classes,
+            &self.txn,
+            block_number,
+            &deprecated_declared_classes_table,
+            &self.file_handlers,
+            &file_offset_table,
+            &class_content_locations_table,
+        )?;
+
+        Ok(self)
+    }
+
+    fn discard_state_snapshot_chunks(self) -> StorageResult<Self> {
+        let header_marker = self.get_header_marker()?;
+        if header_marker.0 != 0 {
+            return Err(StorageError::StorageNotEmpty { header_marker });
+        }
+
+        // Only snapshot chunks are stored before the first block, so the tables are cleared.
+        clear_table(&self.txn, &self.open_table(&self.tables.deployed_contracts)?)?;
+        clear_table(&self.txn, &self.open_table(&self.tables.nonces)?)?;
+        clear_table(&self.txn, &self.open_table(&self.tables.contract_storage)?)?;
+        clear_table(&self.txn, &self.open_table(&self.tables.declared_classes)?)?;
+        clear_table(&self.txn, &self.open_table(&self.tables.deprecated_declared_classes)?)?;
+        Ok(self)
+    }
+}
+
+// Deletes all the entries of a table.
+fn clear_table<'env, T>(txn: &'env DbTransaction<'env, RW>, table: &'env T) -> StorageResult<()>
+where
+    T: Table<'env>,
+    DbCursor<'env, RW, T::Key, T::Value, T::TableVariant>:
+        DbCursorTrait<Key = T::Key, Value = T::Value>,
+{
+    loop {
+        let Some((key, _)) = table.cursor(txn)?.next()? else {
+            return Ok(());
+        };
+        table.delete(txn, &key)?;
+    }
+}
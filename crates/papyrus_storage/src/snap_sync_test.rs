@@ -0,0 +1,261 @@
+use assert_matches::assert_matches;
+use indexmap::indexmap;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockHash, BlockHeader, BlockNumber, BlockSignature};
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce, PatriciaKey};
+use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
+use starknet_api::state::{ContractClass, StateNumber, StorageKey, ThinStateDiff};
+use starknet_api::{felt, patricia_key};
+
+use crate::body::BodyStorageReader;
+use crate::class::ClassStorageReader;
+use crate::compiled_class::CasmStorageReader;
+use crate::header::{HeaderStorageReader, HeaderStorageWriter};
+use crate::snap_sync::SnapSyncStorageWriter;
+use crate::state::StateStorageReader;
+use crate::test_utils::get_test_storage;
+use crate::StorageError;
+
+#[test]
+fn write_state_snapshot() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    let block_number = BlockNumber(5);
+    let header =
+        BlockHeader { block_hash: BlockHash(felt!("0x5")), block_number, ..Default::default() };
+    let contract_address = ContractAddress(patricia_key!("0x10"));
+    let key = StorageKey(patricia_key!("0x1"));
+    let class_hash = ClassHash(felt!("0x100"));
+    let deprecated_class_hash = ClassHash(felt!("0x200"));
+    let state = ThinStateDiff {
+        deployed_contracts: indexmap!(contract_address => class_hash),
+        storage_diffs: indexmap!(contract_address => indexmap!(key => felt!("0x7"))),
+        declared_classes: indexmap!(class_hash => CompiledClassHash(felt!("0x101"))),
+        deprecated_declared_classes: vec![deprecated_class_hash],
+        nonces: indexmap!(contract_address => Nonce(felt!("0x3"))),
+        ..Default::default()
+    };
+    let class = ContractClass::default();
+    let deprecated_class = DeprecatedContractClass::default();
+
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .write_state_snapshot(
+            &header,
+            &BlockSignature::default(),
+            state.clone(),
+            &[(class_hash, &class)],
+            &[(deprecated_class_hash, &deprecated_class)],
+        )
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let txn = reader.begin_ro_txn().unwrap();
+    let next_block_number = BlockNumber(6);
+    assert_eq!(txn.get_header_marker().unwrap(), next_block_number);
+    assert_eq!(txn.get_body_marker().unwrap(), next_block_number);
+    assert_eq!(txn.get_state_marker().unwrap(), next_block_number);
+    assert_eq!(txn.get_class_marker().unwrap(), next_block_number);
+    // The compiled classes of the declared classes are synced for the block.
+    assert_eq!(txn.get_compiled_class_marker().unwrap(), block_number);
+    assert_eq!(txn.get_block_header(block_number).unwrap().unwrap().block_hash, header.block_hash);
+    assert!(txn.get_block_signature(block_number).unwrap().is_some());
+    assert_eq!(txn.get_state_diff(block_number).unwrap(), Some(state));
+
+    let state_number = StateNumber(next_block_number);
+    let state_reader = txn.get_state_reader().unwrap();
+    assert_eq!(
+        state_reader.get_class_hash_at(state_number, &contract_address).unwrap(),
+        Some(class_hash)
+    );
+    assert_eq!(
+        state_reader.get_storage_at(state_number, &contract_address, &key).unwrap(),
+        felt!("0x7")
+    );
+    assert!(state_reader.get_class_definition_at(state_number, &class_hash).unwrap().is_some());
+    assert!(
+        state_reader
+            .get_deprecated_class_definition_at(state_number, &deprecated_class_hash)
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[test]
+fn write_state_snapshot_to_non_empty_storage() {
+    let ((_reader, mut writer), _temp_dir) = get_test_storage();
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(BlockNumber(0), &BlockHeader::default())
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let header = BlockHeader { block_number: BlockNumber(5), ..Default::default() };
+    let result = writer.begin_rw_txn().unwrap().write_state_snapshot(
+        &header,
+        &BlockSignature::default(),
+        ThinStateDiff::default(),
+        &[],
+        &[],
+    );
+    assert_matches!(
+        result.err(),
+        Some(StorageError::StorageNotEmpty { header_marker }) if header_marker == BlockNumber(1)
+    );
+}
+
+#[test]
+fn write_state_snapshot_in_chunks() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    let block_number = BlockNumber(5);
+    let header = BlockHeader { block_number, ..Default::default() };
+    let first_address = ContractAddress(patricia_key!("0x10"));
+    let second_address = ContractAddress(patricia_key!("0x20"));
+    let key = StorageKey(patricia_key!("0x1"));
+    let class_hash = ClassHash(felt!("0x100"));
+    let compiled_class_hash = CompiledClassHash(felt!("0x101"));
+    let first_chunk = ThinStateDiff {
+        deployed_contracts: indexmap!(first_address => class_hash),
+        storage_diffs: indexmap!(first_address => indexmap!(key => felt!("0x7"))),
+        ..Default::default()
+    };
+    let second_chunk = ThinStateDiff {
+        deployed_contracts: indexmap!(second_address => class_hash),
+        nonces: indexmap!(second_address => Nonce(felt!("0x3"))),
+        ..Default::default()
+    };
+    let class = ContractClass::default();
+
+    // The first chunk is written twice, as in a snap sync that started over.
+    for chunk in [&first_chunk, &first_chunk, &second_chunk] {
+        writer
+            .begin_rw_txn()
+            .unwrap()
+            .write_state_snapshot_chunk(block_number, chunk, &[], &[])
+            .unwrap()
+            .commit()
+            .unwrap();
+    }
+    // The classes are written in their own chunk, and again when the snap sync starts over.
+    for _ in 0..2 {
+        writer
+            .begin_rw_txn()
+            .unwrap()
+            .write_state_snapshot_chunk(
+                block_number,
+                &ThinStateDiff::default(),
+                &[(class_hash, &class)],
+                &[],
+            )
+            .unwrap()
+            .commit()
+            .unwrap();
+    }
+    // The chunks don't move the markers.
+    assert_eq!(reader.begin_ro_txn().unwrap().get_state_marker().unwrap(), BlockNumber(0));
+
+    let declarations = ThinStateDiff {
+        declared_classes: indexmap!(class_hash => compiled_class_hash),
+        ..Default::default()
+    };
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .write_state_snapshot(&header, &BlockSignature::default(), declarations, &[], &[])
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_state_marker().unwrap(), BlockNumber(6));
+    let state_number = StateNumber(BlockNumber(6));
+    let state_reader = txn.get_state_reader().unwrap();
+    assert_eq!(
+        state_reader.get_storage_at(state_number, &first_address, &key).unwrap(),
+        felt!("0x7")
+    );
+    assert_eq!(
+        state_reader.get_nonce_at(state_number, &first_address).unwrap(),
+        Some(Nonce::default())
+    );
+    assert_eq!(
+        state_reader.get_nonce_at(state_number, &second_address).unwrap(),
+        Some(Nonce(felt!("0x3")))
+    );
+    assert_eq!(
+        state_reader.get_class_hash_at(state_number, &second_address).unwrap(),
+        Some(class_hash)
+    );
+    assert_eq!(
+        state_reader.get_class_definition_at(state_number, &class_hash).unwrap(),
+        Some(class)
+    );
+}
+
+#[test]
+fn write_state_snapshot_chunk_to_non_empty_storage() {
+    let ((_reader, mut writer), _temp_dir) = get_test_storage();
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(BlockNumber(0), &BlockHeader::default())
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let result = writer.begin_rw_txn().unwrap().write_state_snapshot_chunk(
+        BlockNumber(5),
+        &ThinStateDiff::default(),
+        &[],
+        &[],
+    );
+    assert_matches!(
+        result.err(),
+        Some(StorageError::StorageNotEmpty { header_marker }) if header_marker == BlockNumber(1)
+    );
+}
+
+#[test]
+fn discard_state_snapshot_chunks() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    let block_number = BlockNumber(5);
+    let contract_address = ContractAddress(patricia_key!("0x10"));
+    let key = StorageKey(patricia_key!("0x1"));
+    let class_hash = ClassHash(felt!("0x100"));
+    let deprecated_class_hash = ClassHash(felt!("0x200"));
+    let chunk = ThinStateDiff {
+        deployed_contracts: indexmap!(contract_address => class_hash),
+        storage_diffs: indexmap!(contract_address => indexmap!(key => felt!("0x7"))),
+        nonces: indexmap!(contract_address => Nonce(felt!("0x3"))),
+        ..Default::default()
+    };
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .write_state_snapshot_chunk(
+            block_number,
+            &chunk,
+            &[(class_hash, &ContractClass::default())],
+            &[(deprecated_class_hash, &DeprecatedContractClass::default())],
+        )
+        .unwrap()
+        .discard_state_snapshot_chunks()
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert!(txn.get_class(&class_hash).unwrap().is_none());
+    assert!(txn.get_deprecated_class(&deprecated_class_hash).unwrap().is_none());
+    let state_number = StateNumber(BlockNumber(6));
+    let state_reader = txn.get_state_reader().unwrap();
+    assert!(state_reader.get_class_hash_at(state_number, &contract_address).unwrap().is_none());
+    assert!(state_reader.get_nonce_at(state_number, &contract_address).unwrap().is_none());
+    assert_eq!(
+        state_reader.get_storage_at(state_number, &contract_address, &key).unwrap(),
+        felt!("0x0")
+    );
+}
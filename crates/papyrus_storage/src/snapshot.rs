@@ -0,0 +1,186 @@
+//! Interface for serving many concurrent readers from a single pinned state.
+//!
+//! A [`StorageSnapshot`] pins the state at a given block marker (a [`StateNumber`]). Since the
+//! state tables are indexed by block number, a pinned state can be served by short-lived read
+//! transactions that read at the pinned state number. This way the snapshot doesn't hold a
+//! database read transaction open, which would block the reuse of freed pages and make the
+//! database grow while the writer keeps appending blocks.
+//!
+//! Guardrails:
+//! - A snapshot expires after a configurable age. Reading from an expired snapshot fails with
+//!   [`StorageError::SnapshotExpired`] and the caller should pin a new one.
+//! - If the blocks of the pinned state are reverted (e.g. on a reorg), reading from the snapshot
+//!   fails with [`StorageError::SnapshotReverted`].
+//!
+//! Snapshots are cheap to clone and can be shared between threads.
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use papyrus_storage::open_storage;
+//! # use papyrus_storage::{db::DbConfig, StorageConfig};
+//! # use starknet_api::core::ChainId;
+//! use starknet_api::block::BlockNumber;
+//! use starknet_api::core::ContractAddress;
+//!
+//! # let dir_handle = tempfile::tempdir().unwrap();
+//! # let dir = dir_handle.path().to_path_buf();
+//! # let db_config = DbConfig {
+//! #     path_prefix: dir,
+//! #     chain_id: ChainId::Mainnet,
+//! #     enforce_file_exists: false,
+//! #     min_size: 1 << 20,    // 1MB
+//! #     max_size: 1 << 35,    // 32GB
+//! #     growth_step: 1 << 26, // 64MB
+//! # };
+//! # let storage_config = StorageConfig{db_config, ..Default::default()};
+//! let (reader, _writer) = open_storage(storage_config)?;
+//! let snapshot = reader.pin_snapshot(BlockNumber(0), Duration::from_secs(60))?;
+//! let nonce = snapshot.get_nonce_at(&ContractAddress::default())?;
+//! assert_eq!(nonce, None);
+//! # Ok::<(), papyrus_storage::StorageError>(())
+//! ```
+
+#[cfg(test)]
+#[path = "snapshot_test.rs"]
+mod snapshot_test;
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use starknet_api::block::{BlockHash, BlockNumber};
+use starknet_api::core::{ClassHash, ContractAddress, Nonce};
+use starknet_api::state::{StateNumber, StorageKey};
+use starknet_types_core::felt::Felt;
+
+use crate::db::RO;
+use crate::header::HeaderStorageReader;
+use crate::state::{StateReader, StateStorageReader};
+use crate::{StorageError, StorageReader, StorageResult, StorageTxn};
+
+/// A state pinned at a block marker that can be read concurrently without blocking the writer.
+#[derive(Clone)]
+pub struct StorageSnapshot {
+    reader: StorageReader,
+    inner: Arc<SnapshotInner>,
+}
+
+struct SnapshotInner {
+    state_number: StateNumber,
+    // The hash of the last block of the pinned state, used to detect reverts.
+    last_block_hash: Option<BlockHash>,
+    pinned_at: Instant,
+    max_age: Duration,
+}
+
+impl StorageReader {
+    /// Pins the state at the given block marker, i.e. the state after all the blocks before
+    /// `block_number` were applied. The snapshot can't be read from after `max_age` has passed.
+    ///
+    /// # Errors
+    /// Returns [`StorageError::SnapshotAboveStateMarker`] if the state of the blocks before
+    /// `block_number` isn't in the storage yet.
+    pub fn pin_snapshot(
+        &self,
+        block_number: BlockNumber,
+        max_age: Duration,
+    ) -> StorageResult<StorageSnapshot> {
+        let txn = self.begin_ro_txn()?;
+        let state_marker = txn.get_state_marker()?;
+        if block_number > state_marker {
+            return Err(StorageError::SnapshotAboveStateMarker { block_number, state_marker });
+        }
+        let last_block_hash = last_block_hash(&txn, block_number)?;
+        Ok(StorageSnapshot {
+            reader: self.clone(),
+            inner: Arc::new(SnapshotInner {
+                state_number: StateNumber(block_number),
+                last_block_hash,
+                pinned_at: Instant::now(),
+                max_age,
+            }),
+        })
+    }
+}
+
+impl StorageSnapshot {
+    /// Returns the pinned state number.
+    pub fn state_number(&self) -> StateNumber {
+        self.inner.state_number
+    }
+
+    /// Returns true if the snapshot is older than its maximal age.
+    pub fn is_expired(&self) -> bool {
+        self.inner.pinned_at.elapsed() > self.inner.max_age
+    }
+
+    /// Opens a short-lived read transaction and calls `f` with a state reader and the pinned state
+    /// number. The transaction is closed when `f` returns.
+    ///
+    /// # Errors
+    /// Returns [`StorageError::SnapshotExpired`] if the snapshot is expired and
+    /// [`StorageError::SnapshotReverted`] if the blocks of the pinned state were reverted.
+    pub fn with_state_reader<T>(
+        &self,
+        f: impl FnOnce(&StateReader<'_, RO>, StateNumber) -> StorageResult<T>,
+    ) -> StorageResult<T> {
+        let txn = self.reader.begin_ro_txn()?;
+        self.verify_valid(&txn)?;
+        let state_reader = txn.get_state_reader()?;
+        f(&state_reader, self.inner.state_number)
+    }
+
+    /// Returns the class hash of the contract at the pinned state.
+    pub fn get_class_hash_at(&self, address: &ContractAddress) -> StorageResult<Option<ClassHash>> {
+        self.with_state_reader(|state_reader, state_number| {
+            state_reader.get_class_hash_at(state_number, address)
+        })
+    }
+
+    /// Returns the nonce of the contract at the pinned state.
+    pub fn get_nonce_at(&self, address: &ContractAddress) -> StorageResult<Option<Nonce>> {
+        self.with_state_reader(|state_reader, state_number| {
+            state_reader.get_nonce_at(state_number, address)
+        })
+    }
+
+    /// Returns the storage value of the contract at the pinned state.
+    pub fn get_storage_at(
+        &self,
+        address: &ContractAddress,
+        key: &StorageKey,
+    ) -> StorageResult<Felt> {
+        self.with_state_reader(|state_reader, state_number| {
+            state_reader.get_storage_at(state_number, address, key)
+        })
+    }
+
+    fn verify_valid(&self, txn: &StorageTxn<'_, RO>) -> StorageResult<()> {
+        let age = self.inner.pinned_at.elapsed();
+        if age > self.inner.max_age {
+            return Err(StorageError::SnapshotExpired {
+                block_number: self.inner.state_number.0,
+                age,
+            });
+        }
+        if self.inner.state_number.0 > txn.get_state_marker()?
+            || last_block_hash(txn, self.inner.state_number.0)? != self.inner.last_block_hash
+        {
+            return Err(StorageError::SnapshotReverted { block_number: self.inner.state_number.0 });
+        }
+        Ok(())
+    }
+}
+
+// Returns the hash of the block before the given block marker, if it is known.
+fn last_block_hash(
+    txn: &StorageTxn<'_, RO>,
+    block_number: BlockNumber,
+) -> StorageResult<Option<BlockHash>> {
+    let Some(last_block_number) = block_number.prev() else {
+        return Ok(None);
+    };
+    Ok(txn.get_block_header(last_block_number)?.map(|header| header.block_hash))
+}
@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use assert_matches::assert_matches;
+use indexmap::indexmap;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockHash, BlockHeader, BlockNumber};
+use starknet_api::core::{ContractAddress, Nonce, PatriciaKey};
+use starknet_api::state::ThinStateDiff;
+use starknet_api::{contract_address, felt, patricia_key};
+
+use crate::header::HeaderStorageWriter;
+use crate::state::StateStorageWriter;
+use crate::test_utils::get_test_storage;
+use crate::{StorageError, StorageWriter};
+
+const MAX_AGE: Duration = Duration::from_secs(60);
+
+fn append_block_with_nonce(
+    writer: &mut StorageWriter,
+    block_number: BlockNumber,
+    address: ContractAddress,
+    nonce: Nonce,
+) {
+    let header = BlockHeader {
+        block_hash: BlockHash(felt!(block_number.0 + 1)),
+        block_number,
+        ..Default::default()
+    };
+    let state_diff = ThinStateDiff { nonces: indexmap! { address => nonce }, ..Default::default() };
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(block_number, &header)
+        .unwrap()
+        .append_state_diff(block_number, state_diff)
+        .unwrap()
+        .commit()
+        .unwrap();
+}
+
+#[test]
+fn snapshot_reads_pinned_state() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    let address = contract_address!("0x1");
+    append_block_with_nonce(&mut writer, BlockNumber(0), address, Nonce(felt!(1_u8)));
+
+    let snapshot = reader.pin_snapshot(BlockNumber(1), MAX_AGE).unwrap();
+    assert_eq!(snapshot.get_nonce_at(&address).unwrap(), Some(Nonce(felt!(1_u8))));
+
+    // Appending blocks doesn't change the pinned state.
+    append_block_with_nonce(&mut writer, BlockNumber(1), address, Nonce(felt!(2_u8)));
+    assert_eq!(snapshot.get_nonce_at(&address).unwrap(), Some(Nonce(felt!(1_u8))));
+
+    // Concurrent readers share the same snapshot.
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let snapshot = snapshot.clone();
+            std::thread::spawn(move || snapshot.get_nonce_at(&address).unwrap())
+        })
+        .collect();
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), Some(Nonce(felt!(1_u8))));
+    }
+}
+
+#[test]
+fn snapshot_above_state_marker() {
+    let ((reader, _writer), _temp_dir) = get_test_storage();
+    assert_matches!(
+        reader.pin_snapshot(BlockNumber(1), MAX_AGE),
+        Err(StorageError::SnapshotAboveStateMarker { block_number, state_marker })
+        if block_number == BlockNumber(1) && state_marker == BlockNumber(0)
+    );
+}
+
+#[test]
+fn expired_snapshot() {
+    let ((reader, _writer), _temp_dir) = get_test_storage();
+    let snapshot = reader.pin_snapshot(BlockNumber(0), Duration::ZERO).unwrap();
+    std::thread::sleep(Duration::from_millis(1));
+    assert!(snapshot.is_expired());
+    assert_matches!(
+        snapshot.get_nonce_at(&contract_address!("0x1")),
+        Err(StorageError::SnapshotExpired { .. })
+    );
+}
+
+#[test]
+fn reverted_snapshot() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    let address = contract_address!("0x1");
+    append_block_with_nonce(&mut writer, BlockNumber(0), address, Nonce(felt!(1_u8)));
+    let snapshot = reader.pin_snapshot(BlockNumber(1), MAX_AGE).unwrap();
+
+    let (txn, _) = writer.begin_rw_txn().unwrap().revert_state_diff(BlockNumber(0)).unwrap();
+    let (txn, _, _) = txn.revert_header(BlockNumber(0)).unwrap();
+    txn.commit().unwrap();
+
+    assert_matches!(
+        snapshot.get_nonce_at(&address),
+        Err(StorageError::SnapshotReverted { block_number }) if block_number == BlockNumber(1)
+    );
+}
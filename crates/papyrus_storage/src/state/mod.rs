@@ -60,7 +60,7 @@ use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use indexmap::IndexMap;
 use papyrus_proc_macros::latency_histogram;
 use starknet_api::block::BlockNumber;
-use starknet_api::core::{ClassHash, ContractAddress, Nonce};
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
 use starknet_api::state::{ContractClass, StateNumber, StorageKey, ThinStateDiff};
 use starknet_types_core::felt::Felt;
@@ -102,6 +102,8 @@ pub(crate) type ContractStorageTable<'env> = TableHandle<
     NoVersionValueWrapper<Felt>,
     CommonPrefix,
 >;
+pub(crate) type StateDiffsTable<'env> =
+    TableHandle<'env, BlockNumber, VersionZeroWrapper<LocationInFile>, SimpleTable>;
 pub(crate) type NoncesTable<'env> =
     TableHandle<'env, (ContractAddress, BlockNumber), VersionZeroWrapper<Nonce>, CommonPrefix>;
 
@@ -193,6 +195,7 @@ pub struct StateReader<'env, Mode: TransactionKind> {
     nonces_table: NoncesTable<'env>,
     storage_table: ContractStorageTable<'env>,
     markers_table: MarkersTable<'env>,
+    state_diffs_table: StateDiffsTable<'env>,
     file_handlers: &'env FileHandlers<Mode>,
 }
 
@@ -216,6 +219,7 @@ impl<'env, Mode: TransactionKind> StateReader<'env, Mode> {
         let nonces_table = txn.txn.open_table(&txn.tables.nonces)?;
         let storage_table = txn.txn.open_table(&txn.tables.contract_storage)?;
         let markers_table = txn.txn.open_table(&txn.tables.markers)?;
+        let state_diffs_table = txn.txn.open_table(&txn.tables.state_diffs)?;
         Ok(StateReader {
             txn: &txn.txn,
             declared_classes_table,
@@ -225,6 +229,7 @@ impl<'env, Mode: TransactionKind> StateReader<'env, Mode> {
             nonces_table,
             storage_table,
             markers_table,
+            state_diffs_table,
             file_handlers: &txn.file_handlers,
         })
     }
@@ -238,6 +243,7 @@ impl<'env, Mode: TransactionKind> StateReader<'env, Mode> {
     ///
     /// # Errors
     /// Returns [`StorageError`] if there was an error searching the table.
+    #[latency_histogram("storage_get_class_hash_at_latency_seconds", true)]
     pub fn get_class_hash_at(
         &self,
         state_number: StateNumber,
@@ -269,6 +275,7 @@ impl<'env, Mode: TransactionKind> StateReader<'env, Mode> {
     ///
     /// # Errors
     /// Returns [`StorageError`] if there was an error searching the table.
+    #[latency_histogram("storage_get_nonce_at_latency_seconds", true)]
     pub fn get_nonce_at(
         &self,
         state_number: StateNumber,
@@ -309,6 +316,7 @@ impl<'env, Mode: TransactionKind> StateReader<'env, Mode> {
     ///
     /// # Errors
     /// Returns [`StorageError`] if there was an error searching the table.
+    #[latency_histogram("storage_get_storage_at_latency_seconds", true)]
     pub fn get_storage_at(
         &self,
         state_number: StateNumber,
@@ -423,6 +431,175 @@ impl<'env, Mode: TransactionKind> StateReader<'env, Mode> {
             self.file_handlers.get_deprecated_contract_class_unchecked(value.location_in_file)?,
         ))
     }
+
+    /// Returns the state of the contracts at a given state number, in the order of their
+    /// addresses, starting from `start` and for at most `limit` contracts. The state of a contract
+    /// is its class hash, its nonce and its non-zero storage values.
+    ///
+    /// Returns the contracts as a state diff from the empty state, with the address to continue
+    /// from if `limit` contracts were returned.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`] if there was an error searching the tables.
+    pub fn get_contracts_state(
+        &self,
+        state_number: StateNumber,
+        start: ContractAddress,
+        limit: usize,
+    ) -> StorageResult<(ThinStateDiff, Option<ContractAddress>)> {
+        let mut deployed_contracts_cursor = self.deployed_contracts_table.cursor(self.txn)?;
+        let mut nonces_cursor = self.nonces_table.cursor(self.txn)?;
+        let mut storage_cursor = self.storage_table.cursor(self.txn)?;
+        let mut state = ThinStateDiff::default();
+        let mut n_contracts = 0;
+        let mut next_address = Some(start);
+        while let Some(from_address) = next_address.filter(|_| n_contracts < limit) {
+            // The next contract is the first address from `from_address` on in any of the tables.
+            let Some(address) = [
+                deployed_contracts_cursor
+                    .lower_bound(&(from_address, BlockNumber(0)))?
+                    .map(|((address, _), _)| address),
+                nonces_cursor
+                    .lower_bound(&(from_address, BlockNumber(0)))?
+                    .map(|((address, _), _)| address),
+                storage_cursor
+                    .lower_bound(&((from_address, StorageKey::default()), BlockNumber(0)))?
+                    .map(|(((address, _), _), _)| address),
+            ]
+            .into_iter()
+            .flatten()
+            .min() else {
+                return Ok((state, None));
+            };
+            next_address = ContractAddress::try_from(Felt::from(address) + Felt::ONE).ok();
+
+            let class_hash = self.get_class_hash_at(state_number, &address)?;
+            let nonce = self.get_nonce_at(state_number, &address)?;
+            let storage = self.get_contract_storage_at(state_number, address)?;
+            // The contract may only have data after the given state.
+            if class_hash.is_none() && nonce.is_none() && storage.is_empty() {
+                continue;
+            }
+            if let Some(class_hash) = class_hash {
+                state.deployed_contracts.insert(address, class_hash);
+            }
+            if let Some(nonce) = nonce {
+                state.nonces.insert(address, nonce);
+            }
+            if !storage.is_empty() {
+                state.storage_diffs.insert(address, storage);
+            }
+            n_contracts += 1;
+        }
+        Ok((state, next_address))
+    }
+
+    // Returns the non-zero storage values of a contract at a given state number.
+    fn get_contract_storage_at(
+        &self,
+        state_number: StateNumber,
+        address: ContractAddress,
+    ) -> StorageResult<IndexMap<StorageKey, Felt>> {
+        let mut storage = IndexMap::new();
+        let mut cursor = self.storage_table.cursor(self.txn)?;
+        let mut current =
+            cursor.lower_bound(&((address, StorageKey::default()), BlockNumber(0)))?;
+        while let Some((((got_address, key), block_number), value)) = current {
+            if got_address != address {
+                break;
+            }
+            // The updates of a key are ordered by block, so the last relevant one is kept.
+            if state_number.is_after(block_number) {
+                storage.insert(key, value);
+            }
+            current = cursor.next()?;
+        }
+        storage.retain(|_key, value| *value != Felt::ZERO);
+        Ok(storage)
+    }
+
+    /// Returns the classes declared up to a given state number, in the order of their hashes,
+    /// starting from `start` and for at most `limit` classes.
+    ///
+    /// Returns the classes as a state diff from the empty state, with the class hash to continue
+    /// from if there are more classes.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`] if there was an error searching the tables.
+    ///
+    /// Returns [`StorageError`]::DBInconsistency if the state diff that declared a class is
+    /// missing.
+    pub fn get_declared_classes_state(
+        &self,
+        state_number: StateNumber,
+        start: ClassHash,
+        limit: usize,
+    ) -> StorageResult<(ThinStateDiff, Option<ClassHash>)> {
+        let mut classes_cursor = self.declared_classes_block_table.cursor(self.txn)?;
+        let mut deprecated_classes_cursor =
+            self.deprecated_declared_classes_table.cursor(self.txn)?;
+        let mut next_class = classes_cursor.lower_bound(&start)?;
+        let mut next_deprecated_class = deprecated_classes_cursor.lower_bound(&start)?;
+        let mut state = ThinStateDiff::default();
+        let mut n_classes = 0;
+        loop {
+            let next_class_hash = [
+                next_class.as_ref().map(|(class_hash, _)| *class_hash),
+                next_deprecated_class.as_ref().map(|(class_hash, _)| *class_hash),
+            ]
+            .into_iter()
+            .flatten()
+            .min();
+            let Some(class_hash) = next_class_hash else {
+                return Ok((state, None));
+            };
+            if n_classes == limit {
+                return Ok((state, Some(class_hash)));
+            }
+
+            match next_class {
+                Some((hash, block_number)) if hash == class_hash => {
+                    if state_number.is_after(block_number) {
+                        let compiled_class_hash =
+                            self.get_compiled_class_hash(block_number, &class_hash)?;
+                        state.declared_classes.insert(class_hash, compiled_class_hash);
+                        n_classes += 1;
+                    }
+                    next_class = classes_cursor.next()?;
+                }
+                _ => {
+                    if let Some((_, value)) = &next_deprecated_class {
+                        if state_number.is_after(value.block_number) {
+                            state.deprecated_declared_classes.push(class_hash);
+                            n_classes += 1;
+                        }
+                    }
+                    next_deprecated_class = deprecated_classes_cursor.next()?;
+                }
+            }
+        }
+    }
+
+    // Returns the compiled class hash of a class by the state diff of the block that declared it.
+    // TODO(shahak): Consider indexing the compiled class hashes by class hash, to avoid reading a
+    // state diff for each class.
+    fn get_compiled_class_hash(
+        &self,
+        block_number: BlockNumber,
+        class_hash: &ClassHash,
+    ) -> StorageResult<CompiledClassHash> {
+        let state_diff_location = self.state_diffs_table.get(self.txn, &block_number)?;
+        state_diff_location
+            .map(|location| self.file_handlers.get_thin_state_diff_unchecked(location))
+            .transpose()?
+            .and_then(|state_diff| state_diff.declared_classes.get(class_hash).copied())
+            .ok_or_else(|| StorageError::DBInconsistency {
+                msg: format!(
+                    "Couldn't find the compiled class hash of class {class_hash} in the state \
+                     diff of block {block_number}."
+                ),
+            })
+    }
 }
 
 impl<'env> StateStorageWriter for StorageTxn<'env, RW> {
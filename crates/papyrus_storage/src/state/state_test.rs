@@ -771,3 +771,160 @@ fn declare_revert_declare_scenario() {
             .is_some()
     );
 }
+
+#[test]
+fn get_contracts_state() {
+    let system_contract = ContractAddress(patricia_key!("0x1"));
+    let contract0 = ContractAddress(patricia_key!("0x10"));
+    let contract1 = ContractAddress(patricia_key!("0x20"));
+    let key0 = StorageKey(patricia_key!("0x5"));
+    let key1 = StorageKey(patricia_key!("0x6"));
+    let diff0 = ThinStateDiff {
+        deployed_contracts: indexmap!(contract0 => ClassHash(felt!("0x100"))),
+        storage_diffs: indexmap!(
+            system_contract => indexmap!(key0 => felt!("0x5")),
+            contract0 => indexmap!(key0 => felt!("0x1"), key1 => felt!("0x2")),
+        ),
+        nonces: indexmap!(contract0 => Nonce(felt!("0x1"))),
+        ..Default::default()
+    };
+    let diff1 = ThinStateDiff {
+        deployed_contracts: indexmap!(contract1 => ClassHash(felt!("0x200"))),
+        // Zero values aren't part of the state.
+        storage_diffs: indexmap!(contract0 => indexmap!(key0 => Felt::ZERO, key1 => felt!("0x3"))),
+        nonces: indexmap!(contract0 => Nonce(felt!("0x2"))),
+        replaced_classes: indexmap!(contract0 => ClassHash(felt!("0x300"))),
+        ..Default::default()
+    };
+
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_state_diff(BlockNumber(0), diff0)
+        .unwrap()
+        .append_state_diff(BlockNumber(1), diff1)
+        .unwrap()
+        .commit()
+        .unwrap();
+    let txn = reader.begin_ro_txn().unwrap();
+    let state_reader = txn.get_state_reader().unwrap();
+
+    let state_after_block_0 = ThinStateDiff {
+        deployed_contracts: indexmap!(contract0 => ClassHash(felt!("0x100"))),
+        storage_diffs: indexmap!(
+            system_contract => indexmap!(key0 => felt!("0x5")),
+            contract0 => indexmap!(key0 => felt!("0x1"), key1 => felt!("0x2")),
+        ),
+        nonces: indexmap!(contract0 => Nonce(felt!("0x1"))),
+        ..Default::default()
+    };
+    assert_eq!(
+        state_reader
+            .get_contracts_state(StateNumber(BlockNumber(1)), ContractAddress::default(), 10)
+            .unwrap(),
+        (state_after_block_0, None)
+    );
+
+    let state_number = StateNumber(BlockNumber(2));
+    let (first_contracts, next_address) =
+        state_reader.get_contracts_state(state_number, ContractAddress::default(), 2).unwrap();
+    assert_eq!(
+        first_contracts,
+        ThinStateDiff {
+            deployed_contracts: indexmap!(contract0 => ClassHash(felt!("0x300"))),
+            storage_diffs: indexmap!(
+                system_contract => indexmap!(key0 => felt!("0x5")),
+                contract0 => indexmap!(key1 => felt!("0x3")),
+            ),
+            nonces: indexmap!(contract0 => Nonce(felt!("0x2"))),
+            ..Default::default()
+        }
+    );
+    assert_eq!(next_address, Some(ContractAddress(patricia_key!("0x11"))));
+    assert_eq!(
+        state_reader.get_contracts_state(state_number, next_address.unwrap(), 2).unwrap(),
+        (
+            ThinStateDiff {
+                deployed_contracts: indexmap!(contract1 => ClassHash(felt!("0x200"))),
+                nonces: indexmap!(contract1 => Nonce::default()),
+                ..Default::default()
+            },
+            None
+        )
+    );
+}
+
+#[test]
+fn get_declared_classes_state() {
+    let deprecated_class_hash0 = ClassHash(felt!("0x1"));
+    let deprecated_class_hash1 = ClassHash(felt!("0x2"));
+    let class_hash0 = ClassHash(felt!("0x10"));
+    let class_hash1 = ClassHash(felt!("0x11"));
+    let diff0 = ThinStateDiff {
+        deprecated_declared_classes: vec![deprecated_class_hash0, deprecated_class_hash1],
+        declared_classes: indexmap!(class_hash0 => CompiledClassHash(felt!("0x100"))),
+        ..Default::default()
+    };
+    let diff1 = ThinStateDiff {
+        declared_classes: indexmap!(class_hash1 => CompiledClassHash(felt!("0x101"))),
+        ..Default::default()
+    };
+
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    let class = ContractClass::default();
+    let deprecated_class = DeprecatedContractClass::default();
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_state_diff(BlockNumber(0), diff0)
+        .unwrap()
+        .append_state_diff(BlockNumber(1), diff1)
+        .unwrap()
+        .append_classes(
+            BlockNumber(0),
+            &[(class_hash0, &class)],
+            &[
+                (deprecated_class_hash0, &deprecated_class),
+                (deprecated_class_hash1, &deprecated_class),
+            ],
+        )
+        .unwrap()
+        .append_classes(BlockNumber(1), &[(class_hash1, &class)], &[])
+        .unwrap()
+        .commit()
+        .unwrap();
+    let txn = reader.begin_ro_txn().unwrap();
+    let state_reader = txn.get_state_reader().unwrap();
+
+    let state_number = StateNumber(BlockNumber(2));
+    assert_eq!(
+        state_reader.get_declared_classes_state(state_number, ClassHash::default(), 3).unwrap(),
+        (
+            ThinStateDiff {
+                deprecated_declared_classes: vec![deprecated_class_hash0, deprecated_class_hash1],
+                declared_classes: indexmap!(class_hash0 => CompiledClassHash(felt!("0x100"))),
+                ..Default::default()
+            },
+            Some(class_hash1)
+        )
+    );
+    assert_eq!(
+        state_reader.get_declared_classes_state(state_number, class_hash1, 3).unwrap(),
+        (
+            ThinStateDiff {
+                declared_classes: indexmap!(class_hash1 => CompiledClassHash(felt!("0x101"))),
+                ..Default::default()
+            },
+            None
+        )
+    );
+
+    // Classes declared after the state aren't returned.
+    assert_eq!(
+        state_reader
+            .get_declared_classes_state(StateNumber(BlockNumber(1)), class_hash1, 3)
+            .unwrap(),
+        (ThinStateDiff::default(), None)
+    );
+}
@@ -16,8 +16,10 @@ use starknet_api::transaction::{
 };
 
 use crate::body::TransactionIndex;
+use crate::class_dictionaries::ClassSegment;
 use crate::compression_utils::IsCompressed;
 use crate::header::StorageBlockHeader;
+use crate::migration::MigrationProgress;
 use crate::mmap_file::LocationInFile;
 use crate::state::data::IndexedDeprecatedContractClass;
 use crate::version::Version;
@@ -43,6 +45,11 @@ auto_impl_get_test_instance! {
         pub n_events: usize,
     }
 
+    pub enum ClassSegment {
+        SierraProgram = 0,
+        DeprecatedProgram = 1,
+        Casm = 2,
+    }
     struct EventIndex(pub TransactionIndex, pub EventIndexInTransactionOutput);
     pub struct IndexedDeprecatedContractClass {
         pub block_number: BlockNumber,
@@ -60,6 +67,13 @@ auto_impl_get_test_instance! {
         Class = 4,
         CompiledClass = 5,
         BaseLayerBlock = 6,
+        TransactionHashesIndex = 7,
+        EventKeysIndex = 8,
+        SentToBaseLayerBlock = 9,
+    }
+    pub struct MigrationProgress {
+        pub migrated_entries: u64,
+        pub checkpoint: Option<Vec<u8>>,
     }
     pub enum OffsetKind {
         ThinStateDiff = 0,
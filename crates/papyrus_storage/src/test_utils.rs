@@ -4,6 +4,7 @@
 use starknet_api::core::ChainId;
 use tempfile::{tempdir, TempDir};
 
+use crate::body::indices::IndicesConfig;
 use crate::db::DbConfig;
 use crate::mmap_file::MmapFileConfig;
 use crate::{open_storage, StorageConfig, StorageReader, StorageScope, StorageWriter};
@@ -29,6 +30,7 @@ pub fn get_test_config(storage_scope: Option<StorageScope>) -> (StorageConfig, T
             },
             scope: storage_scope,
             mmap_file_config: get_mmap_file_test_config(),
+            indices: IndicesConfig::default(),
         },
         dir,
     )
@@ -3,7 +3,7 @@
 #[path = "utils_test.rs"]
 mod utils_test;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs::File;
 use std::io::{BufWriter, Write};
 
@@ -16,10 +16,19 @@ use starknet_types_core::felt::Felt;
 use tracing::debug;
 
 use crate::compiled_class::CasmStorageReader;
-use crate::db::table_types::Table;
+use crate::db::table_types::{DbCursorTrait, Table};
 use crate::db::RO;
+use crate::mmap_file::LocationInFile;
 use crate::state::StateStorageReader;
-use crate::{open_storage, StorageConfig, StorageError, StorageReader, StorageResult, StorageTxn};
+use crate::{
+    open_storage,
+    StorageConfig,
+    StorageError,
+    StorageReader,
+    StorageResult,
+    StorageTxn,
+    Tables,
+};
 
 #[derive(Serialize)]
 struct DumpDeclaredClass {
@@ -88,15 +97,95 @@ fn dump_declared_classes_table_by_block_range_internal(
     Ok(())
 }
 
-// TODO(dvir): consider adding storage size metrics.
 // TODO(dvir): relocate all the storage metrics in one module and export them (also in other
 // crates).
 /// Updates storage metrics about the state of the storage.
+///
+/// Besides the database-wide metrics, the size and number of entries of each table are reported
+/// with a `table` label, and the size of each memory mapped file with a `file` label.
 pub fn update_storage_metrics(reader: &StorageReader) -> StorageResult<()> {
     debug!("updating storage metrics");
     gauge!("storage_free_pages_number", reader.db_reader.get_free_pages()? as f64);
     let info = reader.db_reader.get_db_info()?;
     absolute_counter!("storage_last_page_number", info.last_pgno() as u64);
     absolute_counter!("storage_last_transaction_index", info.last_txnid() as u64);
+
+    let db_stats = reader.db_reader.get_db_stats()?;
+    gauge!("storage_db_size_bytes", db_stats.total_size as f64);
+    gauge!("storage_free_space_bytes", (db_stats.freelist_size as u64 * db_stats.page_size) as f64);
+    for table_name in Tables::field_names() {
+        let table_stats = reader.db_reader.get_table_stats(table_name)?;
+        gauge!("storage_table_size_bytes", table_stats.total_size as f64, "table" => *table_name);
+        gauge!("storage_table_entries", table_stats.entries as f64, "table" => *table_name);
+    }
+
+    for (file_name, file_stats) in reader.mmap_files_stats() {
+        gauge!("storage_mmap_file_size_bytes", file_stats.size as f64, "file" => file_name.clone());
+        gauge!("storage_mmap_file_used_bytes", file_stats.offset as f64, "file" => file_name);
+    }
     Ok(())
 }
+
+/// The sizes of the classes in one of the class files.
+#[derive(Clone, Copy, Debug, Default, Serialize, PartialEq, Eq)]
+pub struct ClassFileSizes {
+    /// The number of stored classes.
+    pub classes: usize,
+    /// The number of classes with distinct contents.
+    pub distinct_classes: usize,
+    /// The size of the classes if each class was stored separately, in bytes.
+    pub total_bytes: usize,
+    /// The size of the classes in the file, in bytes.
+    pub stored_bytes: usize,
+}
+
+impl ClassFileSizes {
+    fn add(&mut self, location: LocationInFile, distinct_locations: &mut HashSet<LocationInFile>) {
+        self.classes += 1;
+        self.total_bytes += location.len;
+        if distinct_locations.insert(location) {
+            self.distinct_classes += 1;
+            self.stored_bytes += location.len;
+        }
+    }
+}
+
+/// Returns the sizes of the class files by their names, showing the space that is saved by storing
+/// identical classes once.
+pub fn class_files_sizes(
+    reader: &StorageReader,
+) -> StorageResult<BTreeMap<String, ClassFileSizes>> {
+    let txn = reader.begin_ro_txn()?;
+    let mut sizes = BTreeMap::new();
+
+    let mut contract_class_sizes = ClassFileSizes::default();
+    let mut distinct_locations = HashSet::new();
+    let declared_classes_table = txn.open_table(&txn.tables.declared_classes)?;
+    let mut cursor = declared_classes_table.cursor(&txn.txn)?;
+    while let Some((_class_hash, location)) = cursor.next()? {
+        contract_class_sizes.add(location, &mut distinct_locations);
+    }
+    sizes.insert("contract_class".to_string(), contract_class_sizes);
+
+    let mut deprecated_contract_class_sizes = ClassFileSizes::default();
+    let mut distinct_locations = HashSet::new();
+    let deprecated_declared_classes_table =
+        txn.open_table(&txn.tables.deprecated_declared_classes)?;
+    let mut cursor = deprecated_declared_classes_table.cursor(&txn.txn)?;
+    while let Some((_class_hash, indexed_class)) = cursor.next()? {
+        deprecated_contract_class_sizes
+            .add(indexed_class.location_in_file, &mut distinct_locations);
+    }
+    sizes.insert("deprecated_contract_class".to_string(), deprecated_contract_class_sizes);
+
+    let mut casm_sizes = ClassFileSizes::default();
+    let mut distinct_locations = HashSet::new();
+    let casms_table = txn.open_table(&txn.tables.casms)?;
+    let mut cursor = casms_table.cursor(&txn.txn)?;
+    while let Some((_class_hash, location)) = cursor.next()? {
+        casm_sizes.add(location, &mut distinct_locations);
+    }
+    sizes.insert("casm".to_string(), casm_sizes);
+
+    Ok(sizes)
+}
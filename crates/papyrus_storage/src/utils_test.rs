@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::fs;
 
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use indexmap::indexmap;
 use metrics_exporter_prometheus::PrometheusBuilder;
 use papyrus_test_utils::prometheus_is_contained;
@@ -14,9 +15,15 @@ use starknet_types_core::felt::Felt;
 
 use super::update_storage_metrics;
 use crate::class::ClassStorageWriter;
+use crate::compiled_class::CasmStorageWriter;
 use crate::state::StateStorageWriter;
 use crate::test_utils::get_test_storage;
-use crate::utils::{dump_declared_classes_table_by_block_range_internal, DumpDeclaredClass};
+use crate::utils::{
+    class_files_sizes,
+    dump_declared_classes_table_by_block_range_internal,
+    ClassFileSizes,
+    DumpDeclaredClass,
+};
 
 // TODO(yael): fix dump_table_to_file.
 #[test]
@@ -114,4 +121,62 @@ fn update_storage_metrics_test() {
     };
     assert!(0f64 < last_transaction);
     assert!(last_transaction < 100f64);
+
+    let Gauge(db_size) =
+        prometheus_is_contained(handle.render(), "storage_db_size_bytes", &[]).unwrap()
+    else {
+        panic!("storage_db_size_bytes is not a Gauge")
+    };
+    assert!(0f64 < db_size);
+
+    // The version is written when the storage is opened.
+    let Gauge(storage_version_entries) = prometheus_is_contained(
+        handle.render(),
+        "storage_table_entries",
+        &[("table", "storage_version")],
+    )
+    .unwrap() else {
+        panic!("storage_table_entries is not a Gauge")
+    };
+    assert!(0f64 < storage_version_entries);
+    let Gauge(headers_entries) =
+        prometheus_is_contained(handle.render(), "storage_table_entries", &[("table", "headers")])
+            .unwrap()
+    else {
+        panic!("storage_table_entries is not a Gauge")
+    };
+    assert_eq!(headers_entries, 0f64);
+
+    let Gauge(casm_file_size) = prometheus_is_contained(
+        handle.render(),
+        "storage_mmap_file_size_bytes",
+        &[("file", "casm")],
+    )
+    .unwrap() else {
+        panic!("storage_mmap_file_size_bytes is not a Gauge")
+    };
+    assert!(0f64 < casm_file_size);
+}
+
+#[test]
+fn class_files_sizes_test() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    for class_hash in [ClassHash(Felt::ONE), ClassHash(Felt::TWO)] {
+        writer
+            .begin_rw_txn()
+            .unwrap()
+            .append_casm(&class_hash, &CasmContractClass::default())
+            .unwrap()
+            .commit()
+            .unwrap();
+    }
+
+    let sizes = class_files_sizes(&reader).unwrap();
+    assert_eq!(sizes["contract_class"], ClassFileSizes::default());
+    assert_eq!(sizes["deprecated_contract_class"], ClassFileSizes::default());
+    let casm_sizes = sizes["casm"];
+    assert_eq!(casm_sizes.classes, 2);
+    assert_eq!(casm_sizes.distinct_classes, 1);
+    assert_eq!(casm_sizes.total_bytes, 2 * casm_sizes.stored_bytes);
+    assert_eq!(casm_sizes.stored_bytes, reader.mmap_files_stats()["casm"].offset);
 }
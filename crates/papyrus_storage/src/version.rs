@@ -8,8 +8,8 @@ use crate::db::table_types::Table;
 use crate::db::{TransactionKind, RW};
 use crate::{StorageError, StorageResult, StorageTxn};
 
-const VERSION_STATE_KEY: &str = "storage_version_state";
-const VERSION_BLOCKS_KEY: &str = "storage_version_blocks";
+pub(crate) const VERSION_STATE_KEY: &str = "storage_version_state";
+pub(crate) const VERSION_BLOCKS_KEY: &str = "storage_version_blocks";
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct Version {
@@ -17,6 +17,22 @@ pub struct Version {
     pub minor: u32,
 }
 
+/// A part of the storage that is versioned separately.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum VersionedComponent {
+    State,
+    Blocks,
+}
+
+impl VersionedComponent {
+    fn key(&self) -> &'static str {
+        match self {
+            VersionedComponent::State => VERSION_STATE_KEY,
+            VersionedComponent::Blocks => VERSION_BLOCKS_KEY,
+        }
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum StorageVersionError {
     #[error(
@@ -46,6 +62,10 @@ pub enum StorageVersionError {
 pub trait VersionStorageReader {
     fn get_state_version(&self) -> StorageResult<Option<Version>>;
     fn get_blocks_version(&self) -> StorageResult<Option<Version>>;
+    fn get_component_version(
+        &self,
+        component: VersionedComponent,
+    ) -> StorageResult<Option<Version>>;
 }
 
 pub trait VersionStorageWriter
@@ -56,6 +76,13 @@ where
     fn set_state_version(self, version: &Version) -> StorageResult<Self>;
     fn set_blocks_version(self, version: &Version) -> StorageResult<Self>;
     fn delete_blocks_version(self) -> StorageResult<Self>;
+    // Sets the version of a component after migrating its data. Unlike the other setters, the
+    // major version may change.
+    fn set_migrated_version(
+        self,
+        component: VersionedComponent,
+        version: &Version,
+    ) -> StorageResult<Self>;
 }
 
 impl<'env, Mode: TransactionKind> VersionStorageReader for StorageTxn<'env, Mode> {
@@ -68,6 +95,14 @@ impl<'env, Mode: TransactionKind> VersionStorageReader for StorageTxn<'env, Mode
         let version_table = self.open_table(&self.tables.storage_version)?;
         Ok(version_table.get(&self.txn, &VERSION_BLOCKS_KEY.to_string())?)
     }
+
+    fn get_component_version(
+        &self,
+        component: VersionedComponent,
+    ) -> StorageResult<Option<Version>> {
+        let version_table = self.open_table(&self.tables.storage_version)?;
+        Ok(version_table.get(&self.txn, &component.key().to_string())?)
+    }
 }
 
 impl<'env> VersionStorageWriter for StorageTxn<'env, RW> {
@@ -124,6 +159,28 @@ impl<'env> VersionStorageWriter for StorageTxn<'env, RW> {
         version_table.delete(&self.txn, &VERSION_BLOCKS_KEY.to_string())?;
         Ok(self)
     }
+
+    fn set_migrated_version(
+        self,
+        component: VersionedComponent,
+        version: &Version,
+    ) -> StorageResult<Self> {
+        let version_table = self.open_table(&self.tables.storage_version)?;
+        if let Some(current_storage_version) = self.get_component_version(component)? {
+            if (current_storage_version.major, current_storage_version.minor)
+                >= (version.major, version.minor)
+            {
+                return Err(StorageError::StorageVersionInconsistency(
+                    StorageVersionError::SetLowerVersion {
+                        crate_version: version.clone(),
+                        storage_version: current_storage_version,
+                    },
+                ));
+            }
+        }
+        version_table.upsert(&self.txn, &component.key().to_string(), version)?;
+        Ok(self)
+    }
 }
 
 impl Display for Version {
@@ -25,11 +25,17 @@ use papyrus_config::dumping::{ser_param, SerializeConfig};
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use papyrus_proc_macros::latency_histogram;
 use papyrus_storage::base_layer::{BaseLayerStorageReader, BaseLayerStorageWriter};
+use papyrus_storage::body::indices::{
+    backfill_indices,
+    index_backfill_interval,
+    indices_lag_behind_bodies,
+};
 use papyrus_storage::body::BodyStorageWriter;
 use papyrus_storage::class::ClassStorageWriter;
 use papyrus_storage::compiled_class::{CasmStorageReader, CasmStorageWriter};
 use papyrus_storage::db::DbError;
 use papyrus_storage::header::{HeaderStorageReader, HeaderStorageWriter};
+use papyrus_storage::revert::{RevertStorageWriter, RevertedBlock};
 use papyrus_storage::state::{StateStorageReader, StateStorageWriter};
 use papyrus_storage::{StorageError, StorageReader, StorageWriter};
 use serde::{Deserialize, Serialize};
@@ -39,7 +45,7 @@ use starknet_api::core::{ClassHash, CompiledClassHash, SequencerPublicKey};
 use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
 use starknet_api::state::{StateDiff, ThinStateDiff};
 use starknet_client::reader::PendingData;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tracing::{debug, error, info, instrument, trace, warn};
 
 use crate::pending_sync::sync_pending_data;
@@ -59,6 +65,9 @@ const PENDING_SLEEP_DURATION: Duration = Duration::from_millis(500);
 // Sleep duration, in seconds, between sync progress checks.
 const SLEEP_TIME_SYNC_PROGRESS: Duration = Duration::from_secs(300);
 
+// The number of reverted blocks that are kept for subscribers that lag behind.
+const REVERTED_BLOCKS_CHANNEL_CAPACITY: usize = 1000;
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
 pub struct SyncConfig {
     #[serde(deserialize_with = "deserialize_seconds_to_duration")]
@@ -154,6 +163,8 @@ pub struct GenericStateSync<
     reader: StorageReader,
     writer: StorageWriter,
     sequencer_pub_key: Option<SequencerPublicKey>,
+    // Notifies other components (e.g. mempool and consensus) about blocks reverted on reorgs.
+    reverted_blocks_sender: broadcast::Sender<RevertedBlock>,
 }
 
 pub type StateSyncResult = Result<(), StateSyncError>;
@@ -223,6 +234,7 @@ pub enum SyncEvent {
         block_number: BlockNumber,
         block_hash: BlockHash,
     },
+    BackfillBatchDue,
 }
 
 impl<
@@ -231,6 +243,11 @@ impl<
     TBaseLayerSource: BaseLayerSourceTrait + Sync + Send,
 > GenericStateSync<TCentralSource, TPendingSource, TBaseLayerSource>
 {
+    /// Returns a receiver of the blocks that the sync reverts, e.g. when the central chain reorgs.
+    pub fn subscribe_to_reverted_blocks(&self) -> broadcast::Receiver<RevertedBlock> {
+        self.reverted_blocks_sender.subscribe()
+    }
+
     pub async fn run(&mut self) -> StateSyncResult {
         info!("State sync started.");
         loop {
@@ -343,12 +360,14 @@ impl<
         // TODO(dvir): try use interval instead of stream.
         // TODO: fix the bug and remove this check.
         let check_sync_progress = check_sync_progress(self.reader.clone()).fuse();
+        let backfill_stream = stream_backfill_batches(self.reader.clone()).fuse();
         pin_mut!(
             block_stream,
             state_diff_stream,
             compiled_class_stream,
             base_layer_block_stream,
-            check_sync_progress
+            check_sync_progress,
+            backfill_stream
         );
 
         loop {
@@ -359,6 +378,7 @@ impl<
               res = compiled_class_stream.next() => res,
               res = base_layer_block_stream.next() => res,
               res = check_sync_progress.next() => res,
+              res = backfill_stream.next() => res,
               complete => break,
             }
             .expect("Received None as a sync event.")?;
@@ -393,10 +413,21 @@ impl<
             SyncEvent::NewBaseLayerBlock { block_number, block_hash } => {
                 self.store_base_layer_block(block_number, block_hash)
             }
+            SyncEvent::BackfillBatchDue => self.backfill_batch(),
             SyncEvent::NoProgress => Err(StateSyncError::NoProgress),
         }
     }
 
+    // Adds a batch of the stored blocks that the storage indices don't cover yet, when they aren't
+    // built on opening the storage. The progress is kept in the index markers, so the backfill
+    // continues from where it stopped after a restart.
+    fn backfill_batch(&mut self) -> StateSyncResult {
+        if backfill_indices(&mut self.writer)? {
+            debug!("Backfilled a batch of the storage indices.");
+        }
+        Ok(())
+    }
+
     #[latency_histogram("sync_store_block_latency_seconds", false)]
     #[instrument(
         skip(self, block),
@@ -615,30 +646,17 @@ impl<
     }
 
     // TODO(dan): update necessary metrics.
-    // Deletes the block data from the storage.
-    #[allow(clippy::expect_fun_call)]
+    // Deletes the block data from the storage and notifies the subscribers.
     #[instrument(skip(self), level = "debug", err)]
     fn revert_block(&mut self, block_number: BlockNumber) -> StateSyncResult {
         debug!("Reverting block.");
 
-        let mut txn = self.writer.begin_rw_txn()?;
-        txn = txn.try_revert_base_layer_marker(block_number)?;
-        let res = txn.revert_header(block_number)?;
-        txn = res.0;
-        let mut reverted_block_hash: Option<BlockHash> = None;
-        if let Some(header) = res.1 {
-            reverted_block_hash = Some(header.block_hash);
-
-            let res = txn.revert_body(block_number)?;
-            txn = res.0;
-
-            let res = txn.revert_state_diff(block_number)?;
-            txn = res.0;
-        }
-
+        let (txn, reverted_block) = self.writer.begin_rw_txn()?.revert_block(block_number)?;
         txn.commit()?;
-        if let Some(hash) = reverted_block_hash {
-            info!(%hash, "Reverted block.");
+        if let Some(reverted_block) = reverted_block {
+            info!(hash = %reverted_block.header.block_hash, "Reverted block.");
+            // Sending fails only when there are no subscribers.
+            let _ = self.reverted_blocks_sender.send(reverted_block);
         }
         Ok(())
     }
@@ -801,6 +819,7 @@ impl StateSync {
             reader,
             writer,
             sequencer_pub_key: None,
+            reverted_blocks_sender: broadcast::channel(REVERTED_BLOCKS_CHANNEL_CAPACITY).0,
         }
     }
 }
@@ -886,6 +905,23 @@ fn stream_new_base_layer_block<TBaseLayerSource: BaseLayerSourceTrait + Sync>(
     }
 }
 
+// Yields a backfill event every backfill interval of the storage, so the backfill doesn't compete
+// with the sync of new blocks over the storage writer. Ends once the storage indices cover all the
+// blocks, or at once if they're built on opening the storage.
+fn stream_backfill_batches(
+    reader: StorageReader,
+) -> impl Stream<Item = Result<SyncEvent, StateSyncError>> {
+    let backfill_interval = index_backfill_interval(&reader);
+    futures_util::stream::unfold(reader, move |reader| async move {
+        tokio::time::sleep(backfill_interval?).await;
+        match indices_lag_behind_bodies(&reader) {
+            Ok(true) => Some((Ok(SyncEvent::BackfillBatchDue), reader)),
+            Ok(false) => None,
+            Err(err) => Some((Err(err.into()), reader)),
+        }
+    })
+}
+
 // This function is used to check if the sync is stuck.
 // TODO: fix the bug and remove this function.
 // TODO(dvir): add a test for this scenario.
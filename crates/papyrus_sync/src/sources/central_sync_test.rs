@@ -20,7 +20,7 @@ use starknet_api::crypto::utils::PublicKey;
 use starknet_api::felt;
 use starknet_api::state::StateDiff;
 use starknet_client::reader::PendingData;
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{broadcast, Mutex, RwLock};
 use tracing::{debug, error};
 
 use super::pending::MockPendingSourceTrait;
@@ -121,6 +121,7 @@ async fn run_sync(
         reader,
         writer,
         sequencer_pub_key: None,
+        reverted_blocks_sender: broadcast::channel(1).0,
     };
 
     state_sync.run().await?;
@@ -27,7 +27,7 @@ use starknet_client::reader::objects::pending_data::{
 use starknet_client::reader::objects::state::StateDiff as ClientStateDiff;
 use starknet_client::reader::objects::transaction::Transaction as ClientTransaction;
 use starknet_client::reader::{DeclaredClassHashEntry, PendingData};
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 
 use crate::sources::base_layer::MockBaseLayerSourceTrait;
 use crate::sources::central::MockCentralSourceTrait;
@@ -200,6 +200,7 @@ fn store_base_layer_block_test() {
         reader,
         writer,
         sequencer_pub_key: None,
+        reverted_blocks_sender: broadcast::channel(1).0,
     };
 
     // Trying to store a block without a header in the storage.
@@ -218,6 +219,40 @@ fn store_base_layer_block_test() {
     assert_eq!(base_layer_marker, BlockNumber(1));
 }
 
+#[test]
+fn reverted_blocks_are_broadcast_to_subscribers() {
+    let (reader, mut writer) = get_test_storage().0;
+    add_headers(2, &mut writer);
+
+    let mut gen_state_sync = GenericStateSync {
+        config: SyncConfig::default(),
+        shared_highest_block: Arc::new(RwLock::new(None)),
+        pending_data: Arc::new(RwLock::new(PendingData::default())),
+        central_source: Arc::new(MockCentralSourceTrait::new()),
+        pending_source: Arc::new(MockPendingSourceTrait::new()),
+        pending_classes: Arc::new(RwLock::new(PendingClasses::default())),
+        base_layer_source: Arc::new(MockBaseLayerSourceTrait::new()),
+        reader,
+        writer,
+        sequencer_pub_key: None,
+        reverted_blocks_sender: broadcast::channel(2).0,
+    };
+    let mut first_subscriber = gen_state_sync.subscribe_to_reverted_blocks();
+    let mut second_subscriber = gen_state_sync.subscribe_to_reverted_blocks();
+
+    gen_state_sync.revert_block(BlockNumber(1)).unwrap();
+    gen_state_sync.revert_block(BlockNumber(0)).unwrap();
+
+    for subscriber in [&mut first_subscriber, &mut second_subscriber] {
+        for block_number in [BlockNumber(1), BlockNumber(0)] {
+            let reverted_block = subscriber.try_recv().unwrap();
+            assert_eq!(reverted_block.block_number, block_number);
+            assert_eq!(reverted_block.header.block_hash, BlockHash(block_number.0.into()));
+        }
+        assert_matches!(subscriber.try_recv(), Err(broadcast::error::TryRecvError::Empty));
+    }
+}
+
 // Adds to the storage 'headers_num' headers.
 fn add_headers(headers_num: u64, writer: &mut StorageWriter) {
     for i in 0..headers_num {
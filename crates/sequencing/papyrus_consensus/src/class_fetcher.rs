@@ -0,0 +1,168 @@
+//! Fetching the classes that proposals declare from the node's peers.
+//!
+//! A validator may receive a proposal with a declare transaction of a class it hasn't stored yet.
+//! The [`ClassFetcher`] asks the peers for such classes by their hash, and accepts a class only if
+//! it's within the size limit and its hash matches the requested one. Fetched classes are cached,
+//! so they're available to the validation of the rest of the proposal.
+
+#[cfg(test)]
+#[path = "class_fetcher_test.rs"]
+mod class_fetcher_test;
+
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use futures::channel::mpsc;
+use futures::StreamExt;
+use lru::LruCache;
+use papyrus_common::class_hash::calculate_class_hash;
+use papyrus_common::pending_classes::ApiContractClass;
+use papyrus_network::network_manager::{ReputationModifier, SqmrClientSender};
+use papyrus_protobuf::converters::ProtobufConversionError;
+use papyrus_protobuf::sync::{ClassHashesQuery, DataOrFin};
+use papyrus_storage::class::ClassStorageReader;
+use papyrus_storage::state::StateStorageReader;
+use papyrus_storage::{StorageError, StorageReader};
+use starknet_api::core::ClassHash;
+use starknet_api::state::{ContractClass, StateNumber};
+use tracing::debug;
+
+/// The sender of queries for classes by their hashes.
+pub type ClassByHashSender =
+    SqmrClientSender<ClassHashesQuery, DataOrFin<(ApiContractClass, ClassHash)>>;
+
+const FETCHED_CLASSES_CACHE_SIZE: usize = 100;
+const NETWORK_DATA_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClassFetcherError {
+    #[error("The peers didn't return the class {0}.")]
+    ClassNotFound(ClassHash),
+    #[error(
+        "Received the class {class_hash} with a Sierra program of length {length}, which is above \
+         the limit of {max_length}."
+    )]
+    ClassTooLarge { class_hash: ClassHash, length: usize, max_length: usize },
+    #[error("Asked for the class {expected} and received a class with the hash {actual}.")]
+    WrongClassHash { expected: ClassHash, actual: ClassHash },
+    #[error("Received a deprecated class for {0}. Only Sierra classes can be fetched.")]
+    DeprecatedClass(ClassHash),
+    #[error(transparent)]
+    NetworkTimeout(#[from] tokio::time::error::Elapsed),
+    #[error(transparent)]
+    ProtobufConversionError(#[from] ProtobufConversionError),
+    #[error(transparent)]
+    SendError(#[from] mpsc::SendError),
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+}
+
+/// Gets Sierra classes from the storage, or from the peers if they're not stored.
+pub struct ClassFetcher {
+    storage_reader: StorageReader,
+    class_by_hash_sender: tokio::sync::Mutex<ClassByHashSender>,
+    fetched_classes: Mutex<LruCache<ClassHash, ContractClass>>,
+    max_sierra_program_length: usize,
+}
+
+impl ClassFetcher {
+    pub fn new(
+        storage_reader: StorageReader,
+        class_by_hash_sender: ClassByHashSender,
+        max_sierra_program_length: usize,
+    ) -> Self {
+        Self {
+            storage_reader,
+            class_by_hash_sender: tokio::sync::Mutex::new(class_by_hash_sender),
+            fetched_classes: Mutex::new(LruCache::new(
+                NonZeroUsize::new(FETCHED_CLASSES_CACHE_SIZE)
+                    .expect("The cache size should be positive."),
+            )),
+            max_sierra_program_length,
+        }
+    }
+
+    /// Returns the class with the given hash. If the class isn't in the storage or was fetched
+    /// before, it's fetched from the peers.
+    pub async fn get_class(
+        &self,
+        class_hash: ClassHash,
+    ) -> Result<ContractClass, ClassFetcherError> {
+        if let Some(class) = self.get_known_class(class_hash)? {
+            return Ok(class);
+        }
+        debug!("Fetching the class {class_hash} from the peers.");
+        let class = self.fetch_class(class_hash).await?;
+        self.fetched_classes
+            .lock()
+            .expect("Lock should not be poisoned")
+            .put(class_hash, class.clone());
+        Ok(class)
+    }
+
+    fn get_known_class(
+        &self,
+        class_hash: ClassHash,
+    ) -> Result<Option<ContractClass>, StorageError> {
+        if let Some(class) =
+            self.fetched_classes.lock().expect("Lock should not be poisoned").get(&class_hash)
+        {
+            return Ok(Some(class.clone()));
+        }
+        let txn = self.storage_reader.begin_ro_txn()?;
+        let state_number = StateNumber(txn.get_class_marker()?);
+        txn.get_state_reader()?.get_class_definition_at(state_number, &class_hash)
+    }
+
+    async fn fetch_class(&self, class_hash: ClassHash) -> Result<ContractClass, ClassFetcherError> {
+        let mut client_response_manager = self
+            .class_by_hash_sender
+            .lock()
+            .await
+            .send_new_query(ClassHashesQuery(vec![class_hash]))
+            .await?;
+        let response = tokio::time::timeout(NETWORK_DATA_TIMEOUT, client_response_manager.next())
+            .await?
+            .ok_or(ClassFetcherError::ClassNotFound(class_hash))?;
+        let Some((class, _)) = response?.0 else {
+            return Err(ClassFetcherError::ClassNotFound(class_hash));
+        };
+
+        match self.verify_class(class_hash, class) {
+            Ok(class) => Ok(class),
+            Err(error) => {
+                client_response_manager.report_peer(ReputationModifier::Medium);
+                Err(error)
+            }
+        }
+    }
+
+    fn verify_class(
+        &self,
+        class_hash: ClassHash,
+        class: ApiContractClass,
+    ) -> Result<ContractClass, ClassFetcherError> {
+        let ApiContractClass::ContractClass(class) = class else {
+            return Err(ClassFetcherError::DeprecatedClass(class_hash));
+        };
+        // The size is checked first, so the hash is calculated only for classes that aren't too
+        // large.
+        let length = class.sierra_program.len();
+        if length > self.max_sierra_program_length {
+            return Err(ClassFetcherError::ClassTooLarge {
+                class_hash,
+                length,
+                max_length: self.max_sierra_program_length,
+            });
+        }
+        let calculated_class_hash = calculate_class_hash(&class);
+        if calculated_class_hash != class_hash {
+            return Err(ClassFetcherError::WrongClassHash {
+                expected: class_hash,
+                actual: calculated_class_hash,
+            });
+        }
+        Ok(class)
+    }
+}
@@ -0,0 +1,162 @@
+use assert_matches::assert_matches;
+use futures::{FutureExt, StreamExt};
+use papyrus_common::class_hash::calculate_class_hash;
+use papyrus_common::pending_classes::ApiContractClass;
+use papyrus_network::network_manager::test_utils::{
+    mock_register_sqmr_protocol_client,
+    MockClientResponsesManager,
+};
+use papyrus_network::network_manager::GenericReceiver;
+use papyrus_protobuf::sync::{ClassHashesQuery, DataOrFin};
+use papyrus_storage::class::ClassStorageWriter;
+use papyrus_storage::state::StateStorageWriter;
+use papyrus_storage::test_utils::get_test_storage;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ClassHash, CompiledClassHash};
+use starknet_api::state::{ContractClass, ThinStateDiff};
+use starknet_types_core::felt::Felt;
+
+use super::{ClassFetcher, ClassFetcherError};
+
+const BUFFER_SIZE: usize = 10;
+const MAX_SIERRA_PROGRAM_LENGTH: usize = 2;
+
+type ClassByHashReceiver = GenericReceiver<
+    MockClientResponsesManager<ClassHashesQuery, DataOrFin<(ApiContractClass, ClassHash)>>,
+>;
+
+fn get_class(sierra_program_length: u8) -> (ClassHash, ContractClass) {
+    let class = ContractClass {
+        sierra_program: (0..sierra_program_length).map(Felt::from).collect(),
+        ..Default::default()
+    };
+    (calculate_class_hash(&class), class)
+}
+
+// Answers the next query for a class with the given class, and returns the query's response
+// manager. The fetcher reads only the first response, so no fin is sent.
+async fn respond_with_class(
+    class_by_hash_receiver: &mut ClassByHashReceiver,
+    class_hash: ClassHash,
+    class: ContractClass,
+) -> MockClientResponsesManager<ClassHashesQuery, DataOrFin<(ApiContractClass, ClassHash)>> {
+    let mut mock_responses_manager = class_by_hash_receiver.next().await.unwrap();
+    assert_eq!(*mock_responses_manager.query(), Ok(ClassHashesQuery(vec![class_hash])));
+    mock_responses_manager
+        .send_response(DataOrFin(Some((ApiContractClass::ContractClass(class), class_hash))))
+        .await
+        .unwrap();
+    mock_responses_manager
+}
+
+#[tokio::test]
+async fn fetch_class_from_peers() {
+    let ((storage_reader, _storage_writer), _temp_dir) = get_test_storage();
+    let (class_by_hash_sender, mut class_by_hash_receiver) =
+        mock_register_sqmr_protocol_client(BUFFER_SIZE);
+    let class_fetcher =
+        ClassFetcher::new(storage_reader, class_by_hash_sender, MAX_SIERRA_PROGRAM_LENGTH);
+    let (class_hash, class) = get_class(2);
+
+    let (result, _) = tokio::join!(
+        class_fetcher.get_class(class_hash),
+        respond_with_class(&mut class_by_hash_receiver, class_hash, class.clone()),
+    );
+    assert_eq!(result.unwrap(), class);
+
+    // The fetched class is cached, so it isn't fetched again.
+    assert_eq!(class_fetcher.get_class(class_hash).await.unwrap(), class);
+    assert!(class_by_hash_receiver.next().now_or_never().is_none());
+}
+
+#[tokio::test]
+async fn get_class_from_storage() {
+    let ((storage_reader, mut storage_writer), _temp_dir) = get_test_storage();
+    let (class_by_hash_sender, mut class_by_hash_receiver) =
+        mock_register_sqmr_protocol_client(BUFFER_SIZE);
+    let class_fetcher =
+        ClassFetcher::new(storage_reader, class_by_hash_sender, MAX_SIERRA_PROGRAM_LENGTH);
+    let (class_hash, class) = get_class(2);
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_state_diff(
+            BlockNumber(0),
+            ThinStateDiff {
+                declared_classes: [(class_hash, CompiledClassHash::default())].into(),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .append_classes(BlockNumber(0), &[(class_hash, &class)], &[])
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    assert_eq!(class_fetcher.get_class(class_hash).await.unwrap(), class);
+    assert!(class_by_hash_receiver.next().now_or_never().is_none());
+}
+
+#[tokio::test]
+async fn fetched_class_with_wrong_hash_is_rejected() {
+    let ((storage_reader, _storage_writer), _temp_dir) = get_test_storage();
+    let (class_by_hash_sender, mut class_by_hash_receiver) =
+        mock_register_sqmr_protocol_client(BUFFER_SIZE);
+    let class_fetcher =
+        ClassFetcher::new(storage_reader, class_by_hash_sender, MAX_SIERRA_PROGRAM_LENGTH);
+    let (class_hash, _) = get_class(2);
+    let (other_class_hash, other_class) = get_class(1);
+
+    let (result, mock_responses_manager) = tokio::join!(
+        class_fetcher.get_class(class_hash),
+        respond_with_class(&mut class_by_hash_receiver, class_hash, other_class),
+    );
+    assert_matches!(
+        result,
+        Err(ClassFetcherError::WrongClassHash { expected, actual })
+            if expected == class_hash && actual == other_class_hash
+    );
+    mock_responses_manager.assert_reported().await;
+}
+
+#[tokio::test]
+async fn fetched_class_above_size_limit_is_rejected() {
+    let ((storage_reader, _storage_writer), _temp_dir) = get_test_storage();
+    let (class_by_hash_sender, mut class_by_hash_receiver) =
+        mock_register_sqmr_protocol_client(BUFFER_SIZE);
+    let class_fetcher =
+        ClassFetcher::new(storage_reader, class_by_hash_sender, MAX_SIERRA_PROGRAM_LENGTH);
+    let (class_hash, class) = get_class(3);
+
+    let (result, mock_responses_manager) = tokio::join!(
+        class_fetcher.get_class(class_hash),
+        respond_with_class(&mut class_by_hash_receiver, class_hash, class),
+    );
+    assert_matches!(
+        result,
+        Err(ClassFetcherError::ClassTooLarge {
+            length: 3,
+            max_length: MAX_SIERRA_PROGRAM_LENGTH,
+            ..
+        })
+    );
+    mock_responses_manager.assert_reported().await;
+}
+
+#[tokio::test]
+async fn class_missing_in_peers() {
+    let ((storage_reader, _storage_writer), _temp_dir) = get_test_storage();
+    let (class_by_hash_sender, mut class_by_hash_receiver) =
+        mock_register_sqmr_protocol_client(BUFFER_SIZE);
+    let class_fetcher =
+        ClassFetcher::new(storage_reader, class_by_hash_sender, MAX_SIERRA_PROGRAM_LENGTH);
+    let (class_hash, _) = get_class(2);
+
+    let respond_with_fin = async {
+        let mut mock_responses_manager = class_by_hash_receiver.next().await.unwrap();
+        mock_responses_manager.send_response(DataOrFin(None)).await.unwrap();
+        mock_responses_manager
+    };
+    let (result, _) = tokio::join!(class_fetcher.get_class(class_hash), respond_with_fin);
+    assert_matches!(result, Err(ClassFetcherError::ClassNotFound(hash)) if hash == class_hash);
+}
@@ -16,14 +16,16 @@ use papyrus_config::dumping::{
     ser_required_param,
     SerializeConfig,
 };
+use papyrus_config::validators::cross_param_validation_error;
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializationType, SerializedParam};
 use serde::{Deserialize, Serialize};
 use starknet_api::block::BlockNumber;
+use validator::{Validate, ValidationError};
 
 use super::types::ValidatorId;
 
 /// Configuration for consensus.
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Validate)]
 pub struct ConsensusConfig {
     /// The validator ID of the node.
     pub validator_id: ValidatorId,
@@ -37,7 +39,17 @@ pub struct ConsensusConfig {
     /// The delay (seconds) before starting consensus to give time for network peering.
     #[serde(deserialize_with = "deserialize_seconds_to_duration")]
     pub consensus_delay: Duration,
+    /// The maximal length of the Sierra program of a class that a proposal declares and is
+    /// fetched from the peers.
+    pub max_fetched_sierra_program_length: usize,
+    /// If true and the node isn't a validator, it doesn't validate proposals, and accepts a block
+    /// once a quorum of the validators precommitted it.
+    pub trust_finality: bool,
+    /// The maximal number of decided blocks that consensus proceeds past before they are
+    /// committed. If 0, consensus waits for the commit of each decided block.
+    pub max_pending_commits: usize,
     /// Timeouts configuration for consensus.
+    #[validate]
     pub timeouts: TimeoutsConfig,
     /// Test configuration for consensus.
     pub test: Option<ConsensusTestConfig>,
@@ -76,6 +88,27 @@ impl SerializeConfig for ConsensusConfig {
                 "Delay (seconds) before starting consensus to give time for network peering.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "max_fetched_sierra_program_length",
+                &self.max_fetched_sierra_program_length,
+                "The maximal length of the Sierra program of a class that a proposal declares and \
+                 is fetched from the peers.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "trust_finality",
+                &self.trust_finality,
+                "If true and the node isn't a validator, it doesn't validate proposals, and \
+                 accepts a block once a quorum of the validators precommitted it.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_pending_commits",
+                &self.max_pending_commits,
+                "The maximal number of decided blocks that consensus proceeds past before they \
+                 are committed. If 0, consensus waits for the commit of each decided block.",
+                ParamPrivacyInput::Public,
+            ),
         ]);
         config.extend(append_sub_config_name(self.timeouts.dump(), "timeouts"));
         config.extend(ser_optional_sub_config(&self.test, "test"));
@@ -91,6 +124,9 @@ impl Default for ConsensusConfig {
             start_height: BlockNumber::default(),
             num_validators: 4,
             consensus_delay: Duration::from_secs(5),
+            max_fetched_sierra_program_length: 81920,
+            trust_finality: false,
+            max_pending_commits: 1,
             timeouts: TimeoutsConfig::default(),
             test: None,
         }
@@ -162,7 +198,8 @@ impl Default for ConsensusTestConfig {
 }
 
 /// Configuration for consensus timeouts.
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Validate)]
+#[validate(schema(function = "validate_timeouts_order", skip_on_field_errors = false))]
 pub struct TimeoutsConfig {
     /// The timeout for a proposal.
     #[serde(deserialize_with = "deserialize_float_seconds_to_duration")]
@@ -200,6 +237,29 @@ impl SerializeConfig for TimeoutsConfig {
     }
 }
 
+/// Validates that a proposal, which carries the block, is given at least as long as the votes on
+/// it.
+fn validate_timeouts_order(timeouts: &TimeoutsConfig) -> Result<(), ValidationError> {
+    let longer_vote_timeouts: Vec<&str> = [
+        ("prevote_timeout", timeouts.prevote_timeout),
+        ("precommit_timeout", timeouts.precommit_timeout),
+    ]
+    .into_iter()
+    .filter(|(_, timeout)| *timeout > timeouts.proposal_timeout)
+    .map(|(param_path, _)| param_path)
+    .collect();
+    if !longer_vote_timeouts.is_empty() {
+        let param_paths: Vec<&str> =
+            std::iter::once("proposal_timeout").chain(longer_vote_timeouts).collect();
+        return Err(cross_param_validation_error(
+            "timeouts out of order",
+            &param_paths,
+            "The proposal timeout must not be shorter than the vote timeouts.".to_owned(),
+        ));
+    }
+    Ok(())
+}
+
 impl Default for TimeoutsConfig {
     fn default() -> Self {
         Self {
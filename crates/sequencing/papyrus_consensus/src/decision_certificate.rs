@@ -0,0 +1,141 @@
+//! Certificates of the decisions of consensus.
+//!
+//! A decision is certified by the precommits of a quorum of the validators for the decided block.
+//! A node that isn't a validator and trusts the finality of consensus accepts a block once it holds
+//! a valid certificate for it, instead of validating the block's content itself.
+
+#[cfg(test)]
+#[path = "decision_certificate_test.rs"]
+mod decision_certificate_test;
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+use papyrus_protobuf::consensus::{Vote, VoteType};
+use starknet_api::block::{BlockHash, BlockNumber};
+use tracing::debug;
+
+use crate::types::{Round, ValidatorId};
+
+#[derive(thiserror::Error, PartialEq, Debug)]
+pub enum DecisionCertificateError {
+    #[error("The vote {0:?} isn't a precommit.")]
+    NotPrecommit(Vote),
+    #[error(
+        "The precommit {vote:?} isn't for block {block_hash:?} at height {height} and round \
+         {round}."
+    )]
+    MismatchedPrecommit { vote: Vote, height: BlockNumber, round: Round, block_hash: BlockHash },
+    #[error("The precommit {0:?} is of a voter that isn't a validator.")]
+    UnknownVoter(Vote),
+    #[error("The validator {0:?} precommitted more than once.")]
+    DuplicateVoter(ValidatorId),
+    #[error("{num_precommits} precommits don't form a quorum of {quorum}.")]
+    NoQuorum { num_precommits: usize, quorum: usize },
+}
+
+/// The precommits of a quorum of the validators for a block, which prove that consensus decided on
+/// it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecisionCertificate {
+    pub height: BlockNumber,
+    pub round: Round,
+    pub block_hash: BlockHash,
+    pub precommits: Vec<Vote>,
+}
+
+impl DecisionCertificate {
+    /// Checks that the precommits are of distinct validators, are all for the certified block, and
+    /// form a quorum (>2/3) of the validators.
+    // TODO(matan): Verify the signatures of the precommits once votes are signed. Until then, a
+    // certificate is only as trustworthy as the peers that relayed its precommits.
+    pub fn verify(&self, validators: &[ValidatorId]) -> Result<(), DecisionCertificateError> {
+        let mut voters = HashSet::new();
+        for vote in &self.precommits {
+            if vote.vote_type != VoteType::Precommit {
+                return Err(DecisionCertificateError::NotPrecommit(vote.clone()));
+            }
+            if vote.height != self.height.0
+                || vote.round != self.round
+                || vote.block_hash != Some(self.block_hash)
+            {
+                return Err(DecisionCertificateError::MismatchedPrecommit {
+                    vote: vote.clone(),
+                    height: self.height,
+                    round: self.round,
+                    block_hash: self.block_hash,
+                });
+            }
+            if !validators.contains(&vote.voter) {
+                return Err(DecisionCertificateError::UnknownVoter(vote.clone()));
+            }
+            if !voters.insert(vote.voter) {
+                return Err(DecisionCertificateError::DuplicateVoter(vote.voter));
+            }
+        }
+        let quorum = quorum_size(validators.len());
+        if voters.len() < quorum {
+            return Err(DecisionCertificateError::NoQuorum {
+                num_precommits: voters.len(),
+                quorum,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Collects the precommits of a height until a quorum of them certifies a block.
+pub(crate) struct PrecommitCollector {
+    height: BlockNumber,
+    validators: Vec<ValidatorId>,
+    precommits: HashMap<(Round, ValidatorId), Vote>,
+}
+
+impl PrecommitCollector {
+    pub(crate) fn new(height: BlockNumber, validators: Vec<ValidatorId>) -> Self {
+        Self { height, validators, precommits: HashMap::new() }
+    }
+
+    /// Adds a precommit of a validator. Returns the certificate of the block it's for if the
+    /// precommits for this block now form a quorum.
+    pub(crate) fn add_precommit(&mut self, vote: Vote) -> Option<DecisionCertificate> {
+        if vote.vote_type != VoteType::Precommit
+            || vote.height != self.height.0
+            || !self.validators.contains(&vote.voter)
+        {
+            debug!("Ignoring vote that can't be part of a decision certificate: {vote:?}");
+            return None;
+        }
+        match self.precommits.entry((vote.round, vote.voter)) {
+            Entry::Vacant(entry) => {
+                entry.insert(vote.clone());
+            }
+            Entry::Occupied(entry) => {
+                if entry.get().block_hash != vote.block_hash {
+                    debug!("Ignoring conflicting precommit. Old: {:?}, new: {vote:?}", entry.get());
+                }
+                return None;
+            }
+        }
+        // NIL precommits don't certify any block.
+        let block_hash = vote.block_hash?;
+
+        let precommits = self
+            .validators
+            .iter()
+            .filter_map(|validator| {
+                let precommit = self.precommits.get(&(vote.round, *validator))?;
+                (precommit.block_hash == Some(block_hash)).then(|| precommit.clone())
+            })
+            .collect();
+        let certificate =
+            DecisionCertificate { height: self.height, round: vote.round, block_hash, precommits };
+        certificate.verify(&self.validators).ok()?;
+        Some(certificate)
+    }
+}
+
+// TODO(matan): Use the validators' weights once they're supported, as in the state machine.
+fn quorum_size(num_validators: usize) -> usize {
+    2 * num_validators / 3 + 1
+}
@@ -0,0 +1,140 @@
+use lazy_static::lazy_static;
+use papyrus_protobuf::consensus::{Vote, VoteType};
+use starknet_api::block::{BlockHash, BlockNumber};
+use starknet_types_core::felt::Felt;
+use test_case::test_case;
+
+use super::{DecisionCertificate, DecisionCertificateError, PrecommitCollector};
+use crate::types::ValidatorId;
+
+const HEIGHT: BlockNumber = BlockNumber(1);
+const ROUND: u32 = 0;
+const BLOCK_HASH: BlockHash = BlockHash(Felt::ONE);
+
+lazy_static! {
+    static ref VALIDATORS: Vec<ValidatorId> = (0..4_u32).map(ValidatorId::from).collect();
+}
+
+fn precommit(block_hash: Option<BlockHash>, round: u32, voter: ValidatorId) -> Vote {
+    Vote { vote_type: VoteType::Precommit, height: HEIGHT.0, round, block_hash, voter }
+}
+
+fn certificate(precommits: Vec<Vote>) -> DecisionCertificate {
+    DecisionCertificate { height: HEIGHT, round: ROUND, block_hash: BLOCK_HASH, precommits }
+}
+
+#[test_case(3; "quorum")]
+#[test_case(4; "all validators")]
+fn verify_valid_certificate(num_precommits: usize) {
+    let precommits = VALIDATORS
+        .iter()
+        .take(num_precommits)
+        .map(|validator| precommit(Some(BLOCK_HASH), ROUND, *validator))
+        .collect();
+    assert_eq!(certificate(precommits).verify(&VALIDATORS), Ok(()));
+}
+
+#[test]
+fn verify_certificate_without_quorum() {
+    let precommits = VALIDATORS
+        .iter()
+        .take(2)
+        .map(|validator| precommit(Some(BLOCK_HASH), ROUND, *validator))
+        .collect();
+    assert_eq!(
+        certificate(precommits).verify(&VALIDATORS),
+        Err(DecisionCertificateError::NoQuorum { num_precommits: 2, quorum: 3 })
+    );
+}
+
+#[test]
+fn verify_certificate_with_duplicate_voter() {
+    let precommits = vec![
+        precommit(Some(BLOCK_HASH), ROUND, VALIDATORS[0]),
+        precommit(Some(BLOCK_HASH), ROUND, VALIDATORS[1]),
+        precommit(Some(BLOCK_HASH), ROUND, VALIDATORS[1]),
+    ];
+    assert_eq!(
+        certificate(precommits).verify(&VALIDATORS),
+        Err(DecisionCertificateError::DuplicateVoter(VALIDATORS[1]))
+    );
+}
+
+#[test]
+fn verify_certificate_with_unknown_voter() {
+    let unknown_voter = precommit(Some(BLOCK_HASH), ROUND, ValidatorId::from(100_u32));
+    let precommits = vec![
+        precommit(Some(BLOCK_HASH), ROUND, VALIDATORS[0]),
+        precommit(Some(BLOCK_HASH), ROUND, VALIDATORS[1]),
+        unknown_voter.clone(),
+    ];
+    assert_eq!(
+        certificate(precommits).verify(&VALIDATORS),
+        Err(DecisionCertificateError::UnknownVoter(unknown_voter))
+    );
+}
+
+#[test_case(precommit(Some(BlockHash(Felt::TWO)), ROUND, VALIDATORS[2]); "other block")]
+#[test_case(precommit(None, ROUND, VALIDATORS[2]); "nil")]
+#[test_case(precommit(Some(BLOCK_HASH), ROUND + 1, VALIDATORS[2]); "other round")]
+fn verify_certificate_with_mismatched_precommit(mismatched_precommit: Vote) {
+    let precommits = vec![
+        precommit(Some(BLOCK_HASH), ROUND, VALIDATORS[0]),
+        precommit(Some(BLOCK_HASH), ROUND, VALIDATORS[1]),
+        mismatched_precommit.clone(),
+    ];
+    assert_eq!(
+        certificate(precommits).verify(&VALIDATORS),
+        Err(DecisionCertificateError::MismatchedPrecommit {
+            vote: mismatched_precommit,
+            height: HEIGHT,
+            round: ROUND,
+            block_hash: BLOCK_HASH,
+        })
+    );
+}
+
+#[test]
+fn verify_certificate_with_prevote() {
+    let prevote =
+        Vote { vote_type: VoteType::Prevote, ..precommit(Some(BLOCK_HASH), ROUND, VALIDATORS[0]) };
+    assert_eq!(
+        certificate(vec![prevote.clone()]).verify(&VALIDATORS),
+        Err(DecisionCertificateError::NotPrecommit(prevote))
+    );
+}
+
+#[test]
+fn collect_precommits_until_quorum() {
+    let mut collector = PrecommitCollector::new(HEIGHT, VALIDATORS.clone());
+
+    // Votes that don't count towards the quorum.
+    assert!(collector.add_precommit(precommit(None, ROUND, VALIDATORS[0])).is_none());
+    assert!(
+        collector.add_precommit(precommit(Some(BLOCK_HASH), ROUND + 1, VALIDATORS[0])).is_none()
+    );
+    assert!(
+        collector
+            .add_precommit(precommit(Some(BLOCK_HASH), ROUND, ValidatorId::from(100_u32)))
+            .is_none()
+    );
+
+    assert!(collector.add_precommit(precommit(Some(BLOCK_HASH), ROUND, VALIDATORS[1])).is_none());
+    assert!(collector.add_precommit(precommit(Some(BLOCK_HASH), ROUND, VALIDATORS[2])).is_none());
+    // A replayed precommit isn't counted twice.
+    assert!(collector.add_precommit(precommit(Some(BLOCK_HASH), ROUND, VALIDATORS[2])).is_none());
+    let certificate =
+        collector.add_precommit(precommit(Some(BLOCK_HASH), ROUND, VALIDATORS[3])).unwrap();
+    assert_eq!(
+        certificate,
+        DecisionCertificate {
+            height: HEIGHT,
+            round: ROUND,
+            block_hash: BLOCK_HASH,
+            precommits: VALIDATORS[1..]
+                .iter()
+                .map(|validator| precommit(Some(BLOCK_HASH), ROUND, *validator))
+                .collect(),
+        }
+    );
+}
@@ -3,11 +3,18 @@
 // TODO(Matan): fix #[allow(missing_docs)].
 //! A consensus implementation for a [`Starknet`](https://www.starknet.io/) node.
 
+#[allow(missing_docs)]
+pub mod class_fetcher;
 pub mod config;
+#[allow(missing_docs)]
+pub mod decision_certificate;
 pub mod manager;
 #[allow(missing_docs)]
 pub mod papyrus_consensus_context;
 #[allow(missing_docs)]
+pub mod proposal_builder;
+pub(crate) mod proposal_demultiplexer;
+#[allow(missing_docs)]
 pub mod simulation_network_receiver;
 #[allow(missing_docs)]
 pub mod single_height_consensus;
@@ -17,5 +24,6 @@ pub mod state_machine;
 pub(crate) mod test_utils;
 #[allow(missing_docs)]
 pub mod types;
+pub mod vote_keeper;
 
 pub use manager::{run_consensus, ProposalWrapper};
@@ -4,20 +4,29 @@
 #[path = "manager_test.rs"]
 mod manager_test;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
 use futures::channel::{mpsc, oneshot};
 use futures::stream::FuturesUnordered;
 use futures::{Stream, StreamExt};
-use papyrus_common::metrics::{PAPYRUS_CONSENSUS_HEIGHT, PAPYRUS_CONSENSUS_SYNC_COUNT};
-use papyrus_network::network_manager::BroadcastedMessageManager;
-use papyrus_protobuf::consensus::{ConsensusMessage, Proposal};
+use papyrus_common::metrics::{
+    COMPONENT_LABEL,
+    PAPYRUS_CONSENSUS_HEIGHT,
+    PAPYRUS_CONSENSUS_PENDING_COMMITS,
+    PAPYRUS_CONSENSUS_SYNC_COUNT,
+};
+use papyrus_network::network_manager::{BroadcastedMessageManager, ReputationModifier};
+use papyrus_protobuf::consensus::{ConsensusMessage, Proposal, VoteType};
 use papyrus_protobuf::converters::ProtobufConversionError;
 use starknet_api::block::{BlockHash, BlockNumber};
-use tracing::{debug, info, instrument};
+use tracing::{debug, info, instrument, warn};
 
 use crate::config::TimeoutsConfig;
+use crate::decision_certificate::{DecisionCertificate, PrecommitCollector};
+use crate::proposal_demultiplexer::ProposalDemultiplexer;
 use crate::single_height_consensus::{ShcReturn, ShcTask, SingleHeightConsensus};
 use crate::types::{
     ConsensusBlock,
@@ -25,23 +34,39 @@ use crate::types::{
     ConsensusError,
     Decision,
     ProposalInit,
+    Round,
     ValidatorId,
 };
 
+// The component label of the metrics of consensus.
+const CONSENSUS_COMPONENT: &str = "consensus";
+
 // TODO(dvir): add test for this.
+/// Runs consensus from `start_height` on. The height consensus is currently working on is
+/// published to `shared_height`.
+///
+/// If `trust_finality` is set and the node isn't a validator of a height, it doesn't validate the
+/// height's proposals, and accepts a block once a quorum of the validators precommitted it.
+///
+/// Consensus proceeds to the next height while the decided block is committed, as long as at most
+/// `max_pending_commits` decided blocks aren't committed yet. The pending commits are waited for
+/// before consensus syncs past them, and before it stops on an error.
 #[instrument(skip_all, level = "info")]
-#[allow(missing_docs)]
+#[allow(clippy::too_many_arguments)]
 pub async fn run_consensus<BlockT, ContextT, NetworkReceiverT, SyncReceiverT>(
     mut context: ContextT,
     start_height: BlockNumber,
     validator_id: ValidatorId,
     consensus_delay: Duration,
     timeouts: TimeoutsConfig,
+    trust_finality: bool,
+    max_pending_commits: usize,
     mut network_receiver: NetworkReceiverT,
     mut sync_receiver: SyncReceiverT,
+    shared_height: Arc<AtomicU64>,
 ) -> Result<(), ConsensusError>
 where
-    BlockT: ConsensusBlock,
+    BlockT: ConsensusBlock + 'static,
     ContextT: ConsensusContext<Block = BlockT>,
     NetworkReceiverT: Stream<
             Item = (Result<ConsensusMessage, ProtobufConversionError>, BroadcastedMessageManager),
@@ -51,19 +76,28 @@ where
         Into<(ProposalInit, mpsc::Receiver<BlockT::ProposalChunk>, oneshot::Receiver<BlockHash>)>,
 {
     info!(
-        "Running consensus, start_height={}, validator_id={}, consensus_delay={}, timeouts={:?}",
+        "Running consensus, start_height={}, validator_id={}, consensus_delay={}, timeouts={:?}, \
+         trust_finality={}, max_pending_commits={}",
         start_height,
         validator_id,
         consensus_delay.as_secs(),
-        timeouts
+        timeouts,
+        trust_finality,
+        max_pending_commits
     );
 
     // Add a short delay to allow peers to connect and avoid "InsufficientPeers" error
     tokio::time::sleep(consensus_delay).await;
     let mut current_height = start_height;
-    let mut manager = MultiHeightManager::new(validator_id, timeouts);
+    let mut manager = MultiHeightManager::new(validator_id, timeouts, trust_finality);
+    let mut pending_commits = PendingCommits::new(max_pending_commits);
     loop {
-        metrics::gauge!(PAPYRUS_CONSENSUS_HEIGHT, current_height.0 as f64);
+        metrics::gauge!(
+            PAPYRUS_CONSENSUS_HEIGHT,
+            current_height.0 as f64,
+            COMPONENT_LABEL => CONSENSUS_COMPONENT
+        );
+        shared_height.store(current_height.0, Ordering::Relaxed);
 
         let run_height = manager.run_height(&mut context, current_height, &mut network_receiver);
 
@@ -73,18 +107,108 @@ where
         // we are certain to leave this height.
         tokio::select! {
             decision = run_height => {
-                let decision = decision?;
-                context.decision_reached(decision.block, decision.precommits).await?;
+                let decision = match decision {
+                    Ok(decision) => decision,
+                    Err(err) => return pending_commits.drain_and_fail(err).await,
+                };
+                let commit_receiver = match context
+                    .decision_reached(current_height, decision.block, decision.precommits)
+                    .await
+                {
+                    Ok(commit_receiver) => commit_receiver,
+                    Err(err) => return pending_commits.drain_and_fail(err).await,
+                };
+                pending_commits.push(current_height, commit_receiver).await?;
                 current_height = current_height.unchecked_next();
             },
             sync_height = sync_height(current_height, &mut sync_receiver) => {
-                metrics::increment_counter!(PAPYRUS_CONSENSUS_SYNC_COUNT);
-                current_height = sync_height?.unchecked_next();
+                metrics::increment_counter!(
+                    PAPYRUS_CONSENSUS_SYNC_COUNT,
+                    COMPONENT_LABEL => CONSENSUS_COMPONENT
+                );
+                let sync_height = match sync_height {
+                    Ok(sync_height) => sync_height,
+                    Err(err) => return pending_commits.drain_and_fail(err).await,
+                };
+                // The synced blocks follow the decided ones, which are committed first.
+                pending_commits.drain().await?;
+                current_height = sync_height.unchecked_next();
             }
         }
     }
 }
 
+// The decided blocks that aren't committed yet, by height. Consensus waits for the commits once
+// there are too many of them, so a slow commit holds consensus back instead of piling up blocks.
+#[derive(Debug)]
+struct PendingCommits {
+    max_pending_commits: usize,
+    commit_receivers: VecDeque<(BlockNumber, oneshot::Receiver<()>)>,
+}
+
+impl PendingCommits {
+    fn new(max_pending_commits: usize) -> Self {
+        Self { max_pending_commits, commit_receivers: VecDeque::new() }
+    }
+
+    // Adds the commit of a decided block, and waits for the earliest commits until at most
+    // `max_pending_commits` of them are pending.
+    async fn push(
+        &mut self,
+        height: BlockNumber,
+        commit_receiver: oneshot::Receiver<()>,
+    ) -> Result<(), ConsensusError> {
+        self.commit_receivers.push_back((height, commit_receiver));
+        // The blocks are committed by the order of their heights.
+        loop {
+            let too_many_pending = self.commit_receivers.len() > self.max_pending_commits;
+            let Some((height, commit_receiver)) = self.commit_receivers.front_mut() else {
+                break;
+            };
+            let height = *height;
+            if too_many_pending {
+                commit_receiver.await.map_err(|_| ConsensusError::CommitFailed(height))?;
+            } else {
+                match commit_receiver.try_recv() {
+                    Ok(Some(())) => {}
+                    Ok(None) => break,
+                    Err(_) => return Err(ConsensusError::CommitFailed(height)),
+                }
+            }
+            debug!("Committed the decided block of height {height}.");
+            self.commit_receivers.pop_front();
+        }
+        self.update_metric();
+        Ok(())
+    }
+
+    // Waits for all the pending commits, by the order of their heights.
+    async fn drain(&mut self) -> Result<(), ConsensusError> {
+        while let Some((height, commit_receiver)) = self.commit_receivers.pop_front() {
+            let commit_result = commit_receiver.await;
+            self.update_metric();
+            commit_result.map_err(|_| ConsensusError::CommitFailed(height))?;
+            debug!("Committed the decided block of height {height}.");
+        }
+        Ok(())
+    }
+
+    // Waits for all the pending commits before consensus stops on the given error, so the decided
+    // blocks aren't lost. A failed commit is reported instead of the error.
+    async fn drain_and_fail(&mut self, err: ConsensusError) -> Result<(), ConsensusError> {
+        self.drain().await?;
+        Err(err)
+    }
+
+    fn update_metric(&self) {
+        metrics::gauge!(
+            PAPYRUS_CONSENSUS_PENDING_COMMITS,
+            self.commit_receivers.len() as f64,
+            COMPONENT_LABEL => CONSENSUS_COMPONENT
+        );
+    }
+}
+
 // `Proposal` is defined in the protobuf crate so we can't implement `Into` for it because of the
 // orphan rule. This wrapper enables us to implement `Into` for the inner `Proposal`.
 #[allow(missing_docs)]
@@ -97,12 +221,13 @@ struct MultiHeightManager {
     validator_id: ValidatorId,
     cached_messages: BTreeMap<u64, Vec<ConsensusMessage>>,
     timeouts: TimeoutsConfig,
+    trust_finality: bool,
 }
 
 impl MultiHeightManager {
     /// Create a new consensus manager.
-    pub fn new(validator_id: ValidatorId, timeouts: TimeoutsConfig) -> Self {
-        Self { validator_id, cached_messages: BTreeMap::new(), timeouts }
+    pub fn new(validator_id: ValidatorId, timeouts: TimeoutsConfig, trust_finality: bool) -> Self {
+        Self { validator_id, cached_messages: BTreeMap::new(), timeouts, trust_finality }
     }
 
     /// Run the consensus algorithm for a single height.
@@ -117,7 +242,7 @@ impl MultiHeightManager {
         network_receiver: &mut NetworkReceiverT,
     ) -> Result<Decision<BlockT>, ConsensusError>
     where
-        BlockT: ConsensusBlock,
+        BlockT: ConsensusBlock + 'static,
         ContextT: ConsensusContext<Block = BlockT>,
         NetworkReceiverT: Stream<
                 Item = (
@@ -132,6 +257,10 @@ impl MultiHeightManager {
         )>,
     {
         let validators = context.validators(height).await;
+        if self.trust_finality && !validators.contains(&self.validator_id) {
+            info!("following consensus for height {height:?} with validator set {validators:?}");
+            return self.follow_height(context, height, validators, network_receiver).await;
+        }
         info!("running consensus for height {height:?} with validator set {validators:?}");
         let mut shc = SingleHeightConsensus::new(
             height,
@@ -140,6 +269,8 @@ impl MultiHeightManager {
             self.timeouts.clone(),
         );
         let mut shc_tasks = FuturesUnordered::new();
+        // Dropped once the height ends, which kills the validations of the proposals.
+        let mut proposals = ProposalDemultiplexer::new(height);
 
         match shc.start(context).await? {
             ShcReturn::Decision(decision) => return Ok(decision),
@@ -154,11 +285,14 @@ impl MultiHeightManager {
         loop {
             let shc_return = tokio::select! {
                 message = next_message(&mut current_height_messages, network_receiver) => {
-                    self.handle_message(context, height, &mut shc, message?).await?
+                    self.handle_message(context, height, &mut shc, &mut proposals, message?).await?
                 },
                 Some(shc_task) = shc_tasks.next() => {
                     shc.handle_event(context, shc_task.event).await?
                 },
+                proposal = proposals.next() => {
+                    shc.handle_proposal(context, proposal.init, proposal.block).await?
+                },
             };
 
             match shc_return {
@@ -172,16 +306,122 @@ impl MultiHeightManager {
         }
     }
 
+    // Follows a height without taking part in it: the proposals aren't validated, and the block of
+    // a proposal is accepted once the precommits for it form a decision certificate.
+    async fn follow_height<BlockT, ContextT, NetworkReceiverT>(
+        &mut self,
+        context: &mut ContextT,
+        height: BlockNumber,
+        validators: Vec<ValidatorId>,
+        network_receiver: &mut NetworkReceiverT,
+    ) -> Result<Decision<BlockT>, ConsensusError>
+    where
+        BlockT: ConsensusBlock,
+        ContextT: ConsensusContext<Block = BlockT>,
+        NetworkReceiverT: Stream<
+                Item = (
+                    Result<ConsensusMessage, ProtobufConversionError>,
+                    BroadcastedMessageManager,
+                ),
+            > + Unpin,
+        ProposalWrapper: Into<(
+            ProposalInit,
+            mpsc::Receiver<BlockT::ProposalChunk>,
+            oneshot::Receiver<BlockHash>,
+        )>,
+    {
+        let mut precommit_collector = PrecommitCollector::new(height, validators);
+        let mut proposals: HashMap<Round, (BlockHash, mpsc::Receiver<BlockT::ProposalChunk>)> =
+            HashMap::new();
+        let mut certificate: Option<DecisionCertificate> = None;
+        // The proposals waiting for their ProposalFin, which mustn't block the other messages.
+        let mut pending_fins = FuturesUnordered::new();
+
+        let mut current_height_messages = self.get_current_height_messages(height);
+        loop {
+            tokio::select! {
+                message = next_message(&mut current_height_messages, network_receiver) => {
+                    let Some(message) = self.filter_current_height(height, message?) else {
+                        continue;
+                    };
+                    match message {
+                        ConsensusMessage::Proposal(proposal) => {
+                            let (init, content_receiver, fin_receiver) =
+                                ProposalWrapper(proposal).into();
+                            let proposer_id = context.proposer(height, init.round);
+                            if init.proposer != proposer_id {
+                                let msg = format!(
+                                    "invalid proposer: expected {:?}, got {:?}",
+                                    proposer_id, init.proposer
+                                );
+                                return Err(ConsensusError::InvalidProposal(
+                                    proposer_id,
+                                    height,
+                                    msg,
+                                ));
+                            }
+                            pending_fins.push(async move {
+                                (init.round, fin_receiver.await, content_receiver)
+                            });
+                        }
+                        ConsensusMessage::Vote(vote) if vote.vote_type == VoteType::Precommit => {
+                            if let Some(new_certificate) = precommit_collector.add_precommit(vote)
+                            {
+                                debug!("Received a decision certificate: {new_certificate:?}");
+                                certificate.get_or_insert(new_certificate);
+                            }
+                        }
+                        ConsensusMessage::Vote(_) => {}
+                    }
+                },
+                Some((round, fin, content_receiver)) = pending_fins.next() => {
+                    let Ok(block_hash) = fin else {
+                        warn!("ProposalFin never received for round {round}, ignoring");
+                        continue;
+                    };
+                    if proposals.contains_key(&round) {
+                        warn!("Round {round} already has a proposal, ignoring");
+                        continue;
+                    }
+                    proposals.insert(round, (block_hash, content_receiver));
+                },
+            }
+
+            // The certified block is accepted once both the certificate and its proposal arrived.
+            let Some(certificate) = &certificate else {
+                continue;
+            };
+            match proposals.remove(&certificate.round) {
+                Some((block_hash, content)) if block_hash == certificate.block_hash => {
+                    let block = context
+                        .accept_certified_proposal(height, block_hash, content)
+                        .await
+                        .await?;
+                    return Ok(Decision { precommits: certificate.precommits.clone(), block });
+                }
+                Some((block_hash, _)) => {
+                    warn!(
+                        "The proposal of round {} is for block {block_hash:?}, while the \
+                         validators decided on {:?}.",
+                        certificate.round, certificate.block_hash
+                    );
+                }
+                None => {}
+            }
+        }
+    }
+
     // Handle a single consensus message.
     async fn handle_message<BlockT, ContextT>(
         &mut self,
         context: &mut ContextT,
         height: BlockNumber,
         shc: &mut SingleHeightConsensus<BlockT>,
+        proposals: &mut ProposalDemultiplexer<BlockT>,
         message: ConsensusMessage,
     ) -> Result<ShcReturn<BlockT>, ConsensusError>
     where
-        BlockT: ConsensusBlock,
+        BlockT: ConsensusBlock + 'static,
         ContextT: ConsensusContext<Block = BlockT>,
         ProposalWrapper: Into<(
             ProposalInit,
@@ -193,22 +433,27 @@ impl MultiHeightManager {
         // 1. Malicious - must be capped so a malicious peer can't DoS us.
         // 2. Parallel proposals - we may send/receive a proposal for (H+1, 0).
         // In general I think we will want to only cache (H+1, 0) messages.
-        if message.height() != height.0 {
-            debug!("Received a message for a different height. {:?}", message);
-            if message.height() > height.0 {
-                self.cached_messages.entry(message.height()).or_default().push(message);
-            }
+        let Some(message) = self.filter_current_height(height, message) else {
             return Ok(ShcReturn::Tasks(Vec::new()));
-        }
+        };
         match message {
             ConsensusMessage::Proposal(proposal) => {
                 // Special case due to fake streaming.
                 let (proposal_init, content_receiver, fin_receiver) =
                     ProposalWrapper(proposal).into();
-                let res = shc
-                    .handle_proposal(context, proposal_init, content_receiver, fin_receiver)
-                    .await?;
-                Ok(res)
+                // The proposal is handled by the SHC once its validation completes.
+                if shc.handle_proposal_init(context, &proposal_init)? {
+                    proposals
+                        .route(
+                            context,
+                            shc.current_round(),
+                            proposal_init,
+                            content_receiver,
+                            fin_receiver,
+                        )
+                        .await;
+                }
+                Ok(ShcReturn::Tasks(Vec::new()))
             }
             _ => {
                 let res = shc.handle_message(context, message).await?;
@@ -217,6 +462,23 @@ impl MultiHeightManager {
         }
     }
 
+    // Returns the message if it's for the given height. Otherwise, caches it if it's for a future
+    // height, and drops it if it's for a past one.
+    fn filter_current_height(
+        &mut self,
+        height: BlockNumber,
+        message: ConsensusMessage,
+    ) -> Option<ConsensusMessage> {
+        if message.height() == height.0 {
+            return Some(message);
+        }
+        debug!("Received a message for a different height. {:?}", message);
+        if message.height() > height.0 {
+            self.cached_messages.entry(message.height()).or_default().push(message);
+        }
+        None
+    }
+
     // Filters the cached messages:
     // - returns all of the current height messages.
     // - drops messages from earlier heights.
@@ -251,22 +513,36 @@ where
         return Ok(msg);
     }
 
-    let (msg, mut broadcasted_message_manager) =
-        network_receiver.next().await.ok_or_else(|| {
-            ConsensusError::InternalNetworkError(
-                "NetworkReceiver should never be closed".to_string(),
-            )
-        })?;
-    match msg {
-        // TODO(matan): Return report_sender for use in later errors by SHC.
-        Ok(msg) => {
-            broadcasted_message_manager.continue_propogation();
-            Ok(msg)
-        }
-        Err(e) => {
-            // Failed to parse consensus message
-            broadcasted_message_manager.report_peer();
-            Err(e.into())
+    loop {
+        let (msg, mut broadcasted_message_manager) =
+            network_receiver.next().await.ok_or_else(|| {
+                ConsensusError::InternalNetworkError(
+                    "NetworkReceiver should never be closed".to_string(),
+                )
+            })?;
+        match msg {
+            // TODO(matan): Return report_sender for use in later errors by SHC.
+            Ok(msg) => {
+                broadcasted_message_manager.continue_propogation();
+                return Ok(msg);
+            }
+            // The sender runs a different version of the node, e.g. during an upgrade of the
+            // network, so it isn't reported and its message is still propagated to its peers.
+            Err(ProtobufConversionError::IncompatibleProtocolVersion {
+                version,
+                supported_version,
+            }) => {
+                warn!(
+                    "Ignoring a consensus message of protocol version {version}, which is \
+                     incompatible with version {supported_version}."
+                );
+                broadcasted_message_manager.continue_propogation();
+            }
+            Err(e) => {
+                // Failed to parse consensus message
+                broadcasted_message_manager.report_peer(ReputationModifier::Medium);
+                return Err(e.into());
+            }
         }
     }
 }
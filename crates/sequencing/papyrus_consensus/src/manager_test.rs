@@ -1,3 +1,5 @@
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
 use std::time::Duration;
 use std::vec;
 
@@ -15,7 +17,7 @@ use starknet_api::block::{BlockHash, BlockNumber};
 use starknet_api::transaction::Transaction;
 use starknet_types_core::felt::Felt;
 
-use super::{run_consensus, MultiHeightManager};
+use super::{run_consensus, MultiHeightManager, PendingCommits};
 use crate::config::TimeoutsConfig;
 use crate::test_utils::{precommit, prevote, proposal};
 use crate::types::{
@@ -74,6 +76,13 @@ mock! {
             content: mpsc::Receiver<Transaction>
         ) -> oneshot::Receiver<TestBlock>;
 
+        async fn accept_certified_proposal(
+            &self,
+            height: BlockNumber,
+            block_hash: BlockHash,
+            content: mpsc::Receiver<Transaction>
+        ) -> oneshot::Receiver<TestBlock>;
+
         async fn validators(&self, height: BlockNumber) -> Vec<ValidatorId>;
 
         fn proposer(&self, height: BlockNumber, round: Round) -> ValidatorId;
@@ -89,12 +98,20 @@ mock! {
 
         async fn decision_reached(
             &mut self,
+            height: BlockNumber,
             block: TestBlock,
             precommits: Vec<Vote>,
-        ) -> Result<(), ConsensusError>;
+        ) -> Result<oneshot::Receiver<()>, ConsensusError>;
     }
 }
 
+// A receiver of a commit that already completed.
+fn committed() -> oneshot::Receiver<()> {
+    let (commit_sender, commit_receiver) = oneshot::channel();
+    commit_sender.send(()).unwrap();
+    commit_receiver
+}
+
 type Sender = mpsc::UnboundedSender<(
     Result<ConsensusMessage, ProtobufConversionError>,
     BroadcastedMessageManager,
@@ -133,7 +150,7 @@ async fn manager_multiple_heights_unordered() {
     context.expect_proposer().returning(move |_, _| *PROPOSER_ID);
     context.expect_broadcast().returning(move |_| Ok(()));
 
-    let mut manager = MultiHeightManager::new(*VALIDATOR_ID, TIMEOUTS.clone());
+    let mut manager = MultiHeightManager::new(*VALIDATOR_ID, TIMEOUTS.clone(), false);
     let decision = manager.run_height(&mut context, BlockNumber(1), &mut receiver).await.unwrap();
     assert_eq!(decision.block.id(), BlockHash(Felt::ONE));
 
@@ -164,11 +181,12 @@ async fn run_consensus_sync() {
     context.expect_validators().returning(move |_| vec![*PROPOSER_ID, *VALIDATOR_ID]);
     context.expect_proposer().returning(move |_, _| *PROPOSER_ID);
     context.expect_broadcast().returning(move |_| Ok(()));
-    context.expect_decision_reached().return_once(move |block, votes| {
+    context.expect_decision_reached().return_once(move |height, block, votes| {
+        assert_eq!(height, BlockNumber(2));
         assert_eq!(block.id(), BlockHash(Felt::TWO));
         assert_eq!(votes[0].height, 2);
         decision_tx.send(()).unwrap();
-        Ok(())
+        Ok(committed())
     });
 
     // Send messages for height 2.
@@ -186,8 +204,11 @@ async fn run_consensus_sync() {
             *VALIDATOR_ID,
             Duration::ZERO,
             TIMEOUTS.clone(),
+            false,
+            1,
             &mut network_receiver,
             &mut sync_receiver,
+            Arc::new(AtomicU64::default()),
         )
         .await
     });
@@ -227,11 +248,12 @@ async fn run_consensus_sync_cancellation_safety() {
         },
     );
     context.expect_broadcast().returning(move |_| Ok(()));
-    context.expect_decision_reached().return_once(|block, votes| {
+    context.expect_decision_reached().return_once(|height, block, votes| {
+        assert_eq!(height, BlockNumber(1));
         assert_eq!(block.id(), BlockHash(Felt::ONE));
         assert_eq!(votes[0].height, 1);
         decision_tx.send(()).unwrap();
-        Ok(())
+        Ok(committed())
     });
 
     let (mut network_sender, mut network_receiver) = mpsc::unbounded();
@@ -244,8 +266,11 @@ async fn run_consensus_sync_cancellation_safety() {
             *VALIDATOR_ID,
             Duration::ZERO,
             TIMEOUTS.clone(),
+            false,
+            1,
             &mut network_receiver,
             &mut sync_receiver,
+            Arc::new(AtomicU64::default()),
         )
         .await
     });
@@ -301,7 +326,7 @@ async fn test_timeouts() {
         });
     context.expect_broadcast().returning(move |_| Ok(()));
 
-    let mut manager = MultiHeightManager::new(*VALIDATOR_ID, TIMEOUTS.clone());
+    let mut manager = MultiHeightManager::new(*VALIDATOR_ID, TIMEOUTS.clone(), false);
     let manager_handle = tokio::spawn(async move {
         let decision =
             manager.run_height(&mut context, BlockNumber(1), &mut receiver).await.unwrap();
@@ -321,3 +346,107 @@ async fn test_timeouts() {
 
     manager_handle.await.unwrap();
 }
+
+#[tokio::test]
+async fn follow_height_with_trusted_finality() {
+    let (mut sender, mut receiver) = mpsc::unbounded();
+    // The precommits of a quorum arrive before the proposal. The node's own precommit isn't counted
+    // since it isn't a validator.
+    send(&mut sender, precommit(Some(Felt::ONE), 1, 0, *VALIDATOR_ID)).await;
+    send(&mut sender, prevote(Some(Felt::ONE), 1, 0, *PROPOSER_ID)).await;
+    send(&mut sender, precommit(Some(Felt::ONE), 1, 0, *PROPOSER_ID)).await;
+    send(&mut sender, precommit(Some(Felt::ONE), 1, 0, *VALIDATOR_ID_2)).await;
+    send(&mut sender, precommit(Some(Felt::ONE), 1, 0, *VALIDATOR_ID_3)).await;
+    send(&mut sender, proposal(Felt::ONE, 1, 0, *PROPOSER_ID)).await;
+
+    let mut context = MockTestContext::new();
+    // The proposal isn't validated, and the node doesn't vote.
+    context.expect_validate_proposal().never();
+    context.expect_broadcast().never();
+    context
+        .expect_accept_certified_proposal()
+        .withf(|height, block_hash, _| {
+            *height == BlockNumber(1) && *block_hash == BlockHash(Felt::ONE)
+        })
+        .return_once(move |_, block_hash, _| {
+            let (block_sender, block_receiver) = oneshot::channel();
+            block_sender.send(TestBlock { content: Vec::new(), id: block_hash }).unwrap();
+            block_receiver
+        })
+        .times(1);
+    context
+        .expect_validators()
+        .returning(move |_| vec![*PROPOSER_ID, *VALIDATOR_ID_2, *VALIDATOR_ID_3]);
+    context.expect_proposer().returning(move |_, _| *PROPOSER_ID);
+
+    let mut manager = MultiHeightManager::new(*VALIDATOR_ID, TIMEOUTS.clone(), true);
+    let decision = manager.run_height(&mut context, BlockNumber(1), &mut receiver).await.unwrap();
+    assert_eq!(decision.block.id(), BlockHash(Felt::ONE));
+    let voters: Vec<ValidatorId> = decision.precommits.iter().map(|vote| vote.voter).collect();
+    assert_eq!(voters, vec![*PROPOSER_ID, *VALIDATOR_ID_2, *VALIDATOR_ID_3]);
+}
+
+#[tokio::test]
+async fn pending_commits_backpressure() {
+    let mut pending_commits = PendingCommits::new(1);
+    let (commit_sender_1, commit_receiver_1) = oneshot::channel();
+    let (commit_sender_2, commit_receiver_2) = oneshot::channel();
+
+    // A single pending commit doesn't hold consensus back.
+    pending_commits.push(BlockNumber(1), commit_receiver_1).await.unwrap();
+
+    // A second one waits for the commit of the first.
+    let mut push = Box::pin(pending_commits.push(BlockNumber(2), commit_receiver_2));
+    assert!(tokio::time::timeout(Duration::from_millis(100), &mut push).await.is_err());
+    commit_sender_1.send(()).unwrap();
+    push.await.unwrap();
+    assert_eq!(pending_commits.commit_receivers.len(), 1);
+
+    // Commits that completed are removed.
+    commit_sender_2.send(()).unwrap();
+    pending_commits.push(BlockNumber(3), committed()).await.unwrap();
+    assert!(pending_commits.commit_receivers.is_empty());
+}
+
+#[tokio::test]
+async fn pending_commits_failed_commit() {
+    let mut pending_commits = PendingCommits::new(0);
+    let (commit_sender, commit_receiver) = oneshot::channel::<()>();
+    drop(commit_sender);
+
+    assert_eq!(
+        pending_commits.push(BlockNumber(1), commit_receiver).await,
+        Err(ConsensusError::CommitFailed(BlockNumber(1)))
+    );
+}
+
+#[tokio::test]
+async fn pending_commits_drain() {
+    let mut pending_commits = PendingCommits::new(2);
+    let (commit_sender_1, commit_receiver_1) = oneshot::channel();
+    let (commit_sender_2, commit_receiver_2) = oneshot::channel();
+    pending_commits.push(BlockNumber(1), commit_receiver_1).await.unwrap();
+    pending_commits.push(BlockNumber(2), commit_receiver_2).await.unwrap();
+
+    let mut drain = Box::pin(pending_commits.drain());
+    commit_sender_1.send(()).unwrap();
+    assert!(tokio::time::timeout(Duration::from_millis(100), &mut drain).await.is_err());
+    commit_sender_2.send(()).unwrap();
+    drain.await.unwrap();
+    assert!(pending_commits.commit_receivers.is_empty());
+}
+
+// Consensus waits for the pending commits before it stops on an error, and reports the error.
+#[tokio::test]
+async fn pending_commits_drain_and_fail() {
+    let mut pending_commits = PendingCommits::new(1);
+    let (commit_sender, commit_receiver) = oneshot::channel();
+    pending_commits.push(BlockNumber(1), commit_receiver).await.unwrap();
+
+    commit_sender.send(()).unwrap();
+    assert_eq!(
+        pending_commits.drain_and_fail(ConsensusError::SyncError("Sync failed".to_string())).await,
+        Err(ConsensusError::SyncError("Sync failed".to_string()))
+    );
+    assert!(pending_commits.commit_receivers.is_empty());
+}
@@ -2,8 +2,9 @@
 #[path = "papyrus_consensus_context_test.rs"]
 mod papyrus_consensus_context_test;
 
-use core::panic;
-use std::time::Duration;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
 use futures::channel::{mpsc, oneshot};
@@ -11,14 +12,14 @@ use futures::sink::SinkExt;
 use futures::StreamExt;
 use papyrus_network::network_manager::BroadcastTopicSender;
 use papyrus_protobuf::consensus::{ConsensusMessage, Proposal, Vote};
-use papyrus_storage::body::BodyStorageReader;
-use papyrus_storage::header::HeaderStorageReader;
-use papyrus_storage::{StorageError, StorageReader};
+use papyrus_storage::StorageReader;
 use starknet_api::block::{BlockHash, BlockNumber};
 use starknet_api::core::ContractAddress;
-use starknet_api::transaction::Transaction;
-use tracing::{debug, debug_span, info, warn, Instrument};
+use starknet_api::transaction::{DeclareTransaction, Transaction};
+use tracing::{debug, debug_span, info, instrument, warn, Instrument, Span};
 
+use crate::class_fetcher::ClassFetcher;
+use crate::proposal_builder::{ProposalBuilder, StorageProposalBuilder};
 use crate::types::{
     ConsensusBlock,
     ConsensusContext,
@@ -50,16 +51,25 @@ impl ConsensusBlock for PapyrusConsensusBlock {
     }
 }
 
+// Identifies a proposal the node built or validated in the tracing spans of its lifecycle, from
+// its building or validation to its decision.
+type ProposalCorrelationId = u64;
+
+/// A [`ConsensusContext`] over the papyrus network, whose blocks are built and validated by a
+/// [`ProposalBuilder`]. By default, it proposes the blocks in storage.
 pub struct PapyrusConsensusContext {
-    storage_reader: StorageReader,
+    proposal_builder: Arc<dyn ProposalBuilder>,
     network_broadcast_sender: BroadcastTopicSender<ConsensusMessage>,
     validators: Vec<ValidatorId>,
     sync_broadcast_sender: Option<BroadcastTopicSender<Vote>>,
+    class_fetcher: Option<Arc<ClassFetcher>>,
+    next_proposal_id: AtomicU64,
+    // The correlation ids of the proposals that were built or validated, by their heights and
+    // block hashes, until their height is decided.
+    proposal_ids: Arc<Mutex<HashMap<(BlockNumber, BlockHash), ProposalCorrelationId>>>,
 }
 
 impl PapyrusConsensusContext {
-    // TODO(dvir): remove the dead code attribute after we will use this function.
-    #[allow(dead_code)]
     pub fn new(
         storage_reader: StorageReader,
         network_broadcast_sender: BroadcastTopicSender<ConsensusMessage>,
@@ -67,12 +77,45 @@ impl PapyrusConsensusContext {
         sync_broadcast_sender: Option<BroadcastTopicSender<Vote>>,
     ) -> Self {
         Self {
-            storage_reader,
+            proposal_builder: Arc::new(StorageProposalBuilder::new(storage_reader)),
             network_broadcast_sender,
             validators: (0..num_validators).map(ContractAddress::from).collect(),
             sync_broadcast_sender,
+            class_fetcher: None,
+            next_proposal_id: AtomicU64::new(0),
+            proposal_ids: Arc::default(),
         }
     }
+
+    /// Replaces the builder of the proposals' content.
+    pub fn with_proposal_builder(mut self, proposal_builder: Arc<dyn ProposalBuilder>) -> Self {
+        self.proposal_builder = proposal_builder;
+        self
+    }
+
+    /// Fetches the classes that proposals declare and the node doesn't have before validating
+    /// them.
+    pub fn with_class_fetcher(mut self, class_fetcher: Arc<ClassFetcher>) -> Self {
+        self.class_fetcher = Some(class_fetcher);
+        self
+    }
+
+    fn new_proposal_id(&self) -> ProposalCorrelationId {
+        self.next_proposal_id.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+// Records the correlation id of a proposal whose block is done, so its decision is traced with it.
+fn record_proposal_id(
+    proposal_ids: &Mutex<HashMap<(BlockNumber, BlockHash), ProposalCorrelationId>>,
+    height: BlockNumber,
+    block_hash: BlockHash,
+    proposal_id: ProposalCorrelationId,
+) {
+    proposal_ids
+        .lock()
+        .expect("The lock should not be poisoned")
+        .insert((height, block_hash), proposal_id);
 }
 
 const CHANNEL_SIZE: usize = 5000;
@@ -85,100 +128,111 @@ impl ConsensusContext for PapyrusConsensusContext {
         &self,
         height: BlockNumber,
     ) -> (mpsc::Receiver<Transaction>, oneshot::Receiver<PapyrusConsensusBlock>) {
-        let (mut sender, receiver) = mpsc::channel(CHANNEL_SIZE);
+        let (sender, receiver) = mpsc::channel(CHANNEL_SIZE);
         let (fin_sender, fin_receiver) = oneshot::channel();
+        // The content is sent to consensus as it's built, and kept for the block.
+        let (content_sender, content_receiver) = mpsc::channel(CHANNEL_SIZE);
 
-        let storage_reader = self.storage_reader.clone();
+        let proposal_builder = self.proposal_builder.clone();
+        let proposal_id = self.new_proposal_id();
+        let proposal_ids = self.proposal_ids.clone();
         tokio::spawn(
             async move {
-                // TODO(dvir): consider fix this for the case of reverts. If between the check that
-                // the block in storage and to getting the transaction was a revert
-                // this flow will fail.
-                wait_for_block(&storage_reader, height).await.expect("Failed to wait to block");
-
-                let txn = storage_reader.begin_ro_txn().expect("Failed to begin ro txn");
-                let transactions = txn
-                    .get_block_transactions(height)
-                    .expect("Get transactions from storage failed")
-                    .unwrap_or_else(|| {
-                        panic!("Block in {height} was not found in storage despite waiting for it")
-                    });
-
-                for tx in transactions.clone() {
-                    sender.try_send(tx).expect("Send should succeed");
-                }
-                sender.close_channel();
-
-                let block_hash = txn
-                    .get_block_header(height)
-                    .expect("Get header from storage failed")
-                    .unwrap_or_else(|| {
-                        panic!("Block in {height} was not found in storage despite waiting for it")
-                    })
-                    .block_hash;
-                fin_sender
-                    .send(PapyrusConsensusBlock { content: transactions, id: block_hash })
-                    .expect("Send should succeed");
+                let collect_content = forward_content(receiver, content_sender);
+                let (block_hash, content) =
+                    futures::join!(proposal_builder.build(height, sender), collect_content);
+                let block_hash = match block_hash {
+                    Ok(block_hash) => block_hash,
+                    Err(err) => {
+                        warn!("Failed to build a proposal at height {height}: {err}");
+                        return;
+                    }
+                };
+                record_proposal_id(&proposal_ids, height, block_hash, proposal_id);
+                // This can happen as a result of sync interrupting `run_height`.
+                fin_sender.send(PapyrusConsensusBlock { content, id: block_hash }).unwrap_or_else(
+                    |_| {
+                        warn!("Failed to send block to consensus. height={height}");
+                    },
+                )
             }
-            .instrument(debug_span!("consensus_build_proposal")),
+            .instrument(debug_span!("consensus_build_proposal", %height, proposal_id)),
         );
 
-        (receiver, fin_receiver)
+        (content_receiver, fin_receiver)
     }
 
     async fn validate_proposal(
         &self,
         height: BlockNumber,
-        mut content: mpsc::Receiver<Transaction>,
+        content: mpsc::Receiver<Transaction>,
     ) -> oneshot::Receiver<PapyrusConsensusBlock> {
         let (fin_sender, fin_receiver) = oneshot::channel();
+        let content = match &self.class_fetcher {
+            Some(class_fetcher) => fetch_declared_classes(class_fetcher.clone(), height, content),
+            None => content,
+        };
 
-        let storage_reader = self.storage_reader.clone();
+        let proposal_builder = self.proposal_builder.clone();
+        let proposal_id = self.new_proposal_id();
+        let proposal_ids = self.proposal_ids.clone();
         tokio::spawn(
             async move {
-                // TODO(dvir): consider fix this for the case of reverts. If between the check that
-                // the block in storage and to getting the transaction was a revert
-                // this flow will fail.
-                wait_for_block(&storage_reader, height).await.expect("Failed to wait to block");
-
-                let txn = storage_reader.begin_ro_txn().expect("Failed to begin ro txn");
-                let transactions = txn
-                    .get_block_transactions(height)
-                    .expect("Get transactions from storage failed")
-                    .unwrap_or_else(|| {
-                        panic!("Block in {height} was not found in storage despite waiting for it")
-                    });
-
-                for tx in transactions.iter() {
-                    let received_tx = content
-                        .next()
-                        .await
-                        .unwrap_or_else(|| panic!("Not received transaction equals to {tx:?}"));
-                    if tx != &received_tx {
-                        panic!("Transactions are not equal. In storage: {tx:?}, : {received_tx:?}");
+                let (content, block_hash) = match proposal_builder.validate(height, content).await {
+                    Ok(block) => block,
+                    Err(err) => {
+                        warn!("Invalid proposal at height {height}: {err}");
+                        return;
                     }
-                }
+                };
+                record_proposal_id(&proposal_ids, height, block_hash, proposal_id);
+                // This can happen as a result of sync interrupting `run_height`.
+                fin_sender.send(PapyrusConsensusBlock { content, id: block_hash }).unwrap_or_else(
+                    |_| {
+                        warn!("Failed to send block to consensus. height={height}");
+                    },
+                )
+            }
+            .instrument(debug_span!("consensus_validate_proposal", %height, proposal_id)),
+        );
 
-                if content.next().await.is_some() {
-                    panic!("Received more transactions than expected");
-                }
+        fin_receiver
+    }
 
-                let block_hash = txn
-                    .get_block_header(height)
-                    .expect("Get header from storage failed")
-                    .unwrap_or_else(|| {
-                        panic!("Block in {height} was not found in storage despite waiting for it")
-                    })
-                    .block_hash;
+    async fn accept_certified_proposal(
+        &self,
+        height: BlockNumber,
+        block_hash: BlockHash,
+        content: mpsc::Receiver<Transaction>,
+    ) -> oneshot::Receiver<PapyrusConsensusBlock> {
+        let (fin_sender, fin_receiver) = oneshot::channel();
 
+        let proposal_builder = self.proposal_builder.clone();
+        let proposal_id = self.new_proposal_id();
+        let proposal_ids = self.proposal_ids.clone();
+        tokio::spawn(
+            async move {
+                let content =
+                    match proposal_builder.accept_certified(height, block_hash, content).await {
+                        Ok(content) => content,
+                        Err(err) => {
+                            warn!("Invalid certified proposal at height {height}: {err}");
+                            return;
+                        }
+                    };
+                record_proposal_id(&proposal_ids, height, block_hash, proposal_id);
                 // This can happen as a result of sync interrupting `run_height`.
-                fin_sender
-                    .send(PapyrusConsensusBlock { content: transactions, id: block_hash })
-                    .unwrap_or_else(|_| {
+                fin_sender.send(PapyrusConsensusBlock { content, id: block_hash }).unwrap_or_else(
+                    |_| {
                         warn!("Failed to send block to consensus. height={height}");
-                    })
+                    },
+                )
             }
-            .instrument(debug_span!("consensus_validate_proposal")),
+            .instrument(debug_span!(
+                "consensus_accept_certified_proposal",
+                %height,
+                proposal_id
+            )),
         );
 
         fin_receiver
@@ -205,6 +259,7 @@ impl ConsensusContext for PapyrusConsensusContext {
         fin_receiver: oneshot::Receiver<BlockHash>,
     ) -> Result<(), ConsensusError> {
         let mut network_broadcast_sender = self.network_broadcast_sender.clone();
+        let span = debug_span!("consensus_propose", height = %init.height, round = init.round);
 
         tokio::spawn(
             async move {
@@ -238,40 +293,107 @@ impl ConsensusContext for PapyrusConsensusContext {
                     .await
                     .expect("Failed to send proposal");
             }
-            .instrument(debug_span!("consensus_propose")),
+            .instrument(span),
         );
         Ok(())
     }
 
+    #[instrument(
+        skip_all,
+        fields(%height, proposal_id = tracing::field::Empty),
+        level = "info"
+    )]
     async fn decision_reached(
         &mut self,
+        height: BlockNumber,
         block: Self::Block,
         precommits: Vec<Vote>,
-    ) -> Result<(), ConsensusError> {
-        let height = precommits[0].height;
+    ) -> Result<oneshot::Receiver<()>, ConsensusError> {
+        {
+            let mut proposal_ids =
+                self.proposal_ids.lock().expect("The lock should not be poisoned");
+            if let Some(proposal_id) = proposal_ids.get(&(height, block.id())) {
+                Span::current().record("proposal_id", proposal_id);
+            }
+            proposal_ids.retain(|(proposal_height, _), _| *proposal_height > height);
+        }
         info!(
             "Finished consensus for height: {height}. Agreed on block with id: {:x}",
             block.id().0
         );
-        if let Some(sender) = &mut self.sync_broadcast_sender {
-            sender.send(precommits[0].clone()).await?;
+        if let (Some(sender), Some(precommit)) =
+            (&mut self.sync_broadcast_sender, precommits.into_iter().next())
+        {
+            sender.send(precommit).await?;
         }
 
-        Ok(())
+        let (commit_sender, commit_receiver) = oneshot::channel();
+        let proposal_builder = Arc::clone(&self.proposal_builder);
+        tokio::spawn(
+            async move {
+                match proposal_builder.wait_for_commit(height).await {
+                    // Consensus may have stopped waiting for the commit.
+                    Ok(()) => commit_sender.send(()).unwrap_or_default(),
+                    // Dropping the sender fails the commit.
+                    Err(err) => warn!("Failed to commit the decided block: {err}"),
+                }
+            }
+            .in_current_span(),
+        );
+        Ok(commit_receiver)
     }
 }
 
-const SLEEP_BETWEEN_CHECK_FOR_BLOCK: Duration = Duration::from_secs(10);
+// Forwards the content the builder streams to consensus, and returns all of it once the builder is
+// done. If consensus stops listening, the content is still collected for the block.
+async fn forward_content(
+    mut content: mpsc::Receiver<Transaction>,
+    mut consensus_sender: mpsc::Sender<Transaction>,
+) -> Vec<Transaction> {
+    let mut transactions = Vec::new();
+    while let Some(tx) = content.next().await {
+        if consensus_sender.send(tx.clone()).await.is_err() {
+            debug!("Consensus stopped receiving the proposal content.");
+        }
+        transactions.push(tx);
+    }
+    transactions
+}
 
-async fn wait_for_block(
-    storage_reader: &StorageReader,
+// Forwards the content of a proposal, first getting the Sierra classes that its declare
+// transactions declare. If a class can't be gotten, the rest of the content isn't forwarded, which
+// makes the proposal invalid.
+fn fetch_declared_classes(
+    class_fetcher: Arc<ClassFetcher>,
     height: BlockNumber,
-) -> Result<(), StorageError> {
-    while storage_reader.begin_ro_txn()?.get_body_marker()? <= height {
-        debug!("Waiting for block {height:?} to continue consensus");
-        tokio::time::sleep(SLEEP_BETWEEN_CHECK_FOR_BLOCK).await;
-    }
-    Ok(())
+    mut content: mpsc::Receiver<Transaction>,
+) -> mpsc::Receiver<Transaction> {
+    let (mut sender, receiver) = mpsc::channel(CHANNEL_SIZE);
+    tokio::spawn(
+        async move {
+            while let Some(tx) = content.next().await {
+                if let Transaction::Declare(
+                    declare_tx @ (DeclareTransaction::V2(_) | DeclareTransaction::V3(_)),
+                ) = &tx
+                {
+                    let class_hash = declare_tx.class_hash();
+                    if let Err(err) = class_fetcher.get_class(class_hash).await {
+                        warn!(
+                            "Failed to get the class {class_hash} declared in the proposal at \
+                             height {height}: {err}"
+                        );
+                        return;
+                    }
+                }
+                if sender.send(tx).await.is_err() {
+                    debug!("The proposal builder stopped receiving the proposal content.");
+                    return;
+                }
+            }
+        }
+        .instrument(debug_span!("consensus_fetch_declared_classes", %height)),
+    );
+    receiver
 }
 
 impl From<ProposalWrapper>
@@ -1,5 +1,8 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
 use futures::channel::{mpsc, oneshot};
-use futures::StreamExt;
+use futures::{SinkExt, StreamExt};
 use papyrus_network::network_manager::test_utils::{
     mock_register_broadcast_topic,
     BroadcastNetworkMock,
@@ -7,13 +10,22 @@ use papyrus_network::network_manager::test_utils::{
 use papyrus_protobuf::consensus::{ConsensusMessage, Proposal, Vote};
 use papyrus_storage::body::BodyStorageWriter;
 use papyrus_storage::header::HeaderStorageWriter;
+use papyrus_storage::state::StateStorageWriter;
 use papyrus_storage::test_utils::get_test_storage;
 use papyrus_test_utils::get_test_block;
-use starknet_api::block::Block;
+use starknet_api::block::{Block, BlockHash, BlockHeaderWithoutHash, BlockNumber, StarknetVersion};
+use starknet_api::block_hash::block_hash_calculator::{
+    calculate_block_hash_by_version,
+    calculate_state_diff_commitment_by_version,
+    TransactionHashingData,
+};
 use starknet_api::core::ContractAddress;
+use starknet_api::felt;
+use starknet_api::state::ThinStateDiff;
 use starknet_api::transaction::Transaction;
 
 use crate::papyrus_consensus_context::{PapyrusConsensusBlock, PapyrusConsensusContext};
+use crate::proposal_builder::{ProposalBuilder, ProposalBuilderResult};
 use crate::types::{ConsensusBlock, ConsensusContext, ProposalInit};
 
 // TODO(dvir): consider adding tests for times, i.e, the calls are returned immediately and nothing
@@ -73,6 +85,181 @@ async fn validate_proposal_fail() {
     assert_eq!(fin, Err(oneshot::Canceled));
 }
 
+#[tokio::test]
+async fn validate_proposal_waits_for_state_diff() {
+    let ((storage_reader, mut storage_writer), _temp_dir) = get_test_storage();
+    let block = get_test_block(5, None, None, None);
+    let block_number = block.header.block_number;
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(block_number, &block.header)
+        .unwrap()
+        .append_body(block_number, block.body.clone())
+        .unwrap()
+        .commit()
+        .unwrap();
+    let network_channels = mock_register_broadcast_topic().unwrap();
+    let papyrus_context = PapyrusConsensusContext::new(
+        storage_reader,
+        network_channels.subscriber_channels.messages_to_broadcast_sender,
+        4,
+        None,
+    );
+
+    let (mut validate_sender, validate_receiver) = mpsc::channel(TEST_CHANNEL_SIZE);
+    for tx in block.body.transactions.clone() {
+        validate_sender.try_send(tx).unwrap();
+    }
+    validate_sender.close_channel();
+
+    let mut fin_receiver = papyrus_context.validate_proposal(block_number, validate_receiver).await;
+    tokio::task::yield_now().await;
+    // The block's hash can't be recalculated before its state diff is synced.
+    assert_eq!(fin_receiver.try_recv(), Ok(None));
+}
+
+// Stores a v0.13.2 block whose state diff matches its commitment, and whose hash is the given one
+// or, if not given, the hash of its content.
+fn store_v0_13_2_block(block_hash: Option<BlockHash>) -> (Block, PapyrusConsensusContext) {
+    let ((storage_reader, mut storage_writer), _temp_dir) = get_test_storage();
+    let mut block = get_test_block(5, None, None, None);
+    block.header.starknet_version = StarknetVersion("0.13.2".to_owned());
+    let state_diff = ThinStateDiff::default();
+    block.header.state_diff_commitment = Some(
+        calculate_state_diff_commitment_by_version(&block.header.starknet_version, &state_diff)
+            .unwrap(),
+    );
+    let transactions_data: Vec<_> = block
+        .body
+        .transactions
+        .iter()
+        .zip(block.body.transaction_outputs.iter())
+        .zip(block.body.transaction_hashes.iter())
+        .map(|((tx, tx_output), tx_hash)| TransactionHashingData::new(tx, tx_output, *tx_hash))
+        .collect();
+    block.header.block_hash = block_hash.unwrap_or_else(|| {
+        calculate_block_hash_by_version(
+            BlockHeaderWithoutHash::from(&block.header),
+            &transactions_data,
+            &state_diff,
+        )
+        .unwrap()
+        .0
+    });
+    let block_number = block.header.block_number;
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(block_number, &block.header)
+        .unwrap()
+        .append_body(block_number, block.body.clone())
+        .unwrap()
+        .append_state_diff(block_number, state_diff)
+        .unwrap()
+        .commit()
+        .unwrap();
+    let network_channels = mock_register_broadcast_topic().unwrap();
+    let papyrus_context = PapyrusConsensusContext::new(
+        storage_reader,
+        network_channels.subscriber_channels.messages_to_broadcast_sender,
+        4,
+        None,
+    );
+    (block, papyrus_context)
+}
+
+#[tokio::test]
+async fn build_proposal_recalculates_block_hash() {
+    let (block, papyrus_context) = store_v0_13_2_block(None);
+    let (_proposal_receiver, fin_receiver) =
+        papyrus_context.build_proposal(block.header.block_number).await;
+    assert_eq!(fin_receiver.await.unwrap().id(), block.header.block_hash);
+
+    let (block, papyrus_context) = store_v0_13_2_block(Some(BlockHash(felt!("0x1"))));
+    let (_proposal_receiver, fin_receiver) =
+        papyrus_context.build_proposal(block.header.block_number).await;
+    assert_eq!(fin_receiver.await, Err(oneshot::Canceled));
+}
+
+#[tokio::test]
+async fn validate_proposal_recalculates_block_hash() {
+    let (executed_block, _) = store_v0_13_2_block(None);
+    // The header's hash doesn't commit to the executed state diff, so a proposal that carries it
+    // doesn't form the validated block.
+    let (block, papyrus_context) = store_v0_13_2_block(Some(BlockHash(felt!("0x1"))));
+    let (mut validate_sender, validate_receiver) = mpsc::channel(TEST_CHANNEL_SIZE);
+    for tx in block.body.transactions.clone() {
+        validate_sender.try_send(tx).unwrap();
+    }
+    validate_sender.close_channel();
+
+    let fin = papyrus_context
+        .validate_proposal(block.header.block_number, validate_receiver)
+        .await
+        .await
+        .unwrap();
+    assert_eq!(fin.id(), executed_block.header.block_hash);
+    assert_ne!(fin.id(), block.header.block_hash);
+}
+
+#[tokio::test]
+async fn accept_certified_proposal_recalculates_block_hash() {
+    let (block, papyrus_context) = store_v0_13_2_block(None);
+    let block_number = block.header.block_number;
+    let content = || {
+        let (mut content_sender, content_receiver) = mpsc::channel(TEST_CHANNEL_SIZE);
+        for tx in block.body.transactions.clone() {
+            content_sender.try_send(tx).unwrap();
+        }
+        content_sender.close_channel();
+        content_receiver
+    };
+
+    let fin = papyrus_context
+        .accept_certified_proposal(block_number, block.header.block_hash, content())
+        .await
+        .await
+        .unwrap();
+    assert_eq!(fin.id(), block.header.block_hash);
+    assert_eq!(fin.proposal_iter().collect::<Vec::<Transaction>>(), block.body.transactions);
+
+    // The content doesn't form the block that the proposer claimed the validators certified.
+    let fin = papyrus_context
+        .accept_certified_proposal(block_number, BlockHash(felt!("0x1")), content())
+        .await
+        .await;
+    assert_eq!(fin, Err(oneshot::Canceled));
+}
+
+#[tokio::test]
+async fn custom_proposal_builder() {
+    let (block, papyrus_context, _mock_network, _) = test_setup();
+    // A height that isn't in storage.
+    let block_number = block.header.block_number.unchecked_next();
+    let block_hash = BlockHash(felt!("0x1234"));
+    let papyrus_context = papyrus_context.with_proposal_builder(Arc::new(FixedProposalBuilder {
+        transactions: block.body.transactions.clone(),
+        block_hash,
+    }));
+
+    let (mut proposal_receiver, fin_receiver) = papyrus_context.build_proposal(block_number).await;
+    let mut transactions = Vec::new();
+    while let Some(tx) = proposal_receiver.next().await {
+        transactions.push(tx);
+    }
+    assert_eq!(transactions, block.body.transactions);
+    let fin = fin_receiver.await.unwrap();
+    assert_eq!(fin.id(), block_hash);
+    assert_eq!(fin.proposal_iter().collect::<Vec::<Transaction>>(), block.body.transactions);
+
+    let (mut validate_sender, validate_receiver) = mpsc::channel(TEST_CHANNEL_SIZE);
+    validate_sender.close_channel();
+    let fin =
+        papyrus_context.validate_proposal(block_number, validate_receiver).await.await.unwrap();
+    assert_eq!(fin.id(), block_hash);
+}
+
 #[tokio::test]
 async fn propose() {
     let (block, papyrus_context, mut mock_network, _) = test_setup();
@@ -107,8 +294,58 @@ async fn decision() {
     let (_, mut papyrus_context, _, mut sync_network) = test_setup();
     let block = PapyrusConsensusBlock::default();
     let precommit = Vote::default();
-    papyrus_context.decision_reached(block, vec![precommit.clone()]).await.unwrap();
+    let commit_receiver = papyrus_context
+        .decision_reached(BlockNumber::default(), block, vec![precommit.clone()])
+        .await
+        .unwrap();
     assert_eq!(sync_network.messages_to_broadcast_receiver.next().await.unwrap(), precommit);
+    // The decided block is in storage, so it's committed.
+    commit_receiver.await.unwrap();
+}
+
+#[tokio::test]
+async fn decision_is_committed_once_synced() {
+    let (_, mut papyrus_context, _, _sync_network) = test_setup();
+    let mut commit_receiver = papyrus_context
+        .decision_reached(BlockNumber(1), PapyrusConsensusBlock::default(), vec![Vote::default()])
+        .await
+        .unwrap();
+
+    tokio::task::yield_now().await;
+    // Only the block of height 0 is in storage.
+    assert_eq!(commit_receiver.try_recv(), Ok(None));
+}
+
+// Builds the same block at every height, and accepts any proposal as it.
+struct FixedProposalBuilder {
+    transactions: Vec<Transaction>,
+    block_hash: BlockHash,
+}
+
+#[async_trait]
+impl ProposalBuilder for FixedProposalBuilder {
+    async fn build(
+        &self,
+        _height: BlockNumber,
+        mut content_sender: mpsc::Sender<Transaction>,
+    ) -> ProposalBuilderResult<BlockHash> {
+        for tx in self.transactions.clone() {
+            content_sender.send(tx).await?;
+        }
+        Ok(self.block_hash)
+    }
+
+    async fn validate(
+        &self,
+        _height: BlockNumber,
+        _content: mpsc::Receiver<Transaction>,
+    ) -> ProposalBuilderResult<(Vec<Transaction>, BlockHash)> {
+        Ok((self.transactions.clone(), self.block_hash))
+    }
+
+    async fn wait_for_commit(&self, _height: BlockNumber) -> ProposalBuilderResult<()> {
+        Ok(())
+    }
 }
 
 fn test_setup() -> (
@@ -127,6 +364,8 @@ fn test_setup() -> (
         .unwrap()
         .append_body(block_number, block.body.clone())
         .unwrap()
+        .append_state_diff(block_number, ThinStateDiff::default())
+        .unwrap()
         .commit()
         .unwrap();
 
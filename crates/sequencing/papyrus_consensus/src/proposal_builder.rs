@@ -0,0 +1,231 @@
+//! Building and validating the content of the proposals of a
+//! [`PapyrusConsensusContext`](crate::papyrus_consensus_context::PapyrusConsensusContext).
+//!
+//! The context handles the consensus side of a proposal: the channels consensus streams the content
+//! through, and broadcasting it to the other validators. What a block holds is decided by a
+//! [`ProposalBuilder`], so a node can plug in its own source of blocks, e.g. a batcher that
+//! executes transactions from the mempool, without reimplementing the rest of the context.
+//!
+//! [`StorageProposalBuilder`] proposes the blocks that the node already synced to its storage,
+//! which lets nodes run consensus over an existing chain.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::channel::mpsc;
+use futures::{SinkExt, StreamExt};
+use papyrus_storage::body::BodyStorageReader;
+use papyrus_storage::header::HeaderStorageReader;
+use papyrus_storage::state::StateStorageReader;
+use papyrus_storage::{StorageError, StorageReader};
+use starknet_api::block::{BlockHash, BlockHeaderWithoutHash, BlockNumber};
+use starknet_api::block_hash::block_hash_calculator::{
+    calculate_block_hash_by_version,
+    TransactionHashingData,
+};
+use starknet_api::transaction::Transaction;
+use tracing::debug;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProposalBuilderError {
+    #[error("Invalid proposal content at height {height}: {reason}")]
+    InvalidContent { height: BlockNumber, reason: String },
+    #[error("Block {0} was not found in storage despite waiting for it.")]
+    MissingBlock(BlockNumber),
+    #[error(
+        "The hash of block {height} doesn't match its content. In header: {expected:?}, \
+         calculated: {calculated:?}."
+    )]
+    BlockHashMismatch { height: BlockNumber, expected: BlockHash, calculated: BlockHash },
+    #[error(
+        "The validators certified block {certified:?} at height {height}, but the content forms \
+         block {calculated:?}."
+    )]
+    CertifiedBlockHashMismatch { height: BlockNumber, certified: BlockHash, calculated: BlockHash },
+    #[error(transparent)]
+    SendError(#[from] mpsc::SendError),
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+}
+
+pub type ProposalBuilderResult<T> = Result<T, ProposalBuilderError>;
+
+/// Decides the content of the blocks that consensus proposes and votes on.
+#[async_trait]
+pub trait ProposalBuilder: Send + Sync {
+    /// Builds a block at the given height, streaming its transactions into `content_sender` as
+    /// they're added. Returns the hash of the block once all of them were sent.
+    async fn build(
+        &self,
+        height: BlockNumber,
+        content_sender: mpsc::Sender<Transaction>,
+    ) -> ProposalBuilderResult<BlockHash>;
+
+    /// Validates the streamed transactions of a block proposed at the given height. Returns them
+    /// with the hash of the block if they form a valid block.
+    async fn validate(
+        &self,
+        height: BlockNumber,
+        content: mpsc::Receiver<Transaction>,
+    ) -> ProposalBuilderResult<(Vec<Transaction>, BlockHash)>;
+
+    /// Accepts the streamed transactions of a block that the validators certified at the given
+    /// height. The transactions are validated as those of any proposal, and the hash of the
+    /// validated block must be the certified one.
+    async fn accept_certified(
+        &self,
+        height: BlockNumber,
+        certified_block_hash: BlockHash,
+        content: mpsc::Receiver<Transaction>,
+    ) -> ProposalBuilderResult<Vec<Transaction>> {
+        let (transactions, block_hash) = self.validate(height, content).await?;
+        if block_hash != certified_block_hash {
+            return Err(ProposalBuilderError::CertifiedBlockHashMismatch {
+                height,
+                certified: certified_block_hash,
+                calculated: block_hash,
+            });
+        }
+        Ok(transactions)
+    }
+
+    /// Waits for the block decided at the given height to be committed.
+    async fn wait_for_commit(&self, height: BlockNumber) -> ProposalBuilderResult<()>;
+}
+
+/// Proposes the blocks in storage, waiting for each one and its state diff to be synced. The hash
+/// of a block is recalculated from its content and its executed state diff, so it commits to the
+/// state diff commitment of that diff. A proposal is valid if it holds the transactions of the
+/// block in storage, and the hash it carries is the recalculated one.
+pub struct StorageProposalBuilder {
+    storage_reader: StorageReader,
+}
+
+impl StorageProposalBuilder {
+    pub fn new(storage_reader: StorageReader) -> Self {
+        Self { storage_reader }
+    }
+
+    // TODO(dvir): consider fix this for the case of reverts. If between the check that the block
+    // in storage and to getting the transaction was a revert this flow will fail.
+    async fn synced_block(
+        &self,
+        height: BlockNumber,
+    ) -> ProposalBuilderResult<(Vec<Transaction>, BlockHash)> {
+        wait_for_block(&self.storage_reader, height).await?;
+
+        let txn = self.storage_reader.begin_ro_txn()?;
+        let transactions = txn
+            .get_block_transactions(height)?
+            .ok_or(ProposalBuilderError::MissingBlock(height))?;
+        let block_hash = txn
+            .get_block_header(height)?
+            .ok_or(ProposalBuilderError::MissingBlock(height))?
+            .block_hash;
+        Ok((transactions, block_hash))
+    }
+
+    // Returns the hash of a synced block, recalculated from its content and its executed state
+    // diff by the block hash calculation of the block's Starknet version, which the proposer and
+    // the validators of a block share. Blocks whose Starknet version predates the calculation don't
+    // commit to their state diff, so the hash in their header is returned.
+    fn executed_block_hash(&self, height: BlockNumber) -> ProposalBuilderResult<BlockHash> {
+        let txn = self.storage_reader.begin_ro_txn()?;
+        let missing_block = || ProposalBuilderError::MissingBlock(height);
+        let header = txn.get_block_header(height)?.ok_or_else(missing_block)?;
+        let state_diff = txn.get_state_diff(height)?.ok_or_else(missing_block)?;
+        let transactions = txn.get_block_transactions(height)?.ok_or_else(missing_block)?;
+        let transaction_outputs =
+            txn.get_block_transaction_outputs(height)?.ok_or_else(missing_block)?;
+        let transaction_hashes =
+            txn.get_block_transaction_hashes(height)?.ok_or_else(missing_block)?;
+        let transactions_data: Vec<TransactionHashingData> = transactions
+            .iter()
+            .zip(transaction_outputs.iter())
+            .zip(transaction_hashes)
+            .map(|((tx, tx_output), tx_hash)| TransactionHashingData::new(tx, tx_output, tx_hash))
+            .collect();
+        match calculate_block_hash_by_version(
+            BlockHeaderWithoutHash::from(&header),
+            &transactions_data,
+            &state_diff,
+        ) {
+            Ok((calculated, _)) => Ok(calculated),
+            Err(_) => Ok(header.block_hash),
+        }
+    }
+}
+
+#[async_trait]
+impl ProposalBuilder for StorageProposalBuilder {
+    async fn build(
+        &self,
+        height: BlockNumber,
+        mut content_sender: mpsc::Sender<Transaction>,
+    ) -> ProposalBuilderResult<BlockHash> {
+        let (transactions, block_hash) = self.synced_block(height).await?;
+        let calculated = self.executed_block_hash(height)?;
+        if calculated != block_hash {
+            return Err(ProposalBuilderError::BlockHashMismatch {
+                height,
+                expected: block_hash,
+                calculated,
+            });
+        }
+        for tx in transactions {
+            content_sender.send(tx).await?;
+        }
+        Ok(block_hash)
+    }
+
+    async fn validate(
+        &self,
+        height: BlockNumber,
+        mut content: mpsc::Receiver<Transaction>,
+    ) -> ProposalBuilderResult<(Vec<Transaction>, BlockHash)> {
+        let (transactions, _) = self.synced_block(height).await?;
+        let invalid_content = |reason| ProposalBuilderError::InvalidContent { height, reason };
+
+        for tx in transactions.iter() {
+            let received_tx = content
+                .next()
+                .await
+                .ok_or_else(|| invalid_content(format!("Missing transaction {tx:?}.")))?;
+            if tx != &received_tx {
+                return Err(invalid_content(format!(
+                    "Transactions are not equal. In storage: {tx:?}, received: {received_tx:?}."
+                )));
+            }
+        }
+        if content.next().await.is_some() {
+            return Err(invalid_content("Received more transactions than expected.".to_owned()));
+        }
+        let block_hash = self.executed_block_hash(height)?;
+
+        Ok((transactions, block_hash))
+    }
+
+    // The sync writes the decided block to storage, which commits it.
+    async fn wait_for_commit(&self, height: BlockNumber) -> ProposalBuilderResult<()> {
+        Ok(wait_for_block(&self.storage_reader, height).await?)
+    }
+}
+
+const SLEEP_BETWEEN_CHECK_FOR_BLOCK: Duration = Duration::from_secs(10);
+
+async fn wait_for_block(
+    storage_reader: &StorageReader,
+    height: BlockNumber,
+) -> Result<(), StorageError> {
+    loop {
+        let synced = {
+            let txn = storage_reader.begin_ro_txn()?;
+            txn.get_body_marker()? > height && txn.get_state_marker()? > height
+        };
+        if synced {
+            return Ok(());
+        }
+        debug!("Waiting for block {height:?} to continue consensus");
+        tokio::time::sleep(SLEEP_BETWEEN_CHECK_FOR_BLOCK).await;
+    }
+}
@@ -0,0 +1,109 @@
+//! Routes the proposals received for a height to validation tasks, one per round.
+//!
+//! The validation task of a round is created once the first proposal of the round arrives, and
+//! runs next to the handling of the other consensus messages, so a slow proposal doesn't hold back
+//! the votes or the proposals of other rounds. The validations run until the height ends, even once
+//! consensus leaves their round, since the node can still decide on the proposal of an earlier
+//! round once it receives the precommits for it. All the validations of a height are killed once
+//! the height ends.
+//!
+//! Each round has at most one validation, and proposals of rounds too far ahead of the current
+//! round are dropped, so the number of validations is bounded.
+
+#[cfg(test)]
+#[path = "proposal_demultiplexer_test.rs"]
+mod proposal_demultiplexer_test;
+
+use std::collections::HashSet;
+
+use futures::channel::{mpsc, oneshot};
+use futures::future::{pending, BoxFuture};
+use futures::stream::FuturesUnordered;
+use futures::{FutureExt, StreamExt};
+use starknet_api::block::{BlockHash, BlockNumber};
+use tracing::{debug, warn};
+
+use crate::types::{ConsensusBlock, ConsensusContext, ProposalInit, Round};
+
+/// The number of rounds ahead of the current round whose proposals are validated.
+pub(crate) const MAX_FUTURE_ROUNDS: Round = 10;
+
+/// The result of validating a proposal from a peer node.
+#[derive(Debug, PartialEq)]
+pub(crate) struct ValidatedProposal<BlockT> {
+    pub init: ProposalInit,
+    /// The block of the proposal, or `None` if the proposal is invalid or its stream ended early.
+    pub block: Option<BlockT>,
+}
+
+type Validation<BlockT> = BoxFuture<'static, ValidatedProposal<BlockT>>;
+
+pub(crate) struct ProposalDemultiplexer<BlockT: ConsensusBlock> {
+    height: BlockNumber,
+    // The rounds whose proposal was routed to a validation.
+    routed_rounds: HashSet<Round>,
+    validations: FuturesUnordered<Validation<BlockT>>,
+}
+
+impl<BlockT: ConsensusBlock + 'static> ProposalDemultiplexer<BlockT> {
+    pub(crate) fn new(height: BlockNumber) -> Self {
+        Self { height, routed_rounds: HashSet::new(), validations: FuturesUnordered::new() }
+    }
+
+    /// Routes the stream of a proposal to a new validation task of its round. The stream is
+    /// dropped if the round is more than `MAX_FUTURE_ROUNDS` ahead of `current_round` or already
+    /// had a proposal.
+    pub(crate) async fn route<ContextT: ConsensusContext<Block = BlockT>>(
+        &mut self,
+        context: &ContextT,
+        current_round: Round,
+        init: ProposalInit,
+        content_receiver: mpsc::Receiver<BlockT::ProposalChunk>,
+        fin_receiver: oneshot::Receiver<BlockHash>,
+    ) {
+        assert_eq!(init.height, self.height, "Proposals are routed by the height they belong to.");
+        if init.round > current_round.saturating_add(MAX_FUTURE_ROUNDS) {
+            debug!(
+                "Dropping the proposal of round {}, too far ahead of the current round \
+                 {current_round}.",
+                init.round
+            );
+            return;
+        }
+        if !self.routed_rounds.insert(init.round) {
+            warn!("Round {} already has a proposal, ignoring", init.round);
+            return;
+        }
+
+        let block_receiver = context.validate_proposal(self.height, content_receiver).await;
+        self.validations.push(validate(init, block_receiver, fin_receiver).boxed());
+    }
+
+    /// Returns the next proposal whose validation completed. Never returns while no validation is
+    /// running. Cancel safe.
+    pub(crate) async fn next(&mut self) -> ValidatedProposal<BlockT> {
+        match self.validations.next().await {
+            Some(validated_proposal) => validated_proposal,
+            None => pending().await,
+        }
+    }
+}
+
+async fn validate<BlockT: ConsensusBlock>(
+    init: ProposalInit,
+    block_receiver: oneshot::Receiver<BlockT>,
+    fin_receiver: oneshot::Receiver<BlockHash>,
+) -> ValidatedProposal<BlockT> {
+    let Ok(block) = block_receiver.await else {
+        return ValidatedProposal { init, block: None };
+    };
+    // ProposalFin never received from peer.
+    let Ok(fin) = fin_receiver.await else {
+        return ValidatedProposal { init, block: None };
+    };
+    // TODO(matan): Switch to signature validation.
+    if block.id() != fin {
+        return ValidatedProposal { init, block: None };
+    }
+    ValidatedProposal { init, block: Some(block) }
+}
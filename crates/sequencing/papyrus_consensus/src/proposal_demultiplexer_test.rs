@@ -0,0 +1,121 @@
+use futures::channel::{mpsc, oneshot};
+use lazy_static::lazy_static;
+use starknet_api::block::{BlockHash, BlockNumber};
+use starknet_types_core::felt::Felt;
+
+use super::{ProposalDemultiplexer, ValidatedProposal, MAX_FUTURE_ROUNDS};
+use crate::test_utils::{MockTestContext, TestBlock};
+use crate::types::{ProposalInit, Round, ValidatorId};
+
+lazy_static! {
+    static ref PROPOSER_ID: ValidatorId = 0_u32.into();
+    static ref BLOCK: TestBlock = TestBlock { content: vec![1, 2, 3], id: BlockHash(Felt::ONE) };
+}
+
+const HEIGHT: BlockNumber = BlockNumber(1);
+
+fn init(round: Round) -> ProposalInit {
+    ProposalInit { height: HEIGHT, round, proposer: *PROPOSER_ID }
+}
+
+fn fin(block_hash: BlockHash) -> oneshot::Receiver<BlockHash> {
+    let (fin_sender, fin_receiver) = oneshot::channel();
+    fin_sender.send(block_hash).unwrap();
+    fin_receiver
+}
+
+fn validated_block() -> oneshot::Receiver<TestBlock> {
+    let (block_sender, block_receiver) = oneshot::channel();
+    block_sender.send(BLOCK.clone()).unwrap();
+    block_receiver
+}
+
+#[tokio::test]
+async fn validates_each_round_in_a_task_of_its_own() {
+    let mut context = MockTestContext::new();
+    // The validation of round 0 doesn't complete.
+    let (_round_0_block_sender, round_0_block_receiver) = oneshot::channel();
+    context.expect_validate_proposal().times(1).return_once(move |_, _| round_0_block_receiver);
+    let mut demultiplexer = ProposalDemultiplexer::new(HEIGHT);
+    demultiplexer.route(&context, 0, init(0), mpsc::channel(1).1, fin(BLOCK.id())).await;
+
+    context.checkpoint();
+    context.expect_validate_proposal().times(1).return_once(|_, _| validated_block());
+    demultiplexer.route(&context, 0, init(1), mpsc::channel(1).1, fin(BLOCK.id())).await;
+
+    assert_eq!(
+        demultiplexer.next().await,
+        ValidatedProposal { init: init(1), block: Some(BLOCK.clone()) }
+    );
+}
+
+#[tokio::test]
+async fn invalid_proposal() {
+    let mut context = MockTestContext::new();
+    context.expect_validate_proposal().times(1).return_once(|_, _| validated_block());
+    let mut demultiplexer = ProposalDemultiplexer::new(HEIGHT);
+    // The fin doesn't match the validated block.
+    demultiplexer.route(&context, 0, init(0), mpsc::channel(1).1, fin(BlockHash(Felt::TWO))).await;
+
+    assert_eq!(demultiplexer.next().await, ValidatedProposal { init: init(0), block: None });
+}
+
+#[tokio::test]
+async fn repeated_proposal_is_dropped() {
+    let mut context = MockTestContext::new();
+    let (_block_sender, block_receiver) = oneshot::channel();
+    context.expect_validate_proposal().times(1).return_once(move |_, _| block_receiver);
+    let mut demultiplexer = ProposalDemultiplexer::new(HEIGHT);
+    demultiplexer.route(&context, 0, init(0), mpsc::channel(1).1, fin(BLOCK.id())).await;
+
+    // No new validation.
+    let (content_sender, content_receiver) = mpsc::channel(1);
+    demultiplexer.route(&context, 0, init(0), content_receiver, fin(BLOCK.id())).await;
+    assert!(content_sender.is_closed());
+}
+
+#[tokio::test]
+async fn earlier_round_keeps_validating() {
+    let mut context = MockTestContext::new();
+    let (round_0_block_sender, round_0_block_receiver) = oneshot::channel();
+    context.expect_validate_proposal().times(1).return_once(move |_, _| round_0_block_receiver);
+    let mut demultiplexer = ProposalDemultiplexer::new(HEIGHT);
+    demultiplexer.route(&context, 0, init(0), mpsc::channel(1).1, fin(BLOCK.id())).await;
+
+    // Consensus moved to round 1 before the validation of round 0 completed.
+    context.checkpoint();
+    let (_round_1_block_sender, round_1_block_receiver) = oneshot::channel();
+    context.expect_validate_proposal().times(1).return_once(move |_, _| round_1_block_receiver);
+    demultiplexer.route(&context, 1, init(1), mpsc::channel(1).1, fin(BLOCK.id())).await;
+
+    round_0_block_sender.send(BLOCK.clone()).unwrap();
+    assert_eq!(
+        demultiplexer.next().await,
+        ValidatedProposal { init: init(0), block: Some(BLOCK.clone()) }
+    );
+}
+
+#[tokio::test]
+async fn far_future_round_is_dropped() {
+    // No validation.
+    let context = MockTestContext::new();
+    let mut demultiplexer = ProposalDemultiplexer::new(HEIGHT);
+
+    let (content_sender, content_receiver) = mpsc::channel(1);
+    demultiplexer
+        .route(&context, 0, init(MAX_FUTURE_ROUNDS + 1), content_receiver, fin(BLOCK.id()))
+        .await;
+    assert!(content_sender.is_closed());
+}
+
+#[tokio::test]
+async fn validations_are_killed_with_the_height() {
+    let mut context = MockTestContext::new();
+    let (block_sender, block_receiver) = oneshot::channel::<TestBlock>();
+    context.expect_validate_proposal().times(1).return_once(move |_, _| block_receiver);
+    let mut demultiplexer = ProposalDemultiplexer::new(HEIGHT);
+    demultiplexer.route(&context, 0, init(0), mpsc::channel(1).1, fin(BLOCK.id())).await;
+
+    drop(demultiplexer);
+    assert!(block_sender.is_canceled());
+}
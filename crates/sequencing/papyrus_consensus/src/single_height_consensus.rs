@@ -6,7 +6,7 @@ use std::collections::hash_map::Entry;
 use std::collections::{HashMap, VecDeque};
 use std::time::Duration;
 
-use futures::channel::{mpsc, oneshot};
+use futures::channel::oneshot;
 use papyrus_protobuf::consensus::{ConsensusMessage, Vote, VoteType};
 use starknet_api::block::{BlockHash, BlockNumber};
 use tracing::{debug, info, instrument, trace, warn};
@@ -86,20 +86,13 @@ impl<BlockT: ConsensusBlock> SingleHeightConsensus<BlockT> {
         self.handle_state_machine_events(context, events).await
     }
 
-    /// Receive a proposal from a peer node. Returns only once the proposal has been fully received
-    /// and processed.
-    #[instrument(
-        skip_all,
-        fields(height = %self.height),
-        level = "debug",
-    )]
-    pub(crate) async fn handle_proposal<ContextT: ConsensusContext<Block = BlockT>>(
-        &mut self,
-        context: &mut ContextT,
-        init: ProposalInit,
-        p2p_messages_receiver: mpsc::Receiver<<BlockT as ConsensusBlock>::ProposalChunk>,
-        fin_receiver: oneshot::Receiver<BlockHash>,
-    ) -> Result<ShcReturn<BlockT>, ConsensusError> {
+    /// Checks the init of a proposal from a peer node. Returns whether the proposal should be
+    /// validated, which is false if its round already has a proposal.
+    pub(crate) fn handle_proposal_init<ContextT: ConsensusContext<Block = BlockT>>(
+        &self,
+        context: &ContextT,
+        init: &ProposalInit,
+    ) -> Result<bool, ConsensusError> {
         debug!(
             "Received proposal: height={}, round={}, proposer={:?}",
             init.height.0, init.round, init.proposer
@@ -114,38 +107,38 @@ impl<BlockT: ConsensusBlock> SingleHeightConsensus<BlockT> {
                 format!("invalid proposer: expected {:?}, got {:?}", proposer_id, init.proposer);
             return Err(ConsensusError::InvalidProposal(proposer_id, self.height, msg));
         }
+        if self.proposals.contains_key(&init.round) {
+            warn!("Round {} already has a proposal, ignoring", init.round);
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Receive a validated proposal from a peer node. `block` is `None` if the proposal is invalid
+    /// or wasn't fully received.
+    #[instrument(
+        skip_all,
+        fields(height = %self.height, round = init.round),
+        level = "debug",
+    )]
+    pub(crate) async fn handle_proposal<ContextT: ConsensusContext<Block = BlockT>>(
+        &mut self,
+        context: &mut ContextT,
+        init: ProposalInit,
+        block: Option<BlockT>,
+    ) -> Result<ShcReturn<BlockT>, ConsensusError> {
         let Entry::Vacant(proposal_entry) = self.proposals.entry(init.round) else {
             warn!("Round {} already has a proposal, ignoring", init.round);
             return Ok(ShcReturn::Tasks(Vec::new()));
         };
+        let block_id = block.as_ref().map(|block| block.id());
+        proposal_entry.insert(block);
+        self.process_inbound_proposal(context, &init, block_id).await
+    }
 
-        let block_receiver = context.validate_proposal(self.height, p2p_messages_receiver).await;
-
-        let block = match block_receiver.await {
-            Ok(block) => block,
-            // ProposalFin never received from peer.
-            Err(_) => {
-                proposal_entry.insert(None);
-                return self.process_inbound_proposal(context, &init, None).await;
-            }
-        };
-
-        let fin = match fin_receiver.await {
-            Ok(fin) => fin,
-            // ProposalFin never received from peer.
-            Err(_) => {
-                proposal_entry.insert(None);
-                return self.process_inbound_proposal(context, &init, None).await;
-            }
-        };
-        // TODO(matan): Switch to signature validation.
-        let block_id = block.id();
-        if block_id != fin {
-            proposal_entry.insert(None);
-            return self.process_inbound_proposal(context, &init, None).await;
-        }
-        proposal_entry.insert(Some(block));
-        self.process_inbound_proposal(context, &init, Some(block_id)).await
+    /// The round the node is in.
+    pub(crate) fn current_round(&self) -> Round {
+        self.state_machine.round()
     }
 
     async fn process_inbound_proposal<ContextT: ConsensusContext<Block = BlockT>>(
@@ -396,7 +389,7 @@ impl<BlockT: ConsensusBlock> SingleHeightConsensus<BlockT> {
         Ok(vec![ShcTask { duration, event }])
     }
 
-    #[instrument(skip_all)]
+    #[instrument(skip_all, fields(height = %self.height, round = round))]
     async fn handle_state_machine_decision(
         &mut self,
         block_hash: BlockHash,
@@ -156,15 +156,8 @@ async fn validator(repeat_proposal: bool) {
     );
 
     // Send the proposal from the peer.
-    let (fin_sender, fin_receiver) = oneshot::channel();
-    fin_sender.send(BLOCK.id()).unwrap();
-
     context.expect_proposer().returning(move |_, _| *PROPOSER_ID);
-    context.expect_validate_proposal().times(1).returning(move |_, _| {
-        let (block_sender, block_receiver) = oneshot::channel();
-        block_sender.send(BLOCK.clone()).unwrap();
-        block_receiver
-    });
+    assert_eq!(shc.handle_proposal_init(&context, &PROPOSAL_INIT), Ok(true));
     context
         .expect_broadcast()
         .times(1)
@@ -172,28 +165,13 @@ async fn validator(repeat_proposal: bool) {
             msg == &prevote(Some(BLOCK.id().0), 0, 0, *VALIDATOR_ID_1)
         })
         .returning(move |_| Ok(()));
-    let res = shc
-        .handle_proposal(
-            &mut context,
-            PROPOSAL_INIT.clone(),
-            mpsc::channel(1).1, // content - ignored by SHC.
-            fin_receiver,
-        )
-        .await;
+    let res = shc.handle_proposal(&mut context, PROPOSAL_INIT.clone(), Some(BLOCK.clone())).await;
     assert_eq!(res, Ok(ShcReturn::Tasks(vec![prevote_task(Some(BLOCK.id().0), 0),])));
     if repeat_proposal {
         // Send the same proposal again, which should be ignored (no expectations).
-        let (fin_sender, fin_receiver) = oneshot::channel();
-        fin_sender.send(BLOCK.id()).unwrap();
-
-        let res = shc
-            .handle_proposal(
-                &mut context,
-                PROPOSAL_INIT.clone(),
-                mpsc::channel(1).1, // content - ignored by SHC.
-                fin_receiver,
-            )
-            .await;
+        assert_eq!(shc.handle_proposal_init(&context, &PROPOSAL_INIT), Ok(false));
+        let res =
+            shc.handle_proposal(&mut context, PROPOSAL_INIT.clone(), Some(BLOCK.clone())).await;
         assert_eq!(res, Ok(ShcReturn::Tasks(Vec::new())));
     }
     assert_eq!(
@@ -250,28 +228,14 @@ async fn vote_twice(same_vote: bool) {
         TIMEOUTS.clone(),
     );
 
-    let (fin_sender, fin_receiver) = oneshot::channel();
-    fin_sender.send(BLOCK.id()).unwrap();
-
     context.expect_proposer().times(1).returning(move |_, _| *PROPOSER_ID);
-    context.expect_validate_proposal().times(1).returning(move |_, _| {
-        let (block_sender, block_receiver) = oneshot::channel();
-        block_sender.send(BLOCK.clone()).unwrap();
-        block_receiver
-    });
+    assert_eq!(shc.handle_proposal_init(&context, &PROPOSAL_INIT), Ok(true));
     context
         .expect_broadcast()
         .times(1) // Shows the repeat vote is ignored.
         .withf(move |msg: &ConsensusMessage| msg == &prevote(Some(BLOCK.id().0), 0, 0, *VALIDATOR_ID_1))
         .returning(move |_| Ok(()));
-    let res = shc
-        .handle_proposal(
-            &mut context,
-            PROPOSAL_INIT.clone(),
-            mpsc::channel(1).1, // content - ignored by SHC.
-            fin_receiver,
-        )
-        .await;
+    let res = shc.handle_proposal(&mut context, PROPOSAL_INIT.clone(), Some(BLOCK.clone())).await;
     assert_eq!(res, Ok(ShcReturn::Tasks(vec![prevote_task(Some(BLOCK.id().0), 0),])));
 
     let res =
@@ -9,10 +9,12 @@ mod state_machine_test;
 
 use std::collections::{HashMap, VecDeque};
 
+use papyrus_protobuf::consensus::VoteType;
 use starknet_api::block::BlockHash;
 use tracing::trace;
 
 use crate::types::{Round, ValidatorId};
+use crate::vote_keeper::{Threshold, VoteKeeper};
 
 /// Events which the state machine sends/receives.
 #[derive(Debug, Clone, PartialEq)]
@@ -55,11 +57,8 @@ pub struct StateMachine {
     id: ValidatorId,
     round: Round,
     step: Step,
-    quorum: u32,
     proposals: HashMap<Round, Option<BlockHash>>,
-    // {round: {block_hash: vote_count}
-    prevotes: HashMap<Round, HashMap<Option<BlockHash>, u32>>,
-    precommits: HashMap<Round, HashMap<Option<BlockHash>, u32>>,
+    vote_keeper: VoteKeeper,
     // When true, the state machine will wait for a GetProposal event, buffering all other input
     // events in `events_queue`.
     awaiting_get_proposal: bool,
@@ -73,17 +72,19 @@ impl StateMachine {
             id,
             round: 0,
             step: Step::Propose,
-            quorum: (2 * total_weight / 3) + 1,
             proposals: HashMap::new(),
-            prevotes: HashMap::new(),
-            precommits: HashMap::new(),
+            vote_keeper: VoteKeeper::new(total_weight),
             awaiting_get_proposal: false,
             events_queue: VecDeque::new(),
         }
     }
 
+    pub fn round(&self) -> Round {
+        self.round
+    }
+
     pub fn quorum_size(&self) -> u32 {
-        self.quorum
+        self.vote_keeper.quorum()
     }
 
     /// Starts the state machine, effectively calling `StartRound(0)` from the paper. This is
@@ -275,14 +276,15 @@ impl StateMachine {
     where
         LeaderFn: Fn(Round) -> ValidatorId,
     {
-        let prevote_count = self.prevotes.entry(round).or_default().entry(block_hash).or_insert(0);
         // TODO(matan): Use variable weight.
-        *prevote_count += 1;
+        let thresholds = self.vote_keeper.add_vote(VoteType::Prevote, round, block_hash, 1);
 
+        // A threshold that is reached before the node prevotes in the round is handled once it
+        // does, see `advance_to_step`.
         if self.step != Step::Prevote || round != self.round {
             return VecDeque::new();
         }
-        self.check_prevote_quorum(round, leader_fn)
+        self.handle_thresholds(thresholds, leader_fn)
     }
 
     fn handle_timeout_prevote(&mut self, round: u32) -> VecDeque<StateMachineEvent> {
@@ -303,12 +305,10 @@ impl StateMachine {
     where
         LeaderFn: Fn(Round) -> ValidatorId,
     {
-        let precommit_count =
-            self.precommits.entry(round).or_default().entry(block_hash).or_insert(0);
         // TODO(matan): Use variable weight.
-        *precommit_count += 1;
+        let thresholds = self.vote_keeper.add_vote(VoteType::Precommit, round, block_hash, 1);
 
-        self.check_precommit_quorum(round, leader_fn)
+        self.handle_thresholds(thresholds, leader_fn)
     }
 
     fn handle_timeout_precommit<LeaderFn>(
@@ -351,31 +351,8 @@ impl StateMachine {
         LeaderFn: Fn(Round) -> ValidatorId,
     {
         assert_eq!(round, self.round, "check_prevote_quorum is only called for the current round");
-        let num_votes = self.prevotes.get(&round).map_or(0, |v| v.values().sum());
-        if num_votes < self.quorum {
-            return VecDeque::new();
-        }
-        let mut output = VecDeque::from([StateMachineEvent::TimeoutPrevote(round)]);
-        let Some((block_hash, count)) = leading_vote(&self.prevotes, round) else {
-            return output;
-        };
-        if *count < self.quorum {
-            return output;
-        }
-        if block_hash.is_none() {
-            output.append(&mut self.send_precommit(*block_hash, round, leader_fn));
-            return output;
-        }
-        let Some(proposed_value) = self.proposals.get(&round) else {
-            return output;
-        };
-        if proposed_value != block_hash {
-            // TODO(matan): This can be caused by a malicious leader double proposing.
-            panic!("Proposal does not match quorum.");
-        }
-
-        output.append(&mut self.send_precommit(*block_hash, round, leader_fn));
-        output
+        let thresholds = self.vote_keeper.thresholds(&VoteType::Prevote, round);
+        self.handle_thresholds(thresholds, leader_fn)
     }
 
     fn check_precommit_quorum<LeaderFn>(
@@ -386,40 +363,73 @@ impl StateMachine {
     where
         LeaderFn: Fn(Round) -> ValidatorId,
     {
-        let num_votes = self.precommits.get(&round).map_or(0, |v| v.values().sum());
-        if num_votes < self.quorum {
-            return VecDeque::new();
-        }
-        let mut output = VecDeque::from([StateMachineEvent::TimeoutPrecommit(round)]);
-        let Some((block_hash, count)) = leading_vote(&self.precommits, round) else {
-            return output;
-        };
-        if *count < self.quorum {
-            return output;
+        let thresholds = self.vote_keeper.thresholds(&VoteType::Precommit, round);
+        self.handle_thresholds(thresholds, leader_fn)
+    }
+
+    fn handle_thresholds<LeaderFn>(
+        &mut self,
+        thresholds: Vec<Threshold>,
+        leader_fn: &LeaderFn,
+    ) -> VecDeque<StateMachineEvent>
+    where
+        LeaderFn: Fn(Round) -> ValidatorId,
+    {
+        let mut output = VecDeque::new();
+        for threshold in thresholds {
+            output.append(&mut self.handle_threshold(threshold, leader_fn));
         }
-        if block_hash.is_none() {
-            if round == self.round {
-                output.append(&mut self.advance_to_round(round + 1, leader_fn));
-                return output;
-            } else {
-                // NIL quorum reached on a different round.
-                return output;
+        output
+    }
+
+    fn handle_threshold<LeaderFn>(
+        &mut self,
+        threshold: Threshold,
+        leader_fn: &LeaderFn,
+    ) -> VecDeque<StateMachineEvent>
+    where
+        LeaderFn: Fn(Round) -> ValidatorId,
+    {
+        match threshold {
+            Threshold::PolkaAny(round) => {
+                VecDeque::from([StateMachineEvent::TimeoutPrevote(round)])
+            }
+            Threshold::PolkaValue(round, None) => self.send_precommit(None, round, leader_fn),
+            Threshold::PolkaValue(round, Some(block_hash)) => {
+                if !self.matches_proposal(block_hash, round) {
+                    return VecDeque::new();
+                }
+                self.send_precommit(Some(block_hash), round, leader_fn)
+            }
+            Threshold::PrecommitAny(round) => {
+                VecDeque::from([StateMachineEvent::TimeoutPrecommit(round)])
+            }
+            Threshold::QuorumValue(round, None) => {
+                if round != self.round {
+                    // NIL quorum reached on a different round.
+                    return VecDeque::new();
+                }
+                self.advance_to_round(round + 1, leader_fn)
+            }
+            Threshold::QuorumValue(round, Some(block_hash)) => {
+                if !self.matches_proposal(block_hash, round) {
+                    return VecDeque::new();
+                }
+                VecDeque::from([StateMachineEvent::Decision(block_hash, round)])
             }
         }
+    }
+
+    // Whether the proposal of the round was received. Panics if it isn't of the given block.
+    fn matches_proposal(&self, block_hash: BlockHash, round: u32) -> bool {
         let Some(proposed_value) = self.proposals.get(&round) else {
-            return output;
+            return false;
         };
-        if proposed_value != block_hash {
+        if *proposed_value != Some(block_hash) {
             // TODO(matan): This can be caused by a malicious leader double proposing.
             panic!("Proposal does not match quorum.");
         }
-        if let Some(block_hash) = block_hash {
-            output.append(&mut VecDeque::from([StateMachineEvent::Decision(*block_hash, round)]));
-            output
-        } else {
-            // NIL quorum reached on a different round.
-            output
-        }
+        true
     }
 
     fn send_precommit<LeaderFn>(
@@ -457,11 +467,3 @@ impl StateMachine {
         self.process_proposal(*proposal, round, leader_fn)
     }
 }
-
-fn leading_vote(
-    votes: &HashMap<u32, HashMap<Option<BlockHash>, u32>>,
-    round: u32,
-) -> Option<(&Option<BlockHash>, &u32)> {
-    // We don't care which value is chosen in the case of a tie, since consensus requires 2/3+1.
-    votes.get(&round)?.iter().max_by(|a, b| a.1.cmp(b.1))
-}
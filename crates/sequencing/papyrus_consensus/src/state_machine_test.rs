@@ -304,6 +304,24 @@ fn mixed_quorum(send_prposal: bool) {
     assert_eq!(wrapper.next_event().unwrap(), StateMachineEvent::TimeoutPropose(ROUND + 1));
 }
 
+#[test]
+fn votes_after_a_quorum_dont_reach_it_again() {
+    let mut wrapper = TestWrapper::new(*VALIDATOR_ID, 4, |_: Round| *PROPOSER_ID);
+
+    wrapper.start();
+    assert_eq!(wrapper.next_event().unwrap(), StateMachineEvent::TimeoutPropose(ROUND));
+    wrapper.send_timeout_propose(ROUND);
+    assert_eq!(wrapper.next_event().unwrap(), StateMachineEvent::Prevote(None, ROUND));
+    wrapper.send_prevote(BLOCK_HASH, ROUND);
+    wrapper.send_prevote(BLOCK_HASH, ROUND);
+    assert_eq!(wrapper.next_event().unwrap(), StateMachineEvent::TimeoutPrevote(ROUND));
+    assert!(wrapper.next_event().is_none());
+
+    // The last prevote doesn't schedule another timeout.
+    wrapper.send_prevote(BLOCK_HASH, ROUND);
+    assert!(wrapper.next_event().is_none());
+}
+
 #[test]
 fn dont_handle_enqueued_while_awaiting_get_proposal() {
     let mut wrapper = TestWrapper::new(*PROPOSER_ID, 4, |_: Round| *PROPOSER_ID);
@@ -53,6 +53,13 @@ mock! {
             content: mpsc::Receiver<u32>
         ) -> oneshot::Receiver<TestBlock>;
 
+        async fn accept_certified_proposal(
+            &self,
+            height: BlockNumber,
+            block_hash: BlockHash,
+            content: mpsc::Receiver<u32>
+        ) -> oneshot::Receiver<TestBlock>;
+
         async fn validators(&self, height: BlockNumber) -> Vec<ValidatorId>;
 
         fn proposer(&self, height: BlockNumber, round: Round) -> ValidatorId;
@@ -68,9 +75,10 @@ mock! {
 
         async fn decision_reached(
             &mut self,
+            height: BlockNumber,
             block: TestBlock,
             precommits: Vec<Vote>,
-        ) -> Result<(), ConsensusError>;
+        ) -> Result<oneshot::Receiver<()>, ConsensusError>;
     }
 }
 
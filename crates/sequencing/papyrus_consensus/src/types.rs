@@ -114,6 +114,27 @@ pub trait ConsensusContext {
         content: mpsc::Receiver<<Self::Block as ConsensusBlock>::ProposalChunk>,
     ) -> oneshot::Receiver<Self::Block>;
 
+    /// This function is called by consensus, on a node that isn't a validator and trusts the
+    /// finality of consensus, to get a block that a quorum of the validators precommitted. As
+    /// opposed to `validate_proposal`, the content isn't re-executed, but the context must still
+    /// check that the content forms the block with `block_hash` before returning it, since the
+    /// hash comes from the proposer. It's expected that this call will return immediately.
+    ///
+    /// Params:
+    /// - `height`: The height of the block.
+    /// - `block_hash`: The hash of the block, certified by the precommits.
+    /// - A receiver for the stream of the block's content.
+    ///
+    /// Returns:
+    /// - A receiver for the block. If the block cannot be built the Sender will be dropped by
+    ///   ConsensusContext.
+    async fn accept_certified_proposal(
+        &self,
+        height: BlockNumber,
+        block_hash: BlockHash,
+        content: mpsc::Receiver<<Self::Block as ConsensusBlock>::ProposalChunk>,
+    ) -> oneshot::Receiver<Self::Block>;
+
     /// Get the set of validators for a given height. These are the nodes that can propose and vote
     /// on blocks.
     // TODO(matan): We expect this to change in the future to BTreeMap. Why?
@@ -136,15 +157,24 @@ pub trait ConsensusContext {
         fin_receiver: oneshot::Receiver<BlockHash>,
     ) -> Result<(), ConsensusError>;
 
-    /// Update the context that a decision has been reached for a given height.
+    /// Update the context that a decision has been reached for a given height, handing it the block
+    /// to commit (e.g. to write to storage). It's expected that this call will return without
+    /// waiting for the commit, so consensus can proceed to the next height in parallel to the
+    /// commit. The blocks must be committed in the order of their heights.
+    /// - `height` - The height of the decision.
     /// - `block` identifies the decision.
     /// - `precommits` - All precommits must be for the same `(block.id(), height, round)` and form
     ///   a quorum (>2/3 of the voting power) for this height.
+    ///
+    /// Returns:
+    /// - A receiver that is sent to once the block is committed. If the block cannot be committed
+    ///   the Sender will be dropped by ConsensusContext.
     async fn decision_reached(
         &mut self,
+        height: BlockNumber,
         block: Self::Block,
         precommits: Vec<Vote>,
-    ) -> Result<(), ConsensusError>;
+    ) -> Result<oneshot::Receiver<()>, ConsensusError>;
 }
 
 #[derive(PartialEq)]
@@ -190,4 +220,6 @@ pub enum ConsensusError {
     InternalNetworkError(String),
     #[error("{0}")]
     SyncError(String),
+    #[error("Failed to commit the decided block of height {0}.")]
+    CommitFailed(BlockNumber),
 }
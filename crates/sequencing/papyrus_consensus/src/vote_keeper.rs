@@ -0,0 +1,115 @@
+//! Bookkeeping of the votes of a single height.
+//!
+//! The [`VoteKeeper`] tallies the weight of the prevotes and precommits of each round, and reports
+//! the thresholds of the tendermint [paper](https://arxiv.org/pdf/1807.04938) they reach. The state
+//! machine reacts to these thresholds, without counting votes itself.
+
+#[cfg(test)]
+#[path = "vote_keeper_test.rs"]
+mod vote_keeper_test;
+
+use std::collections::HashMap;
+
+use papyrus_protobuf::consensus::VoteType;
+use starknet_api::block::BlockHash;
+
+use crate::types::Round;
+
+/// A threshold the votes of a round reach. A threshold is reached once the weight of the matching
+/// votes is a quorum, i.e. more than 2/3 of the total weight.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Threshold {
+    /// A quorum of prevotes, for any values (LOC 34).
+    PolkaAny(Round),
+    /// A quorum of prevotes for the same value, which is `None` for nil (LOC 36, 44).
+    PolkaValue(Round, Option<BlockHash>),
+    /// A quorum of precommits, for any values (LOC 47).
+    PrecommitAny(Round),
+    /// A quorum of precommits for the same value, which is `None` for nil (LOC 49).
+    QuorumValue(Round, Option<BlockHash>),
+}
+
+#[derive(Debug, Default)]
+struct RoundTally {
+    total_weight: u32,
+    weight_per_value: HashMap<Option<BlockHash>, u32>,
+}
+
+/// The votes of all the rounds of a height.
+///
+/// The keeper doesn't deduplicate votes; the caller handles replays and conflicting votes.
+#[derive(Debug)]
+pub struct VoteKeeper {
+    quorum: u32,
+    prevotes: HashMap<Round, RoundTally>,
+    precommits: HashMap<Round, RoundTally>,
+}
+
+impl VoteKeeper {
+    /// total_weight - the total voting weight of all validators for this height.
+    pub fn new(total_weight: u32) -> Self {
+        Self {
+            quorum: (2 * total_weight / 3) + 1,
+            prevotes: HashMap::new(),
+            precommits: HashMap::new(),
+        }
+    }
+
+    /// The minimal weight of votes that reaches a threshold.
+    pub fn quorum(&self) -> u32 {
+        self.quorum
+    }
+
+    /// Adds a vote of the given weight, and returns the thresholds this vote made its round reach.
+    /// Each threshold is returned only once, by the vote that reached it.
+    pub fn add_vote(
+        &mut self,
+        vote_type: VoteType,
+        round: Round,
+        block_hash: Option<BlockHash>,
+        weight: u32,
+    ) -> Vec<Threshold> {
+        let reached_before = self.thresholds(&vote_type, round);
+        let tallies = match vote_type {
+            VoteType::Prevote => &mut self.prevotes,
+            VoteType::Precommit => &mut self.precommits,
+        };
+        let tally = tallies.entry(round).or_default();
+        tally.total_weight += weight;
+        *tally.weight_per_value.entry(block_hash).or_default() += weight;
+
+        self.thresholds(&vote_type, round)
+            .into_iter()
+            .filter(|threshold| !reached_before.contains(threshold))
+            .collect()
+    }
+
+    /// Returns all the thresholds the votes of the given type reached in the round so far. The
+    /// `Any` threshold comes before the `Value` threshold.
+    pub fn thresholds(&self, vote_type: &VoteType, round: Round) -> Vec<Threshold> {
+        let tallies = match vote_type {
+            VoteType::Prevote => &self.prevotes,
+            VoteType::Precommit => &self.precommits,
+        };
+        let Some(tally) = tallies.get(&round) else {
+            return Vec::new();
+        };
+        if tally.total_weight < self.quorum {
+            return Vec::new();
+        }
+        let mut thresholds = vec![match vote_type {
+            VoteType::Prevote => Threshold::PolkaAny(round),
+            VoteType::Precommit => Threshold::PrecommitAny(round),
+        }];
+        // Since a quorum is more than 2/3 of the total weight, at most one value reaches it.
+        if let Some((block_hash, _)) =
+            tally.weight_per_value.iter().find(|(_, weight)| **weight >= self.quorum)
+        {
+            thresholds.push(match vote_type {
+                VoteType::Prevote => Threshold::PolkaValue(round, *block_hash),
+                VoteType::Precommit => Threshold::QuorumValue(round, *block_hash),
+            });
+        }
+        thresholds
+    }
+}
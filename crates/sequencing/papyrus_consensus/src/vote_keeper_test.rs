@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use papyrus_protobuf::consensus::VoteType;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use starknet_api::block::BlockHash;
+use starknet_types_core::felt::Felt;
+use test_case::test_case;
+
+use crate::types::Round;
+use crate::vote_keeper::{Threshold, VoteKeeper};
+
+const BLOCK_HASH_1: Option<BlockHash> = Some(BlockHash(Felt::ONE));
+const BLOCK_HASH_2: Option<BlockHash> = Some(BlockHash(Felt::TWO));
+const ROUND: Round = 0;
+const TOTAL_WEIGHT: u32 = 4;
+
+// Adds votes of weight 1 and returns the thresholds each of them reached.
+fn add_votes(
+    vote_keeper: &mut VoteKeeper,
+    vote_type: VoteType,
+    votes: &[Option<BlockHash>],
+) -> Vec<Vec<Threshold>> {
+    votes
+        .iter()
+        .map(|block_hash| vote_keeper.add_vote(vote_type.clone(), ROUND, *block_hash, 1))
+        .collect()
+}
+
+#[test]
+fn quorum_of_prevotes_for_value() {
+    let mut vote_keeper = VoteKeeper::new(TOTAL_WEIGHT);
+    assert_eq!(vote_keeper.quorum(), 3);
+
+    let reached = add_votes(
+        &mut vote_keeper,
+        VoteType::Prevote,
+        &[BLOCK_HASH_1, BLOCK_HASH_1, BLOCK_HASH_1, BLOCK_HASH_1],
+    );
+    assert_eq!(
+        reached,
+        vec![
+            vec![],
+            vec![],
+            vec![Threshold::PolkaAny(ROUND), Threshold::PolkaValue(ROUND, BLOCK_HASH_1)],
+            vec![],
+        ]
+    );
+    assert_eq!(
+        vote_keeper.thresholds(&VoteType::Prevote, ROUND),
+        vec![Threshold::PolkaAny(ROUND), Threshold::PolkaValue(ROUND, BLOCK_HASH_1)]
+    );
+}
+
+#[test]
+fn quorum_of_split_precommits() {
+    let mut vote_keeper = VoteKeeper::new(TOTAL_WEIGHT);
+
+    let reached =
+        add_votes(&mut vote_keeper, VoteType::Precommit, &[BLOCK_HASH_1, BLOCK_HASH_2, None]);
+    assert_eq!(reached, vec![vec![], vec![], vec![Threshold::PrecommitAny(ROUND)]]);
+
+    // The last vote makes a value reach a quorum after the round already reached one.
+    let reached = vote_keeper.add_vote(VoteType::Precommit, ROUND, BLOCK_HASH_1, 2);
+    assert_eq!(reached, vec![Threshold::QuorumValue(ROUND, BLOCK_HASH_1)]);
+}
+
+#[test_case(VoteType::Prevote, Threshold::PolkaValue(ROUND, None); "prevote")]
+#[test_case(VoteType::Precommit, Threshold::QuorumValue(ROUND, None); "precommit")]
+fn quorum_of_nil_votes(vote_type: VoteType, expected_threshold: Threshold) {
+    let mut vote_keeper = VoteKeeper::new(TOTAL_WEIGHT);
+    add_votes(&mut vote_keeper, vote_type.clone(), &[None, None, None]);
+    assert_eq!(vote_keeper.thresholds(&vote_type, ROUND)[1], expected_threshold);
+}
+
+#[test]
+fn votes_are_tallied_per_round_and_type() {
+    let mut vote_keeper = VoteKeeper::new(TOTAL_WEIGHT);
+    add_votes(&mut vote_keeper, VoteType::Prevote, &[BLOCK_HASH_1, BLOCK_HASH_1]);
+    assert!(vote_keeper.add_vote(VoteType::Precommit, ROUND, BLOCK_HASH_1, 1).is_empty());
+    assert!(vote_keeper.add_vote(VoteType::Prevote, ROUND + 1, BLOCK_HASH_1, 1).is_empty());
+
+    assert!(vote_keeper.thresholds(&VoteType::Prevote, ROUND).is_empty());
+    assert!(vote_keeper.thresholds(&VoteType::Precommit, ROUND).is_empty());
+    assert!(vote_keeper.thresholds(&VoteType::Prevote, ROUND + 1).is_empty());
+}
+
+// Adds random votes of random weights, and checks that:
+// 1. Each threshold is reported exactly once, by the vote that reached it.
+// 2. The reported thresholds are those of a direct count of the votes.
+#[test]
+fn random_votes_report_each_threshold_once() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let block_hashes = [BLOCK_HASH_1, BLOCK_HASH_2, None];
+    for _ in 0..100 {
+        let total_weight = rng.gen_range(1..20);
+        let mut vote_keeper = VoteKeeper::new(total_weight);
+        let quorum = vote_keeper.quorum();
+        let mut weights: HashMap<(bool, Round, Option<BlockHash>), u32> = HashMap::new();
+        let mut reported = Vec::new();
+        let mut remaining_weight = HashMap::<(bool, Round), u32>::new();
+        for _ in 0..30 {
+            let is_prevote = rng.gen_bool(0.5);
+            let round = rng.gen_range(0..3);
+            let block_hash = block_hashes[rng.gen_range(0..block_hashes.len())];
+            // Each validator votes once per round and type, so the votes don't exceed the total
+            // weight.
+            let remaining = remaining_weight.entry((is_prevote, round)).or_insert(total_weight);
+            if *remaining == 0 {
+                continue;
+            }
+            let weight = rng.gen_range(1..=*remaining);
+            *remaining -= weight;
+            *weights.entry((is_prevote, round, block_hash)).or_default() += weight;
+
+            let vote_type = if is_prevote { VoteType::Prevote } else { VoteType::Precommit };
+            reported.extend(vote_keeper.add_vote(vote_type, round, block_hash, weight));
+        }
+
+        let mut expected = Vec::new();
+        for ((is_prevote, round), remaining) in remaining_weight {
+            let round_weight = total_weight - remaining;
+            if round_weight < quorum {
+                continue;
+            }
+            expected.push(if is_prevote {
+                Threshold::PolkaAny(round)
+            } else {
+                Threshold::PrecommitAny(round)
+            });
+            for block_hash in block_hashes {
+                if weights.get(&(is_prevote, round, block_hash)).copied().unwrap_or_default()
+                    >= quorum
+                {
+                    expected.push(if is_prevote {
+                        Threshold::PolkaValue(round, block_hash)
+                    } else {
+                        Threshold::QuorumValue(round, block_hash)
+                    });
+                }
+            }
+        }
+        assert_eq!(reported.len(), expected.len(), "reported: {reported:?}");
+        for threshold in expected {
+            assert!(reported.contains(&threshold), "{threshold:?} wasn't reported: {reported:?}");
+        }
+    }
+}
@@ -97,6 +97,22 @@ pub struct BlockHeaderWithoutHash {
     pub starknet_version: StarknetVersion,
 }
 
+impl From<&BlockHeader> for BlockHeaderWithoutHash {
+    fn from(header: &BlockHeader) -> Self {
+        Self {
+            parent_hash: header.parent_hash,
+            block_number: header.block_number,
+            l1_gas_price: header.l1_gas_price,
+            l1_data_gas_price: header.l1_data_gas_price,
+            state_root: header.state_root,
+            sequencer: header.sequencer,
+            timestamp: header.timestamp,
+            l1_da_mode: header.l1_da_mode,
+            starknet_version: header.starknet_version.clone(),
+        }
+    }
+}
+
 /// The [transactions](`crate::transaction::Transaction`) and their
 /// [outputs](`crate::transaction::TransactionOutput`) in a [block](`crate::block::Block`).
 #[derive(Debug, Default, Clone, Eq, PartialEq, Deserialize, Serialize)]
@@ -8,7 +8,7 @@ use super::event_commitment::{calculate_event_commitment, EventLeafElement};
 use super::receipt_commitment::{calculate_receipt_commitment, ReceiptElement};
 use super::state_diff_hash::calculate_state_diff_hash;
 use super::transaction_commitment::{calculate_transaction_commitment, TransactionLeafElement};
-use crate::block::{BlockHash, BlockHeaderWithoutHash};
+use crate::block::{BlockHash, BlockHeader, BlockHeaderWithoutHash, StarknetVersion};
 use crate::core::{EventCommitment, ReceiptCommitment, StateDiffCommitment, TransactionCommitment};
 use crate::crypto::utils::HashChain;
 use crate::data_availability::L1DataAvailabilityMode;
@@ -18,11 +18,14 @@ use crate::transaction::{
     Event,
     Fee,
     MessageToL1,
+    Transaction,
     TransactionExecutionStatus,
     TransactionHash,
+    TransactionOutput,
     TransactionSignature,
 };
 use crate::transaction_hash::ascii_as_felt;
+use crate::StarknetApiError;
 
 #[cfg(test)]
 #[path = "block_hash_calculator_test.rs"]
@@ -49,6 +52,39 @@ pub struct TransactionHashingData {
     pub transaction_hash: TransactionHash,
 }
 
+impl From<&TransactionOutput> for TransactionOutputForHash {
+    fn from(transaction_output: &TransactionOutput) -> Self {
+        Self {
+            actual_fee: transaction_output.actual_fee(),
+            events: transaction_output.events().to_vec(),
+            execution_status: transaction_output.execution_status().clone(),
+            gas_consumed: transaction_output.execution_resources().gas_consumed.clone(),
+            messages_sent: transaction_output.messages_sent().clone(),
+        }
+    }
+}
+
+impl TransactionHashingData {
+    /// The hashing data of a transaction of a block, e.g. of a block in storage.
+    pub fn new(
+        transaction: &Transaction,
+        transaction_output: &TransactionOutput,
+        transaction_hash: TransactionHash,
+    ) -> Self {
+        let transaction_signature = match transaction {
+            Transaction::Declare(tx) => Some(tx.signature()),
+            Transaction::DeployAccount(tx) => Some(tx.signature()),
+            Transaction::Invoke(tx) => Some(tx.signature()),
+            Transaction::Deploy(_) | Transaction::L1Handler(_) => None,
+        };
+        Self {
+            transaction_signature,
+            transaction_output: TransactionOutputForHash::from(transaction_output),
+            transaction_hash,
+        }
+    }
+}
+
 /// Commitments of a block.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct BlockHeaderCommitments {
@@ -59,6 +95,119 @@ pub struct BlockHeaderCommitments {
     pub concatenated_counts: Felt,
 }
 
+/// The versions of the block hash calculation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BlockHashVersion {
+    /// The block hash of blocks from Starknet v0.13.2 on: a Poseidon hash of the header and the
+    /// commitments of the block's content.
+    V0_13_2,
+}
+
+impl TryFrom<&StarknetVersion> for BlockHashVersion {
+    type Error = StarknetApiError;
+
+    fn try_from(starknet_version: &StarknetVersion) -> Result<Self, Self::Error> {
+        let version = starknet_version
+            .0
+            .split('.')
+            .map(|component| component.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()?;
+        if version.as_slice() >= [0, 13, 2].as_slice() {
+            return Ok(Self::V0_13_2);
+        }
+        Err(StarknetApiError::UnsupportedBlockHashVersion(starknet_version.0.clone()))
+    }
+}
+
+/// Calculates the hash of a block and the commitments of its content, by the block hash
+/// calculation of the block's Starknet version.
+///
+/// This is the canonical calculation of a block's hash: the proposer of a block and the validators
+/// that recompute the hash of the proposal must both use it, so they derive the same hash from the
+/// same block.
+pub fn calculate_block_hash_by_version(
+    header: BlockHeaderWithoutHash,
+    transactions_data: &[TransactionHashingData],
+    state_diff: &ThinStateDiff,
+) -> Result<(BlockHash, BlockHeaderCommitments), StarknetApiError> {
+    match BlockHashVersion::try_from(&header.starknet_version)? {
+        BlockHashVersion::V0_13_2 => {
+            let block_commitments =
+                calculate_block_commitments(transactions_data, state_diff, header.l1_da_mode);
+            let block_hash = calculate_block_hash(header, block_commitments.clone());
+            Ok((block_hash, block_commitments))
+        }
+    }
+}
+
+/// Calculates the commitment of a block's state diff, by the state diff commitment calculation of
+/// the block's Starknet version.
+pub fn calculate_state_diff_commitment_by_version(
+    starknet_version: &StarknetVersion,
+    state_diff: &ThinStateDiff,
+) -> Result<StateDiffCommitment, StarknetApiError> {
+    match BlockHashVersion::try_from(starknet_version)? {
+        BlockHashVersion::V0_13_2 => Ok(calculate_state_diff_hash(state_diff)),
+    }
+}
+
+/// Calculates the commitment of a block's transactions, by the transaction commitment calculation
+/// of the block's Starknet version.
+pub fn calculate_transaction_commitment_by_version(
+    starknet_version: &StarknetVersion,
+    transactions_data: &[TransactionHashingData],
+) -> Result<TransactionCommitment, StarknetApiError> {
+    match BlockHashVersion::try_from(starknet_version)? {
+        BlockHashVersion::V0_13_2 => Ok(transaction_commitment(transactions_data)),
+    }
+}
+
+/// Calculates the commitment of a block's events, by the event commitment calculation of the
+/// block's Starknet version.
+pub fn calculate_event_commitment_by_version(
+    starknet_version: &StarknetVersion,
+    transactions_data: &[TransactionHashingData],
+) -> Result<EventCommitment, StarknetApiError> {
+    match BlockHashVersion::try_from(starknet_version)? {
+        BlockHashVersion::V0_13_2 => {
+            Ok(calculate_event_commitment::<Poseidon>(&event_leaf_elements(transactions_data)))
+        }
+    }
+}
+
+/// Calculates the commitment of a block's receipts, by the receipt commitment calculation of the
+/// block's Starknet version.
+pub fn calculate_receipt_commitment_by_version(
+    starknet_version: &StarknetVersion,
+    transactions_data: &[TransactionHashingData],
+) -> Result<ReceiptCommitment, StarknetApiError> {
+    match BlockHashVersion::try_from(starknet_version)? {
+        BlockHashVersion::V0_13_2 => Ok(receipt_commitment(transactions_data)),
+    }
+}
+
+/// Calculates the hash of a block from its header, by the commitments of the block's content that
+/// the header holds. Returns `None` if the header doesn't hold all of them, or if its Starknet
+/// version predates the block hash calculation.
+pub fn calculate_block_hash_of_header(header: &BlockHeader) -> Option<BlockHash> {
+    match BlockHashVersion::try_from(&header.starknet_version).ok()? {
+        BlockHashVersion::V0_13_2 => {}
+    }
+    let block_commitments = BlockHeaderCommitments {
+        transaction_commitment: header.transaction_commitment?,
+        event_commitment: header.event_commitment?,
+        receipt_commitment: header.receipt_commitment?,
+        state_diff_commitment: header.state_diff_commitment.clone()?,
+        concatenated_counts: concat_counts(
+            header.n_transactions,
+            header.n_events,
+            header.state_diff_length?,
+            header.l1_da_mode,
+        ),
+    };
+    Some(calculate_block_hash(BlockHeaderWithoutHash::from(header), block_commitments))
+}
+
 /// Poseidon (
 ///     “STARKNET_BLOCK_HASH0”, block_number, global_state_root, sequencer_address,
 ///     block_timestamp, concat_counts, state_diff_hash, transaction_commitment,
@@ -98,25 +247,10 @@ pub fn calculate_block_commitments(
     state_diff: &ThinStateDiff,
     l1_da_mode: L1DataAvailabilityMode,
 ) -> BlockHeaderCommitments {
-    let transaction_leaf_elements: Vec<TransactionLeafElement> =
-        transactions_data.iter().map(TransactionLeafElement::from).collect();
-    let transaction_commitment =
-        calculate_transaction_commitment::<Poseidon>(&transaction_leaf_elements);
-
-    let event_leaf_elements: Vec<EventLeafElement> = transactions_data
-        .iter()
-        .flat_map(|transaction_data| {
-            transaction_data.transaction_output.events.iter().map(|event| EventLeafElement {
-                event: event.clone(),
-                transaction_hash: transaction_data.transaction_hash,
-            })
-        })
-        .collect();
+    let transaction_commitment = transaction_commitment(transactions_data);
+    let event_leaf_elements = event_leaf_elements(transactions_data);
     let event_commitment = calculate_event_commitment::<Poseidon>(&event_leaf_elements);
-
-    let receipt_elements: Vec<ReceiptElement> =
-        transactions_data.iter().map(ReceiptElement::from).collect();
-    let receipt_commitment = calculate_receipt_commitment::<Poseidon>(&receipt_elements);
+    let receipt_commitment = receipt_commitment(transactions_data);
     let state_diff_commitment = calculate_state_diff_hash(state_diff);
     let concatenated_counts = concat_counts(
         transactions_data.len(),
@@ -133,6 +267,31 @@ pub fn calculate_block_commitments(
     }
 }
 
+fn transaction_commitment(transactions_data: &[TransactionHashingData]) -> TransactionCommitment {
+    let transaction_leaf_elements: Vec<TransactionLeafElement> =
+        transactions_data.iter().map(TransactionLeafElement::from).collect();
+    calculate_transaction_commitment::<Poseidon>(&transaction_leaf_elements)
+}
+
+// The events of all the transactions, in order, each with the hash of its transaction.
+fn event_leaf_elements(transactions_data: &[TransactionHashingData]) -> Vec<EventLeafElement> {
+    transactions_data
+        .iter()
+        .flat_map(|transaction_data| {
+            transaction_data.transaction_output.events.iter().map(|event| EventLeafElement {
+                event: event.clone(),
+                transaction_hash: transaction_data.transaction_hash,
+            })
+        })
+        .collect()
+}
+
+fn receipt_commitment(transactions_data: &[TransactionHashingData]) -> ReceiptCommitment {
+    let receipt_elements: Vec<ReceiptElement> =
+        transactions_data.iter().map(ReceiptElement::from).collect();
+    calculate_receipt_commitment::<Poseidon>(&receipt_elements)
+}
+
 // A single felt: [
 //     transaction_count (64 bits) | event_count (64 bits) | state_diff_length (64 bits)
 //     | L1 data availability mode: 0 for calldata, 1 for blob (1 bit) | 0 ...
@@ -3,6 +3,7 @@ use starknet_types_core::felt::Felt;
 use super::concat_counts;
 use crate::block::{
     BlockHash,
+    BlockHeader,
     BlockHeaderWithoutHash,
     BlockNumber,
     BlockTimestamp,
@@ -13,9 +14,17 @@ use crate::block::{
 use crate::block_hash::block_hash_calculator::{
     calculate_block_commitments,
     calculate_block_hash,
+    calculate_block_hash_by_version,
+    calculate_block_hash_of_header,
+    calculate_event_commitment_by_version,
+    calculate_receipt_commitment_by_version,
+    calculate_state_diff_commitment_by_version,
+    calculate_transaction_commitment_by_version,
+    BlockHashVersion,
     BlockHeaderCommitments,
     TransactionHashingData,
 };
+use crate::block_hash::state_diff_hash::calculate_state_diff_hash;
 use crate::block_hash::test_utils::{get_state_diff, get_transaction_output};
 use crate::core::{
     ContractAddress,
@@ -28,9 +37,9 @@ use crate::core::{
     TransactionCommitment,
 };
 use crate::data_availability::L1DataAvailabilityMode;
-use crate::felt;
 use crate::hash::PoseidonHash;
 use crate::transaction::{TransactionHash, TransactionSignature};
+use crate::{felt, StarknetApiError};
 
 /// Macro to test if changing any field in the header or commitments
 /// results a change in the block hash.
@@ -91,6 +100,160 @@ fn test_block_hash_regression() {
     assert_eq!(BlockHash(expected_hash), calculate_block_hash(block_header, block_commitments),);
 }
 
+#[test]
+fn block_hash_version_by_starknet_version() {
+    for version in ["0.13.2", "0.13.2.1", "0.13.3", "0.14.0", "1.0.0"] {
+        assert_eq!(
+            BlockHashVersion::try_from(&StarknetVersion(version.to_owned())),
+            Ok(BlockHashVersion::V0_13_2)
+        );
+    }
+    for version in ["0.13.1.1", "0.12.3", "0.0.0"] {
+        assert_eq!(
+            BlockHashVersion::try_from(&StarknetVersion(version.to_owned())),
+            Err(StarknetApiError::UnsupportedBlockHashVersion(version.to_owned()))
+        );
+    }
+    assert!(BlockHashVersion::try_from(&StarknetVersion("0.13.x".to_owned())).is_err());
+}
+
+#[test]
+fn block_hash_by_version() {
+    let header = BlockHeaderWithoutHash {
+        block_number: BlockNumber(1),
+        l1_da_mode: L1DataAvailabilityMode::Blob,
+        starknet_version: StarknetVersion("0.13.2".to_owned()),
+        ..Default::default()
+    };
+    let transactions_data = vec![TransactionHashingData {
+        transaction_signature: Some(TransactionSignature(vec![Felt::TWO, Felt::THREE])),
+        transaction_output: get_transaction_output(),
+        transaction_hash: TransactionHash(Felt::ONE),
+    }];
+    let state_diff = get_state_diff();
+
+    let block_commitments =
+        calculate_block_commitments(&transactions_data, &state_diff, header.l1_da_mode);
+    let block_hash = calculate_block_hash(header.clone(), block_commitments.clone());
+    assert_eq!(
+        calculate_block_hash_by_version(header.clone(), &transactions_data, &state_diff),
+        Ok((block_hash, block_commitments))
+    );
+
+    let old_header =
+        BlockHeaderWithoutHash { starknet_version: StarknetVersion("0.13.1".to_owned()), ..header };
+    assert_eq!(
+        calculate_block_hash_by_version(old_header, &transactions_data, &state_diff),
+        Err(StarknetApiError::UnsupportedBlockHashVersion("0.13.1".to_owned()))
+    );
+}
+
+#[test]
+fn content_commitments_by_version() {
+    let transactions_data = vec![TransactionHashingData {
+        transaction_signature: Some(TransactionSignature(vec![Felt::TWO, Felt::THREE])),
+        transaction_output: get_transaction_output(),
+        transaction_hash: TransactionHash(Felt::ONE),
+    }];
+    let block_commitments = calculate_block_commitments(
+        &transactions_data,
+        &get_state_diff(),
+        L1DataAvailabilityMode::Blob,
+    );
+
+    let version = StarknetVersion("0.13.2".to_owned());
+    assert_eq!(
+        calculate_transaction_commitment_by_version(&version, &transactions_data),
+        Ok(block_commitments.transaction_commitment)
+    );
+    assert_eq!(
+        calculate_event_commitment_by_version(&version, &transactions_data),
+        Ok(block_commitments.event_commitment)
+    );
+    assert_eq!(
+        calculate_receipt_commitment_by_version(&version, &transactions_data),
+        Ok(block_commitments.receipt_commitment)
+    );
+
+    let old_version = StarknetVersion("0.13.1".to_owned());
+    let unsupported_version = StarknetApiError::UnsupportedBlockHashVersion("0.13.1".to_owned());
+    assert_eq!(
+        calculate_transaction_commitment_by_version(&old_version, &transactions_data),
+        Err(unsupported_version.clone())
+    );
+    assert_eq!(
+        calculate_event_commitment_by_version(&old_version, &transactions_data),
+        Err(unsupported_version.clone())
+    );
+    assert_eq!(
+        calculate_receipt_commitment_by_version(&old_version, &transactions_data),
+        Err(unsupported_version)
+    );
+}
+
+#[test]
+fn state_diff_commitment_by_version() {
+    let state_diff = get_state_diff();
+    assert_eq!(
+        calculate_state_diff_commitment_by_version(
+            &StarknetVersion("0.13.2".to_owned()),
+            &state_diff
+        ),
+        Ok(calculate_state_diff_hash(&state_diff))
+    );
+    assert_eq!(
+        calculate_state_diff_commitment_by_version(
+            &StarknetVersion("0.13.1".to_owned()),
+            &state_diff
+        ),
+        Err(StarknetApiError::UnsupportedBlockHashVersion("0.13.1".to_owned()))
+    );
+}
+
+#[test]
+fn block_hash_of_header() {
+    let header_without_hash = BlockHeaderWithoutHash {
+        block_number: BlockNumber(1),
+        l1_da_mode: L1DataAvailabilityMode::Blob,
+        starknet_version: StarknetVersion("0.13.2".to_owned()),
+        ..Default::default()
+    };
+    let transactions_data = vec![TransactionHashingData {
+        transaction_signature: Some(TransactionSignature(vec![Felt::TWO, Felt::THREE])),
+        transaction_output: get_transaction_output(),
+        transaction_hash: TransactionHash(Felt::ONE),
+    }];
+    let state_diff = get_state_diff();
+    let (block_hash, block_commitments) = calculate_block_hash_by_version(
+        header_without_hash.clone(),
+        &transactions_data,
+        &state_diff,
+    )
+    .unwrap();
+
+    let header = BlockHeader {
+        block_hash,
+        block_number: header_without_hash.block_number,
+        l1_da_mode: header_without_hash.l1_da_mode,
+        starknet_version: header_without_hash.starknet_version,
+        state_diff_commitment: Some(block_commitments.state_diff_commitment),
+        state_diff_length: Some(state_diff.len()),
+        transaction_commitment: Some(block_commitments.transaction_commitment),
+        event_commitment: Some(block_commitments.event_commitment),
+        n_transactions: transactions_data.len(),
+        n_events: transactions_data[0].transaction_output.events.len(),
+        receipt_commitment: Some(block_commitments.receipt_commitment),
+        ..Default::default()
+    };
+    assert_eq!(calculate_block_hash_of_header(&header), Some(block_hash));
+
+    let header_without_commitments = BlockHeader { receipt_commitment: None, ..header.clone() };
+    assert_eq!(calculate_block_hash_of_header(&header_without_commitments), None);
+    let old_header =
+        BlockHeader { starknet_version: StarknetVersion("0.13.1".to_owned()), ..header };
+    assert_eq!(calculate_block_hash_of_header(&old_header), None);
+}
+
 #[test]
 fn concat_counts_test() {
     let concated = concat_counts(4, 3, 2, L1DataAvailabilityMode::Blob);
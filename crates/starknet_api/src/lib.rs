@@ -39,4 +39,7 @@ pub enum StarknetApiError {
     /// Missing resource type / duplicated resource type.
     #[error("Missing resource type / duplicated resource type; got {0}.")]
     InvalidResourceMappingInitializer(String),
+    /// The block hash of blocks of this Starknet version can't be calculated.
+    #[error("Unsupported block hash calculation for Starknet version {0}.")]
+    UnsupportedBlockHashVersion(String),
 }
@@ -117,6 +117,51 @@ impl ThinStateDiff {
                 .iter()
                 .all(|(_contract_address, storage_diffs)| storage_diffs.is_empty())
     }
+
+    /// Squashes the state diffs of consecutive blocks, oldest first, into one state diff with the
+    /// same effect on the state:
+    /// - A later storage write, class declaration or class replacement overrides an earlier one.
+    /// - The nonce of a contract is the highest one.
+    /// - A class replacement of a contract that was deployed in one of the blocks is folded into
+    ///   its deployment.
+    pub fn squash(state_diffs: impl IntoIterator<Item = ThinStateDiff>) -> Self {
+        state_diffs.into_iter().fold(Self::default(), |mut squashed_state_diff, state_diff| {
+            squashed_state_diff.squash_with(state_diff);
+            squashed_state_diff
+        })
+    }
+
+    /// Squashes the state diff of the next block into this one (see [`ThinStateDiff::squash`]).
+    pub fn squash_with(&mut self, next: ThinStateDiff) {
+        self.deployed_contracts.extend(next.deployed_contracts);
+        for (address, class_hash) in next.replaced_classes {
+            match self.deployed_contracts.get_mut(&address) {
+                Some(deployed_class_hash) => *deployed_class_hash = class_hash,
+                None => {
+                    self.replaced_classes.insert(address, class_hash);
+                }
+            }
+        }
+        for (address, storage_diff) in next.storage_diffs {
+            self.storage_diffs.entry(address).or_default().extend(storage_diff);
+        }
+        for (address, nonce) in next.nonces {
+            let squashed_nonce = self.nonces.entry(address).or_insert(nonce);
+            *squashed_nonce = (*squashed_nonce).max(nonce);
+        }
+        self.declared_classes.extend(next.declared_classes);
+        for class_hash in next.deprecated_declared_classes {
+            if !self.deprecated_declared_classes.contains(&class_hash) {
+                self.deprecated_declared_classes.push(class_hash);
+            }
+        }
+
+        // Keep the addresses strictly increasing.
+        self.deployed_contracts.sort_keys();
+        self.replaced_classes.sort_keys();
+        self.storage_diffs.sort_keys();
+        self.nonces.sort_keys();
+    }
 }
 
 impl From<StateDiff> for ThinStateDiff {
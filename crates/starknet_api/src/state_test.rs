@@ -4,7 +4,7 @@ use indexmap::{indexmap, IndexMap};
 use serde_json::json;
 
 use super::ThinStateDiff;
-use crate::core::{ClassHash, CompiledClassHash, Nonce};
+use crate::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use crate::deprecated_contract_class::EntryPointOffset;
 
 #[test]
@@ -116,3 +116,65 @@ fn thin_state_diff_is_empty() {
         .is_empty()
     );
 }
+
+#[test]
+fn thin_state_diff_squash() {
+    let first_block = ThinStateDiff {
+        deployed_contracts: indexmap! { 2u64.into() => ClassHash(1u64.into()) },
+        storage_diffs: indexmap! {
+            2u64.into() => indexmap! { 0u64.into() => 1u64.into(), 1u64.into() => 1u64.into() },
+        },
+        declared_classes: indexmap! { ClassHash(4u64.into()) => CompiledClassHash(9u64.into()) },
+        deprecated_declared_classes: vec![ClassHash(6u64.into())],
+        nonces: indexmap! { 2u64.into() => Nonce(1u64.into()), 3u64.into() => Nonce(5u64.into()) },
+        replaced_classes: indexmap! { 3u64.into() => ClassHash(2u64.into()) },
+    };
+    let second_block = ThinStateDiff {
+        deployed_contracts: indexmap! { 0u64.into() => ClassHash(1u64.into()) },
+        storage_diffs: indexmap! {
+            2u64.into() => indexmap! { 1u64.into() => 2u64.into() },
+            0u64.into() => indexmap! { 0u64.into() => 3u64.into() },
+        },
+        declared_classes: indexmap! { ClassHash(5u64.into()) => CompiledClassHash(10u64.into()) },
+        deprecated_declared_classes: vec![ClassHash(6u64.into()), ClassHash(7u64.into())],
+        nonces: indexmap! { 2u64.into() => Nonce(2u64.into()), 3u64.into() => Nonce(4u64.into()) },
+        replaced_classes: indexmap! {
+            2u64.into() => ClassHash(4u64.into()),
+            3u64.into() => ClassHash(5u64.into()),
+        },
+    };
+
+    let expected_state_diff = ThinStateDiff {
+        // The replaced class of contract 2 is folded into its deployment.
+        deployed_contracts: indexmap! {
+            0u64.into() => ClassHash(1u64.into()),
+            2u64.into() => ClassHash(4u64.into()),
+        },
+        storage_diffs: indexmap! {
+            0u64.into() => indexmap! { 0u64.into() => 3u64.into() },
+            2u64.into() => indexmap! { 0u64.into() => 1u64.into(), 1u64.into() => 2u64.into() },
+        },
+        declared_classes: indexmap! {
+            ClassHash(4u64.into()) => CompiledClassHash(9u64.into()),
+            ClassHash(5u64.into()) => CompiledClassHash(10u64.into()),
+        },
+        deprecated_declared_classes: vec![ClassHash(6u64.into()), ClassHash(7u64.into())],
+        nonces: indexmap! { 2u64.into() => Nonce(2u64.into()), 3u64.into() => Nonce(5u64.into()) },
+        replaced_classes: indexmap! { 3u64.into() => ClassHash(5u64.into()) },
+    };
+    let squashed_state_diff = ThinStateDiff::squash([first_block.clone(), second_block]);
+    assert_eq!(squashed_state_diff, expected_state_diff);
+    // IndexMap equality ignores the order, so check the addresses are sorted as well.
+    let sorted_addresses: Vec<ContractAddress> = vec![0u64.into(), 2u64.into()];
+    assert_eq!(
+        squashed_state_diff.storage_diffs.keys().copied().collect::<Vec<_>>(),
+        sorted_addresses
+    );
+    assert_eq!(
+        squashed_state_diff.deployed_contracts.keys().copied().collect::<Vec<_>>(),
+        sorted_addresses
+    );
+
+    assert_eq!(ThinStateDiff::squash([first_block.clone()]), first_block);
+    assert_eq!(ThinStateDiff::squash([]), ThinStateDiff::default());
+}
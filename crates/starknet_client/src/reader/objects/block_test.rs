@@ -2,6 +2,13 @@ use assert_matches::assert_matches;
 use indexmap::IndexMap;
 use pretty_assertions::assert_eq;
 use starknet_api::block::BlockHash;
+use starknet_api::block_hash::block_hash_calculator::{
+    calculate_block_hash_of_header,
+    calculate_event_commitment_by_version,
+    calculate_receipt_commitment_by_version,
+    calculate_transaction_commitment_by_version,
+    TransactionHashingData,
+};
 use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce, PatriciaKey};
 use starknet_api::hash::StarkHash;
 use starknet_api::state::StorageKey;
@@ -156,6 +163,39 @@ async fn to_starknet_api_block_and_version() {
     );
 }
 
+// The commitments that a live 0.13.2 block holds in its header are the ones calculated from its
+// content.
+#[test]
+fn block_post_0_13_2_content_commitments() {
+    let raw_block = read_resource_file("reader/block_post_0_13_2.json");
+    let block: Block = serde_json::from_str(&raw_block).unwrap();
+    let starknet_api_block = block.to_starknet_api_block_and_version().unwrap();
+    let header = &starknet_api_block.header;
+    let body = &starknet_api_block.body;
+    let transactions_data: Vec<TransactionHashingData> = body
+        .transactions
+        .iter()
+        .zip(body.transaction_outputs.iter())
+        .zip(body.transaction_hashes.iter())
+        .map(|((tx, tx_output), tx_hash)| TransactionHashingData::new(tx, tx_output, *tx_hash))
+        .collect();
+
+    let version = &header.starknet_version;
+    assert_eq!(
+        calculate_transaction_commitment_by_version(version, &transactions_data).ok(),
+        header.transaction_commitment
+    );
+    assert_eq!(
+        calculate_event_commitment_by_version(version, &transactions_data).ok(),
+        header.event_commitment
+    );
+    assert_eq!(
+        calculate_receipt_commitment_by_version(version, &transactions_data).ok(),
+        header.receipt_commitment
+    );
+    assert_eq!(calculate_block_hash_of_header(header), Some(header.block_hash));
+}
+
 #[tokio::test]
 async fn to_starknet_api_block_and_version_0_13_1() {
     let raw_block = read_resource_file("reader/block_post_0_13_1.json");
@@ -1,3 +1,5 @@
 pub mod commit;
+pub mod commitment_info;
 pub mod errors;
 pub mod input;
+pub mod storage_proof;
@@ -0,0 +1,169 @@
+use std::collections::{HashMap, HashSet};
+
+use starknet_patricia::hash::hash_trait::HashOutput;
+use starknet_patricia::patricia_merkle_tree::commitment_facts::{
+    fetch_commitment_facts,
+    CommitmentFacts,
+};
+use starknet_patricia::patricia_merkle_tree::filled_tree::tree::{FilledTree, FilledTreeImpl};
+use starknet_patricia::patricia_merkle_tree::node_data::leaf::Leaf;
+use starknet_patricia::patricia_merkle_tree::types::{NodeIndex, SubTreeHeight};
+use starknet_patricia::storage::map_storage::MapStorage;
+
+use crate::block_committer::commit::{commit_block, BlockCommitmentResult};
+use crate::block_committer::input::{
+    ConfigImpl,
+    ContractAddress,
+    Input,
+    StarknetStorageKey,
+    StarknetStorageValue,
+};
+use crate::forest::filled_forest::FilledForest;
+use crate::patricia_merkle_tree::leaf::leaf_impl::ContractState;
+use crate::patricia_merkle_tree::types::CompiledClassHash;
+
+#[cfg(test)]
+#[path = "commitment_info_test.rs"]
+pub mod commitment_info_test;
+
+/// The storage keys a block read without writing to them, by contract.
+pub type StorageReads = HashMap<ContractAddress, HashSet<StarknetStorageKey>>;
+
+/// The roots of a tree before and after a block, with the commitment facts of the paths to the
+/// leaves the block accessed in both trees.
+#[derive(Debug, Eq, PartialEq)]
+pub struct CommitmentInfo {
+    pub previous_root: HashOutput,
+    pub updated_root: HashOutput,
+    pub tree_height: SubTreeHeight,
+    pub commitment_facts: CommitmentFacts,
+}
+
+impl CommitmentInfo {
+    // The commitment info of a tree the block didn't modify.
+    fn unmodified(root: HashOutput, commitment_facts: CommitmentFacts) -> Self {
+        Self {
+            previous_root: root,
+            updated_root: root,
+            tree_height: SubTreeHeight::ACTUAL_HEIGHT,
+            commitment_facts,
+        }
+    }
+
+    // The commitment info of a tree, given the facts of its previous paths and the modified tree.
+    fn modified<L: Leaf>(
+        previous_root: HashOutput,
+        mut commitment_facts: CommitmentFacts,
+        filled_tree: &FilledTreeImpl<L>,
+    ) -> Self {
+        commitment_facts.extend(filled_tree.commitment_facts());
+        Self {
+            previous_root,
+            updated_root: filled_tree.get_root_hash(),
+            tree_height: SubTreeHeight::ACTUAL_HEIGHT,
+            commitment_facts,
+        }
+    }
+}
+
+/// The commitment infos of the tries a block accessed, as the Starknet OS gets them.
+#[derive(Debug, Eq, PartialEq)]
+pub struct StateCommitmentInfos {
+    pub contracts_trie: CommitmentInfo,
+    pub classes_trie: CommitmentInfo,
+    pub storage_tries: HashMap<ContractAddress, CommitmentInfo>,
+    /// The states of the accessed contracts before the block.
+    pub previous_contract_states: HashMap<ContractAddress, ContractState>,
+}
+
+/// Commits the block, and returns the commitment infos of the leaves it modified or read.
+pub async fn commit_block_with_commitment_infos(
+    mut input: Input<ConfigImpl>,
+    storage_reads: &StorageReads,
+) -> BlockCommitmentResult<(FilledForest, StateCommitmentInfos)> {
+    // The previous paths are fetched before the storage is moved to the committer.
+    let storage = MapStorage::from(std::mem::take(&mut input.storage));
+
+    let addresses: Vec<ContractAddress> = input
+        .state_diff
+        .accessed_addresses()
+        .into_iter()
+        .chain(storage_reads.keys())
+        .copied()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let contract_indices: Vec<NodeIndex> = addresses.iter().map(NodeIndex::from).collect();
+    let (contracts_trie_facts, mut contract_leaves) = fetch_commitment_facts::<ContractState>(
+        &storage,
+        input.contracts_trie_root_hash,
+        &contract_indices,
+    )?;
+    let previous_contract_states: HashMap<ContractAddress, ContractState> = addresses
+        .iter()
+        .zip(&contract_indices)
+        .map(|(address, index)| {
+            let contract_state =
+                contract_leaves.remove(index).expect("All accessed contract leaves are fetched.");
+            (*address, contract_state)
+        })
+        .collect();
+
+    let class_indices: Vec<NodeIndex> =
+        input.state_diff.class_hash_to_compiled_class_hash.keys().map(NodeIndex::from).collect();
+    let (classes_trie_facts, _) = fetch_commitment_facts::<CompiledClassHash>(
+        &storage,
+        input.classes_trie_root_hash,
+        &class_indices,
+    )?;
+
+    let mut storage_tries_facts = HashMap::new();
+    for (address, contract_state) in &previous_contract_states {
+        let written_keys =
+            input.state_diff.storage_updates.get(address).into_iter().flat_map(HashMap::keys);
+        let read_keys = storage_reads.get(address).into_iter().flatten();
+        let storage_indices: Vec<NodeIndex> =
+            written_keys.chain(read_keys).map(NodeIndex::from).collect();
+        let (storage_trie_facts, _) = fetch_commitment_facts::<StarknetStorageValue>(
+            &storage,
+            contract_state.storage_root_hash,
+            &storage_indices,
+        )?;
+        storage_tries_facts.insert(*address, storage_trie_facts);
+    }
+
+    let previous_contracts_trie_root = input.contracts_trie_root_hash;
+    let previous_classes_trie_root = input.classes_trie_root_hash;
+    input.storage = storage.storage;
+    let filled_forest = commit_block(input).await?;
+
+    let storage_tries = storage_tries_facts
+        .into_iter()
+        .map(|(address, storage_trie_facts)| {
+            let previous_root = previous_contract_states[&address].storage_root_hash;
+            let commitment_info = match filled_forest.storage_tries.get(&address) {
+                Some(storage_trie) => {
+                    CommitmentInfo::modified(previous_root, storage_trie_facts, storage_trie)
+                }
+                // A contract that was only read.
+                None => CommitmentInfo::unmodified(previous_root, storage_trie_facts),
+            };
+            (address, commitment_info)
+        })
+        .collect();
+    let commitment_infos = StateCommitmentInfos {
+        contracts_trie: CommitmentInfo::modified(
+            previous_contracts_trie_root,
+            contracts_trie_facts,
+            &filled_forest.contracts_trie,
+        ),
+        classes_trie: CommitmentInfo::modified(
+            previous_classes_trie_root,
+            classes_trie_facts,
+            &filled_forest.classes_trie,
+        ),
+        storage_tries,
+        previous_contract_states,
+    };
+    Ok((filled_forest, commitment_infos))
+}
@@ -0,0 +1,137 @@
+use std::collections::{HashMap, HashSet};
+
+use pretty_assertions::assert_eq;
+use starknet_patricia::felt::Felt;
+use starknet_patricia::hash::hash_trait::HashOutput;
+use starknet_patricia::storage::map_storage::MapStorage;
+use tracing::level_filters::LevelFilter;
+
+use crate::block_committer::commitment_info::{
+    commit_block_with_commitment_infos,
+    CommitmentInfo,
+    StorageReads,
+};
+use crate::block_committer::input::{
+    ConfigImpl,
+    ContractAddress,
+    Input,
+    StarknetStorageKey,
+    StarknetStorageValue,
+    StateDiff,
+};
+use crate::patricia_merkle_tree::leaf::leaf_impl::ContractState;
+use crate::patricia_merkle_tree::types::{ClassHash, CompiledClassHash, Nonce};
+
+const CONTRACT: ContractAddress = ContractAddress(Felt::ONE);
+const READ_ONLY_CONTRACT: ContractAddress = ContractAddress(Felt::TWO);
+
+fn storage_key(key: u128) -> StarknetStorageKey {
+    StarknetStorageKey(Felt::from(key))
+}
+
+fn storage_updates(
+    updates: &[(u128, u128)],
+) -> HashMap<ContractAddress, HashMap<StarknetStorageKey, StarknetStorageValue>> {
+    HashMap::from([(
+        CONTRACT,
+        updates
+            .iter()
+            .map(|(key, value)| (storage_key(*key), StarknetStorageValue(Felt::from(*value))))
+            .collect(),
+    )])
+}
+
+#[tokio::test]
+async fn test_commitment_infos_of_consecutive_blocks() {
+    let empty_root = HashOutput::default();
+    let config = || ConfigImpl::new(false, LevelFilter::INFO);
+
+    // The first block writes two storage keys of a contract into an empty state.
+    let first_input = Input {
+        storage: HashMap::new(),
+        state_diff: StateDiff {
+            address_to_nonce: HashMap::from([(CONTRACT, Nonce(Felt::ONE))]),
+            class_hash_to_compiled_class_hash: HashMap::from([(
+                ClassHash(Felt::THREE),
+                CompiledClassHash(Felt::TWO),
+            )]),
+            storage_updates: storage_updates(&[(10, 1), (11, 2)]),
+            ..Default::default()
+        },
+        contracts_trie_root_hash: empty_root,
+        classes_trie_root_hash: empty_root,
+        config: config(),
+    };
+    let (first_forest, first_infos) =
+        commit_block_with_commitment_infos(first_input, &StorageReads::new()).await.unwrap();
+
+    assert_eq!(first_infos.contracts_trie.previous_root, empty_root);
+    assert_eq!(first_infos.contracts_trie.updated_root, first_forest.get_contract_root_hash());
+    assert_eq!(
+        first_infos.contracts_trie.commitment_facts,
+        first_forest.contracts_trie.commitment_facts()
+    );
+    assert_eq!(first_infos.classes_trie.updated_root, first_forest.get_compiled_class_root_hash());
+    assert_eq!(
+        first_infos.previous_contract_states,
+        HashMap::from([(CONTRACT, ContractState::default())])
+    );
+    let first_storage_info = &first_infos.storage_tries[&CONTRACT];
+    assert_eq!(first_storage_info.previous_root, empty_root);
+    assert!(!first_storage_info.commitment_facts.is_empty());
+
+    // The second block overwrites one of the keys and reads the other, and reads a key of an
+    // empty contract.
+    let mut storage = MapStorage::default();
+    first_forest.write_to_storage(&mut storage);
+    let second_input = Input {
+        storage: storage.storage,
+        state_diff: StateDiff {
+            storage_updates: storage_updates(&[(10, 3)]),
+            ..Default::default()
+        },
+        contracts_trie_root_hash: first_forest.get_contract_root_hash(),
+        classes_trie_root_hash: first_forest.get_compiled_class_root_hash(),
+        config: config(),
+    };
+    let storage_reads = HashMap::from([
+        (CONTRACT, HashSet::from([storage_key(11)])),
+        (READ_ONLY_CONTRACT, HashSet::from([storage_key(12)])),
+    ]);
+    let (second_forest, second_infos) =
+        commit_block_with_commitment_infos(second_input, &storage_reads).await.unwrap();
+
+    assert_eq!(second_infos.contracts_trie.previous_root, first_infos.contracts_trie.updated_root);
+    assert_eq!(second_infos.contracts_trie.updated_root, second_forest.get_contract_root_hash());
+    assert_eq!(second_infos.classes_trie.previous_root, second_infos.classes_trie.updated_root);
+    assert_eq!(
+        second_infos.previous_contract_states,
+        HashMap::from([
+            (
+                CONTRACT,
+                ContractState {
+                    nonce: Nonce(Felt::ONE),
+                    storage_root_hash: first_storage_info.updated_root,
+                    class_hash: ClassHash::default(),
+                }
+            ),
+            (READ_ONLY_CONTRACT, ContractState::default()),
+        ])
+    );
+
+    // Both keys of the contract were accessed, so the paths to them cover the previous tree.
+    let second_storage_info = &second_infos.storage_tries[&CONTRACT];
+    assert_eq!(second_storage_info.previous_root, first_storage_info.updated_root);
+    for (hash, preimage) in &first_storage_info.commitment_facts {
+        assert_eq!(second_storage_info.commitment_facts.get(hash), Some(preimage));
+    }
+    assert_eq!(
+        second_infos.storage_tries[&READ_ONLY_CONTRACT],
+        CommitmentInfo {
+            previous_root: empty_root,
+            updated_root: empty_root,
+            tree_height: first_storage_info.tree_height,
+            commitment_facts: HashMap::new(),
+        }
+    );
+}
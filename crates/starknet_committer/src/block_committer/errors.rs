@@ -1,3 +1,5 @@
+use starknet_patricia::patricia_merkle_tree::errors::CommitmentFactsError;
+use starknet_patricia::storage::errors::DeserializationError;
 use thiserror::Error;
 
 use crate::forest::forest_errors::ForestError;
@@ -6,4 +8,10 @@ use crate::forest::forest_errors::ForestError;
 pub enum BlockCommitmentError {
     #[error(transparent)]
     ForestError(#[from] ForestError),
+    #[error(transparent)]
+    CommitmentFacts(#[from] CommitmentFactsError),
+    #[error(transparent)]
+    Deserialization(#[from] DeserializationError),
+    #[error("The roots of block {0} aren't in the storage.")]
+    MissingBlockRoots(u64),
 }
@@ -0,0 +1,219 @@
+//! Proofs of storage reads against the state of a block, for light clients.
+//!
+//! Given the roots of the contracts trie and the classes trie after a block, a set of
+//! (contract, key) pairs and a set of classes, a [`StorageProof`] holds the commitment facts of the
+//! paths from the contracts trie root to the read contracts, from the storage trie root of each of
+//! these contracts to its read keys, and from the classes trie root to the read classes. The facts
+//! are the preimages of the inner nodes, so a light client that trusts the state root of the block
+//! verifies the read values, or their absence, by rehashing the paths. The storage holds the facts
+//! of all the committed blocks, and the roots of the blocks written with [`write_block_roots`], so
+//! [`fetch_block_storage_proof`] generates a proof for any of these blocks by its number.
+
+use std::collections::HashMap;
+
+use starknet_patricia::hash::hash_trait::HashOutput;
+use starknet_patricia::patricia_merkle_tree::commitment_facts::{
+    fetch_commitment_facts,
+    CommitmentFacts,
+};
+use starknet_patricia::patricia_merkle_tree::types::NodeIndex;
+use starknet_patricia::storage::db_object::{DBObject, Deserializable};
+use starknet_patricia::storage::errors::DeserializationError;
+use starknet_patricia::storage::storage_trait::{
+    StarknetPrefix,
+    Storage,
+    StorageKey,
+    StorageValue,
+};
+
+use crate::block_committer::commit::BlockCommitmentResult;
+use crate::block_committer::commitment_info::StorageReads;
+use crate::block_committer::errors::BlockCommitmentError;
+use crate::block_committer::input::{ContractAddress, StarknetStorageKey, StarknetStorageValue};
+use crate::hash_function::hash::calculate_global_root_hash;
+use crate::patricia_merkle_tree::leaf::leaf_impl::ContractState;
+use crate::patricia_merkle_tree::types::{ClassHash, CompiledClassHash};
+
+#[cfg(test)]
+#[path = "storage_proof_test.rs"]
+pub mod storage_proof_test;
+
+/// The proof of the storage reads of a contract against the root of its storage trie.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct ContractStorageProof {
+    pub commitment_facts: CommitmentFacts,
+    /// The read values. Keys that aren't in the storage trie are read as zero.
+    pub values: HashMap<StarknetStorageKey, StarknetStorageValue>,
+}
+
+/// The proof of storage reads against the global root of the state.
+#[derive(Debug, Eq, PartialEq)]
+pub struct StorageProof {
+    pub contracts_trie_root_hash: HashOutput,
+    pub classes_trie_root_hash: HashOutput,
+    /// The commitment facts of the paths to the read contracts in the contracts trie.
+    pub contracts_trie_facts: CommitmentFacts,
+    /// The states of the read contracts, which hold the roots of their storage tries. Contracts
+    /// that aren't in the contracts trie have the default state.
+    pub contract_states: HashMap<ContractAddress, ContractState>,
+    pub storage_proofs: HashMap<ContractAddress, ContractStorageProof>,
+    /// The commitment facts of the paths to the read classes in the classes trie.
+    pub classes_trie_facts: CommitmentFacts,
+    /// The compiled class hashes of the read classes. Classes that aren't in the classes trie
+    /// (undeclared, or Cairo 0) have a zero compiled class hash.
+    pub compiled_class_hashes: HashMap<ClassHash, CompiledClassHash>,
+}
+
+impl StorageProof {
+    /// The global root the proof is against, as committed to by the block header.
+    pub fn global_root_hash(&self) -> HashOutput {
+        calculate_global_root_hash(self.contracts_trie_root_hash, self.classes_trie_root_hash)
+    }
+}
+
+/// The roots of the tries of the state after a block.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BlockRoots {
+    pub contracts_trie_root_hash: HashOutput,
+    pub classes_trie_root_hash: HashOutput,
+}
+
+impl BlockRoots {
+    fn db_key(block_number: u64) -> StorageKey {
+        StorageKey([Self::prefix(), b":".to_vec(), block_number.to_be_bytes().to_vec()].concat())
+    }
+}
+
+impl DBObject for BlockRoots {
+    /// Creates a json string describing the roots and casts it into a byte vector.
+    fn serialize(&self) -> StorageValue {
+        let json_string = format!(
+            r#"{{"contracts_trie_root_hash": "{}", "classes_trie_root_hash": "{}"}}"#,
+            self.contracts_trie_root_hash.0.to_hex(),
+            self.classes_trie_root_hash.0.to_hex(),
+        );
+        StorageValue(json_string.into_bytes())
+    }
+
+    fn get_prefix(&self) -> Vec<u8> {
+        StarknetPrefix::BlockRoots.to_storage_prefix()
+    }
+}
+
+impl Deserializable for BlockRoots {
+    fn deserialize(value: &StorageValue) -> Result<Self, DeserializationError> {
+        let json_str = std::str::from_utf8(&value.0)?;
+        let map: HashMap<String, String> = serde_json::from_str(json_str)?;
+        let get_root =
+            |key: &str| map.get(key).ok_or(DeserializationError::NonExistingKey(key.to_string()));
+        Ok(Self {
+            contracts_trie_root_hash: HashOutput::from_hex(get_root("contracts_trie_root_hash")?)?,
+            classes_trie_root_hash: HashOutput::from_hex(get_root("classes_trie_root_hash")?)?,
+        })
+    }
+
+    fn prefix() -> Vec<u8> {
+        StarknetPrefix::BlockRoots.to_storage_prefix()
+    }
+}
+
+/// Writes to storage the roots of the state after the given block, so proofs against the block
+/// are fetched by its number.
+pub fn write_block_roots(storage: &mut impl Storage, block_number: u64, roots: BlockRoots) {
+    storage.set(BlockRoots::db_key(block_number), roots.serialize());
+}
+
+/// Fetches from storage the proof of the given storage reads and classes against the state after
+/// the given block, whose roots were written with [`write_block_roots`].
+pub fn fetch_block_storage_proof(
+    storage: &impl Storage,
+    block_number: u64,
+    storage_reads: &StorageReads,
+    class_hashes: &[ClassHash],
+) -> BlockCommitmentResult<StorageProof> {
+    let roots_value = storage
+        .get(&BlockRoots::db_key(block_number))
+        .ok_or(BlockCommitmentError::MissingBlockRoots(block_number))?;
+    let roots = BlockRoots::deserialize(roots_value)?;
+    fetch_storage_proof(
+        storage,
+        roots.contracts_trie_root_hash,
+        roots.classes_trie_root_hash,
+        storage_reads,
+        class_hashes,
+    )
+}
+
+/// Fetches from storage the proof of the given storage reads and classes against the state with
+/// the given roots. A contract read with no keys is proved by its state alone.
+pub fn fetch_storage_proof(
+    storage: &impl Storage,
+    contracts_trie_root_hash: HashOutput,
+    classes_trie_root_hash: HashOutput,
+    storage_reads: &StorageReads,
+    class_hashes: &[ClassHash],
+) -> BlockCommitmentResult<StorageProof> {
+    let addresses: Vec<ContractAddress> = storage_reads.keys().copied().collect();
+    let contract_indices: Vec<NodeIndex> = addresses.iter().map(NodeIndex::from).collect();
+    let (contracts_trie_facts, mut contract_leaves) = fetch_commitment_facts::<ContractState>(
+        storage,
+        contracts_trie_root_hash,
+        &contract_indices,
+    )?;
+    let contract_states: HashMap<ContractAddress, ContractState> = addresses
+        .iter()
+        .zip(&contract_indices)
+        .map(|(address, index)| {
+            let contract_state =
+                contract_leaves.remove(index).expect("All read contract leaves are fetched.");
+            (*address, contract_state)
+        })
+        .collect();
+
+    let mut storage_proofs = HashMap::new();
+    for (address, keys) in storage_reads {
+        let keys: Vec<StarknetStorageKey> = keys.iter().copied().collect();
+        let storage_indices: Vec<NodeIndex> = keys.iter().map(NodeIndex::from).collect();
+        let (commitment_facts, mut storage_leaves) = fetch_commitment_facts::<StarknetStorageValue>(
+            storage,
+            contract_states[address].storage_root_hash,
+            &storage_indices,
+        )?;
+        let values = keys
+            .into_iter()
+            .zip(&storage_indices)
+            .map(|(key, index)| {
+                let value =
+                    storage_leaves.remove(index).expect("All read storage leaves are fetched.");
+                (key, value)
+            })
+            .collect();
+        storage_proofs.insert(*address, ContractStorageProof { commitment_facts, values });
+    }
+
+    let class_indices: Vec<NodeIndex> = class_hashes.iter().map(NodeIndex::from).collect();
+    let (classes_trie_facts, mut class_leaves) = fetch_commitment_facts::<CompiledClassHash>(
+        storage,
+        classes_trie_root_hash,
+        &class_indices,
+    )?;
+    let compiled_class_hashes = class_hashes
+        .iter()
+        .zip(&class_indices)
+        .map(|(class_hash, index)| {
+            let compiled_class_hash =
+                class_leaves.remove(index).expect("All read class leaves are fetched.");
+            (*class_hash, compiled_class_hash)
+        })
+        .collect();
+
+    Ok(StorageProof {
+        contracts_trie_root_hash,
+        classes_trie_root_hash,
+        contracts_trie_facts,
+        contract_states,
+        storage_proofs,
+        classes_trie_facts,
+        compiled_class_hashes,
+    })
+}
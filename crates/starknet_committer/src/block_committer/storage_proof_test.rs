@@ -0,0 +1,187 @@
+use std::collections::{HashMap, HashSet};
+
+use pretty_assertions::assert_eq;
+use starknet_patricia::felt::Felt;
+use starknet_patricia::hash::hash_trait::HashOutput;
+use starknet_patricia::patricia_merkle_tree::commitment_facts::CommitmentFacts;
+use starknet_patricia::patricia_merkle_tree::filled_tree::tree::FilledTree;
+use starknet_patricia::storage::map_storage::MapStorage;
+use tracing::level_filters::LevelFilter;
+
+use crate::block_committer::commit::commit_block;
+use crate::block_committer::errors::BlockCommitmentError;
+use crate::block_committer::input::{
+    ConfigImpl,
+    ContractAddress,
+    Input,
+    StarknetStorageKey,
+    StarknetStorageValue,
+    StateDiff,
+};
+use crate::block_committer::storage_proof::{
+    fetch_block_storage_proof,
+    fetch_storage_proof,
+    write_block_roots,
+    BlockRoots,
+};
+use crate::forest::filled_forest::FilledForest;
+use crate::patricia_merkle_tree::leaf::leaf_impl::ContractState;
+use crate::patricia_merkle_tree::types::{ClassHash, CompiledClassHash};
+
+const CONTRACT: ContractAddress = ContractAddress(Felt::ONE);
+const SINGLE_KEY_CONTRACT: ContractAddress = ContractAddress(Felt::TWO);
+const UNDEPLOYED_CONTRACT: ContractAddress = ContractAddress(Felt::THREE);
+const DECLARED_CLASS: ClassHash = ClassHash(Felt::THREE);
+const UNDECLARED_CLASS: ClassHash = ClassHash(Felt::TWO);
+const BLOCK_NUMBER: u64 = 7;
+
+fn storage_key(key: u128) -> StarknetStorageKey {
+    StarknetStorageKey(Felt::from(key))
+}
+
+fn storage_value(value: u128) -> StarknetStorageValue {
+    StarknetStorageValue(Felt::from(value))
+}
+
+fn assert_facts_of_tree(facts: &CommitmentFacts, root: HashOutput, tree_facts: &CommitmentFacts) {
+    assert!(facts.contains_key(&root));
+    for (hash, preimage) in facts {
+        assert_eq!(tree_facts.get(hash), Some(preimage));
+    }
+}
+
+async fn committed_forest() -> FilledForest {
+    let empty_root = HashOutput::default();
+    let input = Input {
+        storage: HashMap::new(),
+        state_diff: StateDiff {
+            class_hash_to_compiled_class_hash: HashMap::from([(
+                DECLARED_CLASS,
+                CompiledClassHash(Felt::TWO),
+            )]),
+            storage_updates: HashMap::from([
+                (
+                    CONTRACT,
+                    HashMap::from([
+                        (storage_key(10), storage_value(1)),
+                        (storage_key(11), storage_value(2)),
+                    ]),
+                ),
+                (SINGLE_KEY_CONTRACT, HashMap::from([(storage_key(12), storage_value(3))])),
+            ]),
+            ..Default::default()
+        },
+        contracts_trie_root_hash: empty_root,
+        classes_trie_root_hash: empty_root,
+        config: ConfigImpl::new(false, LevelFilter::INFO),
+    };
+    commit_block(input).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_storage_proof_of_committed_block() {
+    let forest = committed_forest().await;
+    let mut storage = MapStorage::default();
+    forest.write_to_storage(&mut storage);
+
+    let storage_reads = HashMap::from([
+        (CONTRACT, HashSet::from([storage_key(10), storage_key(13)])),
+        (SINGLE_KEY_CONTRACT, HashSet::from([storage_key(12)])),
+        (UNDEPLOYED_CONTRACT, HashSet::from([storage_key(14)])),
+    ]);
+    let proof = fetch_storage_proof(
+        &storage,
+        forest.get_contract_root_hash(),
+        forest.get_compiled_class_root_hash(),
+        &storage_reads,
+        &[DECLARED_CLASS, UNDECLARED_CLASS],
+    )
+    .unwrap();
+
+    assert_eq!(proof.global_root_hash(), forest.get_global_root_hash());
+    assert_facts_of_tree(
+        &proof.contracts_trie_facts,
+        forest.get_contract_root_hash(),
+        &forest.contracts_trie.commitment_facts(),
+    );
+    assert_eq!(proof.contract_states[&UNDEPLOYED_CONTRACT], ContractState::default());
+
+    // The read values, where a key that wasn't written is zero.
+    let contract_storage_root = forest.storage_tries[&CONTRACT].get_root_hash();
+    assert_eq!(proof.contract_states[&CONTRACT].storage_root_hash, contract_storage_root);
+    let contract_proof = &proof.storage_proofs[&CONTRACT];
+    assert_eq!(
+        contract_proof.values,
+        HashMap::from([(storage_key(10), storage_value(1)), (storage_key(13), storage_value(0))])
+    );
+    assert_facts_of_tree(
+        &contract_proof.commitment_facts,
+        contract_storage_root,
+        &forest.storage_tries[&CONTRACT].commitment_facts(),
+    );
+
+    // The storage trie of a single key is an edge from the root to its leaf, whose hash is the
+    // value.
+    let single_key_root = forest.storage_tries[&SINGLE_KEY_CONTRACT].get_root_hash();
+    assert_eq!(
+        proof.storage_proofs[&SINGLE_KEY_CONTRACT].commitment_facts,
+        HashMap::from([(
+            single_key_root,
+            vec![Felt::from(251_u8), Felt::from(12_u8), Felt::THREE]
+        )])
+    );
+
+    // The storage trie of an undeployed contract is empty, so there's nothing to prove.
+    let undeployed_proof = &proof.storage_proofs[&UNDEPLOYED_CONTRACT];
+    assert!(undeployed_proof.commitment_facts.is_empty());
+    assert_eq!(undeployed_proof.values, HashMap::from([(storage_key(14), storage_value(0))]));
+
+    // An undeclared class has a zero compiled class hash.
+    assert_eq!(
+        proof.compiled_class_hashes,
+        HashMap::from([
+            (DECLARED_CLASS, CompiledClassHash(Felt::TWO)),
+            (UNDECLARED_CLASS, CompiledClassHash(Felt::ZERO)),
+        ])
+    );
+    assert_facts_of_tree(
+        &proof.classes_trie_facts,
+        forest.get_compiled_class_root_hash(),
+        &forest.classes_trie.commitment_facts(),
+    );
+}
+
+#[tokio::test]
+async fn test_storage_proof_by_block_number() {
+    let forest = committed_forest().await;
+    let mut storage = MapStorage::default();
+    forest.write_to_storage(&mut storage);
+    let roots = BlockRoots {
+        contracts_trie_root_hash: forest.get_contract_root_hash(),
+        classes_trie_root_hash: forest.get_compiled_class_root_hash(),
+    };
+    write_block_roots(&mut storage, BLOCK_NUMBER, roots);
+
+    let storage_reads = HashMap::from([(CONTRACT, HashSet::from([storage_key(10)]))]);
+    let class_hashes = [DECLARED_CLASS];
+    let proof =
+        fetch_block_storage_proof(&storage, BLOCK_NUMBER, &storage_reads, &class_hashes).unwrap();
+    assert_eq!(
+        proof,
+        fetch_storage_proof(
+            &storage,
+            roots.contracts_trie_root_hash,
+            roots.classes_trie_root_hash,
+            &storage_reads,
+            &class_hashes,
+        )
+        .unwrap()
+    );
+    assert_eq!(proof.global_root_hash(), forest.get_global_root_hash());
+
+    assert!(matches!(
+        fetch_block_storage_proof(&storage, BLOCK_NUMBER + 1, &storage_reads, &class_hashes),
+        Err(BlockCommitmentError::MissingBlockRoots(block_number))
+        if block_number == BLOCK_NUMBER + 1
+    ));
+}
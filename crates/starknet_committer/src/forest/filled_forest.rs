@@ -11,7 +11,7 @@ use tracing::info;
 use crate::block_committer::input::{ContractAddress, StarknetStorageValue};
 use crate::forest::forest_errors::{ForestError, ForestResult};
 use crate::forest::updated_skeleton_forest::UpdatedSkeletonForest;
-use crate::hash_function::hash::ForestHashFunction;
+use crate::hash_function::hash::{calculate_global_root_hash, ForestHashFunction};
 use crate::patricia_merkle_tree::leaf::leaf_impl::{ContractState, ContractStateInput};
 use crate::patricia_merkle_tree::types::{
     ClassHash,
@@ -51,6 +51,14 @@ impl FilledForest {
         self.classes_trie.get_root_hash()
     }
 
+    /// The global root of the state, combining the roots of the contracts and classes tries.
+    pub fn get_global_root_hash(&self) -> HashOutput {
+        calculate_global_root_hash(
+            self.get_contract_root_hash(),
+            self.get_compiled_class_root_hash(),
+        )
+    }
+
     /// Creates a filled forest. Assumes the storage updates and the updated skeletons of the
     /// storage tries include all modified contracts, including those with unmodified storage.
     pub(crate) async fn create<TH: ForestHashFunction + 'static>(
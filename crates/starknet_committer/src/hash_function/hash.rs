@@ -38,6 +38,31 @@ impl TreeHashFunctionImpl {
         "0x434f4e54524143545f434c4153535f4c4541465f5630";
 }
 
+// The hex string corresponding to b'STARKNET_STATE_V0' in big-endian.
+pub const STARKNET_STATE_V0: &str = "0x535441524b4e45545f53544154455f5630";
+
+/// Calculates the global root of the Starknet state, the root that block headers commit to, from
+/// the roots of the contracts trie and the classes trie. Before any class was declared by its
+/// compiled class hash, the global root is the root of the contracts trie.
+pub fn calculate_global_root_hash(
+    contracts_trie_root_hash: HashOutput,
+    classes_trie_root_hash: HashOutput,
+) -> HashOutput {
+    if classes_trie_root_hash.0 == Felt::ZERO {
+        return contracts_trie_root_hash;
+    }
+    let starknet_state_version = Felt::from_hex(STARKNET_STATE_V0)
+        .expect("could not parse hex string corresponding to b'STARKNET_STATE_V0' to Felt");
+    HashOutput(
+        Poseidon::hash_array(&[
+            starknet_state_version.into(),
+            contracts_trie_root_hash.0.into(),
+            classes_trie_root_hash.0.into(),
+        ])
+        .into(),
+    )
+}
+
 /// Implementation of TreeHashFunction for contracts trie.
 /// The implementation is based on the following reference:
 /// <https://docs.starknet.io/documentation/architecture_and_concepts/Network_Architecture/starknet-state/#trie_construction>
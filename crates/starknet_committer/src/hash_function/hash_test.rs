@@ -10,10 +10,14 @@ use starknet_patricia::patricia_merkle_tree::node_data::inner_node::{
     PathToBottom,
 };
 use starknet_patricia::patricia_merkle_tree::updated_skeleton_tree::hash_function::TreeHashFunction;
-use starknet_types_core::hash::Pedersen;
+use starknet_types_core::hash::{Pedersen, Poseidon};
 
 use crate::block_committer::input::StarknetStorageValue;
-use crate::hash_function::hash::TreeHashFunctionImpl;
+use crate::hash_function::hash::{
+    calculate_global_root_hash,
+    TreeHashFunctionImpl,
+    STARKNET_STATE_V0,
+};
 use crate::patricia_merkle_tree::leaf::leaf_impl::ContractState;
 use crate::patricia_merkle_tree::types::{ClassHash, CompiledClassHash, Nonce};
 
@@ -140,3 +144,34 @@ fn test_constant_contract_class_leaf_v0() {
         b"CONTRACT_CLASS_LEAF_V0".as_slice()
     );
 }
+
+#[rstest]
+fn test_constant_starknet_state_v0() {
+    assert_eq!(
+        hex::decode(STARKNET_STATE_V0.trim_start_matches("0x")).unwrap(),
+        b"STARKNET_STATE_V0".as_slice()
+    );
+}
+
+#[rstest]
+fn test_global_root_hash() {
+    use starknet_types_core::hash::StarkHash;
+
+    let contracts_trie_root_hash = HashOutput(Felt::from(1_u8));
+    let classes_trie_root_hash = HashOutput(Felt::from(2_u8));
+    let expected_hash = Poseidon::hash_array(&[
+        Felt::from_hex(STARKNET_STATE_V0).unwrap().into(),
+        contracts_trie_root_hash.0.into(),
+        classes_trie_root_hash.0.into(),
+    ]);
+    assert_eq!(
+        calculate_global_root_hash(contracts_trie_root_hash, classes_trie_root_hash),
+        HashOutput(expected_hash.into())
+    );
+
+    // Without classes, the global root is the root of the contracts trie.
+    assert_eq!(
+        calculate_global_root_hash(contracts_trie_root_hash, HashOutput(Felt::ZERO)),
+        contracts_trie_root_hash
+    );
+}
@@ -1,3 +1,4 @@
+pub mod commitment_facts;
 pub mod errors;
 pub mod filled_tree;
 pub mod node_data;
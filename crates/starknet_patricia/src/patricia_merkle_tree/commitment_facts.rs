@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use crate::felt::Felt;
+use crate::hash::hash_trait::HashOutput;
+use crate::patricia_merkle_tree::errors::CommitmentFactsError;
+use crate::patricia_merkle_tree::filled_tree::node::FilledNode;
+use crate::patricia_merkle_tree::filled_tree::tree::FilledTreeImpl;
+use crate::patricia_merkle_tree::node_data::inner_node::{BinaryData, EdgeData, NodeData};
+use crate::patricia_merkle_tree::node_data::leaf::Leaf;
+use crate::patricia_merkle_tree::types::NodeIndex;
+use crate::storage::errors::StorageError;
+use crate::storage::storage_trait::{create_db_key, StarknetPrefix, Storage, StorageValue};
+
+#[cfg(test)]
+#[path = "commitment_facts_test.rs"]
+pub mod commitment_facts_test;
+
+/// The preimages of inner nodes, by their hash. This is the witness the Starknet OS uses to
+/// traverse a tree: (left, right) for binary nodes and (length, path, bottom) for edge nodes.
+pub type CommitmentFacts = HashMap<HashOutput, Vec<Felt>>;
+
+pub type CommitmentFactsResult<T> = Result<T, CommitmentFactsError>;
+
+/// Returns the preimage of an inner node as it appears in the commitment facts, or None for a leaf.
+pub fn inner_node_preimage<L: Leaf>(node_data: &NodeData<L>) -> Option<Vec<Felt>> {
+    match node_data {
+        NodeData::Binary(BinaryData { left_hash, right_hash }) => {
+            Some(vec![left_hash.0, right_hash.0])
+        }
+        NodeData::Edge(EdgeData { bottom_hash, path_to_bottom }) => {
+            Some(vec![path_to_bottom.length.into(), (&path_to_bottom.path).into(), bottom_hash.0])
+        }
+        NodeData::Leaf(_) => None,
+    }
+}
+
+/// Fetches from storage the commitment facts of the paths from the root to the given leaves, and
+/// the values of the leaves. A leaf whose path ends before reaching it (an empty subtree, or an
+/// edge that leads elsewhere) is empty, and the facts on its path prove it.
+pub fn fetch_commitment_facts<L: Leaf>(
+    storage: &impl Storage,
+    root_hash: HashOutput,
+    leaf_indices: &[NodeIndex],
+) -> CommitmentFactsResult<(CommitmentFacts, HashMap<NodeIndex, L>)> {
+    let mut commitment_facts = CommitmentFacts::new();
+    let mut leaves = HashMap::new();
+    for leaf_index in leaf_indices {
+        let leaf = fetch_path(storage, root_hash, *leaf_index, &mut commitment_facts)?;
+        leaves.insert(*leaf_index, leaf);
+    }
+    Ok((commitment_facts, leaves))
+}
+
+// Traverses from the root towards the leaf and adds the preimages on the way to the commitment
+// facts. Returns the leaf.
+fn fetch_path<L: Leaf>(
+    storage: &impl Storage,
+    root_hash: HashOutput,
+    leaf_index: NodeIndex,
+    commitment_facts: &mut CommitmentFacts,
+) -> CommitmentFactsResult<L> {
+    let mut index = NodeIndex::ROOT;
+    let mut hash = root_hash;
+    loop {
+        if hash == HashOutput::ROOT_OF_EMPTY_TREE {
+            return Ok(L::default());
+        }
+        if index == leaf_index {
+            return Ok(L::deserialize(read_node(storage, L::prefix(), hash)?)?);
+        }
+
+        let value = read_node(storage, StarknetPrefix::InnerNode.to_storage_prefix(), hash)?;
+        let node = FilledNode::<L>::deserialize(hash, value, false)?;
+        if let Some(preimage) = inner_node_preimage(&node.data) {
+            commitment_facts.insert(hash, preimage);
+        }
+        match node.data {
+            NodeData::Binary(BinaryData { left_hash, right_hash }) => {
+                let [left_child, _] = index.get_children_indices();
+                let child = ancestor(leaf_index, left_child);
+                hash = if child == left_child { left_hash } else { right_hash };
+                index = child;
+            }
+            NodeData::Edge(EdgeData { bottom_hash, path_to_bottom }) => {
+                let bottom_index = path_to_bottom.bottom_index(index);
+                if ancestor(leaf_index, bottom_index) != bottom_index {
+                    return Ok(L::default());
+                }
+                hash = bottom_hash;
+                index = bottom_index;
+            }
+            NodeData::Leaf(_) => {
+                unreachable!("Inner nodes are deserialized as binary or edge nodes.")
+            }
+        }
+    }
+}
+
+// Returns the ancestor of the leaf at the height of the given node.
+fn ancestor(leaf_index: NodeIndex, node_index: NodeIndex) -> NodeIndex {
+    leaf_index >> (leaf_index.bit_length() - node_index.bit_length())
+}
+
+fn read_node(
+    storage: &impl Storage,
+    prefix: Vec<u8>,
+    hash: HashOutput,
+) -> CommitmentFactsResult<&StorageValue> {
+    let db_key = create_db_key(prefix, &hash.0.to_bytes_be());
+    match storage.get(&db_key) {
+        Some(value) => Ok(value),
+        None => Err(StorageError::MissingKey(db_key).into()),
+    }
+}
+
+impl<L: Leaf> FilledTreeImpl<L> {
+    /// Returns the preimages of the inner nodes of the tree. A filled tree holds the nodes that
+    /// were modified, so these are the commitment facts of the paths to the modified leaves in
+    /// the updated tree.
+    pub fn commitment_facts(&self) -> CommitmentFacts {
+        self.tree_map
+            .values()
+            .filter_map(|node| Some((node.hash, inner_node_preimage(&node.data)?)))
+            .collect()
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use ethnum::U256;
+use pretty_assertions::assert_eq;
+
+use super::{fetch_commitment_facts, CommitmentFacts};
+use crate::felt::Felt;
+use crate::hash::hash_trait::HashOutput;
+use crate::patricia_merkle_tree::external_test_utils::{
+    create_binary_entry,
+    create_edge_entry,
+    create_root_edge_entry,
+};
+use crate::patricia_merkle_tree::internal_test_utils::{small_tree_index_to_full, MockLeaf};
+use crate::patricia_merkle_tree::types::{NodeIndex, SubTreeHeight};
+use crate::storage::db_object::DBObject;
+use crate::storage::map_storage::MapStorage;
+use crate::storage::storage_trait::{StorageKey, StorageValue};
+
+fn leaf_entry(value: u128) -> (StorageKey, StorageValue) {
+    let leaf = MockLeaf(Felt::from(value));
+    (leaf.get_db_key(&leaf.0.to_bytes_be()), leaf.serialize())
+}
+
+fn facts(entries: &[(u128, &[u128])]) -> CommitmentFacts {
+    entries
+        .iter()
+        .map(|(hash, preimage)| {
+            (HashOutput(Felt::from(*hash)), preimage.iter().map(|felt| Felt::from(*felt)).collect())
+        })
+        .collect()
+}
+
+// This test assumes for simplicity that hash is addition (i.e hash(a,b) = a + b).
+///                 Tree structure (a subtree of height 2 under an edge from the root):
+///
+///                             21
+///                           /   \
+///                         11     10
+///                        /  \      \
+///                       5    6      8
+#[test]
+fn test_fetch_commitment_facts() {
+    let subtree_height = SubTreeHeight::new(2);
+    let storage = MapStorage::from(HashMap::from([
+        create_root_edge_entry(21, subtree_height),
+        create_binary_entry(11, 10),
+        create_binary_entry(5, 6),
+        create_edge_entry(8, 1, 1),
+        leaf_entry(5),
+        leaf_entry(6),
+        leaf_entry(8),
+    ]));
+    let root_hash = HashOutput(Felt::from(21_u128 + 249));
+    let full_index = |index: u128| small_tree_index_to_full(U256::from(index), subtree_height);
+
+    let (commitment_facts, leaves) = fetch_commitment_facts::<MockLeaf>(
+        &storage,
+        root_hash,
+        &[full_index(4), full_index(6), full_index(7)],
+    )
+    .unwrap();
+
+    assert_eq!(
+        commitment_facts,
+        facts(&[(270, &[249, 0, 21]), (21, &[11, 10]), (11, &[5, 6]), (10, &[1, 1, 8])])
+    );
+    // The edge to the leaf at index 7 proves the leaf at index 6 is empty.
+    assert_eq!(
+        leaves,
+        HashMap::from([
+            (full_index(4), MockLeaf(Felt::from(5_u128))),
+            (full_index(6), MockLeaf::default()),
+            (full_index(7), MockLeaf(Felt::from(8_u128))),
+        ])
+    );
+}
+
+#[test]
+fn test_fetch_commitment_facts_of_empty_tree() {
+    let leaf_index = NodeIndex::FIRST_LEAF + 1;
+    let (commitment_facts, leaves) = fetch_commitment_facts::<MockLeaf>(
+        &MapStorage::default(),
+        HashOutput::ROOT_OF_EMPTY_TREE,
+        &[leaf_index],
+    )
+    .unwrap();
+
+    assert_eq!(commitment_facts, CommitmentFacts::new());
+    assert_eq!(leaves, HashMap::from([(leaf_index, MockLeaf::default())]));
+}
+
+#[test]
+fn test_fetch_commitment_facts_missing_node() {
+    let root_hash = HashOutput(Felt::from(21_u128));
+    assert!(
+        fetch_commitment_facts::<MockLeaf>(
+            &MapStorage::default(),
+            root_hash,
+            &[NodeIndex::FIRST_LEAF]
+        )
+        .is_err()
+    );
+}
@@ -2,8 +2,18 @@ use std::fmt::Debug;
 
 use thiserror::Error;
 
+use crate::storage::errors::{DeserializationError, StorageError};
+
 #[derive(Debug, Error)]
 pub enum TypesError<T: Sized + Debug> {
     #[error("Failed to convert type {from:?} to {to}. Reason: {reason}.")]
     ConversionError { from: T, to: &'static str, reason: &'static str },
 }
+
+#[derive(Debug, Error)]
+pub enum CommitmentFactsError {
+    #[error("Failed to deserialize the storage value: {0:?} while fetching the commitment facts.")]
+    Deserialization(#[from] DeserializationError),
+    #[error("Unable to read from storage while fetching the commitment facts: {0:?}.")]
+    StorageRead(#[from] StorageError),
+}
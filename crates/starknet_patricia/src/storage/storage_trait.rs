@@ -39,6 +39,7 @@ pub enum StarknetPrefix {
     StorageLeaf,
     StateTreeLeaf,
     CompiledClassLeaf,
+    BlockRoots,
 }
 
 /// Describes a storage prefix as used in Aerospike DB.
@@ -49,6 +50,7 @@ impl StarknetPrefix {
             Self::StorageLeaf => b"starknet_storage_leaf",
             Self::StateTreeLeaf => b"contract_state",
             Self::CompiledClassLeaf => b"contract_class_leaf",
+            Self::BlockRoots => b"block_roots",
         }
     }
 
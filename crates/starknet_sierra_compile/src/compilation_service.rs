@@ -0,0 +1,259 @@
+//! A pool of workers that compile Sierra classes to Casm, with a cache of the compiled classes.
+//!
+//! Compiling a class takes long and may take much memory, so the [`CompilationService`] compiles
+//! at most `n_workers` classes at once and queues up to `queue_capacity` more; a class that
+//! arrives when the queue is full is rejected with [`CompilationUtilError::QueueFull`]. A caller
+//! waits at most `compilation_timeout` for its class, but the compilation carries on in the
+//! background and its result is cached, so a retry of the same class is answered from the cache.
+//!
+//! The compiled classes are cached by the hash of their Sierra class, in memory and, if a
+//! `cache_dir` is set, on disk, so they survive restarts. The service implements
+//! [`SierraToCasmCompiler`] and is cheap to clone. The gateway compiles the classes of declare
+//! transactions with it; execution gets the classes compiled already, so it doesn't compile.
+
+#[cfg(test)]
+#[path = "compilation_service_test.rs"]
+pub mod compilation_service_test;
+
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+use std::{fs, thread};
+
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use cairo_lang_starknet_classes::contract_class::ContractClass;
+use lru::LruCache;
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
+
+use crate::config::CompilationServiceConfig;
+use crate::errors::CompilationUtilError;
+use crate::SierraToCasmCompiler;
+
+/// The number of classes that wait for compilation.
+pub const COMPILATION_QUEUE_DEPTH: &str = "compilation_queue_depth";
+
+/// The time, in seconds, a class waited in the compilation queue.
+pub const COMPILATION_QUEUE_LATENCY_SECS: &str = "compilation_queue_latency_seconds";
+
+/// The time, in seconds, it took to compile a class.
+pub const COMPILATION_LATENCY_SECS: &str = "compilation_latency_seconds";
+
+/// The number of classes that were answered from the cache.
+pub const COMPILATION_CACHE_HITS: &str = "compilation_cache_hits";
+
+/// The number of classes that weren't in the cache.
+pub const COMPILATION_CACHE_MISSES: &str = "compilation_cache_misses";
+
+/// Identifies a Sierra class by the hash of its serialization.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct CompilationKey([u8; 32]);
+
+impl CompilationKey {
+    pub fn new(contract_class: &ContractClass) -> Result<Self, CompilationUtilError> {
+        let serialized_contract_class = serde_json::to_vec(contract_class)?;
+        Ok(Self(Sha256::digest(serialized_contract_class).into()))
+    }
+
+    fn file_name(&self) -> String {
+        format!("{}.json", hex::encode(self.0))
+    }
+}
+
+/// The compiled classes, by the key of their Sierra class. The most recently used ones are kept in
+/// memory, and all of them are persisted in the cache directory, if there is one.
+pub struct CompiledClassCache {
+    in_memory: Mutex<LruCache<CompilationKey, CasmContractClass>>,
+    cache_dir: Option<PathBuf>,
+}
+
+impl CompiledClassCache {
+    pub fn new(
+        cache_size: NonZeroUsize,
+        cache_dir: Option<PathBuf>,
+    ) -> Result<Self, CompilationUtilError> {
+        if let Some(cache_dir) = &cache_dir {
+            fs::create_dir_all(cache_dir)?;
+        }
+        Ok(Self { in_memory: Mutex::new(LruCache::new(cache_size)), cache_dir })
+    }
+
+    pub fn get(&self, key: &CompilationKey) -> Option<CasmContractClass> {
+        if let Some(casm_contract_class) = self.lock().get(key) {
+            return Some(casm_contract_class.clone());
+        }
+
+        let path = self.cache_dir.as_ref()?.join(key.file_name());
+        let serialized_casm_contract_class = fs::read(&path).ok()?;
+        match serde_json::from_slice::<CasmContractClass>(&serialized_casm_contract_class) {
+            Ok(casm_contract_class) => {
+                self.lock().put(*key, casm_contract_class.clone());
+                Some(casm_contract_class)
+            }
+            Err(error) => {
+                warn!("Failed to read the compiled class at {}: {}", path.display(), error);
+                None
+            }
+        }
+    }
+
+    pub fn insert(&self, key: CompilationKey, casm_contract_class: CasmContractClass) {
+        if let Some(cache_dir) = &self.cache_dir {
+            if let Err(error) = persist(cache_dir, &key, &casm_contract_class) {
+                warn!("Failed to persist the compiled class {}: {}", hex::encode(key.0), error);
+            }
+        }
+        self.lock().put(key, casm_contract_class);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LruCache<CompilationKey, CasmContractClass>> {
+        self.in_memory.lock().expect("The compiled class cache lock should not be poisoned.")
+    }
+}
+
+// Writes the compiled class to a temporary file first, so a crash never leaves a partial file
+// under the name of the class.
+fn persist(
+    cache_dir: &Path,
+    key: &CompilationKey,
+    casm_contract_class: &CasmContractClass,
+) -> Result<(), CompilationUtilError> {
+    let mut temp_file = tempfile::NamedTempFile::new_in(cache_dir)?;
+    serde_json::to_writer(&mut temp_file, casm_contract_class)?;
+    temp_file.persist(cache_dir.join(key.file_name())).map_err(|error| error.error)?;
+    Ok(())
+}
+
+type CompilationResult = Result<CasmContractClass, CompilationUtilError>;
+
+struct CompilationJob {
+    key: CompilationKey,
+    contract_class: ContractClass,
+    enqueued_at: Instant,
+    result_sender: mpsc::Sender<CompilationResult>,
+}
+
+#[derive(Clone)]
+pub struct CompilationService {
+    config: CompilationServiceConfig,
+    job_sender: SyncSender<CompilationJob>,
+    cache: Arc<CompiledClassCache>,
+    queue_depth: Arc<AtomicUsize>,
+}
+
+impl CompilationService {
+    /// Starts the workers, which compile the classes with the given compiler.
+    pub fn new(
+        config: CompilationServiceConfig,
+        compiler: Arc<dyn SierraToCasmCompiler>,
+    ) -> Result<Self, CompilationUtilError> {
+        let cache_size = NonZeroUsize::new(config.cache_size).ok_or_else(|| {
+            CompilationUtilError::UnexpectedError("The cache size must be positive.".to_owned())
+        })?;
+        let cache = Arc::new(CompiledClassCache::new(cache_size, config.cache_dir.clone())?);
+        let (job_sender, job_receiver) = mpsc::sync_channel(config.queue_capacity);
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+        let queue_depth = Arc::new(AtomicUsize::new(0));
+
+        for worker_id in 0..config.n_workers {
+            let worker = Worker {
+                compiler: compiler.clone(),
+                job_receiver: job_receiver.clone(),
+                cache: cache.clone(),
+                queue_depth: queue_depth.clone(),
+            };
+            thread::Builder::new()
+                .name(format!("compilation_worker_{worker_id}"))
+                .spawn(move || worker.run())?;
+        }
+
+        Ok(Self { config, job_sender, cache, queue_depth })
+    }
+
+    pub fn cache(&self) -> &CompiledClassCache {
+        &self.cache
+    }
+}
+
+impl SierraToCasmCompiler for CompilationService {
+    fn compile(&self, contract_class: ContractClass) -> CompilationResult {
+        let key = CompilationKey::new(&contract_class)?;
+        if let Some(casm_contract_class) = self.cache.get(&key) {
+            metrics::increment_counter!(COMPILATION_CACHE_HITS);
+            return Ok(casm_contract_class);
+        }
+        metrics::increment_counter!(COMPILATION_CACHE_MISSES);
+
+        let (result_sender, result_receiver) = mpsc::channel();
+        let job =
+            CompilationJob { key, contract_class, enqueued_at: Instant::now(), result_sender };
+        // Counted before it's sent, so the worker that takes it never sees a smaller depth.
+        update_queue_depth(self.queue_depth.fetch_add(1, Ordering::SeqCst) + 1);
+        if let Err(error) = self.job_sender.try_send(job) {
+            update_queue_depth(self.queue_depth.fetch_sub(1, Ordering::SeqCst) - 1);
+            return Err(match error {
+                TrySendError::Full(_) => CompilationUtilError::QueueFull,
+                TrySendError::Disconnected(_) => CompilationUtilError::UnexpectedError(
+                    "The compilation workers stopped.".to_owned(),
+                ),
+            });
+        }
+
+        match result_receiver.recv_timeout(self.config.compilation_timeout) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => {
+                Err(CompilationUtilError::Timeout(self.config.compilation_timeout))
+            }
+            Err(RecvTimeoutError::Disconnected) => Err(CompilationUtilError::UnexpectedError(
+                "The compilation worker stopped before finishing the compilation.".to_owned(),
+            )),
+        }
+    }
+}
+
+struct Worker {
+    compiler: Arc<dyn SierraToCasmCompiler>,
+    job_receiver: Arc<Mutex<Receiver<CompilationJob>>>,
+    cache: Arc<CompiledClassCache>,
+    queue_depth: Arc<AtomicUsize>,
+}
+
+impl Worker {
+    // Compiles classes until the service is dropped.
+    fn run(self) {
+        loop {
+            let job = self
+                .job_receiver
+                .lock()
+                .expect("The compilation queue lock should not be poisoned.")
+                .recv();
+            let Ok(job) = job else {
+                return;
+            };
+            update_queue_depth(self.queue_depth.fetch_sub(1, Ordering::SeqCst) - 1);
+            metrics::histogram!(
+                COMPILATION_QUEUE_LATENCY_SECS,
+                job.enqueued_at.elapsed().as_secs_f64()
+            );
+
+            let start = Instant::now();
+            let result = self.compiler.compile(job.contract_class);
+            metrics::histogram!(COMPILATION_LATENCY_SECS, start.elapsed().as_secs_f64());
+            if let Ok(casm_contract_class) = &result {
+                self.cache.insert(job.key, casm_contract_class.clone());
+            }
+            // The caller may have stopped waiting; the result is cached anyway.
+            if job.result_sender.send(result).is_err() {
+                debug!("The compilation of {} finished after its timeout.", hex::encode(job.key.0));
+            }
+        }
+    }
+}
+
+fn update_queue_depth(queue_depth: usize) {
+    let queue_depth = u32::try_from(queue_depth).unwrap_or(u32::MAX);
+    metrics::gauge!(COMPILATION_QUEUE_DEPTH, f64::from(queue_depth));
+}
@@ -0,0 +1,118 @@
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use assert_matches::assert_matches;
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use cairo_lang_starknet_classes::contract_class::ContractClass;
+use mempool_test_utils::{get_absolute_path, FAULTY_ACCOUNT_CLASS_FILE, TEST_FILES_FOLDER};
+use tempfile::TempDir;
+
+use crate::cairo_lang_compiler::CairoLangSierraToCasmCompiler;
+use crate::compilation_service::{CompilationKey, CompilationService};
+use crate::config::{CompilationServiceConfig, SierraToCasmCompilationConfig};
+use crate::errors::CompilationUtilError;
+use crate::test_utils::contract_class_from_file;
+use crate::SierraToCasmCompiler;
+
+/// Counts the classes it compiles.
+struct CountingCompiler {
+    compiler: CairoLangSierraToCasmCompiler,
+    n_compilations: AtomicUsize,
+}
+
+impl SierraToCasmCompiler for CountingCompiler {
+    fn compile(
+        &self,
+        contract_class: ContractClass,
+    ) -> Result<CasmContractClass, CompilationUtilError> {
+        self.n_compilations.fetch_add(1, Ordering::SeqCst);
+        self.compiler.compile(contract_class)
+    }
+}
+
+impl CountingCompiler {
+    fn new() -> Arc<Self> {
+        Arc::new(Self {
+            compiler: CairoLangSierraToCasmCompiler {
+                config: SierraToCasmCompilationConfig::default(),
+            },
+            n_compilations: AtomicUsize::new(0),
+        })
+    }
+}
+
+fn contract_class() -> ContractClass {
+    env::set_current_dir(get_absolute_path(TEST_FILES_FOLDER)).expect("Failed to set current dir.");
+    contract_class_from_file(FAULTY_ACCOUNT_CLASS_FILE)
+}
+
+#[test]
+fn compiled_classes_are_cached() {
+    let compiler = CountingCompiler::new();
+    let service =
+        CompilationService::new(CompilationServiceConfig::default(), compiler.clone()).unwrap();
+
+    let casm_contract_class = service.compile(contract_class()).unwrap();
+    assert_eq!(service.compile(contract_class()).unwrap(), casm_contract_class);
+    assert_eq!(compiler.n_compilations.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn compiled_classes_are_persisted() {
+    let cache_dir = TempDir::new().unwrap();
+    let config = CompilationServiceConfig {
+        cache_dir: Some(cache_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let casm_contract_class = CompilationService::new(config.clone(), CountingCompiler::new())
+        .unwrap()
+        .compile(contract_class())
+        .unwrap();
+
+    // A new service, e.g. after a restart, finds the class in the cache directory.
+    let compiler = CountingCompiler::new();
+    let service = CompilationService::new(config, compiler.clone()).unwrap();
+    let key = CompilationKey::new(&contract_class()).unwrap();
+    assert_eq!(service.cache().get(&key), Some(casm_contract_class.clone()));
+    assert_eq!(service.compile(contract_class()).unwrap(), casm_contract_class);
+    assert_eq!(compiler.n_compilations.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn compilation_errors_are_not_cached() {
+    let compiler = CountingCompiler::new();
+    let service =
+        CompilationService::new(CompilationServiceConfig::default(), compiler.clone()).unwrap();
+    let mut contract_class = contract_class();
+    // Truncate the sierra program to trigger an error.
+    contract_class.sierra_program = contract_class.sierra_program[..100].to_vec();
+
+    for _ in 0..2 {
+        assert_matches!(
+            service.compile(contract_class.clone()),
+            Err(CompilationUtilError::CompilationError(..))
+        );
+    }
+    assert_eq!(compiler.n_compilations.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn timeout_and_full_queue() {
+    let compilation_timeout = Duration::from_millis(10);
+    // Without workers, the classes stay in the queue.
+    let config = CompilationServiceConfig {
+        n_workers: 0,
+        queue_capacity: 1,
+        compilation_timeout,
+        ..Default::default()
+    };
+    let service = CompilationService::new(config, CountingCompiler::new()).unwrap();
+
+    assert_matches!(
+        service.compile(contract_class()),
+        Err(CompilationUtilError::Timeout(timeout)) if timeout == compilation_timeout
+    );
+    assert_matches!(service.compile(contract_class()), Err(CompilationUtilError::QueueFull));
+}
@@ -1,6 +1,9 @@
 use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
 
-use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::converters::deserialize_seconds_to_duration;
+use papyrus_config::dumping::{ser_optional_param, ser_param, SerializeConfig};
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
@@ -26,3 +29,75 @@ impl SerializeConfig for SierraToCasmCompilationConfig {
         )])
     }
 }
+
+/// The configuration of the [`CompilationService`](crate::compilation_service::CompilationService).
+#[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
+pub struct CompilationServiceConfig {
+    #[validate(range(min = 1))]
+    pub n_workers: usize,
+    #[validate(range(min = 1))]
+    pub queue_capacity: usize,
+    #[serde(deserialize_with = "deserialize_seconds_to_duration")]
+    pub compilation_timeout: Duration,
+    #[validate(range(min = 1))]
+    pub cache_size: usize,
+    pub cache_dir: Option<PathBuf>,
+}
+
+impl Default for CompilationServiceConfig {
+    fn default() -> Self {
+        Self {
+            n_workers: 4,
+            queue_capacity: 100,
+            compilation_timeout: Duration::from_secs(30),
+            cache_size: 1000,
+            cache_dir: None,
+        }
+    }
+}
+
+impl SerializeConfig for CompilationServiceConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        vec![
+            BTreeMap::from_iter([
+                ser_param(
+                    "n_workers",
+                    &self.n_workers,
+                    "The number of classes that are compiled concurrently.",
+                    ParamPrivacyInput::Public,
+                ),
+                ser_param(
+                    "queue_capacity",
+                    &self.queue_capacity,
+                    "The number of classes that may wait for compilation. Classes beyond it are \
+                     rejected.",
+                    ParamPrivacyInput::Public,
+                ),
+                ser_param(
+                    "compilation_timeout",
+                    &self.compilation_timeout.as_secs(),
+                    "Time in seconds to wait for the compilation of a class, including the time \
+                     it waits in the queue.",
+                    ParamPrivacyInput::Public,
+                ),
+                ser_param(
+                    "cache_size",
+                    &self.cache_size,
+                    "The number of compiled classes that are kept in memory.",
+                    ParamPrivacyInput::Public,
+                ),
+            ]),
+            ser_optional_param(
+                &self.cache_dir,
+                PathBuf::from("./compiled_classes"),
+                "cache_dir",
+                "The directory the compiled classes are persisted in. If not set, the compiled \
+                 classes are only kept in memory.",
+                ParamPrivacyInput::Public,
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use cairo_lang_starknet_classes::allowed_libfuncs::AllowedLibfuncsError;
 use cairo_lang_starknet_classes::casm_contract_class::StarknetSierraCompilationError;
 use thiserror::Error;
@@ -8,6 +10,10 @@ pub enum CompilationUtilError {
     CompilationError(String),
     #[error("Unexpected compilation error: {0}")]
     UnexpectedError(String),
+    #[error("The compilation queue is full.")]
+    QueueFull,
+    #[error("The compilation didn't finish within {0:?}.")]
+    Timeout(Duration),
 }
 
 impl From<AllowedLibfuncsError> for CompilationUtilError {
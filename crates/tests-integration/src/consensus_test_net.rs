@@ -0,0 +1,545 @@
+//! A local network of in-process consensus nodes, for system-level tests of the sequencing
+//! pipeline.
+//!
+//! Each node runs consensus with its own storage in a temporary directory. The nodes' consensus
+//! messages are delivered to each other through a simulated network, and the proposers build their
+//! proposals with a batcher of their own, from the mempool the transactions were added to through
+//! the gateway.
+//! A decided block is committed to the node's storage by a task of its own, while consensus
+//! proceeds to the next height, and is published to the test once committed, so the test can assert
+//! that all the nodes decided the same blocks.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::channel::{mpsc, oneshot};
+use futures::{SinkExt, StreamExt};
+use papyrus_base_layer::l1_gas_price::{GasPriceProvider, L1GasPrices};
+use papyrus_base_layer::l1_messages::SharedL1HandlerQueue;
+use papyrus_consensus::config::TimeoutsConfig;
+use papyrus_consensus::run_consensus;
+use papyrus_consensus::types::{
+    ConsensusBlock,
+    ConsensusContext,
+    ConsensusError,
+    ProposalInit,
+    Round,
+    ValidatorId,
+};
+use papyrus_network::network_manager::test_utils::{
+    create_test_broadcasted_message_manager,
+    mock_register_broadcast_topic,
+    BroadcastNetworkMock,
+    MockBroadcastedMessagesSender,
+    TestSubscriberChannels,
+};
+use papyrus_network::network_manager::BroadcastTopicSender;
+use papyrus_protobuf::consensus::{ConsensusMessage, Proposal, Vote};
+use papyrus_storage::block::{BlockStorageWriter, FullBlock};
+use papyrus_storage::header::HeaderStorageReader;
+use papyrus_storage::test_utils::get_test_storage;
+use papyrus_storage::{StorageReader, StorageWriter};
+use starknet_api::block::{BlockHash, BlockHeader, BlockNumber};
+use starknet_api::core::ContractAddress;
+use starknet_api::executable_transaction::Transaction as ExecutableTransaction;
+use starknet_api::transaction::{Transaction, TransactionHash};
+use starknet_batcher::proposals_manager::{ProposalId, ProposalsManager, ProposalsManagerConfig};
+use starknet_mempool_types::communication::SharedMempoolClient;
+use starknet_mempool_types::mempool_types::AccountState;
+use starknet_types_core::felt::Felt;
+use tempfile::TempDir;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{debug, info};
+
+use crate::network_conditions::{start_link, LinkSender, NetworkConditions};
+
+const CHANNEL_SIZE: usize = 5000;
+// Shorter than the proposal timeout, so the proposals reach the validators in time.
+const PROPOSAL_GENERATION_TIME: Duration = Duration::from_millis(500);
+const MAX_PENDING_COMMITS: usize = 2;
+
+/// A block decided by one of the nodes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecidedBlock {
+    pub validator_id: ValidatorId,
+    pub height: BlockNumber,
+    pub block: TestNetBlock,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestNetBlock {
+    pub transactions: Vec<Transaction>,
+    pub id: BlockHash,
+}
+
+impl TestNetBlock {
+    fn new(height: BlockNumber, transactions: Vec<Transaction>) -> Self {
+        let id = compute_block_id(height, &transactions);
+        Self { transactions, id }
+    }
+}
+
+impl ConsensusBlock for TestNetBlock {
+    type ProposalChunk = Transaction;
+    type ProposalIter = std::vec::IntoIter<Transaction>;
+
+    fn id(&self) -> BlockHash {
+        self.id
+    }
+
+    fn proposal_iter(&self) -> Self::ProposalIter {
+        self.transactions.clone().into_iter()
+    }
+}
+
+// The nodes run in the same process, so the hash of the block's content is deterministic across
+// them.
+fn compute_block_id(height: BlockNumber, transactions: &[Transaction]) -> BlockHash {
+    let mut hasher = DefaultHasher::new();
+    height.hash(&mut hasher);
+    transactions.hash(&mut hasher);
+    BlockHash(Felt::from(hasher.finish()))
+}
+
+fn into_transaction(tx: ExecutableTransaction) -> Transaction {
+    match tx {
+        ExecutableTransaction::Declare(declare_tx) => Transaction::Declare(declare_tx.tx),
+        ExecutableTransaction::DeployAccount(deploy_account_tx) => {
+            Transaction::DeployAccount(deploy_account_tx.tx)
+        }
+        ExecutableTransaction::Invoke(invoke_tx) => Transaction::Invoke(invoke_tx.tx),
+    }
+}
+
+/// The consensus context of a node in the test network.
+///
+/// The validators take turns proposing, by height and round. A proposer builds its proposal with
+/// its batcher, and the other nodes accept any proposal whose id matches its content.
+pub struct TestNetContext {
+    validator_id: ValidatorId,
+    validators: Vec<ValidatorId>,
+    proposals_manager: Arc<Mutex<ProposalsManager>>,
+    next_proposal_id: AtomicU64,
+    // The blocks this node built, by their ids, so the batcher doesn't propose their transactions
+    // again and the mempool commits them once they're decided.
+    built_blocks: Arc<std::sync::Mutex<HashMap<BlockHash, BuiltBlock>>>,
+    network_broadcast_sender: BroadcastTopicSender<ConsensusMessage>,
+    // The decided blocks, handed to the node's storage committer.
+    decisions_to_commit_sender: mpsc::UnboundedSender<DecisionToCommit>,
+}
+
+#[async_trait]
+impl ConsensusContext for TestNetContext {
+    type Block = TestNetBlock;
+
+    async fn build_proposal(
+        &self,
+        height: BlockNumber,
+    ) -> (mpsc::Receiver<Transaction>, oneshot::Receiver<TestNetBlock>) {
+        let (mut sender, receiver) = mpsc::channel(CHANNEL_SIZE);
+        let (fin_sender, fin_receiver) = oneshot::channel();
+
+        let proposal_id = self.next_proposal_id.fetch_add(1, Ordering::Relaxed);
+        let proposals_manager = self.proposals_manager.clone();
+        let built_blocks = self.built_blocks.clone();
+        tokio::spawn(async move {
+            let mut proposal_content = proposals_manager
+                .lock()
+                .await
+                .generate_block_proposal(
+                    proposal_id,
+                    Instant::now() + PROPOSAL_GENERATION_TIME,
+                    height,
+                    // The consensus context doesn't tell the round; the test net doesn't persist
+                    // proposals, so the round isn't used.
+                    0,
+                )
+                .await
+                .expect("Failed to start the proposal generation");
+            let mut transactions = Vec::new();
+            let mut built_block =
+                BuiltBlock { proposal_id: Some(proposal_id), ..Default::default() };
+            while let Some(chunk) = proposal_content.next().await {
+                for executable_tx in chunk {
+                    built_block.add_transaction(&executable_tx);
+                    let tx = into_transaction(executable_tx);
+                    sender.try_send(tx.clone()).expect("Send should succeed");
+                    transactions.push(tx);
+                }
+            }
+            sender.close_channel();
+            let block = TestNetBlock::new(height, transactions);
+            built_blocks
+                .lock()
+                .expect("The lock should not be poisoned")
+                .insert(block.id, built_block);
+            fin_sender.send(block).expect("Send should succeed");
+        });
+
+        (receiver, fin_receiver)
+    }
+
+    async fn validate_proposal(
+        &self,
+        height: BlockNumber,
+        content: mpsc::Receiver<Transaction>,
+    ) -> oneshot::Receiver<TestNetBlock> {
+        let (fin_sender, fin_receiver) = oneshot::channel();
+
+        tokio::spawn(async move {
+            let transactions = content.collect().await;
+            // The receiver is dropped if consensus moved on from the proposal's round.
+            let _ = fin_sender.send(TestNetBlock::new(height, transactions));
+        });
+
+        fin_receiver
+    }
+
+    async fn accept_certified_proposal(
+        &self,
+        height: BlockNumber,
+        _block_hash: BlockHash,
+        content: mpsc::Receiver<Transaction>,
+    ) -> oneshot::Receiver<TestNetBlock> {
+        // The test network's proposals aren't validated either, so the block is built the same way.
+        self.validate_proposal(height, content).await
+    }
+
+    async fn validators(&self, _height: BlockNumber) -> Vec<ValidatorId> {
+        self.validators.clone()
+    }
+
+    fn proposer(&self, height: BlockNumber, round: Round) -> ValidatorId {
+        let index = (height.0 + u64::from(round)) % self.validators.len() as u64;
+        self.validators[usize::try_from(index).expect("Index should fit in usize")]
+    }
+
+    async fn broadcast(&mut self, message: ConsensusMessage) -> Result<(), ConsensusError> {
+        debug!("Validator {} broadcasting message: {message:?}", self.validator_id);
+        self.network_broadcast_sender.send(message).await?;
+        Ok(())
+    }
+
+    async fn propose(
+        &self,
+        init: ProposalInit,
+        content_receiver: mpsc::Receiver<Transaction>,
+        fin_receiver: oneshot::Receiver<BlockHash>,
+    ) -> Result<(), ConsensusError> {
+        let mut network_broadcast_sender = self.network_broadcast_sender.clone();
+
+        tokio::spawn(async move {
+            let transactions = content_receiver.collect().await;
+            let Ok(block_hash) = fin_receiver.await else {
+                return;
+            };
+            let proposal = Proposal {
+                height: init.height.0,
+                round: init.round,
+                proposer: init.proposer,
+                transactions,
+                block_hash,
+            };
+            network_broadcast_sender
+                .send(ConsensusMessage::Proposal(proposal))
+                .await
+                .expect("Failed to send proposal");
+        });
+        Ok(())
+    }
+
+    // The test network's blocks don't handle messages from L1.
+    async fn decision_reached(
+        &mut self,
+        height: BlockNumber,
+        block: TestNetBlock,
+        _precommits: Vec<Vote>,
+    ) -> Result<oneshot::Receiver<()>, ConsensusError> {
+        info!("Validator {} decided block {:?} at height {height}.", self.validator_id, block.id);
+        // Only the proposer knows the hashes of the transactions and the accounts they changed, so
+        // only it commits the block to the shared mempool. Likewise, only the proposer prices its
+        // next blocks by the usage of the decided block.
+        let BuiltBlock { proposal_id, tx_hashes, state_changes } = self
+            .built_blocks
+            .lock()
+            .expect("The lock should not be poisoned")
+            .remove(&block.id)
+            .unwrap_or_default();
+        self.proposals_manager
+            .lock()
+            .await
+            .add_decided_block(height, proposal_id, tx_hashes, vec![], state_changes)
+            .await
+            .expect("Failed to commit the decided block to the mempool");
+        let (commit_sender, commit_receiver) = oneshot::channel();
+        self.decisions_to_commit_sender
+            .unbounded_send((height, block, commit_sender))
+            .expect("The storage writer should be running");
+        Ok(commit_receiver)
+    }
+}
+
+// The id of the proposal of a block a node built, the hashes of its transactions, and the nonce of
+// the last transaction of each account in it.
+#[derive(Default)]
+struct BuiltBlock {
+    proposal_id: Option<ProposalId>,
+    tx_hashes: Vec<TransactionHash>,
+    state_changes: HashMap<ContractAddress, AccountState>,
+}
+
+impl BuiltBlock {
+    fn add_transaction(&mut self, tx: &ExecutableTransaction) {
+        self.tx_hashes.push(tx.tx_hash());
+        // The transactions of an account are proposed by the order of their nonces.
+        self.state_changes.insert(tx.contract_address(), AccountState { nonce: tx.nonce() });
+    }
+}
+
+// A decided block with its height, and the sender of the acknowledgement of its commit.
+type DecisionToCommit = (BlockNumber, TestNetBlock, oneshot::Sender<()>);
+
+// Commits the decided blocks of a node to its storage, by the order of their heights, and publishes
+// them to the test once committed.
+struct StorageCommitter {
+    validator_id: ValidatorId,
+    storage_reader: StorageReader,
+    storage_writer: StorageWriter,
+    decisions_sender: mpsc::UnboundedSender<DecidedBlock>,
+}
+
+impl StorageCommitter {
+    async fn run(mut self, mut decisions_to_commit: mpsc::UnboundedReceiver<DecisionToCommit>) {
+        while let Some((height, block, commit_sender)) = decisions_to_commit.next().await {
+            self.commit(height, block);
+            // Consensus stops waiting for the commit if it stopped.
+            let _ = commit_sender.send(());
+        }
+    }
+
+    fn commit(&mut self, height: BlockNumber, block: TestNetBlock) {
+        let parent_hash = match height.prev() {
+            Some(parent_height) => {
+                self.storage_reader
+                    .begin_ro_txn()
+                    .and_then(|txn| txn.get_block_header(parent_height))
+                    .expect("Failed to read the parent header")
+                    .expect("The parent block should be in storage")
+                    .block_hash
+            }
+            None => BlockHash::default(),
+        };
+        let header = BlockHeader {
+            block_hash: block.id,
+            parent_hash,
+            block_number: height,
+            ..Default::default()
+        };
+        // The test net doesn't execute the transactions, so it has no outputs or state diff to
+        // store with them.
+        let full_block = FullBlock { header, ..Default::default() };
+        self.storage_writer
+            .begin_rw_txn()
+            .and_then(|txn| txn.append_block(full_block))
+            .and_then(|txn| txn.commit())
+            .expect("Failed to commit the decided block");
+
+        // The test may stop listening once it saw the blocks it waits for.
+        let _ = self.decisions_sender.unbounded_send(DecidedBlock {
+            validator_id: self.validator_id,
+            height,
+            block,
+        });
+    }
+}
+
+/// A node of the test network.
+pub struct TestNetNode {
+    pub validator_id: ValidatorId,
+    pub storage_reader: StorageReader,
+    pub consensus_handle: JoinHandle<Result<(), ConsensusError>>,
+    // Keeps the storage's directory for the lifetime of the node.
+    _storage_dir: TempDir,
+}
+
+/// A network of `n_nodes` consensus nodes, whose batchers all propose from the same mempool.
+pub struct ConsensusTestNet {
+    pub nodes: Vec<TestNetNode>,
+    pub decisions_receiver: mpsc::UnboundedReceiver<DecidedBlock>,
+    pub network_handle: JoinHandle<()>,
+}
+
+impl ConsensusTestNet {
+    pub fn new(
+        n_nodes: u64,
+        mempool_client: SharedMempoolClient,
+        timeouts: TimeoutsConfig,
+    ) -> Self {
+        Self::new_with_conditions(n_nodes, mempool_client, timeouts, NetworkConditions::default())
+    }
+
+    /// Like [`ConsensusTestNet::new`], with the nodes' messages delivered under the given network
+    /// conditions.
+    pub fn new_with_conditions(
+        n_nodes: u64,
+        mempool_client: SharedMempoolClient,
+        timeouts: TimeoutsConfig,
+        conditions: NetworkConditions,
+    ) -> Self {
+        let validators: Vec<ValidatorId> = (0..n_nodes).map(ContractAddress::from).collect();
+        let (decisions_sender, decisions_receiver) = mpsc::unbounded();
+
+        let mut nodes = Vec::new();
+        let mut nodes_network_mocks = Vec::new();
+        for validator_id in validators.iter().copied() {
+            let TestSubscriberChannels { subscriber_channels, mock_network } =
+                mock_register_broadcast_topic().expect("Failed to create mock network");
+            let ((storage_reader, storage_writer), storage_dir) = get_test_storage();
+            let (decisions_to_commit_sender, decisions_to_commit_receiver) = mpsc::unbounded();
+            let storage_committer = StorageCommitter {
+                validator_id,
+                storage_reader: storage_reader.clone(),
+                storage_writer,
+                decisions_sender: decisions_sender.clone(),
+            };
+            tokio::spawn(storage_committer.run(decisions_to_commit_receiver));
+            let context = TestNetContext {
+                validator_id,
+                validators: validators.clone(),
+                proposals_manager: Arc::new(Mutex::new(ProposalsManager::new(
+                    ProposalsManagerConfig::default(),
+                    mempool_client.clone(),
+                    // The test network has no base layer.
+                    GasPriceProvider::fixed(L1GasPrices::default()),
+                    SharedL1HandlerQueue::default(),
+                ))),
+                next_proposal_id: AtomicU64::new(0),
+                built_blocks: Arc::default(),
+                network_broadcast_sender: subscriber_channels.messages_to_broadcast_sender,
+                decisions_to_commit_sender,
+            };
+            let consensus_handle = tokio::spawn(run_consensus(
+                context,
+                BlockNumber(0),
+                validator_id,
+                Duration::ZERO,
+                timeouts.clone(),
+                false,
+                MAX_PENDING_COMMITS,
+                subscriber_channels.broadcasted_messages_receiver,
+                futures::stream::pending(),
+                Arc::new(AtomicU64::new(0)),
+            ));
+            nodes.push(TestNetNode {
+                validator_id,
+                storage_reader,
+                consensus_handle,
+                _storage_dir: storage_dir,
+            });
+            nodes_network_mocks.push(mock_network);
+        }
+
+        let network_handle = tokio::spawn(run_network(validators, nodes_network_mocks, conditions));
+        Self { nodes, decisions_receiver, network_handle }
+    }
+
+    /// Waits until all the nodes committed the blocks up to `height`, inclusive.
+    pub async fn wait_for_height(&self, height: BlockNumber) {
+        for node in &self.nodes {
+            // Subscribed before reading the marker, so a commit in between isn't missed.
+            let mut commits = node.storage_reader.subscribe_to_commits();
+            while node
+                .storage_reader
+                .begin_ro_txn()
+                .and_then(|txn| txn.get_header_marker())
+                .expect("Failed to read the header marker")
+                <= height
+            {
+                commits.changed().await.expect("The storage writer should be alive");
+            }
+        }
+    }
+
+    /// Returns the block each node committed at `height`, ordered by node.
+    pub fn committed_headers(&self, height: BlockNumber) -> Vec<Option<BlockHeader>> {
+        self.nodes
+            .iter()
+            .map(|node| {
+                node.storage_reader
+                    .begin_ro_txn()
+                    .and_then(|txn| txn.get_block_header(height))
+                    .expect("Failed to read the header")
+            })
+            .collect()
+    }
+}
+
+// Delivers each message a node broadcasts to all the other nodes, over the links between them.
+async fn run_network(
+    validators: Vec<ValidatorId>,
+    nodes_network_mocks: Vec<BroadcastNetworkMock<ConsensusMessage>>,
+    conditions: NetworkConditions,
+) {
+    let mut nodes_receivers = Vec::new();
+    let mut messages_to_broadcast = futures::stream::SelectAll::new();
+    for (index, mock_network) in nodes_network_mocks.into_iter().enumerate() {
+        let (receiver, messages) = mpsc::unbounded();
+        tokio::spawn(deliver_to_node(messages, mock_network.broadcasted_messages_sender));
+        nodes_receivers.push(receiver);
+        messages_to_broadcast
+            .push(mock_network.messages_to_broadcast_receiver.map(move |message| (index, message)));
+    }
+
+    // The links from each node, with the index of their receiver.
+    let mut links: Vec<Vec<(usize, LinkSender)>> = Vec::new();
+    let mut link_seed = conditions.seed;
+    for (sender_index, sender) in validators.iter().enumerate() {
+        let mut sender_links = Vec::new();
+        for (receiver_index, receiver) in validators.iter().enumerate() {
+            if sender_index == receiver_index {
+                continue;
+            }
+            let link = start_link(
+                conditions.link(*sender, *receiver).clone(),
+                link_seed,
+                nodes_receivers[receiver_index].clone(),
+            );
+            sender_links.push((receiver_index, link));
+            link_seed = link_seed.wrapping_add(1);
+        }
+        links.push(sender_links);
+    }
+
+    while let Some((sender_index, message)) = messages_to_broadcast.next().await {
+        let sent_at = Instant::now();
+        for (receiver_index, link) in &links[sender_index] {
+            if link.unbounded_send((sent_at, message.clone())).is_err() {
+                debug!("Node {receiver_index} no longer receives messages.");
+            }
+        }
+    }
+}
+
+// Passes the messages delivered to a node to its consensus.
+async fn deliver_to_node(
+    mut messages: mpsc::UnboundedReceiver<ConsensusMessage>,
+    mut broadcasted_messages_sender: MockBroadcastedMessagesSender<ConsensusMessage>,
+) {
+    while let Some(message) = messages.next().await {
+        // A node whose consensus stopped no longer receives messages.
+        if broadcasted_messages_sender
+            .send((message, create_test_broadcasted_message_manager()))
+            .await
+            .is_err()
+        {
+            return;
+        }
+    }
+}
@@ -49,7 +49,7 @@ impl IntegrationTestSetup {
         // Derive the configuration for the mempool node.
         let config = create_config(rpc_server_addr).await;
 
-        let (clients, servers) = create_clients_servers_from_config(&config);
+        let (clients, servers, _) = create_clients_servers_from_config(&config);
 
         let GatewayNetworkConfig { ip, port } = config.gateway_config.network_config;
         let gateway_client = GatewayClient::new(SocketAddr::from((ip, port)));
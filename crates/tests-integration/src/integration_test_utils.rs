@@ -17,16 +17,21 @@ use starknet_gateway::config::{
     StatelessTransactionValidatorConfig,
 };
 use starknet_gateway::errors::GatewaySpecError;
+use starknet_gateway::gateway::AddTxResponse;
 use starknet_mempool_node::config::MempoolNodeConfig;
+use starknet_mempool_types::stateless_validation::StatelessValidationConfig;
 use tokio::net::TcpListener;
 
 use crate::integration_test_setup::IntegrationTestSetup;
 
 async fn create_gateway_config() -> GatewayConfig {
     let stateless_tx_validator_config = StatelessTransactionValidatorConfig {
-        validate_non_zero_l1_gas_fee: true,
-        max_calldata_length: 10,
-        max_signature_length: 2,
+        stateless_validation: StatelessValidationConfig {
+            validate_non_zero_l1_gas_fee: true,
+            max_calldata_length: 10,
+            max_signature_length: 2,
+            ..Default::default()
+        },
         ..Default::default()
     };
 
@@ -34,7 +39,12 @@ async fn create_gateway_config() -> GatewayConfig {
     let network_config = GatewayNetworkConfig { ip: socket.ip(), port: socket.port() };
     let stateful_tx_validator_config = StatefulTransactionValidatorConfig::create_for_testing();
 
-    GatewayConfig { network_config, stateless_tx_validator_config, stateful_tx_validator_config }
+    GatewayConfig {
+        network_config,
+        stateless_tx_validator_config,
+        stateful_tx_validator_config,
+        expose_mempool_snapshot: false,
+    }
 }
 
 pub async fn create_config(rpc_server_addr: SocketAddr) -> MempoolNodeConfig {
@@ -59,7 +69,8 @@ impl GatewayClient {
         let response = self.add_tx(tx).await;
         assert!(response.status().is_success());
 
-        response.json().await.unwrap()
+        let AddTxResponse { transaction_hash, .. } = response.json().await.unwrap();
+        transaction_hash
     }
 
     // TODO: implement when usage eventually arises.
@@ -1,4 +1,6 @@
+pub mod consensus_test_net;
 pub mod integration_test_setup;
 pub mod integration_test_utils;
 pub mod mock_batcher;
+pub mod network_conditions;
 pub mod state_reader;
@@ -10,6 +10,10 @@ impl MockBatcher {
         Self { mempool_client }
     }
 
+    pub fn mempool_client(&self) -> SharedMempoolClient {
+        self.mempool_client.clone()
+    }
+
     pub async fn get_txs(&self, n_txs: usize) -> Vec<Transaction> {
         self.mempool_client.get_txs(n_txs).await.unwrap()
     }
@@ -0,0 +1,130 @@
+//! Simulated conditions of the links of the in-process test network.
+//!
+//! Each directed link between two nodes delays the messages sent over it by a latency sampled from
+//! a distribution, limits its throughput to a bandwidth, and duplicates messages with some
+//! probability. Messages are delivered over a link in the order they were sent, like over a single
+//! connection. The randomness is seeded, so a test's conditions are the same between runs.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use futures::channel::mpsc;
+use futures::StreamExt;
+use papyrus_consensus::types::ValidatorId;
+use papyrus_protobuf::consensus::ConsensusMessage;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_distr::{Distribution, Normal};
+use tokio::time::Instant;
+
+/// The distribution of the latency of the messages sent over a link.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LatencyDistribution {
+    Constant(Duration),
+    Uniform {
+        min: Duration,
+        max: Duration,
+    },
+    /// Samples below zero are delivered without latency.
+    Normal {
+        mean: Duration,
+        std_dev: Duration,
+    },
+}
+
+impl Default for LatencyDistribution {
+    fn default() -> Self {
+        Self::Constant(Duration::ZERO)
+    }
+}
+
+impl LatencyDistribution {
+    fn sample(&self, rng: &mut StdRng) -> Duration {
+        match self {
+            Self::Constant(latency) => *latency,
+            Self::Uniform { min, max } => rng.gen_range(*min..=*max),
+            Self::Normal { mean, std_dev } => {
+                let normal = Normal::new(mean.as_secs_f64(), std_dev.as_secs_f64())
+                    .expect("The standard deviation should be finite.");
+                Duration::from_secs_f64(normal.sample(rng).max(0.0))
+            }
+        }
+    }
+}
+
+/// The conditions of a directed link between two nodes. The default link is perfect.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LinkConditions {
+    pub latency: LatencyDistribution,
+    /// If set, the time a message takes to transmit is its size divided by the bandwidth, and a
+    /// message is transmitted only once the previous messages over the link were.
+    pub bandwidth_bytes_per_second: Option<u64>,
+    /// The probability, in [0, 1], that a message is delivered twice.
+    pub duplication_probability: f64,
+}
+
+/// The conditions of all the links of the test network.
+#[derive(Clone, Debug, Default)]
+pub struct NetworkConditions {
+    pub seed: u64,
+    /// The conditions of the links that aren't in `links`.
+    pub default_link: LinkConditions,
+    /// The conditions of specific links, keyed by their sender and receiver.
+    pub links: HashMap<(ValidatorId, ValidatorId), LinkConditions>,
+}
+
+impl NetworkConditions {
+    pub(crate) fn link(&self, sender: ValidatorId, receiver: ValidatorId) -> &LinkConditions {
+        self.links.get(&(sender, receiver)).unwrap_or(&self.default_link)
+    }
+}
+
+/// The sending end of a link: messages sent to it are delivered to the receiver under the link's
+/// conditions.
+pub(crate) type LinkSender = mpsc::UnboundedSender<(Instant, ConsensusMessage)>;
+
+/// Starts delivering the messages sent over the link to `receiver`, and returns the link's sender.
+pub(crate) fn start_link(
+    conditions: LinkConditions,
+    seed: u64,
+    receiver: mpsc::UnboundedSender<ConsensusMessage>,
+) -> LinkSender {
+    assert!((0.0..=1.0).contains(&conditions.duplication_probability));
+    let (sender, messages) = mpsc::unbounded();
+    tokio::spawn(run_link(conditions, StdRng::seed_from_u64(seed), messages, receiver));
+    sender
+}
+
+async fn run_link(
+    conditions: LinkConditions,
+    mut rng: StdRng,
+    mut messages: mpsc::UnboundedReceiver<(Instant, ConsensusMessage)>,
+    receiver: mpsc::UnboundedSender<ConsensusMessage>,
+) {
+    // When the link finishes transmitting the messages sent so far.
+    let mut transmitted_at = Instant::now();
+    // When the last message was delivered. Later messages aren't delivered before it.
+    let mut delivered_at = Instant::now();
+    while let Some((sent_at, message)) = messages.next().await {
+        transmitted_at = transmitted_at.max(sent_at);
+        if let Some(bandwidth) = conditions.bandwidth_bytes_per_second {
+            let n_bytes = Vec::<u8>::from(message.clone()).len();
+            transmitted_at += transmission_time(n_bytes, bandwidth);
+        }
+        delivered_at = delivered_at.max(transmitted_at + conditions.latency.sample(&mut rng));
+        tokio::time::sleep_until(delivered_at).await;
+
+        let n_copies = if rng.gen_bool(conditions.duplication_probability) { 2 } else { 1 };
+        for _ in 0..n_copies {
+            // A node whose consensus stopped no longer receives messages.
+            if receiver.unbounded_send(message.clone()).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn transmission_time(n_bytes: usize, bytes_per_second: u64) -> Duration {
+    let n_bytes = u64::try_from(n_bytes).expect("Message size should fit in u64.");
+    Duration::from_nanos(n_bytes.saturating_mul(1_000_000_000) / bytes_per_second)
+}
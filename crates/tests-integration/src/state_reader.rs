@@ -19,20 +19,9 @@ use itertools::Itertools;
 use mempool_test_utils::starknet_api_test_utils::deploy_account_tx;
 use papyrus_common::pending_classes::PendingClasses;
 use papyrus_rpc::{run_server, RpcConfig};
-use papyrus_storage::body::BodyStorageWriter;
-use papyrus_storage::class::ClassStorageWriter;
-use papyrus_storage::compiled_class::CasmStorageWriter;
-use papyrus_storage::header::HeaderStorageWriter;
-use papyrus_storage::state::StateStorageWriter;
+use papyrus_storage::block::{BlockStorageWriter, FullBlock};
 use papyrus_storage::{open_storage, StorageConfig, StorageReader};
-use starknet_api::block::{
-    BlockBody,
-    BlockHeader,
-    BlockNumber,
-    BlockTimestamp,
-    GasPrice,
-    GasPricePerToken,
-};
+use starknet_api::block::{BlockHeader, BlockNumber, BlockTimestamp, GasPrice, GasPricePerToken};
 use starknet_api::core::{ClassHash, ContractAddress, PatriciaKey, SequencerContractAddress};
 use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
 use starknet_api::state::{StorageKey, ThinStateDiff};
@@ -202,24 +191,15 @@ fn write_state_to_papyrus_storage(
     storage_config.db_config.path_prefix = tempdir.path().to_path_buf();
     let (storage_reader, mut storage_writer) = open_storage(storage_config).unwrap();
 
-    let cairo0_contract_classes =
-        cairo0_contract_classes.iter().map(|(x, y)| (*x, y)).collect::<Vec<_>>();
-
-    let mut write_txn = storage_writer.begin_rw_txn().unwrap();
-    for (class_hash, casm) in cairo1_contract_classes {
-        write_txn = write_txn.append_casm(class_hash, casm).unwrap();
-    }
-    write_txn
-        .append_header(block_number, &block_header)
-        .unwrap()
-        .append_body(block_number, BlockBody::default())
-        .unwrap()
-        .append_state_diff(block_number, state_diff)
-        .unwrap()
-        .append_classes(block_number, &[], cairo0_contract_classes.as_slice())
-        .unwrap()
-        .commit()
-        .unwrap();
+    let block = FullBlock {
+        header: block_header,
+        body: Some(Default::default()),
+        state_diff,
+        deprecated_classes: cairo0_contract_classes.iter().cloned().collect(),
+        casms: cairo1_contract_classes.iter().cloned().collect(),
+        ..Default::default()
+    };
+    storage_writer.begin_rw_txn().unwrap().append_block(block).unwrap().commit().unwrap();
 
     storage_reader
 }
@@ -0,0 +1,97 @@
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use blockifier::test_utils::contracts::FeatureContract;
+use blockifier::test_utils::CairoVersion;
+use futures::StreamExt;
+use papyrus_consensus::config::TimeoutsConfig;
+use rstest::rstest;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::ContractAddress;
+use starknet_mempool_integration_tests::consensus_test_net::{ConsensusTestNet, TestNetBlock};
+use starknet_mempool_integration_tests::integration_test_utils::setup_with_tx_generation;
+use starknet_mempool_integration_tests::network_conditions::{
+    LatencyDistribution,
+    LinkConditions,
+    NetworkConditions,
+};
+
+const N_NODES: u64 = 4;
+
+// A WAN-like network: latencies of tens of milliseconds, limited bandwidth, and duplicates.
+fn wan_conditions() -> NetworkConditions {
+    NetworkConditions {
+        seed: 0,
+        default_link: LinkConditions {
+            latency: LatencyDistribution::Normal {
+                mean: Duration::from_millis(50),
+                std_dev: Duration::from_millis(20),
+            },
+            bandwidth_bytes_per_second: Some(1_000_000),
+            duplication_probability: 0.1,
+        },
+        links: HashMap::from([(
+            (ContractAddress::from(0_u64), ContractAddress::from(1_u64)),
+            LinkConditions {
+                latency: LatencyDistribution::Uniform {
+                    min: Duration::from_millis(100),
+                    max: Duration::from_millis(300),
+                },
+                ..Default::default()
+            },
+        )]),
+    }
+}
+
+#[rstest]
+#[case::perfect_network(NetworkConditions::default())]
+#[case::wan(wan_conditions())]
+#[tokio::test]
+async fn all_nodes_decide_identical_blocks(#[case] conditions: NetworkConditions) {
+    // Setup.
+    let accounts = [
+        FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1),
+        FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0),
+    ];
+    let (mock_running_system, mut tx_generator) = setup_with_tx_generation(&accounts).await;
+
+    let account0_deploy_nonce0 = &tx_generator.account_with_id(0).generate_default_deploy_account();
+    let account1_invoke_nonce0 = tx_generator.account_with_id(1).generate_default_invoke();
+    mock_running_system.assert_add_tx_success(account0_deploy_nonce0).await;
+    mock_running_system.assert_add_tx_success(&account1_invoke_nonce0).await;
+
+    // Test.
+
+    let mut test_net = ConsensusTestNet::new_with_conditions(
+        N_NODES,
+        mock_running_system.batcher.mempool_client(),
+        TimeoutsConfig::default(),
+        conditions,
+    );
+    let last_height = BlockNumber(1);
+    test_net.wait_for_height(last_height).await;
+
+    // Assert.
+
+    let mut decided_blocks: BTreeMap<BlockNumber, Vec<TestNetBlock>> = BTreeMap::new();
+    let n_decisions = usize::try_from((last_height.0 + 1) * N_NODES).unwrap();
+    while decided_blocks.values().map(Vec::len).sum::<usize>() < n_decisions {
+        let decided_block = test_net.decisions_receiver.next().await.unwrap();
+        // The nodes keep deciding blocks after reaching the last height.
+        if decided_block.height <= last_height {
+            decided_blocks.entry(decided_block.height).or_default().push(decided_block.block);
+        }
+    }
+    for (height, blocks) in &decided_blocks {
+        assert_eq!(blocks.len(), N_NODES as usize, "Missing decisions at height {height}.");
+        assert!(blocks.windows(2).all(|pair| pair[0] == pair[1]), "Blocks differ at {height}.");
+
+        let headers = test_net.committed_headers(*height);
+        assert!(headers.iter().all(|header| header.as_ref().unwrap().block_hash == blocks[0].id));
+    }
+
+    // Both transactions are sequenced, each exactly once.
+    let n_decided_txs: usize =
+        decided_blocks.values().map(|blocks| blocks[0].transactions.len()).sum();
+    assert_eq!(n_decided_txs, 2);
+}